@@ -0,0 +1,54 @@
+//! Banc d'essai : primitives de prétraitement sur des images en mémoire.
+//!
+//! Mesure [`adjust_contrast`], [`binarize`] (seuil fixe et Otsu) et
+//! [`rotate_image`] sur des pages pseudo-aléatoires de 1 et 8 mégapixels.
+//!
+//! ```bash
+//! cargo bench --bench preprocessing
+//! ```
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use image::{GrayImage, Luma};
+use std::hint::black_box;
+use text_recognition::preprocessing::{
+    BinarizationMethod, adjust_contrast, binarize, rotate_image,
+};
+use text_recognition::rng::SplitMix64;
+
+/// Primitive de prétraitement appliquée à une page.
+type Primitive = fn(&GrayImage) -> GrayImage;
+
+/// Primitives mesurées, avec leur libellé.
+const PRIMITIVES: [(&str, Primitive); 4] = [
+    ("adjust_contrast(1.5)", |page| adjust_contrast(page, 1.5)),
+    ("binarize(Fixed(128))", |page| {
+        binarize(page, BinarizationMethod::Fixed(128))
+    }),
+    ("binarize(Otsu)", |page| {
+        binarize(page, BinarizationMethod::Otsu)
+    }),
+    ("rotate_image(2.5°)", |page| rotate_image(page, 2.5)),
+];
+
+/// Page pseudo-aléatoire de `width` × `height` pixels.
+fn random_page(width: u32, height: u32) -> GrayImage {
+    let mut rng = SplitMix64::new(42);
+    GrayImage::from_fn(width, height, |_, _| Luma([(rng.next_u64() >> 56) as u8]))
+}
+
+fn bench_primitives(c: &mut Criterion) {
+    let mut group = c.benchmark_group("preprocessing");
+    group.sample_size(10);
+    for (label, width, height) in [("1 Mpx", 1000, 1000), ("8 Mpx", 2480, 3508)] {
+        let page = random_page(width, height);
+        for (name, primitive) in PRIMITIVES {
+            group.bench_with_input(BenchmarkId::new(name, label), &page, |b, page| {
+                b.iter(|| primitive(black_box(page)))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_primitives);
+criterion_main!(benches);
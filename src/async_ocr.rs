@@ -0,0 +1,441 @@
+//! API asynchrone du moteur OCR (feature `async`).
+//!
+//! Les extractions Tesseract sont bloquantes : appelées depuis une tâche
+//! `tokio`, elles monopolisent un thread du runtime. [`AsyncOcrEngine`]
+//! exécute chaque extraction sur le pool de threads bloquants de `tokio`
+//! (`spawn_blocking`) et borne le nombre d'extractions simultanées par un
+//! sémaphore : une rafale de requêtes attend son tour au lieu de lancer des
+//! centaines d'instances Tesseract.
+//!
+//! Les erreurs restent des erreurs `anyhow` dont la cause typée
+//! ([`OcrError`]) se récupère avec `downcast_ref`, comme en synchrone.
+//!
+//! # Annulation
+//!
+//! Abandonner (`drop`) une future avant qu'elle n'obtienne sa place libère
+//! immédiatement sa demande. Une extraction déjà lancée se termine en
+//! arrière-plan et conserve sa place jusqu'à la fin : la limite de
+//! concurrence porte sur les instances Tesseract réellement actives.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::async_ocr::AsyncOcrEngine;
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::ocr::OcrEngine;
+//! use std::path::PathBuf;
+//! use tokio_stream::StreamExt;
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let engine = AsyncOcrEngine::new(OcrEngine::new(OcrConfig::default())?)
+//!     .with_max_concurrency(4)?;
+//!
+//! let text = engine.extract_text_from_file(PathBuf::from("page.png")).await?;
+//! println!("{}", text);
+//!
+//! let images = vec![PathBuf::from("a.png"), PathBuf::from("b.png")];
+//! let mut results = engine.batch_extract_stream(images);
+//! while let Some((index, outcome)) = results.next().await {
+//!     println!("image {} : {} caractères", index, outcome?.text.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::hocr::HocrDocument;
+use crate::ocr::{OcrEngine, OcrError, OcrOutcome};
+use anyhow::{Result, bail};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use tokio::sync::{Semaphore, mpsc};
+use tokio_stream::Stream;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// Moteur OCR utilisable depuis du code asynchrone `tokio`.
+///
+/// Les clones partagent le même moteur et la même limite de concurrence.
+/// Toutes les méthodes doivent être appelées depuis un runtime `tokio`.
+#[derive(Debug, Clone)]
+pub struct AsyncOcrEngine {
+    engine: Arc<OcrEngine>,
+    semaphore: Arc<Semaphore>,
+    max_concurrency: usize,
+}
+
+impl AsyncOcrEngine {
+    /// Enveloppe un moteur OCR configuré.
+    ///
+    /// La limite de concurrence vaut par défaut le nombre de cœurs disponibles.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - Moteur OCR (configuration, prétraitement, délai...)
+    pub fn new(engine: OcrEngine) -> Self {
+        let max_concurrency = thread::available_parallelism()
+            .map(|count| count.get())
+            .unwrap_or(1);
+        Self {
+            engine: Arc::new(engine),
+            semaphore: Arc::new(Semaphore::new(max_concurrency)),
+            max_concurrency,
+        }
+    }
+
+    /// Fixe le nombre maximal d'extractions simultanées.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_concurrency` - Nombre maximal d'instances Tesseract actives
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si `max_concurrency` vaut 0.
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Result<Self> {
+        if max_concurrency == 0 {
+            bail!("La concurrence maximale doit être d'au moins 1");
+        }
+        self.semaphore = Arc::new(Semaphore::new(max_concurrency));
+        self.max_concurrency = max_concurrency;
+        Ok(self)
+    }
+
+    /// Nombre maximal d'extractions simultanées.
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    /// Moteur synchrone enveloppé.
+    pub fn engine(&self) -> &OcrEngine {
+        &self.engine
+    }
+
+    /// Extrait le texte d'une image (voir [`OcrEngine::extract_text_from_file()`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image
+    ///
+    /// # Erreurs
+    ///
+    /// Mêmes erreurs que la méthode synchrone.
+    pub async fn extract_text_from_file(&self, path: PathBuf) -> Result<String> {
+        self.run_blocking(move |engine| engine.extract_text_from_file(&path))
+            .await
+    }
+
+    /// Extrait le texte d'une image encodée en mémoire
+    /// (voir [`OcrEngine::extract_text_from_bytes()`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Contenu du fichier image (PNG, JPEG...)
+    ///
+    /// # Erreurs
+    ///
+    /// Mêmes erreurs que la méthode synchrone.
+    pub async fn extract_text_from_bytes(&self, bytes: Vec<u8>) -> Result<String> {
+        self.run_blocking(move |engine| engine.extract_text_from_bytes(&bytes))
+            .await
+    }
+
+    /// Extrait le document hOCR d'une image (voir [`OcrEngine::extract_hocr_document()`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image
+    ///
+    /// # Erreurs
+    ///
+    /// Mêmes erreurs que la méthode synchrone.
+    pub async fn extract_hocr(&self, path: PathBuf) -> Result<HocrDocument> {
+        self.run_blocking(move |engine| engine.extract_hocr_document(&path))
+            .await
+    }
+
+    /// Extrait le texte de plusieurs images, au plus `max_concurrency` à la fois.
+    ///
+    /// Les résultats sont produits dans l'ordre où les extractions se
+    /// terminent, accompagnés de l'indice de l'image dans `paths`. Abandonner
+    /// le flux arrête le lancement de nouvelles extractions.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Chemins des images
+    pub fn batch_extract_stream(
+        &self,
+        paths: Vec<PathBuf>,
+    ) -> impl Stream<Item = (usize, Result<OcrOutcome>)> + use<> {
+        self.run_blocking_stream(paths, |engine, path| engine.extract_text_detailed(&path))
+    }
+
+    /// Exécute une opération synchrone du moteur sur le pool de threads bloquants.
+    ///
+    /// L'opération attend une place libre (au plus `max_concurrency`
+    /// simultanément) puis la conserve jusqu'à sa fin, même si la future est
+    /// abandonnée entre-temps. Les méthodes `extract_*` reposent sur elle ;
+    /// elle permet d'appeler les autres méthodes de [`OcrEngine`] avec la
+    /// même limite.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - Opération recevant le moteur enveloppé
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne l'erreur de l'opération, ou [`OcrError::Cancelled`] si le
+    /// runtime s'arrête avant qu'elle ne se termine. Une panique de
+    /// l'opération est propagée à l'appelant.
+    pub async fn run_blocking<T, F>(&self, operation: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&OcrEngine) -> Result<T> + Send + 'static,
+    {
+        let permit = Arc::clone(&self.semaphore)
+            .acquire_owned()
+            .await
+            .map_err(|_| OcrError::Cancelled)?;
+        let engine = Arc::clone(&self.engine);
+        let task = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            operation(&engine)
+        });
+        match task.await {
+            Ok(result) => result,
+            Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+            Err(_) => Err(OcrError::Cancelled.into()),
+        }
+    }
+
+    /// Applique une opération synchrone à chaque élément, au plus
+    /// `max_concurrency` à la fois, et produit les résultats à mesure qu'ils
+    /// se terminent.
+    ///
+    /// Variante générique de [`batch_extract_stream()`](Self::batch_extract_stream).
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - Éléments à traiter (chemins, images en mémoire...)
+    /// * `operation` - Opération appliquée à chaque élément
+    ///
+    /// # Retour
+    ///
+    /// Un flux de couples (indice de l'élément dans `items`, résultat)
+    pub fn run_blocking_stream<I, T, F>(
+        &self,
+        items: Vec<I>,
+        operation: F,
+    ) -> impl Stream<Item = (usize, Result<T>)> + use<I, T, F>
+    where
+        I: Send + 'static,
+        T: Send + 'static,
+        F: Fn(&OcrEngine, I) -> Result<T> + Send + Sync + 'static,
+    {
+        // Capacité suffisante pour tous les résultats : l'envoi ne bloque jamais
+        let (sender, receiver) = mpsc::channel(items.len().max(1));
+        let engine = Arc::clone(&self.engine);
+        let semaphore = Arc::clone(&self.semaphore);
+        let operation = Arc::new(operation);
+
+        tokio::spawn(async move {
+            for (index, item) in items.into_iter().enumerate() {
+                let Ok(permit) = Arc::clone(&semaphore).acquire_owned().await else {
+                    break;
+                };
+                // Flux abandonné : ne plus lancer d'extraction
+                if sender.is_closed() {
+                    break;
+                }
+                let engine = Arc::clone(&engine);
+                let operation = Arc::clone(&operation);
+                let sender = sender.clone();
+                tokio::task::spawn_blocking(move || {
+                    let result = operation(&engine, item);
+                    drop(permit);
+                    let _ = sender.blocking_send((index, result));
+                });
+            }
+        });
+
+        ReceiverStream::new(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OcrConfig;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use tokio_stream::StreamExt;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+    }
+
+    fn engine(max_concurrency: usize) -> AsyncOcrEngine {
+        AsyncOcrEngine::new(OcrEngine::new(OcrConfig::default()).unwrap())
+            .with_max_concurrency(max_concurrency)
+            .unwrap()
+    }
+
+    /// Compteur des opérations en cours, avec le maximum observé.
+    #[derive(Default)]
+    struct InFlight {
+        current: AtomicUsize,
+        max: AtomicUsize,
+        done: AtomicUsize,
+    }
+
+    impl InFlight {
+        fn run(&self, duration: Duration) {
+            let current = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max.fetch_max(current, Ordering::SeqCst);
+            thread::sleep(duration);
+            self.current.fetch_sub(1, Ordering::SeqCst);
+            self.done.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_concurrency_is_bounded() {
+        let engine = engine(2);
+        let in_flight = Arc::new(InFlight::default());
+
+        runtime().block_on(async {
+            let tasks: Vec<_> = (0..8)
+                .map(|index| {
+                    let engine = engine.clone();
+                    let in_flight = Arc::clone(&in_flight);
+                    tokio::spawn(async move {
+                        engine
+                            .run_blocking(move |_| {
+                                in_flight.run(Duration::from_millis(20));
+                                Ok(index)
+                            })
+                            .await
+                    })
+                })
+                .collect();
+            for (index, task) in tasks.into_iter().enumerate() {
+                assert_eq!(task.await.unwrap().unwrap(), index);
+            }
+        });
+
+        assert_eq!(in_flight.done.load(Ordering::SeqCst), 8);
+        assert_eq!(in_flight.max.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_dropped_future_does_not_poison_engine() {
+        let engine = engine(1);
+        let (release, wait) = std::sync::mpsc::channel::<()>();
+        let (started, is_started) = std::sync::mpsc::channel::<()>();
+
+        runtime().block_on(async {
+            // Extraction en cours dont la future est abandonnée
+            let running = {
+                let engine = engine.clone();
+                tokio::spawn(async move {
+                    engine
+                        .run_blocking(move |_| {
+                            started.send(()).unwrap();
+                            wait.recv().unwrap();
+                            Ok(())
+                        })
+                        .await
+                })
+            };
+            tokio::task::spawn_blocking(move || is_started.recv().unwrap())
+                .await
+                .unwrap();
+            running.abort();
+            assert!(running.await.unwrap_err().is_cancelled());
+
+            // Demande en attente de place, abandonnée elle aussi
+            let waiting = {
+                let engine = engine.clone();
+                tokio::spawn(async move { engine.run_blocking(|_| Ok(())).await })
+            };
+            tokio::task::yield_now().await;
+            waiting.abort();
+            assert!(waiting.await.unwrap_err().is_cancelled());
+
+            // L'extraction abandonnée rend sa place en se terminant
+            release.send(()).unwrap();
+            assert_eq!(engine.run_blocking(|_| Ok(42)).await.unwrap(), 42);
+        });
+    }
+
+    #[test]
+    fn test_errors_keep_their_typed_cause() {
+        let engine = engine(1);
+
+        let error = runtime()
+            .block_on(engine.run_blocking(|_| -> Result<()> { Err(OcrError::Cancelled.into()) }))
+            .unwrap_err();
+
+        assert_eq!(error.downcast_ref::<OcrError>(), Some(&OcrError::Cancelled));
+    }
+
+    #[test]
+    fn test_stream_yields_in_completion_order_with_indices() {
+        let engine = engine(3);
+        // Le premier élément est le plus lent
+        let delays = vec![60, 5, 30];
+
+        let results: Vec<(usize, u64)> = runtime().block_on(async {
+            engine
+                .run_blocking_stream(delays, |_, delay| {
+                    thread::sleep(Duration::from_millis(delay));
+                    Ok(delay)
+                })
+                .map(|(index, result)| (index, result.unwrap()))
+                .collect()
+                .await
+        });
+
+        assert_eq!(results, [(1, 5), (2, 30), (0, 60)]);
+    }
+
+    #[test]
+    fn test_stream_respects_concurrency_and_reports_errors() {
+        let engine = engine(2);
+        let in_flight = Arc::new(InFlight::default());
+
+        let results: Vec<(usize, Result<usize>)> = runtime().block_on(async {
+            let in_flight = Arc::clone(&in_flight);
+            engine
+                .run_blocking_stream((0..6).collect(), move |_, item: usize| {
+                    in_flight.run(Duration::from_millis(10));
+                    if item == 4 {
+                        bail!("image illisible");
+                    }
+                    Ok(item)
+                })
+                .collect()
+                .await
+        });
+
+        assert_eq!(results.len(), 6);
+        assert_eq!(in_flight.max.load(Ordering::SeqCst), 2);
+        for (index, result) in &results {
+            match result {
+                Ok(item) => assert_eq!(item, index),
+                Err(error) => {
+                    assert_eq!(*index, 4);
+                    assert!(error.to_string().contains("illisible"));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_with_max_concurrency_rejects_zero() {
+        let engine = AsyncOcrEngine::new(OcrEngine::new(OcrConfig::default()).unwrap());
+        assert!(engine.max_concurrency() >= 1);
+        assert!(engine.with_max_concurrency(0).is_err());
+    }
+}
@@ -0,0 +1,701 @@
+//! Benchmark de configurations OCR sur un corpus annoté.
+//!
+//! Ce module permet de répondre à la question « mon changement de prétraitement
+//! a-t-il amélioré les résultats dans l'ensemble ? » en exécutant plusieurs
+//! configurations nommées sur toutes les images d'un corpus, puis en agrégeant
+//! les métriques (CER, WER, temps) par configuration et par tag.
+//!
+//! # Manifeste
+//!
+//! Le corpus est décrit par un fichier manifeste JSON ou TOML. Les chemins
+//! relatifs sont résolus par rapport au répertoire du manifeste.
+//!
+//! ```toml
+//! [[images]]
+//! image = "simple/img-1.png"
+//! expected = "expected/img-1.txt"
+//! tags = ["simple"]
+//!
+//! [[images]]
+//! image = "complex/img-7.png"
+//! expected = "expected/img-7.txt"
+//! tags = ["complex", "invoice"]
+//! ```
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::bench::{NamedConfig, TesseractRunner, load_manifest, run_benchmark};
+//! use text_recognition::config_file::AppConfig;
+//! use std::path::Path;
+//!
+//! let manifest = load_manifest(Path::new("resources/bench.toml"))?;
+//! let configs = vec![NamedConfig::new("default", AppConfig::default())];
+//! let report = run_benchmark(&manifest, &configs, &mut TesseractRunner)?;
+//! println!("{}", report.to_markdown());
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::config_file::AppConfig;
+use crate::metrics::{CsvOptions, OcrMetrics, compare_ocr_result};
+use crate::ocr::OcrEngine;
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Entrée du manifeste : une image, son texte de référence et ses tags.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Chemin vers l'image à analyser.
+    pub image: PathBuf,
+
+    /// Chemin vers le fichier texte de référence.
+    pub expected: PathBuf,
+
+    /// Tags optionnels pour regrouper les résultats (ex: "invoice", "receipt").
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Manifeste d'un corpus de benchmark.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchmarkManifest {
+    /// Images du corpus.
+    pub images: Vec<ManifestEntry>,
+}
+
+/// Configuration nommée à évaluer.
+#[derive(Debug, Clone)]
+pub struct NamedConfig {
+    /// Nom affiché dans le rapport.
+    pub name: String,
+
+    /// Configuration OCR et de prétraitement.
+    pub config: AppConfig,
+}
+
+impl NamedConfig {
+    /// Crée une configuration nommée.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Nom affiché dans le rapport
+    /// * `config` - Configuration à évaluer
+    pub fn new(name: impl Into<String>, config: AppConfig) -> Self {
+        Self {
+            name: name.into(),
+            config,
+        }
+    }
+}
+
+/// Étape d'OCR utilisée par le benchmark.
+///
+/// Ce trait isole l'appel à Tesseract afin que l'agrégation, le classement et
+/// le regroupement par tag puissent être testés avec des sorties prédéfinies.
+pub trait OcrRunner {
+    /// Extrait le texte d'une image avec la configuration donnée.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Configuration à appliquer
+    /// * `image` - Chemin vers l'image à analyser
+    fn run(&mut self, config: &AppConfig, image: &Path) -> Result<String>;
+}
+
+/// Implémentation de [`OcrRunner`] basée sur [`OcrEngine`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TesseractRunner;
+
+impl OcrRunner for TesseractRunner {
+    fn run(&mut self, config: &AppConfig, image: &Path) -> Result<String> {
+        let ocr_config = config.ocr.clone().unwrap_or_default();
+        let engine = match config.preprocessing.clone() {
+            Some(prep) => OcrEngine::with_preprocessing(ocr_config, prep)?,
+            None => OcrEngine::new(ocr_config)?,
+        };
+        engine.extract_text_from_file(image)
+    }
+}
+
+/// Résultat d'une configuration sur une image.
+#[derive(Debug, Clone)]
+pub struct BenchmarkResult {
+    /// Nom de la configuration.
+    pub config_name: String,
+
+    /// Image analysée.
+    pub image: PathBuf,
+
+    /// Tags de l'image.
+    pub tags: Vec<String>,
+
+    /// Métriques calculées, ou `None` si l'extraction a échoué.
+    pub metrics: Option<OcrMetrics>,
+
+    /// Durée de l'extraction.
+    pub duration: Duration,
+}
+
+/// Statistiques agrégées d'une configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigSummary {
+    /// Nom de la configuration.
+    pub name: String,
+
+    /// Nombre d'images traitées avec succès.
+    pub image_count: usize,
+
+    /// Nombre d'extractions en échec.
+    pub failures: usize,
+
+    /// CER moyen.
+    pub mean_cer: f64,
+
+    /// CER médian.
+    pub median_cer: f64,
+
+    /// WER moyen.
+    pub mean_wer: f64,
+
+    /// WER médian.
+    pub median_wer: f64,
+
+    /// Temps total d'extraction (succès et échecs).
+    pub total_time: Duration,
+}
+
+/// Rapport complet d'un benchmark.
+#[derive(Debug, Clone)]
+pub struct BenchmarkReport {
+    /// Résultats individuels (configuration × image), dans l'ordre d'exécution.
+    pub results: Vec<BenchmarkResult>,
+
+    /// Noms des configurations, dans l'ordre fourni.
+    pub config_names: Vec<String>,
+}
+
+impl BenchmarkReport {
+    /// Retourne les statistiques de chaque configuration, dans l'ordre fourni.
+    pub fn summaries(&self) -> Vec<ConfigSummary> {
+        self.config_names
+            .iter()
+            .map(|name| summarize(name, self.results.iter().filter(|r| &r.config_name == name)))
+            .collect()
+    }
+
+    /// Retourne les statistiques triées de la meilleure à la moins bonne configuration.
+    ///
+    /// Le classement se fait par CER moyen croissant, puis par WER moyen.
+    /// Les configurations sans aucun succès sont placées en dernier.
+    pub fn ranking(&self) -> Vec<ConfigSummary> {
+        let mut summaries = self.summaries();
+        summaries.sort_by(|a, b| {
+            (a.image_count == 0)
+                .cmp(&(b.image_count == 0))
+                .then(a.mean_cer.total_cmp(&b.mean_cer))
+                .then(a.mean_wer.total_cmp(&b.mean_wer))
+        });
+        summaries
+    }
+
+    /// Retourne les statistiques par tag, puis par configuration.
+    ///
+    /// Une image portant plusieurs tags est comptée dans chacun d'eux.
+    pub fn tag_breakdown(&self) -> BTreeMap<String, Vec<ConfigSummary>> {
+        let mut tags: Vec<&String> = self.results.iter().flat_map(|r| &r.tags).collect();
+        tags.sort();
+        tags.dedup();
+
+        tags.into_iter()
+            .map(|tag| {
+                let summaries = self
+                    .config_names
+                    .iter()
+                    .map(|name| {
+                        summarize(
+                            name,
+                            self.results
+                                .iter()
+                                .filter(|r| &r.config_name == name && r.tags.contains(tag)),
+                        )
+                    })
+                    .collect();
+                (tag.clone(), summaries)
+            })
+            .collect()
+    }
+
+    /// Génère le tableau comparatif au format Markdown.
+    ///
+    /// Contient le classement global puis, si des tags sont présents,
+    /// une section par tag.
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::new();
+
+        output.push_str("# Benchmark OCR\n\n");
+        output.push_str("## Classement global\n\n");
+        push_markdown_table(&mut output, &self.ranking());
+
+        for (tag, summaries) in self.tag_breakdown() {
+            output.push_str(&format!("\n## Tag : {}\n\n", tag));
+            push_markdown_table(&mut output, &summaries);
+        }
+
+        output
+    }
+
+    /// Génère le tableau comparatif au format CSV.
+    ///
+    /// Une ligne par configuration pour le global (colonne `Tag` vide),
+    /// puis une ligne par couple (tag, configuration).
+    pub fn to_csv(&self) -> String {
+        self.to_csv_with(&CsvOptions::default())
+    }
+
+    /// Génère le tableau comparatif au format CSV avec un formatage choisi.
+    ///
+    /// Mêmes lignes que [`to_csv()`](Self::to_csv) ; les options s'appliquent
+    /// comme pour [`OcrMetrics::to_csv_with()`].
+    pub fn to_csv_with(&self, options: &CsvOptions) -> String {
+        let headers = [
+            "Config",
+            "Tag",
+            "Images",
+            "Failures",
+            "Mean_CER",
+            "Median_CER",
+            "Mean_WER",
+            "Median_WER",
+            "Total_Time_Ms",
+        ];
+        let mut output = options.format_row(headers.map(|header| options.escape_field(header)));
+
+        for summary in self.ranking() {
+            push_csv_row(&mut output, &summary, "", options);
+        }
+        for (tag, summaries) in self.tag_breakdown() {
+            for summary in summaries {
+                push_csv_row(&mut output, &summary, &tag, options);
+            }
+        }
+
+        output
+    }
+}
+
+/// Charge un manifeste de benchmark depuis un fichier JSON ou TOML.
+///
+/// Les chemins relatifs des images et des références sont résolus par rapport
+/// au répertoire contenant le manifeste.
+///
+/// # Arguments
+///
+/// * `path` - Chemin vers le manifeste (`.json` ou `.toml`)
+///
+/// # Erreurs
+///
+/// Retourne une erreur si :
+/// - Le fichier n'existe pas ou n'est pas lisible
+/// - L'extension n'est pas `.json` ou `.toml`
+/// - Le contenu ne correspond pas à la structure attendue
+pub fn load_manifest(path: &Path) -> Result<BenchmarkManifest> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Impossible de lire le manifeste '{}'", path.display()))?;
+
+    let mut manifest: BenchmarkManifest = match extension.as_deref() {
+        Some("json") => serde_json::from_str(&content)
+            .with_context(|| format!("Manifeste JSON invalide : '{}'", path.display()))?,
+        Some("toml") => toml::from_str(&content)
+            .with_context(|| format!("Manifeste TOML invalide : '{}'", path.display()))?,
+        other => {
+            return Err(anyhow!(
+                "Extension non supportée : '{}'. Utilisez .json ou .toml",
+                other.unwrap_or("(aucune)")
+            ));
+        }
+    };
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    for entry in &mut manifest.images {
+        if entry.image.is_relative() {
+            entry.image = base_dir.join(&entry.image);
+        }
+        if entry.expected.is_relative() {
+            entry.expected = base_dir.join(&entry.expected);
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// Exécute chaque configuration sur chaque image du manifeste.
+///
+/// Les textes de référence sont lus une seule fois. Une extraction en échec
+/// n'interrompt pas le benchmark : elle est comptée dans `failures`.
+///
+/// # Arguments
+///
+/// * `manifest` - Corpus à évaluer
+/// * `configs` - Configurations nommées à comparer
+/// * `runner` - Étape d'OCR (ex: [`TesseractRunner`])
+///
+/// # Erreurs
+///
+/// Retourne une erreur si un texte de référence ne peut pas être lu.
+pub fn run_benchmark(
+    manifest: &BenchmarkManifest,
+    configs: &[NamedConfig],
+    runner: &mut impl OcrRunner,
+) -> Result<BenchmarkReport> {
+    let expected_texts = manifest
+        .images
+        .iter()
+        .map(|entry| {
+            std::fs::read_to_string(&entry.expected).with_context(|| {
+                format!(
+                    "Impossible de lire le fichier de référence '{}'",
+                    entry.expected.display()
+                )
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut results = Vec::with_capacity(configs.len() * manifest.images.len());
+
+    for named in configs {
+        for (entry, expected) in manifest.images.iter().zip(&expected_texts) {
+            let start = Instant::now();
+            let outcome = runner.run(&named.config, &entry.image);
+            let duration = start.elapsed();
+
+            results.push(BenchmarkResult {
+                config_name: named.name.clone(),
+                image: entry.image.clone(),
+                tags: entry.tags.clone(),
+                metrics: outcome.ok().map(|text| compare_ocr_result(&text, expected)),
+                duration,
+            });
+        }
+    }
+
+    Ok(BenchmarkReport {
+        results,
+        config_names: configs.iter().map(|c| c.name.clone()).collect(),
+    })
+}
+
+/// Agrège un ensemble de résultats en statistiques de configuration.
+fn summarize<'a>(name: &str, results: impl Iterator<Item = &'a BenchmarkResult>) -> ConfigSummary {
+    let mut cers = Vec::new();
+    let mut wers = Vec::new();
+    let mut failures = 0;
+    let mut total_time = Duration::ZERO;
+
+    for result in results {
+        total_time += result.duration;
+        match &result.metrics {
+            Some(metrics) => {
+                cers.push(metrics.cer);
+                wers.push(metrics.wer);
+            }
+            None => failures += 1,
+        }
+    }
+
+    ConfigSummary {
+        name: name.to_string(),
+        image_count: cers.len(),
+        failures,
+        mean_cer: mean(&cers),
+        median_cer: median(&mut cers),
+        mean_wer: mean(&wers),
+        median_wer: median(&mut wers),
+        total_time,
+    }
+}
+
+/// Moyenne d'une série (0.0 si vide).
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Médiane d'une série (0.0 si vide). Trie la série en place.
+fn median(values: &mut [f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// Ajoute un tableau Markdown de statistiques à la sortie.
+fn push_markdown_table(output: &mut String, summaries: &[ConfigSummary]) {
+    output.push_str("| Config | Images | Échecs | CER moyen | CER médian | WER moyen | WER médian | Temps total |\n");
+    output.push_str("|---|---|---|---|---|---|---|---|\n");
+    for s in summaries {
+        output.push_str(&format!(
+            "| {} | {} | {} | {:.2}% | {:.2}% | {:.2}% | {:.2}% | {:.2}s |\n",
+            s.name,
+            s.image_count,
+            s.failures,
+            s.mean_cer * 100.0,
+            s.median_cer * 100.0,
+            s.mean_wer * 100.0,
+            s.median_wer * 100.0,
+            s.total_time.as_secs_f64()
+        ));
+    }
+}
+
+/// Ajoute une ligne CSV de statistiques à la sortie.
+fn push_csv_row(output: &mut String, summary: &ConfigSummary, tag: &str, options: &CsvOptions) {
+    output.push_str(&options.format_row([
+        options.escape_field(&summary.name),
+        options.escape_field(tag),
+        options.escape_field(&summary.image_count.to_string()),
+        options.escape_field(&summary.failures.to_string()),
+        options.format_decimal(summary.mean_cer),
+        options.format_decimal(summary.median_cer),
+        options.format_decimal(summary.mean_wer),
+        options.format_decimal(summary.median_wer),
+        options.escape_field(&summary.total_time.as_millis().to_string()),
+    ]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OcrConfig;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    /// Runner factice : retourne un texte prédéfini par (langue, image).
+    ///
+    /// La langue de la configuration sert d'identifiant de configuration.
+    struct CannedRunner {
+        outputs: HashMap<(String, PathBuf), String>,
+    }
+
+    impl OcrRunner for CannedRunner {
+        fn run(&mut self, config: &AppConfig, image: &Path) -> Result<String> {
+            let language = config.ocr.clone().unwrap_or_default().language;
+            self.outputs
+                .get(&(language, image.to_path_buf()))
+                .cloned()
+                .ok_or_else(|| anyhow!("Pas de sortie pour {}", image.display()))
+        }
+    }
+
+    fn config_with_language(name: &str, language: &str) -> NamedConfig {
+        NamedConfig::new(
+            name,
+            AppConfig {
+                ocr: Some(OcrConfig {
+                    language: language.to_string(),
+                    ..OcrConfig::default()
+                }),
+                preprocessing: None,
+                ..AppConfig::default()
+            },
+        )
+    }
+
+    /// Crée un corpus de 3 images (références "hello world") dans un répertoire temporaire.
+    fn make_corpus() -> (TempDir, BenchmarkManifest) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut images = Vec::new();
+        for (name, tags) in [
+            ("a", vec!["invoice"]),
+            ("b", vec!["invoice", "receipt"]),
+            ("c", vec!["receipt"]),
+        ] {
+            let expected = dir.path().join(format!("{}.txt", name));
+            std::fs::write(&expected, "hello world").unwrap();
+            images.push(ManifestEntry {
+                image: PathBuf::from(format!("{}.png", name)),
+                expected,
+                tags: tags.into_iter().map(String::from).collect(),
+            });
+        }
+        (dir, BenchmarkManifest { images })
+    }
+
+    fn canned(entries: &[(&str, &str, &str)]) -> CannedRunner {
+        CannedRunner {
+            outputs: entries
+                .iter()
+                .map(|(lang, image, text)| {
+                    ((lang.to_string(), PathBuf::from(image)), text.to_string())
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_run_benchmark_aggregation() {
+        let (_dir, manifest) = make_corpus();
+        let configs = vec![config_with_language("good", "good")];
+        let mut runner = canned(&[
+            ("good", "a.png", "hello world"),
+            ("good", "b.png", "hello world"),
+            ("good", "c.png", "hello"),
+        ]);
+
+        let report = run_benchmark(&manifest, &configs, &mut runner).unwrap();
+        assert_eq!(report.results.len(), 3);
+
+        let summary = &report.summaries()[0];
+        assert_eq!(summary.image_count, 3);
+        assert_eq!(summary.failures, 0);
+        // CER : 0, 0, 6/11 → moyenne 2/11, médiane 0
+        assert!((summary.mean_cer - 2.0 / 11.0).abs() < 1e-9);
+        assert_eq!(summary.median_cer, 0.0);
+        // WER : 0, 0, 0.5
+        assert!((summary.mean_wer - 0.5 / 3.0).abs() < 1e-9);
+        assert_eq!(summary.median_wer, 0.0);
+    }
+
+    #[test]
+    fn test_run_benchmark_ranking() {
+        let (_dir, manifest) = make_corpus();
+        let configs = vec![
+            config_with_language("bad", "bad"),
+            config_with_language("good", "good"),
+            config_with_language("broken", "broken"),
+        ];
+        let mut runner = canned(&[
+            ("bad", "a.png", "hallo wirld"),
+            ("bad", "b.png", "hallo wirld"),
+            ("bad", "c.png", "hallo wirld"),
+            ("good", "a.png", "hello world"),
+            ("good", "b.png", "hello world"),
+            ("good", "c.png", "hello wirld"),
+        ]);
+
+        let report = run_benchmark(&manifest, &configs, &mut runner).unwrap();
+        let ranking: Vec<String> = report.ranking().into_iter().map(|s| s.name).collect();
+        assert_eq!(ranking, vec!["good", "bad", "broken"]);
+
+        let broken = &report.summaries()[2];
+        assert_eq!(broken.image_count, 0);
+        assert_eq!(broken.failures, 3);
+    }
+
+    #[test]
+    fn test_run_benchmark_tag_breakdown() {
+        let (_dir, manifest) = make_corpus();
+        let configs = vec![config_with_language("cfg", "cfg")];
+        let mut runner = canned(&[
+            ("cfg", "a.png", "hello world"),
+            ("cfg", "b.png", "hello world"),
+            ("cfg", "c.png", "xxxxx xxxxx"),
+        ]);
+
+        let report = run_benchmark(&manifest, &configs, &mut runner).unwrap();
+        let breakdown = report.tag_breakdown();
+
+        assert_eq!(
+            breakdown.keys().collect::<Vec<_>>(),
+            vec!["invoice", "receipt"]
+        );
+        let invoice = &breakdown["invoice"][0];
+        assert_eq!(invoice.image_count, 2);
+        assert_eq!(invoice.mean_cer, 0.0);
+        let receipt = &breakdown["receipt"][0];
+        assert_eq!(receipt.image_count, 2);
+        assert!(receipt.mean_cer > 0.0);
+    }
+
+    #[test]
+    fn test_report_markdown_and_csv() {
+        let (_dir, manifest) = make_corpus();
+        let configs = vec![config_with_language("cfg", "cfg")];
+        let mut runner = canned(&[
+            ("cfg", "a.png", "hello world"),
+            ("cfg", "b.png", "hello world"),
+            ("cfg", "c.png", "hello world"),
+        ]);
+        let report = run_benchmark(&manifest, &configs, &mut runner).unwrap();
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("## Classement global"));
+        assert!(markdown.contains("## Tag : invoice"));
+        assert!(markdown.contains("| cfg | 3 | 0 | 0.00% |"));
+
+        let csv = report.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("Config,Tag,Images"));
+        assert!(lines[1].starts_with("cfg,,3,0,"));
+        assert!(lines.iter().any(|l| l.starts_with("cfg,receipt,2,0,")));
+        assert_eq!(report.to_csv_with(&CsvOptions::default()), csv);
+
+        let options = CsvOptions {
+            delimiter: ';',
+            decimal_separator: ',',
+            precision: 2,
+            ..CsvOptions::default()
+        };
+        let csv = report.to_csv_with(&options);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("Config;Tag;Images"));
+        assert!(lines[1].starts_with("cfg;;3;0;0,00;0,00;"));
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&mut []), 0.0);
+        assert_eq!(median(&mut [3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&mut [4.0, 1.0, 2.0, 3.0]), 2.5);
+    }
+
+    #[test]
+    fn test_load_manifest_resolves_relative_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("bench.toml");
+        std::fs::write(
+            &manifest_path,
+            r#"
+[[images]]
+image = "img/a.png"
+expected = "expected/a.txt"
+tags = ["invoice"]
+
+[[images]]
+image = "/abs/b.png"
+expected = "expected/b.txt"
+"#,
+        )
+        .unwrap();
+
+        let manifest = load_manifest(&manifest_path).unwrap();
+        assert_eq!(manifest.images.len(), 2);
+        assert_eq!(manifest.images[0].image, dir.path().join("img/a.png"));
+        assert_eq!(manifest.images[0].tags, vec!["invoice"]);
+        assert_eq!(manifest.images[1].image, PathBuf::from("/abs/b.png"));
+        assert!(manifest.images[1].tags.is_empty());
+    }
+
+    #[test]
+    fn test_load_manifest_unsupported_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("bench.yaml");
+        std::fs::write(&manifest_path, "images: []").unwrap();
+        assert!(load_manifest(&manifest_path).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+//! Annulation et délais des extractions longues.
+//!
+//! Une image pathologique peut occuper Tesseract pendant plusieurs minutes.
+//! Ce module fournit :
+//!
+//! - [`CancellationToken`] : drapeau partagé, levé par exemple sur Ctrl-C,
+//!   consulté par le pool avant chaque image et par le moteur entre les
+//!   étapes du pipeline ;
+//! - l'attente bornée des sous-processus `tesseract`, tués lorsque le délai
+//!   est dépassé.
+//!
+//! Les deux situations sont signalées par une [`OcrError`], récupérable
+//! avec `downcast_ref` sur l'erreur `anyhow`.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::cancellation::CancellationToken;
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::pool::OcrEnginePool;
+//! use std::path::PathBuf;
+//!
+//! let token = CancellationToken::new();
+//! let pool = OcrEnginePool::new(OcrConfig::default(), None, 2)?;
+//! let images = vec![PathBuf::from("a.png"), PathBuf::from("b.png")];
+//! // Un autre thread peut appeler token.cancel() pour arrêter le traitement
+//! let results = pool.batch_extract_cancellable(&images, &token, |worker, path| {
+//!     worker.extract_text_detailed(path)
+//! });
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::ocr::OcrError;
+use anyhow::{Context, Result};
+use std::io::Read;
+use std::process::{Child, Output};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Intervalle de scrutation d'un sous-processus soumis à un délai.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Drapeau d'annulation partagé entre threads.
+///
+/// Les clones partagent le même drapeau : annuler l'un annule tous les autres.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Crée un jeton non annulé.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Demande l'annulation.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Indique si l'annulation a été demandée.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Retourne une erreur si l'annulation a été demandée.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne [`OcrError::Cancelled`] si [`cancel()`](Self::cancel) a été appelé.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(OcrError::Cancelled.into());
+        }
+        Ok(())
+    }
+}
+
+/// Attend la fin d'un sous-processus et collecte ses sorties, en le tuant au-delà de `timeout`.
+///
+/// Les sorties standard et d'erreur doivent avoir été redirigées vers des
+/// tubes (`Stdio::piped()`) ; elles sont lues en parallèle pour que le
+/// processus ne bloque pas sur un tube plein.
+///
+/// # Erreurs
+///
+/// Retourne [`OcrError::Timeout`] si le processus ne s'est pas terminé à
+/// temps (il est alors tué), ou une erreur d'entrée/sortie si l'attente échoue.
+pub(crate) fn wait_with_timeout(mut child: Child, timeout: Option<Duration>) -> Result<Output> {
+    let Some(timeout) = timeout else {
+        return child
+            .wait_with_output()
+            .context("Échec de l'attente du processus tesseract");
+    };
+
+    let stdout = child.stdout.take().map(spawn_reader);
+    let stderr = child.stderr.take().map(spawn_reader);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child
+            .try_wait()
+            .context("Échec de l'attente du processus tesseract")?
+        {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            // Le processus a pu se terminer entre-temps : l'échec de kill() est sans gravité
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(OcrError::Timeout(timeout).into());
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let collect = |reader: Option<thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .map(|handle| handle.join().unwrap_or_default())
+            .unwrap_or_default()
+    };
+    Ok(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+/// Lit un tube jusqu'à sa fermeture dans un thread dédié.
+fn spawn_reader<R: Read + Send + 'static>(mut pipe: R) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        // Une lecture interrompue (processus tué) conserve ce qui a été lu
+        let _ = pipe.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Command, Stdio};
+
+    fn spawn(program: &str, args: &[&str]) -> Child {
+        Command::new(program)
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_token_shared_between_clones() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        assert!(token.check().is_ok());
+
+        token.cancel();
+
+        assert!(clone.is_cancelled());
+        let error = clone.check().unwrap_err();
+        assert_eq!(error.downcast_ref::<OcrError>(), Some(&OcrError::Cancelled));
+    }
+
+    #[test]
+    fn test_wait_with_timeout_kills_slow_process() {
+        let start = Instant::now();
+        let error = wait_with_timeout(spawn("sleep", &["5"]), Some(Duration::from_millis(100)))
+            .unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<OcrError>(),
+            Some(&OcrError::Timeout(Duration::from_millis(100)))
+        );
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_wait_with_timeout_collects_output() {
+        let output =
+            wait_with_timeout(spawn("echo", &["bonjour"]), Some(Duration::from_secs(5))).unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "bonjour\n");
+    }
+}
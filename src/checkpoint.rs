@@ -0,0 +1,311 @@
+//! Journal de reprise des traitements batch.
+//!
+//! Un lot de plusieurs milliers d'images interrompu (coupure de courant,
+//! manque de mémoire) ne doit pas être relancé depuis le début. Le
+//! [`Journal`] ajoute une ligne JSON par image terminée (chemin, sortie ou
+//! erreur, empreinte SHA-256 du fichier, horodatage) ; au redémarrage, les
+//! images déjà traitées avec succès sont sautées ([`Journal::should_skip()`]).
+//!
+//! Le fichier est un [`AppendLog`] : un arrêt brutal pendant l'écriture ne
+//! laisse au pire qu'une dernière ligne tronquée, ignorée au chargement puis
+//! retirée du fichier avant le prochain ajout.
+//!
+//! # Exemple
+//!
+//! ```
+//! use std::path::Path;
+//! use text_recognition::checkpoint::{Journal, JournalEntry};
+//!
+//! let dir = tempfile::tempdir()?;
+//! let path = dir.path().join("lot.jsonl");
+//!
+//! let mut journal = Journal::open(&path)?;
+//! journal.append(JournalEntry::success("scans/p1.png", Some("out/p1.txt".into()), "ab12"))?;
+//!
+//! // Au redémarrage
+//! let journal = Journal::open(&path)?;
+//! assert!(journal.should_skip(Path::new("scans/p1.png"), "ab12", true));
+//! assert!(!journal.should_skip(Path::new("scans/p2.png"), "cd34", true));
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::jsonl::{AppendLog, LoadedLog, parse_log};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Nom du fichier journal dans les messages d'erreur.
+const JOURNAL_NAME: &str = "journal";
+
+/// Ligne du journal : résultat du traitement d'une image.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Chemin de l'image, tel que collecté.
+    pub input: PathBuf,
+    /// Fichier de résultat écrit, si la sortie est un fichier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<PathBuf>,
+    /// Message d'erreur, si le traitement a échoué.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Empreinte SHA-256 du fichier image (hexadécimal).
+    pub content_hash: String,
+    /// Fin du traitement, en secondes depuis l'epoch Unix.
+    pub timestamp: u64,
+}
+
+impl JournalEntry {
+    /// Entrée d'une image traitée avec succès, horodatée maintenant.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Chemin de l'image
+    /// * `output` - Fichier de résultat, si la sortie est un fichier
+    /// * `content_hash` - Empreinte SHA-256 de l'image
+    pub fn success(
+        input: impl Into<PathBuf>,
+        output: Option<PathBuf>,
+        content_hash: impl Into<String>,
+    ) -> Self {
+        Self {
+            input: input.into(),
+            output,
+            error: None,
+            content_hash: content_hash.into(),
+            timestamp: unix_timestamp(),
+        }
+    }
+
+    /// Entrée d'une image en échec, horodatée maintenant.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Chemin de l'image
+    /// * `error` - Message d'erreur
+    /// * `content_hash` - Empreinte SHA-256 de l'image (vide si illisible)
+    pub fn failure(
+        input: impl Into<PathBuf>,
+        error: impl Into<String>,
+        content_hash: impl Into<String>,
+    ) -> Self {
+        Self {
+            input: input.into(),
+            output: None,
+            error: Some(error.into()),
+            content_hash: content_hash.into(),
+            timestamp: unix_timestamp(),
+        }
+    }
+
+    /// Indique si l'image a été traitée avec succès.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Secondes écoulées depuis l'epoch Unix (0 si l'horloge est antérieure).
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// Contenu d'un journal lu sur disque.
+pub type LoadedJournal = LoadedLog<JournalEntry>;
+
+/// Analyse le contenu d'un journal.
+///
+/// Une dernière ligne illisible et sans fin de ligne (écriture interrompue)
+/// est ignorée ; toute ligne complète illisible est une erreur (voir
+/// [`parse_log()`]).
+///
+/// # Arguments
+///
+/// * `content` - Contenu du fichier journal
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::checkpoint::parse_journal;
+///
+/// let content = "{\"input\":\"a.png\",\"content_hash\":\"01\",\"timestamp\":1}\n{\"input\":\"b.p";
+/// let loaded = parse_journal(content)?;
+/// assert_eq!(loaded.entries.len(), 1);
+/// assert!(loaded.truncated_tail);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Erreurs
+///
+/// Retourne une erreur si une ligne complète n'est pas une entrée valide.
+pub fn parse_journal(content: &str) -> Result<LoadedJournal> {
+    parse_log(content, JOURNAL_NAME)
+}
+
+/// Journal de reprise d'un traitement batch (un fichier JSON Lines).
+///
+/// Seule la dernière entrée de chaque image compte : une image en échec
+/// puis retraitée avec succès est considérée comme réussie.
+#[derive(Debug)]
+pub struct Journal {
+    log: AppendLog<JournalEntry>,
+    latest: HashMap<PathBuf, JournalEntry>,
+}
+
+impl Journal {
+    /// Ouvre le journal, en le créant s'il n'existe pas.
+    ///
+    /// Les entrées existantes sont chargées ; une dernière ligne tronquée est
+    /// retirée du fichier pour que les ajouts suivants restent lisibles.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin du fichier journal
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le fichier ne peut pas être lu ou écrit, ou
+    /// si une ligne complète est invalide.
+    pub fn open(path: &Path) -> Result<Self> {
+        let (log, entries) = AppendLog::<JournalEntry>::open(path, JOURNAL_NAME)?;
+        let latest = entries
+            .into_iter()
+            .map(|entry| (entry.input.clone(), entry))
+            .collect();
+        Ok(Self { log, latest })
+    }
+
+    /// Chemin du fichier journal.
+    pub fn path(&self) -> &Path {
+        self.log.path()
+    }
+
+    /// Indique si une ligne tronquée a été retirée à l'ouverture.
+    pub fn recovered_truncated_tail(&self) -> bool {
+        self.log.recovered_truncated_tail()
+    }
+
+    /// Nombre d'images journalisées.
+    pub fn len(&self) -> usize {
+        self.latest.len()
+    }
+
+    /// Indique si le journal ne contient aucune image.
+    pub fn is_empty(&self) -> bool {
+        self.latest.is_empty()
+    }
+
+    /// Dernière entrée journalisée pour une image.
+    pub fn entry(&self, input: &Path) -> Option<&JournalEntry> {
+        self.latest.get(input)
+    }
+
+    /// Ajoute une entrée et la vide sur disque.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si l'écriture échoue.
+    pub fn append(&mut self, entry: JournalEntry) -> Result<()> {
+        self.log.append(&entry)?;
+        self.latest.insert(entry.input.clone(), entry);
+        Ok(())
+    }
+
+    /// Indique si une image peut être sautée lors d'une reprise.
+    ///
+    /// Une image est sautée si sa dernière entrée porte la même empreinte
+    /// (fichier inchangé) et qu'elle a réussi, ou qu'elle a échoué alors que
+    /// `retry_failed` vaut `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Chemin de l'image
+    /// * `content_hash` - Empreinte SHA-256 actuelle de l'image
+    /// * `retry_failed` - Retraiter les images précédemment en échec
+    pub fn should_skip(&self, input: &Path, content_hash: &str, retry_failed: bool) -> bool {
+        self.latest.get(input).is_some_and(|entry| {
+            entry.content_hash == content_hash && (entry.is_success() || !retry_failed)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(entry: &JournalEntry) -> String {
+        serde_json::to_string(entry).unwrap() + "\n"
+    }
+
+    #[test]
+    fn test_corrupted_middle_line_is_an_error() {
+        let entry = line(&JournalEntry::success("a.png", None, "01"));
+        let content = format!("{}pas du json\n{}", entry, entry);
+
+        let error = parse_journal(&content).unwrap_err();
+        assert!(error.to_string().contains("Ligne 2"), "{}", error);
+    }
+
+    #[test]
+    fn test_should_skip_honors_retry_policy_and_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut journal = Journal::open(&dir.path().join("lot.jsonl")).unwrap();
+        journal
+            .append(JournalEntry::success("ok.png", None, "01"))
+            .unwrap();
+        journal
+            .append(JournalEntry::failure("ko.png", "délai dépassé", "02"))
+            .unwrap();
+
+        assert!(journal.should_skip(Path::new("ok.png"), "01", true));
+        assert!(journal.should_skip(Path::new("ok.png"), "01", false));
+        // Fichier modifié depuis : retraité
+        assert!(!journal.should_skip(Path::new("ok.png"), "ff", false));
+        assert!(!journal.should_skip(Path::new("ko.png"), "02", true));
+        assert!(journal.should_skip(Path::new("ko.png"), "02", false));
+        assert!(!journal.should_skip(Path::new("nouvelle.png"), "03", false));
+
+        // La dernière entrée d'une image l'emporte
+        journal
+            .append(JournalEntry::success("ko.png", None, "02"))
+            .unwrap();
+        assert!(journal.should_skip(Path::new("ko.png"), "02", true));
+    }
+
+    #[test]
+    fn test_resumed_run_processes_remaining_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lot.jsonl");
+        let inputs: Vec<(PathBuf, String)> = (1..=6)
+            .map(|n| (PathBuf::from(format!("p{}.png", n)), format!("{:02}", n)))
+            .collect();
+
+        // Premier passage interrompu après 4 images, dont une en échec
+        {
+            let mut journal = Journal::open(&path).unwrap();
+            for (input, hash) in &inputs[..4] {
+                let entry = if input == Path::new("p2.png") {
+                    JournalEntry::failure(input, "image illisible", hash)
+                } else {
+                    JournalEntry::success(input, None, hash)
+                };
+                journal.append(entry).unwrap();
+            }
+        }
+
+        let journal = Journal::open(&path).unwrap();
+        let remaining = |retry_failed| -> Vec<&str> {
+            inputs
+                .iter()
+                .filter(|(input, hash)| !journal.should_skip(input, hash, retry_failed))
+                .map(|(input, _)| input.to_str().unwrap())
+                .collect()
+        };
+
+        assert_eq!(remaining(true), ["p2.png", "p5.png", "p6.png"]);
+        assert_eq!(remaining(false), ["p5.png", "p6.png"]);
+    }
+}
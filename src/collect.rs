@@ -0,0 +1,515 @@
+//! Collecte des fichiers images à traiter en mode batch.
+//!
+//! [`collect_image_files()`] accepte un fichier, un répertoire (parcouru
+//! récursivement si demandé) ou un pattern glob, et retourne la liste triée
+//! des images trouvées selon les [`CollectOptions`].
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::collect::{CollectOptions, collect_image_files};
+//! use std::path::Path;
+//!
+//! let options = CollectOptions {
+//!     recursive: true,
+//!     extensions: vec!["png".to_string(), "jp2".to_string()],
+//!     ..CollectOptions::default()
+//! };
+//! let files = collect_image_files(Path::new("scans"), &options)?;
+//! println!("{} image(s)", files.len());
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Extensions d'images reconnues par défaut (en minuscules, sans point).
+pub const DEFAULT_IMAGE_EXTENSIONS: [&str; 8] =
+    ["png", "jpg", "jpeg", "tiff", "tif", "bmp", "gif", "webp"];
+
+/// Options de collecte des fichiers images.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectOptions {
+    /// Parcourir les sous-répertoires d'un répertoire.
+    pub recursive: bool,
+
+    /// Nombre maximal de niveaux de sous-répertoires parcourus (mode récursif).
+    ///
+    /// `Some(1)` se limite aux sous-répertoires directs ; `None` n'impose
+    /// pas de limite.
+    pub max_depth: Option<usize>,
+
+    /// Extensions acceptées, sans point et insensibles à la casse.
+    pub extensions: Vec<String>,
+
+    /// Suivre les liens symboliques rencontrés lors du parcours.
+    ///
+    /// Sans cette option, les liens symboliques sont ignorés. Avec, chaque
+    /// répertoire n'est visité qu'une fois, ce qui protège des boucles.
+    pub follow_symlinks: bool,
+
+    /// Inclure les fichiers et répertoires cachés (nom commençant par un point).
+    pub include_hidden: bool,
+}
+
+impl Default for CollectOptions {
+    /// Options par défaut : répertoire non récursif, extensions
+    /// [`DEFAULT_IMAGE_EXTENSIONS`], liens symboliques et fichiers cachés ignorés.
+    fn default() -> Self {
+        Self {
+            recursive: false,
+            max_depth: None,
+            extensions: DEFAULT_IMAGE_EXTENSIONS
+                .iter()
+                .map(|ext| ext.to_string())
+                .collect(),
+            follow_symlinks: false,
+            include_hidden: false,
+        }
+    }
+}
+
+/// Vérifie si un fichier a l'une des extensions données.
+///
+/// La comparaison ignore la casse et un éventuel point initial dans
+/// `extensions` (".JP2" équivaut à "jp2").
+///
+/// # Arguments
+///
+/// * `path` - Chemin du fichier
+/// * `extensions` - Extensions acceptées
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::collect::is_image_file_with;
+/// use std::path::Path;
+///
+/// assert!(is_image_file_with(Path::new("scan.JP2"), &["jp2"]));
+/// assert!(!is_image_file_with(Path::new("notes.txt"), &["png", "jpg"]));
+/// ```
+pub fn is_image_file_with<S: AsRef<str>>(path: &Path, extensions: &[S]) -> bool {
+    let Some(ext) = path.extension() else {
+        return false;
+    };
+    let ext = ext.to_string_lossy();
+
+    extensions.iter().any(|allowed| {
+        allowed
+            .as_ref()
+            .trim_start_matches('.')
+            .eq_ignore_ascii_case(&ext)
+    })
+}
+
+/// Collecte les fichiers images depuis un chemin ou un pattern glob.
+///
+/// Cette fonction gère trois cas :
+/// - Un fichier unique : retourne ce fichier
+/// - Un répertoire : trouve les fichiers images, récursivement si
+///   `options.recursive` est activé
+/// - Un pattern glob (contient `*`, `?` ou `[`) : résout le pattern
+///   (`**` parcourt les sous-répertoires)
+///
+/// Les fichiers sont retournés triés. Le chemin donné est toujours utilisé
+/// tel quel, même s'il est caché ou s'il s'agit d'un lien symbolique.
+///
+/// # Arguments
+///
+/// * `path` - Chemin vers fichier, répertoire, ou pattern glob
+/// * `options` - Options de collecte
+///
+/// # Erreurs
+///
+/// Retourne une erreur si :
+/// - Le chemin n'existe pas (sauf pour les patterns glob)
+/// - Un répertoire ne peut pas être lu
+/// - Aucun fichier image n'est trouvé
+pub fn collect_image_files(path: &Path, options: &CollectOptions) -> Result<Vec<PathBuf>> {
+    // Vérifier si c'est un pattern glob (contient *, ?, [, etc.)
+    let path_str = path.to_string_lossy();
+
+    if is_glob_pattern(&path_str) {
+        let match_options = glob::MatchOptions {
+            require_literal_leading_dot: !options.include_hidden,
+            ..glob::MatchOptions::new()
+        };
+
+        let mut files = Vec::new();
+        for entry in glob::glob_with(&path_str, match_options).context("Pattern glob invalide")? {
+            let entry = entry.context("Erreur lors de la résolution du pattern glob")?;
+            if entry.is_file() && is_image_file_with(&entry, &options.extensions) {
+                files.push(entry);
+            }
+        }
+
+        if files.is_empty() {
+            anyhow::bail!("Aucun fichier image trouvé pour le pattern '{}'", path_str);
+        }
+
+        files.sort();
+        Ok(files)
+    } else if path.is_file() {
+        // Un seul fichier
+        if !is_image_file_with(path, &options.extensions) {
+            anyhow::bail!(
+                "Le fichier '{}' n'est pas une image supportée",
+                path.display()
+            );
+        }
+        Ok(vec![path.to_path_buf()])
+    } else if path.is_dir() {
+        let mut files = Vec::new();
+        let mut visited = HashSet::new();
+        walk_directory(path, 0, options, &mut visited, &mut files)?;
+
+        if files.is_empty() {
+            anyhow::bail!(
+                "Aucun fichier image trouvé dans le répertoire '{}'",
+                path.display()
+            );
+        }
+
+        files.sort();
+        Ok(files)
+    } else {
+        anyhow::bail!("Le chemin '{}' n'existe pas", path.display());
+    }
+}
+
+/// Ajoute à `files` les images de `dir`, puis parcourt ses sous-répertoires si demandé.
+///
+/// `depth` est le niveau de `dir` sous le répertoire initial ; `visited`
+/// contient les chemins canoniques des répertoires déjà parcourus.
+fn walk_directory(
+    dir: &Path,
+    depth: usize,
+    options: &CollectOptions,
+    visited: &mut HashSet<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    // Un lien symbolique peut ramener vers un répertoire déjà parcouru
+    let canonical = dir
+        .canonicalize()
+        .with_context(|| format!("Impossible de résoudre le répertoire '{}'", dir.display()))?;
+    if !visited.insert(canonical) {
+        return Ok(());
+    }
+
+    let can_descend = options.recursive && options.max_depth.is_none_or(|max| depth < max);
+
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("Impossible de lire le répertoire '{}'", dir.display()))?
+    {
+        let entry = entry.context("Erreur lors de la lecture d'une entrée du répertoire")?;
+        let entry_path = entry.path();
+
+        if !options.include_hidden && is_hidden(&entry_path) {
+            continue;
+        }
+
+        let file_type = entry
+            .file_type()
+            .with_context(|| format!("Impossible de lire le type de '{}'", entry_path.display()))?;
+        if file_type.is_symlink() && !options.follow_symlinks {
+            continue;
+        }
+
+        // Suit la cible des liens symboliques ; un lien cassé n'est ni un fichier ni un répertoire
+        if entry_path.is_dir() {
+            if can_descend {
+                walk_directory(&entry_path, depth + 1, options, visited, files)?;
+            }
+        } else if entry_path.is_file() && is_image_file_with(&entry_path, &options.extensions) {
+            files.push(entry_path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Retourne le répertoire de départ de la collecte de [`collect_image_files()`].
+///
+/// Pour un répertoire, le répertoire lui-même ; pour un pattern glob, ses
+/// composants précédant le premier joker (`scans` pour `scans/**/*.png`,
+/// `.` pour `*.png`) ; pour un fichier unique, `None`. Les images collectées
+/// se situent sous ce répertoire, ce qui permet de reproduire leur
+/// arborescence relative (voir [`map_output_path()`](crate::output_path::map_output_path)).
+///
+/// # Arguments
+///
+/// * `path` - Chemin passé à [`collect_image_files()`]
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::collect::input_root;
+/// use std::path::Path;
+///
+/// assert_eq!(input_root(Path::new("scans/**/*.png")).as_deref(), Some(Path::new("scans")));
+/// ```
+pub fn input_root(path: &Path) -> Option<PathBuf> {
+    if is_glob_pattern(&path.to_string_lossy()) {
+        let root: PathBuf = path
+            .components()
+            .take_while(|component| !is_glob_pattern(&component.as_os_str().to_string_lossy()))
+            .collect();
+        return Some(if root.as_os_str().is_empty() {
+            PathBuf::from(".")
+        } else {
+            root
+        });
+    }
+    path.is_dir().then(|| path.to_path_buf())
+}
+
+/// Indique si un chemin contient un joker glob (`*`, `?` ou `[`).
+fn is_glob_pattern(path: &str) -> bool {
+    path.contains('*') || path.contains('?') || path.contains('[')
+}
+
+/// Indique si le nom du fichier ou du répertoire commence par un point.
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Arborescence de test :
+    ///
+    /// ```text
+    /// racine/
+    /// ├── a.png
+    /// ├── notes.txt
+    /// ├── scan.JP2
+    /// ├── .cache/cachee.png
+    /// ├── .cachee.png
+    /// ├── 2023/b.jpg
+    /// ├── 2023/janvier/c.tif
+    /// ├── 2023/boucle -> racine (lien symbolique)
+    /// ├── lien.png -> a.png (lien symbolique)
+    /// └── externe -> autre/ (lien symbolique)
+    /// autre/d.png
+    /// ```
+    fn create_tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("racine");
+        let other = dir.path().join("autre");
+
+        for sub in ["2023/janvier", ".cache"] {
+            fs::create_dir_all(root.join(sub)).unwrap();
+        }
+        fs::create_dir_all(&other).unwrap();
+        for file in [
+            "a.png",
+            "notes.txt",
+            "scan.JP2",
+            ".cache/cachee.png",
+            ".cachee.png",
+            "2023/b.jpg",
+            "2023/janvier/c.tif",
+        ] {
+            fs::write(root.join(file), b"").unwrap();
+        }
+        fs::write(other.join("d.png"), b"").unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::symlink;
+            symlink(&root, root.join("2023/boucle")).unwrap();
+            symlink(root.join("a.png"), root.join("lien.png")).unwrap();
+            symlink(&other, root.join("externe")).unwrap();
+        }
+
+        dir
+    }
+
+    /// Collecte depuis `racine` et retourne les chemins relatifs à `racine`.
+    fn collect_relative(dir: &tempfile::TempDir, options: &CollectOptions) -> Vec<String> {
+        let root = dir.path().join("racine");
+        collect_image_files(&root, options)
+            .unwrap()
+            .iter()
+            .map(|path| {
+                path.strip_prefix(&root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_is_image_file_with() {
+        assert!(is_image_file_with(
+            Path::new("a.PNG"),
+            &DEFAULT_IMAGE_EXTENSIONS
+        ));
+        assert!(is_image_file_with(
+            Path::new("a.webp"),
+            &DEFAULT_IMAGE_EXTENSIONS
+        ));
+        assert!(!is_image_file_with(
+            Path::new("a.jp2"),
+            &DEFAULT_IMAGE_EXTENSIONS
+        ));
+        assert!(is_image_file_with(Path::new("a.jp2"), &[".JP2"]));
+        assert!(!is_image_file_with(Path::new("png"), &["png"]));
+    }
+
+    #[test]
+    fn test_collect_directory_non_recursive() {
+        let dir = create_tree();
+        assert_eq!(
+            collect_relative(&dir, &CollectOptions::default()),
+            ["a.png"]
+        );
+    }
+
+    #[test]
+    fn test_collect_recursive() {
+        let dir = create_tree();
+        let options = CollectOptions {
+            recursive: true,
+            ..CollectOptions::default()
+        };
+        assert_eq!(
+            collect_relative(&dir, &options),
+            ["2023/b.jpg", "2023/janvier/c.tif", "a.png"]
+        );
+    }
+
+    #[test]
+    fn test_collect_recursive_max_depth() {
+        let dir = create_tree();
+        let options = CollectOptions {
+            recursive: true,
+            max_depth: Some(1),
+            ..CollectOptions::default()
+        };
+        assert_eq!(collect_relative(&dir, &options), ["2023/b.jpg", "a.png"]);
+
+        let options = CollectOptions {
+            max_depth: Some(0),
+            ..options
+        };
+        assert_eq!(collect_relative(&dir, &options), ["a.png"]);
+    }
+
+    #[test]
+    fn test_collect_hidden() {
+        let dir = create_tree();
+        let options = CollectOptions {
+            recursive: true,
+            include_hidden: true,
+            ..CollectOptions::default()
+        };
+        assert_eq!(
+            collect_relative(&dir, &options),
+            [
+                ".cache/cachee.png",
+                ".cachee.png",
+                "2023/b.jpg",
+                "2023/janvier/c.tif",
+                "a.png"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_custom_extensions() {
+        let dir = create_tree();
+        let options = CollectOptions {
+            recursive: true,
+            extensions: vec!["jp2".to_string(), "tif".to_string()],
+            ..CollectOptions::default()
+        };
+        assert_eq!(
+            collect_relative(&dir, &options),
+            ["2023/janvier/c.tif", "scan.JP2"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_collect_follow_symlinks_with_loop() {
+        let dir = create_tree();
+        let options = CollectOptions {
+            recursive: true,
+            follow_symlinks: true,
+            ..CollectOptions::default()
+        };
+
+        // La boucle 2023/boucle -> racine n'est pas reparcourue
+        assert_eq!(
+            collect_relative(&dir, &options),
+            [
+                "2023/b.jpg",
+                "2023/janvier/c.tif",
+                "a.png",
+                "externe/d.png",
+                "lien.png"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_glob_skips_hidden() {
+        let dir = create_tree();
+        let pattern = dir.path().join("racine/*.png");
+        let names = |options: &CollectOptions| -> Vec<String> {
+            collect_image_files(&pattern, options)
+                .unwrap()
+                .iter()
+                .map(|f| f.file_name().unwrap().to_string_lossy().to_string())
+                .filter(|name| name != "lien.png")
+                .collect()
+        };
+
+        assert_eq!(names(&CollectOptions::default()), ["a.png"]);
+
+        let hidden = CollectOptions {
+            include_hidden: true,
+            ..CollectOptions::default()
+        };
+        assert_eq!(names(&hidden), [".cachee.png", "a.png"]);
+    }
+
+    #[test]
+    fn test_collect_errors() {
+        let dir = create_tree();
+        let options = CollectOptions::default();
+
+        assert!(collect_image_files(&dir.path().join("absent"), &options).is_err());
+        assert!(collect_image_files(&dir.path().join("racine/notes.txt"), &options).is_err());
+        // Le répertoire "autre" ne contient que d.png, exclu par les extensions
+        let jp2_only = CollectOptions {
+            extensions: vec!["jp2".to_string()],
+            ..CollectOptions::default()
+        };
+        assert!(collect_image_files(&dir.path().join("autre"), &jp2_only).is_err());
+    }
+
+    #[test]
+    fn test_input_root() {
+        let dir = create_tree();
+        let racine = dir.path().join("racine");
+
+        assert_eq!(input_root(&racine), Some(racine.clone()));
+        assert_eq!(input_root(&racine.join("a.png")), None);
+        assert_eq!(
+            input_root(&racine.join("**").join("*.png")),
+            Some(racine.clone())
+        );
+        assert_eq!(
+            input_root(&racine.join("2023").join("*.jpg")),
+            Some(racine.join("2023"))
+        );
+        assert_eq!(input_root(Path::new("*.png")), Some(PathBuf::from(".")));
+    }
+}
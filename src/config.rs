@@ -3,8 +3,46 @@
 //! Ce module fournit les structures et méthodes pour configurer
 //! le moteur OCR avec différents paramètres et modes de segmentation.
 
-use serde::{Deserialize, Serialize};
+use crate::hocr::LineMergeOptions;
+use crate::postprocess::TextCleanup;
+use crate::pyramid::PyramidConfig;
+use anyhow::{Result, anyhow, bail};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+#[cfg(feature = "tesseract")]
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+#[cfg(feature = "tesseract")]
+use std::sync::Arc;
+
+/// Caractères autorisés par défaut pour les tickets de caisse.
+///
+/// Lettres (dont accents français), chiffres et ponctuation courante des tickets.
+/// Les symboles souvent confondus avec des lettres ou des chiffres
+/// (`|`, `~`, `` ` ``, `^`, `_`, `{`, `}`, `[`, `]`, `<`, `>`, `\`) sont exclus.
+pub const RECEIPT_CHAR_WHITELIST: &str = concat!(
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz",
+    "àâäçéèêëîïôöùûüÿÀÂÄÇÉÈÊËÎÏÔÖÙÛÜŸ",
+    "0123456789",
+    ".,:;!?'\"-+/%€$£*#&()=@",
+);
+
+/// Caractères autorisés par défaut pour le preset `alphanumeric`
+/// (plaques d'immatriculation, codes produits).
+pub const ALPHANUMERIC_CHAR_WHITELIST: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Noms des presets disponibles via [`OcrConfig::from_preset_name()`].
+const PRESET_NAMES: [&str; 6] = [
+    "document",
+    "screenshot",
+    "single_line",
+    "photo",
+    "receipt",
+    "alphanumeric",
+];
 
 /// Mode de segmentation de page (Page Segmentation Mode).
 ///
@@ -26,8 +64,20 @@ use std::collections::HashMap;
 ///
 /// let mode = PageSegMode::Auto;
 /// let line_mode = PageSegMode::SingleLine;
+///
+/// // Code numérique, nom de variante ou nom façon Tesseract
+/// assert_eq!("6".parse::<PageSegMode>().unwrap(), PageSegMode::SingleBlock);
+/// assert_eq!("single_block".parse::<PageSegMode>().unwrap(), PageSegMode::SingleBlock);
+/// assert_eq!("sparse-text-osd".parse::<PageSegMode>().unwrap(), PageSegMode::SparseTextOsd);
+/// assert_eq!(PageSegMode::SingleBlock.to_string(), "single-block");
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// # Sérialisation
+///
+/// La sérialisation utilise le nom de la variante (`"SingleBlock"`) ; la
+/// désérialisation accepte en plus toutes les formes de [`FromStr`] ainsi que
+/// le code numérique entier (`"page_seg_mode": 6`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum PageSegMode {
     /// PSM 0: Orientation et détection de script uniquement.
     OsdOnly,
@@ -107,6 +157,274 @@ impl PageSegMode {
             PageSegMode::RawLine => 13,
         }
     }
+
+    /// Crée un mode à partir d'un code PSM Tesseract.
+    ///
+    /// Retourne `None` si le code n'est pas compris entre 0 et 13.
+    ///
+    /// # Arguments
+    ///
+    /// * `psm` - Code PSM Tesseract
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::PageSegMode;
+    ///
+    /// assert_eq!(PageSegMode::from_tesseract_psm(7), Some(PageSegMode::SingleLine));
+    /// assert_eq!(PageSegMode::from_tesseract_psm(14), None);
+    /// ```
+    pub fn from_tesseract_psm(psm: i32) -> Option<Self> {
+        usize::try_from(psm)
+            .ok()
+            .and_then(|index| Self::all().get(index).copied())
+    }
+
+    /// Retourne les 14 modes, dans l'ordre des codes PSM (0 à 13).
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::PageSegMode;
+    ///
+    /// for mode in PageSegMode::all() {
+    ///     println!("PSM {} - {}", mode.to_tesseract_psm(), mode.describe());
+    /// }
+    /// ```
+    pub fn all() -> [PageSegMode; 14] {
+        [
+            PageSegMode::OsdOnly,
+            PageSegMode::AutoOsd,
+            PageSegMode::AutoOnly,
+            PageSegMode::Auto,
+            PageSegMode::SingleColumn,
+            PageSegMode::SingleBlockVertText,
+            PageSegMode::SingleBlock,
+            PageSegMode::SingleLine,
+            PageSegMode::SingleWord,
+            PageSegMode::CircleWord,
+            PageSegMode::SingleChar,
+            PageSegMode::SparseText,
+            PageSegMode::SparseTextOsd,
+            PageSegMode::RawLine,
+        ]
+    }
+
+    /// Description courte du mode, telle qu'affichée par `--test-all-psm`.
+    pub fn describe(self) -> &'static str {
+        match self {
+            PageSegMode::OsdOnly => "OSD Only",
+            PageSegMode::AutoOsd => "Auto with OSD",
+            PageSegMode::AutoOnly => "Auto without OSD",
+            PageSegMode::Auto => "Auto (default)",
+            PageSegMode::SingleColumn => "Single column",
+            PageSegMode::SingleBlockVertText => "Single vertical block",
+            PageSegMode::SingleBlock => "Single block",
+            PageSegMode::SingleLine => "Single line",
+            PageSegMode::SingleWord => "Single word",
+            PageSegMode::CircleWord => "Circle word",
+            PageSegMode::SingleChar => "Single char",
+            PageSegMode::SparseText => "Sparse text",
+            PageSegMode::SparseTextOsd => "Sparse text with OSD",
+            PageSegMode::RawLine => "Raw line",
+        }
+    }
+
+    /// Nom façon Tesseract (minuscules séparées par des tirets), utilisé par `Display`.
+    fn tesseract_name(self) -> &'static str {
+        match self {
+            PageSegMode::OsdOnly => "osd-only",
+            PageSegMode::AutoOsd => "auto-osd",
+            PageSegMode::AutoOnly => "auto-only",
+            PageSegMode::Auto => "auto",
+            PageSegMode::SingleColumn => "single-column",
+            PageSegMode::SingleBlockVertText => "single-block-vert-text",
+            PageSegMode::SingleBlock => "single-block",
+            PageSegMode::SingleLine => "single-line",
+            PageSegMode::SingleWord => "single-word",
+            PageSegMode::CircleWord => "circle-word",
+            PageSegMode::SingleChar => "single-char",
+            PageSegMode::SparseText => "sparse-text",
+            PageSegMode::SparseTextOsd => "sparse-text-osd",
+            PageSegMode::RawLine => "raw-line",
+        }
+    }
+}
+
+impl fmt::Display for PageSegMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.tesseract_name())
+    }
+}
+
+impl FromStr for PageSegMode {
+    type Err = anyhow::Error;
+
+    /// Accepte, sans tenir compte de la casse, le code numérique (`"6"`), le
+    /// nom de variante (`"SingleBlock"`, `"single_block"`) ou le nom façon
+    /// Tesseract (`"single-block"`).
+    fn from_str(s: &str) -> Result<Self> {
+        let trimmed = s.trim();
+        if let Ok(psm) = trimmed.parse::<i32>() {
+            return Self::from_tesseract_psm(psm)
+                .ok_or_else(|| anyhow!("Mode PSM invalide : {} (attendu entre 0 et 13)", psm));
+        }
+
+        // "single-block", "single_block" et "SingleBlock" se ramènent à "singleblock"
+        let normalized: String = trimmed
+            .chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .flat_map(char::to_lowercase)
+            .collect();
+        Self::all()
+            .into_iter()
+            .find(|mode| mode.tesseract_name().replace('-', "") == normalized)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::all()
+                    .iter()
+                    .map(|mode| mode.tesseract_name())
+                    .collect();
+                anyhow!(
+                    "Mode PSM inconnu : '{}'. Valeurs acceptées : 0 à 13 ou {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl<'de> Deserialize<'de> for PageSegMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(PageSegModeVisitor)
+    }
+}
+
+/// Désérialise un [`PageSegMode`] depuis une chaîne ou un code entier.
+struct PageSegModeVisitor;
+
+impl Visitor<'_> for PageSegModeVisitor {
+    type Value = PageSegMode;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("un mode PSM (code 0 à 13 ou nom, ex: \"SingleBlock\", \"single-block\")")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<PageSegMode, E> {
+        value.parse().map_err(|e: anyhow::Error| E::custom(e))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<PageSegMode, E> {
+        i32::try_from(value)
+            .ok()
+            .and_then(PageSegMode::from_tesseract_psm)
+            .ok_or_else(|| {
+                E::custom(format!(
+                    "Mode PSM invalide : {} (attendu entre 0 et 13)",
+                    value
+                ))
+            })
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<PageSegMode, E> {
+        i64::try_from(value)
+            .map_err(|_| E::custom(format!("Mode PSM invalide : {}", value)))
+            .and_then(|value| self.visit_i64(value))
+    }
+}
+
+/// Moteur de reconnaissance de Tesseract (OCR Engine Mode).
+///
+/// Tesseract embarque deux moteurs : l'ancien moteur à base de motifs
+/// ("legacy") et le réseau LSTM introduit en version 4.
+///
+/// # Modes
+///
+/// - `LegacyOnly` : Moteur legacy uniquement (OEM 0)
+/// - `LstmOnly` : Réseau LSTM uniquement (OEM 1)
+/// - `LegacyPlusLstm` : Combinaison des deux moteurs (OEM 2)
+/// - `Default` : Choix de Tesseract selon les modèles disponibles (OEM 3)
+///
+/// Les modes 0 et 2 nécessitent des fichiers `.traineddata` contenant le
+/// modèle legacy (dépôt `tessdata`) ; les modèles `tessdata_fast` et
+/// `tessdata_best` ne contiennent que le réseau LSTM.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::config::OcrEngineMode;
+///
+/// assert_eq!(OcrEngineMode::default(), OcrEngineMode::Default);
+/// assert_eq!(OcrEngineMode::LstmOnly.to_tesseract_oem(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OcrEngineMode {
+    /// OEM 0: Moteur legacy uniquement.
+    LegacyOnly,
+
+    /// OEM 1: Réseau de neurones LSTM uniquement.
+    LstmOnly,
+
+    /// OEM 2: Moteur legacy combiné au LSTM.
+    LegacyPlusLstm,
+
+    /// OEM 3: Mode par défaut, selon les modèles disponibles.
+    #[default]
+    Default,
+}
+
+impl OcrEngineMode {
+    /// Convertit le mode vers le code OEM Tesseract (0-3).
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::OcrEngineMode;
+    ///
+    /// assert_eq!(OcrEngineMode::LegacyOnly.to_tesseract_oem(), 0);
+    /// assert_eq!(OcrEngineMode::Default.to_tesseract_oem(), 3);
+    /// ```
+    pub fn to_tesseract_oem(self) -> i32 {
+        match self {
+            OcrEngineMode::LegacyOnly => 0,
+            OcrEngineMode::LstmOnly => 1,
+            OcrEngineMode::LegacyPlusLstm => 2,
+            OcrEngineMode::Default => 3,
+        }
+    }
+
+    /// Crée un mode à partir d'un code OEM Tesseract.
+    ///
+    /// Retourne `None` si le code n'est pas compris entre 0 et 3.
+    ///
+    /// # Arguments
+    ///
+    /// * `oem` - Code OEM Tesseract
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::OcrEngineMode;
+    ///
+    /// assert_eq!(OcrEngineMode::from_tesseract_oem(2), Some(OcrEngineMode::LegacyPlusLstm));
+    /// assert_eq!(OcrEngineMode::from_tesseract_oem(4), None);
+    /// ```
+    pub fn from_tesseract_oem(oem: i32) -> Option<Self> {
+        match oem {
+            0 => Some(OcrEngineMode::LegacyOnly),
+            1 => Some(OcrEngineMode::LstmOnly),
+            2 => Some(OcrEngineMode::LegacyPlusLstm),
+            3 => Some(OcrEngineMode::Default),
+            _ => None,
+        }
+    }
+
+    /// Indique si le mode nécessite le modèle legacy dans les données linguistiques.
+    pub fn requires_legacy_data(self) -> bool {
+        matches!(
+            self,
+            OcrEngineMode::LegacyOnly | OcrEngineMode::LegacyPlusLstm
+        )
+    }
 }
 
 /// Configuration pour le moteur OCR.
@@ -132,6 +450,7 @@ impl PageSegMode {
 ///     page_seg_mode: PageSegMode::SingleBlock,
 ///     dpi: 300,
 ///     tesseract_variables: variables,
+///     ..OcrConfig::default()
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -157,6 +476,169 @@ pub struct OcrConfig {
     /// - `tessedit_char_blacklist`: Caractères interdits
     /// - `preserve_interword_spaces`: Préserver les espaces multiples ("1" = oui, "0" = non)
     pub tesseract_variables: HashMap<String, String>,
+
+    /// Nettoyage appliqué automatiquement au texte extrait.
+    ///
+    /// Par défaut, seuls les espaces de fin sont supprimés. `None` conserve
+    /// la sortie brute de Tesseract.
+    #[serde(default = "default_cleanup")]
+    pub cleanup: Option<TextCleanup>,
+
+    /// Moteur de reconnaissance Tesseract (OEM).
+    ///
+    /// `OcrEngineMode::Default` (OEM 3) lorsque le champ est absent.
+    #[serde(default)]
+    pub engine_mode: OcrEngineMode,
+
+    /// Ajuste le DPI d'après la taille du texte reconnu.
+    ///
+    /// Utile lorsque la résolution réelle de l'image est inconnue : une
+    /// première analyse au DPI configuré mesure la hauteur du texte, supposé
+    /// composé en 10 à 12 points. Si la résolution déduite s'écarte de plus
+    /// de 25 %, l'image est analysée à nouveau avec cette résolution et le
+    /// résultat comptant le plus de mots est conservé. Désactivé par défaut
+    /// (une seconde analyse double le temps de traitement).
+    #[serde(default)]
+    pub adaptive_dpi: bool,
+
+    /// Fichier de mots utilisateur (`--user-words` de Tesseract).
+    ///
+    /// Un mot par ligne, ajoutés au dictionnaire de la langue : utile pour un
+    /// vocabulaire métier (références produit, noms propres). Tesseract lit
+    /// ce fichier à l'initialisation uniquement ; lorsqu'il est défini,
+    /// l'extraction passe par le binaire `tesseract` (voir
+    /// [`OcrEngine`](crate::ocr::OcrEngine)).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_words: Option<PathBuf>,
+
+    /// Fichier de motifs utilisateur (`--user-patterns` de Tesseract).
+    ///
+    /// Un motif par ligne, par exemple `\d\d-\d\d\d` pour une
+    /// référence « 12-345 ». Même contrainte d'initialisation que
+    /// [`user_words`](Self::user_words).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_patterns: Option<PathBuf>,
+
+    /// Fichier temporaire créé par [`with_vocabulary()`](Self::with_vocabulary).
+    ///
+    /// Partagé entre les copies de la configuration (et donc par le moteur
+    /// qui la détient) : le fichier est supprimé avec la dernière d'entre
+    /// elles. Jamais sérialisé. Absent sans la feature `tesseract`.
+    #[cfg(feature = "tesseract")]
+    #[serde(skip)]
+    pub vocabulary_file: Option<Arc<tempfile::TempPath>>,
+
+    /// Limite Tesseract à un thread pour des résultats reproductibles.
+    ///
+    /// Le moteur LSTM répartit son travail entre threads OpenMP ; selon leur
+    /// nombre, deux exécutions identiques peuvent différer de quelques
+    /// caractères. Activé, chaque appel au binaire `tesseract` reçoit
+    /// `OMP_THREAD_LIMIT=1`. Tesseract n'a pas de variable équivalente
+    /// (`set_variable`) : pour l'OCR par la bibliothèque, chargée dans le
+    /// processus, lancer le programme avec `OMP_THREAD_LIMIT=1` dans son
+    /// environnement (OpenMP lit la variable à son initialisation). Voir
+    /// aussi [`determinism`](crate::determinism). Désactivé par défaut.
+    #[serde(default)]
+    pub deterministic: bool,
+
+    /// Passer en [`PageSegMode::SingleBlockVertText`] (PSM 5) lorsque l'image
+    /// contient du texte vertical.
+    ///
+    /// Avant chaque extraction, [`detect_vertical_text()`](crate::preprocessing::detect_vertical_text)
+    /// analyse l'image : si le texte est disposé en colonnes verticales
+    /// (japonais, chinois, étiquettes tournées), l'image est lue en PSM 5
+    /// et un avertissement le signale. Sans effet si `page_seg_mode` vaut
+    /// déjà PSM 5 ou [`PageSegMode::OsdOnly`]. Désactivé par défaut.
+    #[serde(default)]
+    pub auto_vertical: bool,
+
+    /// Relire la page ligne par ligne lorsque Tesseract n'y reconnaît aucun mot.
+    ///
+    /// Sur certaines polices stylisées, la segmentation de page de Tesseract
+    /// ne trouve rien alors que chaque ligne, isolée, est lue correctement.
+    /// Activé, une extraction au texte vide sur une page que
+    /// [`is_blank_page()`](crate::preprocessing::is_blank_page) juge non
+    /// vide est reprise : les lignes sont détectées par projection
+    /// horizontale ([`detect_text_lines()`](crate::preprocessing::detect_text_lines)),
+    /// lues une à une en [`PageSegMode::SingleLine`] (PSM 7) et leurs textes
+    /// joints de haut en bas. Un avertissement indique le nombre de lignes
+    /// lues. Désactivé par défaut.
+    #[serde(default)]
+    pub fallback_line_segmentation: bool,
+
+    /// Fusionner les lignes que Tesseract coupe en deux dans l'extraction structurée.
+    ///
+    /// Un exposant ou un symbole monétaire légèrement surélevé fait souvent
+    /// émettre deux lignes hOCR pour une seule ligne visuelle. Renseigné,
+    /// [`OcrEngine::extract_structured()`](crate::ocr::OcrEngine::extract_structured)
+    /// applique [`merge_split_lines()`](crate::hocr::merge_split_lines) avec
+    /// ces critères avant de construire le résultat. Désactivé (`None`) par
+    /// défaut.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_merge: Option<LineMergeOptions>,
+
+    /// Ne pas lire les illustrations de la page (photos, logos, bruit).
+    ///
+    /// Activé, [`OcrEngine::extract_text_multilang()`](crate::ocr::OcrEngine::extract_text_multilang)
+    /// retourne un texte vide pour les régions classées
+    /// [`RegionClass::Image`](crate::segmentation::RegionClass::Image), et
+    /// [`OcrEngine::extract_hocr_document()`](crate::ocr::OcrEngine::extract_hocr_document)
+    /// retire les mots des paragraphes classés comme tels (voir
+    /// [`drop_words_in_image_regions()`](crate::segmentation::drop_words_in_image_regions)).
+    /// Désactivé par défaut.
+    #[serde(default)]
+    pub skip_image_regions: bool,
+
+    /// Fichiers de configuration Tesseract à appliquer (ex: "digits", "./mon.cfg").
+    ///
+    /// Un nom simple désigne un fichier du répertoire `configs` de tessdata
+    /// ; un chemin (contenant `/`) est lu tel quel. Le binaire `tesseract`
+    /// (hOCR, orientation, vocabulaire utilisateur) les reçoit en fin de
+    /// ligne de commande ; l'OCR par la bibliothèque lit chaque fichier
+    /// (voir [`resolve_tesseract_config()`]) et applique ses lignes
+    /// `variable valeur`. Les [`tesseract_variables`](Self::tesseract_variables)
+    /// l'emportent sur les valeurs des fichiers. Aucun par défaut.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub config_names: Vec<String>,
+
+    /// Répertoire des modèles `.traineddata` de ce moteur.
+    ///
+    /// Transmis à la bibliothèque à l'initialisation (`datapath`) et au
+    /// binaire `tesseract` (`--tessdata-dir`), sans jamais modifier
+    /// `TESSDATA_PREFIX` : deux moteurs d'un même processus peuvent lire des
+    /// modèles différents. Vérifié à la création du moteur (voir
+    /// [`validate_tessdata_dir()`](Self::validate_tessdata_dir)). `None`
+    /// (par défaut) laisse Tesseract chercher ses modèles comme d'habitude.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tessdata_dir: Option<PathBuf>,
+
+    /// Nombre maximal de threads OpenMP des appels au binaire `tesseract`.
+    ///
+    /// Passé dans l'environnement du seul processus enfant
+    /// (`OMP_THREAD_LIMIT`), l'environnement du programme restant intact.
+    /// [`deterministic`](Self::deterministic) l'emporte (un thread). Comme
+    /// pour `deterministic`, Tesseract n'a pas de variable pour limiter les
+    /// threads de la bibliothèque chargée dans le processus : l'OCR par la
+    /// bibliothèque garde la limite lue par OpenMP au démarrage. `None` (par
+    /// défaut) laisse OpenMP choisir.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threads: Option<u32>,
+
+    /// Lire l'image à des échelles croissantes et s'arrêter dès que le texte est stable.
+    ///
+    /// Renseigné, [`OcrEngine::extract_text_detailed()`](crate::ocr::OcrEngine::extract_text_detailed)
+    /// passe par [`OcrEngine::extract_text_pyramid()`](crate::ocr::OcrEngine::extract_text_pyramid)
+    /// avec ces échelles et ces seuils : une grande photo est d'abord lue
+    /// réduite, la pleine résolution n'étant analysée que si les niveaux
+    /// réduits ne concordent pas (voir [`pyramid`](crate::pyramid)).
+    /// Désactivé (`None`) par défaut.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pyramid: Option<PyramidConfig>,
+}
+
+/// Nettoyage par défaut, utilisé lorsque le champ est absent d'un fichier de configuration.
+fn default_cleanup() -> Option<TextCleanup> {
+    Some(TextCleanup::default())
 }
 
 impl Default for OcrConfig {
@@ -168,17 +650,29 @@ impl Default for OcrConfig {
     /// - `page_seg_mode`: `PageSegMode::Auto` (détection automatique)
     /// - `dpi`: 300 (résolution standard pour documents scannés)
     /// - `tesseract_variables`: HashMap vide (aucune variable personnalisée)
+    /// - `cleanup`: suppression des espaces de fin uniquement
+    /// - `engine_mode`: `OcrEngineMode::Default` (OEM 3)
+    /// - `adaptive_dpi`: désactivé
+    /// - `user_words`, `user_patterns`: aucun fichier
+    /// - `deterministic`: désactivé
+    /// - `auto_vertical`, `fallback_line_segmentation`: désactivés
+    /// - `line_merge`: aucune fusion des lignes coupées
+    /// - `skip_image_regions`: désactivé (les illustrations sont lues)
+    /// - `config_names`: aucun fichier de configuration Tesseract
+    /// - `tessdata_dir`, `threads`: répertoire et threads habituels de Tesseract
     ///
     /// # Exemple
     ///
     /// ```
-    /// use text_recognition::config::{OcrConfig, PageSegMode};
+    /// use text_recognition::config::{OcrConfig, OcrEngineMode, PageSegMode};
     ///
     /// let config = OcrConfig::default();
     /// assert_eq!(config.language, "fra");
     /// assert_eq!(config.page_seg_mode, PageSegMode::Auto);
     /// assert_eq!(config.dpi, 300);
     /// assert!(config.tesseract_variables.is_empty());
+    /// assert!(config.cleanup.is_some());
+    /// assert_eq!(config.engine_mode, OcrEngineMode::Default);
     /// ```
     fn default() -> Self {
         Self {
@@ -186,6 +680,22 @@ impl Default for OcrConfig {
             page_seg_mode: PageSegMode::Auto,
             dpi: 300,
             tesseract_variables: HashMap::new(),
+            cleanup: default_cleanup(),
+            engine_mode: OcrEngineMode::Default,
+            adaptive_dpi: false,
+            user_words: None,
+            user_patterns: None,
+            #[cfg(feature = "tesseract")]
+            vocabulary_file: None,
+            deterministic: false,
+            auto_vertical: false,
+            fallback_line_segmentation: false,
+            line_merge: None,
+            skip_image_regions: false,
+            config_names: Vec::new(),
+            tessdata_dir: None,
+            threads: None,
+            pyramid: None,
         }
     }
 }
@@ -225,11 +735,14 @@ impl OcrConfig {
     /// Pour utiliser ce preset avec un moteur OCR :
     ///
     /// ```no_run
+    /// # #[cfg(feature = "tesseract")]
+    /// # {
     /// use text_recognition::config::OcrConfig;
     /// use text_recognition::ocr::OcrEngine;
     ///
     /// let config = OcrConfig::document_preset();
     /// let mut engine = OcrEngine::new(config)?;
+    /// # }
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn document_preset() -> Self {
@@ -242,6 +755,22 @@ impl OcrConfig {
             page_seg_mode: PageSegMode::Auto,
             dpi: 300,
             tesseract_variables: variables,
+            cleanup: default_cleanup(),
+            engine_mode: OcrEngineMode::Default,
+            adaptive_dpi: false,
+            user_words: None,
+            user_patterns: None,
+            #[cfg(feature = "tesseract")]
+            vocabulary_file: None,
+            deterministic: false,
+            auto_vertical: false,
+            fallback_line_segmentation: false,
+            line_merge: None,
+            skip_image_regions: false,
+            config_names: Vec::new(),
+            tessdata_dir: None,
+            threads: None,
+            pyramid: None,
         }
     }
 
@@ -280,11 +809,14 @@ impl OcrConfig {
     /// Pour utiliser ce preset avec un moteur OCR :
     ///
     /// ```no_run
+    /// # #[cfg(feature = "tesseract")]
+    /// # {
     /// use text_recognition::config::OcrConfig;
     /// use text_recognition::ocr::OcrEngine;
     ///
     /// let config = OcrConfig::screenshot_preset();
     /// let mut engine = OcrEngine::new(config)?;
+    /// # }
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn screenshot_preset() -> Self {
@@ -293,6 +825,22 @@ impl OcrConfig {
             page_seg_mode: PageSegMode::Auto,
             dpi: 96,
             tesseract_variables: HashMap::new(),
+            cleanup: default_cleanup(),
+            engine_mode: OcrEngineMode::Default,
+            adaptive_dpi: false,
+            user_words: None,
+            user_patterns: None,
+            #[cfg(feature = "tesseract")]
+            vocabulary_file: None,
+            deterministic: false,
+            auto_vertical: false,
+            fallback_line_segmentation: false,
+            line_merge: None,
+            skip_image_regions: false,
+            config_names: Vec::new(),
+            tessdata_dir: None,
+            threads: None,
+            pyramid: None,
         }
     }
 
@@ -331,11 +879,14 @@ impl OcrConfig {
     /// Pour utiliser ce preset avec un moteur OCR :
     ///
     /// ```no_run
+    /// # #[cfg(feature = "tesseract")]
+    /// # {
     /// use text_recognition::config::OcrConfig;
     /// use text_recognition::ocr::OcrEngine;
     ///
     /// let config = OcrConfig::single_line_preset();
     /// let mut engine = OcrEngine::new(config)?;
+    /// # }
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn single_line_preset() -> Self {
@@ -344,6 +895,22 @@ impl OcrConfig {
             page_seg_mode: PageSegMode::SingleLine,
             dpi: 150,
             tesseract_variables: HashMap::new(),
+            cleanup: default_cleanup(),
+            engine_mode: OcrEngineMode::Default,
+            adaptive_dpi: false,
+            user_words: None,
+            user_patterns: None,
+            #[cfg(feature = "tesseract")]
+            vocabulary_file: None,
+            deterministic: false,
+            auto_vertical: false,
+            fallback_line_segmentation: false,
+            line_merge: None,
+            skip_image_regions: false,
+            config_names: Vec::new(),
+            tessdata_dir: None,
+            threads: None,
+            pyramid: None,
         }
     }
 
@@ -389,11 +956,14 @@ impl OcrConfig {
     /// Pour utiliser ce preset avec un moteur OCR :
     ///
     /// ```no_run
+    /// # #[cfg(feature = "tesseract")]
+    /// # {
     /// use text_recognition::config::OcrConfig;
     /// use text_recognition::ocr::OcrEngine;
     ///
     /// let config = OcrConfig::photo_preset();
     /// let mut engine = OcrEngine::new(config)?;
+    /// # }
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn photo_preset() -> Self {
@@ -406,73 +976,961 @@ impl OcrConfig {
             page_seg_mode: PageSegMode::Auto,
             dpi: 200,
             tesseract_variables: variables,
+            cleanup: default_cleanup(),
+            engine_mode: OcrEngineMode::Default,
+            adaptive_dpi: false,
+            user_words: None,
+            user_patterns: None,
+            #[cfg(feature = "tesseract")]
+            vocabulary_file: None,
+            deterministic: false,
+            auto_vertical: false,
+            fallback_line_segmentation: false,
+            line_merge: None,
+            skip_image_regions: false,
+            config_names: Vec::new(),
+            tessdata_dir: None,
+            threads: None,
+            pyramid: None,
         }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Test de la configuration par défaut.
-    #[test]
-    fn test_default_config() {
-        let config = OcrConfig::default();
 
-        assert_eq!(config.language, "fra");
-        assert_eq!(config.page_seg_mode, PageSegMode::Auto);
-        assert_eq!(config.dpi, 300);
-        assert!(config.tesseract_variables.is_empty());
+    /// Crée une configuration préréglée optimisée pour les tickets de caisse.
+    ///
+    /// Ce preset est idéal pour :
+    /// - Tickets de caisse et reçus de carte bancaire
+    /// - Colonnes étroites avec montants alignés
+    /// - Textes courts et nombres épars
+    ///
+    /// # Configuration appliquée
+    ///
+    /// - **PSM** : `SingleColumn` (mode 4, colonne unique de texte)
+    /// - **DPI** : 400 (les tickets thermiques ont de petits caractères)
+    /// - **Variables Tesseract** :
+    ///   - `preserve_interword_spaces` : "1" (conserve l'alignement des montants)
+    ///   - `tessedit_char_whitelist` : [`RECEIPT_CHAR_WHITELIST`]
+    ///
+    /// Pour une liste de caractères différente, voir
+    /// [`receipt_preset_with_whitelist()`](Self::receipt_preset_with_whitelist).
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::{OcrConfig, PageSegMode};
+    ///
+    /// let config = OcrConfig::receipt_preset();
+    /// assert_eq!(config.page_seg_mode, PageSegMode::SingleColumn);
+    /// assert_eq!(config.dpi, 400);
+    /// ```
+    pub fn receipt_preset() -> Self {
+        Self::receipt_preset_with_whitelist(RECEIPT_CHAR_WHITELIST)
     }
 
-    /// Test du preset pour documents.
-    #[test]
-    fn test_document_preset() {
-        let config = OcrConfig::document_preset();
-
-        // Vérifier les paramètres de base
-        assert_eq!(config.language, "fra");
-        assert_eq!(config.page_seg_mode, PageSegMode::Auto);
-        assert_eq!(config.dpi, 300);
+    /// Crée un preset tickets de caisse avec une liste de caractères personnalisée.
+    ///
+    /// Identique à [`receipt_preset()`](Self::receipt_preset), avec
+    /// `tessedit_char_whitelist` défini à `whitelist`.
+    ///
+    /// # Arguments
+    ///
+    /// * `whitelist` - Caractères autorisés (ex: sans `€` pour des tickets en dollars)
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::OcrConfig;
+    ///
+    /// let config = OcrConfig::receipt_preset_with_whitelist("0123456789.,$ ");
+    /// assert_eq!(
+    ///     config.tesseract_variables.get("tessedit_char_whitelist"),
+    ///     Some(&"0123456789.,$ ".to_string())
+    /// );
+    /// ```
+    pub fn receipt_preset_with_whitelist(whitelist: &str) -> Self {
+        let mut variables = HashMap::new();
+        // Conserver les espaces d'alignement entre libellés et montants
+        variables.insert("preserve_interword_spaces".to_string(), "1".to_string());
+        variables.insert("tessedit_char_whitelist".to_string(), whitelist.to_string());
 
-        // Vérifier les variables Tesseract spécifiques
-        assert_eq!(config.tesseract_variables.len(), 1);
-        assert_eq!(
-            config.tesseract_variables.get("preserve_interword_spaces"),
-            Some(&"1".to_string())
-        );
+        Self {
+            language: "fra".to_string(),
+            page_seg_mode: PageSegMode::SingleColumn,
+            dpi: 400,
+            tesseract_variables: variables,
+            cleanup: default_cleanup(),
+            engine_mode: OcrEngineMode::Default,
+            adaptive_dpi: false,
+            user_words: None,
+            user_patterns: None,
+            #[cfg(feature = "tesseract")]
+            vocabulary_file: None,
+            deterministic: false,
+            auto_vertical: false,
+            fallback_line_segmentation: false,
+            line_merge: None,
+            skip_image_regions: false,
+            config_names: Vec::new(),
+            tessdata_dir: None,
+            threads: None,
+            pyramid: None,
+        }
     }
 
-    /// Test du preset pour captures d'écran.
-    #[test]
-    fn test_screenshot_preset() {
-        let config = OcrConfig::screenshot_preset();
-
-        // Vérifier les paramètres de base
-        assert_eq!(config.language, "fra");
-        assert_eq!(config.page_seg_mode, PageSegMode::Auto);
-        assert_eq!(config.dpi, 96); // DPI spécifique aux écrans
+    /// Crée une configuration préréglée pour les codes alphanumériques courts.
+    ///
+    /// Ce preset est idéal pour :
+    /// - Plaques d'immatriculation recadrées
+    /// - Numéros de série et codes produits
+    /// - Identifiants de conteneurs
+    ///
+    /// # Configuration appliquée
+    ///
+    /// - **PSM** : `SingleLine` (mode 7). Pour un code sans espace,
+    ///   `SingleWord` (mode 8) peut donner de meilleurs résultats.
+    /// - **DPI** : 300
+    /// - **Variables Tesseract** :
+    ///   - `tessedit_char_whitelist` : `whitelist`
+    ///
+    /// # Arguments
+    ///
+    /// * `whitelist` - Caractères autorisés (ex: [`ALPHANUMERIC_CHAR_WHITELIST`])
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::{OcrConfig, PageSegMode};
+    ///
+    /// let config = OcrConfig::alphanumeric_preset("ABCDEFGHJKLMNPQRSTUVWXYZ0123456789-");
+    /// assert_eq!(config.page_seg_mode, PageSegMode::SingleLine);
+    /// ```
+    pub fn alphanumeric_preset(whitelist: &str) -> Self {
+        let mut variables = HashMap::new();
+        variables.insert("tessedit_char_whitelist".to_string(), whitelist.to_string());
 
-        // Vérifier qu'aucune variable Tesseract n'est définie
-        assert!(config.tesseract_variables.is_empty());
+        Self {
+            language: "fra".to_string(),
+            page_seg_mode: PageSegMode::SingleLine,
+            dpi: 300,
+            tesseract_variables: variables,
+            cleanup: default_cleanup(),
+            engine_mode: OcrEngineMode::Default,
+            adaptive_dpi: false,
+            user_words: None,
+            user_patterns: None,
+            #[cfg(feature = "tesseract")]
+            vocabulary_file: None,
+            deterministic: false,
+            auto_vertical: false,
+            fallback_line_segmentation: false,
+            line_merge: None,
+            skip_image_regions: false,
+            config_names: Vec::new(),
+            tessdata_dir: None,
+            threads: None,
+            pyramid: None,
+        }
     }
 
-    /// Test du preset pour ligne de texte unique.
-    #[test]
-    fn test_single_line_preset() {
-        let config = OcrConfig::single_line_preset();
-
-        // Vérifier les paramètres de base
-        assert_eq!(config.language, "fra");
-        assert_eq!(config.page_seg_mode, PageSegMode::SingleLine);
-        assert_eq!(config.dpi, 150);
+    /// Retourne les noms des presets disponibles.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::OcrConfig;
+    ///
+    /// assert!(OcrConfig::preset_names().contains(&"receipt"));
+    /// ```
+    pub fn preset_names() -> &'static [&'static str] {
+        &PRESET_NAMES
+    }
 
-        // Vérifier qu'aucune variable Tesseract n'est définie
-        assert!(config.tesseract_variables.is_empty());
+    /// Crée une configuration à partir du nom d'un preset.
+    ///
+    /// Noms reconnus : voir [`preset_names()`](Self::preset_names). Le preset
+    /// `alphanumeric` utilise [`ALPHANUMERIC_CHAR_WHITELIST`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Nom du preset (ex: "receipt")
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::OcrConfig;
+    ///
+    /// assert!(OcrConfig::from_preset_name("photo").is_some());
+    /// assert!(OcrConfig::from_preset_name("inconnu").is_none());
+    /// ```
+    pub fn from_preset_name(name: &str) -> Option<Self> {
+        match name {
+            "document" => Some(Self::document_preset()),
+            "screenshot" => Some(Self::screenshot_preset()),
+            "single_line" => Some(Self::single_line_preset()),
+            "photo" => Some(Self::photo_preset()),
+            "receipt" => Some(Self::receipt_preset()),
+            "alphanumeric" => Some(Self::alphanumeric_preset(ALPHANUMERIC_CHAR_WHITELIST)),
+            _ => None,
+        }
     }
 
-    /// Test du preset pour photos de texte.
-    #[test]
+    /// Crée une configuration à partir du nom d'un preset, ou retourne une erreur.
+    ///
+    /// Variante de [`from_preset_name()`](Self::from_preset_name) dont le message
+    /// d'erreur liste les presets valides.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Nom du preset (ex: "receipt")
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le preset n'existe pas.
+    pub fn try_from_preset_name(name: &str) -> Result<Self> {
+        Self::from_preset_name(name).ok_or_else(|| {
+            anyhow!(
+                "Preset inconnu : '{}'. Presets disponibles : {}",
+                name,
+                PRESET_NAMES.join(", ")
+            )
+        })
+    }
+
+    /// Ajoute un vocabulaire métier, écrit dans un fichier de mots utilisateur temporaire.
+    ///
+    /// Les mots sont écrits un par ligne (les mots vides sont ignorés) et
+    /// [`user_words`](Self::user_words) pointe vers ce fichier, qui reste en
+    /// place tant qu'une copie de la configuration existe : un
+    /// [`OcrEngine`](crate::ocr::OcrEngine) créé avec elle le garde en vie.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - Mots à ajouter au dictionnaire de Tesseract
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::OcrConfig;
+    ///
+    /// let config = OcrConfig::default().with_vocabulary(&["Kubernetes", "SIRET"])?;
+    /// let path = config.user_words.clone().expect("fichier créé");
+    /// assert_eq!(std::fs::read_to_string(&path)?, "Kubernetes\nSIRET\n");
+    ///
+    /// drop(config);
+    /// assert!(!path.exists());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le fichier temporaire ne peut pas être écrit.
+    #[cfg(feature = "tesseract")]
+    pub fn with_vocabulary(mut self, words: &[&str]) -> Result<Self> {
+        let mut file = tempfile::Builder::new()
+            .prefix("user-words-")
+            .suffix(".txt")
+            .tempfile()
+            .map_err(|e| anyhow!("Échec de création du fichier de mots utilisateur : {}", e))?;
+        for word in words
+            .iter()
+            .map(|word| word.trim())
+            .filter(|word| !word.is_empty())
+        {
+            writeln!(file, "{}", word)
+                .map_err(|e| anyhow!("Échec d'écriture du fichier de mots utilisateur : {}", e))?;
+        }
+        let path = file.into_temp_path();
+        self.user_words = Some(path.to_path_buf());
+        self.vocabulary_file = Some(Arc::new(path));
+        Ok(self)
+    }
+
+    /// Indique si un fichier de mots ou de motifs utilisateur est configuré.
+    pub fn has_user_vocabulary(&self) -> bool {
+        self.user_words.is_some() || self.user_patterns.is_some()
+    }
+
+    /// Arguments `--user-words` et `--user-patterns` du binaire `tesseract`.
+    ///
+    /// Vide si aucun fichier n'est configuré.
+    #[cfg(feature = "tesseract")]
+    pub(crate) fn user_vocabulary_args(&self) -> Vec<String> {
+        [
+            ("--user-words", &self.user_words),
+            ("--user-patterns", &self.user_patterns),
+        ]
+        .into_iter()
+        .filter_map(|(flag, path)| path.as_ref().map(|path| (flag, path)))
+        .flat_map(|(flag, path)| [flag.to_string(), path.display().to_string()])
+        .collect()
+    }
+
+    /// Vérifie que les fichiers de mots et de motifs utilisateur sont lisibles.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur nommant le fichier et l'option concernée si un
+    /// fichier n'existe pas ou ne peut pas être ouvert.
+    pub fn validate_user_vocabulary(&self) -> Result<()> {
+        for (label, option, path) in [
+            ("de mots", "user_words", &self.user_words),
+            ("de motifs", "user_patterns", &self.user_patterns),
+        ] {
+            if let Some(path) = path {
+                // Un répertoire s'ouvre sans erreur : le contenu est lu
+                std::fs::read(path).map_err(|e| {
+                    anyhow!(
+                        "Fichier {} utilisateur introuvable ou illisible ({}) : '{}' ({})",
+                        label,
+                        option,
+                        path.display(),
+                        e
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Vérifie que le répertoire tessdata contient les modèles des langues configurées.
+    ///
+    /// Chaque langue de [`language`](Self::language) (ex: `fra+eng`) doit
+    /// avoir son fichier `<langue>.traineddata` dans
+    /// [`tessdata_dir`](Self::tessdata_dir). Sans répertoire configuré, rien
+    /// n'est vérifié.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::OcrConfig;
+    ///
+    /// let dir = tempfile::tempdir()?;
+    /// std::fs::write(dir.path().join("fra.traineddata"), b"")?;
+    /// let config = OcrConfig {
+    ///     language: "fra+eng".to_string(),
+    ///     tessdata_dir: Some(dir.path().to_path_buf()),
+    ///     ..OcrConfig::default()
+    /// };
+    /// let error = config.validate_tessdata_dir().unwrap_err();
+    /// assert!(error.to_string().contains("eng.traineddata"));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le répertoire n'existe pas, ou nommant le
+    /// premier fichier `.traineddata` absent.
+    pub fn validate_tessdata_dir(&self) -> Result<()> {
+        let Some(ref dir) = self.tessdata_dir else {
+            return Ok(());
+        };
+        if !dir.is_dir() {
+            bail!(
+                "Répertoire tessdata introuvable (tessdata_dir) : '{}'",
+                dir.display()
+            );
+        }
+        let languages = self.language.split('+').map(str::trim);
+        for language in languages.filter(|language| !language.is_empty()) {
+            let model = dir.join(format!("{}.traineddata", language));
+            if !model.is_file() {
+                bail!(
+                    "Modèle de langue '{}' absent du répertoire tessdata : '{}'",
+                    language,
+                    model.display()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Limite de threads OpenMP transmise aux processus `tesseract`.
+    ///
+    /// Un thread avec [`deterministic`](Self::deterministic), sinon
+    /// [`threads`](Self::threads).
+    pub fn thread_limit(&self) -> Option<u32> {
+        if self.deterministic {
+            Some(1)
+        } else {
+            self.threads
+        }
+    }
+
+    /// Variables Tesseract à appliquer, fichiers de configuration compris.
+    ///
+    /// Les variables des fichiers de [`config_names`](Self::config_names)
+    /// viennent d'abord, dans l'ordre des fichiers puis des lignes ; celles
+    /// redéfinies dans [`tesseract_variables`](Self::tesseract_variables) en
+    /// sont retirées. Suivent les variables explicites, triées par nom.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::OcrConfig;
+    ///
+    /// let dir = tempfile::tempdir()?;
+    /// let file = dir.path().join("chiffres.cfg");
+    /// std::fs::write(&file, "tessedit_char_whitelist 0123456789\nclassify_bln_numeric_mode 1\n")?;
+    ///
+    /// let mut config = OcrConfig::default();
+    /// config.config_names.push(file.display().to_string());
+    /// config.tesseract_variables.insert("classify_bln_numeric_mode".into(), "0".into());
+    ///
+    /// let settings = config.tesseract_settings()?;
+    /// assert_eq!(settings[0], ("tessedit_char_whitelist".to_string(), "0123456789".to_string()));
+    /// assert_eq!(settings[1], ("classify_bln_numeric_mode".to_string(), "0".to_string()));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si un fichier de configuration est introuvable,
+    /// illisible ou contient une ligne invalide.
+    pub fn tesseract_settings(&self) -> Result<Vec<(String, String)>> {
+        tesseract_settings_for(&self.config_names, &self.tesseract_variables)
+    }
+
+    /// Applique une surcharge partielle : chaque champ renseigné de `patch`
+    /// remplace celui de la configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `patch` - Champs à remplacer ; les champs à `None` sont laissés intacts
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::{OcrConfig, OcrConfigPatch};
+    ///
+    /// let mut config = OcrConfig {
+    ///     language: "eng".to_string(),
+    ///     ..OcrConfig::default()
+    /// };
+    /// config.apply_patch(&OcrConfigPatch {
+    ///     language: Some("fra".to_string()),
+    ///     ..OcrConfigPatch::default()
+    /// });
+    /// assert_eq!(config.language, "fra");
+    /// assert_eq!(config.dpi, 300);
+    /// ```
+    pub fn apply_patch(&mut self, patch: &OcrConfigPatch) {
+        if let Some(ref language) = patch.language {
+            self.language = language.clone();
+        }
+        if let Some(page_seg_mode) = patch.page_seg_mode {
+            self.page_seg_mode = page_seg_mode;
+        }
+        if let Some(dpi) = patch.dpi {
+            self.dpi = dpi;
+        }
+        if let Some(ref variables) = patch.tesseract_variables {
+            self.tesseract_variables = variables.clone();
+        }
+        if let Some(cleanup) = patch.cleanup {
+            self.cleanup = cleanup;
+        }
+        if let Some(engine_mode) = patch.engine_mode {
+            self.engine_mode = engine_mode;
+        }
+        if let Some(adaptive_dpi) = patch.adaptive_dpi {
+            self.adaptive_dpi = adaptive_dpi;
+        }
+        if let Some(ref user_words) = patch.user_words {
+            self.user_words = user_words.clone();
+        }
+        if let Some(ref user_patterns) = patch.user_patterns {
+            self.user_patterns = user_patterns.clone();
+        }
+        if let Some(deterministic) = patch.deterministic {
+            self.deterministic = deterministic;
+        }
+        if let Some(auto_vertical) = patch.auto_vertical {
+            self.auto_vertical = auto_vertical;
+        }
+        if let Some(fallback) = patch.fallback_line_segmentation {
+            self.fallback_line_segmentation = fallback;
+        }
+        if let Some(ref line_merge) = patch.line_merge {
+            self.line_merge = line_merge.clone();
+        }
+        if let Some(skip) = patch.skip_image_regions {
+            self.skip_image_regions = skip;
+        }
+        if let Some(ref config_names) = patch.config_names {
+            self.config_names = config_names.clone();
+        }
+        if let Some(ref tessdata_dir) = patch.tessdata_dir {
+            self.tessdata_dir = tessdata_dir.clone();
+        }
+        if let Some(threads) = patch.threads {
+            self.threads = threads;
+        }
+        if let Some(ref pyramid) = patch.pyramid {
+            self.pyramid = pyramid.clone();
+        }
+    }
+}
+
+/// Surcharge partielle d'une [`OcrConfig`].
+///
+/// Chaque champ renseigné remplace celui de la configuration (voir
+/// [`OcrConfig::apply_patch()`]) ; un champ à `None` la laisse intacte. Les
+/// collections (`tesseract_variables`, `config_names`) sont remplacées en
+/// entier. Les champs déjà optionnels de [`OcrConfig`] sont doublement
+/// optionnels : `Some(None)` remet le champ à `None` (`null` en JSON ; TOML
+/// n'a pas de valeur nulle et ne peut exprimer que `Some(Some(..))`). Le
+/// fichier de vocabulaire temporaire (`vocabulary_file`) n'est pas
+/// concerné.
+///
+/// Désérialisable : un fichier de configuration n'écrit que les champs à
+/// surcharger. [`diff()`](Self::diff) calcule la surcharge qui mène d'une
+/// configuration à une autre.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::config::{OcrConfig, OcrConfigPatch};
+///
+/// let patch: OcrConfigPatch = toml::from_str("language = \"eng\"\ndpi = 400\n")?;
+/// let mut config = OcrConfig::default();
+/// config.apply_patch(&patch);
+/// assert_eq!((config.language.as_str(), config.dpi), ("eng", 400));
+///
+/// assert_eq!(OcrConfigPatch::diff(&OcrConfig::default(), &config), patch);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OcrConfigPatch {
+    /// Langue(s) Tesseract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Mode de segmentation de page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_seg_mode: Option<PageSegMode>,
+    /// Résolution DPI.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dpi: Option<u32>,
+    /// Variables Tesseract, remplaçant toutes celles de la configuration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tesseract_variables: Option<HashMap<String, String>>,
+    /// Nettoyage du texte extrait.
+    #[serde(
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cleanup: Option<Option<TextCleanup>>,
+    /// Moteur de reconnaissance.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine_mode: Option<OcrEngineMode>,
+    /// DPI adaptatif.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive_dpi: Option<bool>,
+    /// Fichier de mots utilisateur.
+    #[serde(
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub user_words: Option<Option<PathBuf>>,
+    /// Fichier de motifs utilisateur.
+    #[serde(
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub user_patterns: Option<Option<PathBuf>>,
+    /// Limitation de Tesseract à un thread.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deterministic: Option<bool>,
+    /// Passage en PSM 5 pour le texte vertical.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_vertical: Option<bool>,
+    /// Lecture ligne par ligne de secours.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_line_segmentation: Option<bool>,
+    /// Fusion des lignes coupées de l'extraction structurée.
+    #[serde(
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub line_merge: Option<Option<LineMergeOptions>>,
+    /// Saut des régions classées comme illustrations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_image_regions: Option<bool>,
+    /// Fichiers de configuration Tesseract, remplaçant ceux de la configuration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_names: Option<Vec<String>>,
+    /// Répertoire des modèles `.traineddata`.
+    #[serde(
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub tessdata_dir: Option<Option<PathBuf>>,
+    /// Nombre maximal de threads du binaire `tesseract`.
+    #[serde(
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub threads: Option<Option<u32>>,
+    /// Lecture pyramidale.
+    #[serde(
+        deserialize_with = "deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub pyramid: Option<Option<PyramidConfig>>,
+}
+
+impl OcrConfigPatch {
+    /// Calcule la surcharge qui transforme `a` en `b`.
+    ///
+    /// Seuls les champs qui diffèrent sont renseignés, avec la valeur de
+    /// `b` : `a.apply_patch(&OcrConfigPatch::diff(&a, &b))` donne une
+    /// configuration égale à `b` (hors `vocabulary_file`).
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Configuration de départ
+    /// * `b` - Configuration d'arrivée
+    pub fn diff(a: &OcrConfig, b: &OcrConfig) -> Self {
+        Self {
+            language: changed(&a.language, &b.language),
+            page_seg_mode: changed(&a.page_seg_mode, &b.page_seg_mode),
+            dpi: changed(&a.dpi, &b.dpi),
+            tesseract_variables: changed(&a.tesseract_variables, &b.tesseract_variables),
+            cleanup: changed(&a.cleanup, &b.cleanup),
+            engine_mode: changed(&a.engine_mode, &b.engine_mode),
+            adaptive_dpi: changed(&a.adaptive_dpi, &b.adaptive_dpi),
+            user_words: changed(&a.user_words, &b.user_words),
+            user_patterns: changed(&a.user_patterns, &b.user_patterns),
+            deterministic: changed(&a.deterministic, &b.deterministic),
+            auto_vertical: changed(&a.auto_vertical, &b.auto_vertical),
+            fallback_line_segmentation: changed(
+                &a.fallback_line_segmentation,
+                &b.fallback_line_segmentation,
+            ),
+            line_merge: changed(&a.line_merge, &b.line_merge),
+            skip_image_regions: changed(&a.skip_image_regions, &b.skip_image_regions),
+            config_names: changed(&a.config_names, &b.config_names),
+            tessdata_dir: changed(&a.tessdata_dir, &b.tessdata_dir),
+            threads: changed(&a.threads, &b.threads),
+            pyramid: changed(&a.pyramid, &b.pyramid),
+        }
+    }
+
+    /// Indique si la surcharge ne modifie aucun champ.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Valeur de `b` si elle diffère de `a` (champ d'une surcharge calculée par différence).
+pub(crate) fn changed<T: PartialEq + Clone>(a: &T, b: &T) -> Option<T> {
+    (a != b).then(|| b.clone())
+}
+
+/// Désérialise un champ présent en `Some`, y compris une valeur nulle (`Some(None)`).
+///
+/// Avec `#[serde(default)]`, un champ absent reste `None` : les champs de
+/// type `Option<Option<T>>` distinguent ainsi « absent » de « nul ».
+pub(crate) fn deserialize_present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Analyse un fichier de configuration Tesseract.
+///
+/// Chaque ligne contient un nom de variable suivi de sa valeur, séparés
+/// par des espaces ; les lignes vides et les commentaires (`#`) sont
+/// ignorés.
+///
+/// # Arguments
+///
+/// * `content` - Contenu du fichier
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::config::parse_tesseract_config;
+///
+/// let variables = parse_tesseract_config("# Chiffres seuls\n\ntessedit_char_whitelist 0123456789\n")?;
+/// assert_eq!(variables, [("tessedit_char_whitelist".to_string(), "0123456789".to_string())]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Erreurs
+///
+/// Retourne une erreur indiquant le numéro de ligne si une ligne n'a pas
+/// de valeur.
+pub fn parse_tesseract_config(content: &str) -> Result<Vec<(String, String)>> {
+    let mut variables = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once(char::is_whitespace) {
+            Some((key, value)) if !value.trim().is_empty() => {
+                variables.push((key.to_string(), value.trim().to_string()));
+            }
+            _ => anyhow::bail!(
+                "ligne {} : « {} » n'est pas de la forme « variable valeur »",
+                index + 1,
+                line
+            ),
+        }
+    }
+    Ok(variables)
+}
+
+/// Répertoires `tessdata` usuels, après `TESSDATA_PREFIX` s'il est défini.
+const TESSDATA_DIRS: &[&str] = &[
+    "/usr/share/tesseract-ocr/5/tessdata",
+    "/usr/share/tesseract-ocr/4.00/tessdata",
+    "/usr/share/tessdata",
+    "/usr/local/share/tessdata",
+    "/opt/homebrew/share/tessdata",
+];
+
+/// Variables des fichiers `config_names` puis variables explicites triées.
+///
+/// Voir [`OcrConfig::tesseract_settings()`].
+pub(crate) fn tesseract_settings_for(
+    config_names: &[String],
+    variables: &HashMap<String, String>,
+) -> Result<Vec<(String, String)>> {
+    let mut settings = Vec::new();
+    for name in config_names {
+        let path = resolve_tesseract_config(name)?;
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            anyhow!(
+                "Configuration Tesseract '{}' illisible : '{}' ({})",
+                name,
+                path.display(),
+                e
+            )
+        })?;
+        let parsed = parse_tesseract_config(&content).map_err(|e| {
+            anyhow!(
+                "Configuration Tesseract '{}' invalide ({}) : {}",
+                name,
+                path.display(),
+                e
+            )
+        })?;
+        settings.extend(
+            parsed
+                .into_iter()
+                .filter(|(key, _)| !variables.contains_key(key)),
+        );
+    }
+
+    let mut explicit: Vec<(String, String)> = variables
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    explicit.sort();
+    settings.extend(explicit);
+    Ok(settings)
+}
+
+/// Trouve le fichier d'une configuration Tesseract.
+///
+/// Un nom contenant un séparateur de chemin (`./mon.cfg`, chemin absolu)
+/// désigne directement un fichier. Sinon, le fichier est cherché dans
+/// `$TESSDATA_PREFIX/configs`, puis dans le répertoire `configs` des
+/// installations usuelles de tessdata.
+///
+/// # Arguments
+///
+/// * `name` - Nom de configuration (ex: "digits") ou chemin
+///
+/// # Erreurs
+///
+/// Retourne une erreur listant les chemins essayés si le fichier est
+/// introuvable.
+pub fn resolve_tesseract_config(name: &str) -> Result<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os("TESSDATA_PREFIX")
+        .map(PathBuf::from)
+        .into_iter()
+        .collect();
+    dirs.extend(TESSDATA_DIRS.iter().map(PathBuf::from));
+    resolve_tesseract_config_in(name, &dirs)
+}
+
+/// Trouve le fichier d'une configuration Tesseract dans les répertoires tessdata donnés.
+fn resolve_tesseract_config_in(name: &str, tessdata_dirs: &[PathBuf]) -> Result<PathBuf> {
+    let candidates: Vec<PathBuf> = if name.contains(std::path::MAIN_SEPARATOR) || name.contains('/')
+    {
+        vec![PathBuf::from(name)]
+    } else {
+        tessdata_dirs
+            .iter()
+            .map(|dir| dir.join("configs").join(name))
+            .collect()
+    };
+
+    match candidates.iter().find(|path| path.is_file()) {
+        Some(path) => Ok(path.clone()),
+        None => Err(anyhow!(
+            "Configuration Tesseract '{}' introuvable ; chemins cherchés : {}",
+            name,
+            candidates
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tesseract_config_skips_comments_and_blank_lines() {
+        let content = "# Configuration « factures »\n\n\
+                       tessedit_char_whitelist 0123456789,.€\n   \n\
+                       \tpreserve_interword_spaces   1  \n\
+                       # fin\n";
+
+        assert_eq!(
+            parse_tesseract_config(content).unwrap(),
+            [
+                (
+                    "tessedit_char_whitelist".to_string(),
+                    "0123456789,.€".to_string()
+                ),
+                ("preserve_interword_spaces".to_string(), "1".to_string()),
+            ]
+        );
+
+        let error =
+            parse_tesseract_config("load_system_dawg F\n\ntessedit_pageseg_mode\n").unwrap_err();
+        assert!(error.to_string().contains("ligne 3"), "{}", error);
+    }
+
+    #[test]
+    fn test_explicit_variables_override_config_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("base.cfg");
+        let second = dir.path().join("factures.cfg");
+        std::fs::write(
+            &first,
+            "load_system_dawg F\ntessedit_char_whitelist 0123456789\n",
+        )
+        .unwrap();
+        std::fs::write(&second, "user_defined_dpi 200\n").unwrap();
+        let mut config = OcrConfig {
+            config_names: vec![first.display().to_string(), second.display().to_string()],
+            ..OcrConfig::default()
+        };
+        config
+            .tesseract_variables
+            .insert("tessedit_char_whitelist".to_string(), "ABC".to_string());
+
+        let settings = config.tesseract_settings().unwrap();
+
+        let pairs: Vec<(&str, &str)> = settings
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("load_system_dawg", "F"),
+                ("user_defined_dpi", "200"),
+                ("tessedit_char_whitelist", "ABC"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_named_config_lists_searched_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let tessdata = dir.path().join("tessdata");
+        std::fs::create_dir_all(tessdata.join("configs")).unwrap();
+        std::fs::write(tessdata.join("configs").join("digits"), "x 1\n").unwrap();
+        let dirs = [dir.path().join("absent"), tessdata.clone()];
+
+        assert_eq!(
+            resolve_tesseract_config_in("digits", &dirs).unwrap(),
+            tessdata.join("configs").join("digits")
+        );
+
+        let error = resolve_tesseract_config_in("facture", &dirs).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("'facture' introuvable"), "{}", message);
+        for dir in &dirs {
+            let searched = dir.join("configs").join("facture");
+            assert!(
+                message.contains(&searched.display().to_string()),
+                "{}",
+                message
+            );
+        }
+    }
+
+    /// Test de la configuration par défaut.
+    #[test]
+    fn test_default_config() {
+        let config = OcrConfig::default();
+
+        assert_eq!(config.language, "fra");
+        assert_eq!(config.page_seg_mode, PageSegMode::Auto);
+        assert_eq!(config.dpi, 300);
+        assert!(config.tesseract_variables.is_empty());
+    }
+
+    /// Test du preset pour documents.
+    #[test]
+    fn test_document_preset() {
+        let config = OcrConfig::document_preset();
+
+        // Vérifier les paramètres de base
+        assert_eq!(config.language, "fra");
+        assert_eq!(config.page_seg_mode, PageSegMode::Auto);
+        assert_eq!(config.dpi, 300);
+
+        // Vérifier les variables Tesseract spécifiques
+        assert_eq!(config.tesseract_variables.len(), 1);
+        assert_eq!(
+            config.tesseract_variables.get("preserve_interword_spaces"),
+            Some(&"1".to_string())
+        );
+    }
+
+    /// Test du preset pour captures d'écran.
+    #[test]
+    fn test_screenshot_preset() {
+        let config = OcrConfig::screenshot_preset();
+
+        // Vérifier les paramètres de base
+        assert_eq!(config.language, "fra");
+        assert_eq!(config.page_seg_mode, PageSegMode::Auto);
+        assert_eq!(config.dpi, 96); // DPI spécifique aux écrans
+
+        // Vérifier qu'aucune variable Tesseract n'est définie
+        assert!(config.tesseract_variables.is_empty());
+    }
+
+    /// Test du preset pour ligne de texte unique.
+    #[test]
+    fn test_single_line_preset() {
+        let config = OcrConfig::single_line_preset();
+
+        // Vérifier les paramètres de base
+        assert_eq!(config.language, "fra");
+        assert_eq!(config.page_seg_mode, PageSegMode::SingleLine);
+        assert_eq!(config.dpi, 150);
+
+        // Vérifier qu'aucune variable Tesseract n'est définie
+        assert!(config.tesseract_variables.is_empty());
+    }
+
+    /// Test du preset pour photos de texte.
+    #[test]
     fn test_photo_preset() {
         let config = OcrConfig::photo_preset();
 
@@ -508,6 +1966,81 @@ mod tests {
         assert_eq!(PageSegMode::RawLine.to_tesseract_psm(), 13);
     }
 
+    /// Test que chaque orthographe acceptée revient au même mode.
+    #[test]
+    fn test_page_seg_mode_from_str_round_trip() {
+        for (psm, mode) in PageSegMode::all().into_iter().enumerate() {
+            let variant = format!("{:?}", mode);
+            let snake = mode.to_string().replace('-', "_");
+            let spellings = [
+                psm.to_string(),
+                variant.clone(),
+                variant.to_lowercase(),
+                variant.to_uppercase(),
+                snake.clone(),
+                snake.to_uppercase(),
+                mode.to_string(),
+            ];
+            for spelling in &spellings {
+                assert_eq!(
+                    spelling.parse::<PageSegMode>().unwrap(),
+                    mode,
+                    "{}",
+                    spelling
+                );
+            }
+            assert_eq!(mode.to_tesseract_psm(), psm as i32);
+            assert_eq!(mode.to_string().parse::<PageSegMode>().unwrap(), mode);
+        }
+        assert_eq!(
+            "sparse_text_osd".parse::<PageSegMode>().unwrap(),
+            PageSegMode::SparseTextOsd
+        );
+    }
+
+    /// Test du message d'erreur des modes PSM invalides.
+    #[test]
+    fn test_page_seg_mode_from_str_invalid() {
+        let error = "single-blok"
+            .parse::<PageSegMode>()
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'single-blok'"));
+        assert!(error.contains("single-block"));
+        assert!(error.contains("raw-line"));
+
+        let error = "14".parse::<PageSegMode>().unwrap_err().to_string();
+        assert!(error.contains("entre 0 et 13"));
+        assert!("".parse::<PageSegMode>().is_err());
+    }
+
+    /// Test de la désérialisation des formes chaîne et entière, la sérialisation restant sur la variante.
+    #[test]
+    fn test_page_seg_mode_serde() {
+        let config: OcrConfig = serde_json::from_str(
+            r#"{"language": "fra", "page_seg_mode": "Auto", "dpi": 300, "tesseract_variables": {}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.page_seg_mode, PageSegMode::Auto);
+
+        for value in [r#""single-block""#, r#""SINGLE_BLOCK""#, "6"] {
+            let mode: PageSegMode = serde_json::from_str(value).unwrap();
+            assert_eq!(mode, PageSegMode::SingleBlock);
+        }
+        let config: OcrConfig = toml::from_str(
+            "language = \"fra\"\npage_seg_mode = 7\ndpi = 300\n[tesseract_variables]\n",
+        )
+        .unwrap();
+        assert_eq!(config.page_seg_mode, PageSegMode::SingleLine);
+
+        assert!(serde_json::from_str::<PageSegMode>("14").is_err());
+        assert!(serde_json::from_str::<PageSegMode>(r#""colonne""#).is_err());
+        assert_eq!(
+            serde_json::to_string(&PageSegMode::SingleBlock).unwrap(),
+            r#""SingleBlock""#
+        );
+    }
+
     /// Test que chaque preset a des paramètres distincts.
     #[test]
     fn test_presets_are_distinct() {
@@ -535,4 +2068,353 @@ mod tests {
         assert_eq!(config1.page_seg_mode, config2.page_seg_mode);
         assert_eq!(config1.dpi, config2.dpi);
     }
+
+    /// Test du preset pour tickets de caisse.
+    #[test]
+    fn test_receipt_preset() {
+        let config = OcrConfig::receipt_preset();
+
+        assert_eq!(config.page_seg_mode, PageSegMode::SingleColumn);
+        assert_eq!(config.dpi, 400);
+        assert_eq!(config.tesseract_variables.len(), 2);
+        assert_eq!(
+            config.tesseract_variables.get("preserve_interword_spaces"),
+            Some(&"1".to_string())
+        );
+
+        let whitelist = &config.tesseract_variables["tessedit_char_whitelist"];
+        assert_eq!(whitelist, RECEIPT_CHAR_WHITELIST);
+        for ambiguous in ['|', '~', '`', '^', '_', '{', '}', '[', ']', '<', '>', '\\'] {
+            assert!(
+                !whitelist.contains(ambiguous),
+                "'{}' ne doit pas être autorisé",
+                ambiguous
+            );
+        }
+    }
+
+    /// Test du preset tickets de caisse avec liste personnalisée.
+    #[test]
+    fn test_receipt_preset_with_whitelist() {
+        let config = OcrConfig::receipt_preset_with_whitelist("0123456789.,");
+        assert_eq!(
+            config.tesseract_variables.get("tessedit_char_whitelist"),
+            Some(&"0123456789.,".to_string())
+        );
+        assert_eq!(config.page_seg_mode, PageSegMode::SingleColumn);
+    }
+
+    /// Test du preset alphanumérique.
+    #[test]
+    fn test_alphanumeric_preset() {
+        let config = OcrConfig::alphanumeric_preset("AB12");
+
+        assert_eq!(config.page_seg_mode, PageSegMode::SingleLine);
+        assert_eq!(config.dpi, 300);
+        assert_eq!(config.tesseract_variables.len(), 1);
+        assert_eq!(
+            config.tesseract_variables.get("tessedit_char_whitelist"),
+            Some(&"AB12".to_string())
+        );
+    }
+
+    /// Test du registre de presets : chaque nom est résolu.
+    #[test]
+    fn test_preset_registry_resolves_all_names() {
+        for name in OcrConfig::preset_names() {
+            assert!(
+                OcrConfig::from_preset_name(name).is_some(),
+                "Preset '{}' non résolu",
+                name
+            );
+        }
+        assert_eq!(
+            OcrConfig::from_preset_name("receipt").unwrap().dpi,
+            OcrConfig::receipt_preset().dpi
+        );
+    }
+
+    /// Test d'un nom de preset inconnu : l'erreur liste les presets valides.
+    #[test]
+    fn test_unknown_preset_lists_valid_names() {
+        assert!(OcrConfig::from_preset_name("inconnu").is_none());
+
+        let message = OcrConfig::try_from_preset_name("inconnu")
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("inconnu"));
+        for name in OcrConfig::preset_names() {
+            assert!(message.contains(name), "'{}' absent du message", name);
+        }
+    }
+
+    /// Test du nettoyage par défaut lorsqu'il est absent de la configuration sérialisée.
+    #[test]
+    fn test_missing_cleanup_defaults_to_trim() {
+        let config: OcrConfig = serde_json::from_str(
+            r#"{"language": "eng", "page_seg_mode": "Auto", "dpi": 300, "tesseract_variables": {}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.cleanup, Some(TextCleanup::default()));
+
+        let raw: OcrConfig = serde_json::from_str(
+            r#"{"language": "eng", "page_seg_mode": "Auto", "dpi": 300, "tesseract_variables": {}, "cleanup": null}"#,
+        )
+        .unwrap();
+        assert!(raw.cleanup.is_none());
+    }
+
+    #[test]
+    fn test_engine_mode_conversion() {
+        let modes = [
+            (OcrEngineMode::LegacyOnly, 0),
+            (OcrEngineMode::LstmOnly, 1),
+            (OcrEngineMode::LegacyPlusLstm, 2),
+            (OcrEngineMode::Default, 3),
+        ];
+        for (mode, oem) in modes {
+            assert_eq!(mode.to_tesseract_oem(), oem);
+            assert_eq!(OcrEngineMode::from_tesseract_oem(oem), Some(mode));
+        }
+        assert_eq!(OcrEngineMode::from_tesseract_oem(-1), None);
+        assert_eq!(OcrEngineMode::from_tesseract_oem(4), None);
+    }
+
+    #[test]
+    fn test_engine_mode_requires_legacy_data() {
+        assert!(OcrEngineMode::LegacyOnly.requires_legacy_data());
+        assert!(OcrEngineMode::LegacyPlusLstm.requires_legacy_data());
+        assert!(!OcrEngineMode::LstmOnly.requires_legacy_data());
+        assert!(!OcrEngineMode::Default.requires_legacy_data());
+    }
+
+    #[test]
+    fn test_missing_engine_mode_defaults_to_default() {
+        let config: OcrConfig = serde_json::from_str(
+            r#"{"language": "eng", "page_seg_mode": "Auto", "dpi": 300, "tesseract_variables": {}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.engine_mode, OcrEngineMode::Default);
+
+        let lstm: OcrConfig = serde_json::from_str(
+            r#"{"language": "eng", "page_seg_mode": "Auto", "dpi": 300, "tesseract_variables": {}, "engine_mode": "LstmOnly"}"#,
+        )
+        .unwrap();
+        assert_eq!(lstm.engine_mode, OcrEngineMode::LstmOnly);
+    }
+
+    #[test]
+    #[cfg(feature = "tesseract")]
+    fn test_with_vocabulary_writes_words() {
+        let config = OcrConfig::default()
+            .with_vocabulary(&["Kubernetes", " ", "SIRET "])
+            .unwrap();
+        let path = config.user_words.clone().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "Kubernetes\nSIRET\n"
+        );
+        assert!(config.validate_user_vocabulary().is_ok());
+
+        // Le fichier vit tant qu'une copie de la configuration existe
+        let copy = config.clone();
+        drop(config);
+        assert!(path.exists());
+        drop(copy);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    #[cfg(feature = "tesseract")]
+    fn test_user_vocabulary_args() {
+        assert!(OcrConfig::default().user_vocabulary_args().is_empty());
+        assert!(!OcrConfig::default().has_user_vocabulary());
+
+        let config = OcrConfig {
+            user_words: Some(PathBuf::from("mots.txt")),
+            user_patterns: Some(PathBuf::from("motifs.txt")),
+            ..OcrConfig::default()
+        };
+        assert!(config.has_user_vocabulary());
+        assert_eq!(
+            config.user_vocabulary_args(),
+            ["--user-words", "mots.txt", "--user-patterns", "motifs.txt"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tesseract")]
+    fn test_user_vocabulary_toml_round_trip() {
+        let config = OcrConfig {
+            user_words: Some(PathBuf::from("/etc/ocr/mots.txt")),
+            user_patterns: Some(PathBuf::from("motifs.txt")),
+            ..OcrConfig::default()
+        }
+        .with_vocabulary(&["ignoré"])
+        .map(|mut config| {
+            // with_vocabulary remplace user_words ; le fichier temporaire n'est pas sérialisé
+            config.user_words = Some(PathBuf::from("/etc/ocr/mots.txt"));
+            config
+        })
+        .unwrap();
+
+        let serialized = toml::to_string(&config).unwrap();
+        assert!(serialized.contains("user_words = \"/etc/ocr/mots.txt\""));
+        let parsed: OcrConfig = toml::from_str(&serialized).unwrap();
+        assert_eq!(parsed.user_words, config.user_words);
+        assert_eq!(parsed.user_patterns, config.user_patterns);
+        assert!(parsed.vocabulary_file.is_none());
+
+        // Champs absents : aucun fichier
+        let default = toml::to_string(&OcrConfig::default()).unwrap();
+        assert!(!default.contains("user_"));
+        let parsed: OcrConfig = toml::from_str(&default).unwrap();
+        assert!(!parsed.has_user_vocabulary());
+    }
+
+    #[test]
+    fn test_validate_tessdata_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("fra.traineddata"), b"").unwrap();
+        let mut config = OcrConfig {
+            language: "fra".to_string(),
+            tessdata_dir: Some(dir.path().to_path_buf()),
+            ..OcrConfig::default()
+        };
+        config.validate_tessdata_dir().unwrap();
+
+        config.language = "fra+deu".to_string();
+        let message = config.validate_tessdata_dir().unwrap_err().to_string();
+        assert!(message.contains("'deu'"));
+        assert!(message.contains(&dir.path().join("deu.traineddata").display().to_string()));
+
+        config.tessdata_dir = Some(dir.path().join("absent"));
+        let message = config.validate_tessdata_dir().unwrap_err().to_string();
+        assert!(message.contains("Répertoire tessdata introuvable"));
+
+        // Sans répertoire configuré, aucune vérification
+        config.tessdata_dir = None;
+        config.validate_tessdata_dir().unwrap();
+    }
+
+    #[test]
+    fn test_tessdata_and_threads_serde_round_trip() {
+        let config = OcrConfig {
+            tessdata_dir: Some(PathBuf::from("/opt/tessdata_best")),
+            threads: Some(2),
+            ..OcrConfig::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: OcrConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.tessdata_dir, config.tessdata_dir);
+        assert_eq!(parsed.threads, Some(2));
+        let parsed: OcrConfig = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(parsed.tessdata_dir, config.tessdata_dir);
+        assert_eq!(parsed.thread_limit(), Some(2));
+
+        // Champs absents : comportement habituel de Tesseract
+        let default = serde_json::to_string(&OcrConfig::default()).unwrap();
+        assert!(!default.contains("tessdata_dir") && !default.contains("threads"));
+
+        // `deterministic` l'emporte sur `threads`
+        let deterministic = OcrConfig {
+            deterministic: true,
+            ..config
+        };
+        assert_eq!(deterministic.thread_limit(), Some(1));
+    }
+
+    /// Configuration dont chaque champ surchargeable diffère de la configuration par défaut.
+    fn config_with_every_field_changed() -> OcrConfig {
+        OcrConfig {
+            language: "eng".to_string(),
+            page_seg_mode: PageSegMode::SingleLine,
+            dpi: 150,
+            tesseract_variables: HashMap::from([("load_system_dawg".to_string(), "0".to_string())]),
+            cleanup: None,
+            engine_mode: OcrEngineMode::LstmOnly,
+            adaptive_dpi: true,
+            user_words: Some(PathBuf::from("mots.txt")),
+            user_patterns: Some(PathBuf::from("motifs.txt")),
+            #[cfg(feature = "tesseract")]
+            vocabulary_file: None,
+            deterministic: true,
+            auto_vertical: true,
+            fallback_line_segmentation: true,
+            line_merge: Some(LineMergeOptions::default()),
+            skip_image_regions: true,
+            config_names: vec!["digits".to_string()],
+            tessdata_dir: Some(PathBuf::from("/opt/tessdata")),
+            threads: Some(4),
+            pyramid: Some(PyramidConfig::with_levels(vec![0.5, 1.0])),
+        }
+    }
+
+    #[test]
+    fn test_patch_apply_and_diff_are_inverses_for_every_field() {
+        let a = OcrConfig::default();
+        let b = config_with_every_field_changed();
+
+        let patch = OcrConfigPatch::diff(&a, &b);
+        // Tous les champs de la surcharge sont renseignés (`cleanup` à `Some(None)`)
+        let fields = serde_json::to_value(&patch).unwrap();
+        assert_eq!(fields.as_object().unwrap().len(), 18);
+        assert_eq!(patch.cleanup, Some(None));
+
+        let mut patched = a.clone();
+        patched.apply_patch(&patch);
+        assert!(OcrConfigPatch::diff(&patched, &b).is_empty());
+
+        let mut reverted = b.clone();
+        reverted.apply_patch(&OcrConfigPatch::diff(&b, &a));
+        assert!(OcrConfigPatch::diff(&reverted, &a).is_empty());
+    }
+
+    #[test]
+    fn test_unset_patch_fields_leave_values_untouched() {
+        let original = config_with_every_field_changed();
+
+        let mut config = original.clone();
+        config.apply_patch(&OcrConfigPatch::default());
+        assert!(OcrConfigPatch::diff(&original, &config).is_empty());
+
+        config.apply_patch(&OcrConfigPatch {
+            dpi: Some(600),
+            ..OcrConfigPatch::default()
+        });
+        assert_eq!(
+            OcrConfigPatch::diff(&original, &config),
+            OcrConfigPatch {
+                dpi: Some(600),
+                ..OcrConfigPatch::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_patch_toml_round_trip() {
+        let patch = OcrConfigPatch {
+            cleanup: Some(Some(TextCleanup::full())),
+            ..OcrConfigPatch::diff(&OcrConfig::default(), &config_with_every_field_changed())
+        };
+
+        let serialized = toml::to_string(&patch).unwrap();
+        assert!(serialized.contains("language = \"eng\""));
+        let parsed: OcrConfigPatch = toml::from_str(&serialized).unwrap();
+        assert_eq!(parsed, patch);
+
+        // Surcharge partielle : les champs absents restent à None
+        let partial: OcrConfigPatch = toml::from_str("page_seg_mode = \"SingleBlock\"\n").unwrap();
+        assert_eq!(partial.page_seg_mode, Some(PageSegMode::SingleBlock));
+        assert_eq!(partial.cleanup, None);
+        assert!(
+            toml::to_string(&OcrConfigPatch::default())
+                .unwrap()
+                .is_empty()
+        );
+
+        // En JSON, null remet un champ optionnel à None
+        let reset: OcrConfigPatch = serde_json::from_str(r#"{"user_words": null}"#).unwrap();
+        assert_eq!(reset.user_words, Some(None));
+    }
 }
@@ -49,16 +49,144 @@
 //! denoise = true
 //! deskew = false
 //! ```
+//!
+//! # Profils de prétraitement
+//!
+//! À la place d'une table, `preprocessing` accepte le nom d'un profil
+//! ([`PreprocessingProfile`]) : `"fast"`, `"balanced"` ou `"best"`.
+//!
+//! ```toml
+//! preprocessing = "best"
+//!
+//! [ocr]
+//! language = "fra"
+//! page_seg_mode = "Auto"
+//! dpi = 300
+//!
+//! [ocr.tesseract_variables]
+//! ```
+//!
+//! # Presets
+//!
+//! Une clé `preset` au premier niveau part d'un preset nommé (voir
+//! [`OcrConfig::preset_names()`]). Les champs de la section `ocr` sont alors
+//! optionnels et s'appliquent par-dessus le preset, champ par champ
+//! (y compris pour chaque variable de `tesseract_variables`).
+//!
+//! ```toml
+//! preset = "receipt"
+//!
+//! [ocr]
+//! dpi = 300
+//!
+//! [ocr.tesseract_variables]
+//! tessedit_char_whitelist = "0123456789.,€"
+//! ```
+//!
+//! # Profils
+//!
+//! La section `profiles` définit des configurations nommées, et `rules`
+//! associe des patterns glob à ces profils (voir [`resolve_profile()`]). Un
+//! profil s'applique par-dessus la configuration de premier niveau, champ
+//! par champ : il ne contient que ce qui change. Il peut partir de son
+//! propre preset (clé `preset` du profil).
+//!
+//! ```toml
+//! [ocr]
+//! language = "fra"
+//! page_seg_mode = "Auto"
+//! dpi = 300
+//!
+//! [profiles.receipt.ocr]
+//! page_seg_mode = "SingleColumn"
+//!
+//! [[rules]]
+//! pattern = "receipts/**"
+//! profile = "receipt"
+//! ```
+//!
+//! Une règle qui référence un profil inconnu est refusée au chargement
+//! ([`ConfigFileError::UnknownProfile`]).
+//!
+//! # Erreurs de syntaxe
+//!
+//! Les fichiers de plus de [`MAX_CONFIG_FILE_SIZE`] octets sont refusés. Les
+//! erreurs de syntaxe et de type portent la ligne et la colonne fautives
+//! ([`ConfigFileError::Parse`], récupérable avec `downcast_ref`).
+//!
+//! ```
+//! use text_recognition::config_file::{ConfigFileError, load_config};
+//! use std::io::Write;
+//!
+//! let mut file = tempfile::Builder::new().suffix(".toml").tempfile()?;
+//! file.write_all(b"[ocr]\nlanguage = \"fra\"\ndpi = \"haut\"\n")?;
+//!
+//! let error = load_config(file.path()).unwrap_err();
+//! match error.downcast_ref::<ConfigFileError>() {
+//!     Some(ConfigFileError::Parse { line, column, .. }) => assert_eq!((*line, *column), (3, 7)),
+//!     other => panic!("erreur inattendue : {:?}", other),
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
 
 use crate::config::OcrConfig;
-use crate::preprocessing::PreprocessingConfig;
+use crate::preprocessing::{PreprocessingConfig, PreprocessingProfile};
 use anyhow::{Context, Result, anyhow};
-use serde::{Deserialize, Serialize};
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{self, DeserializeOwned, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
 use std::path::Path;
 
+/// Taille maximale acceptée pour un fichier de configuration (1 Mio).
+///
+/// Une configuration tient en quelques kilo-octets : au-delà, le fichier
+/// est presque certainement une erreur (mauvais chemin, fichier binaire).
+pub const MAX_CONFIG_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Erreur typée du chargement d'un fichier de configuration.
+///
+/// Retournée dans l'erreur `anyhow` de [`load_config()`] (récupérable avec
+/// `downcast_ref`), sous un contexte qui nomme le fichier.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ConfigFileError {
+    /// Le fichier dépasse [`MAX_CONFIG_FILE_SIZE`].
+    #[error("fichier trop volumineux (plus de {max} octets)")]
+    TooLarge {
+        /// Taille maximale acceptée, en octets.
+        max: u64,
+    },
+
+    /// Le contenu n'est pas un JSON/TOML valide ou ne correspond pas à la
+    /// structure attendue.
+    #[error("{format} invalide ligne {line}, colonne {column} : {message}")]
+    Parse {
+        /// Format du fichier (`"JSON"` ou `"TOML"`).
+        format: &'static str,
+        /// Ligne de l'erreur (à partir de 1).
+        line: usize,
+        /// Colonne de l'erreur, en caractères (à partir de 1).
+        column: usize,
+        /// Description de l'erreur par le désérialiseur.
+        message: String,
+    },
+
+    /// Une règle de [`AppConfig::rules`] référence un profil absent de
+    /// [`AppConfig::profiles`].
+    #[error("la règle '{pattern}' référence le profil inconnu '{profile}'")]
+    UnknownProfile {
+        /// Pattern glob de la règle.
+        pattern: String,
+        /// Nom du profil introuvable.
+        profile: String,
+    },
+}
+
 /// Configuration complète de l'application, chargeable depuis un fichier JSON ou TOML.
 ///
-/// Les deux champs sont optionnels : un fichier peut ne contenir que la section `ocr`
+/// Tous les champs sont optionnels : un fichier peut ne contenir que la section `ocr`
 /// ou que la section `preprocessing`.
 ///
 /// # Exemple
@@ -70,6 +198,7 @@ use std::path::Path;
 /// let config = AppConfig {
 ///     ocr: Some(OcrConfig::default()),
 ///     preprocessing: None,
+///     ..AppConfig::default()
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,7 +207,20 @@ pub struct AppConfig {
     pub ocr: Option<OcrConfig>,
 
     /// Configuration du prétraitement d'images (optionnel).
+    ///
+    /// Un nom de profil (`preprocessing = "best"`, voir [`PreprocessingProfile`])
+    /// tient lieu de section complète.
+    #[serde(default, deserialize_with = "deserialize_preprocessing")]
     pub preprocessing: Option<PreprocessingConfig>,
+
+    /// Profils nommés, déjà fusionnés avec la configuration de premier niveau
+    /// au chargement (voir la section « Profils » du module).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, AppConfig>,
+
+    /// Règles d'affectation des images aux profils, dans l'ordre d'évaluation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<ProfileRule>,
 }
 
 impl Default for AppConfig {
@@ -86,10 +228,166 @@ impl Default for AppConfig {
         Self {
             ocr: Some(OcrConfig::default()),
             preprocessing: None,
+            profiles: HashMap::new(),
+            rules: Vec::new(),
         }
     }
 }
 
+/// Désérialise la section `preprocessing` : nom de profil ou table complète.
+fn deserialize_preprocessing<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<PreprocessingConfig>, D::Error> {
+    deserializer.deserialize_any(PreprocessingVisitor)
+}
+
+/// Visiteur de la section `preprocessing`.
+struct PreprocessingVisitor;
+
+impl<'de> Visitor<'de> for PreprocessingVisitor {
+    type Value = Option<PreprocessingConfig>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("un profil de prétraitement (fast, balanced, best) ou une table")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<Self::Value, E> {
+        value
+            .parse()
+            .map(|profile: PreprocessingProfile| Some(PreprocessingConfig::from_profile(profile)))
+            .map_err(|e: anyhow::Error| E::custom(e))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> std::result::Result<Self::Value, A::Error> {
+        PreprocessingConfig::deserialize(MapAccessDeserializer::new(map)).map(Some)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_none<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+/// Règle d'affectation d'un profil : les images dont le chemin correspond à
+/// `pattern` sont traitées avec le profil `profile`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileRule {
+    /// Pattern glob, comparé au chemin de l'image relatif à la racine du lot
+    /// (`*` ne traverse pas les répertoires, `**` si).
+    pub pattern: String,
+    /// Nom du profil, clé de [`AppConfig::profiles`].
+    pub profile: String,
+}
+
+/// Options de correspondance des règles : `*` s'arrête aux séparateurs.
+const RULE_MATCH_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Retourne le profil de la première règle dont le pattern correspond à `path`.
+///
+/// Les patterns invalides ne correspondent à rien (ils sont refusés par
+/// [`load_config()`]).
+///
+/// # Arguments
+///
+/// * `path` - Chemin de l'image, relatif à la racine du lot
+/// * `rules` - Règles, dans l'ordre d'évaluation
+///
+/// # Retour
+///
+/// Le nom du profil, ou `None` si aucune règle ne correspond (la
+/// configuration de premier niveau s'applique)
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::config_file::{ProfileRule, resolve_profile};
+/// use std::path::Path;
+///
+/// let rules = [ProfileRule {
+///     pattern: "receipts/**".to_string(),
+///     profile: "receipt".to_string(),
+/// }];
+///
+/// assert_eq!(resolve_profile(Path::new("receipts/2024/a.png"), &rules), Some("receipt"));
+/// assert_eq!(resolve_profile(Path::new("letters/a.png"), &rules), None);
+/// ```
+pub fn resolve_profile<'a>(path: &Path, rules: &'a [ProfileRule]) -> Option<&'a str> {
+    rules
+        .iter()
+        .find(|rule| {
+            glob::Pattern::new(&rule.pattern)
+                .is_ok_and(|pattern| pattern.matches_path_with(path, RULE_MATCH_OPTIONS))
+        })
+        .map(|rule| rule.profile.as_str())
+}
+
+/// Applique `overrides` par-dessus `base`, section par section.
+///
+/// Une section présente dans `overrides` remplace celle de `base`, à
+/// l'exception des variables Tesseract, fusionnées variable par variable
+/// (celles de `overrides` l'emportent). Une section absente de `overrides`
+/// est reprise de `base`. Les profils et règles sont ceux de `base`.
+///
+/// # Arguments
+///
+/// * `base` - Configuration de départ
+/// * `overrides` - Configuration prioritaire
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::config_file::{AppConfig, merge_app_config};
+/// use text_recognition::preprocessing::PreprocessingConfig;
+///
+/// let overrides = AppConfig {
+///     ocr: None,
+///     preprocessing: Some(PreprocessingConfig::default()),
+///     ..AppConfig::default()
+/// };
+/// let merged = merge_app_config(&AppConfig::default(), &overrides);
+///
+/// assert!(merged.ocr.is_some());
+/// assert!(merged.preprocessing.is_some());
+/// ```
+pub fn merge_app_config(base: &AppConfig, overrides: &AppConfig) -> AppConfig {
+    let ocr = match (&base.ocr, &overrides.ocr) {
+        (Some(base_ocr), Some(override_ocr)) => {
+            let mut ocr = override_ocr.clone();
+            for (name, value) in &base_ocr.tesseract_variables {
+                ocr.tesseract_variables
+                    .entry(name.clone())
+                    .or_insert_with(|| value.clone());
+            }
+            Some(ocr)
+        }
+        (base_ocr, override_ocr) => override_ocr.clone().or_else(|| base_ocr.clone()),
+    };
+
+    AppConfig {
+        ocr,
+        preprocessing: overrides
+            .preprocessing
+            .clone()
+            .or_else(|| base.preprocessing.clone()),
+        profiles: base.profiles.clone(),
+        rules: base.rules.clone(),
+    }
+}
+
 /// Charge une configuration depuis un fichier JSON ou TOML.
 ///
 /// Le format est déterminé par l'extension du fichier :
@@ -118,26 +416,288 @@ impl Default for AppConfig {
 /// Retourne une erreur si :
 /// - Le fichier n'existe pas ou n'est pas lisible
 /// - L'extension n'est pas `.json` ou `.toml`
-/// - Le contenu n'est pas un JSON/TOML valide
+/// - Le fichier dépasse [`MAX_CONFIG_FILE_SIZE`] ([`ConfigFileError::TooLarge`])
+/// - Le contenu n'est pas un JSON/TOML valide ([`ConfigFileError::Parse`])
 /// - Les champs ne correspondent pas à la structure attendue
+///   ([`ConfigFileError::Parse`] lorsque la position est connue)
+/// - Une règle a un pattern glob invalide ou référence un profil inconnu
+///   ([`ConfigFileError::UnknownProfile`])
 pub fn load_config(path: &Path) -> Result<AppConfig> {
+    load_config_with_preset(path, None)
+}
+
+/// Charge une configuration depuis un fichier JSON ou TOML en imposant un preset.
+///
+/// Identique à [`load_config()`], mais `preset` (s'il est fourni) remplace
+/// la clé `preset` du fichier. Les champs de la section `ocr` du fichier
+/// restent appliqués par-dessus le preset. Utilisé par la CLI pour que
+/// `--preset` ait priorité sur le fichier.
+///
+/// # Arguments
+///
+/// * `path` - Chemin vers le fichier de configuration
+/// * `preset` - Nom du preset à appliquer (prioritaire sur celui du fichier)
+///
+/// # Erreurs
+///
+/// Retourne les mêmes erreurs que [`load_config()`], ainsi qu'une erreur
+/// listant les presets valides si le nom de preset est inconnu.
+///
+/// `preset` ne s'applique qu'à la configuration de premier niveau (et aux
+/// profils qui en héritent), pas aux profils qui déclarent leur propre preset.
+pub fn load_config_with_preset(path: &Path, preset: Option<&str>) -> Result<AppConfig> {
     let extension = path
         .extension()
         .and_then(|e| e.to_str())
         .map(|e| e.to_lowercase());
 
-    let content = std::fs::read_to_string(path)
+    let format = match extension.as_deref() {
+        Some("json") => ConfigFormat::Json,
+        Some("toml") => ConfigFormat::Toml,
+        other => {
+            return Err(anyhow!(
+                "Extension non supportée : '{}'. Utilisez .json ou .toml",
+                other.unwrap_or("(aucune)")
+            ));
+        }
+    };
+
+    let content = read_limited(path)
         .with_context(|| format!("Impossible de lire le fichier '{}'", path.display()))?;
 
-    match extension.as_deref() {
-        Some("json") => serde_json::from_str(&content)
-            .with_context(|| format!("Fichier JSON invalide : '{}'", path.display())),
-        Some("toml") => toml::from_str(&content)
-            .with_context(|| format!("Fichier TOML invalide : '{}'", path.display())),
-        other => Err(anyhow!(
-            "Extension non supportée : '{}'. Utilisez .json ou .toml",
-            other.unwrap_or("(aucune)")
-        )),
+    let mut value: serde_json::Value = format
+        .parse(&content)
+        .with_context(|| format!("Fichier {} invalide : '{}'", format.name(), path.display()))?;
+
+    let preset_applied = apply_preset(&mut value, preset)
+        .with_context(|| format!("Preset invalide dans '{}'", path.display()))?;
+
+    let profiles_expanded = expand_profiles(&mut value)
+        .with_context(|| format!("Profil invalide dans '{}'", path.display()))?;
+
+    let config: AppConfig = serde_json::from_value(value)
+        .map_err(|error| {
+            // La valeur intermédiaire n'a plus de positions : sans preset ni
+            // profil, le contenu est relu directement pour situer l'erreur
+            let located = (!preset_applied && !profiles_expanded)
+                .then(|| format.parse::<AppConfig>(&content).err())
+                .flatten();
+            located.unwrap_or_else(|| error.into())
+        })
+        .with_context(|| format!("Configuration invalide : '{}'", path.display()))?;
+
+    validate_rules(&config)
+        .with_context(|| format!("Règles de profil invalides dans '{}'", path.display()))?;
+    Ok(config)
+}
+
+/// Fusionne chaque profil par-dessus la configuration de premier niveau.
+///
+/// Le preset propre à un profil est appliqué à ses champs avant la fusion.
+///
+/// Retourne `true` si au moins un profil a été fusionné.
+fn expand_profiles(value: &mut serde_json::Value) -> Result<bool> {
+    let Some(root) = value.as_object_mut() else {
+        return Ok(false);
+    };
+    let Some(serde_json::Value::Object(profiles)) = root.remove("profiles") else {
+        return Ok(false);
+    };
+
+    expand_preprocessing_shorthand(root)?;
+    let mut base = root.clone();
+    base.remove("rules");
+    let base = serde_json::Value::Object(base);
+
+    let mut expanded = serde_json::Map::new();
+    for (name, mut profile) in profiles {
+        let Some(fields) = profile.as_object() else {
+            anyhow::bail!("Le profil '{}' doit être une table", name);
+        };
+        if fields.contains_key("profiles") || fields.contains_key("rules") {
+            anyhow::bail!(
+                "Le profil '{}' ne peut pas définir de profils ni de règles",
+                name
+            );
+        }
+        apply_preset(&mut profile, None)
+            .with_context(|| format!("Preset invalide dans le profil '{}'", name))?;
+        if let Some(fields) = profile.as_object_mut() {
+            expand_preprocessing_shorthand(fields)
+                .with_context(|| format!("Profil de prétraitement invalide dans '{}'", name))?;
+        }
+        let mut merged = base.clone();
+        merge_values(&mut merged, profile);
+        expanded.insert(name, merged);
+    }
+
+    let expanded_any = !expanded.is_empty();
+    root.insert("profiles".to_string(), serde_json::Value::Object(expanded));
+    Ok(expanded_any)
+}
+
+/// Remplace `preprocessing = "<profil>"` par la table complète du profil.
+///
+/// Sans cela, les champs de prétraitement d'un profil remplaceraient le
+/// raccourci au lieu de s'y ajouter lors de la fusion.
+fn expand_preprocessing_shorthand(
+    section: &mut serde_json::Map<String, serde_json::Value>,
+) -> Result<()> {
+    if let Some(serde_json::Value::String(name)) = section.get("preprocessing") {
+        let config = PreprocessingConfig::from_profile(name.parse()?);
+        let expanded =
+            serde_json::to_value(config).context("Échec de la sérialisation du profil")?;
+        section.insert("preprocessing".to_string(), expanded);
+    }
+    Ok(())
+}
+
+/// Vérifie que chaque règle a un pattern valide et un profil défini.
+fn validate_rules(config: &AppConfig) -> Result<()> {
+    for rule in &config.rules {
+        glob::Pattern::new(&rule.pattern)
+            .with_context(|| format!("Pattern glob invalide : '{}'", rule.pattern))?;
+        if !config.profiles.contains_key(&rule.profile) {
+            return Err(ConfigFileError::UnknownProfile {
+                pattern: rule.pattern.clone(),
+                profile: rule.profile.clone(),
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Format d'un fichier de configuration, déterminé par son extension.
+#[derive(Debug, Clone, Copy)]
+enum ConfigFormat {
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Nom du format dans les messages d'erreur.
+    fn name(self) -> &'static str {
+        match self {
+            ConfigFormat::Json => "JSON",
+            ConfigFormat::Toml => "TOML",
+        }
+    }
+
+    /// Désérialise `content`, en localisant l'erreur éventuelle ([`ConfigFileError::Parse`]).
+    fn parse<T: DeserializeOwned>(self, content: &str) -> Result<T> {
+        match self {
+            ConfigFormat::Json => serde_json::from_str(content).map_err(|error| {
+                // Le message de serde_json se termine par " at line L column C"
+                let full = error.to_string();
+                let suffix = format!(" at line {} column {}", error.line(), error.column());
+                let message = full.strip_suffix(&suffix).unwrap_or(&full).to_string();
+                self.parse_error(error.line(), error.column(), message)
+            }),
+            ConfigFormat::Toml => toml::from_str(content).map_err(|error| {
+                let message = error.message().to_string();
+                match error.span() {
+                    Some(span) => {
+                        let (line, column) = line_column(content, span.start);
+                        self.parse_error(line, column, message)
+                    }
+                    None => anyhow!(message),
+                }
+            }),
+        }
+    }
+
+    fn parse_error(self, line: usize, column: usize, message: String) -> anyhow::Error {
+        ConfigFileError::Parse {
+            format: self.name(),
+            line,
+            column,
+            message,
+        }
+        .into()
+    }
+}
+
+/// Ligne et colonne (à partir de 1, colonne en caractères) d'un décalage en octets.
+///
+/// Un décalage hors du texte ou au milieu d'un caractère est ramené à la
+/// frontière de caractère précédente.
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(content.len());
+    while !content.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Lit un fichier texte en refusant ceux de plus de [`MAX_CONFIG_FILE_SIZE`] octets.
+///
+/// La lecture est bornée : un fichier spécial sans fin (`/dev/zero`) est
+/// refusé sans être chargé en mémoire.
+fn read_limited(path: &Path) -> Result<String> {
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)?
+        .take(MAX_CONFIG_FILE_SIZE + 1)
+        .read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > MAX_CONFIG_FILE_SIZE {
+        return Err(ConfigFileError::TooLarge {
+            max: MAX_CONFIG_FILE_SIZE,
+        }
+        .into());
+    }
+    String::from_utf8(bytes).context("Le fichier n'est pas encodé en UTF-8")
+}
+
+/// Remplace la section `ocr` par le preset demandé, surchargé par les champs du fichier.
+///
+/// Le preset est celui passé en argument, sinon la clé `preset` du fichier.
+/// Sans preset, la valeur est laissée intacte (hormis la suppression de la clé).
+///
+/// Retourne `true` si un preset a été appliqué.
+fn apply_preset(value: &mut serde_json::Value, preset: Option<&str>) -> Result<bool> {
+    let Some(root) = value.as_object_mut() else {
+        return Ok(false);
+    };
+
+    let file_preset = root.remove("preset");
+    let name = match (preset, &file_preset) {
+        (Some(name), _) => name.to_string(),
+        (None, Some(serde_json::Value::String(name))) => name.clone(),
+        (None, Some(other)) => {
+            anyhow::bail!("La clé 'preset' doit être une chaîne, trouvé : {}", other)
+        }
+        (None, None) => return Ok(false),
+    };
+
+    let mut merged = serde_json::to_value(OcrConfig::try_from_preset_name(&name)?)
+        .context("Échec de la sérialisation du preset")?;
+    if let Some(overrides) = root.remove("ocr") {
+        merge_values(&mut merged, overrides);
+    }
+    root.insert("ocr".to_string(), merged);
+
+    Ok(true)
+}
+
+/// Fusionne récursivement `overrides` dans `base` (les objets sont fusionnés clé par clé).
+fn merge_values(base: &mut serde_json::Value, overrides: serde_json::Value) {
+    match (base, overrides) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
     }
 }
 
@@ -146,6 +706,7 @@ mod tests {
     use super::*;
     use crate::config::PageSegMode;
     use crate::preprocessing::BinarizationMethod;
+    use crate::rng::SplitMix64;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -328,9 +889,431 @@ deskew = false
         );
     }
 
+    // ─── Presets ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_load_preset_with_overrides() {
+        let content = r#"
+preset = "receipt"
+
+[ocr]
+dpi = 300
+
+[ocr.tesseract_variables]
+preserve_interword_spaces = "0"
+"#;
+        let file = write_temp(".toml", content);
+        let config = load_config(file.path()).unwrap();
+
+        let ocr = config.ocr.unwrap();
+        // Valeurs du preset conservées
+        assert_eq!(ocr.page_seg_mode, PageSegMode::SingleColumn);
+        assert!(
+            ocr.tesseract_variables
+                .contains_key("tessedit_char_whitelist")
+        );
+        // Surcharges du fichier prioritaires
+        assert_eq!(ocr.dpi, 300);
+        assert_eq!(
+            ocr.tesseract_variables.get("preserve_interword_spaces"),
+            Some(&"0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_load_preset_without_ocr_section() {
+        let file = write_temp(".json", r#"{ "preset": "photo" }"#);
+        let config = load_config(file.path()).unwrap();
+
+        let ocr = config.ocr.unwrap();
+        assert_eq!(ocr.dpi, 200);
+        assert!(config.preprocessing.is_none());
+    }
+
+    #[test]
+    fn test_cli_preset_overrides_file_preset() {
+        let content = r#"
+preset = "photo"
+
+[ocr]
+language = "eng"
+"#;
+        let file = write_temp(".toml", content);
+        let config = load_config_with_preset(file.path(), Some("receipt")).unwrap();
+
+        let ocr = config.ocr.unwrap();
+        assert_eq!(ocr.page_seg_mode, PageSegMode::SingleColumn);
+        assert_eq!(ocr.language, "eng");
+    }
+
+    #[test]
+    fn test_load_unknown_preset() {
+        let file = write_temp(".toml", r#"preset = "inconnu""#);
+        let message = format!("{:#}", load_config(file.path()).unwrap_err());
+        assert!(message.contains("inconnu"));
+        assert!(message.contains("receipt"));
+    }
+
     #[test]
     fn test_load_nonexistent_file() {
         let result = load_config(Path::new("/tmp/this_file_does_not_exist.json"));
         assert!(result.is_err());
     }
+
+    /// Position `(ligne, colonne)` d'une erreur [`ConfigFileError::Parse`].
+    fn parse_position(error: &anyhow::Error) -> Option<(usize, usize)> {
+        match error.downcast_ref::<ConfigFileError>() {
+            Some(ConfigFileError::Parse { line, column, .. }) => Some((*line, *column)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_load_syntax_error_positions() {
+        let file = write_temp(".json", "{\n  \"ocr\": {\n    \"dpi\": 300,\n  }\n}");
+        let error = load_config(file.path()).unwrap_err();
+        assert_eq!(parse_position(&error), Some((4, 3)));
+        let message = format!("{:#}", error);
+        assert!(message.contains("Fichier JSON invalide"));
+        assert!(message.contains("ligne 4, colonne 3"));
+        assert!(!message.contains(" at line "));
+
+        let file = write_temp(".toml", "[ocr]\nlanguage = \"fra\"\ndpi = = 300\n");
+        let error = load_config(file.path()).unwrap_err();
+        assert_eq!(parse_position(&error), Some((3, 7)));
+        assert!(format!("{:#}", error).contains("TOML invalide ligne 3"));
+    }
+
+    #[test]
+    fn test_load_type_error_positions() {
+        let file = write_temp(".json", "{\n  \"ocr\": {\n    \"dpi\": \"haut\"\n  }\n}");
+        let error = load_config(file.path()).unwrap_err();
+        assert!(format!("{:#}", error).contains("Configuration invalide"));
+        assert_eq!(parse_position(&error).map(|(line, _)| line), Some(3));
+
+        // Colonne comptée en caractères, pas en octets
+        let file = write_temp(".toml", "# configuré\n[ocr]\ndpi = \"é\"\n");
+        let error = load_config(file.path()).unwrap_err();
+        assert_eq!(parse_position(&error), Some((3, 7)));
+    }
+
+    #[test]
+    fn test_load_too_large_file() {
+        let padding = " ".repeat(MAX_CONFIG_FILE_SIZE as usize);
+        let file = write_temp(".json", &format!("{{}}{}", padding));
+        let error = load_config(file.path()).unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<ConfigFileError>(),
+            Some(&ConfigFileError::TooLarge {
+                max: MAX_CONFIG_FILE_SIZE
+            })
+        );
+    }
+
+    #[test]
+    fn test_line_column() {
+        assert_eq!(line_column("", 0), (1, 1));
+        assert_eq!(line_column("ab\ncd", 4), (2, 2));
+        // Décalage au milieu de « é » ou au-delà du texte
+        assert_eq!(line_column("é", 1), (1, 1));
+        assert_eq!(line_column("é", 99), (1, 2));
+    }
+
+    #[test]
+    fn test_load_truncated_config_never_panics() {
+        let json = r#"{"preset": "receipt", "ocr": {"language": "fra", "dpi": 300},
+ "preprocessing": {"binarize": true, "binarization_method": "Otsu"}}"#;
+        let toml = "preset = \"receipt\"\n[ocr]\nlanguage = \"fra\"\n\
+                    [preprocessing]\nbinarize = true\nbinarization_method = \"Otsu\"\n";
+
+        for (end, _) in json.char_indices() {
+            let file = write_temp(".json", &json[..end]);
+            let error = load_config(file.path()).unwrap_err();
+            // Tout préfixe strict d'un objet JSON est une erreur de syntaxe localisée
+            assert!(parse_position(&error).is_some(), "{:#}", error);
+        }
+        for (end, _) in toml.char_indices() {
+            let file = write_temp(".toml", &toml[..end]);
+            if let Err(error) = load_config(file.path())
+                && let Some((line, column)) = parse_position(&error)
+            {
+                assert!(line >= 1 && column >= 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_random_bytes_never_panics() {
+        let mut rng = SplitMix64::new(0xC0FFEE);
+        let mut next = || rng.next_u64();
+
+        for round in 0..200 {
+            let length = (next() % 64) as usize;
+            let bytes: Vec<u8> = (0..length).map(|_| next() as u8).collect();
+            let extension = if round % 2 == 0 { ".json" } else { ".toml" };
+            let mut file = tempfile::Builder::new()
+                .suffix(extension)
+                .tempfile()
+                .unwrap();
+            file.write_all(&bytes).unwrap();
+
+            // Erreur attendue dans la plupart des cas, mais jamais de panique
+            let _ = load_config(file.path());
+        }
+    }
+
+    // ─── Profils ─────────────────────────────────────────────────────────────
+
+    const PROFILES_TOML: &str = r#"
+[ocr]
+language = "fra"
+page_seg_mode = "Auto"
+dpi = 300
+
+[ocr.tesseract_variables]
+preserve_interword_spaces = "1"
+
+[preprocessing]
+to_grayscale = true
+binarize = true
+binarization_method = "Otsu"
+adjust_contrast = false
+contrast_factor = 1.0
+denoise = true
+deskew = false
+
+[profiles.receipt.ocr]
+page_seg_mode = "SingleColumn"
+
+[profiles.receipt.ocr.tesseract_variables]
+tessedit_char_whitelist = "0123456789.,€"
+
+[profiles.receipt.preprocessing]
+deskew = true
+denoise = false
+
+[profiles.english.ocr]
+language = "eng"
+
+[[rules]]
+pattern = "receipts/**"
+profile = "receipt"
+
+[[rules]]
+pattern = "*.eng.png"
+profile = "english"
+
+[[rules]]
+pattern = "receipts/*.eng.png"
+profile = "english"
+"#;
+
+    #[test]
+    fn test_load_profiles_and_rules() {
+        let file = write_temp(".toml", PROFILES_TOML);
+        let config = load_config(file.path()).unwrap();
+
+        assert_eq!(config.profiles.len(), 2);
+        assert_eq!(config.rules.len(), 3);
+
+        let receipt = &config.profiles["receipt"];
+        let ocr = receipt.ocr.as_ref().unwrap();
+        assert_eq!(ocr.page_seg_mode, PageSegMode::SingleColumn);
+        // Champs et variables hérités du premier niveau
+        assert_eq!((ocr.language.as_str(), ocr.dpi), ("fra", 300));
+        assert_eq!(ocr.tesseract_variables.len(), 2);
+        assert!(receipt.profiles.is_empty() && receipt.rules.is_empty());
+
+        let english = config.profiles["english"].ocr.as_ref().unwrap();
+        assert_eq!(english.language, "eng");
+        assert_eq!(english.page_seg_mode, PageSegMode::Auto);
+
+        // Le premier niveau n'est pas modifié par les profils
+        assert_eq!(
+            config.ocr.as_ref().unwrap().page_seg_mode,
+            PageSegMode::Auto
+        );
+    }
+
+    #[test]
+    fn test_resolve_profile_first_match_wins() {
+        let file = write_temp(".toml", PROFILES_TOML);
+        let config = load_config(file.path()).unwrap();
+        let resolve = |path: &str| resolve_profile(Path::new(path), &config.rules);
+
+        assert_eq!(resolve("receipts/a.png"), Some("receipt"));
+        assert_eq!(resolve("receipts/2024/03/b.png"), Some("receipt"));
+        // La règle receipts/** précède receipts/*.eng.png
+        assert_eq!(resolve("receipts/c.eng.png"), Some("receipt"));
+        assert_eq!(resolve("d.eng.png"), Some("english"));
+        // `*` ne traverse pas les répertoires
+        assert_eq!(resolve("letters/d.eng.png"), None);
+        assert_eq!(resolve("letters/e.png"), None);
+        assert_eq!(resolve_profile(Path::new("receipts/a.png"), &[]), None);
+    }
+
+    #[test]
+    fn test_profile_preprocessing_fields_override_top_level() {
+        let file = write_temp(".toml", PROFILES_TOML);
+        let config = load_config(file.path()).unwrap();
+
+        let top = config.preprocessing.as_ref().unwrap();
+        assert!(top.denoise && !top.deskew);
+
+        let receipt = config.profiles["receipt"].preprocessing.as_ref().unwrap();
+        assert!(receipt.deskew, "champ du profil");
+        assert!(!receipt.denoise, "champ du profil prioritaire");
+        assert!(receipt.to_grayscale && receipt.binarize, "champs hérités");
+        assert_eq!(receipt.binarization_method, BinarizationMethod::Otsu);
+
+        // Profil sans section preprocessing : reprise telle quelle
+        let english = config.profiles["english"].preprocessing.as_ref().unwrap();
+        assert!(english.denoise && !english.deskew);
+    }
+
+    #[test]
+    fn test_load_preprocessing_profile_shorthand() {
+        let toml = write_temp(".toml", "preprocessing = \"best\"\n");
+        let prep = load_config(toml.path()).unwrap().preprocessing.unwrap();
+        assert!(prep.deskew && prep.denoise && prep.binarize);
+        assert_eq!(prep.binarization_method, BinarizationMethod::Adaptive);
+
+        let json = write_temp(".json", r#"{"preprocessing": "balanced"}"#);
+        let prep = load_config(json.path()).unwrap().preprocessing.unwrap();
+        assert!(prep.binarize && !prep.deskew);
+        assert_eq!(prep.despeckle, Some(crate::PROFILE_DESPECKLE_AREA));
+
+        let null = write_temp(".json", r#"{"preprocessing": null}"#);
+        assert!(load_config(null.path()).unwrap().preprocessing.is_none());
+    }
+
+    #[test]
+    fn test_unknown_preprocessing_profile_is_located() {
+        let file = write_temp(".toml", "\npreprocessing = \"turbo\"\n");
+        let error = load_config(file.path()).unwrap_err();
+        assert_eq!(parse_position(&error).map(|(line, _)| line), Some(2));
+
+        let file = write_temp(".json", "{\n  \"preprocessing\": \"turbo\"\n}");
+        let error = load_config(file.path()).unwrap_err();
+        assert_eq!(parse_position(&error).map(|(line, _)| line), Some(2));
+        assert!(format!("{:#}", error).contains("turbo"), "{:#}", error);
+    }
+
+    #[test]
+    fn test_profile_fields_apply_over_preprocessing_shorthand() {
+        let file = write_temp(
+            ".toml",
+            r#"
+preprocessing = "best"
+
+[profiles.straight.preprocessing]
+deskew = false
+
+[[rules]]
+pattern = "*.png"
+profile = "straight"
+"#,
+        );
+        let config = load_config(file.path()).unwrap();
+
+        let straight = config.profiles["straight"].preprocessing.as_ref().unwrap();
+        assert!(!straight.deskew, "champ du profil");
+        assert!(straight.denoise, "étape héritée du raccourci");
+        assert_eq!(straight.binarization_method, BinarizationMethod::Adaptive);
+        assert!(config.preprocessing.unwrap().deskew);
+    }
+
+    #[test]
+    fn test_rule_with_unknown_profile_is_load_error() {
+        let content = PROFILES_TOML.replace("profile = \"english\"", "profile = \"anglais\"");
+        let file = write_temp(".toml", &content);
+
+        let error = load_config(file.path()).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<ConfigFileError>(),
+            Some(&ConfigFileError::UnknownProfile {
+                pattern: "*.eng.png".to_string(),
+                profile: "anglais".to_string(),
+            })
+        );
+        assert!(format!("{:#}", error).contains("anglais"));
+
+        let content = PROFILES_TOML.replace("receipts/**", "receipts/[");
+        let file = write_temp(".toml", &content);
+        let error = load_config(file.path()).unwrap_err();
+        assert!(format!("{:#}", error).contains("Pattern glob invalide"));
+    }
+
+    #[test]
+    fn test_profile_with_own_preset() {
+        let content = r#"{
+            "ocr": {"language": "eng", "page_seg_mode": "Auto", "dpi": 150, "tesseract_variables": {}},
+            "profiles": {"scan": {"preset": "receipt", "ocr": {"dpi": 400}}}
+        }"#;
+        let file = write_temp(".json", content);
+        let config = load_config(file.path()).unwrap();
+
+        let preset = OcrConfig::try_from_preset_name("receipt").unwrap();
+        let scan = config.profiles["scan"].ocr.as_ref().unwrap();
+        assert_eq!(scan.dpi, 400);
+        assert_eq!(scan.page_seg_mode, preset.page_seg_mode);
+        assert_eq!(config.ocr.as_ref().unwrap().dpi, 150);
+    }
+
+    #[test]
+    fn test_merge_app_config_precedence() {
+        let mut base_ocr = OcrConfig::default();
+        base_ocr
+            .tesseract_variables
+            .insert("a".to_string(), "base".to_string());
+        base_ocr
+            .tesseract_variables
+            .insert("b".to_string(), "base".to_string());
+        let base = AppConfig {
+            ocr: Some(base_ocr),
+            preprocessing: Some(PreprocessingConfig {
+                denoise: true,
+                ..PreprocessingConfig::default()
+            }),
+            ..AppConfig::default()
+        };
+
+        let mut override_ocr = OcrConfig {
+            dpi: 600,
+            ..OcrConfig::default()
+        };
+        override_ocr
+            .tesseract_variables
+            .insert("b".to_string(), "profil".to_string());
+        let overrides = AppConfig {
+            ocr: Some(override_ocr),
+            preprocessing: Some(PreprocessingConfig {
+                deskew: true,
+                ..PreprocessingConfig::default()
+            }),
+            ..AppConfig::default()
+        };
+
+        let merged = merge_app_config(&base, &overrides);
+        let ocr = merged.ocr.unwrap();
+        assert_eq!(ocr.dpi, 600);
+        assert_eq!(ocr.tesseract_variables["a"], "base");
+        assert_eq!(ocr.tesseract_variables["b"], "profil");
+        // La section preprocessing présente remplace celle de la base
+        let prep = merged.preprocessing.unwrap();
+        assert!(prep.deskew && !prep.denoise);
+
+        // Section absente : reprise de la base
+        let merged = merge_app_config(
+            &base,
+            &AppConfig {
+                ocr: None,
+                preprocessing: None,
+                ..AppConfig::default()
+            },
+        );
+        assert!(merged.preprocessing.unwrap().denoise);
+        assert_eq!(merged.ocr.unwrap().tesseract_variables.len(), 2);
+    }
 }
@@ -0,0 +1,173 @@
+//! Vérification croisée de la bibliothèque Tesseract et du binaire `tesseract`.
+//!
+//! Le moteur appelle Tesseract de deux façons : par la bibliothèque liée
+//! (crate `tesseract`) pour l'extraction de texte, et par le binaire
+//! `tesseract` pour le hOCR, l'OSD et le vocabulaire utilisateur. Les deux
+//! peuvent diverger (versions, valeurs par défaut différentes).
+//! [`cross_check()`](crate::ocr::cross_check) analyse la même image par les
+//! deux chemins, avec les mêmes langue, PSM, OEM, DPI et variables, et
+//! résume l'écart dans un [`CrossCheckReport`].
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::cross_check::CrossCheckReport;
+//!
+//! let report = CrossCheckReport::from_outputs(
+//!     "Facture 42".to_string(),
+//!     "Facture 42".to_string(),
+//!     "5.3.4".to_string(),
+//!     Some("5.3.4".to_string()),
+//!     Vec::new(),
+//! );
+//! assert!(!report.mismatch);
+//! assert!(report.to_string().starts_with("✓"));
+//! ```
+
+use crate::metrics::calculate_cer;
+use serde::Serialize;
+use std::fmt;
+
+/// CER maximal toléré entre les deux sorties avant de signaler une divergence.
+///
+/// Les deux chemins reçoivent les mêmes paramètres : hors différence de
+/// version, leurs textes devraient être identiques.
+pub const CROSS_CHECK_MAX_CER: f64 = 0.01;
+
+/// Résultat d'une vérification croisée, retourné par [`cross_check()`](crate::ocr::cross_check).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrossCheckReport {
+    /// Texte brut extrait par la bibliothèque (sans nettoyage).
+    pub library_text: String,
+    /// Texte brut extrait par le binaire (sans nettoyage).
+    pub binary_text: String,
+    /// CER du texte du binaire par rapport à celui de la bibliothèque.
+    pub cer: f64,
+    /// Seuil de divergence ([`CROSS_CHECK_MAX_CER`]).
+    pub max_cer: f64,
+    /// Indique si le CER dépasse le seuil.
+    pub mismatch: bool,
+    /// Version de la bibliothèque liée (`TessVersion`).
+    pub library_version: String,
+    /// Version du binaire (`tesseract --version`), `None` si elle n'a pas pu être lue.
+    pub binary_version: Option<String>,
+    /// Options passées au binaire, après l'image et la base de sortie.
+    pub binary_args: Vec<String>,
+}
+
+impl CrossCheckReport {
+    /// Construit le rapport à partir des deux sorties.
+    ///
+    /// # Arguments
+    ///
+    /// * `library_text` - Texte extrait par la bibliothèque
+    /// * `binary_text` - Texte extrait par le binaire
+    /// * `library_version` - Version de la bibliothèque liée
+    /// * `binary_version` - Version du binaire, si connue
+    /// * `binary_args` - Options passées au binaire
+    pub fn from_outputs(
+        library_text: String,
+        binary_text: String,
+        library_version: String,
+        binary_version: Option<String>,
+        binary_args: Vec<String>,
+    ) -> Self {
+        let cer = calculate_cer(&binary_text, &library_text);
+        Self {
+            library_text,
+            binary_text,
+            cer,
+            max_cer: CROSS_CHECK_MAX_CER,
+            mismatch: cer > CROSS_CHECK_MAX_CER,
+            library_version,
+            binary_version,
+            binary_args,
+        }
+    }
+
+    /// Indique si la bibliothèque et le binaire ont la même version.
+    ///
+    /// Retourne `false` si la version du binaire est inconnue.
+    pub fn same_version(&self) -> bool {
+        self.binary_version.as_deref() == Some(self.library_version.as_str())
+    }
+}
+
+impl fmt::Display for CrossCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mismatch {
+            writeln!(
+                f,
+                "✗ Divergence entre la bibliothèque et le binaire (CER {:.2} %, seuil {:.2} %)",
+                self.cer * 100.0,
+                self.max_cer * 100.0
+            )?;
+        } else {
+            writeln!(
+                f,
+                "✓ La bibliothèque et le binaire concordent (CER {:.2} %, seuil {:.2} %)",
+                self.cer * 100.0,
+                self.max_cer * 100.0
+            )?;
+        }
+        writeln!(f, "  Bibliothèque : tesseract {}", self.library_version)?;
+        match self.binary_version {
+            Some(ref version) => writeln!(f, "  Binaire : tesseract {}", version)?,
+            None => writeln!(f, "  Binaire : version inconnue")?,
+        }
+        if !self.same_version() {
+            writeln!(
+                f,
+                "  ⚠ Versions différentes : les résultats peuvent diverger"
+            )?;
+        }
+        write!(f, "  Options du binaire : {}", self.binary_args.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(library: &str, binary: &str, binary_version: Option<&str>) -> CrossCheckReport {
+        CrossCheckReport::from_outputs(
+            library.to_string(),
+            binary.to_string(),
+            "5.3.0".to_string(),
+            binary_version.map(String::from),
+            vec!["-l".to_string(), "fra".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_identical_outputs_agree() {
+        let report = report("Facture 42\n", "Facture 42\n", Some("5.3.0"));
+        assert_eq!(report.cer, 0.0);
+        assert!(!report.mismatch);
+        assert!(report.same_version());
+        let text = report.to_string();
+        assert!(text.starts_with("✓ La bibliothèque et le binaire concordent"));
+        assert!(!text.contains("Versions différentes"));
+        assert!(text.ends_with("Options du binaire : -l fra"));
+    }
+
+    #[test]
+    fn test_divergent_outputs_are_flagged() {
+        let report = report("Facture 42", "Factvre 4Z", Some("5.3.4"));
+        assert!(report.cer > CROSS_CHECK_MAX_CER);
+        assert!(report.mismatch);
+        assert!(!report.same_version());
+        let text = report.to_string();
+        assert!(text.starts_with("✗ Divergence"));
+        assert!(text.contains("Bibliothèque : tesseract 5.3.0"));
+        assert!(text.contains("Binaire : tesseract 5.3.4"));
+        assert!(text.contains("Versions différentes"));
+    }
+
+    #[test]
+    fn test_unknown_binary_version() {
+        let report = report("a", "a", None);
+        assert!(!report.same_version());
+        assert!(report.to_string().contains("Binaire : version inconnue"));
+    }
+}
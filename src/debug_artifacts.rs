@@ -0,0 +1,370 @@
+//! Conservation des fichiers intermédiaires d'une extraction.
+//!
+//! Pour comprendre une extraction décevante, il faut voir exactement ce que
+//! Tesseract a reçu et ce qu'il a renvoyé ; or les images prétraitées et les
+//! sorties brutes sont écrites dans des répertoires temporaires supprimés
+//! aussitôt. Avec [`OcrEngine::with_debug_artifacts()`], chaque extraction
+//! copie ses fichiers dans un sous-répertoire dédié, nommé d'après le
+//! radical de l'image et l'horodatage (`facture-1760443200123`) :
+//!
+//! - `image.<ext>` : image réellement transmise à Tesseract (après prétraitement)
+//! - `output.txt` : texte brut renvoyé, avant nettoyage (ou sortie de l'OSD)
+//! - `output.json` : document hOCR analysé, en JSON versionné
+//!   ([`HocrDocument::to_json()`]), lorsque l'extraction produit un hOCR
+//! - `tesseract.log` / `tesseract.stderr.txt` : diagnostics de la
+//!   bibliothèque Tesseract, ou sortie d'erreur du binaire `tesseract`
+//! - [`SIDECAR_FILE_NAME`] : configuration effective, durées, liste des
+//!   fichiers et erreur éventuelle ([`ArtifactSidecar`])
+//!
+//! Une seconde analyse (DPI adaptatif) suffixe ses fichiers par `-pass2`.
+//! Un échec de copie n'interrompt jamais l'extraction : il est signalé
+//! dans les avertissements du résultat lorsque l'extraction en retourne.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::ocr::OcrEngine;
+//! use std::path::Path;
+//!
+//! let engine = OcrEngine::new(OcrConfig::default())?.with_debug_artifacts("debug/");
+//! engine.extract_text_from_file(Path::new("facture.png"))?;
+//! // debug/facture-<horodatage>/image.png, output.txt, tesseract.log, artifacts.json
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! [`OcrEngine::with_debug_artifacts()`]: crate::ocr::OcrEngine::with_debug_artifacts
+
+use crate::config::OcrConfig;
+use crate::diagnostics::Warning;
+use crate::effective_config::EffectiveConfig;
+use crate::hocr::HocrDocument;
+use serde::Serialize;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Nom du fichier JSON décrivant une extraction dans son sous-répertoire.
+pub const SIDECAR_FILE_NAME: &str = "artifacts.json";
+
+/// Description d'une extraction, écrite dans [`SIDECAR_FILE_NAME`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArtifactSidecar {
+    /// Radical du fichier analysé (`image` pour une image en mémoire).
+    pub source: String,
+    /// Type d'extraction : `ocr`, `hocr` ou `osd`.
+    pub operation: String,
+    /// Configuration effectivement transmise à Tesseract.
+    pub effective_config: EffectiveConfig,
+    /// Analyses Tesseract successives (plusieurs avec le DPI adaptatif).
+    pub passes: Vec<PassTiming>,
+    /// Durée totale de l'extraction, en millisecondes.
+    pub total_ms: f64,
+    /// Fichiers copiés dans le sous-répertoire, dans l'ordre d'écriture.
+    pub files: Vec<String>,
+    /// Message de l'erreur, si l'extraction a échoué.
+    pub error: Option<String>,
+}
+
+/// Durée d'une analyse Tesseract.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PassTiming {
+    /// Résolution transmise à Tesseract pour cette analyse.
+    pub dpi: u32,
+    /// Durée de l'analyse, en millisecondes.
+    pub duration_ms: f64,
+}
+
+/// Enregistreur des fichiers intermédiaires d'une extraction.
+///
+/// Sans répertoire configuré, toutes les méthodes sont sans effet et rien
+/// n'est écrit sur le disque.
+pub(crate) struct ArtifactRecorder {
+    active: Option<ActiveRecorder>,
+    failures: Vec<String>,
+}
+
+/// État d'un enregistreur dont le sous-répertoire a été créé.
+struct ActiveRecorder {
+    dir: PathBuf,
+    sidecar: ArtifactSidecar,
+    started: Instant,
+}
+
+impl ArtifactRecorder {
+    /// Crée le sous-répertoire d'une extraction sous `root`, si configuré.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Répertoire des artefacts (`None` : enregistreur inactif)
+    /// * `source` - Radical du fichier analysé
+    /// * `operation` - Type d'extraction (`ocr`, `hocr`, `osd`)
+    /// * `config` - Configuration du moteur
+    pub(crate) fn start(
+        root: Option<&Path>,
+        source: &str,
+        operation: &str,
+        config: &OcrConfig,
+    ) -> Self {
+        let mut recorder = Self {
+            active: None,
+            failures: Vec::new(),
+        };
+        let Some(root) = root else {
+            return recorder;
+        };
+
+        match create_extraction_dir(root, source) {
+            Ok(dir) => {
+                recorder.active = Some(ActiveRecorder {
+                    dir,
+                    sidecar: ArtifactSidecar {
+                        source: source.to_string(),
+                        operation: operation.to_string(),
+                        effective_config: EffectiveConfig::from_config(config),
+                        passes: Vec::new(),
+                        total_ms: 0.0,
+                        files: Vec::new(),
+                        error: None,
+                    },
+                    started: Instant::now(),
+                });
+            }
+            Err(error) => recorder.failures.push(format!(
+                "Artefacts de débogage désactivés : impossible de créer un répertoire dans '{}' ({})",
+                root.display(),
+                error
+            )),
+        }
+        recorder
+    }
+
+    /// Copie un fichier dans le sous-répertoire sous le nom `name`.
+    pub(crate) fn copy_file(&mut self, source: &Path, name: &str) {
+        if let Some(ref active) = self.active {
+            let result = std::fs::copy(source, active.dir.join(name)).map(|_| ());
+            self.record(name, result);
+        }
+    }
+
+    /// Copie l'image transmise à Tesseract en conservant son extension.
+    pub(crate) fn copy_image(&mut self, image: &Path, suffix: &str) {
+        let extension = image
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_else(|| "img".to_string());
+        self.copy_file(image, &format!("image{}.{}", suffix, extension));
+    }
+
+    /// Écrit `contents` dans le sous-répertoire sous le nom `name`.
+    pub(crate) fn write(&mut self, name: &str, contents: &[u8]) {
+        if let Some(ref active) = self.active {
+            let result = std::fs::write(active.dir.join(name), contents);
+            self.record(name, result);
+        }
+    }
+
+    /// Écrit un document HOCR en JSON versionné sous le nom `name`.
+    pub(crate) fn write_document(&mut self, name: &str, document: &HocrDocument) {
+        if self.active.is_none() {
+            return;
+        }
+        match document.to_json() {
+            Ok(json) => self.write(name, json.as_bytes()),
+            Err(error) => self.record(name, Err(io::Error::other(error))),
+        }
+    }
+
+    /// Analyse un hOCR et l'écrit en JSON versionné sous le nom `name`.
+    ///
+    /// Le hOCR n'est analysé que si l'enregistreur est actif.
+    pub(crate) fn write_hocr(&mut self, name: &str, hocr: &str) {
+        if self.active.is_none() {
+            return;
+        }
+        match HocrDocument::from_hocr_string(hocr) {
+            Ok(document) => self.write_document(name, &document),
+            Err(error) => self.record(name, Err(io::Error::other(format!("{:#}", error)))),
+        }
+    }
+
+    /// Ajoute la durée d'une analyse Tesseract à la description.
+    pub(crate) fn record_pass(&mut self, dpi: u32, duration: Duration) {
+        if let Some(ref mut active) = self.active {
+            active.sidecar.passes.push(PassTiming {
+                dpi,
+                duration_ms: duration.as_secs_f64() * 1000.0,
+            });
+        }
+    }
+
+    /// Écrit la description de l'extraction et retourne les échecs de copie.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - Erreur de l'extraction, si elle a échoué
+    pub(crate) fn finish(mut self, error: Option<&anyhow::Error>) -> Vec<Warning> {
+        if let Some(mut active) = self.active.take() {
+            active.sidecar.total_ms = active.started.elapsed().as_secs_f64() * 1000.0;
+            active.sidecar.error = error.map(|error| format!("{:#}", error));
+            active.sidecar.files.push(SIDECAR_FILE_NAME.to_string());
+            let result = serde_json::to_vec_pretty(&active.sidecar)
+                .map_err(io::Error::other)
+                .and_then(|json| std::fs::write(active.dir.join(SIDECAR_FILE_NAME), json));
+            if let Err(error) = result {
+                self.failures.push(copy_failure(SIDECAR_FILE_NAME, &error));
+            }
+        }
+        self.failures.into_iter().map(Warning::new).collect()
+    }
+
+    /// Consigne le résultat d'une écriture.
+    fn record(&mut self, name: &str, result: io::Result<()>) {
+        match result {
+            Ok(()) => {
+                if let Some(ref mut active) = self.active {
+                    active.sidecar.files.push(name.to_string());
+                }
+            }
+            Err(error) => self.failures.push(copy_failure(name, &error)),
+        }
+    }
+}
+
+/// Message d'avertissement d'un artefact non copié.
+fn copy_failure(name: &str, error: &io::Error) -> String {
+    format!("Artefact de débogage '{}' non enregistré : {}", name, error)
+}
+
+/// Crée `root/<source>-<millisecondes>`, suffixé au besoin pour rester unique.
+fn create_extraction_dir(root: &Path, source: &str) -> io::Result<PathBuf> {
+    std::fs::create_dir_all(root)?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or_default();
+    let base = format!("{}-{}", source, millis);
+
+    // Deux extractions de la même image dans la même milliseconde (lot parallèle)
+    let mut attempt = 1;
+    loop {
+        let dir = match attempt {
+            1 => root.join(&base),
+            n => root.join(format!("{}-{}", base, n)),
+        };
+        match std::fs::create_dir(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => attempt += 1,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Radical d'un chemin d'image, utilisé pour nommer son sous-répertoire.
+pub(crate) fn source_label(path: &Path) -> &str {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or(IN_MEMORY_SOURCE)
+}
+
+/// Radical utilisé pour une image sans fichier d'origine (mémoire, octets).
+pub(crate) const IN_MEMORY_SOURCE: &str = "image";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_inactive_recorder_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("entree.png");
+        std::fs::write(&source, b"png").unwrap();
+
+        let mut recorder = ArtifactRecorder::start(None, "entree", "ocr", &OcrConfig::default());
+        recorder.copy_image(&source, "");
+        recorder.write("output.txt", b"texte");
+        recorder.record_pass(300, Duration::from_millis(5));
+
+        assert!(recorder.finish(None).is_empty());
+        assert_eq!(entries(dir.path()), ["entree.png"]);
+    }
+
+    #[test]
+    fn test_recorder_writes_files_and_sidecar() {
+        let root = tempfile::tempdir().unwrap();
+        let source = root.path().join("scan.PNG");
+        std::fs::write(&source, b"png").unwrap();
+        let artifacts = root.path().join("artefacts");
+
+        let mut recorder =
+            ArtifactRecorder::start(Some(&artifacts), "scan", "ocr", &OcrConfig::default());
+        recorder.copy_image(&source, "");
+        recorder.write("output.txt", b"Bonjour");
+        recorder.copy_file(&root.path().join("absent.log"), "tesseract.log");
+        recorder.record_pass(300, Duration::from_millis(12));
+        let warnings = recorder.finish(Some(&anyhow::anyhow!("échec")));
+
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("tesseract.log"));
+
+        let subdirs = entries(&artifacts);
+        assert_eq!(subdirs.len(), 1);
+        assert!(subdirs[0].starts_with("scan-"));
+        let dir = artifacts.join(&subdirs[0]);
+        assert_eq!(entries(&dir), ["artifacts.json", "image.png", "output.txt"]);
+
+        let sidecar: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(dir.join(SIDECAR_FILE_NAME)).unwrap()).unwrap();
+        assert_eq!(sidecar["source"], "scan");
+        assert_eq!(sidecar["effective_config"]["dpi"], 300);
+        assert_eq!(sidecar["passes"][0]["dpi"], 300);
+        assert_eq!(sidecar["error"], "échec");
+        assert_eq!(
+            sidecar["files"],
+            serde_json::json!(["image.png", "output.txt", "artifacts.json"])
+        );
+    }
+
+    #[test]
+    fn test_extraction_dirs_are_unique() {
+        let root = tempfile::tempdir().unwrap();
+        let first = create_extraction_dir(root.path(), "page").unwrap();
+        let second = create_extraction_dir(root.path(), "page").unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(entries(root.path()).len(), 2);
+    }
+
+    #[test]
+    fn test_unwritable_root_is_a_warning() {
+        let root = tempfile::tempdir().unwrap();
+        let file = root.path().join("fichier");
+        std::fs::write(&file, b"").unwrap();
+
+        // Un fichier à la place du répertoire : rien n'est écrit, un avertissement suffit
+        let mut recorder =
+            ArtifactRecorder::start(Some(&file), "scan", "ocr", &OcrConfig::default());
+        recorder.write("output.txt", b"texte");
+        let warnings = recorder.finish(None);
+
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("désactivés"));
+    }
+
+    #[test]
+    fn test_source_label() {
+        assert_eq!(source_label(Path::new("scans/facture.png")), "facture");
+        assert_eq!(source_label(Path::new("archive.tar.gz")), "archive.tar");
+        assert_eq!(source_label(Path::new("")), IN_MEMORY_SOURCE);
+    }
+}
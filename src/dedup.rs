@@ -0,0 +1,285 @@
+//! Détection des images en double (re-numérisations) avant un traitement batch.
+//!
+//! Un dossier de numérisations contient souvent plusieurs fois la même page :
+//! copie exacte, ou nouveau passage dans le scanner avec une luminosité un
+//! peu différente. L'empreinte perceptuelle [`perceptual_hash()`] (dHash)
+//! résume l'image réduite à 9 × 8 pixels en 64 bits, chaque bit indiquant si
+//! un pixel est plus clair que son voisin de droite : deux numérisations de
+//! la même page ne diffèrent que de quelques bits, mesurés par
+//! [`hamming_distance()`].
+//!
+//! [`find_duplicates()`] regroupe les images dont les empreintes sont
+//! proches. Les groupes sont transitifs (union-find) : si A ressemble à B et
+//! B à C, les trois images forment un seul groupe.
+//!
+//! # Exemple
+//!
+//! ```
+//! use image::{GrayImage, Luma};
+//! use text_recognition::dedup::{hamming_distance, perceptual_hash};
+//!
+//! let page = GrayImage::from_fn(90, 80, |x, y| Luma([((x * 7 + y * 13) % 256) as u8]));
+//! let brighter = GrayImage::from_fn(90, 80, |x, y| {
+//!     Luma([page.get_pixel(x, y)[0].saturating_add(10)])
+//! });
+//!
+//! assert!(hamming_distance(perceptual_hash(&page), perceptual_hash(&brighter)) <= 5);
+//! ```
+
+use image::GrayImage;
+use image::imageops::{self, FilterType};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Largeur de l'image réduite par [`perceptual_hash()`] (une colonne de plus que de bits par ligne).
+const HASH_WIDTH: u32 = 9;
+
+/// Hauteur de l'image réduite par [`perceptual_hash()`] (une ligne de bits par ligne de pixels).
+const HASH_HEIGHT: u32 = 8;
+
+/// Plus grande dimension des images chargées par [`find_duplicates()`] avant le calcul de l'empreinte.
+///
+/// Réduire d'abord l'image avec [`image::DynamicImage::thumbnail()`] évite un
+/// redimensionnement coûteux de la pleine résolution, sans changer l'empreinte
+/// de façon notable.
+pub const HASH_THUMBNAIL_SIZE: u32 = 256;
+
+/// Calcule l'empreinte perceptuelle (dHash) d'une image.
+///
+/// L'image est réduite à 9 × 8 pixels ; le bit `y * 8 + x` vaut 1 si le pixel
+/// `(x, y)` est plus clair que le pixel `(x + 1, y)`. L'empreinte ne dépend que
+/// des variations de luminosité : un éclaircissement uniforme ou un
+/// changement de résolution la modifient peu.
+///
+/// # Arguments
+///
+/// * `image` - Image en niveaux de gris
+///
+/// # Retour
+///
+/// L'empreinte sur 64 bits (0 pour une image uniforme ou vide).
+pub fn perceptual_hash(image: &GrayImage) -> u64 {
+    if image.width() == 0 || image.height() == 0 {
+        return 0;
+    }
+    let small = imageops::resize(image, HASH_WIDTH, HASH_HEIGHT, FilterType::Triangle);
+
+    let mut hash = 0u64;
+    for y in 0..HASH_HEIGHT {
+        for x in 0..HASH_WIDTH - 1 {
+            let bit = u64::from(small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0]);
+            hash |= bit << (y * (HASH_WIDTH - 1) + x);
+        }
+    }
+    hash
+}
+
+/// Nombre de bits différents entre deux empreintes (0 à 64).
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::dedup::hamming_distance;
+///
+/// assert_eq!(hamming_distance(0b1011, 0b0001), 2);
+/// ```
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Groupe d'images quasi identiques, retourné par [`find_duplicates()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateGroup {
+    /// Première image du groupe dans l'ordre des chemins fournis : la seule à traiter.
+    pub original: PathBuf,
+    /// Autres images du groupe, dans l'ordre des chemins fournis.
+    pub duplicates: Vec<PathBuf>,
+}
+
+/// Regroupe les images quasi identiques parmi `paths`.
+///
+/// Chaque image est chargée, réduite (voir [`HASH_THUMBNAIL_SIZE`]) et
+/// résumée par [`perceptual_hash()`]. Deux images dont les empreintes
+/// diffèrent d'au plus `max_distance` bits appartiennent au même groupe,
+/// ainsi que toutes les images qui leur ressemblent de proche en proche.
+///
+/// Les images illisibles sont ignorées : elles n'appartiennent à aucun
+/// groupe et seront signalées par l'extraction elle-même.
+///
+/// # Arguments
+///
+/// * `paths` - Chemins des images, dans l'ordre de traitement
+/// * `max_distance` - Distance de Hamming maximale entre deux doublons (0 : copies exactes à la réduction près)
+///
+/// # Retour
+///
+/// Les groupes d'au moins deux images, triés par position de leur image originale.
+pub fn find_duplicates(paths: &[PathBuf], max_distance: u32) -> Vec<DuplicateGroup> {
+    let hashes: Vec<Option<u64>> = paths
+        .iter()
+        .map(|path| {
+            let image = image::open(path).ok()?;
+            let thumbnail = image.thumbnail(HASH_THUMBNAIL_SIZE, HASH_THUMBNAIL_SIZE);
+            Some(perceptual_hash(&thumbnail.to_luma8()))
+        })
+        .collect();
+    group_hashes(paths, &hashes, max_distance)
+}
+
+/// Regroupe les chemins dont les empreintes sont proches (cœur de [`find_duplicates()`]).
+fn group_hashes(
+    paths: &[PathBuf],
+    hashes: &[Option<u64>],
+    max_distance: u32,
+) -> Vec<DuplicateGroup> {
+    let mut sets = DisjointSet::new(paths.len());
+    for (i, a) in hashes.iter().enumerate() {
+        let Some(a) = *a else { continue };
+        for (j, b) in hashes.iter().enumerate().skip(i + 1) {
+            if let Some(b) = *b
+                && hamming_distance(a, b) <= max_distance
+            {
+                sets.union(i, j);
+            }
+        }
+    }
+
+    // La racine d'un ensemble est son plus petit indice : l'original
+    let mut groups: Vec<DuplicateGroup> = Vec::new();
+    let mut group_of_root = vec![None; paths.len()];
+    for (index, path) in paths.iter().enumerate() {
+        let root = sets.find(index);
+        if root == index {
+            continue;
+        }
+        let group = *group_of_root[root].get_or_insert_with(|| {
+            groups.push(DuplicateGroup {
+                original: paths[root].clone(),
+                duplicates: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        groups[group].duplicates.push(path.clone());
+    }
+    groups
+}
+
+/// Ensembles disjoints (union-find) dont la racine est le plus petit indice.
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    /// Crée `len` ensembles d'un élément.
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+        }
+    }
+
+    /// Racine de l'ensemble de `index`, avec compression des chemins.
+    fn find(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut current = index;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    /// Réunit les ensembles de `a` et `b` sous la plus petite des deux racines.
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        let (root, child) = if a <= b { (a, b) } else { (b, a) };
+        self.parent[child] = root;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    /// Page à motif diagonal, assez riche en variations pour une empreinte non triviale.
+    fn page() -> GrayImage {
+        GrayImage::from_fn(180, 160, |x, y| {
+            Luma([(((x / 12) * 37 + (y / 10) * 91) % 200) as u8 + 20])
+        })
+    }
+
+    #[test]
+    fn test_identical_images_hash_identically() {
+        assert_eq!(perceptual_hash(&page()), perceptual_hash(&page()));
+        assert_eq!(perceptual_hash(&GrayImage::new(0, 0)), 0);
+    }
+
+    #[test]
+    fn test_brightened_copy_stays_close_and_unrelated_image_does_not() {
+        let original = page();
+        let brighter = GrayImage::from_fn(180, 160, |x, y| {
+            Luma([original.get_pixel(x, y)[0].saturating_add(15)])
+        });
+        let unrelated = GrayImage::from_fn(180, 160, |x, y| {
+            Luma([if (x / 30 + y / 40) % 2 == 0 { 30 } else { 230 }])
+        });
+
+        let hash = perceptual_hash(&original);
+        assert!(hamming_distance(hash, perceptual_hash(&brighter)) <= 5);
+        assert!(hamming_distance(hash, perceptual_hash(&unrelated)) > 5);
+    }
+
+    #[test]
+    fn test_grouping_is_transitive() {
+        let paths: Vec<PathBuf> = ["a.png", "b.png", "c.png", "d.png", "e.png"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        // a ~ c (2 bits), c ~ e (2 bits), mais a et e diffèrent de 4 bits ;
+        // b est illisible, d sans rapport
+        let hashes = [
+            Some(0b0000),
+            None,
+            Some(0b0011),
+            Some(u64::MAX),
+            Some(0b1111),
+        ];
+
+        let groups = group_hashes(&paths, &hashes, 2);
+
+        assert_eq!(
+            groups,
+            [DuplicateGroup {
+                original: PathBuf::from("a.png"),
+                duplicates: vec![PathBuf::from("c.png"), PathBuf::from("e.png")],
+            }]
+        );
+        assert!(group_hashes(&paths, &hashes, 1).is_empty());
+    }
+
+    #[test]
+    fn test_find_duplicates_loads_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = page();
+        let rescan = GrayImage::from_fn(180, 160, |x, y| {
+            Luma([original.get_pixel(x, y)[0].saturating_add(8)])
+        });
+        let paths: Vec<PathBuf> = ["page.png", "autre.txt", "page-bis.png"]
+            .iter()
+            .map(|name| dir.path().join(name))
+            .collect();
+        original.save(&paths[0]).unwrap();
+        std::fs::write(&paths[1], "pas une image").unwrap();
+        rescan.save(&paths[2]).unwrap();
+
+        let groups = find_duplicates(&paths, 5);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].original, paths[0]);
+        assert_eq!(groups[0].duplicates, [paths[2].clone()]);
+    }
+}
@@ -0,0 +1,281 @@
+//! Audit du déterminisme des résultats de Tesseract.
+//!
+//! Deux exécutions identiques ne donnent pas toujours le même texte : le
+//! moteur LSTM répartit son travail entre threads OpenMP, et le découpage
+//! peut varier d'une exécution à l'autre. [`determinism_check_with()`]
+//! extrait plusieurs fois le texte d'une même image, compare les empreintes
+//! SHA-256 des sorties et, lorsqu'elles diffèrent, mesure l'écart entre
+//! chaque paire de variantes (CER et numéros des lignes différentes).
+//!
+//! Avec [`OcrConfig::deterministic`](crate::config::OcrConfig::deterministic),
+//! les appels au binaire `tesseract` sont limités à un thread.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::determinism::DeterminismReport;
+//!
+//! let outputs = ["Facture 42\nTotal 10 €", "Facture 42\nTotal 10 €", "Facture 42\nTota1 10 €"];
+//! let report = DeterminismReport::from_outputs(&outputs.map(String::from));
+//!
+//! assert!(!report.deterministic);
+//! assert_eq!(report.variants.len(), 2);
+//! assert_eq!(report.variants[0].runs, [1, 2]);
+//! assert_eq!(report.differences[0].differing_lines, [2]);
+//! ```
+
+use crate::metrics::calculate_cer;
+use crate::pool::OcrWorker;
+use crate::run_metadata::sha256_hex;
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::path::Path;
+
+/// Nombre minimal d'exécutions d'un audit.
+pub const MIN_DETERMINISM_RUNS: usize = 2;
+
+/// Sortie distincte obtenue lors d'un audit de déterminisme.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutputVariant {
+    /// Empreinte SHA-256 du texte (hexadécimal).
+    pub hash: String,
+    /// Numéros des exécutions ayant produit ce texte (à partir de 1).
+    pub runs: Vec<usize>,
+    /// Texte extrait.
+    pub text: String,
+}
+
+/// Écart entre deux variantes d'un audit de déterminisme.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VariantDifference {
+    /// Indice de la première variante dans [`DeterminismReport::variants`].
+    pub first: usize,
+    /// Indice de la seconde variante.
+    pub second: usize,
+    /// CER de la seconde variante par rapport à la première.
+    pub cer: f64,
+    /// Numéros des lignes qui diffèrent (à partir de 1).
+    pub differing_lines: Vec<usize>,
+}
+
+/// Résultat d'un audit de déterminisme.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeterminismReport {
+    /// Nombre d'exécutions.
+    pub runs: usize,
+    /// Empreinte de la sortie de chaque exécution, dans l'ordre.
+    pub hashes: Vec<String>,
+    /// `true` si toutes les exécutions ont produit le même texte.
+    pub deterministic: bool,
+    /// Sorties distinctes, dans l'ordre de leur première apparition.
+    pub variants: Vec<OutputVariant>,
+    /// Écart entre chaque paire de variantes (vide si l'extraction est déterministe).
+    pub differences: Vec<VariantDifference>,
+}
+
+impl DeterminismReport {
+    /// Construit le rapport à partir des textes extraits à chaque exécution.
+    ///
+    /// # Arguments
+    ///
+    /// * `outputs` - Texte de chaque exécution, dans l'ordre
+    pub fn from_outputs(outputs: &[String]) -> Self {
+        let hashes: Vec<String> = outputs
+            .iter()
+            .map(|text| sha256_hex(text.as_bytes()))
+            .collect();
+
+        let mut variants: Vec<OutputVariant> = Vec::new();
+        for (run, (text, hash)) in outputs.iter().zip(&hashes).enumerate() {
+            match variants.iter_mut().find(|variant| variant.hash == *hash) {
+                Some(variant) => variant.runs.push(run + 1),
+                None => variants.push(OutputVariant {
+                    hash: hash.clone(),
+                    runs: vec![run + 1],
+                    text: text.clone(),
+                }),
+            }
+        }
+
+        let differences = (0..variants.len())
+            .flat_map(|first| (first + 1..variants.len()).map(move |second| (first, second)))
+            .map(|(first, second)| VariantDifference {
+                first,
+                second,
+                cer: calculate_cer(&variants[second].text, &variants[first].text),
+                differing_lines: differing_lines(&variants[first].text, &variants[second].text),
+            })
+            .collect();
+
+        Self {
+            runs: outputs.len(),
+            hashes,
+            deterministic: variants.len() <= 1,
+            variants,
+            differences,
+        }
+    }
+}
+
+/// Extrait `runs` fois le texte d'une image et compare les sorties.
+///
+/// Les extractions sont lancées l'une après l'autre par le même worker
+/// (voir [`OcrWorker`]) ; [`ocr::determinism_check()`](crate::ocr::determinism_check)
+/// en est la variante pour un [`OcrEngine`](crate::ocr::OcrEngine).
+///
+/// # Arguments
+///
+/// * `worker` - Worker réalisant chaque extraction
+/// * `path` - Chemin vers l'image
+/// * `runs` - Nombre d'exécutions (au moins [`MIN_DETERMINISM_RUNS`])
+///
+/// # Erreurs
+///
+/// Retourne une erreur si `runs` est inférieur à [`MIN_DETERMINISM_RUNS`]
+/// ou si une exécution échoue.
+pub fn determinism_check_with<W: OcrWorker>(
+    worker: &mut W,
+    path: &Path,
+    runs: usize,
+) -> Result<DeterminismReport> {
+    if runs < MIN_DETERMINISM_RUNS {
+        bail!(
+            "L'audit de déterminisme nécessite au moins {} exécutions, reçu {}",
+            MIN_DETERMINISM_RUNS,
+            runs
+        );
+    }
+
+    let outputs = (1..=runs)
+        .map(|run| {
+            worker
+                .extract(path)
+                .map(|outcome| outcome.text)
+                .with_context(|| format!("Échec de l'exécution {}/{}", run, runs))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(DeterminismReport::from_outputs(&outputs))
+}
+
+/// Numéros (à partir de 1) des lignes qui diffèrent entre deux textes.
+///
+/// Une ligne présente dans un seul des deux textes compte comme différente.
+fn differing_lines(first: &str, second: &str) -> Vec<usize> {
+    let first: Vec<&str> = first.lines().collect();
+    let second: Vec<&str> = second.lines().collect();
+    (0..first.len().max(second.len()))
+        .filter(|&index| first.get(index) != second.get(index))
+        .map(|index| index + 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr::OcrOutcome;
+
+    /// Worker factice : retourne les sorties prédéfinies l'une après l'autre.
+    struct ScriptedWorker {
+        outputs: Vec<&'static str>,
+        calls: usize,
+    }
+
+    impl ScriptedWorker {
+        fn new(outputs: &[&'static str]) -> Self {
+            Self {
+                outputs: outputs.to_vec(),
+                calls: 0,
+            }
+        }
+    }
+
+    impl OcrWorker for ScriptedWorker {
+        fn extract(&mut self, _path: &Path) -> Result<OcrOutcome> {
+            let text = self.outputs[self.calls % self.outputs.len()];
+            self.calls += 1;
+            if text == "échec" {
+                bail!("Tesseract a échoué");
+            }
+            Ok(OcrOutcome {
+                text: text.to_string(),
+                warnings: Vec::new(),
+                preprocessing: None,
+                cache_hit: false,
+            })
+        }
+    }
+
+    #[test]
+    fn test_identical_outputs_are_deterministic() {
+        let mut worker = ScriptedWorker::new(&["Facture 42\nTotal 10 €"]);
+
+        let report = determinism_check_with(&mut worker, Path::new("page.png"), 5).unwrap();
+
+        assert_eq!(worker.calls, 5);
+        assert!(report.deterministic);
+        assert_eq!(report.runs, 5);
+        assert_eq!(report.hashes.len(), 5);
+        assert!(report.hashes.iter().all(|hash| *hash == report.hashes[0]));
+        assert_eq!(report.variants.len(), 1);
+        assert_eq!(report.variants[0].runs, [1, 2, 3, 4, 5]);
+        assert!(report.differences.is_empty());
+    }
+
+    #[test]
+    fn test_mutated_output_lists_differing_pair() {
+        let mut worker = ScriptedWorker::new(&[
+            "Facture 42\nTotal 10 €\nMerci",
+            "Facture 42\nTotal 10 €\nMerci",
+            "Facture 42\nTota1 10 €\nMerci",
+        ]);
+
+        let report = determinism_check_with(&mut worker, Path::new("page.png"), 3).unwrap();
+
+        assert!(!report.deterministic);
+        assert_ne!(report.hashes[0], report.hashes[2]);
+        assert_eq!(report.variants.len(), 2);
+        assert_eq!(report.variants[1].runs, [3]);
+        assert_eq!(report.differences.len(), 1);
+        let difference = &report.differences[0];
+        assert_eq!((difference.first, difference.second), (0, 1));
+        assert!(difference.cer > 0.0);
+        assert_eq!(difference.differing_lines, [2]);
+    }
+
+    #[test]
+    fn test_all_variant_pairs_compared() {
+        let outputs = ["a", "b", "a", "c\nd"].map(String::from);
+
+        let report = DeterminismReport::from_outputs(&outputs);
+
+        assert_eq!(report.variants.len(), 3);
+        let pairs: Vec<(usize, usize)> = report
+            .differences
+            .iter()
+            .map(|difference| (difference.first, difference.second))
+            .collect();
+        assert_eq!(pairs, [(0, 1), (0, 2), (1, 2)]);
+        // Ligne absente d'une des variantes
+        assert_eq!(report.differences[2].differing_lines, [1, 2]);
+    }
+
+    #[test]
+    fn test_determinism_check_rejects_single_run_and_propagates_errors() {
+        let mut worker = ScriptedWorker::new(&["texte"]);
+        let error = determinism_check_with(&mut worker, Path::new("page.png"), 1).unwrap_err();
+        assert!(error.to_string().contains("au moins 2"));
+        assert_eq!(worker.calls, 0);
+
+        let mut worker = ScriptedWorker::new(&["texte", "échec"]);
+        let error = determinism_check_with(&mut worker, Path::new("page.png"), 3).unwrap_err();
+        assert!(error.to_string().contains("exécution 2/3"), "{}", error);
+    }
+
+    #[test]
+    fn test_differing_lines() {
+        assert!(differing_lines("a\nb", "a\nb").is_empty());
+        assert_eq!(differing_lines("a\nb\nc", "a\nB\nc"), [2]);
+        assert_eq!(differing_lines("a", "a\nb\nc"), [2, 3]);
+    }
+}
@@ -0,0 +1,406 @@
+//! Diagnostics émis par Tesseract pendant l'extraction.
+//!
+//! Tesseract signale certains problèmes uniquement par des messages sur
+//! stderr (ex: "Warning: Invalid resolution 0 dpi", "Empty page!!"). Sans
+//! ces messages, un texte vide reste inexpliqué. Ce module convertit ces
+//! lignes en [`Warning`] classés par [`WarningKind`].
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::diagnostics::{WarningKind, parse_tesseract_stderr};
+//!
+//! let warnings = parse_tesseract_stderr("Estimating resolution as 142\nEmpty page!!\n");
+//! assert_eq!(warnings[0].kind(), WarningKind::EstimatedResolution);
+//! assert_eq!(warnings[1].kind(), WarningKind::EmptyPage);
+//! ```
+
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+/// Catégorie d'un message de diagnostic Tesseract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    /// La résolution de l'image est invalide ou absente ("Invalid resolution 0 dpi").
+    InvalidResolution,
+
+    /// Tesseract a estimé lui-même la résolution ("Estimating resolution as 142").
+    EstimatedResolution,
+
+    /// Aucun texte n'a été détecté sur la page ("Empty page!!").
+    EmptyPage,
+
+    /// Pas assez de caractères pour la détection d'orientation ("Too few characters").
+    TooFewCharacters,
+
+    /// Bannière de version affichée au lancement du binaire.
+    Banner,
+
+    /// Page blanche détectée avant l'OCR : Tesseract n'a pas été lancé.
+    BlankPage,
+
+    /// Double page coupée en deux avant l'OCR (voir
+    /// [`detect_page_split()`](crate::preprocessing::detect_page_split)).
+    PageSplit,
+
+    /// Texte vertical détecté avant l'OCR : l'image est lue en PSM 5 (voir
+    /// [`OcrConfig::auto_vertical`](crate::config::OcrConfig::auto_vertical)).
+    VerticalText,
+
+    /// Aucun mot reconnu sur une page non vide : les lignes détectées ont été
+    /// lues une à une en PSM 7 (voir
+    /// [`OcrConfig::fallback_line_segmentation`](crate::config::OcrConfig::fallback_line_segmentation)).
+    LineFallback,
+
+    /// Chemin d'image non transmissible tel quel à la bibliothèque Tesseract
+    /// (non UTF-8) : l'image a été copiée vers un chemin temporaire.
+    PathIssues,
+
+    /// Texte lu sur une réduction de l'image (voir
+    /// [`OcrEngine::extract_text_pyramid()`](crate::ocr::OcrEngine::extract_text_pyramid)).
+    PyramidLevel,
+
+    /// Autre message non classé.
+    Other,
+}
+
+impl WarningKind {
+    /// Indique si ce type de message est du bruit sans conséquence.
+    ///
+    /// Les messages bruités (bannière, estimation de résolution) ne sont pas
+    /// affichés par la CLI.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::diagnostics::WarningKind;
+    ///
+    /// assert!(WarningKind::EstimatedResolution.is_noise());
+    /// assert!(!WarningKind::EmptyPage.is_noise());
+    /// ```
+    pub fn is_noise(self) -> bool {
+        matches!(self, WarningKind::EstimatedResolution | WarningKind::Banner)
+    }
+}
+
+/// Début du message émis lorsqu'une page blanche n'est pas envoyée à Tesseract.
+const BLANK_PAGE_MESSAGE_PREFIX: &str = "page blanche détectée";
+
+/// Début du message émis lorsqu'une double page est coupée avant l'OCR.
+const PAGE_SPLIT_MESSAGE_PREFIX: &str = "double page détectée";
+
+/// Début du message émis lorsque le texte vertical fait passer l'OCR en PSM 5.
+const VERTICAL_TEXT_MESSAGE_PREFIX: &str = "texte vertical détecté";
+
+/// Début des messages émis par la lecture ligne par ligne de secours.
+const LINE_FALLBACK_MESSAGE_PREFIX: &str = "segmentation par lignes";
+
+/// Début du message émis lorsqu'un chemin non UTF-8 est contourné.
+const PATH_ISSUES_MESSAGE_PREFIX: &str = "chemin non utf-8";
+
+/// Début du message émis par la lecture pyramidale.
+const PYRAMID_MESSAGE_PREFIX: &str = "lecture pyramidale";
+
+/// Message de diagnostic émis par Tesseract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// Message brut, tel qu'émis par Tesseract (sans espaces de fin).
+    pub message: String,
+}
+
+impl Warning {
+    /// Crée un diagnostic à partir d'un message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Ligne émise par Tesseract
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    /// Crée l'avertissement d'une page blanche dont l'OCR a été évité.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::diagnostics::{Warning, WarningKind};
+    ///
+    /// assert_eq!(Warning::blank_page().kind(), WarningKind::BlankPage);
+    /// ```
+    pub fn blank_page() -> Self {
+        Self::new("Page blanche détectée : OCR ignoré")
+    }
+
+    /// Crée l'avertissement d'une double page coupée à l'abscisse `split_x`.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::diagnostics::{Warning, WarningKind};
+    ///
+    /// assert_eq!(Warning::page_split(1240).kind(), WarningKind::PageSplit);
+    /// ```
+    pub fn page_split(split_x: u32) -> Self {
+        Self::new(format!(
+            "Double page détectée : coupée à x = {} px, pages lues séparément",
+            split_x
+        ))
+    }
+
+    /// Crée l'avertissement d'une image lue en PSM 5 car son texte est vertical.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::diagnostics::{Warning, WarningKind};
+    ///
+    /// assert_eq!(Warning::vertical_text().kind(), WarningKind::VerticalText);
+    /// ```
+    pub fn vertical_text() -> Self {
+        Self::new("Texte vertical détecté : image lue en PSM 5 (bloc de texte vertical)")
+    }
+
+    /// Crée l'avertissement d'une page relue ligne par ligne, faute de mot reconnu.
+    ///
+    /// # Arguments
+    ///
+    /// * `lines` - Nombre de lignes détectées et lues en PSM 7
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::diagnostics::{Warning, WarningKind};
+    ///
+    /// assert_eq!(Warning::line_fallback(5).kind(), WarningKind::LineFallback);
+    /// ```
+    pub fn line_fallback(lines: usize) -> Self {
+        Self::new(format!(
+            "Segmentation par lignes : aucun mot reconnu, {} ligne(s) détectée(s) lues une à une en PSM 7",
+            lines
+        ))
+    }
+
+    /// Crée l'avertissement d'une lecture ligne par ligne abandonnée.
+    ///
+    /// # Arguments
+    ///
+    /// * `lines` - Nombre de lignes détectées
+    /// * `max_lines` - Nombre maximal de lignes accepté
+    pub fn line_fallback_aborted(lines: usize, max_lines: usize) -> Self {
+        Self::new(format!(
+            "Segmentation par lignes abandonnée : {} lignes détectées (au plus {})",
+            lines, max_lines
+        ))
+    }
+
+    /// Crée l'avertissement d'une image copiée car son chemin n'est pas en UTF-8.
+    ///
+    /// La bibliothèque Tesseract ne reçoit que des chemins UTF-8 : l'image est
+    /// lue depuis une copie temporaire, le résultat est inchangé.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin d'origine de l'image
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use text_recognition::diagnostics::{Warning, WarningKind};
+    ///
+    /// let warning = Warning::path_issues(Path::new("scan.png"));
+    /// assert_eq!(warning.kind(), WarningKind::PathIssues);
+    /// ```
+    pub fn path_issues(path: &Path) -> Self {
+        Self::new(format!(
+            "Chemin non UTF-8 ('{}') : image copiée vers un chemin temporaire pour la bibliothèque Tesseract",
+            path.display()
+        ))
+    }
+
+    /// Crée l'avertissement du niveau retenu par une lecture pyramidale.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale` - Échelle du niveau retenu
+    /// * `levels_read` - Nombre de niveaux lus
+    /// * `time_saved` - Temps gagné estimé par rapport à la pleine résolution
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use text_recognition::diagnostics::{Warning, WarningKind};
+    ///
+    /// let warning = Warning::pyramid_level(0.3, 2, Duration::from_secs(12));
+    /// assert_eq!(warning.kind(), WarningKind::PyramidLevel);
+    /// ```
+    pub fn pyramid_level(scale: f32, levels_read: usize, time_saved: Duration) -> Self {
+        Self::new(format!(
+            "Lecture pyramidale : texte retenu à l'échelle {:.0} % après {} niveau(x), gain estimé {:.1} s",
+            scale * 100.0,
+            levels_read,
+            time_saved.as_secs_f64()
+        ))
+    }
+
+    /// Retourne la catégorie du message.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::diagnostics::{Warning, WarningKind};
+    ///
+    /// let warning = Warning::new("Warning: Invalid resolution 0 dpi. Using 70 instead.");
+    /// assert_eq!(warning.kind(), WarningKind::InvalidResolution);
+    /// ```
+    pub fn kind(&self) -> WarningKind {
+        let message = self.message.to_lowercase();
+
+        if message.contains("invalid resolution") {
+            WarningKind::InvalidResolution
+        } else if message.starts_with("estimating resolution as") {
+            WarningKind::EstimatedResolution
+        } else if message.contains("empty page") {
+            WarningKind::EmptyPage
+        } else if message.contains("too few characters") {
+            WarningKind::TooFewCharacters
+        } else if message.starts_with("tesseract open source ocr engine") {
+            WarningKind::Banner
+        } else if message.starts_with(BLANK_PAGE_MESSAGE_PREFIX) {
+            WarningKind::BlankPage
+        } else if message.starts_with(PAGE_SPLIT_MESSAGE_PREFIX) {
+            WarningKind::PageSplit
+        } else if message.starts_with(VERTICAL_TEXT_MESSAGE_PREFIX) {
+            WarningKind::VerticalText
+        } else if message.starts_with(LINE_FALLBACK_MESSAGE_PREFIX) {
+            WarningKind::LineFallback
+        } else if message.starts_with(PATH_ISSUES_MESSAGE_PREFIX) {
+            WarningKind::PathIssues
+        } else if message.starts_with(PYRAMID_MESSAGE_PREFIX) {
+            WarningKind::PyramidLevel
+        } else {
+            WarningKind::Other
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Convertit la sortie d'erreur de Tesseract en liste de diagnostics.
+///
+/// Chaque ligne non vide devient un [`Warning`], dans l'ordre d'émission.
+///
+/// # Arguments
+///
+/// * `stderr` - Sortie d'erreur du binaire `tesseract` (ou contenu de `debug_file`)
+pub fn parse_tesseract_stderr(stderr: &str) -> Vec<Warning> {
+    stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(Warning::new)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stderr_fixture_kinds() {
+        let stderr = "Tesseract Open Source OCR Engine v5.3.0 with Leptonica\n\
+                      Warning: Invalid resolution 0 dpi. Using 70 instead.\n\
+                      Estimating resolution as 142\n\
+                      \n\
+                      Too few characters. Skipping this page\n\
+                      Error during processing.\n";
+
+        let kinds: Vec<WarningKind> = parse_tesseract_stderr(stderr)
+            .iter()
+            .map(Warning::kind)
+            .collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                WarningKind::Banner,
+                WarningKind::InvalidResolution,
+                WarningKind::EstimatedResolution,
+                WarningKind::TooFewCharacters,
+                WarningKind::Other,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_page_detection() {
+        let warnings = parse_tesseract_stderr("Empty page!!\n");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind(), WarningKind::EmptyPage);
+        assert_eq!(warnings[0].to_string(), "Empty page!!");
+    }
+
+    #[test]
+    fn test_blank_page_warning_kind() {
+        let warning = Warning::blank_page();
+        assert_eq!(warning.kind(), WarningKind::BlankPage);
+        assert!(!warning.kind().is_noise());
+    }
+
+    #[test]
+    fn test_page_split_warning_kind() {
+        let warning = Warning::page_split(512);
+        assert_eq!(warning.kind(), WarningKind::PageSplit);
+        assert!(warning.to_string().contains("x = 512"));
+        assert!(!warning.kind().is_noise());
+    }
+
+    #[test]
+    fn test_line_fallback_warning_kind() {
+        let warning = Warning::line_fallback(5);
+        assert_eq!(warning.kind(), WarningKind::LineFallback);
+        assert!(warning.to_string().contains("5 ligne(s)"));
+        let aborted = Warning::line_fallback_aborted(400, 200);
+        assert_eq!(aborted.kind(), WarningKind::LineFallback);
+        assert!(!aborted.kind().is_noise());
+    }
+
+    #[test]
+    fn test_pyramid_level_warning_kind() {
+        let warning = Warning::pyramid_level(0.6, 2, Duration::from_millis(12_340));
+        assert_eq!(warning.kind(), WarningKind::PyramidLevel);
+        assert!(warning.to_string().contains("échelle 60 %"));
+        assert!(warning.to_string().contains("12.3 s"));
+        assert!(!warning.kind().is_noise());
+    }
+
+    #[test]
+    fn test_vertical_text_warning_kind() {
+        let warning = Warning::vertical_text();
+        assert_eq!(warning.kind(), WarningKind::VerticalText);
+        assert!(warning.to_string().contains("PSM 5"));
+        assert!(!warning.kind().is_noise());
+    }
+
+    #[test]
+    fn test_parse_empty_stderr() {
+        assert!(parse_tesseract_stderr("").is_empty());
+        assert!(parse_tesseract_stderr("\n  \n").is_empty());
+    }
+
+    #[test]
+    fn test_noise_kinds() {
+        assert!(WarningKind::Banner.is_noise());
+        assert!(WarningKind::EstimatedResolution.is_noise());
+        assert!(!WarningKind::InvalidResolution.is_noise());
+        assert!(!WarningKind::Other.is_noise());
+    }
+}
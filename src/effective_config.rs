@@ -0,0 +1,306 @@
+//! Configuration effectivement transmise à Tesseract.
+//!
+//! Entre un preset, un fichier de configuration et les options de la ligne
+//! de commande, il est facile de perdre de vue ce que Tesseract reçoit
+//! réellement. [`EffectiveConfig`] résume la configuration fusionnée d'un
+//! moteur : langue, PSM, DPI, OEM et variables Tesseract, triées pour une
+//! sortie déterministe.
+//!
+//! `set_variable` accepte silencieusement certaines clés mal orthographiées :
+//! les variables absentes de la liste des paramètres connus (instantané de
+//! `tesseract --print-parameters`, voir `tesseract_parameters.txt`) sont
+//! signalées par un avertissement.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::effective_config::EffectiveConfig;
+//!
+//! let mut config = OcrConfig::default();
+//! config
+//!     .tesseract_variables
+//!     .insert("tessedit_char_whitelis".to_string(), "0123456789".to_string());
+//!
+//! let effective = EffectiveConfig::from_config(&config);
+//! assert_eq!(effective.psm_value, 3);
+//! assert_eq!(effective.tesseract_variables["user_defined_dpi"], "300");
+//! assert!(effective.warnings[0].contains("tessedit_char_whitelist"));
+//! ```
+
+use crate::config::{OcrConfig, OcrEngineMode, PageSegMode};
+use crate::metrics::levenshtein_distance;
+use crate::pyramid::PyramidConfig;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Liste des paramètres Tesseract connus, un nom par ligne.
+const TESSERACT_PARAMETERS: &str = include_str!("tesseract_parameters.txt");
+
+/// Distance d'édition maximale pour proposer un paramètre voisin d'une clé inconnue.
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+
+/// Configuration effective d'un moteur OCR, après fusion de toutes les sources.
+///
+/// Les variables incluent `user_defined_dpi`, déduite du DPI puis
+/// éventuellement remplacée par une variable explicite, dans l'ordre où le
+/// moteur les applique.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffectiveConfig {
+    /// Langue(s) Tesseract (ex: "fra", "eng+fra").
+    pub language: String,
+    /// Mode de segmentation de page.
+    pub psm: PageSegMode,
+    /// Valeur numérique du mode de segmentation (`--psm` de Tesseract).
+    pub psm_value: i32,
+    /// Résolution transmise à Tesseract.
+    pub dpi: u32,
+    /// Moteur de reconnaissance.
+    pub engine_mode: OcrEngineMode,
+    /// Valeur numérique du moteur (`--oem` de Tesseract).
+    pub oem_value: i32,
+    /// Variables Tesseract appliquées, triées par nom.
+    pub tesseract_variables: BTreeMap<String, String>,
+    /// Fichier de mots utilisateur (`--user-words`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_words: Option<PathBuf>,
+    /// Fichier de motifs utilisateur (`--user-patterns`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_patterns: Option<PathBuf>,
+    /// Fichiers de configuration Tesseract, dans l'ordre d'application.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub config_names: Vec<String>,
+    /// Répertoire des modèles (`--tessdata-dir`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tessdata_dir: Option<PathBuf>,
+    /// Limite de threads des appels au binaire (`OMP_THREAD_LIMIT` de l'enfant).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_limit: Option<u32>,
+    /// Lecture pyramidale (`--pyramid`), si activée.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pyramid: Option<PyramidConfig>,
+    /// Avertissements sur la configuration (variables inconnues).
+    pub warnings: Vec<String>,
+}
+
+impl EffectiveConfig {
+    /// Résume une configuration OCR telle que le moteur l'applique.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Configuration OCR fusionnée
+    pub fn from_config(config: &OcrConfig) -> Self {
+        let mut tesseract_variables = BTreeMap::new();
+        tesseract_variables.insert("user_defined_dpi".to_string(), config.dpi.to_string());
+        tesseract_variables.extend(
+            config
+                .tesseract_variables
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+
+        let warnings = tesseract_variables
+            .keys()
+            .filter(|key| !is_known_tesseract_parameter(key))
+            .map(|key| unknown_variable_warning(key))
+            .collect();
+
+        Self {
+            language: config.language.clone(),
+            psm: config.page_seg_mode,
+            psm_value: config.page_seg_mode.to_tesseract_psm(),
+            dpi: config.dpi,
+            engine_mode: config.engine_mode,
+            oem_value: config.engine_mode.to_tesseract_oem(),
+            tesseract_variables,
+            user_words: config.user_words.clone(),
+            user_patterns: config.user_patterns.clone(),
+            config_names: config.config_names.clone(),
+            tessdata_dir: config.tessdata_dir.clone(),
+            thread_limit: config.thread_limit(),
+            pyramid: config.pyramid.clone(),
+            warnings,
+        }
+    }
+
+    /// Variables Tesseract sous la forme `cle=valeur;cle=valeur`, triées par nom.
+    pub fn variables_summary(&self) -> String {
+        self.tesseract_variables
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+/// Indique si `name` figure dans la liste des paramètres Tesseract connus.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::effective_config::is_known_tesseract_parameter;
+///
+/// assert!(is_known_tesseract_parameter("preserve_interword_spaces"));
+/// assert!(!is_known_tesseract_parameter("preserve_interword_space"));
+/// ```
+pub fn is_known_tesseract_parameter(name: &str) -> bool {
+    known_parameters().contains(name)
+}
+
+/// Paramètres connus, lus une fois depuis la liste embarquée.
+fn known_parameters() -> &'static BTreeSet<&'static str> {
+    static PARAMETERS: OnceLock<BTreeSet<&'static str>> = OnceLock::new();
+
+    PARAMETERS.get_or_init(|| {
+        TESSERACT_PARAMETERS
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect()
+    })
+}
+
+/// Message d'avertissement d'une variable inconnue, avec le paramètre connu le plus proche.
+fn unknown_variable_warning(key: &str) -> String {
+    let suggestion = known_parameters()
+        .iter()
+        .map(|name| (levenshtein_distance(key, name), *name))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min();
+
+    match suggestion {
+        Some((_, name)) => format!(
+            "Variable Tesseract inconnue '{}' (vouliez-vous dire '{}' ?)",
+            key, name
+        ),
+        None => format!("Variable Tesseract inconnue '{}'", key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameter_list_is_sorted_and_unique() {
+        let names: Vec<&str> = TESSERACT_PARAMETERS
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+
+        assert!(names.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(names.contains(&"tessedit_char_whitelist"));
+        assert!(names.contains(&"user_defined_dpi"));
+    }
+
+    #[test]
+    fn test_variables_sorted_with_dpi() {
+        let mut config = OcrConfig {
+            dpi: 200,
+            ..OcrConfig::default()
+        };
+        for (key, value) in [
+            ("tessedit_char_whitelist", "0123456789"),
+            ("preserve_interword_spaces", "1"),
+            ("load_system_dawg", "0"),
+        ] {
+            config
+                .tesseract_variables
+                .insert(key.to_string(), value.to_string());
+        }
+
+        let effective = EffectiveConfig::from_config(&config);
+
+        let keys: Vec<&str> = effective
+            .tesseract_variables
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                "load_system_dawg",
+                "preserve_interword_spaces",
+                "tessedit_char_whitelist",
+                "user_defined_dpi",
+            ]
+        );
+        assert_eq!(effective.tesseract_variables["user_defined_dpi"], "200");
+        assert_eq!(
+            effective.variables_summary(),
+            "load_system_dawg=0;preserve_interword_spaces=1;\
+             tessedit_char_whitelist=0123456789;user_defined_dpi=200"
+        );
+        assert!(effective.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_explicit_dpi_variable_overrides_dpi() {
+        let mut config = OcrConfig::default();
+        config
+            .tesseract_variables
+            .insert("user_defined_dpi".to_string(), "600".to_string());
+
+        let effective = EffectiveConfig::from_config(&config);
+
+        assert_eq!(effective.dpi, 300);
+        assert_eq!(effective.tesseract_variables["user_defined_dpi"], "600");
+    }
+
+    #[test]
+    fn test_unknown_variable_warning() {
+        let mut config = OcrConfig::default();
+        config
+            .tesseract_variables
+            .insert("tessedit_char_whitlist".to_string(), "0".to_string());
+        config
+            .tesseract_variables
+            .insert("zzz_not_a_parameter_at_all".to_string(), "1".to_string());
+
+        let effective = EffectiveConfig::from_config(&config);
+
+        assert_eq!(
+            effective.warnings,
+            [
+                "Variable Tesseract inconnue 'tessedit_char_whitlist' \
+                 (vouliez-vous dire 'tessedit_char_whitelist' ?)",
+                "Variable Tesseract inconnue 'zzz_not_a_parameter_at_all'",
+            ]
+        );
+        // La variable inconnue est tout de même transmise à Tesseract
+        assert!(
+            effective
+                .tesseract_variables
+                .contains_key("tessedit_char_whitlist")
+        );
+    }
+
+    #[test]
+    fn test_serialize_psm_and_oem() {
+        let effective = EffectiveConfig::from_config(&OcrConfig::default());
+        let json = serde_json::to_value(&effective).unwrap();
+
+        assert_eq!(json["psm"], "Auto");
+        assert_eq!(json["psm_value"], 3);
+        assert_eq!(json["engine_mode"], "Default");
+        assert_eq!(json["oem_value"], 3);
+        assert_eq!(json["tesseract_variables"]["user_defined_dpi"], "300");
+        assert!(json.get("pyramid").is_none());
+    }
+
+    #[test]
+    fn test_pyramid_is_reported() {
+        let config = OcrConfig {
+            pyramid: Some(PyramidConfig::with_levels(vec![0.5, 1.0])),
+            ..OcrConfig::default()
+        };
+
+        let effective = EffectiveConfig::from_config(&config);
+        let json = serde_json::to_value(&effective).unwrap();
+
+        assert_eq!(effective.pyramid, config.pyramid);
+        assert_eq!(json["pyramid"]["levels"], serde_json::json!([0.5, 1.0]));
+    }
+}
@@ -0,0 +1,359 @@
+//! Encodage et fins de ligne des fichiers texte produits.
+//!
+//! Le texte extrait est écrit par défaut en UTF-8 avec des fins de ligne
+//! `\n`. Certains outils Windows attendent un BOM, des fins de ligne
+//! `\r\n`, de l'UTF-16LE ou du Latin-1 : [`encode_text()`] convertit le
+//! texte en octets selon une [`OutputEncoding`] et un [`NewlineStyle`].
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::encoding::{NewlineStyle, OutputEncoding, encode_text};
+//!
+//! let bytes = encode_text("Été\n", OutputEncoding::Utf8Bom, NewlineStyle::CrLf);
+//! assert_eq!(bytes, b"\xEF\xBB\xBF\xC3\x89t\xC3\xA9\r\n");
+//! ```
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Caractère écrit à la place d'un caractère absent de Latin-1.
+pub const LATIN1_REPLACEMENT: u8 = b'?';
+
+/// Encodage d'un fichier texte produit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputEncoding {
+    /// UTF-8 sans BOM (défaut).
+    #[default]
+    Utf8,
+    /// UTF-8 précédé du BOM `EF BB BF`.
+    Utf8Bom,
+    /// UTF-16 petit-boutiste précédé du BOM `FF FE`, comme l'« Unicode » de
+    /// Windows.
+    Utf16Le,
+    /// ISO-8859-1 : les caractères absents de Latin-1 (`œ`, `€`...) sont
+    /// remplacés par [`LATIN1_REPLACEMENT`].
+    Latin1Lossy,
+}
+
+impl OutputEncoding {
+    /// Tous les encodages, dans l'ordre de la documentation.
+    pub const ALL: [OutputEncoding; 4] = [
+        OutputEncoding::Utf8,
+        OutputEncoding::Utf8Bom,
+        OutputEncoding::Utf16Le,
+        OutputEncoding::Latin1Lossy,
+    ];
+
+    /// Nom de l'encodage, tel qu'accepté par [`FromStr`].
+    pub fn name(self) -> &'static str {
+        match self {
+            OutputEncoding::Utf8 => "utf8",
+            OutputEncoding::Utf8Bom => "utf8-bom",
+            OutputEncoding::Utf16Le => "utf16le",
+            OutputEncoding::Latin1Lossy => "latin1-lossy",
+        }
+    }
+}
+
+impl fmt::Display for OutputEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for OutputEncoding {
+    type Err = anyhow::Error;
+
+    /// Accepte le nom de l'encodage, sans tenir compte de la casse.
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim().to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|encoding| encoding.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|encoding| encoding.name()).collect();
+                anyhow!(
+                    "Encodage inconnu : '{}'. Valeurs acceptées : {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Fins de ligne d'un fichier texte produit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NewlineStyle {
+    /// `\n` (défaut).
+    #[default]
+    Lf,
+    /// `\r\n`.
+    CrLf,
+    /// `\r\n` sous Windows, `\n` ailleurs.
+    Platform,
+}
+
+impl NewlineStyle {
+    /// Tous les styles, dans l'ordre de la documentation.
+    pub const ALL: [NewlineStyle; 3] =
+        [NewlineStyle::Lf, NewlineStyle::CrLf, NewlineStyle::Platform];
+
+    /// Nom du style, tel qu'accepté par [`FromStr`].
+    pub fn name(self) -> &'static str {
+        match self {
+            NewlineStyle::Lf => "lf",
+            NewlineStyle::CrLf => "crlf",
+            NewlineStyle::Platform => "platform",
+        }
+    }
+
+    /// Séquence de fin de ligne écrite, [`Platform`](Self::Platform) résolu.
+    pub fn sequence(self) -> &'static str {
+        match self {
+            NewlineStyle::Lf => "\n",
+            NewlineStyle::CrLf => "\r\n",
+            NewlineStyle::Platform if cfg!(windows) => "\r\n",
+            NewlineStyle::Platform => "\n",
+        }
+    }
+}
+
+impl fmt::Display for NewlineStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for NewlineStyle {
+    type Err = anyhow::Error;
+
+    /// Accepte le nom du style, sans tenir compte de la casse.
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim().to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|style| style.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|style| style.name()).collect();
+                anyhow!(
+                    "Style de fin de ligne inconnu : '{}'. Valeurs acceptées : {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Texte encodé, retourné par [`encode_text_detailed()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedText {
+    /// Octets à écrire, BOM compris.
+    pub bytes: Vec<u8>,
+    /// Nombre de caractères remplacés par [`LATIN1_REPLACEMENT`]
+    /// (toujours 0 hors [`OutputEncoding::Latin1Lossy`]).
+    pub substitutions: usize,
+}
+
+/// Convertit un texte en octets selon l'encodage et les fins de ligne demandés.
+///
+/// Raccourci de [`encode_text_detailed()`] qui ignore le nombre de
+/// substitutions.
+///
+/// # Arguments
+///
+/// * `text` - Texte à écrire
+/// * `encoding` - Encodage du fichier
+/// * `newline` - Fins de ligne du fichier
+pub fn encode_text(text: &str, encoding: OutputEncoding, newline: NewlineStyle) -> Vec<u8> {
+    encode_text_detailed(text, encoding, newline).bytes
+}
+
+/// Convertit un texte en octets et compte les caractères non représentables.
+///
+/// Chaque fin de ligne (`\n` ou `\r\n`) devient celle de `newline` : un
+/// `\r\n` déjà présent n'est pas doublé en `\r\r\n`. Pour les encodages
+/// avec BOM, un `U+FEFF` en tête du texte est retiré avant d'écrire le BOM :
+/// le fichier n'en contient jamais deux. Avec les défauts
+/// [`OutputEncoding::Utf8`] et [`NewlineStyle::Lf`], le texte est écrit
+/// octet pour octet, `\r\n` compris.
+///
+/// # Arguments
+///
+/// * `text` - Texte à écrire
+/// * `encoding` - Encodage du fichier
+/// * `newline` - Fins de ligne du fichier
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::encoding::{NewlineStyle, OutputEncoding, encode_text_detailed};
+///
+/// let encoded = encode_text_detailed("Cœur 5 €", OutputEncoding::Latin1Lossy, NewlineStyle::Lf);
+/// assert_eq!(encoded.bytes, b"C?ur 5 ?");
+/// assert_eq!(encoded.substitutions, 2);
+/// ```
+pub fn encode_text_detailed(
+    text: &str,
+    encoding: OutputEncoding,
+    newline: NewlineStyle,
+) -> EncodedText {
+    if encoding == OutputEncoding::Utf8 && newline == NewlineStyle::Lf {
+        return EncodedText {
+            bytes: text.as_bytes().to_vec(),
+            substitutions: 0,
+        };
+    }
+    let text = match encoding {
+        OutputEncoding::Utf8Bom | OutputEncoding::Utf16Le => {
+            text.strip_prefix('\u{FEFF}').unwrap_or(text)
+        }
+        OutputEncoding::Utf8 | OutputEncoding::Latin1Lossy => text,
+    };
+    let text = normalize_newlines(text, newline.sequence());
+    let mut substitutions = 0;
+    let bytes = match encoding {
+        OutputEncoding::Utf8 => text.into_bytes(),
+        OutputEncoding::Utf8Bom => {
+            let mut bytes = vec![0xEF, 0xBB, 0xBF];
+            bytes.extend_from_slice(text.as_bytes());
+            bytes
+        }
+        OutputEncoding::Utf16Le => {
+            let mut bytes = vec![0xFF, 0xFE];
+            bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            bytes
+        }
+        OutputEncoding::Latin1Lossy => text
+            .chars()
+            .map(|c| {
+                u8::try_from(u32::from(c)).unwrap_or_else(|_| {
+                    substitutions += 1;
+                    LATIN1_REPLACEMENT
+                })
+            })
+            .collect(),
+    };
+    EncodedText {
+        bytes,
+        substitutions,
+    }
+}
+
+/// Remplace chaque `\n` ou `\r\n` par `sequence` ; les `\r` isolés sont gardés.
+fn normalize_newlines(text: &str, sequence: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut lines = text.split('\n').peekable();
+    while let Some(line) = lines.next() {
+        if lines.peek().is_none() {
+            // Dernière portion : aucun \n ne suit, son \r final est isolé
+            result.push_str(line);
+        } else {
+            result.push_str(line.strip_suffix('\r').unwrap_or(line));
+            result.push_str(sequence);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Facture n° 42\nMontant : 12,50 €\n";
+
+    #[test]
+    fn test_default_output_unchanged() {
+        assert_eq!(
+            encode_text(TEXT, OutputEncoding::default(), NewlineStyle::default()),
+            TEXT.as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_utf8_bom_written_once() {
+        let bom = [0xEF, 0xBB, 0xBF];
+        for text in [TEXT.to_string(), format!("\u{FEFF}{}", TEXT)] {
+            let bytes = encode_text(&text, OutputEncoding::Utf8Bom, NewlineStyle::Lf);
+            assert_eq!(bytes[..3], bom);
+            assert_eq!(&bytes[3..], TEXT.as_bytes());
+            assert_eq!(bytes.windows(3).filter(|w| *w == bom).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_crlf_does_not_double_convert() {
+        let bytes = encode_text(
+            "a\r\nb\nc\r\n\r\n",
+            OutputEncoding::Utf8,
+            NewlineStyle::CrLf,
+        );
+        assert_eq!(bytes, b"a\r\nb\r\nc\r\n\r\n");
+        // Et dans l'autre sens, les \r\n redeviennent des \n
+        let bytes = encode_text("a\r\nb\n", OutputEncoding::Latin1Lossy, NewlineStyle::Lf);
+        assert_eq!(bytes, b"a\nb\n");
+    }
+
+    #[test]
+    fn test_defaults_keep_crlf() {
+        let text = "a\r\nb\n\u{FEFF}";
+        let encoded = encode_text_detailed(text, OutputEncoding::Utf8, NewlineStyle::Lf);
+        assert_eq!(encoded.bytes, text.as_bytes());
+        assert_eq!(encoded.substitutions, 0);
+    }
+
+    #[test]
+    fn test_lone_carriage_returns_kept() {
+        let bytes = encode_text("a\rb\r", OutputEncoding::Utf8, NewlineStyle::CrLf);
+        assert_eq!(bytes, b"a\rb\r");
+    }
+
+    #[test]
+    fn test_utf16le_round_trips_french_accents() {
+        let text = "Élève à l'école : « où ? » çà et là, cœur\n";
+        let bytes = encode_text(text, OutputEncoding::Utf16Le, NewlineStyle::Lf);
+        assert_eq!(bytes[..2], [0xFF, 0xFE]);
+        let units: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        assert_eq!(String::from_utf16(&units).unwrap(), text);
+    }
+
+    #[test]
+    fn test_latin1_substitution_count() {
+        let encoded = encode_text_detailed(
+            "Œuvre : 3 € le bœuf, déjà payé\n",
+            OutputEncoding::Latin1Lossy,
+            NewlineStyle::CrLf,
+        );
+        assert_eq!(encoded.substitutions, 3);
+        assert_eq!(
+            encoded.bytes,
+            b"?uvre : 3 ? le b?uf, d\xE9j\xE0 pay\xE9\r\n"
+        );
+        assert_eq!(
+            encode_text_detailed("é", OutputEncoding::Utf8, NewlineStyle::Lf).substitutions,
+            0
+        );
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        for encoding in OutputEncoding::ALL {
+            assert_eq!(encoding.name().parse::<OutputEncoding>().unwrap(), encoding);
+        }
+        for style in NewlineStyle::ALL {
+            assert_eq!(style.to_string().parse::<NewlineStyle>().unwrap(), style);
+        }
+        assert_eq!(
+            "UTF8-BOM".parse::<OutputEncoding>().unwrap(),
+            OutputEncoding::Utf8Bom
+        );
+        assert!("cp1252".parse::<OutputEncoding>().is_err());
+    }
+}
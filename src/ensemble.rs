@@ -0,0 +1,557 @@
+//! Fusion de plusieurs exécutions OCR d'une même image (mode ensemble).
+//!
+//! Deux configurations (par exemple PSM 3 et PSM 6) se trompent rarement sur
+//! les mêmes mots : choisir mot par mot le meilleur résultat donne souvent
+//! un texte plus juste que chacune d'elles. Les mots des différentes
+//! exécutions sont appariés par recouvrement de leurs bounding boxes
+//! ([`align_words_by_overlap()`]), puis chaque groupe de mots appariés est
+//! résolu selon une [`MergeStrategy`].
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! # #[cfg(feature = "tesseract")]
+//! # {
+//! use text_recognition::config::{OcrConfig, PageSegMode};
+//! use text_recognition::ensemble::MergeStrategy;
+//! use text_recognition::ocr::ensemble_extract;
+//! use std::path::Path;
+//!
+//! let configs = [
+//!     OcrConfig { page_seg_mode: PageSegMode::Auto, ..OcrConfig::default() },
+//!     OcrConfig { page_seg_mode: PageSegMode::SingleBlock, ..OcrConfig::default() },
+//! ];
+//! let result = ensemble_extract(&configs, Path::new("facture.png"), MergeStrategy::HighestConfidence)?;
+//!
+//! println!("{}", result.text);
+//! println!("Mots retenus par exécution : {:?}", result.words_per_run);
+//! # }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::hocr::{BBox, HocrDocument, HocrWord};
+use anyhow::Result;
+use std::cmp::Reverse;
+
+/// Recouvrement minimal (intersection sur union) pour apparier deux mots.
+pub const MIN_WORD_IOU: f64 = 0.5;
+
+/// Confiance minimale (exclue) d'un mot présent dans une seule exécution
+/// pour qu'il soit conservé.
+pub const UNMATCHED_MIN_CONFIDENCE: u8 = 80;
+
+/// Stratégie de choix du texte parmi des mots appariés entre exécutions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Retenir le mot de plus haute confiance (à égalité, celui de la
+    /// première exécution).
+    HighestConfidence,
+    /// Retenir l'orthographe la plus fréquente ; nécessite au moins trois
+    /// exécutions.
+    ///
+    /// À égalité de voix, l'orthographe dont la meilleure confiance est la
+    /// plus haute l'emporte, puis celle apparue dans la première exécution.
+    MajorityVote,
+}
+
+/// Texte fusionné et provenance des mots retenus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnsembleResult {
+    /// Texte fusionné : mots séparés par une espace, lignes par un retour à
+    /// la ligne et paragraphes par une ligne vide.
+    pub text: String,
+    /// Nombre de mots du texte fusionné provenant de chaque exécution,
+    /// dans l'ordre des exécutions.
+    pub words_per_run: Vec<usize>,
+}
+
+/// Mot d'une exécution, avec sa position dans la structure du document.
+#[derive(Debug, Clone, Copy)]
+struct LocatedWord<'a> {
+    /// Index de l'exécution.
+    run: usize,
+    /// Index du paragraphe dans le document de l'exécution.
+    paragraph: usize,
+    /// Index de la ligne dans le paragraphe.
+    line: usize,
+    word: &'a HocrWord,
+}
+
+/// Groupe de mots appariés entre exécutions (au plus un par exécution).
+#[derive(Debug, Clone)]
+struct Cluster<'a> {
+    /// Mots du groupe, dans l'ordre des exécutions. Le premier sert de
+    /// référence pour l'appariement avec les exécutions suivantes.
+    members: Vec<LocatedWord<'a>>,
+    /// Ligne du texte fusionné : (exécution, paragraphe, ligne).
+    line_key: (usize, usize, usize),
+}
+
+/// Apparie deux listes de mots par recouvrement de leurs bounding boxes.
+///
+/// Deux mots sont appariés si l'intersection sur union (IoU) de leurs
+/// rectangles dépasse [`MIN_WORD_IOU`] ; les paires de plus fort
+/// recouvrement sont retenues en premier et chaque mot est apparié au plus
+/// une fois.
+///
+/// # Arguments
+///
+/// * `a` - Mots de la première exécution, dans l'ordre de lecture
+/// * `b` - Mots de la seconde exécution, dans l'ordre de lecture
+///
+/// # Retour
+///
+/// Une paire d'index par mot : `(Some(i), Some(j))` pour deux mots appariés,
+/// `(Some(i), None)` ou `(None, Some(j))` pour un mot présent d'un seul
+/// côté. Les paires suivent l'ordre de `a` et de `b` ; entre deux paires
+/// appariées, les mots présents d'un seul côté sont intercalés selon leur
+/// position (ligne, puis abscisse).
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::ensemble::align_words_by_overlap;
+/// use text_recognition::hocr::{BBox, HocrWord};
+///
+/// let word = |x, text: &str| HocrWord::new(BBox::new(x, 0, 40, 20), text.to_string(), None);
+/// let a = [word(0, "Total"), word(100, "TTC")];
+/// let b = [word(2, "Tota1"), word(50, ":"), word(101, "TTC")];
+///
+/// assert_eq!(
+///     align_words_by_overlap(&a, &b),
+///     vec![(Some(0), Some(0)), (None, Some(1)), (Some(1), Some(2))]
+/// );
+/// ```
+pub fn align_words_by_overlap(
+    a: &[HocrWord],
+    b: &[HocrWord],
+) -> Vec<(Option<usize>, Option<usize>)> {
+    // Paires candidates, de la plus forte à la plus faible (puis par index)
+    let mut candidates: Vec<(f64, usize, usize)> = Vec::new();
+    for (i, word_a) in a.iter().enumerate() {
+        for (j, word_b) in b.iter().enumerate() {
+            let overlap = word_a.bbox.iou(&word_b.bbox);
+            if overlap > MIN_WORD_IOU {
+                candidates.push((overlap, i, j));
+            }
+        }
+    }
+    candidates.sort_by(|x, y| y.0.total_cmp(&x.0).then((x.1, x.2).cmp(&(y.1, y.2))));
+
+    let mut match_of_a: Vec<Option<usize>> = vec![None; a.len()];
+    let mut matched_b = vec![false; b.len()];
+    for (_, i, j) in candidates {
+        if match_of_a[i].is_none() && !matched_b[j] {
+            match_of_a[i] = Some(j);
+            matched_b[j] = true;
+        }
+    }
+
+    // Parcourir `a` ; entre deux paires appariées, intercaler les mots non
+    // appariés des deux côtés selon leur position
+    let mut pairs = Vec::with_capacity(a.len() + b.len());
+    let mut pending_a: Vec<usize> = Vec::new();
+    let mut next_b = 0;
+    for (i, matched) in match_of_a.iter().enumerate() {
+        match *matched {
+            Some(j) => {
+                let gap_b: Vec<usize> = (next_b..j).filter(|&k| !matched_b[k]).collect();
+                interleave_unmatched(a, b, &pending_a, &gap_b, &mut pairs);
+                pending_a.clear();
+                pairs.push((Some(i), Some(j)));
+                next_b = next_b.max(j + 1);
+            }
+            None => pending_a.push(i),
+        }
+    }
+    let rest_b: Vec<usize> = (next_b..b.len()).filter(|&k| !matched_b[k]).collect();
+    interleave_unmatched(a, b, &pending_a, &rest_b, &mut pairs);
+
+    pairs
+}
+
+/// Ajoute des mots non appariés de `a` et de `b` dans l'ordre de lecture.
+///
+/// Chaque liste garde son ordre ; un mot de `a` passe avant un mot de `b`
+/// s'il est à sa gauche sur la même ligne visuelle, ou au-dessus.
+fn interleave_unmatched(
+    a: &[HocrWord],
+    b: &[HocrWord],
+    unmatched_a: &[usize],
+    unmatched_b: &[usize],
+    pairs: &mut Vec<(Option<usize>, Option<usize>)>,
+) {
+    let (mut ia, mut ib) = (0, 0);
+    while ia < unmatched_a.len() || ib < unmatched_b.len() {
+        let take_a = match (unmatched_a.get(ia), unmatched_b.get(ib)) {
+            (Some(&i), Some(&j)) => {
+                let (box_a, box_b) = (&a[i].bbox, &b[j].bbox);
+                if same_text_line(box_a, box_b) {
+                    box_a.x <= box_b.x
+                } else {
+                    box_a.y <= box_b.y
+                }
+            }
+            (Some(_), None) => true,
+            _ => false,
+        };
+
+        if take_a {
+            pairs.push((Some(unmatched_a[ia]), None));
+            ia += 1;
+        } else {
+            pairs.push((None, Some(unmatched_b[ib])));
+            ib += 1;
+        }
+    }
+}
+
+/// Fusionne les documents HOCR de plusieurs exécutions OCR d'une même image.
+///
+/// Les mots de chaque exécution sont appariés à ceux des exécutions
+/// précédentes ([`align_words_by_overlap()`]), puis chaque groupe est
+/// résolu selon `strategy`. Un mot présent dans une seule exécution n'est
+/// conservé que si sa confiance dépasse [`UNMATCHED_MIN_CONFIDENCE`] (sauf
+/// s'il n'y a qu'une exécution).
+///
+/// # Arguments
+///
+/// * `docs` - Documents HOCR, un par exécution
+/// * `strategy` - Stratégie de choix parmi les mots appariés
+///
+/// # Erreurs
+///
+/// Retourne une erreur si `docs` est vide, ou si
+/// [`MergeStrategy::MajorityVote`] est demandée avec moins de trois
+/// exécutions.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::ensemble::{MergeStrategy, merge_documents};
+/// use text_recognition::hocr::HocrDocument;
+///
+/// let run = |text: &str, conf: u8| {
+///     HocrDocument::from_hocr_string(&format!(
+///         "<p class='ocr_par' title=\"bbox 0 0 40 20\">\n\
+///          <span class='ocr_line' title=\"bbox 0 0 40 20\">\n\
+///          <span class='ocrx_word' title='bbox 0 0 40 20; x_wconf {conf}'>{text}</span>\n\
+///          </span>\n</p>"
+///     ))
+/// };
+/// let docs = [run("Tota1", 62)?, run("Total", 91)?];
+///
+/// let result = merge_documents(&docs, MergeStrategy::HighestConfidence)?;
+/// assert_eq!(result.text, "Total");
+/// assert_eq!(result.words_per_run, vec![0, 1]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn merge_documents(docs: &[HocrDocument], strategy: MergeStrategy) -> Result<EnsembleResult> {
+    check_run_count(docs.len(), strategy)?;
+
+    let runs: Vec<Vec<LocatedWord>> = docs
+        .iter()
+        .enumerate()
+        .map(|(run, doc)| located_words(run, doc))
+        .collect();
+
+    // Groupes initiaux : les mots de la première exécution
+    let mut clusters: Vec<Cluster> = runs[0]
+        .iter()
+        .map(|&word| Cluster {
+            members: vec![word],
+            line_key: (word.run, word.paragraph, word.line),
+        })
+        .collect();
+
+    for run_words in &runs[1..] {
+        let references: Vec<HocrWord> = clusters
+            .iter()
+            .map(|cluster| cluster.members[0].word.clone())
+            .collect();
+        let words: Vec<HocrWord> = run_words.iter().map(|w| w.word.clone()).collect();
+
+        let mut previous: Vec<Option<Cluster>> = clusters.into_iter().map(Some).collect();
+        clusters = Vec::with_capacity(previous.len());
+
+        for pair in align_words_by_overlap(&references, &words) {
+            match pair {
+                (Some(c), Some(w)) => {
+                    if let Some(mut cluster) = previous[c].take() {
+                        cluster.members.push(run_words[w]);
+                        clusters.push(cluster);
+                    }
+                }
+                (Some(c), None) => clusters.extend(previous[c].take()),
+                (None, Some(w)) => {
+                    let word = run_words[w];
+                    // Un mot inséré sur la même ligne visuelle que son voisin rejoint sa ligne
+                    let line_key = match clusters.last() {
+                        Some(last)
+                            if same_text_line(&last.members[0].word.bbox, &word.word.bbox) =>
+                        {
+                            last.line_key
+                        }
+                        _ => (word.run, word.paragraph, word.line),
+                    };
+                    clusters.push(Cluster {
+                        members: vec![word],
+                        line_key,
+                    });
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    let mut text = String::new();
+    let mut words_per_run = vec![0; docs.len()];
+    let mut previous_key: Option<(usize, usize, usize)> = None;
+
+    for cluster in &clusters {
+        let Some(chosen) = choose_word(cluster, strategy, docs.len()) else {
+            continue;
+        };
+
+        if let Some(key) = previous_key {
+            text.push_str(if key == cluster.line_key {
+                " "
+            } else if (key.0, key.1) == (cluster.line_key.0, cluster.line_key.1) {
+                "\n"
+            } else {
+                "\n\n"
+            });
+        }
+        text.push_str(&chosen.word.text);
+        words_per_run[chosen.run] += 1;
+        previous_key = Some(cluster.line_key);
+    }
+
+    Ok(EnsembleResult {
+        text,
+        words_per_run,
+    })
+}
+
+/// Vérifie que le nombre d'exécutions convient à la stratégie.
+pub(crate) fn check_run_count(runs: usize, strategy: MergeStrategy) -> Result<()> {
+    if runs == 0 {
+        anyhow::bail!("Le mode ensemble nécessite au moins une configuration OCR");
+    }
+    if strategy == MergeStrategy::MajorityVote && runs < 3 {
+        anyhow::bail!(
+            "Le vote majoritaire nécessite au moins 3 exécutions ({} fournie(s))",
+            runs
+        );
+    }
+    Ok(())
+}
+
+/// Liste les mots non vides d'un document dans l'ordre de lecture.
+fn located_words(run: usize, doc: &HocrDocument) -> Vec<LocatedWord<'_>> {
+    doc.paragraphs()
+        .enumerate()
+        .flat_map(|(paragraph_idx, paragraph)| {
+            paragraph
+                .lines
+                .iter()
+                .enumerate()
+                .flat_map(move |(line_idx, line)| {
+                    line.words
+                        .iter()
+                        .filter(|word| !word.text.is_empty())
+                        .map(move |word| LocatedWord {
+                            run,
+                            paragraph: paragraph_idx,
+                            line: line_idx,
+                            word,
+                        })
+                })
+        })
+        .collect()
+}
+
+/// Choisit le mot retenu d'un groupe, ou `None` si le groupe est écarté.
+fn choose_word<'a>(
+    cluster: &Cluster<'a>,
+    strategy: MergeStrategy,
+    runs: usize,
+) -> Option<LocatedWord<'a>> {
+    let confidence = |word: &LocatedWord| word.word.confidence.unwrap_or(0);
+
+    // Mot vu par une seule exécution : ne le garder que s'il est sûr
+    if runs > 1 && cluster.members.len() == 1 {
+        let word = cluster.members[0];
+        return (confidence(&word) > UNMATCHED_MIN_CONFIDENCE).then_some(word);
+    }
+
+    match strategy {
+        // Les membres sont dans l'ordre des exécutions : `>` garde le premier à égalité
+        MergeStrategy::HighestConfidence => cluster.members.iter().copied().reduce(|best, word| {
+            if confidence(&word) > confidence(&best) {
+                word
+            } else {
+                best
+            }
+        }),
+        MergeStrategy::MajorityVote => cluster
+            .members
+            .iter()
+            .enumerate()
+            .max_by_key(|(first_idx, word)| {
+                let voters = cluster
+                    .members
+                    .iter()
+                    .filter(|other| other.word.text == word.word.text);
+                let votes = voters.clone().count();
+                let best_confidence = voters.map(confidence).max().unwrap_or(0);
+                // Premier membre de cette orthographe : départage par ordre d'exécution
+                (votes, best_confidence, Reverse(*first_idx))
+            })
+            .map(|(_, word)| *word),
+    }
+}
+
+/// Indique si deux mots se recouvrent verticalement (même ligne visuelle).
+fn same_text_line(a: &BBox, b: &BBox) -> bool {
+    a.y < b.y + b.height && b.y < a.y + a.height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hocr::{HocrLine, HocrParagraph};
+
+    fn word(x: u32, y: u32, text: &str, confidence: u8) -> HocrWord {
+        HocrWord::new(BBox::new(x, y, 40, 20), text.to_string(), Some(confidence))
+    }
+
+    /// Document d'un paragraphe, une ligne par liste de mots.
+    fn document(lines: Vec<Vec<HocrWord>>) -> HocrDocument {
+        let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 500, 100));
+        for words in lines {
+            let y = words.first().map_or(0, |w| w.bbox.y);
+            let mut line = HocrLine::new(BBox::new(0, y, 500, 20));
+            for w in words {
+                line.add_word(w);
+            }
+            paragraph.add_line(line);
+        }
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph);
+        doc
+    }
+
+    #[test]
+    fn test_align_words_by_overlap() {
+        let a = [
+            word(0, 0, "Le", 90),
+            word(50, 0, "montant", 90),
+            word(150, 0, "dû", 90),
+        ];
+        // "montant" décalé de 10 px (IoU 0.6), "dû" décalé de 25 px (IoU 0.23),
+        // un mot supplémentaire entre les deux
+        let b = [
+            word(1, 0, "Le", 90),
+            word(60, 0, "montant", 90),
+            word(110, 0, ":", 90),
+            word(175, 0, "du", 90),
+        ];
+
+        assert_eq!(
+            align_words_by_overlap(&a, &b),
+            vec![
+                (Some(0), Some(0)),
+                (Some(1), Some(1)),
+                (None, Some(2)),
+                (Some(2), None),
+                (None, Some(3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_align_words_best_overlap_wins_and_empty_sides() {
+        let a = [word(0, 0, "a", 90)];
+        // Les deux recouvrent `a` au-delà du seuil, le second davantage
+        let b = [word(8, 0, "b", 90), word(2, 0, "c", 90)];
+        assert_eq!(
+            align_words_by_overlap(&a, &b),
+            vec![(None, Some(0)), (Some(0), Some(1))]
+        );
+
+        assert_eq!(align_words_by_overlap(&a, &[]), vec![(Some(0), None)]);
+        assert_eq!(align_words_by_overlap(&[], &a), vec![(None, Some(0))]);
+    }
+
+    #[test]
+    fn test_merge_prefers_higher_confidence() {
+        let docs = [
+            document(vec![
+                vec![word(0, 0, "Facture", 95), word(50, 0, "n°", 70)],
+                vec![word(0, 30, "Tota1", 60), word(50, 30, "12,50", 92)],
+            ]),
+            document(vec![
+                vec![word(0, 0, "Facturc", 80), word(50, 0, "n°", 88)],
+                vec![word(1, 30, "Total", 91), word(50, 30, "12.50", 92)],
+            ]),
+        ];
+
+        let result = merge_documents(&docs, MergeStrategy::HighestConfidence).unwrap();
+
+        assert_eq!(result.text, "Facture n°\nTotal 12,50");
+        // À égalité (12,50 / 12.50), la première exécution l'emporte
+        assert_eq!(result.words_per_run, vec![2, 2]);
+    }
+
+    #[test]
+    fn test_merge_unmatched_words_need_confidence() {
+        let docs = [
+            document(vec![vec![word(0, 0, "Net", 90), word(100, 0, "€", 81)]]),
+            document(vec![vec![
+                word(0, 0, "Net", 90),
+                word(50, 0, "à", 85),
+                word(200, 0, "~", 30),
+            ]]),
+        ];
+
+        let result = merge_documents(&docs, MergeStrategy::HighestConfidence).unwrap();
+
+        assert_eq!(result.text, "Net à €");
+        assert_eq!(result.words_per_run, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_majority_vote() {
+        let run = |text: &str, confidence: u8| document(vec![vec![word(0, 0, text, confidence)]]);
+
+        // Deux voix contre une, malgré une confiance plus faible
+        let docs = [run("Total", 60), run("Tota1", 95), run("Total", 70)];
+        let result = merge_documents(&docs, MergeStrategy::MajorityVote).unwrap();
+        assert_eq!(result.text, "Total");
+        assert_eq!(result.words_per_run, vec![1, 0, 0]);
+
+        // Égalité de voix : la meilleure confiance, puis la première exécution
+        let docs = [run("O", 80), run("0", 90), run("o", 85)];
+        let result = merge_documents(&docs, MergeStrategy::MajorityVote).unwrap();
+        assert_eq!(result.text, "0");
+
+        let docs = [run("O", 80), run("0", 80), run("o", 80)];
+        let result = merge_documents(&docs, MergeStrategy::MajorityVote).unwrap();
+        assert_eq!(result.text, "O");
+        assert_eq!(result.words_per_run, vec![1, 0, 0]);
+    }
+
+    #[test]
+    fn test_run_count_errors() {
+        let doc = document(vec![vec![word(0, 0, "a", 90)]]);
+
+        assert!(merge_documents(&[], MergeStrategy::HighestConfidence).is_err());
+        assert!(merge_documents(&[doc.clone(), doc.clone()], MergeStrategy::MajorityVote).is_err());
+
+        // Une seule exécution : tous les mots sont conservés
+        let single = document(vec![vec![word(0, 0, "peu", 10), word(50, 0, "sûr", 20)]]);
+        let result = merge_documents(&[single], MergeStrategy::HighestConfidence).unwrap();
+        assert_eq!(result.text, "peu sûr");
+    }
+}
@@ -0,0 +1,246 @@
+//! Estimation de la durée et du volume d'un traitement batch.
+//!
+//! Avant de lancer un lot de plusieurs milliers d'images, le mode
+//! `--batch --dry-run` analyse un petit échantillon tiré au hasard et
+//! extrapole les mesures à l'ensemble du lot. Les durées d'OCR varient
+//! fortement d'une image à l'autre (page blanche, page très dense) : la
+//! médiane de l'échantillon sert d'estimation, encadrée par le minimum et le
+//! maximum observés.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::estimate::extrapolate;
+//! use std::time::Duration;
+//!
+//! let timings = [2, 3, 40].map(Duration::from_secs);
+//! let estimate = extrapolate(&timings, 1000, 4);
+//!
+//! // Médiane : 3 s par image, 250 images par worker
+//! assert_eq!(estimate.wall_time, Duration::from_secs(750));
+//! assert_eq!(estimate.min_wall_time, Duration::from_secs(500));
+//! assert_eq!(estimate.max_wall_time, Duration::from_secs(10_000));
+//! ```
+
+use crate::rng::SplitMix64;
+use std::time::Duration;
+
+/// Extrapolation des durées mesurées sur un échantillon à un lot complet.
+///
+/// Retourné par [`extrapolate()`]. Toutes les durées valent zéro lorsque
+/// l'échantillon ou le lot est vide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Estimate {
+    /// Nombre d'images mesurées.
+    pub sample_size: usize,
+    /// Nombre d'images du lot.
+    pub total_count: usize,
+    /// Nombre de workers réellement occupés (jamais plus que d'images).
+    pub parallelism: usize,
+    /// Durée médiane d'une image.
+    pub median_per_image: Duration,
+    /// Durée de l'image la plus rapide de l'échantillon.
+    pub min_per_image: Duration,
+    /// Durée de l'image la plus lente de l'échantillon.
+    pub max_per_image: Duration,
+    /// Durée totale estimée (d'après la médiane).
+    pub wall_time: Duration,
+    /// Durée totale si toutes les images étaient aussi rapides que la plus rapide.
+    pub min_wall_time: Duration,
+    /// Durée totale si toutes les images étaient aussi lentes que la plus lente.
+    pub max_wall_time: Duration,
+}
+
+/// Extrapole les durées d'un échantillon à un lot traité en parallèle.
+///
+/// Chaque worker traite `ceil(total_count / parallelism)` images ; la durée
+/// totale est ce nombre multiplié par la durée médiane (minimale, maximale)
+/// d'une image.
+///
+/// # Arguments
+///
+/// * `sample_timings` - Durée d'OCR de chaque image de l'échantillon
+/// * `total_count` - Nombre d'images du lot complet
+/// * `parallelism` - Nombre de workers (`--jobs`, 0 est traité comme 1)
+pub fn extrapolate(
+    sample_timings: &[Duration],
+    total_count: usize,
+    parallelism: usize,
+) -> Estimate {
+    let parallelism = parallelism.clamp(1, total_count.max(1));
+    let mut estimate = Estimate {
+        sample_size: sample_timings.len(),
+        total_count,
+        parallelism,
+        ..Estimate::default()
+    };
+    let (Some(&min), Some(&max)) = (sample_timings.iter().min(), sample_timings.iter().max())
+    else {
+        return estimate;
+    };
+
+    let mut sorted = sample_timings.to_vec();
+    sorted.sort_unstable();
+    let mid = sorted.len() / 2;
+    let median = if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2
+    } else {
+        sorted[mid]
+    };
+
+    let images_per_worker = total_count.div_ceil(parallelism) as u32;
+    estimate.median_per_image = median;
+    estimate.min_per_image = min;
+    estimate.max_per_image = max;
+    estimate.wall_time = median * images_per_worker;
+    estimate.min_wall_time = min * images_per_worker;
+    estimate.max_wall_time = max * images_per_worker;
+    estimate
+}
+
+/// Estime le volume total des sorties à partir de celles d'un échantillon.
+///
+/// # Arguments
+///
+/// * `sample_bytes` - Taille en octets du texte extrait pour chaque image de l'échantillon
+/// * `total_count` - Nombre d'images du lot complet
+///
+/// # Retour
+///
+/// La taille médiane multipliée par `total_count`, ou 0 si l'échantillon est vide
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::estimate::estimate_total_bytes;
+///
+/// assert_eq!(estimate_total_bytes(&[1200, 1500, 90_000], 100), 150_000);
+/// ```
+pub fn estimate_total_bytes(sample_bytes: &[u64], total_count: usize) -> u64 {
+    let mut sorted = sample_bytes.to_vec();
+    sorted.sort_unstable();
+    let Some(&median) = sorted.get(sorted.len() / 2) else {
+        return 0;
+    };
+    median * total_count as u64
+}
+
+/// Tire `sample_size` indices distincts parmi `0..count`, triés par ordre croissant.
+///
+/// Le tirage est pseudo-aléatoire et reproductible : une même graine donne
+/// le même échantillon. Si `sample_size >= count`, tous les indices sont
+/// retournés.
+///
+/// # Arguments
+///
+/// * `count` - Nombre d'éléments parmi lesquels tirer
+/// * `sample_size` - Nombre d'indices à tirer
+/// * `seed` - Graine du générateur
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::estimate::sample_indices;
+///
+/// let sample = sample_indices(30_000, 10, 42);
+/// assert_eq!(sample.len(), 10);
+/// assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+/// assert_eq!(sample, sample_indices(30_000, 10, 42));
+/// ```
+pub fn sample_indices(count: usize, sample_size: usize, seed: u64) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..count).collect();
+    if sample_size < count {
+        // Mélange de Fisher-Yates partiel : seuls les premiers éléments sont tirés
+        let mut rng = SplitMix64::new(seed);
+        for i in 0..sample_size {
+            let j = i + (rng.next_u64() % (count - i) as u64) as usize;
+            indices.swap(i, j);
+        }
+        indices.truncate(sample_size);
+        indices.sort_unstable();
+    }
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_secs).collect()
+    }
+
+    #[test]
+    fn test_extrapolate_empty_sample() {
+        let estimate = extrapolate(&[], 500, 4);
+
+        assert_eq!(estimate.sample_size, 0);
+        assert_eq!(estimate.total_count, 500);
+        assert_eq!(estimate.wall_time, Duration::ZERO);
+        assert_eq!(estimate.max_wall_time, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_extrapolate_single_file() {
+        let estimate = extrapolate(&secs(&[7]), 1, 1);
+
+        assert_eq!(estimate.median_per_image, Duration::from_secs(7));
+        assert_eq!(estimate.wall_time, Duration::from_secs(7));
+        assert_eq!(estimate.min_wall_time, estimate.max_wall_time);
+    }
+
+    #[test]
+    fn test_extrapolate_parallelism_above_file_count() {
+        // 16 workers pour 3 images : une image par worker occupé
+        let estimate = extrapolate(&secs(&[4, 6]), 3, 16);
+
+        assert_eq!(estimate.parallelism, 3);
+        assert_eq!(estimate.median_per_image, Duration::from_secs(5));
+        assert_eq!(estimate.wall_time, Duration::from_secs(5));
+
+        // Parallélisme nul : un seul worker
+        assert_eq!(
+            extrapolate(&secs(&[4]), 3, 0).wall_time,
+            Duration::from_secs(12)
+        );
+        // Lot vide
+        let empty = extrapolate(&secs(&[4]), 0, 8);
+        assert_eq!((empty.parallelism, empty.wall_time), (1, Duration::ZERO));
+    }
+
+    #[test]
+    fn test_extrapolate_uses_median_with_range() {
+        // Une image pathologique ne fausse pas l'estimation centrale
+        let estimate = extrapolate(&secs(&[2, 3, 3, 4, 300]), 10, 2);
+
+        assert_eq!(estimate.median_per_image, Duration::from_secs(3));
+        assert_eq!(estimate.wall_time, Duration::from_secs(15));
+        assert_eq!(estimate.min_wall_time, Duration::from_secs(10));
+        assert_eq!(estimate.max_wall_time, Duration::from_secs(1500));
+
+        // 11 images sur 2 workers : le plus chargé en traite 6
+        assert_eq!(
+            extrapolate(&secs(&[3]), 11, 2).wall_time,
+            Duration::from_secs(18)
+        );
+    }
+
+    #[test]
+    fn test_estimate_total_bytes() {
+        assert_eq!(estimate_total_bytes(&[], 100), 0);
+        assert_eq!(estimate_total_bytes(&[2000], 3), 6000);
+    }
+
+    #[test]
+    fn test_sample_indices() {
+        let sample = sample_indices(100, 10, 7);
+        assert_eq!(sample.len(), 10);
+        assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(sample.iter().all(|&index| index < 100));
+        assert_ne!(sample, sample_indices(100, 10, 8));
+
+        assert_eq!(sample_indices(3, 10, 7), [0, 1, 2]);
+        assert!(sample_indices(0, 10, 7).is_empty());
+        assert!(sample_indices(100, 0, 7).is_empty());
+    }
+}
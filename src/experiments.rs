@@ -0,0 +1,570 @@
+//! Registre des expériences : une ligne par exécution d'un lot annoté.
+//!
+//! En ajustant le prétraitement, on perd vite la trace de la configuration
+//! qui a donné tel CER la semaine précédente. L'[`ExperimentLedger`] ajoute
+//! à un fichier JSON Lines un [`RunRecord`] par exécution : horodatage,
+//! libellé choisi par l'utilisateur, configurations OCR et de prétraitement
+//! (JSON complet et empreinte SHA-256), identifiant du jeu d'images, CER et
+//! WER moyens et durée totale. Le fichier n'est jamais réécrit : les
+//! exécutions s'ajoutent à la fin.
+//!
+//! Comme le [journal de reprise](crate::checkpoint), le registre est un
+//! [`AppendLog`](crate::jsonl::AppendLog) : une dernière ligne tronquée
+//! (arrêt pendant l'écriture) est ignorée au chargement, puis retirée du
+//! fichier avant le prochain ajout.
+//!
+//! # Exemple
+//!
+//! ```
+//! use std::time::Duration;
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::experiments::{ExperimentLedger, ExperimentMetric, RunMetrics, RunRecord};
+//!
+//! let dir = tempfile::tempdir()?;
+//! let mut ledger = ExperimentLedger::open(&dir.path().join("experiences.jsonl"))?;
+//!
+//! let metrics = RunMetrics { mean_cer: 0.042, mean_wer: 0.12, count: 20 };
+//! let config = OcrConfig::default();
+//! ledger.record_run(RunRecord::new("base", "factures", &config, None, metrics, Duration::from_secs(31))?)?;
+//!
+//! let best = ledger.best_runs(ExperimentMetric::Cer, 1);
+//! assert_eq!(best[0].label, "base");
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::config::OcrConfig;
+use crate::jsonl::{AppendLog, LoadedLog, parse_log};
+use crate::metrics::OcrMetrics;
+use crate::preprocessing::PreprocessingConfig;
+use crate::run_metadata::sha256_hex;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Nom du fichier registre dans les messages d'erreur.
+const LEDGER_NAME: &str = "registre";
+
+/// Métrique de comparaison des exécutions ; la plus petite valeur est la meilleure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExperimentMetric {
+    /// CER moyen du lot.
+    Cer,
+    /// WER moyen du lot.
+    Wer,
+    /// Durée totale de l'exécution.
+    Duration,
+}
+
+impl ExperimentMetric {
+    /// Toutes les métriques, dans l'ordre des comparaisons.
+    pub const ALL: [ExperimentMetric; 3] = [
+        ExperimentMetric::Cer,
+        ExperimentMetric::Wer,
+        ExperimentMetric::Duration,
+    ];
+
+    /// Nom de la métrique (ex: "cer").
+    pub fn name(self) -> &'static str {
+        match self {
+            ExperimentMetric::Cer => "cer",
+            ExperimentMetric::Wer => "wer",
+            ExperimentMetric::Duration => "duration",
+        }
+    }
+
+    /// Valeur de la métrique pour une exécution (durée en secondes).
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - Exécution enregistrée
+    pub fn value(self, record: &RunRecord) -> f64 {
+        match self {
+            ExperimentMetric::Cer => record.metrics.mean_cer,
+            ExperimentMetric::Wer => record.metrics.mean_wer,
+            ExperimentMetric::Duration => record.duration_ms as f64 / 1000.0,
+        }
+    }
+}
+
+/// Métriques agrégées d'une exécution.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RunMetrics {
+    /// CER moyen des images comparées (0.0 si aucune).
+    pub mean_cer: f64,
+    /// WER moyen des images comparées (0.0 si aucune).
+    pub mean_wer: f64,
+    /// Nombre d'images comparées à leur référence.
+    pub count: usize,
+}
+
+impl RunMetrics {
+    /// Moyenne des métriques de chaque image.
+    ///
+    /// # Arguments
+    ///
+    /// * `metrics` - Métriques des images comparées
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::experiments::RunMetrics;
+    /// use text_recognition::metrics::compare_ocr_result;
+    ///
+    /// let images = [compare_ocr_result("Facture", "Facture"), compare_ocr_result("Factvre", "Facture")];
+    /// let metrics = RunMetrics::from_metrics(&images);
+    /// assert_eq!(metrics.count, 2);
+    /// assert!(metrics.mean_cer > 0.0);
+    /// ```
+    pub fn from_metrics<'a>(metrics: impl IntoIterator<Item = &'a OcrMetrics>) -> Self {
+        let (mut cer, mut wer, mut count) = (0.0, 0.0, 0);
+        for metric in metrics {
+            cer += metric.cer;
+            wer += metric.wer;
+            count += 1;
+        }
+        if count == 0 {
+            return Self {
+                mean_cer: 0.0,
+                mean_wer: 0.0,
+                count,
+            };
+        }
+        Self {
+            mean_cer: cer / count as f64,
+            mean_wer: wer / count as f64,
+            count,
+        }
+    }
+}
+
+/// Ligne du registre : une exécution d'un lot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Fin de l'exécution, en secondes depuis l'epoch Unix.
+    pub timestamp: u64,
+    /// Libellé choisi par l'utilisateur (ex: "otsu-sans-deskew").
+    pub label: String,
+    /// Identifiant du jeu d'images (ex: répertoire ou motif du lot).
+    pub image_set: String,
+    /// Empreinte SHA-256 de la configuration OCR.
+    pub ocr_config_hash: String,
+    /// Configuration OCR complète.
+    pub ocr_config: serde_json::Value,
+    /// Empreinte SHA-256 du prétraitement, `None` sans prétraitement.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preprocessing_hash: Option<String>,
+    /// Configuration de prétraitement complète.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preprocessing: Option<serde_json::Value>,
+    /// Métriques agrégées.
+    pub metrics: RunMetrics,
+    /// Durée totale de l'exécution, en millisecondes.
+    pub duration_ms: u64,
+}
+
+impl RunRecord {
+    /// Enregistrement d'une exécution terminée maintenant.
+    ///
+    /// Les configurations sont conservées en JSON (clés triées) : le
+    /// registre reste lisible même si leurs structures évoluent.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - Libellé de l'expérience
+    /// * `image_set` - Identifiant du jeu d'images
+    /// * `config` - Configuration OCR effective
+    /// * `preprocessing` - Configuration de prétraitement, le cas échéant
+    /// * `metrics` - Métriques agrégées
+    /// * `duration` - Durée totale de l'exécution
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si une configuration ne peut pas être sérialisée.
+    pub fn new(
+        label: impl Into<String>,
+        image_set: impl Into<String>,
+        config: &OcrConfig,
+        preprocessing: Option<&PreprocessingConfig>,
+        metrics: RunMetrics,
+        duration: Duration,
+    ) -> Result<Self> {
+        // Passer par Value trie les clés (variables Tesseract dans une HashMap)
+        let ocr_config = serde_json::to_value(config)
+            .context("Échec de la sérialisation de la configuration OCR")?;
+        let preprocessing = preprocessing
+            .map(serde_json::to_value)
+            .transpose()
+            .context("Échec de la sérialisation du prétraitement")?;
+        let hash = |value: &serde_json::Value| sha256_hex(value.to_string().as_bytes());
+
+        Ok(Self {
+            timestamp: unix_timestamp(),
+            label: label.into(),
+            image_set: image_set.into(),
+            ocr_config_hash: hash(&ocr_config),
+            preprocessing_hash: preprocessing.as_ref().map(hash),
+            ocr_config,
+            preprocessing,
+            metrics,
+            duration_ms: duration.as_millis().try_into().unwrap_or(u64::MAX),
+        })
+    }
+}
+
+/// Secondes écoulées depuis l'epoch Unix (0 si l'horloge est antérieure).
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// Écart d'une métrique entre deux exécutions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MetricDelta {
+    /// Métrique comparée.
+    pub metric: ExperimentMetric,
+    /// Valeur pour la première exécution.
+    pub a: f64,
+    /// Valeur pour la seconde exécution.
+    pub b: f64,
+    /// `b - a` : négatif si la seconde exécution est meilleure.
+    pub delta: f64,
+}
+
+/// Comparaison de deux expériences, retournée par [`ExperimentLedger::compare()`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComparisonSummary {
+    /// Libellé de la première expérience.
+    pub label_a: String,
+    /// Libellé de la seconde expérience.
+    pub label_b: String,
+    /// Écarts, dans l'ordre de [`ExperimentMetric::ALL`].
+    pub deltas: Vec<MetricDelta>,
+    /// Les deux exécutions portent sur le même jeu d'images.
+    pub same_image_set: bool,
+    /// Les deux exécutions ont la même configuration OCR.
+    pub same_ocr_config: bool,
+    /// Les deux exécutions ont le même prétraitement.
+    pub same_preprocessing: bool,
+}
+
+impl ComparisonSummary {
+    /// Écart d'une métrique.
+    pub fn delta(&self, metric: ExperimentMetric) -> Option<&MetricDelta> {
+        self.deltas.iter().find(|delta| delta.metric == metric)
+    }
+}
+
+impl fmt::Display for ComparisonSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} → {}", self.label_a, self.label_b)?;
+        for delta in &self.deltas {
+            writeln!(
+                f,
+                "  {:<8} {:>10.4} {:>10.4} {:>+10.4}",
+                delta.metric.name(),
+                delta.a,
+                delta.b,
+                delta.delta
+            )?;
+        }
+        if !self.same_image_set {
+            writeln!(f, "  ⚠ Jeux d'images différents")?;
+        }
+        write!(
+            f,
+            "  Configuration OCR : {}, prétraitement : {}",
+            if self.same_ocr_config {
+                "identique"
+            } else {
+                "différente"
+            },
+            if self.same_preprocessing {
+                "identique"
+            } else {
+                "différent"
+            }
+        )
+    }
+}
+
+/// Contenu d'un registre lu sur disque.
+pub type LoadedLedger = LoadedLog<RunRecord>;
+
+/// Analyse le contenu d'un registre.
+///
+/// Une dernière ligne illisible et sans fin de ligne (écriture interrompue)
+/// est ignorée ; toute ligne complète illisible est une erreur (voir
+/// [`parse_log()`]).
+///
+/// # Arguments
+///
+/// * `content` - Contenu du fichier registre
+///
+/// # Erreurs
+///
+/// Retourne une erreur si une ligne complète n'est pas une exécution valide.
+pub fn parse_ledger(content: &str) -> Result<LoadedLedger> {
+    parse_log(content, LEDGER_NAME)
+}
+
+/// Registre des expériences (un fichier JSON Lines, en ajout seul).
+#[derive(Debug)]
+pub struct ExperimentLedger {
+    log: AppendLog<RunRecord>,
+    records: Vec<RunRecord>,
+}
+
+impl ExperimentLedger {
+    /// Ouvre le registre, en le créant s'il n'existe pas.
+    ///
+    /// Les exécutions existantes sont chargées ; une dernière ligne tronquée
+    /// est retirée du fichier pour que les ajouts suivants restent lisibles.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin du fichier registre
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le fichier ne peut pas être lu ou écrit, ou
+    /// si une ligne complète est invalide.
+    pub fn open(path: &Path) -> Result<Self> {
+        let (log, records) = AppendLog::open(path, LEDGER_NAME)?;
+        Ok(Self { log, records })
+    }
+
+    /// Chemin du fichier registre.
+    pub fn path(&self) -> &Path {
+        self.log.path()
+    }
+
+    /// Indique si une ligne tronquée a été retirée à l'ouverture.
+    pub fn recovered_truncated_tail(&self) -> bool {
+        self.log.recovered_truncated_tail()
+    }
+
+    /// Exécutions enregistrées, dans l'ordre du fichier.
+    pub fn records(&self) -> &[RunRecord] {
+        &self.records
+    }
+
+    /// Nombre d'exécutions enregistrées.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Indique si le registre ne contient aucune exécution.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Ajoute une exécution et la vide sur disque.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si l'écriture échoue.
+    pub fn record_run(&mut self, record: RunRecord) -> Result<()> {
+        self.log.append(&record)?;
+        self.records.push(record);
+        Ok(())
+    }
+
+    /// Les `n` meilleures exécutions selon une métrique, de la meilleure à la moins bonne.
+    ///
+    /// À valeur égale, l'exécution la plus ancienne vient en premier.
+    ///
+    /// # Arguments
+    ///
+    /// * `metric` - Métrique de classement (la plus petite valeur l'emporte)
+    /// * `n` - Nombre maximal d'exécutions retournées
+    pub fn best_runs(&self, metric: ExperimentMetric, n: usize) -> Vec<&RunRecord> {
+        let mut runs: Vec<&RunRecord> = self.records.iter().collect();
+        runs.sort_by(|a, b| metric.value(a).total_cmp(&metric.value(b)));
+        runs.truncate(n);
+        runs
+    }
+
+    /// Exécutions portant sur un jeu d'images, dans l'ordre du fichier.
+    ///
+    /// # Arguments
+    ///
+    /// * `image_set` - Identifiant du jeu d'images
+    pub fn runs_for_image_set(&self, image_set: &str) -> Vec<&RunRecord> {
+        self.records
+            .iter()
+            .filter(|record| record.image_set == image_set)
+            .collect()
+    }
+
+    /// Dernière exécution enregistrée sous un libellé.
+    pub fn latest(&self, label: &str) -> Option<&RunRecord> {
+        self.records
+            .iter()
+            .rev()
+            .find(|record| record.label == label)
+    }
+
+    /// Compare les dernières exécutions de deux libellés.
+    ///
+    /// # Arguments
+    ///
+    /// * `label_a` - Libellé de référence
+    /// * `label_b` - Libellé comparé (écarts `b - a`)
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si un libellé n'a aucune exécution.
+    pub fn compare(&self, label_a: &str, label_b: &str) -> Result<ComparisonSummary> {
+        let find = |label: &str| {
+            self.latest(label).with_context(|| {
+                format!(
+                    "Aucune exécution '{}' dans le registre '{}'",
+                    label,
+                    self.path().display()
+                )
+            })
+        };
+        let (a, b) = (find(label_a)?, find(label_b)?);
+
+        Ok(ComparisonSummary {
+            label_a: label_a.to_string(),
+            label_b: label_b.to_string(),
+            deltas: ExperimentMetric::ALL
+                .iter()
+                .map(|&metric| {
+                    let (value_a, value_b) = (metric.value(a), metric.value(b));
+                    MetricDelta {
+                        metric,
+                        a: value_a,
+                        b: value_b,
+                        delta: value_b - value_a,
+                    }
+                })
+                .collect(),
+            same_image_set: a.image_set == b.image_set,
+            same_ocr_config: a.ocr_config_hash == b.ocr_config_hash,
+            same_preprocessing: a.preprocessing_hash == b.preprocessing_hash,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(label: &str, image_set: &str, cer: f64, wer: f64, seconds: u64) -> RunRecord {
+        let metrics = RunMetrics {
+            mean_cer: cer,
+            mean_wer: wer,
+            count: 10,
+        };
+        RunRecord::new(
+            label,
+            image_set,
+            &OcrConfig::default(),
+            None,
+            metrics,
+            Duration::from_secs(seconds),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_append_and_reload_preserves_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("experiences.jsonl");
+        let preprocessing = PreprocessingConfig::default();
+        let with_preprocessing = RunRecord::new(
+            "otsu",
+            "factures",
+            &OcrConfig::default(),
+            Some(&preprocessing),
+            RunMetrics {
+                mean_cer: 0.031,
+                mean_wer: 0.09,
+                count: 12,
+            },
+            Duration::from_millis(41_250),
+        )
+        .unwrap();
+        let base = record("base", "factures", 0.05, 0.14, 30);
+
+        let mut ledger = ExperimentLedger::open(&path).unwrap();
+        ledger.record_run(base.clone()).unwrap();
+        ledger.record_run(with_preprocessing.clone()).unwrap();
+        drop(ledger);
+
+        let ledger = ExperimentLedger::open(&path).unwrap();
+        assert!(!ledger.recovered_truncated_tail());
+        assert_eq!(ledger.records(), [base, with_preprocessing.clone()]);
+        assert_eq!(with_preprocessing.duration_ms, 41_250);
+        assert!(with_preprocessing.preprocessing_hash.is_some());
+        assert_eq!(
+            with_preprocessing.ocr_config["language"],
+            OcrConfig::default().language
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+    }
+
+    #[test]
+    fn test_best_runs_and_image_sets() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ledger = ExperimentLedger::open(&dir.path().join("registre.jsonl")).unwrap();
+        for run in [
+            record("a", "factures", 0.08, 0.20, 10),
+            record("b", "tickets", 0.02, 0.30, 50),
+            record("c", "factures", 0.05, 0.10, 20),
+            record("d", "factures", 0.05, 0.40, 5),
+        ] {
+            ledger.record_run(run).unwrap();
+        }
+
+        let labels = |runs: Vec<&RunRecord>| -> Vec<String> {
+            runs.into_iter().map(|run| run.label.clone()).collect()
+        };
+        // À CER égal, l'exécution la plus ancienne d'abord
+        assert_eq!(
+            labels(ledger.best_runs(ExperimentMetric::Cer, 3)),
+            ["b", "c", "d"]
+        );
+        assert_eq!(labels(ledger.best_runs(ExperimentMetric::Wer, 1)), ["c"]);
+        assert_eq!(
+            labels(ledger.best_runs(ExperimentMetric::Duration, 10)),
+            ["d", "a", "c", "b"]
+        );
+        assert_eq!(
+            labels(ledger.runs_for_image_set("factures")),
+            ["a", "c", "d"]
+        );
+        assert!(ledger.runs_for_image_set("inconnu").is_empty());
+    }
+
+    #[test]
+    fn test_compare_deltas() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ledger = ExperimentLedger::open(&dir.path().join("registre.jsonl")).unwrap();
+        ledger
+            .record_run(record("base", "factures", 0.10, 0.25, 30))
+            .unwrap();
+        ledger
+            .record_run(record("deskew", "factures", 0.06, 0.20, 42))
+            .unwrap();
+
+        let summary = ledger.compare("base", "deskew").unwrap();
+        let cer = summary.delta(ExperimentMetric::Cer).unwrap();
+        assert_eq!((cer.a, cer.b), (0.10, 0.06));
+        assert!((cer.delta + 0.04).abs() < 1e-12);
+        let duration = summary.delta(ExperimentMetric::Duration).unwrap();
+        assert_eq!(duration.delta, 12.0);
+        assert!(summary.same_image_set && summary.same_ocr_config && summary.same_preprocessing);
+        assert!(summary.to_string().starts_with("base → deskew\n  cer"));
+
+        let error = ledger.compare("base", "absent").unwrap_err();
+        assert!(error.to_string().contains("Aucune exécution 'absent'"));
+    }
+}
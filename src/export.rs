@@ -0,0 +1,579 @@
+//! Export d'un document HOCR en Markdown ou en HTML.
+//!
+//! Le texte brut perd la mise en forme d'un compte rendu (titres, listes).
+//! [`HocrDocument::to_markdown()`] et [`HocrDocument::to_html()`] la
+//! reconstituent à partir de la géométrie du HOCR :
+//!
+//! - **Titres** : lignes nettement plus hautes que la ligne médiane de la
+//!   page, ou, à défaut, paragraphes d'une seule ligne courte en majuscules.
+//!   La hauteur de la ligne sert de taille de police (`x_size` n'est pas
+//!   extrait du HOCR).
+//! - **Listes** : lignes commençant par `-`, `•` ou un numéro suivi d'un
+//!   point (`1.`), regroupées même si Tesseract les place dans des
+//!   paragraphes distincts.
+//! - **Paragraphes** : les autres lignes, dans l'ordre du document.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! # #[cfg(feature = "tesseract")]
+//! # {
+//! use text_recognition::export::ExportOptions;
+//! use text_recognition::ocr::OcrEngine;
+//! use text_recognition::config::OcrConfig;
+//! use std::path::Path;
+//!
+//! let engine = OcrEngine::new(OcrConfig::default())?;
+//! let doc = engine.extract_hocr_document(Path::new("compte_rendu.png"))?;
+//!
+//! std::fs::write("compte_rendu.md", doc.to_markdown(&ExportOptions::default()))?;
+//! # }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Le sous-module [`lines`] exporte les lignes d'un document en paires
+//! image / texte pour l'affinage des modèles Tesseract.
+
+use crate::hocr::{BBox, HocrDocument, HocrLine};
+
+pub mod lines;
+
+/// Options de l'export Markdown/HTML.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportOptions {
+    /// Rapport minimal entre la hauteur d'une ligne et la hauteur médiane
+    /// des lignes pour en faire un titre de niveau 2 (défaut : 1.5).
+    pub heading_ratio: f32,
+    /// Rapport minimal pour un titre de niveau 1 (défaut : 2.0).
+    pub title_ratio: f32,
+    /// Nombre maximal de mots d'une ligne en majuscules considérée comme un
+    /// titre (défaut : 8).
+    pub max_caps_heading_words: usize,
+    /// Ajouter un attribut `data-bbox="x0 y0 x1 y1"` aux éléments HTML, pour
+    /// retrouver leur position dans l'image (défaut : non).
+    pub include_bbox: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            heading_ratio: 1.5,
+            title_ratio: 2.0,
+            max_caps_heading_words: 8,
+            include_bbox: false,
+        }
+    }
+}
+
+/// Type de puce d'un élément de liste.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListKind {
+    /// Puce `-` ou `•`.
+    Bullet,
+    /// Numéro suivi d'un point.
+    Ordered,
+}
+
+/// Élément de liste : numéro éventuel, texte sans la puce et position.
+#[derive(Debug, Clone, PartialEq)]
+struct ListItem {
+    number: Option<u32>,
+    text: String,
+    bbox: BBox,
+}
+
+/// Bloc de contenu reconstitué à partir du HOCR.
+#[derive(Debug, Clone, PartialEq)]
+enum Block {
+    /// Titre de niveau 1 ou 2.
+    Heading { level: u8, text: String, bbox: BBox },
+    /// Liste d'éléments de même type.
+    List {
+        kind: ListKind,
+        items: Vec<ListItem>,
+    },
+    /// Paragraphe : lignes de texte.
+    Paragraph { lines: Vec<String>, bbox: BBox },
+}
+
+impl HocrDocument {
+    /// Exporte le document en Markdown.
+    ///
+    /// Les titres deviennent `#`/`##`, les listes `-` ou `1.` et les blocs
+    /// sont séparés par une ligne vide. Les caractères ayant un sens en
+    /// Markdown (`*`, `_`, `` ` ``, `\`, `#` en début de ligne) sont échappés.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Seuils de détection des titres
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::export::ExportOptions;
+    /// use text_recognition::hocr::HocrDocument;
+    ///
+    /// let hocr = r#"<p class='ocr_par' title="bbox 0 0 200 20">
+    ///   <span class='ocr_line' title="bbox 0 0 200 20">
+    ///     <span class='ocrx_word' title='bbox 0 0 90 20'>ORDRE</span>
+    ///     <span class='ocrx_word' title='bbox 100 0 130 20'>DU</span>
+    ///     <span class='ocrx_word' title='bbox 140 0 200 20'>JOUR</span>
+    ///   </span>
+    /// </p>
+    /// <p class='ocr_par' title="bbox 0 40 200 60">
+    ///   <span class='ocr_line' title="bbox 0 40 200 60">
+    ///     <span class='ocrx_word' title='bbox 0 40 10 60'>-</span>
+    ///     <span class='ocrx_word' title='bbox 20 40 200 60'>Budget</span>
+    ///   </span>
+    /// </p>"#;
+    /// let doc = HocrDocument::from_hocr_string(hocr)?;
+    ///
+    /// assert_eq!(doc.to_markdown(&ExportOptions::default()), "## ORDRE DU JOUR\n\n- Budget\n");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn to_markdown(&self, options: &ExportOptions) -> String {
+        let blocks: Vec<String> = self
+            .blocks(options)
+            .iter()
+            .map(|block| match block {
+                Block::Heading { level, text, .. } => {
+                    format!(
+                        "{} {}",
+                        "#".repeat(usize::from(*level)),
+                        escape_markdown(text)
+                    )
+                }
+                Block::List { kind, items } => items
+                    .iter()
+                    .map(|item| {
+                        let marker = match (kind, item.number) {
+                            (ListKind::Ordered, Some(number)) => format!("{}.", number),
+                            _ => "-".to_string(),
+                        };
+                        format!("{} {}", marker, escape_markdown(&item.text))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Block::Paragraph { lines, .. } => lines
+                    .iter()
+                    .map(|line| escape_markdown_line_start(&escape_markdown(line)))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            })
+            .collect();
+
+        if blocks.is_empty() {
+            String::new()
+        } else {
+            blocks.join("\n\n") + "\n"
+        }
+    }
+
+    /// Exporte le document en fragment HTML sémantique.
+    ///
+    /// Produit des éléments `<h1>`/`<h2>`, `<ul>`/`<ol>` et `<p>` (lignes
+    /// séparées par `<br>`), sans en-tête de page. Le texte est échappé
+    /// (`&`, `<`, `>`, `"`) et les guillemets français et espaces
+    /// insécables sont écrits en entités (`&laquo;`, `&raquo;`, `&nbsp;`),
+    /// lisibles quel que soit l'encodage déclaré par la page.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Seuils de détection des titres et attribut `data-bbox`
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::export::ExportOptions;
+    /// use text_recognition::hocr::{BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord};
+    ///
+    /// let mut line = HocrLine::new(BBox::new(0, 0, 100, 20));
+    /// line.add_word(HocrWord::new(BBox::new(0, 0, 50, 20), "Budget".to_string(), None));
+    /// line.add_word(HocrWord::new(BBox::new(60, 0, 40, 20), "R&D".to_string(), None));
+    /// let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 100, 20));
+    /// paragraph.add_line(line);
+    /// let mut doc = HocrDocument::new();
+    /// doc.add_paragraph(paragraph);
+    ///
+    /// let options = ExportOptions { include_bbox: true, ..ExportOptions::default() };
+    /// assert_eq!(doc.to_html(&options), "<p data-bbox=\"0 0 100 20\">Budget R&amp;D</p>\n");
+    /// ```
+    pub fn to_html(&self, options: &ExportOptions) -> String {
+        let bbox_attr = |bbox: &BBox| {
+            if options.include_bbox {
+                format!(
+                    " data-bbox=\"{} {} {} {}\"",
+                    bbox.x,
+                    bbox.y,
+                    bbox.x + bbox.width,
+                    bbox.y + bbox.height
+                )
+            } else {
+                String::new()
+            }
+        };
+
+        let mut html = String::new();
+        for block in self.blocks(options) {
+            match block {
+                Block::Heading { level, text, bbox } => {
+                    html.push_str(&format!(
+                        "<h{level}{}>{}</h{level}>\n",
+                        bbox_attr(&bbox),
+                        escape_html(&text)
+                    ));
+                }
+                Block::List { kind, items } => {
+                    let tag = match kind {
+                        ListKind::Bullet => "ul",
+                        ListKind::Ordered => "ol",
+                    };
+                    let start = match items.first().and_then(|item| item.number) {
+                        Some(number) if kind == ListKind::Ordered && number != 1 => {
+                            format!(" start=\"{}\"", number)
+                        }
+                        _ => String::new(),
+                    };
+                    html.push_str(&format!("<{tag}{start}>\n"));
+                    for item in &items {
+                        html.push_str(&format!(
+                            "  <li{}>{}</li>\n",
+                            bbox_attr(&item.bbox),
+                            escape_html(&item.text)
+                        ));
+                    }
+                    html.push_str(&format!("</{tag}>\n"));
+                }
+                Block::Paragraph { lines, bbox } => {
+                    let lines: Vec<String> = lines.iter().map(|line| escape_html(line)).collect();
+                    html.push_str(&format!(
+                        "<p{}>{}</p>\n",
+                        bbox_attr(&bbox),
+                        lines.join("<br>\n")
+                    ));
+                }
+            }
+        }
+        html
+    }
+
+    /// Découpe le document en titres, listes et paragraphes.
+    fn blocks(&self, options: &ExportOptions) -> Vec<Block> {
+        let median_height = median_line_height(self);
+        let mut blocks: Vec<Block> = Vec::new();
+
+        for paragraph in self.paragraphs() {
+            let lines: Vec<&HocrLine> = paragraph
+                .lines
+                .iter()
+                .filter(|line| !line.words.is_empty())
+                .collect();
+            let single_line = lines.len() == 1;
+            // Lignes courantes du paragraphe, émises en bloc avant un titre ou une liste
+            let mut pending: Vec<&HocrLine> = Vec::new();
+
+            for line in lines {
+                let text = line_text(line);
+
+                let block = if let Some(level) =
+                    heading_level(line, &text, single_line, median_height, options)
+                {
+                    Some(Block::Heading {
+                        level,
+                        text,
+                        bbox: line.bbox.clone(),
+                    })
+                } else if let Some((kind, number, item_text)) = parse_list_item(&text) {
+                    let item = ListItem {
+                        number,
+                        text: item_text,
+                        bbox: line.bbox.clone(),
+                    };
+                    Some(Block::List {
+                        kind,
+                        items: vec![item],
+                    })
+                } else {
+                    pending.push(line);
+                    None
+                };
+
+                if let Some(block) = block {
+                    flush_paragraph(&mut blocks, &mut pending);
+                    push_block(&mut blocks, block);
+                }
+            }
+            flush_paragraph(&mut blocks, &mut pending);
+        }
+
+        blocks
+    }
+}
+
+/// Ajoute un bloc, en fusionnant les éléments de liste consécutifs de même type.
+fn push_block(blocks: &mut Vec<Block>, block: Block) {
+    if let Block::List { kind, items } = &block
+        && let Some(Block::List {
+            kind: last_kind,
+            items: last_items,
+        }) = blocks.last_mut()
+        && last_kind == kind
+    {
+        last_items.extend(items.iter().cloned());
+        return;
+    }
+    blocks.push(block);
+}
+
+/// Émet les lignes courantes accumulées sous forme de paragraphe.
+fn flush_paragraph(blocks: &mut Vec<Block>, pending: &mut Vec<&HocrLine>) {
+    let Some(bbox) = union_bbox(pending.iter().map(|line| &line.bbox)) else {
+        return;
+    };
+    let lines = pending.iter().map(|line| line_text(line)).collect();
+    blocks.push(Block::Paragraph { lines, bbox });
+    pending.clear();
+}
+
+/// Texte d'une ligne : mots séparés par une espace.
+fn line_text(line: &HocrLine) -> String {
+    line.words
+        .iter()
+        .map(|word| word.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Hauteur médiane des lignes non vides du document (0 si aucune ligne).
+fn median_line_height(doc: &HocrDocument) -> u32 {
+    let mut heights: Vec<u32> = doc
+        .paragraphs()
+        .flat_map(|paragraph| &paragraph.lines)
+        .filter(|line| !line.words.is_empty())
+        .map(|line| line.bbox.height)
+        .collect();
+    heights.sort_unstable();
+    heights.get(heights.len() / 2).copied().unwrap_or(0)
+}
+
+/// Niveau de titre d'une ligne, ou `None` si c'est une ligne courante.
+fn heading_level(
+    line: &HocrLine,
+    text: &str,
+    single_line: bool,
+    median_height: u32,
+    options: &ExportOptions,
+) -> Option<u8> {
+    if median_height > 0 {
+        let ratio = line.bbox.height as f32 / median_height as f32;
+        if ratio >= options.title_ratio {
+            return Some(1);
+        }
+        if ratio >= options.heading_ratio {
+            return Some(2);
+        }
+    }
+
+    // À défaut de taille distinctive : paragraphe d'une ligne courte en majuscules
+    let is_caps = text.chars().any(char::is_alphabetic)
+        && text
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .all(char::is_uppercase);
+    (single_line
+        && is_caps
+        && line.words.len() <= options.max_caps_heading_words
+        && parse_list_item(text).is_none())
+    .then_some(2)
+}
+
+/// Reconnaît une puce en début de ligne : `-`, `•` ou `12.`.
+///
+/// Retourne le type de liste, le numéro éventuel et le texte sans la puce.
+fn parse_list_item(text: &str) -> Option<(ListKind, Option<u32>, String)> {
+    let (marker, rest) = text.split_once(' ')?;
+    let rest = rest.trim_start();
+    if rest.is_empty() {
+        return None;
+    }
+
+    if marker == "-" || marker == "•" {
+        return Some((ListKind::Bullet, None, rest.to_string()));
+    }
+
+    let digits = marker.strip_suffix('.')?;
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let number = digits.parse().ok()?;
+    Some((ListKind::Ordered, Some(number), rest.to_string()))
+}
+
+/// Plus petit rectangle contenant tous les rectangles donnés.
+fn union_bbox<'a>(boxes: impl Iterator<Item = &'a BBox>) -> Option<BBox> {
+    boxes
+        .map(|bbox| (bbox.x, bbox.y, bbox.x + bbox.width, bbox.y + bbox.height))
+        .reduce(|(x0, y0, x1, y1), (bx0, by0, bx1, by1)| {
+            (x0.min(bx0), y0.min(by0), x1.max(bx1), y1.max(by1))
+        })
+        .map(|(x0, y0, x1, y1)| BBox::new(x0, y0, x1 - x0, y1 - y0))
+}
+
+/// Échappe les caractères ayant un sens en Markdown au sein d'une ligne.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Échappe un `#` ou `>` en début de ligne, qui ferait un titre ou une citation.
+fn escape_markdown_line_start(line: &str) -> String {
+    if line.starts_with('#') || line.starts_with('>') {
+        format!("\\{}", line)
+    } else {
+        line.to_string()
+    }
+}
+
+/// Échappe le texte pour l'inclure dans du HTML.
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '«' => escaped.push_str("&laquo;"),
+            '»' => escaped.push_str("&raquo;"),
+            '\u{a0}' => escaped.push_str("&nbsp;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hocr::{HocrParagraph, HocrWord};
+
+    /// Construit une ligne de hauteur `height` à l'ordonnée `y`.
+    fn line(y: u32, height: u32, text: &str) -> HocrLine {
+        let mut line = HocrLine::new(BBox::new(10, y, 400, height));
+        for (i, word) in text.split_whitespace().enumerate() {
+            let x = 10 + 50 * i as u32;
+            line.add_word(HocrWord::new(
+                BBox::new(x, y, 40, height),
+                word.to_string(),
+                None,
+            ));
+        }
+        line
+    }
+
+    fn paragraph(lines: Vec<HocrLine>) -> HocrParagraph {
+        let bbox = union_bbox(lines.iter().map(|line| &line.bbox)).unwrap();
+        let mut paragraph = HocrParagraph::new(bbox);
+        for line in lines {
+            paragraph.add_line(line);
+        }
+        paragraph
+    }
+
+    /// Un titre en grands caractères, deux paragraphes et trois puces.
+    fn meeting_notes() -> HocrDocument {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph(vec![line(10, 50, "Réunion du 3 mars")]));
+        doc.add_paragraph(paragraph(vec![
+            line(80, 20, "Présents : Alice, Bob."),
+            line(105, 20, "Excusée : Chloé."),
+        ]));
+        doc.add_paragraph(paragraph(vec![line(140, 20, "Le budget est validé.")]));
+        doc.add_paragraph(paragraph(vec![
+            line(170, 20, "- Relancer le fournisseur"),
+            line(195, 20, "• Préparer le devis"),
+        ]));
+        doc.add_paragraph(paragraph(vec![line(220, 20, "- Fixer la date")]));
+        doc
+    }
+
+    #[test]
+    fn test_markdown_meeting_notes() {
+        assert_eq!(
+            meeting_notes().to_markdown(&ExportOptions::default()),
+            "# Réunion du 3 mars\n\n\
+             Présents : Alice, Bob.\nExcusée : Chloé.\n\n\
+             Le budget est validé.\n\n\
+             - Relancer le fournisseur\n- Préparer le devis\n- Fixer la date\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_caps_heading_and_numbered_list() {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph(vec![line(10, 20, "ORDRE DU JOUR")]));
+        doc.add_paragraph(paragraph(vec![
+            line(40, 20, "1. Budget"),
+            line(65, 20, "2. Questions *diverses*"),
+        ]));
+        doc.add_paragraph(paragraph(vec![
+            line(90, 20, "TVA 20 % incluse"),
+            line(115, 20, "# de factures : 3"),
+        ]));
+
+        assert_eq!(
+            doc.to_markdown(&ExportOptions::default()),
+            "## ORDRE DU JOUR\n\n\
+             1. Budget\n2. Questions \\*diverses\\*\n\n\
+             TVA 20 % incluse\n\\# de factures : 3\n"
+        );
+    }
+
+    #[test]
+    fn test_html_structure_and_bbox() {
+        let options = ExportOptions {
+            include_bbox: true,
+            ..ExportOptions::default()
+        };
+        let html = meeting_notes().to_html(&options);
+
+        assert!(html.starts_with("<h1 data-bbox=\"10 10 410 60\">Réunion du 3 mars</h1>\n"));
+        assert!(html.contains(
+            "<p data-bbox=\"10 80 410 125\">Présents : Alice, Bob.<br>\nExcusée : Chloé.</p>\n"
+        ));
+        assert!(html.ends_with(
+            "<ul>\n  <li data-bbox=\"10 170 410 190\">Relancer le fournisseur</li>\n  \
+             <li data-bbox=\"10 195 410 215\">Préparer le devis</li>\n  \
+             <li data-bbox=\"10 220 410 240\">Fixer la date</li>\n</ul>\n"
+        ));
+        assert!(
+            !meeting_notes()
+                .to_html(&ExportOptions::default())
+                .contains("data-bbox")
+        );
+    }
+
+    #[test]
+    fn test_html_escaping() {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph(vec![line(10, 20, "« Q&A » : a <b> \"c\"")]));
+
+        assert_eq!(
+            doc.to_html(&ExportOptions::default()),
+            "<p>&laquo; Q&amp;A &raquo; : a &lt;b&gt; &quot;c&quot;</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_empty_document() {
+        let doc = HocrDocument::new();
+        assert_eq!(doc.to_markdown(&ExportOptions::default()), "");
+        assert_eq!(doc.to_html(&ExportOptions::default()), "");
+    }
+}
@@ -0,0 +1,387 @@
+//! Paires (image de ligne, texte) pour l'affinage des modèles Tesseract.
+//!
+//! L'entraînement d'un modèle LSTM (`tesstrain`) attend un répertoire de
+//! couples `{nom}.tif` / `{nom}.gt.txt` : l'image d'une seule ligne de texte
+//! et sa transcription exacte. [`export_line_pairs()`] les produit à partir
+//! du HOCR d'une page : chaque ligne est découpée dans l'image source, avec
+//! une marge, puis enregistrée en niveaux de gris à côté de son texte.
+//!
+//! Sans référence, le texte écrit est celui de l'OCR, à relire à la main.
+//! Avec une référence, chaque ligne OCR est appariée à une ligne de la
+//! référence par [`compare_by_line()`] et c'est le texte corrigé qui est
+//! écrit ; une ligne dont le CER dépasse [`LineExportOptions::max_cer`] est
+//! écartée, car elle est probablement mal appariée.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! # #[cfg(feature = "tesseract")]
+//! # {
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::export::lines::{LineExportOptions, export_line_pairs};
+//! use text_recognition::ocr::OcrEngine;
+//! use std::path::Path;
+//!
+//! let engine = OcrEngine::new(OcrConfig::default())?;
+//! let image = Path::new("facture.png");
+//! let doc = engine.extract_hocr_document(image)?;
+//! let reference = std::fs::read_to_string("facture.txt")?;
+//!
+//! let summary = export_line_pairs(
+//!     image,
+//!     &doc,
+//!     Some(&reference),
+//!     Path::new("gt/"),
+//!     &LineExportOptions::default(),
+//! )?;
+//! println!("{} paires, {} lignes écartées", summary.exported.len(), summary.skipped.len());
+//! # }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::hocr::{BBox, HocrDocument, HocrLine};
+use crate::metrics::{LineComparison, compare_by_line};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Options de [`export_line_pairs()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineExportOptions {
+    /// Marge ajoutée autour de chaque ligne, en pixels de l'image source
+    /// (défaut : 4).
+    pub padding: u32,
+    /// CER maximal entre la ligne OCR et la ligne de référence appariée ;
+    /// au-delà, la ligne est écartée (défaut : 0.3). Sans effet sans référence.
+    pub max_cer: f64,
+}
+
+impl Default for LineExportOptions {
+    fn default() -> Self {
+        Self {
+            padding: 4,
+            max_cer: 0.3,
+        }
+    }
+}
+
+/// Paire écrite par [`export_line_pairs()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedLine {
+    /// Indice de la ligne dans le document (à partir de 0).
+    pub index: usize,
+    /// Image de la ligne (`{stem}_{index}.tif`).
+    pub image_path: PathBuf,
+    /// Transcription de la ligne (`{stem}_{index}.gt.txt`).
+    pub text_path: PathBuf,
+    /// Texte écrit dans la transcription.
+    pub text: String,
+}
+
+/// Résultat de [`export_line_pairs()`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineExportSummary {
+    /// Paires écrites, dans l'ordre du document.
+    pub exported: Vec<ExportedLine>,
+    /// Indices des lignes écartées : vides, sans ligne de référence appariée,
+    /// au CER trop élevé ou hors de l'image.
+    pub skipped: Vec<usize>,
+}
+
+/// Exporte chaque ligne d'un document HOCR en paire `.tif` / `.gt.txt`.
+///
+/// Les fichiers sont nommés `{stem}_{index}`, `stem` étant le nom de
+/// l'image sans extension et `index` l'indice de la ligne dans le document,
+/// lignes écartées comprises : relancer l'export après correction de la
+/// référence réécrit les mêmes fichiers. Si le cadre de la page HOCR n'a pas
+/// les dimensions de l'image (image redimensionnée par le prétraitement),
+/// les rectangles sont ramenés à l'échelle de l'image source.
+///
+/// # Arguments
+///
+/// * `image_path` - Image source dans laquelle découper les lignes
+/// * `doc` - Document HOCR de cette image
+/// * `reference` - Texte de référence, pour écrire le texte corrigé plutôt que celui de l'OCR
+/// * `out_dir` - Répertoire de sortie, créé au besoin
+/// * `options` - Marge et CER maximal
+///
+/// # Erreurs
+///
+/// Retourne une erreur si l'image ne peut pas être lue, ou si un fichier ne
+/// peut pas être écrit.
+pub fn export_line_pairs(
+    image_path: &Path,
+    doc: &HocrDocument,
+    reference: Option<&str>,
+    out_dir: &Path,
+    options: &LineExportOptions,
+) -> Result<LineExportSummary> {
+    let image = image::open(image_path)
+        .with_context(|| format!("Échec du chargement de l'image '{}'", image_path.display()))?;
+    let stem = image_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| String::from("ligne"));
+
+    let lines: Vec<&HocrLine> = doc
+        .paragraphs()
+        .flat_map(|paragraph| &paragraph.lines)
+        .collect();
+    let texts: Vec<String> = lines.iter().map(|line| line_text(line)).collect();
+    let alignment = reference.map(|reference| compare_by_line(&texts.join("\n"), reference));
+    let ground_truth = ground_truth_texts(&texts, alignment.as_deref(), options.max_cer);
+
+    let (scale_x, scale_y) = match doc.pages.first() {
+        Some(page) if page.bbox.width > 0 && page.bbox.height > 0 => (
+            f64::from(image.width()) / f64::from(page.bbox.width),
+            f64::from(image.height()) / f64::from(page.bbox.height),
+        ),
+        _ => (1.0, 1.0),
+    };
+
+    fs::create_dir_all(out_dir).with_context(|| {
+        format!(
+            "Impossible de créer le répertoire de sortie '{}'",
+            out_dir.display()
+        )
+    })?;
+
+    let mut summary = LineExportSummary::default();
+    for (index, (line, text)) in lines.iter().zip(ground_truth).enumerate() {
+        let bbox = scale_bbox(&line.bbox, scale_x, scale_y);
+        let crop = padded_crop(&bbox, options.padding, image.width(), image.height());
+        let (Some(text), Some(crop)) = (text, crop) else {
+            summary.skipped.push(index);
+            continue;
+        };
+
+        let name = pair_stem(&stem, index);
+        let image_path = out_dir.join(format!("{}.tif", name));
+        let text_path = out_dir.join(format!("{}.gt.txt", name));
+        image
+            .crop_imm(crop.x, crop.y, crop.width, crop.height)
+            .to_luma8()
+            .save(&image_path)
+            .with_context(|| format!("Impossible d'écrire '{}'", image_path.display()))?;
+        fs::write(&text_path, format!("{}\n", text))
+            .with_context(|| format!("Impossible d'écrire '{}'", text_path.display()))?;
+
+        summary.exported.push(ExportedLine {
+            index,
+            image_path,
+            text_path,
+            text,
+        });
+    }
+
+    Ok(summary)
+}
+
+/// Nom commun (sans extension) des deux fichiers d'une paire.
+fn pair_stem(stem: &str, index: usize) -> String {
+    format!("{}_{}", stem, index)
+}
+
+/// Texte d'une ligne : mots dans l'ordre de lecture, séparés par une espace.
+fn line_text(line: &HocrLine) -> String {
+    line.words_in_reading_order()
+        .iter()
+        .map(|word| word.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Transcription de chaque ligne OCR, `None` pour une ligne à écarter.
+///
+/// Sans alignement, le texte OCR est repris tel quel. Avec un alignement,
+/// la ligne de référence appariée est retenue si son CER ne dépasse pas
+/// `max_cer`. Les lignes vides sont toujours écartées.
+fn ground_truth_texts(
+    ocr_lines: &[String],
+    alignment: Option<&[LineComparison]>,
+    max_cer: f64,
+) -> Vec<Option<String>> {
+    ocr_lines
+        .iter()
+        .enumerate()
+        .map(|(index, ocr)| {
+            if ocr.trim().is_empty() {
+                return None;
+            }
+            let Some(alignment) = alignment else {
+                return Some(ocr.trim().to_string());
+            };
+            alignment
+                .iter()
+                .find(|comparison| comparison.ocr_line == Some(index))
+                .filter(|comparison| comparison.reference_line.is_some())
+                .filter(|comparison| comparison.cer <= max_cer)
+                .map(|comparison| comparison.reference_text.trim().to_string())
+                .filter(|text| !text.is_empty())
+        })
+        .collect()
+}
+
+/// Ramène un rectangle du HOCR à l'échelle de l'image source.
+fn scale_bbox(bbox: &BBox, scale_x: f64, scale_y: f64) -> BBox {
+    let scale = |value: u32, factor: f64| (f64::from(value) * factor).round() as u32;
+    let (left, top) = (scale(bbox.x, scale_x), scale(bbox.y, scale_y));
+    let right = scale(bbox.x.saturating_add(bbox.width), scale_x);
+    let bottom = scale(bbox.y.saturating_add(bbox.height), scale_y);
+    BBox::new(
+        left,
+        top,
+        right.saturating_sub(left),
+        bottom.saturating_sub(top),
+    )
+}
+
+/// Zone à découper : le rectangle élargi de `padding`, borné à l'image.
+///
+/// `None` si la zone est vide une fois bornée (ligne hors de l'image).
+fn padded_crop(bbox: &BBox, padding: u32, width: u32, height: u32) -> Option<BBox> {
+    let left = bbox.x.saturating_sub(padding).min(width);
+    let top = bbox.y.saturating_sub(padding).min(height);
+    let right = bbox
+        .x
+        .saturating_add(bbox.width)
+        .saturating_add(padding)
+        .min(width);
+    let bottom = bbox
+        .y
+        .saturating_add(bbox.height)
+        .saturating_add(padding)
+        .min(height);
+    (right > left && bottom > top).then(|| BBox::new(left, top, right - left, bottom - top))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    fn comparison(
+        ocr_line: usize,
+        reference_line: Option<usize>,
+        reference_text: &str,
+        cer: f64,
+    ) -> LineComparison {
+        LineComparison {
+            ocr_line: Some(ocr_line),
+            reference_line,
+            ocr_text: String::new(),
+            reference_text: reference_text.to_string(),
+            cer,
+        }
+    }
+
+    #[test]
+    fn test_padded_crop_clamps_at_image_edges() {
+        // Ligne collée au coin supérieur gauche : la marge ne passe pas sous 0
+        let crop = padded_crop(&BBox::new(2, 1, 50, 20), 4, 100, 40).unwrap();
+        assert_eq!(crop, BBox::new(0, 0, 56, 25));
+
+        // Ligne qui touche le bord droit et le bas
+        let crop = padded_crop(&BBox::new(60, 30, 40, 10), 4, 100, 40).unwrap();
+        assert_eq!(crop, BBox::new(56, 26, 44, 14));
+
+        // Ligne entièrement hors de l'image
+        assert_eq!(padded_crop(&BBox::new(120, 50, 10, 10), 4, 100, 40), None);
+    }
+
+    #[test]
+    fn test_scale_bbox_follows_image_size() {
+        assert_eq!(
+            scale_bbox(&BBox::new(100, 40, 200, 30), 0.5, 0.5),
+            BBox::new(50, 20, 100, 15)
+        );
+    }
+
+    #[test]
+    fn test_pair_file_names() {
+        assert_eq!(pair_stem("facture", 0), "facture_0");
+        assert_eq!(pair_stem("scan.2024", 12), "scan.2024_12");
+    }
+
+    #[test]
+    fn test_ground_truth_uses_aligned_reference_line() {
+        let ocr = vec![
+            "Facturc".to_string(),
+            "bruit".to_string(),
+            "Tota1".to_string(),
+        ];
+        let alignment = [
+            comparison(0, Some(0), "Facture", 0.14),
+            comparison(1, None, "", 1.0),
+            comparison(2, Some(1), " Total ", 0.2),
+        ];
+
+        let texts = ground_truth_texts(&ocr, Some(&alignment), 0.3);
+
+        assert_eq!(
+            texts,
+            [Some("Facture".to_string()), None, Some("Total".to_string())]
+        );
+        // Sans référence, le texte OCR est repris
+        assert_eq!(
+            ground_truth_texts(&ocr, None, 0.3)[0].as_deref(),
+            Some("Facturc")
+        );
+    }
+
+    #[test]
+    fn test_lines_above_max_cer_are_skipped() {
+        let ocr = vec!["a".to_string(), "b".to_string(), String::new()];
+        let alignment = [
+            comparison(0, Some(0), "A", 0.3),
+            comparison(1, Some(1), "B", 0.31),
+            comparison(2, Some(2), "C", 0.0),
+        ];
+
+        let texts = ground_truth_texts(&ocr, Some(&alignment), 0.3);
+
+        // Au seuil exact la ligne est gardée ; une ligne OCR vide est écartée
+        assert_eq!(texts, [Some("A".to_string()), None, None]);
+    }
+
+    #[test]
+    fn test_export_writes_tif_and_gt_pairs() {
+        let dir = tempfile::tempdir().unwrap();
+        let image_path = dir.path().join("ticket.png");
+        GrayImage::from_pixel(200, 100, Luma([255]))
+            .save(&image_path)
+            .unwrap();
+        let doc = HocrDocument::from_hocr_string(
+            r#"<div class='ocr_page' title='bbox 0 0 200 100'>
+  <p class='ocr_par' title="bbox 10 10 190 90">
+    <span class='ocr_line' title="bbox 10 10 190 30">
+      <span class='ocrx_word' title='bbox 10 10 190 30; x_wconf 90'>Tota1</span>
+    </span>
+    <span class='ocr_line' title="bbox 10 60 190 90">
+      <span class='ocrx_word' title='bbox 10 60 190 90; x_wconf 20'>zzzz</span>
+    </span>
+  </p>
+</div>"#,
+        )
+        .unwrap();
+        let out_dir = dir.path().join("gt");
+
+        let summary = export_line_pairs(
+            &image_path,
+            &doc,
+            Some("Total\nMerci"),
+            &out_dir,
+            &LineExportOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(summary.skipped, [1]);
+        assert_eq!(summary.exported.len(), 1);
+        let pair = &summary.exported[0];
+        assert_eq!(pair.image_path, out_dir.join("ticket_0.tif"));
+        assert_eq!(fs::read_to_string(&pair.text_path).unwrap(), "Total\n");
+        let crop = image::open(&pair.image_path).unwrap();
+        assert_eq!((crop.width(), crop.height()), (188, 28));
+        assert!(!out_dir.join("ticket_1.tif").exists());
+    }
+}
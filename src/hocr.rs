@@ -3,15 +3,75 @@
 //! Ce module fournit des structures et méthodes pour extraire les bounding boxes
 //! (rectangles délimitant les mots, lignes, paragraphes, etc.) depuis Tesseract
 //! au format HOCR (HTML with OCR).
+//!
+//! Les paragraphes extraits peuvent être réordonnés selon l'ordre de lecture
+//! (ex: mise en page sur plusieurs colonnes) via [`reorder_reading_order()`].
+//! Chaque mot peut être rattaché à son écriture (latin, grec, cyrillique...)
+//! via [`HocrDocument::words_by_script()`], pour les documents multilingues.
+//!
+//! Une phrase peut être localisée sur l'image malgré les erreurs d'OCR
+//! avec [`HocrDocument::find_phrase_fuzzy()`].
+//!
+//! Les documents de plusieurs pages (TIFF multipage) sont découpés en
+//! [`HocrPage`], qui conservent les dimensions, l'image source et la
+//! résolution déclarées par chaque `ocr_page`.
+//!
+//! Le sous-module [`heatmap`] dessine la carte de chaleur des confiances
+//! des mots ([`confidence_heatmap()`]), et le sous-module [`review`]
+//! regroupe les mots ou lignes peu fiables en zones à relire
+//! ([`HocrDocument::low_confidence_regions()`]). Le sous-module [`merge`]
+//! recolle les lignes que Tesseract coupe en deux autour d'un exposant
+//! ([`merge_split_lines()`]). Le sous-module [`sort`] trie explicitement
+//! paragraphes, lignes et mots selon un [`SortOrder`] ([`HocrDocument::sort()`]).
+//! Le sous-module [`stream`] lit les mots d'un hOCR volumineux en flux,
+//! sans construire le document ([`parse_hocr_stream()`]). Le sous-module
+//! [`json`] enregistre un document analysé en JSON versionné et le relit
+//! ([`HocrDocument::save_json()`], [`HocrDocument::load_json()`]). Le
+//! sous-module [`alternatives`] lit les variantes de lecture des mots
+//! proposées par le LSTM ([`parse_alternatives()`]) et les départage avec
+//! un score externe ([`rescore_with()`]).
 
+#[cfg(feature = "tesseract")]
+use crate::config::{OcrConfig, OcrEngineMode, PageSegMode};
+#[cfg(feature = "tesseract")]
+use crate::diagnostics::{Warning, parse_tesseract_stderr};
+use crate::metrics::{FuzzyMatch, fuzzy_find};
+#[cfg(feature = "tesseract")]
+use crate::ocr::{
+    OcrOutcome, TesseractInvocation, format_warnings_suffix, legacy_data_hint, run_tesseract,
+};
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+use std::io::BufRead;
+#[cfg(feature = "tesseract")]
 use std::path::Path;
-use std::process::Command;
+#[cfg(feature = "tesseract")]
+use std::time::Duration;
+
+pub mod alternatives;
+pub mod heatmap;
+pub mod json;
+pub mod merge;
+pub mod review;
+pub mod sort;
+pub mod stream;
+
+pub use alternatives::{Alternative, WordAlternatives, parse_alternatives, rescore_with};
+pub use heatmap::{DEFAULT_MISSING_COLOR, HeatmapCanvas, HeatmapOptions, confidence_heatmap};
+pub use json::{HOCR_JSON_FORMAT_VERSION, HocrJsonError};
+pub use merge::{LineMergeOptions, merge_split_lines};
+pub use review::{DEFAULT_REVIEW_THRESHOLD, Level, save_region_crops};
+pub use sort::SortOrder;
+use stream::{HocrEvent, HocrEventParser, parse_hocr_events};
+pub use stream::{HocrStreamError, LineId, ParagraphId, parse_hocr_stream};
 
 /// Représente un rectangle délimitant (bounding box).
 ///
 /// Les coordonnées sont exprimées en pixels depuis le coin supérieur gauche de l'image.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BBox {
     /// Coordonnée X du coin supérieur gauche.
     pub x: u32,
@@ -74,10 +134,83 @@ impl BBox {
 
         Ok(Self::new(x0, y0, width, height))
     }
+
+    /// Calcule l'intersection sur union (IoU) avec un autre rectangle.
+    ///
+    /// Retourne une valeur entre 0.0 (rectangles disjoints) et 1.0
+    /// (rectangles identiques).
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::BBox;
+    ///
+    /// let a = BBox::new(0, 0, 10, 10);
+    /// // Intersection 5x10 = 50, union 150
+    /// assert!((a.iou(&BBox::new(5, 0, 10, 10)) - 1.0 / 3.0).abs() < 1e-9);
+    /// ```
+    pub fn iou(&self, other: &BBox) -> f64 {
+        let x0 = self.x.max(other.x);
+        let y0 = self.y.max(other.y);
+        let x1 = (self.x + self.width).min(other.x + other.width);
+        let y1 = (self.y + self.height).min(other.y + other.height);
+        if x1 <= x0 || y1 <= y0 {
+            return 0.0;
+        }
+
+        let intersection = u64::from(x1 - x0) * u64::from(y1 - y0);
+        let union = u64::from(self.width) * u64::from(self.height)
+            + u64::from(other.width) * u64::from(other.height)
+            - intersection;
+        intersection as f64 / union as f64
+    }
+
+    /// Plus petit rectangle contenant ce rectangle et `other`.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::BBox;
+    ///
+    /// let union = BBox::new(10, 20, 30, 10).union(&BBox::new(0, 25, 20, 15));
+    /// assert_eq!(union, BBox::new(0, 20, 40, 20));
+    /// ```
+    pub fn union(&self, other: &BBox) -> BBox {
+        let x0 = self.x.min(other.x);
+        let y0 = self.y.min(other.y);
+        let x1 = (self.x + self.width).max(other.x + other.width);
+        let y1 = (self.y + self.height).max(other.y + other.height);
+        BBox::new(x0, y0, x1 - x0, y1 - y0)
+    }
+
+    /// Rectangle multiplié par `factor`, bords arrondis au pixel le plus proche.
+    ///
+    /// Les bords sont arrondis séparément : deux rectangles jointifs le
+    /// restent après mise à l'échelle.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` - Facteur d'échelle (2.0 double les coordonnées)
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::BBox;
+    ///
+    /// assert_eq!(BBox::new(10, 20, 31, 10).scaled(0.5), BBox::new(5, 10, 16, 5));
+    /// ```
+    pub fn scaled(&self, factor: f64) -> BBox {
+        let scale = |value: u32| (value as f64 * factor).round() as u32;
+        let x0 = scale(self.x);
+        let y0 = scale(self.y);
+        let x1 = scale(self.x + self.width);
+        let y1 = scale(self.y + self.height);
+        BBox::new(x0, y0, x1.saturating_sub(x0), y1.saturating_sub(y0))
+    }
 }
 
 /// Représente un mot avec son bounding box et son texte.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HocrWord {
     /// Rectangle délimitant le mot.
     pub bbox: BBox,
@@ -85,6 +218,19 @@ pub struct HocrWord {
     pub text: String,
     /// Niveau de confiance de la reconnaissance (0-100).
     pub confidence: Option<u8>,
+    /// Langue Tesseract du mot (attribut `lang`, ex: "ell").
+    ///
+    /// Tesseract ne l'indique que lorsque plusieurs langues sont demandées
+    /// (ex: "fra+ell") et que celle du mot diffère de celle du paragraphe.
+    #[serde(default)]
+    pub lang: Option<String>,
+    /// Score combinant la confiance de Tesseract et la plausibilité du mot
+    /// dans un dictionnaire (entre 0 et 1 avec les poids par défaut).
+    ///
+    /// Absent tant que [`rescore_words()`](crate::postprocess::rescore_words)
+    /// n'a pas été appelé.
+    #[serde(default)]
+    pub fused_score: Option<f32>,
 }
 
 impl HocrWord {
@@ -100,21 +246,244 @@ impl HocrWord {
             bbox,
             text,
             confidence,
+            lang: None,
+            fused_score: None,
+        }
+    }
+
+    /// Retourne l'écriture du mot.
+    ///
+    /// La langue Tesseract est utilisée si elle correspond à une écriture
+    /// connue ; sinon l'écriture est déduite des caractères du mot via
+    /// [`classify_script()`].
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::{BBox, HocrWord, Script};
+    ///
+    /// let mut word = HocrWord::new(BBox::new(0, 0, 10, 10), "logos".to_string(), None);
+    /// assert_eq!(word.script(), Script::Latin);
+    ///
+    /// word.lang = Some("ell".to_string());
+    /// assert_eq!(word.script(), Script::Greek);
+    /// ```
+    pub fn script(&self) -> Script {
+        self.lang
+            .as_deref()
+            .and_then(script_for_language)
+            .unwrap_or_else(|| classify_script(&self.text))
+    }
+}
+
+/// Écriture (système d'écriture) d'un mot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Script {
+    /// Alphabet latin (y compris lettres accentuées).
+    Latin,
+    /// Alphabet grec (y compris grec polytonique).
+    Greek,
+    /// Alphabet cyrillique.
+    Cyrillic,
+    /// Chiffres uniquement.
+    Digits,
+    /// Plusieurs écritures, ou caractères d'une autre écriture.
+    Mixed,
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Script::Latin => "Latin",
+            Script::Greek => "Greek",
+            Script::Cyrillic => "Cyrillic",
+            Script::Digits => "Digits",
+            Script::Mixed => "Mixed",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Classe l'écriture d'un mot d'après les plages Unicode de ses caractères.
+///
+/// Seuls les lettres et les chiffres sont pris en compte (la ponctuation est
+/// ignorée). Un mot dont tous les caractères appartiennent à une même
+/// écriture est classé dans celle-ci ; un mot qui en mélange plusieurs
+/// (ex: "abc123"), qui contient une autre écriture ou qui ne contient ni
+/// lettre ni chiffre est classé [`Script::Mixed`].
+///
+/// # Arguments
+///
+/// * `text` - Texte du mot
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::hocr::{Script, classify_script};
+///
+/// assert_eq!(classify_script("αβγ"), Script::Greek);
+/// assert_eq!(classify_script("«Ελλάδα»"), Script::Greek);
+/// assert_eq!(classify_script("1945"), Script::Digits);
+/// assert_eq!(classify_script("abc123"), Script::Mixed);
+/// ```
+pub fn classify_script(text: &str) -> Script {
+    let mut found: Option<Script> = None;
+
+    for c in text.chars().filter(|c| c.is_alphanumeric()) {
+        let script = char_script(c);
+        match found {
+            None => found = Some(script),
+            Some(previous) if previous != script => return Script::Mixed,
+            Some(_) => {}
+        }
+    }
+
+    found.unwrap_or(Script::Mixed)
+}
+
+/// Retourne l'écriture d'un caractère alphanumérique.
+fn char_script(c: char) -> Script {
+    match c as u32 {
+        _ if c.is_ascii_digit() => Script::Digits,
+        0x41..=0x5A | 0x61..=0x7A => Script::Latin,
+        // Latin-1 (hors × et ÷), Latin étendu A/B, Latin étendu additionnel
+        0xC0..=0xD6 | 0xD8..=0xF6 | 0xF8..=0x24F | 0x1E00..=0x1EFF => Script::Latin,
+        // Grec et copte, grec étendu (polytonique)
+        0x370..=0x3FF | 0x1F00..=0x1FFF => Script::Greek,
+        // Cyrillique et supplément cyrillique
+        0x400..=0x52F => Script::Cyrillic,
+        _ => Script::Mixed,
+    }
+}
+
+/// Retourne l'écriture d'un code langue Tesseract, si elle est connue.
+fn script_for_language(lang: &str) -> Option<Script> {
+    match lang {
+        "ell" | "grc" => Some(Script::Greek),
+        "rus" | "ukr" | "bel" | "bul" | "mkd" | "srp" | "kaz" | "kir" | "tgk" | "mon" => {
+            Some(Script::Cyrillic)
+        }
+        "eng" | "fra" | "frm" | "deu" | "spa" | "ita" | "por" | "nld" | "lat" | "cat" | "pol"
+        | "ces" | "ron" | "swe" | "dan" | "nor" | "fin" | "hun" | "tur" => Some(Script::Latin),
+        _ => None,
+    }
+}
+
+/// Sens d'écriture d'une ligne de texte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextDirection {
+    /// De gauche à droite (écritures latines, grecques...).
+    #[default]
+    Ltr,
+    /// De droite à gauche (arabe, hébreu).
+    Rtl,
+    /// De haut en bas (japonais ou chinois vertical, étiquettes tournées).
+    TopToBottom,
+}
+
+impl TextDirection {
+    /// Déduit le sens d'écriture des indications hOCR d'une ligne.
+    ///
+    /// Un angle `textangle` proche de 90° ou de 270° indique une ligne
+    /// verticale ; sinon, l'attribut `dir` (de la ligne ou de son
+    /// paragraphe) distingue `rtl` de `ltr`.
+    ///
+    /// # Arguments
+    ///
+    /// * `angle` - Valeur de la propriété `textangle`, en degrés
+    /// * `dir` - Valeur de l'attribut `dir`
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::TextDirection;
+    ///
+    /// assert_eq!(TextDirection::from_hocr(Some(90.0), None), TextDirection::TopToBottom);
+    /// assert_eq!(TextDirection::from_hocr(None, Some("rtl")), TextDirection::Rtl);
+    /// assert_eq!(TextDirection::from_hocr(Some(180.0), None), TextDirection::Ltr);
+    /// ```
+    pub fn from_hocr(angle: Option<f32>, dir: Option<&str>) -> Self {
+        let vertical = angle.is_some_and(|angle| {
+            let quarter_turns = (angle.rem_euclid(360.0) / 90.0).round() as i32;
+            quarter_turns % 2 == 1
+        });
+        if vertical {
+            TextDirection::TopToBottom
+        } else if dir.is_some_and(|dir| dir.eq_ignore_ascii_case("rtl")) {
+            TextDirection::Rtl
+        } else {
+            TextDirection::Ltr
         }
     }
 }
 
+/// Ligne de base d'une ligne de texte (propriété `baseline` du hOCR).
+///
+/// Tesseract la décrit par une pente et un décalage vertical mesuré depuis
+/// le coin inférieur gauche de la bbox de la ligne : l'ordonnée de la ligne
+/// de base à l'abscisse `x` vaut `bas + offset + slope × (x − gauche)`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Pente de la ligne de base (0 pour une ligne horizontale).
+    pub slope: f32,
+    /// Décalage vertical depuis le bas de la bbox, en pixels (négatif : au-dessus).
+    pub offset: f32,
+}
+
+impl Baseline {
+    /// Parse la valeur de la propriété `baseline` (ex: "0.012 -5").
+    ///
+    /// Retourne `None` si la valeur ne contient pas exactement deux nombres.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::{BBox, Baseline};
+    ///
+    /// let baseline = Baseline::from_hocr("0 -5").unwrap();
+    /// assert_eq!(baseline.y_at(&BBox::new(10, 100, 200, 40), 60), 135.0);
+    /// assert!(Baseline::from_hocr("0").is_none());
+    /// ```
+    pub fn from_hocr(value: &str) -> Option<Self> {
+        let mut parts = value.split_whitespace();
+        let slope = parts.next()?.parse().ok()?;
+        let offset = parts.next()?.parse().ok()?;
+        parts.next().is_none().then_some(Self { slope, offset })
+    }
+
+    /// Ordonnée de la ligne de base à l'abscisse `x`, en pixels.
+    ///
+    /// # Arguments
+    ///
+    /// * `line_bbox` - Rectangle de la ligne à laquelle la ligne de base se rapporte
+    /// * `x` - Abscisse dans l'image
+    pub fn y_at(&self, line_bbox: &BBox, x: u32) -> f32 {
+        let bottom = (line_bbox.y + line_bbox.height) as f32;
+        bottom + self.offset + self.slope * (x as f32 - line_bbox.x as f32)
+    }
+}
+
 /// Représente une ligne de texte avec son bounding box et ses mots.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HocrLine {
     /// Rectangle délimitant la ligne.
     pub bbox: BBox,
     /// Mots composant la ligne.
     pub words: Vec<HocrWord>,
+    /// Sens d'écriture (propriété `textangle` et attribut `dir` du hOCR).
+    #[serde(default)]
+    pub direction: TextDirection,
+    /// Angle de la ligne en degrés (propriété `textangle`), s'il est indiqué.
+    #[serde(default)]
+    pub angle: Option<f32>,
+    /// Ligne de base (propriété `baseline`), si elle est indiquée.
+    #[serde(default)]
+    pub baseline: Option<Baseline>,
 }
 
 impl HocrLine {
-    /// Crée une nouvelle ligne HOCR.
+    /// Crée une nouvelle ligne HOCR, écrite de gauche à droite.
     ///
     /// # Arguments
     ///
@@ -123,6 +492,9 @@ impl HocrLine {
         Self {
             bbox,
             words: Vec::new(),
+            direction: TextDirection::Ltr,
+            angle: None,
+            baseline: None,
         }
     }
 
@@ -134,10 +506,79 @@ impl HocrLine {
     pub fn add_word(&mut self, word: HocrWord) {
         self.words.push(word);
     }
+
+    /// Mots de la ligne dans l'ordre de lecture.
+    ///
+    /// Les mots d'une ligne [`TextDirection::TopToBottom`] sont triés de haut
+    /// en bas, quelle que soit leur abscisse ; les autres gardent l'ordre du
+    /// hOCR (Tesseract émet déjà les lignes `rtl` dans l'ordre logique).
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::{BBox, HocrLine, HocrWord, TextDirection};
+    ///
+    /// let mut line = HocrLine::new(BBox::new(0, 0, 40, 120));
+    /// line.direction = TextDirection::TopToBottom;
+    /// line.add_word(HocrWord::new(BBox::new(4, 60, 30, 30), "語".to_string(), None));
+    /// line.add_word(HocrWord::new(BBox::new(0, 10, 30, 30), "日".to_string(), None));
+    /// line.add_word(HocrWord::new(BBox::new(2, 35, 30, 30), "本".to_string(), None));
+    ///
+    /// let words: Vec<&str> = line.words_in_reading_order().iter().map(|w| w.text.as_str()).collect();
+    /// assert_eq!(words, ["日", "本", "語"]);
+    /// ```
+    pub fn words_in_reading_order(&self) -> Vec<&HocrWord> {
+        let mut words: Vec<&HocrWord> = self.words.iter().collect();
+        if self.direction == TextDirection::TopToBottom {
+            words.sort_by_key(|word| word.bbox.y);
+        }
+        words
+    }
+
+    /// Moyenne des confiances connues des mots de la ligne.
+    ///
+    /// Retourne `None` si aucun mot de la ligne n'a de confiance (ligne
+    /// vide ou mots sans `x_wconf`).
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::{BBox, HocrLine, HocrWord};
+    ///
+    /// let mut line = HocrLine::new(BBox::new(0, 0, 200, 20));
+    /// assert_eq!(line.mean_confidence(), None);
+    ///
+    /// line.add_word(HocrWord::new(BBox::new(0, 0, 90, 20), "Bonjour".into(), Some(90)));
+    /// line.add_word(HocrWord::new(BBox::new(100, 0, 100, 20), "rnonde".into(), Some(45)));
+    /// line.add_word(HocrWord::new(BBox::new(100, 0, 100, 20), "?".into(), None));
+    /// assert_eq!(line.mean_confidence(), Some(67.5));
+    /// assert_eq!(line.min_confidence(), Some(45));
+    /// ```
+    pub fn mean_confidence(&self) -> Option<f32> {
+        mean_confidence(&self.words)
+    }
+
+    /// Plus faible confiance connue des mots de la ligne.
+    ///
+    /// Retourne `None` si aucun mot de la ligne n'a de confiance.
+    pub fn min_confidence(&self) -> Option<u8> {
+        self.words.iter().filter_map(|word| word.confidence).min()
+    }
+}
+
+/// Moyenne des confiances connues des mots, `None` s'il n'y en a aucune.
+fn mean_confidence<'a>(words: impl IntoIterator<Item = &'a HocrWord>) -> Option<f32> {
+    let (sum, count) = words
+        .into_iter()
+        .filter_map(|word| word.confidence)
+        .fold((0u64, 0u64), |(sum, count), confidence| {
+            (sum + u64::from(confidence), count + 1)
+        });
+    (count > 0).then(|| (sum as f64 / count as f64) as f32)
 }
 
 /// Représente un paragraphe avec son bounding box et ses lignes.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HocrParagraph {
     /// Rectangle délimitant le paragraphe.
     pub bbox: BBox,
@@ -166,24 +607,54 @@ impl HocrParagraph {
     pub fn add_line(&mut self, line: HocrLine) {
         self.lines.push(line);
     }
+
+    /// Moyenne des confiances connues de tous les mots du paragraphe.
+    ///
+    /// Chaque mot compte pour un : une longue ligne pèse plus qu'une ligne
+    /// courte. Retourne `None` si aucun mot n'a de confiance.
+    pub fn mean_confidence(&self) -> Option<f32> {
+        mean_confidence(self.lines.iter().flat_map(|line| &line.words))
+    }
 }
 
-/// Représente un document HOCR complet.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct HocrDocument {
-    /// Paragraphes du document.
+/// Séparateur des pages dans [`HocrDocument::full_text()`] (saut de page, comme Tesseract).
+pub const PAGE_SEPARATOR: &str = "\n\x0c\n";
+
+/// Représente une page (`ocr_page`) avec ses métadonnées et ses paragraphes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HocrPage {
+    /// Rectangle de la page, c'est-à-dire les dimensions de l'image analysée.
+    pub bbox: BBox,
+    /// Numéro de la page (`ppageno`, à partir de 0 comme dans Tesseract).
+    pub page_number: u32,
+    /// Image source déclarée par Tesseract (`image "..."`).
+    #[serde(default)]
+    pub image_name: Option<String>,
+    /// Résolution horizontale de numérisation (`scan_res`), si connue.
+    #[serde(default)]
+    pub dpi: Option<u32>,
+    /// Paragraphes de la page.
     pub paragraphs: Vec<HocrParagraph>,
 }
 
-impl HocrDocument {
-    /// Crée un nouveau document HOCR vide.
-    pub fn new() -> Self {
+impl HocrPage {
+    /// Crée une nouvelle page HOCR sans paragraphe.
+    ///
+    /// # Arguments
+    ///
+    /// * `bbox` - Rectangle de la page
+    /// * `page_number` - Numéro de la page (à partir de 0)
+    pub fn new(bbox: BBox, page_number: u32) -> Self {
         Self {
+            bbox,
+            page_number,
+            image_name: None,
+            dpi: None,
             paragraphs: Vec::new(),
         }
     }
 
-    /// Ajoute un paragraphe au document.
+    /// Ajoute un paragraphe à la page.
     ///
     /// # Arguments
     ///
@@ -192,155 +663,751 @@ impl HocrDocument {
         self.paragraphs.push(paragraph);
     }
 
-    /// Parse un document HOCR depuis une chaîne XML/HTML.
+    /// Reconstitue le texte de la page (voir [`HocrDocument::full_text()`]).
+    pub fn full_text(&self) -> String {
+        self.paragraphs
+            .iter()
+            .map(|paragraph| {
+                paragraph
+                    .lines
+                    .iter()
+                    .map(|line| {
+                        line.words_in_reading_order()
+                            .iter()
+                            .map(|word| word.text.as_str())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .filter(|paragraph| !paragraph.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Lit les métadonnées d'une balise `ocr_page`.
+    fn from_title(line: &str, default_number: u32) -> Self {
+        let title = extract_title(line).unwrap_or_default();
+        let bbox = title_property(title, "bbox")
+            .and_then(|values| BBox::from_hocr_string(&format!("bbox {}", values)).ok())
+            .unwrap_or_else(|| BBox::new(0, 0, 0, 0));
+        let page_number = title_property(title, "ppageno")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default_number);
+
+        let mut page = Self::new(bbox, page_number);
+        page.image_name = title_property(title, "image")
+            .map(|value| value.trim_matches('"').to_string())
+            .filter(|name| !name.is_empty());
+        // "scan_res 300 300" : résolutions horizontale et verticale
+        page.dpi = title_property(title, "scan_res")
+            .and_then(|values| values.split_whitespace().next())
+            .and_then(|value| value.parse().ok());
+        page
+    }
+}
+
+/// Élément ignoré lors du parsing d'un document HOCR.
+///
+/// Retourné par [`HocrDocument::from_hocr_string_with_warnings()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HocrParseWarning {
+    /// Numéro de la ligne du fichier HOCR concernée (à partir de 1).
+    pub line: usize,
+    /// Description de l'élément ignoré.
+    pub message: String,
+}
+
+impl HocrParseWarning {
+    /// Crée un avertissement pour une ligne du fichier HOCR.
     ///
-    /// Cette méthode parse le contenu HOCR généré par Tesseract et extrait
-    /// tous les bounding boxes et textes des mots, lignes et paragraphes.
+    /// # Arguments
+    ///
+    /// * `line` - Numéro de ligne (à partir de 1)
+    /// * `message` - Description de l'élément ignoré
+    pub fn new(line: usize, message: &str) -> Self {
+        Self {
+            line,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for HocrParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ligne {} : {}", self.line, self.message)
+    }
+}
+
+/// Représente un document HOCR complet.
+///
+/// Se sérialise en JSON versionné (voir [`json`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct HocrDocument {
+    /// Pages du document, dans l'ordre du fichier HOCR.
+    pub pages: Vec<HocrPage>,
+    /// Ordre des éléments, fixé par le dernier appel à [`sort()`](Self::sort).
+    pub order: SortOrder,
+    /// Facteur d'échelle de l'image analysée par rapport aux coordonnées du document.
+    ///
+    /// `1.0` tant que les coordonnées sont celles de l'image analysée. Après
+    /// [`to_source_coordinates()`](Self::to_source_coordinates), les
+    /// coordonnées sont celles de l'image source et ce facteur permet de
+    /// retrouver celles de l'image analysée ([`processed_bbox()`](Self::processed_bbox)).
+    pub scale_factor: f64,
+}
+
+impl HocrDocument {
+    /// Crée un nouveau document HOCR vide.
+    pub fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            order: SortOrder::Natural,
+            scale_factor: 1.0,
+        }
+    }
+
+    /// Ramène les coordonnées dans le repère de l'image source.
+    ///
+    /// Le prétraitement peut redimensionner l'image avant l'analyse (voir
+    /// [`PreprocessingReport::scale_factor`](crate::preprocessing::PreprocessingReport::scale_factor)) :
+    /// les rectangles des pages, paragraphes, lignes et mots, le décalage
+    /// des lignes de base et la résolution des pages sont divisés par
+    /// `scale_factor`, qui est cumulé dans [`scale_factor`](Self::scale_factor).
+    /// Un facteur de 1.0 (ou invalide) laisse le document inchangé.
     ///
     /// # Arguments
     ///
-    /// * `hocr_content` - Contenu HOCR au format XML/HTML
+    /// * `scale_factor` - Rapport entre les dimensions de l'image analysée et celles de la source
     ///
     /// # Exemple
     ///
-    /// ```no_run
-    /// use text_recognition::hocr::HocrDocument;
+    /// ```
+    /// use text_recognition::hocr::{BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord};
     ///
-    /// let hocr_html = r#"<html>...</html>"#;
-    /// let doc = HocrDocument::from_hocr_string(hocr_html).unwrap();
-    /// println!("Trouvé {} paragraphes", doc.paragraphs.len());
+    /// let mut line = HocrLine::new(BBox::new(0, 0, 200, 40));
+    /// line.add_word(HocrWord::new(BBox::new(40, 20, 100, 40), "Facture".into(), Some(95)));
+    /// let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 200, 40));
+    /// paragraph.add_line(line);
+    /// let mut doc = HocrDocument::new();
+    /// doc.add_paragraph(paragraph);
+    ///
+    /// // Image agrandie deux fois avant l'analyse
+    /// let doc = doc.to_source_coordinates(2.0);
+    /// let word = &doc.paragraphs().next().unwrap().lines[0].words[0];
+    /// assert_eq!(word.bbox, BBox::new(20, 10, 50, 20));
+    /// assert_eq!(doc.processed_bbox(&word.bbox), BBox::new(40, 20, 100, 40));
     /// ```
-    pub fn from_hocr_string(hocr_content: &str) -> Result<Self> {
-        let mut doc = HocrDocument::new();
-
-        // Parser simple basé sur regex
-        // Note: Pour une production robuste, il faudrait utiliser un parser XML/HTML
-        // comme `scraper` ou `html5ever`, mais pour l'apprentissage, un parser simple suffit.
-
-        let mut current_paragraph: Option<HocrParagraph> = None;
-        let mut current_line: Option<HocrLine> = None;
-
-        for line in hocr_content.lines() {
-            let trimmed = line.trim();
-
-            // Détecter les paragraphes
-            if trimmed.contains("class='ocr_par'") || trimmed.contains("class=\"ocr_par\"") {
-                // Sauvegarder le paragraphe précédent s'il existe
-                if let Some(para) = current_paragraph.take() {
-                    doc.add_paragraph(para);
-                }
-
-                // Extraire le bbox du paragraphe
-                if let Some(bbox) = extract_bbox(trimmed) {
-                    current_paragraph = Some(HocrParagraph::new(bbox));
-                }
-            }
-            // Détecter les lignes
-            else if trimmed.contains("class='ocr_line'") || trimmed.contains("class=\"ocr_line\"")
-            {
-                // Sauvegarder la ligne précédente s'il existe
-                if let Some(line_obj) = current_line.take()
-                    && let Some(ref mut para) = current_paragraph
-                {
-                    para.add_line(line_obj);
-                }
-
-                // Extraire le bbox de la ligne
-                if let Some(bbox) = extract_bbox(trimmed) {
-                    current_line = Some(HocrLine::new(bbox));
+    pub fn to_source_coordinates(mut self, scale_factor: f64) -> Self {
+        if scale_factor == 1.0 || !scale_factor.is_finite() || scale_factor <= 0.0 {
+            return self;
+        }
+        let inverse = 1.0 / scale_factor;
+        for page in &mut self.pages {
+            page.bbox = page.bbox.scaled(inverse);
+            page.dpi = page
+                .dpi
+                .map(|dpi| ((dpi as f64 * inverse).round() as u32).max(1));
+            for paragraph in &mut page.paragraphs {
+                paragraph.bbox = paragraph.bbox.scaled(inverse);
+                for line in &mut paragraph.lines {
+                    line.bbox = line.bbox.scaled(inverse);
+                    if let Some(ref mut baseline) = line.baseline {
+                        baseline.offset = (baseline.offset as f64 * inverse) as f32;
+                    }
+                    for word in &mut line.words {
+                        word.bbox = word.bbox.scaled(inverse);
+                    }
                 }
             }
-            // Détecter les mots
-            else if (trimmed.contains("class='ocrx_word'")
-                || trimmed.contains("class=\"ocrx_word\""))
-                && let Some(bbox) = extract_bbox(trimmed)
-                && let Some(text) = extract_word_text(trimmed)
-            {
-                // Extraire la confiance optionnelle
-                let confidence = extract_confidence(trimmed);
-
-                let word = HocrWord::new(bbox, text, confidence);
+        }
+        self.scale_factor *= scale_factor;
+        self
+    }
 
-                if let Some(ref mut line_obj) = current_line {
-                    line_obj.add_word(word);
-                }
-            }
+    /// Rectangle du document exprimé dans le repère de l'image analysée.
+    ///
+    /// Identité tant que [`scale_factor`](Self::scale_factor) vaut 1.0.
+    ///
+    /// # Arguments
+    ///
+    /// * `bbox` - Rectangle dans le repère du document
+    pub fn processed_bbox(&self, bbox: &BBox) -> BBox {
+        if self.scale_factor == 1.0 {
+            return bbox.clone();
         }
+        bbox.scaled(self.scale_factor)
+    }
 
-        // Sauvegarder les derniers éléments
-        if let Some(line_obj) = current_line
-            && let Some(ref mut para) = current_paragraph
-        {
-            para.add_line(line_obj);
+    /// Ajoute un paragraphe à la dernière page du document.
+    ///
+    /// Une page sans dimensions connues est créée si le document n'en a
+    /// encore aucune.
+    ///
+    /// # Arguments
+    ///
+    /// * `paragraph` - Paragraphe à ajouter
+    pub fn add_paragraph(&mut self, paragraph: HocrParagraph) {
+        if self.pages.is_empty() {
+            self.pages.push(HocrPage::new(BBox::new(0, 0, 0, 0), 0));
         }
-        if let Some(para) = current_paragraph {
-            doc.add_paragraph(para);
+        if let Some(page) = self.pages.last_mut() {
+            page.add_paragraph(paragraph);
         }
-
-        Ok(doc)
     }
 
-    /// Génère un rapport texte listant tous les bounding boxes.
+    /// Parcourt les paragraphes de toutes les pages, dans l'ordre du document.
     ///
     /// # Exemple
     ///
-    /// ```no_run
-    /// use text_recognition::hocr::HocrDocument;
+    /// ```
+    /// use text_recognition::hocr::{BBox, HocrDocument, HocrParagraph};
     ///
-    /// let doc = HocrDocument::new();
-    /// let report = doc.generate_report();
-    /// println!("{}", report);
+    /// let mut doc = HocrDocument::new();
+    /// doc.add_paragraph(HocrParagraph::new(BBox::new(0, 0, 100, 20)));
+    ///
+    /// assert_eq!(doc.pages.len(), 1);
+    /// assert_eq!(doc.paragraphs().count(), 1);
     /// ```
-    pub fn generate_report(&self) -> String {
-        let mut report = String::new();
-
-        report.push_str("=== RAPPORT HOCR - BOUNDING BOXES ===\n\n");
+    pub fn paragraphs(&self) -> impl Iterator<Item = &HocrParagraph> {
+        self.pages.iter().flat_map(|page| &page.paragraphs)
+    }
 
-        for (para_idx, para) in self.paragraphs.iter().enumerate() {
-            report.push_str(&format!(
-                "Paragraphe #{}: bbox({}, {}, {}, {})\n",
-                para_idx + 1,
-                para.bbox.x,
-                para.bbox.y,
-                para.bbox.width,
-                para.bbox.height
-            ));
+    /// Moyenne des confiances connues de tous les mots du document.
+    ///
+    /// Chaque mot compte pour un. Retourne `None` si aucun mot n'a de confiance.
+    pub fn mean_confidence(&self) -> Option<f32> {
+        mean_confidence(
+            self.paragraphs()
+                .flat_map(|paragraph| &paragraph.lines)
+                .flat_map(|line| &line.words),
+        )
+    }
 
-            for (line_idx, line) in para.lines.iter().enumerate() {
-                report.push_str(&format!(
-                    "  Ligne #{}: bbox({}, {}, {}, {})\n",
-                    line_idx + 1,
-                    line.bbox.x,
-                    line.bbox.y,
-                    line.bbox.width,
-                    line.bbox.height
-                ));
+    /// Retourne les mots dont le score est inférieur à `threshold`.
+    ///
+    /// Le score d'un mot est son [`fused_score`](HocrWord::fused_score) s'il
+    /// a été calculé, sinon sa confiance Tesseract ramenée entre 0 et 1. Les
+    /// mots sans l'un ni l'autre ne sont pas retenus. Les mots gardent
+    /// l'ordre du document.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Score minimal (entre 0 et 1) d'un mot jugé fiable
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::{BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord};
+    ///
+    /// let mut line = HocrLine::new(BBox::new(0, 0, 200, 20));
+    /// line.add_word(HocrWord::new(BBox::new(0, 0, 90, 20), "Bonjour".into(), Some(95)));
+    /// line.add_word(HocrWord::new(BBox::new(100, 0, 100, 20), "rnonde".into(), Some(45)));
+    /// let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 200, 20));
+    /// paragraph.add_line(line);
+    /// let mut doc = HocrDocument::new();
+    /// doc.add_paragraph(paragraph);
+    ///
+    /// let low: Vec<&str> = doc.low_score_words(0.6).iter().map(|w| w.text.as_str()).collect();
+    /// assert_eq!(low, ["rnonde"]);
+    /// ```
+    pub fn low_score_words(&self, threshold: f32) -> Vec<&HocrWord> {
+        self.paragraphs()
+            .flat_map(|paragraph| &paragraph.lines)
+            .flat_map(|line| &line.words)
+            .filter(|word| {
+                let score = word.fused_score.or_else(|| {
+                    word.confidence
+                        .map(|confidence| f32::from(confidence) / 100.0)
+                });
+                score.is_some_and(|score| score < threshold)
+            })
+            .collect()
+    }
 
-                for (word_idx, word) in line.words.iter().enumerate() {
-                    let conf_str = word
-                        .confidence
-                        .map(|c| format!(" [confiance: {}%]", c))
-                        .unwrap_or_default();
-
-                    report.push_str(&format!(
-                        "    Mot #{}: \"{}\" bbox({}, {}, {}, {}){}",
-                        word_idx + 1,
-                        word.text,
-                        word.bbox.x,
-                        word.bbox.y,
-                        word.bbox.width,
-                        word.bbox.height,
-                        conf_str
-                    ));
-                    report.push('\n');
-                }
+    /// Parse un document HOCR depuis une chaîne XML/HTML.
+    ///
+    /// Cette méthode parse le contenu HOCR généré par Tesseract et extrait
+    /// tous les bounding boxes et textes des mots, lignes et paragraphes.
+    /// Les éléments illisibles sont ignorés ; voir
+    /// [`from_hocr_string_with_warnings()`](Self::from_hocr_string_with_warnings)
+    /// pour les connaître.
+    ///
+    /// # Arguments
+    ///
+    /// * `hocr_content` - Contenu HOCR au format XML/HTML
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::hocr::HocrDocument;
+    ///
+    /// let hocr_html = r#"<html>...</html>"#;
+    /// let doc = HocrDocument::from_hocr_string(hocr_html).unwrap();
+    /// println!("Trouvé {} pages, {} paragraphes", doc.pages.len(), doc.paragraphs().count());
+    /// ```
+    pub fn from_hocr_string(hocr_content: &str) -> Result<Self> {
+        Ok(Self::from_hocr_string_with_warnings(hocr_content).0)
+    }
+
+    /// Parse un document HOCR en signalant les éléments ignorés.
+    ///
+    /// Le parsing est tolérant : un fichier tronqué ou mal formé donne le
+    /// document partiel reconstruit à partir des éléments lisibles, sans
+    /// jamais paniquer. Chaque élément écarté (bbox absente ou invalide, mot
+    /// sans texte, ligne hors paragraphe...) produit un
+    /// [`HocrParseWarning`].
+    ///
+    /// # Arguments
+    ///
+    /// * `hocr_content` - Contenu HOCR au format XML/HTML
+    ///
+    /// # Retour
+    ///
+    /// Le document partiel et la liste des avertissements, dans l'ordre du fichier
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::HocrDocument;
+    ///
+    /// // Fichier coupé au milieu du second mot
+    /// let hocr = "<p class='ocr_par' title='bbox 0 0 100 20'>
+    /// <span class='ocr_line' title='bbox 0 0 100 20'>
+    /// <span class='ocrx_word' title='bbox 0 0 40 20'>Bonjour</span>
+    /// <span class='ocrx_word' title='bbox 50 0 100 20'>mon";
+    /// let (doc, warnings) = HocrDocument::from_hocr_string_with_warnings(hocr);
+    ///
+    /// assert_eq!(doc.full_text(), "Bonjour");
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(warnings[0].line, 4);
+    /// ```
+    pub fn from_hocr_string_with_warnings(hocr_content: &str) -> (Self, Vec<HocrParseWarning>) {
+        let mut parser = HocrEventParser::default();
+        let mut builder = HocrDocumentBuilder::default();
+        let mut on_event = |event: HocrEvent<'_>| {
+            builder.push(event);
+            Ok::<(), Infallible>(())
+        };
+        for line in hocr_content.lines() {
+            match parser.push(line, &mut on_event) {
+                Ok(()) => {}
+                Err(never) => match never {},
+            }
+        }
+        (builder.finish(), parser.finish())
+    }
+
+    /// Parse un document HOCR lu ligne par ligne depuis `reader`.
+    ///
+    /// Contrairement à [`from_hocr_string()`](Self::from_hocr_string), le
+    /// fichier n'est jamais chargé en entier : seule la structure du
+    /// document est construite. Les éléments illisibles sont ignorés comme
+    /// avec [`from_hocr_string_with_warnings()`](Self::from_hocr_string_with_warnings).
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Source du HOCR (ex: `BufReader<File>`)
+    /// * `max_words` - Nombre maximal de mots acceptés, ou `None` sans limite
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::HocrDocument;
+    ///
+    /// let hocr = "<p class='ocr_par' title='bbox 0 0 100 20'>
+    /// <span class='ocr_line' title='bbox 0 0 100 20'>
+    /// <span class='ocrx_word' title='bbox 0 0 40 20'>Bonjour</span>";
+    /// let (doc, warnings) = HocrDocument::from_reader(hocr.as_bytes(), Some(1000))?;
+    ///
+    /// assert_eq!(doc.full_text(), "Bonjour");
+    /// assert!(warnings.is_empty());
+    /// # Ok::<(), text_recognition::hocr::HocrStreamError>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne [`HocrStreamError::Read`] si la lecture échoue (y compris
+    /// sur de l'UTF-8 invalide) et [`HocrStreamError::TooManyWords`] au-delà
+    /// de `max_words` mots.
+    pub fn from_reader(
+        reader: impl BufRead,
+        max_words: Option<usize>,
+    ) -> Result<(Self, Vec<HocrParseWarning>), HocrStreamError> {
+        let mut builder = HocrDocumentBuilder::default();
+        let warnings = parse_hocr_events(reader, max_words, |event| builder.push(event))?;
+        Ok((builder.finish(), warnings))
+    }
+
+    /// Génère un rapport texte listant tous les bounding boxes.
+    ///
+    /// Pour un document de plusieurs pages, chaque page est précédée d'un
+    /// en-tête avec son numéro, ses dimensions, son image et sa résolution.
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::hocr::HocrDocument;
+    ///
+    /// let doc = HocrDocument::new();
+    /// let report = doc.generate_report();
+    /// println!("{}", report);
+    /// ```
+    pub fn generate_report(&self) -> String {
+        let mut report = String::new();
+
+        report.push_str("=== RAPPORT HOCR - BOUNDING BOXES ===\n\n");
+
+        let multi_page = self.pages.len() > 1;
+        for page in &self.pages {
+            if multi_page {
+                report.push_str(&page_header(page));
             }
+            report_paragraphs(&mut report, &page.paragraphs);
+        }
 
-            report.push('\n');
+        let mut scripts: Vec<(Script, usize)> = self
+            .words_by_script()
+            .into_iter()
+            .map(|(script, words)| (script, words.len()))
+            .collect();
+        if !scripts.is_empty() {
+            scripts.sort();
+            let counts: Vec<String> = scripts
+                .iter()
+                .map(|(script, count)| format!("{}: {}", script, count))
+                .collect();
+            report.push_str(&format!("Écritures : {}\n", counts.join(", ")));
         }
 
         report
     }
+
+    /// Reconstitue le texte complet du document.
+    ///
+    /// Les mots sont séparés par un espace, les lignes par un retour à la
+    /// ligne et les paragraphes par une ligne vide, dans l'ordre des
+    /// paragraphes du document. Les pages sont séparées par
+    /// [`PAGE_SEPARATOR`].
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::{BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord};
+    ///
+    /// let mut line = HocrLine::new(BBox::new(0, 0, 100, 20));
+    /// line.add_word(HocrWord::new(BBox::new(0, 0, 40, 20), "Bonjour".to_string(), None));
+    /// line.add_word(HocrWord::new(BBox::new(50, 0, 50, 20), "monde".to_string(), None));
+    /// let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 100, 20));
+    /// paragraph.add_line(line);
+    /// let mut doc = HocrDocument::new();
+    /// doc.add_paragraph(paragraph);
+    ///
+    /// assert_eq!(doc.full_text(), "Bonjour monde");
+    /// ```
+    pub fn full_text(&self) -> String {
+        self.pages
+            .iter()
+            .map(HocrPage::full_text)
+            .collect::<Vec<_>>()
+            .join(PAGE_SEPARATOR)
+    }
+
+    /// Regroupe les mots du document par écriture.
+    ///
+    /// L'écriture de chaque mot est donnée par [`HocrWord::script()`] ; les
+    /// mots conservent leur ordre d'apparition dans chaque groupe.
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::hocr::{HocrDocument, Script};
+    ///
+    /// let hocr = std::fs::read_to_string("citations.hocr")?;
+    /// let doc = HocrDocument::from_hocr_string(&hocr)?;
+    /// if let Some(greek) = doc.words_by_script().get(&Script::Greek) {
+    ///     println!("{} mots grecs", greek.len());
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn words_by_script(&self) -> HashMap<Script, Vec<&HocrWord>> {
+        let mut groups: HashMap<Script, Vec<&HocrWord>> = HashMap::new();
+
+        for word in self
+            .paragraphs()
+            .flat_map(|paragraph| &paragraph.lines)
+            .flat_map(|line| &line.words)
+        {
+            groups.entry(word.script()).or_default().push(word);
+        }
+
+        groups
+    }
+
+    /// Recherche une phrase dans le texte du document en tolérant des erreurs d'OCR.
+    ///
+    /// La recherche porte sur [`full_text()`](Self::full_text) (voir
+    /// [`fuzzy_find()`]) ; chaque occurrence est rattachée aux mots qu'elle
+    /// recouvre, même partiellement.
+    ///
+    /// # Arguments
+    ///
+    /// * `needle` - Phrase recherchée
+    /// * `max_distance` - Nombre maximal d'erreurs (distance de Levenshtein)
+    ///
+    /// # Retour
+    ///
+    /// Les occurrences triées par distance puis par position.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::{BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord};
+    ///
+    /// let mut line = HocrLine::new(BBox::new(0, 0, 300, 20));
+    /// for (x, text) in [(0, "Voir"), (60, "Conditions"), (180, "génerales")] {
+    ///     line.add_word(HocrWord::new(BBox::new(x, 0, 100, 20), text.to_string(), None));
+    /// }
+    /// let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 300, 20));
+    /// paragraph.add_line(line);
+    /// let mut doc = HocrDocument::new();
+    /// doc.add_paragraph(paragraph);
+    ///
+    /// let matches = doc.find_phrase_fuzzy("Conditions générales", 1);
+    /// assert_eq!(matches[0].words.len(), 2);
+    /// assert_eq!(matches[0].words[0].x, 60);
+    /// ```
+    pub fn find_phrase_fuzzy(&self, needle: &str, max_distance: usize) -> Vec<PhraseMatch> {
+        let (text, spans) = self.text_with_word_spans();
+        fuzzy_find(&text, needle, max_distance)
+            .into_iter()
+            .map(|found| PhraseMatch {
+                words: spans
+                    .iter()
+                    .filter(|(start, end, _)| *start < found.end && found.start < *end)
+                    .map(|(_, _, bbox)| bbox.clone())
+                    .collect(),
+                found,
+            })
+            .collect()
+    }
+
+    /// Texte de [`full_text()`](Self::full_text) et position de chaque mot
+    /// (début et fin en caractères, rectangle).
+    fn text_with_word_spans(&self) -> (String, Vec<(usize, usize, BBox)>) {
+        let mut text = String::new();
+        let mut length = 0;
+        let mut spans = Vec::new();
+        let mut push = |text: &mut String, part: &str| {
+            text.push_str(part);
+            length += part.chars().count();
+            length
+        };
+
+        for (page_index, page) in self.pages.iter().enumerate() {
+            if page_index > 0 {
+                push(&mut text, PAGE_SEPARATOR);
+            }
+            let mut page_started = false;
+            for paragraph in &page.paragraphs {
+                let mut paragraph_started = false;
+                for line in &paragraph.lines {
+                    // Même filtre que full_text() : les lignes sans texte sont omises
+                    if line.words.iter().all(|word| word.text.is_empty()) && line.words.len() <= 1 {
+                        continue;
+                    }
+                    let separator = match (paragraph_started, page_started) {
+                        (true, _) => "\n",
+                        (false, true) => "\n\n",
+                        (false, false) => "",
+                    };
+                    push(&mut text, separator);
+                    for (index, word) in line.words_in_reading_order().into_iter().enumerate() {
+                        let start = push(&mut text, if index > 0 { " " } else { "" });
+                        let end = push(&mut text, &word.text);
+                        spans.push((start, end, word.bbox.clone()));
+                    }
+                    paragraph_started = true;
+                }
+                page_started |= paragraph_started;
+            }
+        }
+        (text, spans)
+    }
+}
+
+/// Occurrence approchée d'une phrase dans un document HOCR.
+///
+/// Retournée par [`HocrDocument::find_phrase_fuzzy()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PhraseMatch {
+    /// Occurrence dans le texte du document (positions en caractères).
+    pub found: FuzzyMatch,
+    /// Rectangles des mots recouverts par l'occurrence, dans l'ordre du texte.
+    pub words: Vec<BBox>,
+}
+
+/// Stratégie d'ordre de lecture des paragraphes d'un document HOCR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadingOrder {
+    /// Ordre produit par Tesseract, conservé tel quel.
+    #[default]
+    TopToBottom,
+
+    /// Lecture colonne par colonne, de haut en bas dans chaque colonne.
+    ///
+    /// Les paragraphes qui s'étendent sur plusieurs colonnes (titre pleine
+    /// largeur) découpent la page en bandes horizontales lues dans l'ordre.
+    Columns,
+}
+
+/// Part maximale de la couverture verticale maximale pour qu'une zone soit une gouttière.
+const GUTTER_COVERAGE_RATIO: f64 = 0.2;
+
+/// Largeur minimale d'une gouttière, relative à la largeur du contenu.
+const MIN_GUTTER_WIDTH_RATIO: f64 = 0.02;
+
+/// Réordonne les paragraphes d'un document selon l'ordre de lecture.
+///
+/// Avec [`ReadingOrder::Columns`], les frontières de colonnes sont détectées
+/// par les gouttières : des bandes verticales que presque aucun paragraphe ne
+/// recouvre (la hauteur cumulée des paragraphes qui les traversent reste
+/// inférieure à 20 % du maximum). Un paragraphe qui traverse une gouttière
+/// (titre pleine largeur) est lu à sa position verticale, entre les bandes
+/// de colonnes qui le précèdent et le suivent.
+///
+/// Chaque page est réordonnée indépendamment ; une page sans gouttière
+/// (une seule colonne) n'est pas modifiée.
+///
+/// # Arguments
+///
+/// * `doc` - Document à réordonner
+/// * `strategy` - Stratégie d'ordre de lecture
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::hocr::{HocrDocument, ReadingOrder, reorder_reading_order};
+///
+/// let hocr = std::fs::read_to_string("deux_colonnes.hocr")?;
+/// let mut doc = HocrDocument::from_hocr_string(&hocr)?;
+/// reorder_reading_order(&mut doc, ReadingOrder::Columns);
+/// println!("{}", doc.full_text());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn reorder_reading_order(doc: &mut HocrDocument, strategy: ReadingOrder) {
+    if strategy == ReadingOrder::TopToBottom {
+        return;
+    }
+
+    // Les colonnes sont détectées page par page
+    for page in &mut doc.pages {
+        reorder_paragraphs(&mut page.paragraphs);
+    }
+}
+
+/// Réordonne les paragraphes d'une page selon ses colonnes.
+fn reorder_paragraphs(paragraphs: &mut Vec<HocrParagraph>) {
+    if paragraphs.len() < 2 {
+        return;
+    }
+
+    let boundaries = detect_column_boundaries(paragraphs);
+    if boundaries.is_empty() {
+        return;
+    }
+
+    let crosses_boundary = |bbox: &BBox| {
+        boundaries
+            .iter()
+            .any(|&b| bbox.x < b && bbox.x + bbox.width > b)
+    };
+
+    let (mut spanning, columns): (Vec<HocrParagraph>, Vec<HocrParagraph>) =
+        std::mem::take(paragraphs)
+            .into_iter()
+            .partition(|paragraph| crosses_boundary(&paragraph.bbox));
+    spanning.sort_by_key(|paragraph| paragraph.bbox.y);
+
+    // Bande = nombre de paragraphes pleine largeur situés au-dessus
+    let mut keyed: Vec<(usize, usize, u32, HocrParagraph)> = columns
+        .into_iter()
+        .map(|paragraph| {
+            let band = spanning
+                .iter()
+                .filter(|s| s.bbox.y <= paragraph.bbox.y)
+                .count();
+            let column = boundaries
+                .iter()
+                .filter(|&&b| b <= paragraph.bbox.x)
+                .count();
+            (band, column, paragraph.bbox.y, paragraph)
+        })
+        .collect();
+    keyed.sort_by_key(|(band, column, y, _)| (*band, *column, *y));
+
+    let mut keyed = keyed.into_iter().peekable();
+    for (band, spanning_paragraph) in spanning.into_iter().enumerate() {
+        while let Some((_, _, _, paragraph)) = keyed.next_if(|(b, _, _, _)| *b <= band) {
+            paragraphs.push(paragraph);
+        }
+        paragraphs.push(spanning_paragraph);
+    }
+    paragraphs.extend(keyed.map(|(_, _, _, paragraph)| paragraph));
+}
+
+/// Détecte les abscisses des frontières de colonnes (milieu des gouttières).
+///
+/// La couverture de chaque abscisse est la hauteur cumulée des paragraphes
+/// qui la recouvrent. Une gouttière est une plage intérieure de faible
+/// couverture, assez large pour ne pas être un simple espace entre mots.
+fn detect_column_boundaries(paragraphs: &[HocrParagraph]) -> Vec<u32> {
+    let Some(left) = paragraphs.iter().map(|p| p.bbox.x).min() else {
+        return Vec::new();
+    };
+    let right = paragraphs
+        .iter()
+        .map(|p| p.bbox.x + p.bbox.width)
+        .max()
+        .unwrap_or(left);
+    let width = (right - left) as usize;
+    if width == 0 {
+        return Vec::new();
+    }
+
+    let mut coverage = vec![0u64; width];
+    for paragraph in paragraphs {
+        let start = (paragraph.bbox.x - left) as usize;
+        let end = start + paragraph.bbox.width as usize;
+        for value in &mut coverage[start..end] {
+            *value += paragraph.bbox.height as u64;
+        }
+    }
+
+    let max_coverage = coverage.iter().copied().max().unwrap_or(0) as f64;
+    let threshold = max_coverage * GUTTER_COVERAGE_RATIO;
+    let min_gutter = ((width as f64 * MIN_GUTTER_WIDTH_RATIO) as usize).max(1);
+
+    let mut boundaries = Vec::new();
+    let mut run_start: Option<usize> = None;
+    for (x, &value) in coverage.iter().enumerate() {
+        let low = (value as f64) <= threshold;
+        match (low, run_start) {
+            (true, None) => run_start = Some(x),
+            (false, Some(start)) => {
+                // Les plages touchant les bords de la page ne sont pas des gouttières
+                if start > 0 && x - start >= min_gutter {
+                    boundaries.push(left + ((start + x) / 2) as u32);
+                }
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+
+    boundaries
 }
 
 impl Default for HocrDocument {
@@ -349,6 +1416,625 @@ impl Default for HocrDocument {
     }
 }
 
+/// Rapport approximatif entre la hauteur d'x et la hauteur médiane d'une boîte de mot.
+///
+/// La plupart des mots ont au moins une hampe (« l », « d ») : leur boîte
+/// couvre environ les trois quarts du corps, dont la hauteur d'x occupe la moitié.
+const X_HEIGHT_TO_WORD_HEIGHT: f32 = 0.65;
+
+/// Rapport entre la hauteur d'x et le corps (em) d'une police de labeur courante.
+const X_HEIGHT_TO_EM: f32 = 0.5;
+
+/// Dimensions typographiques estimées à partir des bounding boxes d'un document.
+///
+/// Retourné par [`estimate_text_metrics()`]. Les mesures sont en pixels et
+/// valent 0 lorsque le document ne permet pas de les calculer (aucun mot,
+/// ou aucune paire de lignes consécutives pour l'interligne).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct TextMetricsEstimate {
+    /// Nombre de mots mesurés.
+    pub word_count: usize,
+    /// Hauteur médiane des boîtes de mots.
+    pub median_word_height: f32,
+    /// Écart vertical médian entre deux lignes consécutives d'un paragraphe.
+    pub median_line_spacing: f32,
+    /// Hauteur d'x estimée (hauteur d'une minuscule sans hampe).
+    pub x_height: f32,
+}
+
+/// Estime la taille du texte d'un document hOCR.
+///
+/// Calcule la hauteur médiane des mots, l'écart médian entre le bas d'une
+/// ligne et le haut de la suivante (au sein d'un même paragraphe) et une
+/// hauteur d'x déduite de la hauteur des mots. Sert à vérifier après coup
+/// la résolution supposée d'une image (voir [`suggest_dpi()`]).
+///
+/// # Arguments
+///
+/// * `doc` - Document hOCR analysé
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::hocr::{
+///     BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord, estimate_text_metrics,
+/// };
+///
+/// let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 400, 90));
+/// for y in [0, 50] {
+///     let mut line = HocrLine::new(BBox::new(0, y, 400, 40));
+///     line.add_word(HocrWord::new(BBox::new(0, y, 120, 40), "Bonjour".to_string(), None));
+///     paragraph.add_line(line);
+/// }
+/// let mut doc = HocrDocument::new();
+/// doc.add_paragraph(paragraph);
+///
+/// let estimate = estimate_text_metrics(&doc);
+/// assert_eq!(estimate.median_word_height, 40.0);
+/// assert_eq!(estimate.median_line_spacing, 10.0);
+/// ```
+pub fn estimate_text_metrics(doc: &HocrDocument) -> TextMetricsEstimate {
+    let mut word_heights: Vec<f32> = doc
+        .paragraphs()
+        .flat_map(|paragraph| &paragraph.lines)
+        .flat_map(|line| &line.words)
+        .filter(|word| word.bbox.height > 0 && !word.text.trim().is_empty())
+        .map(|word| word.bbox.height as f32)
+        .collect();
+
+    // Lignes qui se chevauchent (exposants, lignes mal segmentées) : pas d'écart mesurable
+    let mut line_gaps: Vec<f32> = doc
+        .paragraphs()
+        .flat_map(|paragraph| paragraph.lines.windows(2))
+        .filter_map(|pair| {
+            let bottom = pair[0].bbox.y + pair[0].bbox.height;
+            pair[1].bbox.y.checked_sub(bottom).map(|gap| gap as f32)
+        })
+        .collect();
+
+    let median_word_height = median(&mut word_heights);
+    TextMetricsEstimate {
+        word_count: word_heights.len(),
+        median_word_height,
+        median_line_spacing: median(&mut line_gaps),
+        x_height: median_word_height * X_HEIGHT_TO_WORD_HEIGHT,
+    }
+}
+
+/// Déduit la résolution d'une image de la hauteur d'x de son texte.
+///
+/// Suppose que le texte courant est composé en `assumed_font_pt` points
+/// (10 à 12 points pour un document imprimé) avec une hauteur d'x égale à
+/// la moitié du corps : `dpi = (x_height / 0.5) × 72 / assumed_font_pt`.
+///
+/// # Arguments
+///
+/// * `estimate` - Mesures du texte (voir [`estimate_text_metrics()`])
+/// * `assumed_font_pt` - Corps supposé du texte courant, en points
+///
+/// # Retour
+///
+/// La résolution suggérée, ou 0 si la hauteur d'x ou le corps n'est pas positif
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::hocr::{TextMetricsEstimate, suggest_dpi};
+///
+/// // Hauteur d'x de 20 px en 12 points : corps de 40 px pour 1/6 de pouce
+/// let estimate = TextMetricsEstimate {
+///     x_height: 20.0,
+///     ..TextMetricsEstimate::default()
+/// };
+/// assert_eq!(suggest_dpi(&estimate, 12.0), 240);
+/// ```
+pub fn suggest_dpi(estimate: &TextMetricsEstimate, assumed_font_pt: f32) -> u32 {
+    if estimate.x_height <= 0.0 || assumed_font_pt <= 0.0 || !assumed_font_pt.is_finite() {
+        return 0;
+    }
+    let em_pixels = estimate.x_height / X_HEIGHT_TO_EM;
+    (em_pixels * 72.0 / assumed_font_pt).round() as u32
+}
+
+/// Médiane d'une série (0.0 si vide). Trie la série en place.
+fn median(values: &mut [f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(f32::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// Écart vertical, en hauteurs de ligne, au-delà duquel le texte en colonnes insère une ligne vide.
+const BLANK_LINE_GAP_RATIO: f32 = 1.5;
+
+/// Largeur d'une tabulation pour [`LayoutTextOptions::use_tabs`].
+const TAB_WIDTH: usize = 8;
+
+/// Options du texte mis en page par [`to_layout_text()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutTextOptions {
+    /// Largeur maximale d'une ligne, en caractères (défaut : illimitée).
+    ///
+    /// Une page plus large est resserrée horizontalement : les colonnes
+    /// restent alignées entre elles, seuls les mots plus longs que l'espace
+    /// disponible dépassent.
+    pub max_line_width: Option<usize>,
+    /// Aligner avec des tabulations (tous les 8 caractères) plutôt qu'avec
+    /// des espaces (défaut : non).
+    pub use_tabs: bool,
+    /// Écart minimal entre deux mots, en caractères, pour respecter leur
+    /// position ; en dessous, ils sont séparés par une seule espace (défaut : 2).
+    pub min_gap: usize,
+}
+
+impl Default for LayoutTextOptions {
+    fn default() -> Self {
+        Self {
+            max_line_width: None,
+            use_tabs: false,
+            min_gap: 2,
+        }
+    }
+}
+
+/// Rangée de texte : lignes hOCR alignées verticalement (colonnes d'un tableau).
+///
+/// Chaque mot est accompagné de l'abscisse qui le range dans la rangée :
+/// la sienne, ou celle de sa ligne pour une ligne verticale.
+struct LayoutRow<'a> {
+    top: u32,
+    bottom: u32,
+    words: Vec<(u32, &'a HocrWord)>,
+}
+
+/// Mots d'une ligne dans l'ordre de lecture, avec leur abscisse de rangement.
+///
+/// Les mots d'une ligne verticale partagent l'abscisse de la ligne : le tri
+/// (stable) de la rangée conserve leur ordre de haut en bas.
+fn keyed_words(line: &HocrLine) -> impl Iterator<Item = (u32, &HocrWord)> {
+    let vertical = line.direction == TextDirection::TopToBottom;
+    line.words_in_reading_order()
+        .into_iter()
+        .map(move |word| (if vertical { line.bbox.x } else { word.bbox.x }, word))
+}
+
+/// Reconstitue la mise en page d'un document en texte à chasse fixe.
+///
+/// Comme `pdftotext -layout`, chaque mot est placé à la colonne de
+/// caractères correspondant à sa position horizontale : les colonnes d'un
+/// ticket ou d'un tableau restent alignées. La largeur d'un caractère est
+/// la médiane, sur tous les mots de la page, de la largeur du mot divisée
+/// par son nombre de caractères. Les lignes de paragraphes différents
+/// situées à la même hauteur sont fusionnées en une seule ligne, et un écart
+/// vertical de plus d'une ligne et demie produit des lignes vides.
+///
+/// # Arguments
+///
+/// * `doc` - Document hOCR analysé
+/// * `options` - Largeur maximale, tabulations et écart minimal
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::hocr::{
+///     BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord, LayoutTextOptions, to_layout_text,
+/// };
+///
+/// // Un article à gauche, son prix à droite (10 px par caractère)
+/// let mut line = HocrLine::new(BBox::new(0, 0, 440, 20));
+/// line.add_word(HocrWord::new(BBox::new(0, 0, 40, 20), "Pain".to_string(), None));
+/// line.add_word(HocrWord::new(BBox::new(400, 0, 40, 20), "1,20".to_string(), None));
+/// let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 440, 20));
+/// paragraph.add_line(line);
+/// let mut doc = HocrDocument::new();
+/// doc.add_paragraph(paragraph);
+///
+/// let text = to_layout_text(&doc, &LayoutTextOptions::default());
+/// assert_eq!(text, format!("Pain{}1,20", " ".repeat(36)));
+/// ```
+pub fn to_layout_text(doc: &HocrDocument, options: &LayoutTextOptions) -> String {
+    doc.pages
+        .iter()
+        .map(|page| layout_page(&page.paragraphs, options))
+        .collect::<Vec<_>>()
+        .join(PAGE_SEPARATOR)
+}
+
+/// Texte mis en page d'une page.
+fn layout_page(paragraphs: &[HocrParagraph], options: &LayoutTextOptions) -> String {
+    let words = || {
+        paragraphs
+            .iter()
+            .flat_map(|paragraph| &paragraph.lines)
+            .flat_map(|line| &line.words)
+            .filter(|word| !word.text.trim().is_empty())
+    };
+    let Some(origin) = words().map(|word| word.bbox.x).min() else {
+        return String::new();
+    };
+
+    let mut cell_widths: Vec<f32> = words()
+        .map(|word| word.bbox.width as f32 / word.text.trim().chars().count() as f32)
+        .collect();
+    let mut cell_width = median(&mut cell_widths).max(1.0);
+
+    // Page trop large : élargir la cellule pour que la ligne la plus longue tienne
+    if let Some(max_width) = options.max_line_width.filter(|&width| width > 0) {
+        let page_width = words()
+            .map(|word| word_column(word, origin, cell_width) + word.text.trim().chars().count())
+            .max()
+            .unwrap_or(0);
+        if page_width > max_width {
+            cell_width *= page_width as f32 / max_width as f32;
+        }
+    }
+
+    let mut line_heights: Vec<f32> = paragraphs
+        .iter()
+        .flat_map(|paragraph| &paragraph.lines)
+        .filter(|line| line.bbox.height > 0)
+        .map(|line| line.bbox.height as f32)
+        .collect();
+    let line_height = median(&mut line_heights).max(1.0);
+
+    let mut output = Vec::new();
+    let mut previous_bottom = None;
+    for row in layout_rows(paragraphs) {
+        if let Some(bottom) = previous_bottom {
+            let gap = row.top.saturating_sub(bottom) as f32;
+            if gap > BLANK_LINE_GAP_RATIO * line_height {
+                let blank_lines = ((gap / line_height) as usize).saturating_sub(1);
+                output.extend(std::iter::repeat_n(String::new(), blank_lines.max(1)));
+            }
+        }
+        previous_bottom = Some(row.bottom);
+        output.push(layout_row(&row.words, origin, cell_width, options));
+    }
+    output.join("\n")
+}
+
+/// Regroupe les lignes d'une page en rangées, de haut en bas.
+///
+/// Une ligne rejoint la rangée en cours si son centre vertical tombe dans
+/// la hauteur de la rangée (ou inversement) ; les mots d'une rangée sont
+/// triés de gauche à droite, ceux d'une ligne verticale de haut en bas.
+fn layout_rows(paragraphs: &[HocrParagraph]) -> Vec<LayoutRow<'_>> {
+    let mut lines: Vec<&HocrLine> = paragraphs
+        .iter()
+        .flat_map(|paragraph| &paragraph.lines)
+        .filter(|line| line.words.iter().any(|word| !word.text.trim().is_empty()))
+        .collect();
+    lines.sort_by_key(|line| (line.bbox.y, line.bbox.x));
+
+    let mut rows: Vec<LayoutRow> = Vec::new();
+    for line in lines {
+        let top = line.bbox.y;
+        let bottom = top + line.bbox.height;
+        let center = top + line.bbox.height / 2;
+        match rows.last_mut() {
+            Some(row)
+                if (row.top..=row.bottom).contains(&center)
+                    || (top..=bottom).contains(&(row.top + (row.bottom - row.top) / 2)) =>
+            {
+                row.top = row.top.min(top);
+                row.bottom = row.bottom.max(bottom);
+                row.words.extend(keyed_words(line));
+            }
+            _ => rows.push(LayoutRow {
+                top,
+                bottom,
+                words: keyed_words(line).collect(),
+            }),
+        }
+    }
+    for row in &mut rows {
+        row.words.retain(|(_, word)| !word.text.trim().is_empty());
+        row.words.sort_by_key(|(x, _)| *x);
+    }
+    rows
+}
+
+/// Colonne de caractères du début d'un mot.
+fn word_column(word: &HocrWord, origin: u32, cell_width: f32) -> usize {
+    ((word.bbox.x - origin) as f32 / cell_width).round() as usize
+}
+
+/// Texte d'une rangée, mots placés à leur colonne.
+fn layout_row(
+    words: &[(u32, &HocrWord)],
+    origin: u32,
+    cell_width: f32,
+    options: &LayoutTextOptions,
+) -> String {
+    let mut line = String::new();
+    let mut cursor = 0;
+    for (_, word) in words {
+        let text = word.text.trim();
+        let target = word_column(word, origin, cell_width);
+        // Mots qui se chevauchent ou trop proches : une seule espace
+        let gap = target.saturating_sub(cursor);
+        if gap > 0 && gap >= options.min_gap {
+            pad_to(&mut line, &mut cursor, target, options.use_tabs);
+        } else if !line.is_empty() {
+            line.push(' ');
+            cursor += 1;
+        }
+        line.push_str(text);
+        cursor += text.chars().count();
+    }
+    line
+}
+
+/// Complète une ligne jusqu'à la colonne `target`, avec des tabulations si demandé.
+fn pad_to(line: &mut String, cursor: &mut usize, target: usize, use_tabs: bool) {
+    if use_tabs {
+        loop {
+            let next_stop = (*cursor / TAB_WIDTH + 1) * TAB_WIDTH;
+            if next_stop > target {
+                break;
+            }
+            line.push('\t');
+            *cursor = next_stop;
+        }
+    }
+    while *cursor < target {
+        line.push(' ');
+        *cursor += 1;
+    }
+}
+
+/// Ajoute au rapport les bounding boxes des paragraphes, lignes et mots.
+fn report_paragraphs(report: &mut String, paragraphs: &[HocrParagraph]) {
+    for (para_idx, para) in paragraphs.iter().enumerate() {
+        report.push_str(&format!(
+            "Paragraphe #{}: bbox({}, {}, {}, {})\n",
+            para_idx + 1,
+            para.bbox.x,
+            para.bbox.y,
+            para.bbox.width,
+            para.bbox.height
+        ));
+
+        for (line_idx, line) in para.lines.iter().enumerate() {
+            report.push_str(&format!(
+                "  Ligne #{}: bbox({}, {}, {}, {})\n",
+                line_idx + 1,
+                line.bbox.x,
+                line.bbox.y,
+                line.bbox.width,
+                line.bbox.height
+            ));
+
+            for (word_idx, word) in line.words.iter().enumerate() {
+                let conf_str = word
+                    .confidence
+                    .map(|c| format!(" [confiance: {}%]", c))
+                    .unwrap_or_default();
+
+                report.push_str(&format!(
+                    "    Mot #{}: \"{}\" bbox({}, {}, {}, {}){}",
+                    word_idx + 1,
+                    word.text,
+                    word.bbox.x,
+                    word.bbox.y,
+                    word.bbox.width,
+                    word.bbox.height,
+                    conf_str
+                ));
+                report.push('\n');
+            }
+        }
+
+        report.push('\n');
+    }
+}
+
+/// En-tête d'une page dans le rapport HOCR.
+fn page_header(page: &HocrPage) -> String {
+    let mut details = vec![format!("{}x{}", page.bbox.width, page.bbox.height)];
+    if let Some(ref image_name) = page.image_name {
+        details.push(image_name.clone());
+    }
+    if let Some(dpi) = page.dpi {
+        details.push(format!("{} dpi", dpi));
+    }
+    format!(
+        "--- Page #{} ({}) ---\n\n",
+        page.page_number + 1,
+        details.join(", ")
+    )
+}
+
+/// Indique si la balise d'une ligne HOCR porte la classe `class`.
+///
+/// Équivaut à chercher `class='...'` ou `class="..."`, sans allouer de motif.
+fn has_class(line: &str, class: &str) -> bool {
+    line.match_indices("class=").any(|(index, _)| {
+        let value = &line[index + "class=".len()..];
+        match value.chars().next() {
+            Some(quote @ ('\'' | '"')) => value[1..]
+                .strip_prefix(class)
+                .is_some_and(|rest| rest.starts_with(quote)),
+            _ => false,
+        }
+    })
+}
+
+/// Élément de structure repéré sur une ligne d'un fichier HOCR.
+///
+/// Les variantes `Err` portent le message de l'avertissement : l'élément
+/// illisible est ignoré, mais une balise de paragraphe ou de ligne ferme
+/// quand même l'élément précédent.
+enum HocrElement<'a> {
+    /// Début d'une page (`ocr_page`), avec la ligne de sa balise.
+    Page(&'a str),
+    /// Début d'un paragraphe (`ocr_par`).
+    Paragraph(Result<HocrParagraph, &'static str>),
+    /// Début d'une ligne (`ocr_line`).
+    Line(Result<HocrLine, &'static str>),
+    /// Mot (`ocrx_word`).
+    Word(Result<HocrWord, &'static str>),
+}
+
+/// Découpe un fichier HOCR ligne par ligne en [`HocrElement`].
+///
+/// Parser simple, ligne par ligne (une balise par ligne, comme les fichiers
+/// de Tesseract). Pour une production robuste, il faudrait utiliser un
+/// parser XML/HTML comme `scraper` ou `html5ever`, mais pour
+/// l'apprentissage, un parser simple suffit. Utilisé par l'automate
+/// [`stream::HocrEventParser`].
+#[derive(Debug, Default)]
+struct HocrTokenizer {
+    /// Attribut `dir` du paragraphe en cours, hérité par ses lignes.
+    paragraph_dir: Option<String>,
+}
+
+impl HocrTokenizer {
+    /// Élément décrit par une ligne du fichier, s'il y en a un.
+    fn element<'a>(&mut self, line: &'a str) -> Option<HocrElement<'a>> {
+        let trimmed = line.trim();
+        if has_class(trimmed, "ocr_page") {
+            Some(HocrElement::Page(trimmed))
+        } else if has_class(trimmed, "ocr_par") {
+            self.paragraph_dir = extract_dir(trimmed);
+            let paragraph = extract_bbox(trimmed)
+                .map(HocrParagraph::new)
+                .ok_or("paragraphe ignoré : bbox absente ou invalide");
+            Some(HocrElement::Paragraph(paragraph))
+        } else if has_class(trimmed, "ocr_line") {
+            let line = extract_bbox(trimmed)
+                .map(|bbox| self.line(trimmed, bbox))
+                .ok_or("ligne ignorée : bbox absente ou invalide");
+            Some(HocrElement::Line(line))
+        } else if has_class(trimmed, "ocrx_word") {
+            Some(HocrElement::Word(parse_word(trimmed)))
+        } else {
+            None
+        }
+    }
+
+    /// Ligne de rectangle `bbox`, avec son angle, sa ligne de base et son sens d'écriture.
+    fn line(&self, trimmed: &str, bbox: BBox) -> HocrLine {
+        let mut line = HocrLine::new(bbox);
+        let title = extract_title(trimmed);
+        line.angle = title
+            .and_then(|title| title_property(title, "textangle"))
+            .and_then(|value| value.parse().ok());
+        line.baseline = title
+            .and_then(|title| title_property(title, "baseline"))
+            .and_then(Baseline::from_hocr);
+        let dir = extract_dir(trimmed).or_else(|| self.paragraph_dir.clone());
+        line.direction = TextDirection::from_hocr(line.angle, dir.as_deref());
+        line
+    }
+}
+
+/// Lit un mot (`ocrx_word`) : rectangle, texte, confiance et langue.
+fn parse_word(trimmed: &str) -> Result<HocrWord, &'static str> {
+    let bbox = extract_bbox(trimmed).ok_or("mot ignoré : bbox absente ou invalide")?;
+    let text = extract_word_text(trimmed)
+        .ok_or("mot ignoré : texte absent ou balise </span> manquante")?;
+    let mut word = HocrWord::new(bbox, text, extract_confidence(trimmed));
+    word.lang = extract_lang(trimmed);
+    Ok(word)
+}
+
+/// Construit un [`HocrDocument`] à partir des [`HocrEvent`] d'un [`HocrEventParser`].
+#[derive(Debug, Default)]
+struct HocrDocumentBuilder {
+    /// Document en cours de construction.
+    doc: HocrDocument,
+    /// Paragraphe en cours.
+    paragraph: Option<HocrParagraph>,
+    /// Ligne en cours.
+    line: Option<HocrLine>,
+}
+
+impl HocrDocumentBuilder {
+    /// Ajoute un élément retenu par l'automate.
+    fn push(&mut self, event: HocrEvent<'_>) {
+        match event {
+            HocrEvent::Page(tag) => {
+                // Les éléments en cours appartiennent à la page précédente
+                self.close_paragraph();
+                let default_number = self.doc.pages.len() as u32;
+                self.doc
+                    .pages
+                    .push(HocrPage::from_title(tag, default_number));
+            }
+            HocrEvent::Paragraph(paragraph) => {
+                self.close_paragraph();
+                self.paragraph = Some(paragraph);
+            }
+            HocrEvent::Line(line) => {
+                self.close_line();
+                self.line = Some(line);
+            }
+            HocrEvent::Word(word, _, _) => {
+                if let Some(ref mut line) = self.line {
+                    line.add_word(word);
+                }
+            }
+        }
+    }
+
+    /// Ferme les derniers éléments et retourne le document.
+    fn finish(mut self) -> HocrDocument {
+        self.close_paragraph();
+        self.doc
+    }
+
+    /// Ajoute la ligne en cours à son paragraphe.
+    fn close_line(&mut self) {
+        if let (Some(line), Some(paragraph)) = (self.line.take(), &mut self.paragraph) {
+            paragraph.add_line(line);
+        }
+    }
+
+    /// Ajoute la ligne puis le paragraphe en cours à la dernière page.
+    fn close_paragraph(&mut self) {
+        self.close_line();
+        if let Some(paragraph) = self.paragraph.take() {
+            self.doc.add_paragraph(paragraph);
+        }
+    }
+}
+
+/// Extrait le contenu de l'attribut `title` d'une ligne HOCR.
+///
+/// La valeur doit être entourée de guillemets simples ou doubles ; un
+/// attribut non refermé (ligne tronquée) donne `None`.
+///
+/// # Arguments
+///
+/// * `line` - Ligne HTML contenant un attribut title
+fn extract_title(line: &str) -> Option<&str> {
+    let start_idx = line.find("title=")?;
+    let rest = &line[start_idx + "title=".len()..];
+
+    // Le guillemet est un caractère ASCII : `rest[1..]` reste sur une frontière de caractère
+    let quote_char = rest.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+    let value = &rest[1..];
+    let end_idx = value.find(quote_char)?;
+    Some(&value[..end_idx])
+}
+
+/// Retourne la valeur d'une propriété `nom valeur` d'un attribut `title`.
+///
+/// Les propriétés sont séparées par des `;` (ex: `bbox 0 0 800 600; ppageno 0`).
+fn title_property<'a>(title: &'a str, name: &str) -> Option<&'a str> {
+    title.split(';').find_map(|property| {
+        let (key, value) = property.trim().split_once(' ')?;
+        (key == name).then(|| value.trim())
+    })
+}
+
 /// Extrait un bounding box depuis une ligne HOCR.
 ///
 /// # Arguments
@@ -356,41 +2042,81 @@ impl Default for HocrDocument {
 /// * `line` - Ligne HTML contenant un attribut title avec bbox
 fn extract_bbox(line: &str) -> Option<BBox> {
     // Chercher "title='bbox ..." ou "title=\"bbox ..."
-    let start_idx = line.find("title=")?;
-    let rest = &line[start_idx + 6..]; // Sauter "title="
+    let title_content = extract_title(line)?;
+
+    // Chercher "bbox x0 y0 x1 y1"
+    if let Some(bbox_start) = title_content.find("bbox ") {
+        let bbox_str = &title_content[bbox_start..];
+        let bbox_end = bbox_str.find(';').unwrap_or(bbox_str.len());
+        let bbox_values = &bbox_str[..bbox_end];
+
+        BBox::from_hocr_string(bbox_values).ok()
+    } else {
+        None
+    }
+}
+
+/// Extrait le texte d'un mot depuis une ligne HOCR.
+///
+/// # Arguments
+///
+/// * `line` - Ligne HTML contenant le mot
+fn extract_word_text(line: &str) -> Option<String> {
+    // Chercher le contenu entre le premier > et le </span> qui le suit
+    let content = &line[line.find('>')? + 1..];
+    let end_idx = content.find("</span>")?;
+
+    (end_idx > 0).then(|| decode_html_entities(content[..end_idx].trim()))
+}
+
+/// Décode les entités HTML écrites par Tesseract dans le texte des mots.
+///
+/// Tesseract échappe `&`, `<`, `>`, `"` et `'` ; les autres séquences sont
+/// conservées telles quelles.
+fn decode_html_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Extrait l'attribut `lang` depuis une ligne HOCR.
+///
+/// # Arguments
+///
+/// * `line` - Ligne HTML contenant éventuellement `lang='ell'`
+fn extract_lang(line: &str) -> Option<String> {
+    // " lang=" : ne pas confondre avec "xml:lang="
+    let start_idx = line.find(" lang=")?;
+    let rest = &line[start_idx + 6..]; // Sauter " lang="
 
-    let quote_char = rest.chars().next()?;
+    let quote_char = rest.chars().next().filter(|c| *c == '\'' || *c == '"')?;
     let end_idx = rest[1..].find(quote_char)?;
-    let title_content = &rest[1..=end_idx];
-
-    // Chercher "bbox x0 y0 x1 y1"
-    if let Some(bbox_start) = title_content.find("bbox ") {
-        let bbox_str = &title_content[bbox_start..];
-        let bbox_end = bbox_str.find(';').unwrap_or(bbox_str.len());
-        let bbox_values = &bbox_str[..bbox_end];
+    let lang = &rest[1..=end_idx];
 
-        BBox::from_hocr_string(bbox_values).ok()
-    } else {
-        None
-    }
+    (!lang.is_empty()).then(|| lang.to_string())
 }
 
-/// Extrait le texte d'un mot depuis une ligne HOCR.
+/// Extrait l'attribut `dir` (sens d'écriture) d'une ligne HOCR.
 ///
 /// # Arguments
 ///
-/// * `line` - Ligne HTML contenant le mot
-fn extract_word_text(line: &str) -> Option<String> {
-    // Chercher le contenu entre > et </span>
-    let start_idx = line.find('>')? + 1;
-    let end_idx = line.find("</span>")?;
+/// * `line` - Ligne HTML contenant éventuellement un attribut dir
+fn extract_dir(line: &str) -> Option<String> {
+    let start_idx = line.find(" dir=")?;
+    let rest = &line[start_idx + " dir=".len()..];
 
-    if start_idx < end_idx {
-        let text = line[start_idx..end_idx].trim();
-        Some(text.to_string())
-    } else {
-        None
-    }
+    let quote_char = rest.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+    let end_idx = rest[1..].find(quote_char)?;
+    let dir = &rest[1..=end_idx];
+
+    (!dir.is_empty()).then(|| dir.to_string())
 }
 
 /// Extrait le niveau de confiance depuis une ligne HOCR.
@@ -414,20 +2140,24 @@ fn extract_confidence(line: &str) -> Option<u8> {
 ///
 /// Cette fonction appelle directement le binaire `tesseract` en ligne de commande
 /// pour générer la sortie HOCR, qui contient tous les bounding boxes et le texte.
+/// Les autres paramètres (DPI, tessdata, variables) sont ceux de
+/// [`OcrConfig::default()`](crate::config::OcrConfig).
 ///
 /// # Arguments
 ///
 /// * `image_path` - Chemin vers l'image à analyser
 /// * `language` - Code langue Tesseract (ex: "fra", "eng")
 /// * `psm` - Mode de segmentation de page (0-13)
+/// * `oem` - Moteur de reconnaissance Tesseract
 ///
 /// # Exemple
 ///
 /// ```no_run
+/// use text_recognition::config::OcrEngineMode;
 /// use text_recognition::hocr::generate_hocr;
 /// use std::path::Path;
 ///
-/// let hocr = generate_hocr(Path::new("image.png"), "eng", 3).unwrap();
+/// let hocr = generate_hocr(Path::new("image.png"), "eng", 3, OcrEngineMode::Default).unwrap();
 /// println!("HOCR généré: {} octets", hocr.len());
 /// ```
 ///
@@ -436,44 +2166,177 @@ fn extract_confidence(line: &str) -> Option<u8> {
 /// Retourne une erreur si :
 /// - Le binaire `tesseract` n'est pas installé ou introuvable
 /// - Le fichier image n'existe pas ou est illisible
-/// - La génération HOCR échoue
-pub fn generate_hocr(image_path: &Path, language: &str, psm: u8) -> Result<String> {
-    let path_str = image_path.to_str().context("Chemin invalide")?;
+/// - La génération HOCR échoue (ex: modèle legacy absent avec l'OEM 0)
+#[cfg(feature = "tesseract")]
+pub fn generate_hocr(
+    image_path: &Path,
+    language: &str,
+    psm: u8,
+    oem: OcrEngineMode,
+) -> Result<String> {
+    Ok(generate_hocr_detailed(image_path, language, psm, oem)?.text)
+}
+
+/// Génère un fichier HOCR et retourne aussi les diagnostics de Tesseract.
+///
+/// Identique à [`generate_hocr()`], mais les lignes émises sur stderr par le
+/// binaire sont retournées dans [`OcrOutcome::warnings`] ; le champ `text`
+/// contient le document HOCR.
+///
+/// # Arguments
+///
+/// * `image_path` - Chemin vers l'image à analyser
+/// * `language` - Code langue Tesseract (ex: "fra", "eng")
+/// * `psm` - Mode de segmentation de page (0-13)
+/// * `oem` - Moteur de reconnaissance Tesseract
+///
+/// # Erreurs
+///
+/// Mêmes erreurs que [`generate_hocr()`]. En cas d'échec de Tesseract,
+/// le message d'erreur inclut sa sortie d'erreur.
+#[cfg(feature = "tesseract")]
+pub fn generate_hocr_detailed(
+    image_path: &Path,
+    language: &str,
+    psm: u8,
+    oem: OcrEngineMode,
+) -> Result<OcrOutcome> {
+    generate_hocr_with_timeout(image_path, language, psm, oem, None)
+}
+
+/// Génère un fichier HOCR en tuant le binaire `tesseract` au-delà de `timeout`.
+///
+/// Identique à [`generate_hocr_detailed()`], avec un délai maximal.
+///
+/// # Arguments
+///
+/// * `image_path` - Chemin vers l'image à analyser
+/// * `language` - Code langue Tesseract (ex: "fra", "eng")
+/// * `psm` - Mode de segmentation de page (0-13)
+/// * `oem` - Moteur de reconnaissance Tesseract
+/// * `timeout` - Délai maximal, ou `None` pour attendre sans limite
+///
+/// # Erreurs
+///
+/// Mêmes erreurs que [`generate_hocr_detailed()`], ainsi que
+/// [`OcrError::Timeout`](crate::ocr::OcrError::Timeout) si le délai est dépassé
+/// et une erreur si `psm` n'est pas compris entre 0 et 13.
+#[cfg(feature = "tesseract")]
+pub fn generate_hocr_with_timeout(
+    image_path: &Path,
+    language: &str,
+    psm: u8,
+    oem: OcrEngineMode,
+    timeout: Option<Duration>,
+) -> Result<OcrOutcome> {
+    let page_seg_mode = PageSegMode::from_tesseract_psm(psm.into())
+        .with_context(|| format!("Mode PSM invalide : {} (attendu entre 0 et 13)", psm))?;
+    let config = OcrConfig {
+        language: language.to_string(),
+        page_seg_mode,
+        engine_mode: oem,
+        ..OcrConfig::default()
+    };
+    let invocation = TesseractInvocation::new(&config, language, config.dpi);
+    generate_hocr_with_options(image_path, &invocation, &[], timeout, None)
+}
+
+/// Génère un fichier HOCR avec les paramètres d'une analyse.
+///
+/// `options` est inséré avant la configuration `hocr`, qui doit rester en
+/// fin de ligne de commande (ex: `--user-words mots.txt`). Avec
+/// `thread_limit`, le binaire est limité à ce nombre de threads
+/// (`OMP_THREAD_LIMIT` dans son seul environnement).
+#[cfg(feature = "tesseract")]
+pub(crate) fn generate_hocr_with_options(
+    image_path: &Path,
+    invocation: &TesseractInvocation,
+    options: &[String],
+    timeout: Option<Duration>,
+    thread_limit: Option<u32>,
+) -> Result<OcrOutcome> {
+    let file = generate_hocr_file(image_path, invocation, options, timeout, thread_limit)?;
+    let hocr_content = std::fs::read_to_string(file.path())
+        .context("Échec de la lecture du fichier HOCR généré")?;
+
+    Ok(OcrOutcome {
+        text: hocr_content,
+        warnings: file.warnings,
+        preprocessing: None,
+        cache_hit: false,
+    })
+}
+
+/// Fichier HOCR écrit par le binaire `tesseract`, supprimé avec cette valeur.
+#[cfg(feature = "tesseract")]
+pub(crate) struct HocrFile {
+    /// Répertoire temporaire contenant `output.hocr`.
+    dir: tempfile::TempDir,
+    /// Diagnostics émis par le binaire sur stderr.
+    pub(crate) warnings: Vec<Warning>,
+}
+
+#[cfg(feature = "tesseract")]
+impl HocrFile {
+    /// Chemin du fichier HOCR.
+    pub(crate) fn path(&self) -> std::path::PathBuf {
+        self.dir.path().join("output.hocr")
+    }
+
+    /// Parse le fichier en flux (voir [`HocrDocument::from_reader()`]).
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le fichier ne peut pas être ouvert ou lu.
+    pub(crate) fn parse(&self) -> Result<HocrDocument> {
+        let file = std::fs::File::open(self.path())
+            .context("Échec de la lecture du fichier HOCR généré")?;
+        let (doc, _) = HocrDocument::from_reader(std::io::BufReader::new(file), None)
+            .context("Échec de la lecture du fichier HOCR généré")?;
+        Ok(doc)
+    }
+}
 
+/// Lance le binaire `tesseract` en sortie HOCR sans lire le fichier produit.
+///
+/// Mêmes paramètres que [`generate_hocr_with_options()`] ; le fichier peut
+/// ensuite être lu en flux, sans le charger en mémoire.
+#[cfg(feature = "tesseract")]
+pub(crate) fn generate_hocr_file(
+    image_path: &Path,
+    invocation: &TesseractInvocation,
+    options: &[String],
+    timeout: Option<Duration>,
+    thread_limit: Option<u32>,
+) -> Result<HocrFile> {
     // Créer un répertoire temporaire pour la sortie
     let temp_dir = tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
     let output_base = temp_dir.path().join("output");
-    let output_base_str = output_base.to_str().context("Chemin temporaire invalide")?;
-
-    // Appeler tesseract avec l'option hocr
-    let status = Command::new("tesseract")
-        .args([
-            path_str,
-            output_base_str,
-            "-l",
-            language,
-            "--psm",
-            &psm.to_string(),
-            "hocr",
-        ])
-        .status()
-        .context("Impossible de lancer le binaire tesseract")?;
-
-    if !status.success() {
-        anyhow::bail!("Tesseract a échoué lors de la génération HOCR");
-    }
-
-    // Lire le fichier HOCR généré (extension .hocr)
-    let hocr_path = temp_dir.path().join("output.hocr");
-    let hocr_content = std::fs::read_to_string(&hocr_path)
-        .context("Échec de la lecture du fichier HOCR généré")?;
 
-    Ok(hocr_content)
+    // Appeler tesseract avec l'option hocr ; les chemins sont passés tels quels (OsStr)
+    let args = invocation.hocr_args(image_path, &output_base, options);
+    let output = run_tesseract(&args, timeout, thread_limit)?;
+
+    let warnings = parse_tesseract_stderr(&String::from_utf8_lossy(&output.stderr));
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Tesseract a échoué lors de la génération HOCR.{}{}",
+            legacy_data_hint(invocation.engine_mode()),
+            format_warnings_suffix(&warnings)
+        );
+    }
+
+    Ok(HocrFile {
+        dir: temp_dir,
+        warnings,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rng::SplitMix64;
 
     #[test]
     fn test_bbox_from_hocr_string() {
@@ -490,6 +2353,14 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_bbox_iou() {
+        let a = BBox::new(0, 0, 10, 10);
+        assert_eq!(a.iou(&a), 1.0);
+        assert_eq!(a.iou(&BBox::new(10, 0, 10, 10)), 0.0);
+        assert!((a.iou(&BBox::new(5, 0, 10, 10)) - 1.0 / 3.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_extract_bbox() {
         let line = r#"<span class="ocr_line" title="bbox 100 200 300 400; baseline 0 -5">"#;
@@ -500,6 +2371,39 @@ mod tests {
         assert_eq!(bbox.height, 200);
     }
 
+    #[test]
+    fn test_line_baseline_is_parsed() {
+        let doc = HocrDocument::from_hocr_string(
+            r#"<p class='ocr_par' title="bbox 0 0 400 100">
+<span class='ocr_line' title="bbox 0 0 400 40; baseline 0.01 -6; x_size 30">
+<span class='ocrx_word' title='bbox 0 0 90 40; x_wconf 90'>Total</span>
+</span>
+<span class='ocr_line' title="bbox 0 50 400 90; x_size 30">
+<span class='ocrx_word' title='bbox 0 50 90 90; x_wconf 90'>TVA</span>
+</span>
+</p>"#,
+        )
+        .unwrap();
+
+        let lines = &doc.pages[0].paragraphs[0].lines;
+        assert_eq!(
+            lines[0].baseline,
+            Some(Baseline {
+                slope: 0.01,
+                offset: -6.0
+            })
+        );
+        assert_eq!(lines[1].baseline, None);
+    }
+
+    #[test]
+    fn test_bbox_union_is_exact() {
+        let a = BBox::new(100, 90, 80, 30);
+        let b = BBox::new(10, 100, 60, 40);
+        assert_eq!(a.union(&b), BBox::new(10, 90, 170, 50));
+        assert_eq!(a.union(&a), a);
+    }
+
     #[test]
     fn test_extract_word_text() {
         let line = r#"<span class="ocrx_word">Hello</span>"#;
@@ -517,7 +2421,8 @@ mod tests {
     #[test]
     fn test_hocr_document_new() {
         let doc = HocrDocument::new();
-        assert_eq!(doc.paragraphs.len(), 0);
+        assert!(doc.pages.is_empty());
+        assert_eq!(doc.paragraphs().count(), 0);
     }
 
     #[test]
@@ -544,4 +2449,750 @@ mod tests {
         para.add_line(line);
         assert_eq!(para.lines.len(), 1);
     }
+
+    /// Crée un paragraphe d'une ligne contenant `text`.
+    fn paragraph(text: &str, x: u32, y: u32, width: u32, height: u32) -> HocrParagraph {
+        let bbox = BBox::new(x, y, width, height);
+        let mut line = HocrLine::new(bbox.clone());
+        for word in text.split_whitespace() {
+            line.add_word(HocrWord::new(bbox.clone(), word.to_string(), None));
+        }
+        let mut paragraph = HocrParagraph::new(bbox);
+        paragraph.add_line(line);
+        paragraph
+    }
+
+    /// Document sur deux colonnes avec un titre pleine largeur, dans l'ordre
+    /// entrelacé produit par Tesseract.
+    fn two_column_document() -> HocrDocument {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph("Titre", 100, 50, 800, 60));
+        doc.add_paragraph(paragraph("gauche un", 100, 150, 380, 200));
+        doc.add_paragraph(paragraph("droite un", 520, 150, 380, 200));
+        doc.add_paragraph(paragraph("gauche deux", 100, 400, 380, 200));
+        doc.add_paragraph(paragraph("droite deux", 520, 400, 380, 200));
+        doc
+    }
+
+    #[test]
+    fn test_full_text() {
+        let doc = two_column_document();
+        assert!(
+            doc.full_text()
+                .starts_with("Titre\n\ngauche un\n\ndroite un")
+        );
+    }
+
+    #[test]
+    fn test_reorder_two_columns_with_spanning_header() {
+        let mut doc = two_column_document();
+        reorder_reading_order(&mut doc, ReadingOrder::Columns);
+
+        assert_eq!(
+            doc.full_text(),
+            "Titre\n\ngauche un\n\ngauche deux\n\ndroite un\n\ndroite deux"
+        );
+    }
+
+    #[test]
+    fn test_reorder_spanning_paragraph_between_bands() {
+        let mut doc = two_column_document();
+        doc.add_paragraph(paragraph("Légende", 100, 650, 800, 40));
+        doc.add_paragraph(paragraph("gauche trois", 100, 720, 380, 100));
+        doc.add_paragraph(paragraph("droite trois", 520, 720, 380, 100));
+        reorder_reading_order(&mut doc, ReadingOrder::Columns);
+
+        let texts: Vec<String> = doc
+            .paragraphs()
+            .map(|p| p.lines[0].words[0].text.clone())
+            .collect();
+        assert_eq!(texts[5], "Légende");
+    }
+
+    #[test]
+    fn test_reorder_single_column_untouched() {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph("deux", 100, 300, 700, 100));
+        doc.add_paragraph(paragraph("un", 120, 100, 650, 100));
+        let original = doc.clone();
+
+        reorder_reading_order(&mut doc, ReadingOrder::Columns);
+        assert_eq!(doc, original);
+    }
+
+    #[test]
+    fn test_reorder_top_to_bottom_is_identity() {
+        let mut doc = two_column_document();
+        let original = doc.clone();
+
+        reorder_reading_order(&mut doc, ReadingOrder::TopToBottom);
+        assert_eq!(doc, original);
+    }
+
+    /// Extrait HOCR d'une ligne mêlant des mots latins et grecs (`lang='ell'`).
+    pub(super) const MIXED_SCRIPT_HOCR: &str = r#"<div class='ocr_page' id='page_1' title='bbox 0 0 800 200'>
+   <p class='ocr_par' id='par_1_1' lang='fra' title="bbox 10 10 700 50">
+    <span class='ocr_line' id='line_1_1' title="bbox 10 10 700 50; baseline 0 -5">
+     <span class='ocrx_word' id='word_1_1' title='bbox 10 10 90 50; x_wconf 95'>Socrate</span>
+     <span class='ocrx_word' id='word_1_2' title='bbox 100 10 150 50; x_wconf 93'>disait</span>
+     <span class='ocrx_word' id='word_1_3' title='bbox 160 10 260 50; x_wconf 88' lang='ell'>γνῶθι</span>
+     <span class='ocrx_word' id='word_1_4' title='bbox 270 10 390 50; x_wconf 87' lang='ell'>σεαυτόν</span>
+     <span class='ocrx_word' id='word_1_5' title='bbox 400 10 460 50; x_wconf 96'>(399)</span>
+    </span>
+   </p>
+</div>"#;
+
+    #[test]
+    fn test_parse_word_lang() {
+        let doc = HocrDocument::from_hocr_string(MIXED_SCRIPT_HOCR).unwrap();
+        let words = &doc.pages[0].paragraphs[0].lines[0].words;
+
+        assert_eq!(words.len(), 5);
+        assert_eq!(words[0].lang, None);
+        assert_eq!(words[2].lang.as_deref(), Some("ell"));
+        assert_eq!(words[2].text, "γνῶθι");
+        assert_eq!(words[3].script(), Script::Greek);
+    }
+
+    #[test]
+    fn test_extract_lang() {
+        assert_eq!(
+            extract_lang(r#"<span class="ocrx_word" lang="rus">"#).as_deref(),
+            Some("rus")
+        );
+        assert_eq!(extract_lang(r#"<html xml:lang="en">"#), None);
+        assert_eq!(extract_lang("<span class='ocrx_word'>"), None);
+    }
+
+    #[test]
+    fn test_classify_script() {
+        assert_eq!(classify_script("αβγ"), Script::Greek);
+        assert_eq!(classify_script("abc123"), Script::Mixed);
+        assert_eq!(classify_script("Élève,"), Script::Latin);
+        assert_eq!(classify_script("Москва"), Script::Cyrillic);
+        assert_eq!(classify_script("(399)"), Script::Digits);
+        assert_eq!(classify_script("Aθ"), Script::Mixed);
+        assert_eq!(classify_script("—"), Script::Mixed);
+    }
+
+    #[test]
+    fn test_words_by_script() {
+        let doc = HocrDocument::from_hocr_string(MIXED_SCRIPT_HOCR).unwrap();
+        let groups = doc.words_by_script();
+
+        assert_eq!(groups[&Script::Latin].len(), 2);
+        assert_eq!(groups[&Script::Greek].len(), 2);
+        assert_eq!(groups[&Script::Digits].len(), 1);
+        assert!(!groups.contains_key(&Script::Cyrillic));
+        assert_eq!(groups[&Script::Greek][1].text, "σεαυτόν");
+
+        assert!(
+            doc.generate_report()
+                .contains("Écritures : Latin: 2, Greek: 2, Digits: 1")
+        );
+    }
+
+    #[test]
+    fn test_parse_keeps_last_line_in_its_paragraph() {
+        let hocr = r#"<p class='ocr_par' title="bbox 0 0 100 40">
+<span class='ocr_line' title="bbox 0 0 100 20">
+<span class='ocrx_word' title='bbox 0 0 50 20'>un</span>
+</span>
+<span class='ocr_line' title="bbox 0 20 100 40">
+<span class='ocrx_word' title='bbox 0 20 50 40'>deux</span>
+</span>
+</p>
+<p class='ocr_par' title="bbox 0 60 100 80">
+<span class='ocr_line' title="bbox 0 60 100 80">
+<span class='ocrx_word' title='bbox 0 60 50 80'>trois</span>
+</span>
+</p>"#;
+        let doc = HocrDocument::from_hocr_string(hocr).unwrap();
+
+        let paragraphs: Vec<&HocrParagraph> = doc.paragraphs().collect();
+        assert_eq!(paragraphs.len(), 2);
+        assert_eq!(paragraphs[0].lines.len(), 2);
+        assert_eq!(paragraphs[1].lines.len(), 1);
+        assert_eq!(doc.full_text(), "un\ndeux\n\ntrois");
+    }
+
+    /// Colonne verticale dont les mots sont listés dans le désordre, puis
+    /// ligne horizontale d'un paragraphe de droite à gauche.
+    pub(super) const VERTICAL_HOCR: &str = r#"<div class='ocr_page' id='page_1' title='bbox 0 0 400 400'>
+   <p class='ocr_par' id='par_1_1' title="bbox 100 0 140 300">
+    <span class='ocr_line' id='line_1_1' title="bbox 100 0 140 300; textangle 90; x_size 40">
+     <span class='ocrx_word' id='word_1_1' title='bbox 100 200 140 300; x_wconf 90'>bas</span>
+     <span class='ocrx_word' id='word_1_2' title='bbox 100 0 140 90; x_wconf 91'>haut</span>
+     <span class='ocrx_word' id='word_1_3' title='bbox 100 100 140 190; x_wconf 92'>milieu</span>
+    </span>
+   </p>
+   <p class='ocr_par' id='par_1_2' dir='rtl' title="bbox 200 350 390 390">
+    <span class='ocr_line' id='line_1_2' title="bbox 200 350 390 390; baseline 0 -5">
+     <span class='ocrx_word' id='word_1_4' title='bbox 300 350 390 390; x_wconf 88'>שלום</span>
+    </span>
+   </p>
+</div>"#;
+
+    #[test]
+    fn test_parse_text_direction() {
+        let doc = HocrDocument::from_hocr_string(VERTICAL_HOCR).unwrap();
+        let paragraphs = &doc.pages[0].paragraphs;
+
+        let vertical = &paragraphs[0].lines[0];
+        assert_eq!(vertical.direction, TextDirection::TopToBottom);
+        assert_eq!(vertical.angle, Some(90.0));
+        // Ligne de paragraphe `dir='rtl'` : le sens est hérité
+        let rtl = &paragraphs[1].lines[0];
+        assert_eq!(rtl.direction, TextDirection::Rtl);
+        assert_eq!(rtl.angle, None);
+    }
+
+    #[test]
+    fn test_text_direction_from_hocr() {
+        assert_eq!(TextDirection::from_hocr(None, None), TextDirection::Ltr);
+        assert_eq!(
+            TextDirection::from_hocr(Some(270.0), Some("ltr")),
+            TextDirection::TopToBottom
+        );
+        assert_eq!(
+            TextDirection::from_hocr(Some(180.0), Some("rtl")),
+            TextDirection::Rtl
+        );
+    }
+
+    #[test]
+    fn test_vertical_line_text_reads_top_to_bottom() {
+        let doc = HocrDocument::from_hocr_string(VERTICAL_HOCR).unwrap();
+
+        assert_eq!(doc.full_text(), "haut milieu bas\n\nשלום");
+
+        let layout = to_layout_text(&doc, &LayoutTextOptions::default());
+        let first = layout.lines().find(|line| !line.trim().is_empty()).unwrap();
+        assert_eq!(first.trim(), "haut milieu bas", "{}", layout);
+    }
+
+    /// Document HOCR de deux pages, tel que généré pour un TIFF multipage.
+    pub(super) const TWO_PAGE_HOCR: &str = r#"<body>
+  <div class='ocr_page' id='page_1' title='image "scan.tif"; bbox 0 0 2480 3508; ppageno 0; scan_res 300 300'>
+   <div class='ocr_carea' id='block_1_1' title="bbox 100 100 600 140">
+    <p class='ocr_par' id='par_1_1' lang='fra' title="bbox 100 100 600 140">
+     <span class='ocr_line' id='line_1_1' title="bbox 100 100 600 140; baseline 0 -8">
+      <span class='ocrx_word' id='word_1_1' title='bbox 100 100 300 140; x_wconf 96'>Première</span>
+      <span class='ocrx_word' id='word_1_2' title='bbox 320 100 600 140; x_wconf 95'>page</span>
+     </span>
+    </p>
+   </div>
+  </div>
+  <div class='ocr_page' id='page_2' title='image "scan.tif"; bbox 0 0 1240 1754; ppageno 1'>
+   <div class='ocr_carea' id='block_2_1' title="bbox 50 60 400 100">
+    <p class='ocr_par' id='par_2_1' lang='fra' title="bbox 50 60 400 100">
+     <span class='ocr_line' id='line_2_1' title="bbox 50 60 400 100; baseline 0 -6">
+      <span class='ocrx_word' id='word_2_1' title='bbox 50 60 400 100; x_wconf 91'>Deuxième</span>
+     </span>
+    </p>
+   </div>
+  </div>
+</body>"#;
+
+    #[test]
+    fn test_parse_multiple_pages() {
+        let doc = HocrDocument::from_hocr_string(TWO_PAGE_HOCR).unwrap();
+
+        assert_eq!(doc.pages.len(), 2);
+        let first = &doc.pages[0];
+        assert_eq!(first.page_number, 0);
+        assert_eq!(first.bbox, BBox::new(0, 0, 2480, 3508));
+        assert_eq!(first.image_name.as_deref(), Some("scan.tif"));
+        assert_eq!(first.dpi, Some(300));
+        assert_eq!(first.paragraphs.len(), 1);
+
+        let second = &doc.pages[1];
+        assert_eq!(second.page_number, 1);
+        assert_eq!(second.bbox, BBox::new(0, 0, 1240, 1754));
+        // scan_res absent : résolution inconnue
+        assert_eq!(second.dpi, None);
+        assert_eq!(second.full_text(), "Deuxième");
+
+        assert_eq!(doc.paragraphs().count(), 2);
+        assert_eq!(
+            doc.full_text(),
+            format!("Première page{}Deuxième", PAGE_SEPARATOR)
+        );
+    }
+
+    #[test]
+    fn test_to_source_coordinates() {
+        let processed = HocrDocument::from_hocr_string(TWO_PAGE_HOCR).unwrap();
+
+        // Image agrandie deux fois par le prétraitement
+        let doc = processed.clone().to_source_coordinates(2.0);
+        assert_eq!(doc.scale_factor, 2.0);
+        let page = &doc.pages[0];
+        assert_eq!(page.bbox, BBox::new(0, 0, 1240, 1754));
+        assert_eq!(page.dpi, Some(150));
+        let line = &page.paragraphs[0].lines[0];
+        assert_eq!(line.bbox, BBox::new(50, 50, 250, 20));
+        assert_eq!(line.baseline.unwrap().offset, -4.0);
+        assert_eq!(line.words[1].bbox, BBox::new(160, 50, 140, 20));
+        assert_eq!(
+            doc.processed_bbox(&line.words[1].bbox),
+            processed.pages[0].paragraphs[0].lines[0].words[1].bbox
+        );
+        assert_eq!(doc.full_text(), processed.full_text());
+
+        // Facteur neutre ou invalide : document inchangé
+        assert_eq!(processed.clone().to_source_coordinates(1.0), processed);
+        assert_eq!(processed.clone().to_source_coordinates(0.0), processed);
+        assert_eq!(
+            processed.processed_bbox(&BBox::new(1, 2, 3, 4)),
+            BBox::new(1, 2, 3, 4)
+        );
+    }
+
+    #[test]
+    fn test_multi_page_report_has_page_headers() {
+        let report = HocrDocument::from_hocr_string(TWO_PAGE_HOCR)
+            .unwrap()
+            .generate_report();
+
+        assert!(report.contains("--- Page #1 (2480x3508, scan.tif, 300 dpi) ---"));
+        assert!(report.contains("--- Page #2 (1240x1754, scan.tif) ---"));
+        assert!(report.contains("Mot #1: \"Deuxième\""));
+    }
+
+    #[test]
+    fn test_single_page_document_is_flattened() {
+        let hocr = r#"<div class='ocr_page' id='page_1' title='bbox 0 0 200 100'>
+<p class='ocr_par' title="bbox 0 0 100 20">
+<span class='ocr_line' title="bbox 0 0 100 20">
+<span class='ocrx_word' title='bbox 0 0 50 20'>seule</span>
+</span>
+</p>
+</div>"#;
+        let doc = HocrDocument::from_hocr_string(hocr).unwrap();
+
+        assert_eq!(doc.pages.len(), 1);
+        assert_eq!(doc.pages[0].page_number, 0);
+        assert_eq!(doc.pages[0].image_name, None);
+        assert_eq!(doc.pages[0].dpi, None);
+        assert_eq!(doc.full_text(), "seule");
+        assert!(!doc.generate_report().contains("--- Page"));
+
+        // Sans balise ocr_page, les paragraphes sont rattachés à une page implicite
+        let mut manual = HocrDocument::new();
+        manual.add_paragraph(paragraph("seule", 0, 0, 100, 20));
+        assert_eq!(manual.pages.len(), 1);
+        assert_eq!(manual.paragraphs().count(), 1);
+        assert_eq!(manual.full_text(), doc.full_text());
+    }
+
+    #[test]
+    fn test_title_property() {
+        let title = r#"image "a b.png"; bbox 0 0 10 20; ppageno 3; scan_res 72 72"#;
+        assert_eq!(title_property(title, "image"), Some(r#""a b.png""#));
+        assert_eq!(title_property(title, "ppageno"), Some("3"));
+        assert_eq!(title_property(title, "scan_res"), Some("72 72"));
+        assert_eq!(title_property(title, "x_wconf"), None);
+    }
+
+    /// Document de deux lignes au format écrit par Tesseract.
+    pub(super) const WELL_FORMED_HOCR: &str =
+        "<div class='ocr_page' id='page_1' title='image \"scan.png\"; bbox 0 0 400 100; ppageno 0'>
+<div class='ocr_carea' id='block_1_1' title=\"bbox 10 10 390 90\">
+<p class='ocr_par' id='par_1_1' lang='fra' title=\"bbox 10 10 390 90\">
+<span class='ocr_line' id='line_1_1' title=\"bbox 10 10 390 40; baseline 0 -5; x_size 30\">
+<span class='ocrx_word' id='word_1_1' title='bbox 10 10 120 40; x_wconf 96'>Été</span>
+<span class='ocrx_word' id='word_1_2' title='bbox 130 10 390 40; x_wconf 91'>brûlant</span>
+</span>
+<span class='ocr_line' id='line_1_2' title=\"bbox 10 50 390 90; baseline 0 -5; x_size 30\">
+<span class='ocrx_word' id='word_1_3' title='bbox 10 50 200 90; x_wconf 88'>cœur</span>
+<span class='ocrx_word' id='word_1_4' title='bbox 210 50 390 90; x_wconf 93'>d&#39;été</span>
+</span>
+</p>
+</div>
+</div>";
+
+    #[test]
+    fn test_parse_well_formed_without_warnings() {
+        let (doc, warnings) = HocrDocument::from_hocr_string_with_warnings(WELL_FORMED_HOCR);
+
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(doc.full_text(), "Été brûlant\ncœur d'été");
+        assert_eq!(
+            doc,
+            HocrDocument::from_hocr_string(WELL_FORMED_HOCR).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_with_warnings_reports_skipped_elements() {
+        let hocr = "<span class='ocr_line' title='bbox 0 0 10 10'>
+<p class='ocr_par' title='bbox 0 0 100 20'>
+<span class='ocr_line' title='bbox a b c d'>
+<span class='ocrx_word' title='bbox 0 0 10 10'>orphelin</span>
+<span class='ocr_line' title='bbox 0 0 100 20'>
+<span class='ocrx_word' title=\"bbox 0 0 10 10\">sans fin
+<span class='ocrx_word' title=«bbox 0 0 10 10»>guillemets</span>
+<span class='ocrx_word' title='bbox 20 0 60 20'>gardé</span>";
+        let (doc, warnings) = HocrDocument::from_hocr_string_with_warnings(hocr);
+
+        assert_eq!(doc.full_text(), "gardé");
+        let lines: Vec<usize> = warnings.iter().map(|warning| warning.line).collect();
+        assert_eq!(lines, [2, 3, 4, 6, 7]);
+        assert!(warnings[0].message.contains("aucun paragraphe"));
+        assert!(warnings[1].message.contains("bbox"));
+        assert!(warnings[2].message.contains("aucune ligne"));
+        assert!(warnings[3].message.contains("</span>"));
+        assert_eq!(
+            warnings[4].to_string(),
+            "ligne 7 : mot ignoré : bbox absente ou invalide"
+        );
+    }
+
+    #[test]
+    fn test_extractors_on_malformed_lines() {
+        // Guillemet multi-octets, attribut non refermé, balise tronquée
+        assert_eq!(extract_title("<span title=é'bbox 0 0 1 1'>"), None);
+        assert_eq!(extract_title("<span title='bbox 0 0"), None);
+        assert_eq!(extract_title("<span title="), None);
+        assert_eq!(extract_bbox("<span title='bbox 0 0 é 1'>"), None);
+        assert_eq!(
+            extract_bbox("<span title='bbox 5 5 1 1'>"),
+            Some(BBox::new(5, 5, 0, 0))
+        );
+        assert_eq!(extract_word_text("</span>"), None);
+        assert_eq!(extract_word_text("<span>mot"), None);
+        assert_eq!(extract_word_text("<span></span>"), None);
+        assert_eq!(extract_confidence("x_wconf 999"), None);
+        assert_eq!(extract_lang("<span lang=ä>"), None);
+    }
+
+    #[test]
+    fn test_parse_truncated_documents() {
+        let full_words = HocrDocument::from_hocr_string(WELL_FORMED_HOCR)
+            .unwrap()
+            .paragraphs()
+            .flat_map(|paragraph| &paragraph.lines)
+            .map(|line| line.words.len())
+            .sum::<usize>();
+
+        for (end, _) in WELL_FORMED_HOCR.char_indices() {
+            let truncated = &WELL_FORMED_HOCR[..end];
+            let (doc, warnings) = HocrDocument::from_hocr_string_with_warnings(truncated);
+
+            let words = doc
+                .paragraphs()
+                .flat_map(|paragraph| &paragraph.lines)
+                .map(|line| line.words.len())
+                .sum::<usize>();
+            assert!(words <= full_words);
+
+            // Coupure au milieu d'un mot : il est ignoré et signalé
+            let last_line = truncated.lines().last().unwrap_or_default();
+            if last_line.contains("class='ocrx_word'") && !last_line.ends_with("</span>") {
+                assert!(
+                    !warnings.is_empty(),
+                    "aucun avertissement pour {:?}",
+                    last_line
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_random_input_never_panics() {
+        // Fragments de HOCR mélangés à des octets arbitraires
+        const FRAGMENTS: &[&str] = &[
+            "<div class='ocr_page' title='bbox 0 0 9 9; ppageno 0'>",
+            "<p class='ocr_par' title=\"bbox 0 0 9 9\">",
+            "<span class='ocr_line' title='bbox 0 0 9 9'>",
+            "<span class='ocrx_word' title='bbox 1 2 3 4; x_wconf 90'>",
+            " lang='",
+            "title=",
+            "bbox ",
+            "x_wconf ",
+            "</span>",
+            "'>",
+            "\n",
+            "é",
+            "😀",
+            "&amp;",
+            "4294967296",
+        ];
+
+        let mut rng = SplitMix64::new(0x5EED);
+        for _ in 0..500 {
+            let mut input = String::new();
+            for _ in 0..rng.next_u64() % 40 {
+                let value = rng.next_u64();
+                if value.is_multiple_of(3) {
+                    let bytes = value.to_le_bytes();
+                    input.push_str(&String::from_utf8_lossy(&bytes));
+                } else {
+                    input.push_str(FRAGMENTS[(value % FRAGMENTS.len() as u64) as usize]);
+                }
+            }
+
+            let (doc, warnings) = HocrDocument::from_hocr_string_with_warnings(&input);
+            assert!(warnings.iter().all(|warning| warning.line >= 1));
+            let _ = doc.full_text();
+        }
+    }
+
+    #[test]
+    fn test_parse_decodes_html_entities() {
+        let hocr = r#"<p class='ocr_par' title="bbox 0 0 100 20">
+<span class='ocr_line' title="bbox 0 0 100 20">
+<span class='ocrx_word' title='bbox 0 0 30 20'>R&amp;D</span>
+<span class='ocrx_word' title='bbox 40 0 60 20'>&lt;5%&gt;</span>
+<span class='ocrx_word' title='bbox 70 0 100 20'>l&#39;&amp;lt;</span>
+</span>
+</p>"#;
+        let doc = HocrDocument::from_hocr_string(hocr).unwrap();
+
+        assert_eq!(doc.full_text(), "R&D <5%> l'&lt;");
+    }
+
+    /// Paragraphe de lignes espacées de `gap` pixels, dont les mots ont les hauteurs données.
+    fn paragraph_with_heights(top: u32, gap: u32, lines: &[&[u32]]) -> HocrParagraph {
+        let mut paragraph = HocrParagraph::new(BBox::new(0, top, 1000, 500));
+        let mut y = top;
+        for heights in lines {
+            let line_height = heights.iter().copied().max().unwrap_or(0);
+            let mut line = HocrLine::new(BBox::new(0, y, 1000, line_height));
+            for (index, &height) in heights.iter().enumerate() {
+                let bbox = BBox::new(index as u32 * 100, y, 80, height);
+                line.add_word(HocrWord::new(bbox, "mot".to_string(), Some(90)));
+            }
+            paragraph.add_line(line);
+            y += line_height + gap;
+        }
+        paragraph
+    }
+
+    #[test]
+    fn test_estimate_text_metrics() {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph_with_heights(
+            0,
+            14,
+            &[&[30, 40, 40], &[40, 42], &[20, 40]],
+        ));
+        // L'écart entre deux paragraphes n'est pas un interligne
+        doc.add_paragraph(paragraph_with_heights(1000, 10, &[&[40], &[40]]));
+
+        let estimate = estimate_text_metrics(&doc);
+
+        assert_eq!(estimate.word_count, 9);
+        assert_eq!(estimate.median_word_height, 40.0);
+        // Écarts : 14, 14 (premier paragraphe) puis 10
+        assert_eq!(estimate.median_line_spacing, 14.0);
+        assert!((estimate.x_height - 26.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_estimate_text_metrics_empty_document() {
+        let estimate = estimate_text_metrics(&HocrDocument::new());
+        assert_eq!(estimate, TextMetricsEstimate::default());
+        assert_eq!(suggest_dpi(&estimate, 12.0), 0);
+    }
+
+    /// Mot de 10 px par caractère et 20 px de haut.
+    fn layout_word(x: u32, y: u32, text: &str) -> HocrWord {
+        let width = 10 * text.chars().count() as u32;
+        HocrWord::new(BBox::new(x, y, width, 20), text.to_string(), Some(90))
+    }
+
+    /// Paragraphe d'une ligne par entrée, chaque ligne faisant 20 px de haut.
+    fn layout_paragraph(lines: &[(u32, &[(u32, &str)])]) -> HocrParagraph {
+        let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 1000, 1000));
+        for &(y, words) in lines {
+            let mut line = HocrLine::new(BBox::new(words[0].0, y, 500, 20));
+            for &(x, text) in words {
+                line.add_word(layout_word(x, y, text));
+            }
+            paragraph.add_line(line);
+        }
+        paragraph
+    }
+
+    #[test]
+    fn test_layout_text_aligns_second_column() {
+        // Tesseract place souvent chaque colonne d'un ticket dans un paragraphe distinct
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(layout_paragraph(&[
+            (100, &[(50, "Pain")]),
+            (130, &[(50, "Lait"), (100, "entier")]),
+            (160, &[(50, "Beurre"), (120, "doux")]),
+        ]));
+        doc.add_paragraph(layout_paragraph(&[
+            (101, &[(350, "1,20")]),
+            (129, &[(352, "0,95")]),
+            (160, &[(349, "12,50")]),
+        ]));
+
+        let text = to_layout_text(&doc, &LayoutTextOptions::default());
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 3, "{}", text);
+        for (line, price) in lines.iter().zip(["1,20", "0,95", "12,50"]) {
+            assert_eq!(line.find(price), Some(30), "{:?}", line);
+        }
+        assert!(lines[1].starts_with("Lait entier "));
+        assert!(lines[2].starts_with("Beurre doux "));
+    }
+
+    #[test]
+    fn test_layout_text_single_column_has_normal_spacing() {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(layout_paragraph(&[
+            (0, &[(20, "Bonjour"), (100, "le"), (131, "monde")]),
+            (30, &[(22, "une"), (61, "phrase")]),
+            // Écart de plus d'une ligne et demie : ligne vide
+            (100, &[(19, "fin")]),
+        ]));
+
+        let text = to_layout_text(&doc, &LayoutTextOptions::default());
+
+        assert_eq!(text, "Bonjour le monde\nune phrase\n\nfin");
+        assert_eq!(doc.pages.len(), 1);
+    }
+
+    #[test]
+    fn test_layout_text_overlapping_boxes() {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(layout_paragraph(&[(
+            0,
+            &[(0, "Total"), (20, "TTC"), (20, "TTC"), (500, "9,99")],
+        )]));
+        doc.add_paragraph(layout_paragraph(&[(5, &[(10, "chevauche")])]));
+        // Mot vide ou de largeur nulle : ignoré sans division par zéro
+        doc.add_paragraph(layout_paragraph(&[(0, &[(700, " ")])]));
+
+        let text = to_layout_text(&doc, &LayoutTextOptions::default());
+
+        assert_eq!(text.lines().count(), 1, "{}", text);
+        assert!(text.starts_with("Total chevauche TTC TTC"), "{}", text);
+        assert!(text.trim_end().ends_with("9,99"));
+        assert_eq!(
+            to_layout_text(&HocrDocument::new(), &LayoutTextOptions::default()),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_layout_text_options() {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(layout_paragraph(&[(0, &[(0, "Pain"), (400, "1,20")])]));
+
+        let tabs = LayoutTextOptions {
+            use_tabs: true,
+            ..LayoutTextOptions::default()
+        };
+        assert_eq!(to_layout_text(&doc, &tabs), "Pain\t\t\t\t\t1,20");
+
+        // Largeur de 22 caractères : la page de 44 caractères est resserrée de moitié
+        let narrow = LayoutTextOptions {
+            max_line_width: Some(22),
+            ..LayoutTextOptions::default()
+        };
+        let text = to_layout_text(&doc, &narrow);
+        assert_eq!(text.find("1,20"), Some(20), "{:?}", text);
+
+        // Écart minimal très grand : mots simplement séparés par une espace
+        let joined = LayoutTextOptions {
+            min_gap: 100,
+            ..LayoutTextOptions::default()
+        };
+        assert_eq!(to_layout_text(&doc, &joined), "Pain 1,20");
+    }
+
+    #[test]
+    fn test_suggest_dpi() {
+        let estimate = TextMetricsEstimate {
+            x_height: 20.0,
+            ..TextMetricsEstimate::default()
+        };
+        // Corps de 40 px pour 12 points (1/6 de pouce) : 240 DPI
+        assert_eq!(suggest_dpi(&estimate, 12.0), 240);
+        assert_eq!(suggest_dpi(&estimate, 10.0), 288);
+        assert_eq!(suggest_dpi(&estimate, 0.0), 0);
+    }
+
+    /// Document de deux pages : deux paragraphes sur la première, une ligne vide comprise.
+    fn phrase_document() -> HocrDocument {
+        let line = |y: u32, words: &[(u32, &str)]| {
+            let mut line = HocrLine::new(BBox::new(0, y, 500, 20));
+            for &(x, text) in words {
+                line.add_word(HocrWord::new(
+                    BBox::new(x, y, 80, 20),
+                    text.to_string(),
+                    None,
+                ));
+            }
+            line
+        };
+        let mut first = HocrParagraph::new(BBox::new(0, 0, 500, 50));
+        first.add_line(line(0, &[(0, "Facture"), (100, "n°"), (200, "42")]));
+        first.add_line(line(30, &[]));
+        let mut second = HocrParagraph::new(BBox::new(0, 100, 500, 50));
+        second.add_line(line(100, &[(0, "Conditions"), (100, "génerales")]));
+        second.add_line(line(130, &[(0, "de"), (100, "vente")]));
+        let mut last = HocrParagraph::new(BBox::new(0, 0, 500, 20));
+        last.add_line(line(0, &[(0, "Signature")]));
+
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(first);
+        doc.add_paragraph(second);
+        let mut page = HocrPage::new(BBox::new(0, 0, 500, 500), 2);
+        page.add_paragraph(last);
+        doc.pages.push(page);
+        doc
+    }
+
+    #[test]
+    fn test_find_phrase_fuzzy_maps_words_across_two_words() {
+        let doc = phrase_document();
+
+        let matches = doc.find_phrase_fuzzy("Conditions générales", 1);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].found.distance, 1);
+        assert_eq!(matches[0].found.text, "Conditions génerales");
+        assert_eq!(
+            matches[0].words,
+            [BBox::new(0, 100, 80, 20), BBox::new(100, 100, 80, 20)]
+        );
+    }
+
+    #[test]
+    fn test_find_phrase_fuzzy_spans_match_full_text() {
+        let doc = phrase_document();
+        let (text, spans) = doc.text_with_word_spans();
+        assert_eq!(text, doc.full_text());
+
+        let chars: Vec<char> = text.chars().collect();
+        let words: Vec<String> = spans
+            .iter()
+            .map(|(start, end, _)| chars[*start..*end].iter().collect())
+            .collect();
+        assert_eq!(
+            words,
+            [
+                "Facture",
+                "n°",
+                "42",
+                "Conditions",
+                "génerales",
+                "de",
+                "vente",
+                "Signature"
+            ]
+        );
+
+        // Mot de la seconde page, après le séparateur de pages
+        let matches = doc.find_phrase_fuzzy("Signature", 0);
+        assert_eq!(matches[0].words, [BBox::new(0, 0, 80, 20)]);
+        assert!(doc.find_phrase_fuzzy("Conditions générales", 0).is_empty());
+    }
 }
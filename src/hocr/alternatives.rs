@@ -0,0 +1,430 @@
+//! Variantes de lecture des mots, tirées des choix du moteur LSTM.
+//!
+//! Tesseract classe en interne plusieurs symboles candidats pour chaque pas
+//! de temps du LSTM, mais ne restitue que la meilleure lecture. La crate
+//! `tesseract` ne donne pas accès à son itérateur de choix : les variantes
+//! viennent du binaire, lancé avec `-c lstm_choice_mode=2` et la sortie
+//! hOCR (voir [`OcrEngine::extract_with_alternatives()`](crate::ocr::OcrEngine::extract_with_alternatives)).
+//! Chaque mot y est suivi de groupes `ocrx_cinfo`, un par symbole, listant
+//! les glyphes candidats (`ocr_glyph`) et leur confiance (`x_confs`) :
+//!
+//! ```html
+//! <span class='ocrx_word' title='bbox 10 10 90 40; x_wconf 88'>c1ient
+//!  <span class='ocrx_cinfo'><span class='ocr_glyph' title='x_confs 99'>c</span></span>
+//!  <span class='ocrx_cinfo'><span class='ocr_glyph' title='x_confs 61'>1</span><span class='ocr_glyph' title='x_confs 38'>l</span></span>
+//!  ...</span>
+//! ```
+//!
+//! [`parse_alternatives()`] en tire les variantes d'un mot en remplaçant un
+//! seul symbole par l'un de ses concurrents, et [`rescore_with()`] choisit
+//! pour chaque mot la variante préférée d'un score externe (par exemple la
+//! plausibilité [`Corrector::plausibility()`](crate::postprocess::Corrector::plausibility)).
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::hocr::{parse_alternatives, rescore_with};
+//!
+//! let hocr = "<span class='ocr_line' title='bbox 0 0 90 40'>\n\
+//!     <span class='ocrx_word' title='bbox 10 10 90 40; x_wconf 88'>c1e\n\
+//!     <span class='ocrx_cinfo'><span class='ocr_glyph' title='x_confs 99'>c</span></span>\n\
+//!     <span class='ocrx_cinfo'><span class='ocr_glyph' title='x_confs 61'>1</span>\
+//!     <span class='ocr_glyph' title='x_confs 38'>l</span></span>\n\
+//!     <span class='ocrx_cinfo'><span class='ocr_glyph' title='x_confs 97'>e</span></span>\n\
+//!     </span>\n</span>";
+//!
+//! let words = parse_alternatives(hocr, 3);
+//! assert_eq!(words[0].alternatives[0].text, "cle");
+//!
+//! let text = rescore_with(&words, |word| if word == "cle" { 1.0 } else { 0.1 });
+//! assert_eq!(text, "cle");
+//! ```
+
+use super::{
+    BBox, decode_html_entities, extract_bbox, extract_confidence, extract_title, has_class,
+    title_property,
+};
+use serde::Serialize;
+
+/// Variante de lecture d'un mot.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Alternative {
+    /// Texte de la variante.
+    pub text: String,
+    /// Confiance estimée, sur l'échelle de [`WordAlternatives::confidence`] (0 à 100).
+    ///
+    /// Confiance du mot retenu multipliée par le rapport entre la confiance
+    /// du glyphe substitué et celle du glyphe qu'il remplace.
+    pub confidence: f32,
+}
+
+/// Mot reconnu et ses variantes de lecture.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WordAlternatives {
+    /// Texte retenu par Tesseract.
+    pub text: String,
+    /// Confiance du mot retenu (0-100), si Tesseract l'indique.
+    pub confidence: Option<u8>,
+    /// Rectangle délimitant le mot.
+    pub bbox: BBox,
+    /// Rang de la ligne du mot dans le document (à partir de 0).
+    pub line: usize,
+    /// Variantes, de la plus à la moins probable ; le texte retenu n'y figure pas.
+    pub alternatives: Vec<Alternative>,
+}
+
+impl WordAlternatives {
+    /// Candidats du mot : le texte retenu puis ses variantes, avec leur confiance.
+    ///
+    /// Un mot sans confiance est compté à 100.
+    pub fn candidates(&self) -> impl Iterator<Item = (&str, f32)> {
+        let confidence = self.confidence.map_or(100.0, f32::from);
+        std::iter::once((self.text.as_str(), confidence)).chain(
+            self.alternatives
+                .iter()
+                .map(|alternative| (alternative.text.as_str(), alternative.confidence)),
+        )
+    }
+}
+
+/// Glyphe candidat d'un symbole : texte et confiance (0-100).
+type Glyph = (String, f32);
+
+/// Mot en cours de lecture.
+struct WordBuilder {
+    bbox: BBox,
+    confidence: Option<u8>,
+    line: usize,
+    text: String,
+    /// Candidats de chaque symbole, dans l'ordre du mot.
+    symbols: Vec<Vec<Glyph>>,
+    /// `span` ouverts dans le mot et pas encore refermés.
+    depth: usize,
+    /// Indique si le texte qui suit la balise courante est celui d'un glyphe.
+    in_glyph: bool,
+}
+
+impl WordBuilder {
+    /// Mot terminé, avec au plus `max_alternatives` variantes.
+    fn finish(self, max_alternatives: usize) -> WordAlternatives {
+        let text = decode_html_entities(self.text.trim());
+        let confidence = self.confidence;
+        let alternatives = alternatives(&text, confidence, &self.symbols, max_alternatives);
+        WordAlternatives {
+            text,
+            confidence,
+            bbox: self.bbox,
+            line: self.line,
+            alternatives,
+        }
+    }
+}
+
+/// Variantes d'un mot obtenues en substituant un seul symbole.
+///
+/// La lecture de base enchaîne le meilleur glyphe de chaque symbole ; si
+/// elle diffère du texte retenu (correction par le dictionnaire de
+/// Tesseract), elle est proposée elle aussi.
+fn alternatives(
+    text: &str,
+    confidence: Option<u8>,
+    symbols: &[Vec<Glyph>],
+    max_alternatives: usize,
+) -> Vec<Alternative> {
+    let symbols: Vec<Vec<Glyph>> = symbols
+        .iter()
+        .filter(|choices| !choices.is_empty())
+        .map(|choices| {
+            let mut choices = choices.clone();
+            choices.sort_by(|a, b| b.1.total_cmp(&a.1));
+            choices
+        })
+        .collect();
+    if symbols.is_empty() || max_alternatives == 0 {
+        return Vec::new();
+    }
+
+    let best: Vec<&str> = symbols
+        .iter()
+        .map(|choices| choices[0].0.as_str())
+        .collect();
+    let word_confidence = match confidence {
+        Some(confidence) => f32::from(confidence),
+        None => {
+            symbols
+                .iter()
+                .map(|choices| choices[0].1 / 100.0)
+                .product::<f32>()
+                * 100.0
+        }
+    };
+
+    let mut candidates = vec![Alternative {
+        text: best.concat(),
+        confidence: word_confidence,
+    }];
+    for (index, choices) in symbols.iter().enumerate() {
+        let top = choices[0].1;
+        for (glyph, glyph_confidence) in &choices[1..] {
+            let mut path = best.clone();
+            path[index] = glyph;
+            let ratio = if top > 0.0 {
+                glyph_confidence / top
+            } else {
+                0.0
+            };
+            candidates.push(Alternative {
+                text: path.concat(),
+                confidence: word_confidence * ratio,
+            });
+        }
+    }
+
+    // Tri stable : à confiance égale, l'ordre des symboles est conservé
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    let mut seen = vec![text.to_string()];
+    candidates.retain(|candidate| {
+        let fresh = !seen.contains(&candidate.text);
+        if fresh {
+            seen.push(candidate.text.clone());
+        }
+        fresh
+    });
+    candidates.truncate(max_alternatives);
+    candidates
+}
+
+/// Lit les mots d'un hOCR produit avec `lstm_choice_mode=2` et leurs variantes.
+///
+/// Les mots sans groupe `ocrx_cinfo` (hOCR ordinaire, moteur historique)
+/// n'ont aucune variante. Les mots sans bbox valide sont ignorés.
+///
+/// # Arguments
+///
+/// * `hocr` - Contenu du fichier hOCR
+/// * `max_alternatives` - Nombre maximal de variantes conservées par mot
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::hocr::parse_alternatives;
+///
+/// let hocr = "<span class='ocrx_word' title='bbox 0 0 40 20; x_wconf 95'>Total</span>";
+/// let words = parse_alternatives(hocr, 5);
+/// assert_eq!(words[0].text, "Total");
+/// assert!(words[0].alternatives.is_empty());
+/// ```
+pub fn parse_alternatives(hocr: &str, max_alternatives: usize) -> Vec<WordAlternatives> {
+    let mut words = Vec::new();
+    let mut line: Option<usize> = None;
+    let mut word: Option<WordBuilder> = None;
+
+    // Chaque morceau commence par une balise et se termine par le texte qui la suit
+    for piece in hocr.split('<').skip(1) {
+        let Some((tag, trailing)) = piece.split_once('>') else {
+            continue;
+        };
+
+        if let Some(closing) = tag.strip_prefix('/') {
+            if let Some(mut current) = word.take() {
+                let is_span = closing.trim() == "span";
+                if is_span && current.depth == 0 {
+                    words.push(current.finish(max_alternatives));
+                    continue;
+                }
+                if is_span {
+                    current.depth -= 1;
+                    current.in_glyph = false;
+                }
+                if current.depth == 0 {
+                    current.text.push_str(trailing);
+                }
+                word = Some(current);
+            }
+            continue;
+        }
+
+        if let Some(ref mut current) = word {
+            if tag.starts_with("span") {
+                current.depth += 1;
+                if has_class(tag, "ocrx_cinfo") {
+                    current.symbols.push(Vec::new());
+                } else if has_class(tag, "ocr_glyph") {
+                    let confidence = extract_title(tag)
+                        .and_then(|title| title_property(title, "x_confs"))
+                        .and_then(|value| value.split_whitespace().next()?.parse().ok())
+                        .unwrap_or(0.0);
+                    if let Some(symbol) = current.symbols.last_mut() {
+                        symbol.push((decode_html_entities(trailing), confidence));
+                    }
+                    current.in_glyph = true;
+                    continue;
+                }
+            }
+            if current.depth == 0 && !current.in_glyph {
+                current.text.push_str(trailing);
+            }
+        } else if has_class(tag, "ocr_line") {
+            line = Some(line.map_or(0, |index| index + 1));
+        } else if has_class(tag, "ocrx_word")
+            && let Some(bbox) = extract_bbox(tag)
+        {
+            word = Some(WordBuilder {
+                bbox,
+                confidence: extract_confidence(tag),
+                line: line.unwrap_or(0),
+                text: trailing.to_string(),
+                symbols: Vec::new(),
+                depth: 0,
+                in_glyph: false,
+            });
+        }
+    }
+    words
+}
+
+/// Choisit pour chaque mot la variante préférée d'un score externe.
+///
+/// Chaque candidat (texte retenu ou variante) reçoit le score
+/// `confiance / 100 × scorer(texte)` ; le meilleur l'emporte, le texte
+/// retenu par Tesseract en cas d'égalité. Un score externe nettement plus
+/// favorable à une variante suffit donc à la faire choisir.
+///
+/// # Arguments
+///
+/// * `alternatives` - Mots et variantes (voir [`parse_alternatives()`])
+/// * `scorer` - Score d'un mot, entre 0 (invraisemblable) et 1 (certain)
+///
+/// # Retour
+///
+/// Le texte recomposé : mots d'une ligne séparés par une espace, lignes
+/// séparées par un retour à la ligne.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::hocr::{BBox, rescore_with, WordAlternatives};
+///
+/// let word = WordAlternatives {
+///     text: "Tota1".into(),
+///     confidence: Some(80),
+///     bbox: BBox::new(0, 0, 50, 20),
+///     line: 0,
+///     alternatives: Vec::new(),
+/// };
+/// assert_eq!(rescore_with(&[word], |_| 0.5), "Tota1");
+/// ```
+pub fn rescore_with(alternatives: &[WordAlternatives], scorer: impl Fn(&str) -> f32) -> String {
+    let mut text = String::new();
+    let mut previous_line = None;
+    for word in alternatives {
+        let mut best = (word.text.as_str(), f32::NEG_INFINITY);
+        for (candidate, confidence) in word.candidates() {
+            let score = confidence / 100.0 * scorer(candidate);
+            if score > best.1 {
+                best = (candidate, score);
+            }
+        }
+
+        match previous_line {
+            Some(line) if line == word.line => text.push(' '),
+            Some(_) => text.push('\n'),
+            None => {}
+        }
+        previous_line = Some(word.line);
+        text.push_str(best.0);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// hOCR de Tesseract 5 avec `-c lstm_choice_mode=2` (deux lignes, trois mots).
+    const CHOICES_HOCR: &str = r#"<div class='ocr_page' id='page_1' title='image "facture.png"; bbox 0 0 400 120; ppageno 0; scan_res 300 300'>
+   <div class='ocr_carea' id='block_1_1' title="bbox 10 10 390 100">
+    <p class='ocr_par' id='par_1_1' lang='fra' title="bbox 10 10 390 100">
+     <span class='ocr_line' id='line_1_1' title="bbox 10 10 390 40; baseline 0 -6; x_size 30">
+      <span class='ocrx_word' id='word_1_1' title='bbox 10 10 150 40; x_wconf 91'>Facture
+       <span class='ocrx_cinfo' id='lstm_choices_1_1_0'><span class='ocr_glyph' id='choice_1_1_0' title='x_confs 99'>F</span><span class='ocr_glyph' id='choice_1_1_1' title='x_confs 1'>E</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_1_1'><span class='ocr_glyph' id='choice_1_1_2' title='x_confs 98'>a</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_1_2'><span class='ocr_glyph' id='choice_1_1_3' title='x_confs 97'>c</span><span class='ocr_glyph' id='choice_1_1_4' title='x_confs 2'>e</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_1_3'><span class='ocr_glyph' id='choice_1_1_5' title='x_confs 99'>t</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_1_4'><span class='ocr_glyph' id='choice_1_1_6' title='x_confs 99'>u</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_1_5'><span class='ocr_glyph' id='choice_1_1_7' title='x_confs 99'>r</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_1_6'><span class='ocr_glyph' id='choice_1_1_8' title='x_confs 98'>e</span></span></span>
+      <span class='ocrx_word' id='word_1_2' title='bbox 170 10 390 40; x_wconf 72'>c1ient
+       <span class='ocrx_cinfo' id='lstm_choices_1_2_0'><span class='ocr_glyph' id='choice_1_2_0' title='x_confs 99'>c</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_2_1'><span class='ocr_glyph' id='choice_1_2_1' title='x_confs 60'>1</span><span class='ocr_glyph' id='choice_1_2_2' title='x_confs 36'>l</span><span class='ocr_glyph' id='choice_1_2_3' title='x_confs 3'>I</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_2_2'><span class='ocr_glyph' id='choice_1_2_4' title='x_confs 97'>i</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_2_3'><span class='ocr_glyph' id='choice_1_2_5' title='x_confs 95'>e</span><span class='ocr_glyph' id='choice_1_2_6' title='x_confs 4'>c</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_2_4'><span class='ocr_glyph' id='choice_1_2_7' title='x_confs 98'>n</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_2_5'><span class='ocr_glyph' id='choice_1_2_8' title='x_confs 99'>t</span></span></span>
+     </span>
+     <span class='ocr_line' id='line_1_2' title="bbox 10 60 200 100; baseline 0 -6; x_size 30">
+      <span class='ocrx_word' id='word_1_3' title='bbox 10 60 90 100; x_wconf 96'><strong>R&amp;D</strong>
+       <span class='ocrx_cinfo' id='lstm_choices_1_3_0'><span class='ocr_glyph' id='choice_1_3_0' title='x_confs 99'>R</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_3_1'><span class='ocr_glyph' id='choice_1_3_1' title='x_confs 96'>&amp;</span></span>
+       <span class='ocrx_cinfo' id='lstm_choices_1_3_2'><span class='ocr_glyph' id='choice_1_3_2' title='x_confs 99'>D</span></span></span>
+     </span>
+    </p>
+   </div>
+  </div>"#;
+
+    #[test]
+    fn test_parse_captured_choices() {
+        let words = parse_alternatives(CHOICES_HOCR, 5);
+
+        let texts: Vec<&str> = words.iter().map(|word| word.text.as_str()).collect();
+        assert_eq!(texts, ["Facture", "c1ient", "R&D"]);
+        assert_eq!(
+            words.iter().map(|word| word.line).collect::<Vec<_>>(),
+            [0, 0, 1]
+        );
+
+        let client = &words[1];
+        assert_eq!(client.confidence, Some(72));
+        assert_eq!(client.bbox, BBox::new(170, 10, 220, 30));
+        let variants: Vec<&str> = client
+            .alternatives
+            .iter()
+            .map(|alternative| alternative.text.as_str())
+            .collect();
+        assert_eq!(variants, ["client", "cIient", "c1icnt"]);
+        assert!((client.alternatives[0].confidence - 72.0 * 36.0 / 60.0).abs() < 1e-3);
+
+        assert_eq!(words[0].alternatives.len(), 2);
+        // Un seul candidat par symbole : pas de variante
+        assert!(words[2].alternatives.is_empty());
+    }
+
+    #[test]
+    fn test_rescoring_flips_preferred_alternative() {
+        let words = parse_alternatives(CHOICES_HOCR, 5);
+        let dictionary = ["Facture", "client", "R&D"];
+        let scorer = |word: &str| {
+            if dictionary.contains(&word) {
+                1.0
+            } else {
+                0.05
+            }
+        };
+
+        assert_eq!(rescore_with(&words, scorer), "Facture client\nR&D");
+        // Score neutre : les lectures de Tesseract sont conservées
+        assert_eq!(rescore_with(&words, |_| 1.0), "Facture c1ient\nR&D");
+    }
+
+    #[test]
+    fn test_max_alternatives_truncates() {
+        let client = |max| parse_alternatives(CHOICES_HOCR, max).remove(1).alternatives;
+
+        assert_eq!(client(0), []);
+        let one = client(1);
+        assert_eq!(one.len(), 1);
+        assert_eq!(one[0].text, "client");
+        assert_eq!(client(2).len(), 2);
+        assert_eq!(client(10).len(), 3);
+    }
+}
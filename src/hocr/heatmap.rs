@@ -0,0 +1,477 @@
+//! Carte de chaleur des confiances des mots.
+//!
+//! [`confidence_heatmap()`] colore le rectangle de chaque mot selon sa
+//! confiance Tesseract, du rouge (peu fiable) au vert (fiable) en passant
+//! par le jaune, pour repérer d'un coup d'œil les zones à relire. Les
+//! zones sans texte ne sont pas modifiées et les mots sans confiance sont
+//! peints dans un gris neutre.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! # #[cfg(feature = "tesseract")]
+//! # {
+//! use text_recognition::hocr::{HeatmapOptions, confidence_heatmap};
+//! use text_recognition::ocr::OcrEngine;
+//! use text_recognition::config::OcrConfig;
+//! use std::path::Path;
+//!
+//! let path = Path::new("facture.png");
+//! let engine = OcrEngine::new(OcrConfig::default())?;
+//! let doc = engine.extract_hocr_document(path)?;
+//!
+//! let options = HeatmapOptions {
+//!     labels: true,
+//!     ..HeatmapOptions::default()
+//! };
+//! confidence_heatmap(&image::open(path)?, &doc, &options).save("facture.heatmap.png")?;
+//! # }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use super::HocrDocument;
+use crate::redaction::clamp_to_image;
+use image::{DynamicImage, Rgb, Rgba, RgbaImage};
+
+/// Couleur des mots sans confiance par défaut (gris neutre).
+pub const DEFAULT_MISSING_COLOR: Rgb<u8> = Rgb([128, 128, 128]);
+
+/// Couleur des étiquettes de confiance.
+const LABEL_COLOR: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+/// Largeur d'un chiffre de la police embarquée, en pixels.
+const GLYPH_WIDTH: u32 = 3;
+
+/// Hauteur d'un chiffre de la police embarquée, en pixels.
+const GLYPH_HEIGHT: u32 = 5;
+
+/// Police 3×5 des chiffres 0 à 9 : une ligne par octet, bit 2 à gauche.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Support sur lequel la carte de chaleur est dessinée.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeatmapCanvas {
+    /// Image source, les rectangles étant fondus par-dessus.
+    #[default]
+    Original,
+
+    /// Fond blanc aux dimensions de l'image source.
+    Blank,
+}
+
+/// Options de [`confidence_heatmap()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeatmapOptions {
+    /// Dégradé de couleurs : paires (confiance, couleur) triées par confiance
+    /// croissante.
+    ///
+    /// La couleur d'un mot est interpolée linéairement entre les deux
+    /// paires qui encadrent sa confiance ; en dehors du dégradé, la couleur
+    /// de l'extrémité la plus proche est utilisée. Par défaut : rouge à 0,
+    /// jaune à 50, vert à 100.
+    pub gradient: Vec<(u8, Rgb<u8>)>,
+
+    /// Opacité des rectangles, entre 0 (invisibles) et 1 (opaques).
+    ///
+    /// Les valeurs hors de cet intervalle sont ramenées à la borne la plus
+    /// proche. Par défaut : 0.4.
+    pub opacity: f32,
+
+    /// Support du dessin (image source par défaut).
+    pub canvas: HeatmapCanvas,
+
+    /// Inscrire la confiance de chaque mot dans son rectangle.
+    ///
+    /// Le nombre est écrit en noir dans le coin supérieur gauche, avec une
+    /// police bitmap de 3×5 pixels agrandie selon la hauteur du rectangle.
+    /// Les rectangles trop petits pour le contenir restent sans étiquette.
+    pub labels: bool,
+
+    /// Couleur des mots sans confiance.
+    pub missing_color: Rgb<u8>,
+}
+
+impl Default for HeatmapOptions {
+    fn default() -> Self {
+        Self {
+            gradient: vec![
+                (0, Rgb([255, 0, 0])),
+                (50, Rgb([255, 255, 0])),
+                (100, Rgb([0, 255, 0])),
+            ],
+            opacity: 0.4,
+            canvas: HeatmapCanvas::Original,
+            labels: false,
+            missing_color: DEFAULT_MISSING_COLOR,
+        }
+    }
+}
+
+impl HeatmapOptions {
+    /// Couleur associée à une confiance, selon [`gradient`](Self::gradient).
+    ///
+    /// Un dégradé vide donne [`missing_color`](Self::missing_color).
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use image::Rgb;
+    /// use text_recognition::hocr::HeatmapOptions;
+    ///
+    /// let options = HeatmapOptions::default();
+    /// assert_eq!(options.color_for(0), Rgb([255, 0, 0]));
+    /// assert_eq!(options.color_for(25), Rgb([255, 128, 0]));
+    /// assert_eq!(options.color_for(100), Rgb([0, 255, 0]));
+    /// ```
+    pub fn color_for(&self, confidence: u8) -> Rgb<u8> {
+        let (Some(first), Some(last)) = (self.gradient.first(), self.gradient.last()) else {
+            return self.missing_color;
+        };
+        if confidence <= first.0 {
+            return first.1;
+        }
+        if confidence >= last.0 {
+            return last.1;
+        }
+
+        for pair in self.gradient.windows(2) {
+            let ((low, from), (high, to)) = (pair[0], pair[1]);
+            if confidence <= high && high > low {
+                let t = f32::from(confidence - low) / f32::from(high - low);
+                return Rgb(std::array::from_fn(|i| lerp(from.0[i], to.0[i], t)));
+            }
+        }
+        last.1
+    }
+}
+
+/// Dessine la carte de chaleur des confiances des mots de `doc`.
+///
+/// Chaque rectangle de mot (tronqué aux bords de l'image) est fondu avec la
+/// couleur de sa confiance à l'opacité demandée ; les pixels hors de tout
+/// rectangle restent ceux du support. L'image retournée est en RGBA : sur
+/// l'image source, ces pixels sont donc ceux de la source convertis en RGBA.
+///
+/// Les coordonnées du HOCR doivent correspondre à `image` : utiliser le
+/// HOCR produit sur cette même image, sans redimensionnement.
+///
+/// # Arguments
+///
+/// * `image` - Image source (fournit la taille, et le fond avec [`HeatmapCanvas::Original`])
+/// * `doc` - Document HOCR extrait de cette image
+/// * `options` - Dégradé, opacité, support et étiquettes
+///
+/// # Retour
+///
+/// Une nouvelle image RGBA aux dimensions de `image`.
+pub fn confidence_heatmap(
+    image: &DynamicImage,
+    doc: &HocrDocument,
+    options: &HeatmapOptions,
+) -> DynamicImage {
+    let mut canvas = match options.canvas {
+        HeatmapCanvas::Original => image.to_rgba8(),
+        HeatmapCanvas::Blank => {
+            RgbaImage::from_pixel(image.width(), image.height(), Rgba([255, 255, 255, 255]))
+        }
+    };
+    let opacity = if options.opacity.is_nan() {
+        0.0
+    } else {
+        options.opacity.clamp(0.0, 1.0)
+    };
+
+    let words = doc
+        .paragraphs()
+        .flat_map(|paragraph| &paragraph.lines)
+        .flat_map(|line| &line.words);
+    for word in words {
+        let Some(rect) = clamp_to_image(&word.bbox, canvas.width(), canvas.height()) else {
+            continue;
+        };
+        let color = match word.confidence {
+            Some(confidence) => options.color_for(confidence),
+            None => options.missing_color,
+        };
+        blend(&mut canvas, rect, color, opacity);
+        if options.labels
+            && let Some(confidence) = word.confidence
+        {
+            draw_label(&mut canvas, rect, &confidence.to_string());
+        }
+    }
+
+    DynamicImage::ImageRgba8(canvas)
+}
+
+/// Interpolation linéaire entre deux composantes, `t` entre 0 et 1.
+fn lerp(from: u8, to: u8, t: f32) -> u8 {
+    (f32::from(from) + (f32::from(to) - f32::from(from)) * t).round() as u8
+}
+
+/// Fond `color` sur le rectangle `(x0, y0, x1, y1)` avec l'opacité `alpha`.
+///
+/// Le canal alpha de l'image est conservé.
+fn blend(
+    canvas: &mut RgbaImage,
+    (x0, y0, x1, y1): (u32, u32, u32, u32),
+    color: Rgb<u8>,
+    alpha: f32,
+) {
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let pixel = canvas.get_pixel_mut(x, y);
+            for (channel, target) in pixel.0.iter_mut().zip(color.0) {
+                *channel = lerp(*channel, target, alpha);
+            }
+        }
+    }
+}
+
+/// Écrit `text` (des chiffres) dans le coin supérieur gauche du rectangle.
+///
+/// La police est agrandie d'un facteur entier pour occuper au plus la
+/// moitié de la hauteur du rectangle, avec une marge d'un pixel ; rien
+/// n'est écrit si le texte ne tient pas à la taille 1.
+fn draw_label(canvas: &mut RgbaImage, (x0, y0, x1, y1): (u32, u32, u32, u32), text: &str) {
+    let digits: Vec<&[u8; 5]> = text
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|digit| &DIGITS[digit as usize])
+        .collect();
+    if digits.is_empty() {
+        return;
+    }
+
+    // Largeur du texte à la taille 1 : un pixel d'espace entre les chiffres
+    let text_width = digits.len() as u32 * (GLYPH_WIDTH + 1) - 1;
+    let (box_width, box_height) = (x1 - x0, y1 - y0);
+    let fit_width = box_width.saturating_sub(2) / text_width;
+    let fit_height = box_height.saturating_sub(2) / GLYPH_HEIGHT;
+    let scale = fit_width
+        .min(fit_height)
+        .min((box_height / 2 / GLYPH_HEIGHT).max(1));
+    if scale == 0 {
+        return;
+    }
+
+    for (index, rows) in digits.iter().enumerate() {
+        let left = x0 + 1 + index as u32 * (GLYPH_WIDTH + 1) * scale;
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                    continue;
+                }
+                let px = left + column * scale;
+                let py = y0 + 1 + row as u32 * scale;
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        canvas.put_pixel(px + dx, py + dy, LABEL_COLOR);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hocr::{BBox, HocrLine, HocrParagraph, HocrWord};
+    use image::{GenericImageView, RgbImage};
+
+    const OPACITY: f32 = 0.5;
+
+    /// Document d'une ligne : un mot peu sûr, un mot sûr et un mot sans confiance.
+    fn sample_document() -> HocrDocument {
+        let mut line = HocrLine::new(BBox::new(10, 10, 150, 20));
+        line.add_word(HocrWord::new(
+            BBox::new(10, 10, 40, 20),
+            "flou".into(),
+            Some(10),
+        ));
+        line.add_word(HocrWord::new(
+            BBox::new(60, 10, 40, 20),
+            "net".into(),
+            Some(95),
+        ));
+        line.add_word(HocrWord::new(BBox::new(110, 10, 40, 20), "?".into(), None));
+        let mut paragraph = HocrParagraph::new(BBox::new(10, 10, 150, 20));
+        paragraph.add_line(line);
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph);
+        doc
+    }
+
+    /// Image source en dégradé, pour que chaque pixel soit distinct.
+    fn sample_image() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(200, 50, |x, y| {
+            Rgb([200 + (x % 50) as u8, 180 + (y % 70) as u8, 220])
+        }))
+    }
+
+    fn options() -> HeatmapOptions {
+        HeatmapOptions {
+            opacity: OPACITY,
+            ..HeatmapOptions::default()
+        }
+    }
+
+    fn inside(bbox: &BBox, x: u32, y: u32) -> bool {
+        x >= bbox.x && x < bbox.x + bbox.width && y >= bbox.y && y < bbox.y + bbox.height
+    }
+
+    /// Proportion des pixels de `bbox` vérifiant `predicate`.
+    fn share(image: &DynamicImage, bbox: &BBox, predicate: impl Fn([u8; 4]) -> bool) -> f64 {
+        let pixels: Vec<_> = image
+            .pixels()
+            .filter(|&(x, y, _)| inside(bbox, x, y))
+            .collect();
+        let matching = pixels.iter().filter(|(_, _, p)| predicate(p.0)).count();
+        matching as f64 / pixels.len() as f64
+    }
+
+    #[test]
+    fn test_low_confidence_is_red_and_high_is_green() {
+        let heatmap = confidence_heatmap(&sample_image(), &sample_document(), &options());
+
+        let red = share(&heatmap, &BBox::new(10, 10, 40, 20), |[r, g, b, _]| {
+            r > g && r > b
+        });
+        let green = share(&heatmap, &BBox::new(60, 10, 40, 20), |[r, g, b, _]| {
+            g > r && g > b
+        });
+        assert!(
+            red > 0.95,
+            "mot à 10 % : {:.2} des pixels à dominante rouge",
+            red
+        );
+        assert!(
+            green > 0.95,
+            "mot à 95 % : {:.2} des pixels à dominante verte",
+            green
+        );
+    }
+
+    #[test]
+    fn test_pixels_outside_words_are_untouched() {
+        let source = sample_image();
+        let doc = sample_document();
+        let heatmap = confidence_heatmap(&source, &doc, &options());
+        let words: Vec<BBox> = doc
+            .paragraphs()
+            .flat_map(|p| &p.lines)
+            .flat_map(|l| &l.words)
+            .map(|w| w.bbox.clone())
+            .collect();
+
+        assert_eq!(heatmap.dimensions(), source.dimensions());
+        for (x, y, pixel) in heatmap.pixels() {
+            if !words.iter().any(|bbox| inside(bbox, x, y)) {
+                assert_eq!(pixel, source.get_pixel(x, y), "pixel ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_missing_confidence_is_gray() {
+        let options = HeatmapOptions {
+            opacity: 1.0,
+            canvas: HeatmapCanvas::Blank,
+            ..HeatmapOptions::default()
+        };
+        let heatmap = confidence_heatmap(&sample_image(), &sample_document(), &options);
+
+        assert_eq!(heatmap.get_pixel(120, 15), Rgba([128, 128, 128, 255]));
+        // Fond blanc hors des mots
+        assert_eq!(heatmap.get_pixel(5, 5), Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_gradient_interpolation() {
+        let options = HeatmapOptions::default();
+
+        assert_eq!(options.color_for(50), Rgb([255, 255, 0]));
+        assert_eq!(options.color_for(75), Rgb([128, 255, 0]));
+
+        let custom = HeatmapOptions {
+            gradient: vec![(20, Rgb([0, 0, 0])), (30, Rgb([100, 100, 100]))],
+            ..HeatmapOptions::default()
+        };
+        assert_eq!(custom.color_for(5), Rgb([0, 0, 0]));
+        assert_eq!(custom.color_for(25), Rgb([50, 50, 50]));
+        assert_eq!(custom.color_for(90), Rgb([100, 100, 100]));
+
+        let empty = HeatmapOptions {
+            gradient: Vec::new(),
+            ..HeatmapOptions::default()
+        };
+        assert_eq!(empty.color_for(42), DEFAULT_MISSING_COLOR);
+    }
+
+    #[test]
+    fn test_labels_draw_confidence_digits() {
+        let plain = confidence_heatmap(&sample_image(), &sample_document(), &options());
+        let labelled = confidence_heatmap(
+            &sample_image(),
+            &sample_document(),
+            &HeatmapOptions {
+                labels: true,
+                ..options()
+            },
+        );
+
+        let black = |image: &DynamicImage, bbox: &BBox| share(image, bbox, |p| p == LABEL_COLOR.0);
+        let low = BBox::new(10, 10, 40, 20);
+        assert_eq!(black(&plain, &low), 0.0);
+        assert!(black(&labelled, &low) > 0.0);
+        // Pas d'étiquette pour un mot sans confiance
+        let missing = BBox::new(110, 10, 40, 20);
+        assert_eq!(black(&labelled, &missing), 0.0);
+        // Un rectangle trop petit reste sans étiquette
+        let mut tiny = HocrLine::new(BBox::new(0, 0, 4, 4));
+        tiny.add_word(HocrWord::new(BBox::new(0, 0, 4, 4), "x".into(), Some(80)));
+        let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 4, 4));
+        paragraph.add_line(tiny);
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph);
+        let heatmap = confidence_heatmap(
+            &sample_image(),
+            &doc,
+            &HeatmapOptions {
+                labels: true,
+                ..options()
+            },
+        );
+        assert_eq!(black(&heatmap, &BBox::new(0, 0, 4, 4)), 0.0);
+    }
+
+    #[test]
+    fn test_boxes_are_clamped_to_image() {
+        let mut line = HocrLine::new(BBox::new(190, 40, 50, 50));
+        line.add_word(HocrWord::new(
+            BBox::new(190, 40, 50, 50),
+            "bord".into(),
+            Some(0),
+        ));
+        let mut paragraph = HocrParagraph::new(BBox::new(190, 40, 50, 50));
+        paragraph.add_line(line);
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph);
+
+        let heatmap = confidence_heatmap(&sample_image(), &doc, &options());
+        let [r, g, b, _] = heatmap.get_pixel(199, 49).0;
+        assert!(r > g && r > b);
+    }
+}
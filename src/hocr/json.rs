@@ -0,0 +1,368 @@
+//! Enregistrement d'un document HOCR analysé en JSON versionné.
+//!
+//! Relire un hOCR est lent et perd les éléments ignorés à chaque analyse ;
+//! le document analysé peut être conservé tel quel, pour un cache ou un
+//! outil externe. Le JSON reprend la structure de [`HocrDocument`] (pages,
+//! paragraphes, lignes, mots) et porte un champ `format_version` (voir
+//! [`HOCR_JSON_FORMAT_VERSION`]) :
+//!
+//! ```json
+//! {"format_version": 1, "pages": [{"bbox": {...}, "page_number": 0, "paragraphs": [...]}], "order": "natural"}
+//! ```
+//!
+//! Les champs optionnels ajoutés au fil des versions (langue et score
+//! fusionné d'un mot, sens d'écriture, angle et ligne de base d'une ligne,
+//! image et résolution d'une page, ordre de tri, facteur d'échelle) prennent leur valeur par
+//! défaut lorsqu'ils sont absents : un JSON écrit par une version
+//! antérieure reste lisible. Un `format_version` plus récent que celui de
+//! la bibliothèque est refusé ([`HocrJsonError::UnsupportedVersion`]).
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::hocr::{BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord};
+//!
+//! let mut line = HocrLine::new(BBox::new(0, 0, 100, 20));
+//! line.add_word(HocrWord::new(BBox::new(0, 0, 60, 20), "Facture".to_string(), Some(94)));
+//! let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 100, 20));
+//! paragraph.add_line(line);
+//! let mut doc = HocrDocument::new();
+//! doc.add_paragraph(paragraph);
+//!
+//! let json = doc.to_json()?;
+//! assert!(json.starts_with(r#"{"format_version":1,"#));
+//! assert_eq!(HocrDocument::from_json(&json)?, doc);
+//! # Ok::<(), text_recognition::hocr::HocrJsonError>(())
+//! ```
+
+use super::{HocrDocument, HocrPage, SortOrder};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::path::{Path, PathBuf};
+
+/// Version du format JSON écrit par [`HocrDocument::to_json()`].
+///
+/// Incrémentée seulement pour un changement incompatible ; un champ
+/// optionnel nouveau ne la change pas.
+pub const HOCR_JSON_FORMAT_VERSION: u32 = 1;
+
+/// Erreur de l'export ou de l'import JSON d'un document HOCR.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HocrJsonError {
+    /// Le fichier n'a pas pu être lu ou écrit.
+    #[error("fichier JSON HOCR '{}' inaccessible : {message}", path.display())]
+    Io {
+        /// Chemin du fichier.
+        path: PathBuf,
+        /// Description de l'erreur d'entrée/sortie.
+        message: String,
+    },
+
+    /// Le texte n'est pas un document HOCR JSON valide.
+    #[error("JSON HOCR invalide : {message}")]
+    Invalid {
+        /// Description de l'erreur de syntaxe ou de structure.
+        message: String,
+    },
+
+    /// Le champ `format_version` est absent.
+    #[error("JSON HOCR invalide : champ format_version absent")]
+    MissingVersion,
+
+    /// Le document a été écrit dans une version de format non prise en charge.
+    #[error(
+        "version de format JSON HOCR {found} non prise en charge (version {supported} au plus)"
+    )]
+    UnsupportedVersion {
+        /// Version lue dans le document.
+        found: u64,
+        /// Version la plus récente lue par la bibliothèque.
+        supported: u32,
+    },
+}
+
+impl HocrJsonError {
+    fn io(path: &Path, error: &std::io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        }
+    }
+
+    fn invalid(error: &serde_json::Error) -> Self {
+        Self::Invalid {
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Vérifie qu'une version de format est lisible.
+fn check_version(found: u64) -> Result<(), HocrJsonError> {
+    if (1..=u64::from(HOCR_JSON_FORMAT_VERSION)).contains(&found) {
+        Ok(())
+    } else {
+        Err(HocrJsonError::UnsupportedVersion {
+            found,
+            supported: HOCR_JSON_FORMAT_VERSION,
+        })
+    }
+}
+
+/// Forme JSON d'un document, à l'écriture (sans copie des pages).
+#[derive(Serialize)]
+struct DocumentRef<'a> {
+    format_version: u32,
+    pages: &'a [HocrPage],
+    order: &'a SortOrder,
+    scale_factor: f64,
+}
+
+/// Forme JSON d'un document, à la lecture.
+#[derive(Deserialize)]
+struct DocumentData {
+    format_version: u64,
+    #[serde(default)]
+    pages: Vec<HocrPage>,
+    #[serde(default)]
+    order: SortOrder,
+    #[serde(default = "unit_scale")]
+    scale_factor: f64,
+}
+
+/// Facteur d'échelle des documents écrits avant son ajout.
+fn unit_scale() -> f64 {
+    1.0
+}
+
+impl Serialize for HocrDocument {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DocumentRef {
+            format_version: HOCR_JSON_FORMAT_VERSION,
+            pages: &self.pages,
+            order: &self.order,
+            scale_factor: self.scale_factor,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for HocrDocument {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = DocumentData::deserialize(deserializer)?;
+        check_version(data.format_version).map_err(D::Error::custom)?;
+        Ok(Self {
+            pages: data.pages,
+            order: data.order,
+            scale_factor: data.scale_factor,
+        })
+    }
+}
+
+impl HocrDocument {
+    /// Sérialise le document en JSON versionné (sur une ligne).
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne [`HocrJsonError::Invalid`] si la sérialisation échoue.
+    pub fn to_json(&self) -> Result<String, HocrJsonError> {
+        serde_json::to_string(self).map_err(|error| HocrJsonError::invalid(&error))
+    }
+
+    /// Relit un document écrit par [`to_json()`](Self::to_json).
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - Document JSON
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne [`HocrJsonError::MissingVersion`] ou
+    /// [`HocrJsonError::UnsupportedVersion`] si `format_version` est absent
+    /// ou non pris en charge, et [`HocrJsonError::Invalid`] si le JSON est
+    /// mal formé ou ne décrit pas un document.
+    pub fn from_json(json: &str) -> Result<Self, HocrJsonError> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|error| HocrJsonError::invalid(&error))?;
+        // Version vérifiée avant la structure : un format futur peut en différer
+        match value.get("format_version") {
+            None => return Err(HocrJsonError::MissingVersion),
+            Some(version) => {
+                let found = version.as_u64().ok_or_else(|| HocrJsonError::Invalid {
+                    message: format!("format_version n'est pas un entier ({})", version),
+                })?;
+                check_version(found)?;
+            }
+        }
+        serde_json::from_value(value).map_err(|error| HocrJsonError::invalid(&error))
+    }
+
+    /// Enregistre le document en JSON versionné dans un fichier.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Fichier à écrire (remplacé s'il existe)
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne [`HocrJsonError::Io`] si le fichier ne peut pas être écrit.
+    pub fn save_json(&self, path: &Path) -> Result<(), HocrJsonError> {
+        let json = self.to_json()?;
+        std::fs::write(path, json).map_err(|error| HocrJsonError::io(path, &error))
+    }
+
+    /// Relit un document enregistré par [`save_json()`](Self::save_json).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Fichier JSON
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne [`HocrJsonError::Io`] si le fichier est absent ou illisible,
+    /// et les erreurs de [`from_json()`](Self::from_json) sinon.
+    pub fn load_json(path: &Path) -> Result<Self, HocrJsonError> {
+        let json =
+            std::fs::read_to_string(path).map_err(|error| HocrJsonError::io(path, &error))?;
+        Self::from_json(&json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::TWO_PAGE_HOCR;
+    use super::super::{BBox, HocrLine, HocrParagraph, HocrWord, TextDirection};
+    use super::*;
+
+    /// Document de deux pages, avec confiances, plusieurs paragraphes et
+    /// tous les champs optionnels renseignés.
+    fn rich_document() -> HocrDocument {
+        let mut doc = HocrDocument::from_hocr_string(TWO_PAGE_HOCR).unwrap();
+        let mut word = HocrWord::new(BBox::new(60, 200, 80, 30), "λόγος".to_string(), Some(71));
+        word.lang = Some("ell".to_string());
+        word.fused_score = Some(0.625);
+        let mut line = HocrLine::new(BBox::new(60, 200, 30, 300));
+        line.direction = TextDirection::TopToBottom;
+        line.angle = Some(90.0);
+        line.add_word(word);
+        line.add_word(HocrWord::new(
+            BBox::new(60, 240, 80, 30),
+            "sans".to_string(),
+            None,
+        ));
+        let mut paragraph = HocrParagraph::new(BBox::new(60, 200, 80, 300));
+        paragraph.add_line(line);
+        doc.pages[1].add_paragraph(paragraph);
+        doc.order = SortOrder::Columns {
+            boundaries: vec![620],
+        };
+        doc.scale_factor = 0.5;
+        doc
+    }
+
+    #[test]
+    fn test_round_trip_preserves_document() {
+        let doc = rich_document();
+        assert_eq!(doc.paragraphs().count(), 3);
+
+        let json = doc.to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["format_version"], HOCR_JSON_FORMAT_VERSION);
+        assert_eq!(
+            value["pages"][1]["paragraphs"][1]["lines"][0]["direction"],
+            "top_to_bottom"
+        );
+
+        assert_eq!(HocrDocument::from_json(&json).unwrap(), doc);
+    }
+
+    #[test]
+    fn test_missing_optional_fields_use_defaults() {
+        // JSON minimal d'une version antérieure : ni langue, ni sens, ni ordre
+        let json = r#"{
+            "format_version": 1,
+            "pages": [{
+                "bbox": {"x": 0, "y": 0, "width": 400, "height": 100},
+                "page_number": 0,
+                "paragraphs": [{
+                    "bbox": {"x": 10, "y": 10, "width": 380, "height": 30},
+                    "lines": [{
+                        "bbox": {"x": 10, "y": 10, "width": 380, "height": 30},
+                        "words": [{
+                            "bbox": {"x": 10, "y": 10, "width": 110, "height": 30},
+                            "text": "Total",
+                            "confidence": 93
+                        }]
+                    }]
+                }]
+            }]
+        }"#;
+        let doc = HocrDocument::from_json(json).unwrap();
+
+        assert_eq!(doc.order, SortOrder::Natural);
+        assert_eq!(doc.scale_factor, 1.0);
+        assert_eq!(doc.pages[0].dpi, None);
+        let line = &doc.pages[0].paragraphs[0].lines[0];
+        assert_eq!(line.direction, TextDirection::Ltr);
+        assert_eq!((line.angle, line.baseline), (None, None));
+        assert_eq!(line.words[0].confidence, Some(93));
+        assert_eq!(line.words[0].lang, None);
+        assert_eq!(doc.full_text(), "Total");
+    }
+
+    #[test]
+    fn test_unknown_versions_are_rejected() {
+        let error =
+            HocrDocument::from_json(r#"{"format_version": 7, "pages": "futur"}"#).unwrap_err();
+        assert_eq!(
+            error,
+            HocrJsonError::UnsupportedVersion {
+                found: 7,
+                supported: HOCR_JSON_FORMAT_VERSION
+            }
+        );
+        assert!(error.to_string().contains("version de format JSON HOCR 7"));
+
+        assert_eq!(
+            HocrDocument::from_json(r#"{"pages": []}"#),
+            Err(HocrJsonError::MissingVersion)
+        );
+        assert!(matches!(
+            HocrDocument::from_json(r#"{"format_version": "1"}"#),
+            Err(HocrJsonError::Invalid { .. })
+        ));
+        assert!(matches!(
+            HocrDocument::from_json("<html>"),
+            Err(HocrJsonError::Invalid { .. })
+        ));
+        // Version refusée aussi quand le document est imbriqué dans une autre structure
+        let nested: Result<Vec<HocrDocument>, _> =
+            serde_json::from_str(r#"[{"format_version": 2}]"#);
+        assert!(
+            nested
+                .unwrap_err()
+                .to_string()
+                .contains("version de format")
+        );
+    }
+
+    #[test]
+    fn test_file_helpers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("document.json");
+        let doc = rich_document();
+
+        doc.save_json(&path).unwrap();
+        assert_eq!(HocrDocument::load_json(&path).unwrap(), doc);
+
+        let missing = dir.path().join("absent.json");
+        let error = HocrDocument::load_json(&missing).unwrap_err();
+        assert!(matches!(error, HocrJsonError::Io { ref path, .. } if path == &missing));
+        assert!(error.to_string().contains("absent.json"));
+
+        let unwritable = dir.path().join("sous-dossier").join("document.json");
+        assert!(matches!(
+            doc.save_json(&unwritable),
+            Err(HocrJsonError::Io { .. })
+        ));
+    }
+}
@@ -0,0 +1,305 @@
+//! Fusion des lignes qu'une segmentation Tesseract a coupées en deux.
+//!
+//! Un mot légèrement surélevé (exposant, symbole monétaire comme « € 12,50 »
+//! imprimé plus haut que « Total ») suffit souvent à ce que Tesseract émette
+//! deux `ocr_line` pour une seule ligne visuelle. Le texte reconstitué place
+//! alors le montant sur sa propre ligne, ce qui fausse
+//! [`to_layout_text()`](super::to_layout_text) et l'extraction de paires
+//! clé-valeur.
+//!
+//! [`merge_split_lines()`] recolle les lignes consécutives d'un même
+//! paragraphe qui se recouvrent largement en hauteur, ou dont les lignes de
+//! base ([`Baseline`](super::Baseline)) coïncident, tant que l'écart
+//! horizontal entre elles reste faible : deux colonnes distinctes alignées
+//! sur la même hauteur ne sont pas fusionnées.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::hocr::{HocrDocument, LineMergeOptions, merge_split_lines};
+//!
+//! let mut doc = HocrDocument::from_hocr_string(r#"<p class='ocr_par' title="bbox 0 0 400 60">
+//! <span class='ocr_line' title="bbox 10 20 100 60; baseline 0 -8">
+//! <span class='ocrx_word' title='bbox 10 20 100 60; x_wconf 93'>Total</span>
+//! </span>
+//! <span class='ocr_line' title="bbox 120 8 260 48; baseline 0 -6">
+//! <span class='ocrx_word' title='bbox 120 8 150 48; x_wconf 88'>€</span>
+//! <span class='ocrx_word' title='bbox 160 8 260 48; x_wconf 90'>12,50</span>
+//! </span>
+//! </p>"#)?;
+//!
+//! assert_eq!(merge_split_lines(&mut doc, &LineMergeOptions::default()), 1);
+//! assert_eq!(doc.full_text(), "Total € 12,50");
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use super::sort::sort_words;
+use super::{BBox, Baseline, HocrDocument, HocrLine, TextDirection};
+use serde::{Deserialize, Serialize};
+
+/// Critères de fusion des lignes coupées, utilisés par [`merge_split_lines()`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LineMergeOptions {
+    /// Recouvrement vertical au-delà duquel deux lignes sont fusionnées.
+    ///
+    /// Fraction de la hauteur de la plus petite des deux lignes : 0.6 (par
+    /// défaut) fusionne deux lignes dont les bandes verticales se recouvrent
+    /// sur plus de 60 % de la plus petite.
+    pub min_vertical_overlap: f32,
+    /// Écart maximal entre les lignes de base de deux lignes fusionnées, en pixels.
+    ///
+    /// Deux lignes dont les lignes de base hOCR sont à moins de cette
+    /// distance sont fusionnées même si leurs bbox se recouvrent peu (une
+    /// ligne très haute à côté d'une ligne basse). Sans effet si l'une des
+    /// lignes n'a pas de ligne de base. 3 pixels par défaut.
+    pub baseline_tolerance: f32,
+    /// Écart horizontal maximal entre deux lignes fusionnées, en pixels.
+    ///
+    /// Deux colonnes alignées sur la même hauteur sont séparées par un écart
+    /// supérieur : elles restent distinctes. 200 pixels par défaut.
+    pub max_horizontal_gap: u32,
+}
+
+impl Default for LineMergeOptions {
+    fn default() -> Self {
+        Self {
+            min_vertical_overlap: 0.6,
+            baseline_tolerance: 3.0,
+            max_horizontal_gap: 200,
+        }
+    }
+}
+
+/// Fusionne les lignes consécutives d'un même paragraphe qui forment une seule ligne visuelle.
+///
+/// Chaque ligne est comparée à la précédente (éventuellement déjà
+/// fusionnée) : elles sont fusionnées si l'écart horizontal entre leurs bbox
+/// ne dépasse pas [`LineMergeOptions::max_horizontal_gap`] et si leur
+/// recouvrement vertical dépasse [`LineMergeOptions::min_vertical_overlap`]
+/// ou si leurs lignes de base sont à moins de
+/// [`LineMergeOptions::baseline_tolerance`]. La ligne fusionnée a pour bbox
+/// l'union des deux bbox, et ses mots sont triés de gauche à droite (de
+/// droite à gauche pour une ligne [`TextDirection::Rtl`]). Les lignes
+/// verticales ([`TextDirection::TopToBottom`]) ne sont jamais fusionnées.
+///
+/// # Arguments
+///
+/// * `doc` - Document HOCR à modifier
+/// * `options` - Critères de fusion
+///
+/// # Retour
+///
+/// Le nombre de lignes supprimées par fusion.
+pub fn merge_split_lines(doc: &mut HocrDocument, options: &LineMergeOptions) -> usize {
+    let mut merged = 0;
+    for paragraph in doc.pages.iter_mut().flat_map(|page| &mut page.paragraphs) {
+        let lines = std::mem::take(&mut paragraph.lines);
+        let count = lines.len();
+        for line in lines {
+            match paragraph.lines.last_mut() {
+                Some(previous) if should_merge(previous, &line, options) => {
+                    merge_into(previous, line);
+                }
+                _ => paragraph.lines.push(line),
+            }
+        }
+        merged += count - paragraph.lines.len();
+    }
+    merged
+}
+
+/// Indique si deux lignes consécutives forment une seule ligne visuelle.
+fn should_merge(a: &HocrLine, b: &HocrLine, options: &LineMergeOptions) -> bool {
+    if a.direction != b.direction || a.direction == TextDirection::TopToBottom {
+        return false;
+    }
+    if horizontal_gap(&a.bbox, &b.bbox) > options.max_horizontal_gap {
+        return false;
+    }
+    vertical_overlap(&a.bbox, &b.bbox) > options.min_vertical_overlap
+        || baseline_gap(a, b).is_some_and(|gap| gap <= options.baseline_tolerance)
+}
+
+/// Écart horizontal entre deux rectangles, 0 s'ils se chevauchent en abscisse.
+fn horizontal_gap(a: &BBox, b: &BBox) -> u32 {
+    let (left, right) = if a.x <= b.x { (a, b) } else { (b, a) };
+    right.x.saturating_sub(left.x + left.width)
+}
+
+/// Recouvrement des bandes verticales de deux rectangles, en fraction de la plus petite hauteur.
+fn vertical_overlap(a: &BBox, b: &BBox) -> f32 {
+    let smaller = a.height.min(b.height);
+    if smaller == 0 {
+        return 0.0;
+    }
+    let overlap = (a.y + a.height)
+        .min(b.y + b.height)
+        .saturating_sub(a.y.max(b.y));
+    overlap as f32 / smaller as f32
+}
+
+/// Écart vertical entre les lignes de base de deux lignes, mesuré à leur jonction.
+fn baseline_gap(a: &HocrLine, b: &HocrLine) -> Option<f32> {
+    let (baseline_a, baseline_b) = (a.baseline?, b.baseline?);
+    let start = a.bbox.x.max(b.bbox.x);
+    let end = (a.bbox.x + a.bbox.width).min(b.bbox.x + b.bbox.width);
+    let x = start.min(end) + start.abs_diff(end) / 2;
+    Some((baseline_a.y_at(&a.bbox, x) - baseline_b.y_at(&b.bbox, x)).abs())
+}
+
+/// Ajoute les mots de `other` à `target`, qui prend l'union des deux bbox.
+///
+/// La ligne de base conservée est celle de la ligne la plus large, exprimée
+/// par rapport à la nouvelle bbox.
+fn merge_into(target: &mut HocrLine, other: HocrLine) {
+    let bbox = target.bbox.union(&other.bbox);
+    let widest = if other.bbox.width > target.bbox.width {
+        &other
+    } else {
+        &*target
+    };
+    let baseline = widest.baseline.map(|baseline| Baseline {
+        slope: baseline.slope,
+        offset: baseline.y_at(&widest.bbox, bbox.x) - (bbox.y + bbox.height) as f32,
+    });
+
+    target.bbox = bbox;
+    target.baseline = baseline;
+    target.angle = target.angle.or(other.angle);
+    target.words.extend(other.words);
+    sort_words(target);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hocr::{HocrParagraph, HocrWord};
+
+    /// Ligne d'un seul tenant contenant les mots donnés (abscisse, largeur, texte).
+    fn line(y: u32, height: u32, baseline: f32, words: &[(u32, u32, &str)]) -> HocrLine {
+        let x0 = words.iter().map(|&(x, _, _)| x).min().unwrap_or(0);
+        let x1 = words.iter().map(|&(x, w, _)| x + w).max().unwrap_or(0);
+        let mut line = HocrLine::new(BBox::new(x0, y, x1 - x0, height));
+        line.baseline = Some(Baseline {
+            slope: 0.0,
+            offset: baseline,
+        });
+        for &(x, width, text) in words {
+            line.add_word(HocrWord::new(
+                BBox::new(x, y, width, height),
+                text.to_string(),
+                Some(90),
+            ));
+        }
+        line
+    }
+
+    fn document(lines: Vec<HocrLine>) -> HocrDocument {
+        let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 1000, 400));
+        for line in lines {
+            paragraph.add_line(line);
+        }
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph);
+        doc
+    }
+
+    fn texts(doc: &HocrDocument) -> Vec<String> {
+        doc.pages[0].paragraphs[0]
+            .lines
+            .iter()
+            .map(|line| {
+                let words: Vec<&str> = line.words.iter().map(|word| word.text.as_str()).collect();
+                words.join(" ")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_superscripted_amount_is_merged_in_reading_order() {
+        // Tesseract émet d'abord le montant surélevé, puis « Total »
+        let mut doc = document(vec![
+            line(88, 40, -6.0, &[(300, 30, "€"), (340, 110, "12,50")]),
+            line(100, 40, -8.0, &[(40, 120, "Total")]),
+        ]);
+
+        assert_eq!(merge_split_lines(&mut doc, &LineMergeOptions::default()), 1);
+
+        assert_eq!(texts(&doc), ["Total € 12,50"]);
+        let merged = &doc.pages[0].paragraphs[0].lines[0];
+        assert_eq!(merged.bbox, BBox::new(40, 88, 410, 52));
+        // La ligne de base de la ligne la plus large, rapportée à la nouvelle bbox
+        assert_eq!(
+            merged.baseline,
+            Some(Baseline {
+                slope: 0.0,
+                offset: -18.0
+            })
+        );
+        assert_eq!(merged.baseline.unwrap().y_at(&merged.bbox, 300), 122.0);
+    }
+
+    #[test]
+    fn test_stacked_lines_with_small_overlap_stay_separate() {
+        // 4 pixels de recouvrement sur 40 : 10 %
+        let mut doc = document(vec![
+            line(0, 40, -6.0, &[(40, 120, "Total"), (300, 140, "12,50")]),
+            line(36, 40, -6.0, &[(40, 80, "TVA"), (300, 110, "2,50")]),
+        ]);
+
+        assert_eq!(merge_split_lines(&mut doc, &LineMergeOptions::default()), 0);
+        assert_eq!(texts(&doc), ["Total 12,50", "TVA 2,50"]);
+    }
+
+    #[test]
+    fn test_distant_columns_and_vertical_lines_are_not_merged() {
+        let mut doc = document(vec![
+            line(100, 40, -8.0, &[(40, 120, "Colonne")]),
+            line(100, 40, -8.0, &[(600, 120, "voisine")]),
+        ]);
+        assert_eq!(merge_split_lines(&mut doc, &LineMergeOptions::default()), 0);
+
+        let wide_gap = LineMergeOptions {
+            max_horizontal_gap: 500,
+            ..LineMergeOptions::default()
+        };
+        assert_eq!(merge_split_lines(&mut doc, &wide_gap), 1);
+
+        let mut vertical = document(vec![
+            line(0, 100, -5.0, &[(0, 40, "日")]),
+            line(0, 100, -5.0, &[(0, 40, "本")]),
+        ]);
+        for line in &mut vertical.pages[0].paragraphs[0].lines {
+            line.direction = TextDirection::TopToBottom;
+        }
+        assert_eq!(merge_split_lines(&mut vertical, &wide_gap), 0);
+    }
+
+    #[test]
+    fn test_matching_baselines_merge_despite_small_overlap() {
+        // Un titre en grands caractères suivi d'un mot à jambages : les bbox
+        // se recouvrent de 30 pixels sur 60 (50 %), mais les lignes de base
+        // coïncident à l'ordonnée 90
+        let mut doc = document(vec![
+            line(0, 100, -10.0, &[(0, 80, "Prix")]),
+            line(70, 60, -40.0, &[(100, 120, "gagnés")]),
+        ]);
+
+        assert_eq!(merge_split_lines(&mut doc, &LineMergeOptions::default()), 1);
+        assert_eq!(texts(&doc), ["Prix gagnés"]);
+    }
+
+    #[test]
+    fn test_chained_fragments_merge_into_one_line() {
+        let mut doc = document(vec![
+            line(100, 40, -8.0, &[(40, 120, "Total")]),
+            line(90, 40, -6.0, &[(180, 30, "€")]),
+            line(95, 40, -7.0, &[(230, 110, "12,50")]),
+            line(200, 40, -8.0, &[(40, 80, "TVA")]),
+        ]);
+
+        assert_eq!(merge_split_lines(&mut doc, &LineMergeOptions::default()), 2);
+        assert_eq!(texts(&doc), ["Total € 12,50", "TVA"]);
+    }
+}
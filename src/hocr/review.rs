@@ -0,0 +1,366 @@
+//! Zones peu fiables à faire relire.
+//!
+//! [`HocrDocument::low_confidence_regions()`] regroupe les mots (ou les
+//! lignes) consécutifs dont la confiance est sous un seuil en rectangles à
+//! relire : deux éléments peu fiables qui se suivent sont fusionnés tant
+//! qu'ils sont séparés verticalement de moins d'une hauteur de ligne.
+//! [`save_region_crops()`] découpe ensuite ces zones dans l'image pour une
+//! transcription manuelle.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! # #[cfg(feature = "tesseract")]
+//! # {
+//! use text_recognition::hocr::{Level, save_region_crops};
+//! use text_recognition::ocr::OcrEngine;
+//! use text_recognition::config::OcrConfig;
+//! use std::path::Path;
+//!
+//! let path = Path::new("facture.png");
+//! let engine = OcrEngine::new(OcrConfig::default())?;
+//! let doc = engine.extract_hocr_document(path)?;
+//!
+//! let regions = doc.low_confidence_regions(60.0, Level::Line);
+//! save_region_crops(&image::open(path)?, &regions, Path::new("relecture"), "facture")?;
+//! # }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use super::{BBox, HocrDocument, HocrPage};
+use crate::redaction::clamp_to_image;
+use anyhow::{Context, Result};
+use image::DynamicImage;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Seuil de confiance par défaut des zones à relire (échelle 0 à 100).
+pub const DEFAULT_REVIEW_THRESHOLD: f32 = 60.0;
+
+/// Granularité des éléments comparés au seuil.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Level {
+    /// Chaque mot, avec sa confiance Tesseract.
+    Word,
+
+    /// Chaque ligne, avec la moyenne des confiances de ses mots
+    /// ([`HocrLine::mean_confidence()`](super::HocrLine::mean_confidence)).
+    #[default]
+    Line,
+}
+
+/// Zone en cours de construction : rectangle englobant et confiances cumulées.
+struct Region {
+    bbox: BBox,
+    sum: f64,
+    count: u32,
+}
+
+impl Region {
+    fn new(bbox: BBox, confidence: f32) -> Self {
+        Self {
+            bbox,
+            sum: f64::from(confidence),
+            count: 1,
+        }
+    }
+
+    /// Vrai si `bbox` est à moins de `line_height` pixels de la zone, verticalement.
+    fn is_adjacent(&self, bbox: &BBox, line_height: u32) -> bool {
+        let top = self.bbox.y.max(bbox.y);
+        let bottom = bottom(&self.bbox).min(bottom(bbox));
+        top.saturating_sub(bottom) <= line_height
+    }
+
+    fn extend(&mut self, bbox: &BBox, confidence: f32) {
+        self.bbox = union(&self.bbox, bbox);
+        self.sum += f64::from(confidence);
+        self.count += 1;
+    }
+
+    fn finish(self) -> (BBox, f32) {
+        (self.bbox, (self.sum / f64::from(self.count)) as f32)
+    }
+}
+
+impl HocrDocument {
+    /// Zones consécutives dont la confiance est inférieure à `threshold`.
+    ///
+    /// Les éléments du niveau choisi sont parcourus dans l'ordre du
+    /// document. Un élément peu fiable rejoint la zone en cours si
+    /// l'élément précédent l'était aussi et si l'écart vertical entre les
+    /// deux ne dépasse pas la hauteur de sa ligne ; sinon il ouvre une
+    /// nouvelle zone. Les éléments sans confiance (mots sans `x_wconf`,
+    /// lignes vides) sont ignorés sans interrompre la zone en cours. Les
+    /// zones ne franchissent pas les pages.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Confiance minimale (entre 0 et 100) d'un élément jugé fiable
+    /// * `level` - Éléments comparés au seuil : mots ou lignes
+    ///
+    /// # Retour
+    ///
+    /// Rectangle englobant de chaque zone et moyenne des confiances de ses
+    /// éléments, dans l'ordre du document.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::{BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord, Level};
+    ///
+    /// let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 200, 50));
+    /// for (y, confidence) in [(0, 30), (25, 50)] {
+    ///     let mut line = HocrLine::new(BBox::new(0, y, 200, 20));
+    ///     line.add_word(HocrWord::new(BBox::new(0, y, 200, 20), "?".into(), Some(confidence)));
+    ///     paragraph.add_line(line);
+    /// }
+    /// let mut doc = HocrDocument::new();
+    /// doc.add_paragraph(paragraph);
+    ///
+    /// let regions = doc.low_confidence_regions(60.0, Level::Line);
+    /// assert_eq!(regions, [(BBox::new(0, 0, 200, 45), 40.0)]);
+    /// ```
+    pub fn low_confidence_regions(&self, threshold: f32, level: Level) -> Vec<(BBox, f32)> {
+        let mut regions = Vec::new();
+        for page in &self.pages {
+            let mut current: Option<Region> = None;
+            for (bbox, confidence, line_height) in scored_elements(page, level) {
+                if confidence >= threshold {
+                    regions.extend(current.take().map(Region::finish));
+                    continue;
+                }
+                match current.as_mut() {
+                    Some(region) if region.is_adjacent(&bbox, line_height) => {
+                        region.extend(&bbox, confidence)
+                    }
+                    _ => regions.extend(
+                        current
+                            .replace(Region::new(bbox, confidence))
+                            .map(Region::finish),
+                    ),
+                }
+            }
+            regions.extend(current.map(Region::finish));
+        }
+        regions
+    }
+}
+
+/// Éléments notés d'une page : rectangle, confiance et hauteur de leur ligne.
+fn scored_elements(page: &HocrPage, level: Level) -> Vec<(BBox, f32, u32)> {
+    let lines = page
+        .paragraphs
+        .iter()
+        .flat_map(|paragraph| &paragraph.lines);
+    match level {
+        Level::Word => lines
+            .flat_map(|line| {
+                line.words.iter().filter_map(|word| {
+                    word.confidence.map(|confidence| {
+                        (word.bbox.clone(), f32::from(confidence), line.bbox.height)
+                    })
+                })
+            })
+            .collect(),
+        Level::Line => lines
+            .filter_map(|line| {
+                line.mean_confidence()
+                    .map(|confidence| (line.bbox.clone(), confidence, line.bbox.height))
+            })
+            .collect(),
+    }
+}
+
+/// Ordonnée du bord inférieur (exclu) d'un rectangle.
+fn bottom(bbox: &BBox) -> u32 {
+    bbox.y.saturating_add(bbox.height)
+}
+
+/// Plus petit rectangle contenant les deux rectangles.
+fn union(a: &BBox, b: &BBox) -> BBox {
+    let x0 = a.x.min(b.x);
+    let y0 = a.y.min(b.y);
+    let x1 = a.x.saturating_add(a.width).max(b.x.saturating_add(b.width));
+    let y1 = bottom(a).max(bottom(b));
+    BBox::new(x0, y0, x1 - x0, y1 - y0)
+}
+
+/// Découpe chaque zone dans l'image et l'enregistre en PNG.
+///
+/// Les fichiers sont nommés `<stem>_region_<n>.png`, `n` étant l'indice de
+/// la zone dans `regions`. Les zones sont tronquées aux bords de l'image ;
+/// celles qui en sortent entièrement ne produisent aucun fichier.
+///
+/// # Arguments
+///
+/// * `image` - Image analysée, aux dimensions du document HOCR
+/// * `regions` - Zones retournées par [`HocrDocument::low_confidence_regions()`]
+/// * `out_dir` - Répertoire de sortie, créé au besoin
+/// * `stem` - Préfixe des noms de fichiers
+///
+/// # Retour
+///
+/// Chemins des fichiers écrits, dans l'ordre des zones.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le répertoire ne peut pas être créé ou si un
+/// fichier ne peut pas être écrit.
+pub fn save_region_crops(
+    image: &DynamicImage,
+    regions: &[(BBox, f32)],
+    out_dir: &Path,
+    stem: &str,
+) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(out_dir).with_context(|| {
+        format!(
+            "Impossible de créer le répertoire de sortie '{}'",
+            out_dir.display()
+        )
+    })?;
+
+    let mut written = Vec::new();
+    for (index, (bbox, _)) in regions.iter().enumerate() {
+        let Some((x0, y0, x1, y1)) = clamp_to_image(bbox, image.width(), image.height()) else {
+            continue;
+        };
+        let path = out_dir.join(format!("{}_region_{}.png", stem, index));
+        image
+            .crop_imm(x0, y0, x1 - x0, y1 - y0)
+            .save(&path)
+            .with_context(|| format!("Impossible d'écrire '{}'", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hocr::{HocrLine, HocrParagraph, HocrWord};
+    use image::{Rgb, RgbImage};
+
+    /// Ligne d'un seul mot par confiance, `None` pour un mot sans confiance.
+    fn line(y: u32, height: u32, confidences: &[Option<u8>]) -> HocrLine {
+        let mut line = HocrLine::new(BBox::new(10, y, 300, height));
+        for (index, confidence) in confidences.iter().enumerate() {
+            let x = 10 + index as u32 * 100;
+            line.add_word(HocrWord::new(
+                BBox::new(x, y, 90, height),
+                "mot".into(),
+                *confidence,
+            ));
+        }
+        line
+    }
+
+    fn document(lines: Vec<HocrLine>) -> HocrDocument {
+        let mut paragraph = HocrParagraph::new(BBox::new(10, 0, 300, 400));
+        for line in lines {
+            paragraph.add_line(line);
+        }
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph);
+        doc
+    }
+
+    #[test]
+    fn test_line_and_paragraph_confidence_math() {
+        let first = line(0, 20, &[Some(90), Some(40), None]);
+        assert_eq!(first.mean_confidence(), Some(65.0));
+        assert_eq!(first.min_confidence(), Some(40));
+
+        let empty = line(30, 20, &[]);
+        assert_eq!(empty.mean_confidence(), None);
+        assert_eq!(empty.min_confidence(), None);
+        let unscored = line(60, 20, &[None]);
+        assert_eq!(unscored.mean_confidence(), None);
+
+        // Moyenne par mot : (90 + 40 + 20) / 3
+        let doc = document(vec![first, empty, unscored, line(90, 20, &[Some(20)])]);
+        let paragraph = doc.paragraphs().next().unwrap();
+        assert_eq!(paragraph.mean_confidence(), Some(50.0));
+    }
+
+    #[test]
+    fn test_adjacent_low_lines_merge_and_distant_one_stays_separate() {
+        let doc = document(vec![
+            line(0, 20, &[Some(30)]),
+            line(25, 20, &[Some(50)]),
+            line(200, 20, &[Some(40)]),
+        ]);
+
+        let regions = doc.low_confidence_regions(60.0, Level::Line);
+
+        assert_eq!(
+            regions,
+            [
+                (BBox::new(10, 0, 300, 45), 40.0),
+                (BBox::new(10, 200, 300, 20), 40.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reliable_element_splits_regions() {
+        let doc = document(vec![
+            line(0, 20, &[Some(30)]),
+            line(25, 20, &[Some(95)]),
+            line(50, 20, &[Some(30)]),
+        ]);
+
+        assert_eq!(doc.low_confidence_regions(60.0, Level::Line).len(), 2);
+    }
+
+    #[test]
+    fn test_word_level_merges_consecutive_low_words() {
+        let doc = document(vec![line(0, 20, &[Some(20), Some(40), None, Some(90)])]);
+
+        let regions = doc.low_confidence_regions(60.0, Level::Word);
+
+        // Le mot sans confiance est ignoré, le mot fiable n'est pas inclus
+        assert_eq!(regions, [(BBox::new(10, 0, 190, 20), 30.0)]);
+    }
+
+    #[test]
+    fn test_empty_lines_do_not_panic() {
+        let doc = document(vec![line(0, 20, &[]), line(30, 0, &[None])]);
+
+        assert!(doc.low_confidence_regions(60.0, Level::Line).is_empty());
+        assert!(doc.low_confidence_regions(60.0, Level::Word).is_empty());
+        assert!(
+            HocrDocument::new()
+                .low_confidence_regions(60.0, Level::Line)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_save_region_crops_writes_one_file_per_region() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(320, 240, Rgb([255, 255, 255])));
+        let regions = [
+            (BBox::new(10, 0, 300, 45), 40.0),
+            (BBox::new(10, 200, 400, 80), 40.0),
+            (BBox::new(500, 500, 10, 10), 10.0),
+        ];
+        let out_dir = dir.path().join("relecture");
+
+        let written = save_region_crops(&image, &regions, &out_dir, "page").unwrap();
+
+        assert_eq!(
+            written,
+            [
+                out_dir.join("page_region_0.png"),
+                out_dir.join("page_region_1.png")
+            ]
+        );
+        let first = image::open(&written[0]).unwrap();
+        assert_eq!((first.width(), first.height()), (300, 45));
+        // Tronquée aux bords de l'image
+        let second = image::open(&written[1]).unwrap();
+        assert_eq!((second.width(), second.height()), (310, 40));
+        assert!(!out_dir.join("page_region_2.png").exists());
+    }
+}
@@ -0,0 +1,236 @@
+//! Tri explicite des éléments d'un document HOCR.
+//!
+//! [`HocrDocument`] conserve l'ordre des éléments du fichier. Après des
+//! manipulations fondées sur les coordonnées (OCR par régions, fusion de
+//! plusieurs exécutions, recollage de lignes), cet ordre peut ne plus
+//! correspondre à la lecture : [`HocrDocument::sort()`] le rétablit selon
+//! un [`SortOrder`], qui est mémorisé dans [`HocrDocument::order`] et
+//! exposé par [`StructuredText::order`](crate::structured::StructuredText::order).
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::hocr::{HocrDocument, SortOrder};
+//!
+//! let mut doc = HocrDocument::from_hocr_string(r#"<div class='ocr_page' title='bbox 0 0 400 200'>
+//! <p class='ocr_par' title="bbox 10 100 300 30">
+//! <span class='ocr_line' title="bbox 10 100 300 30">
+//! <span class='ocrx_word' title='bbox 10 100 100 30'>bas</span>
+//! </span></p>
+//! <p class='ocr_par' title="bbox 10 10 300 30">
+//! <span class='ocr_line' title="bbox 10 10 300 30">
+//! <span class='ocrx_word' title='bbox 120 10 100 30'>droite</span>
+//! <span class='ocrx_word' title='bbox 10 10 100 30'>gauche</span>
+//! </span></p>
+//! </div>"#)?;
+//!
+//! doc.sort(SortOrder::TopToBottomLeftToRight);
+//! assert_eq!(doc.full_text(), "gauche droite\n\nbas");
+//! assert_eq!(doc.order, SortOrder::TopToBottomLeftToRight);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use super::{BBox, HocrDocument, HocrLine, HocrParagraph, TextDirection};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+/// Ordre des paragraphes, lignes et mots d'un document HOCR.
+///
+/// Se sérialise en `"natural"`, `"top_to_bottom_left_to_right"` ou
+/// `{"columns": {"boundaries": [...]}}`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    /// Ordre du document (celui du fichier HOCR, ou le tri précédent).
+    #[default]
+    Natural,
+
+    /// Paragraphes par ordonnée puis abscisse, lignes par ordonnée, mots
+    /// dans le sens d'écriture de leur ligne.
+    TopToBottomLeftToRight,
+
+    /// Paragraphes répartis en colonnes, lues de gauche à droite ; dans
+    /// chaque colonne, comme [`TopToBottomLeftToRight`](Self::TopToBottomLeftToRight).
+    Columns {
+        /// Abscisses des frontières entre colonnes, en pixels.
+        ///
+        /// Un paragraphe appartient à la colonne qui contient son centre.
+        boundaries: Vec<u32>,
+    },
+}
+
+impl HocrDocument {
+    /// Trie les paragraphes, lignes et mots de chaque page selon `order`.
+    ///
+    /// Avec [`SortOrder::TopToBottomLeftToRight`] et [`SortOrder::Columns`],
+    /// les paragraphes de même ordonnée sont départagés par leur abscisse,
+    /// les lignes d'un paragraphe sont triées de haut en bas et les mots
+    /// d'une ligne de gauche à droite (de droite à gauche pour une ligne
+    /// [`TextDirection::Rtl`], de haut en bas pour une ligne
+    /// [`TextDirection::TopToBottom`]). Le tri est stable : les éléments de
+    /// même position gardent leur ordre relatif.
+    ///
+    /// [`SortOrder::Natural`] ne modifie rien : l'ordre courant, et le
+    /// [`order`](Self::order) qui le décrit, sont conservés.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - Ordre à appliquer
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::{BBox, HocrDocument, HocrParagraph, SortOrder};
+    ///
+    /// let mut doc = HocrDocument::new();
+    /// doc.add_paragraph(HocrParagraph::new(BBox::new(520, 0, 300, 50)));
+    /// doc.add_paragraph(HocrParagraph::new(BBox::new(100, 300, 300, 50)));
+    /// doc.add_paragraph(HocrParagraph::new(BBox::new(100, 0, 300, 50)));
+    ///
+    /// doc.sort(SortOrder::Columns { boundaries: vec![450] });
+    /// let positions: Vec<(u32, u32)> = doc.paragraphs().map(|p| (p.bbox.x, p.bbox.y)).collect();
+    /// assert_eq!(positions, [(100, 0), (100, 300), (520, 0)]);
+    /// ```
+    pub fn sort(&mut self, order: SortOrder) {
+        match &order {
+            SortOrder::Natural => return,
+            SortOrder::TopToBottomLeftToRight => {
+                for page in &mut self.pages {
+                    page.paragraphs
+                        .sort_by_key(|paragraph| position(&paragraph.bbox));
+                }
+            }
+            SortOrder::Columns { boundaries } => {
+                let column = |bbox: &BBox| {
+                    let center = bbox.x + bbox.width / 2;
+                    boundaries.iter().filter(|&&b| b <= center).count()
+                };
+                for page in &mut self.pages {
+                    page.paragraphs.sort_by_key(|paragraph| {
+                        (column(&paragraph.bbox), position(&paragraph.bbox))
+                    });
+                }
+            }
+        }
+
+        for paragraph in self.pages.iter_mut().flat_map(|page| &mut page.paragraphs) {
+            sort_lines(paragraph);
+        }
+        self.order = order;
+    }
+}
+
+/// Clé de tri géométrique : ordonnée, puis abscisse.
+fn position(bbox: &BBox) -> (u32, u32) {
+    (bbox.y, bbox.x)
+}
+
+/// Trie les lignes d'un paragraphe de haut en bas, et les mots de chaque ligne.
+fn sort_lines(paragraph: &mut HocrParagraph) {
+    paragraph.lines.sort_by_key(|line| position(&line.bbox));
+    for line in &mut paragraph.lines {
+        sort_words(line);
+    }
+}
+
+/// Trie les mots d'une ligne dans son sens d'écriture.
+pub(crate) fn sort_words(line: &mut HocrLine) {
+    match line.direction {
+        TextDirection::Ltr => line.words.sort_by_key(|word| word.bbox.x),
+        TextDirection::Rtl => line.words.sort_by_key(|word| Reverse(word.bbox.x)),
+        TextDirection::TopToBottom => line.words.sort_by_key(|word| word.bbox.y),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hocr::HocrWord;
+
+    /// Paragraphe d'une ligne par entrée (ordonnée, mots (abscisse, texte)).
+    fn paragraph(x: u32, y: u32, lines: &[(u32, &[(u32, &str)])]) -> HocrParagraph {
+        let mut paragraph = HocrParagraph::new(BBox::new(x, y, 300, 100));
+        for &(line_y, words) in lines {
+            let mut line = HocrLine::new(BBox::new(x, line_y, 300, 20));
+            for &(word_x, text) in words {
+                line.add_word(HocrWord::new(
+                    BBox::new(word_x, line_y, 40, 20),
+                    text.to_string(),
+                    Some(90),
+                ));
+            }
+            paragraph.add_line(line);
+        }
+        paragraph
+    }
+
+    #[test]
+    fn test_shuffled_document_sorts_top_to_bottom() {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph(10, 300, &[(330, &[(60, "fin"), (10, "la")])]));
+        doc.add_paragraph(paragraph(
+            10,
+            10,
+            &[
+                (40, &[(10, "deuxième")]),
+                (10, &[(60, "ligne"), (10, "Première")]),
+            ],
+        ));
+        doc.add_paragraph(paragraph(10, 150, &[(150, &[(10, "Milieu")])]));
+
+        doc.sort(SortOrder::TopToBottomLeftToRight);
+
+        assert_eq!(
+            doc.full_text(),
+            "Première ligne\ndeuxième\n\nMilieu\n\nla fin"
+        );
+        assert_eq!(doc.order, SortOrder::TopToBottomLeftToRight);
+    }
+
+    #[test]
+    fn test_same_y_breaks_ties_by_x() {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph(400, 10, &[(10, &[(400, "droite")])]));
+        doc.add_paragraph(paragraph(10, 10, &[(10, &[(10, "gauche")])]));
+
+        doc.sort(SortOrder::TopToBottomLeftToRight);
+
+        assert_eq!(doc.full_text(), "gauche\n\ndroite");
+    }
+
+    #[test]
+    fn test_columns_put_left_column_first() {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph(520, 10, &[(10, &[(520, "droite-un")])]));
+        doc.add_paragraph(paragraph(10, 10, &[(10, &[(10, "gauche-un")])]));
+        doc.add_paragraph(paragraph(520, 200, &[(200, &[(520, "droite-deux")])]));
+        doc.add_paragraph(paragraph(10, 200, &[(200, &[(10, "gauche-deux")])]));
+
+        doc.sort(SortOrder::Columns {
+            boundaries: vec![450],
+        });
+
+        assert_eq!(
+            doc.full_text(),
+            "gauche-un\n\ngauche-deux\n\ndroite-un\n\ndroite-deux"
+        );
+    }
+
+    #[test]
+    fn test_natural_keeps_order_and_rtl_words_sort_right_to_left() {
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph(10, 300, &[(300, &[(10, "b")])]));
+        doc.add_paragraph(paragraph(10, 10, &[(10, &[(10, "a")])]));
+        let original = doc.clone();
+        doc.sort(SortOrder::Natural);
+        assert_eq!(doc, original);
+
+        let mut line = HocrLine::new(BBox::new(0, 0, 200, 20));
+        line.direction = TextDirection::Rtl;
+        line.add_word(HocrWord::new(BBox::new(10, 0, 40, 20), "ثاني".into(), None));
+        line.add_word(HocrWord::new(BBox::new(100, 0, 40, 20), "أول".into(), None));
+        sort_words(&mut line);
+        let words: Vec<&str> = line.words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(words, ["أول", "ثاني"]);
+    }
+}
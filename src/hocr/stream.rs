@@ -0,0 +1,439 @@
+//! Lecture en flux des mots d'un document HOCR.
+//!
+//! Le hOCR d'une très grande page (carte numérisée de 100 mégapixels)
+//! dépasse plusieurs dizaines de mégaoctets. [`parse_hocr_stream()`] le lit
+//! ligne par ligne et transmet chaque mot à une fonction dès qu'il est lu,
+//! sans construire le document : seuls un [`ParagraphId`] et un [`LineId`]
+//! permettent à l'appelant de regrouper les mots.
+//! [`HocrDocument::from_reader()`](super::HocrDocument::from_reader)
+//! repose sur le même automate pour construire la structure complète
+//! lorsqu'elle est nécessaire.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::hocr::{LineId, parse_hocr_stream};
+//!
+//! let hocr = "<p class='ocr_par' title='bbox 0 0 100 40'>
+//! <span class='ocr_line' title='bbox 0 0 100 20'>
+//! <span class='ocrx_word' title='bbox 0 0 40 20; x_wconf 96'>Bonjour</span>
+//! <span class='ocr_line' title='bbox 0 20 100 20'>
+//! <span class='ocrx_word' title='bbox 0 20 40 20; x_wconf 91'>monde</span>";
+//!
+//! let mut words = Vec::new();
+//! parse_hocr_stream(hocr.as_bytes(), None, |word, line, _| words.push((word.text, line)))?;
+//! assert_eq!(words, [("Bonjour".to_string(), LineId(0)), ("monde".to_string(), LineId(1))]);
+//! # Ok::<(), text_recognition::hocr::HocrStreamError>(())
+//! ```
+
+use super::{HocrElement, HocrLine, HocrParagraph, HocrParseWarning, HocrTokenizer, HocrWord};
+use std::io::BufRead;
+
+/// Identifiant d'une ligne (`ocr_line`) dans un flux HOCR.
+///
+/// Les lignes sont numérotées à partir de 0 dans l'ordre du fichier, comme
+/// les lignes de [`HocrDocument`](super::HocrDocument) parcourues paragraphe par paragraphe.
+/// Les lignes ignorées (bbox invalide, hors paragraphe) n'ont pas de numéro.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LineId(pub usize);
+
+/// Identifiant d'un paragraphe (`ocr_par`) dans un flux HOCR.
+///
+/// Les paragraphes sont numérotés à partir de 0 dans l'ordre du fichier,
+/// comme [`HocrDocument::paragraphs()`](super::HocrDocument::paragraphs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ParagraphId(pub usize);
+
+/// Erreur de la lecture en flux d'un document HOCR.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HocrStreamError {
+    /// La source n'a pas pu être lue (erreur d'entrée/sortie ou UTF-8 invalide).
+    #[error("lecture du HOCR impossible à la ligne {line} : {message}")]
+    Read {
+        /// Numéro de la ligne qui n'a pas pu être lue (à partir de 1).
+        line: usize,
+        /// Description de l'erreur de lecture.
+        message: String,
+    },
+
+    /// Le document contient plus de mots que la limite demandée.
+    #[error("HOCR trop volumineux : plus de {limit} mots")]
+    TooManyWords {
+        /// Nombre maximal de mots accepté.
+        limit: usize,
+    },
+}
+
+impl HocrStreamError {
+    /// Erreur de lecture de la ligne `line`.
+    fn read(line: usize, error: &std::io::Error) -> Self {
+        Self::Read {
+            line,
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Vérifie que `words` mots ne dépassent pas la limite `max_words`.
+fn check_word_limit(words: usize, max_words: Option<usize>) -> Result<(), HocrStreamError> {
+    match max_words {
+        Some(limit) if words > limit => Err(HocrStreamError::TooManyWords { limit }),
+        _ => Ok(()),
+    }
+}
+
+/// Lit un document HOCR en flux et transmet chaque mot à `on_word`.
+///
+/// Les mots arrivent dans l'ordre du fichier, avec la ligne et le
+/// paragraphe qui les contiennent : ce sont exactement les mots de
+/// [`HocrDocument::from_reader()`](super::HocrDocument::from_reader), qui
+/// repose sur le même automate, dans le même ordre. Seuls le mot en cours
+/// et les avertissements sont gardés en mémoire.
+///
+/// # Arguments
+///
+/// * `reader` - Source du HOCR (ex: `BufReader<File>`)
+/// * `max_words` - Nombre maximal de mots acceptés, ou `None` sans limite
+/// * `on_word` - Fonction appelée pour chaque mot
+///
+/// # Retour
+///
+/// Les éléments ignorés, comme
+/// [`HocrDocument::from_hocr_string_with_warnings()`](super::HocrDocument::from_hocr_string_with_warnings)
+///
+/// # Erreurs
+///
+/// Retourne [`HocrStreamError::Read`] si la lecture échoue et
+/// [`HocrStreamError::TooManyWords`] dès que le mot `max_words + 1` est lu :
+/// `on_word` a alors reçu les `max_words` premiers mots.
+pub fn parse_hocr_stream(
+    reader: impl BufRead,
+    max_words: Option<usize>,
+    mut on_word: impl FnMut(HocrWord, LineId, ParagraphId),
+) -> Result<Vec<HocrParseWarning>, HocrStreamError> {
+    parse_hocr_events(reader, max_words, |event| {
+        if let HocrEvent::Word(word, line, paragraph) = event {
+            on_word(word, line, paragraph);
+        }
+    })
+}
+
+/// Lit un document HOCR en flux et transmet chaque [`HocrEvent`] à `on_event`.
+///
+/// Même lecture, mêmes avertissements et mêmes erreurs que
+/// [`parse_hocr_stream()`], qui ne garde que les mots.
+pub(super) fn parse_hocr_events(
+    reader: impl BufRead,
+    max_words: Option<usize>,
+    mut on_event: impl FnMut(HocrEvent<'_>),
+) -> Result<Vec<HocrParseWarning>, HocrStreamError> {
+    let mut parser = HocrEventParser::default();
+    let mut words = 0;
+    let mut forward = |event: HocrEvent<'_>| {
+        if let HocrEvent::Word(..) = event {
+            words += 1;
+            check_word_limit(words, max_words)?;
+        }
+        on_event(event);
+        Ok(())
+    };
+    for (index, text) in reader.lines().enumerate() {
+        let text = text.map_err(|e| HocrStreamError::read(index + 1, &e))?;
+        parser.push(&text, &mut forward)?;
+    }
+    Ok(parser.finish())
+}
+
+/// Élément de structure d'un document HOCR, transmis dans l'ordre du fichier.
+///
+/// Seuls les éléments retenus sont transmis : une ligne hors paragraphe,
+/// un élément illisible et les mots de l'une ou de l'autre ne produisent
+/// qu'un avertissement.
+pub(super) enum HocrEvent<'a> {
+    /// Début d'une page (`ocr_page`), avec la ligne de sa balise.
+    Page(&'a str),
+    /// Début d'un paragraphe (`ocr_par`).
+    Paragraph(HocrParagraph),
+    /// Début d'une ligne (`ocr_line`) du paragraphe en cours.
+    Line(HocrLine),
+    /// Mot (`ocrx_word`) de la ligne en cours.
+    Word(HocrWord, LineId, ParagraphId),
+}
+
+/// Automate de lecture d'un document HOCR, une ligne du fichier à la fois.
+///
+/// Décide seul des éléments retenus, de leur numérotation et des
+/// avertissements : [`parse_hocr_stream()`],
+/// [`HocrDocument::from_reader()`](super::HocrDocument::from_reader) et
+/// [`HocrDocument::from_hocr_string_with_warnings()`](super::HocrDocument::from_hocr_string_with_warnings)
+/// ne font que consommer ses [`HocrEvent`].
+#[derive(Debug, Default)]
+pub(super) struct HocrEventParser {
+    /// Découpage des lignes du fichier en éléments.
+    tokenizer: HocrTokenizer,
+    /// Paragraphe en cours.
+    paragraph: Option<ParagraphId>,
+    /// Ligne en cours : son numéro, absent si elle est hors paragraphe.
+    line: Option<Option<LineId>>,
+    /// Nombre de paragraphes retenus.
+    paragraphs: usize,
+    /// Nombre de lignes retenues.
+    lines: usize,
+    /// Numéro de la dernière ligne lue du fichier.
+    line_number: usize,
+    /// Éléments ignorés, dans l'ordre du fichier.
+    warnings: Vec<HocrParseWarning>,
+}
+
+impl HocrEventParser {
+    /// Lit la ligne suivante du fichier et transmet son élément, s'il est retenu.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne l'erreur de `on_event`, qui interrompt la lecture.
+    pub(super) fn push<E>(
+        &mut self,
+        text: &str,
+        on_event: &mut impl FnMut(HocrEvent<'_>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        self.line_number += 1;
+        let Some(element) = self.tokenizer.element(text) else {
+            return Ok(());
+        };
+        match element {
+            HocrElement::Page(tag) => {
+                self.close_line();
+                self.paragraph = None;
+                on_event(HocrEvent::Page(tag))?;
+            }
+            HocrElement::Paragraph(result) => {
+                self.close_line();
+                self.paragraph = None;
+                match result {
+                    Ok(paragraph) => {
+                        self.paragraphs += 1;
+                        self.paragraph = Some(ParagraphId(self.paragraphs - 1));
+                        on_event(HocrEvent::Paragraph(paragraph))?;
+                    }
+                    Err(message) => self.warn(message),
+                }
+            }
+            HocrElement::Line(result) => {
+                self.close_line();
+                match result {
+                    Ok(line) => {
+                        if self.paragraph.is_some() {
+                            self.lines += 1;
+                            self.line = Some(Some(LineId(self.lines - 1)));
+                            on_event(HocrEvent::Line(line))?;
+                        } else {
+                            self.line = Some(None);
+                        }
+                    }
+                    Err(message) => self.warn(message),
+                }
+            }
+            HocrElement::Word(Ok(word)) => match (self.line, self.paragraph) {
+                (Some(Some(line_id)), Some(paragraph_id)) => {
+                    on_event(HocrEvent::Word(word, line_id, paragraph_id))?;
+                }
+                // Mot d'une ligne hors paragraphe : ignoré avec sa ligne
+                (Some(None), _) => {}
+                _ => self.warn("mot ignoré : aucune ligne ocr_line en cours"),
+            },
+            HocrElement::Word(Err(message)) => self.warn(message),
+        }
+        Ok(())
+    }
+
+    /// Ferme la dernière ligne et retourne les avertissements.
+    pub(super) fn finish(mut self) -> Vec<HocrParseWarning> {
+        self.close_line();
+        self.warnings
+    }
+
+    /// Ferme la ligne en cours ; une ligne hors paragraphe est signalée à sa fermeture.
+    fn close_line(&mut self) {
+        if let Some(None) = self.line.take() {
+            self.warn("ligne ignorée : aucun paragraphe ocr_par en cours");
+        }
+    }
+
+    /// Signale un élément ignoré à la ligne courante du fichier.
+    fn warn(&mut self, message: &str) {
+        self.warnings
+            .push(HocrParseWarning::new(self.line_number, message));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hocr::{HocrDocument, HocrLine};
+
+    /// Document HOCR généré de `paragraphs` paragraphes de 10 lignes de 10 mots.
+    fn generated_hocr(paragraphs: usize) -> String {
+        let mut hocr = String::from("<div class='ocr_page' title='bbox 0 0 10000 100000'>\n");
+        for p in 0..paragraphs {
+            let y = p * 1000;
+            hocr.push_str(&format!(
+                "<p class='ocr_par' title='bbox 0 {} 10000 1000'>\n",
+                y
+            ));
+            for l in 0..10 {
+                let line_y = y + l * 100;
+                hocr.push_str(&format!(
+                    "<span class='ocr_line' title='bbox 0 {} 10000 100'>\n",
+                    line_y
+                ));
+                for w in 0..10 {
+                    hocr.push_str(&format!(
+                        "<span class='ocrx_word' title='bbox {} {} 900 100; x_wconf {}'>mot{}</span>\n",
+                        w * 1000,
+                        line_y,
+                        50 + w * 5,
+                        (p * 10 + l) * 10 + w
+                    ));
+                }
+                hocr.push_str("</span>\n");
+            }
+            hocr.push_str("</p>\n");
+        }
+        hocr.push_str("</div>\n");
+        hocr
+    }
+
+    /// Mots du document avec les identifiants attendus du flux.
+    fn document_words(doc: &HocrDocument) -> Vec<(HocrWord, LineId, ParagraphId)> {
+        let mut line_id = 0;
+        let mut words = Vec::new();
+        for (paragraph_id, paragraph) in doc.paragraphs().enumerate() {
+            for line in &paragraph.lines {
+                words.extend(
+                    line.words
+                        .iter()
+                        .map(|word| (word.clone(), LineId(line_id), ParagraphId(paragraph_id))),
+                );
+                line_id += 1;
+            }
+        }
+        words
+    }
+
+    /// Mots reçus par la fonction de [`parse_hocr_stream()`].
+    fn streamed_words(hocr: &str) -> (Vec<(HocrWord, LineId, ParagraphId)>, Vec<HocrParseWarning>) {
+        let mut words = Vec::new();
+        let warnings = parse_hocr_stream(hocr.as_bytes(), None, |word, line, paragraph| {
+            words.push((word, line, paragraph))
+        })
+        .unwrap();
+        (words, warnings)
+    }
+
+    #[test]
+    fn test_stream_matches_document_parser_on_fixtures() {
+        let fixtures = [
+            crate::hocr::tests::WELL_FORMED_HOCR,
+            crate::hocr::tests::TWO_PAGE_HOCR,
+            crate::hocr::tests::MIXED_SCRIPT_HOCR,
+            crate::hocr::tests::VERTICAL_HOCR,
+        ];
+        for hocr in fixtures {
+            let (doc, doc_warnings) = HocrDocument::from_hocr_string_with_warnings(hocr);
+            let (words, warnings) = streamed_words(hocr);
+            assert!(!words.is_empty());
+            assert_eq!(words, document_words(&doc));
+            assert_eq!(warnings, doc_warnings);
+        }
+    }
+
+    #[test]
+    fn test_stream_matches_document_parser_on_malformed_input() {
+        // Ligne hors paragraphe, mot hors ligne, bbox invalides, fichier tronqué
+        let hocr = "<div class='ocr_page' title='bbox 0 0 200 100'>
+<span class='ocr_line' title='bbox 0 0 100 20'>
+<span class='ocrx_word' title='bbox 0 0 40 20'>orphelin</span>
+<p class='ocr_par' title='bbox 0 0 200 100'>
+<span class='ocrx_word' title='bbox 0 0 40 20'>sans_ligne</span>
+<span class='ocr_line' title='bbox x'>
+<span class='ocr_line' title='bbox 0 0 200 20'>
+<span class='ocrx_word' title='bbox 0 0 40 20'>gardé</span>
+<span class='ocrx_word'>sans_bbox</span>
+<p class='ocr_par'>
+<span class='ocr_line' title='bbox 0 40 200 20'>
+<span class='ocrx_word' title='bbox 0 40 40 20'>perdu</span>
+<p class='ocr_par' title='bbox 0 60 200 40'>
+<span class='ocr_line' title='bbox 0 60 200 20'>
+<span class='ocrx_word' title='bbox 0 60 40 20'>fin</span>
+<span class='ocrx_word' title='bbox 50 60 40 20'>tronq";
+        let (doc, doc_warnings) = HocrDocument::from_hocr_string_with_warnings(hocr);
+        let (words, warnings) = streamed_words(hocr);
+
+        let texts: Vec<&str> = words
+            .iter()
+            .map(|(word, _, _)| word.text.as_str())
+            .collect();
+        assert_eq!(texts, ["gardé", "fin"]);
+        assert_eq!(words, document_words(&doc));
+        assert_eq!(warnings, doc_warnings);
+        assert_eq!(
+            HocrDocument::from_reader(hocr.as_bytes(), None).unwrap(),
+            (doc, doc_warnings)
+        );
+    }
+
+    #[test]
+    fn test_large_generated_document_is_streamed() {
+        // 500 paragraphes × 10 lignes × 10 mots = 50 000 mots
+        let hocr = generated_hocr(500);
+        let mut count = 0;
+        let mut confidence_sum = 0u64;
+        let mut last = (LineId(0), ParagraphId(0));
+        let warnings = parse_hocr_stream(hocr.as_bytes(), None, |word, line, paragraph| {
+            count += 1;
+            confidence_sum += u64::from(word.confidence.unwrap_or(0));
+            assert!((line, paragraph) >= last);
+            last = (line, paragraph);
+        })
+        .unwrap();
+
+        assert!(warnings.is_empty());
+        assert_eq!(count, 50_000);
+        assert_eq!(last, (LineId(4999), ParagraphId(499)));
+        assert_eq!(confidence_sum, 5_000 * (50..=95).step_by(5).sum::<u64>());
+
+        let (doc, _) = HocrDocument::from_reader(hocr.as_bytes(), Some(50_000)).unwrap();
+        let lines: Vec<&HocrLine> = doc.paragraphs().flat_map(|p| &p.lines).collect();
+        assert_eq!(lines.len(), 5_000);
+        assert_eq!(doc, HocrDocument::from_hocr_string(&hocr).unwrap());
+    }
+
+    #[test]
+    fn test_word_limit_aborts_with_typed_error() {
+        let hocr = generated_hocr(2);
+        let mut received = 0;
+        let error =
+            parse_hocr_stream(hocr.as_bytes(), Some(150), |_, _, _| received += 1).unwrap_err();
+        assert_eq!(error, HocrStreamError::TooManyWords { limit: 150 });
+        assert_eq!(received, 150);
+        assert_eq!(error.to_string(), "HOCR trop volumineux : plus de 150 mots");
+
+        assert_eq!(
+            HocrDocument::from_reader(hocr.as_bytes(), Some(199)).unwrap_err(),
+            HocrStreamError::TooManyWords { limit: 199 }
+        );
+        // La limite est inclusive
+        assert!(HocrDocument::from_reader(hocr.as_bytes(), Some(200)).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_a_read_error() {
+        let mut hocr = b"<p class='ocr_par' title='bbox 0 0 10 10'>\n".to_vec();
+        hocr.extend_from_slice(b"<span class='ocrx_word'>\xFF</span>\n");
+        let error = parse_hocr_stream(hocr.as_slice(), None, |_, _, _| {}).unwrap_err();
+        assert!(
+            matches!(error, HocrStreamError::Read { line: 2, .. }),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! Fichiers JSON Lines en ajout seul.
+//!
+//! Le [journal de reprise](crate::checkpoint) et le registre des expériences
+//! ajoutent une ligne JSON par événement à un fichier jamais réécrit.
+//! [`AppendLog`] regroupe leur lecture et leur écriture : chaque ligne est
+//! écrite d'un seul appel et vidée sur disque, si bien qu'un arrêt brutal
+//! ne laisse au pire qu'une dernière ligne tronquée. Celle-ci est ignorée
+//! au chargement, puis retirée du fichier avant le prochain ajout.
+//!
+//! Une dernière ligne valide mais sans fin de ligne (fichier édité à la
+//! main) n'est pas une troncature : elle est conservée, et une fin de ligne
+//! est écrite avant l'ajout suivant.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::jsonl::AppendLog;
+//!
+//! let dir = tempfile::tempdir()?;
+//! let path = dir.path().join("mesures.jsonl");
+//! std::fs::write(&path, "1\n2")?;
+//!
+//! let (mut log, values) = AppendLog::<u32>::open(&path, "journal")?;
+//! assert_eq!(values, [1, 2]);
+//! log.append(&3)?;
+//! assert_eq!(std::fs::read_to_string(&path)?, "1\n2\n3\n");
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+/// Contenu d'un fichier JSON Lines lu sur disque.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedLog<T> {
+    /// Entrées valides, dans l'ordre du fichier.
+    pub entries: Vec<T>,
+    /// Longueur en octets de la partie valide du fichier.
+    pub valid_len: u64,
+    /// `true` si une dernière ligne tronquée a été ignorée.
+    pub truncated_tail: bool,
+    /// `true` si la dernière ligne, valide, n'a pas de fin de ligne.
+    pub missing_final_newline: bool,
+}
+
+impl<T> Default for LoadedLog<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            valid_len: 0,
+            truncated_tail: false,
+            missing_final_newline: false,
+        }
+    }
+}
+
+/// Analyse le contenu d'un fichier JSON Lines.
+///
+/// Une dernière ligne illisible et sans fin de ligne (écriture interrompue)
+/// est ignorée ; une dernière ligne lisible sans fin de ligne est conservée.
+/// Toute ligne complète illisible est une erreur.
+///
+/// # Arguments
+///
+/// * `content` - Contenu du fichier
+/// * `name` - Nom du fichier dans les messages d'erreur (ex: "journal")
+///
+/// # Erreurs
+///
+/// Retourne une erreur si une ligne complète n'est pas une entrée valide.
+pub fn parse_log<T: DeserializeOwned>(content: &str, name: &str) -> Result<LoadedLog<T>> {
+    let mut loaded = LoadedLog::default();
+    let mut offset = 0;
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        // Seule la dernière ligne peut être sans fin de ligne
+        let complete = line.ends_with('\n');
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            offset += line.len();
+            continue;
+        }
+        match serde_json::from_str::<T>(trimmed) {
+            Ok(entry) => {
+                loaded.entries.push(entry);
+                loaded.missing_final_newline = !complete;
+                offset += line.len();
+            }
+            Err(e) if complete => bail!("Ligne {} du {} invalide : {}", index + 1, name, e),
+            // Dernière ligne illisible sans fin de ligne : écriture interrompue
+            Err(_) => loaded.truncated_tail = true,
+        }
+    }
+
+    loaded.valid_len = offset as u64;
+    Ok(loaded)
+}
+
+/// Fichier JSON Lines ouvert en ajout seul.
+#[derive(Debug)]
+pub struct AppendLog<T> {
+    path: PathBuf,
+    name: &'static str,
+    file: File,
+    truncated_tail: bool,
+    needs_newline: bool,
+    entries: PhantomData<fn(&T)>,
+}
+
+impl<T: Serialize + DeserializeOwned> AppendLog<T> {
+    /// Ouvre le fichier, en le créant s'il n'existe pas, et retourne ses entrées.
+    ///
+    /// Une dernière ligne tronquée est retirée du fichier pour que les
+    /// ajouts suivants restent lisibles.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin du fichier
+    /// * `name` - Nom du fichier dans les messages d'erreur (ex: "journal")
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le fichier ne peut pas être lu ou écrit, ou
+    /// si une ligne complète est invalide.
+    pub fn open(path: &Path, name: &'static str) -> Result<(Self, Vec<T>)> {
+        let loaded = if path.exists() {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Impossible de lire le {} '{}'", name, path.display()))?;
+            parse_log(&content, name)
+                .with_context(|| format!("{} '{}' corrompu", capitalize(name), path.display()))?
+        } else {
+            LoadedLog::default()
+        };
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Impossible d'ouvrir le {} '{}'", name, path.display()))?;
+        if loaded.truncated_tail {
+            file.set_len(loaded.valid_len).with_context(|| {
+                format!(
+                    "Impossible de retirer la ligne tronquée du {} '{}'",
+                    name,
+                    path.display()
+                )
+            })?;
+        }
+
+        let log = Self {
+            path: path.to_path_buf(),
+            name,
+            file,
+            truncated_tail: loaded.truncated_tail,
+            needs_newline: loaded.missing_final_newline,
+            entries: PhantomData,
+        };
+        Ok((log, loaded.entries))
+    }
+
+    /// Chemin du fichier.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Indique si une ligne tronquée a été retirée à l'ouverture.
+    pub fn recovered_truncated_tail(&self) -> bool {
+        self.truncated_tail
+    }
+
+    /// Ajoute une entrée et la vide sur disque.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si l'écriture échoue.
+    pub fn append(&mut self, entry: &T) -> Result<()> {
+        let mut line = if self.needs_newline {
+            String::from("\n")
+        } else {
+            String::new()
+        };
+        line.push_str(&serde_json::to_string(entry).context("Échec de la sérialisation JSON")?);
+        line.push('\n');
+        self.file
+            .write_all(line.as_bytes())
+            .and_then(|()| self.file.sync_data())
+            .with_context(|| {
+                format!(
+                    "Impossible d'écrire dans le {} '{}'",
+                    self.name,
+                    self.path.display()
+                )
+            })?;
+        self.needs_newline = false;
+        Ok(())
+    }
+}
+
+/// Met la première lettre en majuscule (ex: "journal" → "Journal").
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Entry {
+        id: u32,
+        label: String,
+    }
+
+    fn entry(id: u32) -> Entry {
+        Entry {
+            id,
+            label: format!("entrée {}", id),
+        }
+    }
+
+    fn line(entry: &Entry) -> String {
+        serde_json::to_string(entry).unwrap() + "\n"
+    }
+
+    #[test]
+    fn test_truncated_final_line_is_recovered() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lot.jsonl");
+        let partial = &line(&entry(3))[..10];
+        std::fs::write(&path, line(&entry(1)) + &line(&entry(2)) + partial).unwrap();
+
+        let (mut log, entries) = AppendLog::<Entry>::open(&path, "journal").unwrap();
+        assert!(log.recovered_truncated_tail());
+        assert_eq!(entries, [entry(1), entry(2)]);
+
+        // L'ajout suivant ne se colle pas à la ligne tronquée
+        log.append(&entry(3)).unwrap();
+        let reloaded =
+            parse_log::<Entry>(&std::fs::read_to_string(&path).unwrap(), "journal").unwrap();
+        assert!(!reloaded.truncated_tail);
+        assert_eq!(reloaded.entries, [entry(1), entry(2), entry(3)]);
+    }
+
+    #[test]
+    fn test_valid_final_line_without_newline_is_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registre.jsonl");
+        let edited = line(&entry(1)) + line(&entry(2)).trim_end();
+        std::fs::write(&path, &edited).unwrap();
+
+        let (mut log, entries) = AppendLog::<Entry>::open(&path, "registre").unwrap();
+        assert!(!log.recovered_truncated_tail());
+        assert_eq!(entries, [entry(1), entry(2)]);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), edited);
+
+        log.append(&entry(3)).unwrap();
+        log.append(&entry(4)).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            edited + "\n" + &line(&entry(3)) + &line(&entry(4))
+        );
+    }
+
+    #[test]
+    fn test_corrupted_complete_line_is_an_error() {
+        let content = format!("{}pas du json\n{}", line(&entry(1)), line(&entry(2)));
+        let error = parse_log::<Entry>(&content, "registre").unwrap_err();
+        assert!(
+            error.to_string().contains("Ligne 2 du registre invalide"),
+            "{}",
+            error
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        std::fs::write(&path, content).unwrap();
+        let error = AppendLog::<Entry>::open(&path, "journal").unwrap_err();
+        assert!(format!("{:#}", error).contains("Journal '"), "{:#}", error);
+    }
+}
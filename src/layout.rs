@@ -0,0 +1,524 @@
+//! Statistiques de mise en page et recommandation du mode de segmentation (PSM).
+//!
+//! `--test-all-psm` lance Tesseract quatorze fois ; le bon mode se devine
+//! pourtant souvent à la forme de la page. [`layout_stats()`] mesure, sans
+//! OCR, quelques caractéristiques peu coûteuses :
+//!
+//! - les blocs de texte de [`detect_text_regions()`], leur nombre, leur
+//!   rapport largeur/hauteur et la part de la page qu'ils couvrent ;
+//! - le nombre de lignes de la projection horizontale
+//!   ([`detect_text_lines()`]) ;
+//! - le nombre de colonnes de la projection verticale, séparées par des
+//!   gouttières vides d'au moins deux hauteurs de ligne.
+//!
+//! [`rank_psm()`] traduit ces mesures en une liste ordonnée de modes,
+//! chacun avec sa justification. Les règles ne dépendent que de
+//! [`LayoutStats`] et se testent sans image.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::PageSegMode;
+//! use text_recognition::layout::{LayoutStats, rank_psm};
+//!
+//! // Une seule région, large et basse, sur une ligne
+//! let stats = LayoutStats {
+//!     width: 400,
+//!     height: 60,
+//!     region_count: 1,
+//!     median_aspect_ratio: 9.0,
+//!     fill_ratio: 0.5,
+//!     line_count: 1,
+//!     column_count: 1,
+//! };
+//! assert_eq!(rank_psm(&stats)[0].mode, PageSegMode::SingleLine);
+//! ```
+
+use crate::config::PageSegMode;
+use crate::preprocessing::{detect_text_lines, otsu_threshold};
+use crate::segmentation::detect_text_regions;
+use image::GrayImage;
+use serde::Serialize;
+use std::fmt;
+
+/// Rapport largeur/hauteur minimal d'une région lue comme une seule ligne.
+pub const SINGLE_LINE_MIN_ASPECT: f32 = 3.0;
+
+/// Nombre minimal de régions d'une page de texte épars.
+pub const SPARSE_MIN_REGIONS: usize = 4;
+
+/// Part maximale de la page couverte par les régions d'une page de texte épars.
+pub const SPARSE_MAX_FILL: f32 = 0.3;
+
+/// Largeur minimale d'une gouttière entre colonnes, en hauteurs de ligne.
+///
+/// Les espaces entre mots d'une ligne isolée sont plus étroits.
+const MIN_GUTTER_LINES: u32 = 2;
+
+/// Part de l'encre de la colonne de pixels la plus encrée en deçà de
+/// laquelle une colonne de pixels est vide.
+const EMPTY_COLUMN_RATIO: f64 = 0.05;
+
+/// Mesures de mise en page d'une image, calculées par [`layout_stats()`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LayoutStats {
+    /// Largeur de l'image, en pixels.
+    pub width: u32,
+    /// Hauteur de l'image, en pixels.
+    pub height: u32,
+    /// Nombre de blocs de texte ([`detect_text_regions()`]).
+    pub region_count: usize,
+    /// Rapport largeur/hauteur médian des blocs (0 sans bloc).
+    pub median_aspect_ratio: f32,
+    /// Part de la surface de l'image couverte par les blocs, entre 0 et 1.
+    pub fill_ratio: f32,
+    /// Nombre de lignes de texte de la projection horizontale.
+    pub line_count: usize,
+    /// Nombre de colonnes de la projection verticale (0 sans texte).
+    pub column_count: usize,
+}
+
+/// Mode de segmentation suggéré, avec sa justification.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PsmSuggestion {
+    /// Mode suggéré.
+    pub mode: PageSegMode,
+    /// Raison de la suggestion, à afficher.
+    pub reason: String,
+}
+
+impl PsmSuggestion {
+    fn new(mode: PageSegMode, reason: impl Into<String>) -> Self {
+        Self {
+            mode,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Recommandation de mode de segmentation, retournée par
+/// [`recommend_psm()`](crate::ocr::recommend_psm).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PsmRecommendation {
+    /// Mesures de mise en page de l'image.
+    pub stats: LayoutStats,
+    /// Modes suggérés, du plus au moins adapté (jamais vide).
+    pub suggestions: Vec<PsmSuggestion>,
+}
+
+impl PsmRecommendation {
+    /// Classe les modes adaptés à des mesures avec [`rank_psm()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `stats` - Mesures de mise en page
+    pub fn from_stats(stats: LayoutStats) -> Self {
+        let suggestions = rank_psm(&stats);
+        Self { stats, suggestions }
+    }
+
+    /// Mode le plus adapté (la première suggestion).
+    pub fn top(&self) -> PageSegMode {
+        self.suggestions
+            .first()
+            .map_or(PageSegMode::Auto, |suggestion| suggestion.mode)
+    }
+}
+
+impl fmt::Display for PsmRecommendation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = &self.stats;
+        writeln!(
+            f,
+            "Mise en page : {} région(s) de texte, {} ligne(s), {} colonne(s), \
+             remplissage {:.0} %, rapport largeur/hauteur médian {:.1}",
+            stats.region_count,
+            stats.line_count,
+            stats.column_count,
+            stats.fill_ratio * 100.0,
+            stats.median_aspect_ratio
+        )?;
+        for (rank, suggestion) in self.suggestions.iter().enumerate() {
+            if rank > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{}. PSM {} - {} : {}",
+                rank + 1,
+                suggestion.mode.to_tesseract_psm(),
+                suggestion.mode.describe(),
+                suggestion.reason
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Mesure la mise en page d'une image.
+///
+/// # Arguments
+///
+/// * `image` - Image en niveaux de gris
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::layout::layout_stats;
+///
+/// let blank = GrayImage::from_pixel(200, 100, Luma([255]));
+/// let stats = layout_stats(&blank);
+/// assert_eq!((stats.region_count, stats.line_count, stats.column_count), (0, 0, 0));
+/// ```
+pub fn layout_stats(image: &GrayImage) -> LayoutStats {
+    let (width, height) = image.dimensions();
+    let regions = detect_text_regions(image);
+    let lines = detect_text_lines(image);
+
+    let mut aspects: Vec<f32> = regions
+        .iter()
+        .map(|region| region.width as f32 / region.height.max(1) as f32)
+        .collect();
+    aspects.sort_by(f32::total_cmp);
+    let covered: u64 = regions
+        .iter()
+        .map(|region| u64::from(region.width) * u64::from(region.height))
+        .sum();
+    let area = u64::from(width) * u64::from(height);
+
+    LayoutStats {
+        width,
+        height,
+        region_count: regions.len(),
+        median_aspect_ratio: aspects.get(aspects.len() / 2).copied().unwrap_or(0.0),
+        fill_ratio: if area == 0 {
+            0.0
+        } else {
+            (covered as f64 / area as f64).min(1.0) as f32
+        },
+        line_count: lines.len(),
+        column_count: column_count(image, &lines),
+    }
+}
+
+/// Classe les modes de segmentation adaptés à une mise en page.
+///
+/// Les règles sont appliquées dans l'ordre, la première qui s'applique
+/// donne le classement :
+///
+/// 1. aucun texte détecté : segmentation automatique ;
+/// 2. une seule région au moins [`SINGLE_LINE_MIN_ASPECT`] fois plus large
+///    que haute, sur une ligne : [`PageSegMode::SingleLine`] ;
+/// 3. au moins [`SPARSE_MIN_REGIONS`] régions couvrant moins de
+///    [`SPARSE_MAX_FILL`] de la page : [`PageSegMode::SparseText`] ;
+/// 4. plusieurs colonnes : [`PageSegMode::Auto`], qui les sépare, ou
+///    [`PageSegMode::SingleColumn`] colonne par colonne (`--columns`) ;
+/// 5. une seule région de plusieurs lignes : [`PageSegMode::SingleBlock`] ;
+/// 6. sinon : [`PageSegMode::Auto`].
+///
+/// # Arguments
+///
+/// * `stats` - Mesures de mise en page
+///
+/// # Retour
+///
+/// Les modes, du plus au moins adapté ; la liste n'est jamais vide.
+pub fn rank_psm(stats: &LayoutStats) -> Vec<PsmSuggestion> {
+    if stats.region_count == 0 || stats.line_count == 0 {
+        return vec![
+            PsmSuggestion::new(
+                PageSegMode::Auto,
+                "aucun texte détecté : segmentation automatique",
+            ),
+            PsmSuggestion::new(
+                PageSegMode::SparseText,
+                "texte peu contrasté ou très épars possible",
+            ),
+        ];
+    }
+
+    if stats.region_count == 1
+        && stats.line_count == 1
+        && stats.median_aspect_ratio >= SINGLE_LINE_MIN_ASPECT
+    {
+        return vec![
+            PsmSuggestion::new(
+                PageSegMode::SingleLine,
+                format!(
+                    "une seule région large et basse (rapport {:.1}) sur une ligne",
+                    stats.median_aspect_ratio
+                ),
+            ),
+            PsmSuggestion::new(
+                PageSegMode::RawLine,
+                "même ligne, sans les ajustements propres à Tesseract",
+            ),
+            PsmSuggestion::new(PageSegMode::SingleBlock, "bloc d'une seule ligne"),
+        ];
+    }
+
+    if stats.region_count >= SPARSE_MIN_REGIONS && stats.fill_ratio < SPARSE_MAX_FILL {
+        return vec![
+            PsmSuggestion::new(
+                PageSegMode::SparseText,
+                format!(
+                    "{} petites régions dispersées couvrant {:.0} % de la page",
+                    stats.region_count,
+                    stats.fill_ratio * 100.0
+                ),
+            ),
+            PsmSuggestion::new(
+                PageSegMode::Auto,
+                "segmentation automatique si les régions forment des paragraphes",
+            ),
+        ];
+    }
+
+    if stats.column_count >= 2 {
+        return vec![
+            PsmSuggestion::new(
+                PageSegMode::Auto,
+                format!(
+                    "{} colonnes : la segmentation automatique les sépare",
+                    stats.column_count
+                ),
+            ),
+            PsmSuggestion::new(
+                PageSegMode::SingleColumn,
+                "colonne par colonne, après découpage (--columns)",
+            ),
+        ];
+    }
+
+    if stats.region_count == 1 {
+        return vec![
+            PsmSuggestion::new(
+                PageSegMode::SingleBlock,
+                format!("un seul bloc dense de {} lignes", stats.line_count),
+            ),
+            PsmSuggestion::new(
+                PageSegMode::SingleColumn,
+                "une colonne de lignes de tailles variables",
+            ),
+            PsmSuggestion::new(PageSegMode::Auto, "segmentation automatique"),
+        ];
+    }
+
+    vec![
+        PsmSuggestion::new(
+            PageSegMode::Auto,
+            format!(
+                "{} régions sur une colonne : segmentation automatique",
+                stats.region_count
+            ),
+        ),
+        PsmSuggestion::new(
+            PageSegMode::SingleColumn,
+            "une colonne de blocs de tailles variables",
+        ),
+    ]
+}
+
+/// Nombre de colonnes de texte : les gouttières sont les suites de colonnes
+/// de pixels vides, entre la première et la dernière encre, larges d'au
+/// moins [`MIN_GUTTER_LINES`] hauteurs de ligne médianes.
+fn column_count(image: &GrayImage, lines: &[std::ops::Range<u32>]) -> usize {
+    if lines.is_empty() {
+        return 0;
+    }
+    let mut heights: Vec<u32> = lines.iter().map(|line| line.end - line.start).collect();
+    heights.sort_unstable();
+    let min_gutter = heights[heights.len() / 2] * MIN_GUTTER_LINES;
+
+    let threshold = otsu_threshold(image);
+    let mut profile = vec![0u64; image.width() as usize];
+    for (x, _, pixel) in image.enumerate_pixels() {
+        if pixel[0] <= threshold {
+            profile[x as usize] += 1;
+        }
+    }
+    let max = profile.iter().copied().max().unwrap_or(0);
+    let empty_limit = max as f64 * EMPTY_COLUMN_RATIO;
+    let inked: Vec<bool> = profile
+        .iter()
+        .map(|&value| value as f64 > empty_limit)
+        .collect();
+    let (Some(first), Some(last)) = (
+        inked.iter().position(|&ink| ink),
+        inked.iter().rposition(|&ink| ink),
+    ) else {
+        return 0;
+    };
+
+    let mut columns = 1;
+    let mut gap = 0u32;
+    for &ink in &inked[first..=last] {
+        if ink {
+            if gap >= min_gutter {
+                columns += 1;
+            }
+            gap = 0;
+        } else {
+            gap += 1;
+        }
+    }
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{RenderOptions, render_text_image};
+    use image::{Luma, imageops};
+
+    fn stats(
+        region_count: usize,
+        aspect: f32,
+        fill: f32,
+        lines: usize,
+        columns: usize,
+    ) -> LayoutStats {
+        LayoutStats {
+            width: 800,
+            height: 600,
+            region_count,
+            median_aspect_ratio: aspect,
+            fill_ratio: fill,
+            line_count: lines,
+            column_count: columns,
+        }
+    }
+
+    fn modes(stats: &LayoutStats) -> Vec<PageSegMode> {
+        rank_psm(stats)
+            .into_iter()
+            .map(|suggestion| suggestion.mode)
+            .collect()
+    }
+
+    fn render(text: &str) -> GrayImage {
+        render_text_image(text, &RenderOptions::default()).to_luma8()
+    }
+
+    /// Page blanche portant des étiquettes courtes dispersées.
+    fn sparse_labels() -> GrayImage {
+        let mut page = GrayImage::from_pixel(900, 700, Luma([255]));
+        let labels = [
+            ("Lot 12", 40, 30),
+            ("Ref A-7", 600, 60),
+            ("12,50", 250, 250),
+            ("Zone B", 650, 380),
+            ("Poids 3 kg", 60, 560),
+        ];
+        for (text, x, y) in labels {
+            imageops::replace(&mut page, &render(text), x, y);
+        }
+        page
+    }
+
+    #[test]
+    fn test_rank_rules_from_stats() {
+        assert_eq!(modes(&stats(0, 0.0, 0.0, 0, 0))[0], PageSegMode::Auto);
+        assert_eq!(
+            modes(&stats(1, 8.0, 0.4, 1, 1)),
+            [
+                PageSegMode::SingleLine,
+                PageSegMode::RawLine,
+                PageSegMode::SingleBlock
+            ]
+        );
+        assert_eq!(
+            modes(&stats(9, 3.0, 0.1, 6, 4)),
+            [PageSegMode::SparseText, PageSegMode::Auto]
+        );
+        assert_eq!(
+            modes(&stats(2, 0.5, 0.6, 30, 2)),
+            [PageSegMode::Auto, PageSegMode::SingleColumn]
+        );
+        assert_eq!(
+            modes(&stats(1, 2.0, 0.7, 12, 1))[0],
+            PageSegMode::SingleBlock
+        );
+        assert_eq!(modes(&stats(3, 2.0, 0.5, 12, 1))[0], PageSegMode::Auto);
+    }
+
+    #[test]
+    fn test_rule_boundaries() {
+        // Une région carrée d'une ligne (un mot court) n'est pas une ligne large
+        assert_eq!(
+            modes(&stats(1, 2.0, 0.4, 1, 1))[0],
+            PageSegMode::SingleBlock
+        );
+        // Peu de régions ou une page remplie : pas du texte épars
+        assert_eq!(modes(&stats(3, 3.0, 0.1, 3, 1))[0], PageSegMode::Auto);
+        assert_eq!(modes(&stats(6, 3.0, 0.5, 6, 1))[0], PageSegMode::Auto);
+        // Plusieurs colonnes d'un seul bloc chacune
+        assert_eq!(modes(&stats(1, 1.2, 0.6, 20, 2))[0], PageSegMode::Auto);
+    }
+
+    #[test]
+    fn test_recommendation_display() {
+        let recommendation = PsmRecommendation::from_stats(stats(1, 8.0, 0.4, 1, 1));
+        assert_eq!(recommendation.top(), PageSegMode::SingleLine);
+        let text = recommendation.to_string();
+        assert!(text.starts_with("Mise en page : 1 région(s) de texte, 1 ligne(s)"));
+        assert!(text.contains("\n1. PSM 7 - Single line : une seule région"));
+        assert!(text.ends_with("3. PSM 6 - Single block : bloc d'une seule ligne"));
+    }
+
+    #[test]
+    fn test_one_liner_is_single_line() {
+        let stats = layout_stats(&render("Facture 2024-118 du 12/03"));
+        assert_eq!(
+            (stats.region_count, stats.line_count),
+            (1, 1),
+            "{:?}",
+            stats
+        );
+        assert_eq!(rank_psm(&stats)[0].mode, PageSegMode::SingleLine);
+    }
+
+    #[test]
+    fn test_paragraph_is_single_block() {
+        let image = render(
+            "Facture n. 2024-118 du 12 mars\nTotal TTC : 12,50 EUR\n\
+             Reglement par virement\nMerci de votre visite",
+        );
+        let stats = layout_stats(&image);
+        assert_eq!(
+            (stats.region_count, stats.line_count),
+            (1, 4),
+            "{:?}",
+            stats
+        );
+        assert_eq!(stats.column_count, 1);
+        assert_eq!(rank_psm(&stats)[0].mode, PageSegMode::SingleBlock);
+    }
+
+    #[test]
+    fn test_sparse_labels_are_sparse_text() {
+        let stats = layout_stats(&sparse_labels());
+        assert_eq!(stats.region_count, 5, "{:?}", stats);
+        assert!(stats.fill_ratio < SPARSE_MAX_FILL);
+        assert_eq!(rank_psm(&stats)[0].mode, PageSegMode::SparseText);
+    }
+
+    #[test]
+    fn test_two_columns_are_counted() {
+        let column = render("Premier article\nde la page sur\nplusieurs lignes\nde texte suivi");
+        let mut page =
+            GrayImage::from_pixel(column.width() * 2 + 120, column.height(), Luma([255]));
+        imageops::replace(&mut page, &column, 0, 0);
+        imageops::replace(&mut page, &column, i64::from(column.width()) + 120, 0);
+
+        let stats = layout_stats(&page);
+        assert_eq!(
+            (stats.region_count, stats.column_count),
+            (2, 2),
+            "{:?}",
+            stats
+        );
+        assert_eq!(rank_psm(&stats)[0].mode, PageSegMode::Auto);
+    }
+}
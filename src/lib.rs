@@ -6,6 +6,8 @@
 //! # Exemple d'utilisation
 //!
 //! ```no_run
+//! # #[cfg(feature = "tesseract")]
+//! # {
 //! use text_recognition::{OcrEngine, OcrConfig};
 //! use std::path::Path;
 //!
@@ -13,32 +15,270 @@
 //! let engine = OcrEngine::new(config)?;
 //! let text = engine.extract_text_from_file(Path::new("image.png"))?;
 //! println!("Texte extrait: {}", text);
+//! # }
 //! # Ok::<(), anyhow::Error>(())
 //! ```
 //!
+//! # Features
+//!
+//! La feature `tesseract` (par défaut) active le moteur OCR et tout ce qui
+//! en dépend : modules `ocr`, `pool`, `pages`, `cancellation`, `bench`,
+//! `tuning`, `determinism`, `result_cache`, `run_metadata`, `experiments`,
+//! `worker` et `debug_artifacts`, génération HOCR (`hocr::generate_hocr*`) et
+//! vocabulaire temporaire (`OcrConfig::with_vocabulary`). Sans elle
+//! (`default-features = false`), la bibliothèque ne contient que du Rust
+//! pur (configuration, prétraitement, métriques, analyse HOCR...) et
+//! peut viser `wasm32-unknown-unknown`.
+//!
 //! # Modules
 //!
+//! - `async_ocr` : API asynchrone `tokio` à concurrence bornée (feature `async`)
+//! - `bench` : Benchmark de configurations sur un corpus annoté
+//! - `cancellation` : Annulation et délais des extractions longues
+//! - `checkpoint` : Journal de reprise des traitements batch interrompus
+//! - `collect` : Collecte des fichiers images (répertoires, récursion, glob)
 //! - `config` : Configuration du moteur OCR et modes de segmentation
 //! - `config_file` : Chargement de configuration depuis des fichiers JSON/TOML
+//! - `cross_check` : Vérification croisée de la bibliothèque Tesseract et du binaire
+//! - `debug_artifacts` : Fichiers intermédiaires conservés pour déboguer une extraction
+//! - `dedup` : Détection des images en double (empreinte perceptuelle dHash)
+//! - `determinism` : Audit du déterminisme des résultats (exécutions répétées)
+//! - `diagnostics` : Avertissements émis par Tesseract (résolution, page vide...)
+//! - `encoding` : Encodage (UTF-8, BOM, UTF-16LE, Latin-1) et fins de ligne des fichiers texte produits
+//! - `effective_config` : Configuration effectivement transmise à Tesseract
+//! - `estimate` : Estimation de la durée et du volume d'un traitement batch
+//! - `experiments` : Registre des expériences (une ligne JSON par exécution d'un lot annoté)
 //! - `ocr` : Moteur OCR principal pour l'extraction de texte
+//! - `pages` : Lecture page par page des TIFF multipages (itérateur paresseux)
+//! - `overlay` : Calque JSON de surlignage des mots en coordonnées normalisées
+//! - `output_path` : Noms des fichiers de sortie du mode batch (sans collision, gabarits)
+//! - `postprocess` : Nettoyage du texte extrait (césures, espaces, guillemets)
+//! - `pool` : Pool borné de moteurs OCR pour l'extraction concurrente
 //! - `preprocessing` : Prétraitement d'images pour améliorer la qualité OCR
-//! - `metrics` : Calcul de métriques de qualité OCR (CER, WER)
-//! - `hocr` : Extraction et visualisation des bounding boxes au format HOCR
+//! - `pyramid` : Lecture pyramidale (échelles croissantes, arrêt dès que le texte est stable)
+//! - `ensemble` : Fusion de plusieurs exécutions OCR (confiance, vote majoritaire)
+//! - `export` : Export d'un document HOCR en Markdown ou en HTML, et des lignes en paires d'entraînement
+//! - `metadata` : Transcriptions de référence intégrées aux images (chunk PNG, EXIF JPEG)
+//! - `metrics` : Calcul de métriques de qualité OCR (CER, WER) et rapports de comparaison (texte, HTML)
+//! - `jsonl` : Fichiers JSON Lines en ajout seul (journal de reprise, registre des expériences)
+//! - `hocr` : Extraction et visualisation des bounding boxes au format HOCR, carte de chaleur des confiances et zones peu fiables à relire
+//! - `layout` : Statistiques de mise en page et recommandation du mode de segmentation (PSM)
+//! - `result_cache` : Cache des résultats OCR indexé par le contenu de l'image
+//! - `run_metadata` : Métadonnées de reproductibilité des métriques exportées
+//! - `segmentation` : Classification des régions d'une page (texte ou image) et blocs inclinés
+//! - `server` : Serveur HTTP d'OCR à concurrence bornée (feature `server`)
+//! - `structured` : Texte extrait structuré en paragraphes, lignes et mots
+//! - `tuning` : Étude d'ablation des étapes du prétraitement
+//! - `validation` : Vérifications préalables des fichiers images (vide, tronqué...)
+//! - `worker` : Extraction dans un processus enfant, pour qu'un plantage n'affecte qu'une image
+//! - `warm_up` : Préchauffage du moteur sur une image embarquée et vérification de santé
+//! - `receipts` : Analyse des tickets de caisse et contrôle de leurs totaux
+//! - `redaction` : Masquage des données sensibles (IBAN, e-mails) dans les images
+//! - `regions_io` : Fichiers de régions nommées (labelme, format simple) et réécriture avec le texte reconnu
+//! - `rng` : Générateur pseudo-aléatoire déterministe SplitMix64 (échantillonnage, tests, bancs d'essai)
+//! - `region` : Régions d'image en fractions de ses dimensions (captures d'écran)
+//! - `testutil` : Images de texte synthétiques pour les tests (feature `testutil`)
 
+#[cfg(feature = "async")]
+pub mod async_ocr;
+#[cfg(feature = "tesseract")]
+pub mod bench;
+#[cfg(feature = "tesseract")]
+pub mod cancellation;
+pub mod checkpoint;
+pub mod collect;
 pub mod config;
 pub mod config_file;
+#[cfg(feature = "tesseract")]
+pub mod cross_check;
+#[cfg(feature = "tesseract")]
+pub mod debug_artifacts;
+pub mod dedup;
+#[cfg(feature = "tesseract")]
+pub mod determinism;
+pub mod diagnostics;
+pub mod effective_config;
+pub mod encoding;
+pub mod ensemble;
+pub mod estimate;
+#[cfg(feature = "tesseract")]
+pub mod experiments;
+pub mod export;
 pub mod hocr;
+pub mod jsonl;
+pub mod layout;
+pub mod metadata;
 pub mod metrics;
+#[cfg(feature = "tesseract")]
 pub mod ocr;
+pub mod output_path;
+pub mod overlay;
+#[cfg(feature = "tesseract")]
+pub mod pages;
+mod png;
+#[cfg(feature = "tesseract")]
+pub mod pool;
+pub mod postprocess;
 pub mod preprocessing;
+pub mod pyramid;
+pub mod receipts;
+pub mod redaction;
+pub mod region;
+pub mod regions_io;
+#[cfg(feature = "tesseract")]
+pub mod result_cache;
+pub mod rng;
+#[cfg(feature = "tesseract")]
+pub mod run_metadata;
+pub mod segmentation;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod structured;
+#[cfg(any(test, feature = "testutil"))]
+pub mod testutil;
+#[cfg(feature = "tesseract")]
+pub mod tuning;
+pub mod validation;
+#[cfg(feature = "tesseract")]
+pub mod warm_up;
+#[cfg(feature = "tesseract")]
+pub mod worker;
 
 // Exports publics pour faciliter l'utilisation de la bibliothèque
-pub use config::{OcrConfig, PageSegMode};
-pub use config_file::{AppConfig, load_config};
-pub use hocr::{BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord, generate_hocr};
+#[cfg(feature = "async")]
+pub use async_ocr::AsyncOcrEngine;
+#[cfg(feature = "tesseract")]
+pub use bench::{
+    BenchmarkManifest, BenchmarkReport, ConfigSummary, NamedConfig, OcrRunner, TesseractRunner,
+    load_manifest, run_benchmark,
+};
+#[cfg(feature = "tesseract")]
+pub use cancellation::CancellationToken;
+pub use checkpoint::{Journal, JournalEntry};
+pub use collect::{CollectOptions, collect_image_files, input_root, is_image_file_with};
+pub use config::{
+    OcrConfig, OcrConfigPatch, OcrEngineMode, PageSegMode, parse_tesseract_config,
+    resolve_tesseract_config,
+};
+pub use config_file::{
+    AppConfig, ConfigFileError, MAX_CONFIG_FILE_SIZE, ProfileRule, load_config,
+    load_config_with_preset, merge_app_config, resolve_profile,
+};
+#[cfg(feature = "tesseract")]
+pub use cross_check::{CROSS_CHECK_MAX_CER, CrossCheckReport};
+#[cfg(feature = "tesseract")]
+pub use debug_artifacts::{ArtifactSidecar, PassTiming, SIDECAR_FILE_NAME};
+pub use dedup::{DuplicateGroup, find_duplicates, hamming_distance, perceptual_hash};
+#[cfg(feature = "tesseract")]
+pub use determinism::{
+    DeterminismReport, MIN_DETERMINISM_RUNS, OutputVariant, VariantDifference,
+    determinism_check_with,
+};
+pub use diagnostics::{Warning, WarningKind, parse_tesseract_stderr};
+pub use effective_config::{EffectiveConfig, is_known_tesseract_parameter};
+pub use encoding::{EncodedText, NewlineStyle, OutputEncoding, encode_text, encode_text_detailed};
+pub use ensemble::{EnsembleResult, MergeStrategy, align_words_by_overlap, merge_documents};
+pub use estimate::{Estimate, estimate_total_bytes, extrapolate, sample_indices};
+#[cfg(feature = "tesseract")]
+pub use experiments::{
+    ComparisonSummary, ExperimentLedger, ExperimentMetric, MetricDelta, RunMetrics, RunRecord,
+};
+pub use export::ExportOptions;
+pub use export::lines::{ExportedLine, LineExportOptions, LineExportSummary, export_line_pairs};
+pub use hocr::{
+    Alternative, BBox, Baseline, DEFAULT_REVIEW_THRESHOLD, HOCR_JSON_FORMAT_VERSION, HeatmapCanvas,
+    HeatmapOptions, HocrDocument, HocrJsonError, HocrLine, HocrPage, HocrParagraph,
+    HocrParseWarning, HocrStreamError, HocrWord, LayoutTextOptions, LineId, LineMergeOptions,
+    PAGE_SEPARATOR, ParagraphId, PhraseMatch, ReadingOrder, Script, SortOrder, TextDirection,
+    TextMetricsEstimate, WordAlternatives, classify_script, confidence_heatmap,
+    estimate_text_metrics, merge_split_lines, parse_alternatives, parse_hocr_stream,
+    reorder_reading_order, rescore_with, save_region_crops, suggest_dpi, to_layout_text,
+};
+#[cfg(feature = "tesseract")]
+pub use hocr::{generate_hocr, generate_hocr_detailed, generate_hocr_with_timeout};
+pub use layout::{LayoutStats, PsmRecommendation, PsmSuggestion, layout_stats, rank_psm};
+pub use metrics::sink::{CsvMetricsSink, JsonlMetricsSink, MetricsCollection, MetricsSink};
+pub use metrics::spatial::{
+    DEFAULT_IOU_THRESHOLD, DetectionMetrics, WordAnnotation, WordMatch, evaluate_detection,
+    load_annotations, match_words,
+};
+pub use metrics::unordered::{
+    Granularity, UnorderedComparison, compare_unordered, solve_assignment, split_segments,
+};
 pub use metrics::{
-    OcrMetrics, TextError, calculate_cer, calculate_wer, compare_ocr_result, generate_diff_report,
-    levenshtein_distance,
+    CharClass, ClassBreakdown, ClassStats, ConfusionEntry, ConfusionMatrix, CsvLineEnding,
+    CsvOptions, DEFAULT_HTML_CONTEXT, DetailedOcrMetrics, FAST_LEVENSHTEIN_THRESHOLD, FuzzyMatch,
+    HtmlReportOptions, LineComparison, MISSING_CHAR, OcrMetrics, StreamingComparer, TextError,
+    Tokenizer, TokenizerKind, WordDiff, calculate_cer, calculate_wer, calculate_wer_with_tokenizer,
+    character_confusions, character_errors, compare_by_line, compare_files, compare_ocr_result,
+    compare_ocr_result_detailed, compare_ocr_result_detailed_with, compare_ocr_result_with,
+    fuzzy_find, generate_diff_report, generate_diff_report_with, generate_html_report,
+    levenshtein_distance, levenshtein_distance_fast, word_diff,
+};
+#[cfg(feature = "tesseract")]
+pub use ocr::{
+    ComparedImage, ImageComparison, OcrEngine, OcrError, OcrOutcome, PyramidOutcome, RegionResult,
+    check_languages, compare_images, cross_check, default_script_languages, determinism_check,
+    ensemble_extract, installed_languages, recommend_psm, validate_languages,
+};
+pub use output_path::{
+    OutputMapping, OutputPathOptions, OutputTemplate, TemplateValues, format_utc_date,
+    map_output_path, plan_output_paths, plan_templated_output_paths,
+};
+pub use overlay::{OverlayItem, OverlayLevel, OverlayOptions};
+#[cfg(feature = "tesseract")]
+pub use pages::{PageIter, PageResult, PageTimings};
+#[cfg(feature = "tesseract")]
+pub use pool::{OcrEnginePool, OcrWorker, PoolExhaustion, PooledEngine};
+pub use postprocess::{
+    Corrector, NEUTRAL_PLAUSIBILITY, RescoreWeights, TextCleanup, rescore_words, truncate_chars,
+};
+pub use preprocessing::{
+    BinarizationMethod, BlankDetectionOptions, BlankPageResult, Channel, ChannelWeights,
+    ComponentLabels, ComponentStats, Connectivity, DEFAULT_DESKEW_DETECTION_PIXELS,
+    DEFAULT_MAX_PIXELS, DEFAULT_MIN_SKEW_IMPROVEMENT, DeskewOptions, DeskewOutcome, DeskewReason,
+    GrayscaleOptions, MIN_DESKEW_DIMENSION, MIN_GUTTER_COLUMNS, MIN_TEXT_LINE_ROWS, Orientation,
+    OrientationInfo, PROFILE_DESPECKLE_AREA, PageContent, PreprocessingConfig,
+    PreprocessingConfigPatch, PreprocessingError, PreprocessingProfile, PreprocessingReport,
+    connected_components, deskew_checked, detect_page_split, detect_text_lines,
+    detect_vertical_text, full_orientation_correction, histogram, is_blank_page,
+    is_blank_page_masked, multi_otsu_thresholds, otsu_threshold, otsu_threshold_masked,
+    preprocess_image_with_mask, remove_small_components, rotate_orientation, split_pages,
+    to_grayscale_with,
+};
+pub use pyramid::{
+    DEFAULT_PYRAMID_LEVELS, PyramidConfig, PyramidLevel, PyramidReport, is_stable, level_dpi,
+    run_pyramid,
+};
+pub use receipts::{
+    DEFAULT_RECEIPT_EPSILON, ReceiptCheck, ReceiptItem, ReceiptOptions, ReceiptParse,
+    ReceiptRelation, SuspectLine, SuspectReason, TaxLine, parse_receipt, parse_receipt_with,
+};
+pub use redaction::{FieldMatch, FieldMatcher, RedactionStyle, redact_matching, redact_regions};
+pub use region::RelativeRegion;
+pub use regions_io::{NamedRegion, RegionShape, RegionsFile, RegionsFormat};
+#[cfg(feature = "tesseract")]
+pub use result_cache::{CachedResult, DirectoryCache, MemoryCache, ResultCache, cache_key};
+#[cfg(feature = "tesseract")]
+pub use run_metadata::{RUN_METADATA_KEYS, RunMetadataBuilder, build_run_metadata};
+pub use segmentation::{
+    DEFAULT_IMAGE_OVERLAP, MIN_REGION_SKEW, RegionClass, RegionFeatures, SkewedRegion,
+    StraightenedRegion, classify_regions, detect_skewed_components, detect_text_regions,
+    drop_words_in_image_regions, drop_words_in_regions, split_skewed_regions,
+};
+#[cfg(feature = "server")]
+pub use server::{EngineBackend, OcrBackend, OcrServer, OcrService};
+pub use structured::StructuredText;
+#[cfg(feature = "tesseract")]
+pub use tuning::{
+    AblationOptions, AblationReport, PreprocessingStep, StepImpact, ablation_study,
+    ablation_study_with,
+};
+pub use validation::{ImageFileError, ImageFileInfo, MIN_IMAGE_DIMENSION, validate_image_file};
+#[cfg(feature = "tesseract")]
+pub use warm_up::{WARM_UP_IMAGE, WARM_UP_WORD, WarmUpReport};
+#[cfg(feature = "tesseract")]
+pub use worker::{
+    WORKER_ARG, WORKER_PROTOCOL_VERSION, WorkerCommand, WorkerJob, WorkerOutcome, WorkerResult,
+    run_worker,
 };
-pub use ocr::OcrEngine;
-pub use preprocessing::{BinarizationMethod, Orientation, PreprocessingConfig, rotate_orientation};
@@ -4,15 +4,77 @@
 //! et extraire du texte depuis des images en utilisant Tesseract.
 
 use anyhow::{Context, Result};
-use clap::Parser;
-use std::collections::HashMap;
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser};
+use image::DynamicImage;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 use std::fs;
+use std::io::Read;
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use text_recognition::hocr::Level;
+use text_recognition::preprocessing::{
+    PageContent, is_blank_page, preprocess_image, preprocess_image_with_dump, to_grayscale,
+};
+use text_recognition::pyramid::PyramidConfig;
+use text_recognition::run_metadata::sha256_file;
+use text_recognition::worker::{WORKER_ARG, WorkerCommand, run_worker};
 use text_recognition::{
-    BinarizationMethod, HocrDocument, OcrConfig, OcrEngine, PageSegMode, PreprocessingConfig,
-    compare_ocr_result, generate_diff_report, generate_hocr, load_config,
+    AblationOptions, AppConfig, BBox, BinarizationMethod, BlankDetectionOptions, CancellationToken,
+    Channel, ChannelWeights, CollectOptions, ComparedImage, ConfusionMatrix, CsvMetricsSink,
+    CsvOptions, DEFAULT_IOU_THRESHOLD, DEFAULT_MAX_PIXELS, DEFAULT_REVIEW_THRESHOLD, DeskewOptions,
+    DetailedOcrMetrics, DetectionMetrics, DeterminismReport, DirectoryCache, DuplicateGroup,
+    EffectiveConfig, Estimate, ExperimentLedger, ExportOptions, FieldMatcher, FuzzyMatch,
+    GrayscaleOptions, HeatmapOptions, HocrDocument, HtmlReportOptions, ImageComparison,
+    ImageFileError, Journal, JournalEntry, JsonlMetricsSink, LayoutTextOptions, LineExportOptions,
+    LineMergeOptions, MetricsCollection, NamedConfig, NewlineStyle, OcrConfig, OcrConfigPatch,
+    OcrEngine, OcrEngineMode, OcrEnginePool, OcrError, OcrMetrics, OcrOutcome, OutputEncoding,
+    OutputMapping, OutputPathOptions, OutputTemplate, PageSegMode, PhraseMatch,
+    PreprocessingConfig, PreprocessingConfigPatch, PreprocessingError, PreprocessingProfile,
+    ProfileRule, PsmRecommendation, RedactionStyle, RegionsFile, RelativeRegion,
+    RunMetadataBuilder, RunMetrics, RunRecord, StructuredText, TemplateValues, TesseractRunner,
+    TextCleanup, Tokenizer, Warning, WordAnnotation, WordDiff, ablation_study_with,
+    check_languages, collect_image_files, compare_images, compare_ocr_result_detailed_with,
+    confidence_heatmap, cross_check, determinism_check, encode_text_detailed, estimate_total_bytes,
+    evaluate_detection, export_line_pairs, extrapolate, find_duplicates, format_utc_date,
+    generate_diff_report_with, generate_hocr_with_timeout, generate_html_report, input_root,
+    installed_languages, load_annotations, load_config, load_config_with_preset, load_manifest,
+    metadata::{REFERENCE_KEY, read_embedded_reference},
+    parse_receipt, plan_output_paths, plan_templated_output_paths, recommend_psm, redact_matching,
+    resolve_profile, run_benchmark, sample_indices, save_region_crops, to_layout_text,
+    truncate_chars, validate_image_file,
 };
 
+/// Format de sortie du texte extrait (option --format).
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    /// Texte brut.
+    Text,
+    /// Structure JSON (paragraphes, lignes et mots).
+    Json,
+    /// Markdown (titres, listes et paragraphes).
+    Markdown,
+    /// Fragment HTML (`<h1>`, `<ul>`, `<p>`...).
+    Html,
+}
+
+impl OutputFormat {
+    /// Nom du format tel qu'accepté par --format.
+    fn name(self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Markdown => "markdown",
+            OutputFormat::Html => "html",
+        }
+    }
+}
+
 /// Outil d'extraction de texte depuis des images (OCR).
 ///
 /// Utilise Tesseract OCR pour extraire du texte depuis des images.
@@ -20,10 +82,30 @@ use text_recognition::{
 #[derive(Parser, Debug)]
 #[command(name = "text-recognition")]
 #[command(author, version, about, long_about = None)]
+#[command(group(
+    clap::ArgGroup::new("preprocessing")
+        .args(["preprocess", "preprocess_profile"])
+        .multiple(true)
+))]
+#[command(group(
+    clap::ArgGroup::new("reference")
+        .args(["expected", "expected_embedded"])
+))]
+#[command(group(
+    clap::ArgGroup::new("metrics_export")
+        .args(["csv_export", "metrics_jsonl"])
+        .multiple(true)
+))]
 struct Args {
     /// Chemin vers l'image à analyser (ou pattern glob en mode batch)
-    #[arg(value_name = "IMAGE")]
-    image: PathBuf,
+    ///
+    /// "-" lit l'image sur l'entrée standard (ex: `screenshot | text-recognition -`).
+    /// Non requis avec --bench, --list-presets, --list-languages ou --dry-run.
+    #[arg(
+        value_name = "IMAGE",
+        required_unless_present_any = ["bench", "list_presets", "list_languages", "dry_run", "serve"]
+    )]
+    image: Option<PathBuf>,
 
     /// Langue pour l'OCR
     ///
@@ -42,6 +124,48 @@ struct Args {
     #[arg(short, long, alias = "lang", default_value = "fra")]
     language: String,
 
+    /// Preset de configuration OCR
+    ///
+    /// Sert de base à la configuration OCR ; les options --language, --psm
+    /// et --dpi, ainsi que la section ocr d'un fichier --config, s'appliquent
+    /// par-dessus. Voir --list-presets pour les noms disponibles.
+    ///
+    /// Exemple: --preset receipt
+    #[arg(long, value_name = "NAME")]
+    preset: Option<String>,
+
+    /// Lister les presets disponibles et quitter
+    #[arg(long)]
+    list_presets: bool,
+
+    /// Lister les langues Tesseract installées et quitter
+    ///
+    /// Codes utilisables avec --language (ex: eng, fra), lus avec
+    /// `tesseract --list-langs` (TESSDATA_PREFIX est pris en compte).
+    #[arg(long)]
+    list_languages: bool,
+
+    /// Démarrer un serveur HTTP d'OCR (nécessite la feature `server`)
+    ///
+    /// Routes : POST /ocr (image brute, `Content-Type: image/*`, réponse
+    /// JSON `{"text", "duration_ms", "warnings"}`), POST /hocr (hOCR brut)
+    /// et GET /healthz. Paramètres de requête : language, psm, preprocess.
+    /// Les options de configuration (--language, --preprocess, --timeout...)
+    /// servent de valeurs par défaut.
+    ///
+    /// Exemple: --serve 127.0.0.1:8080 --serve-workers 4
+    #[arg(long, value_name = "ADDR:PORT", conflicts_with_all = ["batch", "bench"])]
+    serve: Option<String>,
+
+    /// Nombre maximal d'extractions simultanées du serveur
+    #[arg(long, value_name = "N", default_value_t = 2, requires = "serve",
+          value_parser = clap::value_parser!(u64).range(1..))]
+    serve_workers: u64,
+
+    /// Taille maximale du corps d'une requête du serveur, en octets
+    #[arg(long, value_name = "BYTES", default_value_t = 20 * 1024 * 1024, requires = "serve")]
+    max_body_size: usize,
+
     /// Mode de segmentation de page (PSM: 0-13)
     ///
     /// Modes disponibles:
@@ -59,13 +183,89 @@ struct Args {
     ///  11 = Texte épars
     ///  12 = Texte épars avec OSD
     ///  13 = Ligne brute
-    #[arg(short = 'p', long, default_value_t = 3, value_parser = clap::value_parser!(i32).range(0..=13))]
-    psm: i32,
+    ///
+    /// Le nom du mode est aussi accepté, sans tenir compte de la casse :
+    /// single-block, single_block ou SingleBlock pour le mode 6.
+    #[arg(short = 'p', long, default_value = "3")]
+    psm: PageSegMode,
+
+    /// Moteur de reconnaissance Tesseract (OEM)
+    ///
+    /// Valeurs possibles:
+    ///   0 = Moteur legacy uniquement (nécessite les modèles legacy de tessdata)
+    ///   1 = Réseau LSTM uniquement
+    ///   2 = Legacy et LSTM combinés (nécessite les modèles legacy)
+    ///   3 = Défaut, selon les modèles disponibles
+    ///
+    /// Sans cette option, la valeur du fichier de configuration est utilisée.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(i32).range(0..=3))]
+    oem: Option<i32>,
 
     /// Résolution DPI de l'image
     #[arg(short, long, default_value_t = 300)]
     dpi: u32,
 
+    /// Ajuster le DPI d'après la taille du texte reconnu
+    ///
+    /// Pour les images de résolution inconnue : si la taille du texte mesurée
+    /// lors d'une première analyse suggère un DPI éloigné de plus de 25 % de
+    /// --dpi, l'image est analysée à nouveau et le résultat comptant le plus
+    /// de mots est conservé.
+    #[arg(long)]
+    adaptive_dpi: bool,
+
+    /// Lire l'image d'abord réduite, la pleine résolution en dernier recours
+    ///
+    /// L'image est lue aux échelles données (par défaut 0.3,0.6,1), avec un
+    /// DPI réduit d'autant, jusqu'à ce que deux niveaux consécutifs donnent
+    /// le même texte (même nombre de mots, CER d'au plus 2 %) avec une
+    /// confiance moyenne d'au moins 80 : une grande photo est souvent lue
+    /// dix fois plus vite. L'avertissement affiché indique l'échelle retenue
+    /// et le temps gagné estimé.
+    ///
+    /// Exemple: photo.jpg --pyramid   ou   photo.jpg --pyramid 0.25,0.5,1
+    #[arg(
+        long,
+        value_name = "ÉCHELLES",
+        num_args = 0..=1,
+        default_missing_value = "0.3,0.6,1"
+    )]
+    pyramid: Option<PyramidConfig>,
+
+    /// Lire en PSM 5 (bloc de texte vertical) les images dont le texte est vertical
+    ///
+    /// Une analyse des profils de projection de l'image détecte le texte
+    /// disposé en colonnes (japonais, chinois vertical, étiquettes tournées).
+    #[arg(long)]
+    auto_vertical: bool,
+
+    /// Relire ligne par ligne (PSM 7) une page où aucun mot n'est reconnu
+    ///
+    /// Pour les polices stylisées que la segmentation de page de Tesseract
+    /// ne sait pas découper : si l'extraction ne renvoie aucun texte alors
+    /// que la page n'est pas blanche, chaque ligne détectée par projection
+    /// horizontale est lue séparément.
+    #[arg(long)]
+    line_fallback: bool,
+
+    /// Recoller les lignes coupées en deux par Tesseract (sorties JSON)
+    ///
+    /// Un exposant ou un symbole monétaire surélevé (« Total  € 12,50 »)
+    /// produit souvent deux lignes hOCR : les lignes consécutives d'un
+    /// paragraphe qui se recouvrent en hauteur sont fusionnées. Les critères
+    /// se règlent dans la section [ocr.line_merge] du fichier de configuration.
+    #[arg(long)]
+    merge_lines: bool,
+
+    /// Ne pas lire les photos, logos et zones bruitées de la page
+    ///
+    /// Les paragraphes reconnus dans une illustration (forte densité de
+    /// contours, grandes plages d'encre, traits irréguliers) sont retirés
+    /// des sorties construites à partir du document hOCR (JSON, Markdown,
+    /// HTML, --layout).
+    #[arg(long)]
+    skip_images: bool,
+
     /// Activer le prétraitement d'image
     ///
     /// Le prétraitement peut améliorer la qualité OCR en appliquant diverses
@@ -73,33 +273,49 @@ struct Args {
     #[arg(long)]
     preprocess: bool,
 
+    /// Profil de prétraitement prêt à l'emploi : fast, balanced ou best
+    ///
+    /// - fast: niveaux de gris seulement (le plus rapide)
+    /// - balanced: niveaux de gris, binarisation d'Otsu et suppression des taches
+    /// - best: ajoute la correction d'inclinaison, le débruitage et une
+    ///   binarisation adaptative (le plus lent, pour les scans difficiles)
+    ///
+    /// Active le prétraitement (--preprocess est implicite) et remplace le
+    /// choix des étapes : incompatible avec --grayscale, --binarize,
+    /// --contrast, --denoise, --deskew et --despeckle.
+    ///
+    /// Exemple: --preprocess-profile best
+    #[arg(long, value_name = "PROFIL")]
+    preprocess_profile: Option<PreprocessingProfile>,
+
     /// Convertir en niveaux de gris (prétraitement)
-    #[arg(long, requires = "preprocess")]
+    #[arg(long, requires = "preprocessing")]
     grayscale: bool,
 
     /// Appliquer la binarisation (prétraitement)
     ///
     /// Convertit l'image en noir et blanc pur (0 ou 255).
-    #[arg(long, requires = "preprocess")]
+    #[arg(long, requires = "preprocessing")]
     binarize: bool,
 
-    /// Méthode de binarisation: otsu, fixed, adaptive
+    /// Méthode de binarisation: otsu, multi-otsu, fixed, adaptive
     ///
     /// - otsu: Calcul automatique du seuil optimal (recommandé)
+    /// - multi-otsu: Otsu à trois classes, le niveau intermédiaire (tampon, surlignage) rejoint le fond
     /// - fixed:SEUIL: Seuil fixe (ex: fixed:128)
     /// - adaptive: Seuil adaptatif local
     #[arg(long, default_value = "otsu", requires = "binarize")]
     binarize_method: String,
 
     /// Appliquer un débruitage (filtre médian 3x3)
-    #[arg(long, requires = "preprocess")]
+    #[arg(long, requires = "preprocessing")]
     denoise: bool,
 
     /// Ajuster le contraste
     ///
     /// Facteur de contraste (1.0 = pas de changement, >1.0 = augmentation).
     /// Exemple: --contrast 1.5
-    #[arg(long, requires = "preprocess")]
+    #[arg(long, requires = "preprocessing")]
     contrast: Option<f32>,
 
     /// Corriger l'inclinaison du document (deskew)
@@ -107,9 +323,407 @@ struct Args {
     /// Détecte et corrige les inclinaisons légères (-20° à +20°) par analyse
     /// de la projection horizontale. Pour les rotations à 90°/180°/270°,
     /// utiliser --auto-rotate.
-    #[arg(long, requires = "preprocess")]
+    #[arg(long, requires = "preprocessing")]
     deskew: bool,
 
+    /// Nombre maximal de pixels avant réduction automatique (prétraitement)
+    ///
+    /// Les images plus grandes sont réduites en conservant leurs proportions,
+    /// et le DPI transmis à Tesseract est ajusté en conséquence.
+    /// 0 désactive la limite. Par défaut : 40 000 000 (40 MP).
+    ///
+    /// Exemple: --preprocess --max-pixels 20000000
+    #[arg(long, requires = "preprocessing")]
+    max_pixels: Option<u64>,
+
+    /// Ne pas lancer l'OCR sur les pages blanches (prétraitement)
+    ///
+    /// Une page sans aucun pixel sombre après binarisation d'Otsu donne un
+    /// texte vide et un avertissement, sans appel à Tesseract. En mode batch,
+    /// le résumé indique le nombre de pages blanches. Les seuils se règlent
+    /// dans la section [preprocessing.blank_detection] d'un fichier --config.
+    ///
+    /// Exemple: --batch scans/ --preprocess --skip-blank-pages
+    #[arg(long, requires = "preprocessing")]
+    skip_blank_pages: bool,
+
+    /// Couper les doubles pages (scans de livres) et lire chaque page séparément
+    ///
+    /// La gouttière est cherchée dans les 40 % centraux de l'image. Le texte
+    /// de la page de gauche précède celui de la page de droite, séparés par
+    /// un saut de page ; en mode batch, le résumé compte deux pages logiques.
+    ///
+    /// Exemple: --batch livre/ --preprocess --split-double-pages
+    #[arg(long, requires = "preprocessing")]
+    split_double_pages: bool,
+
+    /// Effacer les taches de moins de N pixels après la binarisation
+    ///
+    /// Les composantes d'encre (8-connexité) de moins de N pixels sont
+    /// effacées : poussières et points de scan isolés. Une lettre imprimée
+    /// couvre généralement plusieurs dizaines de pixels. Sans effet si la
+    /// binarisation n'est pas activée.
+    ///
+    /// Exemple: --preprocess --binarize --despeckle 10
+    #[arg(long, value_name = "N", requires = "preprocessing")]
+    despeckle: Option<u32>,
+
+    /// Pondération des couleurs pour la conversion en niveaux de gris
+    ///
+    /// - 709: luminance Rec. 709 (par défaut), le rouge et le bleu pèsent peu
+    /// - 601: luminance Rec. 601
+    /// - average: moyenne des trois composantes
+    /// - R,G,B: poids choisis ; pour faire ressortir une encre rouge,
+    ///   pondérer le vert et le bleu (ex: 0,0.5,0.5)
+    ///
+    /// Exemple: --preprocess --grayscale --gray-weights 0,0.5,0.5
+    #[arg(long, value_name = "POIDS", requires = "preprocessing")]
+    gray_weights: Option<ChannelWeights>,
+
+    /// Fond sur lequel composer les pixels transparents avant la conversion
+    ///
+    /// Sans cette option, le canal alpha est ignoré : une capture PNG
+    /// transparente garde ses pixels invisibles, souvent noirs.
+    ///
+    /// Exemple: --preprocess --alpha-bg white
+    #[arg(long, value_enum, value_name = "FOND", requires = "preprocessing")]
+    alpha_bg: Option<AlphaBackground>,
+
+    /// Éliminer l'encre d'une couleur : red, green ou blue
+    ///
+    /// Seule la composante choisie sert au niveau de gris : l'encre de
+    /// cette couleur devient aussi claire que le fond (ex: red efface le
+    /// quadrillage rouge d'un registre), le noir reste noir.
+    ///
+    /// Exemple: --preprocess --binarize --drop-channel red
+    #[arg(long, value_name = "COULEUR", requires = "preprocessing")]
+    drop_channel: Option<Channel>,
+
+    /// Analyser l'image par tuiles (très grandes images)
+    ///
+    /// Découpe l'image en bandes horizontales qui se chevauchent, analyse
+    /// chacune séparément puis fusionne les textes en supprimant les doublons
+    /// de la zone de chevauchement. Conserve la résolution d'origine,
+    /// contrairement à --max-pixels.
+    ///
+    /// Exemple: --tiled --tile-size 2000 --tile-overlap 200
+    #[arg(long, conflicts_with_all = ["batch", "hocr", "test_all_psm", "auto_rotate"])]
+    tiled: bool,
+
+    /// Hauteur d'une tuile en pixels (mode --tiled)
+    #[arg(long, default_value_t = 2000, requires = "tiled")]
+    tile_size: u32,
+
+    /// Chevauchement entre deux tuiles en pixels (mode --tiled)
+    #[arg(long, default_value_t = 200, requires = "tiled")]
+    tile_overlap: u32,
+
+    /// Nettoyer le texte extrait
+    ///
+    /// Active tous les nettoyages : césures de fin de ligne recollées,
+    /// lignes vides multiples réduites, caractères de contrôle supprimés,
+    /// guillemets typographiques remplacés par des guillemets droits.
+    /// Sans cette option, seuls les espaces de fin sont supprimés.
+    #[arg(long)]
+    cleanup: bool,
+
+    /// Lire une mise en page sur plusieurs colonnes dans l'ordre de lecture
+    ///
+    /// Segmente la page via HOCR puis lit chaque colonne entièrement de haut
+    /// en bas avant la suivante. Un titre pleine largeur reste en tête.
+    ///
+    /// Exemple: --columns journal.png
+    #[arg(long, conflicts_with_all = ["batch", "hocr", "test_all_psm", "auto_rotate", "tiled"])]
+    columns: bool,
+
+    /// Conserver la mise en page avec des espaces (comme `pdftotext -layout`)
+    ///
+    /// Chaque mot est placé à la colonne correspondant à sa position dans
+    /// l'image : les colonnes d'un ticket ou d'un tableau restent alignées.
+    ///
+    /// Exemple: --layout ticket.png
+    #[arg(
+        long,
+        conflicts_with_all = ["batch", "hocr", "test_all_psm", "auto_rotate", "tiled", "columns", "region", "region_pct", "format"]
+    )]
+    layout: bool,
+
+    /// Analyser seulement une région de l'image, en pixels (x,y,largeur,hauteur)
+    ///
+    /// La région est limitée aux bords de l'image.
+    ///
+    /// Exemple: capture.png --region 1536,0,384,108
+    #[arg(
+        long,
+        value_name = "X,Y,W,H",
+        value_parser = parse_pixel_region,
+        conflicts_with_all = ["hocr", "test_all_psm", "auto_rotate", "tiled", "columns"]
+    )]
+    region: Option<BBox>,
+
+    /// Analyser seulement une région de l'image, en fractions de ses dimensions
+    ///
+    /// x,y,largeur,hauteur entre 0 et 1, depuis le coin supérieur gauche :
+    /// utile quand la taille de l'image en pixels n'est pas connue à l'avance.
+    /// Incompatible avec --region.
+    ///
+    /// Exemple (coin supérieur droit, 20 % × 10 %): capture.png --region-pct 0.8,0.0,0.2,0.1
+    #[arg(
+        long,
+        value_name = "X,Y,W,H",
+        conflicts_with_all = ["region", "hocr", "test_all_psm", "auto_rotate", "tiled", "columns"]
+    )]
+    region_pct: Option<RelativeRegion>,
+
+    /// Masquer des données sensibles dans l'image (IBAN, adresses e-mail)
+    ///
+    /// Liste de champs séparés par des virgules, parmi : iban, email.
+    /// Les mots reconnus sont recouverts d'un rectangle noir et l'image
+    /// masquée est écrite dans le fichier donné par --redacted-out.
+    ///
+    /// Exemple: --redact iban,email --redacted-out releve_masque.png
+    #[arg(
+        long,
+        value_name = "CHAMPS",
+        requires = "redacted_out",
+        conflicts_with_all = ["batch", "hocr", "test_all_psm", "auto_rotate", "tiled", "columns", "bench"]
+    )]
+    redact: Option<String>,
+
+    /// Image de sortie du mode --redact
+    #[arg(long, value_name = "FICHIER", requires = "redact")]
+    redacted_out: Option<PathBuf>,
+
+    /// Marge en pixels ajoutée autour de chaque mot masqué (mode --redact)
+    #[arg(long, default_value_t = 2, requires = "redact")]
+    redact_padding: u32,
+
+    /// Format de sortie du texte extrait
+    ///
+    /// text : texte brut (par défaut)
+    /// json : paragraphes, lignes et mots, avec leur confiance et leurs
+    ///        bounding boxes
+    /// markdown : titres (#, ##), listes et paragraphes
+    /// html : fragment HTML sémantique (<h1>, <ul>, <p>...)
+    ///
+    /// Exemple: --format json --out article.json
+    /// Exemple: --format markdown --out compte_rendu.md
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Text,
+        conflicts_with_all = ["batch", "hocr", "test_all_psm", "auto_rotate", "tiled", "columns", "expected", "expected_embedded", "bench", "redact"]
+    )]
+    format: OutputFormat,
+
+    /// Comparer le texte extrait de IMAGE avec celui d'une autre image (mode A/B)
+    ///
+    /// Les deux images sont traitées avec la même configuration. Affiche les
+    /// deux textes, le diff mot à mot, le CER/WER de la seconde image par
+    /// rapport à IMAGE et la durée de chaque extraction. Avec --format json,
+    /// le résultat est émis en JSON.
+    ///
+    /// Exemple: original.png --compare ameliore.png --preprocess --deskew
+    #[arg(
+        long,
+        value_name = "AUTRE_IMAGE",
+        conflicts_with_all = ["batch", "hocr", "test_all_psm", "auto_rotate", "tiled", "columns", "expected", "expected_embedded", "bench", "redact", "preprocess_only"]
+    )]
+    compare: Option<PathBuf>,
+
+    /// Extraire N fois le texte de IMAGE et vérifier que le résultat est stable
+    ///
+    /// Compare les empreintes SHA-256 des N sorties. Si elles diffèrent,
+    /// affiche chaque variante, le CER entre chaque paire et les lignes
+    /// différentes, et sort avec le code 2. Avec --format json, le rapport
+    /// est émis en JSON. `deterministic = true` dans la section [ocr] du
+    /// fichier de configuration limite le binaire tesseract à un thread.
+    ///
+    /// Exemple: scan.png --determinism-check 10
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(2..),
+        conflicts_with_all = ["batch", "hocr", "test_all_psm", "compare", "expected", "expected_embedded", "bench", "redact", "preprocess_only"]
+    )]
+    determinism_check: Option<u32>,
+
+    /// Comparer le texte de la bibliothèque Tesseract et celui du binaire `tesseract`
+    ///
+    /// Analyse IMAGE par les deux chemins d'appel de Tesseract, avec les mêmes
+    /// langue, PSM, OEM, DPI et variables, puis affiche le CER entre les deux
+    /// textes et les deux versions de Tesseract. Sort avec le code 2 en cas
+    /// de divergence. Avec --format json, le rapport est émis en JSON.
+    /// Utile pour diagnostiquer un environnement (bibliothèque et binaire de
+    /// versions différentes).
+    ///
+    /// Exemple: scan.png --cross-check --psm 6
+    #[arg(
+        long,
+        conflicts_with_all = ["batch", "hocr", "test_all_psm", "compare", "expected", "expected_embedded", "bench", "redact", "preprocess_only", "determinism_check"]
+    )]
+    cross_check: bool,
+
+    /// Analyser le texte extrait comme un ticket de caisse
+    ///
+    /// Extrait les articles (désignation, quantité, prix unitaire, montant),
+    /// le sous-total, les taxes et le total, puis vérifie que la somme des
+    /// articles redonne le sous-total et que le sous-total augmenté des
+    /// taxes redonne le total. Le résultat est émis en JSON, avec les lignes
+    /// suspectes (chiffre mal lu, virgule perdue). Sort avec le code 2 si
+    /// une relation n'est pas vérifiée.
+    ///
+    /// Exemple: ticket.jpg --parse-receipt --psm 6
+    #[arg(
+        long,
+        conflicts_with_all = ["batch", "hocr", "test_all_psm", "compare", "cross_check", "expected", "expected_embedded", "bench", "redact", "preprocess_only", "determinism_check", "out"]
+    )]
+    parse_receipt: bool,
+
+    /// Rechercher une phrase dans le texte reconnu, en tolérant des erreurs d'OCR
+    ///
+    /// Affiche la position (en caractères), la distance de Levenshtein, le
+    /// contexte et les rectangles des mots de chaque occurrence. Avec
+    /// --format json, les occurrences sont émises en JSON.
+    ///
+    /// Exemple: contrat.png --find "Conditions générales"
+    #[arg(
+        long,
+        value_name = "PHRASE",
+        conflicts_with_all = ["batch", "hocr", "test_all_psm", "compare", "expected", "expected_embedded", "bench", "redact", "preprocess_only", "determinism_check"]
+    )]
+    find: Option<String>,
+
+    /// Nombre maximal d'erreurs tolérées par --find (défaut : 20 % de la longueur de la phrase)
+    #[arg(long, value_name = "N", requires = "find")]
+    find_distance: Option<usize>,
+
+    /// Mesurer l'effet de chaque étape du prétraitement sur le CER
+    ///
+    /// Relance l'OCR en désactivant tour à tour chaque étape active
+    /// (niveaux de gris, redressement, débruitage, contraste, binarisation)
+    /// et classe les étapes selon la variation du CER. Les étapes dont le
+    /// retrait améliore le CER sont signalées. Rapport en Markdown, ou en
+    /// JSON avec --format json.
+    ///
+    /// Exemple: facture.png --preprocess --binarize --denoise --expected facture.txt --ablation
+    #[arg(
+        long,
+        requires_all = ["expected", "preprocess"],
+        conflicts_with_all = ["batch", "hocr", "test_all_psm", "compare", "bench", "redact", "preprocess_only", "determinism_check", "find"]
+    )]
+    ablation: bool,
+
+    /// Avec --ablation, tester aussi chaque étape inactive en l'activant seule
+    #[arg(long, requires = "ablation")]
+    ablation_additions: bool,
+
+    /// Évaluer la détection des mots par rapport à des annotations positionnées
+    ///
+    /// Remplace --expected pour ce mode d'évaluation : chaque mot annoté
+    /// (rectangle + texte) est apparié au mot reconnu qui le recouvre le mieux,
+    /// puis la précision, le rappel, le F1 et le CER des paires appariées sont
+    /// affichés. Avec --format json, le résultat est émis en JSON.
+    ///
+    /// Formats : .json (tableau d'objets x, y, w, h, text) ou .csv (x,y,w,h,text)
+    ///
+    /// Exemple: facture.png --annotations facture.json --iou-threshold 0.6
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["batch", "hocr", "test_all_psm", "auto_rotate", "tiled", "columns", "expected", "expected_embedded", "bench", "redact", "preprocess_only", "compare"]
+    )]
+    annotations: Option<PathBuf>,
+
+    /// Lire des régions nommées décrites dans un fichier JSON (labelme ou format simple)
+    ///
+    /// Chaque forme (rectangle ou polygone labelme, ou objet label, x, y, w,
+    /// h) est réduite à son rectangle englobant, limité à l'image, et lue
+    /// séparément avec la langue de la configuration. Un objet JSON associe
+    /// chaque étiquette à son texte, sur la sortie standard ou dans --out.
+    ///
+    /// Exemple: facture.png --regions-file annotations.json --out champs.json
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["batch", "hocr", "test_all_psm", "auto_rotate", "tiled", "columns", "layout", "region", "region_pct", "annotations", "format"]
+    )]
+    regions_file: Option<PathBuf>,
+
+    /// Réécrire aussi le fichier de régions, avec un champ "text" par forme
+    ///
+    /// Le document d'origine (labelme ou format simple) garde sa structure
+    /// et ses autres champs : il peut être rouvert dans labelme.
+    ///
+    /// Exemple: --regions-file annotations.json --regions-export annotations.ocr.json
+    #[arg(long, value_name = "FILE", requires = "regions_file")]
+    regions_export: Option<PathBuf>,
+
+    /// IoU minimale pour apparier un mot reconnu à un mot annoté (entre 0 et 1)
+    #[arg(long, default_value_t = DEFAULT_IOU_THRESHOLD, requires = "annotations")]
+    iou_threshold: f64,
+
+    /// Afficher les décisions du prétraitement sur la sortie d'erreur
+    ///
+    /// Étapes appliquées, angle d'inclinaison détecté, seuil d'Otsu,
+    /// facteur de contraste et dimensions de l'image avant et après.
+    ///
+    /// Exemple: --preprocess --deskew --binarize --verbose
+    #[arg(short = 'v', long)]
+    verbose: bool,
+
+    /// Prétraiter l'image sans lancer l'OCR et écrire le résultat
+    ///
+    /// Exécute le pipeline de prétraitement configuré (--preprocess ou section
+    /// preprocessing du fichier de configuration) et sauvegarde l'image que
+    /// Tesseract recevrait. Utile pour comprendre un échec d'OCR.
+    ///
+    /// En mode batch, ne pas donner de fichier : une image <nom>.png est écrite
+    /// par entrée dans le répertoire --output.
+    ///
+    /// Exemple: --preprocess --binarize --preprocess-only out.png
+    /// Exemple batch: --batch --preprocess --preprocess-only --output pretraitees/ images/
+    #[arg(
+        long,
+        value_name = "OUT_PNG",
+        num_args = 0..=1,
+        conflicts_with_all = ["hocr", "test_all_psm", "bench", "tiled"]
+    )]
+    preprocess_only: Option<Option<PathBuf>>,
+
+    /// Sauvegarder l'image après chaque étape du prétraitement
+    ///
+    /// Écrit un fichier par étape appliquée dans ce répertoire :
+    /// <image>_1_grayscale.png, <image>_2_deskew.png, etc.
+    ///
+    /// Exemple: --preprocess --deskew --binarize --dump-intermediate debug/
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["hocr", "test_all_psm", "bench"])]
+    dump_intermediate: Option<PathBuf>,
+
+    /// Conserver les fichiers transmis à Tesseract et ses sorties brutes
+    ///
+    /// Chaque extraction crée un sous-répertoire <image>-<horodatage>
+    /// contenant l'image réellement analysée (après prétraitement), le texte
+    /// ou le hOCR brut, les diagnostics de Tesseract et un fichier
+    /// artifacts.json (configuration effective, durées). Un échec d'écriture
+    /// est signalé sans interrompre l'extraction.
+    ///
+    /// Exemple: --preprocess --binarize --debug-artifacts debug/
+    #[arg(long, value_name = "DIR")]
+    debug_artifacts: Option<PathBuf>,
+
+    /// Réutiliser les résultats des images déjà lues (cache sur disque)
+    ///
+    /// Un fichier JSON par image et par configuration, indexé par
+    /// l'empreinte SHA-256 du contenu de l'image et de la configuration OCR
+    /// et de prétraitement : une image inchangée n'est pas relue. Un fichier
+    /// de cache corrompu est ignoré. Vider le répertoire après une mise à
+    /// jour de Tesseract.
+    ///
+    /// Exemple: --batch factures/ --output results/ --result-cache ~/.cache/ocr
+    #[arg(long, value_name = "DIR")]
+    result_cache: Option<PathBuf>,
+
     /// Corriger automatiquement l'orientation de l'image
     ///
     /// Utilise Tesseract (PSM 0) pour détecter l'orientation réelle de l'image
@@ -117,7 +731,9 @@ struct Args {
     /// Utile pour les images à l'envers ou pivotées de 90°/270°.
     ///
     /// Compatible avec --preprocess pour combiner correction d'orientation
-    /// et prétraitement d'image.
+    /// et prétraitement d'image. Avec --deskew, la correction fine est
+    /// enchaînée dans la même passe : une photo pivotée de ~95° est
+    /// entièrement redressée, même si l'OSD de Tesseract échoue.
     #[arg(long)]
     auto_rotate: bool,
 
@@ -133,16 +749,120 @@ struct Args {
     #[arg(long, value_name = "CONFIG_FILE")]
     config: Option<PathBuf>,
 
+    /// Variable Tesseract à appliquer (répétable)
+    ///
+    /// S'applique par-dessus les variables du preset et du fichier de
+    /// configuration. Les noms absents de la liste des paramètres Tesseract
+    /// connus produisent un avertissement.
+    ///
+    /// Exemple: --var tessedit_char_whitelist=0123456789 --var load_system_dawg=0
+    #[arg(long = "var", value_name = "CLE=VALEUR", value_parser = parse_tesseract_variable)]
+    vars: Vec<(String, String)>,
+
+    /// Fichier de mots utilisateur ajoutés au dictionnaire (un mot par ligne)
+    ///
+    /// Remplace le fichier `user_words` du fichier de configuration. Vérifié
+    /// à la création du moteur ; l'OCR passe alors par le binaire tesseract.
+    ///
+    /// Exemple: --user-words vocabulaire.txt
+    #[arg(long, value_name = "FICHIER")]
+    user_words: Option<PathBuf>,
+
+    /// Fichier de motifs utilisateur (un motif par ligne, ex: \d\d-\d\d\d)
+    ///
+    /// Remplace le fichier `user_patterns` du fichier de configuration.
+    ///
+    /// Exemple: --user-patterns references.txt
+    #[arg(long, value_name = "FICHIER")]
+    user_patterns: Option<PathBuf>,
+
+    /// Fichier de configuration Tesseract à appliquer (répétable)
+    ///
+    /// Un nom simple (digits, quiet...) est cherché dans tessdata/configs ;
+    /// un chemin (./mon.cfg) est lu tel quel. Les variables --var
+    /// l'emportent sur celles des fichiers. S'ajoute aux fichiers
+    /// `config_names` du fichier de configuration.
+    ///
+    /// Exemple: --tess-config digits --tess-config ./factures.cfg
+    #[arg(long = "tess-config", value_name = "NAME")]
+    tess_configs: Vec<String>,
+
+    /// Répertoire des modèles .traineddata (remplace TESSDATA_PREFIX pour ce traitement)
+    ///
+    /// Transmis à la bibliothèque et au binaire tesseract (--tessdata-dir),
+    /// sans modifier l'environnement. Le modèle de chaque langue doit s'y
+    /// trouver : vérifié à la création du moteur.
+    ///
+    /// Exemple: --tessdata-dir /opt/tessdata_best --language fra
+    #[arg(long, value_name = "DIR")]
+    tessdata_dir: Option<PathBuf>,
+
+    /// Nombre maximal de threads des appels au binaire tesseract
+    ///
+    /// Passé dans l'environnement du seul processus tesseract
+    /// (OMP_THREAD_LIMIT). `deterministic = true` (fichier de configuration)
+    /// l'emporte (un thread). Sans effet sur l'OCR par la bibliothèque, qui
+    /// garde la limite du processus.
+    ///
+    /// Exemple: --batch scans/ --jobs 4 --threads 1
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    threads: Option<u32>,
+
+    /// Afficher la configuration effective transmise à Tesseract
+    ///
+    /// Langue, PSM, DPI, OEM et variables Tesseract après fusion du preset,
+    /// du fichier de configuration et des options CLI. Affichée sur la sortie
+    /// d'erreur avant l'OCR ; avec --dry-run, affichée sur la sortie standard
+    /// (en JSON avec --format json) sans lancer l'OCR.
+    ///
+    /// Exemple: --preset receipt --config ticket.toml --show-config --dry-run
+    #[arg(long)]
+    show_config: bool,
+
+    /// Ne rien traiter : afficher la configuration effective ou estimer un lot
+    ///
+    /// Sans --batch, affiche la configuration effective (voir --show-config)
+    /// sans lancer l'OCR. Avec --batch, collecte les fichiers, vérifie leur
+    /// en-tête, analyse un échantillon (--sample) avec la configuration réelle
+    /// et estime la durée totale pour --jobs workers ainsi que le volume des
+    /// sorties. Aucune sortie n'est écrite.
+    ///
+    /// Exemple: scans/ --batch --recursive --jobs 8 --dry-run --sample 20
+    #[arg(long, conflicts_with_all = ["bench", "list_presets"])]
+    dry_run: bool,
+
+    /// Nombre d'images tirées au hasard pour l'estimation (--batch --dry-run)
+    #[arg(long, value_name = "N", default_value_t = 10, requires = "dry_run")]
+    sample: usize,
+
     /// Fichier contenant le texte de référence attendu
     ///
     /// Si fourni, le programme comparera le résultat OCR avec ce texte
     /// et affichera les métriques de qualité (CER, WER, etc.) au lieu
     /// du texte extrait.
     ///
+    /// En mode batch, doit être un répertoire contenant un fichier
+    /// `<nom_image>.txt` par image : les métriques sont affichées pour chaque
+    /// image et les confusions de caractères agrégées sur tout le corpus.
+    ///
     /// Exemple: --expected expected_text.txt
+    /// Exemple batch: --batch "images/*.png" --expected resources/expected/
     #[arg(short = 'e', long)]
     expected: Option<PathBuf>,
 
+    /// Comparer avec la transcription de référence intégrée à l'image
+    ///
+    /// Remplace --expected : la référence est lue dans le chunk PNG
+    /// `ocr_reference` (iTXt ou tEXt) ou dans le champ EXIF UserComment d'un
+    /// JPEG. Erreur si l'image n'en contient pas ; en mode batch, l'image est
+    /// alors comptée en échec. La colonne `reference_source` de --csv-export
+    /// indique la provenance de la référence (`file` ou `embedded`).
+    ///
+    /// Exemple: facture.png --expected-embedded --metrics
+    /// Exemple batch: --batch scans/ --expected-embedded --csv-export results.csv
+    #[arg(long)]
+    expected_embedded: bool,
+
     /// Afficher un rapport détaillé des métriques
     ///
     /// Nécessite l'option --expected. Affiche un rapport complet formaté
@@ -151,9 +871,41 @@ struct Args {
     /// Sans cette option, seules les métriques essentielles sont affichées.
     ///
     /// Exemple: --expected expected.txt --metrics
-    #[arg(short = 'm', long, requires = "expected")]
+    #[arg(short = 'm', long, requires = "reference")]
     metrics: bool,
 
+    /// Écrire le rapport de --metrics en HTML autonome (à partager)
+    ///
+    /// Synthèse des métriques, niveau de qualité et textes côte à côte :
+    /// substitutions en jaune, suppressions barrées en rouge, insertions en
+    /// vert. Les longues portions identiques sont repliées.
+    ///
+    /// Exemple: --expected page.txt --metrics --html rapport.html
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "metrics",
+        conflicts_with_all = ["batch", "csv_export"]
+    )]
+    html: Option<PathBuf>,
+
+    /// Découpage en mots utilisé pour le WER
+    ///
+    /// whitespace : séquences séparées par des espaces (par défaut)
+    /// unicode : lettres et chiffres, élisions découpées (l'école = l' école),
+    ///           idéogrammes isolés pour le chinois et le japonais
+    /// regex:MOTIF : chaque correspondance du motif est un mot
+    ///
+    /// Exemple: --expected attendu.txt --tokenizer unicode
+    #[arg(
+        long,
+        value_name = "NAME",
+        default_value = "whitespace",
+        value_parser = parse_tokenizer,
+        requires = "reference"
+    )]
+    tokenizer: Tokenizer,
+
     /// Tester tous les modes PSM (0-13) et afficher les résultats
     ///
     /// Cette option teste tous les 14 modes de segmentation de page disponibles
@@ -170,6 +922,32 @@ struct Args {
     #[arg(long, conflicts_with = "psm")]
     test_all_psm: bool,
 
+    /// Recommander un mode PSM d'après la mise en page de l'image
+    ///
+    /// Mesure, sans lancer Tesseract, les blocs de texte, les lignes et les
+    /// colonnes de l'image, puis affiche les modes PSM adaptés, du plus au
+    /// moins adapté, avec leur justification. Bien plus rapide que
+    /// --test-all-psm. Avec --format json, la recommandation est émise en
+    /// JSON.
+    ///
+    /// Exemple: --recommend-psm
+    /// Exemple: --recommend-psm --apply
+    #[arg(long, conflicts_with_all = ["psm", "test_all_psm", "batch", "hocr", "bench"])]
+    recommend_psm: bool,
+
+    /// Extraire le texte avec le premier mode de --recommend-psm
+    ///
+    /// La recommandation est affichée sur la sortie d'erreur, puis
+    /// l'extraction se déroule normalement avec ce mode. Incompatible avec
+    /// les sorties qui relancent Tesseract avec --psm (--redact, --heatmap,
+    /// --review-regions).
+    #[arg(
+        long,
+        requires = "recommend_psm",
+        conflicts_with_all = ["redact", "heatmap", "review_regions"]
+    )]
+    apply: bool,
+
     /// Mode batch : traiter plusieurs images
     ///
     /// En mode batch, l'argument IMAGE peut être:
@@ -184,16 +962,222 @@ struct Args {
     #[arg(short, long)]
     batch: bool,
 
+    /// Parcourir les sous-répertoires (mode batch sur un répertoire)
+    ///
+    /// Exemple: --batch --recursive scans/
+    #[arg(long)]
+    recursive: bool,
+
+    /// Nombre maximal de niveaux de sous-répertoires parcourus avec --recursive
+    ///
+    /// Exemple: --batch --recursive --max-depth 2 scans/
+    #[arg(long, value_name = "N", requires = "recursive")]
+    max_depth: Option<usize>,
+
+    /// Extensions d'images acceptées, séparées par des virgules
+    ///
+    /// Remplace la liste par défaut (png, jpg, jpeg, tiff, tif, bmp, gif, webp).
+    ///
+    /// Exemple: --batch --extensions png,jpg,jp2 scans/
+    #[arg(long, value_name = "EXT", value_delimiter = ',')]
+    extensions: Option<Vec<String>>,
+
+    /// Suivre les liens symboliques lors du parcours d'un répertoire
+    ///
+    /// Chaque répertoire n'est visité qu'une fois (protection contre les boucles).
+    #[arg(long)]
+    follow_symlinks: bool,
+
+    /// Inclure les fichiers et répertoires cachés (nom commençant par un point)
+    #[arg(long)]
+    hidden: bool,
+
+    /// Nombre d'images traitées en parallèle en mode batch
+    ///
+    /// Chaque worker utilise sa propre instance Tesseract : la mémoire
+    /// consommée augmente avec ce nombre.
+    ///
+    /// Exemple: --batch images/ --jobs 4
+    #[arg(short = 'j', long, default_value_t = 1, requires = "batch")]
+    jobs: usize,
+
+    /// Extraire chaque image du mode batch dans un processus séparé
+    ///
+    /// Un plantage de Tesseract sur une image malformée n'arrête plus tout
+    /// le lot : l'image est comptée en échec et le traitement continue.
+    /// Avec --timeout, le processus d'une image trop longue est tué (OCR
+    /// par la bibliothèque compris). Coûte un lancement de processus et un
+    /// chargement du modèle par image.
+    ///
+    /// Exemple: --batch archives/ --isolate --timeout 120
+    #[arg(long, requires = "batch")]
+    isolate: bool,
+
+    /// Durée maximale de l'extraction d'une image, en secondes
+    ///
+    /// Au-delà, l'image est abandonnée avec une erreur de délai (en mode
+    /// batch, le traitement continue avec les images suivantes). Les appels
+    /// au binaire tesseract (orientation, HOCR) sont tués ; l'OCR par la
+    /// bibliothèque, qui ne peut pas être interrompu, se termine en
+    /// arrière-plan et son résultat est ignoré.
+    ///
+    /// En mode batch, Ctrl-C arrête proprement le traitement : aucune nouvelle
+    /// image n'est commencée, l'image en cours s'arrête à l'étape suivante et
+    /// le résumé indique les images annulées. Un second Ctrl-C interrompt
+    /// immédiatement le programme.
+    ///
+    /// Exemple: --batch scans/ --timeout 60
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: Option<u64>,
+
+    /// Écrire les échecs du mode batch dans un fichier JSON
+    ///
+    /// Tableau d'objets `{"path", "error", "kind"}` où `kind` vaut
+    /// `not_found`, `image_decode`, `io`, `ocr`, `timeout`, `skipped`,
+    /// `duplicate` (avec `duplicate_of`, voir --skip-duplicates) ou
+    /// `cancelled`. Le fichier est écrit même sans échec (tableau vide).
+    ///
+    /// Codes de sortie du mode batch : 0 = tout a réussi, 2 = certaines images
+    /// ont échoué, 3 = toutes ont échoué ou erreur d'E/S fatale
+    /// (1 = erreur d'utilisation ou de configuration).
+    ///
+    /// Exemple: --batch images/ --error-report echecs.json
+    #[arg(long, value_name = "FILE", requires = "batch")]
+    error_report: Option<PathBuf>,
+
     /// Répertoire de sortie pour les résultats batch
     ///
     /// En mode batch, au lieu d'afficher les résultats dans le terminal,
     /// les sauvegarder dans des fichiers .txt dans ce répertoire.
-    /// Le nom de fichier sera: <nom_image_sans_extension>.txt
+    /// Le nom de fichier sera: <nom_image_sans_extension>.txt, dans
+    /// l'arborescence relative de l'image (répertoire ou pattern glob).
+    /// Deux images qui donneraient le même fichier sont départagées par un
+    /// suffixe (page1.txt, page1-2.txt) : aucune sortie n'est écrasée.
     ///
     /// Exemple: --batch --output results/
     #[arg(short = 'o', long, requires = "batch")]
     output: Option<PathBuf>,
 
+    /// Écrire toutes les sorties directement dans --output, sans sous-répertoires
+    ///
+    /// Les noms en double restent départagés par un suffixe numérique.
+    ///
+    /// Exemple: --batch --recursive scans/ --output results/ --flatten
+    #[arg(long, requires = "output")]
+    flatten: bool,
+
+    /// Écrire la correspondance image → fichier de sortie en JSON
+    ///
+    /// Tableau d'objets { "input", "output", "renamed" }, renamed indiquant
+    /// un nom départagé par un suffixe.
+    ///
+    /// Exemple: --batch scans/ --output results/ --output-map results/index.json
+    #[arg(long, value_name = "FILE", requires = "output")]
+    output_map: Option<PathBuf>,
+
+    /// Gabarit des chemins de sortie, relatif à --output
+    ///
+    /// Variables : {stem} (nom de l'image sans extension), {ext}, {parent}
+    /// (répertoire relatif de l'image), {lang}, {psm}, {date} (AAAA-MM-JJ,
+    /// UTC), {index} (rang de l'image, à partir de 1) et {hash8} (début du
+    /// SHA-256 de l'image). Les répertoires sont créés au besoin ; une
+    /// variable inconnue, un segment '..' ou un chemin absolu sont refusés.
+    /// Un manifeste manifest.json (image, sortie, statut, durée) est écrit
+    /// dans --output.
+    ///
+    /// Exemple: --batch scans/ --output results/ --output-template '{date}/{stem}_{lang}_ocr.txt'
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        requires = "output",
+        conflicts_with = "flatten"
+    )]
+    output_template: Option<OutputTemplate>,
+
+    /// Journaliser chaque image terminée pour pouvoir reprendre le lot
+    ///
+    /// Une ligne JSON par image (sortie ou erreur, empreinte SHA-256,
+    /// horodatage). Au redémarrage avec le même journal, les images déjà
+    /// traitées avec succès et inchangées sont sautées ; celles en échec
+    /// sont retraitées (sauf --no-retry-failed). Créé s'il n'existe pas.
+    ///
+    /// Exemple: --batch scans/ --output results/ --checkpoint lot.jsonl
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "batch",
+        conflicts_with = "resume"
+    )]
+    checkpoint: Option<PathBuf>,
+
+    /// Reprendre un lot depuis un journal --checkpoint existant
+    ///
+    /// Comme --checkpoint, mais échoue si le journal n'existe pas (faute de
+    /// frappe dans le chemin, lot jamais lancé).
+    ///
+    /// Exemple: --batch scans/ --output results/ --resume lot.jsonl
+    #[arg(long, value_name = "FILE", requires = "batch")]
+    resume: Option<PathBuf>,
+
+    /// Mode batch : ne lire qu'une image par groupe de doublons
+    ///
+    /// Les images dont l'empreinte perceptuelle (dHash sur 64 bits) diffère
+    /// d'au plus N bits sont regroupées, de proche en proche. Seule la
+    /// première image de chaque groupe est lue ; les autres apparaissent
+    /// « doublon de … » dans le résumé et dans --error-report (`kind`
+    /// `duplicate`), sans compter comme des échecs. 0 ne regroupe que les
+    /// copies exactes, 5 tolère une re-numérisation un peu plus claire.
+    ///
+    /// Exemple: --batch scans/ --skip-duplicates 5
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "batch",
+        value_parser = clap::value_parser!(u32).range(0..=64)
+    )]
+    skip_duplicates: Option<u32>,
+
+    /// Lire toutes les images, doublons compris (comportement par défaut)
+    ///
+    /// Annule un --skip-duplicates donné plus tôt sur la ligne de commande.
+    #[arg(long, overrides_with = "skip_duplicates")]
+    no_skip_duplicates: bool,
+
+    /// Avec --checkpoint ou --resume : ne pas retraiter les images en échec
+    ///
+    /// Les échecs journalisés sont repris tels quels dans le bilan.
+    #[arg(long)]
+    no_retry_failed: bool,
+
+    /// Fichier de sortie pour le texte extrait (image unique)
+    ///
+    /// Le texte est écrit dans ce fichier au lieu d'être affiché.
+    /// Avec --expected, les métriques restent affichées dans le terminal.
+    ///
+    /// Exemple: capture.png --out capture.txt
+    #[arg(long, value_name = "FILE", conflicts_with = "batch")]
+    out: Option<PathBuf>,
+
+    /// Encodage des fichiers texte écrits par --out et par le mode batch
+    ///
+    /// - utf8: UTF-8 sans BOM (défaut)
+    /// - utf8-bom: UTF-8 précédé du BOM EF BB BF
+    /// - utf16le: UTF-16 petit-boutiste précédé du BOM FF FE
+    /// - latin1-lossy: ISO-8859-1 ; les caractères absents de Latin-1 (œ, €...)
+    ///   sont remplacés par '?' et comptés dans un avertissement
+    ///
+    /// Exemple: scans/ --batch --output-dir textes/ --encoding utf8-bom --newline crlf
+    #[arg(long, value_name = "ENCODAGE", default_value_t = OutputEncoding::Utf8)]
+    encoding: OutputEncoding,
+
+    /// Fins de ligne des fichiers texte écrits par --out et par le mode batch
+    ///
+    /// - lf: \n (défaut)
+    /// - crlf: \r\n
+    /// - platform: \r\n sous Windows, \n ailleurs
+    #[arg(long, value_name = "STYLE", default_value_t = NewlineStyle::Lf)]
+    newline: NewlineStyle,
+
     /// Exporter les métriques au format CSV
     ///
     /// Nécessite l'option --expected. Exporte les métriques de comparaison
@@ -201,13 +1185,74 @@ struct Args {
     /// Le fichier CSV contiendra une ligne d'en-têtes et une ligne de données.
     ///
     /// Compatible avec --batch pour exporter les métriques de plusieurs images
-    /// dans un seul fichier CSV (une ligne par image).
+    /// dans un seul fichier CSV (une ligne par image). En mode batch, les
+    /// lignes sont ajoutées au fichier existant, sous verrou, pour que
+    /// plusieurs traitements puissent partager le même fichier ; l'en-tête
+    /// n'est écrit que pour un nouveau fichier et doit sinon être identique.
     ///
     /// Exemple: --expected expected.txt --csv-export metrics.csv
     /// Exemple batch: --batch --expected expected.txt --csv-export results.csv
-    #[arg(long, value_name = "CSV_FILE", requires = "expected")]
+    #[arg(long, value_name = "CSV_FILE", requires = "reference")]
     csv_export: Option<PathBuf>,
 
+    /// Ajouter les métriques du batch à un fichier JSON Lines
+    ///
+    /// Une ligne `{ "metadata": {...}, "metrics": {...} }` par image comparée,
+    /// ajoutée sous verrou au fichier existant (partageable entre plusieurs
+    /// traitements).
+    ///
+    /// Exemple: --batch scans/ --expected refs/ --metrics-jsonl metrics.jsonl
+    #[arg(
+        long,
+        value_name = "JSONL_FILE",
+        requires = "batch",
+        requires = "reference"
+    )]
+    metrics_jsonl: Option<PathBuf>,
+
+    /// Libellé de l'exécution enregistrée dans le registre --ledger
+    ///
+    /// Exemple: --batch scans/ --expected refs/ --experiment-label otsu --ledger experiences.jsonl
+    #[arg(long, value_name = "NAME", requires = "ledger")]
+    experiment_label: Option<String>,
+
+    /// Ajouter l'exécution du batch au registre des expériences (JSON Lines)
+    ///
+    /// Une ligne par exécution : libellé, configurations OCR et de
+    /// prétraitement, jeu d'images (argument IMAGE), CER et WER moyens des
+    /// images comparées et durée totale. Le fichier n'est jamais réécrit.
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "experiment_label",
+        requires = "batch",
+        requires = "reference"
+    )]
+    ledger: Option<PathBuf>,
+
+    /// Ne pas ajouter les métadonnées de reproductibilité à l'export CSV
+    ///
+    /// Par défaut, chaque ligne indique l'empreinte SHA-256, la taille et les
+    /// dimensions de l'image, les versions de Tesseract et de l'outil, les
+    /// paramètres OCR et l'empreinte du prétraitement. Le calcul de
+    /// l'empreinte relit tout le fichier, ce qui peut être long pour de
+    /// grandes images.
+    #[arg(long, requires = "metrics_export")]
+    no_metadata: bool,
+
+    /// Délimiteur de champs des exports CSV (--csv-export, bench.csv)
+    ///
+    /// Les champs qui contiennent le délimiteur sont mis entre guillemets.
+    /// Un tableur configuré en français attend généralement `;`.
+    ///
+    /// Exemple: --csv-delimiter ';' --csv-decimal ','
+    #[arg(long, value_name = "CHAR", default_value_t = ',')]
+    csv_delimiter: char,
+
+    /// Séparateur décimal des colonnes décimales des exports CSV (CER, WER...)
+    #[arg(long, value_name = "CHAR", default_value_t = '.')]
+    csv_decimal: char,
+
     /// Générer et afficher les bounding boxes au format HOCR
     ///
     /// Active l'extraction des bounding boxes (rectangles délimitant les mots,
@@ -232,26 +1277,576 @@ struct Args {
     /// Exemple: --hocr --hocr-output boxes.txt
     #[arg(long, value_name = "HOCR_FILE", requires = "hocr")]
     hocr_output: Option<PathBuf>,
+
+    /// Écrire un calque JSON de surlignage des mots, lignes et paragraphes
+    ///
+    /// Tableau d'objets { "text", "confidence", "level", "polygon" }, les
+    /// sommets du polygone étant normalisés entre 0 et 1 par rapport aux
+    /// dimensions de l'image. Passe par l'extraction HOCR.
+    ///
+    /// Exemple: --overlay facture.overlay.json
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["batch", "hocr", "test_all_psm"])]
+    overlay: Option<PathBuf>,
+
+    /// Écrire une carte de chaleur des confiances des mots (image PNG)
+    ///
+    /// Chaque mot est coloré selon sa confiance Tesseract, du rouge (0) au
+    /// vert (100) en passant par le jaune ; les mots sans confiance sont
+    /// gris et les zones sans texte restent intactes. Passe par l'extraction
+    /// HOCR sur l'image d'origine.
+    ///
+    /// Exemple: --heatmap facture.heatmap.png
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["batch", "hocr", "test_all_psm", "overlay", "export_lines"])]
+    heatmap: Option<PathBuf>,
+
+    /// Inscrire la confiance de chaque mot sur la carte de chaleur
+    #[arg(long, requires = "heatmap")]
+    heatmap_labels: bool,
+
+    /// Lister les zones peu fiables à relire
+    ///
+    /// Regroupe les lignes (ou les mots, voir --review-level) consécutives
+    /// dont la confiance est sous le seuil (60 par défaut, entre 0 et 100)
+    /// en rectangles, fusionnés tant qu'ils restent à moins d'une hauteur de
+    /// ligne l'un de l'autre. Passe par l'extraction HOCR sur l'image
+    /// d'origine.
+    ///
+    /// Exemple: --review-regions 70 --review-out relecture/
+    #[arg(
+        long,
+        value_name = "THRESHOLD",
+        num_args = 0..=1,
+        conflicts_with_all = ["batch", "hocr", "test_all_psm", "overlay", "export_lines", "heatmap"]
+    )]
+    review_regions: Option<Option<f32>>,
+
+    /// Éléments comparés au seuil de --review-regions
+    #[arg(long, value_enum, default_value_t = ReviewLevel::Line, requires = "review_regions")]
+    review_level: ReviewLevel,
+
+    /// Découper chaque zone à relire dans ce répertoire (<image>_region_<n>.png)
+    #[arg(long, value_name = "DIR", requires = "review_regions")]
+    review_out: Option<PathBuf>,
+
+    /// Exporter chaque ligne en paire image/texte pour l'affinage de Tesseract
+    ///
+    /// Écrit dans le répertoire `<image>_<n>.tif` (ligne découpée, en niveaux
+    /// de gris) et `<image>_<n>.gt.txt` (sa transcription). Avec --expected,
+    /// la transcription est la ligne de référence appariée à la ligne OCR ;
+    /// les lignes trop différentes (voir --export-max-cer) sont écartées.
+    ///
+    /// Exemple: --export-lines gt/ --expected facture.txt
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["batch", "hocr", "test_all_psm", "overlay"])]
+    export_lines: Option<PathBuf>,
+
+    /// Marge autour de chaque ligne exportée, en pixels
+    #[arg(
+        long,
+        value_name = "PIXELS",
+        default_value_t = 4,
+        requires = "export_lines"
+    )]
+    export_padding: u32,
+
+    /// CER maximal entre une ligne OCR et sa ligne de référence pour l'exporter
+    #[arg(
+        long,
+        value_name = "CER",
+        default_value_t = 0.3,
+        requires = "export_lines"
+    )]
+    export_max_cer: f64,
+
+    /// Mode benchmark : comparer des configurations sur un corpus annoté
+    ///
+    /// Prend un manifeste JSON ou TOML listant les images, leurs textes
+    /// de référence et des tags optionnels. Chaque configuration fournie
+    /// via --bench-config est exécutée sur toutes les images, puis un
+    /// tableau comparatif (CER/WER moyen et médian, temps total) est produit,
+    /// globalement et par tag.
+    ///
+    /// Exemple: --bench corpus.toml --bench-config a.toml --bench-config b.toml
+    #[arg(long, value_name = "MANIFEST", conflicts_with_all = ["batch", "hocr", "test_all_psm"])]
+    bench: Option<PathBuf>,
+
+    /// Fichier de configuration à évaluer en mode benchmark (répétable)
+    ///
+    /// Le nom de la configuration dans le rapport est le nom du fichier
+    /// sans extension. Sans cette option, la configuration par défaut est évaluée.
+    #[arg(long, value_name = "CONFIG_FILE", requires = "bench")]
+    bench_config: Vec<PathBuf>,
+
+    /// Répertoire de sortie du rapport de benchmark
+    ///
+    /// Si spécifié, écrit `bench.md` et `bench.csv` dans ce répertoire
+    /// au lieu d'afficher le tableau Markdown dans le terminal.
+    ///
+    /// Exemple: --bench corpus.toml --bench-output results/
+    #[arg(long, value_name = "DIR", requires = "bench")]
+    bench_output: Option<PathBuf>,
+
+    /// Identifiants des options données sur la ligne de commande, renseignés
+    /// par [`Args::try_parse_with_sources()`].
+    #[arg(skip)]
+    explicit: HashSet<String>,
+}
+
+impl Args {
+    /// Parse les arguments en retenant ceux donnés explicitement.
+    ///
+    /// Une option laissée à sa valeur par défaut n'est pas explicite, même
+    /// si cette valeur est aussi celle que l'utilisateur aurait tapée : seul
+    /// `--language fra` explicite permet de surcharger la langue d'un
+    /// fichier de configuration par « fra ».
+    fn try_parse_with_sources<I, T>(argv: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        let matches = Self::command().try_get_matches_from(argv)?;
+        let mut args = Self::from_arg_matches(&matches)?;
+        args.explicit = matches
+            .ids()
+            .filter(|id| matches.value_source(id.as_str()) == Some(ValueSource::CommandLine))
+            .map(|id| id.to_string())
+            .collect();
+        Ok(args)
+    }
+
+    /// Indique si l'option `id` (nom du champ) a été donnée sur la ligne de commande.
+    fn is_explicit(&self, id: &str) -> bool {
+        self.explicit.contains(id)
+    }
+
+    /// Retourne le chemin de l'image, requis hors mode benchmark.
+    fn image(&self) -> Result<&Path> {
+        self.image.as_deref().context("L'argument IMAGE est requis")
+    }
+
+    /// Indique si l'image doit être lue sur l'entrée standard (IMAGE = "-").
+    fn reads_stdin(&self) -> bool {
+        self.image.as_deref().is_some_and(is_stdin_path)
+    }
+
+    /// Indique si le prétraitement est demandé (--preprocess ou --preprocess-profile).
+    fn preprocessing_enabled(&self) -> bool {
+        self.preprocess || self.preprocess_profile.is_some()
+    }
+}
+
+/// Indique si un chemin désigne l'entrée standard ("-").
+fn is_stdin_path(path: &Path) -> bool {
+    path == Path::new(STDIN_PATH)
+}
+
+/// Valeur de l'argument IMAGE désignant l'entrée standard.
+const STDIN_PATH: &str = "-";
+
+/// Contenu de l'entrée standard, lu une seule fois.
+static STDIN_BYTES: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// Retourne le contenu brut de l'entrée standard, lu au premier appel.
+///
+/// `Stdin` n'effectue aucune conversion de fin de ligne, y compris sous
+/// Windows : les octets de l'image sont lus tels quels.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si la lecture échoue.
+fn stdin_bytes() -> Result<&'static [u8]> {
+    if let Some(bytes) = STDIN_BYTES.get() {
+        return Ok(bytes);
+    }
+
+    let mut bytes = Vec::new();
+    std::io::stdin()
+        .lock()
+        .read_to_end(&mut bytes)
+        .context("Impossible de lire l'image sur l'entrée standard")?;
+
+    Ok(STDIN_BYTES.get_or_init(|| bytes))
+}
+
+/// Charge l'image d'entrée depuis un fichier ou l'entrée standard ("-").
+///
+/// # Erreurs
+///
+/// Retourne une erreur si l'image ne peut pas être lue ou décodée.
+fn load_input_image(image_path: &Path) -> Result<DynamicImage> {
+    if is_stdin_path(image_path) {
+        return image::load_from_memory(stdin_bytes()?)
+            .context("Échec du décodage de l'image lue sur l'entrée standard");
+    }
+
+    image::open(image_path)
+        .with_context(|| format!("Échec du chargement de l'image '{}'", image_path.display()))
+}
+
+/// Mesure la mise en page de l'image d'entrée et recommande un mode PSM (--recommend-psm).
+///
+/// # Erreurs
+///
+/// Retourne une erreur si l'image ne peut pas être lue ou décodée.
+fn recommend_psm_for(args: &Args) -> Result<PsmRecommendation> {
+    let image = load_input_image(args.image()?)?;
+    Ok(recommend_psm(&image.to_luma8()))
+}
+
+/// Incohérence entre les arguments, détectée par [`validate_args()`].
+///
+/// Chaque variante nomme l'option en cause pour que le message indique
+/// directement quoi corriger.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+enum ArgError {
+    /// Deux options qui ne peuvent pas être combinées.
+    #[error("{flag} est incompatible avec {other} : {reason}")]
+    Conflict {
+        /// Option refusée.
+        flag: &'static str,
+        /// Option (ou valeur) avec laquelle elle entre en conflit.
+        other: &'static str,
+        /// Raison du conflit.
+        reason: &'static str,
+    },
+    /// Valeur d'option invalide.
+    #[error("{flag} : valeur invalide '{value}' ({reason})")]
+    InvalidValue {
+        /// Option en cause.
+        flag: &'static str,
+        /// Valeur reçue.
+        value: String,
+        /// Valeurs acceptées ou raison du refus.
+        reason: String,
+    },
+    /// Un fichier est attendu, mais le chemin désigne un répertoire.
+    #[error("{flag} : '{}' est un répertoire, un fichier est attendu", .path.display())]
+    ExpectedFile {
+        /// Option en cause.
+        flag: &'static str,
+        /// Chemin reçu.
+        path: PathBuf,
+    },
+    /// Un répertoire est attendu, mais le chemin désigne un fichier.
+    #[error("{flag} : '{}' n'est pas un répertoire ({context})", .path.display())]
+    ExpectedDirectory {
+        /// Option en cause.
+        flag: &'static str,
+        /// Chemin reçu.
+        path: PathBuf,
+        /// Mode qui demande un répertoire.
+        context: &'static str,
+    },
+    /// Chemin inexistant.
+    #[error("{flag} : '{}' introuvable", .path.display())]
+    NotFound {
+        /// Option en cause.
+        flag: &'static str,
+        /// Chemin reçu.
+        path: PathBuf,
+    },
+    /// Option qui nécessite un fichier image, alors que l'image est lue sur stdin.
+    #[error("{flag} est incompatible avec la lecture sur l'entrée standard (IMAGE = \"-\")")]
+    StdinIncompatible {
+        /// Option en cause.
+        flag: &'static str,
+    },
+}
+
+/// Région de l'image à analyser (--region ou --region-pct).
+#[derive(Debug, Clone, PartialEq)]
+enum AnalysisRegion {
+    /// Rectangle en pixels.
+    Pixels(BBox),
+    /// Rectangle en fractions des dimensions de l'image.
+    Relative(RelativeRegion),
+}
+
+/// Fond des pixels transparents avant la conversion en niveaux de gris (option --alpha-bg).
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum AlphaBackground {
+    /// Fond blanc (captures d'écran, documents).
+    White,
+    /// Fond noir.
+    Black,
+}
+
+impl AlphaBackground {
+    /// Niveau de gris du fond.
+    fn level(self) -> u8 {
+        match self {
+            AlphaBackground::White => 255,
+            AlphaBackground::Black => 0,
+        }
+    }
+}
+
+/// Éléments comparés au seuil de confiance (option --review-level).
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ReviewLevel {
+    /// Chaque mot.
+    Word,
+    /// Chaque ligne, avec la moyenne des confiances de ses mots.
+    Line,
+}
+
+impl From<ReviewLevel> for Level {
+    fn from(level: ReviewLevel) -> Self {
+        match level {
+            ReviewLevel::Word => Level::Word,
+            ReviewLevel::Line => Level::Line,
+        }
+    }
+}
+
+/// Arguments acceptés par [`validate_args()`], avec les valeurs déjà interprétées.
+///
+/// Les autres options restent accessibles par déréférencement vers [`Args`].
+#[derive(Debug)]
+struct ValidatedArgs<'a> {
+    /// Arguments de la ligne de commande.
+    args: &'a Args,
+    /// Méthode de binarisation (--binarize-method).
+    binarization_method: BinarizationMethod,
+    /// Région à analyser, si --region ou --region-pct est donné.
+    analysis_region: Option<AnalysisRegion>,
+}
+
+impl Deref for ValidatedArgs<'_> {
+    type Target = Args;
+
+    fn deref(&self) -> &Args {
+        self.args
+    }
+}
+
+/// Vérifie la cohérence des arguments et interprète les valeurs textuelles.
+///
+/// Complète les contraintes déclarées à clap (`requires`, `conflicts_with`)
+/// par celles qui dépendent d'une valeur ou du système de fichiers : options
+/// sans effet en mode batch, `--auto-rotate` avec le PSM 0, méthode de
+/// binarisation, `--expected` fichier ou répertoire selon le mode, options
+/// qui nécessitent un fichier image quand IMAGE vaut "-".
+///
+/// # Arguments
+///
+/// * `args` - Arguments analysés par clap
+///
+/// # Erreurs
+///
+/// Retourne toutes les incohérences trouvées, pas seulement la première.
+fn validate_args(args: &Args) -> Result<ValidatedArgs<'_>, Vec<ArgError>> {
+    let mut errors = Vec::new();
+
+    if args.reads_stdin() {
+        errors.extend(
+            stdin_incompatible_flags(args)
+                .into_iter()
+                .map(|flag| ArgError::StdinIncompatible { flag }),
+        );
+    }
+
+    if args.batch {
+        let batch_conflicts = [
+            (
+                args.metrics,
+                "--metrics",
+                "le mode batch n'affiche que le CER et le WER de chaque image",
+            ),
+            (
+                args.region.is_some(),
+                "--region",
+                "les images d'un lot n'ont pas les mêmes dimensions",
+            ),
+            (
+                args.region_pct.is_some(),
+                "--region-pct",
+                "l'analyse d'une région ne s'applique qu'à une image",
+            ),
+        ];
+        errors.extend(
+            batch_conflicts
+                .into_iter()
+                .filter(|(enabled, _, _)| *enabled)
+                .map(|(_, flag, reason)| ArgError::Conflict {
+                    flag,
+                    other: "--batch",
+                    reason,
+                }),
+        );
+    }
+
+    if args.preprocess_profile.is_some() {
+        let step_flags = [
+            (args.grayscale, "--grayscale"),
+            (args.binarize, "--binarize"),
+            (args.contrast.is_some(), "--contrast"),
+            (args.denoise, "--denoise"),
+            (args.deskew, "--deskew"),
+            (args.despeckle.is_some(), "--despeckle"),
+        ];
+        errors.extend(
+            step_flags
+                .into_iter()
+                .filter(|(enabled, _)| *enabled)
+                .map(|(_, flag)| ArgError::Conflict {
+                    flag,
+                    other: "--preprocess-profile",
+                    reason: "le profil choisit déjà les étapes du prétraitement",
+                }),
+        );
+    }
+
+    if args.gray_weights.is_some() && args.drop_channel.is_some() {
+        errors.push(ArgError::Conflict {
+            flag: "--gray-weights",
+            other: "--drop-channel",
+            reason: "seule la composante conservée sert au niveau de gris",
+        });
+    }
+
+    if args.auto_rotate && args.psm == PageSegMode::OsdOnly {
+        errors.push(ArgError::Conflict {
+            flag: "--auto-rotate",
+            other: "--psm 0",
+            reason: "le PSM 0 détecte l'orientation sans reconnaître de texte",
+        });
+    }
+
+    let binarization_method = match parse_binarization_method(&args.binarize_method) {
+        Ok(method) => method,
+        Err(error) => {
+            errors.push(error);
+            BinarizationMethod::Otsu
+        }
+    };
+
+    if let Some(ref expected) = args.expected {
+        if !expected.exists() {
+            errors.push(ArgError::NotFound {
+                flag: "--expected",
+                path: expected.clone(),
+            });
+        } else if args.batch && !expected.is_dir() {
+            errors.push(ArgError::ExpectedDirectory {
+                flag: "--expected",
+                path: expected.clone(),
+                context: "en mode batch, un fichier <image>.txt par image",
+            });
+        } else if !args.batch && expected.is_dir() {
+            errors.push(ArgError::ExpectedFile {
+                flag: "--expected",
+                path: expected.clone(),
+            });
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let analysis_region = match (&args.region, args.region_pct) {
+        (Some(bbox), _) => Some(AnalysisRegion::Pixels(bbox.clone())),
+        (None, Some(region)) => Some(AnalysisRegion::Relative(region)),
+        (None, None) => None,
+    };
+
+    Ok(ValidatedArgs {
+        args,
+        binarization_method,
+        analysis_region,
+    })
+}
+
+/// Options demandées qui nécessitent un fichier image (refusées avec IMAGE = "-").
+fn stdin_incompatible_flags(args: &Args) -> Vec<&'static str> {
+    [
+        (args.batch, "--batch"),
+        (args.test_all_psm, "--test-all-psm"),
+        (args.hocr, "--hocr"),
+        (args.tiled, "--tiled"),
+        (args.columns, "--columns"),
+        (args.layout, "--layout"),
+        (args.region.is_some(), "--region"),
+        (args.region_pct.is_some(), "--region-pct"),
+        (args.redact.is_some(), "--redact"),
+        (args.compare.is_some(), "--compare"),
+        (args.determinism_check.is_some(), "--determinism-check"),
+        (args.cross_check, "--cross-check"),
+        (args.find.is_some(), "--find"),
+        (args.ablation, "--ablation"),
+        (args.annotations.is_some(), "--annotations"),
+        (args.regions_file.is_some(), "--regions-file"),
+        (args.export_lines.is_some(), "--export-lines"),
+        (args.heatmap.is_some(), "--heatmap"),
+        (args.review_regions.is_some(), "--review-regions"),
+        (args.format == OutputFormat::Json, "--format json"),
+        (args.format == OutputFormat::Markdown, "--format markdown"),
+        (args.format == OutputFormat::Html, "--format html"),
+        (args.auto_rotate, "--auto-rotate"),
+        (args.expected_embedded, "--expected-embedded"),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, flag)| enabled.then_some(flag))
+    .collect()
+}
+
+/// Affiche les erreurs de [`validate_args()`] sur la sortie d'erreur.
+fn print_arg_errors(errors: &[ArgError]) {
+    eprintln!("Arguments invalides :");
+    for error in errors {
+        eprintln!("  - {}", error);
+    }
+}
+
+/// Construit le nettoyage du texte demandé sur la ligne de commande.
+///
+/// `--cleanup` active tous les nettoyages ; sinon seuls les espaces de fin
+/// sont supprimés.
+fn cleanup_from_args(args: &Args) -> Option<TextCleanup> {
+    Some(if args.cleanup {
+        TextCleanup::full()
+    } else {
+        TextCleanup::default()
+    })
+}
+
+/// Retourne le moteur de reconnaissance demandé par `--oem`, s'il est présent.
+fn engine_mode_from_args(args: &Args) -> Option<OcrEngineMode> {
+    args.oem.and_then(OcrEngineMode::from_tesseract_oem)
+}
+
+/// Parse une région en pixels de la forme `x,y,largeur,hauteur` (option --region).
+fn parse_pixel_region(region: &str) -> Result<BBox, String> {
+    let values = region
+        .split(',')
+        .map(|value| value.trim().parse::<u32>())
+        .collect::<Result<Vec<_>, _>>();
+    match values.as_deref() {
+        Ok(&[x, y, width, height]) if width > 0 && height > 0 => Ok(BBox::new(x, y, width, height)),
+        _ => Err(format!(
+            "région invalide : '{}' (attendu x,y,largeur,hauteur en pixels, largeur et hauteur non nulles)",
+            region
+        )),
+    }
 }
 
-/// Convertit un code PSM numérique en PageSegMode.
-fn psm_from_int(psm: i32) -> PageSegMode {
-    match psm {
-        0 => PageSegMode::OsdOnly,
-        1 => PageSegMode::AutoOsd,
-        2 => PageSegMode::AutoOnly,
-        3 => PageSegMode::Auto,
-        4 => PageSegMode::SingleColumn,
-        5 => PageSegMode::SingleBlockVertText,
-        6 => PageSegMode::SingleBlock,
-        7 => PageSegMode::SingleLine,
-        8 => PageSegMode::SingleWord,
-        9 => PageSegMode::CircleWord,
-        10 => PageSegMode::SingleChar,
-        11 => PageSegMode::SparseText,
-        12 => PageSegMode::SparseTextOsd,
-        13 => PageSegMode::RawLine,
-        _ => PageSegMode::Auto, // Fallback (ne devrait jamais arriver grâce au value_parser)
+/// Parse le découpage en mots de l'option --tokenizer.
+fn parse_tokenizer(name: &str) -> Result<Tokenizer, String> {
+    Tokenizer::from_name(name).map_err(|e| e.to_string())
+}
+
+/// Parse une variable Tesseract de la forme `CLE=VALEUR` (option --var).
+///
+/// La valeur peut être vide ou contenir elle-même des `=`.
+fn parse_tesseract_variable(variable: &str) -> Result<(String, String), String> {
+    match variable.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!(
+            "variable invalide '{}' : format attendu CLE=VALEUR",
+            variable
+        )),
     }
 }
 
@@ -259,26 +1854,33 @@ fn psm_from_int(psm: i32) -> PageSegMode {
 ///
 /// Formats supportés:
 /// - "otsu" -> BinarizationMethod::Otsu
+/// - "multi-otsu" -> BinarizationMethod::MultiOtsu
 /// - "fixed:128" -> BinarizationMethod::Fixed(128)
 /// - "adaptive" -> BinarizationMethod::Adaptive
-fn parse_binarization_method(method: &str) -> Result<BinarizationMethod> {
+fn parse_binarization_method(method: &str) -> Result<BinarizationMethod, ArgError> {
+    let invalid = |reason: String| ArgError::InvalidValue {
+        flag: "--binarize-method",
+        value: method.to_string(),
+        reason,
+    };
     if method == "otsu" {
         Ok(BinarizationMethod::Otsu)
+    } else if method == "multi-otsu" {
+        Ok(BinarizationMethod::MultiOtsu)
     } else if method == "adaptive" {
         Ok(BinarizationMethod::Adaptive)
     } else if let Some(threshold_str) = method.strip_prefix("fixed:") {
         let threshold = threshold_str.parse::<u8>().map_err(|_| {
-            anyhow::anyhow!(
-                "Seuil invalide: '{}'. Doit être entre 0 et 255",
+            invalid(format!(
+                "seuil '{}' invalide, doit être entre 0 et 255",
                 threshold_str
-            )
+            ))
         })?;
         Ok(BinarizationMethod::Fixed(threshold))
     } else {
-        anyhow::bail!(
-            "Méthode de binarisation invalide: '{}'. Utilisez 'otsu', 'adaptive', ou 'fixed:SEUIL'",
-            method
-        )
+        Err(invalid(String::from(
+            "utilisez 'otsu', 'multi-otsu', 'adaptive' ou 'fixed:SEUIL'",
+        )))
     }
 }
 
@@ -292,72 +1894,77 @@ fn parse_binarization_method(method: &str) -> Result<BinarizationMethod> {
 ///
 /// Si --csv-export est fourni avec --expected, exporte toutes les métriques
 /// dans un fichier CSV avec une ligne par mode PSM.
-fn test_all_psm_modes(args: &Args) -> Result<()> {
+fn test_all_psm_modes(args: &ValidatedArgs) -> Result<()> {
+    let image = args.image()?;
+
     println!("═══════════════════════════════════════════════════════════");
     println!("         TEST DE TOUS LES MODES PSM (0-13)");
     println!("═══════════════════════════════════════════════════════════");
     println!();
-    println!("Image: {}", args.image.display());
+    println!("Image: {}", image.display());
     println!("Langue: {}", args.language);
     println!("DPI: {}", args.dpi);
     println!();
 
     // Charger le texte de référence si fourni
-    let expected_text = if let Some(expected_path) = &args.expected {
-        let text = fs::read_to_string(expected_path).map_err(|e| {
-            anyhow::anyhow!(
-                "Impossible de lire le fichier de référence '{}': {}",
-                expected_path.display(),
-                e
-            )
-        })?;
+    let expected_text = load_reference(args, image)?;
+    if let Some(ref text) = expected_text {
         println!("Texte de référence chargé: {} caractères", text.len());
         println!();
-        Some(text)
-    } else {
-        None
-    };
+    }
 
-    // Liste de tous les modes PSM avec leurs noms
-    let all_psm_modes = [
-        (0, "OSD Only", PageSegMode::OsdOnly),
-        (1, "Auto with OSD", PageSegMode::AutoOsd),
-        (2, "Auto without OSD", PageSegMode::AutoOnly),
-        (3, "Auto (default)", PageSegMode::Auto),
-        (4, "Single column", PageSegMode::SingleColumn),
-        (5, "Single vertical block", PageSegMode::SingleBlockVertText),
-        (6, "Single block", PageSegMode::SingleBlock),
-        (7, "Single line", PageSegMode::SingleLine),
-        (8, "Single word", PageSegMode::SingleWord),
-        (9, "Circle word", PageSegMode::CircleWord),
-        (10, "Single char", PageSegMode::SingleChar),
-        (11, "Sparse text", PageSegMode::SparseText),
-        (12, "Sparse text with OSD", PageSegMode::SparseTextOsd),
-        (13, "Raw line", PageSegMode::RawLine),
-    ];
+    let all_psm_modes = PageSegMode::all();
 
     // Construire la configuration de prétraitement si nécessaire
-    let preprocess_config = if args.preprocess {
-        let binarization_method = parse_binarization_method(&args.binarize_method)?;
+    let preprocess_config = if let Some(profile) = args.preprocess_profile {
+        Some(PreprocessingConfig {
+            max_pixels: args.max_pixels.unwrap_or(DEFAULT_MAX_PIXELS),
+            skip_blank_pages: args.skip_blank_pages,
+            split_double_pages: args.split_double_pages,
+            grayscale_options: grayscale_options_from_args(args, GrayscaleOptions::default()),
+            ..PreprocessingConfig::from_profile(profile)
+        })
+    } else if args.preprocess {
         Some(PreprocessingConfig {
             to_grayscale: args.grayscale,
             binarize: args.binarize,
-            binarization_method,
+            binarization_method: args.binarization_method,
             adjust_contrast: args.contrast.is_some(),
             contrast_factor: args.contrast.unwrap_or(1.0),
             denoise: args.denoise,
             deskew: args.deskew,
+            max_pixels: args.max_pixels.unwrap_or(DEFAULT_MAX_PIXELS),
+            skip_blank_pages: args.skip_blank_pages,
+            blank_detection: BlankDetectionOptions::default(),
+            split_double_pages: args.split_double_pages,
+            despeckle: args.despeckle,
+            grayscale_options: grayscale_options_from_args(args, GrayscaleOptions::default()),
+            deskew_options: DeskewOptions::default(),
         })
     } else {
         None
     };
 
+    // Métadonnées communes à toutes les lignes CSV (l'image n'est hachée qu'une fois)
+    let csv_base_metadata = args.csv_export.as_ref().map(|_| {
+        let config = OcrConfig {
+            language: args.language.clone(),
+            dpi: args.dpi,
+            engine_mode: engine_mode_from_args(args).unwrap_or_default(),
+            ..OcrConfig::default()
+        };
+        csv_metadata(args, image, &config, preprocess_config.as_ref())
+    });
+
     // Buffer CSV pour accumuler les résultats
+    let csv_options = csv_options(args)?;
     let mut csv_buffer = String::new();
     let mut first_metric = true;
 
     // Tester chaque mode PSM
-    for (psm_num, psm_name, psm_mode) in &all_psm_modes {
+    for psm_mode in all_psm_modes {
+        let psm_num = psm_mode.to_tesseract_psm();
+        let psm_name = psm_mode.describe();
         println!("───────────────────────────────────────────────────────────");
         println!("PSM {} - {}", psm_num, psm_name);
         println!("───────────────────────────────────────────────────────────");
@@ -365,9 +1972,24 @@ fn test_all_psm_modes(args: &Args) -> Result<()> {
         // Créer la configuration avec le PSM actuel
         let config = OcrConfig {
             language: args.language.clone(),
-            page_seg_mode: *psm_mode,
+            page_seg_mode: psm_mode,
             dpi: args.dpi,
             tesseract_variables: HashMap::new(),
+            cleanup: cleanup_from_args(args),
+            engine_mode: engine_mode_from_args(args).unwrap_or_default(),
+            adaptive_dpi: args.adaptive_dpi,
+            user_words: args.user_words.clone(),
+            user_patterns: args.user_patterns.clone(),
+            vocabulary_file: None,
+            deterministic: false,
+            auto_vertical: false,
+            fallback_line_segmentation: args.line_fallback,
+            line_merge: None,
+            skip_image_regions: false,
+            config_names: args.tess_configs.clone(),
+            tessdata_dir: args.tessdata_dir.clone(),
+            threads: args.threads,
+            pyramid: args.pyramid.clone(),
         };
 
         // Créer le moteur OCR
@@ -376,14 +1998,18 @@ fn test_all_psm_modes(args: &Args) -> Result<()> {
         } else {
             OcrEngine::new(config)?
         };
+        let engine = match args.debug_artifacts {
+            Some(ref dir) => engine.with_debug_artifacts(dir),
+            None => engine,
+        };
 
         // Extraire le texte (avec correction d'orientation si demandée)
         let extraction_result = if args.auto_rotate {
-            let helper = OcrEngine::new(OcrConfig::default())?;
-            let corrected = helper.detect_and_correct_orientation(&args.image)?;
-            engine.extract_text_from_image(&corrected)
+            engine
+                .extract_text_auto_rotated(image)
+                .map(|outcome| outcome.text)
         } else {
-            engine.extract_text_from_file(&args.image)
+            engine.extract_text_from_file(image)
         };
 
         match extraction_result {
@@ -395,11 +2021,12 @@ fn test_all_psm_modes(args: &Args) -> Result<()> {
                     println!("⚠ Aucun texte extrait");
                 } else {
                     // Limiter l'affichage pour ne pas surcharger le terminal
-                    let preview = if trimmed_text.len() > 200 {
+                    let char_count = trimmed_text.chars().count();
+                    let preview = if char_count > 200 {
                         format!(
                             "{}... ({} caractères)",
-                            &trimmed_text[..200],
-                            trimmed_text.len()
+                            truncate_chars(trimmed_text, 200),
+                            char_count
                         )
                     } else {
                         trimmed_text.to_string()
@@ -410,22 +2037,21 @@ fn test_all_psm_modes(args: &Args) -> Result<()> {
 
                 // Si un texte de référence est fourni, calculer les métriques
                 if let Some(ref expected) = expected_text {
-                    let metrics = compare_ocr_result(&text, expected);
+                    let detailed =
+                        compare_ocr_result_detailed_with(&text, expected, &args.tokenizer);
+                    let metrics = &detailed.metrics;
 
                     // Si export CSV demandé, accumuler les métriques
-                    if args.csv_export.is_some() {
-                        let mut metadata = HashMap::new();
-                        metadata.insert(
-                            "image".to_string(),
-                            args.image.to_string_lossy().to_string(),
-                        );
+                    if let Some(ref base_metadata) = csv_base_metadata {
+                        let mut metadata = base_metadata.clone();
                         metadata.insert("psm".to_string(), psm_num.to_string());
                         metadata.insert("psm_name".to_string(), psm_name.to_string());
-                        metadata.insert("language".to_string(), args.language.clone());
-                        metadata.insert("dpi".to_string(), args.dpi.to_string());
-                        metadata.insert("preprocess".to_string(), args.preprocess.to_string());
 
-                        csv_buffer.push_str(&metrics.to_csv(first_metric, Some(&metadata)));
+                        csv_buffer.push_str(&detailed.to_csv_with(
+                            first_metric,
+                            Some(&metadata),
+                            &csv_options,
+                        ));
                         first_metric = false;
                     }
 
@@ -479,486 +2105,5023 @@ fn test_all_psm_modes(args: &Args) -> Result<()> {
     Ok(())
 }
 
-/// Collecte les fichiers images depuis un chemin ou un pattern glob.
+/// Construit les options de formatage CSV demandées (--csv-delimiter, --csv-decimal).
+fn csv_options(args: &Args) -> Result<CsvOptions> {
+    let options = CsvOptions {
+        delimiter: args.csv_delimiter,
+        decimal_separator: args.csv_decimal,
+        ..CsvOptions::default()
+    };
+    options.validate()?;
+    Ok(options)
+}
+
+/// Construit les métadonnées d'une ligne de métriques exportée en CSV.
 ///
-/// Cette fonction gère trois cas :
-/// - Un fichier unique : retourne ce fichier
-/// - Un répertoire : trouve tous les fichiers images (png, jpg, jpeg, tiff, bmp, gif)
-/// - Un pattern glob : résout le pattern et retourne les fichiers correspondants
+/// Par défaut, utilise [`RunMetadataBuilder`] (empreinte et dimensions de
+/// l'image, versions, paramètres) ; avec --no-metadata, seuls l'image et les
+/// paramètres OCR sont indiqués.
+fn csv_metadata(
+    args: &Args,
+    image: &Path,
+    config: &OcrConfig,
+    preprocessing: Option<&PreprocessingConfig>,
+) -> HashMap<String, String> {
+    let mut metadata = if args.no_metadata {
+        HashMap::from([
+            ("image".to_string(), image.to_string_lossy().to_string()),
+            ("language".to_string(), config.language.clone()),
+            (
+                "psm".to_string(),
+                config.page_seg_mode.to_tesseract_psm().to_string(),
+            ),
+            ("dpi".to_string(), config.dpi.to_string()),
+        ])
+    } else {
+        RunMetadataBuilder::new(image, config)
+            .preprocessing(preprocessing)
+            .build()
+    };
+    metadata.insert(
+        "preprocess".to_string(),
+        args.preprocessing_enabled().to_string(),
+    );
+    let source = if args.expected_embedded {
+        "embedded"
+    } else {
+        "file"
+    };
+    metadata.insert("reference_source".to_string(), source.to_string());
+    metadata
+}
+
+/// Texte de référence d'une image en mode simple (--expected ou --expected-embedded).
 ///
-/// # Arguments
+/// # Retour
 ///
-/// * `path` - Chemin vers fichier, répertoire, ou pattern glob
+/// `None` si aucune référence n'est demandée.
 ///
 /// # Erreurs
 ///
-/// Retourne une erreur si :
-/// - Le chemin n'existe pas (sauf pour les patterns glob)
-/// - Aucun fichier image n'est trouvé
-fn collect_image_files(path: &Path) -> Result<Vec<PathBuf>> {
-    // Vérifier si c'est un pattern glob (contient *, ?, [, etc.)
-    let path_str = path.to_string_lossy();
-    let is_glob_pattern =
-        path_str.contains('*') || path_str.contains('?') || path_str.contains('[');
-
-    if is_glob_pattern {
-        // Résoudre le pattern glob
-        let mut files = Vec::new();
-        for entry in glob::glob(&path_str).context("Pattern glob invalide")? {
-            let entry = entry.context("Erreur lors de la résolution du pattern glob")?;
-            if entry.is_file() && is_image_file(&entry) {
-                files.push(entry);
-            }
-        }
-
-        if files.is_empty() {
-            anyhow::bail!("Aucun fichier image trouvé pour le pattern '{}'", path_str);
-        }
-
-        files.sort();
-        Ok(files)
-    } else if path.is_file() {
-        // Un seul fichier
-        if !is_image_file(path) {
-            anyhow::bail!(
-                "Le fichier '{}' n'est pas une image supportée",
-                path.display()
-            );
-        }
-        Ok(vec![path.to_path_buf()])
-    } else if path.is_dir() {
-        // Répertoire : trouver tous les fichiers images
-        let mut files = Vec::new();
-        for entry in fs::read_dir(path)
-            .with_context(|| format!("Impossible de lire le répertoire '{}'", path.display()))?
-        {
-            let entry = entry.context("Erreur lors de la lecture d'une entrée du répertoire")?;
-            let entry_path = entry.path();
-            if entry_path.is_file() && is_image_file(&entry_path) {
-                files.push(entry_path);
-            }
-        }
-
-        if files.is_empty() {
-            anyhow::bail!(
-                "Aucun fichier image trouvé dans le répertoire '{}'",
-                path.display()
-            );
-        }
-
-        files.sort();
-        Ok(files)
-    } else {
-        anyhow::bail!("Le chemin '{}' n'existe pas", path.display());
+/// Retourne une erreur si le fichier --expected ne peut pas être lu, ou si
+/// l'image ne contient pas de référence intégrée lisible.
+fn load_reference(args: &Args, image: &Path) -> Result<Option<String>> {
+    if args.expected_embedded {
+        return embedded_reference(image).map(Some);
     }
+    args.expected
+        .as_deref()
+        .map(|path| {
+            fs::read_to_string(path).with_context(|| {
+                format!(
+                    "Impossible de lire le fichier de référence '{}'",
+                    path.display()
+                )
+            })
+        })
+        .transpose()
 }
 
-/// Vérifie si un fichier est une image supportée (par extension).
+/// Transcription intégrée à une image (--expected-embedded), obligatoire.
 ///
-/// Extensions supportées : png, jpg, jpeg, tiff, tif, bmp, gif
-fn is_image_file(path: &Path) -> bool {
-    if let Some(ext) = path.extension() {
-        let ext = ext.to_string_lossy().to_lowercase();
-        matches!(
-            ext.as_str(),
-            "png" | "jpg" | "jpeg" | "tiff" | "tif" | "bmp" | "gif"
+/// # Erreurs
+///
+/// Retourne une erreur si l'image ne contient pas de référence intégrée,
+/// ou si elle ne peut pas être décodée.
+fn embedded_reference(image: &Path) -> Result<String> {
+    read_embedded_reference(image)?.with_context(|| {
+        format!(
+            "Aucune référence intégrée dans '{}' (chunk PNG {} ou UserComment EXIF)",
+            image.display(),
+            REFERENCE_KEY
         )
-    } else {
-        false
+    })
+}
+
+/// Construit les options de collecte des images à partir des arguments CLI.
+fn collect_options_from_args(args: &Args) -> CollectOptions {
+    let defaults = CollectOptions::default();
+    CollectOptions {
+        recursive: args.recursive,
+        max_depth: args.max_depth,
+        extensions: args.extensions.clone().unwrap_or(defaults.extensions),
+        follow_symlinks: args.follow_symlinks,
+        include_hidden: args.hidden,
     }
 }
 
-/// Traite plusieurs images en mode batch.
-///
-/// Cette fonction collecte les fichiers images selon le chemin fourni
-/// (fichier unique, répertoire, ou pattern glob), puis extrait le texte
-/// de chaque image avec la configuration fournie.
+/// Nombre de confusions de caractères affichées dans le résumé du mode batch.
+const TOP_CORPUS_CONFUSIONS: usize = 15;
+
+/// Codes de sortie du programme.
 ///
-/// Les résultats peuvent être affichés dans le terminal ou sauvegardés
-/// dans des fichiers si un répertoire de sortie est spécifié.
+/// - `0` : succès (toutes les images traitées en mode batch)
+/// - `1` : erreur d'utilisation ou de configuration (arguments, fichier de config...)
+/// - `2` : mode batch, certaines images ont échoué
+/// - `3` : mode batch, toutes les images ont échoué, ou erreur d'E/S fatale
+const EXIT_OK: u8 = 0;
+const EXIT_USAGE: u8 = 1;
+const EXIT_PARTIAL_FAILURE: u8 = 2;
+const EXIT_FATAL: u8 = 3;
+
+/// Détermine le code de sortie du mode batch à partir des compteurs.
 ///
 /// # Arguments
 ///
-/// * `args` - Arguments de la ligne de commande
-/// * `engine` - Moteur OCR configuré
-///
-/// # Erreurs
-///
-/// Retourne une erreur si :
-/// - Aucun fichier image n'est trouvé
-/// - Le répertoire de sortie ne peut pas être créé
-/// - Une erreur d'écriture survient
-fn process_batch(args: &Args, engine: &OcrEngine) -> Result<()> {
-    // Collecter les fichiers images
-    let image_files = collect_image_files(&args.image)?;
-
-    println!("═══════════════════════════════════════════════════════════");
-    println!("              MODE BATCH - TRAITEMENT MULTIPLE");
-    println!("═══════════════════════════════════════════════════════════");
-    println!();
-    println!("Nombre d'images à traiter: {}", image_files.len());
-    println!();
-
-    // Créer le répertoire de sortie si nécessaire
-    if let Some(ref output_dir) = args.output {
-        fs::create_dir_all(output_dir).with_context(|| {
-            format!(
-                "Impossible de créer le répertoire de sortie '{}'",
-                output_dir.display()
-            )
-        })?;
-        println!("Répertoire de sortie: {}", output_dir.display());
-        println!();
+/// * `success` - Nombre d'images traitées avec succès
+/// * `failed` - Nombre d'images en échec
+/// * `total` - Nombre total d'images
+fn decide_exit_code(success: usize, failed: usize, total: usize) -> u8 {
+    if total == 0 {
+        EXIT_USAGE
+    } else if failed == 0 {
+        EXIT_OK
+    } else if success == 0 {
+        EXIT_FATAL
+    } else {
+        EXIT_PARTIAL_FAILURE
     }
+}
 
-    // Statistiques globales
-    let mut success_count = 0;
-    let mut error_count = 0;
+/// Catégorie d'échec d'une image en mode batch (champ `kind` du rapport JSON).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum FailureKind {
+    /// Le fichier image n'existe pas.
+    NotFound,
+    /// L'image n'a pas pu être décodée.
+    ImageDecode,
+    /// Erreur d'entrée/sortie (lecture de la référence, écriture du résultat...).
+    Io,
+    /// Échec de Tesseract.
+    Ocr,
+    /// Échec d'une étape du prétraitement (voir les champs `step` et `step_index`).
+    Preprocessing,
+    /// L'extraction a dépassé le délai --timeout.
+    Timeout,
+    /// Fichier écarté avant l'OCR (vide, tronqué, format inconnu, trop petit).
+    Skipped,
+    /// Doublon d'une autre image du lot, non lu (--skip-duplicates).
+    Duplicate,
+    /// Image non traitée (ou interrompue) après une demande d'interruption.
+    Cancelled,
+}
 
-    // Traiter chaque image
-    for (index, image_path) in image_files.iter().enumerate() {
-        let file_num = index + 1;
-        println!("───────────────────────────────────────────────────────────");
-        println!(
-            "[{}/{}] Traitement: {}",
-            file_num,
-            image_files.len(),
-            image_path.display()
-        );
-        println!("───────────────────────────────────────────────────────────");
+/// Échec d'une image en mode batch, tel qu'écrit par --error-report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct BatchFailure {
+    /// Chemin de l'image.
+    path: PathBuf,
+    /// Message d'erreur complet (avec ses causes).
+    error: String,
+    /// Catégorie de l'échec.
+    kind: FailureKind,
+    /// Étape du prétraitement en échec, pour [`FailureKind::Preprocessing`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    step: Option<String>,
+    /// Rang de cette étape parmi celles exécutées (à partir de 1).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    step_index: Option<usize>,
+    /// Image lue à la place de celle-ci, pour [`FailureKind::Duplicate`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duplicate_of: Option<PathBuf>,
+}
 
-        // Extraire le texte (avec correction d'orientation si demandée)
-        let extraction_result = if args.auto_rotate {
-            let helper = OcrEngine::new(OcrConfig::default())?;
-            let corrected = helper.detect_and_correct_orientation(image_path)?;
-            engine.extract_text_from_image(&corrected)
+impl BatchFailure {
+    /// Construit l'échec d'une image en classant l'erreur.
+    fn new(path: &Path, error: &anyhow::Error) -> Self {
+        let preprocessing = match error.downcast_ref::<OcrError>() {
+            Some(OcrError::Preprocessing(failure)) => Some(failure),
+            _ => error.downcast_ref::<PreprocessingError>(),
+        };
+        let kind = if preprocessing.is_some() {
+            FailureKind::Preprocessing
+        } else if let Some(ocr_error) = error.downcast_ref::<OcrError>() {
+            match ocr_error {
+                OcrError::Timeout(_) => FailureKind::Timeout,
+                OcrError::Cancelled => FailureKind::Cancelled,
+                OcrError::Preprocessing(_) => FailureKind::Preprocessing,
+            }
+        } else if !path.exists() {
+            FailureKind::NotFound
+        } else if error.chain().any(|cause| cause.is::<image::ImageError>()) {
+            FailureKind::ImageDecode
+        } else if error.chain().any(|cause| cause.is::<std::io::Error>()) {
+            FailureKind::Io
         } else {
-            engine.extract_text_from_file(image_path)
+            FailureKind::Ocr
         };
 
-        match extraction_result {
-            Ok(text) => {
-                success_count += 1;
-
-                // Afficher ou sauvegarder le résultat
-                if let Some(ref output_dir) = args.output {
-                    // Sauvegarder dans un fichier
-                    let output_filename = image_path
-                        .file_stem()
-                        .context("Impossible d'extraire le nom du fichier")?
-                        .to_string_lossy()
-                        .to_string()
-                        + ".txt";
-                    let output_path = output_dir.join(output_filename);
-
-                    fs::write(&output_path, &text).with_context(|| {
-                        format!(
-                            "Impossible d'écrire le fichier de sortie '{}'",
-                            output_path.display()
-                        )
-                    })?;
+        Self {
+            path: path.to_path_buf(),
+            error: format!("{:#}", error),
+            kind,
+            step: preprocessing.map(|failure| failure.step.clone()),
+            step_index: preprocessing.map(|failure| failure.index),
+            duplicate_of: None,
+        }
+    }
 
-                    println!("✓ Succès - Résultat sauvegardé: {}", output_path.display());
-                } else {
-                    // Afficher dans le terminal
-                    let trimmed_text = text.trim();
-                    if trimmed_text.is_empty() {
-                        println!("⚠ Aucun texte extrait");
-                    } else {
-                        // Limiter l'affichage pour ne pas surcharger
-                        let preview = if trimmed_text.len() > 300 {
-                            format!(
-                                "{}... ({} caractères)",
-                                &trimmed_text[..300],
-                                trimmed_text.len()
-                            )
-                        } else {
-                            trimmed_text.to_string()
-                        };
-                        println!("Texte extrait:");
-                        println!("{}", preview);
-                    }
-                    println!("✓ Succès");
-                }
-            }
-            Err(e) => {
-                error_count += 1;
-                println!("✗ Erreur: {}", e);
-            }
+    /// Construit l'entrée d'une image écartée par les vérifications préalables.
+    fn skipped(path: &Path, reason: &ImageFileError) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            error: reason.to_string(),
+            kind: FailureKind::Skipped,
+            step: None,
+            step_index: None,
+            duplicate_of: None,
         }
+    }
 
-        println!();
+    /// Construit l'entrée d'une image non lue car doublon de `original`.
+    fn duplicate(path: &Path, original: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            error: format!("doublon de {}", original.display()),
+            kind: FailureKind::Duplicate,
+            step: None,
+            step_index: None,
+            duplicate_of: Some(original.to_path_buf()),
+        }
     }
+}
 
-    // Afficher le résumé
-    println!("═══════════════════════════════════════════════════════════");
-    println!("                   RÉSUMÉ DU TRAITEMENT");
-    println!("═══════════════════════════════════════════════════════════");
-    println!("Total:     {} images", image_files.len());
-    println!("Succès:    {} images", success_count);
-    println!("Erreurs:   {} images", error_count);
-    println!(
-        "Taux de réussite: {:.1}%",
-        (success_count as f64 / image_files.len() as f64) * 100.0
-    );
-    println!("═══════════════════════════════════════════════════════════");
+/// Sépare les images exploitables de celles écartées par [`validate_image_file()`].
+///
+/// Seuls les fichiers vides, tronqués, de format inconnu ou trop petits sont
+/// écartés ; les autres erreurs (fichier introuvable, lecture impossible)
+/// sont laissées à l'extraction, qui les signale comme des échecs.
+fn preflight_images(image_files: &[PathBuf]) -> (Vec<PathBuf>, Vec<BatchFailure>) {
+    let mut valid = Vec::with_capacity(image_files.len());
+    let mut skipped = Vec::new();
 
-    if error_count > 0 {
-        anyhow::bail!("{} image(s) n'ont pas pu être traitées", error_count);
+    for path in image_files {
+        match validate_image_file(path) {
+            Err(e) => match e.downcast_ref::<ImageFileError>() {
+                Some(reason) => skipped.push(BatchFailure::skipped(path, reason)),
+                None => valid.push(path.clone()),
+            },
+            Ok(_) => valid.push(path.clone()),
+        }
     }
 
-    Ok(())
+    (valid, skipped)
 }
 
-fn main() -> Result<()> {
-    // Parser les arguments de la ligne de commande
-    let args = Args::parse();
+/// Retire des images à lire les doublons trouvés par [`find_duplicates()`].
+///
+/// Seule l'image originale de chaque groupe est conservée ; l'ordre des
+/// images restantes est inchangé.
+fn split_duplicates(
+    image_files: Vec<PathBuf>,
+    groups: &[DuplicateGroup],
+) -> (Vec<PathBuf>, Vec<BatchFailure>) {
+    let original_of: HashMap<&Path, &Path> = groups
+        .iter()
+        .flat_map(|group| {
+            group
+                .duplicates
+                .iter()
+                .map(|duplicate| (duplicate.as_path(), group.original.as_path()))
+        })
+        .collect();
 
-    // Mode spécial: tester tous les PSM
-    if args.test_all_psm {
-        return test_all_psm_modes(&args);
+    let mut duplicates = Vec::new();
+    let mut remaining = Vec::with_capacity(image_files.len());
+    for path in image_files {
+        match original_of.get(path.as_path()) {
+            Some(original) => duplicates.push(BatchFailure::duplicate(&path, original)),
+            None => remaining.push(path),
+        }
     }
+    (remaining, duplicates)
+}
 
-    // Mode batch : traiter plusieurs images
-    if args.batch {
-        // Validation: en mode batch, --expected et --metrics ne sont pas supportés
-        if args.expected.is_some() {
-            anyhow::bail!(
-                "L'option --expected n'est pas supportée en mode batch. \
-                 Traitez les images individuellement pour comparer avec des textes de référence."
-            );
+/// Bilan du mode batch, affiché dans le résumé et écrit dans le rapport d'erreurs.
+#[derive(Debug, Default)]
+struct BatchSummary {
+    /// Nombre d'images collectées.
+    total: usize,
+    /// Nombre d'images traitées avec succès.
+    success: usize,
+    /// Échecs de l'extraction ou de l'écriture du résultat.
+    failures: Vec<BatchFailure>,
+    /// Images écartées avant l'OCR.
+    skipped: Vec<BatchFailure>,
+    /// Doublons non lus (--skip-duplicates), ni succès ni échecs.
+    duplicates: Vec<BatchFailure>,
+    /// Images non traitées à cause d'une interruption.
+    cancelled: Vec<BatchFailure>,
+    /// Pages blanches dont l'OCR a été évité (comptées dans les succès).
+    blank: usize,
+    /// Pages logiques des images traitées avec succès (une double page coupée en compte deux).
+    pages: usize,
+    /// Images déjà traitées lors d'une exécution précédente (comptées dans les succès).
+    resumed: usize,
+    /// Résultats lus dans le cache --result-cache (comptés dans les succès).
+    cached: usize,
+    /// Fichiers de sortie des images (avec --output).
+    outputs: Vec<OutputMapping>,
+    /// Nombre d'images par profil, si --config définit des règles.
+    profiles: Vec<(String, usize)>,
+}
+
+impl BatchSummary {
+    /// Commence le bilan de `total` images, dont `skipped` écartées avant l'OCR.
+    fn new(total: usize, skipped: Vec<BatchFailure>) -> Self {
+        Self {
+            total,
+            skipped,
+            ..Self::default()
         }
     }
 
-    // Charger la configuration depuis un fichier si --config est fourni
-    let file_config = if let Some(ref config_path) = args.config {
-        let app_config = load_config(config_path)
-            .with_context(|| format!("Impossible de charger '{}'", config_path.display()))?;
-        Some(app_config)
-    } else {
-        None
-    };
+    /// Enregistre le résultat d'une image et retourne l'échec éventuel.
+    fn record(&mut self, path: &Path, result: Result<()>) -> Option<&BatchFailure> {
+        let error = match result {
+            Ok(()) => {
+                self.success += 1;
+                return None;
+            }
+            Err(e) => e,
+        };
 
-    // Créer la configuration OCR (fichier de config en base, arguments CLI en surcharge)
-    let config = {
-        let base = file_config
-            .as_ref()
-            .and_then(|c| c.ocr.clone())
-            .unwrap_or_default();
+        let failure = BatchFailure::new(path, &error);
+        let list = if failure.kind == FailureKind::Cancelled {
+            &mut self.cancelled
+        } else {
+            &mut self.failures
+        };
+        list.push(failure);
+        list.last()
+    }
 
-        OcrConfig {
-            // Les arguments CLI ont priorité sur le fichier (valeurs non-défaut)
-            language: if args.language != "fra" {
-                args.language.clone()
-            } else {
-                base.language
-            },
-            page_seg_mode: if args.psm != 3 {
-                psm_from_int(args.psm)
-            } else {
-                base.page_seg_mode
-            },
-            dpi: if args.dpi != 300 { args.dpi } else { base.dpi },
-            tesseract_variables: base.tesseract_variables,
-        }
-    };
+    /// Code de sortie : les images ignorées ou annulées empêchent [`EXIT_OK`],
+    /// pas les doublons.
+    fn exit_code(&self) -> u8 {
+        decide_exit_code(
+            self.success,
+            self.failures.len() + self.skipped.len() + self.cancelled.len(),
+            self.total,
+        )
+    }
 
-    // Créer le moteur OCR avec ou sans prétraitement
-    let engine = if args.batch {
-        // En mode batch, créer le moteur une seule fois et le réutiliser
-        if args.preprocess {
-            let base_prep = file_config
-                .as_ref()
-                .and_then(|c| c.preprocessing.clone())
-                .unwrap_or_default();
-
-            let binarization_method = parse_binarization_method(&args.binarize_method)?;
-
-            let preprocess_config = PreprocessingConfig {
-                to_grayscale: args.grayscale || base_prep.to_grayscale,
-                binarize: args.binarize || base_prep.binarize,
-                binarization_method: if args.binarize {
-                    binarization_method
-                } else {
-                    base_prep.binarization_method
-                },
-                adjust_contrast: args.contrast.is_some() || base_prep.adjust_contrast,
-                contrast_factor: args.contrast.unwrap_or(base_prep.contrast_factor),
-                denoise: args.denoise || base_prep.denoise,
-                deskew: args.deskew || base_prep.deskew,
-            };
+    /// Entrées du rapport d'erreurs : ignorées, doublons, échecs puis annulées.
+    fn report_entries(&self) -> Vec<BatchFailure> {
+        self.skipped
+            .iter()
+            .chain(&self.duplicates)
+            .chain(&self.failures)
+            .chain(&self.cancelled)
+            .cloned()
+            .collect()
+    }
+}
 
-            OcrEngine::with_preprocessing(config, preprocess_config)?
-        } else if let Some(prep) = file_config.as_ref().and_then(|c| c.preprocessing.clone()) {
-            OcrEngine::with_preprocessing(config, prep)?
-        } else {
-            OcrEngine::new(config)?
+impl fmt::Display for BatchSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Total:     {} images", self.total)?;
+        writeln!(f, "Succès:    {} images", self.success)?;
+        writeln!(f, "Erreurs:   {} images", self.failures.len())?;
+        writeln!(f, "Ignorées:  {} images", self.skipped.len())?;
+        for entry in &self.skipped {
+            writeln!(f, "  ⊘ {} ({})", entry.path.display(), entry.error)?;
         }
-    } else if args.preprocess {
-        // Construire la configuration de prétraitement (fichier en base, CLI en surcharge)
-        let base_prep = file_config
-            .as_ref()
-            .and_then(|c| c.preprocessing.clone())
-            .unwrap_or_default();
+        if !self.duplicates.is_empty() {
+            writeln!(
+                f,
+                "Doublons:  {} images (OCR non lancé)",
+                self.duplicates.len()
+            )?;
+            for entry in &self.duplicates {
+                writeln!(f, "  ≡ {} ({})", entry.path.display(), entry.error)?;
+            }
+        }
+        if self.resumed > 0 {
+            writeln!(
+                f,
+                "Reprises:  {} images (déjà traitées, --checkpoint)",
+                self.resumed
+            )?;
+        }
+        if self.cached > 0 {
+            writeln!(
+                f,
+                "Cache:     {} images (résultat réutilisé, OCR non lancé)",
+                self.cached
+            )?;
+        }
+        if self.blank > 0 {
+            writeln!(f, "Blanches:  {} pages (OCR non lancé)", self.blank)?;
+        }
+        // Les pages des images reprises ne sont pas connues
+        let processed = self.success - self.resumed;
+        if self.pages > processed {
+            writeln!(
+                f,
+                "Pages:     {} pages logiques ({} doubles pages coupées)",
+                self.pages,
+                self.pages - processed
+            )?;
+        }
+        if !self.profiles.is_empty() {
+            writeln!(f, "Profils:")?;
+            for (profile, count) in &self.profiles {
+                writeln!(f, "  {}: {} images", profile, count)?;
+            }
+        }
+        let renamed: Vec<&OutputMapping> = self.outputs.iter().filter(|m| m.renamed).collect();
+        if !renamed.is_empty() {
+            writeln!(f, "Renommées: {} sorties (noms en double)", renamed.len())?;
+            for mapping in renamed {
+                writeln!(
+                    f,
+                    "  {} → {}",
+                    mapping.input.display(),
+                    mapping.output.display()
+                )?;
+            }
+        }
+        if !self.cancelled.is_empty() {
+            writeln!(
+                f,
+                "Annulées:  {} images (traitement interrompu)",
+                self.cancelled.len()
+            )?;
+        }
+        write!(
+            f,
+            "Taux de réussite: {:.1}%",
+            (self.success as f64 / self.total as f64) * 100.0
+        )
+    }
+}
 
-        let binarization_method = parse_binarization_method(&args.binarize_method)?;
+/// Bilan de `--batch --dry-run` : composition du lot et estimation du traitement.
+#[derive(Debug, Default)]
+struct DryRunReport {
+    /// Nombre d'images collectées.
+    total: usize,
+    /// Nombre d'images par extension (en minuscules).
+    by_extension: BTreeMap<String, usize>,
+    /// Images qui seraient écartées avant l'OCR, par motif.
+    skipped: BTreeMap<String, usize>,
+    /// Images de l'échantillon détectées comme pages blanches.
+    sample_blank: usize,
+    /// Images de l'échantillon impossibles à décoder.
+    sample_unreadable: usize,
+    /// Images de l'échantillon dont l'OCR a échoué.
+    sample_failed: usize,
+    /// Durées extrapolées à partir de l'échantillon.
+    estimate: Estimate,
+    /// Volume estimé des sorties texte, en octets.
+    output_bytes: u64,
+}
 
-        let preprocess_config = PreprocessingConfig {
-            to_grayscale: args.grayscale || base_prep.to_grayscale,
-            binarize: args.binarize || base_prep.binarize,
-            binarization_method: if args.binarize {
-                binarization_method
-            } else {
-                base_prep.binarization_method
-            },
-            adjust_contrast: args.contrast.is_some() || base_prep.adjust_contrast,
-            contrast_factor: args.contrast.unwrap_or(base_prep.contrast_factor),
-            denoise: args.denoise || base_prep.denoise,
-            deskew: args.deskew || base_prep.deskew,
+impl DryRunReport {
+    /// Décompte un lot d'images collectées et les écarts des vérifications préalables.
+    fn new(image_files: &[PathBuf], skipped: &[BatchFailure]) -> Self {
+        let mut report = Self {
+            total: image_files.len(),
+            ..Self::default()
         };
+        for path in image_files {
+            let extension = path
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|| "(sans extension)".to_string());
+            *report.by_extension.entry(extension).or_default() += 1;
+        }
+        for entry in skipped {
+            *report.skipped.entry(entry.error.clone()).or_default() += 1;
+        }
+        report
+    }
+}
+
+impl fmt::Display for DryRunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let estimate = &self.estimate;
+        writeln!(f, "Total:     {} images", self.total)?;
+        for (extension, count) in &self.by_extension {
+            writeln!(f, "  .{:<8} {}", extension, count)?;
+        }
+        let skipped: usize = self.skipped.values().sum();
+        writeln!(f, "À traiter: {} images", self.total - skipped)?;
+        writeln!(f, "Ignorées:  {} images", skipped)?;
+        for (reason, count) in &self.skipped {
+            writeln!(f, "  ⊘ {}: {}", reason, count)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "Échantillon: {} images", estimate.sample_size)?;
+        writeln!(f, "  Pages blanches: {}", self.sample_blank)?;
+        writeln!(f, "  Illisibles:     {}", self.sample_unreadable)?;
+        writeln!(f, "  Échecs OCR:     {}", self.sample_failed)?;
+        if estimate.sample_size == 0 {
+            return write!(f, "Aucune image mesurée : pas d'estimation de durée");
+        }
+        writeln!(
+            f,
+            "Durée par image: {} (médiane, de {} à {})",
+            format_duration(estimate.median_per_image),
+            format_duration(estimate.min_per_image),
+            format_duration(estimate.max_per_image)
+        )?;
+        writeln!(
+            f,
+            "Durée estimée:   {} avec {} worker(s) (de {} à {})",
+            format_duration(estimate.wall_time),
+            estimate.parallelism,
+            format_duration(estimate.min_wall_time),
+            format_duration(estimate.max_wall_time)
+        )?;
+        write!(
+            f,
+            "Sorties:         environ {}",
+            format_bytes(self.output_bytes)
+        )
+    }
+}
 
-        OcrEngine::with_preprocessing(config, preprocess_config)?
-    } else if let Some(prep) = file_config.as_ref().and_then(|c| c.preprocessing.clone()) {
-        // Pas de --preprocess CLI mais le fichier de config a une section preprocessing
-        OcrEngine::with_preprocessing(config, prep)?
+/// Formate une durée pour un humain (ex: "850 ms", "12.4 s", "3 min 20 s", "2 h 05 min").
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if duration < Duration::from_secs(1) {
+        format!("{} ms", duration.as_millis())
+    } else if seconds < 60 {
+        format!("{:.1} s", duration.as_secs_f64())
+    } else if seconds < 3600 {
+        format!("{} min {:02} s", seconds / 60, seconds % 60)
     } else {
-        OcrEngine::new(config)?
-    };
+        format!("{} h {:02} min", seconds / 3600, seconds % 3600 / 60)
+    }
+}
 
-    // En mode batch, traiter toutes les images et terminer
-    if args.batch {
-        return process_batch(&args, &engine);
+/// Formate une taille en octets (ex: "512 o", "4.2 Ko", "1.5 Mo").
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["Ko", "Mo", "Go", "To"];
+    if bytes < 1024 {
+        return format!("{} o", bytes);
     }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
 
-    // Mode normal: traiter une seule image
+/// Jeton levé par Ctrl-C pendant le mode batch.
+static INTERRUPT_TOKEN: OnceLock<CancellationToken> = OnceLock::new();
 
-    // Mode HOCR : générer et afficher les bounding boxes
-    if args.hocr {
-        // Générer le HOCR via Tesseract
-        let hocr_content = generate_hocr(&args.image, &args.language, args.psm as u8)?;
+/// Installe le gestionnaire de Ctrl-C qui annule `token`.
+///
+/// Le premier Ctrl-C annule le jeton (arrêt propre) et rétablit le
+/// comportement par défaut : un second Ctrl-C interrompt le programme.
+#[cfg(unix)]
+fn install_interrupt_handler(token: &CancellationToken) {
+    extern "C" fn on_interrupt(_signal: libc::c_int) {
+        // Uniquement des opérations atomiques, sûres dans un gestionnaire de signal
+        if let Some(token) = INTERRUPT_TOKEN.get() {
+            token.cancel();
+        }
+        // SAFETY: signal() fait partie des fonctions sûres en contexte de signal
+        unsafe {
+            libc::signal(libc::SIGINT, libc::SIG_DFL);
+        }
+    }
 
-        // Parser le document HOCR
-        let doc = HocrDocument::from_hocr_string(&hocr_content)?;
+    if INTERRUPT_TOKEN.set(token.clone()).is_err() {
+        return;
+    }
+    let handler = on_interrupt as extern "C" fn(libc::c_int);
+    // SAFETY: le gestionnaire n'accède qu'à un OnceLock initialisé et à un AtomicBool
+    unsafe {
+        libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+    }
+}
 
-        // Générer le rapport
-        let report = doc.generate_report();
+/// Sans signaux POSIX, Ctrl-C conserve son comportement par défaut.
+#[cfg(not(unix))]
+fn install_interrupt_handler(_token: &CancellationToken) {}
 
-        // Sauvegarder ou afficher le rapport
-        if let Some(output_path) = args.hocr_output {
-            fs::write(&output_path, &report).with_context(|| {
-                format!(
-                    "Impossible d'écrire le fichier de sortie '{}'",
-                    output_path.display()
-                )
-            })?;
-            println!("✓ Rapport HOCR sauvegardé dans: {}", output_path.display());
-        } else {
-            println!("{}", report);
+/// Point d'entrée du processus enfant d'une extraction isolée (voir --isolate).
+///
+/// Lit la tâche sur l'entrée standard et écrit le résultat sur la sortie
+/// standard ; un échec de l'extraction fait partie du résultat.
+fn worker_main() -> ExitCode {
+    match run_worker(std::io::stdin().lock(), std::io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            ExitCode::from(EXIT_FATAL)
         }
+    }
+}
 
-        return Ok(());
+/// Exécute une extraction, bornée par --timeout s'il est fourni.
+fn extract_with_timeout<F>(
+    engine: &OcrEngine,
+    timeout: Option<Duration>,
+    extract: F,
+) -> Result<OcrOutcome>
+where
+    F: FnOnce(&OcrEngine) -> Result<OcrOutcome> + Send + 'static,
+{
+    match timeout {
+        Some(timeout) => engine.run_with_deadline(timeout, extract),
+        None => extract(engine),
     }
+}
 
-    // Extraire le texte (avec correction d'orientation si demandée)
-    let text = if args.auto_rotate {
-        // Détecter et corriger l'orientation via Tesseract PSM 0
-        let helper = OcrEngine::new(OcrConfig::default())?;
-        let corrected_image = helper.detect_and_correct_orientation(&args.image)?;
-        engine.extract_text_from_image(&corrected_image)?
-    } else {
-        engine.extract_text_from_file(&args.image)?
-    };
+/// Écrit le rapport d'échecs du mode batch au format JSON (tableau).
+fn write_error_report(path: &Path, failures: &[BatchFailure]) -> Result<()> {
+    let json = serde_json::to_string_pretty(failures)
+        .context("Impossible de sérialiser le rapport d'erreurs")?;
+    fs::write(path, json + "\n").with_context(|| {
+        format!(
+            "Impossible d'écrire le rapport d'erreurs '{}'",
+            path.display()
+        )
+    })
+}
 
-    // Si un fichier de référence est fourni, comparer et afficher les métriques
-    if let Some(expected_path) = args.expected {
-        let expected_text = fs::read_to_string(&expected_path).map_err(|e| {
-            anyhow::anyhow!(
-                "Impossible de lire le fichier de référence '{}': {}",
-                expected_path.display(),
-                e
-            )
-        })?;
+/// Traite plusieurs images en mode batch.
+///
+/// Cette fonction collecte les fichiers images selon le chemin fourni
+/// (fichier unique, répertoire, ou pattern glob), puis extrait le texte
+/// de chaque image avec la configuration fournie.
+///
+/// Les résultats peuvent être affichés dans le terminal ou sauvegardés
+/// dans des fichiers si un répertoire de sortie est spécifié. L'échec d'une
+/// image n'interrompt pas le traitement des suivantes.
+///
+/// Les fichiers vides, tronqués ou trop petits sont écartés avant l'OCR
+/// (voir [`preflight_images()`]) : ils apparaissent comme ignorés dans le
+/// résumé et dans le rapport d'erreurs, et empêchent un code de sortie
+/// [`EXIT_OK`] sans être comptés comme des erreurs d'OCR.
+///
+/// Ctrl-C arrête le traitement proprement : les images non traitées sont
+/// comptées comme annulées dans le résumé.
+///
+/// Avec des règles de profil dans le fichier --config, chaque image est
+/// traitée avec le profil de la première règle qui correspond à son chemin
+/// (voir [`group_by_profile()`]) ; un moteur est créé par profil utilisé et
+/// les profils sont traités l'un après l'autre.
+///
+/// # Arguments
+///
+/// * `args` - Arguments de la ligne de commande
+/// * `engine` - Moteur OCR configuré (configuration de premier niveau)
+/// * `file_config` - Configuration chargée depuis --config (profils et règles)
+///
+/// # Retour
+///
+/// Le code de sortie calculé par [`decide_exit_code()`], ou [`EXIT_FATAL`]
+/// si le répertoire de sortie ou le rapport d'erreurs ne peut pas être écrit.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si aucun fichier image n'est trouvé ou si le moteur
+/// d'un profil ne peut pas être créé.
+fn process_batch(
+    args: &ValidatedArgs,
+    engine: &OcrEngine,
+    file_config: Option<&AppConfig>,
+) -> Result<u8> {
+    // Collecter les fichiers images
+    let image_files = collect_image_files(args.image()?, &collect_options_from_args(args))?;
 
-        let metrics = compare_ocr_result(&text, &expected_text);
+    println!("═══════════════════════════════════════════════════════════");
+    println!("              MODE BATCH - TRAITEMENT MULTIPLE");
+    println!("═══════════════════════════════════════════════════════════");
+    println!();
+    println!("Nombre d'images à traiter: {}", image_files.len());
+    if args.jobs > 1 {
+        println!("Workers en parallèle: {}", args.jobs);
+    }
+    println!();
 
-        // Export CSV si demandé
-        if let Some(csv_path) = args.csv_export {
-            // Créer les métadonnées
-            let mut metadata = HashMap::new();
-            metadata.insert(
-                "image".to_string(),
-                args.image.to_string_lossy().to_string(),
+    // Créer le répertoire de sortie si nécessaire
+    if let Some(ref output_dir) = args.output {
+        if let Err(e) = fs::create_dir_all(output_dir) {
+            eprintln!(
+                "✗ Impossible de créer le répertoire de sortie '{}': {}",
+                output_dir.display(),
+                e
             );
-            metadata.insert("language".to_string(), args.language.clone());
-            metadata.insert("psm".to_string(), args.psm.to_string());
-            metadata.insert("dpi".to_string(), args.dpi.to_string());
-            metadata.insert("preprocess".to_string(), args.preprocess.to_string());
+            return Ok(EXIT_FATAL);
+        }
+        println!("Répertoire de sortie: {}", output_dir.display());
+        println!();
+    }
 
-            // Générer le CSV
-            let csv_content = metrics.to_csv(true, Some(&metadata));
+    // Écarter les fichiers vides ou tronqués avant de lancer l'OCR
+    let (valid_files, skipped) = preflight_images(&image_files);
+    for entry in &skipped {
+        println!("⊘ Ignorée: {} ({})", entry.path.display(), entry.error);
+    }
+    if !skipped.is_empty() {
+        println!();
+    }
 
-            // Écrire dans le fichier
-            fs::write(&csv_path, csv_content).with_context(|| {
-                format!(
-                    "Impossible d'écrire le fichier CSV '{}'",
-                    csv_path.display()
-                )
-            })?;
+    // Ne lire qu'une image par groupe de doublons (--skip-duplicates)
+    let (valid_files, duplicates) = match args.skip_duplicates {
+        Some(max_distance) => {
+            let groups = find_duplicates(&valid_files, max_distance);
+            let (remaining, duplicates) = split_duplicates(valid_files, &groups);
+            for entry in &duplicates {
+                println!("≡ Doublon: {} ({})", entry.path.display(), entry.error);
+            }
+            if !duplicates.is_empty() {
+                println!();
+            }
+            (remaining, duplicates)
+        }
+        None => (valid_files, Vec::new()),
+    };
 
-            println!("✓ Métriques exportées vers: {}", csv_path.display());
-        } else if args.metrics {
-            // Rapport détaillé avec generate_diff_report()
-            let report = generate_diff_report(&text, &expected_text);
-            println!("{}", report);
-        } else {
-            // Affichage simple des métriques essentielles
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            println!("               RÉSULTATS DE LA COMPARAISON OCR");
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            println!();
-            println!("MÉTRIQUES:");
-            println!(
-                "  • CER (Character Error Rate):  {:.2}%",
-                metrics.cer * 100.0
-            );
-            println!(
-                "  • WER (Word Error Rate):       {:.2}%",
-                metrics.wer * 100.0
-            );
-            println!(
-                "  • Distance de Levenshtein:     {}",
-                metrics.levenshtein_distance
-            );
-            println!(
-                "  • Précision:                   {:.2}%",
-                metrics.accuracy() * 100.0
-            );
-            println!();
-            println!("STATISTIQUES:");
-            println!(
-                "  • Référence:  {} caractères, {} mots",
-                metrics.reference_char_count, metrics.reference_word_count
-            );
-            println!(
-                "  • OCR:        {} caractères, {} mots",
-                metrics.ocr_char_count, metrics.ocr_word_count
-            );
-            println!();
-            println!(
-                "  • Match exact: {}",
-                if metrics.exact_match {
-                    "Oui ✓"
-                } else {
-                    "Non ✗"
+    // Statistiques globales
+    let mut summary = BatchSummary::new(image_files.len(), skipped);
+    summary.duplicates = duplicates;
+
+    // Fichiers de sortie : arborescence reproduite (ou --output-template),
+    // noms en double départagés
+    let output_paths: Vec<Option<PathBuf>> = match args.output {
+        Some(ref output_dir) => {
+            let mappings = match args.output_template {
+                Some(ref template) => templated_output_paths(
+                    args,
+                    engine,
+                    file_config,
+                    template,
+                    &valid_files,
+                    output_dir,
+                )?,
+                None => batch_output_paths(args, &valid_files, output_dir, "txt"),
+            };
+            let paths = mappings
+                .iter()
+                .map(|mapping| Some(mapping.output.clone()))
+                .collect();
+            summary.outputs = mappings;
+            paths
+        }
+        None => vec![None; valid_files.len()],
+    };
+
+    // Reprise : sauter les images déjà journalisées, après le calcul des
+    // noms de sortie pour qu'ils restent ceux de la première exécution
+    let journal = open_checkpoint(args)?;
+    let (valid_files, output_paths) = match journal {
+        Some(ref journal) => {
+            let retry_failed = !args.no_retry_failed;
+            let mut remaining = (Vec::new(), Vec::new());
+            for (image_path, output_path) in valid_files.into_iter().zip(output_paths) {
+                // Seules les images déjà journalisées sont relues pour l'empreinte
+                let done = journal.entry(&image_path).filter(|_| {
+                    sha256_file(&image_path)
+                        .is_ok_and(|hash| journal.should_skip(&image_path, &hash, retry_failed))
+                });
+                match done {
+                    Some(entry) if entry.is_success() => {
+                        summary.success += 1;
+                        summary.resumed += 1;
+                    }
+                    Some(entry) => {
+                        let error = anyhow::anyhow!(entry.error.clone().unwrap_or_default());
+                        summary
+                            .failures
+                            .push(BatchFailure::new(&image_path, &error));
+                    }
+                    _ => {
+                        remaining.0.push(image_path);
+                        remaining.1.push(output_path);
+                    }
                 }
+            }
+            println!(
+                "Reprise ({}): {} images déjà traitées, {} restantes",
+                journal.path().display(),
+                summary.resumed + summary.failures.len(),
+                remaining.0.len()
             );
             println!();
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            remaining
         }
+        None => (valid_files, output_paths),
+    };
+    let checkpoint = journal.map(Mutex::new);
+    let outputs_by_input: HashMap<&Path, Option<&Path>> = valid_files
+        .iter()
+        .map(PathBuf::as_path)
+        .zip(output_paths.iter().map(Option::as_deref))
+        .collect();
+
+    // Confusions de caractères agrégées sur le corpus (avec --expected)
+    let mut confusions = ConfusionMatrix::new();
+    let mut compared_count = 0;
+    // Métriques par image pour --csv-export et --metrics-jsonl
+    let export_metrics = args.csv_export.is_some() || args.metrics_jsonl.is_some();
+    let mut collected_metrics = MetricsCollection::new();
+    // Métriques de chaque image comparée pour --ledger
+    let mut run_metrics = Vec::new();
+    let started = Instant::now();
+
+    // Ctrl-C : ne plus commencer de nouvelle image et arrêter l'image en cours
+    // entre deux étapes du pipeline
+    let token = CancellationToken::new();
+    install_interrupt_handler(&token);
+    // Avec --isolate, le délai est imposé au processus enfant de chaque image
+    let timeout = args.timeout.map(Duration::from_secs);
+    let deadline = if args.isolate { None } else { timeout };
+    let isolation = if args.isolate {
+        Some(WorkerCommand::current_exe()?)
     } else {
-        // Afficher le résultat normalement
-        println!("{}", text);
+        None
+    };
+    let auto_rotate = args.auto_rotate;
+    // Durée d'extraction de chaque image, pour le manifeste de --output-template
+    let durations: Mutex<HashMap<PathBuf, Duration>> = Mutex::new(HashMap::new());
+
+    // Un moteur par profil utilisé, créé avant la première image
+    let rules = file_config.map_or(&[][..], |config| config.rules.as_slice());
+    let root = args.image.as_deref().and_then(input_root);
+    let groups = group_by_profile(&valid_files, root.as_deref(), rules);
+    let mut group_engines = Vec::with_capacity(groups.len());
+    // Configuration de chaque profil et profil de chaque image, pour les
+    // métadonnées des métriques exportées
+    let mut group_settings = Vec::with_capacity(groups.len());
+    let mut group_of_image = vec![0; valid_files.len()];
+    for (group, (profile, indices)) in groups.iter().enumerate() {
+        let group_engine = match (profile, file_config) {
+            (Some(name), Some(config)) => {
+                let profile_config = config
+                    .profiles
+                    .get(*name)
+                    .with_context(|| format!("Profil inconnu : '{}'", name))?;
+                profile_engine(args, profile_config)
+                    .with_context(|| format!("Configuration du profil '{}' invalide", name))?
+            }
+            _ => engine.clone(),
+        };
+        for &index in indices {
+            group_of_image[index] = group;
+        }
+        group_settings.push((
+            group_engine.config().clone(),
+            group_engine.preprocessing_config().cloned(),
+        ));
+        let group_engine = match isolation {
+            Some(ref worker) => group_engine.with_isolation(worker.clone()),
+            None => group_engine,
+        };
+        group_engines.push(group_engine.with_cancellation(token.clone()));
+        if !rules.is_empty() {
+            summary.profiles.push((
+                profile.unwrap_or(DEFAULT_PROFILE_LABEL).to_string(),
+                indices.len(),
+            ));
+        }
     }
 
-    Ok(())
+    // Extraire le texte de toutes les images (en parallèle avec --jobs),
+    // avec correction d'orientation si demandée
+    let extract = |worker: &mut OcrEngine, path: &Path| {
+        let image_path = path.to_path_buf();
+        let start = Instant::now();
+        let result = extract_with_timeout(worker, deadline, move |engine| {
+            if auto_rotate {
+                engine.extract_text_auto_rotated(&image_path)
+            } else {
+                engine.extract_text_detailed(&image_path)
+            }
+        });
+        if let Ok(mut durations) = durations.lock() {
+            durations.insert(path.to_path_buf(), start.elapsed());
+        }
+        // Avec --checkpoint, la sortie est écrite et journalisée au fil de l'eau
+        match checkpoint {
+            Some(ref journal) => {
+                let output_path = outputs_by_input.get(path).copied().flatten();
+                checkpoint_result(
+                    journal,
+                    path,
+                    output_path,
+                    result,
+                    args.encoding,
+                    args.newline,
+                )
+            }
+            None => result,
+        }
+    };
+    let mut indexed_results = Vec::with_capacity(valid_files.len());
+    for ((_, indices), group_engine) in groups.iter().zip(group_engines) {
+        let files: Vec<PathBuf> = indices
+            .iter()
+            .map(|&index| valid_files[index].clone())
+            .collect();
+        let pool = OcrEnginePool::from_engine(group_engine, args.jobs)?;
+        let group_results = pool.batch_extract_cancellable(&files, &token, extract);
+        indexed_results.extend(indices.iter().copied().zip(group_results));
+    }
+    indexed_results.sort_by_key(|(index, _)| *index);
+    let results = indexed_results.into_iter().map(|(_, result)| result);
+    if token.is_cancelled() {
+        println!("⚠ Interruption demandée : les images restantes ne sont pas traitées");
+        println!();
+    }
+
+    // Afficher les résultats dans l'ordre des images
+    for (index, ((image_path, extraction_result), output_path)) in valid_files
+        .iter()
+        .zip(results)
+        .zip(&output_paths)
+        .enumerate()
+    {
+        // Images jamais commencées : seulement comptées dans le résumé
+        if let Err(ref e) = extraction_result
+            && matches!(e.downcast_ref::<OcrError>(), Some(OcrError::Cancelled))
+        {
+            summary.record(image_path, extraction_result.map(|_| ()));
+            continue;
+        }
+
+        let file_num = index + 1;
+        println!("───────────────────────────────────────────────────────────");
+        println!(
+            "[{}/{}] Traitement: {}",
+            file_num,
+            valid_files.len(),
+            image_path.display()
+        );
+        println!("───────────────────────────────────────────────────────────");
+
+        let blank = matches!(extraction_result, Ok(ref outcome) if outcome.is_blank_page());
+        let cached = matches!(extraction_result, Ok(ref outcome) if outcome.cache_hit);
+        let pages = extraction_result.as_ref().map_or(0, OcrOutcome::page_count);
+        let handled = extraction_result.and_then(|outcome| {
+            print_warnings(&outcome.warnings);
+            let compared = report_batch_result(
+                args,
+                image_path,
+                output_path.as_deref(),
+                checkpoint.is_some(),
+                &outcome.text,
+                &mut confusions,
+                &mut compared_count,
+            )?;
+            if let Some(ref detailed) = compared
+                && args.ledger.is_some()
+            {
+                run_metrics.push(detailed.metrics.clone());
+            }
+            if let Some(detailed) = compared
+                && export_metrics
+            {
+                let (config, preprocessing) = &group_settings[group_of_image[index]];
+                let metadata = csv_metadata(args, image_path, config, preprocessing.as_ref());
+                collected_metrics.push(detailed, metadata);
+            }
+            Ok(())
+        });
+
+        if blank && handled.is_ok() {
+            summary.blank += 1;
+        }
+        if cached && handled.is_ok() {
+            summary.cached += 1;
+        }
+        if handled.is_ok() {
+            summary.pages += pages;
+        }
+        if let Some(failure) = summary.record(image_path, handled) {
+            println!("✗ Erreur: {}", failure.error);
+        }
+
+        println!();
+    }
+
+    // Afficher le résumé
+    println!("═══════════════════════════════════════════════════════════");
+    println!("                   RÉSUMÉ DU TRAITEMENT");
+    println!("═══════════════════════════════════════════════════════════");
+    println!("{}", summary);
+
+    if args.expected.is_some() || args.expected_embedded {
+        println!();
+        println!(
+            "TOP CONFUSIONS ({} images comparées, {} erreurs de caractères):",
+            compared_count,
+            confusions.total()
+        );
+        if confusions.is_empty() {
+            println!("  Aucune");
+        }
+        for entry in confusions.top_n(TOP_CORPUS_CONFUSIONS) {
+            println!("  {}", entry);
+        }
+    }
+    println!("═══════════════════════════════════════════════════════════");
+
+    // Rapport d'erreurs machine (écrit même vide, pour simplifier les scripts)
+    if let Some(ref report_path) = args.error_report {
+        if let Err(e) = write_error_report(report_path, &summary.report_entries()) {
+            eprintln!("✗ {:#}", e);
+            return Ok(EXIT_FATAL);
+        }
+        println!("Rapport d'erreurs: {}", report_path.display());
+    }
+
+    if let Err(e) = write_batch_metrics(args, &collected_metrics) {
+        eprintln!("✗ {:#}", e);
+        return Ok(EXIT_FATAL);
+    }
+
+    if let Err(e) = record_experiment(args, engine, &run_metrics, started.elapsed()) {
+        eprintln!("✗ {:#}", e);
+        return Ok(EXIT_FATAL);
+    }
+
+    if let Some(ref map_path) = args.output_map {
+        if let Err(e) = write_output_map(map_path, &summary.outputs) {
+            eprintln!("✗ {:#}", e);
+            return Ok(EXIT_FATAL);
+        }
+        println!("Correspondance des sorties: {}", map_path.display());
+    }
+
+    if let (Some(output_dir), Some(_)) = (&args.output, &args.output_template) {
+        let durations = durations.into_inner().unwrap_or_else(|e| e.into_inner());
+        let manifest_path = output_dir.join(MANIFEST_FILE_NAME);
+        let manifest = build_manifest(&image_files, &summary, &durations);
+        if let Err(e) = write_manifest(&manifest_path, &manifest) {
+            eprintln!("✗ {:#}", e);
+            return Ok(EXIT_FATAL);
+        }
+        println!("Manifeste: {}", manifest_path.display());
+    }
+
+    Ok(summary.exit_code())
+}
+
+/// Nom du manifeste écrit dans --output avec --output-template.
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Statut d'une image dans le manifeste de --output-template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ManifestStatus {
+    /// Texte extrait et sauvegardé (y compris lors d'une exécution précédente).
+    Success,
+    /// Échec de l'extraction ou de l'écriture du résultat.
+    Failed,
+    /// Fichier écarté avant l'OCR.
+    Skipped,
+    /// Doublon d'une autre image du lot, non lu.
+    Duplicate,
+    /// Image non traitée à cause d'une interruption.
+    Cancelled,
+}
+
+/// Ligne du manifeste de --output-template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct ManifestEntry {
+    /// Image d'entrée.
+    input: PathBuf,
+    /// Fichier de sortie écrit, pour les images en succès.
+    output: Option<PathBuf>,
+    /// Issue du traitement de l'image.
+    status: ManifestStatus,
+    /// Durée de l'extraction en millisecondes, si elle a eu lieu lors de cette exécution.
+    duration_ms: Option<u64>,
+    /// Message d'erreur ou raison de l'écart.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Assemble le manifeste d'un lot : une entrée par image collectée, dans l'ordre de `images`.
+///
+/// Le statut est déduit du bilan : une image absente des échecs, des
+/// images ignorées, des doublons et des annulations est en succès.
+fn build_manifest(
+    images: &[PathBuf],
+    summary: &BatchSummary,
+    durations: &HashMap<PathBuf, Duration>,
+) -> Vec<ManifestEntry> {
+    let outcomes: HashMap<&Path, (ManifestStatus, &BatchFailure)> = [
+        (ManifestStatus::Skipped, &summary.skipped),
+        (ManifestStatus::Duplicate, &summary.duplicates),
+        (ManifestStatus::Failed, &summary.failures),
+        (ManifestStatus::Cancelled, &summary.cancelled),
+    ]
+    .into_iter()
+    .flat_map(|(status, entries)| {
+        entries
+            .iter()
+            .map(move |entry| (entry.path.as_path(), (status, entry)))
+    })
+    .collect();
+    let outputs: HashMap<&Path, &Path> = summary
+        .outputs
+        .iter()
+        .map(|mapping| (mapping.input.as_path(), mapping.output.as_path()))
+        .collect();
+
+    images
+        .iter()
+        .map(|image| {
+            let outcome = outcomes.get(image.as_path());
+            let status = outcome.map_or(ManifestStatus::Success, |(status, _)| *status);
+            ManifestEntry {
+                input: image.clone(),
+                output: (status == ManifestStatus::Success)
+                    .then(|| outputs.get(image.as_path()).map(|path| path.to_path_buf()))
+                    .flatten(),
+                status,
+                duration_ms: durations
+                    .get(image)
+                    .map(|duration| duration.as_millis() as u64),
+                error: outcome.map(|(_, entry)| entry.error.clone()),
+            }
+        })
+        .collect()
+}
+
+/// Écrit le manifeste de --output-template au format JSON (tableau).
+fn write_manifest(path: &Path, manifest: &[ManifestEntry]) -> Result<()> {
+    let json =
+        serde_json::to_string_pretty(manifest).context("Impossible de sérialiser le manifeste")?;
+    fs::write(path, json + "\n")
+        .with_context(|| format!("Impossible d'écrire le manifeste '{}'", path.display()))
+}
+
+/// Ajoute les métriques du batch aux fichiers de --csv-export et --metrics-jsonl.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si un fichier ne peut pas être ouvert ou écrit, ou
+/// si le fichier CSV existant a un autre en-tête.
+fn write_batch_metrics(args: &Args, metrics: &MetricsCollection) -> Result<()> {
+    if let Some(ref csv_path) = args.csv_export {
+        let sink = CsvMetricsSink::open(csv_path, csv_options(args)?)?;
+        let count = metrics.flush_to(&sink)?;
+        println!(
+            "✓ {} lignes de métriques ajoutées à: {}",
+            count,
+            csv_path.display()
+        );
+    }
+    if let Some(ref jsonl_path) = args.metrics_jsonl {
+        let sink = JsonlMetricsSink::open(jsonl_path)?;
+        let count = metrics.flush_to(&sink)?;
+        println!(
+            "✓ {} lignes de métriques ajoutées à: {}",
+            count,
+            jsonl_path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Ajoute l'exécution du batch au registre de --ledger.
+///
+/// La configuration enregistrée est celle du moteur principal : avec des
+/// profils par règles (--config), elle ne décrit pas toutes les images.
+///
+/// # Arguments
+///
+/// * `args` - Arguments de la ligne de commande
+/// * `engine` - Moteur principal du batch
+/// * `metrics` - Métriques des images comparées
+/// * `duration` - Durée totale du batch
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le registre ne peut pas être lu ou écrit.
+fn record_experiment(
+    args: &Args,
+    engine: &OcrEngine,
+    metrics: &[OcrMetrics],
+    duration: Duration,
+) -> Result<()> {
+    let (Some(ledger_path), Some(label)) = (&args.ledger, &args.experiment_label) else {
+        return Ok(());
+    };
+    let mut ledger = ExperimentLedger::open(ledger_path)?;
+    if ledger.recovered_truncated_tail() {
+        eprintln!(
+            "⚠ Ligne tronquée retirée du registre '{}'",
+            ledger_path.display()
+        );
+    }
+    let image_set = args
+        .image
+        .as_deref()
+        .map_or_else(String::new, |image| image.display().to_string());
+    let record = RunRecord::new(
+        label.as_str(),
+        image_set,
+        engine.config(),
+        engine.preprocessing_config(),
+        RunMetrics::from_metrics(metrics),
+        duration,
+    )?;
+    let (mean_cer, count) = (record.metrics.mean_cer, record.metrics.count);
+    ledger.record_run(record)?;
+    println!(
+        "✓ Exécution '{}' ajoutée au registre: {} (CER moyen {:.2}%, {} images)",
+        label,
+        ledger_path.display(),
+        mean_cer * 100.0,
+        count
+    );
+    Ok(())
+}
+
+/// Libellé de la configuration de premier niveau dans le décompte par profil.
+const DEFAULT_PROFILE_LABEL: &str = "(défaut)";
+
+/// Répartit les images d'un lot entre les profils des règles de --config.
+///
+/// Le chemin de chaque image est rendu relatif à `root` (voir
+/// [`input_root()`]) avant d'être comparé aux règles avec [`resolve_profile()`].
+///
+/// # Retour
+///
+/// Pour chaque profil utilisé (`None` pour la configuration de premier
+/// niveau), dans l'ordre de première apparition, les indices de ses images
+/// dans `images`.
+fn group_by_profile<'a>(
+    images: &[PathBuf],
+    root: Option<&Path>,
+    rules: &'a [ProfileRule],
+) -> Vec<(Option<&'a str>, Vec<usize>)> {
+    let mut groups: Vec<(Option<&str>, Vec<usize>)> = Vec::new();
+    for (index, image) in images.iter().enumerate() {
+        let relative = root
+            .and_then(|root| image.strip_prefix(root).ok())
+            .unwrap_or(image);
+        let profile = resolve_profile(relative, rules);
+        match groups.iter_mut().find(|(name, _)| *name == profile) {
+            Some((_, indices)) => indices.push(index),
+            None => groups.push((profile, vec![index])),
+        }
+    }
+    groups
+}
+
+/// Crée le moteur OCR d'un profil de --config pour le mode batch.
+///
+/// Les options CLI s'appliquent par-dessus le profil, comme pour la
+/// configuration de premier niveau.
+fn profile_engine(args: &ValidatedArgs, profile: &AppConfig) -> Result<OcrEngine> {
+    let config = build_ocr_config(args, Some(profile))?;
+    check_language_packs(&config)?;
+    let engine = match build_preprocessing_config(args, Some(profile))? {
+        Some(prep) => OcrEngine::with_preprocessing(config, prep)?,
+        None => OcrEngine::new(config)?,
+    };
+    let engine = engine.with_timeout(args.timeout.map(Duration::from_secs));
+    Ok(match args.debug_artifacts {
+        Some(ref dir) => engine.with_debug_artifacts(dir),
+        None => engine,
+    })
+}
+
+/// Estime un traitement batch sans rien écrire (`--batch --dry-run`).
+///
+/// Tous les fichiers collectés passent les vérifications d'en-tête du mode
+/// batch ; seul un échantillon tiré au hasard parmi les images valides est
+/// décodé, testé comme page blanche et analysé avec la configuration réelle
+/// pour mesurer la durée d'OCR d'une image.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si aucun fichier image n'est trouvé.
+fn estimate_batch(args: &Args, engine: &OcrEngine) -> Result<u8> {
+    let image_files = collect_image_files(args.image()?, &collect_options_from_args(args))?;
+    let (valid_files, skipped) = preflight_images(&image_files);
+    let mut report = DryRunReport::new(&image_files, &skipped);
+
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or_default();
+    let sample = sample_indices(valid_files.len(), args.sample, seed);
+
+    println!("═══════════════════════════════════════════════════════════");
+    println!("            MODE BATCH - ESTIMATION (--dry-run)");
+    println!("═══════════════════════════════════════════════════════════");
+    println!();
+    println!(
+        "Analyse de {} image(s) sur {} ...",
+        sample.len(),
+        valid_files.len()
+    );
+    println!();
+
+    let blank_options = engine
+        .preprocessing_config()
+        .map(|config| config.blank_detection.clone())
+        .unwrap_or_default();
+    let timeout = args.timeout.map(Duration::from_secs);
+    let mut timings = Vec::with_capacity(sample.len());
+    let mut output_sizes = Vec::with_capacity(sample.len());
+    for path in sample.into_iter().map(|index| &valid_files[index]) {
+        let Ok(image) = image::open(path) else {
+            report.sample_unreadable += 1;
+            continue;
+        };
+        if is_blank_page(&to_grayscale(&image), &blank_options).content == PageContent::Blank {
+            report.sample_blank += 1;
+        }
+
+        let owned_path = path.clone();
+        let auto_rotate = args.auto_rotate;
+        let start = Instant::now();
+        let result = extract_with_timeout(engine, timeout, move |engine| {
+            if auto_rotate {
+                engine.extract_text_auto_rotated(&owned_path)
+            } else {
+                engine.extract_text_detailed(&owned_path)
+            }
+        });
+        timings.push(start.elapsed());
+        match result {
+            Ok(outcome) => output_sizes.push(outcome.text.len() as u64),
+            Err(e) => {
+                report.sample_failed += 1;
+                println!("✗ {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    report.estimate = extrapolate(&timings, valid_files.len(), args.jobs);
+    report.output_bytes = estimate_total_bytes(&output_sizes, valid_files.len());
+    println!("{}", report);
+    println!();
+    println!("Aucune sortie écrite (--dry-run).");
+    Ok(EXIT_OK)
+}
+
+/// Calcule les fichiers de sortie des images d'un lot (options --output et --flatten).
+///
+/// L'arborescence est reproduite à partir du répertoire ou du préfixe fixe
+/// du pattern glob passé en IMAGE.
+fn batch_output_paths(
+    args: &Args,
+    images: &[PathBuf],
+    output_dir: &Path,
+    extension: &str,
+) -> Vec<OutputMapping> {
+    let root = args.image.as_deref().and_then(input_root);
+    let options = OutputPathOptions {
+        extension: extension.to_string(),
+        flatten: args.flatten,
+    };
+    plan_output_paths(images, root.as_deref(), output_dir, &options)
+}
+
+/// Calcule les fichiers de sortie des images d'un lot avec --output-template.
+///
+/// `{lang}` et `{psm}` sont ceux de la configuration qui lira l'image : le
+/// profil de la première règle de --config qui correspond à son chemin, ou
+/// la configuration de premier niveau. `{date}` est la date du lancement
+/// (UTC) et `{index}` le rang de l'image parmi les images à lire.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si un profil est inconnu ou invalide, si une image
+/// ne peut pas être lue pour `{hash8}`, ou si un chemin sortirait de --output.
+fn templated_output_paths(
+    args: &ValidatedArgs,
+    engine: &OcrEngine,
+    file_config: Option<&AppConfig>,
+    template: &OutputTemplate,
+    images: &[PathBuf],
+    output_dir: &Path,
+) -> Result<Vec<OutputMapping>> {
+    let root = args.image.as_deref().and_then(input_root);
+    let rules = file_config.map_or(&[][..], |config| config.rules.as_slice());
+    let top_level = (
+        engine.config().language.clone(),
+        engine.config().page_seg_mode,
+    );
+    let mut settings = vec![top_level; images.len()];
+    for (profile, indices) in group_by_profile(images, root.as_deref(), rules) {
+        let (Some(name), Some(config)) = (profile, file_config) else {
+            continue;
+        };
+        let profile_config = config
+            .profiles
+            .get(name)
+            .with_context(|| format!("Profil inconnu : '{}'", name))?;
+        let ocr = build_ocr_config(args, Some(profile_config))
+            .with_context(|| format!("Configuration du profil '{}' invalide", name))?;
+        for index in indices {
+            settings[index] = (ocr.language.clone(), ocr.page_seg_mode);
+        }
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let date = format_utc_date(now);
+    let values = images
+        .iter()
+        .zip(settings)
+        .enumerate()
+        .map(|(index, (image, (lang, psm)))| {
+            let hash8 = if template.uses_hash() {
+                let mut hash = sha256_file(image)?;
+                hash.truncate(8);
+                hash
+            } else {
+                String::new()
+            };
+            Ok(TemplateValues {
+                lang,
+                psm,
+                date: date.clone(),
+                index: index + 1,
+                hash8,
+                ..TemplateValues::from_input(image, root.as_deref())
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    plan_templated_output_paths(images, &values, output_dir, template)
+}
+
+/// Écrit la correspondance image → fichier de sortie au format JSON (tableau).
+fn write_output_map(path: &Path, mappings: &[OutputMapping]) -> Result<()> {
+    let json = serde_json::to_string_pretty(mappings)
+        .context("Impossible de sérialiser la correspondance des sorties")?;
+    fs::write(path, json + "\n").with_context(|| {
+        format!(
+            "Impossible d'écrire la correspondance des sorties '{}'",
+            path.display()
+        )
+    })
+}
+
+/// Compare (avec --expected ou --expected-embedded), puis affiche ou sauvegarde le texte d'une image du batch.
+///
+/// # Retour
+///
+/// Les métriques de l'image si elle a un texte de référence.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le texte de référence ne peut pas être lu ou si
+/// le fichier de sortie ne peut pas être écrit.
+fn report_batch_result(
+    args: &Args,
+    image_path: &Path,
+    output_path: Option<&Path>,
+    saved: bool,
+    text: &str,
+    confusions: &mut ConfusionMatrix,
+    compared_count: &mut usize,
+) -> Result<Option<DetailedOcrMetrics>> {
+    // Comparer avec le texte de référence de l'image si disponible
+    let mut compared = None;
+    let reference = if args.expected_embedded {
+        Some(embedded_reference(image_path)?)
+    } else if let Some(ref expected_dir) = args.expected {
+        let reference_path = expected_dir.join(file_stem(image_path)? + ".txt");
+        if reference_path.is_file() {
+            Some(fs::read_to_string(&reference_path).with_context(|| {
+                format!(
+                    "Impossible de lire le fichier de référence '{}'",
+                    reference_path.display()
+                )
+            })?)
+        } else {
+            println!("⚠ Pas de texte de référence: {}", reference_path.display());
+            None
+        }
+    } else {
+        None
+    };
+    if let Some(reference) = reference {
+        let detailed = compare_ocr_result_detailed_with(text, &reference, &args.tokenizer);
+        println!(
+            "CER: {:.2}% | WER: {:.2}%",
+            detailed.metrics.cer * 100.0,
+            detailed.metrics.wer * 100.0
+        );
+        confusions.add_pair(text, &reference);
+        *compared_count += 1;
+        compared = Some(detailed);
+    }
+
+    // Afficher ou sauvegarder le résultat
+    if let Some(output_path) = output_path {
+        // Déjà sauvegardé à la fin de l'extraction avec --checkpoint
+        if !saved {
+            save_batch_output(output_path, text, args.encoding, args.newline)?;
+        }
+        println!("✓ Succès - Résultat sauvegardé: {}", output_path.display());
+    } else {
+        // Afficher dans le terminal
+        let trimmed_text = text.trim();
+        if trimmed_text.is_empty() {
+            println!("⚠ Aucun texte extrait");
+        } else {
+            // Limiter l'affichage pour ne pas surcharger
+            let char_count = trimmed_text.chars().count();
+            let preview = if char_count > 300 {
+                format!(
+                    "{}... ({} caractères)",
+                    truncate_chars(trimmed_text, 300),
+                    char_count
+                )
+            } else {
+                trimmed_text.to_string()
+            };
+            println!("Texte extrait:");
+            println!("{}", preview);
+        }
+        println!("✓ Succès");
+    }
+
+    Ok(compared)
+}
+
+/// Sauvegarde le texte d'une image dans son fichier de sortie.
+///
+/// Le sous-répertoire de l'image est créé si nécessaire.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le répertoire ou le fichier ne peut pas être écrit.
+fn save_batch_output(
+    output_path: &Path,
+    text: &str,
+    encoding: OutputEncoding,
+    newline: NewlineStyle,
+) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "Impossible de créer le répertoire de sortie '{}'",
+                parent.display()
+            )
+        })?;
+    }
+    write_text_output(output_path, text, encoding, newline)
+}
+
+/// Écrit un texte extrait avec l'encodage et les fins de ligne de --encoding et --newline.
+///
+/// Les caractères remplacés par l'encodage Latin-1 sont signalés sur la
+/// sortie d'erreur.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le fichier ne peut pas être écrit.
+fn write_text_output(
+    path: &Path,
+    text: &str,
+    encoding: OutputEncoding,
+    newline: NewlineStyle,
+) -> Result<()> {
+    let encoded = encode_text_detailed(text, encoding, newline);
+    if encoded.substitutions > 0 {
+        eprintln!(
+            "⚠ {} : {} caractère(s) absent(s) de Latin-1 remplacé(s) par '?'",
+            path.display(),
+            encoded.substitutions
+        );
+    }
+    fs::write(path, encoded.bytes).with_context(|| {
+        format!(
+            "Impossible d'écrire le fichier de sortie '{}'",
+            path.display()
+        )
+    })
+}
+
+/// Ouvre le journal de reprise de --checkpoint ou --resume, s'il est demandé.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le journal de --resume n'existe pas, ou s'il ne
+/// peut pas être lu.
+fn open_checkpoint(args: &Args) -> Result<Option<Journal>> {
+    let path = match (&args.checkpoint, &args.resume) {
+        (Some(path), _) => path,
+        (None, Some(path)) if !path.exists() => {
+            anyhow::bail!("Journal de reprise introuvable : '{}'", path.display())
+        }
+        (None, Some(path)) => path,
+        (None, None) => return Ok(None),
+    };
+
+    let journal = Journal::open(path)?;
+    if journal.recovered_truncated_tail() {
+        println!(
+            "⚠ Journal '{}' : dernière ligne tronquée ignorée (interruption)",
+            path.display()
+        );
+    }
+    Ok(Some(journal))
+}
+
+/// Sauvegarde le résultat d'une image puis le journalise (--checkpoint).
+///
+/// La sortie est écrite avant la ligne du journal : une image journalisée
+/// avec succès a toujours son fichier de résultat. Les images annulées
+/// (jamais terminées) ne sont pas journalisées.
+///
+/// # Erreurs
+///
+/// Retourne l'erreur d'extraction, d'écriture de la sortie ou du journal.
+fn checkpoint_result(
+    journal: &Mutex<Journal>,
+    image_path: &Path,
+    output_path: Option<&Path>,
+    result: Result<OcrOutcome>,
+    encoding: OutputEncoding,
+    newline: NewlineStyle,
+) -> Result<OcrOutcome> {
+    if let Err(ref e) = result
+        && matches!(e.downcast_ref::<OcrError>(), Some(OcrError::Cancelled))
+    {
+        return result;
+    }
+
+    let result = result.and_then(|outcome| {
+        if let Some(output_path) = output_path {
+            save_batch_output(output_path, &outcome.text, encoding, newline)?;
+        }
+        Ok(outcome)
+    });
+    let hash = sha256_file(image_path).unwrap_or_default();
+    let entry = match result {
+        Ok(_) => JournalEntry::success(image_path, output_path.map(Path::to_path_buf), hash),
+        Err(ref e) => JournalEntry::failure(image_path, format!("{:#}", e), hash),
+    };
+    journal
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Journal de reprise inaccessible"))?
+        .append(entry)?;
+    result
+}
+
+/// Charge le fichier --config, s'il est fourni.
+///
+/// --preset a priorité sur le preset du fichier, la section ocr du fichier
+/// s'applique par-dessus.
+fn load_file_config(args: &Args) -> Result<Option<AppConfig>> {
+    let Some(ref config_path) = args.config else {
+        return Ok(None);
+    };
+    let app_config = load_config_with_preset(config_path, args.preset.as_deref())
+        .with_context(|| format!("Impossible de charger '{}'", config_path.display()))?;
+    Ok(Some(app_config))
+}
+
+/// Construit la configuration OCR : preset < fichier de configuration < options CLI.
+///
+/// Les options de la ligne de commande sont appliquées sur la base sous
+/// forme de surcharge (voir [`ocr_patch_from_args()`]).
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le nom de preset est inconnu.
+fn build_ocr_config(args: &Args, file_config: Option<&AppConfig>) -> Result<OcrConfig> {
+    let mut config = match file_config.and_then(|c| c.ocr.clone()) {
+        Some(ocr) => ocr,
+        None => match args.preset {
+            Some(ref name) => OcrConfig::try_from_preset_name(name)?,
+            None => OcrConfig::default(),
+        },
+    };
+    let patch = ocr_patch_from_args(args, &config);
+    config.apply_patch(&patch);
+    Ok(config)
+}
+
+/// Surcharge formée des options OCR données sur la ligne de commande.
+///
+/// --language, --psm et --dpi comptent dès qu'ils sont donnés, même avec
+/// leur valeur par défaut ; les options booléennes ne peuvent qu'activer.
+/// Les variables --var remplacent celles de même nom de `base` et les
+/// fichiers --tess-config s'appliquent après les siens.
+fn ocr_patch_from_args(args: &Args, base: &OcrConfig) -> OcrConfigPatch {
+    let tesseract_variables = (!args.vars.is_empty()).then(|| {
+        let mut variables = base.tesseract_variables.clone();
+        variables.extend(args.vars.iter().cloned());
+        variables
+    });
+    let config_names = (!args.tess_configs.is_empty()).then(|| {
+        base.config_names
+            .iter()
+            .chain(&args.tess_configs)
+            .cloned()
+            .collect()
+    });
+
+    OcrConfigPatch {
+        language: args.is_explicit("language").then(|| args.language.clone()),
+        page_seg_mode: args.is_explicit("psm").then_some(args.psm),
+        dpi: args.is_explicit("dpi").then_some(args.dpi),
+        tesseract_variables,
+        cleanup: args.cleanup.then(|| cleanup_from_args(args)),
+        engine_mode: engine_mode_from_args(args),
+        adaptive_dpi: args.adaptive_dpi.then_some(true),
+        user_words: args.user_words.clone().map(Some),
+        user_patterns: args.user_patterns.clone().map(Some),
+        deterministic: None,
+        auto_vertical: args.auto_vertical.then_some(true),
+        fallback_line_segmentation: args.line_fallback.then_some(true),
+        // Des critères lus dans le fichier de configuration sont conservés
+        line_merge: (args.merge_lines && base.line_merge.is_none())
+            .then(|| Some(LineMergeOptions::default())),
+        skip_image_regions: args.skip_images.then_some(true),
+        config_names,
+        tessdata_dir: args.tessdata_dir.clone().map(Some),
+        threads: args.threads.map(Some),
+        pyramid: args.pyramid.clone().map(Some),
+    }
+}
+
+/// Démarre le serveur HTTP d'OCR (--serve) et traite les requêtes jusqu'à l'arrêt du processus.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si la configuration est invalide ou si l'adresse ne
+/// peut pas être écoutée.
+#[cfg(feature = "server")]
+fn run_server(args: &ValidatedArgs, addr: &str) -> Result<u8> {
+    use text_recognition::server::{EngineBackend, OcrServer, OcrService};
+
+    let file_config = load_file_config(args)?;
+    let config = build_ocr_config(args, file_config.as_ref())?;
+    check_language_packs(&config)?;
+    let engine = match build_preprocessing_config(args, file_config.as_ref())? {
+        Some(prep) => OcrEngine::with_preprocessing(config, prep)?,
+        None => OcrEngine::new(config)?,
+    };
+
+    let backend = EngineBackend::new(engine, args.serve_workers as usize)?
+        .with_timeout(args.timeout.map(Duration::from_secs));
+    let start = Instant::now();
+    backend
+        .warm_up()
+        .context("Échec du préchauffage des moteurs OCR")?;
+    eprintln!(
+        "{} moteur(s) OCR préchauffé(s) en {:.2?}",
+        backend.max_concurrency(),
+        start.elapsed()
+    );
+    let service = OcrService::new(backend).with_max_body_bytes(args.max_body_size);
+    let server = OcrServer::bind(addr, service)?;
+    match server.local_addr() {
+        Some(local) => eprintln!("Serveur OCR en écoute sur http://{}", local),
+        None => eprintln!("Serveur OCR en écoute sur {}", addr),
+    }
+    server.run();
+    Ok(EXIT_OK)
+}
+
+/// Sans la feature `server`, --serve échoue avec une indication de compilation.
+#[cfg(not(feature = "server"))]
+fn run_server(_args: &ValidatedArgs, _addr: &str) -> Result<u8> {
+    anyhow::bail!(
+        "--serve nécessite la feature `server` (cargo run --features server -- --serve ADDR:PORT)"
+    )
+}
+
+/// Vérifie que les langues demandées sont installées avant de lancer l'OCR.
+///
+/// Sans binaire `tesseract`, les langues installées sont inconnues : la
+/// vérification est alors laissée à l'initialisation de Tesseract. Avec un
+/// répertoire `tessdata_dir`, ses modèles sont vérifiés à la création du
+/// moteur (`OcrConfig::validate_tessdata_dir`).
+fn check_language_packs(config: &OcrConfig) -> Result<()> {
+    if config.tessdata_dir.is_some() {
+        return Ok(());
+    }
+    match installed_languages() {
+        Ok(installed) => check_languages(&config.language, &installed),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Formate la configuration effective pour --show-config.
+fn format_effective_config(effective: &EffectiveConfig) -> String {
+    let mut lines = vec![
+        "Configuration effective :".to_string(),
+        format!("  Langue : {}", effective.language),
+        format!("  PSM    : {} ({:?})", effective.psm_value, effective.psm),
+        format!("  DPI    : {}", effective.dpi),
+        format!(
+            "  OEM    : {} ({:?})",
+            effective.oem_value, effective.engine_mode
+        ),
+        "  Variables Tesseract :".to_string(),
+    ];
+    lines.extend(
+        effective
+            .tesseract_variables
+            .iter()
+            .map(|(key, value)| format!("    {} = {}", key, value)),
+    );
+    for (label, path) in [
+        ("Mots utilisateur   ", &effective.user_words),
+        ("Motifs utilisateur ", &effective.user_patterns),
+        ("Répertoire tessdata", &effective.tessdata_dir),
+    ] {
+        if let Some(path) = path {
+            lines.push(format!("  {}: {}", label, path.display()));
+        }
+    }
+    if let Some(threads) = effective.thread_limit {
+        lines.push(format!("  Threads tesseract  : {}", threads));
+    }
+    if let Some(pyramid) = &effective.pyramid {
+        let levels: Vec<String> = pyramid.levels.iter().map(f32::to_string).collect();
+        lines.push(format!(
+            "  Pyramide           : {} (CER max {}, confiance min {})",
+            levels.join(","),
+            pyramid.max_cer,
+            pyramid.min_confidence
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Construit la configuration de prétraitement à partir du fichier et des arguments CLI.
+///
+/// - Avec `--preprocess` : la section `preprocessing` du fichier sert de base
+///   et les options CLI activées la surchargent.
+/// - Avec `--preprocess-profile` : les étapes sont celles du profil ; les
+///   autres réglages du fichier (`max_pixels`, pages blanches...) sont conservés.
+/// - Sans `--preprocess` : la section `preprocessing` du fichier est utilisée telle quelle.
+/// - Sinon : aucun prétraitement.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si la méthode de binarisation est invalide.
+fn build_preprocessing_config(
+    args: &ValidatedArgs,
+    file_config: Option<&AppConfig>,
+) -> Result<Option<PreprocessingConfig>> {
+    let file_prep = file_config.and_then(|c| c.preprocessing.clone());
+
+    if !args.preprocessing_enabled() {
+        return Ok(file_prep);
+    }
+
+    // Un profil remplace les étapes du fichier mais garde ses autres réglages
+    let base_prep = match args.preprocess_profile {
+        Some(profile) => {
+            let file_prep = file_prep.unwrap_or_default();
+            PreprocessingConfig {
+                max_pixels: file_prep.max_pixels,
+                skip_blank_pages: file_prep.skip_blank_pages,
+                blank_detection: file_prep.blank_detection,
+                split_double_pages: file_prep.split_double_pages,
+                grayscale_options: file_prep.grayscale_options,
+                deskew_options: file_prep.deskew_options,
+                ..PreprocessingConfig::from_profile(profile)
+            }
+        }
+        None => file_prep.unwrap_or_default(),
+    };
+
+    let mut config = base_prep;
+    let patch = preprocessing_patch_from_args(args, &config);
+    config.apply_patch(&patch);
+    Ok(Some(config))
+}
+
+/// Surcharge formée des options de prétraitement données sur la ligne de commande.
+///
+/// Les options booléennes ne peuvent qu'activer une étape ; la méthode de
+/// binarisation n'est surchargée que si --binarize-method est donné.
+fn preprocessing_patch_from_args(
+    args: &ValidatedArgs,
+    base: &PreprocessingConfig,
+) -> PreprocessingConfigPatch {
+    let grayscale_set =
+        args.gray_weights.is_some() || args.alpha_bg.is_some() || args.drop_channel.is_some();
+
+    PreprocessingConfigPatch {
+        to_grayscale: args.grayscale.then_some(true),
+        binarize: args.binarize.then_some(true),
+        binarization_method: args
+            .is_explicit("binarize_method")
+            .then_some(args.binarization_method),
+        adjust_contrast: args.contrast.map(|_| true),
+        contrast_factor: args.contrast,
+        denoise: args.denoise.then_some(true),
+        deskew: args.deskew.then_some(true),
+        max_pixels: args.max_pixels,
+        skip_blank_pages: args.skip_blank_pages.then_some(true),
+        blank_detection: None,
+        split_double_pages: args.split_double_pages.then_some(true),
+        despeckle: args.despeckle.map(Some),
+        grayscale_options: grayscale_set
+            .then(|| grayscale_options_from_args(args, base.grayscale_options)),
+        deskew_options: None,
+    }
+}
+
+/// Options de conversion en niveaux de gris : celles de la ligne de commande
+/// (--gray-weights, --alpha-bg, --drop-channel), à défaut celles de `base`.
+fn grayscale_options_from_args(args: &Args, base: GrayscaleOptions) -> GrayscaleOptions {
+    GrayscaleOptions {
+        weights: args.gray_weights.unwrap_or(base.weights),
+        alpha_background: args
+            .alpha_bg
+            .map(AlphaBackground::level)
+            .or(base.alpha_background),
+        drop_channel: args.drop_channel.or(base.drop_channel),
+    }
+}
+
+/// Affiche les avertissements de Tesseract sur la sortie d'erreur.
+///
+/// Les messages bruités (bannière, estimation de résolution) sont ignorés.
+fn print_warnings(warnings: &[Warning]) {
+    for warning in warnings.iter().filter(|w| !w.kind().is_noise()) {
+        eprintln!("⚠ {}", warning);
+    }
+}
+
+/// Affiche le rapport de prétraitement d'une extraction sur la sortie d'erreur (mode --verbose).
+fn print_preprocessing_report(outcome: &OcrOutcome) {
+    match outcome.preprocessing {
+        Some(ref report) => eprintln!("{}", report),
+        None if outcome.cache_hit => eprintln!("Prétraitement : résultat lu dans le cache"),
+        None => eprintln!("Prétraitement : aucun"),
+    }
+}
+
+/// Image d'une comparaison A/B, telle qu'émise par --compare --format json.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ComparedImageOutput {
+    /// Chemin de l'image.
+    path: PathBuf,
+    /// Texte extrait (absent si l'extraction a échoué).
+    text: Option<String>,
+    /// Message d'erreur si l'extraction a échoué.
+    error: Option<String>,
+    /// Durée de l'extraction en millisecondes.
+    duration_ms: f64,
+}
+
+/// Sortie JSON de --format json : texte structuré et configuration effective.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct JsonOutput {
+    /// Paragraphes, lignes et mots extraits.
+    #[serde(flatten)]
+    structured: StructuredText,
+    /// Configuration transmise à Tesseract.
+    config: EffectiveConfig,
+}
+
+/// Sortie JSON du mode --compare.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ComparisonOutput {
+    /// Image de référence (IMAGE).
+    first: ComparedImageOutput,
+    /// Image comparée (--compare).
+    second: ComparedImageOutput,
+    /// CER du second texte par rapport au premier.
+    cer: Option<f64>,
+    /// WER du second texte par rapport au premier.
+    wer: Option<f64>,
+    /// Diff mot à mot du second texte par rapport au premier.
+    word_diff: Vec<WordDiff>,
+}
+
+impl From<&ComparedImage> for ComparedImageOutput {
+    fn from(image: &ComparedImage) -> Self {
+        Self {
+            path: image.path.clone(),
+            text: image.text.clone(),
+            error: image.error.clone(),
+            duration_ms: image.duration.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+impl From<&ImageComparison> for ComparisonOutput {
+    fn from(comparison: &ImageComparison) -> Self {
+        Self {
+            first: ComparedImageOutput::from(&comparison.first),
+            second: ComparedImageOutput::from(&comparison.second),
+            cer: comparison.metrics.as_ref().map(|m| m.cer),
+            wer: comparison.metrics.as_ref().map(|m| m.wer),
+            word_diff: comparison.word_diff.clone(),
+        }
+    }
+}
+
+/// Affiche une comparaison A/B : textes, diff mot à mot, métriques et durées.
+fn print_comparison(comparison: &ImageComparison) {
+    for (label, image) in [("A", &comparison.first), ("B", &comparison.second)] {
+        println!(
+            "=== {} : {} ({:.2} s) ===",
+            label,
+            image.path.display(),
+            image.duration.as_secs_f64()
+        );
+        match (&image.text, &image.error) {
+            (Some(text), _) => println!("{}", text.trim_end()),
+            (None, Some(error)) => println!("✗ Échec de l'extraction : {}", error),
+            (None, None) => {}
+        }
+        println!();
+    }
+
+    let Some(ref metrics) = comparison.metrics else {
+        println!("Comparaison impossible : une des extractions a échoué.");
+        return;
+    };
+
+    println!("=== Diff mot à mot (A → B) ===");
+    for op in &comparison.word_diff {
+        match op {
+            WordDiff::Equal(word) => println!("  {}", word),
+            WordDiff::Deleted(word) => println!("- {}", word),
+            WordDiff::Inserted(word) => println!("+ {}", word),
+            WordDiff::Replaced { expected, found } => println!("~ {} → {}", expected, found),
+        }
+    }
+    println!();
+    println!("CER (B par rapport à A) : {:.2}%", metrics.cer * 100.0);
+    println!("WER (B par rapport à A) : {:.2}%", metrics.wer * 100.0);
+}
+
+/// Affiche le rapport d'un audit de déterminisme (--determinism-check).
+/// Part de la longueur de la phrase tolérée en erreurs par --find sans --find-distance.
+const DEFAULT_FIND_ERROR_RATIO: usize = 5;
+
+/// Nombre maximal d'erreurs par défaut de --find : 20 % de la longueur de la phrase.
+fn default_find_distance(phrase: &str) -> usize {
+    phrase.chars().count() / DEFAULT_FIND_ERROR_RATIO
+}
+
+/// Nombre de caractères de contexte affichés de part et d'autre d'une occurrence.
+const FIND_CONTEXT_CHARS: usize = 30;
+
+/// Contexte d'une occurrence : l'occurrence entre crochets, entourée d'au
+/// plus [`FIND_CONTEXT_CHARS`] caractères, sauts de ligne remplacés par des espaces.
+fn match_context(text: &str, found: &FuzzyMatch) -> String {
+    let chars: Vec<char> = text
+        .chars()
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .collect();
+    let before_start = found.start.saturating_sub(FIND_CONTEXT_CHARS);
+    let after_end = (found.end + FIND_CONTEXT_CHARS).min(chars.len());
+    let slice = |range: std::ops::Range<usize>| chars[range].iter().collect::<String>();
+    format!(
+        "{}{}[{}]{}{}",
+        if before_start > 0 { "…" } else { "" },
+        slice(before_start..found.start),
+        slice(found.start..found.end),
+        slice(found.end..after_end),
+        if after_end < chars.len() { "…" } else { "" }
+    )
+}
+
+/// Affiche les occurrences de --find (position, distance, contexte, mots).
+fn print_phrase_matches(matches: &[PhraseMatch], text: &str, phrase: &str, max_distance: usize) {
+    println!(
+        "=== Recherche : « {} » (au plus {} erreur(s)) ===",
+        phrase, max_distance
+    );
+    if matches.is_empty() {
+        println!("✗ Aucune occurrence");
+        return;
+    }
+    println!("✓ {} occurrence(s)", matches.len());
+    for (index, phrase_match) in matches.iter().enumerate() {
+        let found = &phrase_match.found;
+        println!(
+            "  [{}] caractères {}–{}, distance {} : « {} »",
+            index + 1,
+            found.start,
+            found.end,
+            found.distance,
+            found.text
+        );
+        println!("      {}", match_context(text, found));
+        let words: Vec<String> = phrase_match
+            .words
+            .iter()
+            .map(|bbox| format!("({}, {}) {}×{}", bbox.x, bbox.y, bbox.width, bbox.height))
+            .collect();
+        println!("      Mots : {}", words.join(", "));
+    }
+}
+
+/// Affiche les zones de --review-regions ; l'indice est celui des fichiers de --review-out.
+fn print_review_regions(regions: &[(BBox, f32)], threshold: f32) {
+    println!("=== Zones à relire (confiance < {}) ===", threshold);
+    if regions.is_empty() {
+        println!("✓ Aucune zone peu fiable");
+        return;
+    }
+    println!("✗ {} zone(s)", regions.len());
+    for (index, (bbox, confidence)) in regions.iter().enumerate() {
+        println!(
+            "  [{}] ({}, {}) {}×{}, confiance moyenne {:.1}",
+            index, bbox.x, bbox.y, bbox.width, bbox.height, confidence
+        );
+    }
+}
+
+fn print_determinism_report(report: &DeterminismReport, image: &Path) {
+    println!(
+        "=== Audit de déterminisme : {} ({} exécutions) ===",
+        image.display(),
+        report.runs
+    );
+    // La bibliothèque Tesseract n'est limitée à un thread que par l'environnement du processus
+    println!(
+        "OMP_THREAD_LIMIT : {}",
+        std::env::var("OMP_THREAD_LIMIT").unwrap_or_else(|_| "non défini".to_string())
+    );
+    if report.deterministic {
+        let hash = report
+            .hashes
+            .first()
+            .map(String::as_str)
+            .unwrap_or_default();
+        println!("✓ Sorties identiques (sha256 {})", hash);
+        return;
+    }
+
+    println!("✗ {} sorties différentes", report.variants.len());
+    for (index, variant) in report.variants.iter().enumerate() {
+        let runs: Vec<String> = variant.runs.iter().map(usize::to_string).collect();
+        println!(
+            "  Variante {} (exécutions {}) : sha256 {}",
+            index + 1,
+            runs.join(", "),
+            variant.hash
+        );
+    }
+    for difference in &report.differences {
+        let lines: Vec<String> = difference
+            .differing_lines
+            .iter()
+            .map(usize::to_string)
+            .collect();
+        println!(
+            "  Variantes {} et {} : CER {:.2}%, lignes différentes : {}",
+            difference.first + 1,
+            difference.second + 1,
+            difference.cer * 100.0,
+            lines.join(", ")
+        );
+    }
+}
+
+/// Affiche les métriques de détection et la liste des mots annotés non détectés.
+fn print_detection_metrics(
+    metrics: &DetectionMetrics,
+    annotations: &[WordAnnotation],
+    iou_threshold: f64,
+) {
+    println!("=== Détection des mots (IoU ≥ {:.2}) ===", iou_threshold);
+    println!("Mots annotés :          {}", annotations.len());
+    println!(
+        "Mots reconnus :         {}",
+        metrics.true_positives + metrics.false_positives
+    );
+    println!("Appariés (TP) :         {}", metrics.true_positives);
+    println!("En trop (FP) :          {}", metrics.false_positives);
+    println!("Manqués (FN) :          {}", metrics.false_negatives);
+    println!();
+    println!("Précision :             {:.2}%", metrics.precision * 100.0);
+    println!("Rappel :                {:.2}%", metrics.recall * 100.0);
+    println!("F1 :                    {:.2}%", metrics.f1 * 100.0);
+    println!(
+        "CER (paires appariées): {:.2}%",
+        metrics.matched_cer * 100.0
+    );
+
+    let missed: Vec<&str> = annotations
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !metrics.matches.iter().any(|m| m.annotation == *index))
+        .map(|(_, annotation)| annotation.text.as_str())
+        .collect();
+    if !missed.is_empty() {
+        println!();
+        println!("Mots non détectés : {}", missed.join(", "));
+    }
+}
+
+/// Retourne le nom de fichier sans extension d'une image.
+fn file_stem(path: &Path) -> Result<String> {
+    if is_stdin_path(path) {
+        return Ok("stdin".to_string());
+    }
+
+    Ok(path
+        .file_stem()
+        .context("Impossible d'extraire le nom du fichier")?
+        .to_string_lossy()
+        .to_string())
+}
+
+/// Prétraite une image et sauvegarde le résultat de chaque étape.
+///
+/// Les fichiers sont nommés `<image>_<n>_<étape>.png` dans `dump_dir`.
+///
+/// # Arguments
+///
+/// * `image_path` - Image source
+/// * `config` - Configuration du prétraitement
+/// * `dump_dir` - Répertoire de sortie des images intermédiaires
+///
+/// # Erreurs
+///
+/// Retourne une erreur si l'image ne peut pas être chargée ou si une étape
+/// ne peut pas être sauvegardée.
+fn dump_intermediate_stages(
+    image_path: &Path,
+    config: &PreprocessingConfig,
+    dump_dir: &Path,
+) -> Result<DynamicImage> {
+    let img = load_input_image(image_path)?;
+    let (result, files) =
+        preprocess_image_with_dump(&img, config, dump_dir, &file_stem(image_path)?)?;
+
+    for file in &files {
+        println!("✓ Étape sauvegardée: {}", file.display());
+    }
+
+    Ok(result)
+}
+
+/// Exécute uniquement le pipeline de prétraitement et écrit l'image résultante.
+///
+/// - Image unique : le résultat est écrit dans `output`.
+/// - Mode batch : un fichier `<nom_image>.png` par image dans le répertoire `--output`.
+///
+/// Si `--dump-intermediate` est fourni, les étapes intermédiaires sont aussi sauvegardées.
+///
+/// # Arguments
+///
+/// * `args` - Arguments de la ligne de commande
+/// * `output` - Fichier de sortie (image unique)
+/// * `config` - Configuration du prétraitement
+///
+/// # Erreurs
+///
+/// Retourne une erreur si la destination est manquante, si une image ne peut
+/// pas être chargée ou si l'écriture échoue.
+fn run_preprocess_only(
+    args: &Args,
+    output: Option<&Path>,
+    config: &PreprocessingConfig,
+) -> Result<()> {
+    let preprocess_file = |image_path: &Path| -> Result<DynamicImage> {
+        match args.dump_intermediate {
+            Some(ref dump_dir) => dump_intermediate_stages(image_path, config, dump_dir),
+            None => preprocess_image(&load_input_image(image_path)?, config),
+        }
+    };
+
+    if args.batch {
+        let output_dir = args
+            .output
+            .as_ref()
+            .context("En mode batch, --preprocess-only nécessite --output <REPERTOIRE>")?;
+        fs::create_dir_all(output_dir).with_context(|| {
+            format!(
+                "Impossible de créer le répertoire de sortie '{}'",
+                output_dir.display()
+            )
+        })?;
+
+        let images = collect_image_files(args.image()?, &collect_options_from_args(args))?;
+        for mapping in batch_output_paths(args, &images, output_dir, "png") {
+            let (image_path, output_path) = (mapping.input, mapping.output);
+            if let Some(parent) = output_path.parent() {
+                fs::create_dir_all(parent).with_context(|| {
+                    format!("Impossible de créer le répertoire '{}'", parent.display())
+                })?;
+            }
+            preprocess_file(&image_path)?
+                .save(&output_path)
+                .with_context(|| format!("Impossible d'écrire '{}'", output_path.display()))?;
+            println!("✓ {} → {}", image_path.display(), output_path.display());
+        }
+    } else {
+        let output_path =
+            output.context("--preprocess-only nécessite un fichier de sortie (ex: out.png)")?;
+        preprocess_file(args.image()?)?
+            .save(output_path)
+            .with_context(|| format!("Impossible d'écrire '{}'", output_path.display()))?;
+        println!("✓ Image prétraitée sauvegardée: {}", output_path.display());
+    }
+
+    Ok(())
+}
+
+/// Exécute le mode benchmark et affiche ou sauvegarde le rapport.
+///
+/// Charge le manifeste et chaque fichier `--bench-config`, exécute toutes
+/// les configurations sur toutes les images, puis produit le tableau
+/// comparatif en Markdown (terminal ou `bench.md`) et en CSV (`bench.csv`).
+///
+/// # Arguments
+///
+/// * `args` - Arguments de la ligne de commande
+/// * `manifest_path` - Chemin vers le manifeste du corpus
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le manifeste, une configuration ou un texte de
+/// référence ne peut pas être chargé, ou si le rapport ne peut pas être écrit.
+fn run_bench(args: &Args, manifest_path: &Path) -> Result<()> {
+    let manifest = load_manifest(manifest_path)
+        .with_context(|| format!("Impossible de charger '{}'", manifest_path.display()))?;
+
+    let configs = if args.bench_config.is_empty() {
+        vec![NamedConfig::new("default", AppConfig::default())]
+    } else {
+        args.bench_config
+            .iter()
+            .map(|path| {
+                let config = load_config(path)
+                    .with_context(|| format!("Impossible de charger '{}'", path.display()))?;
+                let name = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.display().to_string());
+                Ok(NamedConfig::new(name, config))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    println!(
+        "Benchmark: {} image(s) × {} configuration(s)",
+        manifest.images.len(),
+        configs.len()
+    );
+    println!();
+
+    let report = run_benchmark(&manifest, &configs, &mut TesseractRunner)?;
+
+    if let Some(ref output_dir) = args.bench_output {
+        fs::create_dir_all(output_dir).with_context(|| {
+            format!(
+                "Impossible de créer le répertoire de sortie '{}'",
+                output_dir.display()
+            )
+        })?;
+        let markdown_path = output_dir.join("bench.md");
+        let csv_path = output_dir.join("bench.csv");
+        fs::write(&markdown_path, report.to_markdown())
+            .with_context(|| format!("Impossible d'écrire '{}'", markdown_path.display()))?;
+        fs::write(&csv_path, report.to_csv_with(&csv_options(args)?))
+            .with_context(|| format!("Impossible d'écrire '{}'", csv_path.display()))?;
+        println!("✓ Rapport Markdown: {}", markdown_path.display());
+        println!("✓ Rapport CSV:      {}", csv_path.display());
+    } else {
+        println!("{}", report.to_markdown());
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    // Processus enfant d'une extraction isolée (--isolate) : pas d'analyse des arguments
+    if std::env::args_os()
+        .nth(1)
+        .is_some_and(|arg| arg == WORKER_ARG)
+    {
+        return worker_main();
+    }
+
+    // Parser les arguments de la ligne de commande (--help et --version sortent avec 0)
+    let args = match Args::try_parse_with_sources(std::env::args_os()) {
+        Ok(args) => args,
+        Err(e) => {
+            let _ = e.print();
+            return if e.use_stderr() {
+                ExitCode::from(EXIT_USAGE)
+            } else {
+                ExitCode::SUCCESS
+            };
+        }
+    };
+
+    let args = match validate_args(&args) {
+        Ok(args) => args,
+        Err(errors) => {
+            print_arg_errors(&errors);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    match run(&args) {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::from(EXIT_USAGE)
+        }
+    }
+}
+
+/// Exécute la commande demandée et retourne le code de sortie.
+///
+/// Les erreurs retournées (configuration invalide, fichier introuvable...)
+/// sont converties en code [`EXIT_USAGE`] par `main`.
+fn run(args: &ValidatedArgs) -> Result<u8> {
+    // Lister les presets disponibles
+    if args.list_presets {
+        for name in OcrConfig::preset_names() {
+            let preset = OcrConfig::try_from_preset_name(name)?;
+            println!(
+                "{:<14} PSM {:<2}  {} DPI",
+                name,
+                preset.page_seg_mode.to_tesseract_psm(),
+                preset.dpi
+            );
+        }
+        return Ok(EXIT_OK);
+    }
+
+    // Lister les langues installées
+    if args.list_languages {
+        for language in installed_languages()? {
+            println!("{}", language);
+        }
+        return Ok(EXIT_OK);
+    }
+
+    // Mode benchmark : comparer des configurations sur un corpus
+    if let Some(ref manifest_path) = args.bench {
+        run_bench(args, manifest_path)?;
+        return Ok(EXIT_OK);
+    }
+
+    // Mode spécial: tester tous les PSM
+    if args.test_all_psm {
+        test_all_psm_modes(args)?;
+        return Ok(EXIT_OK);
+    }
+
+    // Recommandation du mode PSM seule : mesurer la mise en page sans OCR
+    if args.recommend_psm && !args.apply {
+        let recommendation = recommend_psm_for(args)?;
+        if args.format == OutputFormat::Json {
+            let json = serde_json::to_string_pretty(&recommendation)
+                .context("Échec de la sérialisation JSON")?;
+            println!("{}", json);
+        } else {
+            println!("{}", recommendation);
+        }
+        return Ok(EXIT_OK);
+    }
+
+    // Configuration seule : afficher la configuration effective sans lancer l'OCR
+    if args.dry_run && !args.batch {
+        let file_config = load_file_config(args)?;
+        let effective =
+            EffectiveConfig::from_config(&build_ocr_config(args, file_config.as_ref())?);
+        for warning in &effective.warnings {
+            eprintln!("⚠ {}", warning);
+        }
+        if args.format == OutputFormat::Json {
+            let json = serde_json::to_string_pretty(&effective)
+                .context("Échec de la sérialisation JSON")?;
+            println!("{}", json);
+        } else {
+            println!("{}", format_effective_config(&effective));
+        }
+        return Ok(EXIT_OK);
+    }
+
+    // Mode serveur : OCR à la demande sur HTTP
+    if let Some(ref addr) = args.serve {
+        return run_server(args, addr);
+    }
+
+    let image_path = args.image()?.to_path_buf();
+
+    // Mode batch : traiter plusieurs images
+    if args.batch {
+        // Validation: en mode batch, --expected désigne un répertoire de références
+        if let Some(ref expected_dir) = args.expected {
+            if !expected_dir.is_dir() {
+                anyhow::bail!(
+                    "En mode batch, --expected doit être un répertoire contenant \
+                     un fichier <nom_image>.txt par image ('{}' n'est pas un répertoire)",
+                    expected_dir.display()
+                );
+            }
+            if args.metrics {
+                anyhow::bail!(
+                    "L'option --metrics n'est pas supportée en mode batch. \
+                     Traitez les images individuellement pour un rapport détaillé."
+                );
+            }
+        }
+    }
+
+    let file_config = load_file_config(args)?;
+    let mut config = build_ocr_config(args, file_config.as_ref())?;
+    if args.apply {
+        let recommendation = recommend_psm_for(args)?;
+        eprintln!("{}", recommendation);
+        config.page_seg_mode = recommendation.top();
+    }
+
+    // Variables inconnues signalées dans tous les modes, configuration affichée sur demande
+    let effective = EffectiveConfig::from_config(&config);
+    for warning in &effective.warnings {
+        eprintln!("⚠ {}", warning);
+    }
+    if args.show_config {
+        eprintln!("{}", format_effective_config(&effective));
+    }
+
+    // Configuration de prétraitement (fichier en base, CLI en surcharge)
+    let preprocess_config = build_preprocessing_config(args, file_config.as_ref())?;
+
+    // Mode prétraitement seul : écrire l'image prétraitée sans lancer Tesseract
+    if let Some(ref output) = args.preprocess_only {
+        let preprocess_config = preprocess_config.context(
+            "Aucun prétraitement configuré : utilisez --preprocess ou une section \
+             [preprocessing] dans le fichier de configuration",
+        )?;
+        run_preprocess_only(args, output.as_deref(), &preprocess_config)?;
+        return Ok(EXIT_OK);
+    }
+
+    // Sauvegarder les étapes intermédiaires du prétraitement si demandé
+    if let Some(ref dump_dir) = args.dump_intermediate {
+        if args.batch {
+            anyhow::bail!("--dump-intermediate en mode batch nécessite --preprocess-only");
+        }
+        let preprocess_config = preprocess_config
+            .as_ref()
+            .context("--dump-intermediate nécessite un prétraitement (--preprocess)")?;
+        dump_intermediate_stages(&image_path, preprocess_config, dump_dir)?;
+    }
+
+    // Le mode HOCR appelle directement le binaire et a besoin du moteur configuré
+    let engine_mode = config.engine_mode;
+
+    // Langue absente : échouer avant la première image plutôt qu'au milieu d'un lot
+    check_language_packs(&config)?;
+
+    // Créer le moteur OCR avec ou sans prétraitement
+    // (en mode batch, le moteur est créé une seule fois et réutilisé)
+    let engine = match preprocess_config {
+        Some(prep) => OcrEngine::with_preprocessing(config, prep)?,
+        None => OcrEngine::new(config)?,
+    };
+    let timeout = args.timeout.map(Duration::from_secs);
+    let engine = engine.with_timeout(timeout);
+    let engine = match args.debug_artifacts {
+        Some(ref dir) => engine.with_debug_artifacts(dir),
+        None => engine,
+    };
+    let engine = match args.result_cache {
+        Some(ref dir) => engine.with_result_cache(Arc::new(DirectoryCache::new(dir))),
+        None => engine,
+    };
+
+    // En mode batch, traiter toutes les images (ou estimer le traitement) et terminer
+    if args.batch {
+        if args.dry_run {
+            return estimate_batch(args, &engine);
+        }
+        return process_batch(args, &engine, file_config.as_ref());
+    }
+
+    // Mode normal: traiter une seule image
+
+    // Mode HOCR : générer et afficher les bounding boxes
+    if args.hocr {
+        // Générer le HOCR via Tesseract
+        let outcome = generate_hocr_with_timeout(
+            &image_path,
+            &args.language,
+            args.psm.to_tesseract_psm() as u8,
+            engine_mode,
+            timeout,
+        )?;
+        print_warnings(&outcome.warnings);
+        let hocr_content = outcome.text;
+
+        // Parser le document HOCR
+        let doc = HocrDocument::from_hocr_string(&hocr_content)?;
+
+        // Générer le rapport
+        let report = doc.generate_report();
+
+        // Sauvegarder ou afficher le rapport
+        if let Some(ref output_path) = args.hocr_output {
+            fs::write(output_path, &report).with_context(|| {
+                format!(
+                    "Impossible d'écrire le fichier de sortie '{}'",
+                    output_path.display()
+                )
+            })?;
+            println!("✓ Rapport HOCR sauvegardé dans: {}", output_path.display());
+        } else {
+            println!("{}", report);
+        }
+
+        return Ok(EXIT_OK);
+    }
+
+    // Mode masquage : repérer les données sensibles et écrire l'image masquée
+    if let (Some(fields), Some(output_path)) = (&args.redact, &args.redacted_out) {
+        let matcher = FieldMatcher::from_names(fields)?;
+        let outcome = generate_hocr_with_timeout(
+            &image_path,
+            &args.language,
+            args.psm.to_tesseract_psm() as u8,
+            engine_mode,
+            timeout,
+        )?;
+        print_warnings(&outcome.warnings);
+        let doc = HocrDocument::from_hocr_string(&outcome.text)?;
+
+        let matches = matcher.find_matches(&doc);
+        let redacted = redact_matching(
+            &load_input_image(&image_path)?,
+            &doc,
+            &matcher,
+            RedactionStyle::SolidBlack,
+            args.redact_padding,
+        );
+        redacted.save(output_path).with_context(|| {
+            format!(
+                "Impossible d'écrire l'image masquée '{}'",
+                output_path.display()
+            )
+        })?;
+
+        // Ne jamais afficher le texte masqué : seulement le décompte par champ
+        let counts: Vec<String> = matcher
+            .field_names()
+            .iter()
+            .map(|name| {
+                let count = matches.iter().filter(|m| m.field == *name).count();
+                format!("{}: {}", name, count)
+            })
+            .collect();
+        println!(
+            "✓ {} occurrence(s) masquée(s) ({}) → {}",
+            matches.len(),
+            counts.join(", "),
+            output_path.display()
+        );
+
+        return Ok(EXIT_OK);
+    }
+
+    // Mode A/B : comparer les textes extraits de deux images
+    if let Some(ref other_path) = args.compare {
+        if matches!(args.format, OutputFormat::Markdown | OutputFormat::Html) {
+            anyhow::bail!("--compare ne supporte que --format text ou json");
+        }
+        let comparison = compare_images(&engine, &image_path, other_path)?;
+        if args.format == OutputFormat::Json {
+            let json = serde_json::to_string_pretty(&ComparisonOutput::from(&comparison))
+                .context("Échec de la sérialisation JSON")?;
+            println!("{}", json);
+        } else {
+            print_comparison(&comparison);
+        }
+        return Ok(EXIT_OK);
+    }
+
+    // Audit de déterminisme : même image, même configuration, N exécutions
+    if let Some(runs) = args.determinism_check {
+        if matches!(args.format, OutputFormat::Markdown | OutputFormat::Html) {
+            anyhow::bail!("--determinism-check ne supporte que --format text ou json");
+        }
+        let report = determinism_check(&engine, &image_path, runs as usize)?;
+        if args.format == OutputFormat::Json {
+            let json =
+                serde_json::to_string_pretty(&report).context("Échec de la sérialisation JSON")?;
+            println!("{}", json);
+        } else {
+            print_determinism_report(&report, &image_path);
+        }
+        return Ok(if report.deterministic {
+            EXIT_OK
+        } else {
+            EXIT_PARTIAL_FAILURE
+        });
+    }
+
+    // Vérification croisée : même image par la bibliothèque et par le binaire
+    if args.cross_check {
+        if matches!(args.format, OutputFormat::Markdown | OutputFormat::Html) {
+            anyhow::bail!("--cross-check ne supporte que --format text ou json");
+        }
+        let report = cross_check(&engine, &image_path)?;
+        if args.format == OutputFormat::Json {
+            let json =
+                serde_json::to_string_pretty(&report).context("Échec de la sérialisation JSON")?;
+            println!("{}", json);
+        } else {
+            println!("{}", report);
+        }
+        return Ok(if report.mismatch {
+            EXIT_PARTIAL_FAILURE
+        } else {
+            EXIT_OK
+        });
+    }
+
+    // Étude d'ablation : effet de chaque étape du prétraitement sur le CER
+    if args.ablation
+        && let (Some(expected_path), Some(preprocessing)) =
+            (&args.expected, engine.preprocessing_config())
+    {
+        if args.format == OutputFormat::Html {
+            anyhow::bail!("--ablation ne supporte que --format text, markdown ou json");
+        }
+        let reference = fs::read_to_string(expected_path).with_context(|| {
+            format!(
+                "Impossible de lire le fichier de référence '{}'",
+                expected_path.display()
+            )
+        })?;
+        let options = AblationOptions {
+            include_additions: args.ablation_additions,
+        };
+        let report = ablation_study_with(
+            &mut TesseractRunner,
+            &image_path,
+            &reference,
+            preprocessing,
+            engine.config(),
+            &options,
+        )?;
+        if args.format == OutputFormat::Json {
+            let json =
+                serde_json::to_string_pretty(&report).context("Échec de la sérialisation JSON")?;
+            println!("{}", json);
+        } else {
+            print!("{}", report.render_markdown());
+        }
+        return Ok(EXIT_OK);
+    }
+
+    // Recherche approchée d'une phrase dans le texte reconnu
+    if let Some(ref phrase) = args.find {
+        if matches!(args.format, OutputFormat::Markdown | OutputFormat::Html) {
+            anyhow::bail!("--find ne supporte que --format text ou json");
+        }
+        let max_distance = args
+            .find_distance
+            .unwrap_or_else(|| default_find_distance(phrase));
+        let doc = engine.extract_hocr_document(&image_path)?;
+        let matches = doc.find_phrase_fuzzy(phrase, max_distance);
+        if args.format == OutputFormat::Json {
+            let json =
+                serde_json::to_string_pretty(&matches).context("Échec de la sérialisation JSON")?;
+            println!("{}", json);
+        } else {
+            print_phrase_matches(&matches, &doc.full_text(), phrase, max_distance);
+        }
+        return Ok(EXIT_OK);
+    }
+
+    // Évaluation de la détection : mots reconnus contre annotations positionnées
+    if let Some(ref annotations_path) = args.annotations {
+        if matches!(args.format, OutputFormat::Markdown | OutputFormat::Html) {
+            anyhow::bail!("--annotations ne supporte que --format text ou json");
+        }
+        if !(args.iou_threshold > 0.0 && args.iou_threshold <= 1.0) {
+            anyhow::bail!(
+                "--iou-threshold doit être compris entre 0 (exclu) et 1, reçu {}",
+                args.iou_threshold
+            );
+        }
+        let annotations = load_annotations(annotations_path)?;
+        let doc = engine.extract_hocr_document(&image_path)?;
+        let metrics = evaluate_detection(&annotations, &doc, args.iou_threshold);
+        if args.format == OutputFormat::Json {
+            let json =
+                serde_json::to_string_pretty(&metrics).context("Échec de la sérialisation JSON")?;
+            println!("{}", json);
+        } else {
+            print_detection_metrics(&metrics, &annotations, args.iou_threshold);
+        }
+        return Ok(EXIT_OK);
+    }
+
+    // Régions nommées d'un fichier d'annotation : texte de chaque étiquette
+    if let Some(ref regions_path) = args.regions_file {
+        let file = RegionsFile::load(regions_path)?;
+        let (width, height) = image::image_dimensions(&image_path).with_context(|| {
+            format!("Échec du chargement de l'image '{}'", image_path.display())
+        })?;
+        let language = engine.config().language.clone();
+        let regions: Vec<(BBox, String)> = file
+            .regions(width, height)?
+            .into_iter()
+            .map(|region| (region.bbox, language.clone()))
+            .collect();
+        let texts: Vec<String> = engine
+            .extract_text_multilang(&image_path, &regions)?
+            .into_iter()
+            .map(|result| result.text)
+            .collect();
+        if let Some(ref export_path) = args.regions_export {
+            fs::write(export_path, file.to_json_with_texts(&texts)? + "\n").with_context(|| {
+                format!(
+                    "Impossible d'écrire le fichier de régions '{}'",
+                    export_path.display()
+                )
+            })?;
+            // Sortie d'erreur : la sortie standard peut porter le JSON des étiquettes
+            eprintln!(
+                "✓ Fichier de régions annoté sauvegardé dans: {}",
+                export_path.display()
+            );
+        }
+        let json = file.to_label_json(&texts)? + "\n";
+        if let Some(ref out_path) = args.out {
+            fs::write(out_path, &json).with_context(|| {
+                format!(
+                    "Impossible d'écrire le fichier de sortie '{}'",
+                    out_path.display()
+                )
+            })?;
+            println!(
+                "✓ {} région(s) sauvegardée(s) dans: {}",
+                texts.len(),
+                out_path.display()
+            );
+        } else {
+            print!("{}", json);
+        }
+        return Ok(EXIT_OK);
+    }
+
+    // Calque de surlignage pour une visionneuse web
+    if let Some(ref overlay_path) = args.overlay {
+        let doc = engine.extract_hocr_document(&image_path)?;
+        // Le cadre de la page HOCR donne les dimensions de l'image vue par
+        // Tesseract : les proportions restent justes même après un redimensionnement
+        let (width, height) = match doc.pages.first() {
+            Some(page) if page.bbox.width > 0 && page.bbox.height > 0 => {
+                (page.bbox.width, page.bbox.height)
+            }
+            _ => {
+                let image = load_input_image(&image_path)?;
+                (image.width(), image.height())
+            }
+        };
+        fs::write(overlay_path, doc.to_overlay_json(width, height)).with_context(|| {
+            format!(
+                "Impossible d'écrire le fichier de sortie '{}'",
+                overlay_path.display()
+            )
+        })?;
+        println!("✓ Calque sauvegardé dans: {}", overlay_path.display());
+        return Ok(EXIT_OK);
+    }
+
+    // Carte de chaleur des confiances, sur l'image d'origine
+    if let Some(ref heatmap_path) = args.heatmap {
+        let outcome = generate_hocr_with_timeout(
+            &image_path,
+            &args.language,
+            args.psm.to_tesseract_psm() as u8,
+            engine_mode,
+            timeout,
+        )?;
+        print_warnings(&outcome.warnings);
+        let doc = HocrDocument::from_hocr_string(&outcome.text)?;
+        let options = HeatmapOptions {
+            labels: args.heatmap_labels,
+            ..HeatmapOptions::default()
+        };
+        confidence_heatmap(&load_input_image(&image_path)?, &doc, &options)
+            .save(heatmap_path)
+            .with_context(|| {
+                format!(
+                    "Impossible d'écrire la carte de chaleur '{}'",
+                    heatmap_path.display()
+                )
+            })?;
+        println!(
+            "✓ Carte de chaleur sauvegardée dans: {}",
+            heatmap_path.display()
+        );
+        return Ok(EXIT_OK);
+    }
+
+    // Zones peu fiables à relire, sur l'image d'origine
+    if let Some(threshold) = args.review_regions {
+        let threshold = threshold.unwrap_or(DEFAULT_REVIEW_THRESHOLD);
+        if !(0.0..=100.0).contains(&threshold) {
+            anyhow::bail!(
+                "--review-regions doit être compris entre 0 et 100, reçu {}",
+                threshold
+            );
+        }
+        let outcome = generate_hocr_with_timeout(
+            &image_path,
+            &args.language,
+            args.psm.to_tesseract_psm() as u8,
+            engine_mode,
+            timeout,
+        )?;
+        print_warnings(&outcome.warnings);
+        let doc = HocrDocument::from_hocr_string(&outcome.text)?;
+        let regions = doc.low_confidence_regions(threshold, args.review_level.into());
+        print_review_regions(&regions, threshold);
+
+        if let Some(ref out_dir) = args.review_out {
+            let stem = image_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| String::from("image"));
+            let written =
+                save_region_crops(&load_input_image(&image_path)?, &regions, out_dir, &stem)?;
+            println!(
+                "✓ {} zone(s) découpée(s) dans: {}",
+                written.len(),
+                out_dir.display()
+            );
+        }
+        return Ok(EXIT_OK);
+    }
+
+    // Paires image/texte pour l'affinage d'un modèle Tesseract
+    if let Some(ref out_dir) = args.export_lines {
+        let reference = load_reference(args, &image_path)?;
+        let doc = engine.extract_hocr_document(&image_path)?;
+        let options = LineExportOptions {
+            padding: args.export_padding,
+            max_cer: args.export_max_cer,
+        };
+        let summary =
+            export_line_pairs(&image_path, &doc, reference.as_deref(), out_dir, &options)?;
+        println!(
+            "✓ {} paires exportées dans: {}",
+            summary.exported.len(),
+            out_dir.display()
+        );
+        if !summary.skipped.is_empty() {
+            println!("⚠ {} lignes écartées", summary.skipped.len());
+        }
+        return Ok(EXIT_OK);
+    }
+
+    // Formats structurés : JSON (paragraphes, lignes, mots), Markdown ou HTML
+    let rendered = match args.format {
+        OutputFormat::Text => None,
+        OutputFormat::Json => {
+            let output = JsonOutput {
+                structured: engine.extract_structured(&image_path)?,
+                config: effective,
+            };
+            Some(
+                serde_json::to_string_pretty(&output).context("Échec de la sérialisation JSON")?
+                    + "\n",
+            )
+        }
+        OutputFormat::Markdown => Some(
+            engine
+                .extract_hocr_document(&image_path)?
+                .to_markdown(&ExportOptions::default()),
+        ),
+        OutputFormat::Html => Some(
+            engine
+                .extract_hocr_document(&image_path)?
+                .to_html(&ExportOptions::default()),
+        ),
+    };
+    if let Some(rendered) = rendered {
+        if let Some(ref out_path) = args.out {
+            fs::write(out_path, &rendered).with_context(|| {
+                format!(
+                    "Impossible d'écrire le fichier de sortie '{}'",
+                    out_path.display()
+                )
+            })?;
+            println!(
+                "✓ Export {} sauvegardé dans: {}",
+                args.format.name(),
+                out_path.display()
+            );
+        } else {
+            print!("{}", rendered);
+        }
+
+        return Ok(EXIT_OK);
+    }
+
+    // Extraire le texte (par tuiles, par colonnes, mis en page, d'une région, ou avec correction d'orientation si demandée)
+    let text = if args.tiled {
+        engine.extract_text_tiled(&image_path, args.tile_size, args.tile_overlap)?
+    } else if args.columns {
+        engine.extract_text_columns(&image_path)?
+    } else if args.layout {
+        to_layout_text(
+            &engine.extract_hocr_document(&image_path)?,
+            &LayoutTextOptions::default(),
+        )
+    } else if let Some(ref region) = args.analysis_region {
+        match region {
+            AnalysisRegion::Pixels(bbox) => engine.extract_text_from_region(&image_path, bbox)?,
+            AnalysisRegion::Relative(region) => {
+                engine.extract_text_from_relative_region(&image_path, region)?
+            }
+        }
+    } else if args.auto_rotate {
+        // Détecter et corriger l'orientation via Tesseract PSM 0 (et --deskew)
+        let path = image_path.clone();
+        let outcome = extract_with_timeout(&engine, timeout, move |engine| {
+            engine.extract_text_auto_rotated(&path)
+        })?;
+        print_warnings(&outcome.warnings);
+        if args.verbose {
+            print_preprocessing_report(&outcome);
+        }
+        outcome.text
+    } else {
+        let outcome = if is_stdin_path(&image_path) {
+            let bytes = stdin_bytes()?;
+            extract_with_timeout(&engine, timeout, move |engine| {
+                engine.extract_text_from_bytes_detailed(bytes)
+            })?
+        } else {
+            let path = image_path.clone();
+            extract_with_timeout(&engine, timeout, move |engine| {
+                engine.extract_text_detailed(&path)
+            })?
+        };
+        print_warnings(&outcome.warnings);
+        if args.verbose {
+            print_preprocessing_report(&outcome);
+        }
+        outcome.text
+    };
+
+    // Ticket de caisse : articles et totaux en JSON, code 2 si l'arithmétique ne tombe pas juste
+    if args.parse_receipt {
+        let receipt = parse_receipt(&text);
+        let json =
+            serde_json::to_string_pretty(&receipt).context("Échec de la sérialisation JSON")?;
+        println!("{}", json);
+        return Ok(if receipt.valid {
+            EXIT_OK
+        } else {
+            EXIT_PARTIAL_FAILURE
+        });
+    }
+
+    // Sauvegarder le texte extrait si demandé
+    if let Some(ref out_path) = args.out {
+        write_text_output(out_path, &text, args.encoding, args.newline)?;
+        println!("✓ Texte sauvegardé dans: {}", out_path.display());
+    }
+
+    // Si un fichier de référence est fourni, comparer et afficher les métriques
+    if let Some(expected_text) = load_reference(args, &image_path)? {
+        let detailed = compare_ocr_result_detailed_with(&text, &expected_text, &args.tokenizer);
+        let metrics = &detailed.metrics;
+
+        // Export CSV si demandé
+        if let Some(ref csv_path) = args.csv_export {
+            // Créer les métadonnées
+            let metadata = csv_metadata(
+                args,
+                &image_path,
+                engine.config(),
+                engine.preprocessing_config(),
+            );
+
+            // Générer le CSV
+            let csv_content = detailed.to_csv_with(true, Some(&metadata), &csv_options(args)?);
+
+            // Écrire dans le fichier
+            fs::write(csv_path, csv_content).with_context(|| {
+                format!(
+                    "Impossible d'écrire le fichier CSV '{}'",
+                    csv_path.display()
+                )
+            })?;
+
+            println!("✓ Métriques exportées vers: {}", csv_path.display());
+        } else if args.metrics {
+            // Rapport détaillé avec generate_diff_report_with()
+            let report = generate_diff_report_with(&text, &expected_text, &args.tokenizer);
+            println!("{}", report);
+            if let Some(ref html_path) = args.html {
+                let options = HtmlReportOptions {
+                    tokenizer: args.tokenizer.clone(),
+                    ..HtmlReportOptions::default()
+                };
+                let html = generate_html_report(&text, &expected_text, &options);
+                fs::write(html_path, html).with_context(|| {
+                    format!(
+                        "Impossible d'écrire le rapport HTML '{}'",
+                        html_path.display()
+                    )
+                })?;
+                println!("✓ Rapport HTML écrit dans: {}", html_path.display());
+            }
+        } else {
+            // Affichage simple des métriques essentielles
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            println!("               RÉSULTATS DE LA COMPARAISON OCR");
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            println!();
+            println!("MÉTRIQUES:");
+            println!(
+                "  • CER (Character Error Rate):  {:.2}%",
+                metrics.cer * 100.0
+            );
+            println!(
+                "  • WER (Word Error Rate):       {:.2}%",
+                metrics.wer * 100.0
+            );
+            println!(
+                "  • Distance de Levenshtein:     {}",
+                metrics.levenshtein_distance
+            );
+            println!(
+                "  • Précision:                   {:.2}%",
+                metrics.accuracy() * 100.0
+            );
+            println!();
+            println!("STATISTIQUES:");
+            println!(
+                "  • Référence:  {} caractères, {} mots",
+                metrics.reference_char_count, metrics.reference_word_count
+            );
+            println!(
+                "  • OCR:        {} caractères, {} mots",
+                metrics.ocr_char_count, metrics.ocr_word_count
+            );
+            println!();
+            println!(
+                "  • Match exact: {}",
+                if metrics.exact_match {
+                    "Oui ✓"
+                } else {
+                    "Non ✗"
+                }
+            );
+            println!();
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        }
+    } else if args.out.is_none() {
+        // Afficher le résultat normalement
+        println!("{}", text);
+    }
+
+    Ok(EXIT_OK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text_recognition::fuzzy_find;
+    use text_recognition::pool::OcrWorker;
+
+    #[test]
+    fn test_build_ocr_config_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("ticket.toml");
+        fs::write(
+            &config_path,
+            "[ocr]\ndpi = 350\n\n[ocr.tesseract_variables]\n\
+             preserve_interword_spaces = \"0\"\nload_system_dawg = \"0\"\n",
+        )
+        .unwrap();
+
+        let args = Args::try_parse_with_sources([
+            "text-recognition",
+            "ticket.png",
+            "--preset",
+            "receipt",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--psm",
+            "single-block",
+            "--var",
+            "load_system_dawg=1",
+        ])
+        .unwrap();
+        let file_config = load_file_config(&args).unwrap();
+        let config = build_ocr_config(&args, file_config.as_ref()).unwrap();
+        let effective = EffectiveConfig::from_config(&config);
+
+        // CLI > fichier > preset
+        assert_eq!(effective.psm_value, 6);
+        assert_eq!(effective.dpi, 350);
+        let variables = &effective.tesseract_variables;
+        assert_eq!(variables["load_system_dawg"], "1");
+        assert_eq!(variables["preserve_interword_spaces"], "0");
+        assert_eq!(
+            variables["tessedit_char_whitelist"],
+            text_recognition::config::RECEIPT_CHAR_WHITELIST
+        );
+        assert_eq!(variables["user_defined_dpi"], "350");
+        assert!(effective.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_explicit_cli_value_overrides_file_even_when_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("anglais.toml");
+        fs::write(
+            &config_path,
+            "[ocr]\nlanguage = \"eng\"\npage_seg_mode = \"Auto\"\ndpi = 200\n\n\
+             [ocr.tesseract_variables]\n",
+        )
+        .unwrap();
+
+        let with_flag = Args::try_parse_with_sources([
+            "text-recognition",
+            "page.png",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--language",
+            "fra",
+            "--dpi",
+            "300",
+        ])
+        .unwrap();
+        let file_config = load_file_config(&with_flag).unwrap();
+        let config = build_ocr_config(&with_flag, file_config.as_ref()).unwrap();
+        // `fra` et 300 sont les valeurs par défaut de clap mais ont été saisis
+        assert_eq!(config.language, "fra");
+        assert_eq!(config.dpi, 300);
+
+        let without_flag = Args::try_parse_with_sources([
+            "text-recognition",
+            "page.png",
+            "--config",
+            config_path.to_str().unwrap(),
+        ])
+        .unwrap();
+        let config = build_ocr_config(&without_flag, file_config.as_ref()).unwrap();
+        assert_eq!(config.language, "eng");
+        assert_eq!(config.dpi, 200);
+    }
+
+    #[test]
+    fn test_merge_lines_flag_keeps_file_criteria() {
+        let args = Args::try_parse_with_sources(["text-recognition", "page.png", "--merge-lines"])
+            .unwrap();
+        let config = build_ocr_config(&args, None).unwrap();
+        assert_eq!(config.line_merge, Some(LineMergeOptions::default()));
+
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("factures.toml");
+        fs::write(
+            &config_path,
+            "[ocr]\nlanguage = \"fra\"\npage_seg_mode = \"Auto\"\ndpi = 300\n\n\
+             [ocr.tesseract_variables]\n\n\
+             [ocr.line_merge]\nmax_horizontal_gap = 600\n",
+        )
+        .unwrap();
+        let args = Args::try_parse_with_sources([
+            "text-recognition",
+            "page.png",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--merge-lines",
+        ])
+        .unwrap();
+        let file_config = load_file_config(&args).unwrap();
+        let config = build_ocr_config(&args, file_config.as_ref()).unwrap();
+        assert_eq!(
+            config.line_merge,
+            Some(LineMergeOptions {
+                max_horizontal_gap: 600,
+                ..LineMergeOptions::default()
+            })
+        );
+
+        let args = Args::try_parse_with_sources(["text-recognition", "page.png"]).unwrap();
+        assert_eq!(build_ocr_config(&args, None).unwrap().line_merge, None);
+    }
+
+    #[test]
+    fn test_regions_file_args() {
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "facture.png",
+            "--regions-file",
+            "annotations.json",
+            "--out",
+            "champs.json",
+        ])
+        .unwrap();
+        assert_eq!(args.regions_file, Some(PathBuf::from("annotations.json")));
+        assert_eq!(args.regions_export, None);
+
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "facture.png",
+            "--regions-file",
+            "annotations.json",
+            "--regions-export",
+            "annotations.ocr.json",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.regions_export,
+            Some(PathBuf::from("annotations.ocr.json"))
+        );
+        let without_regions = [
+            "text-recognition",
+            "facture.png",
+            "--regions-export",
+            "a.json",
+        ];
+        assert!(Args::try_parse_from(without_regions).is_err());
+
+        for conflict in [["--region", "0,0,10,10"], ["--format", "json"]] {
+            let mut argv = vec![
+                "text-recognition",
+                "facture.png",
+                "--regions-file",
+                "annotations.json",
+            ];
+            argv.extend(conflict);
+            assert!(Args::try_parse_from(argv).is_err());
+        }
+
+        let errors = validation_errors(&["text-recognition", "-", "--regions-file", "a.json"]);
+        assert!(errors.contains(&ArgError::StdinIncompatible {
+            flag: "--regions-file"
+        }));
+    }
+
+    #[test]
+    fn test_skip_images_flag() {
+        let args = Args::try_parse_with_sources(["text-recognition", "page.png", "--skip-images"])
+            .unwrap();
+        assert!(build_ocr_config(&args, None).unwrap().skip_image_regions);
+
+        let args = Args::try_parse_with_sources(["text-recognition", "page.png"]).unwrap();
+        assert!(!build_ocr_config(&args, None).unwrap().skip_image_regions);
+    }
+
+    #[test]
+    fn test_binarize_flag_keeps_file_binarization_method() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("scan.toml");
+        let file_prep = PreprocessingConfig {
+            binarization_method: BinarizationMethod::Adaptive,
+            ..PreprocessingConfig::default()
+        };
+        fs::write(
+            &config_path,
+            format!("[preprocessing]\n{}", toml::to_string(&file_prep).unwrap()),
+        )
+        .unwrap();
+
+        let args = Args::try_parse_with_sources([
+            "text-recognition",
+            "scan.png",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--preprocess",
+            "--binarize",
+        ])
+        .unwrap();
+        let file_config = load_file_config(&args).unwrap();
+        let validated = validate_args(&args).unwrap();
+        let prep = build_preprocessing_config(&validated, file_config.as_ref())
+            .unwrap()
+            .unwrap();
+
+        assert!(prep.binarize);
+        assert_eq!(prep.binarization_method, BinarizationMethod::Adaptive);
+    }
+
+    #[test]
+    fn test_tess_configs_follow_file_configs() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("factures.toml");
+        fs::write(
+            &config_path,
+            "[ocr]\nlanguage = \"fra\"\npage_seg_mode = \"Auto\"\ndpi = 300\n\
+             config_names = [\"quiet\"]\n\n[ocr.tesseract_variables]\n",
+        )
+        .unwrap();
+
+        let args = Args::try_parse_with_sources([
+            "text-recognition",
+            "facture.png",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--tess-config",
+            "digits",
+            "--tess-config",
+            "./factures.cfg",
+        ])
+        .unwrap();
+        let file_config = load_file_config(&args).unwrap();
+        let config = build_ocr_config(&args, file_config.as_ref()).unwrap();
+
+        assert_eq!(config.config_names, ["quiet", "digits", "./factures.cfg"]);
+    }
+
+    #[test]
+    fn test_tessdata_dir_and_threads_args() {
+        let args = Args::try_parse_with_sources([
+            "text-recognition",
+            "page.png",
+            "--tessdata-dir",
+            "/opt/tessdata_best",
+            "--threads",
+            "2",
+        ])
+        .unwrap();
+        let config = build_ocr_config(&args, None).unwrap();
+        assert_eq!(
+            config.tessdata_dir,
+            Some(PathBuf::from("/opt/tessdata_best"))
+        );
+        assert_eq!(config.threads, Some(2));
+
+        let zero_threads = ["text-recognition", "page.png", "--threads", "0"];
+        assert!(Args::try_parse_from(zero_threads).is_err());
+    }
+
+    #[test]
+    fn test_parse_tesseract_variable() {
+        assert_eq!(
+            parse_tesseract_variable("tessedit_char_whitelist=0123456789"),
+            Ok((
+                "tessedit_char_whitelist".to_string(),
+                "0123456789".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_tesseract_variable("page_separator="),
+            Ok(("page_separator".to_string(), String::new()))
+        );
+        assert!(parse_tesseract_variable("load_system_dawg").is_err());
+        assert!(parse_tesseract_variable("=1").is_err());
+    }
+
+    #[test]
+    fn test_region_options() {
+        assert_eq!(
+            parse_pixel_region("1536, 0,384,108"),
+            Ok(BBox::new(1536, 0, 384, 108))
+        );
+        assert!(parse_pixel_region("10,10,0,5").is_err());
+        assert!(parse_pixel_region("10,10,5").is_err());
+
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "capture.png",
+            "--region-pct",
+            "0.8,0.0,0.2,0.1",
+        ])
+        .unwrap();
+        let region = args.region_pct.unwrap();
+        // Même zone qu'en pixels sur une capture 1920×1080
+        assert_eq!(region.to_bbox(1920, 1080), BBox::new(1536, 0, 384, 108));
+
+        let both = Args::try_parse_from([
+            "text-recognition",
+            "capture.png",
+            "--region",
+            "1536,0,384,108",
+            "--region-pct",
+            "0.8,0.0,0.2,0.1",
+        ]);
+        assert!(both.is_err());
+        assert!(
+            Args::try_parse_from([
+                "text-recognition",
+                "capture.png",
+                "--region-pct",
+                "0.8,0.0,0.3,0.1",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_decide_exit_code() {
+        assert_eq!(decide_exit_code(5, 0, 5), EXIT_OK);
+        assert_eq!(decide_exit_code(3, 2, 5), EXIT_PARTIAL_FAILURE);
+        assert_eq!(decide_exit_code(0, 5, 5), EXIT_FATAL);
+        assert_eq!(decide_exit_code(0, 0, 0), EXIT_USAGE);
+    }
+
+    #[test]
+    fn test_batch_failures_report() {
+        let good = PathBuf::from("resources/simple/img-1.png");
+        let missing = PathBuf::from("resources/simple/inexistante.png");
+        let results: Vec<Result<()>> = vec![
+            Ok(()),
+            Err(anyhow::anyhow!(
+                "Le fichier '{}' n'existe pas",
+                missing.display()
+            )),
+        ];
+
+        let failures: Vec<BatchFailure> = [&good, &missing]
+            .into_iter()
+            .zip(&results)
+            .filter_map(|(path, result)| result.as_ref().err().map(|e| BatchFailure::new(path, e)))
+            .collect();
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].kind, FailureKind::NotFound);
+        assert_eq!(
+            decide_exit_code(
+                results.len() - failures.len(),
+                failures.len(),
+                results.len()
+            ),
+            EXIT_PARTIAL_FAILURE
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let report_path = dir.path().join("errors.json");
+        write_error_report(&report_path, &failures).unwrap();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+        assert_eq!(report.as_array().unwrap().len(), 1);
+        assert_eq!(report[0]["path"], "resources/simple/inexistante.png");
+        assert_eq!(report[0]["kind"], "not_found");
+        assert!(
+            report[0]["error"]
+                .as_str()
+                .unwrap()
+                .contains("n'existe pas")
+        );
+    }
+
+    #[test]
+    fn test_failure_kind_from_error_chain() {
+        let good = Path::new("resources/simple/img-1.png");
+
+        let io = anyhow::Error::from(std::io::Error::other("disque plein")).context("écriture");
+        assert_eq!(BatchFailure::new(good, &io).kind, FailureKind::Io);
+
+        let ocr = anyhow::anyhow!("Échec de l'initialisation de Tesseract");
+        assert_eq!(BatchFailure::new(good, &ocr).kind, FailureKind::Ocr);
+    }
+
+    #[test]
+    fn test_batch_summary_renamed_outputs() {
+        let images = [
+            PathBuf::from("scans/a/page1.png"),
+            PathBuf::from("scans/b/page1.png"),
+        ];
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--output",
+            "results",
+            "--flatten",
+        ])
+        .unwrap();
+        let mut summary = BatchSummary::new(2, Vec::new());
+        summary.outputs = batch_output_paths(&args, &images, Path::new("results"), "txt");
+
+        let report = summary.to_string();
+        assert!(report.contains("Renommées: 1 sorties (noms en double)"));
+        assert!(report.contains("scans/b/page1.png → results/page1-2.txt"));
+    }
+
+    #[test]
+    fn test_overlay_args() {
+        let args = Args::try_parse_from(["text-recognition", "page.png", "--overlay", "page.json"])
+            .unwrap();
+        assert_eq!(args.overlay, Some(PathBuf::from("page.json")));
+
+        let batch = [
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--overlay",
+            "o.json",
+        ];
+        assert!(Args::try_parse_from(batch).is_err());
+    }
+
+    #[test]
+    fn test_heatmap_args() {
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "page.png",
+            "--heatmap",
+            "page.heatmap.png",
+            "--heatmap-labels",
+        ])
+        .unwrap();
+        assert_eq!(args.heatmap, Some(PathBuf::from("page.heatmap.png")));
+        assert!(args.heatmap_labels);
+
+        let without_heatmap = ["text-recognition", "page.png", "--heatmap-labels"];
+        assert!(Args::try_parse_from(without_heatmap).is_err());
+        let with_overlay = [
+            "text-recognition",
+            "page.png",
+            "--heatmap",
+            "h.png",
+            "--overlay",
+            "o.json",
+        ];
+        assert!(Args::try_parse_from(with_overlay).is_err());
+    }
+
+    #[test]
+    fn test_review_regions_args() {
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "page.png",
+            "--review-regions",
+            "--review-level",
+            "word",
+            "--review-out",
+            "relecture",
+        ])
+        .unwrap();
+        assert_eq!(args.review_regions, Some(None));
+        assert_eq!(args.review_level, ReviewLevel::Word);
+        assert_eq!(args.review_out, Some(PathBuf::from("relecture")));
+
+        let args = Args::try_parse_from(["text-recognition", "page.png", "--review-regions", "70"])
+            .unwrap();
+        assert_eq!(args.review_regions, Some(Some(70.0)));
+        assert_eq!(args.review_level, ReviewLevel::Line);
+
+        let without_review = ["text-recognition", "page.png", "--review-out", "relecture"];
+        assert!(Args::try_parse_from(without_review).is_err());
+        let with_heatmap = [
+            "text-recognition",
+            "page.png",
+            "--review-regions",
+            "--heatmap",
+            "h.png",
+        ];
+        assert!(Args::try_parse_from(with_heatmap).is_err());
+    }
+
+    #[test]
+    fn test_pyramid_args() {
+        let args = Args::try_parse_from(["text-recognition", "photo.jpg", "--pyramid"]).unwrap();
+        assert_eq!(args.pyramid, Some(PyramidConfig::default()));
+
+        let args = Args::try_parse_with_sources([
+            "text-recognition",
+            "photo.jpg",
+            "--pyramid",
+            "0.25,0.5,1",
+        ])
+        .unwrap();
+        let pyramid = args.pyramid.clone().unwrap();
+        assert_eq!(pyramid.levels, vec![0.25, 0.5, 1.0]);
+        let patch = ocr_patch_from_args(&args, &OcrConfig::default());
+        assert_eq!(patch.pyramid, Some(Some(pyramid)));
+
+        let decreasing =
+            Args::try_parse_from(["text-recognition", "photo.jpg", "--pyramid", "1,0.5"]);
+        assert!(decreasing.is_err());
+    }
+
+    #[test]
+    fn test_isolate_args() {
+        let args =
+            Args::try_parse_from(["text-recognition", "scans", "--batch", "--isolate"]).unwrap();
+        assert!(args.isolate);
+
+        let without_batch = Args::try_parse_from(["text-recognition", "scan.png", "--isolate"]);
+        assert!(without_batch.is_err());
+    }
+
+    #[test]
+    fn test_experiment_ledger_args() {
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--expected",
+            "refs",
+            "--experiment-label",
+            "otsu",
+            "--ledger",
+            "experiences.jsonl",
+        ])
+        .unwrap();
+        assert_eq!(args.experiment_label.as_deref(), Some("otsu"));
+        assert_eq!(args.ledger, Some(PathBuf::from("experiences.jsonl")));
+
+        let without_label = [
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--expected",
+            "refs",
+            "--ledger",
+            "e.jsonl",
+        ];
+        assert!(Args::try_parse_from(without_label).is_err());
+        let without_reference = [
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--experiment-label",
+            "otsu",
+            "--ledger",
+            "e.jsonl",
+        ];
+        assert!(Args::try_parse_from(without_reference).is_err());
+    }
+
+    #[test]
+    fn test_html_report_args() {
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "page.png",
+            "--expected",
+            "page.txt",
+            "--metrics",
+            "--html",
+            "rapport.html",
+        ])
+        .unwrap();
+        assert_eq!(args.html, Some(PathBuf::from("rapport.html")));
+
+        let without_metrics = [
+            "text-recognition",
+            "page.png",
+            "--expected",
+            "page.txt",
+            "--html",
+            "rapport.html",
+        ];
+        assert!(Args::try_parse_from(without_metrics).is_err());
+    }
+
+    #[test]
+    fn test_metrics_jsonl_args() {
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--expected",
+            "refs",
+            "--metrics-jsonl",
+            "metrics.jsonl",
+            "--no-metadata",
+        ])
+        .unwrap();
+        assert_eq!(args.metrics_jsonl, Some(PathBuf::from("metrics.jsonl")));
+        assert!(args.no_metadata);
+
+        let without_batch = [
+            "text-recognition",
+            "page.png",
+            "--expected",
+            "page.txt",
+            "--metrics-jsonl",
+            "m.jsonl",
+        ];
+        assert!(Args::try_parse_from(without_batch).is_err());
+        let without_expected = [
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--metrics-jsonl",
+            "m.jsonl",
+        ];
+        assert!(Args::try_parse_from(without_expected).is_err());
+        let no_export = ["text-recognition", "page.png", "--no-metadata"];
+        assert!(Args::try_parse_from(no_export).is_err());
+    }
+
+    #[test]
+    fn test_expected_embedded_args() {
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--expected-embedded",
+            "--csv-export",
+            "results.csv",
+        ])
+        .unwrap();
+        assert!(args.expected_embedded);
+        assert_eq!(args.expected, None);
+        let metadata = csv_metadata(&args, Path::new("a.png"), &OcrConfig::default(), None);
+        assert_eq!(metadata["reference_source"], "embedded");
+
+        let both = [
+            "text-recognition",
+            "page.png",
+            "--expected-embedded",
+            "--expected",
+            "page.txt",
+        ];
+        assert!(Args::try_parse_from(both).is_err());
+        let with_compare = [
+            "text-recognition",
+            "a.png",
+            "--expected-embedded",
+            "--compare",
+            "b.png",
+        ];
+        assert!(Args::try_parse_from(with_compare).is_err());
+        assert_eq!(
+            validation_errors(&["text-recognition", "-", "--expected-embedded"]),
+            [ArgError::StdinIncompatible {
+                flag: "--expected-embedded"
+            }]
+        );
+    }
+
+    #[test]
+    fn test_encoding_args_and_output() {
+        let args = Args::try_parse_from(["text-recognition", "scan.png"]).unwrap();
+        assert_eq!(args.encoding, OutputEncoding::Utf8);
+        assert_eq!(args.newline, NewlineStyle::Lf);
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "scan.png",
+            "--encoding",
+            "utf16le",
+            "--newline",
+            "crlf",
+        ])
+        .unwrap();
+        assert_eq!(args.encoding, OutputEncoding::Utf16Le);
+        assert_eq!(args.newline, NewlineStyle::CrLf);
+        let unknown = ["text-recognition", "scan.png", "--encoding", "cp1252"];
+        assert!(Args::try_parse_from(unknown).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("sous/page.txt");
+        save_batch_output(&out, "Été\n", OutputEncoding::Utf8, NewlineStyle::Lf).unwrap();
+        assert_eq!(fs::read(&out).unwrap(), "Été\n".as_bytes());
+        save_batch_output(&out, "Été\n", OutputEncoding::Utf8Bom, NewlineStyle::CrLf).unwrap();
+        assert_eq!(
+            fs::read(&out).unwrap(),
+            b"\xEF\xBB\xBF\xC3\x89t\xC3\xA9\r\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_receipt_args() {
+        let args =
+            Args::try_parse_from(["text-recognition", "ticket.jpg", "--parse-receipt"]).unwrap();
+        assert!(args.parse_receipt);
+        let with_batch = ["text-recognition", "tickets", "--batch", "--parse-receipt"];
+        assert!(Args::try_parse_from(with_batch).is_err());
+        let with_out = [
+            "text-recognition",
+            "ticket.jpg",
+            "--parse-receipt",
+            "--out",
+            "ticket.json",
+        ];
+        assert!(Args::try_parse_from(with_out).is_err());
+    }
+
+    #[test]
+    fn test_cross_check_args() {
+        let args = Args::try_parse_from(["text-recognition", "scan.png", "--cross-check"]).unwrap();
+        assert!(args.cross_check);
+        let with_batch = ["text-recognition", "scans", "--batch", "--cross-check"];
+        assert!(Args::try_parse_from(with_batch).is_err());
+        let with_expected = [
+            "text-recognition",
+            "scan.png",
+            "--cross-check",
+            "--expected",
+            "scan.txt",
+        ];
+        assert!(Args::try_parse_from(with_expected).is_err());
+        assert_eq!(
+            validation_errors(&["text-recognition", "-", "--cross-check"]),
+            [ArgError::StdinIncompatible {
+                flag: "--cross-check"
+            }]
+        );
+    }
+
+    #[test]
+    fn test_recommend_psm_args() {
+        let args =
+            Args::try_parse_from(["text-recognition", "scan.png", "--recommend-psm"]).unwrap();
+        assert!(args.recommend_psm && !args.apply);
+        for invalid in [
+            &["text-recognition", "scan.png", "--apply"][..],
+            &[
+                "text-recognition",
+                "scan.png",
+                "--recommend-psm",
+                "--psm",
+                "6",
+            ],
+            &[
+                "text-recognition",
+                "scan.png",
+                "--recommend-psm",
+                "--test-all-psm",
+            ],
+            &[
+                "text-recognition",
+                "scan.png",
+                "--recommend-psm",
+                "--apply",
+                "--heatmap",
+                "carte.png",
+            ],
+        ] {
+            assert!(Args::try_parse_from(invalid).is_err(), "{:?}", invalid);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("blanche.png");
+        image::GrayImage::from_pixel(64, 64, image::Luma([255]))
+            .save(&image)
+            .unwrap();
+        let args = Args::try_parse_from([
+            "text-recognition",
+            image.to_str().unwrap(),
+            "--recommend-psm",
+            "--apply",
+        ])
+        .unwrap();
+        let recommendation = recommend_psm_for(&args).unwrap();
+        assert_eq!(recommendation.stats.region_count, 0);
+        assert_eq!(recommendation.top(), PageSegMode::Auto);
+    }
+
+    #[test]
+    fn test_load_reference_embedded_or_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("page.png");
+        image::GrayImage::new(16, 16).save(&image).unwrap();
+        let image_arg = image.to_str().unwrap();
+
+        let embedded =
+            Args::try_parse_from(["text-recognition", image_arg, "--expected-embedded"]).unwrap();
+        let error = load_reference(&embedded, &image).unwrap_err();
+        assert!(error.to_string().contains("Aucune référence intégrée"));
+
+        text_recognition::metadata::write_embedded_reference(&image, "Référence intégrée").unwrap();
+        assert_eq!(
+            load_reference(&embedded, &image).unwrap().as_deref(),
+            Some("Référence intégrée")
+        );
+
+        let reference = dir.path().join("page.txt");
+        fs::write(&reference, "Référence fichier").unwrap();
+        let from_file = Args::try_parse_from([
+            "text-recognition",
+            image_arg,
+            "--expected",
+            reference.to_str().unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(
+            load_reference(&from_file, &image).unwrap().as_deref(),
+            Some("Référence fichier")
+        );
+        let metadata = csv_metadata(&from_file, &image, &OcrConfig::default(), None);
+        assert_eq!(metadata["reference_source"], "file");
+    }
+
+    /// Analyse les arguments avec clap puis les valide.
+    fn validation_errors(argv: &[&str]) -> Vec<ArgError> {
+        let args = Args::try_parse_from(argv).unwrap();
+        validate_args(&args).unwrap_err()
+    }
+
+    #[test]
+    fn test_validate_args_rejects_metrics_in_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let expected = dir.path().to_str().unwrap();
+        let argv = [
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--expected",
+            expected,
+            "--metrics",
+        ];
+
+        assert!(matches!(
+            validation_errors(&argv).as_slice(),
+            [ArgError::Conflict {
+                flag: "--metrics",
+                other: "--batch",
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_validate_args_rejects_auto_rotate_with_osd_only() {
+        let argv = [
+            "text-recognition",
+            "scan.png",
+            "--auto-rotate",
+            "--psm",
+            "0",
+        ];
+
+        assert!(matches!(
+            validation_errors(&argv).as_slice(),
+            [ArgError::Conflict {
+                flag: "--auto-rotate",
+                other: "--psm 0",
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_validate_args_rejects_out_of_range_threshold() {
+        let argv = [
+            "text-recognition",
+            "scan.png",
+            "--preprocess",
+            "--binarize",
+            "--binarize-method",
+            "fixed:300",
+        ];
+
+        let errors = validation_errors(&argv);
+        assert!(
+            matches!(
+                errors.as_slice(),
+                [ArgError::InvalidValue { flag: "--binarize-method", value, .. }] if value == "fixed:300"
+            ),
+            "{:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_validate_args_rejects_region_in_batch() {
+        let argv = [
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--region",
+            "0,0,10,10",
+        ];
+
+        assert!(matches!(
+            validation_errors(&argv).as_slice(),
+            [ArgError::Conflict {
+                flag: "--region",
+                other: "--batch",
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_validate_args_checks_expected_file_or_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let reference = dir.path().join("scan.txt");
+        fs::write(&reference, "Bonjour").unwrap();
+        let (dir_arg, file_arg) = (dir.path().to_str().unwrap(), reference.to_str().unwrap());
+
+        let single = validation_errors(&["text-recognition", "scan.png", "--expected", dir_arg]);
+        assert!(matches!(
+            single.as_slice(),
+            [ArgError::ExpectedFile {
+                flag: "--expected",
+                ..
+            }]
+        ));
+
+        let batch = validation_errors(&["text-recognition", "scans", "--batch", "-e", file_arg]);
+        assert!(matches!(
+            batch.as_slice(),
+            [ArgError::ExpectedDirectory {
+                flag: "--expected",
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_validate_args_reports_all_errors() {
+        let argv = [
+            "text-recognition",
+            "-",
+            "--auto-rotate",
+            "--psm",
+            "0",
+            "--preprocess",
+            "--binarize",
+            "--binarize-method",
+            "fixed:",
+            "--expected",
+            "/nonexistent/reference.txt",
+        ];
+
+        let errors = validation_errors(&argv);
+
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors.contains(&ArgError::StdinIncompatible {
+            flag: "--auto-rotate"
+        }));
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, ArgError::Conflict { .. }))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, ArgError::InvalidValue { .. }))
+        );
+        assert!(errors.contains(&ArgError::NotFound {
+            flag: "--expected",
+            path: PathBuf::from("/nonexistent/reference.txt"),
+        }));
+        assert!(errors[0].to_string().contains("--auto-rotate"));
+    }
+
+    #[test]
+    fn test_validate_args_parses_valid_flags() {
+        let dir = tempfile::tempdir().unwrap();
+        let reference = dir.path().join("scan.txt");
+        fs::write(&reference, "Bonjour").unwrap();
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "scan.png",
+            "--preprocess",
+            "--binarize",
+            "--binarize-method",
+            "fixed:128",
+            "--region-pct",
+            "0.8,0,0.2,0.1",
+            "--expected",
+            reference.to_str().unwrap(),
+            "--metrics",
+        ])
+        .unwrap();
+
+        let validated = validate_args(&args).unwrap();
+
+        assert_eq!(
+            validated.binarization_method,
+            BinarizationMethod::Fixed(128)
+        );
+        assert!(matches!(
+            validated.analysis_region,
+            Some(AnalysisRegion::Relative(region)) if region.to_bbox(100, 100) == BBox::new(80, 0, 20, 10)
+        ));
+        // Les autres options restent accessibles
+        assert!(validated.metrics);
+    }
+
+    #[test]
+    fn test_export_lines_args() {
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "facture.png",
+            "--export-lines",
+            "gt",
+            "--export-max-cer",
+            "0.1",
+        ])
+        .unwrap();
+        assert_eq!(args.export_lines, Some(PathBuf::from("gt")));
+        assert_eq!((args.export_padding, args.export_max_cer), (4, 0.1));
+
+        let without_dir = ["text-recognition", "facture.png", "--export-padding", "8"];
+        assert!(Args::try_parse_from(without_dir).is_err());
+    }
+
+    #[test]
+    fn test_preprocess_profile_args() {
+        let args = Args::try_parse_with_sources([
+            "text-recognition",
+            "scan.png",
+            "--preprocess-profile",
+            "balanced",
+            "--skip-blank-pages",
+        ])
+        .unwrap();
+        let validated = validate_args(&args).unwrap();
+        let config = build_preprocessing_config(&validated, None)
+            .unwrap()
+            .unwrap();
+        assert!(config.binarize && config.skip_blank_pages);
+        assert_eq!(
+            config.despeckle,
+            Some(text_recognition::PROFILE_DESPECKLE_AREA)
+        );
+
+        let unknown = [
+            "text-recognition",
+            "scan.png",
+            "--preprocess-profile",
+            "turbo",
+        ];
+        assert!(Args::try_parse_from(unknown).is_err());
+    }
+
+    #[test]
+    fn test_validate_args_rejects_steps_with_profile() {
+        let errors = validation_errors(&[
+            "text-recognition",
+            "scan.png",
+            "--preprocess",
+            "--preprocess-profile",
+            "best",
+            "--binarize",
+            "--deskew",
+            "--max-pixels",
+            "1000000",
+        ]);
+
+        let flags: Vec<&str> = errors
+            .iter()
+            .map(|error| match error {
+                ArgError::Conflict { flag, other, .. } => {
+                    assert_eq!(*other, "--preprocess-profile");
+                    *flag
+                }
+                other => panic!("erreur inattendue : {:?}", other),
+            })
+            .collect();
+        assert_eq!(flags, ["--binarize", "--deskew"]);
+    }
+
+    #[test]
+    fn test_despeckle_args() {
+        let args = Args::try_parse_with_sources([
+            "text-recognition",
+            "scan.png",
+            "--preprocess",
+            "--binarize",
+            "--despeckle",
+            "10",
+        ])
+        .unwrap();
+        let validated = validate_args(&args).unwrap();
+        let config = build_preprocessing_config(&validated, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.despeckle, Some(10));
+
+        let without_preprocess = ["text-recognition", "scan.png", "--despeckle", "10"];
+        assert!(Args::try_parse_from(without_preprocess).is_err());
+    }
+
+    #[test]
+    fn test_grayscale_options_args() {
+        let args = Args::try_parse_with_sources([
+            "text-recognition",
+            "capture.png",
+            "--preprocess-profile",
+            "balanced",
+            "--gray-weights",
+            "0,0.5,0.5",
+            "--alpha-bg",
+            "white",
+        ])
+        .unwrap();
+        let validated = validate_args(&args).unwrap();
+        let config = build_preprocessing_config(&validated, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            config.grayscale_options,
+            GrayscaleOptions {
+                weights: ChannelWeights::Custom {
+                    r: 0.0,
+                    g: 0.5,
+                    b: 0.5
+                },
+                alpha_background: Some(255),
+                drop_channel: None,
+            }
+        );
+
+        let args = Args::try_parse_with_sources([
+            "text-recognition",
+            "registre.png",
+            "--preprocess",
+            "--drop-channel",
+            "red",
+        ])
+        .unwrap();
+        let validated = validate_args(&args).unwrap();
+        let config = build_preprocessing_config(&validated, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.grayscale_options.drop_channel, Some(Channel::Red));
+
+        let errors = validation_errors(&[
+            "text-recognition",
+            "registre.png",
+            "--preprocess",
+            "--drop-channel",
+            "red",
+            "--gray-weights",
+            "601",
+        ]);
+        assert!(matches!(
+            errors.as_slice(),
+            [ArgError::Conflict {
+                flag: "--gray-weights",
+                ..
+            }]
+        ));
+        let invalid = [
+            "text-recognition",
+            "a.png",
+            "--preprocess",
+            "--gray-weights",
+            "lab",
+        ];
+        assert!(Args::try_parse_from(invalid).is_err());
+        let without_preprocess = ["text-recognition", "a.png", "--alpha-bg", "white"];
+        assert!(Args::try_parse_from(without_preprocess).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_args() {
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--checkpoint",
+            "lot.jsonl",
+            "--no-retry-failed",
+        ])
+        .unwrap();
+        assert_eq!(args.checkpoint, Some(PathBuf::from("lot.jsonl")));
+        assert!(args.no_retry_failed);
+
+        for argv in [
+            &["text-recognition", "page.png", "--checkpoint", "lot.jsonl"][..],
+            &[
+                "text-recognition",
+                "scans",
+                "--batch",
+                "--checkpoint",
+                "lot.jsonl",
+                "--resume",
+                "lot.jsonl",
+            ],
+        ] {
+            assert!(Args::try_parse_from(argv).is_err(), "{:?}", argv);
+        }
+    }
+
+    #[test]
+    fn test_resume_requires_existing_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("absent.jsonl");
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--resume",
+            path.to_str().unwrap(),
+        ])
+        .unwrap();
+
+        let error = open_checkpoint(&args).unwrap_err();
+        assert!(error.to_string().contains("introuvable"), "{}", error);
+        assert!(!path.exists());
+
+        fs::write(&path, "").unwrap();
+        assert!(open_checkpoint(&args).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_serve_args() {
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "--serve",
+            "127.0.0.1:8080",
+            "--serve-workers",
+            "4",
+        ])
+        .unwrap();
+        assert_eq!(args.serve.as_deref(), Some("127.0.0.1:8080"));
+        assert_eq!(args.serve_workers, 4);
+        assert_eq!(args.max_body_size, 20 * 1024 * 1024);
+
+        for argv in [
+            &["text-recognition", "page.png", "--serve-workers", "4"][..],
+            &[
+                "text-recognition",
+                "--serve",
+                "127.0.0.1:8080",
+                "--serve-workers",
+                "0",
+            ],
+            &[
+                "text-recognition",
+                "scans",
+                "--batch",
+                "--serve",
+                "127.0.0.1:8080",
+            ],
+        ] {
+            assert!(Args::try_parse_from(argv).is_err(), "{:?}", argv);
+        }
+    }
+
+    #[test]
+    fn test_ablation_requires_expected_and_preprocess() {
+        let parse = |extra: &[&str]| {
+            let mut argv = vec!["text-recognition", "page.png", "--ablation"];
+            argv.extend_from_slice(extra);
+            Args::try_parse_from(argv)
+        };
+
+        assert!(parse(&["--expected", "page.txt", "--preprocess"]).is_ok());
+        assert!(parse(&["--expected", "page.txt"]).is_err());
+        assert!(parse(&["--preprocess"]).is_err());
+        assert!(parse(&["--expected", "page.txt", "--preprocess", "--batch"]).is_err());
+        let args = parse(&[
+            "--expected",
+            "x.txt",
+            "--preprocess",
+            "--ablation-additions",
+        ])
+        .unwrap();
+        assert!(args.ablation_additions);
+    }
+
+    #[test]
+    fn test_csv_options_from_args() {
+        let parse = |extra: &[&str]| {
+            let mut argv = vec!["text-recognition", "page.png"];
+            argv.extend_from_slice(extra);
+            csv_options(&Args::try_parse_from(argv).unwrap())
+        };
+
+        assert_eq!(parse(&[]).unwrap(), CsvOptions::default());
+        let options = parse(&["--csv-delimiter", ";", "--csv-decimal", ","]).unwrap();
+        assert_eq!((options.delimiter, options.decimal_separator), (';', ','));
+        assert!(parse(&["--csv-delimiter", "\""]).is_err());
+        assert!(
+            Args::try_parse_from(["text-recognition", "page.png", "--csv-delimiter", ";;"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_find_helpers() {
+        assert_eq!(default_find_distance("générales"), 1);
+        assert_eq!(default_find_distance("Conditions générales"), 4);
+
+        let text = "Article 3.\nLes Conditions génerales s'appliquent à toute commande passée.";
+        let found = fuzzy_find(text, "générales", 1).remove(0);
+        assert_eq!(
+            match_context(text, &found),
+            "Article 3. Les Conditions [génerales] s'appliquent à toute commande…"
+        );
+
+        let args = Args::try_parse_from(["text-recognition", "a.png", "--find-distance", "2"]);
+        assert!(args.is_err(), "--find-distance nécessite --find");
+    }
+
+    #[test]
+    fn test_group_by_profile() {
+        let rules = [
+            ProfileRule {
+                pattern: "receipts/**".to_string(),
+                profile: "receipt".to_string(),
+            },
+            ProfileRule {
+                pattern: "*.eng.png".to_string(),
+                profile: "english".to_string(),
+            },
+        ];
+        let images: Vec<PathBuf> = [
+            "corpus/receipts/a.png",
+            "corpus/letter.png",
+            "corpus/receipts/2024/b.png",
+            "corpus/c.eng.png",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+
+        let groups = group_by_profile(&images, Some(Path::new("corpus")), &rules);
+        assert_eq!(
+            groups,
+            [
+                (Some("receipt"), vec![0, 2]),
+                (None, vec![1]),
+                (Some("english"), vec![3]),
+            ]
+        );
+
+        // Sans règles : un seul groupe, configuration de premier niveau
+        assert_eq!(
+            group_by_profile(&images, None, &[]),
+            [(None, vec![0, 1, 2, 3])]
+        );
+
+        let mut summary = BatchSummary::new(4, Vec::new());
+        summary.profiles = vec![("receipt".to_string(), 2), ("(défaut)".to_string(), 2)];
+        assert!(
+            summary
+                .to_string()
+                .contains("Profils:\n  receipt: 2 images\n  (défaut): 2 images")
+        );
+    }
+
+    #[test]
+    fn test_dry_run_report() {
+        let images: Vec<PathBuf> = ["a.PNG", "b.png", "c.jpg", "d.png", "e"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        let skipped = vec![BatchFailure::skipped(
+            Path::new("d.png"),
+            &ImageFileError::Empty,
+        )];
+        let mut report = DryRunReport::new(&images, &skipped);
+        report.sample_blank = 1;
+        report.estimate = extrapolate(&[Duration::from_secs(2), Duration::from_secs(40)], 4, 2);
+        report.output_bytes = 3 * 1024 * 1024 / 2;
+
+        let text = report.to_string();
+        assert!(text.contains("  .png      3"), "{}", text);
+        assert!(text.contains("  .jpg      1"), "{}", text);
+        assert!(text.contains("(sans extension) 1"), "{}", text);
+        assert!(text.contains("À traiter: 4 images"), "{}", text);
+        assert!(text.contains("⊘ fichier vide: 1"), "{}", text);
+        assert!(text.contains("Pages blanches: 1"), "{}", text);
+        assert!(
+            text.contains("Durée estimée:   42.0 s avec 2 worker(s) (de 4.0 s à 1 min 20 s)"),
+            "{}",
+            text
+        );
+        assert!(text.contains("environ 1.5 Mo"), "{}", text);
+
+        report.estimate = extrapolate(&[], 4, 2);
+        assert!(report.to_string().contains("pas d'estimation de durée"));
+    }
+
+    #[test]
+    fn test_format_duration_and_bytes() {
+        assert_eq!(format_duration(Duration::from_millis(850)), "850 ms");
+        assert_eq!(format_duration(Duration::from_secs(200)), "3 min 20 s");
+        assert_eq!(format_duration(Duration::from_secs(7500)), "2 h 05 min");
+        assert_eq!(format_bytes(512), "512 o");
+        assert_eq!(format_bytes(4300), "4.2 Ko");
+    }
+
+    #[test]
+    fn test_batch_summary_blank_pages() {
+        let mut summary = BatchSummary::new(2, Vec::new());
+        summary.record(Path::new("blanche.png"), Ok(()));
+        summary.record(Path::new("texte.png"), Ok(()));
+        assert!(!summary.to_string().contains("Blanches"));
+
+        summary.blank += 1;
+
+        assert!(
+            summary
+                .to_string()
+                .contains("Blanches:  1 pages (OCR non lancé)")
+        );
+        assert_eq!(summary.exit_code(), EXIT_OK);
+    }
+
+    #[test]
+    fn test_batch_summary_counts_logical_pages() {
+        let mut summary = BatchSummary::new(2, Vec::new());
+        summary.record(Path::new("simple.png"), Ok(()));
+        summary.record(Path::new("double.png"), Ok(()));
+        summary.pages = 2;
+        assert!(!summary.to_string().contains("Pages:"));
+
+        // Une double page coupée compte deux pages logiques
+        let split = OcrOutcome {
+            text: String::new(),
+            warnings: vec![Warning::page_split(800)],
+            preprocessing: None,
+            cache_hit: false,
+        };
+        summary.pages = 1 + split.page_count();
+
+        assert!(
+            summary
+                .to_string()
+                .contains("Pages:     3 pages logiques (1 doubles pages coupées)")
+        );
+    }
+
+    #[test]
+    fn test_cancelled_batch_summary() {
+        struct InstantWorker;
+
+        impl OcrWorker for InstantWorker {
+            fn extract(&mut self, path: &Path) -> Result<OcrOutcome> {
+                Ok(OcrOutcome {
+                    text: path.display().to_string(),
+                    warnings: Vec::new(),
+                    preprocessing: None,
+                    cache_hit: false,
+                })
+            }
+        }
+
+        let pool = OcrEnginePool::with_factory(1, || Ok(InstantWorker)).unwrap();
+        let token = CancellationToken::new();
+        let paths: Vec<PathBuf> = (0..4)
+            .map(|i| PathBuf::from(format!("{}.png", i)))
+            .collect();
+
+        // Interruption pendant la première image
+        let results = pool.batch_extract_cancellable(&paths, &token, |worker, path| {
+            token.cancel();
+            worker.extract(path)
+        });
+
+        let mut summary = BatchSummary::new(paths.len(), Vec::new());
+        for (path, result) in paths.iter().zip(results) {
+            summary.record(path, result.map(|_| ()));
+        }
+
+        assert_eq!(summary.success, 1);
+        assert!(summary.failures.is_empty());
+        assert_eq!(summary.cancelled.len(), 3);
+        assert!(
+            summary
+                .cancelled
+                .iter()
+                .all(|c| c.kind == FailureKind::Cancelled)
+        );
+        assert_eq!(summary.exit_code(), EXIT_PARTIAL_FAILURE);
+        assert!(
+            summary
+                .to_string()
+                .contains("Annulées:  3 images (traitement interrompu)")
+        );
+        assert_eq!(summary.report_entries().len(), 3);
+    }
+
+    #[test]
+    fn test_failure_kind_timeout() {
+        let good = Path::new("resources/simple/img-1.png");
+        let timeout = anyhow::Error::from(OcrError::Timeout(Duration::from_secs(5)));
+
+        assert_eq!(BatchFailure::new(good, &timeout).kind, FailureKind::Timeout);
+    }
+
+    #[test]
+    fn test_error_report_includes_preprocessing_step() {
+        let good = Path::new("resources/simple/img-1.png");
+        let failure = PreprocessingError {
+            step: "binarize".to_string(),
+            index: 3,
+            dimensions: (4032, 3024),
+            reason: "fenêtre plus grande que l'image".to_string(),
+        };
+        let error = anyhow::Error::from(OcrError::Preprocessing(failure))
+            .context("Échec du prétraitement de l'image");
+
+        let failures = [BatchFailure::new(good, &error)];
+        assert_eq!(failures[0].kind, FailureKind::Preprocessing);
+
+        let dir = tempfile::tempdir().unwrap();
+        let report_path = dir.path().join("errors.json");
+        write_error_report(&report_path, &failures).unwrap();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+        assert_eq!(report[0]["kind"], "preprocessing");
+        assert_eq!(report[0]["step"], "binarize");
+        assert_eq!(report[0]["step_index"], 3);
+        assert!(
+            report[0]["error"]
+                .as_str()
+                .unwrap()
+                .contains("étape 3 (binarize) en échec sur une image 4032×3024")
+        );
+
+        // Les autres échecs n'ont pas de champ `step`
+        let timeout = anyhow::Error::from(OcrError::Timeout(Duration::from_secs(5)));
+        let json = serde_json::to_value(BatchFailure::new(good, &timeout)).unwrap();
+        assert!(json.get("step").is_none());
+    }
+
+    #[test]
+    fn test_skip_duplicates_args() {
+        let parse = |extra: &[&str]| {
+            let mut argv = vec!["text-recognition", "scans/", "--batch"];
+            argv.extend_from_slice(extra);
+            Args::try_parse_from(argv)
+        };
+
+        assert_eq!(parse(&[]).unwrap().skip_duplicates, None);
+        assert_eq!(
+            parse(&["--skip-duplicates", "5"]).unwrap().skip_duplicates,
+            Some(5)
+        );
+        let args = parse(&["--skip-duplicates", "5", "--no-skip-duplicates"]).unwrap();
+        assert_eq!(args.skip_duplicates, None);
+        assert!(parse(&["--skip-duplicates", "65"]).is_err());
+        assert!(
+            Args::try_parse_from(["text-recognition", "a.png", "--skip-duplicates", "5"]).is_err(),
+            "--skip-duplicates nécessite --batch"
+        );
+    }
+
+    #[test]
+    fn test_output_template_args() {
+        let args = Args::try_parse_from([
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--output",
+            "results",
+            "--output-template",
+            "{date}/{stem}_{lang}_ocr.txt",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.output_template.as_ref().map(OutputTemplate::as_str),
+            Some("{date}/{stem}_{lang}_ocr.txt")
+        );
+
+        // Variable inconnue refusée dès l'analyse des arguments
+        let error = Args::try_parse_from([
+            "text-recognition",
+            "scans",
+            "--batch",
+            "--output",
+            "results",
+            "--output-template",
+            "{stem}_{langue}.txt",
+        ])
+        .unwrap_err();
+        assert!(error.to_string().contains("{langue}"), "{}", error);
+
+        for extra in [
+            &["--output-template", "../{stem}.txt"][..],
+            &["--flatten", "--output-template", "{stem}.txt"],
+        ] {
+            let mut argv = vec![
+                "text-recognition",
+                "scans",
+                "--batch",
+                "--output",
+                "results",
+            ];
+            argv.extend_from_slice(extra);
+            assert!(Args::try_parse_from(argv).is_err(), "{:?}", extra);
+        }
+        assert!(
+            Args::try_parse_from([
+                "text-recognition",
+                "scans",
+                "--batch",
+                "--output-template",
+                "{stem}.txt"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_templated_output_paths_use_profile_language() {
+        let dir = tempfile::tempdir().unwrap();
+        let scans = dir.path().join("scans");
+        fs::create_dir_all(scans.join("en")).unwrap();
+        let images = vec![scans.join("en/page.png"), scans.join("page.png")];
+        for image in &images {
+            fs::write(image, "octets").unwrap();
+        }
+        let config_path = dir.path().join("profils.toml");
+        fs::write(
+            &config_path,
+            "[profiles.anglais.ocr]\nlanguage = \"eng\"\npage_seg_mode = \"SingleBlock\"\n\
+             dpi = 300\n\n[profiles.anglais.ocr.tesseract_variables]\n\n\
+             [[rules]]\npattern = \"en/**\"\nprofile = \"anglais\"\n",
+        )
+        .unwrap();
+        let args = Args::try_parse_with_sources([
+            "text-recognition",
+            scans.to_str().unwrap(),
+            "--batch",
+            "--output",
+            "results",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--output-template",
+            "{parent}/{index}_{stem}_{lang}_psm{psm}_{hash8}.txt",
+        ])
+        .unwrap();
+        let validated = validate_args(&args).unwrap();
+        let file_config = load_file_config(&args).unwrap();
+        let engine = OcrEngine::new(OcrConfig::default()).unwrap();
+
+        let mappings = templated_output_paths(
+            &validated,
+            &engine,
+            file_config.as_ref(),
+            args.output_template.as_ref().unwrap(),
+            &images,
+            Path::new("results"),
+        )
+        .unwrap();
+
+        // Les deux images ont le même contenu, donc la même empreinte
+        let hash8 = &sha256_file(&images[0]).unwrap()[..8];
+        assert_eq!(
+            mappings[0].output,
+            Path::new("results/en").join(format!("1_page_eng_psm6_{}.txt", hash8))
+        );
+        assert_eq!(
+            mappings[1].output,
+            Path::new("results").join(format!("2_page_fra_psm3_{}.txt", hash8))
+        );
+    }
+
+    #[test]
+    fn test_manifest_lists_every_image_with_its_status() {
+        let images: Vec<PathBuf> = ["vide.png", "a.png", "b.png", "a-bis.png", "c.png"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        let mut summary = BatchSummary::new(
+            images.len(),
+            vec![BatchFailure::skipped(&images[0], &ImageFileError::Empty)],
+        );
+        summary.duplicates = vec![BatchFailure::duplicate(&images[3], &images[1])];
+        summary.outputs = ["a.png", "b.png", "c.png"]
+            .iter()
+            .map(|name| OutputMapping {
+                input: PathBuf::from(name),
+                output: Path::new("out/2024-03-18").join(name.replace(".png", "_ocr.txt")),
+                renamed: false,
+            })
+            .collect();
+        summary.record(&images[1], Ok(()));
+        summary.record(&images[2], Err(anyhow::anyhow!("Tesseract a échoué")));
+        summary.record(&images[4], Err(OcrError::Cancelled.into()));
+        let durations = HashMap::from([
+            (images[1].clone(), Duration::from_millis(1250)),
+            (images[2].clone(), Duration::from_millis(40)),
+        ]);
+
+        let manifest = build_manifest(&images, &summary, &durations);
+
+        let statuses: Vec<ManifestStatus> = manifest.iter().map(|entry| entry.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                ManifestStatus::Skipped,
+                ManifestStatus::Success,
+                ManifestStatus::Failed,
+                ManifestStatus::Duplicate,
+                ManifestStatus::Cancelled,
+            ]
+        );
+        assert_eq!(
+            manifest[1],
+            ManifestEntry {
+                input: images[1].clone(),
+                output: Some(PathBuf::from("out/2024-03-18/a_ocr.txt")),
+                status: ManifestStatus::Success,
+                duration_ms: Some(1250),
+                error: None,
+            }
+        );
+        assert_eq!(manifest[2].output, None);
+        assert_eq!(manifest[2].duration_ms, Some(40));
+        assert_eq!(manifest[2].error.as_deref(), Some("Tesseract a échoué"));
+        assert_eq!(manifest[3].duration_ms, None);
+
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json[0]["status"], "skipped");
+        assert_eq!(json[0]["output"], serde_json::Value::Null);
+        assert_eq!(json[1]["output"], "out/2024-03-18/a_ocr.txt");
+        assert!(json[1].get("error").is_none());
+    }
+
+    #[test]
+    fn test_split_duplicates_marks_duplicates() {
+        let images: Vec<PathBuf> = ["a.png", "b.png", "a-bis.png", "c.png", "a-ter.png"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        let groups = [DuplicateGroup {
+            original: PathBuf::from("a.png"),
+            duplicates: vec![PathBuf::from("a-bis.png"), PathBuf::from("a-ter.png")],
+        }];
+
+        let (remaining, duplicates) = split_duplicates(images.clone(), &groups);
+
+        assert_eq!(
+            remaining,
+            [images[0].clone(), images[1].clone(), images[3].clone()]
+        );
+        assert_eq!(duplicates.len(), 2);
+        assert!(duplicates.iter().all(|d| d.kind == FailureKind::Duplicate));
+        assert_eq!(duplicates[1].path, images[4]);
+        assert_eq!(
+            duplicates[1].duplicate_of.as_deref(),
+            Some(images[0].as_path())
+        );
+
+        // Les doublons n'empêchent pas un code de sortie 0
+        let mut summary = BatchSummary::new(images.len(), Vec::new());
+        summary.duplicates = duplicates;
+        summary.success = remaining.len();
+        assert_eq!(summary.exit_code(), EXIT_OK);
+
+        let display = summary.to_string();
+        assert!(display.contains("Doublons:  2 images"));
+        assert!(display.contains("≡ a-bis.png (doublon de a.png)"));
+
+        let report = serde_json::to_value(summary.report_entries()).unwrap();
+        assert_eq!(report[0]["kind"], "duplicate");
+        assert_eq!(report[0]["duplicate_of"], "a.png");
+        assert!(split_duplicates(images.clone(), &[]).1.is_empty());
+    }
+
+    #[test]
+    fn test_preflight_skips_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let png = |width, height| {
+            let img = image::DynamicImage::ImageLuma8(image::GrayImage::from_pixel(
+                width,
+                height,
+                image::Luma([200]),
+            ));
+            let mut bytes = Vec::new();
+            img.write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+            bytes
+        };
+
+        let valid = dir.path().join("valide.png");
+        let empty = dir.path().join("vide.png");
+        let truncated = dir.path().join("tronquee.png");
+        let tiny = dir.path().join("minuscule.png");
+        fs::write(&valid, png(40, 20)).unwrap();
+        fs::write(&empty, b"").unwrap();
+        fs::write(&truncated, &png(40, 20)[..18]).unwrap();
+        fs::write(&tiny, png(1, 1)).unwrap();
+
+        let files = vec![valid.clone(), empty, truncated, tiny];
+        let (to_process, skipped) = preflight_images(&files);
+
+        assert_eq!(to_process, vec![valid]);
+        assert_eq!(skipped.len(), 3);
+        assert!(skipped.iter().all(|s| s.kind == FailureKind::Skipped));
+        assert_eq!(skipped[0].error, ImageFileError::Empty.to_string());
+        assert_eq!(skipped[1].error, ImageFileError::CorruptHeader.to_string());
+        assert!(skipped[2].error.contains("1×1"));
+
+        // Les fichiers ignorés ne sont pas des erreurs d'OCR, mais l'exécution n'est pas complète
+        assert_eq!(
+            decide_exit_code(1, skipped.len(), files.len()),
+            EXIT_PARTIAL_FAILURE
+        );
+
+        let report_path = dir.path().join("errors.json");
+        write_error_report(&report_path, &skipped).unwrap();
+        let report: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+        assert_eq!(report[0]["kind"], "skipped");
+        assert_eq!(report[0]["error"], "fichier vide");
+    }
 }
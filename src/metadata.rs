@@ -0,0 +1,903 @@
+//! Transcriptions de référence intégrées aux métadonnées des images.
+//!
+//! Pour qu'une image de test et sa transcription ne soient jamais séparées,
+//! la référence peut être rangée dans le fichier image lui-même :
+//!
+//! - PNG : chunk `iTXt` (ou `tEXt`) de clé [`REFERENCE_KEY`] ;
+//! - JPEG : champ EXIF `UserComment`, en UCS-2 (`UNICODE`) ou en UTF-8
+//!   (code `ASCII` ou non défini).
+//!
+//! Le texte doit être de l'UTF-8 valide. Un chunk `tEXt` est en principe en
+//! Latin-1, ce qui ne se distingue pas d'un UTF-8 invalide : il est refusé
+//! avec une erreur de décodage plutôt que converti en texte erroné. Les
+//! chunks compressés (`zTXt`, `iTXt` compressé) ne sont pas pris en charge.
+//!
+//! [`write_embedded_reference()`] réécrit le fichier sans toucher aux
+//! données de l'image (chunks `IDAT`, données compressées JPEG).
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::metadata::{read_embedded_reference, write_embedded_reference};
+//!
+//! let dir = tempfile::tempdir()?;
+//! let path = dir.path().join("facture.png");
+//! image::GrayImage::new(32, 16).save(&path)?;
+//!
+//! assert_eq!(read_embedded_reference(&path)?, None);
+//! write_embedded_reference(&path, "Facture n° 42 — réglée")?;
+//! assert_eq!(read_embedded_reference(&path)?.as_deref(), Some("Facture n° 42 — réglée"));
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::png;
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::Path;
+
+/// Clé du chunk PNG contenant la transcription de référence.
+pub const REFERENCE_KEY: &str = "ocr_reference";
+
+/// Signature d'un fichier PNG.
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+/// Marqueur de début d'un fichier JPEG (SOI).
+const JPEG_SOI: &[u8; 2] = b"\xff\xd8";
+
+/// En-tête du segment APP1 contenant les données EXIF.
+const EXIF_HEADER: &[u8; 6] = b"Exif\0\0";
+
+/// Étiquette TIFF du pointeur vers l'IFD EXIF.
+const TAG_EXIF_IFD: u16 = 0x8769;
+/// Étiquette TIFF du pointeur vers l'IFD GPS.
+const TAG_GPS_IFD: u16 = 0x8825;
+/// Étiquette EXIF du pointeur vers l'IFD d'interopérabilité.
+const TAG_INTEROP_IFD: u16 = 0xA005;
+/// Étiquette EXIF des données propriétaires du fabricant.
+const TAG_MAKER_NOTE: u16 = 0x927C;
+/// Étiquette EXIF du commentaire utilisateur.
+const TAG_USER_COMMENT: u16 = 0x9286;
+
+/// Type TIFF `LONG` (entier non signé de 32 bits).
+const TYPE_LONG: u16 = 4;
+/// Type TIFF `UNDEFINED` (octets bruts).
+const TYPE_UNDEFINED: u16 = 7;
+
+/// Lit la transcription de référence intégrée à une image PNG ou JPEG.
+///
+/// # Arguments
+///
+/// * `path` - Chemin de l'image
+///
+/// # Retour
+///
+/// `None` si l'image ne contient pas de référence (pas de chunk
+/// [`REFERENCE_KEY`], pas de `UserComment` EXIF, ou un commentaire vide).
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le fichier ne peut pas être lu, s'il n'est ni un
+/// PNG ni un JPEG, si ses métadonnées sont mal formées ou si le texte n'est
+/// pas décodable (Latin-1, UCS-2 tronqué, jeu de caractères JIS...).
+pub fn read_embedded_reference(path: &Path) -> Result<Option<String>> {
+    let data =
+        fs::read(path).with_context(|| format!("Impossible de lire '{}'", path.display()))?;
+    let reference = if data.starts_with(PNG_SIGNATURE) {
+        read_png_reference(&data)
+    } else if data.starts_with(JPEG_SOI) {
+        read_jpeg_reference(&data)
+    } else {
+        bail!(
+            "'{}' n'est ni un PNG ni un JPEG : référence intégrée non prise en charge",
+            path.display()
+        );
+    };
+    reference.with_context(|| format!("Référence intégrée illisible dans '{}'", path.display()))
+}
+
+/// Intègre une transcription de référence à une image PNG ou JPEG.
+///
+/// Le fichier est réécrit en place. Une référence existante est remplacée.
+///
+/// - PNG : les chunks de clé [`REFERENCE_KEY`] sont retirés et un chunk
+///   `iTXt` non compressé est ajouté avant `IEND` ; les autres chunks sont
+///   recopiés à l'identique.
+/// - JPEG : le segment EXIF est reconstruit avec un `UserComment` en UCS-2.
+///   Les entrées de l'IFD principal, de l'IFD EXIF et de l'IFD GPS sont
+///   conservées ; la vignette, l'IFD d'interopérabilité et la note du
+///   fabricant (`MakerNote`), dont les décalages internes ne survivraient
+///   pas au déplacement, sont abandonnés.
+///
+/// # Arguments
+///
+/// * `path` - Chemin de l'image
+/// * `text` - Transcription de référence
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le fichier ne peut pas être lu ou écrit, s'il
+/// n'est ni un PNG ni un JPEG, si sa structure est invalide, ou si le texte
+/// ne tient pas dans un segment EXIF (64 Kio).
+pub fn write_embedded_reference(path: &Path, text: &str) -> Result<()> {
+    let data =
+        fs::read(path).with_context(|| format!("Impossible de lire '{}'", path.display()))?;
+    let rewritten = if data.starts_with(PNG_SIGNATURE) {
+        write_png_reference(&data, text)
+    } else if data.starts_with(JPEG_SOI) {
+        write_jpeg_reference(&data, text)
+    } else {
+        bail!(
+            "'{}' n'est ni un PNG ni un JPEG : référence intégrée non prise en charge",
+            path.display()
+        );
+    }
+    .with_context(|| format!("Impossible d'intégrer la référence à '{}'", path.display()))?;
+    fs::write(path, rewritten).with_context(|| format!("Impossible d'écrire '{}'", path.display()))
+}
+
+/// Lit `N` octets à partir de `offset`.
+fn bytes_at<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    offset
+        .checked_add(N)
+        .and_then(|end| data.get(offset..end))
+        .and_then(|slice| slice.try_into().ok())
+        .with_context(|| format!("Données tronquées à l'octet {}", offset))
+}
+
+/// Décode un texte UTF-8 sans remplacer les séquences invalides.
+fn decode_utf8(bytes: &[u8], what: &str) -> Result<String> {
+    String::from_utf8(bytes.to_vec()).map_err(|error| {
+        anyhow::anyhow!(
+            "{} n'est pas de l'UTF-8 valide (octet {} ; texte Latin-1 ?)",
+            what,
+            error.utf8_error().valid_up_to()
+        )
+    })
+}
+
+/// Chunk d'un fichier PNG.
+struct PngChunk<'a> {
+    /// Type du chunk (`IHDR`, `tEXt`...).
+    kind: [u8; 4],
+    /// Données du chunk.
+    data: &'a [u8],
+    /// Chunk complet (longueur, type, données, CRC).
+    raw: &'a [u8],
+}
+
+impl PngChunk<'_> {
+    /// Mot-clé d'un chunk textuel (`tEXt`, `zTXt`, `iTXt`), avant le premier octet nul.
+    fn keyword(&self) -> Option<&[u8]> {
+        if !matches!(&self.kind, b"tEXt" | b"zTXt" | b"iTXt") {
+            return None;
+        }
+        let end = self.data.iter().position(|&byte| byte == 0)?;
+        Some(&self.data[..end])
+    }
+
+    /// Indique si le chunk porte la référence intégrée.
+    fn is_reference(&self) -> bool {
+        self.keyword() == Some(REFERENCE_KEY.as_bytes())
+    }
+
+    /// Vérifie le CRC du chunk.
+    fn check_crc(&self) -> Result<()> {
+        let stored = u32::from_be_bytes(bytes_at(self.raw, self.raw.len() - 4)?);
+        let computed = png::crc32(&self.raw[4..self.raw.len() - 4]);
+        if stored != computed {
+            bail!(
+                "CRC invalide pour le chunk {}",
+                String::from_utf8_lossy(&self.kind)
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Découpe un fichier PNG en chunks, jusqu'à `IEND` inclus.
+fn png_chunks(data: &[u8]) -> Result<Vec<PngChunk<'_>>> {
+    let mut chunks = Vec::new();
+    let mut pos = PNG_SIGNATURE.len();
+    while pos < data.len() {
+        let length = u32::from_be_bytes(bytes_at(data, pos)?) as usize;
+        let kind = bytes_at(data, pos + 4)?;
+        let end = length
+            .checked_add(pos + 12)
+            .filter(|&end| end <= data.len())
+            .with_context(|| format!("Chunk PNG tronqué à l'octet {}", pos))?;
+        chunks.push(PngChunk {
+            kind,
+            data: &data[pos + 8..end - 4],
+            raw: &data[pos..end],
+        });
+        pos = end;
+        if &kind == b"IEND" {
+            break;
+        }
+    }
+    Ok(chunks)
+}
+
+/// Cherche la référence dans les chunks textuels d'un PNG.
+fn read_png_reference(data: &[u8]) -> Result<Option<String>> {
+    for chunk in png_chunks(data)? {
+        if !chunk.is_reference() {
+            continue;
+        }
+        chunk.check_crc()?;
+        let body = &chunk.data[REFERENCE_KEY.len() + 1..];
+        let text = match &chunk.kind {
+            b"tEXt" => decode_utf8(body, "Le chunk tEXt")?,
+            b"iTXt" => decode_utf8(itxt_text(body)?, "Le chunk iTXt")?,
+            _ => bail!("Chunk zTXt compressé non pris en charge"),
+        };
+        return Ok(Some(text));
+    }
+    Ok(None)
+}
+
+/// Texte d'un chunk `iTXt`, après le mot-clé et son octet nul.
+///
+/// Les champs suivants sont l'indicateur et la méthode de compression, la
+/// langue et le mot-clé traduit (terminés par un octet nul).
+fn itxt_text(body: &[u8]) -> Result<&[u8]> {
+    let [compressed, _method] = bytes_at(body, 0).context("Chunk iTXt mal formé")?;
+    if compressed != 0 {
+        bail!("Chunk iTXt compressé non pris en charge");
+    }
+    let mut rest = &body[2..];
+    for field in ["langue", "mot-clé traduit"] {
+        let end = rest
+            .iter()
+            .position(|&byte| byte == 0)
+            .with_context(|| format!("Chunk iTXt mal formé : {} non terminé", field))?;
+        rest = &rest[end + 1..];
+    }
+    Ok(rest)
+}
+
+/// Réécrit un PNG avec un chunk `iTXt` de référence avant `IEND`.
+fn write_png_reference(data: &[u8], text: &str) -> Result<Vec<u8>> {
+    let chunks = png_chunks(data)?;
+    if chunks.last().is_none_or(|chunk| &chunk.kind != b"IEND") {
+        bail!("PNG sans chunk IEND");
+    }
+
+    let mut itxt = Vec::with_capacity(REFERENCE_KEY.len() + 5 + text.len());
+    itxt.extend_from_slice(REFERENCE_KEY.as_bytes());
+    // Séparateur, pas de compression, méthode 0, langue et mot-clé traduit vides
+    itxt.extend_from_slice(&[0, 0, 0, 0, 0]);
+    itxt.extend_from_slice(text.as_bytes());
+
+    let mut output = Vec::with_capacity(data.len() + itxt.len() + 12);
+    output.extend_from_slice(PNG_SIGNATURE);
+    for chunk in &chunks {
+        if chunk.is_reference() {
+            continue;
+        }
+        if &chunk.kind == b"IEND" {
+            output.extend_from_slice(&png::chunk(b"iTXt", &itxt)?);
+        }
+        output.extend_from_slice(chunk.raw);
+    }
+    Ok(output)
+}
+
+/// Segment d'un fichier JPEG situé avant les données compressées.
+struct JpegSegment<'a> {
+    /// Marqueur (second octet, ex. `0xE1` pour APP1).
+    marker: u8,
+    /// Contenu du segment, après sa longueur.
+    payload: &'a [u8],
+    /// Segment complet (marqueur, longueur, contenu).
+    raw: &'a [u8],
+}
+
+impl JpegSegment<'_> {
+    /// Données TIFF d'un segment APP1 EXIF.
+    fn exif(&self) -> Option<&[u8]> {
+        if self.marker != 0xE1 {
+            return None;
+        }
+        self.payload.strip_prefix(EXIF_HEADER.as_slice())
+    }
+}
+
+/// Découpe l'en-tête d'un JPEG en segments, jusqu'au début du scan (SOS).
+///
+/// # Retour
+///
+/// Les segments et le reste du fichier à partir du marqueur SOS.
+fn jpeg_segments(data: &[u8]) -> Result<(Vec<JpegSegment<'_>>, &[u8])> {
+    let mut segments = Vec::new();
+    let mut pos = JPEG_SOI.len();
+    loop {
+        let [prefix, marker] = bytes_at(data, pos).context("JPEG tronqué avant les données")?;
+        if prefix != 0xFF {
+            bail!("Marqueur JPEG attendu à l'octet {}", pos);
+        }
+        // Octets de remplissage entre deux segments
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        // Début du scan (SOS) ou fin d'image (EOI) : le reste est recopié tel quel
+        if marker == 0xDA || marker == 0xD9 {
+            return Ok((segments, &data[pos..]));
+        }
+        let length = usize::from(u16::from_be_bytes(bytes_at(data, pos + 2)?));
+        let end = pos + 2 + length;
+        if length < 2 || end > data.len() {
+            bail!("Segment JPEG tronqué à l'octet {}", pos);
+        }
+        segments.push(JpegSegment {
+            marker,
+            payload: &data[pos + 4..end],
+            raw: &data[pos..end],
+        });
+        pos = end;
+    }
+}
+
+/// Cherche la référence dans le `UserComment` EXIF d'un JPEG.
+fn read_jpeg_reference(data: &[u8]) -> Result<Option<String>> {
+    let (segments, _) = jpeg_segments(data)?;
+    let Some(tiff) = segments.iter().find_map(JpegSegment::exif) else {
+        return Ok(None);
+    };
+    let tiff = Tiff::parse(tiff)?;
+    let ifd0 = tiff.entries(tiff.first_ifd()?)?;
+    let Some(exif_offset) = pointer(&ifd0, TAG_EXIF_IFD, tiff.order)? else {
+        return Ok(None);
+    };
+    let exif = tiff.entries(exif_offset)?;
+    let Some(comment) = exif.iter().find(|entry| entry.tag == TAG_USER_COMMENT) else {
+        return Ok(None);
+    };
+    let text = decode_user_comment(&comment.value, tiff.order)?;
+    Ok((!text.trim().is_empty()).then_some(text))
+}
+
+/// Décode un `UserComment` EXIF : code de jeu de caractères sur 8 octets, puis le texte.
+///
+/// `UNICODE` est de l'UCS-2 (UTF-16) dans l'ordre des octets du TIFF, sauf
+/// marque d'ordre (BOM) en tête ; `ASCII` et le code non défini (huit
+/// octets nuls) sont lus en UTF-8 strict. Les octets nuls finaux sont retirés.
+fn decode_user_comment(value: &[u8], order: ByteOrder) -> Result<String> {
+    let Some((code, body)) = value.split_first_chunk::<8>() else {
+        bail!("UserComment trop court ({} octets)", value.len());
+    };
+    let text = match code {
+        b"UNICODE\0" => {
+            if body.len() % 2 != 0 {
+                bail!(
+                    "UserComment UCS-2 de longueur impaire ({} octets)",
+                    body.len()
+                );
+            }
+            let (order, body) = match body {
+                [0xFE, 0xFF, rest @ ..] => (ByteOrder::Big, rest),
+                [0xFF, 0xFE, rest @ ..] => (ByteOrder::Little, rest),
+                _ => (order, body),
+            };
+            let units = body
+                .chunks_exact(2)
+                .map(|unit| order.u16([unit[0], unit[1]]));
+            char::decode_utf16(units)
+                .collect::<Result<String, _>>()
+                .context("UserComment UCS-2 invalide (surrogate isolé)")?
+        }
+        b"ASCII\0\0\0" | [0, 0, 0, 0, 0, 0, 0, 0] => decode_utf8(body, "Le UserComment")?,
+        _ => bail!(
+            "Jeu de caractères du UserComment non pris en charge : {:?}",
+            String::from_utf8_lossy(code).trim_end_matches('\0')
+        ),
+    };
+    Ok(text.trim_end_matches('\0').to_string())
+}
+
+/// Réécrit un JPEG avec un segment EXIF contenant la référence.
+fn write_jpeg_reference(data: &[u8], text: &str) -> Result<Vec<u8>> {
+    let (segments, scan) = jpeg_segments(data)?;
+
+    let existing = segments
+        .iter()
+        .find_map(JpegSegment::exif)
+        .map(Tiff::parse)
+        .transpose()?;
+    let mut exif = match &existing {
+        Some(tiff) => ExifDirectories::read(tiff)?,
+        None => ExifDirectories::default(),
+    };
+    let order = existing
+        .as_ref()
+        .map_or(ByteOrder::Little, |tiff| tiff.order);
+
+    let mut comment = b"UNICODE\0".to_vec();
+    for unit in text.encode_utf16() {
+        order.put_u16(&mut comment, unit);
+    }
+    exif.exif.retain(|entry| entry.tag != TAG_USER_COMMENT);
+    exif.exif.push(IfdEntry {
+        tag: TAG_USER_COMMENT,
+        kind: TYPE_UNDEFINED,
+        count: u32::try_from(comment.len()).context("Référence trop longue")?,
+        value: comment,
+    });
+
+    let tiff = exif.to_tiff(order);
+    let length = 2 + EXIF_HEADER.len() + tiff.len();
+    let Ok(length) = u16::try_from(length) else {
+        bail!(
+            "Référence trop longue pour un segment EXIF ({} octets, maximum {})",
+            length,
+            u16::MAX
+        );
+    };
+    let mut app1 = vec![0xFF, 0xE1];
+    app1.extend_from_slice(&length.to_be_bytes());
+    app1.extend_from_slice(EXIF_HEADER);
+    app1.extend_from_slice(&tiff);
+
+    // Le segment EXIF suit SOI, ou l'en-tête JFIF (APP0) s'il est en tête
+    let mut output = Vec::with_capacity(data.len() + app1.len());
+    output.extend_from_slice(JPEG_SOI);
+    let mut pending = Some(app1);
+    for segment in segments.iter().filter(|segment| segment.exif().is_none()) {
+        if segment.marker != 0xE0
+            && let Some(app1) = pending.take()
+        {
+            output.extend_from_slice(&app1);
+        }
+        output.extend_from_slice(segment.raw);
+    }
+    if let Some(app1) = pending {
+        output.extend_from_slice(&app1);
+    }
+    output.extend_from_slice(scan);
+    Ok(output)
+}
+
+/// Ordre des octets d'un fichier TIFF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ByteOrder {
+    /// `II` : petit-boutiste.
+    Little,
+    /// `MM` : gros-boutiste.
+    Big,
+}
+
+impl ByteOrder {
+    /// Décode un entier de 16 bits.
+    fn u16(self, bytes: [u8; 2]) -> u16 {
+        match self {
+            ByteOrder::Little => u16::from_le_bytes(bytes),
+            ByteOrder::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    /// Décode un entier de 32 bits.
+    fn u32(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            ByteOrder::Little => u32::from_le_bytes(bytes),
+            ByteOrder::Big => u32::from_be_bytes(bytes),
+        }
+    }
+
+    /// Ajoute un entier de 16 bits.
+    fn put_u16(self, output: &mut Vec<u8>, value: u16) {
+        match self {
+            ByteOrder::Little => output.extend_from_slice(&value.to_le_bytes()),
+            ByteOrder::Big => output.extend_from_slice(&value.to_be_bytes()),
+        }
+    }
+
+    /// Ajoute un entier de 32 bits.
+    fn put_u32(self, output: &mut Vec<u8>, value: u32) {
+        match self {
+            ByteOrder::Little => output.extend_from_slice(&value.to_le_bytes()),
+            ByteOrder::Big => output.extend_from_slice(&value.to_be_bytes()),
+        }
+    }
+}
+
+/// Données TIFF d'un segment EXIF.
+struct Tiff<'a> {
+    /// Données, à partir de l'en-tête `II*\0` ou `MM\0*`.
+    data: &'a [u8],
+    /// Ordre des octets.
+    order: ByteOrder,
+}
+
+/// Entrée d'un IFD, avec sa valeur brute (dans l'ordre des octets du TIFF).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IfdEntry {
+    /// Étiquette.
+    tag: u16,
+    /// Type TIFF de la valeur.
+    kind: u16,
+    /// Nombre de valeurs.
+    count: u32,
+    /// Valeur brute (`count` fois la taille du type).
+    value: Vec<u8>,
+}
+
+impl<'a> Tiff<'a> {
+    /// Lit l'en-tête TIFF.
+    fn parse(data: &'a [u8]) -> Result<Self> {
+        let order = match data.get(..4) {
+            Some(b"II*\0") => ByteOrder::Little,
+            Some(b"MM\0*") => ByteOrder::Big,
+            _ => bail!("En-tête TIFF du segment EXIF invalide"),
+        };
+        Ok(Self { data, order })
+    }
+
+    /// Décalage du premier IFD.
+    fn first_ifd(&self) -> Result<usize> {
+        Ok(self.order.u32(bytes_at(self.data, 4)?) as usize)
+    }
+
+    /// Entrées de l'IFD situé à `offset` ; les entrées de type inconnu sont ignorées.
+    fn entries(&self, offset: usize) -> Result<Vec<IfdEntry>> {
+        let count = self.order.u16(bytes_at(self.data, offset)?);
+        let mut entries = Vec::with_capacity(usize::from(count));
+        for index in 0..usize::from(count) {
+            let base = offset + 2 + 12 * index;
+            let tag = self.order.u16(bytes_at(self.data, base)?);
+            let kind = self.order.u16(bytes_at(self.data, base + 2)?);
+            let count = self.order.u32(bytes_at(self.data, base + 4)?);
+            let Some(size) = type_size(kind) else {
+                continue;
+            };
+            let length = (count as usize)
+                .checked_mul(size)
+                .with_context(|| format!("Entrée EXIF 0x{:04X} trop longue", tag))?;
+            let start = if length <= 4 {
+                base + 8
+            } else {
+                self.order.u32(bytes_at(self.data, base + 8)?) as usize
+            };
+            let value = start
+                .checked_add(length)
+                .and_then(|end| self.data.get(start..end))
+                .with_context(|| format!("Entrée EXIF 0x{:04X} hors du segment", tag))?;
+            entries.push(IfdEntry {
+                tag,
+                kind,
+                count,
+                value: value.to_vec(),
+            });
+        }
+        Ok(entries)
+    }
+}
+
+/// Taille en octets d'une valeur du type TIFF `kind`.
+fn type_size(kind: u16) -> Option<usize> {
+    match kind {
+        1 | 2 | 6 | 7 => Some(1),
+        3 | 8 => Some(2),
+        4 | 9 | 11 | 13 => Some(4),
+        5 | 10 | 12 => Some(8),
+        _ => None,
+    }
+}
+
+/// Décalage désigné par l'entrée pointeur `tag`, si elle est présente.
+fn pointer(entries: &[IfdEntry], tag: u16, order: ByteOrder) -> Result<Option<usize>> {
+    entries
+        .iter()
+        .find(|entry| entry.tag == tag)
+        .map(|entry| Ok(order.u32(bytes_at(&entry.value, 0)?) as usize))
+        .transpose()
+}
+
+/// IFD conservés lors de la réécriture d'un segment EXIF.
+#[derive(Debug, Default)]
+struct ExifDirectories {
+    /// IFD principal, sans les pointeurs vers les sous-IFD.
+    main: Vec<IfdEntry>,
+    /// IFD EXIF, sans le pointeur d'interopérabilité ni la note du fabricant.
+    exif: Vec<IfdEntry>,
+    /// IFD GPS.
+    gps: Vec<IfdEntry>,
+}
+
+impl ExifDirectories {
+    /// Lit les IFD d'un segment EXIF existant.
+    fn read(tiff: &Tiff<'_>) -> Result<Self> {
+        let main = tiff.entries(tiff.first_ifd()?)?;
+        let sub_ifd = |tag| -> Result<Vec<IfdEntry>> {
+            match pointer(&main, tag, tiff.order)? {
+                Some(offset) => tiff.entries(offset),
+                None => Ok(Vec::new()),
+            }
+        };
+        let mut exif = sub_ifd(TAG_EXIF_IFD)?;
+        let gps = sub_ifd(TAG_GPS_IFD)?;
+        exif.retain(|entry| entry.tag != TAG_INTEROP_IFD && entry.tag != TAG_MAKER_NOTE);
+        let main = main
+            .into_iter()
+            .filter(|entry| entry.tag != TAG_EXIF_IFD && entry.tag != TAG_GPS_IFD)
+            .collect();
+        Ok(Self { main, exif, gps })
+    }
+
+    /// Sérialise les IFD en données TIFF (en-tête, IFD principal, EXIF, GPS).
+    fn to_tiff(&self, order: ByteOrder) -> Vec<u8> {
+        let mut main = self.main.clone();
+        let mut exif = self.exif.clone();
+        exif.sort_by_key(|entry| entry.tag);
+        let pointer = |tag| IfdEntry {
+            tag,
+            kind: TYPE_LONG,
+            count: 1,
+            value: vec![0; 4],
+        };
+        main.push(pointer(TAG_EXIF_IFD));
+        if !self.gps.is_empty() {
+            main.push(pointer(TAG_GPS_IFD));
+        }
+        main.sort_by_key(|entry| entry.tag);
+
+        // Les pointeurs ne changent pas la taille des IFD : on place d'abord, on écrit ensuite
+        let exif_offset = 8 + ifd_len(&main);
+        let gps_offset = exif_offset + ifd_len(&exif);
+        for entry in &mut main {
+            let offset = match entry.tag {
+                TAG_EXIF_IFD => exif_offset,
+                TAG_GPS_IFD => gps_offset,
+                _ => continue,
+            };
+            entry.value.clear();
+            order.put_u32(&mut entry.value, offset as u32);
+        }
+
+        let mut tiff = match order {
+            ByteOrder::Little => b"II*\0".to_vec(),
+            ByteOrder::Big => b"MM\0*".to_vec(),
+        };
+        order.put_u32(&mut tiff, 8);
+        write_ifd(&mut tiff, &main, order);
+        write_ifd(&mut tiff, &exif, order);
+        if !self.gps.is_empty() {
+            write_ifd(&mut tiff, &self.gps, order);
+        }
+        tiff
+    }
+}
+
+/// Taille d'un IFD sérialisé, valeurs longues (alignées sur 2 octets) comprises.
+fn ifd_len(entries: &[IfdEntry]) -> usize {
+    let values: usize = entries
+        .iter()
+        .filter(|entry| entry.value.len() > 4)
+        .map(|entry| entry.value.len().next_multiple_of(2))
+        .sum();
+    2 + 12 * entries.len() + 4 + values
+}
+
+/// Ajoute un IFD (sans IFD suivant) suivi de ses valeurs longues.
+fn write_ifd(tiff: &mut Vec<u8>, entries: &[IfdEntry], order: ByteOrder) {
+    let mut value_offset = tiff.len() + 2 + 12 * entries.len() + 4;
+    let mut values = Vec::new();
+    order.put_u16(tiff, entries.len() as u16);
+    for entry in entries {
+        order.put_u16(tiff, entry.tag);
+        order.put_u16(tiff, entry.kind);
+        order.put_u32(tiff, entry.count);
+        if entry.value.len() <= 4 {
+            let mut inline = [0u8; 4];
+            inline[..entry.value.len()].copy_from_slice(&entry.value);
+            tiff.extend_from_slice(&inline);
+        } else {
+            order.put_u32(tiff, value_offset as u32);
+            values.extend_from_slice(&entry.value);
+            if entry.value.len() % 2 != 0 {
+                values.push(0);
+            }
+            value_offset += entry.value.len().next_multiple_of(2);
+        }
+    }
+    order.put_u32(tiff, 0);
+    tiff.extend_from_slice(&values);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma, RgbImage};
+
+    const FRENCH: &str = "Référence : « Noël à l'évêché », 12,50 € — ça marche.\nDeuxième ligne";
+
+    fn png_file(dir: &Path) -> std::path::PathBuf {
+        let path = dir.join("page.png");
+        GrayImage::from_fn(40, 20, |x, y| Luma([((x * 7 + y * 3) % 256) as u8]))
+            .save(&path)
+            .unwrap();
+        path
+    }
+
+    fn jpeg_file(dir: &Path) -> std::path::PathBuf {
+        let path = dir.join("page.jpg");
+        RgbImage::from_fn(40, 20, |x, _| image::Rgb([(x * 6) as u8, 128, 200]))
+            .save(&path)
+            .unwrap();
+        path
+    }
+
+    /// Insère un chunk avant `IEND`, sans autre contrôle.
+    fn insert_png_chunk(path: &Path, kind: &[u8; 4], data: &[u8]) {
+        let png = fs::read(path).unwrap();
+        let iend = png.len() - 12;
+        let mut output = png[..iend].to_vec();
+        output.extend_from_slice(&png::chunk(kind, data).unwrap());
+        output.extend_from_slice(&png[iend..]);
+        fs::write(path, output).unwrap();
+    }
+
+    #[test]
+    fn test_png_round_trip_preserves_pixels() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = png_file(dir.path());
+        let before = image::open(&path).unwrap();
+
+        assert_eq!(read_embedded_reference(&path).unwrap(), None);
+        write_embedded_reference(&path, "ancienne").unwrap();
+        write_embedded_reference(&path, FRENCH).unwrap();
+
+        assert_eq!(
+            read_embedded_reference(&path).unwrap().as_deref(),
+            Some(FRENCH)
+        );
+        assert_eq!(image::open(&path).unwrap(), before);
+        let png = fs::read(&path).unwrap();
+        let references = png_chunks(&png)
+            .unwrap()
+            .iter()
+            .filter(|chunk| chunk.is_reference())
+            .count();
+        assert_eq!(references, 1);
+    }
+
+    #[test]
+    fn test_jpeg_round_trip_keeps_image_decodable() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = jpeg_file(dir.path());
+        let before = image::open(&path).unwrap();
+
+        assert_eq!(read_embedded_reference(&path).unwrap(), None);
+        write_embedded_reference(&path, "ancienne").unwrap();
+        write_embedded_reference(&path, FRENCH).unwrap();
+
+        assert_eq!(
+            read_embedded_reference(&path).unwrap().as_deref(),
+            Some(FRENCH)
+        );
+        assert_eq!(image::open(&path).unwrap(), before);
+        let jpeg = fs::read(&path).unwrap();
+        let (segments, _) = jpeg_segments(&jpeg).unwrap();
+        assert_eq!(segments.iter().filter(|s| s.exif().is_some()).count(), 1);
+        // L'en-tête JFIF reste en tête
+        assert_eq!(segments[0].marker, 0xE0);
+    }
+
+    #[test]
+    fn test_jpeg_rewrite_keeps_existing_exif_entries() {
+        let orientation = IfdEntry {
+            tag: 0x0112,
+            kind: 3,
+            count: 1,
+            value: vec![0, 6],
+        };
+        let make = IfdEntry {
+            tag: 0x010F,
+            kind: 2,
+            count: 9,
+            value: b"Fabricant".to_vec(),
+        };
+        let existing = ExifDirectories {
+            main: vec![orientation.clone(), make.clone()],
+            exif: Vec::new(),
+            gps: Vec::new(),
+        };
+        let mut app1 = EXIF_HEADER.to_vec();
+        app1.extend_from_slice(&existing.to_tiff(ByteOrder::Big));
+        let dir = tempfile::tempdir().unwrap();
+        let path = jpeg_file(dir.path());
+        let jpeg = fs::read(&path).unwrap();
+        let mut with_exif = JPEG_SOI.to_vec();
+        with_exif.extend_from_slice(&[0xFF, 0xE1]);
+        with_exif.extend_from_slice(&((app1.len() + 2) as u16).to_be_bytes());
+        with_exif.extend_from_slice(&app1);
+        with_exif.extend_from_slice(&jpeg[2..]);
+        fs::write(&path, with_exif).unwrap();
+
+        write_embedded_reference(&path, FRENCH).unwrap();
+
+        assert_eq!(
+            read_embedded_reference(&path).unwrap().as_deref(),
+            Some(FRENCH)
+        );
+        let jpeg = fs::read(&path).unwrap();
+        let (segments, _) = jpeg_segments(&jpeg).unwrap();
+        let tiff = Tiff::parse(segments.iter().find_map(JpegSegment::exif).unwrap()).unwrap();
+        assert_eq!(tiff.order, ByteOrder::Big);
+        let main = tiff.entries(tiff.first_ifd().unwrap()).unwrap();
+        assert!(main.contains(&orientation));
+        assert!(main.contains(&make));
+    }
+
+    #[test]
+    fn test_latin1_text_chunk_is_a_decoding_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = png_file(dir.path());
+        insert_png_chunk(&path, b"tEXt", b"ocr_reference\0R\xe9f\xe9rence");
+
+        let error = read_embedded_reference(&path).unwrap_err();
+        assert!(format!("{:#}", error).contains("n'est pas de l'UTF-8 valide"));
+    }
+
+    #[test]
+    fn test_malformed_itxt_chunk_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = png_file(dir.path());
+        insert_png_chunk(&path, b"iTXt", b"ocr_reference\0\0\0fr");
+
+        let error = read_embedded_reference(&path).unwrap_err();
+        assert!(format!("{:#}", error).contains("mal formé"));
+    }
+
+    #[test]
+    fn test_utf8_text_chunk_and_other_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = png_file(dir.path());
+        insert_png_chunk(&path, b"tEXt", b"Comment\0autre chose");
+        assert_eq!(read_embedded_reference(&path).unwrap(), None);
+
+        insert_png_chunk(&path, b"tEXt", "ocr_reference\0Été".as_bytes());
+        assert_eq!(
+            read_embedded_reference(&path).unwrap().as_deref(),
+            Some("Été")
+        );
+    }
+
+    #[test]
+    fn test_decode_user_comment_variants() {
+        let mut ucs2_be = b"UNICODE\0".to_vec();
+        for unit in "Noël".encode_utf16() {
+            ucs2_be.extend_from_slice(&unit.to_be_bytes());
+        }
+        assert_eq!(
+            decode_user_comment(&ucs2_be, ByteOrder::Big).unwrap(),
+            "Noël"
+        );
+
+        let mut bom_le = b"UNICODE\0\xff\xfe".to_vec();
+        for unit in "Noël".encode_utf16() {
+            bom_le.extend_from_slice(&unit.to_le_bytes());
+        }
+        assert_eq!(
+            decode_user_comment(&bom_le, ByteOrder::Big).unwrap(),
+            "Noël"
+        );
+
+        let utf8 = [b"ASCII\0\0\0".as_slice(), "éà\0\0".as_bytes()].concat();
+        assert_eq!(decode_user_comment(&utf8, ByteOrder::Little).unwrap(), "éà");
+
+        assert!(decode_user_comment(b"UNICODE\0\x41", ByteOrder::Little).is_err());
+        assert!(decode_user_comment(b"ASCII\0\0\0\xe9t\xe9", ByteOrder::Little).is_err());
+        assert!(decode_user_comment(b"JIS\0\0\0\0\0abc", ByteOrder::Little).is_err());
+    }
+
+    #[test]
+    fn test_unsupported_format_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("page.bmp");
+        GrayImage::new(8, 8).save(&path).unwrap();
+
+        assert!(read_embedded_reference(&path).is_err());
+        assert!(write_embedded_reference(&path, "texte").is_err());
+    }
+}
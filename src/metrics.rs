@@ -6,13 +6,57 @@
 //! Les métriques principales incluent :
 //! - **CER** (Character Error Rate) : Taux d'erreur au niveau des caractères
 //! - **WER** (Word Error Rate) : Taux d'erreur au niveau des mots
-//! - **Distance de Levenshtein** : Nombre minimal d'opérations pour transformer un texte en un autre
+//! - **Distance de Levenshtein** : Nombre minimal d'opérations pour transformer un texte en un autre,
+//!   avec une variante bit-parallèle ([`levenshtein_distance_fast()`]) pour les textes longs
+//! - **Confusions de caractères** : Substitutions les plus fréquentes (ex: `O` lu `0`),
+//!   agrégeables sur un corpus via [`ConfusionMatrix`]
+//!
+//! [`fuzzy_find()`] recherche une phrase connue dans un texte bruité, en
+//! tolérant un nombre borné d'erreurs.
+//!
+//! Pour des références très longues (un livre entier), [`StreamingComparer`]
+//! consomme le texte OCR par morceaux avec une mémoire bornée.
+//!
+//! Le sous-module [`spatial`] évalue la détection des mots (précision, rappel,
+//! F1) par rapport à des annotations positionnées.
+//!
+//! Le sous-module [`unordered`] apparie les paragraphes (ou les lignes) des
+//! deux textes indépendamment de leur ordre, pour qu'une colonne lue avant
+//! l'autre ne ruine pas le CER.
+//!
+//! [`generate_html_report()`] produit un rapport HTML autonome à partager :
+//! synthèse des métriques et alignement des deux textes côte à côte.
+//!
+//! Le sous-module [`sink`] ajoute les métriques d'un lot à un fichier CSV ou
+//! JSONL partagé, sous verrou, sans corrompre les lignes des autres écrivains.
 //!
 //! Ces métriques permettent de :
 //! - Mesurer l'efficacité de différentes configurations OCR
 //! - Comparer l'impact des prétraitements
 //! - Identifier les configurations optimales pour différents types d'images
 
+use crate::postprocess::truncate_chars;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+mod html;
+pub mod sink;
+pub mod spatial;
+pub mod unordered;
+
+pub use html::{DEFAULT_HTML_CONTEXT, HtmlReportOptions, generate_html_report};
+
+/// Caractère sentinelle représentant l'absence de caractère dans une confusion.
+///
+/// Utilisé comme `found` pour un caractère manquant dans le texte OCR, et
+/// comme `expected` pour un caractère ajouté à tort.
+pub const MISSING_CHAR: char = '∅';
+
 /// Type d'erreur identifié lors de la comparaison de textes.
 ///
 /// Cette enum catégorise les différentes erreurs qui peuvent survenir
@@ -163,7 +207,7 @@ impl TextError {
 /// # Exemples
 ///
 /// ```
-/// use text_recognition::metrics::OcrMetrics;
+/// use text_recognition::metrics::{OcrMetrics, TokenizerKind};
 ///
 /// let metrics = OcrMetrics {
 ///     cer: 0.05,
@@ -174,11 +218,12 @@ impl TextError {
 ///     reference_word_count: 12,
 ///     ocr_word_count: 12,
 ///     exact_match: false,
+///     tokenizer: TokenizerKind::Whitespace,
 /// };
 ///
 /// println!("CER: {:.2}%", metrics.cer * 100.0);
 /// ```
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OcrMetrics {
     /// Character Error Rate : taux d'erreur au niveau des caractères (0.0 = parfait, 1.0 = 100% d'erreurs).
     pub cer: f64,
@@ -204,6 +249,10 @@ pub struct OcrMetrics {
 
     /// Indique si le texte OCR correspond exactement au texte de référence.
     pub exact_match: bool,
+
+    /// Découpage en mots utilisé pour le WER et les nombres de mots.
+    #[serde(default)]
+    pub tokenizer: TokenizerKind,
 }
 
 impl OcrMetrics {
@@ -230,6 +279,7 @@ impl OcrMetrics {
             reference_word_count: 0,
             ocr_word_count: 0,
             exact_match: true,
+            tokenizer: TokenizerKind::Whitespace,
         }
     }
 
@@ -238,7 +288,7 @@ impl OcrMetrics {
     /// # Exemples
     ///
     /// ```
-    /// use text_recognition::metrics::OcrMetrics;
+    /// use text_recognition::metrics::{OcrMetrics, TokenizerKind};
     ///
     /// let metrics = OcrMetrics {
     ///     cer: 0.05,
@@ -249,6 +299,7 @@ impl OcrMetrics {
     ///     reference_word_count: 12,
     ///     ocr_word_count: 12,
     ///     exact_match: false,
+    ///     tokenizer: TokenizerKind::Whitespace,
     /// };
     ///
     /// assert_eq!(metrics.accuracy(), 0.95);
@@ -260,7 +311,8 @@ impl OcrMetrics {
     /// Exporte les métriques vers une ligne CSV avec en-têtes.
     ///
     /// Génère une chaîne CSV représentant ces métriques, avec en-têtes optionnels.
-    /// Format CSV standard avec virgules comme séparateurs.
+    /// Format CSV standard avec virgules comme séparateurs et point décimal
+    /// (options par défaut de [`to_csv_with()`](Self::to_csv_with)).
     ///
     /// # Arguments
     ///
@@ -284,7 +336,7 @@ impl OcrMetrics {
     /// # Exemples
     ///
     /// ```
-    /// use text_recognition::metrics::OcrMetrics;
+    /// use text_recognition::metrics::{OcrMetrics, TokenizerKind};
     /// use std::collections::HashMap;
     ///
     /// let metrics = OcrMetrics {
@@ -296,6 +348,7 @@ impl OcrMetrics {
     ///     reference_word_count: 12,
     ///     ocr_word_count: 12,
     ///     exact_match: false,
+    ///     tokenizer: TokenizerKind::Whitespace,
     /// };
     ///
     /// // Sans métadonnées
@@ -314,59 +367,227 @@ impl OcrMetrics {
         include_headers: bool,
         metadata: Option<&std::collections::HashMap<String, String>>,
     ) -> String {
-        let mut result = String::new();
+        self.to_csv_with(include_headers, metadata, &CsvOptions::default())
+    }
+
+    /// Exporte les métriques vers une ligne CSV avec un formatage choisi.
+    ///
+    /// Mêmes colonnes que [`to_csv()`](Self::to_csv). Le séparateur décimal
+    /// et la précision ne s'appliquent qu'aux colonnes décimales (CER, WER,
+    /// Accuracy), jamais aux métadonnées ; les champs qui contiennent le
+    /// délimiteur choisi, un guillemet ou un saut de ligne sont mis entre
+    /// guillemets (voir [`CsvOptions::escape_field()`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `include_headers` - Si `true`, inclut une ligne d'en-têtes avant les données
+    /// * `metadata` - Métadonnées optionnelles à ajouter (image, config, etc.)
+    /// * `options` - Délimiteur, séparateur décimal, précision, guillemets, fin de ligne
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use text_recognition::metrics::{CsvOptions, OcrMetrics};
+    ///
+    /// let metrics = OcrMetrics { cer: 0.05, ..OcrMetrics::zero() };
+    /// let options = CsvOptions {
+    ///     delimiter: ';',
+    ///     decimal_separator: ',',
+    ///     precision: 2,
+    ///     ..CsvOptions::default()
+    /// };
+    ///
+    /// let csv = metrics.to_csv_with(false, None, &options);
+    /// assert_eq!(csv, "0,05;0,00;0;0;0;0;0;true;0,95\n");
+    /// ```
+    pub fn to_csv_with(
+        &self,
+        include_headers: bool,
+        metadata: Option<&std::collections::HashMap<String, String>>,
+        options: &CsvOptions,
+    ) -> String {
+        self.csv_rows(include_headers, metadata, options, &[], [])
+    }
+
+    /// Lignes CSV de [`to_csv_with()`](Self::to_csv_with), suivies de
+    /// colonnes supplémentaires déjà formatées.
+    fn csv_rows<const N: usize>(
+        &self,
+        include_headers: bool,
+        metadata: Option<&std::collections::HashMap<String, String>>,
+        options: &CsvOptions,
+        extra_headers: &[&str; N],
+        extra_values: [String; N],
+    ) -> String {
+        // Ordre alphabétique des métadonnées pour cohérence
+        let mut keys: Vec<&String> = metadata
+            .map(|meta| meta.keys().collect())
+            .unwrap_or_default();
+        keys.sort();
 
-        // Construire les en-têtes
+        let mut result = String::new();
         if include_headers {
-            // En-têtes de métadonnées (si présentes)
-            if let Some(meta) = metadata {
-                let mut keys: Vec<_> = meta.keys().collect();
-                keys.sort(); // Ordre alphabétique pour cohérence
-                for key in &keys {
-                    result.push_str(key);
-                    result.push(',');
-                }
-            }
+            let headers = keys
+                .iter()
+                .map(|key| key.as_str())
+                .chain(METRICS_CSV_HEADERS)
+                .chain(extra_headers.iter().copied())
+                .map(|header| options.escape_field(header));
+            result.push_str(&options.format_row(headers));
+        }
 
-            // En-têtes des métriques
-            result.push_str("CER,WER,Levenshtein_Distance,Reference_Char_Count,OCR_Char_Count,");
-            result.push_str("Reference_Word_Count,OCR_Word_Count,Exact_Match,Accuracy\n");
-        }
-
-        // Construire la ligne de données
-        // Métadonnées (si présentes)
-        if let Some(meta) = metadata {
-            let mut keys: Vec<_> = meta.keys().collect();
-            keys.sort();
-            for key in &keys {
-                // Échapper les valeurs qui contiennent des virgules ou guillemets
-                let value = &meta[*key];
-                if value.contains(',') || value.contains('"') || value.contains('\n') {
-                    result.push('"');
-                    result.push_str(&value.replace('"', "\"\""));
-                    result.push('"');
-                } else {
-                    result.push_str(value);
-                }
-                result.push(',');
+        let values = keys
+            .iter()
+            .filter_map(|key| metadata.and_then(|meta| meta.get(*key)))
+            .map(|value| options.escape_field(value))
+            .chain([
+                options.format_decimal(self.cer),
+                options.format_decimal(self.wer),
+                options.escape_field(&self.levenshtein_distance.to_string()),
+                options.escape_field(&self.reference_char_count.to_string()),
+                options.escape_field(&self.ocr_char_count.to_string()),
+                options.escape_field(&self.reference_word_count.to_string()),
+                options.escape_field(&self.ocr_word_count.to_string()),
+                options.escape_field(&self.exact_match.to_string()),
+                options.format_decimal(self.accuracy()),
+            ])
+            .chain(extra_values);
+        result.push_str(&options.format_row(values));
+        result
+    }
+}
+
+/// En-têtes des colonnes de métriques de [`OcrMetrics::to_csv()`].
+const METRICS_CSV_HEADERS: [&str; 9] = [
+    "CER",
+    "WER",
+    "Levenshtein_Distance",
+    "Reference_Char_Count",
+    "OCR_Char_Count",
+    "Reference_Word_Count",
+    "OCR_Word_Count",
+    "Exact_Match",
+    "Accuracy",
+];
+
+/// Fin de ligne d'un export CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvLineEnding {
+    /// `\n` (Unix).
+    #[default]
+    Lf,
+    /// `\r\n` (Windows, RFC 4180).
+    CrLf,
+}
+
+impl CsvLineEnding {
+    /// Caractères de fin de ligne.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CsvLineEnding::Lf => "\n",
+            CsvLineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// Formatage d'un export CSV (voir [`OcrMetrics::to_csv_with()`]).
+///
+/// Les options par défaut produisent le format historique : virgule,
+/// point décimal, 6 décimales, guillemets seulement si nécessaire, `\n`.
+/// Un tableur configuré en français attend plutôt `;` et `,` décimale.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::CsvOptions;
+///
+/// let options = CsvOptions { delimiter: ';', ..CsvOptions::default() };
+/// assert_eq!(options.escape_field("a;b"), "\"a;b\"");
+/// assert_eq!(options.escape_field("a,b"), "a,b");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Délimiteur de champs.
+    pub delimiter: char,
+    /// Séparateur décimal des colonnes décimales.
+    pub decimal_separator: char,
+    /// Nombre de décimales des colonnes décimales (valeurs arrondies).
+    pub precision: usize,
+    /// Mettre tous les champs entre guillemets, et pas seulement ceux qui l'exigent.
+    pub quote_all: bool,
+    /// Fin de ligne.
+    pub line_ending: CsvLineEnding,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            decimal_separator: '.',
+            precision: 6,
+            quote_all: false,
+            line_ending: CsvLineEnding::Lf,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Vérifie que le délimiteur et le séparateur décimal sont utilisables.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si l'un d'eux est un guillemet ou un saut de
+    /// ligne, qui rendraient le fichier illisible.
+    pub fn validate(&self) -> Result<()> {
+        for (name, c) in [
+            ("délimiteur", self.delimiter),
+            ("séparateur décimal", self.decimal_separator),
+        ] {
+            if matches!(c, '"' | '\n' | '\r') {
+                anyhow::bail!("Caractère {:?} invalide comme {} CSV", c, name);
             }
         }
+        Ok(())
+    }
 
-        // Métriques
-        result.push_str(&format!(
-            "{:.6},{:.6},{},{},{},{},{},{},{:.6}\n",
-            self.cer,
-            self.wer,
-            self.levenshtein_distance,
-            self.reference_char_count,
-            self.ocr_char_count,
-            self.reference_word_count,
-            self.ocr_word_count,
-            self.exact_match,
-            self.accuracy()
-        ));
+    /// Échappe un champ : entre guillemets (doublés à l'intérieur) s'il
+    /// contient le délimiteur, un guillemet ou un saut de ligne, ou si
+    /// `quote_all` est activé.
+    pub fn escape_field(&self, value: &str) -> String {
+        let needs_quotes = self.quote_all
+            || value
+                .chars()
+                .any(|c| c == self.delimiter || matches!(c, '"' | '\n' | '\r'));
+        if needs_quotes {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
 
-        result
+    /// Formate une valeur décimale avec `precision` décimales et le séparateur choisi.
+    ///
+    /// Le champ est échappé comme les autres (utile si le séparateur décimal
+    /// est aussi le délimiteur).
+    pub fn format_decimal(&self, value: f64) -> String {
+        let formatted = format!("{:.*}", self.precision, value);
+        let localized = if self.decimal_separator == '.' {
+            formatted
+        } else {
+            formatted.replace('.', &self.decimal_separator.to_string())
+        };
+        self.escape_field(&localized)
+    }
+
+    /// Assemble des champs déjà échappés en une ligne terminée par la fin de ligne.
+    pub fn format_row<I>(&self, fields: I) -> String
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let fields: Vec<String> = fields.into_iter().collect();
+        let mut row = fields.join(&self.delimiter.to_string());
+        row.push_str(self.line_ending.as_str());
+        row
     }
 }
 
@@ -395,8 +616,10 @@ impl Default for OcrMetrics {
 ///
 /// # Algorithme
 ///
-/// Utilise la programmation dynamique avec une matrice de taille (n+1) × (m+1)
-/// où n et m sont les longueurs des deux chaînes.
+/// Utilise la programmation dynamique sur une matrice de taille (n+1) × (m+1)
+/// où n et m sont les longueurs des deux chaînes, dont seules deux lignes
+/// sont conservées. Pour des textes longs, [`levenshtein_distance_fast()`]
+/// donne le même résultat bien plus vite.
 ///
 /// # Exemples
 ///
@@ -422,7 +645,7 @@ impl Default for OcrMetrics {
 /// # Complexité
 ///
 /// - **Temps** : O(n × m) où n et m sont les longueurs des chaînes
-/// - **Espace** : O(n × m)
+/// - **Espace** : O(m)
 pub fn levenshtein_distance(source: &str, target: &str) -> usize {
     let source_chars: Vec<char> = source.chars().collect();
     let target_chars: Vec<char> = target.chars().collect();
@@ -438,23 +661,14 @@ pub fn levenshtein_distance(source: &str, target: &str) -> usize {
         return source_len;
     }
 
-    // Créer une matrice (source_len + 1) × (target_len + 1)
-    let mut matrix = vec![vec![0usize; target_len + 1]; source_len + 1];
-
-    // Initialiser la première colonne (suppressions depuis source)
-    #[allow(clippy::needless_range_loop)]
-    for i in 0..=source_len {
-        matrix[i][0] = i;
-    }
-
-    // Initialiser la première ligne (insertions pour atteindre target)
-    #[allow(clippy::needless_range_loop)]
-    for j in 0..=target_len {
-        matrix[0][j] = j;
-    }
+    // Ligne précédente de la matrice, initialisée aux insertions pour atteindre target
+    let mut previous: Vec<usize> = (0..=target_len).collect();
+    let mut current = vec![0usize; target_len + 1];
 
-    // Remplir la matrice
+    // Remplir la matrice ligne par ligne
     for i in 1..=source_len {
+        // Première colonne : suppressions depuis source
+        current[0] = i;
         for j in 1..=target_len {
             // Coût de substitution : 0 si les caractères sont identiques, 1 sinon
             let substitution_cost = if source_chars[i - 1] == target_chars[j - 1] {
@@ -463,24 +677,311 @@ pub fn levenshtein_distance(source: &str, target: &str) -> usize {
                 1
             };
 
-            matrix[i][j] = std::cmp::min(
+            current[j] = std::cmp::min(
                 std::cmp::min(
-                    matrix[i - 1][j] + 1, // Suppression
-                    matrix[i][j - 1] + 1, // Insertion
+                    previous[j] + 1,    // Suppression
+                    current[j - 1] + 1, // Insertion
                 ),
-                matrix[i - 1][j - 1] + substitution_cost, // Substitution
+                previous[j - 1] + substitution_cost, // Substitution
             );
         }
+        std::mem::swap(&mut previous, &mut current);
     }
 
-    // La distance est dans la dernière cellule
-    matrix[source_len][target_len]
+    // La distance est dans la dernière cellule de la dernière ligne calculée
+    previous[target_len]
+}
+
+/// Longueur (en caractères) au-delà de laquelle [`calculate_cer()`] utilise
+/// [`levenshtein_distance_fast()`].
+///
+/// En dessous, la programmation dynamique classique est aussi rapide et
+/// évite la construction des masques de bits.
+pub const FAST_LEVENSHTEIN_THRESHOLD: usize = 256;
+
+/// Nombre de lignes de la matrice traitées par un bloc de bits.
+const MYERS_BLOCK_BITS: usize = u64::BITS as usize;
+
+/// Distance de Levenshtein par l'algorithme bit-parallèle de Myers.
+///
+/// Donne exactement le même résultat que [`levenshtein_distance()`], mais
+/// calcule 64 cellules de la matrice par opération : la plus courte des deux
+/// chaînes (le motif) est découpée en blocs de 64 caractères (extension par
+/// blocs de Hyyrö), chaque colonne de la matrice étant représentée par ses
+/// variations verticales sous forme de masques de bits.
+///
+/// Les caractères sont d'abord associés à des indices (un masque par
+/// caractère présent dans le motif), ce qui traite correctement les
+/// caractères Unicode multi-octets. Le préfixe et le suffixe communs sont
+/// retirés avant le calcul.
+///
+/// # Arguments
+///
+/// * `source` - La chaîne source (texte OCR)
+/// * `target` - La chaîne cible (texte de référence)
+///
+/// # Retour
+///
+/// Le nombre minimal d'opérations nécessaires pour transformer `source` en `target`.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::{levenshtein_distance, levenshtein_distance_fast};
+///
+/// assert_eq!(levenshtein_distance_fast("kitten", "sitting"), 3);
+///
+/// let reference = "Le café coûte 2,50 € ".repeat(100);
+/// let ocr = reference.replace("coûte", "coute");
+/// assert_eq!(
+///     levenshtein_distance_fast(&ocr, &reference),
+///     levenshtein_distance(&ocr, &reference)
+/// );
+/// ```
+///
+/// # Complexité
+///
+/// - **Temps** : O(⌈m / 64⌉ × n) où m est la longueur de la plus courte chaîne
+/// - **Espace** : O(⌈m / 64⌉ × σ) où σ est le nombre de caractères distincts du motif
+pub fn levenshtein_distance_fast(source: &str, target: &str) -> usize {
+    let source: Vec<char> = source.chars().collect();
+    let target: Vec<char> = target.chars().collect();
+
+    // Le préfixe et le suffixe communs ne changent pas la distance
+    let prefix = source
+        .iter()
+        .zip(&target)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (source, target) = (&source[prefix..], &target[prefix..]);
+    let suffix = source
+        .iter()
+        .rev()
+        .zip(target.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let source = &source[..source.len() - suffix];
+    let target = &target[..target.len() - suffix];
+
+    // La distance est symétrique : le motif (découpé en blocs) est le plus court
+    let (pattern, text) = if source.len() <= target.len() {
+        (source, target)
+    } else {
+        (target, source)
+    };
+    if pattern.is_empty() {
+        return text.len();
+    }
+    myers_distance(pattern, text)
+}
+
+/// Cœur de [`levenshtein_distance_fast()`] pour un motif non vide.
+fn myers_distance(pattern: &[char], text: &[char]) -> usize {
+    let blocks = pattern.len().div_ceil(MYERS_BLOCK_BITS);
+
+    // Masques de correspondance : bit i du caractère c à 1 si pattern[i] == c,
+    // rangés par indice de caractère puis par bloc
+    let mut alphabet: HashMap<char, usize> = HashMap::new();
+    let mut match_masks: Vec<u64> = Vec::new();
+    for (row, &c) in pattern.iter().enumerate() {
+        let next_index = alphabet.len();
+        let index = *alphabet.entry(c).or_insert(next_index);
+        if index == next_index {
+            match_masks.resize(match_masks.len() + blocks, 0);
+        }
+        match_masks[index * blocks + row / MYERS_BLOCK_BITS] |= 1 << (row % MYERS_BLOCK_BITS);
+    }
+    let no_match = vec![0u64; blocks];
+
+    // Variations verticales positives (+1) et négatives (-1) de la colonne courante
+    let mut positive = vec![u64::MAX; blocks];
+    let mut negative = vec![0u64; blocks];
+    // Seule la dernière ligne du dernier bloc compte pour la distance
+    let last_row_bit = 1u64 << ((pattern.len() - 1) % MYERS_BLOCK_BITS);
+
+    let mut distance = pattern.len();
+    for c in text {
+        let masks = match alphabet.get(c) {
+            Some(&index) => &match_masks[index * blocks..(index + 1) * blocks],
+            None => &no_match[..],
+        };
+        // Première ligne de la matrice : D[0][j] = j, variation horizontale +1
+        let mut carry = 1;
+        for (block, (positive, negative)) in positive.iter_mut().zip(&mut negative).enumerate() {
+            let high_bit = if block + 1 == blocks {
+                last_row_bit
+            } else {
+                1 << (MYERS_BLOCK_BITS - 1)
+            };
+            carry = advance_myers_block(positive, negative, masks[block], carry, high_bit);
+        }
+        match carry {
+            1 => distance += 1,
+            -1 => distance -= 1,
+            _ => {}
+        }
+    }
+    distance
+}
+
+/// Avance un bloc de 64 lignes d'une colonne de la matrice (Hyyrö, 2003).
+///
+/// `carry` est la variation horizontale (-1, 0 ou +1) sur la ligne qui précède
+/// le bloc ; la valeur retournée est celle de la ligne `high_bit` du bloc,
+/// transmise au bloc suivant.
+fn advance_myers_block(
+    positive: &mut u64,
+    negative: &mut u64,
+    matches: u64,
+    carry: i8,
+    high_bit: u64,
+) -> i8 {
+    let carry_negative = u64::from(carry < 0);
+    let carry_positive = u64::from(carry > 0);
+
+    let vertical = matches | *negative;
+    let matches = matches | carry_negative;
+    let horizontal = ((matches & *positive).wrapping_add(*positive) ^ *positive) | matches;
+    let horizontal_positive = *negative | !(horizontal | *positive);
+    let horizontal_negative = *positive & horizontal;
+
+    let carry_out = if horizontal_positive & high_bit != 0 {
+        1
+    } else if horizontal_negative & high_bit != 0 {
+        -1
+    } else {
+        0
+    };
+
+    let horizontal_positive = (horizontal_positive << 1) | carry_positive;
+    let horizontal_negative = (horizontal_negative << 1) | carry_negative;
+    *positive = horizontal_negative | !(vertical | horizontal_positive);
+    *negative = horizontal_positive & vertical;
+    carry_out
+}
+
+/// Occurrence approchée d'un motif dans un texte, retournée par [`fuzzy_find()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FuzzyMatch {
+    /// Position du premier caractère de l'occurrence (en caractères, à partir de 0).
+    pub start: usize,
+    /// Position qui suit le dernier caractère de l'occurrence (exclue).
+    pub end: usize,
+    /// Distance de Levenshtein entre l'occurrence et le motif.
+    pub distance: usize,
+    /// Texte de l'occurrence.
+    pub text: String,
+}
+
+/// Recherche les occurrences approchées de `needle` dans `haystack`.
+///
+/// Implémente l'algorithme de Sellers : la programmation dynamique de la
+/// distance de Levenshtein, dont la première ligne est nulle pour qu'une
+/// occurrence puisse commencer n'importe où. Chaque fin de texte dont la
+/// distance ne dépasse pas `max_distance` donne une occurrence candidate ;
+/// à égalité de coût, l'alignement retenu est le plus court.
+///
+/// Les candidates qui se chevauchent sont dédoublonnées : seule la
+/// meilleure (distance minimale, puis la première) est conservée, ce qui
+/// ne garde que les minima locaux. À distance et début égaux, l'occurrence
+/// dont la longueur est la plus proche de celle du motif est préférée.
+///
+/// # Arguments
+///
+/// * `haystack` - Texte dans lequel chercher (texte OCR)
+/// * `needle` - Motif recherché
+/// * `max_distance` - Nombre maximal d'erreurs (substitutions, insertions, suppressions)
+///
+/// # Retour
+///
+/// Les occurrences triées par distance puis par position (vide si `needle`
+/// est vide).
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::fuzzy_find;
+///
+/// let matches = fuzzy_find("Voir les Conditions génerales de vente", "générales", 1);
+///
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!((matches[0].start, matches[0].end), (20, 29));
+/// assert_eq!(matches[0].distance, 1);
+/// assert_eq!(matches[0].text, "génerales");
+/// ```
+///
+/// # Complexité
+///
+/// - **Temps** : O(n × m) où n et m sont les longueurs du texte et du motif
+/// - **Espace** : O(m) pour la programmation dynamique
+pub fn fuzzy_find(haystack: &str, needle: &str, max_distance: usize) -> Vec<FuzzyMatch> {
+    let needle: Vec<char> = needle.chars().collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let offsets: Vec<usize> = haystack
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(haystack.len()))
+        .collect();
+
+    // Colonne j : (distance, début de l'alignement) du préfixe i du motif
+    // aligné sur un texte qui se termine à la position j
+    let mut previous: Vec<(usize, usize)> = (0..=needle.len()).map(|i| (i, 0)).collect();
+    let mut candidates = Vec::new();
+    for (j, c) in haystack.chars().enumerate() {
+        let mut column = Vec::with_capacity(needle.len() + 1);
+        column.push((0, j + 1));
+        for (i, &expected) in needle.iter().enumerate() {
+            let substitution_cost = usize::from(expected != c);
+            let (diagonal, diagonal_start) = previous[i];
+            let (left, left_start) = previous[i + 1];
+            let (up, up_start) = column[i];
+            // Coût minimal, puis début le plus tardif (occurrence la plus courte)
+            let cell = [
+                (diagonal + substitution_cost, diagonal_start),
+                (left + 1, left_start),
+                (up + 1, up_start),
+            ]
+            .into_iter()
+            .min_by_key(|&(cost, start)| (cost, std::cmp::Reverse(start)))
+            .unwrap_or((diagonal + substitution_cost, diagonal_start));
+            column.push(cell);
+        }
+
+        let (distance, start) = column[needle.len()];
+        if distance <= max_distance && start <= j {
+            candidates.push((distance, start, j + 1));
+        }
+        previous = column;
+    }
+
+    // À distance et début égaux, la longueur la plus proche de celle du motif
+    // l'emporte ("tota1" plutôt que "tota" pour "total")
+    candidates.sort_unstable_by_key(|&(distance, start, end)| {
+        (distance, start, (end - start).abs_diff(needle.len()), end)
+    });
+    let mut matches: Vec<FuzzyMatch> = Vec::new();
+    for (distance, start, end) in candidates {
+        if matches.iter().any(|m| start < m.end && m.start < end) {
+            continue;
+        }
+        matches.push(FuzzyMatch {
+            start,
+            end,
+            distance,
+            text: haystack[offsets[start]..offsets[end]].to_string(),
+        });
+    }
+    matches
 }
 
 /// Calcule le CER (Character Error Rate) entre le texte OCR et le texte de référence.
 ///
 /// Le CER est le taux d'erreur au niveau des caractères, calculé comme le rapport
 /// entre la distance de Levenshtein et le nombre de caractères dans le texte de référence.
+/// Quand les deux textes dépassent [`FAST_LEVENSHTEIN_THRESHOLD`] caractères, la
+/// distance est calculée par [`levenshtein_distance_fast()`] (résultat identique).
 ///
 /// **Formule** : CER = distance_levenshtein / nombre_caractères_référence
 ///
@@ -528,10 +1029,184 @@ pub fn calculate_cer(ocr_text: &str, reference_text: &str) -> f64 {
         return if ocr_len == 0 { 0.0 } else { 1.0 };
     }
 
-    let distance = levenshtein_distance(ocr_text, reference_text);
+    let distance = if reference_len > FAST_LEVENSHTEIN_THRESHOLD
+        && ocr_text.chars().count() > FAST_LEVENSHTEIN_THRESHOLD
+    {
+        levenshtein_distance_fast(ocr_text, reference_text)
+    } else {
+        levenshtein_distance(ocr_text, reference_text)
+    };
     distance as f64 / reference_len as f64
 }
 
+/// Découpage d'un texte en mots pour le calcul du WER.
+///
+/// - [`Tokenizer::Whitespace`] (par défaut) : séquences de caractères non
+///   blancs, comportement historique de [`calculate_wer()`] ;
+/// - [`Tokenizer::UnicodeWords`] : suites de lettres et de chiffres, la
+///   ponctuation servant de séparateur. Les élisions sont découpées
+///   (`l'école` donne `l` et `école`, comme `l' école`), les nombres
+///   décimaux conservés (`12,50`) et chaque idéogramme ou kana hiragana est un
+///   mot, le chinois et le japonais s'écrivant sans espaces ;
+/// - [`Tokenizer::Custom`] : chaque correspondance de l'expression régulière
+///   est un mot.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::Tokenizer;
+///
+/// assert_eq!(Tokenizer::Whitespace.tokenize("l'école"), ["l'école"]);
+/// assert_eq!(Tokenizer::UnicodeWords.tokenize("l'école"), ["l", "école"]);
+///
+/// let tokenizer = Tokenizer::from_name("regex:[0-9]+")?;
+/// assert_eq!(tokenizer.tokenize("Total 12,50"), ["12", "50"]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub enum Tokenizer {
+    /// Séparation sur les espaces blancs.
+    #[default]
+    Whitespace,
+    /// Mots Unicode : lettres et chiffres, idéogrammes isolés.
+    UnicodeWords,
+    /// Mots définis par une expression régulière.
+    Custom(Regex),
+}
+
+/// Nature du découpage en mots utilisé pour des métriques (voir [`Tokenizer`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenizerKind {
+    /// Séparation sur les espaces blancs.
+    #[default]
+    Whitespace,
+    /// Mots Unicode.
+    #[serde(rename = "unicode")]
+    UnicodeWords,
+    /// Expression régulière personnalisée.
+    Custom,
+}
+
+impl TokenizerKind {
+    /// Retourne le nom du découpage (`whitespace`, `unicode` ou `custom`).
+    pub fn name(self) -> &'static str {
+        match self {
+            TokenizerKind::Whitespace => "whitespace",
+            TokenizerKind::UnicodeWords => "unicode",
+            TokenizerKind::Custom => "custom",
+        }
+    }
+}
+
+impl Tokenizer {
+    /// Crée un découpage à partir de son nom.
+    ///
+    /// Noms acceptés : `whitespace`, `unicode` et `regex:MOTIF`.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le nom est inconnu ou si le motif est une
+    /// expression régulière invalide.
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "whitespace" => Ok(Tokenizer::Whitespace),
+            "unicode" => Ok(Tokenizer::UnicodeWords),
+            _ => match name.strip_prefix("regex:") {
+                Some(pattern) => {
+                    let regex = Regex::new(pattern).with_context(|| {
+                        format!("Expression régulière de découpage invalide '{}'", pattern)
+                    })?;
+                    Ok(Tokenizer::Custom(regex))
+                }
+                None => anyhow::bail!(
+                    "Découpage en mots inconnu '{}'. Utilisez 'whitespace', 'unicode' ou 'regex:MOTIF'",
+                    name
+                ),
+            },
+        }
+    }
+
+    /// Retourne la nature du découpage, enregistrée dans [`OcrMetrics`].
+    pub fn kind(&self) -> TokenizerKind {
+        match self {
+            Tokenizer::Whitespace => TokenizerKind::Whitespace,
+            Tokenizer::UnicodeWords => TokenizerKind::UnicodeWords,
+            Tokenizer::Custom(_) => TokenizerKind::Custom,
+        }
+    }
+
+    /// Découpe un texte en mots.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Texte à découper
+    pub fn tokenize<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        match self {
+            Tokenizer::Whitespace => text.split_whitespace().collect(),
+            Tokenizer::UnicodeWords => unicode_words(text),
+            Tokenizer::Custom(regex) => regex
+                .find_iter(text)
+                .map(|m| m.as_str())
+                .filter(|word| !word.is_empty())
+                .collect(),
+        }
+    }
+}
+
+/// Découpe un texte en mots Unicode (voir [`Tokenizer::UnicodeWords`]).
+fn unicode_words(text: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut words = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (index, &(offset, c)) in chars.iter().enumerate() {
+        if is_ideographic(c) {
+            if let Some(begin) = start.take() {
+                words.push(&text[begin..offset]);
+            }
+            words.push(&text[offset..offset + c.len_utf8()]);
+            continue;
+        }
+
+        // Séparateur décimal entre deux chiffres : "12,50", "3.14"
+        let decimal_separator = matches!(c, ',' | '.')
+            && start.is_some()
+            && index > 0
+            && chars[index - 1].1.is_numeric()
+            && chars
+                .get(index + 1)
+                .is_some_and(|(_, next)| next.is_numeric());
+
+        if is_word_char(c) || decimal_separator {
+            start.get_or_insert(offset);
+        } else if let Some(begin) = start.take() {
+            words.push(&text[begin..offset]);
+        }
+    }
+    if let Some(begin) = start {
+        words.push(&text[begin..]);
+    }
+
+    words
+}
+
+/// Lettre, chiffre ou diacritique combinant.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || ('\u{300}'..='\u{36F}').contains(&c)
+}
+
+/// Idéogramme CJC ou kana hiragana, chacun formant un mot.
+fn is_ideographic(c: char) -> bool {
+    matches!(c as u32,
+        // Hiragana
+        0x3040..=0x309F
+        // Idéogrammes unifiés, extension A et idéogrammes de compatibilité
+        | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF
+        // Extensions B et suivantes
+        | 0x20000..=0x3134F)
+}
+
 /// Calcule le WER (Word Error Rate) entre le texte OCR et le texte de référence.
 ///
 /// Le WER est le taux d'erreur au niveau des mots, calculé comme le rapport
@@ -541,7 +1216,8 @@ pub fn calculate_cer(ocr_text: &str, reference_text: &str) -> f64 {
 /// **Formule** : WER = distance_levenshtein_mots / nombre_mots_référence
 ///
 /// Les mots sont définis comme des séquences de caractères non-blancs séparées
-/// par des espaces blancs.
+/// par des espaces blancs (voir [`calculate_wer_with_tokenizer()`] pour un
+/// autre découpage).
 ///
 /// # Arguments
 ///
@@ -590,9 +1266,35 @@ pub fn calculate_cer(ocr_text: &str, reference_text: &str) -> f64 {
 /// donc même une petite différence dans un mot (ex: "hello" vs "helo")
 /// compte comme une erreur complète.
 pub fn calculate_wer(ocr_text: &str, reference_text: &str) -> f64 {
-    // Diviser en mots (séquences non-blanches)
-    let reference_words: Vec<&str> = reference_text.split_whitespace().collect();
-    let ocr_words: Vec<&str> = ocr_text.split_whitespace().collect();
+    calculate_wer_with_tokenizer(ocr_text, reference_text, &Tokenizer::Whitespace)
+}
+
+/// Calcule le WER avec un découpage en mots donné.
+///
+/// # Arguments
+///
+/// * `ocr_text` - Le texte extrait par OCR
+/// * `reference_text` - Le texte de référence attendu
+/// * `tokenizer` - Découpage des deux textes en mots
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::{Tokenizer, calculate_wer_with_tokenizer};
+///
+/// // Élision séparée par erreur : une seule différence d'espacement
+/// let ocr = "l' école";
+/// let reference = "l'école";
+/// assert!(calculate_wer_with_tokenizer(ocr, reference, &Tokenizer::Whitespace) > 0.0);
+/// assert_eq!(calculate_wer_with_tokenizer(ocr, reference, &Tokenizer::UnicodeWords), 0.0);
+/// ```
+pub fn calculate_wer_with_tokenizer(
+    ocr_text: &str,
+    reference_text: &str,
+    tokenizer: &Tokenizer,
+) -> f64 {
+    let reference_words = tokenizer.tokenize(reference_text);
+    let ocr_words = tokenizer.tokenize(ocr_text);
 
     let reference_word_count = reference_words.len();
 
@@ -668,11 +1370,12 @@ fn word_levenshtein_distance(source: &[&str], target: &[&str]) -> usize {
     matrix[source_len][target_len]
 }
 
-/// Compare un résultat OCR avec un texte de référence et calcule toutes les métriques.
+/// Aligne le texte OCR sur la référence et retourne les erreurs de caractères.
 ///
-/// Cette fonction effectue une analyse complète de la qualité d'un résultat OCR
-/// en calculant le CER, le WER, la distance de Levenshtein, et en comptant les
-/// caractères et mots dans les deux textes.
+/// L'alignement est obtenu en remontant la matrice de Levenshtein : chaque
+/// opération du chemin optimal devient une [`TextError::Substitution`],
+/// [`TextError::Deletion`] ou [`TextError::Insertion`]. En cas d'égalité,
+/// la substitution est préférée à la suppression, puis à l'insertion.
 ///
 /// # Arguments
 ///
@@ -681,12 +1384,622 @@ fn word_levenshtein_distance(source: &[&str], target: &[&str]) -> usize {
 ///
 /// # Retour
 ///
-/// Une structure `OcrMetrics` contenant toutes les métriques calculées :
-/// - `cer` : Character Error Rate
-/// - `wer` : Word Error Rate
-/// - `levenshtein_distance` : Distance de Levenshtein au niveau des caractères
-/// - `reference_char_count` : Nombre de caractères dans la référence
-/// - `ocr_char_count` : Nombre de caractères dans le texte OCR
+/// Les erreurs dans l'ordre du texte. Le nombre d'erreurs est égal à la
+/// distance de Levenshtein.
+///
+/// # Exemples
+///
+/// ```
+/// use text_recognition::metrics::{TextError, character_errors};
+///
+/// let errors = character_errors("chot", "chat");
+/// assert_eq!(
+///     errors,
+///     vec![TextError::Substitution { position: 2, expected: 'a', found: 'o' }]
+/// );
+/// ```
+pub fn character_errors(ocr_text: &str, reference_text: &str) -> Vec<TextError> {
+    let ocr_chars: Vec<char> = ocr_text.chars().collect();
+    let reference_chars: Vec<char> = reference_text.chars().collect();
+
+    // Positions dans la référence (substitutions, suppressions) ou dans le texte OCR (insertions)
+    let (mut ocr_position, mut reference_position) = (0, 0);
+    let mut errors = Vec::new();
+    for step in align_characters(&ocr_chars, &reference_chars) {
+        match step {
+            CharAlignment::Equal(_) => {}
+            CharAlignment::Substitution { expected, found } => {
+                errors.push(TextError::Substitution {
+                    position: reference_position,
+                    expected,
+                    found,
+                });
+            }
+            CharAlignment::Deletion(expected) => errors.push(TextError::Deletion {
+                position: reference_position,
+                expected,
+            }),
+            CharAlignment::Insertion(found) => errors.push(TextError::Insertion {
+                position: ocr_position,
+                found,
+            }),
+        }
+        ocr_position += usize::from(step.ocr_char().is_some());
+        reference_position += usize::from(step.reference_char().is_some());
+    }
+    errors
+}
+
+/// Étape de l'alignement caractère par caractère (voir [`align_characters()`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CharAlignment {
+    /// Caractère identique dans les deux textes.
+    Equal(char),
+    /// Caractère de la référence lu comme un autre.
+    Substitution {
+        /// Caractère de la référence.
+        expected: char,
+        /// Caractère lu par l'OCR.
+        found: char,
+    },
+    /// Caractère de la référence absent du texte OCR.
+    Deletion(char),
+    /// Caractère du texte OCR absent de la référence.
+    Insertion(char),
+}
+
+impl CharAlignment {
+    /// Caractère de la référence couvert par cette étape.
+    pub(crate) fn reference_char(self) -> Option<char> {
+        match self {
+            CharAlignment::Equal(c)
+            | CharAlignment::Substitution { expected: c, .. }
+            | CharAlignment::Deletion(c) => Some(c),
+            CharAlignment::Insertion(_) => None,
+        }
+    }
+
+    /// Caractère du texte OCR couvert par cette étape.
+    pub(crate) fn ocr_char(self) -> Option<char> {
+        match self {
+            CharAlignment::Equal(c)
+            | CharAlignment::Substitution { found: c, .. }
+            | CharAlignment::Insertion(c) => Some(c),
+            CharAlignment::Deletion(_) => None,
+        }
+    }
+}
+
+/// Alignement complet du texte OCR sur la référence, caractères identiques compris.
+///
+/// Obtenu en remontant la matrice de Levenshtein ; en cas d'égalité, la
+/// substitution est préférée à la suppression, puis à l'insertion (voir
+/// [`character_errors()`]).
+pub(crate) fn align_characters(ocr_chars: &[char], reference_chars: &[char]) -> Vec<CharAlignment> {
+    let ocr_len = ocr_chars.len();
+    let reference_len = reference_chars.len();
+
+    // Matrice de Levenshtein complète (nécessaire pour la remontée)
+    let mut matrix = vec![vec![0usize; reference_len + 1]; ocr_len + 1];
+
+    #[allow(clippy::needless_range_loop)]
+    for i in 0..=ocr_len {
+        matrix[i][0] = i;
+    }
+
+    #[allow(clippy::needless_range_loop)]
+    for j in 0..=reference_len {
+        matrix[0][j] = j;
+    }
+
+    for i in 1..=ocr_len {
+        for j in 1..=reference_len {
+            let substitution_cost = usize::from(ocr_chars[i - 1] != reference_chars[j - 1]);
+
+            matrix[i][j] = std::cmp::min(
+                std::cmp::min(matrix[i - 1][j] + 1, matrix[i][j - 1] + 1),
+                matrix[i - 1][j - 1] + substitution_cost,
+            );
+        }
+    }
+
+    // Remonter le chemin optimal depuis la dernière cellule
+    let mut steps = Vec::with_capacity(ocr_len.max(reference_len));
+    let (mut i, mut j) = (ocr_len, reference_len);
+
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 {
+            let (found, expected) = (ocr_chars[i - 1], reference_chars[j - 1]);
+            let substitution_cost = usize::from(found != expected);
+            if matrix[i][j] == matrix[i - 1][j - 1] + substitution_cost {
+                steps.push(if substitution_cost == 1 {
+                    CharAlignment::Substitution { expected, found }
+                } else {
+                    CharAlignment::Equal(found)
+                });
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+
+        if j > 0 && (i == 0 || matrix[i][j] == matrix[i][j - 1] + 1) {
+            // Caractère de la référence absent du texte OCR
+            steps.push(CharAlignment::Deletion(reference_chars[j - 1]));
+            j -= 1;
+        } else {
+            // Caractère du texte OCR absent de la référence
+            steps.push(CharAlignment::Insertion(ocr_chars[i - 1]));
+            i -= 1;
+        }
+    }
+
+    steps.reverse();
+    steps
+}
+
+/// Opération d'un alignement mot à mot entre le texte OCR et la référence.
+///
+/// Retournée par [`word_diff()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordDiff {
+    /// Mot identique dans les deux textes.
+    Equal(String),
+    /// Mot de la référence absent du texte OCR.
+    Deleted(String),
+    /// Mot du texte OCR absent de la référence.
+    Inserted(String),
+    /// Mot de la référence lu différemment.
+    Replaced {
+        /// Mot attendu (référence).
+        expected: String,
+        /// Mot trouvé dans le texte OCR.
+        found: String,
+    },
+}
+
+/// Aligne les mots du texte OCR sur ceux de la référence.
+///
+/// Même principe que [`character_errors()`] au niveau des mots : le chemin
+/// optimal de la matrice de Levenshtein est remonté, en préférant la
+/// substitution à la suppression, puis à l'insertion. Contrairement à
+/// [`character_errors()`], les mots identiques sont conservés
+/// ([`WordDiff::Equal`]) pour pouvoir afficher le diff complet.
+///
+/// # Arguments
+///
+/// * `ocr_text` - Le texte extrait par OCR
+/// * `reference_text` - Le texte de référence attendu
+///
+/// # Retour
+///
+/// Les opérations dans l'ordre du texte. Le nombre d'opérations différentes
+/// de [`WordDiff::Equal`] est égal à la distance de Levenshtein en mots.
+///
+/// # Exemples
+///
+/// ```
+/// use text_recognition::metrics::{WordDiff, word_diff};
+///
+/// let diff = word_diff("le chot dort", "le chat dort");
+/// assert_eq!(
+///     diff,
+///     vec![
+///         WordDiff::Equal("le".to_string()),
+///         WordDiff::Replaced { expected: "chat".to_string(), found: "chot".to_string() },
+///         WordDiff::Equal("dort".to_string()),
+///     ]
+/// );
+/// ```
+pub fn word_diff(ocr_text: &str, reference_text: &str) -> Vec<WordDiff> {
+    let ocr_words: Vec<&str> = ocr_text.split_whitespace().collect();
+    let reference_words: Vec<&str> = reference_text.split_whitespace().collect();
+
+    let ocr_len = ocr_words.len();
+    let reference_len = reference_words.len();
+
+    // Matrice de Levenshtein complète (nécessaire pour la remontée)
+    let mut matrix = vec![vec![0usize; reference_len + 1]; ocr_len + 1];
+
+    #[allow(clippy::needless_range_loop)]
+    for i in 0..=ocr_len {
+        matrix[i][0] = i;
+    }
+
+    #[allow(clippy::needless_range_loop)]
+    for j in 0..=reference_len {
+        matrix[0][j] = j;
+    }
+
+    for i in 1..=ocr_len {
+        for j in 1..=reference_len {
+            let substitution_cost = usize::from(ocr_words[i - 1] != reference_words[j - 1]);
+
+            matrix[i][j] = std::cmp::min(
+                std::cmp::min(matrix[i - 1][j] + 1, matrix[i][j - 1] + 1),
+                matrix[i - 1][j - 1] + substitution_cost,
+            );
+        }
+    }
+
+    // Remonter le chemin optimal depuis la dernière cellule
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (ocr_len, reference_len);
+
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 {
+            let substitution_cost = usize::from(ocr_words[i - 1] != reference_words[j - 1]);
+            if matrix[i][j] == matrix[i - 1][j - 1] + substitution_cost {
+                diff.push(if substitution_cost == 0 {
+                    WordDiff::Equal(reference_words[j - 1].to_string())
+                } else {
+                    WordDiff::Replaced {
+                        expected: reference_words[j - 1].to_string(),
+                        found: ocr_words[i - 1].to_string(),
+                    }
+                });
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+
+        if j > 0 && (i == 0 || matrix[i][j] == matrix[i][j - 1] + 1) {
+            diff.push(WordDiff::Deleted(reference_words[j - 1].to_string()));
+            j -= 1;
+        } else {
+            diff.push(WordDiff::Inserted(ocr_words[i - 1].to_string()));
+            i -= 1;
+        }
+    }
+
+    diff.reverse();
+    diff
+}
+
+/// Confusion de caractères agrégée : `expected` lu `found`, `count` fois.
+///
+/// Les caractères manquants ou ajoutés sont représentés par [`MISSING_CHAR`].
+///
+/// # Exemples
+///
+/// ```
+/// use text_recognition::metrics::ConfusionEntry;
+///
+/// let entry = ConfusionEntry { expected: 'O', found: '0', count: 3 };
+/// assert_eq!(entry.to_string(), "'O' → '0' (3)");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfusionEntry {
+    /// Caractère attendu (référence), ou [`MISSING_CHAR`] pour une insertion.
+    pub expected: char,
+    /// Caractère trouvé (OCR), ou [`MISSING_CHAR`] pour une suppression.
+    pub found: char,
+    /// Nombre d'occurrences de cette confusion.
+    pub count: usize,
+}
+
+impl fmt::Display for ConfusionEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' → '{}' ({})", self.expected, self.found, self.count)
+    }
+}
+
+/// Accumulateur de confusions de caractères sur un ou plusieurs textes.
+///
+/// Permet de savoir quelles confusions dominent sur un corpus entier
+/// (ex: `0`↔`O`, `1`↔`l`, `é`↔`e`) avant d'ajouter une whitelist.
+///
+/// # Exemples
+///
+/// ```
+/// use text_recognition::metrics::ConfusionMatrix;
+///
+/// let mut matrix = ConfusionMatrix::new();
+/// matrix.add_pair("l1lo", "lilo");
+/// matrix.add_pair("1l", "il");
+///
+/// let top = matrix.top_n(1);
+/// assert_eq!((top[0].expected, top[0].found, top[0].count), ('i', '1', 2));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfusionMatrix {
+    counts: HashMap<(char, char), usize>,
+}
+
+impl ConfusionMatrix {
+    /// Crée un accumulateur vide.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ajoute les confusions d'un couple (texte OCR, texte de référence).
+    ///
+    /// # Arguments
+    ///
+    /// * `ocr_text` - Le texte extrait par OCR
+    /// * `reference_text` - Le texte de référence attendu
+    pub fn add_pair(&mut self, ocr_text: &str, reference_text: &str) {
+        for error in character_errors(ocr_text, reference_text) {
+            let key = match error {
+                TextError::Substitution {
+                    expected, found, ..
+                } => (expected, found),
+                TextError::Deletion { expected, .. } => (expected, MISSING_CHAR),
+                TextError::Insertion { found, .. } => (MISSING_CHAR, found),
+                TextError::WordError { .. } => continue,
+            };
+            *self.counts.entry(key).or_insert(0) += 1;
+        }
+    }
+
+    /// Fusionne les confusions d'un autre accumulateur dans celui-ci.
+    pub fn merge(&mut self, other: &ConfusionMatrix) {
+        for (&key, &count) in &other.counts {
+            *self.counts.entry(key).or_insert(0) += count;
+        }
+    }
+
+    /// Retourne toutes les confusions, triées par nombre d'occurrences décroissant.
+    ///
+    /// À nombre égal, l'ordre est celui des caractères attendus puis trouvés.
+    pub fn entries(&self) -> Vec<ConfusionEntry> {
+        let mut entries: Vec<ConfusionEntry> = self
+            .counts
+            .iter()
+            .map(|(&(expected, found), &count)| ConfusionEntry {
+                expected,
+                found,
+                count,
+            })
+            .collect();
+
+        entries.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then(a.expected.cmp(&b.expected))
+                .then(a.found.cmp(&b.found))
+        });
+        entries
+    }
+
+    /// Retourne les `n` confusions les plus fréquentes.
+    pub fn top_n(&self, n: usize) -> Vec<ConfusionEntry> {
+        let mut entries = self.entries();
+        entries.truncate(n);
+        entries
+    }
+
+    /// Nombre total d'erreurs de caractères accumulées.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Indique si aucune confusion n'a été enregistrée.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+/// Ponctuation hors ASCII reconnue par [`CharClass::of()`].
+///
+/// Guillemets, tirets et signes typographiques courants en français.
+const UNICODE_PUNCTUATION: &str = "«»‹›“”‘’‚„…‐‑‒–—―¡¿·•§¶†‡‰′″";
+
+/// Classe d'un caractère pour [`ClassBreakdown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CharClass {
+    /// Lettre, accentuée ou non (`a`, `É`, `ç`…).
+    Letter,
+    /// Chiffre ou autre caractère numérique.
+    Digit,
+    /// Ponctuation (`,`, `:`, `«`, `…`…).
+    Punctuation,
+    /// Espace, tabulation ou saut de ligne.
+    Whitespace,
+    /// Tout le reste : symboles monétaires (`€`, `$`), mathématiques (`+`, `=`)…
+    Other,
+}
+
+impl CharClass {
+    /// Toutes les classes, dans l'ordre d'affichage des rapports.
+    pub const ALL: [CharClass; 5] = [
+        CharClass::Letter,
+        CharClass::Digit,
+        CharClass::Punctuation,
+        CharClass::Whitespace,
+        CharClass::Other,
+    ];
+
+    /// Classe d'un caractère.
+    ///
+    /// La ponctuation suit les catégories Unicode `P*` : en ASCII, les
+    /// symboles `$ + < = > ^ ` | ~` sont donc classés dans [`CharClass::Other`].
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::metrics::CharClass;
+    ///
+    /// assert_eq!(CharClass::of('é'), CharClass::Letter);
+    /// assert_eq!(CharClass::of('7'), CharClass::Digit);
+    /// assert_eq!(CharClass::of('«'), CharClass::Punctuation);
+    /// assert_eq!(CharClass::of('€'), CharClass::Other);
+    /// ```
+    pub fn of(c: char) -> Self {
+        if c.is_whitespace() {
+            CharClass::Whitespace
+        } else if c.is_numeric() {
+            CharClass::Digit
+        } else if c.is_alphabetic() {
+            CharClass::Letter
+        } else if (c.is_ascii_punctuation() && !"$+<=>^`|~".contains(c))
+            || UNICODE_PUNCTUATION.contains(c)
+        {
+            CharClass::Punctuation
+        } else {
+            CharClass::Other
+        }
+    }
+
+    /// Nom affiché dans les rapports.
+    pub fn name(self) -> &'static str {
+        match self {
+            CharClass::Letter => "Letter",
+            CharClass::Digit => "Digit",
+            CharClass::Punctuation => "Punctuation",
+            CharClass::Whitespace => "Whitespace",
+            CharClass::Other => "Other",
+        }
+    }
+}
+
+/// Caractères de référence d'une classe et erreurs qui les touchent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassStats {
+    /// Nombre de caractères de la classe dans la référence.
+    pub count: usize,
+    /// Substitutions et suppressions de ces caractères.
+    pub errors: usize,
+}
+
+impl ClassStats {
+    /// Taux d'erreur de la classe (`errors / count`), 0.0 si elle est absente.
+    pub fn error_rate(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.count as f64
+        }
+    }
+}
+
+/// Répartition des caractères de référence et des erreurs par classe.
+///
+/// Seules les substitutions et suppressions sont attribuées, à la classe du
+/// caractère attendu : une insertion ne correspond à aucun caractère de la
+/// référence. La somme des erreurs peut donc être inférieure à la distance
+/// de Levenshtein.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::{CharClass, ClassBreakdown};
+///
+/// let classes = ClassBreakdown::from_texts("Total: l23", "Total: 123");
+/// assert_eq!(classes.get(CharClass::Digit).errors, 1);
+/// assert_eq!(classes.get(CharClass::Letter).error_rate(), 0.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassBreakdown {
+    /// Lettres.
+    pub letter: ClassStats,
+    /// Chiffres.
+    pub digit: ClassStats,
+    /// Ponctuation.
+    pub punctuation: ClassStats,
+    /// Espaces.
+    pub whitespace: ClassStats,
+    /// Autres caractères.
+    pub other: ClassStats,
+}
+
+impl ClassBreakdown {
+    /// Calcule la répartition à partir de l'alignement de [`character_errors()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `ocr_text` - Le texte extrait par OCR
+    /// * `reference_text` - Le texte de référence attendu
+    pub fn from_texts(ocr_text: &str, reference_text: &str) -> Self {
+        let mut breakdown = Self::default();
+        for c in reference_text.chars() {
+            breakdown.get_mut(CharClass::of(c)).count += 1;
+        }
+        for error in character_errors(ocr_text, reference_text) {
+            match error {
+                TextError::Substitution { expected, .. } | TextError::Deletion { expected, .. } => {
+                    breakdown.get_mut(CharClass::of(expected)).errors += 1;
+                }
+                TextError::Insertion { .. } | TextError::WordError { .. } => {}
+            }
+        }
+        breakdown
+    }
+
+    /// Statistiques d'une classe.
+    pub fn get(&self, class: CharClass) -> ClassStats {
+        match class {
+            CharClass::Letter => self.letter,
+            CharClass::Digit => self.digit,
+            CharClass::Punctuation => self.punctuation,
+            CharClass::Whitespace => self.whitespace,
+            CharClass::Other => self.other,
+        }
+    }
+
+    fn get_mut(&mut self, class: CharClass) -> &mut ClassStats {
+        match class {
+            CharClass::Letter => &mut self.letter,
+            CharClass::Digit => &mut self.digit,
+            CharClass::Punctuation => &mut self.punctuation,
+            CharClass::Whitespace => &mut self.whitespace,
+            CharClass::Other => &mut self.other,
+        }
+    }
+}
+
+/// Calcule les confusions de caractères entre le texte OCR et la référence.
+///
+/// Raccourci pour un [`ConfusionMatrix`] alimenté par un seul couple de textes.
+///
+/// # Arguments
+///
+/// * `ocr_text` - Le texte extrait par OCR
+/// * `reference_text` - Le texte de référence attendu
+///
+/// # Retour
+///
+/// Les confusions triées par nombre d'occurrences décroissant. Les suppressions
+/// et insertions utilisent [`MISSING_CHAR`] comme caractère trouvé ou attendu.
+///
+/// # Exemples
+///
+/// ```
+/// use text_recognition::metrics::character_confusions;
+///
+/// let confusions = character_confusions("c0de", "codé");
+/// assert_eq!(confusions.len(), 2);
+/// assert!(confusions.iter().any(|c| c.expected == 'o' && c.found == '0'));
+/// assert!(confusions.iter().any(|c| c.expected == 'é' && c.found == 'e'));
+/// ```
+pub fn character_confusions(ocr_text: &str, reference_text: &str) -> Vec<ConfusionEntry> {
+    let mut matrix = ConfusionMatrix::new();
+    matrix.add_pair(ocr_text, reference_text);
+    matrix.entries()
+}
+
+/// Compare un résultat OCR avec un texte de référence et calcule toutes les métriques.
+///
+/// Cette fonction effectue une analyse complète de la qualité d'un résultat OCR
+/// en calculant le CER, le WER, la distance de Levenshtein, et en comptant les
+/// caractères et mots dans les deux textes.
+///
+/// # Arguments
+///
+/// * `ocr_text` - Le texte extrait par OCR
+/// * `reference_text` - Le texte de référence attendu
+///
+/// # Retour
+///
+/// Une structure `OcrMetrics` contenant toutes les métriques calculées :
+/// - `cer` : Character Error Rate
+/// - `wer` : Word Error Rate
+/// - `levenshtein_distance` : Distance de Levenshtein au niveau des caractères
+/// - `reference_char_count` : Nombre de caractères dans la référence
+/// - `ocr_char_count` : Nombre de caractères dans le texte OCR
 /// - `reference_word_count` : Nombre de mots dans la référence
 /// - `ocr_word_count` : Nombre de mots dans le texte OCR
 /// - `exact_match` : `true` si les textes sont identiques
@@ -716,56 +2029,672 @@ fn word_levenshtein_distance(source: &[&str], target: &[&str]) -> usize {
 /// la qualité du résultat par rapport à un texte de référence connu :
 ///
 /// ```no_run
+/// # #[cfg(feature = "tesseract")]
+/// # {
 /// use text_recognition::ocr::OcrEngine;
 /// use text_recognition::config::OcrConfig;
 /// use text_recognition::metrics::compare_ocr_result;
 /// use std::path::Path;
 ///
-/// # fn main() -> anyhow::Result<()> {
 /// let mut engine = OcrEngine::new(OcrConfig::default())?;
 /// let ocr_text = engine.extract_text_from_file(Path::new("test.png"))?;
 /// let reference = "Expected text content";
 ///
-/// let metrics = compare_ocr_result(&ocr_text, reference);
+/// let metrics = compare_ocr_result(&ocr_text, reference);
+/// println!("CER: {:.2}%", metrics.cer * 100.0);
+/// println!("WER: {:.2}%", metrics.wer * 100.0);
+/// println!("Accuracy: {:.2}%", metrics.accuracy() * 100.0);
+/// # }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn compare_ocr_result(ocr_text: &str, reference_text: &str) -> OcrMetrics {
+    compare_ocr_result_with(ocr_text, reference_text, &Tokenizer::Whitespace)
+}
+
+/// Compare un résultat OCR à une référence avec un découpage en mots donné.
+///
+/// Identique à [`compare_ocr_result()`], mais le WER et les nombres de mots
+/// utilisent `tokenizer` ; sa nature est enregistrée dans
+/// [`OcrMetrics::tokenizer`].
+///
+/// # Arguments
+///
+/// * `ocr_text` - Le texte extrait par OCR
+/// * `reference_text` - Le texte de référence attendu
+/// * `tokenizer` - Découpage des deux textes en mots
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::{Tokenizer, TokenizerKind, compare_ocr_result_with};
+///
+/// let metrics = compare_ocr_result_with("東京に行く", "東京に行く", &Tokenizer::UnicodeWords);
+/// assert_eq!(metrics.reference_word_count, 5);
+/// assert_eq!(metrics.tokenizer, TokenizerKind::UnicodeWords);
+/// ```
+pub fn compare_ocr_result_with(
+    ocr_text: &str,
+    reference_text: &str,
+    tokenizer: &Tokenizer,
+) -> OcrMetrics {
+    // Calculer la distance de Levenshtein
+    let levenshtein_distance = levenshtein_distance(ocr_text, reference_text);
+
+    // Compter les caractères
+    let reference_char_count = reference_text.chars().count();
+    let ocr_char_count = ocr_text.chars().count();
+
+    // Compter les mots
+    let reference_word_count = tokenizer.tokenize(reference_text).len();
+    let ocr_word_count = tokenizer.tokenize(ocr_text).len();
+
+    // Calculer le CER
+    let cer = calculate_cer(ocr_text, reference_text);
+
+    // Calculer le WER
+    let wer = calculate_wer_with_tokenizer(ocr_text, reference_text, tokenizer);
+
+    // Vérifier si c'est un match exact
+    let exact_match = ocr_text == reference_text;
+
+    OcrMetrics {
+        cer,
+        wer,
+        levenshtein_distance,
+        reference_char_count,
+        ocr_char_count,
+        reference_word_count,
+        ocr_word_count,
+        exact_match,
+        tokenizer: tokenizer.kind(),
+    }
+}
+
+/// Métriques OCR complétées par la répartition par classe de caractères.
+///
+/// Retourné par [`compare_ocr_result_detailed()`]. Sérialisé, les champs de
+/// [`OcrMetrics`] restent au premier niveau, à côté de `classes`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetailedOcrMetrics {
+    /// Métriques globales.
+    #[serde(flatten)]
+    pub metrics: OcrMetrics,
+    /// Effectifs et taux d'erreur par classe de caractères.
+    pub classes: ClassBreakdown,
+}
+
+impl DetailedOcrMetrics {
+    /// Exporte les métriques vers une ligne CSV avec un formatage choisi.
+    ///
+    /// Mêmes colonnes que [`OcrMetrics::to_csv_with()`], suivies de
+    /// `Digit_CER` et `Letter_CER` (taux d'erreur des chiffres et des
+    /// lettres de la référence).
+    ///
+    /// # Arguments
+    ///
+    /// * `include_headers` - Si `true`, inclut une ligne d'en-têtes avant les données
+    /// * `metadata` - Métadonnées optionnelles à ajouter (image, config, etc.)
+    /// * `options` - Délimiteur, séparateur décimal, précision, guillemets, fin de ligne
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::metrics::{CsvOptions, compare_ocr_result_detailed};
+    ///
+    /// let detailed = compare_ocr_result_detailed("Total: l23", "Total: 123");
+    /// let csv = detailed.to_csv_with(true, None, &CsvOptions::default());
+    /// assert!(csv.lines().next().unwrap().ends_with(",Accuracy,Digit_CER,Letter_CER"));
+    /// assert!(csv.lines().nth(1).unwrap().ends_with(",0.333333,0.000000"));
+    /// ```
+    pub fn to_csv_with(
+        &self,
+        include_headers: bool,
+        metadata: Option<&HashMap<String, String>>,
+        options: &CsvOptions,
+    ) -> String {
+        self.metrics.csv_rows(
+            include_headers,
+            metadata,
+            options,
+            &CLASS_CSV_HEADERS,
+            [
+                options.format_decimal(self.classes.digit.error_rate()),
+                options.format_decimal(self.classes.letter.error_rate()),
+            ],
+        )
+    }
+}
+
+/// En-têtes ajoutés par [`DetailedOcrMetrics::to_csv_with()`].
+const CLASS_CSV_HEADERS: [&str; 2] = ["Digit_CER", "Letter_CER"];
+
+/// Compare un résultat OCR à une référence, avec la répartition par classe.
+///
+/// Équivaut à [`compare_ocr_result_detailed_with()`] avec le découpage en
+/// mots par défaut.
+///
+/// # Arguments
+///
+/// * `ocr_text` - Le texte extrait par OCR
+/// * `reference_text` - Le texte de référence attendu
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::compare_ocr_result_detailed;
+///
+/// let detailed = compare_ocr_result_detailed("Total: l23,4S €", "Total: 123,45 €");
+/// assert!(detailed.classes.digit.error_rate() > 0.0);
+/// assert_eq!(detailed.classes.letter.error_rate(), 0.0);
+/// assert_eq!(detailed.metrics.levenshtein_distance, 2);
+/// ```
+pub fn compare_ocr_result_detailed(ocr_text: &str, reference_text: &str) -> DetailedOcrMetrics {
+    compare_ocr_result_detailed_with(ocr_text, reference_text, &Tokenizer::Whitespace)
+}
+
+/// Compare un résultat OCR à une référence avec un découpage en mots donné,
+/// avec la répartition par classe.
+///
+/// # Arguments
+///
+/// * `ocr_text` - Le texte extrait par OCR
+/// * `reference_text` - Le texte de référence attendu
+/// * `tokenizer` - Découpage des deux textes en mots
+pub fn compare_ocr_result_detailed_with(
+    ocr_text: &str,
+    reference_text: &str,
+    tokenizer: &Tokenizer,
+) -> DetailedOcrMetrics {
+    DetailedOcrMetrics {
+        metrics: compare_ocr_result_with(ocr_text, reference_text, tokenizer),
+        classes: ClassBreakdown::from_texts(ocr_text, reference_text),
+    }
+}
+
+/// Appariement d'une ligne OCR et d'une ligne de référence.
+///
+/// Retourné par [`compare_by_line()`]. Une ligne sans vis-à-vis (ajoutée par
+/// l'OCR ou absente de sa sortie) a l'autre indice à `None`, un texte vide
+/// en face et un CER de 1.0.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineComparison {
+    /// Indice de la ligne dans le texte OCR (à partir de 0).
+    pub ocr_line: Option<usize>,
+    /// Indice de la ligne dans la référence (à partir de 0).
+    pub reference_line: Option<usize>,
+    /// Texte de la ligne OCR.
+    pub ocr_text: String,
+    /// Texte de la ligne de référence.
+    pub reference_text: String,
+    /// CER de la ligne OCR par rapport à la ligne de référence.
+    pub cer: f64,
+}
+
+/// Aligne ligne à ligne le texte OCR et la référence.
+///
+/// L'alignement minimise la somme des CER des lignes appariées (plafonnés
+/// à 1.0), une ligne sans vis-à-vis coûtant 1.0 : une ligne ajoutée ou
+/// perdue par l'OCR ne décale pas les suivantes. Les lignes sont celles de
+/// [`str::lines()`].
+///
+/// # Arguments
+///
+/// * `ocr_text` - Le texte extrait par OCR
+/// * `reference_text` - Le texte de référence attendu
+///
+/// # Retour
+///
+/// Les appariements dans l'ordre des deux textes.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::compare_by_line;
+///
+/// let lines = compare_by_line("Facture\nbruit\nTotal: 12", "Facture\nTotal: 12");
+/// assert_eq!(lines.len(), 3);
+/// assert_eq!((lines[1].ocr_line, lines[1].reference_line), (Some(1), None));
+/// assert_eq!(lines[2].reference_text, "Total: 12");
+/// ```
+pub fn compare_by_line(ocr_text: &str, reference_text: &str) -> Vec<LineComparison> {
+    let ocr_lines: Vec<&str> = ocr_text.lines().collect();
+    let reference_lines: Vec<&str> = reference_text.lines().collect();
+    let (ocr_len, reference_len) = (ocr_lines.len(), reference_lines.len());
+
+    let pair_cer: Vec<Vec<f64>> = ocr_lines
+        .iter()
+        .map(|ocr| {
+            reference_lines
+                .iter()
+                .map(|reference| calculate_cer(ocr, reference))
+                .collect()
+        })
+        .collect();
+
+    // Coût minimal d'alignement des i premières lignes OCR et j premières lignes de référence
+    let mut cost = vec![vec![0.0f64; reference_len + 1]; ocr_len + 1];
+    for (i, row) in cost.iter_mut().enumerate() {
+        row[0] = i as f64;
+    }
+    for (j, cell) in cost[0].iter_mut().enumerate() {
+        *cell = j as f64;
+    }
+    for i in 1..=ocr_len {
+        for j in 1..=reference_len {
+            let paired = cost[i - 1][j - 1] + pair_cer[i - 1][j - 1].min(1.0);
+            let skipped = (cost[i - 1][j] + 1.0).min(cost[i][j - 1] + 1.0);
+            cost[i][j] = paired.min(skipped);
+        }
+    }
+
+    let mut alignment = Vec::new();
+    let (mut i, mut j) = (ocr_len, reference_len);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && cost[i][j] == cost[i - 1][j - 1] + pair_cer[i - 1][j - 1].min(1.0) {
+            alignment.push(LineComparison {
+                ocr_line: Some(i - 1),
+                reference_line: Some(j - 1),
+                ocr_text: ocr_lines[i - 1].to_string(),
+                reference_text: reference_lines[j - 1].to_string(),
+                cer: pair_cer[i - 1][j - 1],
+            });
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && (j == 0 || cost[i][j] == cost[i - 1][j] + 1.0) {
+            alignment.push(LineComparison {
+                ocr_line: Some(i - 1),
+                reference_line: None,
+                ocr_text: ocr_lines[i - 1].to_string(),
+                reference_text: String::new(),
+                cer: 1.0,
+            });
+            i -= 1;
+        } else {
+            alignment.push(LineComparison {
+                ocr_line: None,
+                reference_line: Some(j - 1),
+                ocr_text: String::new(),
+                reference_text: reference_lines[j - 1].to_string(),
+                cer: 1.0,
+            });
+            j -= 1;
+        }
+    }
+
+    alignment.reverse();
+    alignment
+}
+
+/// Demi-largeur de bande utilisée par [`compare_files()`].
+pub const DEFAULT_BAND_WIDTH: usize = 100;
+
+/// Coût d'une cellule hors de la bande (jamais atteinte par un chemin calculé).
+const OUT_OF_BAND: usize = usize::MAX / 2;
+
+/// Distance de Levenshtein calculée ligne par ligne dans une bande glissante.
+///
+/// Seule la ligne courante de la matrice est conservée, limitée à
+/// `2 × band_width + 1` colonnes autour du meilleur alignement de la ligne
+/// précédente : la mémoire est en O(band_width) quelle que soit la longueur
+/// des textes. Chaque valeur calculée est le coût d'un alignement valide,
+/// donc la distance obtenue est un majorant de la distance exacte, égal à
+/// celle-ci tant que l'alignement optimal reste dans la bande.
+#[derive(Debug, Clone)]
+struct BandedLevenshtein<T> {
+    /// Séquence de référence (colonnes de la matrice).
+    reference: Vec<T>,
+    /// Demi-largeur de la bande.
+    band_width: usize,
+    /// Première colonne de la bande pour la ligne courante.
+    start: usize,
+    /// Coûts de la ligne courante, pour les colonnes `start..start + row.len()`.
+    row: Vec<usize>,
+    /// Ligne de travail réutilisée, pour éviter une allocation par élément.
+    next_row: Vec<usize>,
+}
+
+impl<T: PartialEq> BandedLevenshtein<T> {
+    /// Initialise la première ligne (aucun élément OCR consommé).
+    fn new(reference: Vec<T>, band_width: usize) -> Self {
+        let band_width = band_width.max(1);
+        let end = reference.len().min(2 * band_width);
+        Self {
+            reference,
+            band_width,
+            start: 0,
+            row: (0..=end).collect(),
+            next_row: Vec::with_capacity(2 * band_width + 1),
+        }
+    }
+
+    /// Nombre maximal de colonnes conservées par ligne.
+    fn max_row_len(&self) -> usize {
+        2 * self.band_width + 1
+    }
+
+    /// Coût de la ligne courante en colonne `j` (hors bande : [`OUT_OF_BAND`]).
+    fn cost(&self, j: usize) -> usize {
+        j.checked_sub(self.start)
+            .and_then(|offset| self.row.get(offset))
+            .copied()
+            .unwrap_or(OUT_OF_BAND)
+    }
+
+    /// Consomme un élément OCR : calcule la ligne suivante de la matrice.
+    fn push(&mut self, item: &T) {
+        // Centre de la nouvelle bande : diagonale du meilleur coût précédent
+        let best = self
+            .row
+            .iter()
+            .enumerate()
+            .min_by_key(|&(_, &cost)| cost)
+            .map_or(self.start, |(offset, _)| self.start + offset);
+
+        let m = self.reference.len();
+        let start = (best + 1)
+            .saturating_sub(self.band_width)
+            .max(self.start)
+            .min(m);
+        let end = (start + self.max_row_len() - 1).min(m);
+
+        self.next_row.clear();
+        for j in start..=end {
+            let deletion = self.cost(j).saturating_add(1);
+            let substitution = match j.checked_sub(1) {
+                Some(previous) => {
+                    let mismatch = usize::from(self.reference[previous] != *item);
+                    self.cost(previous).saturating_add(mismatch)
+                }
+                None => OUT_OF_BAND,
+            };
+            let insertion = self
+                .next_row
+                .last()
+                .map_or(OUT_OF_BAND, |&left| left.saturating_add(1));
+
+            self.next_row
+                .push(deletion.min(substitution).min(insertion).min(OUT_OF_BAND));
+        }
+
+        std::mem::swap(&mut self.row, &mut self.next_row);
+        self.start = start;
+    }
+
+    /// Distance finale : meilleur coût de la dernière ligne complété jusqu'à la fin de la référence.
+    fn distance(&self) -> usize {
+        let m = self.reference.len();
+        self.row
+            .iter()
+            .enumerate()
+            .map(|(offset, &cost)| cost.saturating_add(m - (self.start + offset)))
+            .min()
+            .unwrap_or(m)
+    }
+}
+
+/// Comparaison incrémentale d'un texte OCR avec une référence très longue.
+///
+/// Le texte de référence est fourni à la création, puis le texte OCR est
+/// consommé morceau par morceau via [`push_ocr_chunk()`](Self::push_ocr_chunk).
+/// Les distances (caractères et mots) sont calculées dans une bande de
+/// `band_width` colonnes de part et d'autre du meilleur alignement courant :
+/// la mémoire de calcul reste en O(band_width) et le temps en
+/// O(longueur × band_width), au lieu de O(n × m) pour [`compare_ocr_result()`].
+///
+/// # Précision
+///
+/// Le résultat est exact tant que l'alignement optimal reste dans la bande.
+/// Lorsqu'il en sort (ex: un bloc de texte manquant plus long que la bande),
+/// la distance retournée est un **majorant** de la distance exacte : CER et
+/// WER sont alors surestimés.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::{StreamingComparer, compare_ocr_result};
+///
+/// let reference = "Il était une fois un petit chaperon rouge";
+/// let mut comparer = StreamingComparer::new(reference, 16);
+/// comparer.push_ocr_chunk("Il était une fo");
+/// comparer.push_ocr_chunk("is un petit chaperon rooge");
+///
+/// let metrics = comparer.finalize();
+/// assert_eq!(metrics, compare_ocr_result("Il était une fois un petit chaperon rooge", reference));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamingComparer {
+    /// Alignement au niveau des caractères.
+    chars: BandedLevenshtein<char>,
+    /// Alignement au niveau des mots.
+    words: BandedLevenshtein<String>,
+    /// Mot OCR en cours, coupé par la fin du dernier morceau.
+    pending_word: String,
+    /// Nombre de caractères OCR consommés.
+    ocr_char_count: usize,
+    /// Nombre de mots OCR consommés.
+    ocr_word_count: usize,
+}
+
+impl StreamingComparer {
+    /// Crée un comparateur pour une référence donnée.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference_text` - Texte de référence complet
+    /// * `band_width` - Demi-largeur de la bande d'alignement (minimum 1) ;
+    ///   plus elle est grande, plus le résultat est exact et le calcul lent
+    pub fn new(reference_text: &str, band_width: usize) -> Self {
+        let reference_chars: Vec<char> = reference_text.chars().collect();
+        let reference_words: Vec<String> = reference_text
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+
+        Self {
+            chars: BandedLevenshtein::new(reference_chars, band_width),
+            words: BandedLevenshtein::new(reference_words, band_width),
+            pending_word: String::new(),
+            ocr_char_count: 0,
+            ocr_word_count: 0,
+        }
+    }
+
+    /// Consomme un morceau du texte OCR.
+    ///
+    /// Les morceaux peuvent être coupés n'importe où, y compris au milieu
+    /// d'un mot : le mot est complété par le morceau suivant.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - Suite du texte OCR
+    pub fn push_ocr_chunk(&mut self, chunk: &str) {
+        for c in chunk.chars() {
+            self.chars.push(&c);
+            self.ocr_char_count += 1;
+
+            if c.is_whitespace() {
+                self.flush_word();
+            } else {
+                self.pending_word.push(c);
+            }
+        }
+    }
+
+    /// Consomme le mot OCR en cours, s'il y en a un.
+    fn flush_word(&mut self) {
+        if !self.pending_word.is_empty() {
+            let word = std::mem::take(&mut self.pending_word);
+            self.words.push(&word);
+            self.ocr_word_count += 1;
+        }
+    }
+
+    /// Termine la comparaison et retourne les métriques.
+    ///
+    /// Les cas particuliers (référence vide) suivent [`calculate_cer()`] et
+    /// [`calculate_wer()`].
+    pub fn finalize(mut self) -> OcrMetrics {
+        self.flush_word();
+
+        let levenshtein_distance = self.chars.distance();
+        let word_distance = self.words.distance();
+        let reference_char_count = self.chars.reference.len();
+        let reference_word_count = self.words.reference.len();
+
+        let rate = |distance: usize, reference: usize, ocr: usize| {
+            if reference == 0 {
+                if ocr == 0 { 0.0 } else { 1.0 }
+            } else {
+                distance as f64 / reference as f64
+            }
+        };
+
+        OcrMetrics {
+            cer: rate(
+                levenshtein_distance,
+                reference_char_count,
+                self.ocr_char_count,
+            ),
+            wer: rate(word_distance, reference_word_count, self.ocr_word_count),
+            levenshtein_distance,
+            reference_char_count,
+            ocr_char_count: self.ocr_char_count,
+            reference_word_count,
+            ocr_word_count: self.ocr_word_count,
+            exact_match: levenshtein_distance == 0,
+            tokenizer: TokenizerKind::Whitespace,
+        }
+    }
+}
+
+/// Compare un fichier texte OCR à un fichier de référence sans charger l'OCR en mémoire.
+///
+/// La référence est lue entièrement, le fichier OCR est lu ligne par ligne
+/// et transmis à un [`StreamingComparer`] de demi-largeur
+/// [`DEFAULT_BAND_WIDTH`]. Voir [`StreamingComparer`] pour la précision.
+///
+/// # Arguments
+///
+/// * `ocr_path` - Fichier contenant le texte extrait par OCR
+/// * `reference_path` - Fichier contenant le texte de référence
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::metrics::compare_files;
+/// use std::path::Path;
+///
+/// let metrics = compare_files(Path::new("livre_ocr.txt"), Path::new("livre.txt"))?;
 /// println!("CER: {:.2}%", metrics.cer * 100.0);
-/// println!("WER: {:.2}%", metrics.wer * 100.0);
-/// println!("Accuracy: {:.2}%", metrics.accuracy() * 100.0);
-/// # Ok(())
-/// # }
+/// # Ok::<(), anyhow::Error>(())
 /// ```
-pub fn compare_ocr_result(ocr_text: &str, reference_text: &str) -> OcrMetrics {
-    // Calculer la distance de Levenshtein
-    let levenshtein_distance = levenshtein_distance(ocr_text, reference_text);
+///
+/// # Erreurs
+///
+/// Retourne une erreur si un des fichiers ne peut pas être lu ou n'est pas
+/// encodé en UTF-8.
+pub fn compare_files(ocr_path: &Path, reference_path: &Path) -> Result<OcrMetrics> {
+    let reference = std::fs::read_to_string(reference_path).with_context(|| {
+        format!(
+            "Impossible de lire le fichier de référence '{}'",
+            reference_path.display()
+        )
+    })?;
+    let file = std::fs::File::open(ocr_path).with_context(|| {
+        format!(
+            "Impossible d'ouvrir le fichier OCR '{}'",
+            ocr_path.display()
+        )
+    })?;
+
+    let mut comparer = StreamingComparer::new(&reference, DEFAULT_BAND_WIDTH);
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line).with_context(|| {
+            format!(
+                "Échec de la lecture du fichier OCR '{}'",
+                ocr_path.display()
+            )
+        })?;
+        if read == 0 {
+            break;
+        }
+        comparer.push_ocr_chunk(&line);
+    }
 
-    // Compter les caractères
-    let reference_char_count = reference_text.chars().count();
-    let ocr_char_count = ocr_text.chars().count();
+    Ok(comparer.finalize())
+}
 
-    // Compter les mots
-    let reference_word_count = reference_text.split_whitespace().count();
-    let ocr_word_count = ocr_text.split_whitespace().count();
+/// Nombre de confusions affichées dans [`generate_diff_report()`].
+const TOP_CONFUSIONS_IN_REPORT: usize = 10;
 
-    // Calculer le CER
-    let cer = calculate_cer(ocr_text, reference_text);
+/// Niveau de qualité d'un résultat, d'après son CER.
+///
+/// Affiché dans la synthèse de [`generate_diff_report()`] et en badge dans
+/// [`generate_html_report()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum QualityBand {
+    /// Texte identique à la référence.
+    Perfect,
+    /// CER inférieur à 5 %.
+    Excellent,
+    /// CER inférieur à 15 %.
+    Good,
+    /// CER inférieur à 30 %.
+    Fair,
+    /// CER d'au moins 30 %.
+    Poor,
+}
 
-    // Calculer le WER
-    let wer = calculate_wer(ocr_text, reference_text);
+impl QualityBand {
+    /// Niveau de qualité de métriques.
+    pub(crate) fn of(metrics: &OcrMetrics) -> Self {
+        if metrics.exact_match {
+            QualityBand::Perfect
+        } else if metrics.cer < 0.05 {
+            QualityBand::Excellent
+        } else if metrics.cer < 0.15 {
+            QualityBand::Good
+        } else if metrics.cer < 0.30 {
+            QualityBand::Fair
+        } else {
+            QualityBand::Poor
+        }
+    }
 
-    // Vérifier si c'est un match exact
-    let exact_match = ocr_text == reference_text;
+    /// Libellé du rapport (ex: "Good (< 15% error)").
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            QualityBand::Perfect => "Perfect (exact match)",
+            QualityBand::Excellent => "Excellent (< 5% error)",
+            QualityBand::Good => "Good (< 15% error)",
+            QualityBand::Fair => "Fair (< 30% error)",
+            QualityBand::Poor => "Poor (≥ 30% error)",
+        }
+    }
 
-    OcrMetrics {
-        cer,
-        wer,
-        levenshtein_distance,
-        reference_char_count,
-        ocr_char_count,
-        reference_word_count,
-        ocr_word_count,
-        exact_match,
+    /// Nom court, en minuscules (ex: "good").
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            QualityBand::Perfect => "perfect",
+            QualityBand::Excellent => "excellent",
+            QualityBand::Good => "good",
+            QualityBand::Fair => "fair",
+            QualityBand::Poor => "poor",
+        }
     }
 }
 
+/// Écart minimal entre le CER ordinaire et le CER insensible à l'ordre pour
+/// que [`generate_diff_report()`] affiche la section ORDER-INSENSITIVE.
+const ORDER_INSENSITIVE_MIN_GAIN: f64 = 0.05;
+
+/// Nombre maximal de segments par texte pour la section ORDER-INSENSITIVE
+/// (la matrice des distances croît comme le carré du nombre de segments).
+const ORDER_INSENSITIVE_MAX_SEGMENTS: usize = 200;
+
 /// Génère un rapport détaillé des différences entre le texte OCR et le texte de référence.
 ///
 /// Cette fonction produit un rapport formaté en texte qui présente :
@@ -791,7 +2720,14 @@ pub fn compare_ocr_result(ocr_text: &str, reference_text: &str) -> OcrMetrics {
 /// 2. **Métriques** : CER, WER, distance de Levenshtein, précision
 /// 3. **Statistiques** : Nombre de caractères et mots dans chaque texte
 /// 4. **Comparaison** : Affichage des deux textes pour comparaison visuelle
-/// 5. **Résumé** : Évaluation qualitative du résultat (Excellent, Bon, Moyen, Faible)
+/// 5. **Confusions** : Les confusions de caractères les plus fréquentes
+/// 6. **Résumé** : Évaluation qualitative du résultat (Excellent, Bon, Moyen, Faible)
+///
+/// Une section **ORDER-INSENSITIVE** suit les métriques lorsque les
+/// paragraphes (ou, pour une référence d'un seul paragraphe, les lignes)
+/// appariés sans tenir compte de leur ordre donnent un CER inférieur d'au
+/// moins 5 points (voir [`unordered::compare_unordered()`]) : elle indique
+/// ce CER et le segment OCR apparié à chaque segment de référence.
 ///
 /// # Exemples
 ///
@@ -828,6 +2764,10 @@ pub fn compare_ocr_result(ocr_text: &str, reference_text: &str) -> OcrMetrics {
 /// Reference: "hello world"
 /// OCR:       "helo world"
 ///
+/// TOP CONFUSIONS:
+/// ---------------
+/// 'l' → '∅' (1)
+///
 /// SUMMARY:
 /// --------
 /// Quality: Good (minor errors)
@@ -843,38 +2783,48 @@ pub fn compare_ocr_result(ocr_text: &str, reference_text: &str) -> OcrMetrics {
 /// - Documenter la qualité des résultats
 ///
 /// ```no_run
+/// # #[cfg(feature = "tesseract")]
+/// # {
 /// use text_recognition::ocr::OcrEngine;
 /// use text_recognition::config::OcrConfig;
 /// use text_recognition::metrics::generate_diff_report;
 /// use std::path::Path;
 /// use std::fs;
 ///
-/// # fn main() -> anyhow::Result<()> {
 /// let mut engine = OcrEngine::new(OcrConfig::default())?;
 /// let ocr_text = engine.extract_text_from_file(Path::new("test.png"))?;
 /// let reference = fs::read_to_string("test_expected.txt")?;
 ///
 /// let report = generate_diff_report(&ocr_text, &reference);
 /// fs::write("report.txt", report)?;
-/// # Ok(())
 /// # }
+/// # Ok::<(), anyhow::Error>(())
 /// ```
 pub fn generate_diff_report(ocr_text: &str, reference_text: &str) -> String {
+    generate_diff_report_with(ocr_text, reference_text, &Tokenizer::Whitespace)
+}
+
+/// Génère le rapport de [`generate_diff_report()`] avec un découpage en mots donné.
+///
+/// Le découpage est indiqué dans la section METRICS lorsqu'il n'est pas
+/// celui par défaut.
+///
+/// # Arguments
+///
+/// * `ocr_text` - Le texte extrait par OCR
+/// * `reference_text` - Le texte de référence attendu
+/// * `tokenizer` - Découpage des deux textes en mots
+pub fn generate_diff_report_with(
+    ocr_text: &str,
+    reference_text: &str,
+    tokenizer: &Tokenizer,
+) -> String {
     // Calculer les métriques
-    let metrics = compare_ocr_result(ocr_text, reference_text);
+    let DetailedOcrMetrics { metrics, classes } =
+        compare_ocr_result_detailed_with(ocr_text, reference_text, tokenizer);
 
     // Déterminer la qualité du résultat
-    let quality = if metrics.exact_match {
-        "Perfect (exact match)"
-    } else if metrics.cer < 0.05 {
-        "Excellent (< 5% error)"
-    } else if metrics.cer < 0.15 {
-        "Good (< 15% error)"
-    } else if metrics.cer < 0.30 {
-        "Fair (< 30% error)"
-    } else {
-        "Poor (≥ 30% error)"
-    };
+    let quality = QualityBand::of(&metrics).label();
 
     // Construire le rapport
     let mut report = String::new();
@@ -903,6 +2853,17 @@ pub fn generate_diff_report(ocr_text: &str, reference_text: &str) -> String {
         "Accuracy:                   {:.2}%\n",
         metrics.accuracy() * 100.0
     ));
+    if metrics.tokenizer != TokenizerKind::Whitespace {
+        report.push_str(&format!(
+            "Word tokenizer:             {}\n",
+            metrics.tokenizer.name()
+        ));
+    }
+
+    // Métriques insensibles à l'ordre, si un réordonnancement explique l'écart
+    if let Some(section) = order_insensitive_section(ocr_text, reference_text, metrics.cer) {
+        report.push_str(&section);
+    }
 
     // Statistiques
     report.push_str("\nSTATISTICS:\n");
@@ -916,19 +2877,40 @@ pub fn generate_diff_report(ocr_text: &str, reference_text: &str) -> String {
         metrics.ocr_char_count, metrics.ocr_word_count
     ));
 
+    // Répartition par classe de caractères
+    report.push_str("\nBY CHARACTER CLASS:\n");
+    report.push_str("-------------------\n");
+    report.push_str("Class         Count  Errors  Error rate\n");
+    for class in CharClass::ALL {
+        let stats = classes.get(class);
+        report.push_str(&format!(
+            "{:<12} {:>6} {:>7} {:>10.2}%\n",
+            class.name(),
+            stats.count,
+            stats.errors,
+            stats.error_rate() * 100.0
+        ));
+    }
+
     // Comparaison
     report.push_str("\nCOMPARISON:\n");
     report.push_str("-----------\n");
 
     // Limiter la longueur des textes affichés pour la lisibilité
     let max_display_len = 200;
-    let ref_display = if reference_text.len() > max_display_len {
-        format!("{}... (truncated)", &reference_text[..max_display_len])
+    let ref_display = if reference_text.chars().count() > max_display_len {
+        format!(
+            "{}... (truncated)",
+            truncate_chars(reference_text, max_display_len)
+        )
     } else {
         reference_text.to_string()
     };
-    let ocr_display = if ocr_text.len() > max_display_len {
-        format!("{}... (truncated)", &ocr_text[..max_display_len])
+    let ocr_display = if ocr_text.chars().count() > max_display_len {
+        format!(
+            "{}... (truncated)",
+            truncate_chars(ocr_text, max_display_len)
+        )
     } else {
         ocr_text.to_string()
     };
@@ -936,6 +2918,18 @@ pub fn generate_diff_report(ocr_text: &str, reference_text: &str) -> String {
     report.push_str(&format!("Reference: \"{}\"\n", ref_display));
     report.push_str(&format!("OCR:       \"{}\"\n", ocr_display));
 
+    // Confusions de caractères
+    report.push_str("\nTOP CONFUSIONS:\n");
+    report.push_str("---------------\n");
+    let confusions = character_confusions(ocr_text, reference_text);
+    if confusions.is_empty() {
+        report.push_str("None\n");
+    } else {
+        for entry in confusions.iter().take(TOP_CONFUSIONS_IN_REPORT) {
+            report.push_str(&format!("{}\n", entry));
+        }
+    }
+
     // Résumé
     report.push_str("\nSUMMARY:\n");
     report.push_str("--------\n");
@@ -954,9 +2948,62 @@ pub fn generate_diff_report(ocr_text: &str, reference_text: &str) -> String {
     report
 }
 
+/// Section ORDER-INSENSITIVE de [`generate_diff_report_with()`], si elle apporte une information.
+fn order_insensitive_section(ocr_text: &str, reference_text: &str, cer: f64) -> Option<String> {
+    use unordered::{Granularity, compare_unordered, split_segments};
+
+    let granularity = if split_segments(reference_text, Granularity::Paragraph).len() > 1 {
+        Granularity::Paragraph
+    } else {
+        Granularity::Line
+    };
+    let too_many = |text| split_segments(text, granularity).len() > ORDER_INSENSITIVE_MAX_SEGMENTS;
+    if too_many(ocr_text) || too_many(reference_text) {
+        return None;
+    }
+    let comparison = compare_unordered(ocr_text, reference_text, granularity);
+    if comparison.reference_segments < 2 || cer - comparison.cer < ORDER_INSENSITIVE_MIN_GAIN {
+        return None;
+    }
+
+    let title = format!("ORDER-INSENSITIVE ({}):", granularity.name());
+    let order: Vec<String> = comparison
+        .assignment
+        .iter()
+        .map(|matched| matched.map_or("-".to_string(), |o| (o + 1).to_string()))
+        .collect();
+    let mut section = format!("\n{}\n{}\n", title, "-".repeat(title.chars().count()));
+    section.push_str(&format!(
+        "Character Error Rate (CER): {:.2}%\n",
+        comparison.cer * 100.0
+    ));
+    section.push_str(&format!(
+        "Levenshtein Distance:       {}\n",
+        comparison.distance
+    ));
+    section.push_str(&format!(
+        "Matched {}:{} {}/{} (OCR order: {})\n",
+        granularity.name(),
+        " ".repeat(18 - granularity.name().len()),
+        comparison.assignment.iter().flatten().count(),
+        comparison.reference_segments,
+        order.join(", ")
+    ));
+    if !comparison.unmatched_ocr.is_empty() {
+        section.push_str(&format!(
+            "Extra OCR {}:{} {}\n",
+            granularity.name(),
+            " ".repeat(16 - granularity.name().len()),
+            comparison.unmatched_ocr.len()
+        ));
+    }
+    Some(section)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rng::SplitMix64;
 
     #[test]
     fn test_levenshtein_identical_strings() {
@@ -1019,6 +3066,84 @@ mod tests {
         assert_eq!(levenshtein_distance("HELLO", "hello"), 5);
     }
 
+    /// Chaîne pseudo-aléatoire de `len` caractères tirés dans `alphabet`.
+    fn random_string(rng: &mut SplitMix64, alphabet: &[char], len: usize) -> String {
+        (0..len)
+            .map(|_| alphabet[(rng.next_u64() % alphabet.len() as u64) as usize])
+            .collect()
+    }
+
+    #[test]
+    fn test_levenshtein_fast_matches_classic_on_random_short_pairs() {
+        // Petit alphabet (correspondances fréquentes) avec des caractères multi-octets
+        let alphabet = ['a', 'b', 'c', 'é', '€', ' ', '🐱'];
+        let mut rng = SplitMix64::new(7);
+        for _ in 0..3000 {
+            let source_len = (rng.next_u64() % 25) as usize;
+            let source = random_string(&mut rng, &alphabet, source_len);
+            let target_len = (rng.next_u64() % 25) as usize;
+            let target = random_string(&mut rng, &alphabet, target_len);
+            assert_eq!(
+                levenshtein_distance_fast(&source, &target),
+                levenshtein_distance(&source, &target),
+                "{source:?} / {target:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_levenshtein_fast_matches_classic_across_blocks() {
+        let alphabet = ['a', 'b', 'é', ' ', 'x', 'y', 'z', '0'];
+        let mut rng = SplitMix64::new(11);
+        for _ in 0..40 {
+            let source_len = 60 + (rng.next_u64() % 300) as usize;
+            let source = random_string(&mut rng, &alphabet, source_len);
+            let target_len = 60 + (rng.next_u64() % 300) as usize;
+            let target = random_string(&mut rng, &alphabet, target_len);
+            assert_eq!(
+                levenshtein_distance_fast(&source, &target),
+                levenshtein_distance(&source, &target)
+            );
+        }
+    }
+
+    #[test]
+    fn test_levenshtein_fast_known_long_distances() {
+        let text = "Le café coûte 2,50 € ; ".repeat(500);
+        assert_eq!(levenshtein_distance_fast(&text, &text), 0);
+        assert_eq!(levenshtein_distance_fast(&text, ""), text.chars().count());
+        assert_eq!(
+            levenshtein_distance_fast(&"a".repeat(300), &"b".repeat(300)),
+            300
+        );
+
+        // Substitutions aux frontières des blocs de 64 lignes
+        let mut substituted: Vec<char> = "a".repeat(200).chars().collect();
+        for index in [0, 63, 64, 127, 128, 199] {
+            substituted[index] = 'é';
+        }
+        let substituted: String = substituted.into_iter().collect();
+        assert_eq!(levenshtein_distance_fast(&"a".repeat(200), &substituted), 6);
+
+        // Insertion d'un bloc de 100 caractères au milieu du texte
+        let middle = text.char_indices().nth(5000).unwrap().0;
+        let inserted = format!("{}{}{}", &text[..middle], "#".repeat(100), &text[middle..]);
+        assert_eq!(levenshtein_distance_fast(&text, &inserted), 100);
+        assert_eq!(levenshtein_distance_fast(&inserted, &text), 100);
+    }
+
+    #[test]
+    fn test_calculate_cer_uses_fast_distance_on_long_texts() {
+        let reference = "Le café coûte 2,50 € ; ".repeat(40);
+        let ocr = reference.replace("coûte", "coute");
+        assert!(ocr.chars().count() > FAST_LEVENSHTEIN_THRESHOLD);
+
+        let expected =
+            levenshtein_distance(&ocr, &reference) as f64 / reference.chars().count() as f64;
+        assert_eq!(calculate_cer(&ocr, &reference), expected);
+        assert!((expected - 40.0 / reference.chars().count() as f64).abs() < 1e-12);
+    }
+
     #[test]
     fn test_calculate_cer_identical_texts() {
         assert_eq!(calculate_cer("hello world", "hello world"), 0.0);
@@ -1427,6 +3552,88 @@ mod tests {
         assert!(report.contains("OCR:       11 characters, 2 words"));
     }
 
+    #[test]
+    fn test_generate_diff_report_class_table() {
+        let report = generate_diff_report("Total: l23,4S €", "Total: 123,45 €");
+
+        assert!(report.contains("BY CHARACTER CLASS:"));
+        assert!(
+            report.contains("Letter            5       0       0.00%"),
+            "{}",
+            report
+        );
+        assert!(
+            report.contains("Digit             5       2      40.00%"),
+            "{}",
+            report
+        );
+    }
+
+    // ============================================================
+    // Tests de la répartition par classe de caractères
+    // ============================================================
+
+    #[test]
+    fn test_digit_misreads_only_affect_digit_class() {
+        let detailed = compare_ocr_result_detailed("Total: l23,4S €", "Total: 123,45 €");
+
+        assert!(detailed.classes.digit.error_rate() > 0.0);
+        assert_eq!(detailed.classes.letter.error_rate(), 0.0);
+        assert_eq!(detailed.classes.digit.errors, 2);
+        assert_eq!(
+            detailed.metrics,
+            compare_ocr_result("Total: l23,4S €", "Total: 123,45 €")
+        );
+    }
+
+    #[test]
+    fn test_class_counts_euro_and_accented_letters() {
+        let classes = ClassBreakdown::from_texts("", "Été : 12 € à Noël");
+
+        assert_eq!(classes.letter.count, 8);
+        assert_eq!(classes.digit.count, 2);
+        assert_eq!(classes.punctuation.count, 1);
+        assert_eq!(classes.whitespace.count, 5);
+        assert_eq!(classes.other.count, 1);
+        assert_eq!(CharClass::of('€'), CharClass::Other);
+        // Texte OCR vide : chaque caractère de référence est une suppression
+        assert_eq!(classes.letter.error_rate(), 1.0);
+    }
+
+    #[test]
+    fn test_compare_by_line_realigns_after_extra_line() {
+        let lines = compare_by_line("Facturc\n~~\nTotal\nMerci", "Facture\nTotal\nMerci");
+
+        let pairs: Vec<(Option<usize>, Option<usize>)> = lines
+            .iter()
+            .map(|line| (line.ocr_line, line.reference_line))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                (Some(0), Some(0)),
+                (Some(1), None),
+                (Some(2), Some(1)),
+                (Some(3), Some(2)),
+            ]
+        );
+        assert!((lines[0].cer - 1.0 / 7.0).abs() < 1e-9);
+        assert_eq!(lines[1].cer, 1.0);
+    }
+
+    #[test]
+    fn test_detailed_csv_appends_class_columns() {
+        let detailed = compare_ocr_result_detailed("Total: l23,4S €", "Total: 123,45 €");
+        let mut metadata = HashMap::new();
+        metadata.insert("image".to_string(), "ticket\npng".to_string());
+
+        let csv = detailed.to_csv_with(true, Some(&metadata), &CsvOptions::default());
+
+        assert!(csv.starts_with("image,CER,"));
+        assert!(csv.contains(",Accuracy,Digit_CER,Letter_CER\n"), "{}", csv);
+        assert!(csv.ends_with(",0.400000,0.000000\n"), "{}", csv);
+    }
+
     #[test]
     fn test_generate_diff_report_comparison_section() {
         let report = generate_diff_report("hello world", "goodbye world");
@@ -1445,6 +3652,11 @@ mod tests {
         // Vérifier que le texte est tronqué
         assert!(report.contains("... (truncated)"));
         assert!(report.contains("250 characters"));
+
+        // Texte multi-octets : la coupure tombe entre deux caractères
+        let accented = "é".repeat(250);
+        let report = generate_diff_report(&accented, &accented);
+        assert!(report.contains(&format!("{}... (truncated)", "é".repeat(200))));
     }
 
     #[test]
@@ -1458,6 +3670,25 @@ mod tests {
         assert!(report.contains("OCR:       0 characters, 0 words"));
     }
 
+    #[test]
+    fn test_generate_diff_report_order_insensitive_section() {
+        let reference =
+            "Premier paragraphe de la colonne gauche.\n\nSecond paragraphe, colonne droite.";
+        let swapped =
+            "Second paragraphe, colonne droite.\n\nPremier paragraphe de la colonne gauche.";
+
+        let report = generate_diff_report(swapped, reference);
+        assert!(report.contains("ORDER-INSENSITIVE (paragraphs):"));
+        assert!(report.contains("Matched paragraphs:         2/2 (OCR order: 2, 1)"));
+        let section = report.split("ORDER-INSENSITIVE").nth(1).unwrap();
+        assert!(section.contains("Character Error Rate (CER): 0.00%"));
+
+        // Même ordre : pas de section
+        assert!(!generate_diff_report(reference, reference).contains("ORDER-INSENSITIVE"));
+        let typo = reference.replace("gauche", "gauchc");
+        assert!(!generate_diff_report(&typo, reference).contains("ORDER-INSENSITIVE"));
+    }
+
     #[test]
     fn test_generate_diff_report_format() {
         let report = generate_diff_report("test", "test");
@@ -1618,6 +3849,7 @@ mod tests {
             reference_word_count: 12,
             ocr_word_count: 12,
             exact_match: false,
+            tokenizer: TokenizerKind::Whitespace,
         };
 
         let csv = metrics.to_csv(false, None);
@@ -1650,6 +3882,7 @@ mod tests {
             reference_word_count: 12,
             ocr_word_count: 12,
             exact_match: false,
+            tokenizer: TokenizerKind::Whitespace,
         };
 
         let csv = metrics.to_csv(true, None);
@@ -1685,6 +3918,7 @@ mod tests {
             reference_word_count: 12,
             ocr_word_count: 12,
             exact_match: false,
+            tokenizer: TokenizerKind::Whitespace,
         };
 
         let mut metadata = std::collections::HashMap::new();
@@ -1717,6 +3951,7 @@ mod tests {
             reference_word_count: 2,
             ocr_word_count: 2,
             exact_match: true,
+            tokenizer: TokenizerKind::Whitespace,
         };
 
         let mut metadata = std::collections::HashMap::new();
@@ -1742,6 +3977,7 @@ mod tests {
             reference_word_count: 2,
             ocr_word_count: 2,
             exact_match: true,
+            tokenizer: TokenizerKind::Whitespace,
         };
 
         let csv = metrics.to_csv(true, None);
@@ -1764,6 +4000,7 @@ mod tests {
             reference_word_count: 4,
             ocr_word_count: 3,
             exact_match: false,
+            tokenizer: TokenizerKind::Whitespace,
         };
 
         let csv = metrics.to_csv(false, None);
@@ -1783,6 +4020,7 @@ mod tests {
             reference_word_count: 12,
             ocr_word_count: 12,
             exact_match: false,
+            tokenizer: TokenizerKind::Whitespace,
         };
 
         let metrics2 = OcrMetrics {
@@ -1794,6 +4032,7 @@ mod tests {
             reference_word_count: 12,
             ocr_word_count: 11,
             exact_match: false,
+            tokenizer: TokenizerKind::Whitespace,
         };
 
         // Premier export avec en-têtes
@@ -1836,4 +4075,511 @@ mod tests {
         assert!(pos_a < pos_m);
         assert!(pos_m < pos_z);
     }
+
+    fn csv_sample() -> (OcrMetrics, std::collections::HashMap<String, String>) {
+        let metrics = OcrMetrics {
+            cer: 0.123_456_7,
+            wer: 0.25,
+            levenshtein_distance: 3,
+            reference_char_count: 24,
+            ocr_char_count: 23,
+            reference_word_count: 4,
+            ocr_word_count: 4,
+            exact_match: false,
+            ..OcrMetrics::zero()
+        };
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("image".to_string(), "scan;1,v2.png".to_string());
+        (metrics, metadata)
+    }
+
+    #[test]
+    fn test_to_csv_with_default_options_is_identical() {
+        let (metrics, metadata) = csv_sample();
+
+        for headers in [false, true] {
+            assert_eq!(
+                metrics.to_csv_with(headers, Some(&metadata), &CsvOptions::default()),
+                metrics.to_csv(headers, Some(&metadata))
+            );
+        }
+        assert_eq!(
+            metrics.to_csv(true, Some(&metadata)),
+            "image,CER,WER,Levenshtein_Distance,Reference_Char_Count,OCR_Char_Count,\
+             Reference_Word_Count,OCR_Word_Count,Exact_Match,Accuracy\n\
+             \"scan;1,v2.png\",0.123457,0.250000,3,24,23,4,4,false,0.876543\n"
+        );
+    }
+
+    #[test]
+    fn test_to_csv_with_semicolon_and_decimal_comma() {
+        let (metrics, metadata) = csv_sample();
+        let options = CsvOptions {
+            delimiter: ';',
+            decimal_separator: ',',
+            ..CsvOptions::default()
+        };
+
+        let csv = metrics.to_csv_with(false, Some(&metadata), &options);
+
+        // Le point du nom de fichier n'est pas touché, la métadonnée est
+        // citée à cause du `;` et non plus de la `,`
+        assert_eq!(
+            csv,
+            "\"scan;1,v2.png\";0,123457;0,250000;3;24;23;4;4;false;0,876543\n"
+        );
+    }
+
+    #[test]
+    fn test_to_csv_with_precision_quote_all_and_crlf() {
+        let (metrics, _) = csv_sample();
+        let options = CsvOptions {
+            precision: 2,
+            quote_all: true,
+            line_ending: CsvLineEnding::CrLf,
+            ..CsvOptions::default()
+        };
+
+        let csv = metrics.to_csv_with(true, None, &options);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("\"CER\",\"WER\""));
+        assert_eq!(
+            lines[1],
+            "\"0.12\",\"0.25\",\"3\",\"24\",\"23\",\"4\",\"4\",\"false\",\"0.88\""
+        );
+        assert_eq!(lines[2], "");
+    }
+
+    #[test]
+    fn test_csv_options_escape_and_validate() {
+        let options = CsvOptions {
+            delimiter: '\t',
+            ..CsvOptions::default()
+        };
+        assert_eq!(options.escape_field("a,b"), "a,b");
+        assert_eq!(options.escape_field("a\tb"), "\"a\tb\"");
+        assert_eq!(options.escape_field("dit \"oui\""), "\"dit \"\"oui\"\"\"");
+        assert_eq!(options.escape_field("ligne\r"), "\"ligne\r\"");
+        assert!(options.validate().is_ok());
+
+        // Séparateur décimal identique au délimiteur : la valeur est citée
+        let same = CsvOptions {
+            decimal_separator: ',',
+            precision: 1,
+            ..CsvOptions::default()
+        };
+        assert_eq!(same.format_decimal(0.5), "\"0,5\"");
+
+        for invalid in [
+            CsvOptions {
+                delimiter: '"',
+                ..CsvOptions::default()
+            },
+            CsvOptions {
+                decimal_separator: '\n',
+                ..CsvOptions::default()
+            },
+        ] {
+            assert!(invalid.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_character_errors_count_matches_levenshtein() {
+        let pairs = [
+            ("kitten", "sitting"),
+            ("helo world", "hello world"),
+            ("", "abc"),
+        ];
+        for (ocr, reference) in pairs {
+            assert_eq!(
+                character_errors(ocr, reference).len(),
+                levenshtein_distance(ocr, reference)
+            );
+        }
+    }
+
+    #[test]
+    fn test_character_errors_deletion_and_insertion() {
+        assert_eq!(
+            character_errors("cht", "chat"),
+            vec![TextError::Deletion {
+                position: 2,
+                expected: 'a'
+            }]
+        );
+        assert_eq!(
+            character_errors("chaat", "chat"),
+            vec![TextError::Insertion {
+                position: 2,
+                found: 'a'
+            }]
+        );
+    }
+
+    #[test]
+    fn test_word_diff_identical_texts_all_equal() {
+        let diff = word_diff("bonjour  le\nmonde", "bonjour le monde");
+        assert_eq!(diff.len(), 3);
+        assert!(diff.iter().all(|op| matches!(op, WordDiff::Equal(_))));
+    }
+
+    #[test]
+    fn test_word_diff_changes_match_word_distance() {
+        let diff = word_diff("le gros chot", "le chat dort");
+        let changes = diff
+            .iter()
+            .filter(|op| !matches!(op, WordDiff::Equal(_)))
+            .count();
+        assert_eq!(
+            changes,
+            word_levenshtein_distance(&["le", "gros", "chot"], &["le", "chat", "dort"])
+        );
+
+        assert_eq!(
+            word_diff("chat", "le chat"),
+            vec![
+                WordDiff::Deleted("le".to_string()),
+                WordDiff::Equal("chat".to_string())
+            ]
+        );
+        assert_eq!(
+            word_diff("le gros chat", "le chat"),
+            vec![
+                WordDiff::Equal("le".to_string()),
+                WordDiff::Inserted("gros".to_string()),
+                WordDiff::Equal("chat".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_character_confusions_single_substitution() {
+        let confusions = character_confusions("l1lo", "lilo");
+        assert_eq!(
+            confusions,
+            vec![ConfusionEntry {
+                expected: 'i',
+                found: '1',
+                count: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_character_confusions_missing_char_sentinel() {
+        let confusions = character_confusions("helo", "hello");
+        assert_eq!(confusions.len(), 1);
+        assert_eq!(confusions[0].expected, 'l');
+        assert_eq!(confusions[0].found, MISSING_CHAR);
+    }
+
+    #[test]
+    fn test_character_confusions_unicode() {
+        let confusions = character_confusions("coeur ete", "cœur été");
+        // "œ" lu "o" + "e" : une substitution et une insertion, pas d'octets isolés
+        assert!(
+            confusions
+                .iter()
+                .any(|c| c.expected == 'é' && c.found == 'e' && c.count == 2)
+        );
+        assert!(confusions.iter().any(|c| c.expected == 'œ'));
+        assert!(confusions.iter().all(|c| c.expected != '\u{fffd}'));
+    }
+
+    #[test]
+    fn test_confusion_matrix_accumulates_corpus() {
+        let mut matrix = ConfusionMatrix::new();
+        matrix.add_pair("l1lo", "lilo");
+        matrix.add_pair("1ci", "ici");
+        matrix.add_pair("0rdre", "Ordre");
+
+        assert_eq!(matrix.total(), 3);
+        let top = matrix.top_n(2);
+        assert_eq!(top.len(), 2);
+        assert_eq!((top[0].expected, top[0].found, top[0].count), ('i', '1', 2));
+        assert_eq!((top[1].expected, top[1].found, top[1].count), ('O', '0', 1));
+
+        let mut other = ConfusionMatrix::new();
+        other.add_pair("0", "O");
+        matrix.merge(&other);
+        assert_eq!(matrix.top_n(2)[1].count, 2);
+    }
+
+    #[test]
+    fn test_diff_report_contains_top_confusions() {
+        let report = generate_diff_report("l1lo", "lilo");
+        assert!(report.contains("TOP CONFUSIONS:"));
+        assert!(report.contains("'i' → '1' (1)"));
+
+        let report = generate_diff_report("same", "same");
+        assert!(report.contains("TOP CONFUSIONS:\n---------------\nNone"));
+    }
+
+    /// Texte synthétique déterministe d'environ `len` caractères (mots de 2 à 9 lettres).
+    fn synthetic_text(len: usize) -> String {
+        let mut state = 0x2545_F491u32;
+        let mut text = String::with_capacity(len + 16);
+        while text.len() < len {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let word_len = 2 + (state % 8) as usize;
+            for k in 0..word_len {
+                text.push((b'a' + ((state >> (k * 3)) % 26) as u8) as char);
+            }
+            text.push(if state.is_multiple_of(11) { '\n' } else { ' ' });
+        }
+        text
+    }
+
+    /// Applique des erreurs OCR isolées : une substitution tous les 500 caractères,
+    /// une insertion tous les 1 700 et une suppression tous les 2 900.
+    ///
+    /// Retourne le texte modifié et le nombre d'opérations appliquées.
+    fn corrupt(reference: &str) -> (String, usize) {
+        let mut ocr = String::with_capacity(reference.len());
+        let mut edits = 0;
+        for (i, c) in reference.chars().enumerate() {
+            if i > 0 && i.is_multiple_of(2900) {
+                edits += 1;
+                continue;
+            }
+            if i > 0 && i.is_multiple_of(500) {
+                ocr.push(if c == '#' { '@' } else { '#' });
+                edits += 1;
+                continue;
+            }
+            ocr.push(c);
+            if i > 0 && i.is_multiple_of(1700) {
+                ocr.push('%');
+                edits += 1;
+            }
+        }
+        (ocr, edits)
+    }
+
+    /// Compare par morceaux de taille fixe (coupant mots et caractères multi-octets).
+    fn streamed(ocr: &str, reference: &str, band_width: usize, chunk_chars: usize) -> OcrMetrics {
+        let mut comparer = StreamingComparer::new(reference, band_width);
+        let chars: Vec<char> = ocr.chars().collect();
+        for chunk in chars.chunks(chunk_chars) {
+            comparer.push_ocr_chunk(&chunk.iter().collect::<String>());
+        }
+        comparer.finalize()
+    }
+
+    #[test]
+    fn test_streaming_matches_full_comparison() {
+        let reference = format!("{}é ç ü", synthetic_text(2_500));
+        let (ocr, _) = corrupt(&reference);
+
+        assert_eq!(
+            streamed(&ocr, &reference, 32, 97),
+            compare_ocr_result(&ocr, &reference)
+        );
+    }
+
+    #[test]
+    fn test_streaming_edge_cases() {
+        assert_eq!(streamed("", "", 4, 10), compare_ocr_result("", ""));
+        assert_eq!(streamed("abc", "", 4, 10), compare_ocr_result("abc", ""));
+        assert_eq!(
+            streamed("", "abc def", 4, 10),
+            compare_ocr_result("", "abc def")
+        );
+        assert_eq!(
+            streamed("hello world", "hello world", 1, 3),
+            compare_ocr_result("hello world", "hello world")
+        );
+    }
+
+    #[test]
+    fn test_streaming_one_megabyte_generous_band() {
+        let reference = synthetic_text(1_000_000);
+        let (ocr, edits) = corrupt(&reference);
+
+        // Erreurs isolées : la distance exacte est le nombre d'opérations
+        let metrics = streamed(&ocr, &reference, 16, 4096);
+        assert_eq!(metrics.levenshtein_distance, edits);
+        assert_eq!(metrics.reference_char_count, reference.chars().count());
+        assert_eq!(metrics.ocr_char_count, ocr.chars().count());
+    }
+
+    #[test]
+    fn test_streaming_narrow_band_is_close_upper_bound() {
+        let reference = synthetic_text(200_000);
+        let (mut ocr, edits) = corrupt(&reference);
+        // Un bloc de 6 caractères parasites, plus large que la bande
+        ocr.insert_str(100_000, "######");
+        let exact = edits + 6;
+
+        let metrics = streamed(&ocr, &reference, 2, 1000);
+        assert!(metrics.levenshtein_distance >= exact);
+        assert!(
+            metrics.levenshtein_distance as f64 <= exact as f64 * 1.05,
+            "distance {} trop éloignée de {}",
+            metrics.levenshtein_distance,
+            exact
+        );
+    }
+
+    #[test]
+    fn test_banded_levenshtein_memory_is_bounded() {
+        let reference: Vec<char> = synthetic_text(50_000).chars().collect();
+        let band_width = 8;
+        let mut banded = BandedLevenshtein::new(reference.clone(), band_width);
+
+        for c in reference.iter().rev().take(20_000) {
+            banded.push(c);
+            assert!(banded.row.len() <= banded.max_row_len());
+        }
+
+        // Aucune ligne n'a dépassé la bande : les tampons n'ont jamais grandi
+        assert_eq!(banded.max_row_len(), 2 * band_width + 1);
+        assert!(banded.row.capacity() <= banded.max_row_len());
+        assert!(banded.next_row.capacity() <= banded.max_row_len());
+    }
+
+    #[test]
+    fn test_compare_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let reference = synthetic_text(20_000);
+        let (ocr, edits) = corrupt(&reference);
+        let ocr_path = dir.path().join("ocr.txt");
+        let reference_path = dir.path().join("reference.txt");
+        std::fs::write(&ocr_path, &ocr).unwrap();
+        std::fs::write(&reference_path, &reference).unwrap();
+
+        let metrics = compare_files(&ocr_path, &reference_path).unwrap();
+        assert_eq!(metrics.levenshtein_distance, edits);
+
+        assert!(compare_files(&dir.path().join("absent.txt"), &reference_path).is_err());
+    }
+
+    #[test]
+    fn test_unicode_words_apostrophe() {
+        let ocr = "l' école est fermée";
+        let reference = "l'école est fermée";
+
+        assert!(calculate_wer_with_tokenizer(ocr, reference, &Tokenizer::Whitespace) > 0.0);
+        assert_eq!(
+            calculate_wer_with_tokenizer(ocr, reference, &Tokenizer::UnicodeWords),
+            0.0
+        );
+        assert_eq!(
+            Tokenizer::UnicodeWords.tokenize("l’école, aujourd'hui !"),
+            ["l", "école", "aujourd", "hui"]
+        );
+    }
+
+    #[test]
+    fn test_unicode_words_cjk() {
+        let words = Tokenizer::UnicodeWords.tokenize("東京に行きます");
+        assert_eq!(words, ["東", "京", "に", "行", "き", "ま", "す"]);
+
+        // Sans espaces, le découpage par défaut ne voit qu'un seul mot
+        assert_eq!(Tokenizer::Whitespace.tokenize("東京に行きます").len(), 1);
+    }
+
+    #[test]
+    fn test_unicode_words_numbers_and_marks() {
+        assert_eq!(
+            Tokenizer::UnicodeWords.tokenize("Total: 12,50 €. Fin."),
+            ["Total", "12,50", "Fin"]
+        );
+        // Diacritique combinant (e + accent aigu) conservé dans le mot
+        assert_eq!(
+            Tokenizer::UnicodeWords.tokenize("e\u{301}cole"),
+            ["e\u{301}cole"]
+        );
+    }
+
+    #[test]
+    fn test_tokenizer_from_name() {
+        assert_eq!(
+            Tokenizer::from_name("whitespace").unwrap().kind(),
+            TokenizerKind::Whitespace
+        );
+        assert_eq!(
+            Tokenizer::from_name("unicode").unwrap().kind(),
+            TokenizerKind::UnicodeWords
+        );
+        let custom = Tokenizer::from_name(r"regex:\w+").unwrap();
+        assert_eq!(custom.kind(), TokenizerKind::Custom);
+        assert_eq!(custom.tokenize("a-b c"), ["a", "b", "c"]);
+
+        assert!(Tokenizer::from_name("mots").is_err());
+        assert!(Tokenizer::from_name("regex:(").is_err());
+    }
+
+    #[test]
+    fn test_compare_ocr_result_records_tokenizer() {
+        let default = compare_ocr_result("l' école", "l'école");
+        assert_eq!(default.tokenizer, TokenizerKind::Whitespace);
+        assert_eq!(default.ocr_word_count, 2);
+
+        let unicode = compare_ocr_result_with("l' école", "l'école", &Tokenizer::UnicodeWords);
+        assert_eq!(unicode.tokenizer, TokenizerKind::UnicodeWords);
+        assert_eq!(unicode.wer, 0.0);
+        assert_eq!(unicode.reference_word_count, 2);
+        assert_eq!(unicode.cer, default.cer);
+
+        let json = serde_json::to_value(&unicode).unwrap();
+        assert_eq!(json["tokenizer"], "unicode");
+        assert!(!generate_diff_report("a", "a").contains("Word tokenizer"));
+        assert!(
+            generate_diff_report_with("a", "a", &Tokenizer::UnicodeWords)
+                .contains("Word tokenizer:             unicode")
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_find_one_error() {
+        let text = "Article 3.\nLes Conditions génerales s'appliquent.";
+
+        let matches = fuzzy_find(text, "générales", 1);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].distance, 1);
+        assert_eq!(matches[0].text, "génerales");
+        let chars: Vec<char> = text.chars().collect();
+        let span: String = chars[matches[0].start..matches[0].end].iter().collect();
+        assert_eq!(span, "génerales");
+    }
+
+    #[test]
+    fn test_fuzzy_find_respects_threshold() {
+        assert!(fuzzy_find("Conditions gnrles", "générales", 1).is_empty());
+        assert_eq!(fuzzy_find("Conditions gnrles", "générales", 3).len(), 1);
+        assert!(fuzzy_find("texte", "", 2).is_empty());
+        assert!(fuzzy_find("", "motif", 2).is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_find_char_span_on_accented_text() {
+        // Les positions sont en caractères, pas en octets
+        let text = "éàü çà Conditions générales";
+
+        let matches = fuzzy_find(text, "Conditions", 0);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].start, matches[0].end), (7, 17));
+        assert_eq!(matches[0].text, "Conditions");
+    }
+
+    #[test]
+    fn test_fuzzy_find_deduplicates_and_sorts() {
+        // Les fins voisines d'une occurrence exacte ne sont pas dupliquées
+        let matches = fuzzy_find("prix : tota1 ... total ... t0tal", "total", 1);
+
+        let found: Vec<(&str, usize)> = matches
+            .iter()
+            .map(|m| (m.text.as_str(), m.distance))
+            .collect();
+        assert_eq!(found, [("total", 0), ("tota1", 1), ("t0tal", 1)]);
+        assert!(matches[1].start < matches[2].start);
+    }
 }
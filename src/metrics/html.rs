@@ -0,0 +1,420 @@
+//! Rapport HTML autonome de comparaison d'un texte OCR avec sa référence.
+//!
+//! Le rapport texte de [`generate_diff_report()`](super::generate_diff_report)
+//! convient à un terminal, moins à un relecteur non technique.
+//! [`generate_html_report()`] produit une page complète (CSS intégré, aucune
+//! ressource externe) : synthèse des métriques, badge du niveau de qualité,
+//! puis les deux textes côte à côte, alignés caractère par caractère.
+//! Substitutions en jaune des deux côtés, suppressions barrées en rouge côté
+//! référence, insertions en vert côté OCR. Les longues portions identiques
+//! sont repliées derrière une ellipse dépliable (`<details>`).
+
+use super::{
+    CharAlignment, OcrMetrics, QualityBand, Tokenizer, TokenizerKind, align_characters,
+    compare_ocr_result_with,
+};
+use crate::export::escape_html;
+
+/// Caractères identiques conservés de part et d'autre d'une différence.
+pub const DEFAULT_HTML_CONTEXT: usize = 40;
+
+/// Nombre minimal de caractères identiques masqués par un repli.
+///
+/// Une portion à peine plus longue que le contexte reste affichée : la
+/// déplier coûterait plus qu'elle ne fait gagner.
+const MIN_COLLAPSED_CHARS: usize = 10;
+
+/// Feuille de style intégrée au rapport.
+const STYLE: &str = "\
+body{font-family:system-ui,sans-serif;margin:2em;color:#222}
+table.metrics{border-collapse:collapse;margin-bottom:1.5em}
+table.metrics th,table.metrics td{border:1px solid #ccc;padding:.3em .8em;text-align:left}
+.badge{display:inline-block;padding:.2em .7em;border-radius:1em;font-weight:bold;color:#fff}
+.badge.perfect{background:#1b7f3b}.badge.excellent{background:#2e9e52}
+.badge.good{background:#7a9e1b}.badge.fair{background:#c98a00}.badge.poor{background:#c0392b}
+.sides{display:flex;gap:1em}
+.side{flex:1;min-width:0}
+.text{font-family:ui-monospace,monospace;white-space:pre-wrap;word-break:break-word;\
+border:1px solid #ccc;padding:.8em;background:#fafafa}
+.sub{background:#fff176}
+del.del{background:#ffcdd2;color:#b71c1c;text-decoration:line-through}
+ins.ins{background:#c8e6c9;color:#1b5e20;text-decoration:none}
+details.same{display:inline}
+details.same summary{display:inline;cursor:pointer;color:#777;list-style:none}
+details.same summary::-webkit-details-marker{display:none}
+details.same[open] summary{display:none}
+";
+
+/// Options de [`generate_html_report()`].
+#[derive(Debug, Clone)]
+pub struct HtmlReportOptions {
+    /// Caractères identiques affichés autour de chaque différence ; le reste
+    /// des longues portions identiques est replié.
+    pub context: usize,
+    /// Surligner les différences qui ne portent que sur des espaces blancs
+    /// (espaces, tabulations, fins de ligne). Désactivé, ces différences
+    /// sont affichées comme du texte ordinaire ; les métriques ne changent pas.
+    pub show_whitespace: bool,
+    /// Découpage en mots utilisé pour le WER de la synthèse.
+    pub tokenizer: Tokenizer,
+    /// Titre de la page.
+    pub title: String,
+}
+
+impl Default for HtmlReportOptions {
+    /// Contexte de [`DEFAULT_HTML_CONTEXT`] caractères, différences d'espaces
+    /// surlignées, découpage sur les espaces, titre "OCR comparison report".
+    fn default() -> Self {
+        Self {
+            context: DEFAULT_HTML_CONTEXT,
+            show_whitespace: true,
+            tokenizer: Tokenizer::Whitespace,
+            title: "OCR comparison report".to_string(),
+        }
+    }
+}
+
+/// Nature d'une cellule de l'alignement affiché.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CellKind {
+    /// Texte identique (ou différence d'espaces non surlignée).
+    Same,
+    /// Caractère de la référence lu comme un autre.
+    Substitution,
+    /// Caractère de la référence absent du texte OCR.
+    Deletion,
+    /// Caractère du texte OCR absent de la référence.
+    Insertion,
+}
+
+/// Étape de l'alignement telle qu'elle est affichée.
+#[derive(Debug, Clone, Copy)]
+struct Cell {
+    kind: CellKind,
+    reference: Option<char>,
+    ocr: Option<char>,
+}
+
+impl Cell {
+    /// Cellule d'une étape d'alignement.
+    fn from_step(step: CharAlignment, show_whitespace: bool) -> Self {
+        let (reference, ocr) = (step.reference_char(), step.ocr_char());
+        let kind = match step {
+            CharAlignment::Equal(_) => CellKind::Same,
+            // Différence d'espaces blancs seulement : affichée sans surlignage
+            _ if !show_whitespace
+                && reference.is_none_or(char::is_whitespace)
+                && ocr.is_none_or(char::is_whitespace) =>
+            {
+                CellKind::Same
+            }
+            CharAlignment::Substitution { .. } => CellKind::Substitution,
+            CharAlignment::Deletion(_) => CellKind::Deletion,
+            CharAlignment::Insertion(_) => CellKind::Insertion,
+        };
+        Self {
+            kind,
+            reference,
+            ocr,
+        }
+    }
+}
+
+/// Côté du rendu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Reference,
+    Ocr,
+}
+
+impl Side {
+    /// Caractère de la cellule de ce côté.
+    fn char_of(self, cell: &Cell) -> Option<char> {
+        match self {
+            Side::Reference => cell.reference,
+            Side::Ocr => cell.ocr,
+        }
+    }
+}
+
+/// Génère un rapport HTML autonome comparant le texte OCR à la référence.
+///
+/// La page contient un tableau des métriques (CER, WER, distance de
+/// Levenshtein, précision, nombres de caractères et de mots), un badge du
+/// niveau de qualité (celui de [`generate_diff_report()`](super::generate_diff_report)),
+/// puis la référence et le texte OCR côte à côte, construits à partir de
+/// la remontée de l'alignement de Levenshtein. Les textes sont échappés :
+/// un `<script>` présent dans l'un d'eux est affiché, jamais exécuté.
+///
+/// # Arguments
+///
+/// * `ocr` - Le texte extrait par OCR
+/// * `reference` - Le texte de référence attendu
+/// * `options` - Contexte autour des différences, espaces, découpage en mots
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::{HtmlReportOptions, generate_html_report};
+///
+/// let html = generate_html_report("Le cbat", "Le chat", &HtmlReportOptions::default());
+/// assert!(html.starts_with("<!DOCTYPE html>"));
+/// assert!(html.contains("<span class=\"sub\">h</span>"));
+/// assert!(html.contains("<span class=\"sub\">b</span>"));
+/// ```
+pub fn generate_html_report(ocr: &str, reference: &str, options: &HtmlReportOptions) -> String {
+    let metrics = compare_ocr_result_with(ocr, reference, &options.tokenizer);
+    let ocr_chars: Vec<char> = ocr.chars().collect();
+    let reference_chars: Vec<char> = reference.chars().collect();
+    let cells: Vec<Cell> = align_characters(&ocr_chars, &reference_chars)
+        .into_iter()
+        .map(|step| Cell::from_step(step, options.show_whitespace))
+        .collect();
+
+    let title = escape_html(&options.title);
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", title));
+    html.push_str(&format!("<style>\n{}</style>\n</head>\n<body>\n", STYLE));
+    html.push_str(&format!("<h1>{}</h1>\n", title));
+    html.push_str(&summary_table(&metrics));
+    html.push_str("<div class=\"sides\">\n");
+    for (side, heading) in [(Side::Reference, "Reference"), (Side::Ocr, "OCR")] {
+        html.push_str(&format!(
+            "<div class=\"side\">\n<h2>{}</h2>\n<div class=\"text {}\">{}</div>\n</div>\n",
+            heading,
+            match side {
+                Side::Reference => "reference",
+                Side::Ocr => "ocr",
+            },
+            render_side(&cells, side, options.context)
+        ));
+    }
+    html.push_str("</div>\n</body>\n</html>\n");
+    html
+}
+
+/// Tableau des métriques et badge du niveau de qualité.
+fn summary_table(metrics: &OcrMetrics) -> String {
+    let band = QualityBand::of(metrics);
+    let mut rows = vec![
+        (
+            "Character Error Rate (CER)",
+            format!("{:.2}%", metrics.cer * 100.0),
+        ),
+        (
+            "Word Error Rate (WER)",
+            format!("{:.2}%", metrics.wer * 100.0),
+        ),
+        (
+            "Levenshtein distance",
+            metrics.levenshtein_distance.to_string(),
+        ),
+        ("Accuracy", format!("{:.2}%", metrics.accuracy() * 100.0)),
+        (
+            "Reference",
+            format!(
+                "{} characters, {} words",
+                metrics.reference_char_count, metrics.reference_word_count
+            ),
+        ),
+        (
+            "OCR",
+            format!(
+                "{} characters, {} words",
+                metrics.ocr_char_count, metrics.ocr_word_count
+            ),
+        ),
+    ];
+    if metrics.tokenizer != TokenizerKind::Whitespace {
+        rows.push(("Word tokenizer", metrics.tokenizer.name().to_string()));
+    }
+
+    let mut table = format!(
+        "<p>Quality: <span class=\"badge {}\">{}</span></p>\n<table class=\"metrics\">\n",
+        band.name(),
+        escape_html(band.label())
+    );
+    for (label, value) in rows {
+        table.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", label, value));
+    }
+    table.push_str("</table>\n");
+    table
+}
+
+/// Rendu d'un côté de l'alignement : cellules regroupées par nature, longues
+/// portions identiques repliées.
+fn render_side(cells: &[Cell], side: Side, context: usize) -> String {
+    let mut html = String::new();
+    let mut start = 0;
+    while start < cells.len() {
+        let kind = cells[start].kind;
+        let end = cells[start..]
+            .iter()
+            .position(|cell| cell.kind != kind)
+            .map_or(cells.len(), |offset| start + offset);
+        let text: String = cells[start..end]
+            .iter()
+            .filter_map(|cell| side.char_of(cell))
+            .collect();
+
+        match (kind, side) {
+            (CellKind::Same, _) => {
+                let (has_before, has_after) = (start > 0, end < cells.len());
+                html.push_str(&render_same(&text, has_before, has_after, context));
+            }
+            (CellKind::Substitution, _) => {
+                html.push_str(&format!("<span class=\"sub\">{}</span>", visible(&text)));
+            }
+            (CellKind::Deletion, Side::Reference) => {
+                html.push_str(&format!("<del class=\"del\">{}</del>", visible(&text)));
+            }
+            (CellKind::Insertion, Side::Ocr) => {
+                html.push_str(&format!("<ins class=\"ins\">{}</ins>", visible(&text)));
+            }
+            // Suppression côté OCR, insertion côté référence : rien à afficher
+            (CellKind::Deletion, Side::Ocr) | (CellKind::Insertion, Side::Reference) => {}
+        }
+        start = end;
+    }
+    html
+}
+
+/// Portion identique, repliée au-delà de `context` caractères de chaque différence voisine.
+///
+/// `has_before` et `has_after` indiquent une différence avant et après la
+/// portion. Un texte sans aucune différence garde ses `context` premiers
+/// caractères visibles.
+fn render_same(text: &str, has_before: bool, has_after: bool, context: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let lead = if has_before || !has_after { context } else { 0 };
+    let trail = if has_after { context } else { 0 };
+    if chars.len() < lead + trail + MIN_COLLAPSED_CHARS {
+        return escape_html(text);
+    }
+
+    let collect = |range: &[char]| escape_html(&range.iter().collect::<String>());
+    let hidden = &chars[lead..chars.len() - trail];
+    format!(
+        "{}<details class=\"same\"><summary>… {} identical characters …</summary>{}</details>{}",
+        collect(&chars[..lead]),
+        hidden.len(),
+        collect(hidden),
+        collect(&chars[chars.len() - trail..])
+    )
+}
+
+/// Texte d'une différence, espaces blancs rendus visibles.
+///
+/// Une espace surlignée seule serait invisible : `␣` pour une espace, `⇥`
+/// pour une tabulation et `↵` suivi du retour à la ligne pour une fin de ligne.
+fn visible(text: &str) -> String {
+    let mut shown = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            ' ' => shown.push('␣'),
+            '\t' => shown.push('⇥'),
+            '\n' => shown.push_str("↵\n"),
+            _ => shown.push(c),
+        }
+    }
+    escape_html(&shown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Contenu du bloc de texte d'un côté du rapport.
+    fn side_text<'a>(html: &'a str, class: &str) -> &'a str {
+        let marker = format!("<div class=\"text {}\">", class);
+        let start = html.find(&marker).unwrap() + marker.len();
+        let end = start + html[start..].find("</div>").unwrap();
+        &html[start..end]
+    }
+
+    #[test]
+    fn test_known_diff_highlights_in_order() {
+        let html = generate_html_report(
+            "n cbat noirs",
+            "un chat noir",
+            &HtmlReportOptions::default(),
+        );
+
+        assert_eq!(
+            side_text(&html, "reference"),
+            "<del class=\"del\">u</del>n c<span class=\"sub\">h</span>at noir"
+        );
+        assert_eq!(
+            side_text(&html, "ocr"),
+            "n c<span class=\"sub\">b</span>at noir<ins class=\"ins\">s</ins>"
+        );
+        // CER de 3/12 : 25 %
+        assert!(html.contains("<span class=\"badge fair\">Fair (&lt; 30% error)</span>"));
+        assert!(html.contains("<tr><th>Levenshtein distance</th><td>3</td></tr>"));
+        // Autonome : aucune ressource externe
+        assert!(!html.contains("href=") && !html.contains("src="));
+    }
+
+    #[test]
+    fn test_input_text_is_escaped() {
+        let html = generate_html_report(
+            "<script>alert(1)</script> & co",
+            "<script>alert('x')</script> & co",
+            &HtmlReportOptions {
+                title: "Rapport <b>".to_string(),
+                ..HtmlReportOptions::default()
+            },
+        );
+
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;alert("));
+        assert!(html.contains("&amp; co"));
+        assert!(html.contains("<title>Rapport &lt;b&gt;</title>"));
+    }
+
+    #[test]
+    fn test_long_identical_middle_is_collapsed() {
+        let middle = "identique ".repeat(20);
+        let reference = format!("Début{}Fin.", middle);
+        let ocr = format!("Debut{}Fin,", middle);
+        let options = HtmlReportOptions {
+            context: 5,
+            ..HtmlReportOptions::default()
+        };
+        let html = generate_html_report(&ocr, &reference, &options);
+
+        let ocr_side = side_text(&html, "ocr");
+        // « but » + milieu + « Fin », moins 5 caractères de contexte de chaque côté
+        let hidden = 3 + middle.chars().count() + 3 - 10;
+        assert!(ocr_side.starts_with("D<span class=\"sub\">e</span>but"));
+        assert!(ocr_side.contains(&format!(
+            "<details class=\"same\"><summary>… {} identical characters …</summary>",
+            hidden
+        )));
+        assert!(ocr_side.ends_with("</details>e Fin<span class=\"sub\">,</span>"));
+        // Les deux côtés replient la même portion
+        assert_eq!(html.matches("<details class=\"same\">").count(), 2);
+
+        // Portion courte : rien n'est replié
+        let html = generate_html_report("Debut fin", "Début fin", &options);
+        assert!(!html.contains("<details"));
+    }
+
+    #[test]
+    fn test_whitespace_differences_can_be_hidden() {
+        let shown = generate_html_report("un  chat", "un chat", &HtmlReportOptions::default());
+        assert!(side_text(&shown, "ocr").contains("<ins class=\"ins\">␣</ins>"));
+
+        let hidden = generate_html_report(
+            "un  chat",
+            "un chat",
+            &HtmlReportOptions {
+                show_whitespace: false,
+                ..HtmlReportOptions::default()
+            },
+        );
+        assert_eq!(side_text(&hidden, "ocr"), "un  chat");
+        assert_eq!(side_text(&hidden, "reference"), "un chat");
+    }
+}
@@ -0,0 +1,517 @@
+//! Écriture des métriques dans des fichiers partagés entre plusieurs traitements.
+//!
+//! Plusieurs traitements batch (éventuellement sur des machines différentes)
+//! peuvent ajouter leurs métriques au même fichier réseau. Les deux
+//! implémentations de [`MetricsSink`] ouvrent le fichier en ajout et prennent
+//! un verrou consultatif exclusif ([`File::lock()`]) autour de chaque
+//! écriture ; chaque enregistrement est formaté entièrement puis écrit en un
+//! seul appel, terminé par `\n`, pour ne jamais entrelacer deux lignes :
+//!
+//! - [`CsvMetricsSink`] : une ligne CSV par enregistrement, l'en-tête n'étant
+//!   écrit que si le fichier est vide. Un fichier existant dont l'en-tête
+//!   diffère est refusé.
+//! - [`JsonlMetricsSink`] : un objet JSON `{ "metadata", "metrics" }` par ligne.
+//!
+//! [`MetricsCollection`] accumule les métriques d'un lot avant de les écrire
+//! avec [`MetricsCollection::flush_to()`].
+//!
+//! Le verrou est consultatif : il ne protège que des écrivains qui le
+//! prennent aussi. Sur un partage réseau, il dépend de la prise en charge
+//! des verrous par le système de fichiers (NFSv4, SMB).
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::metrics::CsvOptions;
+//! use text_recognition::metrics::compare_ocr_result_detailed;
+//! use text_recognition::metrics::sink::{CsvMetricsSink, MetricsCollection};
+//! use std::collections::HashMap;
+//! use std::path::Path;
+//!
+//! let mut collection = MetricsCollection::new();
+//! let metadata = HashMap::from([("image".to_string(), "page_1.png".to_string())]);
+//! collection.push(compare_ocr_result_detailed("Facture", "Facture"), metadata);
+//!
+//! let sink = CsvMetricsSink::open(Path::new("/mnt/partage/metrics.csv"), CsvOptions::default())?;
+//! collection.flush_to(&sink)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use super::{CsvOptions, DetailedOcrMetrics};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Destination d'enregistrements de métriques (métriques et métadonnées).
+///
+/// Les implémentations sont partagées entre threads : `write` prend `&self`.
+pub trait MetricsSink: Send + Sync + fmt::Debug {
+    /// Ajoute un enregistrement à la destination.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si l'enregistrement ne peut pas être écrit.
+    fn write(&self, metrics: &DetailedOcrMetrics, metadata: &HashMap<String, String>)
+    -> Result<()>;
+}
+
+/// Métriques accumulées au fil d'un traitement, avec leurs métadonnées.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::compare_ocr_result_detailed;
+/// use text_recognition::metrics::sink::MetricsCollection;
+/// use std::collections::HashMap;
+///
+/// let mut collection = MetricsCollection::new();
+/// collection.push(compare_ocr_result_detailed("Facturc", "Facture"), HashMap::new());
+///
+/// assert_eq!(collection.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsCollection {
+    records: Vec<(DetailedOcrMetrics, HashMap<String, String>)>,
+}
+
+impl MetricsCollection {
+    /// Crée une collection vide.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ajoute les métriques d'une image et leurs métadonnées.
+    ///
+    /// # Arguments
+    ///
+    /// * `metrics` - Métriques de l'image
+    /// * `metadata` - Métadonnées de l'enregistrement (image, paramètres...)
+    pub fn push(&mut self, metrics: DetailedOcrMetrics, metadata: HashMap<String, String>) {
+        self.records.push((metrics, metadata));
+    }
+
+    /// Nombre d'enregistrements de la collection.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Indique si la collection est vide.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Écrit tous les enregistrements dans `sink`, dans l'ordre d'ajout.
+    ///
+    /// La collection est conservée : elle peut être écrite dans plusieurs
+    /// destinations (CSV et JSONL).
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - Destination des enregistrements
+    ///
+    /// # Retour
+    ///
+    /// Le nombre d'enregistrements écrits.
+    ///
+    /// # Erreurs
+    ///
+    /// S'arrête à la première écriture refusée par `sink` ; les
+    /// enregistrements précédents restent écrits.
+    pub fn flush_to(&self, sink: &dyn MetricsSink) -> Result<usize> {
+        for (metrics, metadata) in &self.records {
+            sink.write(metrics, metadata)?;
+        }
+        Ok(self.records.len())
+    }
+}
+
+/// Fichier ouvert en ajout, verrouillé à chaque écriture.
+#[derive(Debug)]
+struct LockedAppendFile {
+    path: PathBuf,
+    /// Le verrou du système ne départage que des descripteurs distincts :
+    /// le mutex sérialise les threads qui partagent celui-ci.
+    file: Mutex<File>,
+}
+
+impl LockedAppendFile {
+    /// Ouvre (ou crée) le fichier en ajout et en lecture.
+    fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .with_context(|| {
+                format!(
+                    "Impossible d'ouvrir le fichier de métriques '{}'",
+                    path.display()
+                )
+            })?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Exécute `write` sous le verrou exclusif du fichier.
+    fn with_lock<T>(&self, write: impl FnOnce(&File) -> Result<T>) -> Result<T> {
+        let file = self
+            .file
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Verrou du fichier de métriques empoisonné"))?;
+        file.lock().with_context(|| {
+            format!(
+                "Impossible de verrouiller le fichier de métriques '{}'",
+                self.path.display()
+            )
+        })?;
+        let result = write(&file);
+        // Le verrou est aussi libéré à la fermeture du fichier
+        let unlocked = file.unlock().with_context(|| {
+            format!(
+                "Impossible de déverrouiller le fichier de métriques '{}'",
+                self.path.display()
+            )
+        });
+        let value = result?;
+        unlocked?;
+        Ok(value)
+    }
+
+    /// Écrit `record` (terminé par `\n`) en un seul appel.
+    fn append(&self, mut file: &File, record: &str) -> Result<()> {
+        file.write_all(record.as_bytes()).with_context(|| {
+            format!(
+                "Impossible d'écrire dans le fichier de métriques '{}'",
+                self.path.display()
+            )
+        })
+    }
+}
+
+/// Ajout de lignes CSV dans un fichier partagé.
+///
+/// L'en-tête est écrit avec la première ligne si le fichier est vide
+/// (taille nulle) ; sinon la première ligne du fichier doit être
+/// exactement l'en-tête de l'enregistrement (mêmes colonnes, dans le même
+/// ordre), qui dépend des clés de métadonnées et de `options`.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::{CsvOptions, compare_ocr_result_detailed};
+/// use text_recognition::metrics::sink::{CsvMetricsSink, MetricsSink};
+/// use std::collections::HashMap;
+///
+/// let dir = tempfile::tempdir()?;
+/// let path = dir.path().join("metrics.csv");
+/// let metrics = compare_ocr_result_detailed("Facture", "Facture");
+/// let metadata = HashMap::from([("image".to_string(), "a.png".to_string())]);
+///
+/// // Deux exécutions successives : un seul en-tête
+/// for _ in 0..2 {
+///     let sink = CsvMetricsSink::open(&path, CsvOptions::default())?;
+///     sink.write(&metrics, &metadata)?;
+/// }
+/// assert_eq!(std::fs::read_to_string(&path)?.lines().count(), 3);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct CsvMetricsSink {
+    file: LockedAppendFile,
+    options: CsvOptions,
+}
+
+impl CsvMetricsSink {
+    /// Ouvre (ou crée) le fichier CSV en ajout.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin du fichier CSV
+    /// * `options` - Délimiteur, séparateur décimal, précision, fin de ligne
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si `options` est invalide ou si le fichier ne
+    /// peut pas être ouvert.
+    pub fn open(path: &Path, options: CsvOptions) -> Result<Self> {
+        options.validate()?;
+        Ok(Self {
+            file: LockedAppendFile::open(path)?,
+            options,
+        })
+    }
+}
+
+impl MetricsSink for CsvMetricsSink {
+    fn write(
+        &self,
+        metrics: &DetailedOcrMetrics,
+        metadata: &HashMap<String, String>,
+    ) -> Result<()> {
+        let row = metrics.to_csv_with(false, Some(metadata), &self.options);
+        let with_header = metrics.to_csv_with(true, Some(metadata), &self.options);
+        let header = &with_header[..with_header.len() - row.len()];
+
+        self.file.with_lock(|file| {
+            let size = file
+                .metadata()
+                .with_context(|| {
+                    format!(
+                        "Impossible de lire la taille de '{}'",
+                        self.file.path.display()
+                    )
+                })?
+                .len();
+            if size == 0 {
+                return self.file.append(file, &with_header);
+            }
+
+            let existing = first_line(file).with_context(|| {
+                format!(
+                    "Impossible de lire l'en-tête de '{}'",
+                    self.file.path.display()
+                )
+            })?;
+            let expected = header.trim_end_matches(['\r', '\n']);
+            if existing != expected {
+                anyhow::bail!(
+                    "Le fichier CSV '{}' a un en-tête différent de celui des métriques à ajouter \
+                     (colonnes trouvées : {} ; attendues : {}). \
+                     Utilisez un autre fichier ou les mêmes options (--no-metadata, --csv-delimiter).",
+                    self.file.path.display(),
+                    existing,
+                    expected
+                );
+            }
+            self.file.append(file, &row)
+        })
+    }
+}
+
+/// Première ligne du fichier, sans sa fin de ligne.
+fn first_line(mut file: &File) -> Result<String> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut line = String::new();
+    BufReader::new(file).read_line(&mut line)?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Ajout d'enregistrements JSON Lines dans un fichier partagé.
+///
+/// Chaque ligne est un objet `{ "metadata": {...}, "metrics": {...} }`,
+/// les métadonnées étant triées par clé et `metrics` ayant la forme
+/// sérialisée de [`DetailedOcrMetrics`].
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::compare_ocr_result_detailed;
+/// use text_recognition::metrics::sink::{JsonlMetricsSink, MetricsSink};
+/// use std::collections::HashMap;
+///
+/// let dir = tempfile::tempdir()?;
+/// let path = dir.path().join("metrics.jsonl");
+/// let sink = JsonlMetricsSink::open(&path)?;
+/// sink.write(&compare_ocr_result_detailed("Facture", "Facture"), &HashMap::new())?;
+///
+/// let line: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+/// assert_eq!(line["metrics"]["cer"], 0.0);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct JsonlMetricsSink {
+    file: LockedAppendFile,
+}
+
+/// Ligne d'un fichier JSONL de métriques.
+#[derive(Serialize)]
+struct JsonlRecord<'a> {
+    metadata: BTreeMap<&'a str, &'a str>,
+    metrics: &'a DetailedOcrMetrics,
+}
+
+impl JsonlMetricsSink {
+    /// Ouvre (ou crée) le fichier JSONL en ajout.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin du fichier JSONL
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le fichier ne peut pas être ouvert.
+    pub fn open(path: &Path) -> Result<Self> {
+        Ok(Self {
+            file: LockedAppendFile::open(path)?,
+        })
+    }
+}
+
+impl MetricsSink for JsonlMetricsSink {
+    fn write(
+        &self,
+        metrics: &DetailedOcrMetrics,
+        metadata: &HashMap<String, String>,
+    ) -> Result<()> {
+        let record = JsonlRecord {
+            metadata: metadata
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect(),
+            metrics,
+        };
+        let mut line = serde_json::to_string(&record).context("Échec de la sérialisation JSON")?;
+        line.push('\n');
+
+        self.file.with_lock(|file| self.file.append(file, &line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::compare_ocr_result_detailed;
+    use std::fs;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn metadata(image: &str) -> HashMap<String, String> {
+        HashMap::from([
+            ("image".to_string(), image.to_string()),
+            ("psm".to_string(), "3".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_csv_append_keeps_single_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.csv");
+        let metrics = compare_ocr_result_detailed("Facturc", "Facture");
+
+        let first = CsvMetricsSink::open(&path, CsvOptions::default()).unwrap();
+        first.write(&metrics, &metadata("a.png")).unwrap();
+        first.write(&metrics, &metadata("b.png")).unwrap();
+        drop(first);
+        // Une autre exécution ajoute au même fichier
+        let second = CsvMetricsSink::open(&path, CsvOptions::default()).unwrap();
+        second.write(&metrics, &metadata("c.png")).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("image,psm,CER,WER"));
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.starts_with("image,"))
+                .count(),
+            1
+        );
+        assert!(lines[3].starts_with("c.png,3,"));
+        assert!(content.ends_with('\n'));
+    }
+
+    #[test]
+    fn test_csv_rejects_different_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.csv");
+        fs::write(&path, "image,CER,WER\na.png,0.1,0.2\n").unwrap();
+        let before = fs::read_to_string(&path).unwrap();
+
+        let sink = CsvMetricsSink::open(&path, CsvOptions::default()).unwrap();
+        let error = sink
+            .write(&compare_ocr_result_detailed("a", "a"), &metadata("b.png"))
+            .unwrap_err();
+
+        let message = format!("{:#}", error);
+        assert!(message.contains("en-tête différent"), "{}", message);
+        assert!(message.contains("image,CER,WER"), "{}", message);
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+    }
+
+    #[test]
+    fn test_jsonl_concurrent_writers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = Arc::new(dir.path().join("metrics.jsonl"));
+        let metrics = Arc::new(compare_ocr_result_detailed("Facturc n°12", "Facture n°12"));
+
+        // Un descripteur par thread, comme deux traitements distincts
+        let writers: Vec<_> = (0..2)
+            .map(|worker| {
+                let path = Arc::clone(&path);
+                let metrics = Arc::clone(&metrics);
+                thread::spawn(move || {
+                    let sink = JsonlMetricsSink::open(&path).unwrap();
+                    for index in 0..100 {
+                        let image = format!("w{}_{}.png", worker, index);
+                        sink.write(&metrics, &metadata(&image)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let content = fs::read_to_string(path.as_path()).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 200);
+        for line in lines {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["metadata"]["psm"], "3");
+            assert!(value["metrics"]["cer"].as_f64().unwrap() > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_shared_sink_between_threads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.csv");
+        let sink = Arc::new(CsvMetricsSink::open(&path, CsvOptions::default()).unwrap());
+        let metrics = compare_ocr_result_detailed("a", "a");
+
+        thread::scope(|scope| {
+            for worker in 0..4 {
+                let sink = Arc::clone(&sink);
+                let metrics = &metrics;
+                scope.spawn(move || {
+                    for index in 0..25 {
+                        let image = format!("{}_{}.png", worker, index);
+                        sink.write(metrics, &metadata(&image)).unwrap();
+                    }
+                });
+            }
+        });
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 101);
+        assert_eq!(content.matches("image,psm").count(), 1);
+    }
+
+    #[test]
+    fn test_collection_flush_to_sinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut collection = MetricsCollection::new();
+        assert!(collection.is_empty());
+        collection.push(compare_ocr_result_detailed("a", "a"), metadata("a.png"));
+        collection.push(compare_ocr_result_detailed("b", "c"), metadata("b.png"));
+
+        let csv = CsvMetricsSink::open(&dir.path().join("m.csv"), CsvOptions::default()).unwrap();
+        let jsonl = JsonlMetricsSink::open(&dir.path().join("m.jsonl")).unwrap();
+        assert_eq!(collection.flush_to(&csv).unwrap(), 2);
+        assert_eq!(collection.flush_to(&jsonl).unwrap(), 2);
+
+        let csv_content = fs::read_to_string(dir.path().join("m.csv")).unwrap();
+        assert_eq!(csv_content.lines().count(), 3);
+        let jsonl_content = fs::read_to_string(dir.path().join("m.jsonl")).unwrap();
+        assert_eq!(jsonl_content.lines().count(), 2);
+        assert!(jsonl_content.lines().nth(1).unwrap().contains("\"b.png\""));
+    }
+}
@@ -0,0 +1,505 @@
+//! Métriques de détection des mots par rapport à une vérité terrain spatiale.
+//!
+//! Le CER et le WER ne mesurent que la transcription. Ce module évalue aussi
+//! la détection : chaque mot annoté (rectangle + texte) est apparié au mot
+//! reconnu qui le recouvre le mieux (IoU), puis la précision, le rappel et
+//! le F1 sont calculés, ainsi que le CER restreint aux paires appariées.
+//!
+//! # Format des annotations
+//!
+//! [`load_annotations()`] accepte deux formats, choisis selon l'extension :
+//!
+//! - `.json` : tableau d'objets `{"x": 10, "y": 20, "w": 80, "h": 18, "text": "Facture"}`
+//! - `.csv` : 5 colonnes `x,y,w,h,text` (ligne d'en-tête facultative, texte
+//!   entre guillemets s'il contient une virgule)
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::hocr::HocrDocument;
+//! use text_recognition::metrics::spatial::{
+//!     DEFAULT_IOU_THRESHOLD, evaluate_detection, load_annotations,
+//! };
+//! use std::path::Path;
+//!
+//! let annotations = load_annotations(Path::new("facture.json"))?;
+//! let doc = HocrDocument::from_hocr_string(&std::fs::read_to_string("facture.hocr")?)?;
+//! let metrics = evaluate_detection(&annotations, &doc, DEFAULT_IOU_THRESHOLD);
+//! println!("F1 : {:.2}", metrics.f1);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::hocr::{BBox, HocrDocument, HocrWord};
+use crate::metrics::levenshtein_distance;
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Seuil d'IoU par défaut pour apparier un mot reconnu à un mot annoté.
+pub const DEFAULT_IOU_THRESHOLD: f64 = 0.5;
+
+/// Mot de la vérité terrain : rectangle et texte attendu.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WordAnnotation {
+    /// Rectangle du mot dans l'image.
+    pub bbox: BBox,
+    /// Texte attendu.
+    pub text: String,
+}
+
+/// Appariement d'un mot annoté et d'un mot reconnu.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WordMatch {
+    /// Indice du mot dans les annotations.
+    pub annotation: usize,
+    /// Indice du mot reconnu (ordre de lecture du document).
+    pub predicted: usize,
+    /// Intersection sur union des deux rectangles.
+    pub iou: f64,
+}
+
+/// Métriques de détection calculées par [`evaluate_detection()`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DetectionMetrics {
+    /// Mots annotés appariés à un mot reconnu.
+    pub true_positives: usize,
+    /// Mots reconnus sans mot annoté correspondant.
+    pub false_positives: usize,
+    /// Mots annotés qui n'ont pas été détectés.
+    pub false_negatives: usize,
+    /// Précision : TP / (TP + FP), 0.0 sans mot reconnu.
+    pub precision: f64,
+    /// Rappel : TP / (TP + FN), 0.0 sans mot annoté.
+    pub recall: f64,
+    /// Moyenne harmonique de la précision et du rappel.
+    pub f1: f64,
+    /// CER calculé uniquement sur les paires appariées (0.0 sans appariement).
+    pub matched_cer: f64,
+    /// Paires appariées, par indice d'annotation croissant.
+    pub matches: Vec<WordMatch>,
+}
+
+/// Ligne d'un fichier d'annotations (JSON ou CSV).
+#[derive(Debug, Deserialize)]
+struct AnnotationRecord {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+    text: String,
+}
+
+impl From<AnnotationRecord> for WordAnnotation {
+    fn from(record: AnnotationRecord) -> Self {
+        Self {
+            bbox: BBox::new(record.x, record.y, record.w, record.h),
+            text: record.text,
+        }
+    }
+}
+
+/// Charge des annotations de mots depuis un fichier JSON ou CSV.
+///
+/// Voir la documentation du module pour les deux formats acceptés.
+///
+/// # Arguments
+///
+/// * `path` - Chemin du fichier `.json` ou `.csv`
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le fichier ne peut pas être lu, si son extension
+/// n'est ni `.json` ni `.csv`, ou si une entrée est invalide (le numéro de
+/// ligne est indiqué pour le CSV).
+pub fn load_annotations(path: &Path) -> Result<Vec<WordAnnotation>> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Impossible de lire le fichier '{}'", path.display()))?;
+
+    match extension.as_deref() {
+        Some("json") => {
+            let records: Vec<AnnotationRecord> = serde_json::from_str(&content)
+                .with_context(|| format!("Fichier JSON invalide : '{}'", path.display()))?;
+            Ok(records.into_iter().map(WordAnnotation::from).collect())
+        }
+        Some("csv") => parse_annotations_csv(&content)
+            .with_context(|| format!("Fichier CSV invalide : '{}'", path.display())),
+        other => Err(anyhow!(
+            "Extension non supportée : '{}'. Utilisez .json ou .csv",
+            other.unwrap_or("(aucune)")
+        )),
+    }
+}
+
+/// Parse des annotations au format CSV `x,y,w,h,text`.
+fn parse_annotations_csv(content: &str) -> Result<Vec<WordAnnotation>> {
+    let mut annotations = Vec::new();
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.splitn(5, ',').collect();
+        if index == 0
+            && fields
+                .first()
+                .is_some_and(|f| f.trim().eq_ignore_ascii_case("x"))
+        {
+            continue;
+        }
+        if fields.len() != 5 {
+            anyhow::bail!(
+                "Ligne {} : 5 colonnes attendues (x,y,w,h,text), {} trouvée(s)",
+                index + 1,
+                fields.len()
+            );
+        }
+
+        let number = |field: &str, name: &str| -> Result<u32> {
+            field
+                .trim()
+                .parse()
+                .with_context(|| format!("Ligne {} : {} invalide '{}'", index + 1, name, field))
+        };
+        let record = AnnotationRecord {
+            x: number(fields[0], "x")?,
+            y: number(fields[1], "y")?,
+            w: number(fields[2], "w")?,
+            h: number(fields[3], "h")?,
+            text: unquote_csv_field(fields[4]),
+        };
+        annotations.push(WordAnnotation::from(record));
+    }
+
+    Ok(annotations)
+}
+
+/// Retire les guillemets d'un champ CSV (`"a,b"` → `a,b`, `""` → `"`).
+fn unquote_csv_field(field: &str) -> String {
+    match field
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+    {
+        Some(inner) => inner.replace("\"\"", "\""),
+        None => field.to_string(),
+    }
+}
+
+/// Apparie les mots annotés aux mots reconnus selon l'IoU de leurs rectangles.
+///
+/// L'appariement est glouton : les paires sont considérées par IoU
+/// décroissante et chaque mot n'est apparié qu'une fois. Une paire n'est
+/// retenue que si son IoU est non nulle et au moins égale à `iou_threshold`.
+///
+/// # Arguments
+///
+/// * `annotations` - Mots de la vérité terrain
+/// * `predicted` - Mots reconnus
+/// * `iou_threshold` - IoU minimale d'une paire (ex: [`DEFAULT_IOU_THRESHOLD`])
+///
+/// # Retour
+///
+/// Les paires retenues, triées par indice d'annotation.
+pub fn match_words(
+    annotations: &[WordAnnotation],
+    predicted: &[&HocrWord],
+    iou_threshold: f64,
+) -> Vec<WordMatch> {
+    let mut candidates: Vec<WordMatch> = annotations
+        .iter()
+        .enumerate()
+        .flat_map(|(a, annotation)| {
+            predicted
+                .iter()
+                .enumerate()
+                .map(move |(p, word)| WordMatch {
+                    annotation: a,
+                    predicted: p,
+                    iou: annotation.bbox.iou(&word.bbox),
+                })
+        })
+        .filter(|m| m.iou > 0.0 && m.iou >= iou_threshold)
+        .collect();
+
+    // IoU décroissante ; à égalité, ordre des annotations puis des mots
+    candidates.sort_by(|a, b| {
+        b.iou
+            .total_cmp(&a.iou)
+            .then(a.annotation.cmp(&b.annotation))
+            .then(a.predicted.cmp(&b.predicted))
+    });
+
+    let mut annotation_used = vec![false; annotations.len()];
+    let mut predicted_used = vec![false; predicted.len()];
+    let mut matches = Vec::new();
+    for candidate in candidates {
+        if annotation_used[candidate.annotation] || predicted_used[candidate.predicted] {
+            continue;
+        }
+        annotation_used[candidate.annotation] = true;
+        predicted_used[candidate.predicted] = true;
+        matches.push(candidate);
+    }
+
+    matches.sort_by_key(|m| m.annotation);
+    matches
+}
+
+/// Évalue la détection des mots d'un document HOCR par rapport aux annotations.
+///
+/// Les mots reconnus vides (espaces uniquement) sont ignorés. Le CER des
+/// paires appariées est le total des distances de Levenshtein rapporté au
+/// nombre total de caractères attendus de ces paires.
+///
+/// # Arguments
+///
+/// * `annotations` - Mots de la vérité terrain
+/// * `document` - Document HOCR produit par l'OCR
+/// * `iou_threshold` - IoU minimale pour apparier deux mots
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::hocr::{BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord};
+/// use text_recognition::metrics::spatial::{WordAnnotation, evaluate_detection};
+///
+/// let mut line = HocrLine::new(BBox::new(0, 0, 200, 20));
+/// line.add_word(HocrWord::new(BBox::new(0, 0, 80, 20), "Facture".to_string(), Some(95)));
+/// let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 200, 20));
+/// paragraph.add_line(line);
+/// let mut doc = HocrDocument::new();
+/// doc.add_paragraph(paragraph);
+///
+/// let annotations = vec![WordAnnotation {
+///     bbox: BBox::new(0, 0, 80, 20),
+///     text: "Facture".to_string(),
+/// }];
+/// let metrics = evaluate_detection(&annotations, &doc, 0.5);
+/// assert_eq!(metrics.f1, 1.0);
+/// assert_eq!(metrics.matched_cer, 0.0);
+/// ```
+pub fn evaluate_detection(
+    annotations: &[WordAnnotation],
+    document: &HocrDocument,
+    iou_threshold: f64,
+) -> DetectionMetrics {
+    let predicted: Vec<&HocrWord> = document
+        .paragraphs()
+        .flat_map(|paragraph| &paragraph.lines)
+        .flat_map(|line| &line.words)
+        .filter(|word| !word.text.trim().is_empty())
+        .collect();
+
+    let matches = match_words(annotations, &predicted, iou_threshold);
+
+    let true_positives = matches.len();
+    let false_positives = predicted.len() - true_positives;
+    let false_negatives = annotations.len() - true_positives;
+
+    let ratio = |numerator: usize, denominator: usize| {
+        if denominator == 0 {
+            0.0
+        } else {
+            numerator as f64 / denominator as f64
+        }
+    };
+    let precision = ratio(true_positives, predicted.len());
+    let recall = ratio(true_positives, annotations.len());
+    let f1 = if precision + recall == 0.0 {
+        0.0
+    } else {
+        2.0 * precision * recall / (precision + recall)
+    };
+
+    let (distance, reference_len) = matches.iter().fold((0, 0), |(distance, len), m| {
+        let expected = &annotations[m.annotation].text;
+        (
+            distance + levenshtein_distance(&predicted[m.predicted].text, expected),
+            len + expected.chars().count(),
+        )
+    });
+
+    DetectionMetrics {
+        true_positives,
+        false_positives,
+        false_negatives,
+        precision,
+        recall,
+        f1,
+        matched_cer: ratio(distance, reference_len),
+        matches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hocr::{HocrLine, HocrParagraph};
+    use std::io::Write;
+
+    fn annotation(x: u32, width: u32, text: &str) -> WordAnnotation {
+        WordAnnotation {
+            bbox: BBox::new(x, 0, width, 20),
+            text: text.to_string(),
+        }
+    }
+
+    fn document(words: Vec<HocrWord>) -> HocrDocument {
+        let mut line = HocrLine::new(BBox::new(0, 0, 500, 20));
+        for word in words {
+            line.add_word(word);
+        }
+        let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 500, 20));
+        paragraph.add_line(line);
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph);
+        doc
+    }
+
+    fn word(x: u32, width: u32, text: &str) -> HocrWord {
+        HocrWord::new(BBox::new(x, 0, width, 20), text.to_string(), Some(90))
+    }
+
+    /// Vérité terrain de 4 mots ; l'OCR manque "TTC", invente "@@" et décale
+    /// "42" de 14 px (IoU 26/54 ≈ 0.48, juste sous le seuil par défaut).
+    fn fixture() -> (Vec<WordAnnotation>, HocrDocument) {
+        let annotations = vec![
+            annotation(0, 100, "Facture"),
+            annotation(120, 100, "numéro"),
+            annotation(240, 40, "42"),
+            annotation(300, 60, "TTC"),
+        ];
+        let doc = document(vec![
+            word(0, 100, "Facture"),
+            word(120, 100, "numero"),
+            word(254, 40, "42"),
+            word(400, 30, "@@"),
+        ]);
+        (annotations, doc)
+    }
+
+    #[test]
+    fn test_evaluate_detection_default_threshold() {
+        let (annotations, doc) = fixture();
+        let metrics = evaluate_detection(&annotations, &doc, DEFAULT_IOU_THRESHOLD);
+
+        assert_eq!(metrics.true_positives, 2);
+        assert_eq!(metrics.false_positives, 2);
+        assert_eq!(metrics.false_negatives, 2);
+        assert_eq!(metrics.precision, 0.5);
+        assert_eq!(metrics.recall, 0.5);
+        assert_eq!(metrics.f1, 0.5);
+        // "numero" au lieu de "numéro" : 1 erreur sur 13 caractères appariés
+        assert_eq!(metrics.matched_cer, 1.0 / 13.0);
+        assert_eq!(
+            metrics
+                .matches
+                .iter()
+                .map(|m| (m.annotation, m.predicted))
+                .collect::<Vec<_>>(),
+            vec![(0, 0), (1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_detection_lower_threshold_matches_shifted_box() {
+        let (annotations, doc) = fixture();
+        let metrics = evaluate_detection(&annotations, &doc, 0.4);
+
+        assert_eq!(metrics.true_positives, 3);
+        assert_eq!(metrics.false_positives, 1);
+        assert_eq!(metrics.false_negatives, 1);
+        assert_eq!(metrics.precision, 0.75);
+        assert_eq!(metrics.recall, 0.75);
+        assert_eq!(metrics.f1, 0.75);
+        assert_eq!(metrics.matched_cer, 1.0 / 15.0);
+        assert!((metrics.matches[2].iou - 26.0 / 54.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_match_words_is_greedy_by_iou() {
+        let annotations = vec![annotation(0, 100, "a"), annotation(20, 100, "b")];
+        let near_b = word(18, 100, "b");
+        let near_a = word(10, 100, "a");
+        let predicted = vec![&near_b, &near_a];
+
+        let matches = match_words(&annotations, &predicted, 0.5);
+
+        // "b" (IoU 0.96) est apparié en premier, puis "a" avec le mot restant
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.annotation, m.predicted))
+                .collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_detection_empty() {
+        let metrics = evaluate_detection(&[], &HocrDocument::new(), DEFAULT_IOU_THRESHOLD);
+
+        assert_eq!(metrics.precision, 0.0);
+        assert_eq!(metrics.recall, 0.0);
+        assert_eq!(metrics.f1, 0.0);
+        assert_eq!(metrics.matched_cer, 0.0);
+    }
+
+    fn write_temp(suffix: &str, content: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::Builder::new().suffix(suffix).tempfile().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_load_annotations_json() {
+        let file = write_temp(
+            ".json",
+            r#"[{"x": 0, "y": 5, "w": 100, "h": 20, "text": "Facture"}]"#,
+        );
+        let annotations = load_annotations(file.path()).unwrap();
+
+        assert_eq!(
+            annotations,
+            vec![WordAnnotation {
+                bbox: BBox::new(0, 5, 100, 20),
+                text: "Facture".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_load_annotations_csv() {
+        let file = write_temp(
+            ".csv",
+            "x,y,w,h,text\n0,5,100,20,Facture\n\n120,5,40,20,\"1,5\"\n",
+        );
+        let annotations = load_annotations(file.path()).unwrap();
+
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations[0].bbox, BBox::new(0, 5, 100, 20));
+        assert_eq!(annotations[1].text, "1,5");
+    }
+
+    #[test]
+    fn test_load_annotations_csv_invalid_line() {
+        let file = write_temp(".csv", "0,5,100,20,Facture\n0,abc,1,1,x\n");
+        let error = format!("{:#}", load_annotations(file.path()).unwrap_err());
+
+        assert!(error.contains("Ligne 2"));
+    }
+
+    #[test]
+    fn test_load_annotations_unsupported_extension() {
+        let file = write_temp(".txt", "");
+        let error = load_annotations(file.path()).unwrap_err().to_string();
+
+        assert!(error.contains("Extension non supportée"));
+    }
+}
@@ -0,0 +1,392 @@
+//! Comparaison insensible à l'ordre des paragraphes ou des lignes.
+//!
+//! Sur une page à plusieurs colonnes ou un document de plusieurs pages,
+//! Tesseract peut lire des paragraphes justes dans un autre ordre que la
+//! référence : le CER classique compte alors chaque paragraphe déplacé comme
+//! supprimé puis réinséré. [`compare_unordered()`] découpe les deux textes
+//! en segments ([`Granularity`]), apparie chaque segment OCR au segment de
+//! référence le plus proche par une affectation optimale (algorithme
+//! hongrois, [`solve_assignment()`]) et calcule le CER de cet appariement,
+//! à côté des métriques ordinaires.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::metrics::unordered::{Granularity, compare_unordered};
+//!
+//! let reference = "Le marché ouvre à 8 heures.\n\nStationnement interdit le dimanche.";
+//! let ocr = "Stationnement interdit le dimanche.\n\nLe marché ouvre à 8 heures.";
+//!
+//! let comparison = compare_unordered(ocr, reference, Granularity::Paragraph);
+//! assert!(comparison.ordered.cer > 0.3);
+//! assert_eq!(comparison.cer, 0.0);
+//! assert_eq!(comparison.assignment, [Some(1), Some(0)]);
+//! ```
+
+use super::{OcrMetrics, compare_ocr_result, levenshtein_distance_fast};
+use serde::Serialize;
+
+/// Découpage des textes en segments appariables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Granularity {
+    /// Paragraphes, séparés par une ou plusieurs lignes vides.
+    Paragraph,
+    /// Lignes non vides.
+    Line,
+}
+
+impl Granularity {
+    /// Nom affiché dans les rapports (`paragraphs`, `lines`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Granularity::Paragraph => "paragraphs",
+            Granularity::Line => "lines",
+        }
+    }
+}
+
+/// Résultat de [`compare_unordered()`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnorderedComparison {
+    /// Découpage utilisé.
+    pub granularity: Granularity,
+    /// Métriques ordinaires, sensibles à l'ordre ([`compare_ocr_result()`]).
+    pub ordered: OcrMetrics,
+    /// CER de la meilleure affectation : distance totale rapportée au
+    /// nombre de caractères des segments de référence (0.0 sans référence).
+    pub cer: f64,
+    /// Somme des distances de Levenshtein des paires, plus la longueur des
+    /// segments sans correspondant.
+    pub distance: usize,
+    /// Nombre de caractères des segments de référence (espaces de bord exclus).
+    pub reference_char_count: usize,
+    /// Nombre de segments de référence.
+    pub reference_segments: usize,
+    /// Nombre de segments OCR.
+    pub ocr_segments: usize,
+    /// Pour chaque segment de référence, l'indice du segment OCR apparié
+    /// (`None` : segment manquant dans l'OCR).
+    pub assignment: Vec<Option<usize>>,
+    /// Segments OCR sans segment de référence (texte en trop), par indice croissant.
+    pub unmatched_ocr: Vec<usize>,
+}
+
+impl UnorderedComparison {
+    /// Indique si l'ordre des segments OCR diffère de celui de la référence.
+    ///
+    /// Vrai si deux segments appariés sont inversés ; les segments manquants
+    /// ou en trop ne changent pas l'ordre.
+    pub fn is_reordered(&self) -> bool {
+        let matched: Vec<usize> = self.assignment.iter().flatten().copied().collect();
+        matched.windows(2).any(|pair| pair[0] > pair[1])
+    }
+}
+
+/// Découpe un texte en segments non vides, sans espaces de bord.
+///
+/// # Arguments
+///
+/// * `text` - Texte à découper
+/// * `granularity` - Paragraphes (séparés par des lignes vides) ou lignes
+pub fn split_segments(text: &str, granularity: Granularity) -> Vec<&str> {
+    match granularity {
+        Granularity::Line => text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect(),
+        Granularity::Paragraph => {
+            let mut paragraphs = Vec::new();
+            let mut start: Option<usize> = None;
+            let mut end = 0;
+            let mut offset = 0;
+            for line in text.split_inclusive('\n') {
+                if line.trim().is_empty() {
+                    if let Some(first) = start.take() {
+                        paragraphs.push(text[first..end].trim());
+                    }
+                } else {
+                    start.get_or_insert(offset);
+                    end = offset + line.len();
+                }
+                offset += line.len();
+            }
+            if let Some(first) = start {
+                paragraphs.push(text[first..end].trim());
+            }
+            paragraphs
+        }
+    }
+}
+
+/// Compare deux textes en appariant leurs segments indépendamment de leur ordre.
+///
+/// Le coût d'une paire (segment OCR, segment de référence) est leur
+/// distance de Levenshtein, c'est-à-dire le CER de la paire multiplié par la
+/// longueur du segment de référence : minimiser la somme des distances
+/// minimise le CER global, ce que ne garantit pas une somme de CER. Un
+/// segment de référence sans correspondant compte pour sa longueur
+/// (suppression), de même qu'un segment OCR en trop (insertion).
+/// L'affectation optimale est calculée par [`solve_assignment()`].
+///
+/// # Arguments
+///
+/// * `ocr_text` - Le texte extrait par OCR
+/// * `reference_text` - Le texte de référence attendu
+/// * `granularity` - Découpage en paragraphes ou en lignes
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::unordered::{Granularity, compare_unordered};
+///
+/// // Le paragraphe du milieu n'a pas été lu
+/// let comparison = compare_unordered("Un.\n\nTrois.", "Un.\n\nDeux.\n\nTrois.", Granularity::Paragraph);
+/// assert_eq!(comparison.assignment, [Some(0), None, Some(1)]);
+/// assert_eq!(comparison.distance, "Deux.".len());
+/// ```
+pub fn compare_unordered(
+    ocr_text: &str,
+    reference_text: &str,
+    granularity: Granularity,
+) -> UnorderedComparison {
+    let ocr = split_segments(ocr_text, granularity);
+    let reference = split_segments(reference_text, granularity);
+    let ocr_lengths: Vec<usize> = ocr.iter().map(|s| s.chars().count()).collect();
+    let reference_lengths: Vec<usize> = reference.iter().map(|s| s.chars().count()).collect();
+
+    // Matrice carrée : lignes = référence (puis segments fictifs), colonnes = OCR (idem)
+    let size = ocr.len().max(reference.len());
+    let costs: Vec<Vec<u64>> = (0..size)
+        .map(|r| {
+            (0..size)
+                .map(|o| match (reference.get(r), ocr.get(o)) {
+                    (Some(expected), Some(found)) => {
+                        levenshtein_distance_fast(found, expected) as u64
+                    }
+                    (Some(_), None) => reference_lengths[r] as u64,
+                    (None, Some(_)) => ocr_lengths[o] as u64,
+                    (None, None) => 0,
+                })
+                .collect()
+        })
+        .collect();
+    let columns = solve_assignment(&costs);
+
+    let assignment: Vec<Option<usize>> = (0..reference.len())
+        .map(|r| Some(columns[r]).filter(|&o| o < ocr.len()))
+        .collect();
+    let unmatched_ocr: Vec<usize> = (0..ocr.len())
+        .filter(|o| !assignment.contains(&Some(*o)))
+        .collect();
+    let distance = (0..size)
+        .map(|r| costs[r][columns[r]] as usize)
+        .sum::<usize>();
+    let reference_char_count: usize = reference_lengths.iter().sum();
+
+    UnorderedComparison {
+        granularity,
+        ordered: compare_ocr_result(ocr_text, reference_text),
+        cer: if reference_char_count == 0 {
+            if distance == 0 { 0.0 } else { 1.0 }
+        } else {
+            distance as f64 / reference_char_count as f64
+        },
+        distance,
+        reference_char_count,
+        reference_segments: reference.len(),
+        ocr_segments: ocr.len(),
+        assignment,
+        unmatched_ocr,
+    }
+}
+
+/// Résout un problème d'affectation de coût minimal (algorithme hongrois).
+///
+/// Version en O(n³) avec potentiels (Kuhn-Munkres) : chaque ligne est
+/// affectée à une colonne distincte de sorte que la somme des coûts soit
+/// minimale. Une matrice rectangulaire se ramène à ce cas en ajoutant des
+/// lignes ou des colonnes fictives, comme le fait [`compare_unordered()`].
+///
+/// # Arguments
+///
+/// * `costs` - Matrice carrée `n × n`, `costs[ligne][colonne]`
+///
+/// # Retour
+///
+/// Pour chaque ligne, la colonne affectée (vide pour une matrice vide).
+///
+/// # Panics
+///
+/// Panique si la matrice n'est pas carrée.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::metrics::unordered::solve_assignment;
+///
+/// let costs = vec![vec![4, 1, 3], vec![2, 0, 5], vec![3, 2, 2]];
+/// // 1 + 2 + 2 = 5 : ligne 0 → colonne 1, ligne 1 → colonne 0, ligne 2 → colonne 2
+/// assert_eq!(solve_assignment(&costs), [1, 0, 2]);
+/// ```
+pub fn solve_assignment(costs: &[Vec<u64>]) -> Vec<usize> {
+    let n = costs.len();
+    assert!(
+        costs.iter().all(|row| row.len() == n),
+        "la matrice de coûts doit être carrée"
+    );
+    if n == 0 {
+        return Vec::new();
+    }
+
+    // Indices à partir de 1 ; la colonne 0 est la colonne fictive de départ
+    let cost = |row: usize, column: usize| costs[row - 1][column - 1] as i128;
+    let mut row_potential = vec![0i128; n + 1];
+    let mut column_potential = vec![0i128; n + 1];
+    let mut row_of_column = vec![0usize; n + 1];
+    let mut previous = vec![0usize; n + 1];
+
+    for row in 1..=n {
+        row_of_column[0] = row;
+        let mut column = 0;
+        let mut min_slack = vec![i128::MAX; n + 1];
+        let mut used = vec![false; n + 1];
+        loop {
+            used[column] = true;
+            let current_row = row_of_column[column];
+            let mut delta = i128::MAX;
+            let mut next = 0;
+            for candidate in 1..=n {
+                if used[candidate] {
+                    continue;
+                }
+                let slack = cost(current_row, candidate)
+                    - row_potential[current_row]
+                    - column_potential[candidate];
+                if slack < min_slack[candidate] {
+                    min_slack[candidate] = slack;
+                    previous[candidate] = column;
+                }
+                if min_slack[candidate] < delta {
+                    delta = min_slack[candidate];
+                    next = candidate;
+                }
+            }
+            for candidate in 0..=n {
+                if used[candidate] {
+                    row_potential[row_of_column[candidate]] += delta;
+                    column_potential[candidate] -= delta;
+                } else {
+                    min_slack[candidate] -= delta;
+                }
+            }
+            column = next;
+            if row_of_column[column] == 0 {
+                break;
+            }
+        }
+        // Remonter le chemin augmentant
+        while column != 0 {
+            let before = previous[column];
+            row_of_column[column] = row_of_column[before];
+            column = before;
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for column in 1..=n {
+        assignment[row_of_column[column] - 1] = column - 1;
+    }
+    assignment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAGRAPHS: [&str; 3] = [
+        "Le conseil municipal s'est réuni le 12 mars.",
+        "L'ordre du jour portait sur le budget des écoles.",
+        "La séance a été levée à 21 heures.",
+    ];
+
+    #[test]
+    fn test_split_segments() {
+        let text = "  Titre\n\n\nPremier paragraphe\nsur deux lignes.\n \nFin\n";
+        assert_eq!(
+            split_segments(text, Granularity::Paragraph),
+            ["Titre", "Premier paragraphe\nsur deux lignes.", "Fin"]
+        );
+        assert_eq!(
+            split_segments(text, Granularity::Line),
+            ["Titre", "Premier paragraphe", "sur deux lignes.", "Fin"]
+        );
+        assert!(split_segments(" \n\n", Granularity::Paragraph).is_empty());
+    }
+
+    #[test]
+    fn test_swapped_paragraphs_have_zero_unordered_cer() {
+        let reference = PARAGRAPHS.join("\n\n");
+        let ocr = [PARAGRAPHS[2], PARAGRAPHS[0], PARAGRAPHS[1]].join("\n\n");
+
+        let comparison = compare_unordered(&ocr, &reference, Granularity::Paragraph);
+        assert!(comparison.ordered.cer > 0.4, "{}", comparison.ordered.cer);
+        assert_eq!(comparison.cer, 0.0);
+        assert_eq!(comparison.assignment, [Some(1), Some(2), Some(0)]);
+        assert!(comparison.is_reordered());
+        assert!(comparison.unmatched_ocr.is_empty());
+
+        // Une faute dans un paragraphe déplacé reste comptée
+        let ocr = ocr.replace("levée", "levee");
+        let comparison = compare_unordered(&ocr, &reference, Granularity::Paragraph);
+        assert_eq!(comparison.distance, 1);
+        assert!(comparison.cer > 0.0 && comparison.cer < 0.01);
+    }
+
+    #[test]
+    fn test_missing_and_extra_paragraphs_are_charged_in_full() {
+        let reference = PARAGRAPHS.join("\n\n");
+        let missing = [PARAGRAPHS[2], PARAGRAPHS[0]].join("\n\n");
+
+        let comparison = compare_unordered(&missing, &reference, Granularity::Paragraph);
+        assert_eq!(comparison.assignment, [Some(1), None, Some(0)]);
+        assert_eq!(comparison.distance, PARAGRAPHS[1].chars().count());
+        assert_eq!(
+            comparison.reference_char_count,
+            PARAGRAPHS.iter().map(|p| p.chars().count()).sum::<usize>()
+        );
+
+        let extra = format!("{}\n\nPage 2/2", reference);
+        let comparison = compare_unordered(&extra, &reference, Granularity::Paragraph);
+        assert_eq!(comparison.unmatched_ocr, [3]);
+        assert_eq!(comparison.distance, "Page 2/2".len());
+        assert!(!comparison.is_reordered());
+
+        let comparison = compare_unordered("", "", Granularity::Line);
+        assert_eq!(comparison.cer, 0.0);
+        assert_eq!(compare_unordered("bruit", "", Granularity::Line).cer, 1.0);
+    }
+
+    #[test]
+    fn test_solve_assignment_on_known_matrix() {
+        // Exemple classique : optimum 140 (ouvrier 0 → tâche 2, 1 → 1, 2 → 0, 3 → 3)
+        let costs = vec![
+            vec![82, 83, 69, 92],
+            vec![77, 37, 49, 92],
+            vec![11, 69, 5, 86],
+            vec![8, 9, 98, 23],
+        ];
+        let assignment = solve_assignment(&costs);
+        assert_eq!(assignment, [2, 1, 0, 3]);
+        let total: u64 = assignment
+            .iter()
+            .enumerate()
+            .map(|(row, &column)| costs[row][column])
+            .sum();
+        assert_eq!(total, 140);
+
+        assert!(solve_assignment(&[]).is_empty());
+        assert_eq!(solve_assignment(&[vec![7]]), [0]);
+    }
+}
@@ -4,14 +4,81 @@
 //! le moteur Tesseract OCR et permet d'extraire du texte depuis
 //! des images avec différentes configurations.
 
-use crate::config::OcrConfig;
+use crate::cancellation::{CancellationToken, wait_with_timeout};
+use crate::config::{OcrConfig, OcrEngineMode, PageSegMode, tesseract_settings_for};
+use crate::cross_check::CrossCheckReport;
+use crate::debug_artifacts::{ArtifactRecorder, IN_MEMORY_SOURCE, source_label};
+use crate::determinism::{DeterminismReport, determinism_check_with};
+use crate::diagnostics::{Warning, WarningKind, parse_tesseract_stderr};
+use crate::effective_config::EffectiveConfig;
+use crate::ensemble::{EnsembleResult, MergeStrategy, check_run_count, merge_documents};
+use crate::hocr::{
+    BBox, HocrDocument, PAGE_SEPARATOR, ReadingOrder, SortOrder, TextMetricsEstimate,
+    WordAlternatives, estimate_text_metrics, generate_hocr_file, merge_split_lines,
+    parse_alternatives, reorder_reading_order, suggest_dpi,
+};
+use crate::layout::{PsmRecommendation, layout_stats};
+use crate::metrics::{OcrMetrics, WordDiff, compare_ocr_result, levenshtein_distance, word_diff};
 use crate::preprocessing::{
-    Orientation, PreprocessingConfig, preprocess_image, rotate_orientation,
+    Orientation, OrientationInfo, PageContent, PreprocessingConfig, PreprocessingError,
+    PreprocessingReport, detect_page_split, detect_text_lines, detect_vertical_text,
+    downscale_factor, downscale_to_max_pixels, effective_dpi, full_orientation_correction,
+    is_blank_page, preprocess_image, preprocess_image_with_report, rotate_orientation,
+    save_with_dpi, split_pages, to_grayscale,
+};
+use crate::pyramid::{
+    PyramidConfig, PyramidLevel, PyramidReport, level_dpi, run_pyramid, scale_image,
 };
+use crate::region::RelativeRegion;
+use crate::result_cache::{CachedResult, ResultCache, cache_key};
+use crate::run_metadata::tesseract_version;
+use crate::segmentation::{
+    DEFAULT_IMAGE_OVERLAP, RegionClass, SkewedRegion, classify_region, drop_words_in_image_regions,
+    split_skewed_regions,
+};
+use crate::structured::StructuredText;
+use crate::worker::{WorkerCommand, WorkerJob};
 use anyhow::{Context, Result};
-use image::DynamicImage;
-use std::path::Path;
-use std::process::Command;
+use image::{DynamicImage, GrayImage};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+
+/// Erreur typée d'une extraction interrompue.
+///
+/// Retournée dans l'erreur `anyhow` (récupérable avec `downcast_ref`) par
+/// les extractions soumises à un délai ou à un [`CancellationToken`], ou
+/// dont le prétraitement a échoué.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum OcrError {
+    /// L'extraction n'a pas abouti dans le délai imparti.
+    #[error("délai de {:.1} s dépassé", .0.as_secs_f64())]
+    Timeout(Duration),
+
+    /// L'extraction a été annulée avant de se terminer.
+    #[error("extraction annulée")]
+    Cancelled,
+
+    /// Une étape du prétraitement a échoué.
+    #[error(transparent)]
+    Preprocessing(#[from] PreprocessingError),
+}
+
+/// Ajoute `context` à une erreur de prétraitement.
+///
+/// Une [`PreprocessingError`] est d'abord convertie en
+/// [`OcrError::Preprocessing`], pour que l'appelant d'une extraction
+/// n'ait qu'un type à inspecter.
+fn preprocessing_failure(error: anyhow::Error, context: &'static str) -> anyhow::Error {
+    match error.downcast::<PreprocessingError>() {
+        Ok(failure) => anyhow::Error::new(OcrError::from(failure)).context(context),
+        Err(error) => error.context(context),
+    }
+}
 
 /// Moteur OCR principal basé sur Tesseract.
 ///
@@ -27,12 +94,28 @@ use std::process::Command;
 /// let config = OcrConfig::default();
 /// let engine = OcrEngine::new(config).expect("Échec initialisation OCR");
 /// ```
-#[derive(Debug)]
+///
+/// # Concurrence
+///
+/// `OcrEngine` est `Send + Sync` et peut être partagé derrière un `Arc` :
+/// chaque extraction initialise sa propre instance Tesseract. Pour borner le
+/// nombre d'instances simultanées, utiliser [`OcrEnginePool`](crate::pool::OcrEnginePool).
+#[derive(Debug, Clone)]
 pub struct OcrEngine {
     /// Configuration du moteur OCR.
     config: OcrConfig,
     /// Configuration optionnelle du prétraitement d'images.
     preprocessing_config: Option<PreprocessingConfig>,
+    /// Délai maximal d'un appel au binaire tesseract, tué au-delà.
+    timeout: Option<Duration>,
+    /// Jeton consulté entre les étapes du pipeline d'extraction.
+    cancellation: Option<CancellationToken>,
+    /// Répertoire où conserver les fichiers intermédiaires de chaque extraction.
+    debug_artifacts_dir: Option<PathBuf>,
+    /// Cache des résultats consulté par `extract_text_detailed()`.
+    result_cache: Option<Arc<dyn ResultCache>>,
+    /// Processus enfant auquel confier chaque extraction.
+    isolation: Option<WorkerCommand>,
 }
 
 impl OcrEngine {
@@ -57,6 +140,7 @@ impl OcrEngine {
     ///     page_seg_mode: PageSegMode::Auto,
     ///     dpi: 300,
     ///     tesseract_variables: HashMap::new(),
+    ///     ..OcrConfig::default()
     /// };
     ///
     /// let engine = OcrEngine::new(config).expect("Échec initialisation OCR");
@@ -68,15 +152,63 @@ impl OcrEngine {
     /// - Tesseract n'est pas installé sur le système
     /// - Les données linguistiques spécifiées ne sont pas disponibles
     /// - L'initialisation de Tesseract échoue pour une autre raison
+    /// - Un fichier `user_words` ou `user_patterns` est introuvable ou illisible
+    /// - Le répertoire `tessdata_dir` n'existe pas ou ne contient pas le
+    ///   fichier `.traineddata` d'une langue configurée
     pub fn new(config: OcrConfig) -> Result<Self> {
         // Pour l'instant, on crée simplement la structure
         // La validation de Tesseract sera faite lors de l'utilisation réelle
+        config.validate_user_vocabulary()?;
+        config.validate_tessdata_dir()?;
         Ok(Self {
             config,
             preprocessing_config: None,
+            timeout: None,
+            cancellation: None,
+            debug_artifacts_dir: None,
+            result_cache: None,
+            isolation: None,
         })
     }
 
+    /// Crée un moteur OCR après avoir vérifié que ses langues sont installées.
+    ///
+    /// [`new()`](Self::new) ne contacte pas Tesseract : une langue absente
+    /// n'est signalée qu'à la première extraction, par une erreur
+    /// d'initialisation peu explicite. Ce constructeur interroge
+    /// `tesseract --list-langs` au préalable (voir [`validate_languages()`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Configuration du moteur OCR
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::config::OcrConfig;
+    /// use text_recognition::ocr::OcrEngine;
+    ///
+    /// let config = OcrConfig {
+    ///     language: "eng+fra".to_string(),
+    ///     ..OcrConfig::default()
+    /// };
+    /// let engine = OcrEngine::new_validated(config)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si une des langues de `config.language` n'est pas
+    /// installée, ou si les langues installées ne peuvent pas être listées.
+    /// Avec `tessdata_dir`, les modèles sont cherchés dans ce répertoire
+    /// (voir [`OcrConfig::validate_tessdata_dir()`]) plutôt que listés.
+    pub fn new_validated(config: OcrConfig) -> Result<Self> {
+        if config.tessdata_dir.is_none() {
+            validate_languages(&config.language)?;
+        }
+        Self::new(config)
+    }
+
     /// Crée un nouveau moteur OCR avec configuration de prétraitement.
     ///
     /// Cette méthode permet d'activer le prétraitement d'images avant l'OCR,
@@ -102,16 +234,264 @@ impl OcrEngine {
     /// let engine = OcrEngine::with_preprocessing(ocr_config, preprocess_config)
     ///     .expect("Échec initialisation OCR");
     /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Mêmes erreurs que [`new()`](Self::new).
     pub fn with_preprocessing(
         config: OcrConfig,
         preprocessing_config: PreprocessingConfig,
     ) -> Result<Self> {
+        config.validate_user_vocabulary()?;
         Ok(Self {
             config,
             preprocessing_config: Some(preprocessing_config),
+            timeout: None,
+            cancellation: None,
+            debug_artifacts_dir: None,
+            result_cache: None,
+            isolation: None,
         })
     }
 
+    /// Limite la durée des appels au binaire `tesseract` (OSD, HOCR, OCR avec vocabulaire utilisateur).
+    ///
+    /// Au-delà du délai, le sous-processus est tué et l'appel retourne
+    /// [`OcrError::Timeout`]. L'OCR par la bibliothèque Tesseract, qui ne
+    /// peut pas être interrompu, se borne avec
+    /// [`run_with_deadline()`](Self::run_with_deadline).
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Délai maximal, ou `None` pour attendre sans limite
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Associe un jeton d'annulation consulté entre les étapes de l'extraction.
+    ///
+    /// Une fois le jeton annulé, l'extraction en cours s'arrête avant l'étape
+    /// suivante (OSD, chargement, prétraitement, reconnaissance) avec
+    /// [`OcrError::Cancelled`].
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Jeton partagé, par exemple levé sur Ctrl-C
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Conserve les fichiers intermédiaires de chaque extraction dans `dir`.
+    ///
+    /// Chaque extraction crée un sous-répertoire `<radical>-<horodatage>`
+    /// contenant l'image transmise à Tesseract, ses sorties brutes et un
+    /// fichier JSON de description (voir [`debug_artifacts`](crate::debug_artifacts)).
+    /// Un échec d'écriture n'interrompt pas l'extraction.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Répertoire des artefacts, créé au besoin
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::config::OcrConfig;
+    /// use text_recognition::ocr::OcrEngine;
+    /// use std::path::Path;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::default())?.with_debug_artifacts("/tmp/ocr-debug");
+    /// let text = engine.extract_text_from_file(Path::new("facture.png"))?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn with_debug_artifacts(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.debug_artifacts_dir = Some(dir.into());
+        self
+    }
+
+    /// Réutilise les résultats d'un cache pour les images déjà lues.
+    ///
+    /// [`extract_text_detailed()`](Self::extract_text_detailed) (et donc
+    /// [`extract_text_from_file()`](Self::extract_text_from_file)) calcule la
+    /// clé de l'image ([`cache_key()`]) et retourne le résultat en cache sans
+    /// lancer Tesseract ([`OcrOutcome::cache_hit`]) ; sinon, le résultat de
+    /// l'extraction est enregistré. Un échec d'enregistrement est signalé
+    /// par un avertissement sans interrompre l'extraction.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache` - Cache partagé, par exemple un [`DirectoryCache`](crate::result_cache::DirectoryCache)
+    pub fn with_result_cache(mut self, cache: Arc<dyn ResultCache>) -> Self {
+        self.result_cache = Some(cache);
+        self
+    }
+
+    /// Confie chaque extraction à un processus enfant lancé par `worker`.
+    ///
+    /// [`extract_text_detailed()`](Self::extract_text_detailed) et
+    /// [`extract_text_auto_rotated()`](Self::extract_text_auto_rotated)
+    /// transmettent l'image et la configuration à un nouveau processus (voir
+    /// [`worker`](crate::worker)) : un plantage de Tesseract sur une image
+    /// malformée devient une erreur de cette seule image. Le délai de
+    /// [`with_timeout()`](Self::with_timeout) borne alors toute l'extraction
+    /// dans l'enfant, qui est tué au-delà. Le cache de résultats reste
+    /// consulté par le processus courant ; les artefacts de débogage et le
+    /// rapport de prétraitement ne sont pas produits.
+    ///
+    /// # Arguments
+    ///
+    /// * `worker` - Commande du processus enfant, par exemple
+    ///   [`WorkerCommand::current_exe()`]
+    pub fn with_isolation(mut self, worker: WorkerCommand) -> Self {
+        self.isolation = Some(worker);
+        self
+    }
+
+    /// Extrait le texte de `path` dans un processus enfant.
+    fn extract_isolated(
+        &self,
+        worker: &WorkerCommand,
+        path: &Path,
+        auto_rotate: bool,
+    ) -> Result<OcrOutcome> {
+        self.check_cancelled()?;
+        let job = WorkerJob {
+            auto_rotate,
+            timeout_ms: self
+                .timeout
+                .map(|timeout| u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX)),
+            ..WorkerJob::new(path, self.config.clone(), self.preprocessing_config.clone())
+        };
+        worker.run(&job)
+    }
+
+    /// Retourne le répertoire des artefacts de débogage, s'il est configuré.
+    pub fn debug_artifacts_dir(&self) -> Option<&Path> {
+        self.debug_artifacts_dir.as_deref()
+    }
+
+    /// Démarre l'enregistrement des artefacts d'une extraction (sans effet si non configuré).
+    fn artifact_recorder(&self, source: &str, operation: &str) -> ArtifactRecorder {
+        ArtifactRecorder::start(
+            self.debug_artifacts_dir.as_deref(),
+            source,
+            operation,
+            &self.config,
+        )
+    }
+
+    /// Exécute une extraction sur un thread auxiliaire en la bornant à `timeout`.
+    ///
+    /// La fonction reçoit un clone du moteur. Si elle ne se termine pas à
+    /// temps, [`OcrError::Timeout`] est retourné immédiatement : le thread
+    /// auxiliaire ne peut pas être interrompu et termine son travail en
+    /// arrière-plan, son résultat étant ignoré (les sous-processus sont
+    /// eux tués si [`with_timeout()`](Self::with_timeout) est configuré).
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Délai maximal
+    /// * `extract` - Extraction à exécuter avec le moteur
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::ocr::OcrEngine;
+    /// use text_recognition::config::OcrConfig;
+    /// use std::path::PathBuf;
+    /// use std::time::Duration;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let path = PathBuf::from("photo.jpg");
+    /// let outcome = engine.run_with_deadline(Duration::from_secs(30), move |engine| {
+    ///     engine.extract_text_auto_rotated(&path)
+    /// })?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne [`OcrError::Timeout`] si le délai est dépassé, ou l'erreur de
+    /// l'extraction.
+    pub fn run_with_deadline<T, F>(&self, timeout: Duration, extract: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&OcrEngine) -> Result<T> + Send + 'static,
+    {
+        let engine = self.clone();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            // Le récepteur a disparu si le délai est dépassé : résultat ignoré
+            let _ = sender.send(extract(&engine));
+        });
+
+        match receiver.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(OcrError::Timeout(timeout).into()),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                anyhow::bail!("Le thread d'extraction s'est arrêté sans résultat")
+            }
+        }
+    }
+
+    /// Extrait le texte d'une image en abandonnant au-delà de `timeout`.
+    ///
+    /// Variante de [`extract_text_from_file()`](Self::extract_text_from_file)
+    /// exécutée par [`run_with_deadline()`](Self::run_with_deadline).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    /// * `timeout` - Délai maximal de l'extraction
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne [`OcrError::Timeout`] si le délai est dépassé, ou les mêmes
+    /// erreurs que [`extract_text_from_file()`](Self::extract_text_from_file).
+    pub fn extract_text_with_deadline(&self, path: &Path, timeout: Duration) -> Result<String> {
+        let path = path.to_path_buf();
+        self.run_with_deadline(timeout, move |engine| engine.extract_text_from_file(&path))
+    }
+
+    /// Retourne [`OcrError::Cancelled`] si le jeton d'annulation a été levé.
+    fn check_cancelled(&self) -> Result<()> {
+        match self.cancellation {
+            Some(ref token) => token.check(),
+            None => Ok(()),
+        }
+    }
+
+    /// Retourne la configuration OCR du moteur.
+    pub fn config(&self) -> &OcrConfig {
+        &self.config
+    }
+
+    /// Retourne la configuration effectivement transmise à Tesseract.
+    ///
+    /// Les variables sont triées et incluent `user_defined_dpi` ; les clés
+    /// absentes de la liste des paramètres Tesseract connus produisent un
+    /// avertissement (voir [`EffectiveConfig`]).
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::{OcrConfig, OcrEngine};
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let effective = engine.effective_config();
+    /// println!("{}", serde_json::to_string_pretty(&effective)?);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn effective_config(&self) -> EffectiveConfig {
+        EffectiveConfig::from_config(&self.config)
+    }
+
+    /// Retourne la configuration de prétraitement du moteur, s'il y en a une.
+    pub fn preprocessing_config(&self) -> Option<&PreprocessingConfig> {
+        self.preprocessing_config.as_ref()
+    }
+
     /// Détecte l'orientation et le script d'une image via le binaire Tesseract (PSM 0).
     ///
     /// Cette méthode appelle le binaire `tesseract` en ligne de commande avec `--psm 0`
@@ -144,19 +524,55 @@ impl OcrEngine {
     /// - Le fichier image n'existe pas ou est illisible
     /// - La détection échoue (image trop petite, format non supporté, etc.)
     pub fn detect_orientation(&self, path: &Path) -> Result<String> {
-        let path_str = path.to_str().context("Chemin invalide")?;
+        Ok(self.detect_orientation_detailed(path)?.text)
+    }
+
+    /// Détecte l'orientation et le script d'une image, avec les diagnostics de Tesseract.
+    ///
+    /// Identique à [`detect_orientation()`](Self::detect_orientation), mais les
+    /// lignes émises sur stderr par le binaire sont retournées dans
+    /// [`OcrOutcome::warnings`] au lieu d'être ignorées.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    ///
+    /// # Erreurs
+    ///
+    /// Mêmes erreurs que [`detect_orientation()`](Self::detect_orientation).
+    pub fn detect_orientation_detailed(&self, path: &Path) -> Result<OcrOutcome> {
+        self.detect_orientation_from(path, source_label(path))
+    }
+
+    /// Détection d'orientation dont les artefacts sont nommés d'après `source`.
+    fn detect_orientation_from(&self, path: &Path, source: &str) -> Result<OcrOutcome> {
+        let mut artifacts = self.artifact_recorder(source, "osd");
+        artifacts.copy_image(path, "");
+        let result = self.run_orientation_detection(path, &mut artifacts);
+        let failures = artifacts.finish(result.as_ref().err());
+        result.map(|mut outcome| {
+            outcome.warnings.extend(failures);
+            outcome
+        })
+    }
 
-        let output = Command::new("tesseract")
-            // OSD requiert obligatoirement le modèle "osd", indépendamment de la langue configurée.
-            // Utiliser une autre langue (ex: "fra") échouerait avec une erreur Tesseract.
-            .args([path_str, "stdout", "--psm", "0", "-l", "osd"])
-            .output()
-            .context(
-                "Impossible de lancer le binaire tesseract. Est-il installé et dans le PATH ?",
-            )?;
+    /// Lance l'OSD de Tesseract et conserve ses sorties brutes dans `artifacts`.
+    fn run_orientation_detection(
+        &self,
+        path: &Path,
+        artifacts: &mut ArtifactRecorder,
+    ) -> Result<OcrOutcome> {
+        // OSD requiert obligatoirement le modèle "osd", indépendamment de la langue configurée.
+        // Utiliser une autre langue (ex: "fra") échouerait avec une erreur Tesseract.
+        let started = Instant::now();
+        let output = self.run_binary(&self.osd_args(path))?;
+        artifacts.record_pass(self.config.dpi, started.elapsed());
+        artifacts.write("output.txt", &output.stdout);
+        artifacts.write("tesseract.stderr.txt", &output.stderr);
 
         // La sortie utile est sur stdout ; les warnings vont sur stderr
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let warnings = parse_tesseract_stderr(&String::from_utf8_lossy(&output.stderr));
 
         // Filtrer les lignes pertinentes (ignorer les lignes vides)
         let info: String = stdout
@@ -168,11 +584,17 @@ impl OcrEngine {
         if info.is_empty() {
             anyhow::bail!(
                 "Aucune information d'orientation retournée par Tesseract. \
-                 Assurez-vous que l'image est lisible et que les données linguistiques sont installées."
+                 Assurez-vous que l'image est lisible et que les données linguistiques sont installées.{}",
+                format_warnings_suffix(&warnings)
             );
         }
 
-        Ok(info)
+        Ok(OcrOutcome {
+            text: info,
+            warnings,
+            preprocessing: None,
+            cache_hit: false,
+        })
     }
 
     /// Détecte l'orientation d'une image et retourne l'image corrigée.
@@ -203,31 +625,153 @@ impl OcrEngine {
     /// - Le binaire `tesseract` n'est pas installé ou introuvable
     /// - Le chargement de l'image échoue
     pub fn detect_and_correct_orientation(&self, path: &Path) -> Result<DynamicImage> {
-        let path_str = path.to_str().context("Chemin invalide")?;
+        let orientation = self
+            .detect_orientation_info(path)?
+            .map_or(Orientation::Upright, |info| info.orientation);
+
+        // Charger l'image et appliquer la correction
+        let img = image::open(path)
+            .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
+
+        Ok(rotate_orientation(&img, orientation))
+    }
 
+    /// Détecte l'orientation et le script d'une image via Tesseract (PSM 0).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    ///
+    /// # Retour
+    ///
+    /// `None` si Tesseract n'a pas pu déterminer l'orientation
+    /// (ex: trop peu de caractères).
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le binaire `tesseract` n'est pas installé ou introuvable.
+    pub fn detect_orientation_info(&self, path: &Path) -> Result<Option<OrientationInfo>> {
         // Appeler Tesseract PSM 0 pour obtenir l'orientation
-        let output = Command::new("tesseract")
-            .args([path_str, "stdout", "--psm", "0", "-l", "osd"])
-            .output()
-            .context("Impossible de lancer le binaire tesseract")?;
+        let output = self.run_binary(&self.osd_args(path))?;
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(OrientationInfo::from_osd_output(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
 
-        // Parser la ligne "Orientation in degrees: N"
-        let degrees = stdout
-            .lines()
-            .find(|line| line.starts_with("Orientation in degrees:"))
-            .and_then(|line| line.split(':').nth(1))
-            .and_then(|val| val.trim().parse::<u32>().ok())
-            .unwrap_or(0);
+    /// Extrait le texte après correction automatique de l'orientation.
+    ///
+    /// La rotation grossière (multiple de 90°) provient de l'OSD de Tesseract.
+    /// Si le prétraitement active `deskew`, la correction fine est enchaînée
+    /// dans la même passe via [`full_orientation_correction()`] : une image
+    /// pivotée de 95° est ainsi entièrement redressée, et une heuristique de
+    /// projection prend le relais lorsque l'OSD échoue. Les autres étapes du
+    /// prétraitement sont appliquées ensuite.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::ocr::OcrEngine;
+    /// use text_recognition::config::OcrConfig;
+    /// use text_recognition::preprocessing::PreprocessingConfig;
+    /// use std::path::Path;
+    ///
+    /// let prep = PreprocessingConfig {
+    ///     deskew: true,
+    ///     ..Default::default()
+    /// };
+    /// let engine = OcrEngine::with_preprocessing(OcrConfig::default(), prep)?;
+    /// let outcome = engine.extract_text_auto_rotated(Path::new("photo.jpg"))?;
+    /// println!("{}", outcome.text);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Mêmes erreurs que [`extract_text_detailed()`](Self::extract_text_detailed).
+    pub fn extract_text_auto_rotated(&self, path: &Path) -> Result<OcrOutcome> {
+        if let Some(ref worker) = self.isolation {
+            return self.extract_isolated(worker, path, true);
+        }
+        if !path.exists() {
+            anyhow::bail!("Le fichier '{}' n'existe pas", path.display());
+        }
 
-        let orientation = Orientation::from_tesseract_degrees(degrees);
+        // Un échec de l'OSD n'est pas bloquant : l'image est traitée telle quelle,
+        // sauf dépassement du délai
+        let osd = match self.detect_orientation_info(path) {
+            Ok(osd) => osd,
+            Err(e) if e.downcast_ref::<OcrError>().is_some() => return Err(e),
+            Err(_) => None,
+        };
+        self.check_cancelled()?;
+        let orientation = osd
+            .as_ref()
+            .map_or(Orientation::Upright, |info| info.orientation);
 
-        // Charger l'image et appliquer la correction
         let img = image::open(path)
             .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
+        if let Some(outcome) = self.blank_page_outcome(&img) {
+            return Ok(outcome);
+        }
 
-        Ok(rotate_orientation(&img, orientation))
+        let source = source_label(path);
+        let Some(ref preprocess_config) = self.preprocessing_config else {
+            let rotated = rotate_orientation(&img, orientation);
+            return self.extract_text_from_image_with_dpi(&rotated, self.config.dpi, source);
+        };
+
+        // Réduire d'abord les très grandes images : la détection d'inclinaison
+        // est coûteuse sur des dizaines de mégapixels
+        let input_dimensions = (img.width(), img.height());
+        let scale_factor =
+            downscale_factor(img.width(), img.height(), preprocess_config.max_pixels)
+                .unwrap_or(1.0);
+        let dpi = effective_dpi(self.config.dpi, scale_factor);
+        let downscaled = downscale_to_max_pixels(&img, preprocess_config.max_pixels);
+        let mut steps_before = Vec::new();
+        if downscaled.is_some() {
+            steps_before.push("downscale".to_string());
+        }
+        let img = downscaled.unwrap_or(img);
+
+        let (corrected, skew_angle, remaining_config) = if preprocess_config.deskew {
+            let (corrected, angle) = full_orientation_correction(&img, osd.as_ref());
+            let remaining = PreprocessingConfig {
+                deskew: false,
+                ..preprocess_config.clone()
+            };
+            (corrected, Some(angle), remaining)
+        } else {
+            (
+                rotate_orientation(&img, orientation),
+                None,
+                preprocess_config.clone(),
+            )
+        };
+        if skew_angle.is_some() || orientation != Orientation::Upright {
+            steps_before.push("orientation".to_string());
+        }
+
+        self.check_cancelled()?;
+        let (preprocessed, mut report) =
+            preprocess_image_with_report(&corrected, &remaining_config)
+                .map_err(|e| preprocessing_failure(e, "Échec du prétraitement de l'image"))?;
+        self.check_cancelled()?;
+
+        // La réduction et l'orientation ont été traitées avant le pipeline
+        report.input_dimensions = input_dimensions;
+        report.scale_factor = scale_factor;
+        report.applied_steps.splice(0..0, steps_before);
+        report.detected_skew_angle = skew_angle;
+
+        let mut outcome = self.extract_text_from_image_with_dpi(&preprocessed, dpi, source)?;
+        outcome.preprocessing = Some(report);
+        Ok(outcome)
     }
 
     /// Extrait le texte d'une image.
@@ -259,6 +803,7 @@ impl OcrEngine {
     ///     page_seg_mode: PageSegMode::SingleBlock,
     ///     dpi: 300,
     ///     tesseract_variables: variables,
+    ///     ..OcrConfig::default()
     /// };
     ///
     /// let engine = OcrEngine::new(config)?;
@@ -276,102 +821,259 @@ impl OcrEngine {
     /// - Les données linguistiques ne sont pas disponibles
     /// - Une variable Tesseract invalide est définie
     pub fn extract_text_from_file(&self, path: &Path) -> Result<String> {
-        // Vérifier que le fichier existe
-        if !path.exists() {
-            anyhow::bail!("Le fichier '{}' n'existe pas", path.display());
-        }
-
-        // En mode OSD uniquement, déléguer vers detect_orientation()
-        if matches!(
-            self.config.page_seg_mode,
-            crate::config::PageSegMode::OsdOnly
-        ) {
-            return self.detect_orientation(path);
-        }
-
-        // Si le prétraitement est activé, charger et prétraiter l'image
-        if let Some(ref preprocess_config) = self.preprocessing_config {
-            let img = image::open(path)
-                .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
-
-            let preprocessed = preprocess_image(&img, preprocess_config)
-                .context("Échec du prétraitement de l'image")?;
-
-            return self.extract_text_from_image(&preprocessed);
-        }
-
-        // Sinon, utiliser directement le chemin du fichier
-        // Convertir le chemin en string
-        let path_str = path.to_str().context("Chemin invalide")?;
-
-        // Initialiser Tesseract avec la langue configurée
-        let mut tesseract = tesseract::Tesseract::new(None, Some(&self.config.language))
-            .context("Échec de l'initialisation de Tesseract")?;
-
-        // Appliquer le mode de segmentation de page
-        let psm = match self.config.page_seg_mode {
-            crate::config::PageSegMode::OsdOnly => tesseract::PageSegMode::PsmOsdOnly,
-            crate::config::PageSegMode::AutoOsd => tesseract::PageSegMode::PsmAutoOsd,
-            crate::config::PageSegMode::AutoOnly => tesseract::PageSegMode::PsmAutoOnly,
-            crate::config::PageSegMode::Auto => tesseract::PageSegMode::PsmAuto,
-            crate::config::PageSegMode::SingleColumn => tesseract::PageSegMode::PsmSingleColumn,
-            crate::config::PageSegMode::SingleBlockVertText => {
-                tesseract::PageSegMode::PsmSingleBlockVertText
-            }
-            crate::config::PageSegMode::SingleBlock => tesseract::PageSegMode::PsmSingleBlock,
-            crate::config::PageSegMode::SingleLine => tesseract::PageSegMode::PsmSingleLine,
-            crate::config::PageSegMode::SingleWord => tesseract::PageSegMode::PsmSingleWord,
-            crate::config::PageSegMode::CircleWord => tesseract::PageSegMode::PsmCircleWord,
-            crate::config::PageSegMode::SingleChar => tesseract::PageSegMode::PsmSingleChar,
-            crate::config::PageSegMode::SparseText => tesseract::PageSegMode::PsmSparseText,
-            crate::config::PageSegMode::SparseTextOsd => tesseract::PageSegMode::PsmSparseTextOsd,
-            crate::config::PageSegMode::RawLine => tesseract::PageSegMode::PsmRawLine,
-        };
-        tesseract.set_page_seg_mode(psm);
-
-        // Appliquer le DPI
-        tesseract = tesseract
-            .set_variable("user_defined_dpi", &self.config.dpi.to_string())
-            .context("Échec de la configuration du DPI")?;
-
-        // Appliquer toutes les variables Tesseract personnalisées
-        for (key, value) in &self.config.tesseract_variables {
-            tesseract = tesseract
-                .set_variable(key, value)
-                .with_context(|| format!("Échec de la configuration de la variable '{}'", key))?;
-        }
-
-        // Charger l'image
-        tesseract = tesseract
-            .set_image(path_str)
-            .context("Échec du chargement de l'image")?;
-
-        // Extraire le texte
-        let text = tesseract
-            .get_text()
-            .context("Échec de l'extraction du texte")?;
-
-        Ok(text)
+        Ok(self.extract_text_detailed(path)?.text)
     }
 
-    /// Extrait le texte d'une image en mémoire.
+    /// Extrait le texte d'une image avec les diagnostics émis par Tesseract.
     ///
-    /// Cette méthode prend une `DynamicImage` et utilise Tesseract pour
-    /// extraire son contenu textuel. Utile lorsque l'image a déjà été
-    /// chargée ou prétraitée en mémoire.
+    /// Identique à [`extract_text_from_file()`](Self::extract_text_from_file),
+    /// mais retourne aussi les avertissements de Tesseract (résolution
+    /// invalide, page vide, etc.) qui expliquent souvent un texte vide.
+    /// Un texte vide sans message explicite produit un avertissement
+    /// [`WarningKind::EmptyPage`].
     ///
     /// # Arguments
     ///
-    /// * `image` - L'image à analyser
+    /// * `path` - Chemin vers l'image à analyser
     ///
     /// # Exemple
     ///
     /// ```no_run
     /// use text_recognition::ocr::OcrEngine;
     /// use text_recognition::config::OcrConfig;
-    /// use image::open;
+    /// use std::path::Path;
     ///
-    /// let config = OcrConfig::default();
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let outcome = engine.extract_text_detailed(Path::new("image.png"))?;
+    /// for warning in &outcome.warnings {
+    ///     eprintln!("{:?}: {}", warning.kind(), warning);
+    /// }
+    /// println!("{}", outcome.text);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Mêmes erreurs que [`extract_text_from_file()`](Self::extract_text_from_file).
+    pub fn extract_text_detailed(&self, path: &Path) -> Result<OcrOutcome> {
+        self.check_cancelled()?;
+
+        let Some(ref cache) = self.result_cache else {
+            return self.extract_text_uncached(path);
+        };
+        // Clé incalculable (fichier absent...) : l'extraction signale l'erreur
+        let Ok(key) = cache_key(path, &self.config, self.preprocessing_config.as_ref()) else {
+            return self.extract_text_uncached(path);
+        };
+        if let Some(cached) = cache.get(&key) {
+            return Ok(cached.into());
+        }
+
+        let mut outcome = self.extract_text_uncached(path)?;
+        if let Err(e) = cache.put(&key, &CachedResult::from(&outcome)) {
+            outcome.warnings.push(Warning::new(format!(
+                "Cache de résultats : enregistrement impossible ({:#})",
+                e
+            )));
+        }
+        Ok(outcome)
+    }
+
+    /// Extrait le texte d'une image en la lisant d'abord réduite.
+    ///
+    /// L'image est analysée aux échelles `levels`, croissantes (ex:
+    /// `[0.3, 0.6, 1.0]`), avec un DPI réduit d'autant
+    /// ([`level_dpi()`](crate::pyramid::level_dpi)). La lecture s'arrête dès
+    /// qu'un niveau confirme le précédent ([`is_stable()`](crate::pyramid::is_stable),
+    /// seuils de [`OcrConfig::pyramid`] ou par défaut) : le texte de ce
+    /// niveau est retenu, sinon celui du dernier niveau. Le prétraitement
+    /// configuré est appliqué à chaque niveau ; la coupure des doubles pages,
+    /// le passage en PSM 5 du texte vertical et la relecture ligne par ligne
+    /// ne le sont pas. Une page blanche (`skip_blank_pages`) n'est lue à
+    /// aucun niveau. Un avertissement [`WarningKind::PyramidLevel`] indique
+    /// le niveau retenu et le temps gagné estimé.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    /// * `levels` - Échelles essayées, strictement croissantes
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::ocr::OcrEngine;
+    /// use text_recognition::config::OcrConfig;
+    /// use std::path::Path;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let result = engine.extract_text_pyramid(Path::new("photo-24mpx.jpg"), &[0.3, 0.6, 1.0])?;
+    /// println!("{:?} gagnées", result.report.estimated_time_saved());
+    /// println!("{}", result.outcome.text);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si les échelles sont invalides
+    /// ([`PyramidConfig::validate()`]), si l'image ne peut pas être chargée
+    /// ou si l'OCR d'un niveau échoue.
+    pub fn extract_text_pyramid(&self, path: &Path, levels: &[f32]) -> Result<PyramidOutcome> {
+        self.check_cancelled()?;
+        let pyramid = PyramidConfig {
+            levels: levels.to_vec(),
+            ..self.config.pyramid.clone().unwrap_or_default()
+        };
+        pyramid.validate()?;
+        if !path.exists() {
+            anyhow::bail!("Le fichier '{}' n'existe pas", path.display());
+        }
+
+        let image = image::open(path)
+            .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
+        if let Some(outcome) = self.blank_page_outcome(&image) {
+            return Ok(PyramidOutcome {
+                outcome,
+                report: PyramidReport {
+                    levels: Vec::new(),
+                    requested_levels: levels.len(),
+                },
+            });
+        }
+
+        let source = source_label(path);
+        let (mut outcome, report) = run_pyramid(&pyramid, |scale| {
+            self.check_cancelled()?;
+            self.recognize_pyramid_level(&image, scale, source)
+        })?;
+        if let Some(level) = report.accepted() {
+            outcome.warnings.push(Warning::pyramid_level(
+                level.scale,
+                report.levels.len(),
+                report.estimated_time_saved(),
+            ));
+        }
+        Ok(PyramidOutcome { outcome, report })
+    }
+
+    /// Lit l'image réduite à l'échelle `scale`, avec la confiance moyenne de ses mots.
+    fn recognize_pyramid_level(
+        &self,
+        image: &DynamicImage,
+        scale: f32,
+        source: &str,
+    ) -> Result<(PyramidLevel, OcrOutcome)> {
+        let started = Instant::now();
+        let scaled = scale_image(image, scale);
+        let dpi = level_dpi(self.config.dpi, scale);
+        let (processed, dpi, report) = match self.preprocessing_config {
+            Some(ref preprocess_config) => {
+                let (preprocessed, report) =
+                    preprocess_image_with_report(&scaled, preprocess_config).map_err(|e| {
+                        preprocessing_failure(e, "Échec du prétraitement de l'image")
+                    })?;
+                self.check_cancelled()?;
+                let dpi = effective_dpi(dpi, report.scale_factor);
+                (Cow::Owned(preprocessed), dpi, Some(report))
+            }
+            None => (scaled, dpi, None),
+        };
+
+        let temp_dir = tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
+        let temp_path = temp_dir.path().join("temp_image.png");
+        save_with_dpi(&processed, &temp_path, dpi)?;
+        let path_str = temp_path.to_str().context("Chemin temporaire invalide")?;
+
+        let mut artifacts = self.artifact_recorder(&format!("{}-x{}", source, scale), "pyramid");
+        artifacts.copy_image(&temp_path, "");
+        // Le hOCR de la même reconnaissance fournit la confiance des mots
+        let result = self.recognize_pass(path_str, dpi, true, &mut artifacts, "");
+        let failures = artifacts.finish(result.as_ref().err());
+        let (mut outcome, hocr) = result?;
+        outcome.warnings.extend(failures);
+        outcome.preprocessing = report;
+
+        let level = PyramidLevel {
+            scale,
+            dpi,
+            text: outcome.text.clone(),
+            mean_confidence: hocr
+                .and_then(|hocr| HocrDocument::from_hocr_string(&hocr).ok())
+                .and_then(|document| document.mean_confidence()),
+            duration: started.elapsed(),
+        };
+        Ok((level, outcome))
+    }
+
+    /// Extrait le texte d'une image sans consulter le cache de résultats.
+    fn extract_text_uncached(&self, path: &Path) -> Result<OcrOutcome> {
+        if let Some(ref worker) = self.isolation {
+            return self.extract_isolated(worker, path, false);
+        }
+
+        // Vérifier que le fichier existe
+        if !path.exists() {
+            anyhow::bail!("Le fichier '{}' n'existe pas", path.display());
+        }
+
+        // Lecture pyramidale demandée par la configuration (hors OSD seul)
+        if let Some(ref pyramid) = self.config.pyramid
+            && !matches!(
+                self.config.page_seg_mode,
+                crate::config::PageSegMode::OsdOnly
+            )
+        {
+            return Ok(self.extract_text_pyramid(path, &pyramid.levels)?.outcome);
+        }
+
+        // GIF et WebP : décodés ici (première image) plutôt que par le chargeur de Tesseract
+        if needs_in_memory_decoding(path) {
+            let img = image::open(path)
+                .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
+            return self.extract_text_from_decoded_image(&img, source_label(path));
+        }
+
+        // En mode OSD uniquement, déléguer vers detect_orientation()
+        if matches!(
+            self.config.page_seg_mode,
+            crate::config::PageSegMode::OsdOnly
+        ) {
+            return self.detect_orientation_detailed(path);
+        }
+
+        // Si le prétraitement ou la détection du texte vertical est activé, charger l'image
+        if self.preprocessing_config.is_some() || self.config.auto_vertical {
+            let img = image::open(path)
+                .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
+
+            return self.extract_text_from_loaded_image(&img, source_label(path));
+        }
+
+        // Sinon, utiliser directement le chemin du fichier (copié si la
+        // bibliothèque ne peut pas le recevoir tel quel)
+        let input = LibraryInput::new(path)?;
+        let mut outcome = self.recognize_file(&input.path, self.config.dpi, source_label(path))?;
+        outcome.warnings.extend(input.warning);
+        Ok(outcome)
+    }
+
+    /// Extrait le texte d'une image en mémoire.
+    ///
+    /// Cette méthode prend une `DynamicImage` et utilise Tesseract pour
+    /// extraire son contenu textuel. Utile lorsque l'image a déjà été
+    /// chargée ou prétraitée en mémoire.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - L'image à analyser
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::ocr::OcrEngine;
+    /// use text_recognition::config::OcrConfig;
+    /// use image::open;
+    ///
+    /// let config = OcrConfig::default();
     /// let engine = OcrEngine::new(config)?;
     ///
     /// let img = open("document.png")?;
@@ -387,54 +1089,394 @@ impl OcrEngine {
     /// - L'image ne peut pas être convertie dans un format compatible
     /// - Une variable Tesseract invalide est définie
     pub fn extract_text_from_image(&self, image: &DynamicImage) -> Result<String> {
+        Ok(self.extract_text_from_image_detailed(image)?.text)
+    }
+
+    /// Extrait le texte d'une image en mémoire avec les diagnostics de Tesseract.
+    ///
+    /// Voir [`extract_text_detailed()`](Self::extract_text_detailed).
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - L'image à analyser
+    ///
+    /// # Erreurs
+    ///
+    /// Mêmes erreurs que [`extract_text_from_image()`](Self::extract_text_from_image).
+    pub fn extract_text_from_image_detailed(&self, image: &DynamicImage) -> Result<OcrOutcome> {
+        self.extract_text_from_image_with_dpi(image, self.config.dpi, IN_MEMORY_SOURCE)
+    }
+
+    /// Extrait le texte d'une image encodée en mémoire (PNG, JPEG, TIFF...).
+    ///
+    /// Le format est détecté à partir du contenu. Le prétraitement configuré
+    /// est appliqué comme pour [`extract_text_from_file()`](Self::extract_text_from_file).
+    /// Utile pour traiter des images reçues par le réseau ou sur l'entrée standard.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Contenu brut du fichier image
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::ocr::OcrEngine;
+    /// use text_recognition::config::OcrConfig;
+    ///
+    /// let bytes = std::fs::read("capture.png")?;
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let text = engine.extract_text_from_bytes(&bytes)?;
+    /// println!("{}", text);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si les octets ne forment pas une image décodable,
+    /// ou pour les mêmes raisons que [`extract_text_from_file()`](Self::extract_text_from_file).
+    pub fn extract_text_from_bytes(&self, bytes: &[u8]) -> Result<String> {
+        Ok(self.extract_text_from_bytes_detailed(bytes)?.text)
+    }
+
+    /// Extrait le texte d'une image encodée en mémoire avec les diagnostics de Tesseract.
+    ///
+    /// Voir [`extract_text_from_bytes()`](Self::extract_text_from_bytes).
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Contenu brut du fichier image
+    ///
+    /// # Erreurs
+    ///
+    /// Mêmes erreurs que [`extract_text_from_bytes()`](Self::extract_text_from_bytes).
+    pub fn extract_text_from_bytes_detailed(&self, bytes: &[u8]) -> Result<OcrOutcome> {
+        let img = decode_image_bytes(bytes)?;
+        self.extract_text_from_decoded_image(&img, IN_MEMORY_SOURCE)
+    }
+
+    /// Extrait le texte d'une image décodée hors de Tesseract (octets, GIF, WebP).
+    ///
+    /// `source` nomme les artefacts de débogage de l'extraction.
+    pub(crate) fn extract_text_from_decoded_image(
+        &self,
+        img: &DynamicImage,
+        source: &str,
+    ) -> Result<OcrOutcome> {
+        // La détection d'orientation passe par le binaire tesseract, qui lit un fichier
+        if matches!(
+            self.config.page_seg_mode,
+            crate::config::PageSegMode::OsdOnly
+        ) {
+            let temp_dir =
+                tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
+            let temp_path = temp_dir.path().join("temp_image.png");
+            save_with_dpi(img, &temp_path, self.config.dpi)?;
+            return self.detect_orientation_from(&temp_path, source);
+        }
+
+        self.extract_text_from_loaded_image(img, source)
+    }
+
+    /// Extrait le texte d'une image déjà chargée, en appliquant le prétraitement configuré.
+    fn extract_text_from_loaded_image(
+        &self,
+        image: &DynamicImage,
+        source: &str,
+    ) -> Result<OcrOutcome> {
+        if let Some(vertical_engine) = self.vertical_text_engine(image) {
+            let mut outcome = vertical_engine.extract_text_from_loaded_image(image, source)?;
+            outcome.warnings.insert(0, Warning::vertical_text());
+            return Ok(outcome);
+        }
+        let Some(ref preprocess_config) = self.preprocessing_config else {
+            return self.extract_text_from_image_with_dpi(image, self.config.dpi, source);
+        };
+        if preprocess_config.split_double_pages
+            && let Some(split_x) = detect_page_split(&to_grayscale(image))
+        {
+            return self.extract_split_pages(image, split_x, source);
+        }
+        self.extract_single_page(image, source)
+    }
+
+    /// Moteur à utiliser pour une image dont le texte est écrit en colonnes verticales.
+    ///
+    /// Retourne `None` si [`OcrConfig::auto_vertical`] est désactivé, si le
+    /// mode de segmentation est déjà vertical (ou OSD seul) ou si
+    /// [`detect_vertical_text()`] ne reconnaît pas de texte vertical. Sinon,
+    /// retourne une copie du moteur en [`PageSegMode::SingleBlockVertText`](crate::config::PageSegMode::SingleBlockVertText).
+    fn vertical_text_engine(&self, image: &DynamicImage) -> Option<OcrEngine> {
+        use crate::config::PageSegMode;
+
+        if !self.config.auto_vertical
+            || matches!(
+                self.config.page_seg_mode,
+                PageSegMode::SingleBlockVertText | PageSegMode::OsdOnly
+            )
+            || !detect_vertical_text(&to_grayscale(image))
+        {
+            return None;
+        }
+        let mut engine = self.clone();
+        engine.config.page_seg_mode = PageSegMode::SingleBlockVertText;
+        engine.config.auto_vertical = false;
+        Some(engine)
+    }
+
+    /// Lit une double page coupée à `split_x` : la page de gauche, puis celle de droite.
+    ///
+    /// Les textes sont séparés par [`PAGE_SEPARATOR`] ; le rapport de
+    /// prétraitement est celui de la page de gauche.
+    fn extract_split_pages(
+        &self,
+        image: &DynamicImage,
+        split_x: u32,
+        source: &str,
+    ) -> Result<OcrOutcome> {
+        let (left, right) = split_pages(image, split_x);
+        let left = self
+            .extract_single_page(&left, &format!("{}-gauche", source))
+            .context("Échec de l'extraction de la page de gauche")?;
+        self.check_cancelled()?;
+        let right = self
+            .extract_single_page(&right, &format!("{}-droite", source))
+            .context("Échec de l'extraction de la page de droite")?;
+
+        let mut warnings = vec![Warning::page_split(split_x)];
+        warnings.extend(left.warnings);
+        warnings.extend(right.warnings);
+        Ok(OcrOutcome {
+            text: format!("{}{}{}", left.text, PAGE_SEPARATOR, right.text),
+            warnings,
+            preprocessing: left.preprocessing,
+            cache_hit: false,
+        })
+    }
+
+    /// Extrait le texte d'une page (hors double page) en appliquant le prétraitement configuré.
+    fn extract_single_page(&self, image: &DynamicImage, source: &str) -> Result<OcrOutcome> {
+        let Some(ref preprocess_config) = self.preprocessing_config else {
+            return self.extract_text_from_image_with_dpi(image, self.config.dpi, source);
+        };
+        if let Some(outcome) = self.blank_page_outcome(image) {
+            return Ok(outcome);
+        }
+
+        let (preprocessed, report) = preprocess_image_with_report(image, preprocess_config)
+            .map_err(|e| preprocessing_failure(e, "Échec du prétraitement de l'image"))?;
+        self.check_cancelled()?;
+        // Une image redimensionnée n'a plus la résolution de la source
+        let dpi = effective_dpi(self.config.dpi, report.scale_factor);
+
+        let mut outcome = self.extract_text_from_image_with_dpi(&preprocessed, dpi, source)?;
+        outcome.preprocessing = Some(report);
+        Ok(outcome)
+    }
+
+    /// Retourne un résultat vide si la page est blanche et que `skip_blank_pages` est activé.
+    fn blank_page_outcome(&self, image: &DynamicImage) -> Option<OcrOutcome> {
+        let preprocess_config = self.preprocessing_config.as_ref()?;
+        if !preprocess_config.skip_blank_pages {
+            return None;
+        }
+        let result = is_blank_page(&to_grayscale(image), &preprocess_config.blank_detection);
+        (result.content == PageContent::Blank).then(|| OcrOutcome {
+            text: String::new(),
+            warnings: vec![Warning::blank_page()],
+            preprocessing: None,
+            cache_hit: false,
+        })
+    }
+
+    /// Extrait le texte d'une image en mémoire avec un DPI explicite.
+    ///
+    /// Utilisé lorsque l'image a été redimensionnée et que le DPI de la
+    /// configuration ne correspond plus à sa résolution effective. `source`
+    /// nomme les artefacts de débogage de l'extraction.
+    fn extract_text_from_image_with_dpi(
+        &self,
+        image: &DynamicImage,
+        dpi: u32,
+        source: &str,
+    ) -> Result<OcrOutcome> {
         // Sauvegarder temporairement l'image pour Tesseract
         // (Tesseract nécessite un chemin de fichier)
         let temp_dir = tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
         let temp_path = temp_dir.path().join("temp_image.png");
 
-        image
-            .save(&temp_path)
-            .context("Échec de la sauvegarde de l'image temporaire")?;
+        save_with_dpi(image, &temp_path, dpi)?;
 
         let path_str = temp_path.to_str().context("Chemin temporaire invalide")?;
 
-        // Initialiser Tesseract avec la langue configurée
-        let mut tesseract = tesseract::Tesseract::new(None, Some(&self.config.language))
-            .context("Échec de l'initialisation de Tesseract")?;
+        self.recognize_file(path_str, dpi, source)
+    }
 
-        // Appliquer le mode de segmentation de page
-        let psm = match self.config.page_seg_mode {
-            crate::config::PageSegMode::OsdOnly => tesseract::PageSegMode::PsmOsdOnly,
-            crate::config::PageSegMode::AutoOsd => tesseract::PageSegMode::PsmAutoOsd,
-            crate::config::PageSegMode::AutoOnly => tesseract::PageSegMode::PsmAutoOnly,
-            crate::config::PageSegMode::Auto => tesseract::PageSegMode::PsmAuto,
-            crate::config::PageSegMode::SingleColumn => tesseract::PageSegMode::PsmSingleColumn,
-            crate::config::PageSegMode::SingleBlockVertText => {
-                tesseract::PageSegMode::PsmSingleBlockVertText
-            }
-            crate::config::PageSegMode::SingleBlock => tesseract::PageSegMode::PsmSingleBlock,
-            crate::config::PageSegMode::SingleLine => tesseract::PageSegMode::PsmSingleLine,
-            crate::config::PageSegMode::SingleWord => tesseract::PageSegMode::PsmSingleWord,
-            crate::config::PageSegMode::CircleWord => tesseract::PageSegMode::PsmCircleWord,
-            crate::config::PageSegMode::SingleChar => tesseract::PageSegMode::PsmSingleChar,
-            crate::config::PageSegMode::SparseText => tesseract::PageSegMode::PsmSparseText,
-            crate::config::PageSegMode::SparseTextOsd => tesseract::PageSegMode::PsmSparseTextOsd,
-            crate::config::PageSegMode::RawLine => tesseract::PageSegMode::PsmRawLine,
+    /// Analyse un fichier image avec Tesseract et collecte ses diagnostics.
+    ///
+    /// Avec `adaptive_dpi`, une première analyse au DPI demandé mesure la
+    /// taille du texte ; si la résolution déduite s'en écarte trop, l'image
+    /// est analysée à nouveau et le résultat comptant le plus de mots est
+    /// conservé (le premier en cas d'égalité).
+    ///
+    /// Avec [`with_debug_artifacts()`](Self::with_debug_artifacts), l'image
+    /// et les sorties brutes de chaque analyse sont conservées, y compris
+    /// lorsque l'analyse échoue.
+    fn recognize_file(&self, path_str: &str, dpi: u32, source: &str) -> Result<OcrOutcome> {
+        self.check_cancelled()?;
+        #[cfg(test)]
+        RECOGNIZE_CALLS.with(|calls| calls.set(calls.get() + 1));
+
+        let mut artifacts = self.artifact_recorder(source, "ocr");
+        artifacts.copy_image(Path::new(path_str), "");
+        let result = self.recognize_with_retry(path_str, dpi, &mut artifacts);
+        let failures = artifacts.finish(result.as_ref().err());
+        let mut outcome = result?;
+        outcome.warnings.extend(failures);
+        self.line_segmentation_fallback(outcome, path_str, dpi, source)
+    }
+
+    /// Relit la page ligne par ligne si l'analyse n'a reconnu aucun mot.
+    ///
+    /// Sans effet si [`OcrConfig::fallback_line_segmentation`] est
+    /// désactivé, si `outcome` contient du texte, si la page est blanche ou
+    /// si aucune ligne n'est détectée. Au-delà de [`MAX_FALLBACK_LINES`]
+    /// lignes, la détection est jugée aberrante : `outcome` est retourné
+    /// avec un avertissement. Sinon, chaque ligne (avec une marge de
+    /// [`FALLBACK_LINE_PADDING`] pixels) est lue en PSM 7 et les textes
+    /// remplacent celui de `outcome`.
+    fn line_segmentation_fallback(
+        &self,
+        mut outcome: OcrOutcome,
+        path_str: &str,
+        dpi: u32,
+        source: &str,
+    ) -> Result<OcrOutcome> {
+        use crate::config::PageSegMode;
+
+        // Test du texte d'abord : l'image n'est relue qu'en cas d'échec
+        if !self.config.fallback_line_segmentation || !outcome.text.trim().is_empty() {
+            return Ok(outcome);
+        }
+        // Image illisible par le crate image : le résultat de Tesseract est conservé
+        let Ok(image) = image::open(path_str) else {
+            return Ok(outcome);
         };
-        tesseract.set_page_seg_mode(psm);
+        let gray = to_grayscale(&image);
+        let blank_detection = self
+            .preprocessing_config
+            .as_ref()
+            .map(|config| config.blank_detection.clone())
+            .unwrap_or_default();
+        let content = is_blank_page(&gray, &blank_detection).content;
+        if !needs_line_fallback(&outcome.text, content) {
+            return Ok(outcome);
+        }
+        let lines = detect_text_lines(&gray);
+        if lines.is_empty() {
+            return Ok(outcome);
+        }
+        if lines.len() > MAX_FALLBACK_LINES {
+            outcome.warnings.push(Warning::line_fallback_aborted(
+                lines.len(),
+                MAX_FALLBACK_LINES,
+            ));
+            return Ok(outcome);
+        }
 
-        // Appliquer le DPI
-        tesseract = tesseract
-            .set_variable("user_defined_dpi", &self.config.dpi.to_string())
-            .context("Échec de la configuration du DPI")?;
+        let mut engine = self.clone();
+        engine.config.page_seg_mode = PageSegMode::SingleLine;
+        engine.config.fallback_line_segmentation = false;
+        let mut texts = Vec::with_capacity(lines.len());
+        let mut warnings = Vec::new();
+        for (index, rows) in lines.iter().enumerate() {
+            self.check_cancelled()?;
+            let top = rows.start.saturating_sub(FALLBACK_LINE_PADDING);
+            let bottom = rows
+                .end
+                .saturating_add(FALLBACK_LINE_PADDING)
+                .min(image.height());
+            let strip = image.crop_imm(0, top, image.width(), bottom - top);
+            let line = engine
+                .extract_text_from_image_with_dpi(
+                    &strip,
+                    dpi,
+                    &format!("{}-ligne{}", source, index + 1),
+                )
+                .with_context(|| format!("Échec de la lecture de la ligne {}", index + 1))?;
+            texts.push(line.text);
+            warnings.extend(line.warnings);
+        }
 
-        // Appliquer toutes les variables Tesseract personnalisées
-        for (key, value) in &self.config.tesseract_variables {
-            tesseract = tesseract
-                .set_variable(key, value)
-                .with_context(|| format!("Échec de la configuration de la variable '{}'", key))?;
+        outcome.text = join_line_texts(&texts);
+        outcome
+            .warnings
+            .insert(0, Warning::line_fallback(lines.len()));
+        outcome.warnings.extend(warnings);
+        Ok(outcome)
+    }
+
+    /// Analyse un fichier, puis l'analyse à nouveau si le DPI adaptatif le demande.
+    fn recognize_with_retry(
+        &self,
+        path_str: &str,
+        dpi: u32,
+        artifacts: &mut ArtifactRecorder,
+    ) -> Result<OcrOutcome> {
+        if !self.config.adaptive_dpi {
+            return Ok(self.recognize_pass(path_str, dpi, false, artifacts, "")?.0);
+        }
+
+        let (first, hocr) = self.recognize_pass(path_str, dpi, true, artifacts, "")?;
+        // Un hOCR illisible laisse simplement le premier résultat en place
+        let corrected_dpi = hocr
+            .and_then(|hocr| HocrDocument::from_hocr_string(&hocr).ok())
+            .and_then(|doc| adaptive_dpi_retry(dpi, &estimate_text_metrics(&doc)));
+        let Some(corrected_dpi) = corrected_dpi else {
+            return Ok(first);
+        };
+
+        self.check_cancelled()?;
+        let (second, _) =
+            self.recognize_pass(path_str, corrected_dpi, false, artifacts, "-pass2")?;
+        if count_words(&second.text) > count_words(&first.text) {
+            Ok(second)
+        } else {
+            Ok(first)
+        }
+    }
+
+    /// Lance une analyse Tesseract, avec en option le hOCR de la même reconnaissance.
+    ///
+    /// La bibliothèque Tesseract écrit ses avertissements directement sur la
+    /// sortie d'erreur du processus. La variable `debug_file` les redirige
+    /// vers un fichier temporaire, relu après l'extraction. Les sorties
+    /// brutes sont copiées dans `artifacts`, suffixées par `suffix`.
+    ///
+    /// Avec un vocabulaire utilisateur, l'analyse est confiée au binaire
+    /// (voir [`recognize_pass_with_binary()`](Self::recognize_pass_with_binary)).
+    fn recognize_pass(
+        &self,
+        path_str: &str,
+        dpi: u32,
+        with_hocr: bool,
+        artifacts: &mut ArtifactRecorder,
+        suffix: &str,
+    ) -> Result<(OcrOutcome, Option<String>)> {
+        if self.config.has_user_vocabulary() {
+            return self.recognize_pass_with_binary(path_str, dpi, with_hocr, artifacts, suffix);
         }
 
+        let started = Instant::now();
+        let log_dir = tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
+        let log_path = log_dir.path().join("tesseract.log");
+        let log_str = log_path.to_str().context("Chemin temporaire invalide")?;
+
+        // Initialiser Tesseract avec la langue et les paramètres configurés
+        let mut tesseract = self
+            .init_tesseract(&self.config.language, dpi)?
+            .set_variable("debug_file", log_str)
+            .context("Échec de la redirection des diagnostics Tesseract")?;
+
         // Charger l'image
         tesseract = tesseract
             .set_image(path_str)
@@ -444,7 +1486,3336 @@ impl OcrEngine {
         let text = tesseract
             .get_text()
             .context("Échec de l'extraction du texte")?;
+        artifacts.write(&format!("output{}.txt", suffix), text.as_bytes());
+
+        // Même reconnaissance : Tesseract ne relance pas l'analyse
+        let hocr = if with_hocr {
+            Some(
+                tesseract
+                    .get_hocr_text(0)
+                    .context("Échec de la génération du hOCR")?,
+            )
+        } else {
+            None
+        };
+
+        // Le fichier n'existe pas si Tesseract n'a rien signalé
+        let log = std::fs::read_to_string(&log_path).unwrap_or_default();
+        let warnings = parse_tesseract_stderr(&log);
+        if let Some(ref hocr) = hocr {
+            artifacts.write_hocr(&format!("output{}.json", suffix), hocr);
+        }
+        artifacts.write(&format!("tesseract{}.log", suffix), log.as_bytes());
+        artifacts.record_pass(dpi, started.elapsed());
+
+        Ok((self.pass_outcome(&text, warnings), hocr))
+    }
+
+    /// Lance une analyse avec le binaire `tesseract`, qui reçoit le vocabulaire utilisateur.
+    ///
+    /// Tesseract ne lit les fichiers `--user-words` et `--user-patterns`
+    /// qu'à son initialisation, que la bibliothèque `tesseract` ne permet
+    /// pas de paramétrer (une variable définie après coup est ignorée). Le
+    /// binaire reçoit donc ces fichiers, le DPI et les variables de la
+    /// configuration en ligne de commande ; il est soumis au délai de
+    /// [`with_timeout()`](Self::with_timeout).
+    fn recognize_pass_with_binary(
+        &self,
+        path_str: &str,
+        dpi: u32,
+        with_hocr: bool,
+        artifacts: &mut ArtifactRecorder,
+        suffix: &str,
+    ) -> Result<(OcrOutcome, Option<String>)> {
+        let started = Instant::now();
+        let temp_dir = tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
+        let output_base = temp_dir.path().join("output");
+
+        let mut args = TesseractInvocation::new(&self.config, &self.config.language, dpi)
+            .binary_args(path_str, &output_base, &self.config.user_vocabulary_args());
+        args.push("txt".into());
+        if with_hocr {
+            args.push("hocr".into());
+        }
+
+        let output = self.run_binary(&args)?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        artifacts.write(
+            &format!("tesseract{}.stderr.txt", suffix),
+            stderr.as_bytes(),
+        );
+        let warnings = parse_tesseract_stderr(&stderr);
+        if !output.status.success() {
+            anyhow::bail!(
+                "Tesseract a échoué lors de l'extraction du texte.{}{}",
+                legacy_data_hint(self.config.engine_mode),
+                format_warnings_suffix(&warnings)
+            );
+        }
+
+        let text = std::fs::read_to_string(temp_dir.path().join("output.txt"))
+            .context("Échec de la lecture du texte extrait")?;
+        artifacts.write(&format!("output{}.txt", suffix), text.as_bytes());
+        let hocr = if with_hocr {
+            let hocr = std::fs::read_to_string(temp_dir.path().join("output.hocr"))
+                .context("Échec de la lecture du fichier HOCR généré")?;
+            artifacts.write_hocr(&format!("output{}.json", suffix), &hocr);
+            Some(hocr)
+        } else {
+            None
+        };
+        artifacts.record_pass(dpi, started.elapsed());
+
+        Ok((self.pass_outcome(&text, warnings), hocr))
+    }
+
+    /// Nettoie le texte brut d'une analyse et signale une page vide.
+    fn pass_outcome(&self, raw_text: &str, mut warnings: Vec<Warning>) -> OcrOutcome {
+        let text = self.clean_text(raw_text);
+        if text.trim().is_empty() && !warnings.iter().any(|w| w.kind() == WarningKind::EmptyPage) {
+            warnings.push(Warning::new("Empty page!!"));
+        }
+
+        OcrOutcome {
+            text,
+            warnings,
+            preprocessing: None,
+            cache_hit: false,
+        }
+    }
+
+    /// Extrait le texte d'une très grande image en la découpant en tuiles.
+    ///
+    /// Alternative à la réduction automatique (`max_pixels`) lorsque la
+    /// résolution doit être conservée. L'image est découpée en bandes
+    /// horizontales pleine largeur de `tile_size` pixels de haut, qui se
+    /// chevauchent de `overlap` pixels pour ne pas couper une ligne de texte.
+    /// Chaque tuile est prétraitée (si configuré) puis analysée, et les textes
+    /// sont fusionnés en supprimant les mots dupliqués dans la zone de
+    /// chevauchement (comparaison par distance de Levenshtein).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    /// * `tile_size` - Hauteur d'une tuile en pixels
+    /// * `overlap` - Chevauchement entre deux tuiles consécutives en pixels
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::ocr::OcrEngine;
+    /// use text_recognition::config::OcrConfig;
+    /// use std::path::Path;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let text = engine.extract_text_tiled(Path::new("scan_100mp.png"), 2000, 200)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si :
+    /// - `tile_size` vaut 0 ou `overlap` est supérieur ou égal à `tile_size`
+    /// - Le fichier n'existe pas ou ne peut pas être chargé
+    /// - Tesseract échoue lors de l'extraction d'une tuile
+    pub fn extract_text_tiled(&self, path: &Path, tile_size: u32, overlap: u32) -> Result<String> {
+        if tile_size == 0 || overlap >= tile_size {
+            anyhow::bail!(
+                "Tuilage invalide : taille {} et chevauchement {} (le chevauchement doit être inférieur à la taille)",
+                tile_size,
+                overlap
+            );
+        }
+
+        let img = image::open(path)
+            .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
+        let temp_dir = tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
+
+        // Une seule instance Tesseract réutilisée pour toutes les tuiles
+        let mut tesseract = self.init_tesseract(&self.config.language, self.config.dpi)?;
+        let mut merged = String::new();
+
+        for (index, (y, height)) in tile_bands(img.height(), tile_size, overlap)
+            .into_iter()
+            .enumerate()
+        {
+            let tile = img.crop_imm(0, y, img.width(), height);
+            let tile = match self.preprocessing_config {
+                Some(ref preprocess_config) => preprocess_image(&tile, preprocess_config)
+                    .map_err(|e| preprocessing_failure(e, "Échec du prétraitement de la tuile"))?,
+                None => tile,
+            };
+
+            let tile_path = temp_dir.path().join(format!("tile_{}.png", index));
+            tile.save(&tile_path)
+                .context("Échec de la sauvegarde de la tuile temporaire")?;
+            let tile_str = tile_path.to_str().context("Chemin temporaire invalide")?;
+
+            tesseract = tesseract
+                .set_image(tile_str)
+                .context("Échec du chargement de la tuile")?;
+            let text = tesseract
+                .get_text()
+                .with_context(|| format!("Échec de l'extraction de la tuile #{}", index + 1))?;
+
+            merged = merge_overlapping_texts(&merged, &text);
+        }
+
+        Ok(self.clean_text(&merged))
+    }
+
+    /// Extrait le texte d'une région rectangulaire d'une image, en pixels.
+    ///
+    /// La région est limitée aux bords de l'image, puis analysée comme une
+    /// image en mémoire (prétraitement et DPI de la configuration).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    /// * `bbox` - Région à analyser, en pixels
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::config::OcrConfig;
+    /// use text_recognition::hocr::BBox;
+    /// use text_recognition::ocr::OcrEngine;
+    /// use std::path::Path;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::screenshot_preset())?;
+    /// let text = engine.extract_text_from_region(Path::new("capture.png"), &BBox::new(1536, 0, 384, 108))?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si :
+    /// - Le fichier n'existe pas ou ne peut pas être chargé
+    /// - La région est vide ou entièrement hors de l'image
+    /// - Tesseract échoue lors de l'extraction
+    pub fn extract_text_from_region(&self, path: &Path, bbox: &BBox) -> Result<String> {
+        let img = image::open(path)
+            .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
+        self.extract_text_from_image(&crop_region(&img, bbox)?)
+    }
+
+    /// Extrait le texte d'une région exprimée en fractions des dimensions de l'image.
+    ///
+    /// La région est convertie en pixels avec [`RelativeRegion::to_bbox()`]
+    /// une fois l'image chargée ; voir
+    /// [`extract_text_from_region()`](Self::extract_text_from_region).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    /// * `region` - Région relative (fractions entre 0 et 1)
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::config::OcrConfig;
+    /// use text_recognition::ocr::OcrEngine;
+    /// use text_recognition::region::RelativeRegion;
+    /// use std::path::Path;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::screenshot_preset())?;
+    /// // Coin supérieur droit, 20 % × 10 %
+    /// let region = RelativeRegion::new(0.8, 0.0, 0.2, 0.1)?;
+    /// let text = engine.extract_text_from_relative_region(Path::new("capture.png"), &region)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si la région est invalide (voir
+    /// [`RelativeRegion::validate()`]) ou pour les mêmes raisons que
+    /// [`extract_text_from_region()`](Self::extract_text_from_region).
+    pub fn extract_text_from_relative_region(
+        &self,
+        path: &Path,
+        region: &RelativeRegion,
+    ) -> Result<String> {
+        region.validate()?;
+        let img = image::open(path)
+            .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
+        let bbox = region.to_bbox(img.width(), img.height());
+        self.extract_text_from_image(&crop_region(&img, &bbox)?)
+    }
+
+    /// Extrait le texte de plusieurs régions d'une image, chacune avec sa propre langue.
+    ///
+    /// Utile pour les documents bilingues (ex: un contrat en français avec des
+    /// clauses en anglais) où chaque section nécessite un modèle linguistique différent.
+    ///
+    /// Chaque région est découpée dans l'image puis analysée avec une instance
+    /// Tesseract initialisée pour sa langue. Les instances sont mises en cache
+    /// par langue : deux régions en "fra" ne rechargent pas le modèle deux fois.
+    /// Si [`OcrConfig::skip_image_regions`] est activé, les régions classées
+    /// [`RegionClass::Image`] (photo, logo) ne sont pas lues et leur texte
+    /// est vide.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    /// * `regions` - Liste de couples (rectangle, langue Tesseract)
+    ///
+    /// # Retour
+    ///
+    /// Un [`RegionResult`] par région, dans le même ordre que `regions`.
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::ocr::OcrEngine;
+    /// use text_recognition::config::OcrConfig;
+    /// use text_recognition::hocr::BBox;
+    /// use std::path::Path;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let regions = vec![
+    ///     (BBox::new(0, 0, 800, 400), "fra".to_string()),
+    ///     (BBox::new(0, 400, 800, 200), "eng".to_string()),
+    /// ];
+    /// for result in engine.extract_text_multilang(Path::new("contrat.png"), &regions)? {
+    ///     println!("[{}] {}", result.language, result.text);
+    /// }
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si :
+    /// - Le fichier n'existe pas ou ne peut pas être chargé
+    /// - Une région est vide ou entièrement hors de l'image
+    /// - Les données linguistiques d'une région ne sont pas disponibles
+    /// - Tesseract échoue lors de l'extraction
+    pub fn extract_text_multilang(
+        &self,
+        path: &Path,
+        regions: &[(BBox, String)],
+    ) -> Result<Vec<RegionResult>> {
+        let img = image::open(path)
+            .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
+
+        let temp_dir = tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
+        let mut cache = LanguageCache::new();
+        let mut region_index = 0;
+
+        ocr_regions(
+            &img,
+            regions,
+            &mut cache,
+            |lang| self.init_tesseract(lang, self.config.dpi),
+            |tesseract, region_img| {
+                if self.config.skip_image_regions
+                    && classify_region(&region_img.to_luma8()) == RegionClass::Image
+                {
+                    return Ok((tesseract, String::new()));
+                }
+                let region_img = match self.preprocessing_config {
+                    Some(ref preprocess_config) => preprocess_image(region_img, preprocess_config)
+                        .map_err(|e| {
+                            preprocessing_failure(e, "Échec du prétraitement de la région")
+                        })?,
+                    None => region_img.clone(),
+                };
+
+                region_index += 1;
+                let region_path = temp_dir.path().join(format!("region_{}.png", region_index));
+                region_img
+                    .save(&region_path)
+                    .context("Échec de la sauvegarde de la région temporaire")?;
+                let region_str = region_path.to_str().context("Chemin temporaire invalide")?;
+
+                let mut tesseract = tesseract
+                    .set_image(region_str)
+                    .context("Échec du chargement de la région")?;
+                let text = tesseract.get_text().with_context(|| {
+                    format!("Échec de l'extraction de la région #{}", region_index)
+                })?;
+                Ok((tesseract, self.clean_text(&text)))
+            },
+        )
+    }
+
+    /// Extrait le texte d'une image multilingue en détectant le script de chaque bloc.
+    ///
+    /// Les blocs de texte (paragraphes) sont d'abord segmentés via la sortie HOCR
+    /// de Tesseract. Pour chaque bloc, la détection de script (OSD, PSM 0) est
+    /// lancée et le script détecté est converti en langue via `script_languages`
+    /// (ex: "Latin" → "fra+eng", "Cyrillic" → "rus"). Les blocs dont le script
+    /// n'est pas présent dans la table utilisent la langue de la configuration.
+    ///
+    /// Voir [`default_script_languages()`] pour une table de correspondance de départ.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    /// * `script_languages` - Table de correspondance script OSD → langue Tesseract
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::ocr::{OcrEngine, default_script_languages};
+    /// use text_recognition::config::OcrConfig;
+    /// use std::path::Path;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let results = engine.extract_text_by_script(
+    ///     Path::new("bilingue.png"),
+    ///     &default_script_languages(),
+    /// )?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si :
+    /// - Le binaire `tesseract` n'est pas installé ou introuvable
+    /// - La segmentation HOCR échoue
+    /// - L'extraction d'une région échoue
+    pub fn extract_text_by_script(
+        &self,
+        path: &Path,
+        script_languages: &HashMap<String, String>,
+    ) -> Result<Vec<RegionResult>> {
+        let doc = generate_hocr_file(
+            path,
+            &TesseractInvocation::new(&self.config, &self.config.language, self.config.dpi),
+            &[],
+            self.timeout,
+            self.config.thread_limit(),
+        )?
+        .parse()?;
+
+        let img = image::open(path)
+            .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
+        let temp_dir = tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
+
+        let mut regions = Vec::with_capacity(doc.paragraphs().count());
+        for (index, paragraph) in doc.paragraphs().enumerate() {
+            let block_path = temp_dir.path().join(format!("block_{}.png", index));
+            crop_region(&img, &paragraph.bbox)?
+                .save(&block_path)
+                .context("Échec de la sauvegarde du bloc temporaire")?;
+
+            // Un bloc trop petit peut faire échouer l'OSD : on garde alors la langue par défaut
+            let language = self
+                .detect_orientation(&block_path)
+                .ok()
+                .and_then(|osd| parse_osd_script(&osd))
+                .and_then(|script| script_languages.get(&script).cloned())
+                .unwrap_or_else(|| self.config.language.clone());
+
+            regions.push((paragraph.bbox.clone(), language));
+        }
+
+        self.extract_text_multilang(path, &regions)
+    }
+
+    /// Extrait le texte d'une page en respectant l'ordre de lecture des colonnes.
+    ///
+    /// La page est segmentée via la sortie HOCR de Tesseract, puis les
+    /// paragraphes sont réordonnés avec [`ReadingOrder::Columns`] : un titre
+    /// pleine largeur reste en tête, puis chaque colonne est lue entièrement
+    /// de haut en bas avant la suivante. Une page sur une seule colonne est
+    /// lue dans l'ordre de Tesseract.
+    ///
+    /// Si un prétraitement est configuré, il est appliqué avant la segmentation.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::ocr::OcrEngine;
+    /// use text_recognition::config::OcrConfig;
+    /// use std::path::Path;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let text = engine.extract_text_columns(Path::new("journal.png"))?;
+    /// println!("{}", text);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si :
+    /// - Le binaire `tesseract` n'est pas installé ou introuvable
+    /// - L'image ne peut pas être chargée ou prétraitée
+    /// - La sortie HOCR ne peut pas être analysée
+    pub fn extract_text_columns(&self, path: &Path) -> Result<String> {
+        let mut doc = self.extract_hocr_document(path)?;
+        reorder_reading_order(&mut doc, ReadingOrder::Columns);
+        Ok(self.clean_text(&doc.full_text()))
+    }
+
+    /// Extrait le texte d'une page portant des blocs inclinés (tickets agrafés de travers).
+    ///
+    /// Un redressement de la page entière ne corrige pas un ticket agrafé à
+    /// 30° sur une feuille droite. Les blocs inclinés d'au moins
+    /// [`MIN_REGION_SKEW`](crate::segmentation::MIN_REGION_SKEW) degrés sont repérés par
+    /// [`split_skewed_regions()`] : le fond de page, d'où ils sont effacés,
+    /// est lu normalement, puis chaque bloc est lu séparément après avoir
+    /// été redressé. Le texte de chaque bloc suit celui du fond, précédé
+    /// d'un marqueur `--- Région N (x,y largeur×hauteur, angle°) ---`, du
+    /// haut de la page vers le bas. Sans bloc incliné, le résultat est celui
+    /// de [`extract_text_from_image()`](Self::extract_text_from_image).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::ocr::OcrEngine;
+    /// use text_recognition::config::OcrConfig;
+    /// use std::path::Path;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let text = engine.extract_text_composite(Path::new("archive_p12.png"))?;
+    /// println!("{}", text);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si l'image ne peut pas être chargée ou si
+    /// l'extraction du fond ou d'un bloc échoue.
+    pub fn extract_text_composite(&self, path: &Path) -> Result<String> {
+        let img = image::open(path)
+            .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
+        let (background, blocks) = split_skewed_regions(&img.to_luma8());
+        if blocks.is_empty() {
+            return self.extract_text_from_image(&img);
+        }
+
+        let background_text =
+            self.extract_text_from_image(&DynamicImage::ImageLuma8(background))?;
+        let mut block_texts = Vec::with_capacity(blocks.len());
+        for (index, block) in blocks.into_iter().enumerate() {
+            let text = self
+                .extract_text_from_image(&DynamicImage::ImageLuma8(block.image))
+                .with_context(|| format!("Échec de l'extraction du bloc incliné #{}", index + 1))?;
+            block_texts.push((block.region, text));
+        }
+        // Ordre de lecture explicite, indépendant de l'ordre de détection
+        block_texts.sort_by_key(|(region, _)| (region.bbox.y, region.bbox.x));
+        Ok(composite_text(&background_text, &block_texts))
+    }
+
+    /// Extrait le texte en conservant les paragraphes, les lignes et les mots.
+    ///
+    /// La structure provient de la sortie HOCR du binaire `tesseract` ;
+    /// chaque mot conserve sa confiance et son bounding box. L'affichage du
+    /// résultat donne le même texte que
+    /// [`extract_text_from_file()`](Self::extract_text_from_file) avec le
+    /// nettoyage par défaut (les nettoyages ne sont pas appliqués aux mots).
+    /// Si [`OcrConfig::line_merge`] est renseigné, les lignes que Tesseract
+    /// a coupées en deux sont d'abord recollées ([`merge_split_lines()`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::ocr::OcrEngine;
+    /// use text_recognition::config::OcrConfig;
+    /// use std::path::Path;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let structured = engine.extract_structured(Path::new("article.png"))?;
+    /// println!("{} paragraphe(s)", structured.paragraph_texts().len());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Mêmes erreurs que [`extract_text_columns()`](Self::extract_text_columns).
+    pub fn extract_structured(&self, path: &Path) -> Result<StructuredText> {
+        let mut doc = self.extract_hocr_document(path)?;
+        if let Some(ref options) = self.config.line_merge {
+            merge_split_lines(&mut doc, options);
+        }
+        Ok(StructuredText::from(&doc))
+    }
+
+    /// Génère et analyse le document HOCR d'une image avec la configuration du moteur.
+    ///
+    /// Le prétraitement éventuel est appliqué et les formats que Tesseract
+    /// ne lit pas directement sont convertis en PNG temporaire. Le document
+    /// peut ensuite être exporté en Markdown ou en HTML
+    /// ([`HocrDocument::to_markdown()`], [`HocrDocument::to_html()`]).
+    /// Si [`OcrConfig::skip_image_regions`] est activé, les mots des
+    /// paragraphes classés comme illustrations sont retirés
+    /// ([`drop_words_in_image_regions()`]).
+    ///
+    /// Si le prétraitement redimensionne l'image (`max_pixels`), Tesseract
+    /// reçoit la résolution effective et les coordonnées du document sont
+    /// ramenées à celles de l'image source
+    /// ([`HocrDocument::to_source_coordinates()`], facteur conservé dans
+    /// [`HocrDocument::scale_factor`]).
+    ///
+    /// Avec un cache de résultats ([`with_result_cache()`](Self::with_result_cache)),
+    /// le document y est rangé sous une clé propre au hOCR et réutilisé tant
+    /// que l'image et la configuration sont inchangées.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image
+    ///
+    /// # Erreurs
+    ///
+    /// Mêmes erreurs que [`extract_text_columns()`](Self::extract_text_columns).
+    pub fn extract_hocr_document(&self, path: &Path) -> Result<HocrDocument> {
+        let Some(ref cache) = self.result_cache else {
+            return self.extract_hocr_document_uncached(path);
+        };
+        let Ok(key) = cache_key(path, &self.config, self.preprocessing_config.as_ref()) else {
+            return self.extract_hocr_document_uncached(path);
+        };
+        let key = format!("{}-hocr", key);
+        if let Some(document) = cache.get(&key).and_then(|cached| cached.document) {
+            return Ok(document);
+        }
+
+        let document = self.extract_hocr_document_uncached(path)?;
+        // Le document n'a pas de liste d'avertissements : un échec d'enregistrement est ignoré
+        let _ = cache.put(
+            &key,
+            &CachedResult {
+                text: document.full_text(),
+                warnings: Vec::new(),
+                document: Some(document.clone()),
+            },
+        );
+        Ok(document)
+    }
+
+    /// Génère le document HOCR d'une image sans consulter le cache de résultats.
+    fn extract_hocr_document_uncached(&self, path: &Path) -> Result<HocrDocument> {
+        let mut artifacts = self.artifact_recorder(source_label(path), "hocr");
+        let mut result = self.generate_hocr_document(path, &mut artifacts);
+        // Le document n'a pas de liste d'avertissements : un échec de copie est ignoré
+        let _ = artifacts.finish(result.as_ref().err());
+        if let Ok(ref mut doc) = result
+            && self.config.skip_image_regions
+        {
+            // Format que `image` ne lit pas (ex. PDF converti) : le document est gardé tel quel
+            if let Ok(img) = image::open(path) {
+                drop_words_in_image_regions(doc, &img.to_luma8(), DEFAULT_IMAGE_OVERLAP);
+            }
+        }
+        result
+    }
+
+    /// Extrait les mots d'une image avec leurs variantes de lecture.
+    ///
+    /// La crate `tesseract` n'expose pas l'itérateur de choix de Tesseract :
+    /// le binaire `tesseract` est lancé en sortie hOCR avec
+    /// `-c lstm_choice_mode=2`, qui ajoute à chaque mot les glyphes candidats
+    /// du LSTM (voir [`parse_alternatives()`]). Le moteur historique
+    /// (`OcrEngineMode::TesseractOnly`) n'en produit pas : les mots n'ont
+    /// alors aucune variante. Le prétraitement éventuel est appliqué et les
+    /// rectangles sont exprimés dans le repère de l'image source. Les
+    /// variantes se départagent ensuite avec
+    /// [`rescore_with()`](crate::hocr::rescore_with).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image
+    /// * `max_alternatives` - Nombre maximal de variantes conservées par mot
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::config::OcrConfig;
+    /// use text_recognition::hocr::rescore_with;
+    /// use text_recognition::ocr::OcrEngine;
+    /// use text_recognition::postprocess::Corrector;
+    /// use std::path::Path;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let words = engine.extract_with_alternatives(Path::new("facture.png"), 3)?;
+    /// let dictionary = Corrector::from_words(["facture", "client", "total"]);
+    /// println!("{}", rescore_with(&words, |word| dictionary.plausibility(word)));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Mêmes erreurs que [`extract_hocr_document()`](Self::extract_hocr_document).
+    pub fn extract_with_alternatives(
+        &self,
+        path: &Path,
+        max_alternatives: usize,
+    ) -> Result<Vec<WordAlternatives>> {
+        self.check_cancelled()?;
+        let temp_dir = tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
+        let input = self.binary_input(path, temp_dir.path())?;
+
+        let mut options = vec!["-c".to_string(), "lstm_choice_mode=2".to_string()];
+        options.extend(self.config.user_vocabulary_args());
+        let hocr_file = generate_hocr_file(
+            &input.path,
+            &TesseractInvocation::new(&self.config, &self.config.language, input.dpi(&self.config)),
+            &options,
+            self.timeout,
+            self.config.thread_limit(),
+        )?;
+        let hocr = std::fs::read_to_string(hocr_file.path())
+            .context("Échec de la lecture du fichier HOCR généré")?;
+
+        let mut words = parse_alternatives(&hocr, max_alternatives);
+        if input.scale_factor != 1.0 {
+            for word in &mut words {
+                word.bbox = word.bbox.scaled(1.0 / input.scale_factor);
+            }
+        }
+        Ok(words)
+    }
+
+    /// Arguments de l'OSD (modèle `osd`, PSM 0), traduits comme ceux d'une
+    /// analyse (voir [`TesseractInvocation::osd()`]).
+    fn osd_args(&self, path: &Path) -> Vec<OsString> {
+        TesseractInvocation::new(&self.config, &self.config.language, self.config.dpi)
+            .osd()
+            .binary_args(path, "stdout", &[])
+    }
+
+    /// Génère le hOCR d'une image et conserve ses sorties brutes dans `artifacts`.
+    fn generate_hocr_document(
+        &self,
+        path: &Path,
+        artifacts: &mut ArtifactRecorder,
+    ) -> Result<HocrDocument> {
+        let started = Instant::now();
+
+        let temp_dir = tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
+        let input = self.binary_input(path, temp_dir.path())?;
+        artifacts.copy_image(&input.path, "");
+        let hocr_file = generate_hocr_file(
+            &input.path,
+            &TesseractInvocation::new(&self.config, &self.config.language, input.dpi(&self.config)),
+            &self.config.user_vocabulary_args(),
+            self.timeout,
+            self.config.thread_limit(),
+        )?;
+        artifacts.record_pass(input.dpi(&self.config), started.elapsed());
+        // Lignes non vides de la sortie d'erreur du binaire
+        let stderr: String = hocr_file
+            .warnings
+            .iter()
+            .map(|warning| format!("{}\n", warning.message))
+            .collect();
+        artifacts.write("tesseract.stderr.txt", stderr.as_bytes());
+
+        // Lu en flux : le hOCR d'une très grande page n'est pas chargé en entier
+        let document = hocr_file.parse()?;
+        // Les artefacts gardent les coordonnées de l'image analysée, conservée à côté
+        artifacts.write_document("output.json", &document);
+        Ok(document.to_source_coordinates(input.scale_factor))
+    }
+
+    /// Fichier à transmettre au binaire `tesseract`, qui ne lit qu'un fichier.
+    ///
+    /// Avec un prétraitement, ou pour un format que Leptonica gère mal (voir
+    /// [`needs_in_memory_decoding()`]), l'image est décodée, prétraitée et
+    /// enregistrée en PNG dans `temp_dir`, avec sa résolution effective si
+    /// le prétraitement l'a redimensionnée ; sinon `path` est retourné tel quel.
+    fn binary_input(&self, path: &Path, temp_dir: &Path) -> Result<BinaryInput> {
+        if self.preprocessing_config.is_none() && !needs_in_memory_decoding(path) {
+            return Ok(BinaryInput {
+                path: path.to_path_buf(),
+                scale_factor: 1.0,
+            });
+        }
+        let img = image::open(path)
+            .with_context(|| format!("Échec du chargement de l'image '{}'", path.display()))?;
+        let (img, scale_factor) = match self.preprocessing_config {
+            Some(ref preprocess_config) => {
+                let (img, report) = preprocess_image_with_report(&img, preprocess_config)
+                    .map_err(|e| preprocessing_failure(e, "Échec du prétraitement de l'image"))?;
+                (img, report.scale_factor)
+            }
+            None => (img, 1.0),
+        };
+        let temp_path = temp_dir.join("preprocessed.png");
+        // Le binaire lit la résolution dans l'image : celle de l'image redimensionnée
+        save_with_dpi(
+            &img,
+            &temp_path,
+            effective_dpi(self.config.dpi, scale_factor),
+        )?;
+        Ok(BinaryInput {
+            path: temp_path,
+            scale_factor,
+        })
+    }
+
+    /// Lance le binaire `tesseract` avec le délai et la limite de threads du moteur.
+    fn run_binary(&self, args: &[OsString]) -> Result<Output> {
+        run_tesseract(args, self.timeout, self.config.thread_limit())
+    }
+
+    /// Applique le nettoyage configuré (`OcrConfig::cleanup`) au texte extrait.
+    fn clean_text(&self, text: &str) -> String {
+        match self.config.cleanup {
+            Some(ref cleanup) => cleanup.apply(text),
+            None => text.to_string(),
+        }
+    }
+
+    /// Initialise une instance Tesseract pour la langue et le DPI donnés.
+    ///
+    /// Applique le mode de segmentation, le DPI et toutes les variables
+    /// Tesseract définies dans la configuration du moteur.
+    pub(crate) fn init_tesseract(&self, language: &str, dpi: u32) -> Result<tesseract::Tesseract> {
+        TesseractInvocation::new(&self.config, language, dpi).init_library()
+    }
+}
+
+/// Fichier transmis au binaire `tesseract` (voir [`OcrEngine::binary_input()`]).
+struct BinaryInput {
+    /// Image à analyser : la source, ou sa version prétraitée.
+    path: PathBuf,
+    /// Facteur d'échelle de l'image par rapport à la source
+    /// ([`PreprocessingReport::scale_factor`]).
+    scale_factor: f64,
+}
+
+impl BinaryInput {
+    /// Résolution à annoncer à Tesseract pour cette image.
+    fn dpi(&self, config: &OcrConfig) -> u32 {
+        effective_dpi(config.dpi, self.scale_factor)
+    }
+}
+
+/// Chemin transmis à la bibliothèque Tesseract.
+///
+/// `TessBaseAPI` ne reçoit que des chaînes UTF-8 : une image dont le chemin
+/// n'est pas en UTF-8 est copiée dans un répertoire temporaire, sous un nom
+/// ASCII, le temps de l'analyse. Le binaire, lui, reçoit le chemin d'origine
+/// (voir [`tesseract_command_args()`]).
+struct LibraryInput {
+    /// Chemin UTF-8 de l'image à analyser.
+    path: String,
+    /// Avertissement émis lorsque l'image a été copiée.
+    warning: Option<Warning>,
+    /// Répertoire de la copie, supprimé avec la structure.
+    _temp_dir: Option<tempfile::TempDir>,
+}
+
+impl LibraryInput {
+    /// Prépare le chemin de `path` pour la bibliothèque.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si la copie temporaire échoue.
+    fn new(path: &Path) -> Result<Self> {
+        if let Some(path_str) = path.to_str() {
+            return Ok(Self {
+                path: path_str.to_string(),
+                warning: None,
+                _temp_dir: None,
+            });
+        }
+
+        let temp_dir = tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
+        // L'extension guide le chargeur de Leptonica : la garder si elle est ASCII
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .filter(|ext| ext.is_ascii())
+            .unwrap_or("img");
+        let copy = temp_dir.path().join(format!("image.{}", extension));
+        std::fs::copy(path, &copy)
+            .with_context(|| format!("Impossible de copier l'image '{}'", path.display()))?;
+        let copy_str = copy.to_str().context("Chemin temporaire invalide")?;
+
+        Ok(Self {
+            path: copy_str.to_string(),
+            warning: Some(Warning::path_issues(path)),
+            _temp_dir: Some(temp_dir),
+        })
+    }
+}
+
+/// Extensions que le chargeur de Tesseract (Leptonica) gère mal.
+///
+/// GIF et WebP échouent selon la compilation de Leptonica ("Unsupported image
+/// type") ; les formats animés ne sont lus que pour leur première image.
+const IN_MEMORY_EXTENSIONS: &[&str] = &["gif", "webp"];
+
+/// Indique si l'image doit être décodée par la crate `image` avant l'OCR.
+fn needs_in_memory_decoding(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| IN_MEMORY_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Décode une image à partir de son contenu brut (format détecté automatiquement).
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le contenu est vide ou n'est pas une image supportée.
+fn decode_image_bytes(bytes: &[u8]) -> Result<DynamicImage> {
+    if bytes.is_empty() {
+        anyhow::bail!("Aucune donnée image reçue");
+    }
+    image::load_from_memory(bytes).context("Échec du décodage de l'image")
+}
+
+/// Résultat de l'OCR d'une région d'image.
+///
+/// Retourné par [`OcrEngine::extract_text_multilang()`] et
+/// [`OcrEngine::extract_text_by_script()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionResult {
+    /// Rectangle de la région dans l'image source.
+    pub bbox: BBox,
+    /// Langue Tesseract utilisée pour cette région (ex: "fra", "eng").
+    pub language: String,
+    /// Texte extrait de la région.
+    pub text: String,
+}
+
+/// Texte extrait accompagné des diagnostics émis par Tesseract.
+///
+/// Retourné par [`OcrEngine::extract_text_detailed()`] et les autres
+/// méthodes `*_detailed`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcrOutcome {
+    /// Texte extrait (ou données brutes, ex: HOCR ou informations OSD).
+    pub text: String,
+    /// Avertissements émis par Tesseract, dans l'ordre d'émission.
+    pub warnings: Vec<Warning>,
+    /// Décisions du prétraitement, si l'image a été prétraitée.
+    pub preprocessing: Option<PreprocessingReport>,
+    /// `true` si le résultat provient du cache (voir
+    /// [`OcrEngine::with_result_cache()`]) : Tesseract n'a pas été lancé.
+    pub cache_hit: bool,
+}
+
+impl OcrOutcome {
+    /// Indique si l'OCR a été évité parce que la page est blanche.
+    pub fn is_blank_page(&self) -> bool {
+        self.warnings
+            .iter()
+            .any(|warning| warning.kind() == WarningKind::BlankPage)
+    }
+
+    /// Nombre de pages logiques lues : 2 pour une double page coupée
+    /// (voir [`PreprocessingConfig::split_double_pages`]), 1 sinon.
+    pub fn page_count(&self) -> usize {
+        1 + self
+            .warnings
+            .iter()
+            .filter(|warning| warning.kind() == WarningKind::PageSplit)
+            .count()
+    }
+}
+
+/// Résultat d'une lecture pyramidale ([`OcrEngine::extract_text_pyramid()`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyramidOutcome {
+    /// Résultat de l'extraction du niveau retenu.
+    pub outcome: OcrOutcome,
+    /// Niveaux lus, niveau retenu et temps gagné estimé.
+    pub report: PyramidReport,
+}
+
+/// Extraction d'une des deux images d'une [`ImageComparison`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparedImage {
+    /// Chemin de l'image.
+    pub path: PathBuf,
+    /// Texte extrait, ou `None` si l'extraction a échoué.
+    pub text: Option<String>,
+    /// Message d'erreur complet (avec ses causes) si l'extraction a échoué.
+    pub error: Option<String>,
+    /// Durée de l'extraction.
+    pub duration: Duration,
+}
+
+impl ComparedImage {
+    /// Extrait le texte de `path` en mesurant la durée, sans propager l'erreur.
+    fn extract(engine: &OcrEngine, path: &Path) -> Self {
+        let start = Instant::now();
+        let result = engine.extract_text_from_file(path);
+        let duration = start.elapsed();
+
+        let (text, error) = match result {
+            Ok(text) => (Some(text), None),
+            Err(e) => (None, Some(format!("{:#}", e))),
+        };
+
+        Self {
+            path: path.to_path_buf(),
+            text,
+            error,
+            duration,
+        }
+    }
+}
+
+/// Comparaison A/B des textes extraits de deux images avec la même configuration.
+///
+/// La première image sert de référence pour les métriques et le diff.
+/// Retournée par [`compare_images()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageComparison {
+    /// Première image (référence).
+    pub first: ComparedImage,
+    /// Seconde image (comparée à la première).
+    pub second: ComparedImage,
+    /// CER/WER de la seconde image par rapport à la première, si les deux
+    /// extractions ont réussi.
+    pub metrics: Option<OcrMetrics>,
+    /// Diff mot à mot de la seconde image par rapport à la première (vide si
+    /// une extraction a échoué).
+    pub word_diff: Vec<WordDiff>,
+}
+
+/// Compare les textes extraits de deux images par le même moteur.
+///
+/// Utile pour évaluer un prétraitement (`original.png` contre
+/// `enhanced.png`) sans texte de référence : le texte de `a` sert de
+/// référence pour le calcul du CER/WER et du diff mot à mot.
+///
+/// L'échec d'une extraction n'interrompt pas la comparaison : l'erreur est
+/// notée dans le [`ComparedImage`] correspondant, l'autre texte reste
+/// disponible, et `metrics` vaut `None`.
+///
+/// # Arguments
+///
+/// * `engine` - Moteur OCR utilisé pour les deux images
+/// * `a` - Image de référence
+/// * `b` - Image comparée
+///
+/// # Erreurs
+///
+/// Retourne une erreur si les deux extractions échouent.
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::ocr::{OcrEngine, compare_images};
+/// use text_recognition::config::OcrConfig;
+/// use std::path::Path;
+///
+/// let engine = OcrEngine::new(OcrConfig::default())?;
+/// let comparison = compare_images(&engine, Path::new("original.png"), Path::new("enhanced.png"))?;
+///
+/// if let Some(metrics) = comparison.metrics {
+///     println!("CER: {:.2}%", metrics.cer * 100.0);
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn compare_images(engine: &OcrEngine, a: &Path, b: &Path) -> Result<ImageComparison> {
+    let first = ComparedImage::extract(engine, a);
+    let second = ComparedImage::extract(engine, b);
+
+    if let (Some(first_error), Some(second_error)) = (&first.error, &second.error) {
+        anyhow::bail!(
+            "Échec de l'extraction des deux images : '{}' : {} ; '{}' : {}",
+            a.display(),
+            first_error,
+            b.display(),
+            second_error
+        );
+    }
+
+    let (metrics, word_diff) = match (&first.text, &second.text) {
+        (Some(reference), Some(text)) => (
+            Some(compare_ocr_result(text, reference)),
+            word_diff(text, reference),
+        ),
+        _ => (None, Vec::new()),
+    };
+
+    Ok(ImageComparison {
+        first,
+        second,
+        metrics,
+        word_diff,
+    })
+}
+
+/// Extrait le texte d'une image avec plusieurs configurations et fusionne les résultats.
+///
+/// Chaque configuration produit un document HOCR, trié selon
+/// [`SortOrder::TopToBottomLeftToRight`] pour que toutes les exécutions
+/// partagent le même ordre ; les mots des différentes exécutions sont
+/// appariés par recouvrement de leurs bounding boxes, puis fusionnés selon
+/// `strategy` (voir [`merge_documents()`]).
+///
+/// # Arguments
+///
+/// * `engine_configs` - Une configuration par exécution (ex: PSM 3 et PSM 6)
+/// * `path` - Chemin vers l'image
+/// * `strategy` - Stratégie de choix parmi les mots appariés
+///
+/// # Erreurs
+///
+/// Retourne une erreur si aucune configuration n'est fournie, si
+/// [`MergeStrategy::MajorityVote`] est demandée avec moins de trois
+/// configurations, ou si une exécution échoue.
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::config::OcrConfig;
+/// use text_recognition::ensemble::MergeStrategy;
+/// use text_recognition::ocr::ensemble_extract;
+/// use std::path::Path;
+///
+/// let configs = [OcrConfig::document_preset(), OcrConfig::screenshot_preset()];
+/// let result = ensemble_extract(&configs, Path::new("scan.png"), MergeStrategy::HighestConfidence)?;
+/// println!("{}", result.text);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn ensemble_extract(
+    engine_configs: &[OcrConfig],
+    path: &Path,
+    strategy: MergeStrategy,
+) -> Result<EnsembleResult> {
+    // Vérifier avant de lancer Tesseract
+    check_run_count(engine_configs.len(), strategy)?;
+
+    let docs = engine_configs
+        .iter()
+        .enumerate()
+        .map(|(run, config)| {
+            let psm = config.page_seg_mode.to_tesseract_psm();
+            let mut doc = OcrEngine::new(config.clone())
+                .and_then(|engine| engine.extract_hocr_document(path))
+                .with_context(|| format!("Échec de l'exécution {} (PSM {})", run + 1, psm))?;
+            // Des PSM différents n'émettent pas les éléments dans le même ordre
+            doc.sort(SortOrder::TopToBottomLeftToRight);
+            Ok(doc)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    merge_documents(&docs, strategy)
+}
+
+/// Extrait plusieurs fois le texte d'une image pour vérifier que le résultat est stable.
+///
+/// Voir [`determinism_check_with()`] : les sorties sont comparées par
+/// empreinte SHA-256, et chaque paire de variantes est décrite par son CER
+/// et ses lignes différentes. Activer [`OcrConfig::deterministic`] limite
+/// les appels au binaire à un thread.
+///
+/// # Arguments
+///
+/// * `engine` - Moteur OCR utilisé pour chaque exécution
+/// * `path` - Chemin vers l'image
+/// * `runs` - Nombre d'exécutions (au moins 2)
+///
+/// # Erreurs
+///
+/// Retourne une erreur si `runs` est inférieur à 2 ou si une exécution échoue.
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::config::OcrConfig;
+/// use text_recognition::ocr::{OcrEngine, determinism_check};
+/// use std::path::Path;
+///
+/// let engine = OcrEngine::new(OcrConfig::default())?;
+/// let report = determinism_check(&engine, Path::new("scan.png"), 10)?;
+/// if !report.deterministic {
+///     println!("{} sorties différentes", report.variants.len());
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn determinism_check(
+    engine: &OcrEngine,
+    path: &Path,
+    runs: usize,
+) -> Result<DeterminismReport> {
+    determinism_check_with(&mut engine.clone(), path, runs)
+}
+
+/// Analyse une image par la bibliothèque Tesseract puis par le binaire, et compare les textes.
+///
+/// Les deux chemins reçoivent la même image (prétraitée une seule fois si
+/// le moteur a un prétraitement) et les mêmes paramètres, traduits par une
+/// seule fonction : langue, PSM, OEM, DPI et variables de la
+/// configuration. Le vocabulaire utilisateur, que la bibliothèque ne sait
+/// pas charger, n'est transmis à aucun des deux. Les textes comparés sont
+/// les sorties brutes, sans le nettoyage de [`OcrConfig::cleanup`].
+///
+/// Utile pour diagnostiquer un environnement où la bibliothèque liée et le
+/// binaire `tesseract` du `PATH` n'ont pas la même version.
+///
+/// # Arguments
+///
+/// * `engine` - Moteur dont la configuration est vérifiée
+/// * `path` - Chemin vers l'image
+///
+/// # Erreurs
+///
+/// Retourne une erreur si l'image ne peut pas être lue ou prétraitée, ou si
+/// l'un des deux chemins échoue.
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::config::OcrConfig;
+/// use text_recognition::ocr::{OcrEngine, cross_check};
+/// use std::path::Path;
+///
+/// let engine = OcrEngine::new(OcrConfig::default())?;
+/// let report = cross_check(&engine, Path::new("scan.png"))?;
+/// println!("{}", report);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn cross_check(engine: &OcrEngine, path: &Path) -> Result<CrossCheckReport> {
+    let config = &engine.config;
+    let temp_dir = tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
+    let input = engine.binary_input(path, temp_dir.path())?;
+    let library_input = LibraryInput::new(&input.path)?;
+    let invocation = TesseractInvocation::new(config, &config.language, input.dpi(config));
+
+    let library_text = invocation
+        .init_library()?
+        .set_image(&library_input.path)
+        .context("Échec du chargement de l'image par la bibliothèque")?
+        .get_text()
+        .context("Échec de l'extraction du texte par la bibliothèque")?;
+
+    let output_base = temp_dir.path().join("binary");
+    let mut args = invocation.binary_args(&input.path, &output_base, &[]);
+    args.push("txt".into());
+    let output = engine.run_binary(&args)?;
+    if !output.status.success() {
+        let warnings = parse_tesseract_stderr(&String::from_utf8_lossy(&output.stderr));
+        anyhow::bail!(
+            "Le binaire tesseract a échoué.{}{}",
+            legacy_data_hint(config.engine_mode),
+            format_warnings_suffix(&warnings)
+        );
+    }
+    let binary_text = std::fs::read_to_string(temp_dir.path().join("binary.txt"))
+        .context("Échec de la lecture du texte extrait par le binaire")?;
+
+    Ok(CrossCheckReport::from_outputs(
+        library_text,
+        binary_text,
+        tesseract::plumbing::version()
+            .to_string_lossy()
+            .into_owned(),
+        tesseract_version(),
+        args[2..args.len() - 1]
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect(),
+    ))
+}
+
+/// Recommande un mode de segmentation (PSM) d'après la mise en page de l'image.
+///
+/// Alternative peu coûteuse à `--test-all-psm` : aucune analyse Tesseract
+/// n'est lancée. Les mesures de [`layout_stats()`] (blocs de texte,
+/// lignes, colonnes, remplissage) sont traduites en une liste ordonnée de
+/// modes par [`rank_psm()`](crate::layout::rank_psm).
+///
+/// # Arguments
+///
+/// * `image` - Image en niveaux de gris
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::PageSegMode;
+/// use text_recognition::ocr::recommend_psm;
+///
+/// let blank = GrayImage::from_pixel(200, 100, Luma([255]));
+/// assert_eq!(recommend_psm(&blank).top(), PageSegMode::Auto);
+/// ```
+pub fn recommend_psm(image: &GrayImage) -> PsmRecommendation {
+    PsmRecommendation::from_stats(layout_stats(image))
+}
+
+/// Formate les avertissements pour les ajouter à un message d'erreur.
+///
+/// Retourne une chaîne vide s'il n'y a aucun avertissement.
+pub(crate) fn format_warnings_suffix(warnings: &[Warning]) -> String {
+    if warnings.is_empty() {
+        return String::new();
+    }
+    let messages: Vec<&str> = warnings.iter().map(|w| w.message.as_str()).collect();
+    format!(" Tesseract : {}", messages.join(" | "))
+}
+
+/// Lance le binaire `tesseract` et collecte ses sorties, en le tuant au-delà de `timeout`.
+///
+/// Avec `thread_limit`, le binaire est limité à ce nombre de threads
+/// OpenMP (voir [`tesseract_child_command()`]).
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le binaire ne peut pas être lancé, ou
+/// [`OcrError::Timeout`] si le délai est dépassé.
+pub(crate) fn run_tesseract(
+    args: &[OsString],
+    timeout: Option<Duration>,
+    thread_limit: Option<u32>,
+) -> Result<Output> {
+    let child = tesseract_child_command(args, thread_limit)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Impossible de lancer le binaire tesseract. Est-il installé et dans le PATH ?")?;
+    wait_with_timeout(child, timeout)
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Programme lancé à la place de `tesseract` par les tests du thread courant.
+    static TESSERACT_OVERRIDE: std::cell::RefCell<Option<PathBuf>> =
+        const { std::cell::RefCell::new(None) };
+
+    /// Nombre d'analyses Tesseract (`recognize_file`) lancées par le thread courant.
+    static RECOGNIZE_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Commande du binaire `tesseract` avec ses arguments et son environnement.
+///
+/// `thread_limit` est placé dans l'environnement du seul processus enfant
+/// (`OMP_THREAD_LIMIT`), sans modifier celui du processus courant : la
+/// répartition du travail du moteur LSTM entre threads peut faire varier le
+/// résultat d'une exécution à l'autre (voir [`OcrConfig::deterministic`]).
+/// Sans limite, l'enfant hérite de l'environnement tel quel.
+pub(crate) fn tesseract_child_command(args: &[OsString], thread_limit: Option<u32>) -> Command {
+    let mut command = tesseract_command();
+    if let Some(threads) = thread_limit {
+        command.env("OMP_THREAD_LIMIT", threads.to_string());
+    }
+    command.args(args);
+    command
+}
+
+/// Prépare la commande du binaire `tesseract`.
+///
+/// Les tests peuvent la remplacer par un programme factice (voir
+/// `TESSERACT_OVERRIDE`) pour simuler un binaire lent.
+fn tesseract_command() -> Command {
+    #[cfg(test)]
+    if let Some(program) = TESSERACT_OVERRIDE.with(|program| program.borrow().clone()) {
+        return Command::new(program);
+    }
+    Command::new("tesseract")
+}
+
+/// Construit les arguments d'un appel au binaire `tesseract`.
+///
+/// Les options `-l`, `--psm` et `--oem` sont toujours passées explicitement ;
+/// `configs` contient les fichiers de configuration Tesseract à ajouter en fin
+/// de ligne de commande (ex: `hocr`).
+///
+/// # Arguments
+///
+/// * `input` - Chemin de l'image
+/// * `output` - Base du fichier de sortie, ou `stdout`
+/// * `language` - Code langue Tesseract
+/// * `psm` - Mode de segmentation de page (0-13)
+/// * `oem` - Moteur de reconnaissance
+/// * `configs` - Configurations Tesseract supplémentaires
+///
+/// Les chemins sont transmis tels quels (`OsStr`) : un nom qui n'est pas
+/// en UTF-8, ou un chemin Windows avec des caractères non ASCII, reste
+/// utilisable.
+pub(crate) fn tesseract_command_args(
+    input: impl AsRef<OsStr>,
+    output: impl AsRef<OsStr>,
+    language: &str,
+    psm: i32,
+    oem: OcrEngineMode,
+    configs: &[&str],
+) -> Vec<OsString> {
+    let mut args = vec![
+        input.as_ref().to_os_string(),
+        output.as_ref().to_os_string(),
+        "-l".into(),
+        language.into(),
+        "--psm".into(),
+        psm.to_string().into(),
+        "--oem".into(),
+        oem.to_tesseract_oem().to_string().into(),
+    ];
+    args.extend(configs.iter().map(OsString::from));
+    args
+}
+
+/// Paramètres d'une analyse, traduits à l'identique pour la bibliothèque et le binaire.
+///
+/// Toute option transmise à Tesseract passe par ce type : la bibliothèque
+/// ([`init_library()`](Self::init_library)) et le binaire
+/// ([`binary_args()`](Self::binary_args)) reçoivent ainsi la même langue,
+/// le même PSM, le même OEM, le même DPI, le même répertoire tessdata et
+/// les mêmes variables. Le binaire
+/// lit lui-même les fichiers de configuration ; la bibliothèque reçoit leurs
+/// variables une à une.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TesseractInvocation {
+    /// Code langue Tesseract (ex: `fra+eng`).
+    language: String,
+    /// Mode de segmentation de page.
+    page_seg_mode: PageSegMode,
+    /// Moteur de reconnaissance.
+    engine_mode: OcrEngineMode,
+    /// Résolution transmise à Tesseract.
+    dpi: u32,
+    /// Fichiers de configuration Tesseract ([`OcrConfig::config_names`]).
+    config_names: Vec<String>,
+    /// Variables explicites ([`OcrConfig::tesseract_variables`]).
+    variables: HashMap<String, String>,
+    /// Répertoire des modèles ([`OcrConfig::tessdata_dir`]).
+    tessdata_dir: Option<PathBuf>,
+}
+
+impl TesseractInvocation {
+    /// Traduit la configuration du moteur pour une analyse.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Configuration du moteur (PSM, OEM, variables)
+    /// * `language` - Code langue, qui peut différer de celui de `config`
+    /// * `dpi` - Résolution de l'analyse
+    pub(crate) fn new(config: &OcrConfig, language: &str, dpi: u32) -> Self {
+        Self {
+            language: language.to_string(),
+            page_seg_mode: config.page_seg_mode,
+            engine_mode: config.engine_mode,
+            dpi,
+            config_names: config.config_names.clone(),
+            variables: config.tesseract_variables.clone(),
+            tessdata_dir: config.tessdata_dir.clone(),
+        }
+    }
+
+    /// Mêmes paramètres pour l'OSD : modèle `osd` et PSM 0.
+    ///
+    /// L'OSD requiert obligatoirement le modèle `osd`, indépendamment de la
+    /// langue configurée ; le reste (OEM, DPI, tessdata, variables et
+    /// fichiers de configuration) est conservé.
+    pub(crate) fn osd(mut self) -> Self {
+        self.language = "osd".to_string();
+        self.page_seg_mode = PageSegMode::OsdOnly;
+        self
+    }
+
+    /// Moteur de reconnaissance de l'analyse.
+    pub(crate) fn engine_mode(&self) -> OcrEngineMode {
+        self.engine_mode
+    }
+
+    /// Arguments du binaire `tesseract` pour `input` et `output`.
+    ///
+    /// `-l`, `--psm`, `--oem`, `--dpi` et `--tessdata-dir` (si un
+    /// répertoire est configuré), une option `-c nom=valeur` par
+    /// variable explicite (triées ; une variable `user_defined_dpi` remplace
+    /// `--dpi`), les `options` supplémentaires puis les fichiers de
+    /// configuration. Les formats de sortie (`txt`, `hocr`) sont à ajouter
+    /// par l'appelant.
+    pub(crate) fn binary_args(
+        &self,
+        input: impl AsRef<OsStr>,
+        output: impl AsRef<OsStr>,
+        options: &[String],
+    ) -> Vec<OsString> {
+        let mut args = tesseract_command_args(
+            input,
+            output,
+            &self.language,
+            self.page_seg_mode.to_tesseract_psm(),
+            self.engine_mode,
+            &[],
+        );
+        args.extend(["--dpi".into(), self.dpi.to_string().into()]);
+        if let Some(ref dir) = self.tessdata_dir {
+            args.extend(["--tessdata-dir".into(), dir.as_os_str().to_os_string()]);
+        }
+        let mut variables: Vec<_> = self.variables.iter().collect();
+        variables.sort();
+        for (key, value) in variables {
+            args.extend(["-c".into(), format!("{}={}", key, value).into()]);
+        }
+        args.extend(options.iter().map(OsString::from));
+        args.extend(self.config_names.iter().map(OsString::from));
+        args
+    }
+
+    /// Arguments du binaire pour une sortie hOCR : ceux de
+    /// [`binary_args()`](Self::binary_args) suivis de la configuration `hocr`,
+    /// qui doit rester en fin de ligne de commande.
+    pub(crate) fn hocr_args(
+        &self,
+        input: impl AsRef<OsStr>,
+        output: impl AsRef<OsStr>,
+        options: &[String],
+    ) -> Vec<OsString> {
+        let mut args = self.binary_args(input, output, options);
+        args.push("hocr".into());
+        args
+    }
+
+    /// Variables appliquées à la bibliothèque, dans l'ordre : le DPI
+    /// (`user_defined_dpi`, équivalent de `--dpi`), les variables des
+    /// fichiers de configuration puis les variables explicites triées.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si un fichier de configuration est introuvable
+    /// ou invalide.
+    fn library_variables(&self) -> Result<Vec<(String, String)>> {
+        let mut variables = vec![("user_defined_dpi".to_string(), self.dpi.to_string())];
+        variables.extend(tesseract_settings_for(&self.config_names, &self.variables)?);
+        Ok(variables)
+    }
+
+    /// Initialise une instance de la bibliothèque Tesseract avec ces paramètres.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si Tesseract ne peut pas être initialisé (langue
+    /// absente, modèle legacy manquant), si le répertoire tessdata n'est pas
+    /// en UTF-8, si un fichier de configuration est invalide ou si
+    /// Tesseract refuse une variable.
+    pub(crate) fn init_library(&self) -> Result<tesseract::Tesseract> {
+        let engine_mode = self.engine_mode;
+        let oem = match engine_mode {
+            OcrEngineMode::LegacyOnly => tesseract::OcrEngineMode::TesseractOnly,
+            OcrEngineMode::LstmOnly => tesseract::OcrEngineMode::LstmOnly,
+            OcrEngineMode::LegacyPlusLstm => tesseract::OcrEngineMode::TesseractLstmCombined,
+            OcrEngineMode::Default => tesseract::OcrEngineMode::Default,
+        };
+        // Répertoire passé à l'initialisation, jamais par TESSDATA_PREFIX
+        let datapath = self
+            .tessdata_dir
+            .as_deref()
+            .map(|dir| {
+                dir.to_str().with_context(|| {
+                    format!(
+                        "Répertoire tessdata non UTF-8, illisible par la bibliothèque Tesseract : '{}'",
+                        dir.display()
+                    )
+                })
+            })
+            .transpose()?;
+        let mut tesseract = tesseract::Tesseract::new_with_oem(datapath, Some(&self.language), oem)
+            .with_context(|| {
+                format!(
+                    "Échec de l'initialisation de Tesseract (OEM {}).{}",
+                    engine_mode.to_tesseract_oem(),
+                    legacy_data_hint(engine_mode)
+                )
+            })?;
+
+        // Appliquer le mode de segmentation de page
+        let psm = match self.page_seg_mode {
+            PageSegMode::OsdOnly => tesseract::PageSegMode::PsmOsdOnly,
+            PageSegMode::AutoOsd => tesseract::PageSegMode::PsmAutoOsd,
+            PageSegMode::AutoOnly => tesseract::PageSegMode::PsmAutoOnly,
+            PageSegMode::Auto => tesseract::PageSegMode::PsmAuto,
+            PageSegMode::SingleColumn => tesseract::PageSegMode::PsmSingleColumn,
+            PageSegMode::SingleBlockVertText => tesseract::PageSegMode::PsmSingleBlockVertText,
+            PageSegMode::SingleBlock => tesseract::PageSegMode::PsmSingleBlock,
+            PageSegMode::SingleLine => tesseract::PageSegMode::PsmSingleLine,
+            PageSegMode::SingleWord => tesseract::PageSegMode::PsmSingleWord,
+            PageSegMode::CircleWord => tesseract::PageSegMode::PsmCircleWord,
+            PageSegMode::SingleChar => tesseract::PageSegMode::PsmSingleChar,
+            PageSegMode::SparseText => tesseract::PageSegMode::PsmSparseText,
+            PageSegMode::SparseTextOsd => tesseract::PageSegMode::PsmSparseTextOsd,
+            PageSegMode::RawLine => tesseract::PageSegMode::PsmRawLine,
+        };
+        tesseract.set_page_seg_mode(psm);
+
+        // DPI, variables des fichiers de configuration puis variables personnalisées
+        for (key, value) in self.library_variables()? {
+            tesseract = tesseract
+                .set_variable(&key, &value)
+                .with_context(|| format!("Échec de la configuration de la variable '{}'", key))?;
+        }
+
+        Ok(tesseract)
+    }
+}
+
+/// Explication ajoutée aux erreurs Tesseract lorsque le moteur legacy est demandé.
+///
+/// Les modèles `tessdata_fast` et `tessdata_best` (souvent installés par
+/// défaut) ne contiennent que le réseau LSTM : l'initialisation échoue
+/// alors en OEM 0 ou 2. Retourne une chaîne vide pour les autres modes.
+pub(crate) fn legacy_data_hint(oem: OcrEngineMode) -> &'static str {
+    if oem.requires_legacy_data() {
+        " Le moteur legacy (OEM 0 et 2) nécessite des fichiers .traineddata \
+         contenant le modèle legacy (dépôt tessdata), absent de tessdata_fast \
+         et tessdata_best."
+    } else {
+        ""
+    }
+}
+
+/// Retourne une table de correspondance script OSD → langue Tesseract par défaut.
+///
+/// Les noms de scripts sont ceux retournés par Tesseract en PSM 0
+/// (ligne `Script: ...`).
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::ocr::default_script_languages;
+///
+/// let table = default_script_languages();
+/// assert_eq!(table.get("Latin"), Some(&"fra+eng".to_string()));
+/// assert_eq!(table.get("Cyrillic"), Some(&"rus".to_string()));
+/// ```
+pub fn default_script_languages() -> HashMap<String, String> {
+    let mut table = HashMap::new();
+    table.insert("Latin".to_string(), "fra+eng".to_string());
+    table.insert("Cyrillic".to_string(), "rus".to_string());
+    table.insert("Greek".to_string(), "ell".to_string());
+    table.insert("Arabic".to_string(), "ara".to_string());
+    table.insert("Han".to_string(), "chi_sim".to_string());
+    table
+}
+
+/// Corps supposé du texte courant pour le DPI adaptatif (entre 10 et 12 points).
+const ADAPTIVE_DPI_FONT_PT: f32 = 11.0;
+
+/// Écart relatif au-delà duquel le DPI adaptatif relance l'analyse.
+const ADAPTIVE_DPI_TOLERANCE: f64 = 0.25;
+
+/// Nombre minimal de mots reconnus pour se fier à la taille mesurée du texte.
+const ADAPTIVE_DPI_MIN_WORDS: usize = 5;
+
+/// Bornes des résolutions proposées par le DPI adaptatif.
+const ADAPTIVE_DPI_RANGE: std::ops::RangeInclusive<u32> = 70..=1200;
+
+/// Décide si une première analyse doit être relancée avec un autre DPI.
+///
+/// # Arguments
+///
+/// * `configured_dpi` - DPI de la première analyse
+/// * `estimate` - Taille du texte mesurée lors de cette analyse
+///
+/// # Retour
+///
+/// Le DPI corrigé (borné à [`ADAPTIVE_DPI_RANGE`]) s'il s'écarte de plus de
+/// 25 % du DPI configuré, `None` s'il est proche ou si trop peu de mots ont
+/// été reconnus pour l'estimer
+fn adaptive_dpi_retry(configured_dpi: u32, estimate: &TextMetricsEstimate) -> Option<u32> {
+    if estimate.word_count < ADAPTIVE_DPI_MIN_WORDS || configured_dpi == 0 {
+        return None;
+    }
+    let suggested = suggest_dpi(estimate, ADAPTIVE_DPI_FONT_PT)
+        .clamp(*ADAPTIVE_DPI_RANGE.start(), *ADAPTIVE_DPI_RANGE.end());
+    let deviation = (suggested as f64 - configured_dpi as f64).abs() / configured_dpi as f64;
+    (deviation > ADAPTIVE_DPI_TOLERANCE).then_some(suggested)
+}
+
+/// Nombre maximal de lignes relues une à une par la segmentation de secours.
+///
+/// Au-delà, la détection est jugée aberrante (trame, photo, bruit) : la page
+/// n'est pas relue, ce qui éviterait des centaines d'appels à Tesseract.
+pub const MAX_FALLBACK_LINES: usize = 200;
+
+/// Marge ajoutée au-dessus et au-dessous de chaque ligne relue, en pixels.
+pub const FALLBACK_LINE_PADDING: u32 = 4;
+
+/// Indique si la lecture ligne par ligne doit prendre le relais.
+///
+/// C'est le cas lorsque l'analyse n'a produit aucun texte (ou seulement des
+/// espaces) alors que la page contient des formes plus grandes qu'une tache.
+fn needs_line_fallback(text: &str, content: PageContent) -> bool {
+    text.trim().is_empty() && content == PageContent::HasContent
+}
+
+/// Joint les textes des lignes relues, de haut en bas, un par ligne.
+///
+/// Les espaces autour de chaque texte sont retirés et les lignes restées
+/// vides sont omises.
+fn join_line_texts(texts: &[String]) -> String {
+    texts
+        .iter()
+        .map(|text| text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Nombre de mots d'un texte extrait.
+fn count_words(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+/// Liste les langues installées pour Tesseract.
+///
+/// Exécute `tesseract --list-langs`. Le binaire hérite de l'environnement :
+/// lorsque `TESSDATA_PREFIX` est défini, ce sont les langues de ce
+/// répertoire qui sont listées.
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::ocr::installed_languages;
+///
+/// for language in installed_languages()? {
+///     println!("{}", language);
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le binaire `tesseract` est introuvable ou s'il échoue.
+pub fn installed_languages() -> Result<Vec<String>> {
+    let output = tesseract_command()
+        .arg("--list-langs")
+        .output()
+        .context("Impossible d'exécuter 'tesseract --list-langs' (Tesseract est-il installé ?)")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "'tesseract --list-langs' a échoué : {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(parse_language_list(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Vérifie que toutes les langues d'une configuration sont installées.
+///
+/// # Arguments
+///
+/// * `config_language` - Langue(s) Tesseract, séparées par `+` (ex: "eng+fra")
+///
+/// # Erreurs
+///
+/// Retourne une erreur si les langues installées ne peuvent pas être
+/// listées, ou si une langue manque (voir [`check_languages()`]).
+pub fn validate_languages(config_language: &str) -> Result<()> {
+    check_languages(config_language, &installed_languages()?)
+}
+
+/// Vérifie qu'une configuration de langues est couverte par une liste de langues installées.
+///
+/// # Arguments
+///
+/// * `config_language` - Langue(s) Tesseract, séparées par `+` (ex: "eng+fra")
+/// * `installed` - Langues installées (voir [`installed_languages()`])
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::ocr::check_languages;
+///
+/// let installed = vec!["eng".to_string(), "osd".to_string()];
+/// assert!(check_languages("eng", &installed).is_ok());
+///
+/// let error = check_languages("eng+fra", &installed).unwrap_err();
+/// assert!(error.to_string().contains("fra"));
+/// ```
+///
+/// # Erreurs
+///
+/// Retourne une erreur nommant chaque langue manquante, avec le paquet à
+/// installer (apt ou Homebrew) et les langues disponibles.
+pub fn check_languages(config_language: &str, installed: &[String]) -> Result<()> {
+    let missing: Vec<&str> = config_language
+        .split('+')
+        .map(str::trim)
+        .filter(|code| !code.is_empty() && !installed.iter().any(|language| language == code))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let packages: Vec<String> = missing
+        .iter()
+        .map(|code| format!("tesseract-ocr-{}", code.to_lowercase().replace('_', "-")))
+        .collect();
+    let available = if installed.is_empty() {
+        "aucune".to_string()
+    } else {
+        installed.join(", ")
+    };
+    anyhow::bail!(
+        "Données linguistiques Tesseract manquantes : {} (installées : {}). \
+         Installez-les avec 'apt install {}' ou 'brew install tesseract-lang', \
+         ou copiez {} dans le répertoire tessdata (TESSDATA_PREFIX).",
+        missing.join(", "),
+        available,
+        packages.join(" "),
+        missing
+            .iter()
+            .map(|code| format!("{}.traineddata", code))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// Extrait les codes de langue de la sortie de `tesseract --list-langs`.
+///
+/// L'en-tête varie selon la version (`List of available languages (3):`
+/// pour Tesseract 4, suivi du répertoire tessdata pour Tesseract 5).
+fn parse_language_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("List of available languages"))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Extrait le nom du script depuis la sortie OSD de Tesseract.
+///
+/// # Arguments
+///
+/// * `osd_output` - Sortie de Tesseract en PSM 0 (contient une ligne `Script: Latin`)
+fn parse_osd_script(osd_output: &str) -> Option<String> {
+    osd_output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Script:"))
+        .map(|script| script.trim().to_string())
+        .filter(|script| !script.is_empty())
+}
+
+/// Assemble le texte du fond de page et celui des blocs inclinés (voir
+/// [`OcrEngine::extract_text_composite()`]).
+fn composite_text(background: &str, blocks: &[(SkewedRegion, String)]) -> String {
+    let mut sections = Vec::with_capacity(blocks.len() + 1);
+    if !background.trim().is_empty() {
+        sections.push(background.trim_end().to_string());
+    }
+    for (index, (region, text)) in blocks.iter().enumerate() {
+        sections.push(format!(
+            "--- Région {} ({},{} {}×{}, {:.1}°) ---\n{}",
+            index + 1,
+            region.bbox.x,
+            region.bbox.y,
+            region.bbox.width,
+            region.bbox.height,
+            region.angle,
+            text.trim_end()
+        ));
+    }
+    sections.join("\n\n")
+}
+
+/// Applique une étape d'OCR à chaque région, en réutilisant les instances par langue.
+///
+/// Séparé de [`OcrEngine::extract_text_multilang()`] pour que le découpage,
+/// l'ordre des résultats et l'utilisation du cache soient testables sans Tesseract.
+///
+/// # Arguments
+///
+/// * `image` - Image source
+/// * `regions` - Liste de couples (rectangle, langue)
+/// * `cache` - Cache d'instances par langue
+/// * `init` - Initialise une instance pour une langue
+/// * `recognize` - Extrait le texte d'une région et rend l'instance
+fn ocr_regions<T>(
+    image: &DynamicImage,
+    regions: &[(BBox, String)],
+    cache: &mut LanguageCache<T>,
+    mut init: impl FnMut(&str) -> Result<T>,
+    mut recognize: impl FnMut(T, &DynamicImage) -> Result<(T, String)>,
+) -> Result<Vec<RegionResult>> {
+    let mut results = Vec::with_capacity(regions.len());
+
+    for (bbox, language) in regions {
+        let region_img = crop_region(image, bbox)?;
+        let instance = cache.take_or_init(language, &mut init)?;
+        let (instance, text) = recognize(instance, &region_img)?;
+        cache.put_back(language, instance);
+
+        results.push(RegionResult {
+            bbox: bbox.clone(),
+            language: language.clone(),
+            text,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Nombre maximal de mots comparés dans la zone de chevauchement de deux tuiles.
+const MAX_OVERLAP_WORDS: usize = 30;
+
+/// Distance de Levenshtein maximale (relative à la longueur) pour considérer
+/// deux séquences de mots comme identiques.
+const OVERLAP_MAX_DISTANCE_RATIO: f64 = 0.2;
+
+/// Calcule les bandes horizontales (y, hauteur) couvrant une image.
+///
+/// Deux bandes consécutives se chevauchent de `overlap` pixels ; la dernière
+/// bande est tronquée au bas de l'image. Suppose `overlap < tile_size`.
+fn tile_bands(image_height: u32, tile_size: u32, overlap: u32) -> Vec<(u32, u32)> {
+    let stride = tile_size - overlap;
+    let mut bands = Vec::new();
+    let mut y = 0;
+
+    loop {
+        let height = tile_size.min(image_height - y);
+        bands.push((y, height));
+        if y + height >= image_height {
+            break;
+        }
+        y += stride;
+    }
+
+    bands
+}
+
+/// Fusionne le texte d'une tuile avec le texte accumulé des tuiles précédentes.
+///
+/// Cherche la plus longue séquence de mots qui termine `previous` et commence
+/// `next` (à une distance de Levenshtein près, pour tolérer les erreurs d'OCR
+/// différentes d'une tuile à l'autre), puis ne conserve qu'un seul exemplaire.
+fn merge_overlapping_texts(previous: &str, next: &str) -> String {
+    let previous = previous.trim_end();
+    let next = next.trim();
+    if previous.is_empty() {
+        return next.to_string();
+    }
+    if next.is_empty() {
+        return previous.to_string();
+    }
+
+    let previous_words: Vec<&str> = previous.split_whitespace().collect();
+    let next_words: Vec<&str> = next.split_whitespace().collect();
+    let max_overlap = previous_words
+        .len()
+        .min(next_words.len())
+        .min(MAX_OVERLAP_WORDS);
+
+    let overlap = (1..=max_overlap).rev().find(|&count| {
+        let tail = previous_words[previous_words.len() - count..].join(" ");
+        let head = next_words[..count].join(" ");
+        let length = tail.chars().count().max(head.chars().count());
+        levenshtein_distance(&tail, &head) as f64 <= length as f64 * OVERLAP_MAX_DISTANCE_RATIO
+    });
+
+    match overlap {
+        Some(count) => {
+            let rest = skip_words(next, count);
+            if rest.trim().is_empty() {
+                previous.to_string()
+            } else {
+                // Conserver le séparateur d'origine (saut de ligne ou espace)
+                let separator = if rest.trim_start_matches([' ', '\t']).starts_with('\n') {
+                    "\n"
+                } else {
+                    " "
+                };
+                format!("{}{}{}", previous, separator, rest.trim_start())
+            }
+        }
+        None => format!("{}\n{}", previous, next),
+    }
+}
+
+/// Retourne le texte restant après les `count` premiers mots.
+fn skip_words(text: &str, count: usize) -> &str {
+    let mut remaining = text;
+    for _ in 0..count {
+        remaining = remaining.trim_start();
+        let end = remaining
+            .find(char::is_whitespace)
+            .unwrap_or(remaining.len());
+        remaining = &remaining[end..];
+    }
+    remaining
+}
+
+/// Découpe une région dans une image en la limitant aux bords de l'image.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si la région est vide une fois limitée à l'image.
+fn crop_region(image: &DynamicImage, bbox: &BBox) -> Result<DynamicImage> {
+    let x = bbox.x.min(image.width());
+    let y = bbox.y.min(image.height());
+    let width = bbox.width.min(image.width() - x);
+    let height = bbox.height.min(image.height() - y);
+
+    if width == 0 || height == 0 {
+        anyhow::bail!(
+            "Région bbox({}, {}, {}, {}) vide ou hors de l'image ({}x{})",
+            bbox.x,
+            bbox.y,
+            bbox.width,
+            bbox.height,
+            image.width(),
+            image.height()
+        );
+    }
+
+    Ok(image.crop_imm(x, y, width, height))
+}
+
+/// Cache d'instances initialisées par langue.
+///
+/// L'initialisation de Tesseract charge le modèle linguistique depuis le disque,
+/// ce qui est coûteux. Ce cache conserve une instance par langue pour la durée
+/// d'une extraction multi-régions.
+///
+/// Les méthodes de `tesseract::Tesseract` consomment l'instance (`self`), d'où le
+/// couple [`take_or_init()`](Self::take_or_init) / [`put_back()`](Self::put_back).
+struct LanguageCache<T> {
+    /// Instances disponibles, indexées par langue.
+    instances: HashMap<String, T>,
+    /// Nombre d'initialisations effectuées (absences du cache).
+    init_count: usize,
+}
+
+impl<T> LanguageCache<T> {
+    /// Crée un cache vide.
+    fn new() -> Self {
+        Self {
+            instances: HashMap::new(),
+            init_count: 0,
+        }
+    }
+
+    /// Retire l'instance associée à `language` du cache, ou en initialise une nouvelle.
+    fn take_or_init(&mut self, language: &str, init: impl FnOnce(&str) -> Result<T>) -> Result<T> {
+        match self.instances.remove(language) {
+            Some(instance) => Ok(instance),
+            None => {
+                self.init_count += 1;
+                init(language)
+            }
+        }
+    }
+
+    /// Remet une instance dans le cache pour une utilisation ultérieure.
+    fn put_back(&mut self, language: &str, instance: T) {
+        self.instances.insert(language.to_string(), instance);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_cache_hit_for_shared_language() {
+        let mut cache: LanguageCache<String> = LanguageCache::new();
+
+        for language in ["fra", "eng", "fra"] {
+            let instance = cache
+                .take_or_init(language, |lang| Ok(format!("instance-{}", lang)))
+                .unwrap();
+            assert_eq!(instance, format!("instance-{}", language));
+            cache.put_back(language, instance);
+        }
+
+        // "fra" ne doit être initialisé qu'une seule fois
+        assert_eq!(cache.init_count, 2);
+    }
+
+    #[test]
+    fn test_language_cache_init_error_propagates() {
+        let mut cache: LanguageCache<String> = LanguageCache::new();
+        let result = cache.take_or_init("xxx", |_| anyhow::bail!("langue absente"));
+        assert!(result.is_err());
+        assert_eq!(cache.init_count, 1);
+    }
+
+    #[test]
+    fn test_parse_osd_script() {
+        let osd =
+            "Page number: 0\nOrientation in degrees: 0\nScript: Cyrillic\nScript confidence: 2.5";
+        assert_eq!(parse_osd_script(osd), Some("Cyrillic".to_string()));
+        assert_eq!(parse_osd_script("Orientation in degrees: 0"), None);
+    }
+
+    #[test]
+    fn test_parse_language_list() {
+        let tesseract4 = "List of available languages (3):\neng\nfra\nosd\n";
+        assert_eq!(parse_language_list(tesseract4), ["eng", "fra", "osd"]);
+
+        let tesseract5 = "List of available languages in \"/usr/share/tesseract-ocr/5/tessdata/\" (4):\r\n\
+                          chi_sim \r\neng\r\nosd\r\nscript/Latin  \r\n\r\n";
+        assert_eq!(
+            parse_language_list(tesseract5),
+            ["chi_sim", "eng", "osd", "script/Latin"]
+        );
+
+        assert!(parse_language_list("List of available languages (0):\n").is_empty());
+    }
+
+    #[test]
+    fn test_check_languages_reports_missing_component() {
+        let installed = vec!["eng".to_string(), "osd".to_string(), "chi_sim".to_string()];
+
+        assert!(check_languages("eng", &installed).is_ok());
+        assert!(check_languages("chi_sim+eng", &installed).is_ok());
+
+        let error = check_languages("eng+fra", &installed)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("manquantes : fra "), "{}", error);
+        assert!(error.contains("apt install tesseract-ocr-fra"), "{}", error);
+        assert!(error.contains("brew install tesseract-lang"), "{}", error);
+        assert!(
+            error.contains("installées : eng, osd, chi_sim"),
+            "{}",
+            error
+        );
+        assert!(!error.contains("eng.traineddata"), "{}", error);
+
+        let error = check_languages("chi_tra", &[]).unwrap_err().to_string();
+        assert!(error.contains("tesseract-ocr-chi-tra"), "{}", error);
+        assert!(error.contains("installées : aucune"), "{}", error);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_installed_languages_runs_tesseract() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("tesseract-langues");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\necho 'List of available languages in \"/opt/tessdata/\" (2):'\n\
+             echo eng\necho osd\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        TESSERACT_OVERRIDE.with(|program| *program.borrow_mut() = Some(script));
+
+        assert_eq!(installed_languages().unwrap(), ["eng", "osd"]);
+        assert!(validate_languages("eng").is_ok());
+        let error = validate_languages("fra").unwrap_err();
+        assert!(error.to_string().contains("fra.traineddata"));
+        let error = OcrEngine::new_validated(OcrConfig::default()).unwrap_err();
+        assert!(error.to_string().contains("tesseract-ocr-fra"));
+    }
+
+    #[test]
+    fn test_needs_line_fallback() {
+        assert!(needs_line_fallback("", PageContent::HasContent));
+        assert!(needs_line_fallback(" \n\n", PageContent::HasContent));
+        assert!(!needs_line_fallback("Total", PageContent::HasContent));
+        // Page blanche ou quasi blanche : rien à relire
+        assert!(!needs_line_fallback("", PageContent::Blank));
+        assert!(!needs_line_fallback("", PageContent::NearBlank));
+    }
+
+    #[test]
+    fn test_join_line_texts_keeps_order_with_single_newlines() {
+        let texts = [
+            "Première ligne\n".to_string(),
+            " deuxième \n\n".to_string(),
+            "\n".to_string(),
+            "troisième".to_string(),
+        ];
+        assert_eq!(
+            join_line_texts(&texts),
+            "Première ligne\ndeuxième\ntroisième"
+        );
+        assert_eq!(join_line_texts(&[]), "");
+    }
+
+    #[test]
+    fn test_adaptive_dpi_retry() {
+        // Hauteur d'x de 20 px en 11 points : environ 262 DPI
+        let estimate = TextMetricsEstimate {
+            word_count: 40,
+            median_word_height: 30.0,
+            median_line_spacing: 12.0,
+            x_height: 20.0,
+        };
+        assert_eq!(adaptive_dpi_retry(300, &estimate), None);
+        assert_eq!(adaptive_dpi_retry(150, &estimate), Some(262));
+        assert_eq!(adaptive_dpi_retry(600, &estimate), Some(262));
+
+        // Trop peu de mots pour se fier à la mesure
+        let sparse = TextMetricsEstimate {
+            word_count: 2,
+            ..estimate
+        };
+        assert_eq!(adaptive_dpi_retry(150, &sparse), None);
+
+        // Texte minuscule : suggestion bornée au minimum utile à Tesseract
+        let tiny = TextMetricsEstimate {
+            x_height: 1.0,
+            ..estimate
+        };
+        assert_eq!(adaptive_dpi_retry(300, &tiny), Some(70));
+    }
+
+    #[test]
+    fn test_count_words() {
+        assert_eq!(count_words("  Bonjour le\nmonde \t!"), 4);
+        assert_eq!(count_words(""), 0);
+    }
+
+    #[test]
+    fn test_crop_region_clamps_to_image() {
+        let img = DynamicImage::new_luma8(100, 50);
+        let cropped = crop_region(&img, &BBox::new(80, 40, 50, 50)).unwrap();
+        assert_eq!((cropped.width(), cropped.height()), (20, 10));
+    }
+
+    #[test]
+    fn test_crop_region_outside_image() {
+        let img = DynamicImage::new_luma8(100, 50);
+        assert!(crop_region(&img, &BBox::new(200, 0, 10, 10)).is_err());
+    }
+
+    #[test]
+    fn test_relative_region_crops_same_pixels() {
+        let img = DynamicImage::ImageLuma8(image::GrayImage::from_fn(400, 200, |x, y| {
+            image::Luma([((x * 7 + y * 13) % 256) as u8])
+        }));
+        let region = RelativeRegion::new(0.8, 0.0, 0.2, 0.1).unwrap();
+
+        let by_fraction = crop_region(&img, &region.to_bbox(img.width(), img.height())).unwrap();
+        let by_pixels = crop_region(&img, &BBox::new(320, 0, 80, 20)).unwrap();
+
+        assert_eq!(by_fraction.to_luma8(), by_pixels.to_luma8());
+    }
+
+    #[test]
+    fn test_composite_text_marks_each_block() {
+        let block = |x, y, angle| SkewedRegion {
+            bbox: BBox::new(x, y, 300, 200),
+            angle,
+            confidence: 0.8,
+        };
+        let text = composite_text(
+            "Relevé de compte\n",
+            &[
+                (block(640, 40, -30.0), "TICKET 118\n".to_string()),
+                (block(20, 600, 12.5), "Reçu".to_string()),
+            ],
+        );
+        assert_eq!(
+            text,
+            "Relevé de compte\n\n\
+             --- Région 1 (640,40 300×200, -30.0°) ---\nTICKET 118\n\n\
+             --- Région 2 (20,600 300×200, 12.5°) ---\nReçu"
+        );
+        assert!(
+            composite_text("  \n", &[(block(0, 0, 5.0), "x".to_string())])
+                .starts_with("--- Région 1")
+        );
+    }
+
+    #[test]
+    fn test_tile_bands_cover_image_with_overlap() {
+        assert_eq!(
+            tile_bands(250, 100, 20),
+            vec![(0, 100), (80, 100), (160, 90)]
+        );
+        assert_eq!(tile_bands(80, 100, 20), vec![(0, 80)]);
+    }
+
+    #[test]
+    fn test_merge_overlapping_texts_removes_duplicate_strip() {
+        let first = "Bonjour à tous\nhello world\n";
+        let second = "hello world\nfin du document\n";
+        assert_eq!(
+            merge_overlapping_texts(first, second),
+            "Bonjour à tous\nhello world\nfin du document"
+        );
+    }
+
+    #[test]
+    fn test_merge_overlapping_texts_tolerates_ocr_errors() {
+        let merged = merge_overlapping_texts("intro hello world", "hello wor1d suite");
+        assert_eq!(merged, "intro hello world suite");
+    }
+
+    #[test]
+    fn test_merge_overlapping_texts_without_overlap() {
+        assert_eq!(merge_overlapping_texts("", "début"), "début");
+        assert_eq!(merge_overlapping_texts("abc def", "xyz"), "abc def\nxyz");
+    }
+
+    #[test]
+    fn test_region_results_preserve_input_order() {
+        use crate::testutil::{RenderOptions, render_text_image};
+
+        // 3 lignes de 6 caractères de 16 × 32 pixels, marge de 4 : 104 × 120 pixels
+        let img = render_text_image(
+            "Titre \n\nEN  FR",
+            &RenderOptions {
+                margin: 4,
+                ..RenderOptions::default()
+            },
+        );
+        assert_eq!((img.width(), img.height()), (104, 120));
+        let regions = vec![
+            (BBox::new(0, 0, 104, 40), "fra".to_string()),
+            (BBox::new(0, 80, 52, 40), "eng".to_string()),
+            (BBox::new(52, 80, 52, 40), "fra".to_string()),
+        ];
+        let mut cache = LanguageCache::new();
+
+        // Instance factice : la langue ; texte factice : langue + largeur de la région
+        let results = ocr_regions(
+            &img,
+            &regions,
+            &mut cache,
+            |lang| Ok(lang.to_string()),
+            |instance, region| {
+                // Chaque région découpée contient bien du texte
+                assert!(region.to_luma8().pixels().any(|p| p[0] < 128));
+                let text = format!("{}:{}", instance, region.width());
+                Ok((instance, text))
+            },
+        )
+        .unwrap();
+
+        assert_eq!(results.len(), 3);
+        for (result, (bbox, language)) in results.iter().zip(&regions) {
+            assert_eq!(&result.bbox, bbox);
+            assert_eq!(&result.language, language);
+        }
+        assert_eq!(results[0].text, "fra:104");
+        assert_eq!(results[1].text, "eng:52");
+        assert_eq!(results[2].text, "fra:52");
+
+        // Deux régions partagent "fra" : une seule initialisation pour cette langue
+        assert_eq!(cache.init_count, 2);
+    }
+
+    #[test]
+    fn test_decode_image_bytes_png() {
+        use image::{ImageFormat, Luma};
+        use std::io::Cursor;
+
+        let img = DynamicImage::ImageLuma8(image::GrayImage::from_pixel(12, 8, Luma([200])));
+        let mut bytes = Vec::new();
+        img.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+
+        let decoded = decode_image_bytes(&bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (12, 8));
+        assert_eq!(decoded.to_luma8().get_pixel(3, 3)[0], 200);
+    }
+
+    #[test]
+    fn test_decode_image_bytes_invalid() {
+        assert!(decode_image_bytes(&[]).is_err());
+
+        let error = decode_image_bytes(b"pas une image").unwrap_err();
+        assert!(error.to_string().contains("décodage"));
+    }
+
+    #[test]
+    fn test_needs_in_memory_decoding() {
+        assert!(needs_in_memory_decoding(Path::new("anim.gif")));
+        assert!(needs_in_memory_decoding(Path::new("photo.WEBP")));
+        assert!(!needs_in_memory_decoding(Path::new("scan.png")));
+        assert!(!needs_in_memory_decoding(Path::new("sans_extension")));
+    }
+
+    #[test]
+    fn test_tesseract_command_args_include_oem() {
+        let args = tesseract_command_args(
+            "page.png",
+            "out",
+            "fra",
+            6,
+            OcrEngineMode::LstmOnly,
+            &["hocr"],
+        );
+        assert_eq!(
+            args,
+            [
+                "page.png", "out", "-l", "fra", "--psm", "6", "--oem", "1", "hocr"
+            ]
+        );
+
+        let osd =
+            tesseract_command_args("page.png", "stdout", "osd", 0, OcrEngineMode::Default, &[]);
+        assert_eq!(osd[osd.len() - 2..], ["--oem", "3"]);
+    }
+
+    #[test]
+    fn test_binary_input_dpi_follows_scale_factor() {
+        let config = OcrConfig::default();
+        let upscaled = BinaryInput {
+            path: PathBuf::from("page.png"),
+            scale_factor: 2.0,
+        };
+        let invocation = TesseractInvocation::new(&config, &config.language, upscaled.dpi(&config));
+        let args = invocation.binary_args("page.png", "out", &[]);
+        let dpi = args.iter().position(|arg| arg == "--dpi").unwrap();
+        assert_eq!(args[dpi + 1], (config.dpi * 2).to_string().as_str());
+        assert!(
+            invocation
+                .library_variables()
+                .unwrap()
+                .contains(&("user_defined_dpi".to_string(), (config.dpi * 2).to_string()))
+        );
+
+        let unscaled = BinaryInput {
+            path: PathBuf::from("page.png"),
+            scale_factor: 1.0,
+        };
+        assert_eq!(unscaled.dpi(&config), config.dpi);
+    }
+
+    #[test]
+    fn test_child_command_tessdata_and_threads() {
+        let config = OcrConfig {
+            tessdata_dir: Some(PathBuf::from("/opt/tessdata_best")),
+            threads: Some(3),
+            ..OcrConfig::default()
+        };
+        let invocation = TesseractInvocation::new(&config, &config.language, config.dpi);
+        let args = invocation.binary_args("page.png", "out", &[]);
+        let command = tesseract_child_command(&args, config.thread_limit());
+
+        let command_args: Vec<&OsStr> = command.get_args().collect();
+        let dir = command_args
+            .iter()
+            .position(|arg| *arg == "--tessdata-dir")
+            .unwrap();
+        assert_eq!(command_args[dir + 1], "/opt/tessdata_best");
+        let envs: Vec<_> = command.get_envs().collect();
+        assert_eq!(
+            envs,
+            [(OsStr::new("OMP_THREAD_LIMIT"), Some(OsStr::new("3")))]
+        );
+        // L'environnement du processus courant n'est jamais modifié
+        assert_ne!(
+            std::env::var_os("OMP_THREAD_LIMIT").as_deref(),
+            Some(OsStr::new("3"))
+        );
+
+        // Sans réglage : ni option ni variable propre à l'enfant
+        let default = OcrConfig::default();
+        let args = TesseractInvocation::new(&default, &default.language, default.dpi).binary_args(
+            "page.png",
+            "out",
+            &[],
+        );
+        let command = tesseract_child_command(&args, default.thread_limit());
+        assert!(!args.iter().any(|arg| arg == "--tessdata-dir"));
+        assert_eq!(command.get_envs().count(), 0);
+    }
+
+    #[test]
+    fn test_engine_rejects_missing_traineddata() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = OcrEngine::new(OcrConfig {
+            language: "fra".to_string(),
+            tessdata_dir: Some(dir.path().to_path_buf()),
+            ..OcrConfig::default()
+        })
+        .unwrap_err();
+        assert!(error.to_string().contains("fra.traineddata"));
+    }
+
+    #[test]
+    fn test_tesseract_invocation_binary_args() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("chiffres.cfg");
+        std::fs::write(&file, "tessedit_char_whitelist 0123456789\n").unwrap();
+        let mut config = OcrConfig {
+            page_seg_mode: crate::config::PageSegMode::SingleLine,
+            engine_mode: OcrEngineMode::LegacyPlusLstm,
+            config_names: vec![file.display().to_string()],
+            tessdata_dir: Some(dir.path().to_path_buf()),
+            ..OcrConfig::default()
+        };
+        config
+            .tesseract_variables
+            .insert("preserve_interword_spaces".to_string(), "1".to_string());
+        config
+            .tesseract_variables
+            .insert("load_system_dawg".to_string(), "0".to_string());
+        let tessdata = dir.path().to_str().unwrap();
+
+        let invocation = TesseractInvocation::new(&config, "fra+eng", 450);
+        let options = ["--user-words".to_string(), "mots.txt".to_string()];
+        let args = invocation.binary_args("page.png", "out", &options);
+        assert_eq!(
+            args,
+            [
+                "page.png",
+                "out",
+                "-l",
+                "fra+eng",
+                "--psm",
+                "7",
+                "--oem",
+                "2",
+                "--dpi",
+                "450",
+                "--tessdata-dir",
+                tessdata,
+                "-c",
+                "load_system_dawg=0",
+                "-c",
+                "preserve_interword_spaces=1",
+                "--user-words",
+                "mots.txt",
+                file.to_str().unwrap(),
+            ]
+        );
+
+        // Le hOCR et l'OSD passent par la même traduction
+        let hocr = invocation.hocr_args("page.png", "out", &options);
+        assert_eq!(hocr[..args.len()], args[..]);
+        assert_eq!(hocr[args.len()..], ["hocr"]);
+        assert_eq!(
+            invocation
+                .clone()
+                .osd()
+                .binary_args("page.png", "stdout", &[]),
+            [
+                "page.png",
+                "stdout",
+                "-l",
+                "osd",
+                "--psm",
+                "0",
+                "--oem",
+                "2",
+                "--dpi",
+                "450",
+                "--tessdata-dir",
+                tessdata,
+                "-c",
+                "load_system_dawg=0",
+                "-c",
+                "preserve_interword_spaces=1",
+                file.to_str().unwrap(),
+            ]
+        );
+        // La bibliothèque reçoit les variables du fichier avant les explicites
+        assert_eq!(
+            invocation.library_variables().unwrap()[..2],
+            [
+                ("user_defined_dpi".to_string(), "450".to_string()),
+                (
+                    "tessedit_char_whitelist".to_string(),
+                    "0123456789".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tesseract_invocation_library_variables_match_binary() {
+        let mut config = OcrConfig::default();
+        config
+            .tesseract_variables
+            .insert("user_defined_dpi".to_string(), "200".to_string());
+        let invocation = TesseractInvocation::new(&config, "fra", 300);
+
+        assert_eq!(
+            invocation.library_variables().unwrap(),
+            [
+                ("user_defined_dpi".to_string(), "300".to_string()),
+                ("user_defined_dpi".to_string(), "200".to_string()),
+            ]
+        );
+        // Même ordre côté binaire : la variable explicite, appliquée en dernier, l'emporte
+        let args = invocation.binary_args("page.png", "stdout", &[]);
+        assert_eq!(
+            args[args.len() - 4..],
+            ["--dpi", "300", "-c", "user_defined_dpi=200"]
+        );
+    }
+
+    #[test]
+    fn test_legacy_data_hint() {
+        assert!(legacy_data_hint(OcrEngineMode::LegacyOnly).contains("tessdata"));
+        assert!(legacy_data_hint(OcrEngineMode::LegacyPlusLstm).contains("legacy"));
+        assert!(legacy_data_hint(OcrEngineMode::LstmOnly).is_empty());
+        assert!(legacy_data_hint(OcrEngineMode::Default).is_empty());
+    }
+
+    /// Remplace `tesseract` par un script qui ne se termine pas à temps.
+    #[cfg(unix)]
+    fn slow_tesseract(dir: &Path) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = dir.join("tesseract-lent");
+        std::fs::write(&script, "#!/bin/sh\nexec sleep 5\n").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        TESSERACT_OVERRIDE.with(|program| *program.borrow_mut() = Some(script));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_orientation_timeout_kills_subprocess() {
+        let dir = tempfile::tempdir().unwrap();
+        slow_tesseract(dir.path());
+        let engine = OcrEngine::new(OcrConfig::default())
+            .unwrap()
+            .with_timeout(Some(Duration::from_millis(200)));
+
+        let start = Instant::now();
+        let error = engine
+            .detect_orientation_info(Path::new("resources/simple/img-1.png"))
+            .unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<OcrError>(),
+            Some(&OcrError::Timeout(Duration::from_millis(200)))
+        );
+        assert!(start.elapsed() < Duration::from_secs(3));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_auto_rotate_propagates_osd_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        slow_tesseract(dir.path());
+        let engine = OcrEngine::new(OcrConfig::default())
+            .unwrap()
+            .with_timeout(Some(Duration::from_millis(200)));
+
+        let error = engine
+            .extract_text_auto_rotated(Path::new("resources/simple/img-1.png"))
+            .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<OcrError>(),
+            Some(OcrError::Timeout(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_with_deadline() {
+        let engine = OcrEngine::new(OcrConfig::default()).unwrap();
+
+        let fast = engine.run_with_deadline(Duration::from_secs(5), |_| Ok(42));
+        assert_eq!(fast.unwrap(), 42);
+
+        let slow = engine.run_with_deadline(Duration::from_millis(50), |_| {
+            std::thread::sleep(Duration::from_secs(2));
+            Ok(())
+        });
+        assert_eq!(
+            slow.unwrap_err().downcast_ref::<OcrError>(),
+            Some(&OcrError::Timeout(Duration::from_millis(50)))
+        );
+    }
+
+    /// Écrit une page (blanche ou avec un rectangle sombre) et retourne son chemin.
+    fn write_page(dir: &Path, with_content: bool) -> PathBuf {
+        let mut page = image::GrayImage::from_pixel(120, 160, image::Luma([255]));
+        if with_content {
+            for (x, y) in (20..100).flat_map(|x| (40..60).map(move |y| (x, y))) {
+                page.put_pixel(x, y, image::Luma([0]));
+            }
+        }
+        let path = dir.join(if with_content {
+            "texte.png"
+        } else {
+            "blanche.png"
+        });
+        page.save(&path).unwrap();
+        path
+    }
+
+    fn blank_skipping_engine(skip_blank_pages: bool) -> OcrEngine {
+        let preprocessing = PreprocessingConfig {
+            skip_blank_pages,
+            ..PreprocessingConfig::default()
+        };
+        OcrEngine::with_preprocessing(OcrConfig::default(), preprocessing).unwrap()
+    }
+
+    #[test]
+    fn test_blank_page_skips_tesseract() {
+        let dir = tempfile::tempdir().unwrap();
+        let blank = write_page(dir.path(), false);
+        RECOGNIZE_CALLS.with(|calls| calls.set(0));
+
+        let outcome = blank_skipping_engine(true)
+            .extract_text_detailed(&blank)
+            .unwrap();
+
+        assert_eq!(outcome.text, "");
+        assert!(outcome.is_blank_page());
+        assert_eq!(RECOGNIZE_CALLS.with(|calls| calls.get()), 0);
+        assert_eq!(
+            blank_skipping_engine(true)
+                .extract_text_from_file(&blank)
+                .unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_blank_page_detection_requires_option() {
+        let dir = tempfile::tempdir().unwrap();
+        let blank = write_page(dir.path(), false);
+        let content = write_page(dir.path(), true);
+        RECOGNIZE_CALLS.with(|calls| calls.set(0));
+
+        // Le résultat dépend de l'installation de Tesseract : seul l'appel compte
+        let _ = blank_skipping_engine(false).extract_text_detailed(&blank);
+        assert_eq!(RECOGNIZE_CALLS.with(|calls| calls.get()), 1);
+
+        let _ = blank_skipping_engine(true).extract_text_detailed(&content);
+        assert_eq!(RECOGNIZE_CALLS.with(|calls| calls.get()), 2);
+    }
+
+    #[test]
+    fn test_result_cache_reuses_unchanged_image() {
+        use crate::config::PageSegMode;
+        use crate::result_cache::DirectoryCache;
+
+        let dir = tempfile::tempdir().unwrap();
+        fake_tesseract(
+            dir.path(),
+            "if [ \"$2\" = stdout ]; then exit 0; fi\n\
+             echo \"$@\" > \"$2.txt\"\n",
+        );
+        let page = write_page(dir.path(), true);
+        let cache_dir = dir.path().join("cache");
+        let cache: Arc<dyn ResultCache> = Arc::new(DirectoryCache::new(&cache_dir));
+        let config = OcrConfig::default().with_vocabulary(&["Facture"]).unwrap();
+        let engine = OcrEngine::new(config.clone())
+            .unwrap()
+            .with_result_cache(Arc::clone(&cache));
+        RECOGNIZE_CALLS.with(|calls| calls.set(0));
+
+        let first = engine.extract_text_detailed(&page).unwrap();
+        let second = engine.extract_text_detailed(&page).unwrap();
+
+        assert!(!first.cache_hit);
+        assert!(second.cache_hit);
+        assert_eq!(second.text, first.text);
+        assert_eq!(RECOGNIZE_CALLS.with(|calls| calls.get()), 1);
+
+        // Autre PSM : autre clé
+        let single_line = OcrEngine::new(OcrConfig {
+            page_seg_mode: PageSegMode::SingleLine,
+            ..config
+        })
+        .unwrap()
+        .with_result_cache(Arc::clone(&cache));
+        let outcome = single_line.extract_text_detailed(&page).unwrap();
+        assert!(!outcome.cache_hit);
+        assert!(outcome.text.contains("--psm 7"), "{}", outcome.text);
+        assert_eq!(RECOGNIZE_CALLS.with(|calls| calls.get()), 2);
+
+        // Fichiers de cache corrompus : l'image est relue au lieu d'échouer
+        for entry in std::fs::read_dir(&cache_dir).unwrap() {
+            std::fs::write(entry.unwrap().path(), "{\"text\": tronqu").unwrap();
+        }
+        let reread = engine.extract_text_detailed(&page).unwrap();
+        assert!(!reread.cache_hit);
+        assert!(reread.text.contains("--psm 3"), "{}", reread.text);
+        assert_eq!(RECOGNIZE_CALLS.with(|calls| calls.get()), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hocr_document_maps_back_to_source_coordinates() {
+        let dir = tempfile::tempdir().unwrap();
+        fake_tesseract(
+            dir.path(),
+            "printf '%s\\n' \
+             \"<p class='ocr_par' title='bbox 10 20 50 30'>\" \
+             \"<span class='ocr_line' title='bbox 10 20 50 30'>\" \
+             \"<span class='ocrx_word' title='bbox 10 20 40 30; x_wconf 90'>Bonjour</span>\" \
+             > \"$2.hocr\"\n",
+        );
+        // 120 × 160 pixels : réduite de moitié au-delà de 4 800 pixels
+        let page = write_page(dir.path(), true);
+        let downscaling = OcrEngine::with_preprocessing(
+            OcrConfig::default(),
+            PreprocessingConfig {
+                max_pixels: 4_800,
+                ..PreprocessingConfig::default()
+            },
+        )
+        .unwrap();
+
+        let doc = downscaling.extract_hocr_document(&page).unwrap();
+        assert_eq!(doc.scale_factor, 0.5);
+        let word = &doc.paragraphs().next().unwrap().lines[0].words[0];
+        assert_eq!(word.bbox, BBox::new(20, 40, 60, 20));
+        assert_eq!(doc.processed_bbox(&word.bbox), BBox::new(10, 20, 30, 10));
+
+        // Sans redimensionnement, les coordonnées de Tesseract sont conservées
+        let plain = OcrEngine::new(OcrConfig::default()).unwrap();
+        let doc = plain.extract_hocr_document(&page).unwrap();
+        assert_eq!(doc.scale_factor, 1.0);
+        let word = &doc.paragraphs().next().unwrap().lines[0].words[0];
+        assert_eq!(word.bbox, BBox::new(10, 20, 30, 10));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_with_alternatives_requests_lstm_choices() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = dir.path().join("args.log");
+        fake_tesseract(
+            dir.path(),
+            &format!(
+                "echo \"$@\" > '{}'\n\
+                 printf '%s\\n' \
+                 \"<span class='ocr_line' title='bbox 0 0 100 20'>\" \
+                 \"<span class='ocrx_word' title='bbox 0 0 40 20; x_wconf 80'>Tota1\" \
+                 \"<span class='ocrx_cinfo'><span class='ocr_glyph' title='x_confs 99'>T</span></span>\" \
+                 \"<span class='ocrx_cinfo'><span class='ocr_glyph' title='x_confs 99'>o</span></span>\" \
+                 \"<span class='ocrx_cinfo'><span class='ocr_glyph' title='x_confs 99'>t</span></span>\" \
+                 \"<span class='ocrx_cinfo'><span class='ocr_glyph' title='x_confs 99'>a</span></span>\" \
+                 \"<span class='ocrx_cinfo'><span class='ocr_glyph' title='x_confs 55'>1</span><span class='ocr_glyph' title='x_confs 44'>l</span></span>\" \
+                 \"</span>\" \"</span>\" > \"$2.hocr\"\n",
+                args.display()
+            ),
+        );
+        let page = write_page(dir.path(), true);
+        let engine = OcrEngine::new(OcrConfig::default()).unwrap();
+
+        let words = engine.extract_with_alternatives(&page, 2).unwrap();
+
+        let args = std::fs::read_to_string(&args).unwrap();
+        assert!(args.contains("-c lstm_choice_mode=2"), "{}", args);
+        assert!(args.trim_end().ends_with("hocr"), "{}", args);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].text, "Tota1");
+        assert_eq!(words[0].alternatives[0].text, "Total");
+        let dictionary = crate::postprocess::Corrector::from_words(["Total"]);
+        assert_eq!(
+            crate::hocr::rescore_with(&words, |word| dictionary.plausibility(word)),
+            "Total"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_result_cache_reuses_hocr_document() {
+        use crate::result_cache::DirectoryCache;
+
+        let dir = tempfile::tempdir().unwrap();
+        let calls = dir.path().join("calls.log");
+        fake_tesseract(
+            dir.path(),
+            &format!(
+                "echo hocr >> '{}'\n\
+                 printf '%s\\n' \
+                 \"<p class='ocr_par' title='bbox 0 0 100 20'>\" \
+                 \"<span class='ocr_line' title='bbox 0 0 100 20'>\" \
+                 \"<span class='ocrx_word' title='bbox 0 0 40 20; x_wconf 90'>Bonjour</span>\" \
+                 > \"$2.hocr\"\n",
+                calls.display()
+            ),
+        );
+        let page = write_page(dir.path(), true);
+        let cache_dir = dir.path().join("cache");
+        let cache: Arc<dyn ResultCache> = Arc::new(DirectoryCache::new(&cache_dir));
+        let engine = OcrEngine::new(OcrConfig::default())
+            .unwrap()
+            .with_result_cache(cache);
+
+        let first = engine.extract_hocr_document(&page).unwrap();
+        let second = engine.extract_hocr_document(&page).unwrap();
+
+        assert_eq!(first.full_text(), "Bonjour");
+        assert_eq!(second, first);
+        assert_eq!(std::fs::read_to_string(&calls).unwrap(), "hocr\n");
+        let entry = std::fs::read_dir(&cache_dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let json = std::fs::read_to_string(entry.path()).unwrap();
+        assert!(json.contains("\"format_version\""), "{}", json);
+    }
+
+    #[test]
+    fn test_config_names_passed_to_binary_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        fake_tesseract(
+            dir.path(),
+            "if [ \"$2\" = stdout ]; then exit 0; fi\n\
+             echo \"$@\" > \"$2.txt\"\n",
+        );
+        let page = write_page(dir.path(), true);
+        let config = OcrConfig {
+            config_names: vec!["digits".to_string(), "./factures.cfg".to_string()],
+            ..OcrConfig::default().with_vocabulary(&["TVA"]).unwrap()
+        };
+        let engine = OcrEngine::new(config).unwrap();
+
+        let outcome = engine.extract_text_detailed(&page).unwrap();
+
+        assert!(
+            outcome
+                .text
+                .trim_end()
+                .ends_with("digits ./factures.cfg txt"),
+            "{}",
+            outcome.text
+        );
+    }
+
+    #[test]
+    fn test_cancelled_engine_stops_before_extraction() {
+        let token = CancellationToken::new();
+        let engine = OcrEngine::new(OcrConfig::default())
+            .unwrap()
+            .with_cancellation(token.clone());
+        token.cancel();
+
+        let error = engine
+            .extract_text_detailed(Path::new("resources/simple/img-1.png"))
+            .unwrap_err();
+
+        assert_eq!(error.downcast_ref::<OcrError>(), Some(&OcrError::Cancelled));
+    }
+
+    /// Remplace `tesseract` par un script shell (OSD et hOCR factices).
+    #[cfg(unix)]
+    fn fake_tesseract(dir: &Path, body: &str) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = dir.join("tesseract-factice");
+        std::fs::write(&script, format!("#!/bin/sh\n{}", body)).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        TESSERACT_OVERRIDE.with(|program| *program.borrow_mut() = Some(script));
+    }
+
+    /// Fichiers de l'unique sous-répertoire d'artefacts de `root`, triés.
+    fn artifact_files(root: &Path) -> (PathBuf, Vec<String>) {
+        let subdirs: Vec<PathBuf> = std::fs::read_dir(root)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(subdirs.len(), 1, "{:?}", subdirs);
+        let mut files: Vec<String> = std::fs::read_dir(&subdirs[0])
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        files.sort();
+        (subdirs[0].clone(), files)
+    }
+
+    #[test]
+    fn test_debug_artifacts_for_resource_image() {
+        let artifacts = tempfile::tempdir().unwrap();
+        let engine = OcrEngine::new(OcrConfig::default())
+            .unwrap()
+            .with_debug_artifacts(artifacts.path());
+        assert_eq!(engine.debug_artifacts_dir(), Some(artifacts.path()));
+
+        let result = engine.extract_text_detailed(Path::new("resources/simple/img-1.png"));
+
+        let (dir, files) = artifact_files(artifacts.path());
+        assert!(
+            dir.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("img-1-")
+        );
+        // L'image et la description sont conservées même si l'OCR échoue
+        let expected: &[&str] = match result {
+            Ok(_) => &["artifacts.json", "image.png", "output.txt", "tesseract.log"],
+            Err(_) => &["artifacts.json", "image.png"],
+        };
+        assert_eq!(files, expected);
+        assert_eq!(
+            std::fs::read(dir.join("image.png")).unwrap(),
+            std::fs::read("resources/simple/img-1.png").unwrap()
+        );
+        let sidecar: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(dir.join("artifacts.json")).unwrap()).unwrap();
+        assert_eq!(sidecar["operation"], "ocr");
+        assert_eq!(sidecar["effective_config"]["language"], "fra");
+    }
+
+    #[test]
+    fn test_debug_artifacts_for_preprocessed_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let page = write_page(dir.path(), true);
+        let artifacts = dir.path().join("artefacts");
+        let engine =
+            OcrEngine::with_preprocessing(OcrConfig::default(), PreprocessingConfig::default())
+                .unwrap()
+                .with_debug_artifacts(&artifacts);
+
+        let _ = engine.extract_text_detailed(&page);
+
+        // L'image copiée est celle produite par le prétraitement (niveaux de gris)
+        let (subdir, files) = artifact_files(&artifacts);
+        assert!(files.contains(&"image.png".to_string()));
+        let copied = image::open(subdir.join("image.png")).unwrap();
+        assert_eq!(copied.color(), image::ColorType::L8);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_debug_artifacts_for_subprocess_calls() {
+        let dir = tempfile::tempdir().unwrap();
+        fake_tesseract(
+            dir.path(),
+            "if [ \"$2\" = stdout ]; then echo 'Rotate: 0'; else printf '%s\\n' \
+             \"<p class='ocr_par' title='bbox 0 0 100 20'>\" \
+             \"<span class='ocr_line' title='bbox 0 0 100 20'>\" \
+             \"<span class='ocrx_word' title='bbox 0 0 40 20; x_wconf 90'>Bonjour</span>\" \
+             > \"$2.hocr\"; fi\n\
+             echo 'Estimating resolution as 142' >&2\n",
+        );
+        let page = write_page(dir.path(), true);
+
+        let osd_dir = dir.path().join("osd");
+        let engine = OcrEngine::new(OcrConfig::default())
+            .unwrap()
+            .with_debug_artifacts(&osd_dir);
+        let outcome = engine.detect_orientation_detailed(&page).unwrap();
+        assert_eq!(outcome.text, "Rotate: 0");
+        let (subdir, files) = artifact_files(&osd_dir);
+        assert_eq!(
+            files,
+            [
+                "artifacts.json",
+                "image.png",
+                "output.txt",
+                "tesseract.stderr.txt"
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(subdir.join("tesseract.stderr.txt")).unwrap(),
+            "Estimating resolution as 142\n"
+        );
+
+        let hocr_dir = dir.path().join("hocr");
+        let engine = engine.with_debug_artifacts(&hocr_dir);
+        engine.extract_hocr_document(&page).unwrap();
+        let (subdir, files) = artifact_files(&hocr_dir);
+        assert_eq!(
+            files,
+            [
+                "artifacts.json",
+                "image.png",
+                "output.json",
+                "tesseract.stderr.txt"
+            ]
+        );
+        let document = HocrDocument::load_json(&subdir.join("output.json")).unwrap();
+        assert_eq!(document.full_text(), "Bonjour");
+        let sidecar: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(subdir.join("artifacts.json")).unwrap()).unwrap();
+        assert_eq!(sidecar["source"], "texte");
+        assert_eq!(sidecar["operation"], "hocr");
+        assert_eq!(sidecar["passes"].as_array().unwrap().len(), 1);
+        assert!(sidecar["error"].is_null());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_path_reaches_binary_and_library() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        // Le script ne répond que si le chemin reçu désigne bien l'image
+        fake_tesseract(dir.path(), "[ -f \"$1\" ] && echo 'Rotate: 0'\n");
+        let page = dir.path().join(OsStr::from_bytes(b"caf\xe9.png"));
+        std::fs::rename(write_page(dir.path(), true), &page).unwrap();
+
+        let outcome = OcrEngine::new(OcrConfig::default())
+            .unwrap()
+            .detect_orientation_detailed(&page)
+            .unwrap();
+        assert_eq!(outcome.text, "Rotate: 0");
+
+        let input = LibraryInput::new(&page).unwrap();
+        assert!(input.path.is_ascii());
+        assert!(input.path.ends_with("image.png"));
+        assert_eq!(
+            std::fs::read(&input.path).unwrap(),
+            std::fs::read(&page).unwrap()
+        );
+        assert_eq!(
+            input.warning.map(|w| w.kind()),
+            Some(WarningKind::PathIssues)
+        );
+
+        // Un chemin UTF-8 est transmis tel quel, sans copie ni avertissement
+        let utf8 = dir.path().join("café.png");
+        let input = LibraryInput::new(&utf8).unwrap();
+        assert_eq!(Path::new(&input.path), utf8);
+        assert!(input.warning.is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_debug_artifacts_unset_writes_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        fake_tesseract(dir.path(), "echo 'Rotate: 0'\n");
+        let page = write_page(dir.path(), true);
+        let engine = OcrEngine::new(OcrConfig::default()).unwrap();
+        assert_eq!(engine.debug_artifacts_dir(), None);
+
+        engine.detect_orientation_detailed(&page).unwrap();
+        let _ = engine.extract_text_detailed(&page);
+
+        let mut entries: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, ["tesseract-factice", "texte.png"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_debug_artifacts_failure_does_not_fail_extraction() {
+        let dir = tempfile::tempdir().unwrap();
+        fake_tesseract(dir.path(), "echo 'Rotate: 0'\n");
+        let page = write_page(dir.path(), true);
+        // Un fichier occupe la place du répertoire des artefacts
+        let blocked = dir.path().join("bloque");
+        std::fs::write(&blocked, b"").unwrap();
+
+        let outcome = OcrEngine::new(OcrConfig::default())
+            .unwrap()
+            .with_debug_artifacts(&blocked)
+            .detect_orientation_detailed(&page)
+            .unwrap();
+
+        assert_eq!(outcome.text, "Rotate: 0");
+        assert!(
+            outcome
+                .warnings
+                .iter()
+                .any(|warning| warning.message.contains("Artefacts de débogage"))
+        );
+    }
+
+    #[test]
+    fn test_missing_user_vocabulary_fails_engine_creation() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("absent.txt");
+        let config = OcrConfig {
+            user_words: Some(missing.clone()),
+            ..OcrConfig::default()
+        };
+
+        let error = OcrEngine::new(config.clone()).unwrap_err().to_string();
+        assert!(error.contains("user_words"), "{}", error);
+        assert!(error.contains(&missing.display().to_string()), "{}", error);
+        assert!(OcrEngine::with_preprocessing(config, PreprocessingConfig::default()).is_err());
+
+        // Un répertoire n'est pas un fichier de motifs lisible
+        let config = OcrConfig {
+            user_patterns: Some(dir.path().to_path_buf()),
+            ..OcrConfig::default()
+        };
+        let error = OcrEngine::new(config).unwrap_err().to_string();
+        assert!(error.contains("user_patterns"), "{}", error);
+    }
+
+    #[test]
+    fn test_engine_keeps_vocabulary_file_alive() {
+        let config = OcrConfig::default()
+            .with_vocabulary(&["Kubernetes"])
+            .unwrap();
+        let path = config.user_words.clone().unwrap();
+
+        let engine = OcrEngine::new(config).unwrap();
+        assert!(path.exists());
+        assert_eq!(engine.config().user_words.as_deref(), Some(path.as_path()));
+        assert_eq!(engine.effective_config().user_words, Some(path.clone()));
+
+        drop(engine);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_split_double_pages_reads_left_then_right() {
+        let dir = tempfile::tempdir().unwrap();
+        let counter = dir.path().join("appels.txt");
+        fake_tesseract(
+            dir.path(),
+            &format!(
+                "if [ \"$2\" = stdout ]; then exit 0; fi\n\
+                 n=$(cat '{0}' 2>/dev/null || echo 0); n=$((n + 1)); echo $n > '{0}'\n\
+                 echo \"page $n\" > \"$2.txt\"\n",
+                counter.display()
+            ),
+        );
+        // Deux pavés sombres séparés par une gouttière blanche de 60 px
+        let image = image::GrayImage::from_fn(400, 200, |x, y| {
+            let ink = (20..170).contains(&x) || (230..380).contains(&x);
+            image::Luma([if ink && (y / 10) % 2 == 0 { 0 } else { 255 }])
+        });
+        let page = dir.path().join("double.png");
+        image.save(&page).unwrap();
+        let config = OcrConfig::default().with_vocabulary(&["Sommaire"]).unwrap();
+        let prep = PreprocessingConfig {
+            split_double_pages: true,
+            ..PreprocessingConfig::default()
+        };
+        let engine = OcrEngine::with_preprocessing(config, prep).unwrap();
+
+        let outcome = engine.extract_text_detailed(&page).unwrap();
+
+        assert_eq!(outcome.page_count(), 2);
+        assert!(
+            outcome
+                .warnings
+                .iter()
+                .any(|warning| warning.to_string().contains("x = 200")),
+            "{:?}",
+            outcome.warnings
+        );
+        let pages: Vec<&str> = outcome.text.split(PAGE_SEPARATOR).collect();
+        assert_eq!(pages.len(), 2, "{:?}", outcome.text);
+        assert!(pages[0].starts_with("page 1") && pages[1].starts_with("page 2"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_auto_vertical_switches_to_vertical_psm() {
+        let dir = tempfile::tempdir().unwrap();
+        fake_tesseract(
+            dir.path(),
+            "if [ \"$2\" = stdout ]; then exit 0; fi\n\
+             echo \"$@\" > \"$2.txt\"\n",
+        );
+        // Cinq colonnes de « caractères » sombres, décalés d'une colonne à l'autre
+        let vertical = image::GrayImage::from_fn(300, 400, |x, y| {
+            let column = x / 60;
+            let in_column = x % 60 >= 15 && x % 60 < 45;
+            let in_char = (y + column * 7) % 24 < 20;
+            let ink = (20..380).contains(&y) && in_column && in_char;
+            image::Luma([if ink { 0 } else { 255 }])
+        });
+        let page = dir.path().join("vertical.png");
+        vertical.save(&page).unwrap();
+        let config = OcrConfig {
+            auto_vertical: true,
+            ..OcrConfig::default().with_vocabulary(&["縦書き"]).unwrap()
+        };
+        let engine = OcrEngine::new(config).unwrap();
+
+        let outcome = engine.extract_text_detailed(&page).unwrap();
+
+        assert!(outcome.text.contains("--psm 5"), "{}", outcome.text);
+        assert_eq!(outcome.warnings[0].kind(), WarningKind::VerticalText);
+
+        // Une page horizontale garde le mode configuré
+        let horizontal = image::imageops::rotate90(&vertical);
+        horizontal.save(&page).unwrap();
+        let outcome = engine.extract_text_detailed(&page).unwrap();
+
+        assert!(outcome.text.contains("--psm 3"), "{}", outcome.text);
+        assert!(
+            outcome
+                .warnings
+                .iter()
+                .all(|warning| warning.kind() != WarningKind::VerticalText)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_user_vocabulary_passed_to_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let args_file = dir.path().join("args.txt");
+        fake_tesseract(
+            dir.path(),
+            &format!(
+                "echo \"$@\" > '{}'\n\
+                 if [ \"$2\" = stdout ]; then exit 0; fi\n\
+                 echo 'Kubernetes 12-345' > \"$2.txt\"\n\
+                 echo '<div/>' > \"$2.hocr\"\n",
+                args_file.display()
+            ),
+        );
+        let page = write_page(dir.path(), true);
+        let patterns = dir.path().join("motifs.txt");
+        std::fs::write(&patterns, "\\d\\d-\\d\\d\\d\n").unwrap();
+        let mut config = OcrConfig {
+            user_patterns: Some(patterns.clone()),
+            ..OcrConfig::default()
+        }
+        .with_vocabulary(&["Kubernetes"])
+        .unwrap();
+        config
+            .tesseract_variables
+            .insert("load_system_dawg".to_string(), "0".to_string());
+        let words = config.user_words.clone().unwrap();
+        let engine = OcrEngine::new(config).unwrap();
+
+        let text = engine.extract_text_from_file(&page).unwrap();
+        assert_eq!(text, "Kubernetes 12-345");
+        let args = std::fs::read_to_string(&args_file).unwrap();
+        assert!(
+            args.trim_end().ends_with(&format!(
+                "--dpi 300 -c load_system_dawg=0 --user-words {} --user-patterns {} txt",
+                words.display(),
+                patterns.display()
+            )),
+            "{}",
+            args
+        );
+
+        // Le hOCR reçoit le même vocabulaire, avant la configuration hocr
+        engine.extract_hocr_document(&page).unwrap();
+        let args = std::fs::read_to_string(&args_file).unwrap();
+        assert!(
+            args.trim_end().ends_with(&format!(
+                "--user-words {} --user-patterns {} hocr",
+                words.display(),
+                patterns.display()
+            )),
+            "{}",
+            args
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_deterministic_limits_binary_threads() {
+        let dir = tempfile::tempdir().unwrap();
+        fake_tesseract(
+            dir.path(),
+            "echo \"OMP_THREAD_LIMIT=${OMP_THREAD_LIMIT:-absent}\"\n",
+        );
+        let page = write_page(dir.path(), true);
+
+        let engine = OcrEngine::new(OcrConfig {
+            deterministic: true,
+            auto_vertical: false,
+            ..OcrConfig::default()
+        })
+        .unwrap();
+        let outcome = engine.detect_orientation_detailed(&page).unwrap();
+        assert_eq!(outcome.text, "OMP_THREAD_LIMIT=1");
 
-        Ok(text)
+        let args = vec![page.clone().into_os_string(), "stdout".into()];
+        let output = run_tesseract(&args, None, None).unwrap();
+        let inherited = std::env::var("OMP_THREAD_LIMIT").unwrap_or("absent".to_string());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout).trim(),
+            format!("OMP_THREAD_LIMIT={}", inherited)
+        );
     }
 }
@@ -0,0 +1,1087 @@
+//! Noms des fichiers de sortie du mode batch.
+//!
+//! Deux images `scans/a/page1.png` et `scans/b/page1.png` produiraient le
+//! même `page1.txt` dans un répertoire de sortie à plat. Ce module :
+//!
+//! - reproduit l'arborescence relative des images sous le répertoire de
+//!   sortie ([`map_output_path()`]) : `results/a/page1.txt` et
+//!   `results/b/page1.txt` ;
+//! - départage les noms restés en double (sortie à plat, même nom avec deux
+//!   extensions différentes) par un suffixe numérique
+//!   ([`plan_output_paths()`]) : `page1.txt`, `page1-2.txt`. Aucune sortie
+//!   d'un lot n'en écrase une autre ;
+//! - calcule les noms imposés par un système aval à partir d'un gabarit
+//!   ([`OutputTemplate`], ex: `{date}/{stem}_{lang}_ocr.txt`), sans
+//!   jamais sortir du répertoire de sortie ([`plan_templated_output_paths()`]) ;
+//! - remplace dans les noms les caractères refusés par Windows (`:`, `?`,
+//!   `*`...) et suffixe les noms réservés (`CON`, `NUL`...), pour qu'un lot
+//!   produit sous Linux reste copiable sous Windows ([`sanitize_file_stem()`]).
+//!   Les noms qui ne sont pas en UTF-8 sont conservés, octet pour octet,
+//!   hormis ces caractères.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::output_path::{OutputPathOptions, plan_output_paths};
+//! use std::path::{Path, PathBuf};
+//!
+//! let inputs = [PathBuf::from("scans/a/page1.png"), PathBuf::from("scans/b/page1.png")];
+//! let options = OutputPathOptions { flatten: true, ..OutputPathOptions::default() };
+//! let mappings = plan_output_paths(&inputs, Some(Path::new("scans")), Path::new("results"), &options);
+//!
+//! assert_eq!(mappings[0].output, Path::new("results/page1.txt"));
+//! assert_eq!(mappings[1].output, Path::new("results/page1-2.txt"));
+//! assert!(mappings[1].renamed);
+//! ```
+
+use crate::config::PageSegMode;
+use anyhow::{Result, bail};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+/// Options de nommage des fichiers de sortie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputPathOptions {
+    /// Extension des fichiers de sortie, sans point (ex: "txt", "png").
+    pub extension: String,
+
+    /// Écrire toutes les sorties directement dans le répertoire de sortie,
+    /// sans reproduire l'arborescence des images.
+    pub flatten: bool,
+}
+
+impl Default for OutputPathOptions {
+    /// Sorties `.txt` reproduisant l'arborescence des images.
+    fn default() -> Self {
+        Self {
+            extension: "txt".to_string(),
+            flatten: false,
+        }
+    }
+}
+
+/// Correspondance entre une image du lot et son fichier de sortie.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutputMapping {
+    /// Image d'entrée.
+    pub input: PathBuf,
+    /// Fichier de sortie.
+    pub output: PathBuf,
+    /// Le nom a reçu un suffixe numérique pour éviter une collision.
+    pub renamed: bool,
+}
+
+/// Calcule le fichier de sortie d'une image, sans tenir compte des collisions.
+///
+/// Si `input_root` est fourni et contient `input`, le chemin relatif de
+/// l'image est reproduit sous `output_dir` (sauf avec
+/// [`OutputPathOptions::flatten`]) ; sinon la sortie est placée directement
+/// dans `output_dir`. Le nom de fichier conserve le nom de l'image sans son
+/// extension (`rapport.v2.png` donne `rapport.v2.txt`), y compris s'il n'est
+/// pas en UTF-8.
+///
+/// Les séparateurs `\` des chemins Windows sont traités comme des `/`.
+///
+/// # Arguments
+///
+/// * `input` - Image d'entrée
+/// * `input_root` - Répertoire de départ de la collecte (répertoire ou
+///   préfixe fixe d'un pattern glob)
+/// * `output_dir` - Répertoire de sortie
+/// * `options` - Options de nommage
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::output_path::{OutputPathOptions, map_output_path};
+/// use std::path::Path;
+///
+/// let output = map_output_path(
+///     Path::new("scans/2024/mars/page1.png"),
+///     Some(Path::new("scans")),
+///     Path::new("results"),
+///     &OutputPathOptions::default(),
+/// );
+/// assert_eq!(output, Path::new("results/2024/mars/page1.txt"));
+/// ```
+pub fn map_output_path(
+    input: &Path,
+    input_root: Option<&Path>,
+    output_dir: &Path,
+    options: &OutputPathOptions,
+) -> PathBuf {
+    let input = normalize_separators(input);
+    let mut file_name = input
+        .file_stem()
+        .map(sanitize_os_stem)
+        .unwrap_or_else(|| OsString::from("sortie"));
+    file_name.push(".");
+    file_name.push(&options.extension);
+
+    let relative_dir = input_root
+        .filter(|_| !options.flatten)
+        .and_then(|root| {
+            input
+                .strip_prefix(normalize_separators(root))
+                .ok()
+                .and_then(Path::parent)
+                .map(Path::to_path_buf)
+        })
+        .unwrap_or_default();
+
+    output_dir.join(relative_dir).join(file_name)
+}
+
+/// Caractères refusés dans un nom de fichier Windows, en plus des caractères de contrôle.
+const FORBIDDEN_FILE_NAME_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Noms de périphériques réservés par Windows, quelle que soit l'extension.
+const RESERVED_FILE_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Rend un nom de fichier (sans extension) valide sur tous les systèmes.
+///
+/// Les caractères refusés par Windows (`< > : " / \ | ? *`) et les
+/// caractères de contrôle sont remplacés par `_` ; un nom réservé (`CON`,
+/// `NUL`, `COM1`...) reçoit un `_` final. Un nom vide devient `sortie`.
+/// Deux noms rendus identiques sont ensuite départagés par
+/// [`plan_output_paths()`].
+///
+/// # Arguments
+///
+/// * `stem` - Nom de fichier sans extension
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::output_path::sanitize_file_stem;
+///
+/// assert_eq!(sanitize_file_stem("facture: mars?"), "facture_ mars_");
+/// assert_eq!(sanitize_file_stem("con"), "con_");
+/// assert_eq!(sanitize_file_stem("page1"), "page1");
+/// ```
+pub fn sanitize_file_stem(stem: &str) -> String {
+    if stem.is_empty() {
+        return "sortie".to_string();
+    }
+    let mut sanitized: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_control() || FORBIDDEN_FILE_NAME_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    if RESERVED_FILE_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(&sanitized))
+    {
+        sanitized.push('_');
+    }
+    sanitized
+}
+
+/// Variante de [`sanitize_file_stem()`] pour un nom qui peut ne pas être en UTF-8.
+///
+/// Sous Unix, les octets non UTF-8 sont conservés : seuls les caractères
+/// ASCII refusés sont remplacés.
+fn sanitize_os_stem(stem: &OsStr) -> OsString {
+    if let Some(stem) = stem.to_str() {
+        return OsString::from(sanitize_file_stem(stem));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+        let bytes = stem
+            .as_bytes()
+            .iter()
+            .map(|&byte| {
+                let forbidden = byte.is_ascii_control()
+                    || FORBIDDEN_FILE_NAME_CHARS.contains(&char::from(byte));
+                if forbidden { b'_' } else { byte }
+            })
+            .collect();
+        OsString::from_vec(bytes)
+    }
+    #[cfg(not(unix))]
+    {
+        OsString::from(sanitize_file_stem(&stem.to_string_lossy()))
+    }
+}
+
+/// Calcule les fichiers de sortie de toutes les images d'un lot.
+///
+/// Chaque chemin est d'abord calculé par [`map_output_path()`]. Lorsqu'il est
+/// déjà attribué à une image précédente, un suffixe `-2`, `-3`... est ajouté
+/// au nom de fichier, jusqu'à obtenir un nom libre. Les noms sont comparés
+/// sans tenir compte de la casse, pour rester distincts sur les systèmes de
+/// fichiers insensibles à la casse.
+///
+/// # Arguments
+///
+/// * `inputs` - Images du lot, dans l'ordre de traitement
+/// * `input_root` - Voir [`map_output_path()`]
+/// * `output_dir` - Répertoire de sortie
+/// * `options` - Options de nommage
+///
+/// # Retour
+///
+/// Une [`OutputMapping`] par image, dans l'ordre de `inputs`.
+pub fn plan_output_paths(
+    inputs: &[PathBuf],
+    input_root: Option<&Path>,
+    output_dir: &Path,
+    options: &OutputPathOptions,
+) -> Vec<OutputMapping> {
+    let mut used = HashSet::new();
+
+    inputs
+        .iter()
+        .map(|input| {
+            let natural = map_output_path(input, input_root, output_dir, options);
+            unique_mapping(&mut used, input, natural)
+        })
+        .collect()
+}
+
+/// Attribue à `input` le chemin `natural`, suffixé s'il est déjà dans `used`.
+fn unique_mapping(used: &mut HashSet<String>, input: &Path, natural: PathBuf) -> OutputMapping {
+    let mut output = natural.clone();
+    let mut suffix = 2;
+    while !used.insert(collision_key(&output)) {
+        output = with_suffix(&natural, suffix);
+        suffix += 1;
+    }
+
+    OutputMapping {
+        input: input.to_path_buf(),
+        renamed: output != natural,
+        output,
+    }
+}
+
+/// Variable d'un [`OutputTemplate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placeholder {
+    Stem,
+    Ext,
+    Parent,
+    Lang,
+    Psm,
+    Date,
+    Index,
+    Hash8,
+}
+
+impl Placeholder {
+    /// Variables reconnues, dans l'ordre de la documentation.
+    const ALL: [Placeholder; 8] = [
+        Placeholder::Stem,
+        Placeholder::Ext,
+        Placeholder::Parent,
+        Placeholder::Lang,
+        Placeholder::Psm,
+        Placeholder::Date,
+        Placeholder::Index,
+        Placeholder::Hash8,
+    ];
+
+    /// Nom de la variable, entre accolades dans le gabarit.
+    fn name(self) -> &'static str {
+        match self {
+            Placeholder::Stem => "stem",
+            Placeholder::Ext => "ext",
+            Placeholder::Parent => "parent",
+            Placeholder::Lang => "lang",
+            Placeholder::Psm => "psm",
+            Placeholder::Date => "date",
+            Placeholder::Index => "index",
+            Placeholder::Hash8 => "hash8",
+        }
+    }
+}
+
+/// Morceau d'un [`OutputTemplate`] : texte fixe ou variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplatePart {
+    Literal(String),
+    Placeholder(Placeholder),
+}
+
+/// Gabarit de chemin des fichiers de sortie du mode batch (option `--output-template`).
+///
+/// Le gabarit est un chemin relatif au répertoire de sortie, où les
+/// variables suivantes sont remplacées pour chaque image (voir
+/// [`TemplateValues`]) :
+///
+/// | Variable   | Valeur                                                        |
+/// |------------|---------------------------------------------------------------|
+/// | `{stem}`   | nom de l'image sans extension (`page1`)                       |
+/// | `{ext}`    | extension de l'image, sans point (`png`)                      |
+/// | `{parent}` | répertoire de l'image relatif à la racine du lot (`a/b`)      |
+/// | `{lang}`   | langue OCR (`fra+eng`)                                        |
+/// | `{psm}`    | numéro du mode de segmentation (`3`)                          |
+/// | `{date}`   | date du traitement, `AAAA-MM-JJ` (UTC)                        |
+/// | `{index}`  | rang de l'image dans le lot, à partir de 1                    |
+/// | `{hash8}`  | 8 premiers caractères hexadécimaux du SHA-256 de l'image      |
+///
+/// Une variable inconnue, une accolade non fermée, un segment `..` ou un
+/// chemin absolu sont refusés dès l'analyse du gabarit
+/// ([`parse()`](Self::parse)). Le rendu ([`render()`](Self::render))
+/// refuse aussi un chemin qui remonterait au-dessus du répertoire de sortie
+/// à cause de la valeur d'une variable.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::output_path::{OutputTemplate, TemplateValues};
+/// use std::path::Path;
+///
+/// let template: OutputTemplate = "{date}/{stem}_{lang}_ocr.txt".parse()?;
+/// let values = TemplateValues {
+///     lang: "fra".to_string(),
+///     date: "2024-03-18".to_string(),
+///     ..TemplateValues::from_input(Path::new("scans/facture.png"), None)
+/// };
+/// assert_eq!(template.render(&values)?, Path::new("2024-03-18/facture_fra_ocr.txt"));
+///
+/// assert!("{stem}.{format}".parse::<OutputTemplate>().is_err());
+/// assert!("../{stem}.txt".parse::<OutputTemplate>().is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTemplate {
+    source: String,
+    parts: Vec<TemplatePart>,
+}
+
+impl OutputTemplate {
+    /// Analyse un gabarit de chemin de sortie.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - Gabarit, relatif au répertoire de sortie (ex: `{parent}/{stem}.txt`)
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le gabarit est vide, absolu, se termine par un
+    /// séparateur, contient un segment `..`, une accolade non appariée ou une
+    /// variable inconnue.
+    pub fn parse(template: &str) -> Result<Self> {
+        if template.trim().is_empty() {
+            bail!("Gabarit de sortie vide");
+        }
+        if template.starts_with(['/', '\\']) || Path::new(template).is_absolute() {
+            bail!(
+                "Gabarit de sortie '{}' absolu : il doit être relatif au répertoire de sortie",
+                template
+            );
+        }
+        if template.ends_with(['/', '\\']) {
+            bail!("Gabarit de sortie '{}' sans nom de fichier final", template);
+        }
+        if template.split(['/', '\\']).any(|segment| segment == "..") {
+            bail!(
+                "Gabarit de sortie '{}' refusé : les segments '..' sont interdits",
+                template
+            );
+        }
+
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template;
+        while let Some(position) = rest.find(['{', '}']) {
+            literal.push_str(&rest[..position]);
+            if rest[position..].starts_with('}') {
+                bail!("Accolade '}}' sans '{{' dans le gabarit '{}'", template);
+            }
+            let after = &rest[position + 1..];
+            let Some(end) = after.find('}') else {
+                bail!("Accolade '{{' non fermée dans le gabarit '{}'", template);
+            };
+            let name = &after[..end];
+            let Some(placeholder) = Placeholder::ALL
+                .into_iter()
+                .find(|placeholder| placeholder.name() == name)
+            else {
+                let known: Vec<String> = Placeholder::ALL
+                    .iter()
+                    .map(|placeholder| format!("{{{}}}", placeholder.name()))
+                    .collect();
+                bail!(
+                    "Variable inconnue '{{{}}}' dans le gabarit '{}' (variables reconnues : {})",
+                    name,
+                    template,
+                    known.join(", ")
+                );
+            };
+            if !literal.is_empty() {
+                parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+            }
+            parts.push(TemplatePart::Placeholder(placeholder));
+            rest = &after[end + 1..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(literal));
+        }
+
+        Ok(Self {
+            source: template.to_string(),
+            parts,
+        })
+    }
+
+    /// Texte du gabarit, tel qu'analysé.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Indique si le gabarit utilise `{hash8}`, dont le calcul demande de lire l'image.
+    pub fn uses_hash(&self) -> bool {
+        self.parts
+            .contains(&TemplatePart::Placeholder(Placeholder::Hash8))
+    }
+
+    /// Calcule le chemin de sortie d'une image, relatif au répertoire de sortie.
+    ///
+    /// Les segments vides (variable `{parent}` vide) et `.` sont retirés.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - Valeurs des variables pour l'image
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le chemin obtenu contient un segment `..`
+    /// (valeur de variable malveillante) ou n'a pas de nom de fichier.
+    pub fn render(&self, values: &TemplateValues) -> Result<PathBuf> {
+        let mut rendered = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(text) => rendered.push_str(text),
+                TemplatePart::Placeholder(placeholder) => {
+                    rendered.push_str(&values.value(*placeholder));
+                }
+            }
+        }
+
+        let mut path = PathBuf::new();
+        for segment in rendered.split(['/', '\\']) {
+            match segment {
+                "" | "." => {}
+                ".." => bail!(
+                    "Le gabarit '{}' donne '{}', qui sort du répertoire de sortie",
+                    self.source,
+                    rendered
+                ),
+                _ => path.push(segment),
+            }
+        }
+        if path.file_name().is_none() || rendered.ends_with(['/', '\\']) {
+            bail!(
+                "Le gabarit '{}' donne '{}', sans nom de fichier",
+                self.source,
+                rendered
+            );
+        }
+        // Un composant « C: » ou la racine ne peuvent venir que d'une variable
+        if path
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            bail!(
+                "Le gabarit '{}' donne '{}', qui sort du répertoire de sortie",
+                self.source,
+                rendered
+            );
+        }
+        Ok(path)
+    }
+}
+
+impl FromStr for OutputTemplate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for OutputTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Valeurs des variables d'un [`OutputTemplate`] pour une image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateValues {
+    /// Nom de l'image sans extension (`{stem}`).
+    pub stem: String,
+    /// Extension de l'image, sans point (`{ext}`).
+    pub ext: String,
+    /// Répertoire de l'image relatif à la racine du lot (`{parent}`).
+    pub parent: PathBuf,
+    /// Langue OCR (`{lang}`).
+    pub lang: String,
+    /// Mode de segmentation (`{psm}`, rendu par son numéro).
+    pub psm: PageSegMode,
+    /// Date du traitement (`{date}`), voir [`format_utc_date()`].
+    pub date: String,
+    /// Rang de l'image dans le lot, à partir de 1 (`{index}`).
+    pub index: usize,
+    /// Début de l'empreinte SHA-256 de l'image (`{hash8}`).
+    pub hash8: String,
+}
+
+impl TemplateValues {
+    /// Valeurs tirées du chemin d'une image ; les autres variables sont vides.
+    ///
+    /// `{parent}` ne garde que les répertoires ordinaires du chemin relatif
+    /// à `input_root` : il est vide si l'image n'est pas sous la racine, et
+    /// ne contient jamais de `..`. Les noms qui ne sont pas en UTF-8 sont
+    /// convertis avec des caractères de remplacement, et `{stem}` passe par
+    /// [`sanitize_file_stem()`].
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Image d'entrée
+    /// * `input_root` - Racine du lot, voir [`map_output_path()`]
+    pub fn from_input(input: &Path, input_root: Option<&Path>) -> Self {
+        let input = normalize_separators(input);
+        let lossy = |value: Option<&OsStr>| {
+            value
+                .map(|value| value.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        let parent = input_root
+            .and_then(|root| input.strip_prefix(normalize_separators(root)).ok())
+            .and_then(Path::parent)
+            .map(|relative| {
+                relative
+                    .components()
+                    .filter(|component| matches!(component, Component::Normal(_)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            stem: sanitize_file_stem(&lossy(input.file_stem())),
+            ext: lossy(input.extension()),
+            parent,
+            lang: String::new(),
+            psm: PageSegMode::Auto,
+            date: String::new(),
+            index: 0,
+            hash8: String::new(),
+        }
+    }
+
+    /// Valeur textuelle d'une variable.
+    fn value(&self, placeholder: Placeholder) -> String {
+        match placeholder {
+            Placeholder::Stem => self.stem.clone(),
+            Placeholder::Ext => self.ext.clone(),
+            Placeholder::Parent => self.parent.to_string_lossy().replace('\\', "/"),
+            Placeholder::Lang => self.lang.clone(),
+            Placeholder::Psm => self.psm.to_tesseract_psm().to_string(),
+            Placeholder::Date => self.date.clone(),
+            Placeholder::Index => self.index.to_string(),
+            Placeholder::Hash8 => self.hash8.clone(),
+        }
+    }
+}
+
+/// Formate une date Unix (secondes) en `AAAA-MM-JJ`, en temps universel.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::output_path::format_utc_date;
+///
+/// assert_eq!(format_utc_date(0), "1970-01-01");
+/// assert_eq!(format_utc_date(1_709_251_200), "2024-03-01");
+/// ```
+pub fn format_utc_date(unix_seconds: u64) -> String {
+    // Conversion jours → date civile (algorithme de H. Hinnant)
+    let days = (unix_seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Calcule les fichiers de sortie d'un lot à partir d'un gabarit.
+///
+/// Chaque chemin est rendu par [`OutputTemplate::render()`] puis placé sous
+/// `output_dir` ; les collisions sont départagées par un suffixe comme dans
+/// [`plan_output_paths()`].
+///
+/// # Arguments
+///
+/// * `inputs` - Images du lot, dans l'ordre de traitement
+/// * `values` - Valeurs des variables de chaque image, dans le même ordre
+/// * `output_dir` - Répertoire de sortie
+/// * `template` - Gabarit des chemins
+///
+/// # Retour
+///
+/// Une [`OutputMapping`] par image, dans l'ordre de `inputs`.
+///
+/// # Erreurs
+///
+/// Retourne l'erreur de rendu de la première image dont le chemin sortirait
+/// du répertoire de sortie, ou une erreur si `values` n'a pas autant
+/// d'éléments que `inputs`.
+pub fn plan_templated_output_paths(
+    inputs: &[PathBuf],
+    values: &[TemplateValues],
+    output_dir: &Path,
+    template: &OutputTemplate,
+) -> Result<Vec<OutputMapping>> {
+    if inputs.len() != values.len() {
+        bail!(
+            "{} valeurs de gabarit pour {} images",
+            values.len(),
+            inputs.len()
+        );
+    }
+    let mut used = HashSet::new();
+    inputs
+        .iter()
+        .zip(values)
+        .map(|(input, values)| {
+            let natural = output_dir.join(template.render(values)?);
+            Ok(unique_mapping(&mut used, input, natural))
+        })
+        .collect()
+}
+
+/// Remplace les `\` par des `/` et retire les composants `.` d'un chemin UTF-8.
+///
+/// Les chemins qui ne sont pas en UTF-8 sont conservés tels quels.
+fn normalize_separators(path: &Path) -> PathBuf {
+    let path = match path.to_str() {
+        Some(text) if text.contains('\\') => PathBuf::from(text.replace('\\', "/")),
+        _ => path.to_path_buf(),
+    };
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+/// Clé de comparaison des chemins de sortie, insensible à la casse.
+fn collision_key(path: &Path) -> String {
+    path.to_string_lossy().to_lowercase()
+}
+
+/// Ajoute `-<suffix>` au nom de `path`, avant l'extension.
+fn with_suffix(path: &Path, suffix: usize) -> PathBuf {
+    let mut file_name = path
+        .file_stem()
+        .map(|stem| stem.to_os_string())
+        .unwrap_or_default();
+    file_name.push(format!("-{}", suffix));
+    if let Some(extension) = path.extension() {
+        file_name.push(".");
+        file_name.push(extension);
+    }
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mirrored() -> OutputPathOptions {
+        OutputPathOptions::default()
+    }
+
+    fn flat() -> OutputPathOptions {
+        OutputPathOptions {
+            flatten: true,
+            ..OutputPathOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_nested_inputs_mirror_directories() {
+        let root = Path::new("scans");
+        let output = Path::new("results");
+
+        assert_eq!(
+            map_output_path(
+                Path::new("scans/page1.png"),
+                Some(root),
+                output,
+                &mirrored()
+            ),
+            Path::new("results/page1.txt")
+        );
+        assert_eq!(
+            map_output_path(
+                Path::new("scans/a/b/c/page1.png"),
+                Some(root),
+                output,
+                &mirrored()
+            ),
+            Path::new("results/a/b/c/page1.txt")
+        );
+        // "./scans" et "scans" désignent la même racine
+        assert_eq!(
+            map_output_path(
+                Path::new("./scans/a/page1.png"),
+                Some(Path::new("scans")),
+                output,
+                &mirrored()
+            ),
+            Path::new("results/a/page1.txt")
+        );
+    }
+
+    #[test]
+    fn test_flatten_and_unrelated_roots() {
+        let input = Path::new("scans/a/page1.png");
+        assert_eq!(
+            map_output_path(input, Some(Path::new("scans")), Path::new("out"), &flat()),
+            Path::new("out/page1.txt")
+        );
+        // Image hors de la racine, ou sans racine (fichier unique) : à plat
+        assert_eq!(
+            map_output_path(
+                input,
+                Some(Path::new("autre")),
+                Path::new("out"),
+                &mirrored()
+            ),
+            Path::new("out/page1.txt")
+        );
+        assert_eq!(
+            map_output_path(input, None, Path::new("out"), &mirrored()),
+            Path::new("out/page1.txt")
+        );
+    }
+
+    #[test]
+    fn test_windows_separators() {
+        assert_eq!(
+            map_output_path(
+                Path::new(r"scans\a\page1.png"),
+                Some(Path::new(r"scans")),
+                Path::new("results"),
+                &mirrored()
+            ),
+            Path::new("results/a/page1.txt")
+        );
+        assert_eq!(
+            map_output_path(
+                Path::new(r"C:\scans\b\page1.png"),
+                Some(Path::new(r"C:\scans")),
+                Path::new("results"),
+                &flat()
+            ),
+            Path::new("results/page1.txt")
+        );
+    }
+
+    #[test]
+    fn test_keeps_inner_dots_and_extension_option() {
+        let options = OutputPathOptions {
+            extension: "png".to_string(),
+            ..OutputPathOptions::default()
+        };
+        assert_eq!(
+            map_output_path(
+                Path::new("rapport.v2.tiff"),
+                None,
+                Path::new("out"),
+                &options
+            ),
+            Path::new("out/rapport.v2.png")
+        );
+    }
+
+    #[test]
+    fn test_sanitizes_windows_file_names() {
+        assert_eq!(sanitize_file_stem("a<b>c:d\"e|f?g*h"), "a_b_c_d_e_f_g_h");
+        assert_eq!(sanitize_file_stem("tab\tnul\u{0}"), "tab_nul_");
+        assert_eq!(sanitize_file_stem("NUL"), "NUL_");
+        assert_eq!(sanitize_file_stem("lpt9"), "lpt9_");
+        assert_eq!(sanitize_file_stem("console"), "console");
+        assert_eq!(sanitize_file_stem(""), "sortie");
+
+        // Les noms rendus identiques sont départagés par le suffixe habituel
+        let inputs = [
+            PathBuf::from("scans/facture:1.png"),
+            PathBuf::from("scans/facture?1.png"),
+            PathBuf::from("scans/CON.png"),
+        ];
+        let outputs: Vec<PathBuf> = plan_output_paths(&inputs, None, Path::new("r"), &flat())
+            .into_iter()
+            .map(|mapping| mapping.output)
+            .collect();
+        assert_eq!(
+            outputs,
+            [
+                PathBuf::from("r/facture_1.txt"),
+                PathBuf::from("r/facture_1-2.txt"),
+                PathBuf::from("r/CON_.txt"),
+            ]
+        );
+
+        let values = TemplateValues::from_input(Path::new("scans/prn.png"), None);
+        assert_eq!(values.stem, "prn_");
+    }
+
+    #[test]
+    fn test_same_stem_collisions() {
+        let inputs = [
+            PathBuf::from("scans/a/page1.png"),
+            PathBuf::from("scans/b/page1.png"),
+            PathBuf::from("scans/b/page1.jpg"),
+            PathBuf::from("scans/b/PAGE1.tif"),
+            PathBuf::from("scans/b/page1-2.png"),
+        ];
+        let root = Some(Path::new("scans"));
+
+        // Arborescence reproduite : seules les collisions d'un même répertoire sont renommées
+        let outputs: Vec<PathBuf> = plan_output_paths(&inputs, root, Path::new("r"), &mirrored())
+            .into_iter()
+            .map(|mapping| mapping.output)
+            .collect();
+        assert_eq!(
+            outputs,
+            [
+                PathBuf::from("r/a/page1.txt"),
+                PathBuf::from("r/b/page1.txt"),
+                PathBuf::from("r/b/page1-2.txt"),
+                PathBuf::from("r/b/PAGE1-3.txt"),
+                // "page1-2" est déjà attribué : suffixe sur le nom d'origine
+                PathBuf::from("r/b/page1-2-2.txt"),
+            ]
+        );
+
+        let mappings = plan_output_paths(&inputs, root, Path::new("r"), &flat());
+        let unique: HashSet<String> = mappings
+            .iter()
+            .map(|mapping| collision_key(&mapping.output))
+            .collect();
+        assert_eq!(unique.len(), inputs.len());
+        assert_eq!(mappings[0].output, Path::new("r/page1.txt"));
+        assert!(!mappings[0].renamed);
+        assert_eq!(mappings[1].output, Path::new("r/page1-2.txt"));
+        assert!(mappings[1].renamed);
+        assert_eq!(mappings[1].input, inputs[1]);
+    }
+
+    /// Valeurs de toutes les variables pour `scans/2024/mars/facture.v2.PNG`.
+    fn fixture_values() -> TemplateValues {
+        TemplateValues {
+            lang: "fra+eng".to_string(),
+            psm: PageSegMode::SingleBlock,
+            date: format_utc_date(1_710_720_000),
+            index: 7,
+            hash8: "3fa9c01e".to_string(),
+            ..TemplateValues::from_input(
+                Path::new("scans/2024/mars/facture.v2.PNG"),
+                Some(Path::new("scans")),
+            )
+        }
+    }
+
+    fn render(template: &str) -> PathBuf {
+        OutputTemplate::parse(template)
+            .unwrap()
+            .render(&fixture_values())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_template_renders_each_placeholder() {
+        assert_eq!(render("{stem}.txt"), Path::new("facture.v2.txt"));
+        assert_eq!(render("{stem}.{ext}.txt"), Path::new("facture.v2.PNG.txt"));
+        assert_eq!(
+            render("{parent}/{stem}.txt"),
+            Path::new("2024/mars/facture.v2.txt")
+        );
+        assert_eq!(
+            render("{stem}_{lang}.txt"),
+            Path::new("facture.v2_fra+eng.txt")
+        );
+        assert_eq!(
+            render("psm{psm}/{stem}.txt"),
+            Path::new("psm6/facture.v2.txt")
+        );
+        assert_eq!(
+            render("{date}/{stem}.txt"),
+            Path::new("2024-03-18/facture.v2.txt")
+        );
+        assert_eq!(render("{index}-{stem}.txt"), Path::new("7-facture.v2.txt"));
+        assert_eq!(render("{hash8}.txt"), Path::new("3fa9c01e.txt"));
+        assert_eq!(
+            render("{date}/{stem}_{lang}_ocr.txt"),
+            Path::new("2024-03-18/facture.v2_fra+eng_ocr.txt")
+        );
+
+        // Image hors de la racine : {parent} vide, le segment disparaît
+        let values =
+            TemplateValues::from_input(Path::new("ailleurs/page.png"), Some(Path::new("scans")));
+        let template = OutputTemplate::parse("{parent}/{stem}.txt").unwrap();
+        assert_eq!(template.render(&values).unwrap(), Path::new("page.txt"));
+        assert!(!template.uses_hash());
+        assert!(OutputTemplate::parse("{hash8}.txt").unwrap().uses_hash());
+        assert_eq!(template.to_string(), "{parent}/{stem}.txt");
+    }
+
+    #[test]
+    fn test_template_rejects_unknown_or_malformed_placeholders() {
+        let error = OutputTemplate::parse("{stem}_{format}.txt").unwrap_err();
+        assert!(error.to_string().contains("{format}"), "{}", error);
+        assert!(error.to_string().contains("{hash8}"), "{}", error);
+
+        for template in ["{stem.txt", "stem}.txt", "{}.txt", "", "sorties/"] {
+            assert!(OutputTemplate::parse(template).is_err(), "{}", template);
+        }
+    }
+
+    #[test]
+    fn test_template_cannot_escape_output_root() {
+        for template in [
+            "../{stem}.txt",
+            "{parent}/../../{stem}.txt",
+            "/tmp/{stem}.txt",
+            r"..\{stem}.txt",
+        ] {
+            assert!(OutputTemplate::parse(template).is_err(), "{}", template);
+        }
+
+        // {parent} ne garde que des répertoires ordinaires
+        let values = TemplateValues::from_input(
+            Path::new("scans/../../etc/passwd.png"),
+            Some(Path::new("scans")),
+        );
+        assert!(
+            values
+                .parent
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+        );
+
+        // Une valeur contenant « .. » est refusée au rendu
+        let template = OutputTemplate::parse("{lang}/{stem}.txt").unwrap();
+        let values = TemplateValues {
+            lang: "../..".to_string(),
+            ..fixture_values()
+        };
+        let error = template.render(&values).unwrap_err();
+        assert!(
+            error.to_string().contains("sort du répertoire"),
+            "{}",
+            error
+        );
+        let mappings = plan_templated_output_paths(
+            &[PathBuf::from("scans/facture.png")],
+            &[values],
+            Path::new("out"),
+            &template,
+        );
+        assert!(mappings.is_err());
+    }
+
+    #[test]
+    fn test_templated_plan_departs_collisions() {
+        let inputs = [
+            PathBuf::from("scans/a/page.png"),
+            PathBuf::from("scans/b/page.png"),
+        ];
+        let values: Vec<TemplateValues> = inputs
+            .iter()
+            .map(|input| TemplateValues::from_input(input, Some(Path::new("scans"))))
+            .collect();
+
+        let flat = OutputTemplate::parse("{stem}_ocr.txt").unwrap();
+        let mappings =
+            plan_templated_output_paths(&inputs, &values, Path::new("out"), &flat).unwrap();
+        assert_eq!(mappings[0].output, Path::new("out/page_ocr.txt"));
+        assert_eq!(mappings[1].output, Path::new("out/page_ocr-2.txt"));
+        assert!(mappings[1].renamed);
+
+        let nested = OutputTemplate::parse("{parent}/{stem}").unwrap();
+        let mappings =
+            plan_templated_output_paths(&inputs, &values, Path::new("out"), &nested).unwrap();
+        assert_eq!(mappings[1].output, Path::new("out/b/page"));
+        assert!(
+            plan_templated_output_paths(&inputs, &values[..1], Path::new("out"), &nested).is_err()
+        );
+    }
+
+    #[test]
+    fn test_format_utc_date() {
+        assert_eq!(format_utc_date(0), "1970-01-01");
+        assert_eq!(format_utc_date(951_782_400), "2000-02-29");
+        assert_eq!(format_utc_date(1_710_720_000 + 86_399), "2024-03-18");
+        assert_eq!(format_utc_date(4_102_444_800), "2100-01-01");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_file_names() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        // "caf\xe9.png" en Latin-1
+        let name = OsStr::from_bytes(b"caf\xe9.png");
+        let input = Path::new("scans/a").join(name);
+        let output = map_output_path(
+            &input,
+            Some(Path::new("scans")),
+            Path::new("out"),
+            &mirrored(),
+        );
+
+        assert_eq!(
+            output,
+            Path::new("out/a").join(OsStr::from_bytes(b"caf\xe9.txt"))
+        );
+
+        let inputs = [input.clone(), Path::new("scans/b").join(name)];
+        let mappings =
+            plan_output_paths(&inputs, Some(Path::new("scans")), Path::new("out"), &flat());
+        assert_eq!(
+            mappings[1].output,
+            Path::new("out").join(OsStr::from_bytes(b"caf\xe9-2.txt"))
+        );
+
+        // Les caractères refusés sont remplacés sans toucher aux octets non UTF-8
+        let output = map_output_path(
+            &Path::new("scans").join(OsStr::from_bytes(b"caf\xe9:1.png")),
+            None,
+            Path::new("out"),
+            &flat(),
+        );
+        assert_eq!(
+            output,
+            Path::new("out").join(OsStr::from_bytes(b"caf\xe9_1.txt"))
+        );
+    }
+}
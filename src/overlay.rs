@@ -0,0 +1,308 @@
+//! Calque de surlignage des mots reconnus, pour un affichage web.
+//!
+//! Une visionneuse surligne le texte reconnu par-dessus l'image affichée, à
+//! une taille d'écran quelconque. [`HocrDocument::to_overlay_json()`]
+//! produit pour cela un tableau JSON de polygones en coordonnées
+//! normalisées (0 à 1, origine en haut à gauche) : la visionneuse n'a qu'à
+//! les multiplier par la taille affichée.
+//!
+//! Les éléments sont listés par niveau (mots, puis lignes, puis
+//! paragraphes) et chacun garde l'ordre du document. Les coordonnées sont
+//! arrondies à 5 décimales, soit une précision d'un centième de pixel sur
+//! une image de 1 000 px.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::hocr::HocrDocument;
+//! use text_recognition::overlay::{OverlayItem, OverlayLevel};
+//!
+//! let hocr = r#"<p class='ocr_par' title="bbox 100 50 200 100">
+//!   <span class='ocr_line' title="bbox 100 50 200 100">
+//!     <span class='ocrx_word' title='bbox 100 50 200 100; x_wconf 93'>Total</span>
+//!   </span>
+//! </p>"#;
+//! let doc = HocrDocument::from_hocr_string(hocr)?;
+//!
+//! let json = doc.to_overlay_json(1000, 500);
+//! let items: Vec<OverlayItem> = serde_json::from_str(&json)?;
+//! assert_eq!(items[0].level, OverlayLevel::Word);
+//! assert_eq!(items[0].polygon[2], [0.2, 0.2]);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::hocr::{BBox, HocrDocument, HocrLine};
+use serde::{Deserialize, Serialize};
+
+/// Nombre de décimales des coordonnées normalisées.
+const COORDINATE_DECIMALS: i32 = 5;
+
+/// Niveau de la structure HOCR d'un élément du calque.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayLevel {
+    /// Mot (`ocrx_word`).
+    Word,
+    /// Ligne (`ocr_line`).
+    Line,
+    /// Paragraphe (`ocr_par`).
+    Paragraph,
+}
+
+/// Élément du calque : texte et polygone normalisé.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverlayItem {
+    /// Texte de l'élément (mots séparés par des espaces, lignes par `\n`).
+    pub text: String,
+    /// Confiance de Tesseract (0-100) ; moyenne des mots pour une ligne ou
+    /// un paragraphe, absente si aucun mot n'en a.
+    pub confidence: Option<u8>,
+    /// Niveau de l'élément.
+    pub level: OverlayLevel,
+    /// Sommets `[x, y]` normalisés, dans le sens horaire depuis le coin
+    /// supérieur gauche.
+    pub polygon: Vec<[f64; 2]>,
+}
+
+/// Niveaux inclus dans le calque.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlayOptions {
+    /// Inclure les mots (défaut : oui).
+    pub words: bool,
+    /// Inclure les lignes (défaut : oui).
+    pub lines: bool,
+    /// Inclure les paragraphes (défaut : oui).
+    pub paragraphs: bool,
+}
+
+impl Default for OverlayOptions {
+    fn default() -> Self {
+        Self {
+            words: true,
+            lines: true,
+            paragraphs: true,
+        }
+    }
+}
+
+impl HocrDocument {
+    /// Exporte les mots, lignes et paragraphes en calque JSON normalisé.
+    ///
+    /// Équivaut à [`to_overlay_json_with()`](Self::to_overlay_json_with)
+    /// avec les options par défaut (tous les niveaux).
+    ///
+    /// # Arguments
+    ///
+    /// * `image_width` - Largeur de l'image analysée, en pixels
+    /// * `image_height` - Hauteur de l'image analysée, en pixels
+    pub fn to_overlay_json(&self, image_width: u32, image_height: u32) -> String {
+        self.to_overlay_json_with(image_width, image_height, &OverlayOptions::default())
+    }
+
+    /// Exporte les niveaux choisis en calque JSON normalisé.
+    ///
+    /// Le JSON est un tableau d'[`OverlayItem`], relisible avec
+    /// `serde_json::from_str::<Vec<OverlayItem>>`.
+    ///
+    /// # Arguments
+    ///
+    /// * `image_width` - Largeur de l'image analysée, en pixels
+    /// * `image_height` - Hauteur de l'image analysée, en pixels
+    /// * `options` - Niveaux à inclure
+    pub fn to_overlay_json_with(
+        &self,
+        image_width: u32,
+        image_height: u32,
+        options: &OverlayOptions,
+    ) -> String {
+        let items = self.overlay_items(image_width, image_height, options);
+        // Des chaînes et des nombres finis se sérialisent toujours
+        serde_json::to_string(&items).unwrap_or_else(|_| String::from("[]"))
+    }
+
+    /// Éléments du calque : mots, puis lignes, puis paragraphes.
+    ///
+    /// Les coordonnées sont divisées par les dimensions de l'image, bornées
+    /// à `[0, 1]` et arrondies à 5 décimales. Une dimension nulle est
+    /// traitée comme valant 1 pixel.
+    ///
+    /// # Arguments
+    ///
+    /// * `image_width` - Largeur de l'image analysée, en pixels
+    /// * `image_height` - Hauteur de l'image analysée, en pixels
+    /// * `options` - Niveaux à inclure
+    pub fn overlay_items(
+        &self,
+        image_width: u32,
+        image_height: u32,
+        options: &OverlayOptions,
+    ) -> Vec<OverlayItem> {
+        let normalize = |bbox: &BBox| normalized_polygon(bbox, image_width, image_height);
+        let lines = || self.paragraphs().flat_map(|paragraph| &paragraph.lines);
+        let mut items = Vec::new();
+
+        if options.words {
+            items.extend(
+                lines()
+                    .flat_map(|line| &line.words)
+                    .map(|word| OverlayItem {
+                        text: word.text.clone(),
+                        confidence: word.confidence,
+                        level: OverlayLevel::Word,
+                        polygon: normalize(&word.bbox),
+                    }),
+            );
+        }
+        if options.lines {
+            items.extend(lines().map(|line| OverlayItem {
+                text: line_text(line),
+                confidence: line.mean_confidence().map(round_confidence),
+                level: OverlayLevel::Line,
+                polygon: normalize(&line.bbox),
+            }));
+        }
+        if options.paragraphs {
+            items.extend(self.paragraphs().map(|paragraph| {
+                OverlayItem {
+                    text: paragraph
+                        .lines
+                        .iter()
+                        .map(line_text)
+                        .filter(|text| !text.is_empty())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    confidence: paragraph.mean_confidence().map(round_confidence),
+                    level: OverlayLevel::Paragraph,
+                    polygon: normalize(&paragraph.bbox),
+                }
+            }));
+        }
+
+        items
+    }
+}
+
+/// Texte d'une ligne, mots dans l'ordre de lecture.
+fn line_text(line: &HocrLine) -> String {
+    line.words_in_reading_order()
+        .iter()
+        .map(|word| word.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Arrondit une confiance moyenne à l'entier le plus proche.
+fn round_confidence(mean: f32) -> u8 {
+    mean.round() as u8
+}
+
+/// Rectangle d'une bounding box en coordonnées normalisées, sens horaire.
+fn normalized_polygon(bbox: &BBox, image_width: u32, image_height: u32) -> Vec<[f64; 2]> {
+    let x = |value: u32| normalize_coordinate(value, image_width);
+    let y = |value: u32| normalize_coordinate(value, image_height);
+    let (left, top) = (x(bbox.x), y(bbox.y));
+    let right = x(bbox.x.saturating_add(bbox.width));
+    let bottom = y(bbox.y.saturating_add(bbox.height));
+    vec![[left, top], [right, top], [right, bottom], [left, bottom]]
+}
+
+/// Divise une coordonnée par la dimension de l'image, bornée et arrondie.
+fn normalize_coordinate(value: u32, dimension: u32) -> f64 {
+    let scale = 10f64.powi(COORDINATE_DECIMALS);
+    let ratio = (f64::from(value) / f64::from(dimension.max(1))).clamp(0.0, 1.0);
+    (ratio * scale).round() / scale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOCR: &str = r#"<div class='ocr_page' title='bbox 0 0 1000 500'>
+  <p class='ocr_par' title="bbox 100 50 700 160">
+    <span class='ocr_line' title="bbox 100 50 700 100">
+      <span class='ocrx_word' title='bbox 100 50 200 100; x_wconf 90'>Facture</span>
+      <span class='ocrx_word' title='bbox 250 50 700 100; x_wconf 81'>n°42</span>
+    </span>
+    <span class='ocr_line' title="bbox 100 110 400 160">
+      <span class='ocrx_word' title='bbox 100 110 400 160; x_wconf 70'>Total</span>
+    </span>
+  </p>
+</div>"#;
+
+    fn document() -> HocrDocument {
+        HocrDocument::from_hocr_string(HOCR).unwrap()
+    }
+
+    #[test]
+    fn test_word_polygon_is_normalized() {
+        let items = document().overlay_items(1000, 500, &OverlayOptions::default());
+
+        let word = &items[0];
+        assert_eq!(word.text, "Facture");
+        assert_eq!(word.level, OverlayLevel::Word);
+        assert_eq!(word.confidence, Some(90));
+        assert_eq!(
+            word.polygon,
+            vec![[0.1, 0.1], [0.2, 0.1], [0.2, 0.2], [0.1, 0.2]]
+        );
+    }
+
+    #[test]
+    fn test_items_are_ordered_by_level() {
+        let items = document().overlay_items(1000, 500, &OverlayOptions::default());
+
+        let levels: Vec<OverlayLevel> = items.iter().map(|item| item.level).collect();
+        assert_eq!(
+            levels,
+            [
+                OverlayLevel::Word,
+                OverlayLevel::Word,
+                OverlayLevel::Word,
+                OverlayLevel::Line,
+                OverlayLevel::Line,
+                OverlayLevel::Paragraph,
+            ]
+        );
+        assert_eq!(items[3].text, "Facture n°42");
+        assert_eq!(items[3].confidence, Some(86));
+        assert_eq!(items[5].text, "Facture n°42\nTotal");
+        assert_eq!(items[5].confidence, Some(80));
+    }
+
+    #[test]
+    fn test_level_filtering_omits_paragraphs() {
+        let options = OverlayOptions {
+            paragraphs: false,
+            ..OverlayOptions::default()
+        };
+        let items = document().overlay_items(1000, 500, &options);
+
+        assert_eq!(items.len(), 5);
+        assert!(
+            items
+                .iter()
+                .all(|item| item.level != OverlayLevel::Paragraph)
+        );
+    }
+
+    #[test]
+    fn test_coordinates_are_rounded_and_clamped() {
+        let polygon = normalized_polygon(&BBox::new(1, 0, 5000, 3), 3, 7);
+
+        assert_eq!(polygon[0], [0.33333, 0.0]);
+        assert_eq!(polygon[2], [1.0, 0.42857]);
+    }
+
+    #[test]
+    fn test_json_parses_back_into_items() {
+        let doc = document();
+        let json = doc.to_overlay_json(1000, 500);
+
+        assert!(json.contains(r#""level":"word""#), "{}", json);
+        let parsed: Vec<OverlayItem> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed,
+            doc.overlay_items(1000, 500, &OverlayOptions::default())
+        );
+    }
+}
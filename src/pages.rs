@@ -0,0 +1,420 @@
+//! Lecture page par page des documents multipages (TIFF).
+//!
+//! [`OcrEngine::pages()`] retourne un itérateur qui décode, prétraite et
+//! lit une page à la fois : la mémoire utilisée reste celle d'une page,
+//! même pour un TIFF de plusieurs centaines de pages. Les autres formats
+//! d'image donnent exactement une page, d'index 0.
+//!
+//! Une erreur d'OCR ne concerne que sa page : l'itération continue avec la
+//! suivante. Une erreur de décodage du TIFF (fichier tronqué...) est la
+//! dernière valeur produite.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::ocr::OcrEngine;
+//! use std::path::Path;
+//!
+//! let engine = OcrEngine::new(OcrConfig::default())?;
+//! for page in engine.pages(Path::new("archive.tiff"))? {
+//!     let page = page?;
+//!     println!("--- page {} ---\n{}", page.index + 1, page.text);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::debug_artifacts::source_label;
+use crate::diagnostics::Warning;
+use crate::ocr::OcrEngine;
+use crate::preprocessing::PreprocessingReport;
+use anyhow::{Context, Result};
+use image::{DynamicImage, ImageBuffer, ImageFormat, Pixel};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tiff::decoder::{Decoder, DecodingResult};
+
+/// Texte extrait d'une page d'un document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageResult {
+    /// Rang de la page dans le document (à partir de 0).
+    pub index: usize,
+    /// Texte extrait de la page.
+    pub text: String,
+    /// Avertissements émis pour cette page.
+    pub warnings: Vec<Warning>,
+    /// Décisions du prétraitement, si la page a été prétraitée.
+    pub preprocessing: Option<PreprocessingReport>,
+    /// Durées mesurées, si demandées (voir [`PageIter::with_timings()`]).
+    pub timings: Option<PageTimings>,
+}
+
+/// Durées de traitement d'une page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageTimings {
+    /// Décodage de la page. Nul pour une image d'une seule page, décodée
+    /// pendant l'OCR.
+    pub decode: Duration,
+    /// Prétraitement et OCR de la page.
+    pub ocr: Duration,
+}
+
+/// Itérateur paresseux sur les pages d'un document, créé par [`OcrEngine::pages()`].
+///
+/// Chaque appel à `next()` décode et lit la page suivante ; les pages non
+/// demandées ne sont jamais décodées.
+pub struct PageIter<'a> {
+    engine: &'a OcrEngine,
+    path: PathBuf,
+    source: PageSource,
+    next_index: usize,
+    measure_timings: bool,
+    on_decode: Option<Box<dyn FnMut(usize) + 'a>>,
+}
+
+/// Origine des pages restant à lire.
+enum PageSource {
+    /// TIFF décodé image par image.
+    Tiff(Box<Decoder<BufReader<File>>>),
+    /// Image d'une seule page, pas encore lue.
+    Single,
+    /// Plus aucune page.
+    Done,
+}
+
+impl OcrEngine {
+    /// Itère sur les pages d'une image, en lisant une page à la fois.
+    ///
+    /// Un TIFF multipage donne une page par image du fichier, dans l'ordre ;
+    /// tout autre format donne une seule page, lue comme par
+    /// [`extract_text_detailed()`](Self::extract_text_detailed). Le format
+    /// est reconnu au contenu du fichier.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le fichier ne peut pas être ouvert ou si
+    /// l'en-tête d'un TIFF est invalide. Les erreurs de chaque page sont
+    /// produites par l'itérateur.
+    pub fn pages(&self, path: &Path) -> Result<PageIter<'_>> {
+        if !path.exists() {
+            anyhow::bail!("Le fichier '{}' n'existe pas", path.display());
+        }
+        let format = image::ImageReader::open(path)
+            .and_then(|reader| reader.with_guessed_format())
+            .with_context(|| format!("Impossible de lire '{}'", path.display()))?
+            .format();
+
+        let source = if format == Some(ImageFormat::Tiff) {
+            let file = File::open(path)
+                .with_context(|| format!("Impossible d'ouvrir '{}'", path.display()))?;
+            let decoder = Decoder::new(BufReader::new(file))
+                .with_context(|| format!("En-tête TIFF invalide : '{}'", path.display()))?;
+            PageSource::Tiff(Box::new(decoder))
+        } else {
+            PageSource::Single
+        };
+
+        Ok(PageIter {
+            engine: self,
+            path: path.to_path_buf(),
+            source,
+            next_index: 0,
+            measure_timings: false,
+            on_decode: None,
+        })
+    }
+}
+
+impl<'a> PageIter<'a> {
+    /// Mesure la durée de décodage et d'OCR de chaque page ([`PageResult::timings`]).
+    pub fn with_timings(mut self) -> Self {
+        self.measure_timings = true;
+        self
+    }
+
+    /// Appelle `hook` avec l'index de chaque page au moment où elle est
+    /// décodée, avant son OCR (suivi de progression).
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - Fonction recevant l'index de la page (à partir de 0)
+    pub fn on_decode(mut self, hook: impl FnMut(usize) + 'a) -> Self {
+        self.on_decode = Some(Box::new(hook));
+        self
+    }
+
+    /// Lit la page `index` à partir de son image décodée, ou du fichier
+    /// pour une image d'une seule page.
+    fn read_page(&self, index: usize, frame: Option<DynamicImage>) -> Result<PageResult> {
+        let start = Instant::now();
+        let outcome = match frame {
+            Some(image) => {
+                let source = format!("{}_{}", source_label(&self.path), index + 1);
+                self.engine
+                    .extract_text_from_decoded_image(&image, &source)?
+            }
+            None => self.engine.extract_text_detailed(&self.path)?,
+        };
+        Ok(PageResult {
+            index,
+            text: outcome.text,
+            warnings: outcome.warnings,
+            preprocessing: outcome.preprocessing,
+            timings: self.measure_timings.then(|| PageTimings {
+                decode: Duration::ZERO,
+                ocr: start.elapsed(),
+            }),
+        })
+    }
+}
+
+impl Iterator for PageIter<'_> {
+    type Item = Result<PageResult>;
+
+    fn next(&mut self) -> Option<Result<PageResult>> {
+        let index = self.next_index;
+        let start = Instant::now();
+        let frame = match &mut self.source {
+            PageSource::Done => return None,
+            PageSource::Single => {
+                self.source = PageSource::Done;
+                None
+            }
+            PageSource::Tiff(decoder) => match next_frame(decoder, index) {
+                Ok(Some(frame)) => Some(frame),
+                Ok(None) => {
+                    self.source = PageSource::Done;
+                    return None;
+                }
+                Err(e) => {
+                    self.source = PageSource::Done;
+                    return Some(Err(e.context(format!(
+                        "Échec du décodage de la page {} de '{}'",
+                        index + 1,
+                        self.path.display()
+                    ))));
+                }
+            },
+        };
+        let decode = if frame.is_some() {
+            start.elapsed()
+        } else {
+            Duration::ZERO
+        };
+        self.next_index += 1;
+        if let Some(hook) = &mut self.on_decode {
+            hook(index);
+        }
+
+        let page = self.read_page(index, frame).map(|mut page| {
+            if let Some(ref mut timings) = page.timings {
+                timings.decode = decode;
+            }
+            page
+        });
+        Some(page.with_context(|| {
+            format!(
+                "Échec de l'OCR de la page {} de '{}'",
+                index + 1,
+                self.path.display()
+            )
+        }))
+    }
+}
+
+/// Décode l'image `index` du TIFF, ou `None` s'il n'en contient pas autant.
+///
+/// Les images sont lues dans l'ordre : `index` vaut 0 au premier appel et
+/// augmente de 1 à chaque appel.
+fn next_frame(
+    decoder: &mut Decoder<BufReader<File>>,
+    index: usize,
+) -> Result<Option<DynamicImage>> {
+    if index > 0 {
+        if !decoder.more_images() {
+            return Ok(None);
+        }
+        decoder.next_image()?;
+    }
+    decode_frame(decoder).map(Some)
+}
+
+/// Convertit l'image courante du décodeur TIFF en [`DynamicImage`].
+///
+/// Formats pris en charge : niveaux de gris (1, 8 ou 16 bits, avec ou sans
+/// alpha sur 8 bits), RGB et RGBA sur 8 ou 16 bits.
+fn decode_frame<R: std::io::Read + std::io::Seek>(
+    decoder: &mut Decoder<R>,
+) -> Result<DynamicImage> {
+    let (width, height) = decoder.dimensions()?;
+    let color_type = decoder.colortype()?;
+    let data = decoder.read_image()?;
+
+    let image = match (color_type, data) {
+        (tiff::ColorType::Gray(1), DecodingResult::U8(packed)) => {
+            DynamicImage::ImageLuma8(unpack_bilevel(width, height, &packed)?)
+        }
+        (tiff::ColorType::Gray(8), DecodingResult::U8(data)) => {
+            DynamicImage::ImageLuma8(frame_buffer(width, height, data)?)
+        }
+        (tiff::ColorType::Gray(16), DecodingResult::U16(data)) => {
+            DynamicImage::ImageLuma16(frame_buffer(width, height, data)?)
+        }
+        (tiff::ColorType::GrayA(8), DecodingResult::U8(data)) => {
+            DynamicImage::ImageLumaA8(frame_buffer(width, height, data)?)
+        }
+        (tiff::ColorType::RGB(8), DecodingResult::U8(data)) => {
+            DynamicImage::ImageRgb8(frame_buffer(width, height, data)?)
+        }
+        (tiff::ColorType::RGBA(8), DecodingResult::U8(data)) => {
+            DynamicImage::ImageRgba8(frame_buffer(width, height, data)?)
+        }
+        (tiff::ColorType::RGB(16), DecodingResult::U16(data)) => {
+            DynamicImage::ImageRgb16(frame_buffer(width, height, data)?)
+        }
+        (tiff::ColorType::RGBA(16), DecodingResult::U16(data)) => {
+            DynamicImage::ImageRgba16(frame_buffer(width, height, data)?)
+        }
+        (other, _) => anyhow::bail!("Format de page TIFF non pris en charge : {:?}", other),
+    };
+    Ok(image)
+}
+
+/// Construit le tampon d'une page à partir de ses échantillons.
+fn frame_buffer<P: Pixel>(
+    width: u32,
+    height: u32,
+    data: Vec<P::Subpixel>,
+) -> Result<ImageBuffer<P, Vec<P::Subpixel>>> {
+    ImageBuffer::from_raw(width, height, data).context("Données de page TIFF incomplètes")
+}
+
+/// Développe une page bitonale (1 bit par pixel, lignes alignées sur l'octet).
+///
+/// Le décodeur a déjà ramené l'interprétation photométrique à « 1 = blanc ».
+fn unpack_bilevel(width: u32, height: u32, packed: &[u8]) -> Result<image::GrayImage> {
+    let row_bytes = (width as usize).div_ceil(8);
+    if packed.len() < row_bytes * height as usize {
+        anyhow::bail!("Données de page TIFF incomplètes");
+    }
+    Ok(image::GrayImage::from_fn(width, height, |x, y| {
+        let byte = packed[y as usize * row_bytes + x as usize / 8];
+        let bit = (byte >> (7 - x % 8)) & 1;
+        image::Luma([if bit == 1 { 255 } else { 0 }])
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OcrConfig;
+    use tiff::encoder::{TiffEncoder, colortype};
+
+    /// Écrit un TIFF d'une page par largeur, de 12 pixels de haut.
+    fn write_tiff(path: &Path, widths: &[u32]) {
+        let mut encoder = TiffEncoder::new(File::create(path).unwrap()).unwrap();
+        for (page, &width) in widths.iter().enumerate() {
+            let data = vec![(page as u8) * 60; (width * 12) as usize];
+            encoder
+                .write_image::<colortype::Gray8>(width, 12, &data)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_tiff_frames_are_decoded_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scan.tiff");
+        write_tiff(&path, &[10, 20, 30]);
+
+        let mut decoder = Decoder::new(BufReader::new(File::open(&path).unwrap())).unwrap();
+        let mut frames = Vec::new();
+        while let Some(frame) = next_frame(&mut decoder, frames.len()).unwrap() {
+            frames.push(frame);
+        }
+
+        let widths: Vec<u32> = frames.iter().map(|frame| frame.width()).collect();
+        assert_eq!(widths, [10, 20, 30]);
+        assert_eq!(frames[2].to_luma8().get_pixel(0, 0)[0], 120);
+    }
+
+    #[test]
+    fn test_pages_yield_every_tiff_frame() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scan.tiff");
+        write_tiff(&path, &[10, 20, 30]);
+        let engine = OcrEngine::new(OcrConfig::default()).unwrap();
+
+        let mut decoded = Vec::new();
+        let indices: Vec<usize> = engine
+            .pages(&path)
+            .unwrap()
+            .on_decode(|index| decoded.push(index))
+            .map(|page| page.unwrap().index)
+            .collect();
+
+        assert_eq!(indices, [0, 1, 2]);
+        assert_eq!(decoded, [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_dropped_iterator_decodes_no_further_page() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scan.tiff");
+        write_tiff(&path, &[10, 20, 30]);
+        let engine = OcrEngine::new(OcrConfig::default()).unwrap();
+
+        let mut decoded = 0;
+        {
+            let mut pages = engine.pages(&path).unwrap().on_decode(|_| decoded += 1);
+            assert!(pages.next().is_some());
+        }
+
+        assert_eq!(decoded, 1);
+    }
+
+    #[test]
+    fn test_single_png_is_one_page() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("page.png");
+        image::GrayImage::from_pixel(8, 8, image::Luma([255]))
+            .save(&path)
+            .unwrap();
+        let engine = OcrEngine::new(OcrConfig::default()).unwrap();
+
+        let mut decoded = Vec::new();
+        let pages: Vec<_> = engine
+            .pages(&path)
+            .unwrap()
+            .on_decode(|index| decoded.push(index))
+            .collect();
+
+        assert_eq!(pages.len(), 1);
+        assert_eq!(decoded, [0]);
+        assert_eq!(pages[0].as_ref().unwrap().index, 0);
+    }
+
+    #[test]
+    fn test_unpack_bilevel() {
+        // 10 pixels par ligne : 2 octets, les 6 derniers bits inutilisés
+        let packed = [0b1010_0000, 0b1100_0000, 0xFF, 0x00];
+        let image = unpack_bilevel(10, 2, &packed).unwrap();
+
+        let row: Vec<u8> = (0..10).map(|x| image.get_pixel(x, 0)[0]).collect();
+        assert_eq!(row, [255, 0, 255, 0, 0, 0, 0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(9, 1)[0], 0);
+        assert!(unpack_bilevel(10, 3, &packed).is_err());
+    }
+
+    #[test]
+    fn test_missing_file_is_an_error() {
+        let engine = OcrEngine::new(OcrConfig::default()).unwrap();
+        assert!(engine.pages(Path::new("absent.tiff")).is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//! Chunks PNG écrits à la main.
+//!
+//! Les [métadonnées](crate::metadata) (`iTXt`) et l'enregistrement avec DPI
+//! du [prétraitement](crate::preprocessing) (`pHYs`) insèrent leurs propres
+//! chunks dans un flux PNG déjà encodé.
+
+use anyhow::{Context, Result};
+
+/// Encode un chunk PNG (longueur, type, données, CRC).
+///
+/// # Erreurs
+///
+/// Retourne une erreur si les données dépassent la longueur d'un chunk.
+pub(crate) fn chunk(kind: &[u8; 4], data: &[u8]) -> Result<Vec<u8>> {
+    let length = u32::try_from(data.len()).context("Chunk PNG trop long")?;
+    let mut chunk = Vec::with_capacity(data.len() + 12);
+    chunk.extend_from_slice(&length.to_be_bytes());
+    chunk.extend_from_slice(kind);
+    chunk.extend_from_slice(data);
+    let crc = crc32(&chunk[4..]);
+    chunk.extend_from_slice(&crc.to_be_bytes());
+    Ok(chunk)
+}
+
+/// CRC-32 (polynôme IEEE réfléchi 0xEDB88320) utilisé par les chunks PNG.
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_known_values() {
+        // Valeur de référence du CRC-32 sur "123456789"
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+    }
+
+    #[test]
+    fn test_chunk_layout() {
+        let chunk = chunk(b"IEND", &[]).unwrap();
+        assert_eq!(
+            chunk,
+            [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]
+        );
+    }
+}
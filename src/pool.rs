@@ -0,0 +1,784 @@
+//! Pool de moteurs OCR pour l'extraction concurrente.
+//!
+//! [`OcrEngine`] est `Send + Sync` : il ne contient que sa configuration et
+//! initialise une instance Tesseract à chaque extraction. Le partager
+//! derrière un `Arc` est donc sûr, mais rien ne borne alors le nombre
+//! d'instances Tesseract actives simultanément (chacune charge ses données
+//! linguistiques en mémoire).
+//!
+//! [`OcrEnginePool`] rend cette limite explicite : au plus `max_workers`
+//! workers existent, chacun utilisé par un seul thread à la fois via
+//! [`PooledEngine`]. Lorsque tous les workers sont occupés, [`OcrEnginePool::get()`]
+//! bloque ou retourne une erreur selon la [`PoolExhaustion`] configurée.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::pool::OcrEnginePool;
+//! use std::path::PathBuf;
+//!
+//! let pool = OcrEnginePool::new(OcrConfig::default(), None, 4)?;
+//! let images = vec![PathBuf::from("a.png"), PathBuf::from("b.png")];
+//! for result in pool.batch_extract(&images) {
+//!     println!("{}", result?.text);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::cancellation::CancellationToken;
+use crate::config::OcrConfig;
+use crate::ocr::{OcrEngine, OcrError, OcrOutcome};
+use crate::preprocessing::PreprocessingConfig;
+use anyhow::{Result, anyhow};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+/// Worker capable d'extraire le texte d'une image, géré par un [`OcrEnginePool`].
+///
+/// Implémenté par [`OcrEngine`]. Les tests peuvent fournir leur propre
+/// implémentation pour vérifier le comportement du pool sans Tesseract.
+pub trait OcrWorker: Send {
+    /// Extrait le texte d'une image.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    fn extract(&mut self, path: &Path) -> Result<OcrOutcome>;
+
+    /// Prépare le worker avant sa première extraction.
+    ///
+    /// Ne fait rien par défaut. Appelé par [`OcrEnginePool::warm_up()`].
+    fn warm_up(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl OcrWorker for OcrEngine {
+    fn extract(&mut self, path: &Path) -> Result<OcrOutcome> {
+        self.extract_text_detailed(path)
+    }
+
+    /// Voir [`OcrEngine::warm_up()`].
+    fn warm_up(&mut self) -> Result<()> {
+        OcrEngine::warm_up(self).map(|_| ())
+    }
+}
+
+/// Comportement de [`OcrEnginePool::get()`] lorsque tous les workers sont occupés.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoolExhaustion {
+    /// Attendre qu'un worker soit rendu au pool.
+    #[default]
+    Block,
+
+    /// Retourner immédiatement une erreur.
+    Error,
+}
+
+/// Fabrique de workers d'un pool.
+type WorkerFactory<W> = Box<dyn Fn() -> Result<W> + Send + Sync>;
+
+/// État partagé du pool, protégé par un `Mutex`.
+struct PoolState<W> {
+    /// Workers disponibles.
+    idle: Vec<W>,
+    /// Nombre de workers créés (disponibles, empruntés ou en cours de création).
+    created: usize,
+}
+
+/// Pool borné de workers OCR partageable entre threads.
+///
+/// Les workers sont créés à la demande, jusqu'à `max_workers`, puis
+/// réutilisés. Le pool est `Send + Sync` et s'utilise typiquement derrière
+/// un `Arc`.
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::config::OcrConfig;
+/// use text_recognition::pool::{OcrEnginePool, PoolExhaustion};
+/// use std::path::Path;
+///
+/// let pool = OcrEnginePool::new(OcrConfig::default(), None, 2)?
+///     .with_exhaustion(PoolExhaustion::Error);
+///
+/// let mut engine = pool.get()?;
+/// let outcome = engine.extract_text_detailed(Path::new("image.png"))?;
+/// println!("{}", outcome.text);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct OcrEnginePool<W: OcrWorker = OcrEngine> {
+    factory: WorkerFactory<W>,
+    max_workers: usize,
+    exhaustion: PoolExhaustion,
+    state: Mutex<PoolState<W>>,
+    available: Condvar,
+}
+
+impl OcrEnginePool<OcrEngine> {
+    /// Crée un pool de moteurs OCR partageant la même configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Configuration OCR de chaque worker
+    /// * `preprocessing_config` - Prétraitement appliqué par chaque worker, si besoin
+    /// * `max_workers` - Nombre maximal de workers (et donc d'instances Tesseract simultanées)
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si `max_workers` vaut 0.
+    pub fn new(
+        config: OcrConfig,
+        preprocessing_config: Option<PreprocessingConfig>,
+        max_workers: usize,
+    ) -> Result<Self> {
+        let engine = match preprocessing_config {
+            Some(preprocessing_config) => {
+                OcrEngine::with_preprocessing(config, preprocessing_config)?
+            }
+            None => OcrEngine::new(config)?,
+        };
+        Self::from_engine(engine, max_workers)
+    }
+
+    /// Crée un pool dont chaque worker est une copie du moteur fourni.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - Moteur configuré servant de modèle
+    /// * `max_workers` - Nombre maximal de workers
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si `max_workers` vaut 0.
+    pub fn from_engine(engine: OcrEngine, max_workers: usize) -> Result<Self> {
+        Self::with_factory(max_workers, move || Ok(engine.clone()))
+    }
+}
+
+impl<W: OcrWorker> OcrEnginePool<W> {
+    /// Crée un pool dont les workers sont construits par `factory`.
+    ///
+    /// `factory` est appelée à la demande, au plus `max_workers` fois en
+    /// l'absence d'échec.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_workers` - Nombre maximal de workers
+    /// * `factory` - Fonction de création d'un worker
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si `max_workers` vaut 0.
+    pub fn with_factory(
+        max_workers: usize,
+        factory: impl Fn() -> Result<W> + Send + Sync + 'static,
+    ) -> Result<Self> {
+        if max_workers == 0 {
+            anyhow::bail!("Un pool OCR doit contenir au moins un worker");
+        }
+
+        Ok(Self {
+            factory: Box::new(factory),
+            max_workers,
+            exhaustion: PoolExhaustion::default(),
+            state: Mutex::new(PoolState {
+                idle: Vec::new(),
+                created: 0,
+            }),
+            available: Condvar::new(),
+        })
+    }
+
+    /// Définit le comportement de [`get()`](Self::get) lorsque le pool est épuisé.
+    pub fn with_exhaustion(mut self, exhaustion: PoolExhaustion) -> Self {
+        self.exhaustion = exhaustion;
+        self
+    }
+
+    /// Nombre maximal de workers du pool.
+    pub fn max_workers(&self) -> usize {
+        self.max_workers
+    }
+
+    /// Emprunte un worker, rendu automatiquement au pool à la fin de sa portée.
+    ///
+    /// Réutilise un worker disponible, sinon en crée un si la limite n'est pas
+    /// atteinte. Dans le cas contraire, bloque ou échoue selon la
+    /// [`PoolExhaustion`] configurée.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si :
+    /// - Le pool est épuisé avec [`PoolExhaustion::Error`]
+    /// - La création d'un worker échoue
+    pub fn get(&self) -> Result<PooledEngine<'_, W>> {
+        self.acquire(self.exhaustion)
+    }
+
+    /// Crée tous les workers du pool et préchauffe chacun une fois.
+    ///
+    /// À appeler au démarrage d'un service : les workers sont sinon créés à
+    /// la demande, et la première requête de chacun paie l'initialisation de
+    /// Tesseract. Attend que les workers empruntés par d'autres threads soient
+    /// rendus.
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::config::OcrConfig;
+    /// use text_recognition::pool::OcrEnginePool;
+    ///
+    /// let pool = OcrEnginePool::new(OcrConfig::default(), None, 4)?;
+    /// pool.warm_up()?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si la création ou le préchauffage d'un worker
+    /// échoue (voir [`OcrEngine::warm_up()`]).
+    pub fn warm_up(&self) -> Result<()> {
+        // Garder chaque worker emprunté : le suivant est alors un autre worker
+        let mut workers = Vec::with_capacity(self.max_workers);
+        for index in 0..self.max_workers {
+            let mut worker = self.acquire(PoolExhaustion::Block)?;
+            worker
+                .warm_up()
+                .map_err(|e| e.context(format!("Échec du préchauffage du worker {}", index + 1)))?;
+            workers.push(worker);
+        }
+        Ok(())
+    }
+
+    /// Extrait le texte d'une image en empruntant un worker (appel bloquant).
+    ///
+    /// Attend toujours qu'un worker se libère, quelle que soit la
+    /// [`PoolExhaustion`] configurée. Cette méthode bloque le thread courant
+    /// pendant l'OCR : dans un runtime asynchrone, l'appeler depuis
+    /// `tokio::task::spawn_blocking` (ou équivalent) avec un `Arc<OcrEnginePool>`
+    /// pour ne pas bloquer les threads de l'exécuteur.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::config::OcrConfig;
+    /// use text_recognition::pool::OcrEnginePool;
+    /// use std::path::PathBuf;
+    /// use std::sync::Arc;
+    ///
+    /// let pool = Arc::new(OcrEnginePool::new(OcrConfig::default(), None, 2)?);
+    /// let path = PathBuf::from("image.png");
+    ///
+    /// // Avec tokio : tokio::task::spawn_blocking(move || pool.extract_text_blocking(&path))
+    /// let handle = std::thread::spawn({
+    ///     let pool = Arc::clone(&pool);
+    ///     move || pool.extract_text_blocking(&path)
+    /// });
+    /// let outcome = handle.join().expect("thread OCR interrompu")?;
+    /// println!("{}", outcome.text);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si la création du worker ou l'extraction échoue.
+    pub fn extract_text_blocking(&self, path: &Path) -> Result<OcrOutcome> {
+        self.acquire(PoolExhaustion::Block)?.extract(path)
+    }
+
+    /// Extrait le texte de plusieurs images en parallèle.
+    ///
+    /// Voir [`batch_extract_with()`](Self::batch_extract_with).
+    pub fn batch_extract(&self, paths: &[PathBuf]) -> Vec<Result<OcrOutcome>> {
+        self.batch_extract_with(paths, |worker, path| worker.extract(path))
+    }
+
+    /// Traite plusieurs images en parallèle avec une fonction d'extraction personnalisée.
+    ///
+    /// Au plus `max_workers` threads traitent les images, chacun avec son
+    /// propre worker. L'échec d'une image n'interrompt pas les autres.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Images à traiter
+    /// * `extract` - Extraction à appliquer avec le worker emprunté
+    ///
+    /// # Retour
+    ///
+    /// Un résultat par image, dans l'ordre de `paths`.
+    pub fn batch_extract_with<F>(&self, paths: &[PathBuf], extract: F) -> Vec<Result<OcrOutcome>>
+    where
+        F: Fn(&mut W, &Path) -> Result<OcrOutcome> + Sync,
+    {
+        self.batch_extract_cancellable(paths, &CancellationToken::new(), extract)
+    }
+
+    /// Traite plusieurs images en parallèle jusqu'à l'annulation de `token`.
+    ///
+    /// Identique à [`batch_extract_with()`](Self::batch_extract_with), mais
+    /// aucune nouvelle image n'est commencée une fois le jeton annulé : les
+    /// images en cours se terminent (ou s'arrêtent entre deux étapes si le
+    /// moteur partage le jeton, voir [`OcrEngine::with_cancellation()`]) et
+    /// les suivantes reçoivent [`OcrError::Cancelled`].
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Images à traiter
+    /// * `token` - Jeton d'annulation consulté avant chaque image
+    /// * `extract` - Extraction à appliquer avec le worker emprunté
+    ///
+    /// # Retour
+    ///
+    /// Un résultat par image, dans l'ordre de `paths`.
+    pub fn batch_extract_cancellable<F>(
+        &self,
+        paths: &[PathBuf],
+        token: &CancellationToken,
+        extract: F,
+    ) -> Vec<Result<OcrOutcome>>
+    where
+        F: Fn(&mut W, &Path) -> Result<OcrOutcome> + Sync,
+    {
+        let next = AtomicUsize::new(0);
+        let thread_count = self.max_workers.min(paths.len());
+
+        let per_thread: Vec<Vec<(usize, Result<OcrOutcome>)>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..thread_count)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut results = Vec::new();
+                        while !token.is_cancelled() {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(path) = paths.get(index) else {
+                                break;
+                            };
+                            let result = self
+                                .acquire(PoolExhaustion::Block)
+                                .and_then(|mut worker| extract(&mut worker, path));
+                            results.push((index, result));
+                        }
+                        results
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        });
+
+        let mut slots: Vec<Option<Result<OcrOutcome>>> = paths.iter().map(|_| None).collect();
+        for (index, result) in per_thread.into_iter().flatten() {
+            slots[index] = Some(result);
+        }
+
+        slots
+            .into_iter()
+            .zip(paths)
+            .map(|(slot, path)| {
+                slot.unwrap_or_else(|| {
+                    if token.is_cancelled() {
+                        Err(OcrError::Cancelled.into())
+                    } else {
+                        Err(anyhow!("Image non traitée: {}", path.display()))
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Emprunte un worker selon la politique d'épuisement donnée.
+    fn acquire(&self, exhaustion: PoolExhaustion) -> Result<PooledEngine<'_, W>> {
+        let mut state = self.lock_state();
+
+        loop {
+            if let Some(worker) = state.idle.pop() {
+                return Ok(PooledEngine {
+                    pool: self,
+                    worker: Some(worker),
+                });
+            }
+
+            if state.created < self.max_workers {
+                // Réserver la place avant de relâcher le verrou : la création
+                // (initialisation Tesseract) peut être longue
+                state.created += 1;
+                drop(state);
+
+                return match (self.factory)() {
+                    Ok(worker) => Ok(PooledEngine {
+                        pool: self,
+                        worker: Some(worker),
+                    }),
+                    Err(e) => {
+                        self.lock_state().created -= 1;
+                        self.available.notify_one();
+                        Err(e.context("Échec de la création d'un worker OCR"))
+                    }
+                };
+            }
+
+            match exhaustion {
+                PoolExhaustion::Error => anyhow::bail!(
+                    "Pool OCR épuisé : les {} workers sont déjà utilisés",
+                    self.max_workers
+                ),
+                PoolExhaustion::Block => {
+                    state = self
+                        .available
+                        .wait(state)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+        }
+    }
+
+    /// Verrouille l'état du pool.
+    ///
+    /// L'état reste cohérent même si un thread a paniqué en le modifiant
+    /// (seules des opérations atomiques sur `idle` et `created` y sont faites).
+    fn lock_state(&self) -> MutexGuard<'_, PoolState<W>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Rend un worker au pool et réveille un thread en attente.
+    fn release(&self, worker: W) {
+        self.lock_state().idle.push(worker);
+        self.available.notify_one();
+    }
+}
+
+impl<W: OcrWorker> fmt::Debug for OcrEnginePool<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock_state();
+        f.debug_struct("OcrEnginePool")
+            .field("max_workers", &self.max_workers)
+            .field("exhaustion", &self.exhaustion)
+            .field("created", &state.created)
+            .field("idle", &state.idle.len())
+            .finish()
+    }
+}
+
+/// Worker emprunté à un [`OcrEnginePool`], rendu au pool à sa destruction.
+///
+/// Donne accès au worker par déréférencement : avec le pool par défaut,
+/// toutes les méthodes de [`OcrEngine`] sont disponibles.
+pub struct PooledEngine<'a, W: OcrWorker = OcrEngine> {
+    pool: &'a OcrEnginePool<W>,
+    worker: Option<W>,
+}
+
+impl<W: OcrWorker> PooledEngine<'_, W> {
+    /// Extrait le texte d'une image avec ce worker.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin vers l'image à analyser
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne les erreurs d'extraction du worker.
+    pub fn extract(&mut self, path: &Path) -> Result<OcrOutcome> {
+        self.deref_mut().extract(path)
+    }
+}
+
+impl<W: OcrWorker> Deref for PooledEngine<'_, W> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        // `worker` n'est vidé que dans `drop`
+        self.worker
+            .as_ref()
+            .unwrap_or_else(|| unreachable!("worker rendu au pool"))
+    }
+}
+
+impl<W: OcrWorker> DerefMut for PooledEngine<'_, W> {
+    fn deref_mut(&mut self) -> &mut W {
+        self.worker
+            .as_mut()
+            .unwrap_or_else(|| unreachable!("worker rendu au pool"))
+    }
+}
+
+impl<W: OcrWorker> Drop for PooledEngine<'_, W> {
+    fn drop(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            self.pool.release(worker);
+        }
+    }
+}
+
+impl<W: OcrWorker> fmt::Debug for PooledEngine<'_, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PooledEngine")
+            .field("max_workers", &self.pool.max_workers)
+            .finish()
+    }
+}
+
+/// Garantit que le moteur peut être partagé entre threads.
+///
+/// Une régression (ajout d'un `Rc` ou d'un `RefCell` dans `OcrEngine`)
+/// casse la compilation plutôt que les utilisateurs du pool.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<OcrEngine>();
+    assert_send_sync::<OcrEnginePool>();
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+    use std::thread;
+    use std::time::Duration;
+
+    /// Compteur de valeur courante et de valeur maximale atteinte.
+    #[derive(Default)]
+    struct Gauge {
+        current: AtomicUsize,
+        max: AtomicUsize,
+    }
+
+    impl Gauge {
+        fn enter(&self) {
+            let value = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max.fetch_max(value, Ordering::SeqCst);
+        }
+
+        fn exit(&self) {
+            self.current.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Worker simulé : retourne le nom du fichier après une courte pause.
+    struct FakeWorker {
+        busy: Arc<Gauge>,
+    }
+
+    impl OcrWorker for FakeWorker {
+        fn extract(&mut self, path: &Path) -> Result<OcrOutcome> {
+            self.busy.enter();
+            thread::sleep(Duration::from_millis(10));
+            self.busy.exit();
+            Ok(OcrOutcome {
+                text: path.display().to_string(),
+                warnings: Vec::new(),
+                preprocessing: None,
+                cache_hit: false,
+            })
+        }
+    }
+
+    /// Crée un pool de workers simulés et les compteurs associés.
+    fn fake_pool(max_workers: usize) -> (OcrEnginePool<FakeWorker>, Arc<Gauge>, Arc<Gauge>) {
+        let initializing = Arc::new(Gauge::default());
+        let busy = Arc::new(Gauge::default());
+
+        let pool = OcrEnginePool::with_factory(max_workers, {
+            let initializing = Arc::clone(&initializing);
+            let busy = Arc::clone(&busy);
+            move || {
+                initializing.enter();
+                thread::sleep(Duration::from_millis(20));
+                initializing.exit();
+                Ok(FakeWorker {
+                    busy: Arc::clone(&busy),
+                })
+            }
+        })
+        .unwrap();
+
+        (pool, initializing, busy)
+    }
+
+    #[test]
+    fn test_engine_and_pool_are_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<OcrEngine>();
+        assert_send_sync::<OcrEnginePool>();
+        assert_send_sync::<Arc<OcrEnginePool>>();
+    }
+
+    #[test]
+    fn test_eight_threads_share_pool_of_two() {
+        let (pool, initializing, busy) = fake_pool(2);
+        let pool = Arc::new(pool);
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let pool = Arc::clone(&pool);
+                thread::spawn(move || pool.extract_text_blocking(Path::new(&format!("{}.png", i))))
+            })
+            .collect();
+
+        for (i, handle) in handles.into_iter().enumerate() {
+            let outcome = handle.join().unwrap().unwrap();
+            assert_eq!(outcome.text, format!("{}.png", i));
+        }
+
+        assert!(initializing.max.load(Ordering::SeqCst) <= 2);
+        assert!(busy.max.load(Ordering::SeqCst) <= 2);
+        assert!(pool.lock_state().created <= 2);
+    }
+
+    #[test]
+    fn test_exhaustion_error_policy() {
+        let (pool, _, _) = fake_pool(2);
+        let pool = pool.with_exhaustion(PoolExhaustion::Error);
+
+        let first = pool.get().unwrap();
+        let _second = pool.get().unwrap();
+        let error = pool.get().unwrap_err();
+        assert!(error.to_string().contains("épuisé"));
+
+        // Un worker rendu est de nouveau disponible
+        drop(first);
+        assert!(pool.get().is_ok());
+    }
+
+    #[test]
+    fn test_batch_extract_preserves_order() {
+        let (pool, _, busy) = fake_pool(3);
+        let paths: Vec<PathBuf> = (0..10)
+            .map(|i| PathBuf::from(format!("{}.png", i)))
+            .collect();
+
+        let results = pool.batch_extract(&paths);
+
+        assert_eq!(results.len(), 10);
+        for (path, result) in paths.iter().zip(results) {
+            assert_eq!(result.unwrap().text, path.display().to_string());
+        }
+        assert!(busy.max.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn test_batch_cancellation_stops_after_current_item() {
+        let (pool, _, _) = fake_pool(1);
+        let token = CancellationToken::new();
+        let paths: Vec<PathBuf> = (0..5)
+            .map(|i| PathBuf::from(format!("{}.png", i)))
+            .collect();
+
+        // Annulation pendant le traitement de la première image
+        let results = pool.batch_extract_cancellable(&paths, &token, |worker, path| {
+            token.cancel();
+            worker.extract(path)
+        });
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].as_ref().unwrap().text, "0.png");
+        for result in &results[1..] {
+            let error = result.as_ref().unwrap_err();
+            assert_eq!(error.downcast_ref::<OcrError>(), Some(&OcrError::Cancelled));
+        }
+    }
+
+    #[test]
+    fn test_factory_failure_releases_slot() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let pool = OcrEnginePool::with_factory(1, {
+            let attempts = Arc::clone(&attempts);
+            move || {
+                if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                    anyhow::bail!("Tesseract indisponible");
+                }
+                Ok(FakeWorker {
+                    busy: Arc::new(Gauge::default()),
+                })
+            }
+        })
+        .unwrap()
+        .with_exhaustion(PoolExhaustion::Error);
+
+        assert!(pool.get().is_err());
+        assert!(pool.get().is_ok());
+    }
+
+    /// Worker simulé comptant ses préchauffages.
+    struct WarmWorker {
+        warm_ups: Arc<AtomicUsize>,
+        fail: bool,
+    }
+
+    impl OcrWorker for WarmWorker {
+        fn extract(&mut self, path: &Path) -> Result<OcrOutcome> {
+            Ok(OcrOutcome {
+                text: path.display().to_string(),
+                warnings: Vec::new(),
+                preprocessing: None,
+                cache_hit: false,
+            })
+        }
+
+        fn warm_up(&mut self) -> Result<()> {
+            self.warm_ups.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                anyhow::bail!("Mot de préchauffage non reconnu");
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_warm_up_creates_and_warms_every_worker() {
+        let warm_ups = Arc::new(AtomicUsize::new(0));
+        let pool = OcrEnginePool::with_factory(3, {
+            let warm_ups = Arc::clone(&warm_ups);
+            move || {
+                Ok(WarmWorker {
+                    warm_ups: Arc::clone(&warm_ups),
+                    fail: false,
+                })
+            }
+        })
+        .unwrap();
+
+        pool.warm_up().unwrap();
+        assert_eq!(warm_ups.load(Ordering::SeqCst), 3);
+        assert_eq!(pool.lock_state().created, 3);
+        assert_eq!(pool.lock_state().idle.len(), 3);
+
+        // Les workers préchauffés sont réutilisés sans nouveau préchauffage
+        drop(pool.get().unwrap());
+        assert_eq!(warm_ups.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_warm_up_failure_names_worker() {
+        let pool = OcrEnginePool::with_factory(2, || {
+            Ok(WarmWorker {
+                warm_ups: Arc::new(AtomicUsize::new(0)),
+                fail: true,
+            })
+        })
+        .unwrap();
+
+        let error = pool.warm_up().unwrap_err();
+        assert!(format!("{:#}", error).contains("worker 1"));
+    }
+
+    #[test]
+    fn test_zero_workers_rejected() {
+        assert!(OcrEnginePool::new(OcrConfig::default(), None, 0).is_err());
+    }
+}
@@ -0,0 +1,647 @@
+//! Post-traitement du texte produit par Tesseract.
+//!
+//! La sortie brute de Tesseract se termine par des retours à la ligne et un
+//! saut de page (form feed), conserve les césures de fin de ligne
+//! ("recon-\nnaissance") et les traits d'union conditionnels (U+00AD).
+//! [`TextCleanup`] regroupe des nettoyages activables individuellement.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::postprocess::TextCleanup;
+//!
+//! let cleanup = TextCleanup::full();
+//! let text = cleanup.apply("La recon-\nnaissance “optique”\n\n\n\nde caractères\n\x0c");
+//! assert_eq!(text, "La reconnaissance \"optique\"\n\nde caractères");
+//! ```
+//!
+//! # Score fusionné des mots
+//!
+//! La confiance de Tesseract prédit mal les erreurs : "rnodern" peut être lu
+//! avec une confiance de 90. [`rescore_words()`] la combine avec la
+//! plausibilité du mot dans un dictionnaire ([`Corrector`]) et range le
+//! résultat dans [`HocrWord::fused_score`] ;
+//! [`HocrDocument::low_score_words()`] liste ensuite les mots douteux.
+
+use crate::hocr::{HocrDocument, HocrWord};
+use crate::metrics::levenshtein_distance;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Trait d'union conditionnel (soft hyphen), invisible à l'affichage.
+const SOFT_HYPHEN: char = '\u{AD}';
+
+/// Options de nettoyage du texte extrait.
+///
+/// La valeur par défaut ne fait que supprimer les espaces de fin, ce qui
+/// préserve le texte reconnu. [`TextCleanup::full()`] active tous les
+/// nettoyages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextCleanup {
+    /// Supprimer les espaces de fin de ligne et de fin de texte (dont le saut de page final).
+    pub trim_trailing_whitespace: bool,
+
+    /// Recoller les mots coupés en fin de ligne ("recon-\nnaissance" → "reconnaissance").
+    ///
+    /// La fusion n'a lieu que si la suite commence par une minuscule :
+    /// "well-\nKnown" reste sur deux lignes. Un trait d'union conditionnel
+    /// en fin de ligne est toujours recollé.
+    pub dehyphenate_line_breaks: bool,
+
+    /// Réduire les suites de lignes vides à une seule ligne vide.
+    pub collapse_multiple_blank_lines: bool,
+
+    /// Supprimer les caractères de contrôle (sauf retour à la ligne et tabulation)
+    /// et les traits d'union conditionnels.
+    pub strip_control_chars: bool,
+
+    /// Remplacer les guillemets typographiques (‘ ’ “ ”) par leurs équivalents droits.
+    ///
+    /// Les guillemets français (« ») sont conservés.
+    pub normalize_quotes: bool,
+}
+
+impl Default for TextCleanup {
+    /// Crée un nettoyage limité à la suppression des espaces de fin.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::postprocess::TextCleanup;
+    ///
+    /// let cleanup = TextCleanup::default();
+    /// assert!(cleanup.trim_trailing_whitespace);
+    /// assert!(!cleanup.dehyphenate_line_breaks);
+    /// ```
+    fn default() -> Self {
+        Self {
+            trim_trailing_whitespace: true,
+            dehyphenate_line_breaks: false,
+            collapse_multiple_blank_lines: false,
+            strip_control_chars: false,
+            normalize_quotes: false,
+        }
+    }
+}
+
+impl TextCleanup {
+    /// Crée un nettoyage avec toutes les options activées.
+    pub fn full() -> Self {
+        Self {
+            trim_trailing_whitespace: true,
+            dehyphenate_line_breaks: true,
+            collapse_multiple_blank_lines: true,
+            strip_control_chars: true,
+            normalize_quotes: true,
+        }
+    }
+
+    /// Applique les nettoyages activés au texte.
+    ///
+    /// Les caractères de contrôle sont supprimés en premier pour que les
+    /// césures suivies d'un caractère parasite soient tout de même recollées.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Texte brut produit par Tesseract
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::postprocess::TextCleanup;
+    ///
+    /// assert_eq!(TextCleanup::default().apply("Bonjour  \n\n\x0c"), "Bonjour");
+    /// ```
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+
+        if self.strip_control_chars {
+            text = strip_control_chars(&text);
+        }
+        if self.dehyphenate_line_breaks {
+            text = dehyphenate_line_breaks(&text);
+        }
+        if self.normalize_quotes {
+            text = normalize_quotes(&text);
+        }
+        if self.trim_trailing_whitespace {
+            text = trim_trailing_whitespace(&text);
+        }
+        if self.collapse_multiple_blank_lines {
+            text = collapse_multiple_blank_lines(&text);
+        }
+
+        text
+    }
+}
+
+/// Supprime les espaces de fin de chaque ligne et de fin de texte.
+fn trim_trailing_whitespace(text: &str) -> String {
+    text.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+/// Recolle les mots coupés par une césure en fin de ligne.
+fn dehyphenate_line_breaks(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+
+    for line in text.split('\n') {
+        if let Some(previous) = lines.last_mut()
+            && let Some(stem) = hyphenated_stem(previous, line)
+        {
+            *previous = format!("{}{}", stem, line.trim_start());
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+
+    lines.join("\n")
+}
+
+/// Retourne le début du mot coupé si `line` termine par une césure que `next` complète.
+fn hyphenated_stem<'a>(line: &'a str, next: &str) -> Option<&'a str> {
+    let line = line.trim_end();
+    let next_char = next.trim_start().chars().next()?;
+
+    if let Some(stem) = line.strip_suffix(SOFT_HYPHEN) {
+        return Some(stem);
+    }
+
+    let stem = line.strip_suffix('-')?;
+    let ends_with_letter = stem.chars().last().is_some_and(char::is_alphabetic);
+
+    // "well-\nKnown" : la majuscule indique deux mots distincts
+    (ends_with_letter && next_char.is_lowercase()).then_some(stem)
+}
+
+/// Réduit les suites de lignes vides à une seule.
+fn collapse_multiple_blank_lines(text: &str) -> String {
+    let mut result: Vec<&str> = Vec::new();
+
+    for line in text.split('\n') {
+        let blank = line.trim().is_empty();
+        let previous_blank = result.last().is_some_and(|l| l.trim().is_empty());
+        if blank && previous_blank {
+            continue;
+        }
+        result.push(line);
+    }
+
+    result.join("\n")
+}
+
+/// Tronque un texte à `max_chars` caractères, sans couper un caractère multi-octets.
+///
+/// Contrairement à `&text[..n]`, qui panique si l'octet `n` tombe au milieu
+/// d'un caractère (« é », emoji...), la limite est comptée en caractères.
+///
+/// # Arguments
+///
+/// * `text` - Texte à tronquer
+/// * `max_chars` - Nombre maximal de caractères conservés
+///
+/// # Retour
+///
+/// Le préfixe de `text` d'au plus `max_chars` caractères (le texte entier s'il est plus court)
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::postprocess::truncate_chars;
+///
+/// assert_eq!(truncate_chars("Été chaud", 3), "Été");
+/// assert_eq!(truncate_chars("court", 200), "court");
+/// ```
+pub fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// Plausibilité d'un mot sans lettre (nombre, ponctuation) : ni bonne ni mauvaise.
+pub const NEUTRAL_PLAUSIBILITY: f32 = 0.5;
+
+/// Dictionnaire de mots servant à juger la plausibilité d'un mot reconnu.
+///
+/// Les mots sont comparés sans tenir compte de la casse.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::postprocess::Corrector;
+///
+/// let corrector = Corrector::from_words(["modern", "model"]);
+/// assert!(corrector.contains("Modern"));
+/// assert_eq!(corrector.nearest("rnodern"), Some(("modern", 2)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Corrector {
+    words: BTreeSet<String>,
+}
+
+impl Corrector {
+    /// Crée un dictionnaire à partir d'une liste de mots.
+    ///
+    /// Les entrées vides sont ignorées.
+    ///
+    /// # Arguments
+    ///
+    /// * `words` - Mots du dictionnaire
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let words = words
+            .into_iter()
+            .map(|word| word.as_ref().trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+        Self { words }
+    }
+
+    /// Charge un dictionnaire texte, un mot par ligne.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin du fichier
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le fichier ne peut pas être lu.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Impossible de lire le dictionnaire '{}'", path.display()))?;
+        Ok(Self::from_words(content.lines()))
+    }
+
+    /// Nombre de mots du dictionnaire.
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Indique si le dictionnaire est vide.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Indique si le mot figure dans le dictionnaire.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+
+    /// Mot du dictionnaire le plus proche et sa distance d'édition.
+    ///
+    /// À distance égale, le premier mot dans l'ordre alphabétique l'emporte.
+    /// Retourne `None` si le dictionnaire est vide.
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - Mot reconnu
+    pub fn nearest(&self, word: &str) -> Option<(&str, usize)> {
+        let word = word.to_lowercase();
+        let length = word.chars().count();
+        let mut best: Option<(&str, usize)> = None;
+
+        for candidate in &self.words {
+            // La distance est au moins l'écart de longueur
+            let gap = candidate.chars().count().abs_diff(length);
+            if best.is_some_and(|(_, distance)| gap >= distance) {
+                continue;
+            }
+            let distance = levenshtein_distance(&word, candidate);
+            if best.is_none_or(|(_, best_distance)| distance < best_distance) {
+                best = Some((candidate, distance));
+                if distance == 0 {
+                    break;
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Plausibilité d'un mot reconnu, entre 0 et 1.
+    ///
+    /// Vaut 1 pour un mot du dictionnaire et `1 / (1 + d)` pour un mot à une
+    /// distance d'édition `d` du mot le plus proche. La ponctuation
+    /// entourant le mot est ignorée ; un mot sans lettre (nombre,
+    /// ponctuation) ou un dictionnaire vide donne
+    /// [`NEUTRAL_PLAUSIBILITY`].
+    ///
+    /// # Arguments
+    ///
+    /// * `word` - Mot reconnu
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::postprocess::{Corrector, NEUTRAL_PLAUSIBILITY};
+    ///
+    /// let corrector = Corrector::from_words(["modern"]);
+    /// assert_eq!(corrector.plausibility("modern,"), 1.0);
+    /// assert_eq!(corrector.plausibility("rnodern"), 1.0 / 3.0);
+    /// assert_eq!(corrector.plausibility("1984"), NEUTRAL_PLAUSIBILITY);
+    /// ```
+    pub fn plausibility(&self, word: &str) -> f32 {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+        if !word.chars().any(char::is_alphabetic) {
+            return NEUTRAL_PLAUSIBILITY;
+        }
+        match self.nearest(word) {
+            Some((_, distance)) => 1.0 / (1.0 + distance as f32),
+            None => NEUTRAL_PLAUSIBILITY,
+        }
+    }
+}
+
+/// Poids du score fusionné de [`rescore_words()`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RescoreWeights {
+    /// Poids de la confiance Tesseract ramenée entre 0 et 1 (défaut : 0.5).
+    pub confidence: f32,
+    /// Poids de la plausibilité dans le dictionnaire (défaut : 0.5).
+    pub dictionary: f32,
+}
+
+impl Default for RescoreWeights {
+    fn default() -> Self {
+        Self {
+            confidence: 0.5,
+            dictionary: 0.5,
+        }
+    }
+}
+
+/// Calcule le score fusionné de chaque mot du document.
+///
+/// Score = `confidence × (confiance / 100) + dictionary × plausibilité`
+/// ([`Corrector::plausibility()`]), rangé dans [`HocrWord::fused_score`].
+/// Un mot sans confiance Tesseract compte pour une confiance de 50.
+///
+/// # Arguments
+///
+/// * `doc` - Document HOCR dont les mots sont notés
+/// * `dictionary` - Dictionnaire de référence
+/// * `weights` - Poids de la confiance et de la plausibilité
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::hocr::{BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord};
+/// use text_recognition::postprocess::{Corrector, RescoreWeights, rescore_words};
+///
+/// let mut line = HocrLine::new(BBox::new(0, 0, 100, 20));
+/// line.add_word(HocrWord::new(BBox::new(0, 0, 100, 20), "rnodern".into(), Some(90)));
+/// let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 100, 20));
+/// paragraph.add_line(line);
+/// let mut doc = HocrDocument::new();
+/// doc.add_paragraph(paragraph);
+///
+/// rescore_words(&mut doc, &Corrector::from_words(["modern"]), &RescoreWeights::default());
+/// assert_eq!(doc.low_score_words(0.7).len(), 1);
+/// ```
+pub fn rescore_words(doc: &mut HocrDocument, dictionary: &Corrector, weights: &RescoreWeights) {
+    let words = doc
+        .pages
+        .iter_mut()
+        .flat_map(|page| &mut page.paragraphs)
+        .flat_map(|paragraph| &mut paragraph.lines)
+        .flat_map(|line| &mut line.words);
+    for word in words {
+        word.fused_score = Some(fused_score(word, dictionary, weights));
+    }
+}
+
+/// Score fusionné d'un mot.
+fn fused_score(word: &HocrWord, dictionary: &Corrector, weights: &RescoreWeights) -> f32 {
+    let confidence = word
+        .confidence
+        .map_or(0.5, |value| f32::from(value) / 100.0);
+    weights.confidence * confidence + weights.dictionary * dictionary.plausibility(&word.text)
+}
+
+/// Supprime les caractères de contrôle (hors `\n` et `\t`) et les traits d'union conditionnels.
+///
+/// Les césures conditionnelles en fin de ligne sont conservées pour être
+/// recollées par [`dehyphenate_line_breaks`].
+fn strip_control_chars(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let keep = match c {
+            '\n' | '\t' => true,
+            SOFT_HYPHEN => chars.peek() == Some(&'\n'),
+            '\r' => false,
+            c => !c.is_control(),
+        };
+        if keep {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+/// Remplace les guillemets typographiques simples et doubles par des guillemets droits.
+fn normalize_quotes(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => '\'',
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => '"',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Nettoyage avec une seule option activée.
+    fn only(set: impl FnOnce(&mut TextCleanup)) -> TextCleanup {
+        let mut cleanup = TextCleanup {
+            trim_trailing_whitespace: false,
+            ..TextCleanup::default()
+        };
+        set(&mut cleanup);
+        cleanup
+    }
+
+    #[test]
+    fn test_trim_trailing_whitespace() {
+        let cleanup = only(|c| c.trim_trailing_whitespace = true);
+        assert_eq!(
+            cleanup.apply("  Ligne un   \nLigne deux\t\n\n\x0c"),
+            "  Ligne un\nLigne deux"
+        );
+    }
+
+    #[test]
+    fn test_dehyphenate_line_breaks() {
+        let cleanup = only(|c| c.dehyphenate_line_breaks = true);
+        assert_eq!(
+            cleanup.apply("La recon-\nnaissance est faite"),
+            "La reconnaissance est faite"
+        );
+        assert_eq!(cleanup.apply("recon- \n  naissance"), "reconnaissance");
+    }
+
+    #[test]
+    fn test_dehyphenate_keeps_capitalized_continuation() {
+        let cleanup = only(|c| c.dehyphenate_line_breaks = true);
+        assert_eq!(cleanup.apply("well-\nKnown"), "well-\nKnown");
+        assert_eq!(cleanup.apply("1939-\n1945"), "1939-\n1945");
+    }
+
+    #[test]
+    fn test_dehyphenate_soft_hyphen() {
+        let cleanup = only(|c| c.dehyphenate_line_breaks = true);
+        assert_eq!(cleanup.apply("recon\u{AD}\nNaissance"), "reconNaissance");
+    }
+
+    #[test]
+    fn test_collapse_multiple_blank_lines() {
+        let cleanup = only(|c| c.collapse_multiple_blank_lines = true);
+        assert_eq!(
+            cleanup.apply("un\n\n\n \n\ndeux\n\ntrois"),
+            "un\n\ndeux\n\ntrois"
+        );
+    }
+
+    #[test]
+    fn test_strip_control_chars() {
+        let cleanup = only(|c| c.strip_control_chars = true);
+        assert_eq!(cleanup.apply("a\x0cb\r\nc\td\u{7}e\u{AD}f"), "ab\nc\tdef");
+    }
+
+    #[test]
+    fn test_normalize_quotes() {
+        let cleanup = only(|c| c.normalize_quotes = true);
+        assert_eq!(
+            cleanup.apply("l’été “chaud” « doux »"),
+            "l'été \"chaud\" « doux »"
+        );
+    }
+
+    #[test]
+    fn test_truncate_chars() {
+        assert_eq!(truncate_chars("", 5), "");
+        assert_eq!(truncate_chars("abc", 0), "");
+        assert_eq!(truncate_chars("abc", 3), "abc");
+        // « é » occupe deux octets : &text[..1] paniquerait
+        assert_eq!(truncate_chars("éèê", 1), "é");
+        assert_eq!(truncate_chars("a😀b", 2), "a😀");
+
+        let long = "ü".repeat(250);
+        assert_eq!(truncate_chars(&long, 200).chars().count(), 200);
+    }
+
+    #[test]
+    fn test_default_only_trims() {
+        let raw = "Bonjour  le monde\n\nrecon-\nnaissance “test”\n\x0c";
+        assert_eq!(
+            TextCleanup::default().apply(raw),
+            "Bonjour  le monde\n\nrecon-\nnaissance “test”"
+        );
+    }
+
+    #[test]
+    fn test_full_combined() {
+        let raw = "Titre\u{7}\n\n\n\nLa recon-\r\nnaissance “optique”  \n\x0c";
+        assert_eq!(
+            TextCleanup::full().apply(raw),
+            "Titre\n\nLa reconnaissance \"optique\""
+        );
+    }
+
+    /// Document d'une ligne avec les mots (texte, confiance) donnés.
+    fn document(words: &[(&str, u8)]) -> HocrDocument {
+        use crate::hocr::{BBox, HocrLine, HocrParagraph};
+
+        let mut line = HocrLine::new(BBox::new(0, 0, 1000, 20));
+        for (index, (text, confidence)) in words.iter().enumerate() {
+            let x = index as u32 * 100;
+            line.add_word(HocrWord::new(
+                BBox::new(x, 0, 90, 20),
+                text.to_string(),
+                Some(*confidence),
+            ));
+        }
+        let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 1000, 20));
+        paragraph.add_line(line);
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph);
+        doc
+    }
+
+    fn scores(doc: &HocrDocument) -> Vec<f32> {
+        doc.paragraphs()
+            .flat_map(|paragraph| &paragraph.lines)
+            .flat_map(|line| &line.words)
+            .map(|word| word.fused_score.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_confident_misread_scores_below_plausible_word() {
+        let corrector = Corrector::from_words(["modern", "art"]);
+        let mut doc = document(&[("rnodern", 90), ("modern", 70)]);
+
+        rescore_words(&mut doc, &corrector, &RescoreWeights::default());
+
+        let scores = scores(&doc);
+        assert!(scores[0] < scores[1], "{:?}", scores);
+    }
+
+    #[test]
+    fn test_numbers_and_punctuation_are_neutral() {
+        let corrector = Corrector::from_words(["modern"]);
+
+        for word in ["1984", "42,50", "—", "%"] {
+            assert_eq!(
+                corrector.plausibility(word),
+                NEUTRAL_PLAUSIBILITY,
+                "{}",
+                word
+            );
+        }
+        assert_eq!(corrector.plausibility("(Modern)"), 1.0);
+        assert_eq!(
+            Corrector::default().plausibility("modern"),
+            NEUTRAL_PLAUSIBILITY
+        );
+    }
+
+    #[test]
+    fn test_low_score_words_uses_fused_score() {
+        let corrector = Corrector::from_words(["le", "musée", "moderne"]);
+        let mut doc = document(&[("Le", 60), ("rnusée", 95), ("moderne", 55), ("2024", 40)]);
+
+        // Confiance brute : les mots les moins sûrs de Tesseract
+        let raw: Vec<&str> = doc
+            .low_score_words(0.65)
+            .iter()
+            .map(|word| word.text.as_str())
+            .collect();
+        assert_eq!(raw, ["Le", "moderne", "2024"]);
+
+        rescore_words(&mut doc, &corrector, &RescoreWeights::default());
+        let fused: Vec<&str> = doc
+            .low_score_words(0.65)
+            .iter()
+            .map(|word| word.text.as_str())
+            .collect();
+        assert_eq!(fused, ["rnusée", "2024"]);
+    }
+}
@@ -3,11 +3,46 @@
 //! Ce module fournit des fonctions pour préparer les images avant l'extraction de texte
 //! avec Tesseract. Les opérations de prétraitement incluent :
 //!
-//! - Conversion en niveaux de gris
+//! - Conversion en niveaux de gris (pondération des composantes, fond des
+//!   pixels transparents, élimination d'une couleur : [`GrayscaleOptions`])
 //! - Binarisation (Otsu, seuil fixe, adaptative)
 //! - Ajustement de contraste
 //! - Débruitage
 //! - Correction de l'inclinaison (deskew)
+//! - Réduction des images trop grandes (`max_pixels`)
+//! - Suppression des petites taches après binarisation (`despeckle`)
+//!
+//! [`preprocess_image_with_report()`] retourne en plus les décisions prises
+//! (angle d'inclinaison détecté, seuil d'Otsu...) dans un [`PreprocessingReport`].
+//!
+//! # Erreurs d'étape
+//!
+//! Une étape qui échoue interrompt le pipeline avec une [`PreprocessingError`]
+//! (récupérable avec `downcast_ref`) nommant l'étape, son rang et les
+//! dimensions de l'image qu'elle a reçue. Une étape qui ne peut simplement
+//! pas s'appliquer (correction d'inclinaison d'une image minuscule) est
+//! ignorée et l'explique dans [`PreprocessingReport::warnings`].
+//!
+//! # Masque de contenu
+//!
+//! La correction d'inclinaison remplit de blanc les coins laissés vides par
+//! la rotation. Sur un scan sombre, ces pixels synthétiques faussent le seuil
+//! d'Otsu et la détection des pages blanches. [`preprocess_image_with_mask()`]
+//! retourne un masque de même taille que l'image : 255 pour les pixels issus
+//! de l'image source, 0 pour le remplissage. [`otsu_threshold_masked()`] et
+//! [`is_blank_page_masked()`] ignorent les pixels à 0 ; le pipeline lui-même
+//! calcule le seuil d'Otsu sur les seuls pixels source.
+//!
+//! # Doubles pages
+//!
+//! Un scan de livre contient souvent deux pages en vis-à-vis : lues comme
+//! une seule page, leurs lignes sont entremêlées. [`detect_page_split()`]
+//! repère la gouttière centrale et [`split_pages()`] coupe l'image en deux ;
+//! avec [`PreprocessingConfig::split_double_pages`], l'OCR lit la page de
+//! gauche puis celle de droite.
+//!
+//! Il fournit aussi [`save_with_dpi()`] pour écrire des fichiers PNG dont la
+//! résolution est lisible par le binaire `tesseract`.
 //!
 //! # Exemple
 //!
@@ -20,15 +55,21 @@
 //! let preprocessed = preprocess_image(&img, &config);
 //! ```
 
-use anyhow::Result;
-use image::{DynamicImage, GrayImage, imageops};
+use crate::config::changed;
+use crate::hocr::BBox;
+use crate::png;
+use anyhow::{Context, Result, anyhow};
+use image::{DynamicImage, GrayImage, ImageBuffer, ImageFormat, Luma, Rgb, RgbImage, imageops};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 /// Configuration pour le prétraitement d'images.
 ///
 /// Cette structure définit les paramètres à appliquer lors du prétraitement
 /// d'une image avant l'OCR.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PreprocessingConfig {
     /// Active la conversion en niveaux de gris
     pub to_grayscale: bool,
@@ -50,6 +91,78 @@ pub struct PreprocessingConfig {
 
     /// Active la correction de l'inclinaison
     pub deskew: bool,
+
+    /// Nombre maximal de pixels (largeur × hauteur) avant réduction automatique.
+    ///
+    /// Au-delà, l'image est réduite en conservant ses proportions afin d'éviter
+    /// des allocations de plusieurs Go et un OCR très lent. `0` désactive la limite.
+    #[serde(default = "default_max_pixels")]
+    pub max_pixels: u64,
+
+    /// Ne pas lancer l'OCR sur les pages blanches (voir [`is_blank_page()`]).
+    ///
+    /// Une page classée [`PageContent::Blank`] donne un texte vide et un
+    /// avertissement [`WarningKind::BlankPage`](crate::diagnostics::WarningKind::BlankPage),
+    /// sans appel à Tesseract.
+    #[serde(default)]
+    pub skip_blank_pages: bool,
+
+    /// Seuils de la détection des pages blanches.
+    #[serde(default)]
+    pub blank_detection: BlankDetectionOptions,
+
+    /// Couper les doubles pages (scans de livres) avant l'OCR (voir [`detect_page_split()`]).
+    ///
+    /// La détection porte sur l'image d'origine ; chaque page est ensuite
+    /// prétraitée et lue séparément, de gauche à droite.
+    #[serde(default)]
+    pub split_double_pages: bool,
+
+    /// Après la binarisation, effacer les composantes d'encre de moins de
+    /// N pixels (voir [`remove_small_components()`]).
+    ///
+    /// Sans effet si `binarize` est désactivé. `None` désactive le nettoyage.
+    #[serde(default)]
+    pub despeckle: Option<u32>,
+
+    /// Pondération, fond des pixels transparents et composante éliminée des
+    /// conversions en niveaux de gris du pipeline (voir [`to_grayscale_with()`]).
+    ///
+    /// Par défaut, conversion identique à [`to_grayscale()`].
+    #[serde(default)]
+    pub grayscale_options: GrayscaleOptions,
+
+    /// Garde-fous de la correction d'inclinaison (voir [`deskew_checked()`]).
+    #[serde(default)]
+    pub deskew_options: DeskewOptions,
+}
+
+/// Côté minimal (en pixels) d'une image pour que la correction d'inclinaison soit tentée.
+///
+/// En deçà, la variance des projections ne permet pas d'estimer un angle.
+/// Valeur par défaut de [`DeskewOptions::min_dimension`].
+pub const MIN_DESKEW_DIMENSION: u32 = 16;
+
+/// Nombre de pixels au-delà duquel l'angle d'inclinaison est cherché sur une copie réduite.
+///
+/// Valeur par défaut de [`DeskewOptions::detection_max_pixels`] : un
+/// mégapixel suffit à estimer l'angle au demi-degré près.
+pub const DEFAULT_DESKEW_DETECTION_PIXELS: u64 = 1_000_000;
+
+/// Gain relatif minimal de la variance de projection par rapport à 0° pour redresser.
+///
+/// Valeur par défaut de [`DeskewOptions::min_relative_improvement`].
+///
+/// Une image de texte inclinée de 2° gagne déjà environ 40 % ; un bruit pur
+/// culmine à 0°, l'interpolation lissant les autres angles.
+pub const DEFAULT_MIN_SKEW_IMPROVEMENT: f64 = 0.1;
+
+/// Limite par défaut du nombre de pixels : 40 mégapixels.
+pub const DEFAULT_MAX_PIXELS: u64 = 40_000_000;
+
+/// Valeur par défaut de `max_pixels` pour la désérialisation.
+fn default_max_pixels() -> u64 {
+    DEFAULT_MAX_PIXELS
 }
 
 impl Default for PreprocessingConfig {
@@ -62,10 +175,313 @@ impl Default for PreprocessingConfig {
             contrast_factor: 1.0,
             denoise: false,
             deskew: false,
+            max_pixels: DEFAULT_MAX_PIXELS,
+            skip_blank_pages: false,
+            blank_detection: BlankDetectionOptions::default(),
+            split_double_pages: false,
+            despeckle: None,
+            grayscale_options: GrayscaleOptions::default(),
+            deskew_options: DeskewOptions::default(),
+        }
+    }
+}
+
+/// Surface (en pixels) des taches effacées par les profils `balanced` et `best`.
+pub const PROFILE_DESPECKLE_AREA: u32 = 5;
+
+impl PreprocessingConfig {
+    /// Profil rapide : conversion en niveaux de gris seulement.
+    ///
+    /// Adapté aux captures d'écran et aux scans propres, déjà contrastés.
+    /// De l'ordre de 10 ms pour une page A4 à 300 DPI (build release).
+    pub fn fast() -> Self {
+        Self::default()
+    }
+
+    /// Profil équilibré : niveaux de gris, binarisation d'Otsu et suppression
+    /// des taches de moins de [`PROFILE_DESPECKLE_AREA`] pixels.
+    ///
+    /// Environ dix fois le temps de [`fast()`](Self::fast) (de l'ordre de
+    /// 0,1 s pour une page A4 à 300 DPI), toujours négligeable devant l'OCR.
+    pub fn balanced() -> Self {
+        Self {
+            binarize: true,
+            binarization_method: BinarizationMethod::Otsu,
+            despeckle: Some(PROFILE_DESPECKLE_AREA),
+            ..Self::default()
+        }
+    }
+
+    /// Profil précis : niveaux de gris, correction de l'inclinaison,
+    /// débruitage, binarisation adaptative et suppression des taches.
+    ///
+    /// Pour les scans de travers ou à l'éclairage inégal. Nettement plus
+    /// lent : de l'ordre de 15 s pour une page A4 à 300 DPI (build release),
+    /// dont l'essentiel pour la correction d'inclinaison qui essaie une série
+    /// d'angles, soit plus que l'OCR lui-même.
+    pub fn best() -> Self {
+        Self {
+            deskew: true,
+            denoise: true,
+            binarize: true,
+            binarization_method: BinarizationMethod::Adaptive,
+            despeckle: Some(PROFILE_DESPECKLE_AREA),
+            ..Self::default()
+        }
+    }
+
+    /// Configuration d'un profil de prétraitement.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::preprocessing::{PreprocessingConfig, PreprocessingProfile};
+    ///
+    /// let config = PreprocessingConfig::from_profile("best".parse()?);
+    /// assert!(config.deskew && config.binarize);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_profile(profile: PreprocessingProfile) -> Self {
+        match profile {
+            PreprocessingProfile::Fast => Self::fast(),
+            PreprocessingProfile::Balanced => Self::balanced(),
+            PreprocessingProfile::Best => Self::best(),
+        }
+    }
+
+    /// Applique une surcharge partielle : chaque champ renseigné de `patch`
+    /// remplace celui de la configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `patch` - Champs à remplacer ; les champs à `None` sont laissés intacts
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::preprocessing::{PreprocessingConfig, PreprocessingConfigPatch};
+    ///
+    /// let mut config = PreprocessingConfig::best();
+    /// config.apply_patch(&PreprocessingConfigPatch {
+    ///     deskew: Some(false),
+    ///     ..PreprocessingConfigPatch::default()
+    /// });
+    /// assert!(!config.deskew);
+    /// assert!(config.denoise);
+    /// ```
+    pub fn apply_patch(&mut self, patch: &PreprocessingConfigPatch) {
+        if let Some(to_grayscale) = patch.to_grayscale {
+            self.to_grayscale = to_grayscale;
+        }
+        if let Some(binarize) = patch.binarize {
+            self.binarize = binarize;
+        }
+        if let Some(method) = patch.binarization_method {
+            self.binarization_method = method;
+        }
+        if let Some(adjust_contrast) = patch.adjust_contrast {
+            self.adjust_contrast = adjust_contrast;
+        }
+        if let Some(contrast_factor) = patch.contrast_factor {
+            self.contrast_factor = contrast_factor;
+        }
+        if let Some(denoise) = patch.denoise {
+            self.denoise = denoise;
+        }
+        if let Some(deskew) = patch.deskew {
+            self.deskew = deskew;
+        }
+        if let Some(max_pixels) = patch.max_pixels {
+            self.max_pixels = max_pixels;
+        }
+        if let Some(skip_blank_pages) = patch.skip_blank_pages {
+            self.skip_blank_pages = skip_blank_pages;
+        }
+        if let Some(ref blank_detection) = patch.blank_detection {
+            self.blank_detection = blank_detection.clone();
+        }
+        if let Some(split_double_pages) = patch.split_double_pages {
+            self.split_double_pages = split_double_pages;
+        }
+        if let Some(despeckle) = patch.despeckle {
+            self.despeckle = despeckle;
+        }
+        if let Some(grayscale_options) = patch.grayscale_options {
+            self.grayscale_options = grayscale_options;
+        }
+        if let Some(ref deskew_options) = patch.deskew_options {
+            self.deskew_options = deskew_options.clone();
+        }
+    }
+}
+
+/// Surcharge partielle d'une [`PreprocessingConfig`].
+///
+/// Même principe que [`OcrConfigPatch`](crate::config::OcrConfigPatch) :
+/// chaque champ renseigné remplace celui de la configuration (voir
+/// [`PreprocessingConfig::apply_patch()`]), `despeckle` est doublement
+/// optionnel (`Some(None)` désactive la suppression des taches) et
+/// [`diff()`](Self::diff) calcule la surcharge entre deux configurations.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::preprocessing::{PreprocessingConfig, PreprocessingConfigPatch};
+///
+/// let patch: PreprocessingConfigPatch = toml::from_str("binarize = true\ndespeckle = 5\n")?;
+/// let mut config = PreprocessingConfig::default();
+/// config.apply_patch(&patch);
+/// assert_eq!(config, PreprocessingConfig::balanced());
+///
+/// assert_eq!(PreprocessingConfigPatch::diff(&PreprocessingConfig::default(), &config), patch);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreprocessingConfigPatch {
+    /// Conversion en niveaux de gris.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_grayscale: Option<bool>,
+    /// Binarisation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binarize: Option<bool>,
+    /// Méthode de binarisation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binarization_method: Option<BinarizationMethod>,
+    /// Ajustement du contraste.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adjust_contrast: Option<bool>,
+    /// Facteur de contraste.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contrast_factor: Option<f32>,
+    /// Débruitage.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub denoise: Option<bool>,
+    /// Correction de l'inclinaison.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deskew: Option<bool>,
+    /// Nombre maximal de pixels avant réduction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_pixels: Option<u64>,
+    /// Saut des pages blanches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_blank_pages: Option<bool>,
+    /// Seuils de la détection des pages blanches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blank_detection: Option<BlankDetectionOptions>,
+    /// Découpe des doubles pages.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split_double_pages: Option<bool>,
+    /// Surface maximale des taches supprimées.
+    #[serde(
+        deserialize_with = "crate::config::deserialize_present",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub despeckle: Option<Option<u32>>,
+    /// Options de conversion en niveaux de gris.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grayscale_options: Option<GrayscaleOptions>,
+    /// Garde-fous de la correction d'inclinaison.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deskew_options: Option<DeskewOptions>,
+}
+
+impl PreprocessingConfigPatch {
+    /// Calcule la surcharge qui transforme `a` en `b`.
+    ///
+    /// Seuls les champs qui diffèrent sont renseignés, avec la valeur de
+    /// `b` : `a.apply_patch(&PreprocessingConfigPatch::diff(&a, &b))` donne `b`.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Configuration de départ
+    /// * `b` - Configuration d'arrivée
+    pub fn diff(a: &PreprocessingConfig, b: &PreprocessingConfig) -> Self {
+        Self {
+            to_grayscale: changed(&a.to_grayscale, &b.to_grayscale),
+            binarize: changed(&a.binarize, &b.binarize),
+            binarization_method: changed(&a.binarization_method, &b.binarization_method),
+            adjust_contrast: changed(&a.adjust_contrast, &b.adjust_contrast),
+            contrast_factor: changed(&a.contrast_factor, &b.contrast_factor),
+            denoise: changed(&a.denoise, &b.denoise),
+            deskew: changed(&a.deskew, &b.deskew),
+            max_pixels: changed(&a.max_pixels, &b.max_pixels),
+            skip_blank_pages: changed(&a.skip_blank_pages, &b.skip_blank_pages),
+            blank_detection: changed(&a.blank_detection, &b.blank_detection),
+            split_double_pages: changed(&a.split_double_pages, &b.split_double_pages),
+            despeckle: changed(&a.despeckle, &b.despeckle),
+            grayscale_options: changed(&a.grayscale_options, &b.grayscale_options),
+            deskew_options: changed(&a.deskew_options, &b.deskew_options),
+        }
+    }
+
+    /// Indique si la surcharge ne modifie aucun champ.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Profil de prétraitement : compromis entre vitesse et précision.
+///
+/// Raccourci pour ne pas choisir soi-même les étapes : voir
+/// [`PreprocessingConfig::fast()`], [`balanced()`](PreprocessingConfig::balanced)
+/// et [`best()`](PreprocessingConfig::best). Dans un fichier de
+/// configuration, `preprocessing = "best"` tient lieu de section complète.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreprocessingProfile {
+    /// Niveaux de gris seulement.
+    Fast,
+    /// Niveaux de gris, Otsu et suppression des taches.
+    Balanced,
+    /// Toutes les étapes utiles aux scans difficiles.
+    Best,
+}
+
+impl PreprocessingProfile {
+    /// Tous les profils, du plus rapide au plus précis.
+    pub const ALL: [PreprocessingProfile; 3] = [
+        PreprocessingProfile::Fast,
+        PreprocessingProfile::Balanced,
+        PreprocessingProfile::Best,
+    ];
+
+    /// Nom du profil (`"fast"`, `"balanced"` ou `"best"`).
+    pub fn name(self) -> &'static str {
+        match self {
+            PreprocessingProfile::Fast => "fast",
+            PreprocessingProfile::Balanced => "balanced",
+            PreprocessingProfile::Best => "best",
         }
     }
 }
 
+impl fmt::Display for PreprocessingProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for PreprocessingProfile {
+    type Err = anyhow::Error;
+
+    /// Accepte le nom du profil, sans tenir compte de la casse.
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim().to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|profile| profile.name()).collect();
+                anyhow!(
+                    "Profil de prétraitement inconnu : '{}'. Valeurs acceptées : {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
 /// Méthode de binarisation pour convertir une image en noir et blanc.
 ///
 /// La binarisation transforme chaque pixel en noir ou blanc selon un seuil,
@@ -80,6 +496,13 @@ pub enum BinarizationMethod {
 
     /// Binarisation adaptative - seuil calculé localement
     Adaptive,
+
+    /// Otsu à trois classes - sépare texte, fond et niveau intermédiaire
+    ///
+    /// Adapté aux documents qui mêlent un troisième niveau de gris au texte
+    /// et au fond (tampon, surlignage, ombre) : la classe intermédiaire
+    /// rejoint le fond. Voir [`multi_otsu_thresholds()`].
+    MultiOtsu,
 }
 
 /// Orientation d'une image détectée par Tesseract (PSM 0).
@@ -117,6 +540,59 @@ impl Orientation {
     }
 }
 
+/// Informations d'orientation et de script retournées par Tesseract (PSM 0).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrientationInfo {
+    /// Orientation détectée (ligne `Orientation in degrees`).
+    pub orientation: Orientation,
+    /// Confiance de la détection d'orientation (ligne `Orientation confidence`).
+    pub orientation_confidence: Option<f32>,
+    /// Script détecté (ligne `Script`, ex: "Latin").
+    pub script: Option<String>,
+    /// Confiance de la détection de script (ligne `Script confidence`).
+    pub script_confidence: Option<f32>,
+}
+
+impl OrientationInfo {
+    /// Analyse la sortie de Tesseract en PSM 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `osd_output` - Sortie standard de `tesseract image stdout --psm 0`
+    ///
+    /// # Retour
+    ///
+    /// `None` si la sortie ne contient pas de ligne `Orientation in degrees`.
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::preprocessing::{Orientation, OrientationInfo};
+    ///
+    /// let osd = "Orientation in degrees: 90\nOrientation confidence: 4.2\nScript: Latin";
+    /// let info = OrientationInfo::from_osd_output(osd).unwrap();
+    /// assert_eq!(info.orientation, Orientation::Clockwise90);
+    /// assert_eq!(info.script.as_deref(), Some("Latin"));
+    /// ```
+    pub fn from_osd_output(osd_output: &str) -> Option<Self> {
+        let field = |name: &str| {
+            osd_output.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                (key.trim() == name).then(|| value.trim().to_string())
+            })
+        };
+
+        let degrees = field("Orientation in degrees")?.parse::<u32>().ok()?;
+
+        Some(Self {
+            orientation: Orientation::from_tesseract_degrees(degrees),
+            orientation_confidence: field("Orientation confidence").and_then(|v| v.parse().ok()),
+            script: field("Script").filter(|v| !v.is_empty()),
+            script_confidence: field("Script confidence").and_then(|v| v.parse().ok()),
+        })
+    }
+}
+
 /// Corrige l'orientation d'une image selon l'angle détecté.
 ///
 /// Applique une rotation de 90°, 180° ou 270° pour remettre l'image droite.
@@ -147,6 +623,138 @@ pub fn rotate_orientation(image: &DynamicImage, orientation: Orientation) -> Dyn
     }
 }
 
+/// Décisions prises par le pipeline de prétraitement.
+///
+/// Retourné par [`preprocess_image_with_report()`] pour comprendre un mauvais
+/// résultat OCR : rotation inattendue d'une page droite, seuil de
+/// binarisation aberrant sur un scan délavé, etc.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreprocessingReport {
+    /// Dimensions (largeur, hauteur) de l'image reçue.
+    pub input_dimensions: (u32, u32),
+
+    /// Dimensions (largeur, hauteur) de l'image produite.
+    pub output_dimensions: (u32, u32),
+
+    /// Étapes appliquées, dans l'ordre (voir [`preprocess_image_with_callback()`]).
+    pub applied_steps: Vec<String>,
+
+    /// Facteur d'échelle appliqué par les étapes de redimensionnement
+    /// (`downscale`), `1.0` si l'image n'a pas été redimensionnée.
+    ///
+    /// Les marges ajoutées par le redressement n'en font pas partie. La
+    /// résolution de l'image produite est celle de la source multipliée par
+    /// ce facteur (voir [`effective_dpi()`]) ; les coordonnées du hOCR s'y
+    /// ramènent avec [`HocrDocument::to_source_coordinates()`](crate::hocr::HocrDocument::to_source_coordinates).
+    pub scale_factor: f64,
+
+    /// Angle de correction détecté en degrés, si `deskew` est activé.
+    ///
+    /// Un angle inférieur à 0.1° en valeur absolue est ignoré (pas de rotation).
+    pub detected_skew_angle: Option<f64>,
+
+    /// Décision de la correction d'inclinaison, si `deskew` est activé
+    /// (voir [`deskew_checked()`]).
+    pub deskew: Option<DeskewOutcome>,
+
+    /// Seuil calculé par la méthode d'Otsu, si elle a été appliquée.
+    pub otsu_threshold: Option<u8>,
+
+    /// Facteur de contraste appliqué, si l'ajustement de contraste est activé.
+    pub contrast_factor_used: Option<f32>,
+
+    /// Indique si les couleurs de l'image ont été inversées.
+    ///
+    /// Aucune étape actuelle n'inverse l'image : la valeur est toujours `false`.
+    pub inverted: bool,
+
+    /// Avertissements non bloquants des étapes (étape ignorée, etc.).
+    pub warnings: Vec<String>,
+}
+
+impl fmt::Display for PreprocessingReport {
+    /// Affiche le rapport sur plusieurs lignes, pour le mode verbeux de la CLI.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (in_w, in_h) = self.input_dimensions;
+        let (out_w, out_h) = self.output_dimensions;
+        writeln!(f, "Prétraitement : {}x{} → {}x{}", in_w, in_h, out_w, out_h)?;
+
+        let steps = if self.applied_steps.is_empty() {
+            "aucune".to_string()
+        } else {
+            self.applied_steps.join(", ")
+        };
+        write!(f, "  Étapes : {}", steps)?;
+
+        if self.scale_factor != 1.0 {
+            write!(f, "\n  Facteur d'échelle : {:.3}", self.scale_factor)?;
+        }
+
+        if let Some(angle) = self.detected_skew_angle {
+            write!(f, "\n  Inclinaison détectée : {:.1}°", angle)?;
+        }
+        if let Some(outcome) = self.deskew
+            && !outcome.applied
+            && !matches!(outcome.reason, DeskewReason::TooSmall { .. })
+        {
+            write!(f, "\n  Redressement non appliqué : {}", outcome.reason)?;
+        }
+        if let Some(threshold) = self.otsu_threshold {
+            write!(f, "\n  Seuil d'Otsu : {}", threshold)?;
+        }
+        if let Some(factor) = self.contrast_factor_used {
+            write!(f, "\n  Facteur de contraste : {}", factor)?;
+        }
+        if self.inverted {
+            write!(f, "\n  Couleurs inversées")?;
+        }
+        for warning in &self.warnings {
+            write!(f, "\n  ⚠ {}", warning)?;
+        }
+        Ok(())
+    }
+}
+
+/// Échec d'une étape du pipeline de prétraitement.
+///
+/// Retournée dans l'erreur `anyhow` (récupérable avec `downcast_ref`) par
+/// les fonctions `preprocess_image*`, et portée par
+/// [`OcrError::Preprocessing`](crate::ocr::OcrError::Preprocessing) lors
+/// d'une extraction.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::preprocessing::PreprocessingError;
+///
+/// let error = PreprocessingError {
+///     step: "binarize".to_string(),
+///     index: 3,
+///     dimensions: (4032, 3024),
+///     reason: "fenêtre plus grande que l'image".to_string(),
+/// };
+/// assert_eq!(
+///     error.to_string(),
+///     "étape 3 (binarize) en échec sur une image 4032×3024 : fenêtre plus grande que l'image"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "étape {index} ({step}) en échec sur une image {}×{} : {reason}",
+    dimensions.0,
+    dimensions.1
+)]
+pub struct PreprocessingError {
+    /// Nom de l'étape (voir [`preprocess_image_with_callback()`]).
+    pub step: String,
+    /// Rang de l'étape parmi celles exécutées, à partir de 1.
+    pub index: usize,
+    /// Dimensions (largeur, hauteur) de l'image reçue par l'étape.
+    pub dimensions: (u32, u32),
+    /// Message de l'erreur d'origine, avec ses causes.
+    pub reason: String,
+}
+
 /// Applique un pipeline de prétraitement complet à une image.
 ///
 /// Cette fonction est le point d'entrée principal pour préparer une image
@@ -171,1268 +779,4977 @@ pub fn rotate_orientation(image: &DynamicImage, orientation: Orientation) -> Dyn
 ///
 /// # Erreurs
 ///
-/// Retourne une erreur si une étape du prétraitement échoue.
+/// Retourne une erreur si une étape du prétraitement échoue ; elle contient
+/// une [`PreprocessingError`] nommant l'étape.
 pub fn preprocess_image(
     image: &DynamicImage,
     config: &PreprocessingConfig,
 ) -> Result<DynamicImage> {
-    let mut img = image.clone();
-
-    // Conversion en niveaux de gris
-    if config.to_grayscale {
-        img = DynamicImage::ImageLuma8(to_grayscale(&img));
-    }
-
-    // Correction de l'inclinaison (deskew - avant les autres traitements)
-    if config.deskew {
-        let gray = img.to_luma8();
-        let deskewed = deskew(&gray);
-        img = DynamicImage::ImageLuma8(deskewed);
-    }
-
-    // Débruitage (avant ajustement de contraste et binarisation)
-    if config.denoise {
-        let gray = img.to_luma8();
-        let denoised = denoise(&gray);
-        img = DynamicImage::ImageLuma8(denoised);
-    }
-
-    // Ajustement de contraste (doit être fait avant la binarisation)
-    if config.adjust_contrast {
-        let gray = img.to_luma8();
-        let contrasted = adjust_contrast(&gray, config.contrast_factor);
-        img = DynamicImage::ImageLuma8(contrasted);
-    }
-
-    // Binarisation
-    if config.binarize {
-        let gray = img.to_luma8();
-        let binary = binarize(&gray, config.binarization_method);
-        img = DynamicImage::ImageLuma8(binary);
-    }
-
-    // Pipeline de prétraitement terminé
-
-    Ok(img)
+    preprocess_image_with_callback(image, config, |_, _| {})
 }
 
-/// Convertit une image en niveaux de gris.
+/// Applique le pipeline de prétraitement et retourne le rapport des décisions prises.
 ///
-/// Cette conversion simplifie l'image en conservant uniquement l'information
-/// de luminance, ce qui réduit le bruit de couleur et améliore la performance OCR.
+/// Identique à [`preprocess_image()`], qui ignore le rapport.
 ///
 /// # Arguments
 ///
-/// * `image` - L'image source à convertir
+/// * `image` - L'image source à prétraiter
+/// * `config` - Configuration du prétraitement
 ///
 /// # Exemple
 ///
-/// ```no_run
-/// use text_recognition::preprocessing::to_grayscale;
-/// use image::open;
+/// ```
+/// use image::{DynamicImage, GrayImage};
+/// use text_recognition::preprocessing::{PreprocessingConfig, preprocess_image_with_report};
 ///
-/// let img = open("color_document.png").unwrap();
-/// let gray = to_grayscale(&img);
+/// let img = DynamicImage::ImageLuma8(GrayImage::new(40, 20));
+/// let config = PreprocessingConfig {
+///     binarize: true,
+///     ..PreprocessingConfig::default()
+/// };
+/// let (_, report) = preprocess_image_with_report(&img, &config).unwrap();
+/// assert_eq!(report.applied_steps, ["grayscale", "binarize"]);
+/// assert!(report.otsu_threshold.is_some());
+/// assert_eq!(report.detected_skew_angle, None);
 /// ```
-pub fn to_grayscale(image: &DynamicImage) -> GrayImage {
-    image.to_luma8()
+///
+/// # Erreurs
+///
+/// Retourne une erreur si une étape du prétraitement échoue.
+pub fn preprocess_image_with_report(
+    image: &DynamicImage,
+    config: &PreprocessingConfig,
+) -> Result<(DynamicImage, PreprocessingReport)> {
+    let (img, report, _) = run_pipeline(image, config, |_, _| {})?;
+    Ok((img, report))
 }
 
-/// Ajuste le contraste d'une image en niveaux de gris.
+/// Applique le pipeline de prétraitement en notifiant chaque étape.
 ///
-/// Cette fonction applique une transformation linéaire aux valeurs des pixels
-/// pour augmenter ou diminuer le contraste. Un facteur > 1.0 augmente le contraste,
-/// tandis qu'un facteur < 1.0 le diminue.
+/// Identique à [`preprocess_image()`], mais appelle `on_stage` après chaque
+/// étape effectivement appliquée, avec le nom de l'étape et l'image obtenue.
+/// Utile pour visualiser ce que Tesseract reçoit réellement.
 ///
-/// La formule utilisée est : `new_value = ((old_value - 128) * factor) + 128`
-/// où 128 est la valeur pivot (gris moyen).
+/// Étapes, dans l'ordre (les étapes désactivées ne sont pas notifiées) :
+///
+/// 1. `downscale` - réduction (`max_pixels`), seulement si l'image dépasse la limite
+/// 2. `grayscale` - conversion en niveaux de gris
+/// 3. `deskew` - correction de l'inclinaison
+/// 4. `denoise` - débruitage
+/// 5. `contrast` - ajustement de contraste
+/// 6. `binarize` - binarisation
+/// 7. `despeckle` - suppression des petites taches (`despeckle`), après la binarisation
 ///
 /// # Arguments
 ///
-/// * `image` - L'image en niveaux de gris à traiter
-/// * `factor` - Le facteur de contraste (recommandé: 0.5 à 3.0)
-///   - 1.0 = pas de changement
-///   - > 1.0 = augmentation du contraste
-///   - < 1.0 = diminution du contraste
+/// * `image` - L'image source à prétraiter
+/// * `config` - Configuration du prétraitement
+/// * `on_stage` - Fonction appelée après chaque étape appliquée
 ///
 /// # Exemple
 ///
 /// ```no_run
-/// use text_recognition::preprocessing::{to_grayscale, adjust_contrast};
+/// use text_recognition::preprocessing::{PreprocessingConfig, preprocess_image_with_callback};
 /// use image::open;
 ///
-/// let img = open("low_contrast.png").unwrap();
-/// let gray = to_grayscale(&img);
-/// let enhanced = adjust_contrast(&gray, 1.5); // Augmente le contraste de 50%
+/// let img = open("document.png").unwrap();
+/// let config = PreprocessingConfig::default();
+/// let result = preprocess_image_with_callback(&img, &config, |stage, image| {
+///     println!("{} : {}x{}", stage, image.width(), image.height());
+/// })
+/// .unwrap();
 /// ```
-pub fn adjust_contrast(image: &GrayImage, factor: f32) -> GrayImage {
-    let mut output = image.clone();
-
-    for pixel in output.pixels_mut() {
-        let value = pixel[0] as f32;
-        // Appliquer la transformation de contraste autour du point pivot (128)
-        let new_value = ((value - 128.0) * factor) + 128.0;
-        // Clamper entre 0 et 255
-        pixel[0] = new_value.clamp(0.0, 255.0) as u8;
-    }
-
-    output
+///
+/// # Erreurs
+///
+/// Retourne une erreur si une étape du prétraitement échoue.
+pub fn preprocess_image_with_callback(
+    image: &DynamicImage,
+    config: &PreprocessingConfig,
+    on_stage: impl FnMut(&str, &DynamicImage),
+) -> Result<DynamicImage> {
+    Ok(run_pipeline(image, config, on_stage)?.0)
 }
 
-/// Applique un filtre de débruitage à une image en niveaux de gris.
-///
-/// Cette fonction utilise un filtre médian 3x3 pour réduire le bruit salt-and-pepper
-/// (poivre et sel) tout en préservant les contours. Le filtre médian remplace chaque
-/// pixel par la valeur médiane de son voisinage.
+/// Applique le pipeline de prétraitement et retourne le masque de contenu.
 ///
-/// Le filtre médian est particulièrement efficace pour :
-/// - Réduire le bruit impulsionnel (pixels isolés noirs ou blancs)
-/// - Préserver les contours et les détails du texte
-/// - Améliorer la qualité avant binarisation
+/// Le masque a les dimensions de l'image prétraitée : 255 pour les pixels
+/// issus de l'image source, 0 pour ceux ajoutés par la correction
+/// d'inclinaison. Sans rotation, le masque est entièrement à 255.
 ///
 /// # Arguments
 ///
-/// * `image` - L'image en niveaux de gris à débruiter
+/// * `image` - L'image source à prétraiter
+/// * `config` - Configuration du prétraitement
 ///
 /// # Exemple
 ///
-/// ```no_run
-/// use text_recognition::preprocessing::{to_grayscale, denoise};
-/// use image::open;
+/// ```
+/// use image::{DynamicImage, GrayImage, Luma};
+/// use text_recognition::preprocessing::{
+///     BlankDetectionOptions, PageContent, PreprocessingConfig, is_blank_page_masked,
+///     preprocess_image_with_mask,
+/// };
 ///
-/// let img = open("noisy_document.png").unwrap();
-/// let gray = to_grayscale(&img);
-/// let denoised = denoise(&gray);
+/// let img = DynamicImage::ImageLuma8(GrayImage::from_pixel(60, 40, Luma([90])));
+/// let (output, mask) = preprocess_image_with_mask(&img, &PreprocessingConfig::default())?;
+/// assert_eq!(mask.dimensions(), (output.width(), output.height()));
+/// assert!(mask.pixels().all(|pixel| pixel[0] == 255));
+///
+/// let result = is_blank_page_masked(&output.to_luma8(), &mask, &BlankDetectionOptions::default());
+/// assert_eq!(result.content, PageContent::Blank);
+/// # Ok::<(), anyhow::Error>(())
 /// ```
-pub fn denoise(image: &GrayImage) -> GrayImage {
-    let (width, height) = image.dimensions();
-    let mut output = image.clone();
+///
+/// # Erreurs
+///
+/// Retourne une erreur si une étape du prétraitement échoue.
+pub fn preprocess_image_with_mask(
+    image: &DynamicImage,
+    config: &PreprocessingConfig,
+) -> Result<(DynamicImage, GrayImage)> {
+    let (img, _, mask) = run_pipeline(image, config, |_, _| {})?;
+    let mask = mask
+        .unwrap_or_else(|| GrayImage::from_pixel(img.width(), img.height(), image::Luma([255])));
+    Ok((img, mask))
+}
 
-    // Appliquer un filtre médian 3x3
-    for y in 1..height - 1 {
-        for x in 1..width - 1 {
-            // Collecter les valeurs du voisinage 3x3
-            let mut neighbors = [0u8; 9];
-            let mut idx = 0;
+/// Exécute le pipeline de prétraitement en notifiant chaque étape et en construisant le rapport.
+///
+/// Retourne aussi le masque de contenu, `None` tant qu'aucune étape n'a
+/// ajouté de pixel synthétique (tous les pixels proviennent de la source).
+fn run_pipeline(
+    image: &DynamicImage,
+    config: &PreprocessingConfig,
+    on_stage: impl FnMut(&str, &DynamicImage),
+) -> Result<(DynamicImage, PreprocessingReport, Option<GrayImage>), PreprocessingError> {
+    let mut run = PipelineRun::new(image, on_stage);
+
+    // Réduction des images trop grandes (avant tout autre traitement, pour limiter la mémoire)
+    let mut img = run
+        .apply("downscale", image, |img, _| {
+            Ok(downscale_to_max_pixels(img, config.max_pixels))
+        })?
+        .unwrap_or_else(|| image.clone());
+    let scale_factor =
+        downscale_factor(image.width(), image.height(), config.max_pixels).unwrap_or(1.0);
 
-            for dy in 0..3 {
-                for dx in 0..3 {
-                    neighbors[idx] = image.get_pixel(x + dx - 1, y + dy - 1)[0];
-                    idx += 1;
+    // Conversion en niveaux de gris
+    if config.to_grayscale
+        && let Some(gray) = run.apply("grayscale", &img, |img, _| {
+            Ok(Some(DynamicImage::ImageLuma8(to_grayscale_with(
+                img,
+                &config.grayscale_options,
+            ))))
+        })?
+    {
+        img = gray;
+    }
+
+    // Correction de l'inclinaison (deskew - avant les autres traitements)
+    let mut skew_angle = None;
+    let mut deskew_outcome = None;
+    let mut content_mask = None;
+    if config.deskew
+        && let Some(deskewed) = run.apply("deskew", &img, |img, warnings| {
+            let (deskewed, mask, outcome) = deskew_checked_with_mask(
+                &to_grayscale_with(img, &config.grayscale_options),
+                &config.deskew_options,
+            );
+            deskew_outcome = Some(outcome);
+            if let DeskewReason::TooSmall { .. } = outcome.reason {
+                warnings.push(format!("deskew ignoré : {}", outcome.reason));
+                return Ok(None);
+            }
+            skew_angle = Some(outcome.angle);
+            content_mask = mask;
+            Ok(Some(DynamicImage::ImageLuma8(deskewed)))
+        })?
+    {
+        img = deskewed;
+    }
+
+    // Débruitage (avant ajustement de contraste et binarisation)
+    if config.denoise
+        && let Some(denoised) = run.apply("denoise", &img, |img, _| {
+            Ok(Some(DynamicImage::ImageLuma8(denoise(&to_grayscale_with(
+                img,
+                &config.grayscale_options,
+            )))))
+        })?
+    {
+        img = denoised;
+    }
+
+    // Ajustement de contraste (doit être fait avant la binarisation)
+    if config.adjust_contrast
+        && let Some(contrasted) = run.apply("contrast", &img, |img, _| {
+            let contrasted = adjust_contrast(
+                &to_grayscale_with(img, &config.grayscale_options),
+                config.contrast_factor,
+            );
+            Ok(Some(DynamicImage::ImageLuma8(contrasted)))
+        })?
+    {
+        img = contrasted;
+    }
+
+    // Binarisation (seuil d'Otsu calculé ici pour être reporté)
+    let mut reported_threshold = None;
+    if config.binarize
+        && let Some(binary) = run.apply("binarize", &img, |img, _| {
+            let gray = to_grayscale_with(img, &config.grayscale_options);
+            let binary = match config.binarization_method {
+                BinarizationMethod::Otsu => {
+                    // Le remplissage de la rotation ne participe pas au seuil
+                    let threshold = match content_mask {
+                        Some(ref mask) => otsu_threshold_masked(&gray, mask),
+                        None => otsu_threshold(&gray),
+                    };
+                    reported_threshold = Some(threshold);
+                    binarize_fixed(&gray, threshold)
                 }
+                method => binarize(&gray, method),
+            };
+            Ok(Some(DynamicImage::ImageLuma8(binary)))
+        })?
+    {
+        img = binary;
+    }
+
+    // Suppression des taches : seulement sur une image binarisée
+    if let Some(min_area) = config.despeckle
+        && let Some(cleaned) = run.apply("despeckle", &img, |img, warnings| {
+            if !config.binarize {
+                warnings.push("despeckle ignoré : binarisation désactivée".to_string());
+                return Ok(None);
             }
+            let cleaned = remove_small_components(
+                &to_grayscale_with(img, &config.grayscale_options),
+                min_area,
+            );
+            Ok(Some(DynamicImage::ImageLuma8(cleaned)))
+        })?
+    {
+        img = cleaned;
+    }
 
-            // Trier et prendre la médiane
-            neighbors.sort_unstable();
-            let median = neighbors[4]; // Élément du milieu (index 4 sur 9)
+    // Pipeline de prétraitement terminé
+    let mut report = run.report;
+    report.scale_factor = scale_factor;
+    report.detected_skew_angle = skew_angle;
+    report.deskew = deskew_outcome;
+    report.otsu_threshold = reported_threshold;
+    report.contrast_factor_used = config.adjust_contrast.then_some(config.contrast_factor);
+    report.output_dimensions = (img.width(), img.height());
+
+    Ok((img, report, content_mask))
+}
 
-            output.put_pixel(x, y, image::Luma([median]));
+/// Exécution en cours du pipeline : rapport en construction et notification des étapes.
+struct PipelineRun<F> {
+    report: PreprocessingReport,
+    on_stage: F,
+}
+
+impl<F: FnMut(&str, &DynamicImage)> PipelineRun<F> {
+    /// Démarre le pipeline sur `image`.
+    fn new(image: &DynamicImage, on_stage: F) -> Self {
+        let dimensions = (image.width(), image.height());
+        Self {
+            report: PreprocessingReport {
+                input_dimensions: dimensions,
+                output_dimensions: dimensions,
+                applied_steps: Vec::new(),
+                scale_factor: 1.0,
+                detected_skew_angle: None,
+                deskew: None,
+                otsu_threshold: None,
+                contrast_factor_used: None,
+                inverted: false,
+                warnings: Vec::new(),
+            },
+            on_stage,
         }
     }
 
-    output
+    /// Exécute l'étape `step` sur `image`.
+    ///
+    /// `run` reçoit l'image et la liste des avertissements du rapport ; il
+    /// retourne `None` si l'étape ne s'applique pas (elle n'est alors ni
+    /// notifiée ni comptée). Son erreur devient une [`PreprocessingError`].
+    fn apply(
+        &mut self,
+        step: &str,
+        image: &DynamicImage,
+        run: impl FnOnce(&DynamicImage, &mut Vec<String>) -> Result<Option<DynamicImage>>,
+    ) -> Result<Option<DynamicImage>, PreprocessingError> {
+        let index = self.report.applied_steps.len() + 1;
+        match run(image, &mut self.report.warnings) {
+            Ok(Some(output)) => {
+                self.report.applied_steps.push(step.to_string());
+                (self.on_stage)(step, &output);
+                Ok(Some(output))
+            }
+            Ok(None) => Ok(None),
+            Err(source) => Err(PreprocessingError {
+                step: step.to_string(),
+                index,
+                dimensions: (image.width(), image.height()),
+                reason: format!("{:#}", source),
+            }),
+        }
+    }
 }
 
-/// Corrige l'inclinaison d'une image (deskew).
-///
-/// Cette fonction détecte et corrige l'inclinaison d'un document scanné ou photographié
-/// en utilisant la méthode de projection horizontale.
+/// Applique le pipeline de prétraitement en sauvegardant l'image après chaque étape.
 ///
-/// # Algorithme
+/// Les fichiers sont nommés `<stem>_<n>_<étape>.png` dans `dir`, où `n` est
+/// le rang de l'étape parmi celles appliquées (ex: `facture_1_grayscale.png`,
+/// `facture_2_deskew.png`). Voir [`preprocess_image_with_callback()`] pour
+/// la liste des étapes.
 ///
-/// 1. **Détection d'angle** : teste des angles de -20° à +20° par pas de 0.5°.
-///    Pour chaque angle candidat, l'image est virtuellement projetée sur l'axe horizontal
-///    et la variance des sommes de lignes est calculée. Un texte bien aligné produit des
-///    lignes alternant entre zones denses (texte) et zones vides (interlignes), ce qui
-///    maximise la variance. L'angle donnant la variance maximale est retenu.
+/// # Arguments
 ///
-/// 2. **Rotation** : l'image est pivotée de l'angle opposé avec interpolation bilinéaire
-///    pour éviter les artefacts. Les pixels hors image sont remplis en blanc (255).
+/// * `image` - L'image source à prétraiter
+/// * `config` - Configuration du prétraitement
+/// * `dir` - Répertoire de sortie (créé si nécessaire)
+/// * `stem` - Préfixe des noms de fichiers
 ///
-/// # Arguments
+/// # Retour
 ///
-/// * `image` - L'image en niveaux de gris à corriger
+/// L'image finale et la liste des fichiers écrits, dans l'ordre des étapes.
 ///
 /// # Exemple
 ///
 /// ```no_run
-/// use text_recognition::preprocessing::{to_grayscale, deskew};
+/// use text_recognition::preprocessing::{PreprocessingConfig, preprocess_image_with_dump};
 /// use image::open;
+/// use std::path::Path;
 ///
-/// let img = open("skewed_document.png").unwrap();
-/// let gray = to_grayscale(&img);
-/// let deskewed = deskew(&gray);
+/// let img = open("facture.png").unwrap();
+/// let config = PreprocessingConfig::default();
+/// let (_, files) = preprocess_image_with_dump(&img, &config, Path::new("debug"), "facture")
+///     .unwrap();
+/// for file in files {
+///     println!("{}", file.display());
+/// }
 /// ```
-pub fn deskew(image: &GrayImage) -> GrayImage {
-    let angle = detect_skew_angle(image);
-    if angle.abs() < 0.1 {
-        // Angle négligeable, pas de rotation nécessaire
-        return image.clone();
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le répertoire ne peut pas être créé, si une image
+/// intermédiaire ne peut pas être sauvegardée ou si une étape échoue.
+pub fn preprocess_image_with_dump(
+    image: &DynamicImage,
+    config: &PreprocessingConfig,
+    dir: &Path,
+    stem: &str,
+) -> Result<(DynamicImage, Vec<PathBuf>)> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Impossible de créer le répertoire '{}'", dir.display()))?;
+
+    let mut files = Vec::new();
+    let mut save_error = None;
+
+    let result = preprocess_image_with_callback(image, config, |stage, stage_image| {
+        if save_error.is_some() {
+            return;
+        }
+        let path = dir.join(format!("{}_{}_{}.png", stem, files.len() + 1, stage));
+        match stage_image.save(&path) {
+            Ok(()) => files.push(path),
+            Err(e) => {
+                save_error = Some(anyhow::Error::new(e).context(format!(
+                    "Impossible de sauvegarder l'étape '{}' dans '{}'",
+                    stage,
+                    path.display()
+                )))
+            }
+        }
+    })?;
+
+    if let Some(e) = save_error {
+        return Err(e);
     }
-    rotate_image(image, -angle)
+
+    Ok((result, files))
 }
 
-/// Détecte l'angle d'inclinaison d'une image par projection horizontale.
-///
-/// Teste des angles de -20° à +20° par pas de 0.5° et retourne l'angle
-/// qui maximise la variance des projections horizontales.
+/// Calcule le facteur de réduction nécessaire pour respecter une limite de pixels.
 ///
 /// # Arguments
 ///
-/// * `image` - L'image en niveaux de gris à analyser
+/// * `width` - Largeur de l'image
+/// * `height` - Hauteur de l'image
+/// * `max_pixels` - Nombre maximal de pixels (`0` = pas de limite)
 ///
 /// # Retour
 ///
-/// L'angle d'inclinaison estimé en degrés (valeur positive = sens horaire).
-fn detect_skew_angle(image: &GrayImage) -> f64 {
-    let (width, height) = image.dimensions();
-    let cx = width as f64 / 2.0;
-    let cy = height as f64 / 2.0;
-
-    let mut best_angle = 0.0f64;
-    let mut best_variance = 0.0f64;
-
-    // Tester des angles de -20° à +20° par pas de 0.5°
-    let mut angle = -20.0f64;
-    while angle <= 20.0 {
-        let rad = angle.to_radians();
-        let cos_a = rad.cos();
-        let sin_a = rad.sin();
-
-        // Calculer la projection horizontale pour cet angle
-        let mut row_sums = vec![0u64; height as usize];
-
-        for y in 0..height {
-            for x in 0..width {
-                // Coordonnées relatives au centre
-                let dx = x as f64 - cx;
-                let dy = y as f64 - cy;
-
-                // Pixel source après rotation inverse
-                let src_x = dx * cos_a + dy * sin_a + cx;
-                let src_y = -dx * sin_a + dy * cos_a + cy;
-
-                if src_x >= 0.0
-                    && src_x < width as f64 - 1.0
-                    && src_y >= 0.0
-                    && src_y < height as f64 - 1.0
-                {
-                    // Interpolation bilinéaire pour la valeur du pixel source
-                    let sx = src_x as u32;
-                    let sy = src_y as u32;
-                    let fx = src_x - sx as f64;
-                    let fy = src_y - sy as f64;
-
-                    let p00 = image.get_pixel(sx, sy)[0] as f64;
-                    let p10 = image.get_pixel(sx + 1, sy)[0] as f64;
-                    let p01 = image.get_pixel(sx, sy + 1)[0] as f64;
-                    let p11 = image.get_pixel(sx + 1, sy + 1)[0] as f64;
-
-                    let val = p00 * (1.0 - fx) * (1.0 - fy)
-                        + p10 * fx * (1.0 - fy)
-                        + p01 * (1.0 - fx) * fy
-                        + p11 * fx * fy;
-
-                    // Pixel sombre = texte (valeur basse = contribution forte)
-                    row_sums[y as usize] += (255.0 - val) as u64;
-                }
-            }
-        }
-
-        // Calculer la variance des sommes de lignes
-        let n = row_sums.len() as f64;
-        let mean = row_sums.iter().sum::<u64>() as f64 / n;
-        let variance = row_sums
-            .iter()
-            .map(|&s| {
-                let diff = s as f64 - mean;
-                diff * diff
-            })
-            .sum::<f64>()
-            / n;
-
-        if variance > best_variance {
-            best_variance = variance;
-            best_angle = angle;
-        }
-
-        angle += 0.5;
+/// `Some(facteur)` (entre 0 et 1) si l'image dépasse la limite, `None` sinon.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::preprocessing::downscale_factor;
+///
+/// assert_eq!(downscale_factor(1000, 1000, 4_000_000), None);
+/// assert_eq!(downscale_factor(4000, 4000, 4_000_000), Some(0.5));
+/// ```
+pub fn downscale_factor(width: u32, height: u32, max_pixels: u64) -> Option<f64> {
+    let pixels = width as u64 * height as u64;
+    if max_pixels == 0 || pixels <= max_pixels {
+        return None;
     }
-
-    best_angle
+    Some((max_pixels as f64 / pixels as f64).sqrt())
 }
 
-/// Fait pivoter une image en niveaux de gris d'un angle donné avec interpolation bilinéaire.
+/// Résolution effective d'une image redimensionnée par `scale_factor`.
 ///
-/// La rotation est effectuée autour du centre de l'image. Les pixels hors image
-/// après rotation sont remplis en blanc (255).
+/// Une image réduite de moitié n'a plus que la moitié de ses points par
+/// pouce : Tesseract doit le savoir pour estimer la taille du texte.
 ///
 /// # Arguments
 ///
-/// * `image` - L'image en niveaux de gris à faire pivoter
-/// * `angle_deg` - L'angle de rotation en degrés (positif = sens antihoraire)
+/// * `dpi` - Résolution de l'image source
+/// * `scale_factor` - Facteur d'échelle appliqué (voir [`PreprocessingReport::scale_factor`])
 ///
-/// # Retour
+/// # Exemple
 ///
-/// Une nouvelle image pivotée de même taille que l'originale.
-fn rotate_image(image: &GrayImage, angle_deg: f64) -> GrayImage {
-    let (width, height) = image.dimensions();
-    let cx = width as f64 / 2.0;
-    let cy = height as f64 / 2.0;
-
-    let rad = angle_deg.to_radians();
-    let cos_a = rad.cos();
-    let sin_a = rad.sin();
-
-    let mut output = GrayImage::new(width, height);
-
-    for y in 0..height {
-        for x in 0..width {
-            // Coordonnées relatives au centre
-            let dx = x as f64 - cx;
-            let dy = y as f64 - cy;
-
-            // Coordonnées dans l'image source (rotation inverse)
-            let src_x = dx * cos_a + dy * sin_a + cx;
-            let src_y = -dx * sin_a + dy * cos_a + cy;
-
-            if src_x >= 0.0
-                && src_x < width as f64 - 1.0
-                && src_y >= 0.0
-                && src_y < height as f64 - 1.0
-            {
-                // Interpolation bilinéaire
-                let sx = src_x as u32;
-                let sy = src_y as u32;
-                let fx = src_x - sx as f64;
-                let fy = src_y - sy as f64;
-
-                let p00 = image.get_pixel(sx, sy)[0] as f64;
-                let p10 = image.get_pixel(sx + 1, sy)[0] as f64;
-                let p01 = image.get_pixel(sx, sy + 1)[0] as f64;
-                let p11 = image.get_pixel(sx + 1, sy + 1)[0] as f64;
-
-                let val = p00 * (1.0 - fx) * (1.0 - fy)
-                    + p10 * fx * (1.0 - fy)
-                    + p01 * (1.0 - fx) * fy
-                    + p11 * fx * fy;
-
-                output.put_pixel(x, y, image::Luma([val.round() as u8]));
-            } else {
-                // Remplir les bords avec du blanc
-                output.put_pixel(x, y, image::Luma([255u8]));
-            }
-        }
+/// ```
+/// use text_recognition::preprocessing::effective_dpi;
+///
+/// assert_eq!(effective_dpi(300, 2.0), 600);
+/// assert_eq!(effective_dpi(300, 0.5), 150);
+/// assert_eq!(effective_dpi(300, 1.0), 300);
+/// ```
+pub fn effective_dpi(dpi: u32, scale_factor: f64) -> u32 {
+    if scale_factor == 1.0 {
+        return dpi;
     }
-
-    output
+    ((dpi as f64 * scale_factor).round() as u32).max(1)
 }
 
-/// Binarise une image en niveaux de gris en noir et blanc pur.
+/// Réduit une image dépassant `max_pixels` en conservant ses proportions.
 ///
-/// Cette fonction convertit chaque pixel en noir (0) ou blanc (255) selon
-/// la méthode de binarisation spécifiée. La binarisation peut améliorer
-/// la qualité OCR en éliminant les variations de gris intermédiaires.
+/// Le même facteur est appliqué à la largeur et à la hauteur (voir
+/// [`downscale_factor()`]). Le DPI effectif de l'image est multiplié par
+/// ce facteur : [`OcrEngine`](crate::ocr::OcrEngine) en tient compte
+/// automatiquement pour conserver des tailles physiques cohérentes.
 ///
 /// # Arguments
 ///
-/// * `image` - L'image en niveaux de gris à binariser
-/// * `method` - La méthode de binarisation à utiliser
+/// * `image` - L'image source
+/// * `max_pixels` - Nombre maximal de pixels (`0` = pas de limite)
+///
+/// # Retour
+///
+/// `Some(image réduite)` si une réduction a été nécessaire, `None` sinon.
 ///
 /// # Exemple
 ///
 /// ```no_run
-/// use text_recognition::preprocessing::{to_grayscale, binarize, BinarizationMethod};
+/// use text_recognition::preprocessing::{DEFAULT_MAX_PIXELS, downscale_to_max_pixels};
 /// use image::open;
 ///
-/// let img = open("document.png").unwrap();
-/// let gray = to_grayscale(&img);
-/// let binary = binarize(&gray, BinarizationMethod::Otsu);
+/// let img = open("scan_geant.png").unwrap();
+/// let img = downscale_to_max_pixels(&img, DEFAULT_MAX_PIXELS).unwrap_or(img);
 /// ```
-pub fn binarize(image: &GrayImage, method: BinarizationMethod) -> GrayImage {
-    match method {
-        BinarizationMethod::Otsu => binarize_otsu(image),
-        BinarizationMethod::Fixed(threshold) => binarize_fixed(image, threshold),
-        BinarizationMethod::Adaptive => binarize_adaptive(image),
-    }
+pub fn downscale_to_max_pixels(image: &DynamicImage, max_pixels: u64) -> Option<DynamicImage> {
+    let factor = downscale_factor(image.width(), image.height(), max_pixels)?;
+    let width = ((image.width() as f64 * factor).floor() as u32).max(1);
+    let height = ((image.height() as f64 * factor).floor() as u32).max(1);
+    Some(image.resize_exact(width, height, imageops::FilterType::Triangle))
 }
 
-/// Calcule le seuil optimal avec la méthode d'Otsu.
+/// Nombre de mètres dans un pouce (résolution du chunk PNG pHYs en pixels/mètre).
+const METERS_PER_INCH: f64 = 0.0254;
+
+/// Sauvegarde une image au format PNG en y inscrivant sa résolution.
 ///
-/// La méthode d'Otsu calcule automatiquement le seuil optimal en maximisant
-/// la variance inter-classe entre les pixels noirs et blancs.
+/// Le chunk `pHYs` ajouté indique la résolution en pixels par mètre. Sans
+/// lui, le binaire `tesseract` suppose 70 DPI ("Warning: Invalid resolution
+/// 0 dpi"), ce qui dégrade la segmentation des fichiers temporaires.
 ///
 /// # Arguments
 ///
-/// * `image` - L'image en niveaux de gris
+/// * `image` - L'image à sauvegarder
+/// * `path` - Chemin du fichier PNG à écrire
+/// * `dpi` - Résolution à inscrire (en général `OcrConfig::dpi`)
 ///
-/// # Retour
+/// # Exemple
 ///
-/// Le seuil optimal (valeur entre 0 et 255)
-fn calculate_otsu_threshold(image: &GrayImage) -> u8 {
-    // Calculer l'histogramme
-    let mut histogram = [0u32; 256];
-    for pixel in image.pixels() {
-        histogram[pixel[0] as usize] += 1;
+/// ```no_run
+/// use text_recognition::preprocessing::save_with_dpi;
+/// use std::path::Path;
+///
+/// let img = image::open("scan.gif")?;
+/// save_with_dpi(&img, Path::new("scan.png"), 300)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Erreurs
+///
+/// Retourne une erreur si l'encodage PNG ou l'écriture du fichier échoue.
+pub fn save_with_dpi(image: &DynamicImage, path: &Path, dpi: u32) -> Result<()> {
+    let bytes = encode_png_with_dpi(image, dpi)?;
+    std::fs::write(path, bytes)
+        .with_context(|| format!("Impossible d'écrire l'image '{}'", path.display()))
+}
+
+/// Encode une image en PNG et insère un chunk `pHYs` juste après `IHDR`.
+fn encode_png_with_dpi(image: &DynamicImage, dpi: u32) -> Result<Vec<u8>> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut png), ImageFormat::Png)
+        .context("Échec de l'encodage PNG")?;
+
+    // Signature (8 octets) + IHDR (longueur 4 + type 4 + données 13 + CRC 4)
+    const IHDR_END: usize = 8 + 4 + 4 + 13 + 4;
+    if png.len() < IHDR_END || &png[12..16] != b"IHDR" {
+        anyhow::bail!("Flux PNG inattendu : chunk IHDR absent");
     }
 
-    let total_pixels = (image.width() * image.height()) as f64;
+    let pixels_per_meter = (dpi as f64 / METERS_PER_INCH).round() as u32;
+    let mut data = Vec::with_capacity(9);
+    data.extend_from_slice(&pixels_per_meter.to_be_bytes());
+    data.extend_from_slice(&pixels_per_meter.to_be_bytes());
+    data.push(1); // Unité : mètre
 
-    // Calculer la somme totale pondérée
-    let mut sum_total = 0.0;
-    for (i, &count) in histogram.iter().enumerate() {
-        sum_total += i as f64 * count as f64;
-    }
+    png.splice(IHDR_END..IHDR_END, png::chunk(b"pHYs", &data)?);
+    Ok(png)
+}
 
-    let mut sum_background = 0.0;
-    let mut weight_background = 0.0;
-    let mut max_variance = 0.0;
-    let mut threshold = 0u8;
+/// Pondération des composantes rouge, verte et bleue lors de la conversion
+/// en niveaux de gris (voir [`to_grayscale_with()`]).
+///
+/// Accepte aussi une chaîne ([`FromStr`]) : `601`, `709`, `average` ou trois
+/// poids `R,G,B` (ex: `0,0.5,0.5`).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelWeights {
+    /// Luminance Rec. 601 (0,299 R + 0,587 G + 0,114 B), celle des
+    /// téléviseurs analogiques et de nombreux outils.
+    Luma601,
+
+    /// Luminance Rec. 709 (0,2126 R + 0,7152 G + 0,0722 B), celle de
+    /// [`to_grayscale()`] : le rouge et le bleu y pèsent peu.
+    #[default]
+    Luma709,
+
+    /// Moyenne des trois composantes.
+    Average,
+
+    /// Poids choisis, ramenés à une somme de 1.
+    ///
+    /// Les poids négatifs comptent pour 0 ; des poids tous nuls donnent la
+    /// moyenne. Une encre est d'autant plus sombre que les composantes
+    /// qu'elle absorbe sont pondérées : pour faire ressortir un texte
+    /// rouge, pondérer le vert et le bleu.
+    Custom {
+        /// Poids du rouge.
+        r: f32,
+        /// Poids du vert.
+        g: f32,
+        /// Poids du bleu.
+        b: f32,
+    },
+}
 
-    // Tester tous les seuils possibles
-    for (t, &count) in histogram.iter().enumerate() {
-        weight_background += count as f64;
+impl ChannelWeights {
+    /// Poids des composantes rouge, verte et bleue, de somme 1.
+    fn coefficients(self) -> [f32; 3] {
+        const AVERAGE: [f32; 3] = [1.0 / 3.0; 3];
+        match self {
+            ChannelWeights::Luma601 => [0.299, 0.587, 0.114],
+            ChannelWeights::Luma709 => [0.2126, 0.7152, 0.0722],
+            ChannelWeights::Average => AVERAGE,
+            ChannelWeights::Custom { r, g, b } => {
+                let weights = [r, g, b].map(|w| if w.is_finite() { w.max(0.0) } else { 0.0 });
+                let sum: f32 = weights.iter().sum();
+                if sum > 0.0 {
+                    weights.map(|w| w / sum)
+                } else {
+                    AVERAGE
+                }
+            }
+        }
+    }
+}
 
-        if weight_background == 0.0 {
-            continue;
+impl FromStr for ChannelWeights {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "601" | "luma601" => return Ok(ChannelWeights::Luma601),
+            "709" | "luma709" => return Ok(ChannelWeights::Luma709),
+            "average" => return Ok(ChannelWeights::Average),
+            _ => {}
         }
 
-        let weight_foreground = total_pixels - weight_background;
+        let weights = s
+            .split(',')
+            .map(|part| {
+                part.trim()
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|w| w.is_finite() && *w >= 0.0)
+            })
+            .collect::<Option<Vec<f32>>>();
+        match weights.as_deref() {
+            Some(&[r, g, b]) if r + g + b > 0.0 => Ok(ChannelWeights::Custom { r, g, b }),
+            _ => Err(anyhow!(
+                "Pondération inconnue : '{}'. Valeurs acceptées : 601, 709, average \
+                 ou trois poids positifs R,G,B (ex: 0,0.5,0.5)",
+                s
+            )),
+        }
+    }
+}
 
-        if weight_foreground == 0.0 {
-            break;
+/// Composante d'une image couleur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    /// Rouge.
+    Red,
+    /// Vert.
+    Green,
+    /// Bleu.
+    Blue,
+}
+
+impl Channel {
+    /// Toutes les composantes, dans l'ordre RGB.
+    pub const ALL: [Channel; 3] = [Channel::Red, Channel::Green, Channel::Blue];
+
+    /// Nom de la composante (`"red"`, `"green"` ou `"blue"`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Channel::Red => "red",
+            Channel::Green => "green",
+            Channel::Blue => "blue",
         }
+    }
+}
 
-        sum_background += t as f64 * count as f64;
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
 
-        let mean_background = sum_background / weight_background;
-        let mean_foreground = (sum_total - sum_background) / weight_foreground;
+impl FromStr for Channel {
+    type Err = anyhow::Error;
+
+    /// Accepte le nom de la composante, sans tenir compte de la casse.
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim().to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|channel| channel.name() == name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Composante inconnue : '{}'. Valeurs acceptées : red, green, blue",
+                    s
+                )
+            })
+    }
+}
 
-        // Calculer la variance inter-classe
-        let variance =
-            weight_background * weight_foreground * (mean_background - mean_foreground).powi(2);
+/// Options de la conversion en niveaux de gris ([`to_grayscale_with()`]).
+///
+/// Les valeurs par défaut reproduisent exactement [`to_grayscale()`] :
+/// pondération Rec. 709, canal alpha ignoré, aucune composante éliminée.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrayscaleOptions {
+    /// Pondération des composantes.
+    pub weights: ChannelWeights,
+
+    /// Niveau de gris du fond sur lequel les pixels transparents sont
+    /// composés (255 : blanc, 0 : noir).
+    ///
+    /// `None` ignore le canal alpha, comme [`to_grayscale()`] : une capture
+    /// PNG transparente garde alors la couleur (souvent noire) de ses
+    /// pixels invisibles.
+    pub alpha_background: Option<u8>,
 
-        if variance > max_variance {
-            max_variance = variance;
-            threshold = t as u8;
+    /// Éliminer l'encre d'une couleur (« dropout » des scanners).
+    ///
+    /// Seule la composante choisie est conservée : une encre de cette
+    /// couleur devient aussi claire que le fond blanc (ex: `Red` efface le
+    /// quadrillage rouge d'un registre comptable) tandis que le noir reste
+    /// noir. La pondération est alors ignorée.
+    pub drop_channel: Option<Channel>,
+}
+
+/// Convertit une image en niveaux de gris selon des options.
+///
+/// Le canal alpha est d'abord composé sur [`GrayscaleOptions::alpha_background`]
+/// s'il est indiqué, puis la composante éliminée ou la pondération donne
+/// le niveau de gris. Avec les options par défaut, le résultat est
+/// identique à celui de [`to_grayscale()`], octet pour octet.
+///
+/// # Arguments
+///
+/// * `image` - L'image source à convertir
+/// * `options` - Pondération, fond des pixels transparents, composante éliminée
+///
+/// # Exemple
+///
+/// ```
+/// use image::{DynamicImage, Rgba, RgbaImage};
+/// use text_recognition::preprocessing::{GrayscaleOptions, to_grayscale_with};
+///
+/// // Capture d'écran transparente : pixels invisibles noirs
+/// let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 4, Rgba([0, 0, 0, 0])));
+/// let options = GrayscaleOptions {
+///     alpha_background: Some(255),
+///     ..GrayscaleOptions::default()
+/// };
+///
+/// assert_eq!(to_grayscale_with(&image, &options).get_pixel(0, 0).0, [255]);
+/// ```
+pub fn to_grayscale_with(image: &DynamicImage, options: &GrayscaleOptions) -> GrayImage {
+    if options.alpha_background.is_none()
+        && options.drop_channel.is_none()
+        && options.weights == ChannelWeights::Luma709
+    {
+        return to_grayscale(image);
+    }
+
+    let rgba = image.to_rgba8();
+    let rgb = RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, alpha] = rgba.get_pixel(x, y).0;
+        Rgb(match options.alpha_background {
+            Some(background) => [r, g, b].map(|channel| composite(channel, alpha, background)),
+            None => [r, g, b],
+        })
+    });
+
+    match (options.drop_channel, options.weights) {
+        (Some(channel), _) => GrayImage::from_fn(rgb.width(), rgb.height(), |x, y| {
+            Luma([rgb.get_pixel(x, y)[channel as usize]])
+        }),
+        // Arrondis de la bibliothèque image, comme to_grayscale()
+        (None, ChannelWeights::Luma709) => DynamicImage::ImageRgb8(rgb).to_luma8(),
+        (None, weights) => {
+            let coefficients = weights.coefficients();
+            GrayImage::from_fn(rgb.width(), rgb.height(), |x, y| {
+                let value: f32 = rgb
+                    .get_pixel(x, y)
+                    .0
+                    .iter()
+                    .zip(coefficients)
+                    .map(|(&channel, weight)| f32::from(channel) * weight)
+                    .sum();
+                Luma([value.round().clamp(0.0, 255.0) as u8])
+            })
         }
     }
+}
 
-    threshold
+/// Compose une composante d'opacité `alpha` sur un fond uni.
+fn composite(channel: u8, alpha: u8, background: u8) -> u8 {
+    let (channel, alpha, background) =
+        (u32::from(channel), u32::from(alpha), u32::from(background));
+    ((channel * alpha + background * (255 - alpha) + 127) / 255) as u8
 }
 
-/// Binarise une image avec la méthode d'Otsu.
+/// Convertit une image en niveaux de gris.
 ///
-/// Cette fonction calcule automatiquement le seuil optimal et binarise l'image.
+/// Cette conversion simplifie l'image en conservant uniquement l'information
+/// de luminance, ce qui réduit le bruit de couleur et améliore la performance OCR.
+///
+/// La luminance est celle de Rec. 709 et le canal alpha est ignoré ; voir
+/// [`to_grayscale_with()`] pour d'autres pondérations ou un fond pour les
+/// pixels transparents.
 ///
 /// # Arguments
 ///
-/// * `image` - L'image en niveaux de gris à binariser
-fn binarize_otsu(image: &GrayImage) -> GrayImage {
-    let threshold = calculate_otsu_threshold(image);
-    binarize_fixed(image, threshold)
+/// * `image` - L'image source à convertir
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::preprocessing::to_grayscale;
+/// use image::open;
+///
+/// let img = open("color_document.png").unwrap();
+/// let gray = to_grayscale(&img);
+/// ```
+pub fn to_grayscale(image: &DynamicImage) -> GrayImage {
+    image.to_luma8()
 }
 
-/// Binarise une image avec un seuil fixe.
+/// Ajuste le contraste d'une image en niveaux de gris.
 ///
-/// Pixels >= threshold deviennent blancs (255), les autres deviennent noirs (0).
+/// Cette fonction applique une transformation linéaire aux valeurs des pixels
+/// pour augmenter ou diminuer le contraste. Un facteur > 1.0 augmente le contraste,
+/// tandis qu'un facteur < 1.0 le diminue.
+///
+/// La formule utilisée est : `new_value = ((old_value - 128) * factor) + 128`
+/// où 128 est la valeur pivot (gris moyen).
 ///
 /// # Arguments
 ///
-/// * `image` - L'image en niveaux de gris à binariser
-/// * `threshold` - Le seuil de binarisation (0-255)
-fn binarize_fixed(image: &GrayImage, threshold: u8) -> GrayImage {
-    let mut output = image.clone();
+/// * `image` - L'image en niveaux de gris à traiter
+/// * `factor` - Le facteur de contraste (recommandé: 0.5 à 3.0)
+///   - 1.0 = pas de changement
+///   - > 1.0 = augmentation du contraste
+///   - < 1.0 = diminution du contraste
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::preprocessing::{to_grayscale, adjust_contrast};
+/// use image::open;
+///
+/// let img = open("low_contrast.png").unwrap();
+/// let gray = to_grayscale(&img);
+/// let enhanced = adjust_contrast(&gray, 1.5); // Augmente le contraste de 50%
+/// ```
+pub fn adjust_contrast(image: &GrayImage, factor: f32) -> GrayImage {
+    // La transformation ne dépend que du niveau : calculée une fois par niveau
+    let table: [u8; 256] = std::array::from_fn(|level| {
+        // Transformation de contraste autour du point pivot (128), bornée à [0, 255]
+        let new_value = ((level as f32 - 128.0) * factor) + 128.0;
+        new_value.clamp(0.0, 255.0) as u8
+    });
+    apply_lookup_table(image, &table)
+}
 
-    for pixel in output.pixels_mut() {
-        pixel[0] = if pixel[0] >= threshold { 255 } else { 0 };
+/// Remplace chaque niveau de gris par son image dans `table`, en une passe
+/// sur le tampon brut.
+fn apply_lookup_table(image: &GrayImage, table: &[u8; 256]) -> GrayImage {
+    let mut output = image.clone();
+    for value in output.iter_mut() {
+        *value = table[usize::from(*value)];
     }
-
     output
 }
 
-/// Binarise une image avec une méthode adaptative.
+/// Table de binarisation : noir (0) jusqu'à `last_black` inclus, blanc (255) au-delà.
+fn threshold_table(last_black: Option<u8>) -> [u8; 256] {
+    std::array::from_fn(|level| match last_black {
+        Some(last) if level <= usize::from(last) => 0,
+        _ => 255,
+    })
+}
+
+/// Applique un filtre de débruitage à une image en niveaux de gris.
 ///
-/// La méthode adaptative calcule un seuil local pour chaque pixel en fonction
-/// de son voisinage, ce qui est utile pour les images avec un éclairage non uniforme.
+/// Cette fonction utilise un filtre médian 3x3 pour réduire le bruit salt-and-pepper
+/// (poivre et sel) tout en préservant les contours. Le filtre médian remplace chaque
+/// pixel par la valeur médiane de son voisinage.
 ///
-/// Cette implémentation utilise une fenêtre glissante de 15x15 pixels et calcule
-/// la moyenne locale comme seuil. Un pixel devient blanc si sa valeur est supérieure
-/// à la moyenne locale moins une constante (C=10).
+/// Le filtre médian est particulièrement efficace pour :
+/// - Réduire le bruit impulsionnel (pixels isolés noirs ou blancs)
+/// - Préserver les contours et les détails du texte
+/// - Améliorer la qualité avant binarisation
 ///
 /// # Arguments
 ///
-/// * `image` - L'image en niveaux de gris à binariser
-fn binarize_adaptive(image: &GrayImage) -> GrayImage {
-    const WINDOW_SIZE: u32 = 15;
-    const C: i32 = 10; // Constante à soustraire de la moyenne
-
+/// * `image` - L'image en niveaux de gris à débruiter
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::preprocessing::{to_grayscale, denoise};
+/// use image::open;
+///
+/// let img = open("noisy_document.png").unwrap();
+/// let gray = to_grayscale(&img);
+/// let denoised = denoise(&gray);
+/// ```
+pub fn denoise(image: &GrayImage) -> GrayImage {
     let (width, height) = image.dimensions();
-    let mut output = GrayImage::new(width, height);
-
-    let half_window = WINDOW_SIZE / 2;
-
-    for y in 0..height {
-        for x in 0..width {
-            // Calculer les limites de la fenêtre
-            let x_start = x.saturating_sub(half_window);
-            let x_end = (x + half_window + 1).min(width);
-            let y_start = y.saturating_sub(half_window);
-            let y_end = (y + half_window + 1).min(height);
+    let mut output = image.clone();
 
-            // Calculer la moyenne locale
-            let mut sum = 0u32;
-            let mut count = 0u32;
+    // Appliquer un filtre médian 3x3
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            // Collecter les valeurs du voisinage 3x3
+            let mut neighbors = [0u8; 9];
+            let mut idx = 0;
 
-            for wy in y_start..y_end {
-                for wx in x_start..x_end {
-                    sum += image.get_pixel(wx, wy)[0] as u32;
-                    count += 1;
+            for dy in 0..3 {
+                for dx in 0..3 {
+                    neighbors[idx] = image.get_pixel(x + dx - 1, y + dy - 1)[0];
+                    idx += 1;
                 }
             }
 
-            let mean = (sum / count) as i32;
-            let threshold = (mean - C).max(0) as u8;
+            // Trier et prendre la médiane
+            neighbors.sort_unstable();
+            let median = neighbors[4]; // Élément du milieu (index 4 sur 9)
 
-            // Appliquer le seuil local
-            let pixel_value = image.get_pixel(x, y)[0];
-            output.put_pixel(
-                x,
-                y,
-                image::Luma([if pixel_value >= threshold { 255 } else { 0 }]),
-            );
+            output.put_pixel(x, y, image::Luma([median]));
         }
     }
 
     output
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Corrige l'inclinaison d'une image (deskew).
+///
+/// Cette fonction détecte et corrige l'inclinaison d'un document scanné ou photographié
+/// en utilisant la méthode de projection horizontale.
+///
+/// # Algorithme
+///
+/// 1. **Détection d'angle** : teste des angles de -20° à +20° par pas de 0.5°.
+///    Pour chaque angle candidat, l'image est virtuellement projetée sur l'axe horizontal
+///    et la variance des sommes de lignes est calculée. Un texte bien aligné produit des
+///    lignes alternant entre zones denses (texte) et zones vides (interlignes), ce qui
+///    maximise la variance. L'angle donnant la variance maximale est retenu.
+///
+/// 2. **Rotation** : l'image est pivotée de l'angle opposé avec interpolation bilinéaire
+///    pour éviter les artefacts. Les pixels hors image sont remplis en blanc (255).
+///
+/// Les garde-fous par défaut de [`deskew_checked()`] s'appliquent : une image
+/// trop petite, uniforme ou sans angle significatif est retournée telle quelle.
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris à corriger
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::preprocessing::{to_grayscale, deskew};
+/// use image::open;
+///
+/// let img = open("skewed_document.png").unwrap();
+/// let gray = to_grayscale(&img);
+/// let deskewed = deskew(&gray);
+/// ```
+pub fn deskew(image: &GrayImage) -> GrayImage {
+    deskew_checked(image, &DeskewOptions::default()).0
+}
 
-    #[test]
-    fn test_preprocessing_config_default() {
-        let config = PreprocessingConfig::default();
-        assert!(config.to_grayscale);
-        assert!(!config.binarize);
-        assert_eq!(config.binarization_method, BinarizationMethod::Otsu);
-        assert!(!config.adjust_contrast);
-        assert_eq!(config.contrast_factor, 1.0);
-        assert!(!config.denoise);
-        assert!(!config.deskew);
+/// Garde-fous de la correction d'inclinaison, utilisés par [`deskew_checked()`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeskewOptions {
+    /// Côté minimal (en pixels) d'une image pour tenter la correction.
+    pub min_dimension: u32,
+
+    /// Nombre de pixels au-delà duquel l'angle est cherché sur une copie réduite.
+    ///
+    /// La rotation est toujours appliquée à pleine résolution. `0` désactive
+    /// la réduction.
+    pub detection_max_pixels: u64,
+
+    /// Gain relatif minimal de la meilleure variance de projection sur celle
+    /// de l'image non pivotée (0.1 = 10 % de mieux) pour retenir un angle.
+    ///
+    /// En deçà, aucun angle ne se détache (bruit, image sans lignes de
+    /// texte) et l'image n'est pas pivotée.
+    pub min_relative_improvement: f64,
+}
+
+impl Default for DeskewOptions {
+    fn default() -> Self {
+        Self {
+            min_dimension: MIN_DESKEW_DIMENSION,
+            detection_max_pixels: DEFAULT_DESKEW_DETECTION_PIXELS,
+            min_relative_improvement: DEFAULT_MIN_SKEW_IMPROVEMENT,
+        }
     }
+}
 
-    #[test]
-    fn test_binarization_method_equality() {
+/// Raison de la décision prise par [`deskew_checked()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeskewReason {
+    /// Un angle significatif a été détecté et corrigé.
+    Corrected,
+
+    /// L'angle détecté est inférieur à 0,1° : pas de rotation.
+    NegligibleAngle,
+
+    /// L'image est trop petite pour estimer un angle.
+    TooSmall {
+        /// Largeur de l'image.
+        width: u32,
+        /// Hauteur de l'image.
+        height: u32,
+        /// Côté minimal requis ([`DeskewOptions::min_dimension`]).
+        minimum: u32,
+    },
+
+    /// L'image est uniforme (toute blanche, toute noire) : aucune ligne à redresser.
+    Uniform,
+
+    /// Aucun angle ne se détache assez de l'image non pivotée.
+    NotSignificant {
+        /// Gain relatif de la meilleure variance sur celle à 0°.
+        improvement: f64,
+        /// Gain minimal requis ([`DeskewOptions::min_relative_improvement`]).
+        threshold: f64,
+    },
+}
+
+impl fmt::Display for DeskewReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeskewReason::Corrected => write!(f, "inclinaison corrigée"),
+            DeskewReason::NegligibleAngle => write!(f, "angle négligeable"),
+            DeskewReason::TooSmall {
+                width,
+                height,
+                minimum,
+            } => write!(
+                f,
+                "image trop petite ({}×{}, minimum {} px)",
+                width, height, minimum
+            ),
+            DeskewReason::Uniform => write!(f, "image uniforme"),
+            DeskewReason::NotSignificant {
+                improvement,
+                threshold,
+            } => write!(
+                f,
+                "aucun angle significatif (gain {:.0} %, minimum {:.0} %)",
+                improvement * 100.0,
+                threshold * 100.0
+            ),
+        }
+    }
+}
+
+/// Décision de [`deskew_checked()`], reprise par [`PreprocessingReport::deskew`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeskewOutcome {
+    /// Angle de correction retenu en degrés (0 si aucun angle n'est significatif).
+    pub angle: f64,
+    /// Indique si l'image a été pivotée.
+    pub applied: bool,
+    /// Raison de la décision.
+    pub reason: DeskewReason,
+}
+
+/// Corrige l'inclinaison d'une image avec des garde-fous pour les entrées extrêmes.
+///
+/// Contrairement à une recherche d'angle brute :
+/// - une image dont un côté est inférieur à [`DeskewOptions::min_dimension`]
+///   n'est pas analysée ;
+/// - au-delà de [`DeskewOptions::detection_max_pixels`], l'angle est cherché
+///   sur une copie réduite (la rotation reste à pleine résolution) ;
+/// - une image uniforme, ou dont aucun angle n'améliore la variance de
+///   projection d'au moins [`DeskewOptions::min_relative_improvement`] par
+///   rapport à 0° (bruit), n'est pas pivotée.
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris à corriger
+/// * `options` - Garde-fous à appliquer
+///
+/// # Retour
+///
+/// L'image corrigée (copie de l'originale si elle n'est pas pivotée) et la
+/// décision prise.
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::preprocessing::{DeskewOptions, DeskewReason, deskew_checked};
+///
+/// let blank = GrayImage::from_pixel(100, 100, Luma([255]));
+/// let (output, outcome) = deskew_checked(&blank, &DeskewOptions::default());
+/// assert_eq!(output, blank);
+/// assert!(!outcome.applied);
+/// assert_eq!(outcome.reason, DeskewReason::Uniform);
+/// ```
+pub fn deskew_checked(image: &GrayImage, options: &DeskewOptions) -> (GrayImage, DeskewOutcome) {
+    let (output, _, outcome) = deskew_checked_with_mask(image, options);
+    (output, outcome)
+}
+
+/// Comme [`deskew_checked()`], en retournant aussi le masque de contenu.
+///
+/// Le masque vaut `None` lorsque l'image n'est pas pivotée.
+fn deskew_checked_with_mask(
+    image: &GrayImage,
+    options: &DeskewOptions,
+) -> (GrayImage, Option<GrayImage>, DeskewOutcome) {
+    let outcome = check_skew(image, options);
+    if !outcome.applied {
+        return (image.clone(), None, outcome);
+    }
+    let (rotated, mask) = rotate_image_with_mask(image, outcome.angle);
+    (rotated, Some(mask), outcome)
+}
+
+/// Détecte l'angle d'inclinaison avec les garde-fous de [`deskew_checked()`].
+fn check_skew(image: &GrayImage, options: &DeskewOptions) -> DeskewOutcome {
+    let skipped = |angle, reason| DeskewOutcome {
+        angle,
+        applied: false,
+        reason,
+    };
+
+    let (width, height) = image.dimensions();
+    if width.min(height) < options.min_dimension.max(2) {
+        return skipped(
+            0.0,
+            DeskewReason::TooSmall {
+                width,
+                height,
+                minimum: options.min_dimension,
+            },
+        );
+    }
+
+    let first = image.as_raw()[0];
+    if image.as_raw().iter().all(|&value| value == first) {
+        return skipped(0.0, DeskewReason::Uniform);
+    }
+
+    let pixels = u64::from(width) * u64::from(height);
+    let reduced =
+        (options.detection_max_pixels > 0 && pixels > options.detection_max_pixels).then(|| {
+            let scale = (options.detection_max_pixels as f64 / pixels as f64).sqrt();
+            let new_width = ((width as f64 * scale) as u32).max(2);
+            let new_height = ((height as f64 * scale) as u32).max(2);
+            imageops::resize(image, new_width, new_height, imageops::FilterType::Triangle)
+        });
+    let detection = reduced.as_ref().unwrap_or(image);
+
+    let search = skew_search(detection, -20.0, 20.0, 0.5);
+    if search.angle.abs() < 0.1 {
+        return skipped(search.angle, DeskewReason::NegligibleAngle);
+    }
+
+    let baseline = projection_variance(detection, 0.0, background_level(detection));
+    let improvement = if baseline > 0.0 {
+        (search.best_variance - baseline) / baseline
+    } else {
+        f64::INFINITY
+    };
+    if improvement < options.min_relative_improvement {
+        return skipped(
+            0.0,
+            DeskewReason::NotSignificant {
+                improvement,
+                threshold: options.min_relative_improvement,
+            },
+        );
+    }
+
+    DeskewOutcome {
+        angle: search.angle,
+        applied: true,
+        reason: DeskewReason::Corrected,
+    }
+}
+
+/// Détecte l'angle d'inclinaison d'une image par projection horizontale.
+///
+/// Teste des angles de -20° à +20° par pas de 0.5° et retourne l'angle
+/// qui maximise la variance des projections horizontales.
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris à analyser
+///
+/// # Retour
+///
+/// L'angle de correction estimé en degrés, à passer tel quel à `rotate_image`
+/// (valeur positive = rotation dans le sens horaire à l'écran).
+pub(crate) fn detect_skew_angle(image: &GrayImage) -> f64 {
+    best_skew(image).0
+}
+
+/// Recherche l'angle d'inclinaison maximisant la variance de projection.
+///
+/// # Retour
+///
+/// Le couple (angle en degrés, variance obtenue pour cet angle).
+fn best_skew(image: &GrayImage) -> (f64, f64) {
+    // Tester des angles de -20° à +20° par pas de 0.5°
+    let search = skew_search(image, -20.0, 20.0, 0.5);
+    (search.angle, search.best_variance)
+}
+
+/// Résultat d'une recherche d'angle par [`skew_search()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SkewSearch {
+    /// Angle de correction retenu, en degrés (même convention que [`detect_skew_angle()`]).
+    pub(crate) angle: f64,
+    /// Variance de projection obtenue pour cet angle.
+    pub(crate) best_variance: f64,
+    /// Moyenne des variances de tous les angles testés.
+    pub(crate) mean_variance: f64,
+}
+
+/// Teste les angles de `from` à `to` (inclus) par pas de `step` degrés.
+///
+/// L'angle retenu maximise la variance de projection horizontale, comme
+/// pour [`detect_skew_angle()`] ; la moyenne des variances indique si ce
+/// maximum se détache nettement des autres angles.
+pub(crate) fn skew_search(image: &GrayImage, from: f64, to: f64, step: f64) -> SkewSearch {
+    // Une image d'une seule ligne ou colonne n'a pas de projection exploitable
+    if image.width() < 2 || image.height() < 2 {
+        return SkewSearch {
+            angle: 0.0,
+            best_variance: 0.0,
+            mean_variance: 0.0,
+        };
+    }
+
+    let background = background_level(image);
+    let mut best_angle = 0.0f64;
+    let mut best_variance = 0.0f64;
+    let mut total = 0.0f64;
+    let mut count = 0u32;
+
+    let mut angle = from;
+    while angle <= to + 1e-9 {
+        let variance = projection_variance(image, angle, background);
+        total += variance;
+        count += 1;
+
+        if variance > best_variance {
+            best_variance = variance;
+            best_angle = angle;
+        }
+
+        angle += step;
+    }
+
+    SkewSearch {
+        angle: best_angle,
+        best_variance,
+        mean_variance: if count == 0 {
+            0.0
+        } else {
+            total / f64::from(count)
+        },
+    }
+}
+
+/// Niveau du fond d'une image : sa luminosité médiane.
+///
+/// Blanc (255) pour une page de texte, quelconque pour une image uniforme
+/// ou du bruit.
+fn background_level(image: &GrayImage) -> f64 {
+    let histogram = histogram(image);
+    let half = (image.width() as u64 * image.height() as u64).div_ceil(2);
+    let mut cumulated = 0u64;
+    for (level, &count) in histogram.iter().enumerate() {
+        cumulated += u64::from(count);
+        if cumulated >= half {
+            return level as f64;
+        }
+    }
+    255.0
+}
+
+/// Calcule la variance de la projection horizontale de l'image pivotée d'un angle donné.
+///
+/// Un texte dont les lignes sont horizontales après rotation produit une forte
+/// variance (alternance lignes de texte / interlignes). Chaque pixel compte
+/// pour son écart au fond (`background`) : les lignes que la rotation
+/// virtuelle fait sortir en partie de l'image ne créent donc pas de variance
+/// artificielle sur une image uniforme (noire comprise) ou du bruit.
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris à analyser
+/// * `angle` - Angle de rotation virtuelle en degrés
+/// * `background` - Niveau du fond (voir [`background_level()`])
+fn projection_variance(image: &GrayImage, angle: f64, background: f64) -> f64 {
+    let (width, height) = image.dimensions();
+    let cx = width as f64 / 2.0;
+    let cy = height as f64 / 2.0;
+
+    let rad = angle.to_radians();
+    let cos_a = rad.cos();
+    let sin_a = rad.sin();
+
+    // Calculer la projection horizontale pour cet angle
+    let mut row_sums = vec![0.0f64; height as usize];
+
+    for y in 0..height {
+        for x in 0..width {
+            // Coordonnées relatives au centre
+            let dx = x as f64 - cx;
+            let dy = y as f64 - cy;
+
+            // Pixel source après rotation inverse
+            let src_x = dx * cos_a + dy * sin_a + cx;
+            let src_y = -dx * sin_a + dy * cos_a + cy;
+
+            if src_x >= 0.0
+                && src_x < width as f64 - 1.0
+                && src_y >= 0.0
+                && src_y < height as f64 - 1.0
+            {
+                // Interpolation bilinéaire pour la valeur du pixel source
+                let sx = src_x as u32;
+                let sy = src_y as u32;
+                let fx = src_x - sx as f64;
+                let fy = src_y - sy as f64;
+
+                let p00 = image.get_pixel(sx, sy)[0] as f64;
+                let p10 = image.get_pixel(sx + 1, sy)[0] as f64;
+                let p01 = image.get_pixel(sx, sy + 1)[0] as f64;
+                let p11 = image.get_pixel(sx + 1, sy + 1)[0] as f64;
+
+                let val = p00 * (1.0 - fx) * (1.0 - fy)
+                    + p10 * fx * (1.0 - fy)
+                    + p01 * (1.0 - fx) * fy
+                    + p11 * fx * fy;
+
+                // Pixel plus sombre que le fond = texte (contribution forte)
+                row_sums[y as usize] += background - val;
+            }
+        }
+    }
+
+    // Calculer la variance des sommes de lignes
+    let n = row_sums.len() as f64;
+    let mean = row_sums.iter().sum::<f64>() / n;
+    row_sums
+        .iter()
+        .map(|&s| {
+            let diff = s - mean;
+            diff * diff
+        })
+        .sum::<f64>()
+        / n
+}
+
+/// Corrige l'orientation complète d'une image : rotation grossière puis deskew fin.
+///
+/// `--deskew` ne gère que les inclinaisons de ±20° et la correction
+/// d'orientation que les multiples exacts de 90° : une photo prise à 80°
+/// ne relève d'aucun des deux. Cette fonction combine les deux étapes :
+///
+/// 1. **Rotation grossière** (multiple de 90°) : d'après l'OSD de Tesseract si
+///    fourni, sinon par une heuristique comparant la meilleure variance de
+///    projection de l'image et de l'image pivotée de 90°.
+/// 2. **Deskew fin** (±20°) sur le résultat, voir [`deskew()`].
+///
+/// # Arguments
+///
+/// * `image` - L'image à corriger
+/// * `osd` - Orientation détectée par Tesseract (PSM 0), si disponible
+///
+/// # Retour
+///
+/// L'image corrigée (niveaux de gris) et l'angle total appliqué en degrés,
+/// dans `]-180, 180]` (positif = sens horaire à l'écran).
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::preprocessing::full_orientation_correction;
+/// use image::open;
+///
+/// let img = open("photo_80_degres.jpg").unwrap();
+/// let (corrected, angle) = full_orientation_correction(&img, None);
+/// println!("Rotation appliquée : {:.1}°", angle);
+/// ```
+pub fn full_orientation_correction(
+    image: &DynamicImage,
+    osd: Option<&OrientationInfo>,
+) -> (DynamicImage, f64) {
+    let gray = to_grayscale(image);
+
+    // Étape 1 : rotation grossière
+    let (coarse, coarse_angle, skew) = match osd {
+        Some(info) => {
+            let rotated =
+                rotate_orientation(&DynamicImage::ImageLuma8(gray), info.orientation).to_luma8();
+            let skew = detect_skew_angle(&rotated);
+            (rotated, orientation_angle(info.orientation), skew)
+        }
+        None => {
+            // Heuristique : des lignes de texte verticales donnent une meilleure
+            // projection une fois l'image pivotée de 90°. La projection ne distingue
+            // pas 90° de 270° : on retient arbitrairement -90°.
+            let (skew, variance) = best_skew(&gray);
+            let rotated = imageops::rotate270(&gray);
+            let (rotated_skew, rotated_variance) = best_skew(&rotated);
+            if rotated_variance > variance {
+                (rotated, -90.0, rotated_skew)
+            } else {
+                (gray, 0.0, skew)
+            }
+        }
+    };
+
+    // Étape 2 : deskew fin sur l'image redressée
+    let (corrected, fine_angle) = if skew.abs() < 0.1 {
+        (coarse, 0.0)
+    } else {
+        (rotate_image(&coarse, skew), skew)
+    };
+
+    (
+        DynamicImage::ImageLuma8(corrected),
+        normalize_angle(coarse_angle + fine_angle),
+    )
+}
+
+/// Retourne l'angle (sens horaire) appliqué par [`rotate_orientation()`].
+fn orientation_angle(orientation: Orientation) -> f64 {
+    match orientation {
+        Orientation::Upright => 0.0,
+        Orientation::Clockwise90 => 90.0,
+        Orientation::UpsideDown => 180.0,
+        Orientation::CounterClockwise90 => -90.0,
+    }
+}
+
+/// Ramène un angle en degrés dans l'intervalle `]-180, 180]`.
+fn normalize_angle(angle: f64) -> f64 {
+    let mut normalized = angle % 360.0;
+    if normalized <= -180.0 {
+        normalized += 360.0;
+    } else if normalized > 180.0 {
+        normalized -= 360.0;
+    }
+    normalized
+}
+
+/// Fait pivoter une image en niveaux de gris d'un angle donné avec interpolation bilinéaire.
+///
+/// La rotation est effectuée autour du centre de l'image. Les pixels hors image
+/// après rotation sont remplis en blanc (255).
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris à faire pivoter
+/// * `angle_deg` - L'angle de rotation en degrés (positif = sens horaire à l'écran, axe y vers le bas)
+///
+/// # Retour
+///
+/// Une nouvelle image pivotée de même taille que l'originale.
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::preprocessing::rotate_image;
+///
+/// let img = GrayImage::from_pixel(40, 20, Luma([0]));
+/// let rotated = rotate_image(&img, 90.0);
+/// assert_eq!(rotated.dimensions(), (40, 20));
+/// // Les coins sortent de l'image source : remplis en blanc
+/// assert_eq!(rotated.get_pixel(0, 0)[0], 255);
+/// ```
+pub fn rotate_image(image: &GrayImage, angle_deg: f64) -> GrayImage {
+    rotate_image_with_mask(image, angle_deg).0
+}
+
+/// Fait pivoter une image comme [`rotate_image()`] et retourne son masque de contenu.
+///
+/// Le masque vaut 255 pour les pixels interpolés depuis l'image source et 0
+/// pour ceux remplis en blanc.
+fn rotate_image_with_mask(image: &GrayImage, angle_deg: f64) -> (GrayImage, GrayImage) {
+    let (width, height) = image.dimensions();
+    let cx = width as f64 / 2.0;
+    let cy = height as f64 / 2.0;
+
+    let rad = angle_deg.to_radians();
+    let cos_a = rad.cos();
+    let sin_a = rad.sin();
+
+    let mut output = GrayImage::new(width, height);
+    let mut mask = GrayImage::new(width, height);
+    if width == 0 || height == 0 {
+        return (output, mask);
+    }
+    let stride = width as usize;
+    let source = image.as_raw();
+    let max_x = width as f64 - 1.0;
+    let max_y = height as f64 - 1.0;
+
+    // Termes de la rotation inverse qui ne dépendent que de la colonne
+    // (dx · cos et -dx · sin, dx relatif au centre) ; les sommes gardent
+    // l'ordre de la formule pour un résultat identique au bit près
+    let column_terms: Vec<(f64, f64)> = (0..width)
+        .map(|x| {
+            let dx = x as f64 - cx;
+            (dx * cos_a, -dx * sin_a)
+        })
+        .collect();
+
+    let rows = output
+        .chunks_exact_mut(stride)
+        .zip(mask.chunks_exact_mut(stride));
+    for (y, (output_row, mask_row)) in rows.enumerate() {
+        // Termes constants sur la ligne
+        let dy = y as f64 - cy;
+        let row_x = dy * sin_a;
+        let row_y = dy * cos_a;
+
+        for (x, &(col_x, col_y)) in column_terms.iter().enumerate() {
+            // Coordonnées dans l'image source (rotation inverse)
+            let src_x = col_x + row_x + cx;
+            let src_y = col_y + row_y + cy;
+
+            if src_x >= 0.0 && src_x < max_x && src_y >= 0.0 && src_y < max_y {
+                // Interpolation bilinéaire
+                let sx = src_x as usize;
+                let sy = src_y as usize;
+                let fx = src_x - sx as f64;
+                let fy = src_y - sy as f64;
+
+                let top = sy * stride + sx;
+                let bottom = top + stride;
+                let p00 = source[top] as f64;
+                let p10 = source[top + 1] as f64;
+                let p01 = source[bottom] as f64;
+                let p11 = source[bottom + 1] as f64;
+
+                let val = p00 * (1.0 - fx) * (1.0 - fy)
+                    + p10 * fx * (1.0 - fy)
+                    + p01 * (1.0 - fx) * fy
+                    + p11 * fx * fy;
+
+                output_row[x] = val.round() as u8;
+                mask_row[x] = 255;
+            } else {
+                // Remplir les bords avec du blanc
+                output_row[x] = 255;
+            }
+        }
+    }
+
+    (output, mask)
+}
+
+/// Binarise une image en niveaux de gris en noir et blanc pur.
+///
+/// Cette fonction convertit chaque pixel en noir (0) ou blanc (255) selon
+/// la méthode de binarisation spécifiée. La binarisation peut améliorer
+/// la qualité OCR en éliminant les variations de gris intermédiaires.
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris à binariser
+/// * `method` - La méthode de binarisation à utiliser
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::preprocessing::{to_grayscale, binarize, BinarizationMethod};
+/// use image::open;
+///
+/// let img = open("document.png").unwrap();
+/// let gray = to_grayscale(&img);
+/// let binary = binarize(&gray, BinarizationMethod::Otsu);
+/// ```
+pub fn binarize(image: &GrayImage, method: BinarizationMethod) -> GrayImage {
+    match method {
+        BinarizationMethod::Otsu => binarize_otsu(image),
+        BinarizationMethod::Fixed(threshold) => binarize_fixed(image, threshold),
+        BinarizationMethod::Adaptive => binarize_adaptive(image),
+        BinarizationMethod::MultiOtsu => binarize_multi_otsu(image),
+    }
+}
+
+/// Calcule l'histogramme des niveaux de gris d'une image.
+///
+/// # Retour
+///
+/// Le nombre de pixels de chaque niveau, de 0 (noir) à 255 (blanc)
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::preprocessing::histogram;
+///
+/// let img = GrayImage::from_pixel(4, 2, Luma([200]));
+/// let counts = histogram(&img);
+/// assert_eq!(counts[200], 8);
+/// assert_eq!(counts.iter().sum::<u32>(), 8);
+/// ```
+pub fn histogram(image: &GrayImage) -> [u32; 256] {
+    let mut histogram = [0u32; 256];
+    for pixel in image.pixels() {
+        histogram[pixel[0] as usize] += 1;
+    }
+    histogram
+}
+
+/// Calcule le seuil optimal avec la méthode d'Otsu.
+///
+/// La méthode d'Otsu calcule automatiquement le seuil optimal en maximisant
+/// la variance inter-classe entre les pixels noirs et blancs. La classe
+/// sombre contient les niveaux inférieurs ou égaux au seuil.
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris
+///
+/// # Retour
+///
+/// Le seuil optimal (valeur entre 0 et 255)
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::preprocessing::{BinarizationMethod, binarize, otsu_threshold};
+///
+/// let gray = image::open("document.png")?.to_luma8();
+/// let threshold = otsu_threshold(&gray);
+/// // Même seuil que BinarizationMethod::Otsu, réutilisable sur d'autres images
+/// let binary = binarize(&gray, BinarizationMethod::Fixed(threshold));
+/// # Ok::<(), image::ImageError>(())
+/// ```
+pub fn otsu_threshold(image: &GrayImage) -> u8 {
+    otsu_threshold_from_histogram(&histogram(image))
+}
+
+/// Calcule le seuil d'Otsu sur les seuls pixels source d'un masque de contenu.
+///
+/// Les pixels dont le masque vaut 0 (remplissage d'une rotation, voir
+/// [`preprocess_image_with_mask()`]) sont ignorés ; un pixel hors du masque
+/// compte comme pixel source.
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris
+/// * `mask` - Masque de contenu, de mêmes dimensions que l'image
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::preprocessing::otsu_threshold_masked;
+///
+/// // Moitié gauche : scan sombre (20 et 80) ; moitié droite : remplissage blanc
+/// let img = GrayImage::from_fn(40, 10, |x, y| match (x, y) {
+///     (20.., _) => Luma([255]),
+///     (_, 0..5) => Luma([20]),
+///     _ => Luma([80]),
+/// });
+/// let mask = GrayImage::from_fn(40, 10, |x, _| Luma([if x < 20 { 255 } else { 0 }]));
+/// assert_eq!(otsu_threshold_masked(&img, &mask), 20);
+/// ```
+pub fn otsu_threshold_masked(image: &GrayImage, mask: &GrayImage) -> u8 {
+    otsu_threshold_from_histogram(&masked_histogram(image, Some(mask)))
+}
+
+/// Histogramme des pixels source d'une image (tous les pixels sans masque).
+fn masked_histogram(image: &GrayImage, mask: Option<&GrayImage>) -> [u32; 256] {
+    let mut histogram = [0u32; 256];
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if is_source_pixel(mask, x, y) {
+            histogram[pixel[0] as usize] += 1;
+        }
+    }
+    histogram
+}
+
+/// Indique si un pixel provient de l'image source d'après le masque de contenu.
+fn is_source_pixel(mask: Option<&GrayImage>, x: u32, y: u32) -> bool {
+    mask.and_then(|mask| mask.get_pixel_checked(x, y))
+        .is_none_or(|value| value[0] != 0)
+}
+
+/// Seuil d'Otsu à deux classes calculé sur un histogramme.
+fn otsu_threshold_from_histogram(histogram: &[u32; 256]) -> u8 {
+    let total_pixels: f64 = histogram.iter().map(|&count| count as f64).sum();
+
+    // Calculer la somme totale pondérée
+    let mut sum_total = 0.0;
+    for (i, &count) in histogram.iter().enumerate() {
+        sum_total += i as f64 * count as f64;
+    }
+
+    let mut sum_background = 0.0;
+    let mut weight_background = 0.0;
+    let mut max_variance = 0.0;
+    let mut threshold = 0u8;
+
+    // Tester tous les seuils possibles
+    for (t, &count) in histogram.iter().enumerate() {
+        weight_background += count as f64;
+
+        if weight_background == 0.0 {
+            continue;
+        }
+
+        let weight_foreground = total_pixels - weight_background;
+
+        if weight_foreground == 0.0 {
+            break;
+        }
+
+        sum_background += t as f64 * count as f64;
+
+        let mean_background = sum_background / weight_background;
+        let mean_foreground = (sum_total - sum_background) / weight_foreground;
+
+        // Calculer la variance inter-classe
+        let variance =
+            weight_background * weight_foreground * (mean_background - mean_foreground).powi(2);
+
+        if variance > max_variance {
+            max_variance = variance;
+            threshold = t as u8;
+        }
+    }
+
+    threshold
+}
+
+/// Binarise une image avec la méthode d'Otsu.
+///
+/// Cette fonction calcule automatiquement le seuil optimal et binarise l'image.
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris à binariser
+fn binarize_otsu(image: &GrayImage) -> GrayImage {
+    let threshold = otsu_threshold(image);
+    binarize_fixed(image, threshold)
+}
+
+/// Calcule les seuils d'Otsu séparant l'image en `classes` classes.
+///
+/// Pour trois classes, tous les couples de seuils `t1 < t2` sont testés et
+/// le couple retenu maximise la variance inter-classe. Les classes sont
+/// `[0, t1]`, `]t1, t2]` et `]t2, 255]`. Une image de moins de trois niveaux
+/// de gris ne peut pas être découpée en trois classes non vides : les deux
+/// seuils valent alors le seuil d'Otsu classique.
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris
+/// * `classes` - Nombre de classes : 2 (Otsu classique) ou 3
+///
+/// # Retour
+///
+/// Les `classes - 1` seuils par ordre croissant, ou un vecteur vide si
+/// `classes` n'est ni 2 ni 3
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::preprocessing::multi_otsu_thresholds;
+///
+/// // Texte noir, tampon gris et fond blanc
+/// let img = GrayImage::from_fn(30, 10, |x, _| Luma([[20, 130, 240][x as usize / 10]]));
+/// let thresholds = multi_otsu_thresholds(&img, 3);
+/// assert_eq!(thresholds.len(), 2);
+/// assert!((20..130).contains(&thresholds[0]));
+/// assert!((130..240).contains(&thresholds[1]));
+/// ```
+pub fn multi_otsu_thresholds(image: &GrayImage, classes: usize) -> Vec<u8> {
+    let histogram = histogram(image);
+    match classes {
+        2 => vec![otsu_threshold_from_histogram(&histogram)],
+        3 => three_class_thresholds(&histogram).to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+/// Seuils d'Otsu à trois classes, par recherche exhaustive des couples de seuils.
+fn three_class_thresholds(histogram: &[u32; 256]) -> [u8; 2] {
+    // Effectifs et sommes pondérées cumulés jusqu'à chaque niveau inclus
+    let mut weights = [0.0f64; 256];
+    let mut sums = [0.0f64; 256];
+    let (mut weight, mut sum) = (0.0, 0.0);
+    for (level, &count) in histogram.iter().enumerate() {
+        weight += count as f64;
+        sum += level as f64 * count as f64;
+        weights[level] = weight;
+        sums[level] = sum;
+    }
+
+    // Maximiser la variance inter-classe revient à maximiser la somme des
+    // (somme pondérée)² / effectif de chaque classe, la moyenne globale étant fixe
+    let class_score = |low: usize, high: usize| {
+        let (weight_before, sum_before) = match low {
+            0 => (0.0, 0.0),
+            _ => (weights[low - 1], sums[low - 1]),
+        };
+        let weight = weights[high] - weight_before;
+        let sum = sums[high] - sum_before;
+        (weight > 0.0).then(|| sum * sum / weight)
+    };
+
+    let mut best: Option<(f64, usize, usize)> = None;
+    for low in 0..254 {
+        let Some(dark) = class_score(0, low) else {
+            continue;
+        };
+        for high in low + 1..255 {
+            let (Some(middle), Some(light)) =
+                (class_score(low + 1, high), class_score(high + 1, 255))
+            else {
+                continue;
+            };
+            let score = dark + middle + light;
+            if best.is_none_or(|(best_score, _, _)| score > best_score) {
+                best = Some((score, low, high));
+            }
+        }
+    }
+
+    match best {
+        Some((_, low, high)) => [low as u8, high as u8],
+        None => {
+            let threshold = otsu_threshold_from_histogram(histogram);
+            [threshold, threshold]
+        }
+    }
+}
+
+/// Binarise une image avec les seuils d'Otsu à trois classes.
+///
+/// La classe sombre devient noire et la classe claire blanche. Le texte est
+/// supposé occuper la plus petite des deux classes extrêmes ; la classe
+/// intermédiaire rejoint l'autre, c'est-à-dire le fond : blanche pour un
+/// texte sombre, noire pour un texte clair.
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris à binariser
+fn binarize_multi_otsu(image: &GrayImage) -> GrayImage {
+    let histogram = histogram(image);
+    let [low, high] = three_class_thresholds(&histogram);
+
+    let count = |levels: &[u32]| levels.iter().map(|&count| count as u64).sum::<u64>();
+    let dark = count(&histogram[..=low as usize]);
+    let light = count(&histogram[high as usize + 1..]);
+    let threshold = if dark <= light { low } else { high };
+
+    apply_lookup_table(image, &threshold_table(Some(threshold)))
+}
+
+/// Binarise une image avec un seuil fixe.
+///
+/// Pixels >= threshold deviennent blancs (255), les autres deviennent noirs (0).
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris à binariser
+/// * `threshold` - Le seuil de binarisation (0-255)
+fn binarize_fixed(image: &GrayImage, threshold: u8) -> GrayImage {
+    apply_lookup_table(image, &threshold_table(threshold.checked_sub(1)))
+}
+
+/// Binarise une image avec une méthode adaptative.
+///
+/// La méthode adaptative calcule un seuil local pour chaque pixel en fonction
+/// de son voisinage, ce qui est utile pour les images avec un éclairage non uniforme.
+///
+/// Cette implémentation utilise une fenêtre glissante de 15x15 pixels et calcule
+/// la moyenne locale comme seuil. Un pixel devient blanc si sa valeur est supérieure
+/// à la moyenne locale moins une constante (C=10).
+///
+/// # Arguments
+///
+/// * `image` - L'image en niveaux de gris à binariser
+fn binarize_adaptive(image: &GrayImage) -> GrayImage {
+    const WINDOW_SIZE: u32 = 15;
+    const C: i32 = 10; // Constante à soustraire de la moyenne
+
+    let (width, height) = image.dimensions();
+    let mut output = GrayImage::new(width, height);
+
+    let half_window = WINDOW_SIZE / 2;
+
+    for y in 0..height {
+        for x in 0..width {
+            // Calculer les limites de la fenêtre
+            let x_start = x.saturating_sub(half_window);
+            let x_end = (x + half_window + 1).min(width);
+            let y_start = y.saturating_sub(half_window);
+            let y_end = (y + half_window + 1).min(height);
+
+            // Calculer la moyenne locale
+            let mut sum = 0u32;
+            let mut count = 0u32;
+
+            for wy in y_start..y_end {
+                for wx in x_start..x_end {
+                    sum += image.get_pixel(wx, wy)[0] as u32;
+                    count += 1;
+                }
+            }
+
+            let mean = (sum / count) as i32;
+            let threshold = (mean - C).max(0) as u8;
+
+            // Appliquer le seuil local
+            let pixel_value = image.get_pixel(x, y)[0];
+            output.put_pixel(
+                x,
+                y,
+                image::Luma([if pixel_value >= threshold { 255 } else { 0 }]),
+            );
+        }
+    }
+
+    output
+}
+
+/// Seuils de la détection des pages blanches (voir [`is_blank_page()`]).
+///
+/// Les valeurs par défaut conviennent à des scans à 300 DPI : une tache de
+/// 16 pixels mesure environ 1,3 mm de côté, et un millième de la page est
+/// bien en dessous d'une seule ligne de texte.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlankDetectionOptions {
+    /// Écart minimal entre le pixel le plus sombre et le plus clair.
+    ///
+    /// En dessous, la page est uniforme (fond gris, grain du papier) et
+    /// considérée comme blanche sans binarisation.
+    pub min_contrast: u8,
+
+    /// Surface maximale (en pixels) d'une composante considérée comme une tache.
+    pub max_speck_area: u32,
+
+    /// Proportion maximale de pixels sombres d'une page presque blanche.
+    pub max_speck_ratio: f64,
+}
+
+impl Default for BlankDetectionOptions {
+    fn default() -> Self {
+        Self {
+            min_contrast: 32,
+            max_speck_area: 16,
+            max_speck_ratio: 0.001,
+        }
+    }
+}
+
+/// Classement d'une page par [`is_blank_page()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PageContent {
+    /// Aucun pixel sombre : l'OCR peut être évité.
+    Blank,
+    /// Seulement quelques taches isolées (poussière, bruit de numérisation).
+    NearBlank,
+    /// La page contient au moins une forme plus grande qu'une tache.
+    HasContent,
+}
+
+/// Résultat de la détection des pages blanches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlankPageResult {
+    /// Classement de la page.
+    pub content: PageContent,
+    /// Proportion de pixels sombres après binarisation d'Otsu (0.0 à 1.0).
+    pub foreground_ratio: f64,
+    /// Nombre de composantes connexes (8-connexité) de pixels sombres.
+    pub component_count: usize,
+    /// Surface en pixels de la plus grande composante.
+    pub largest_component: u32,
+}
+
+/// Détermine si une page est blanche, presque blanche ou contient du texte.
+///
+/// L'image est binarisée par la méthode d'Otsu, puis les pixels sombres sont
+/// regroupés en composantes connexes :
+///
+/// - aucun pixel sombre (ou contraste inférieur à `min_contrast`) :
+///   [`PageContent::Blank`] ;
+/// - uniquement des composantes d'au plus `max_speck_area` pixels, couvrant
+///   au plus `max_speck_ratio` de la page : [`PageContent::NearBlank`] ;
+/// - sinon : [`PageContent::HasContent`].
+///
+/// # Arguments
+///
+/// * `image` - Page en niveaux de gris
+/// * `options` - Seuils de la détection
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::preprocessing::{BlankDetectionOptions, PageContent, is_blank_page};
+///
+/// let page = GrayImage::from_pixel(200, 300, Luma([255]));
+/// let result = is_blank_page(&page, &BlankDetectionOptions::default());
+/// assert_eq!(result.content, PageContent::Blank);
+/// ```
+pub fn is_blank_page(image: &GrayImage, options: &BlankDetectionOptions) -> BlankPageResult {
+    classify_page(image, None, options)
+}
+
+/// Détermine si une page est blanche en ignorant les pixels synthétiques.
+///
+/// Identique à [`is_blank_page()`], mais seuls les pixels source du masque
+/// de contenu (voir [`preprocess_image_with_mask()`]) sont pris en compte :
+/// le contraste, le seuil d'Otsu et la proportion de pixels sombres sont
+/// calculés sans le remplissage blanc d'une rotation.
+///
+/// # Arguments
+///
+/// * `image` - Page en niveaux de gris
+/// * `mask` - Masque de contenu, de mêmes dimensions que l'image
+/// * `options` - Seuils de la détection
+pub fn is_blank_page_masked(
+    image: &GrayImage,
+    mask: &GrayImage,
+    options: &BlankDetectionOptions,
+) -> BlankPageResult {
+    classify_page(image, Some(mask), options)
+}
+
+/// Classe une page d'après ses pixels source (tous les pixels sans masque).
+fn classify_page(
+    image: &GrayImage,
+    mask: Option<&GrayImage>,
+    options: &BlankDetectionOptions,
+) -> BlankPageResult {
+    let blank = BlankPageResult {
+        content: PageContent::Blank,
+        foreground_ratio: 0.0,
+        component_count: 0,
+        largest_component: 0,
+    };
+
+    let source: Vec<bool> = image
+        .enumerate_pixels()
+        .map(|(x, y, _)| is_source_pixel(mask, x, y))
+        .collect();
+    let source_pixels = source.iter().filter(|&&is_source| is_source).count();
+    let (min, max) = image
+        .pixels()
+        .zip(&source)
+        .filter(|&(_, &is_source)| is_source)
+        .fold((u8::MAX, u8::MIN), |(min, max), (p, _)| {
+            (min.min(p[0]), max.max(p[0]))
+        });
+    if source_pixels == 0 || max - min < options.min_contrast {
+        return blank;
+    }
+
+    // Classe sombre d'Otsu : pixels inférieurs ou égaux au seuil
+    let threshold = otsu_threshold_from_histogram(&masked_histogram(image, mask));
+    let foreground: Vec<bool> = image
+        .pixels()
+        .zip(&source)
+        .map(|(p, &is_source)| is_source && p[0] <= threshold)
+        .collect();
+    let areas = component_areas(&foreground, image.width() as usize);
+    if areas.is_empty() {
+        return blank;
+    }
+
+    let foreground_pixels: u64 = areas.iter().map(|&area| area as u64).sum();
+    let foreground_ratio = foreground_pixels as f64 / source_pixels as f64;
+    let largest_component = areas.iter().copied().max().unwrap_or(0);
+    let content = if largest_component <= options.max_speck_area
+        && foreground_ratio <= options.max_speck_ratio
+    {
+        PageContent::NearBlank
+    } else {
+        PageContent::HasContent
+    };
+
+    BlankPageResult {
+        content,
+        foreground_ratio,
+        component_count: areas.len(),
+        largest_component,
+    }
+}
+
+/// Surfaces des composantes connexes (8-connexité) d'un masque ligne par ligne.
+fn component_areas(mask: &[bool], width: usize) -> Vec<u32> {
+    let height = mask.len() / width.max(1);
+    label_foreground(mask, width as u32, height as u32, Connectivity::Eight)
+        .components
+        .iter()
+        .map(|component| component.area)
+        .collect()
+}
+
+/// Voisinage utilisé pour relier deux pixels d'une composante connexe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Connectivity {
+    /// Voisins horizontaux et verticaux seulement.
+    Four,
+    /// Voisins horizontaux, verticaux et diagonaux.
+    #[default]
+    Eight,
+}
+
+/// Statistiques d'une composante connexe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentStats {
+    /// Étiquette de la composante dans [`ComponentLabels::labels`] (à partir de 1).
+    pub label: u32,
+    /// Nombre de pixels de la composante.
+    pub area: u32,
+    /// Rectangle englobant.
+    pub bbox: BBox,
+    /// Centre de gravité (x, y) des pixels, en pixels.
+    pub centroid: (f64, f64),
+}
+
+/// Résultat de l'étiquetage des composantes connexes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentLabels {
+    /// Étiquette de chaque pixel : 0 pour le fond, `n` pour la n-ième composante.
+    pub labels: ImageBuffer<Luma<u32>, Vec<u32>>,
+    /// Composantes, dans l'ordre de leur premier pixel (ligne par ligne) ;
+    /// `components[i].label == i + 1`.
+    pub components: Vec<ComponentStats>,
+}
+
+/// Étiquette les composantes connexes de l'encre d'une image binaire.
+///
+/// Les pixels sombres (valeur inférieure à 128) forment l'encre, comme
+/// après [`binarize()`]. L'étiquetage se fait en deux passes avec une
+/// structure union-find : la mémoire reste proportionnelle à l'image, sans
+/// récursion, même pour une composante couvrant toute la page.
+///
+/// # Arguments
+///
+/// * `binary` - Image binaire (encre noire sur fond blanc)
+/// * `connectivity` - Voisinage reliant deux pixels d'encre
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::preprocessing::{Connectivity, connected_components};
+///
+/// // Deux pixels reliés seulement par un coin
+/// let mut image = GrayImage::from_pixel(3, 3, Luma([255]));
+/// image.put_pixel(0, 0, Luma([0]));
+/// image.put_pixel(1, 1, Luma([0]));
+///
+/// assert_eq!(connected_components(&image, Connectivity::Four).components.len(), 2);
+/// assert_eq!(connected_components(&image, Connectivity::Eight).components.len(), 1);
+/// ```
+pub fn connected_components(binary: &GrayImage, connectivity: Connectivity) -> ComponentLabels {
+    let foreground: Vec<bool> = binary.pixels().map(|p| p[0] < 128).collect();
+    label_foreground(&foreground, binary.width(), binary.height(), connectivity)
+}
+
+/// Efface (en blanc) les composantes d'encre de moins de `min_area` pixels.
+///
+/// Les composantes sont calculées en 8-connexité, pour qu'un trait
+/// diagonal d'une lettre ne soit pas découpé en points isolés.
+///
+/// # Arguments
+///
+/// * `binary` - Image binaire (encre noire sur fond blanc)
+/// * `min_area` - Surface minimale, en pixels, d'une composante conservée
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::preprocessing::remove_small_components;
+///
+/// let mut image = GrayImage::from_pixel(10, 10, Luma([255]));
+/// image.put_pixel(5, 5, Luma([0]));
+///
+/// let cleaned = remove_small_components(&image, 2);
+/// assert_eq!(cleaned.get_pixel(5, 5)[0], 255);
+/// ```
+pub fn remove_small_components(binary: &GrayImage, min_area: u32) -> GrayImage {
+    let labels = connected_components(binary, Connectivity::Eight);
+    let mut cleaned = binary.clone();
+    for (pixel, label) in cleaned.pixels_mut().zip(labels.labels.pixels()) {
+        let label = label[0];
+        if label != 0 && labels.components[label as usize - 1].area < min_area {
+            pixel[0] = 255;
+        }
+    }
+    cleaned
+}
+
+/// Étiquetage en deux passes d'un masque ligne par ligne.
+///
+/// La première passe attribue une étiquette provisoire à chaque pixel et
+/// note les équivalences avec ses voisins déjà vus ; la seconde remplace
+/// chaque étiquette par celle, définitive, de sa classe d'équivalence.
+fn label_foreground(
+    foreground: &[bool],
+    width: u32,
+    height: u32,
+    connectivity: Connectivity,
+) -> ComponentLabels {
+    let w = width as usize;
+    let mut labels = vec![0u32; foreground.len()];
+    // parent[0] est réservé au fond
+    let mut parent: Vec<u32> = vec![0];
+
+    for index in 0..foreground.len() {
+        if !foreground[index] {
+            continue;
+        }
+        let (x, y) = (index % w, index / w);
+        let mut neighbors = [0u32; 4];
+        if x > 0 {
+            neighbors[0] = labels[index - 1];
+        }
+        if y > 0 {
+            neighbors[1] = labels[index - w];
+            if connectivity == Connectivity::Eight {
+                if x > 0 {
+                    neighbors[2] = labels[index - w - 1];
+                }
+                if x + 1 < w {
+                    neighbors[3] = labels[index - w + 1];
+                }
+            }
+        }
+
+        let label = match neighbors.iter().copied().filter(|&l| l != 0).min() {
+            Some(label) => {
+                for &neighbor in neighbors.iter().filter(|&&l| l != 0) {
+                    union(&mut parent, label, neighbor);
+                }
+                label
+            }
+            None => {
+                let label = parent.len() as u32;
+                parent.push(label);
+                label
+            }
+        };
+        labels[index] = label;
+    }
+
+    // Étiquettes définitives, numérotées dans l'ordre de premier passage
+    let mut final_labels = vec![0u32; parent.len()];
+    let mut accumulators: Vec<ComponentAccumulator> = Vec::new();
+    for (index, label) in labels.iter_mut().enumerate() {
+        if *label == 0 {
+            continue;
+        }
+        let root = find(&mut parent, *label) as usize;
+        let (x, y) = ((index % w) as u32, (index / w) as u32);
+        if final_labels[root] == 0 {
+            accumulators.push(ComponentAccumulator::new(x, y));
+            final_labels[root] = accumulators.len() as u32;
+        }
+        *label = final_labels[root];
+        accumulators[*label as usize - 1].add(x, y);
+    }
+
+    let components = accumulators
+        .into_iter()
+        .enumerate()
+        .map(|(index, accumulator)| accumulator.into_stats(index as u32 + 1))
+        .collect();
+
+    ComponentLabels {
+        labels: ImageBuffer::from_raw(width, height, labels)
+            .unwrap_or_else(|| ImageBuffer::new(width, height)),
+        components,
+    }
+}
+
+/// Statistiques d'une composante en cours d'accumulation.
+struct ComponentAccumulator {
+    area: u32,
+    sum_x: u64,
+    sum_y: u64,
+    min: (u32, u32),
+    max: (u32, u32),
+}
+
+impl ComponentAccumulator {
+    /// Composante vide dont le premier pixel est `(x, y)`.
+    fn new(x: u32, y: u32) -> Self {
+        Self {
+            area: 0,
+            sum_x: 0,
+            sum_y: 0,
+            min: (x, y),
+            max: (x, y),
+        }
+    }
+
+    /// Ajoute le pixel `(x, y)`.
+    fn add(&mut self, x: u32, y: u32) {
+        self.area += 1;
+        self.sum_x += u64::from(x);
+        self.sum_y += u64::from(y);
+        self.min = (self.min.0.min(x), self.min.1.min(y));
+        self.max = (self.max.0.max(x), self.max.1.max(y));
+    }
+
+    /// Statistiques finales de la composante d'étiquette `label`.
+    fn into_stats(self, label: u32) -> ComponentStats {
+        let area = f64::from(self.area);
+        ComponentStats {
+            label,
+            area: self.area,
+            bbox: BBox::new(
+                self.min.0,
+                self.min.1,
+                self.max.0 - self.min.0 + 1,
+                self.max.1 - self.min.1 + 1,
+            ),
+            centroid: (self.sum_x as f64 / area, self.sum_y as f64 / area),
+        }
+    }
+}
+
+/// Racine de la classe de `label`, avec compression de chemin par moitiés.
+fn find(parent: &mut [u32], mut label: u32) -> u32 {
+    while parent[label as usize] != label {
+        let grandparent = parent[parent[label as usize] as usize];
+        parent[label as usize] = grandparent;
+        label = grandparent;
+    }
+    label
+}
+
+/// Fusionne les classes de `a` et `b` ; la plus petite racine est conservée.
+fn union(parent: &mut [u32], a: u32, b: u32) {
+    let (root_a, root_b) = (find(parent, a), find(parent, b));
+    if root_a != root_b {
+        let (low, high) = (root_a.min(root_b), root_a.max(root_b));
+        parent[high as usize] = low;
+    }
+}
+
+/// Part centrale de la largeur dans laquelle la gouttière d'une double page est cherchée.
+const GUTTER_SEARCH_RATIO: f64 = 0.4;
+
+/// Largeur minimale d'une gouttière, en colonnes.
+pub const MIN_GUTTER_COLUMNS: u32 = 10;
+
+/// Encre maximale d'une colonne de gouttière, relative à l'encre moyenne d'une colonne.
+const GUTTER_INK_RATIO: f64 = 0.1;
+
+/// Part minimale de l'encre totale que doit porter chaque page d'une double page.
+const MIN_PAGE_INK_SHARE: f64 = 0.1;
+
+/// Cherche la gouttière d'une double page et retourne l'abscisse de coupe.
+///
+/// Le profil de projection verticale (nombre de pixels sombres par colonne,
+/// après seuil d'Otsu) est parcouru dans les 40 % centraux de la largeur.
+/// Une vallée est une suite d'au moins [`MIN_GUTTER_COLUMNS`] colonnes
+/// presque vides (moins de 10 % de l'encre moyenne d'une colonne) ; la plus
+/// profonde est retenue, puis la plus large à profondeur égale. Chaque côté
+/// doit porter au moins 10 % de l'encre de l'image : une page unique
+/// entourée de marges n'est pas coupée.
+///
+/// Une page unique composée en deux colonnes peut être prise pour une
+/// double page : l'option n'est à activer que pour des scans de livres.
+///
+/// # Arguments
+///
+/// * `image` - Image en niveaux de gris
+///
+/// # Retour
+///
+/// L'abscisse du milieu de la gouttière, ou `None` si l'image ne contient
+/// pas deux pages
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::preprocessing::detect_page_split;
+///
+/// // Deux pavés sombres séparés par 60 colonnes blanches
+/// let image = GrayImage::from_fn(400, 200, |x, _| {
+///     if (20..170).contains(&x) || (230..380).contains(&x) { Luma([0]) } else { Luma([255]) }
+/// });
+/// assert_eq!(detect_page_split(&image), Some(200));
+///
+/// let single = GrayImage::from_fn(400, 200, |x, _| {
+///     if (50..350).contains(&x) { Luma([0]) } else { Luma([255]) }
+/// });
+/// assert_eq!(detect_page_split(&single), None);
+/// ```
+pub fn detect_page_split(image: &GrayImage) -> Option<u32> {
+    let (width, height) = image.dimensions();
+    if width < MIN_GUTTER_COLUMNS * 3 || height == 0 {
+        return None;
+    }
+
+    let threshold = otsu_threshold(image);
+    let ink: Vec<u64> = (0..width)
+        .map(|x| {
+            (0..height)
+                .filter(|&y| image.get_pixel(x, y).0[0] <= threshold)
+                .count() as u64
+        })
+        .collect();
+    let total: u64 = ink.iter().sum();
+    // Image uniforme : le seuil d'Otsu classe tout du même côté
+    if total == 0 || total == u64::from(width) * u64::from(height) {
+        return None;
+    }
+    let max_gutter_ink = total as f64 / f64::from(width) * GUTTER_INK_RATIO;
+
+    let margin = f64::from(width) * (1.0 - GUTTER_SEARCH_RATIO) / 2.0;
+    let search = (margin.round() as usize)..((f64::from(width) - margin).round() as usize);
+
+    // Vallées : suites de colonnes presque vides dans la zone de recherche
+    let mut valleys: Vec<std::ops::Range<usize>> = Vec::new();
+    let mut start = None;
+    let flags = ink[search.clone()]
+        .iter()
+        .map(|&column| column as f64 <= max_gutter_ink)
+        .chain(std::iter::once(false));
+    for (x, empty) in (search.start..).zip(flags) {
+        match (empty, start) {
+            (true, None) => start = Some(x),
+            (false, Some(begin)) => {
+                valleys.push(begin..x);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    let min_page_ink = total as f64 * MIN_PAGE_INK_SHARE;
+    valleys
+        .into_iter()
+        .filter(|valley| valley.len() >= MIN_GUTTER_COLUMNS as usize)
+        .filter(|valley| {
+            let left: u64 = ink[..valley.start].iter().sum();
+            let right: u64 = ink[valley.end..].iter().sum();
+            left as f64 >= min_page_ink && right as f64 >= min_page_ink
+        })
+        .map(|valley| {
+            let valley_ink: u64 = ink[valley.clone()].iter().sum();
+            (valley, valley_ink)
+        })
+        // Encre moyenne la plus faible (comparée par produit en croix), puis la plus large
+        .min_by(|(a, a_ink), (b, b_ink)| {
+            (u128::from(*a_ink) * b.len() as u128)
+                .cmp(&(u128::from(*b_ink) * a.len() as u128))
+                .then(b.len().cmp(&a.len()))
+        })
+        .map(|(valley, _)| ((valley.start + valley.end) / 2) as u32)
+}
+
+/// Coupe une double page en deux à l'abscisse `split_x`.
+///
+/// # Arguments
+///
+/// * `image` - Double page
+/// * `split_x` - Abscisse de coupe (ramenée à la largeur de l'image), par
+///   exemple celle retournée par [`detect_page_split()`]
+///
+/// # Retour
+///
+/// La page de gauche (colonnes `0..split_x`) et celle de droite (colonnes
+/// `split_x..`), de la hauteur de l'image
+pub fn split_pages(image: &DynamicImage, split_x: u32) -> (DynamicImage, DynamicImage) {
+    let (width, height) = (image.width(), image.height());
+    let split_x = split_x.min(width);
+    (
+        image.crop_imm(0, 0, split_x, height),
+        image.crop_imm(split_x, 0, width - split_x, height),
+    )
+}
+
+/// Part de l'encre maximale d'une projection en deçà de laquelle une rangée est vide.
+const EMPTY_PROJECTION_RATIO: f64 = 0.05;
+
+/// Nombre minimal de bandes d'encre (lignes ou colonnes de texte) pour conclure.
+const MIN_TEXT_BANDS: usize = 2;
+
+/// Avance minimale de la part de colonnes vides sur celle de rangées vides.
+const VERTICAL_GAP_MARGIN: f64 = 0.1;
+
+/// Rapport minimal entre la longueur d'une colonne de texte et son épaisseur.
+const MIN_TEXT_LINE_ELONGATION: f64 = 2.0;
+
+/// Bandes d'encre d'un profil de projection.
+struct ProjectionBands {
+    /// Nombre de bandes.
+    count: usize,
+    /// Part des positions vides entre la première et la dernière position encrée.
+    gap_share: f64,
+    /// Nombre de positions entre la première et la dernière position encrée.
+    span: usize,
+}
+
+/// Indique si le texte d'une image est disposé en colonnes verticales.
+///
+/// Du texte horizontal produit un profil de projection horizontale (encre
+/// par rangée, après seuil d'Otsu) périodique : des bandes de texte
+/// alternent avec des interlignes vides, alors que presque aucune colonne
+/// n'est vide (les espaces entre mots ne sont pas alignés d'une ligne à
+/// l'autre). Le texte vertical donne le profil inverse. L'image est déclarée
+/// verticale si son profil de colonnes compte au moins deux bandes et une
+/// part de positions vides (entre la première et la dernière encre)
+/// supérieure de plus de 10 points à celle du profil de rangées, et si ces
+/// colonnes sont au moins deux fois plus longues qu'épaisses (les mots
+/// d'une ligne horizontale isolée forment des bandes plus larges que hautes).
+///
+/// Une page très aérée, avec un seul mot par ligne, peut produire des
+/// colonnes vides : à réserver aux documents susceptibles de contenir du
+/// texte vertical ([`OcrConfig::auto_vertical`](crate::config::OcrConfig::auto_vertical)).
+///
+/// # Arguments
+///
+/// * `image` - Image en niveaux de gris
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::preprocessing::detect_vertical_text;
+///
+/// // Trois colonnes de « caractères » sombres, décalés d'une colonne à l'autre
+/// let vertical = GrayImage::from_fn(200, 200, |x, y| {
+///     let column = x / 40;
+///     let in_column = x % 40 >= 10 && x % 40 < 30;
+///     let in_char = (y + column * 7) % 24 < 20;
+///     if (20..180).contains(&y) && in_column && in_char { Luma([0]) } else { Luma([255]) }
+/// });
+/// assert!(detect_vertical_text(&vertical));
+///
+/// let horizontal = image::imageops::rotate90(&vertical);
+/// assert!(!detect_vertical_text(&horizontal));
+/// ```
+pub fn detect_vertical_text(image: &GrayImage) -> bool {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return false;
+    }
+
+    let threshold = otsu_threshold(image);
+    let mut rows = vec![0u64; height as usize];
+    let mut columns = vec![0u64; width as usize];
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel[0] <= threshold {
+            rows[y as usize] += 1;
+            columns[x as usize] += 1;
+        }
+    }
+    let total: u64 = rows.iter().sum();
+    // Image uniforme : le seuil d'Otsu classe tout du même côté
+    if total == 0 || total == u64::from(width) * u64::from(height) {
+        return false;
+    }
+
+    let rows = projection_bands(&rows);
+    let columns = projection_bands(&columns);
+    let column_thickness = columns.span as f64 * (1.0 - columns.gap_share) / columns.count as f64;
+    columns.count >= MIN_TEXT_BANDS
+        && columns.gap_share > rows.gap_share + VERTICAL_GAP_MARGIN
+        && rows.span as f64 >= column_thickness * MIN_TEXT_LINE_ELONGATION
+}
+
+/// Analyse les bandes d'encre d'un profil de projection non vide.
+fn projection_bands(profile: &[u64]) -> ProjectionBands {
+    let max = profile.iter().copied().max().unwrap_or(0);
+    let empty_limit = max as f64 * EMPTY_PROJECTION_RATIO;
+    let is_empty = |value: &u64| *value as f64 <= empty_limit;
+    let first = profile
+        .iter()
+        .position(|value| !is_empty(value))
+        .unwrap_or(0);
+    let last = profile
+        .iter()
+        .rposition(|value| !is_empty(value))
+        .unwrap_or(first);
+
+    let span = &profile[first..=last];
+    let empty = span.iter().filter(|value| is_empty(value)).count();
+    let count = 1 + span
+        .windows(2)
+        .filter(|pair| is_empty(&pair[0]) && !is_empty(&pair[1]))
+        .count();
+    ProjectionBands {
+        count,
+        gap_share: empty as f64 / span.len() as f64,
+        span: span.len(),
+    }
+}
+
+/// Hauteur minimale d'une ligne de texte, en rangées : en deçà, une bande est du bruit.
+pub const MIN_TEXT_LINE_ROWS: u32 = 3;
+
+/// Détecte les lignes de texte d'une image par projection horizontale.
+///
+/// Le profil de projection horizontale (nombre de pixels sombres par
+/// rangée, après seuil d'Otsu) est découpé aux vallées : une rangée est
+/// vide si son encre ne dépasse pas 5 % de celle de la rangée la plus
+/// encrée. Chaque suite de rangées non vides d'au moins
+/// [`MIN_TEXT_LINE_ROWS`] rangées est une ligne. Des lignes qui se touchent
+/// (interligne nul, jambages) ne sont pas séparées.
+///
+/// # Arguments
+///
+/// * `image` - Image en niveaux de gris, texte horizontal
+///
+/// # Retour
+///
+/// Les intervalles de rangées (`début..fin`, fin exclue) de chaque ligne,
+/// de haut en bas ; vide pour une image uniforme
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::preprocessing::detect_text_lines;
+///
+/// // Deux lignes sombres : rangées 10 à 29 et 50 à 69
+/// let image = GrayImage::from_fn(200, 100, |x, y| {
+///     let ink = (20..180).contains(&x) && ((10..30).contains(&y) || (50..70).contains(&y));
+///     if ink { Luma([0]) } else { Luma([255]) }
+/// });
+/// assert_eq!(detect_text_lines(&image), [10..30, 50..70]);
+/// ```
+pub fn detect_text_lines(image: &GrayImage) -> Vec<std::ops::Range<u32>> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+
+    let threshold = otsu_threshold(image);
+    let mut rows = vec![0u64; height as usize];
+    for (_, y, pixel) in image.enumerate_pixels() {
+        if pixel[0] <= threshold {
+            rows[y as usize] += 1;
+        }
+    }
+    let total: u64 = rows.iter().sum();
+    // Image uniforme : le seuil d'Otsu classe tout du même côté
+    if total == 0 || total == u64::from(width) * u64::from(height) {
+        return Vec::new();
+    }
+
+    let max = rows.iter().copied().max().unwrap_or(0);
+    let empty_limit = max as f64 * EMPTY_PROJECTION_RATIO;
+    let mut lines = Vec::new();
+    let mut start = None;
+    let flags = rows
+        .iter()
+        .map(|&row| row as f64 > empty_limit)
+        .chain(std::iter::once(false));
+    for (y, inked) in (0u32..).zip(flags) {
+        match (inked, start) {
+            (true, None) => start = Some(y),
+            (false, Some(begin)) => {
+                if y - begin >= MIN_TEXT_LINE_ROWS {
+                    lines.push(begin..y);
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::SplitMix64;
+
+    #[test]
+    fn test_preprocessing_config_default() {
+        let config = PreprocessingConfig::default();
+        assert!(config.to_grayscale);
+        assert!(!config.binarize);
+        assert_eq!(config.binarization_method, BinarizationMethod::Otsu);
+        assert!(!config.adjust_contrast);
+        assert_eq!(config.contrast_factor, 1.0);
+        assert!(!config.denoise);
+        assert!(!config.deskew);
+    }
+
+    /// Configuration dont chaque champ diffère de la configuration par défaut.
+    fn config_with_every_field_changed() -> PreprocessingConfig {
+        PreprocessingConfig {
+            to_grayscale: false,
+            binarize: true,
+            binarization_method: BinarizationMethod::Fixed(128),
+            adjust_contrast: true,
+            contrast_factor: 1.5,
+            denoise: true,
+            deskew: true,
+            max_pixels: 1_000_000,
+            skip_blank_pages: true,
+            blank_detection: BlankDetectionOptions {
+                min_contrast: 10,
+                ..BlankDetectionOptions::default()
+            },
+            split_double_pages: true,
+            despeckle: Some(3),
+            grayscale_options: GrayscaleOptions {
+                drop_channel: Some(Channel::Red),
+                ..GrayscaleOptions::default()
+            },
+            deskew_options: DeskewOptions {
+                min_relative_improvement: 0.2,
+                ..DeskewOptions::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_patch_apply_and_diff_are_inverses_for_every_field() {
+        let a = PreprocessingConfig::default();
+        let b = config_with_every_field_changed();
+
+        let patch = PreprocessingConfigPatch::diff(&a, &b);
+        let fields = serde_json::to_value(&patch).unwrap();
+        assert_eq!(fields.as_object().unwrap().len(), 14);
+
+        let mut patched = a.clone();
+        patched.apply_patch(&patch);
+        assert_eq!(patched, b);
+
+        // Sens inverse : `despeckle` revient à None par Some(None)
+        let back = PreprocessingConfigPatch::diff(&b, &a);
+        assert_eq!(back.despeckle, Some(None));
+        let mut reverted = b.clone();
+        reverted.apply_patch(&back);
+        assert_eq!(reverted, a);
+
+        assert!(PreprocessingConfigPatch::diff(&a, &a).is_empty());
+    }
+
+    #[test]
+    fn test_unset_patch_fields_leave_values_untouched() {
+        let original = config_with_every_field_changed();
+        let mut config = original.clone();
+        config.apply_patch(&PreprocessingConfigPatch::default());
+        assert_eq!(config, original);
+
+        config.apply_patch(&PreprocessingConfigPatch {
+            denoise: Some(false),
+            ..PreprocessingConfigPatch::default()
+        });
+        assert_eq!(
+            config,
+            PreprocessingConfig {
+                denoise: false,
+                ..original
+            }
+        );
+    }
+
+    #[test]
+    fn test_patch_toml_round_trip() {
+        let patch = PreprocessingConfigPatch::diff(
+            &PreprocessingConfig::default(),
+            &config_with_every_field_changed(),
+        );
+
+        let serialized = toml::to_string(&patch).unwrap();
+        let parsed: PreprocessingConfigPatch = toml::from_str(&serialized).unwrap();
+        assert_eq!(parsed, patch);
+
+        let partial: PreprocessingConfigPatch =
+            toml::from_str("deskew = true\n[grayscale_options]\ndrop_channel = \"red\"\n").unwrap();
+        assert_eq!(partial.deskew, Some(true));
+        assert_eq!(
+            partial
+                .grayscale_options
+                .map(|options| options.drop_channel),
+            Some(Some(Channel::Red))
+        );
+        assert_eq!(partial.binarize, None);
+    }
+
+    #[test]
+    fn test_binarization_method_equality() {
         assert_eq!(BinarizationMethod::Otsu, BinarizationMethod::Otsu);
         assert_eq!(
-            BinarizationMethod::Fixed(128),
-            BinarizationMethod::Fixed(128)
+            BinarizationMethod::Fixed(128),
+            BinarizationMethod::Fixed(128)
+        );
+        assert_ne!(
+            BinarizationMethod::Fixed(100),
+            BinarizationMethod::Fixed(128)
+        );
+        assert_eq!(BinarizationMethod::Adaptive, BinarizationMethod::Adaptive);
+    }
+
+    #[test]
+    fn test_binarize_fixed() {
+        use image::Luma;
+
+        // Créer une image de test 2x2
+        let mut img = GrayImage::new(2, 2);
+        img.put_pixel(0, 0, Luma([100]));
+        img.put_pixel(0, 1, Luma([150]));
+        img.put_pixel(1, 0, Luma([200]));
+        img.put_pixel(1, 1, Luma([50]));
+
+        // Binariser avec seuil 128
+        let binary = binarize(&img, BinarizationMethod::Fixed(128));
+
+        assert_eq!(binary.get_pixel(0, 0)[0], 0); // 100 < 128 -> 0
+        assert_eq!(binary.get_pixel(0, 1)[0], 255); // 150 >= 128 -> 255
+        assert_eq!(binary.get_pixel(1, 0)[0], 255); // 200 >= 128 -> 255
+        assert_eq!(binary.get_pixel(1, 1)[0], 0); // 50 < 128 -> 0
+    }
+
+    #[test]
+    fn test_otsu_threshold() {
+        use image::Luma;
+
+        // Créer une image bimodale simple (fond clair, texte sombre)
+        let mut img = GrayImage::new(10, 10);
+        for y in 0..10 {
+            for x in 0..10 {
+                // Zone sombre (0-50) et zone claire (200-255)
+                let value = if x < 5 { 30 } else { 220 };
+                img.put_pixel(x, y, Luma([value]));
+            }
+        }
+
+        let threshold = otsu_threshold(&img);
+
+        // Le seuil devrait séparer correctement les deux groupes
+        // Il devrait être entre les deux pics (de 30 à 220)
+        assert!(threshold >= 30, "Threshold {} should be >= 30", threshold);
+        assert!(threshold <= 220, "Threshold {} should be <= 220", threshold);
+        assert_eq!(multi_otsu_thresholds(&img, 2), vec![threshold]);
+    }
+
+    #[test]
+    fn test_histogram() {
+        use image::Luma;
+
+        let img = GrayImage::from_fn(16, 16, |x, y| Luma([(x * 16 + y) as u8]));
+        let counts = histogram(&img);
+
+        assert!(counts.iter().all(|&count| count == 1));
+        assert_eq!(histogram(&GrayImage::new(0, 0)), [0; 256]);
+    }
+
+    /// Image à trois plateaux bruités : 15 % à 40, 25 % à 128, 60 % à 220.
+    fn trimodal_image() -> GrayImage {
+        use image::Luma;
+
+        GrayImage::from_fn(100, 40, |x, y| {
+            let center = match x {
+                0..15 => 40,
+                15..40 => 128,
+                _ => 220,
+            };
+            // Écart déterministe de -10 à +10 autour du plateau
+            let offset = ((x * 7 + y * 13) % 21) as i32 - 10;
+            Luma([(center + offset) as u8])
+        })
+    }
+
+    #[test]
+    fn test_multi_otsu_thresholds() {
+        let img = trimodal_image();
+        let thresholds = multi_otsu_thresholds(&img, 3);
+
+        // Chaque seuil tombe dans la vallée entre deux plateaux
+        assert_eq!(thresholds.len(), 2);
+        assert!(
+            (50..118).contains(&thresholds[0]),
+            "Seuil bas {} hors de la vallée 50-118",
+            thresholds[0]
+        );
+        assert!(
+            (138..210).contains(&thresholds[1]),
+            "Seuil haut {} hors de la vallée 138-210",
+            thresholds[1]
+        );
+
+        assert!(multi_otsu_thresholds(&img, 1).is_empty());
+        assert!(multi_otsu_thresholds(&img, 4).is_empty());
+    }
+
+    #[test]
+    fn test_multi_otsu_two_levels_falls_back_to_otsu() {
+        use image::Luma;
+
+        let img = GrayImage::from_fn(10, 10, |x, _| Luma([if x < 3 { 30 } else { 220 }]));
+        let threshold = otsu_threshold(&img);
+
+        assert_eq!(multi_otsu_thresholds(&img, 3), vec![threshold, threshold]);
+        let binary = binarize(&img, BinarizationMethod::MultiOtsu);
+        assert_eq!(binary.get_pixel(0, 0)[0], 0);
+        assert_eq!(binary.get_pixel(9, 0)[0], 255);
+    }
+
+    #[test]
+    fn test_binarize_multi_otsu() {
+        use image::Luma;
+
+        // Texte sombre minoritaire : la classe intermédiaire rejoint le fond blanc
+        let img = trimodal_image();
+        let binary = binarize(&img, BinarizationMethod::MultiOtsu);
+        assert!(binary.pixels().all(|p| p[0] == 0 || p[0] == 255));
+        for (x, expected) in [(5, 0), (25, 255), (70, 255)] {
+            assert_eq!(binary.get_pixel(x, 20)[0], expected, "colonne {}", x);
+        }
+
+        // Texte clair minoritaire sur fond sombre : la classe intermédiaire devient noire
+        let mut inverted = img.clone();
+        for pixel in inverted.pixels_mut() {
+            *pixel = Luma([255 - pixel[0]]);
+        }
+        let binary = binarize(&inverted, BinarizationMethod::MultiOtsu);
+        assert!(binary.pixels().all(|p| p[0] == 0 || p[0] == 255));
+        for (x, expected) in [(5, 255), (25, 0), (70, 0)] {
+            assert_eq!(binary.get_pixel(x, 20)[0], expected, "colonne {}", x);
+        }
+    }
+
+    #[test]
+    fn test_binarize_otsu() {
+        use crate::testutil::{RenderOptions, render_text_image};
+        use image::Rgb;
+
+        // Texte gris foncé sur fond gris clair, bruité
+        let options = RenderOptions {
+            foreground: Rgb([50, 50, 50]),
+            background: Rgb([200, 200, 200]),
+            noise_sigma: 15.0,
+            ..RenderOptions::default()
+        };
+        let img = render_text_image("Texte OCR", &options).to_luma8();
+        let clean = render_text_image(
+            "Texte OCR",
+            &RenderOptions {
+                noise_sigma: 0.0,
+                ..options
+            },
+        )
+        .to_luma8();
+
+        let binary = binarize(&img, BinarizationMethod::Otsu);
+
+        // Tous les pixels devraient être soit 0 soit 255
+        for pixel in binary.pixels() {
+            assert!(
+                pixel[0] == 0 || pixel[0] == 255,
+                "Pixel value should be 0 or 255, got {}",
+                pixel[0]
+            );
+        }
+
+        // Le seuil sépare le texte du fond malgré le bruit
+        let mismatches = binary
+            .pixels()
+            .zip(clean.pixels())
+            .filter(|(b, c)| (b[0] == 0) != (c[0] == 50))
+            .count();
+        assert!(
+            mismatches * 100 < binary.pixels().len(),
+            "{} pixels mal classés",
+            mismatches
+        );
+    }
+
+    #[test]
+    fn test_binarize_adaptive() {
+        use image::Luma;
+
+        // Créer une image avec éclairage non uniforme (gradient)
+        let mut img = GrayImage::new(20, 20);
+        for y in 0..20 {
+            for x in 0..20 {
+                // Gradient de gauche (sombre) à droite (clair)
+                // Avec un pattern de texte (alternance)
+                let base = 50 + (x * 10); // Gradient 50 -> 240
+                let text_offset = if (x + y) % 3 == 0 { 0 } else { 40 };
+                let value = (base + text_offset).min(255) as u8;
+                img.put_pixel(x, y, Luma([value]));
+            }
+        }
+
+        let binary = binarize(&img, BinarizationMethod::Adaptive);
+
+        // Tous les pixels devraient être soit 0 soit 255
+        for pixel in binary.pixels() {
+            assert!(
+                pixel[0] == 0 || pixel[0] == 255,
+                "Pixel value should be 0 or 255, got {}",
+                pixel[0]
+            );
+        }
+
+        // Vérifier qu'il y a bien un mélange de pixels noirs et blancs
+        let mut black_count = 0;
+        let mut white_count = 0;
+        for pixel in binary.pixels() {
+            if pixel[0] == 0 {
+                black_count += 1;
+            } else {
+                white_count += 1;
+            }
+        }
+
+        assert!(black_count > 0, "Should have some black pixels");
+        assert!(white_count > 0, "Should have some white pixels");
+    }
+
+    #[test]
+    fn test_adjust_contrast_no_change() {
+        use image::Luma;
+
+        // Créer une image de test
+        let mut img = GrayImage::new(2, 2);
+        img.put_pixel(0, 0, Luma([50]));
+        img.put_pixel(0, 1, Luma([128]));
+        img.put_pixel(1, 0, Luma([200]));
+        img.put_pixel(1, 1, Luma([100]));
+
+        // Appliquer un facteur de 1.0 (pas de changement)
+        let result = adjust_contrast(&img, 1.0);
+
+        // Les valeurs devraient être identiques
+        assert_eq!(result.get_pixel(0, 0)[0], 50);
+        assert_eq!(result.get_pixel(0, 1)[0], 128);
+        assert_eq!(result.get_pixel(1, 0)[0], 200);
+        assert_eq!(result.get_pixel(1, 1)[0], 100);
+    }
+
+    #[test]
+    fn test_adjust_contrast_increase() {
+        use image::Luma;
+
+        // Créer une image avec du gris moyen
+        let mut img = GrayImage::new(2, 2);
+        img.put_pixel(0, 0, Luma([100])); // Plus sombre que 128
+        img.put_pixel(0, 1, Luma([128])); // Point pivot
+        img.put_pixel(1, 0, Luma([150])); // Plus clair que 128
+        img.put_pixel(1, 1, Luma([180]));
+
+        // Augmenter le contraste (facteur > 1.0)
+        let result = adjust_contrast(&img, 2.0);
+
+        // Les valeurs sombres devraient être plus sombres
+        assert!(
+            result.get_pixel(0, 0)[0] < 100,
+            "Dark pixel should become darker"
+        );
+
+        // Le point pivot devrait rester à 128
+        assert_eq!(result.get_pixel(0, 1)[0], 128);
+
+        // Les valeurs claires devraient être plus claires
+        assert!(
+            result.get_pixel(1, 0)[0] > 150,
+            "Bright pixel should become brighter"
+        );
+        assert!(
+            result.get_pixel(1, 1)[0] > 180,
+            "Bright pixel should become brighter"
+        );
+    }
+
+    #[test]
+    fn test_adjust_contrast_decrease() {
+        use image::Luma;
+
+        // Créer une image avec des valeurs contrastées
+        let mut img = GrayImage::new(2, 2);
+        img.put_pixel(0, 0, Luma([50])); // Très sombre
+        img.put_pixel(0, 1, Luma([200])); // Très clair
+
+        // Diminuer le contraste (facteur < 1.0)
+        let result = adjust_contrast(&img, 0.5);
+
+        // Les valeurs devraient se rapprocher de 128
+        assert!(
+            result.get_pixel(0, 0)[0] > 50,
+            "Dark pixel should become lighter"
+        );
+        assert!(
+            result.get_pixel(0, 1)[0] < 200,
+            "Bright pixel should become darker"
+        );
+    }
+
+    #[test]
+    fn test_adjust_contrast_clamping() {
+        use image::Luma;
+
+        // Créer une image avec des valeurs extrêmes
+        let mut img = GrayImage::new(2, 2);
+        img.put_pixel(0, 0, Luma([10])); // Très sombre
+        img.put_pixel(0, 1, Luma([240])); // Très clair
+
+        // Augmenter fortement le contraste
+        let result = adjust_contrast(&img, 3.0);
+
+        // Avec facteur 3.0:
+        // Pixel 0,0: ((10 - 128) * 3.0) + 128 = -354 + 128 = -226 -> clamped to 0
+        // Pixel 0,1: ((240 - 128) * 3.0) + 128 = 336 + 128 = 464 -> clamped to 255
+        assert_eq!(
+            result.get_pixel(0, 0)[0],
+            0,
+            "Very dark pixel with high contrast should clamp to 0"
+        );
+        assert_eq!(
+            result.get_pixel(0, 1)[0],
+            255,
+            "Very bright pixel with high contrast should clamp to 255"
+        );
+    }
+
+    #[test]
+    fn test_denoise_removes_salt_and_pepper() {
+        use image::Luma;
+
+        // Créer une image 5x5 avec bruit salt-and-pepper
+        let mut img = GrayImage::new(5, 5);
+
+        // Remplir avec une valeur uniforme
+        for y in 0..5 {
+            for x in 0..5 {
+                img.put_pixel(x, y, Luma([128]));
+            }
+        }
+
+        // Ajouter du bruit (pixels isolés)
+        img.put_pixel(2, 2, Luma([0])); // Pepper (noir)
+        img.put_pixel(1, 1, Luma([255])); // Salt (blanc)
+        img.put_pixel(3, 3, Luma([255])); // Salt (blanc)
+
+        let denoised = denoise(&img);
+
+        // Les pixels bruités au centre devraient être corrigés
+        // Le filtre médian remplace les valeurs aberrantes par la médiane du voisinage
+        assert_ne!(
+            denoised.get_pixel(2, 2)[0],
+            0,
+            "Pepper noise should be removed"
+        );
+        assert_ne!(
+            denoised.get_pixel(1, 1)[0],
+            255,
+            "Salt noise should be removed"
+        );
+
+        // Les pixels corrigés devraient être proches de 128
+        assert!(
+            (denoised.get_pixel(2, 2)[0] as i16 - 128).abs() < 10,
+            "Denoised pixel should be close to 128"
+        );
+    }
+
+    #[test]
+    fn test_denoise_preserves_edges() {
+        use image::Luma;
+
+        // Créer une image avec un contour net (moitié noire, moitié blanche)
+        let mut img = GrayImage::new(5, 5);
+
+        for y in 0..5 {
+            for x in 0..5 {
+                let value = if x < 2 { 50 } else { 200 };
+                img.put_pixel(x, y, Luma([value]));
+            }
+        }
+
+        let denoised = denoise(&img);
+
+        // Les zones uniformes devraient rester similaires
+        assert_eq!(
+            denoised.get_pixel(1, 2)[0],
+            50,
+            "Dark area should be preserved"
+        );
+        assert_eq!(
+            denoised.get_pixel(3, 2)[0],
+            200,
+            "Bright area should be preserved"
+        );
+    }
+
+    #[test]
+    fn test_denoise_median_calculation() {
+        use image::Luma;
+
+        // Créer une image de test 3x3 avec des valeurs connues
+        let mut img = GrayImage::new(3, 3);
+        let values = [
+            [10, 20, 30],
+            [40, 100, 60], // Centre = 100, médiane du voisinage devrait être calculée
+            [70, 80, 90],
+        ];
+
+        for y in 0..3 {
+            for x in 0..3 {
+                img.put_pixel(x, y, Luma([values[y as usize][x as usize]]));
+            }
+        }
+
+        let denoised = denoise(&img);
+
+        // Le pixel central devrait être la médiane de [10,20,30,40,100,60,70,80,90]
+        // Trié: [10,20,30,40,60,70,80,90,100]
+        // Médiane (index 4): 60
+        assert_eq!(
+            denoised.get_pixel(1, 1)[0],
+            60,
+            "Center pixel should be the median of neighborhood"
+        );
+    }
+
+    #[test]
+    fn test_deskew_preserves_dimensions() {
+        use image::Luma;
+
+        // Créer une image uniforme (angle nul attendu)
+        let mut img = GrayImage::new(20, 20);
+        for y in 0..20 {
+            for x in 0..20 {
+                img.put_pixel(x, y, Luma([200]));
+            }
+        }
+
+        let deskewed = deskew(&img);
+
+        // Les dimensions doivent être conservées
+        assert_eq!(deskewed.dimensions(), img.dimensions());
+    }
+
+    #[test]
+    fn test_deskew_uniform_image_unchanged() {
+        use image::Luma;
+
+        // Une image uniforme n'a pas d'inclinaison détectable
+        // -> deskew doit retourner l'image quasi inchangée
+        let mut img = GrayImage::new(30, 30);
+        for y in 0..30 {
+            for x in 0..30 {
+                img.put_pixel(x, y, Luma([200]));
+            }
+        }
+
+        let deskewed = deskew(&img);
+        assert_eq!(deskewed.dimensions(), (30, 30));
+    }
+
+    #[test]
+    fn test_deskew_checked_degenerate_inputs() {
+        let options = DeskewOptions {
+            min_dimension: 0,
+            ..DeskewOptions::default()
+        };
+        for (width, height) in [(0, 0), (1, 1), (1, 100), (100, 0)] {
+            let img = GrayImage::new(width, height);
+            let (output, outcome) = deskew_checked(&img, &options);
+            assert_eq!(output.dimensions(), (width, height));
+            assert!(!outcome.applied);
+            assert!(matches!(outcome.reason, DeskewReason::TooSmall { .. }));
+            assert_eq!(rotate_image(&img, 10.0).dimensions(), (width, height));
+            assert_eq!(skew_search(&img, -20.0, 20.0, 0.5).best_variance, 0.0);
+        }
+
+        let (_, outcome) = deskew_checked(&GrayImage::new(15, 15), &DeskewOptions::default());
+        assert_eq!(
+            outcome.reason.to_string(),
+            "image trop petite (15×15, minimum 16 px)"
+        );
+    }
+
+    #[test]
+    fn test_deskew_checked_uniform_images_not_rotated() {
+        for level in [255, 0] {
+            let img = GrayImage::from_pixel(120, 80, image::Luma([level]));
+            let (output, outcome) = deskew_checked(&img, &DeskewOptions::default());
+            assert_eq!(output, img);
+            assert_eq!(outcome.angle, 0.0);
+            assert!(!outcome.applied);
+            assert_eq!(outcome.reason, DeskewReason::Uniform);
+        }
+    }
+
+    #[test]
+    fn test_deskew_checked_detects_ten_degrees() {
+        use crate::testutil::{RenderOptions, render_text_image};
+
+        let skewed = render_text_image(
+            "Lorem ipsum dolor\nsit amet, conse\nctetur adipiscin\ng elit, sed do e",
+            &RenderOptions {
+                rotation_degrees: 10.0,
+                ..RenderOptions::default()
+            },
+        )
+        .to_luma8();
+
+        let (output, outcome) = deskew_checked(&skewed, &DeskewOptions::default());
+        assert!(outcome.applied);
+        assert_eq!(outcome.reason, DeskewReason::Corrected);
+        assert!(
+            (outcome.angle + 10.0).abs() <= 1.0,
+            "angle : {}",
+            outcome.angle
+        );
+        assert_eq!(output.dimensions(), skewed.dimensions());
+
+        // Angle cherché sur une copie réduite, rotation à pleine résolution
+        let reduced = DeskewOptions {
+            detection_max_pixels: 40_000,
+            ..DeskewOptions::default()
+        };
+        let (output, outcome) = deskew_checked(&skewed, &reduced);
+        assert!(outcome.applied);
+        assert!(
+            (outcome.angle + 10.0).abs() <= 1.5,
+            "angle : {}",
+            outcome.angle
+        );
+        assert_eq!(output.dimensions(), skewed.dimensions());
+    }
+
+    #[test]
+    fn test_deskew_checked_ignores_faint_skew() {
+        // Une seule bande à peine plus sombre, inclinée de 3°, dans un bruit
+        // fort : l'angle trouvé n'est pas négligeable, mais la projection
+        // pivotée ne gagne que quelques pour cent sur l'image non pivotée
+        let mut rng = SplitMix64::new(0xD35C);
+        let slope = 3.0f64.to_radians().tan();
+        let image = GrayImage::from_fn(400, 400, |x, y| {
+            let band = (y as f64 - (200.0 + slope * x as f64)).abs() < 4.0;
+            let level = if band { 122.0 } else { 128.0 };
+            Luma([(level + 20.0 * rng.next_gaussian()).clamp(0.0, 255.0) as u8])
+        });
+
+        let options = DeskewOptions::default();
+        let (output, outcome) = deskew_checked(&image, &options);
+        assert_eq!(output, image);
+        assert!(!outcome.applied);
+        assert_eq!(outcome.angle, 0.0);
+        match outcome.reason {
+            DeskewReason::NotSignificant {
+                improvement,
+                threshold,
+            } => {
+                assert_eq!(threshold, options.min_relative_improvement);
+                assert!(
+                    improvement > 0.0 && improvement < threshold,
+                    "{}",
+                    improvement
+                );
+            }
+            reason => panic!("raison inattendue : {:?}", reason),
+        }
+    }
+
+    #[test]
+    fn test_deskew_checked_rejects_weak_improvement() {
+        let skewed = rotate_image(&text_lines_image(200), 2.0);
+        let strict = DeskewOptions {
+            min_relative_improvement: 100.0,
+            ..DeskewOptions::default()
+        };
+
+        let (output, outcome) = deskew_checked(&skewed, &strict);
+        assert_eq!(output, skewed);
+        assert!(!outcome.applied);
+        assert_eq!(outcome.angle, 0.0);
+        assert!(matches!(
+            outcome.reason,
+            DeskewReason::NotSignificant { threshold, .. } if threshold == 100.0
+        ));
+        assert!(
+            outcome
+                .reason
+                .to_string()
+                .starts_with("aucun angle significatif")
+        );
+    }
+
+    #[test]
+    fn test_preprocess_report_records_deskew_outcome() {
+        let config = PreprocessingConfig {
+            deskew: true,
+            ..PreprocessingConfig::default()
+        };
+        let blank = DynamicImage::ImageLuma8(GrayImage::from_pixel(64, 64, image::Luma([255])));
+
+        let (_, report) = preprocess_image_with_report(&blank, &config).expect("prétraitement");
+        let outcome = report.deskew.expect("décision du redressement");
+        assert!(!outcome.applied);
+        assert_eq!(outcome.reason, DeskewReason::Uniform);
+        assert!(
+            report
+                .to_string()
+                .contains("Redressement non appliqué : image uniforme")
+        );
+    }
+
+    #[test]
+    fn test_detect_skew_angle_horizontal_lines() {
+        // Lignes de texte horizontales -> l'angle détecté doit être proche de 0°
+        let img = text_lines_image(200);
+
+        let angle = detect_skew_angle(&img);
+
+        // L'angle détecté doit être proche de 0° (lignes déjà horizontales)
+        assert!(
+            angle.abs() <= 2.0,
+            "Angle détecté {} devrait être proche de 0°",
+            angle
+        );
+    }
+
+    #[test]
+    fn test_rotate_image_zero_angle() {
+        use image::Luma;
+
+        // Une rotation de 0° doit retourner une image très proche de l'originale
+        let mut img = GrayImage::new(10, 10);
+        for y in 0..10 {
+            for x in 0..10 {
+                img.put_pixel(x, y, Luma([(x * 25) as u8]));
+            }
+        }
+
+        let rotated = rotate_image(&img, 0.0);
+        assert_eq!(rotated.dimensions(), img.dimensions());
+
+        // Les pixels centraux (hors bords) doivent être quasi identiques
+        for y in 1..9 {
+            for x in 1..9 {
+                let orig = img.get_pixel(x, y)[0] as i16;
+                let rot = rotated.get_pixel(x, y)[0] as i16;
+                assert!(
+                    (orig - rot).abs() <= 2,
+                    "Pixel ({},{}) : orig={} rot={}",
+                    x,
+                    y,
+                    orig,
+                    rot
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_preprocess_pipeline_order() {
+        use image::{GenericImageView, Luma};
+
+        // Créer une image de test
+        let mut img = GrayImage::new(10, 10);
+        for y in 0..10 {
+            for x in 0..10 {
+                img.put_pixel(x, y, Luma([128]));
+            }
+        }
+
+        let dynamic_img = DynamicImage::ImageLuma8(img);
+
+        // Tester avec toutes les options activées
+        let config = PreprocessingConfig {
+            to_grayscale: true,
+            binarize: true,
+            binarization_method: BinarizationMethod::Fixed(128),
+            adjust_contrast: true,
+            contrast_factor: 1.5,
+            denoise: true,
+            deskew: true,
+            max_pixels: DEFAULT_MAX_PIXELS,
+            skip_blank_pages: false,
+            blank_detection: BlankDetectionOptions::default(),
+            split_double_pages: false,
+            despeckle: None,
+            grayscale_options: GrayscaleOptions::default(),
+            deskew_options: DeskewOptions::default(),
+        };
+
+        let result = preprocess_image(&dynamic_img, &config);
+
+        // Le pipeline devrait réussir sans erreur
+        assert!(result.is_ok(), "Preprocessing pipeline should succeed");
+
+        let processed = result.unwrap();
+        assert_eq!(
+            processed.dimensions(),
+            (10, 10),
+            "Dimensions should be preserved"
         );
-        assert_ne!(
-            BinarizationMethod::Fixed(100),
-            BinarizationMethod::Fixed(128)
+    }
+
+    #[test]
+    fn test_to_grayscale_from_rgb() {
+        use image::{Rgb, RgbImage};
+
+        // Créer une image RGB de test
+        let mut rgb_img = RgbImage::new(3, 3);
+        rgb_img.put_pixel(0, 0, Rgb([255, 0, 0])); // Rouge
+        rgb_img.put_pixel(1, 1, Rgb([0, 255, 0])); // Vert
+        rgb_img.put_pixel(2, 2, Rgb([0, 0, 255])); // Bleu
+
+        let dynamic_img = DynamicImage::ImageRgb8(rgb_img);
+
+        // Convertir en niveaux de gris
+        let gray = to_grayscale(&dynamic_img);
+
+        // Vérifier que l'image est bien en niveaux de gris
+        assert_eq!(gray.dimensions(), (3, 3));
+
+        // Vérifier que la conversion a réussi et que les pixels ont des valeurs valides
+        // (les pixels u8 sont automatiquement dans [0, 255])
+        assert_eq!(gray.pixels().count(), 9, "Should have 9 pixels");
+    }
+
+    #[test]
+    fn test_preprocess_with_minimal_config() {
+        use image::{GenericImageView, Luma};
+
+        // Créer une image de test
+        let mut img = GrayImage::new(5, 5);
+        for y in 0..5 {
+            for x in 0..5 {
+                img.put_pixel(x, y, Luma([150]));
+            }
+        }
+
+        let dynamic_img = DynamicImage::ImageLuma8(img);
+
+        // Configuration minimale : seulement grayscale
+        let config = PreprocessingConfig {
+            to_grayscale: true,
+            binarize: false,
+            binarization_method: BinarizationMethod::Otsu,
+            adjust_contrast: false,
+            contrast_factor: 1.0,
+            denoise: false,
+            deskew: false,
+            max_pixels: DEFAULT_MAX_PIXELS,
+            skip_blank_pages: false,
+            blank_detection: BlankDetectionOptions::default(),
+            split_double_pages: false,
+            despeckle: None,
+            grayscale_options: GrayscaleOptions::default(),
+            deskew_options: DeskewOptions::default(),
+        };
+
+        let result = preprocess_image(&dynamic_img, &config);
+
+        assert!(result.is_ok(), "Minimal preprocessing should succeed");
+
+        let processed = result.unwrap();
+        assert_eq!(processed.dimensions(), (5, 5));
+    }
+
+    #[test]
+    fn test_preprocess_only_binarization() {
+        use image::Luma;
+
+        // Créer une image de test avec des valeurs variées
+        let mut img = GrayImage::new(4, 4);
+        for y in 0..4 {
+            for x in 0..4 {
+                let value = if (x + y) % 2 == 0 { 50 } else { 200 };
+                img.put_pixel(x, y, Luma([value]));
+            }
+        }
+
+        let dynamic_img = DynamicImage::ImageLuma8(img);
+
+        // Configuration : seulement binarisation
+        let config = PreprocessingConfig {
+            to_grayscale: false,
+            binarize: true,
+            binarization_method: BinarizationMethod::Fixed(100),
+            adjust_contrast: false,
+            contrast_factor: 1.0,
+            denoise: false,
+            deskew: false,
+            max_pixels: DEFAULT_MAX_PIXELS,
+            skip_blank_pages: false,
+            blank_detection: BlankDetectionOptions::default(),
+            split_double_pages: false,
+            despeckle: None,
+            grayscale_options: GrayscaleOptions::default(),
+            deskew_options: DeskewOptions::default(),
+        };
+
+        let result = preprocess_image(&dynamic_img, &config);
+
+        assert!(
+            result.is_ok(),
+            "Binarization-only preprocessing should succeed"
+        );
+
+        let processed = result.unwrap();
+
+        // Vérifier que l'image est bien binarisée
+        let gray_result = processed.to_luma8();
+        for pixel in gray_result.pixels() {
+            assert!(
+                pixel[0] == 0 || pixel[0] == 255,
+                "Binarized pixel should be 0 or 255, got {}",
+                pixel[0]
+            );
+        }
+    }
+
+    #[test]
+    fn test_preprocess_contrast_then_binarize() {
+        use image::Luma;
+
+        // Créer une image avec faible contraste
+        let mut img = GrayImage::new(4, 4);
+        for y in 0..4 {
+            for x in 0..4 {
+                let value = if (x + y) % 2 == 0 { 100 } else { 140 };
+                img.put_pixel(x, y, Luma([value]));
+            }
+        }
+
+        let dynamic_img = DynamicImage::ImageLuma8(img);
+
+        // Configuration : augmenter le contraste puis binariser
+        let config = PreprocessingConfig {
+            to_grayscale: false,
+            binarize: true,
+            binarization_method: BinarizationMethod::Otsu,
+            adjust_contrast: true,
+            contrast_factor: 2.0,
+            denoise: false,
+            deskew: false,
+            max_pixels: DEFAULT_MAX_PIXELS,
+            skip_blank_pages: false,
+            blank_detection: BlankDetectionOptions::default(),
+            split_double_pages: false,
+            despeckle: None,
+            grayscale_options: GrayscaleOptions::default(),
+            deskew_options: DeskewOptions::default(),
+        };
+
+        let result = preprocess_image(&dynamic_img, &config);
+
+        assert!(
+            result.is_ok(),
+            "Contrast + binarization preprocessing should succeed"
+        );
+
+        let processed = result.unwrap();
+
+        // Vérifier que le résultat est binarisé
+        let gray_result = processed.to_luma8();
+        for pixel in gray_result.pixels() {
+            assert!(
+                pixel[0] == 0 || pixel[0] == 255,
+                "Final image should be binarized"
+            );
+        }
+    }
+
+    #[test]
+    fn test_preprocess_denoise_then_binarize() {
+        use image::{GenericImageView, Luma};
+
+        // Créer une image avec du bruit
+        let mut img = GrayImage::new(5, 5);
+        for y in 0..5 {
+            for x in 0..5 {
+                img.put_pixel(x, y, Luma([128]));
+            }
+        }
+        // Ajouter des pixels bruités
+        img.put_pixel(2, 2, Luma([0]));
+        img.put_pixel(1, 1, Luma([255]));
+
+        let dynamic_img = DynamicImage::ImageLuma8(img);
+
+        // Configuration : débruiter puis binariser
+        let config = PreprocessingConfig {
+            to_grayscale: false,
+            binarize: true,
+            binarization_method: BinarizationMethod::Fixed(128),
+            adjust_contrast: false,
+            contrast_factor: 1.0,
+            denoise: true,
+            deskew: false,
+            max_pixels: DEFAULT_MAX_PIXELS,
+            skip_blank_pages: false,
+            blank_detection: BlankDetectionOptions::default(),
+            split_double_pages: false,
+            despeckle: None,
+            grayscale_options: GrayscaleOptions::default(),
+            deskew_options: DeskewOptions::default(),
+        };
+
+        let result = preprocess_image(&dynamic_img, &config);
+
+        assert!(
+            result.is_ok(),
+            "Denoise + binarization preprocessing should succeed"
         );
-        assert_eq!(BinarizationMethod::Adaptive, BinarizationMethod::Adaptive);
+
+        let processed = result.unwrap();
+        assert_eq!(processed.dimensions(), (5, 5));
     }
 
     #[test]
-    fn test_binarize_fixed() {
-        use image::Luma;
+    fn test_binarization_method_clone() {
+        let method1 = BinarizationMethod::Otsu;
+        let method2 = method1;
 
-        // Créer une image de test 2x2
-        let mut img = GrayImage::new(2, 2);
-        img.put_pixel(0, 0, Luma([100]));
-        img.put_pixel(0, 1, Luma([150]));
-        img.put_pixel(1, 0, Luma([200]));
-        img.put_pixel(1, 1, Luma([50]));
+        assert_eq!(method1, method2);
 
-        // Binariser avec seuil 128
-        let binary = binarize(&img, BinarizationMethod::Fixed(128));
+        let method3 = BinarizationMethod::Fixed(150);
+        let method4 = method3;
 
-        assert_eq!(binary.get_pixel(0, 0)[0], 0); // 100 < 128 -> 0
-        assert_eq!(binary.get_pixel(0, 1)[0], 255); // 150 >= 128 -> 255
-        assert_eq!(binary.get_pixel(1, 0)[0], 255); // 200 >= 128 -> 255
-        assert_eq!(binary.get_pixel(1, 1)[0], 0); // 50 < 128 -> 0
+        assert_eq!(method3, method4);
+    }
+
+    #[test]
+    fn test_preprocessing_config_clone() {
+        let config1 = PreprocessingConfig::default();
+        let config2 = config1.clone();
+
+        assert_eq!(config1.to_grayscale, config2.to_grayscale);
+        assert_eq!(config1.binarize, config2.binarize);
+        assert_eq!(config1.binarization_method, config2.binarization_method);
+        assert_eq!(config1.adjust_contrast, config2.adjust_contrast);
+        assert_eq!(config1.contrast_factor, config2.contrast_factor);
+        assert_eq!(config1.denoise, config2.denoise);
+        assert_eq!(config1.deskew, config2.deskew);
     }
 
     #[test]
-    fn test_calculate_otsu_threshold() {
+    fn test_binarize_all_methods() {
         use image::Luma;
 
-        // Créer une image bimodale simple (fond clair, texte sombre)
+        // Créer une image de test
         let mut img = GrayImage::new(10, 10);
         for y in 0..10 {
             for x in 0..10 {
-                // Zone sombre (0-50) et zone claire (200-255)
-                let value = if x < 5 { 30 } else { 220 };
+                let value = if x < 5 { 60 } else { 180 };
                 img.put_pixel(x, y, Luma([value]));
             }
         }
 
-        let threshold = calculate_otsu_threshold(&img);
-
-        // Le seuil devrait séparer correctement les deux groupes
-        // Il devrait être entre les deux pics (de 30 à 220)
-        assert!(threshold >= 30, "Threshold {} should be >= 30", threshold);
-        assert!(threshold <= 220, "Threshold {} should be <= 220", threshold);
-    }
+        // Tester chaque méthode de binarisation
+        let methods = vec![
+            BinarizationMethod::Otsu,
+            BinarizationMethod::Fixed(120),
+            BinarizationMethod::Adaptive,
+        ];
 
-    #[test]
-    fn test_binarize_otsu() {
-        use image::Luma;
+        for method in methods {
+            let binary = binarize(&img, method);
 
-        // Créer une image avec deux niveaux distincts
-        let mut img = GrayImage::new(4, 4);
-        for y in 0..4 {
-            for x in 0..4 {
-                let value = if (x + y) % 2 == 0 { 50 } else { 200 };
-                img.put_pixel(x, y, Luma([value]));
+            // Vérifier que tous les pixels sont 0 ou 255
+            for pixel in binary.pixels() {
+                assert!(
+                    pixel[0] == 0 || pixel[0] == 255,
+                    "Binarization method {:?} should produce only 0 or 255",
+                    method
+                );
             }
         }
-
-        let binary = binarize(&img, BinarizationMethod::Otsu);
-
-        // Tous les pixels devraient être soit 0 soit 255
-        for pixel in binary.pixels() {
-            assert!(
-                pixel[0] == 0 || pixel[0] == 255,
-                "Pixel value should be 0 or 255, got {}",
-                pixel[0]
-            );
-        }
     }
 
     #[test]
-    fn test_binarize_adaptive() {
-        use image::Luma;
-
-        // Créer une image avec éclairage non uniforme (gradient)
-        let mut img = GrayImage::new(20, 20);
-        for y in 0..20 {
-            for x in 0..20 {
-                // Gradient de gauche (sombre) à droite (clair)
-                // Avec un pattern de texte (alternance)
-                let base = 50 + (x * 10); // Gradient 50 -> 240
-                let text_offset = if (x + y) % 3 == 0 { 0 } else { 40 };
-                let value = (base + text_offset).min(255) as u8;
-                img.put_pixel(x, y, Luma([value]));
-            }
-        }
-
-        let binary = binarize(&img, BinarizationMethod::Adaptive);
-
-        // Tous les pixels devraient être soit 0 soit 255
-        for pixel in binary.pixels() {
-            assert!(
-                pixel[0] == 0 || pixel[0] == 255,
-                "Pixel value should be 0 or 255, got {}",
-                pixel[0]
-            );
-        }
+    fn test_downscale_below_threshold_is_noop() {
+        let img = DynamicImage::new_luma8(200, 100);
+        assert!(downscale_to_max_pixels(&img, 20_000).is_none());
+        assert!(downscale_to_max_pixels(&img, 0).is_none());
+        assert_eq!(downscale_factor(200, 100, 20_000), None);
+    }
 
-        // Vérifier qu'il y a bien un mélange de pixels noirs et blancs
-        let mut black_count = 0;
-        let mut white_count = 0;
-        for pixel in binary.pixels() {
-            if pixel[0] == 0 {
-                black_count += 1;
-            } else {
-                white_count += 1;
-            }
-        }
+    #[test]
+    fn test_downscale_above_threshold_keeps_aspect_ratio() {
+        let img = DynamicImage::new_luma8(400, 100);
+        let downscaled = downscale_to_max_pixels(&img, 10_000).unwrap();
 
-        assert!(black_count > 0, "Should have some black pixels");
-        assert!(white_count > 0, "Should have some white pixels");
+        assert_eq!((downscaled.width(), downscaled.height()), (200, 50));
+        assert!(downscaled.width() as u64 * downscaled.height() as u64 <= 10_000);
     }
 
     #[test]
-    fn test_adjust_contrast_no_change() {
-        use image::Luma;
+    fn test_preprocess_image_applies_max_pixels() {
+        let img = DynamicImage::new_luma8(400, 100);
+        let config = PreprocessingConfig {
+            max_pixels: 10_000,
+            ..PreprocessingConfig::default()
+        };
+        let result = preprocess_image(&img, &config).unwrap();
+        assert_eq!((result.width(), result.height()), (200, 50));
+    }
 
-        // Créer une image de test
-        let mut img = GrayImage::new(2, 2);
-        img.put_pixel(0, 0, Luma([50]));
-        img.put_pixel(0, 1, Luma([128]));
-        img.put_pixel(1, 0, Luma([200]));
-        img.put_pixel(1, 1, Luma([100]));
+    #[test]
+    fn test_callback_fires_for_enabled_stages_in_order() {
+        let img = DynamicImage::new_luma8(400, 100);
+        let config = PreprocessingConfig {
+            to_grayscale: true,
+            binarize: true,
+            binarization_method: BinarizationMethod::Otsu,
+            adjust_contrast: true,
+            contrast_factor: 1.2,
+            denoise: true,
+            deskew: true,
+            max_pixels: 10_000,
+            skip_blank_pages: false,
+            blank_detection: BlankDetectionOptions::default(),
+            split_double_pages: false,
+            despeckle: None,
+            grayscale_options: GrayscaleOptions::default(),
+            deskew_options: DeskewOptions::default(),
+        };
 
-        // Appliquer un facteur de 1.0 (pas de changement)
-        let result = adjust_contrast(&img, 1.0);
+        let mut stages = Vec::new();
+        preprocess_image_with_callback(&img, &config, |stage, _| stages.push(stage.to_string()))
+            .unwrap();
 
-        // Les valeurs devraient être identiques
-        assert_eq!(result.get_pixel(0, 0)[0], 50);
-        assert_eq!(result.get_pixel(0, 1)[0], 128);
-        assert_eq!(result.get_pixel(1, 0)[0], 200);
-        assert_eq!(result.get_pixel(1, 1)[0], 100);
+        assert_eq!(
+            stages,
+            vec![
+                "downscale",
+                "grayscale",
+                "deskew",
+                "denoise",
+                "contrast",
+                "binarize"
+            ]
+        );
     }
 
     #[test]
-    fn test_adjust_contrast_increase() {
-        use image::Luma;
-
-        // Créer une image avec du gris moyen
-        let mut img = GrayImage::new(2, 2);
-        img.put_pixel(0, 0, Luma([100])); // Plus sombre que 128
-        img.put_pixel(0, 1, Luma([128])); // Point pivot
-        img.put_pixel(1, 0, Luma([150])); // Plus clair que 128
-        img.put_pixel(1, 1, Luma([180]));
+    fn test_callback_skips_disabled_stages() {
+        let img = DynamicImage::new_luma8(20, 20);
+        let config = PreprocessingConfig {
+            to_grayscale: false,
+            binarize: true,
+            binarization_method: BinarizationMethod::Fixed(128),
+            adjust_contrast: false,
+            contrast_factor: 1.0,
+            denoise: false,
+            deskew: false,
+            max_pixels: DEFAULT_MAX_PIXELS,
+            skip_blank_pages: false,
+            blank_detection: BlankDetectionOptions::default(),
+            split_double_pages: false,
+            despeckle: None,
+            grayscale_options: GrayscaleOptions::default(),
+            deskew_options: DeskewOptions::default(),
+        };
 
-        // Augmenter le contraste (facteur > 1.0)
-        let result = adjust_contrast(&img, 2.0);
+        let mut stages = Vec::new();
+        preprocess_image_with_callback(&img, &config, |stage, _| stages.push(stage.to_string()))
+            .unwrap();
 
-        // Les valeurs sombres devraient être plus sombres
-        assert!(
-            result.get_pixel(0, 0)[0] < 100,
-            "Dark pixel should become darker"
-        );
+        assert_eq!(stages, vec!["binarize"]);
+    }
 
-        // Le point pivot devrait rester à 128
-        assert_eq!(result.get_pixel(0, 1)[0], 128);
+    #[test]
+    fn test_failing_step_names_step_and_index() {
+        let img = DynamicImage::new_luma8(40, 30);
+        let mut stages = Vec::new();
+        let mut run = PipelineRun::new(&img, |stage: &str, _: &DynamicImage| {
+            stages.push(stage.to_string())
+        });
+        let gray = run
+            .apply("grayscale", &img, |img, _| {
+                Ok(Some(DynamicImage::ImageLuma8(to_grayscale(img))))
+            })
+            .unwrap()
+            .unwrap();
+        // Étape ignorée : pas comptée dans le rang de la suivante
+        assert!(run.apply("noop", &gray, |_, _| Ok(None)).unwrap().is_none());
+
+        let error = run
+            .apply("sauvola", &gray, |_, _| {
+                Err(anyhow::anyhow!("fenêtre plus grande que l'image"))
+            })
+            .unwrap_err();
 
-        // Les valeurs claires devraient être plus claires
-        assert!(
-            result.get_pixel(1, 0)[0] > 150,
-            "Bright pixel should become brighter"
-        );
-        assert!(
-            result.get_pixel(1, 1)[0] > 180,
-            "Bright pixel should become brighter"
+        assert_eq!(error.step, "sauvola");
+        assert_eq!(error.index, 2);
+        assert_eq!(error.dimensions, (40, 30));
+        assert_eq!(
+            error.to_string(),
+            "étape 2 (sauvola) en échec sur une image 40×30 : fenêtre plus grande que l'image"
         );
+        assert_eq!(run.report.applied_steps, ["grayscale"]);
+        assert_eq!(stages, ["grayscale"]);
     }
 
     #[test]
-    fn test_adjust_contrast_decrease() {
-        use image::Luma;
-
-        // Créer une image avec des valeurs contrastées
-        let mut img = GrayImage::new(2, 2);
-        img.put_pixel(0, 0, Luma([50])); // Très sombre
-        img.put_pixel(0, 1, Luma([200])); // Très clair
+    fn test_deskew_skipped_on_tiny_image() {
+        let img = DynamicImage::new_luma8(5, 5);
+        let config = PreprocessingConfig {
+            deskew: true,
+            binarize: true,
+            ..PreprocessingConfig::default()
+        };
 
-        // Diminuer le contraste (facteur < 1.0)
-        let result = adjust_contrast(&img, 0.5);
+        let (output, report) = preprocess_image_with_report(&img, &config).unwrap();
 
-        // Les valeurs devraient se rapprocher de 128
-        assert!(
-            result.get_pixel(0, 0)[0] > 50,
-            "Dark pixel should become lighter"
-        );
+        assert_eq!((output.width(), output.height()), (5, 5));
+        assert_eq!(report.applied_steps, ["grayscale", "binarize"]);
+        assert_eq!(report.detected_skew_angle, None);
+        assert_eq!(report.warnings.len(), 1);
         assert!(
-            result.get_pixel(0, 1)[0] < 200,
-            "Bright pixel should become darker"
+            report.warnings[0].starts_with("deskew ignoré : image trop petite (5×5"),
+            "{:?}",
+            report.warnings
         );
+        assert!(report.to_string().contains("⚠ deskew ignoré"));
     }
 
     #[test]
-    fn test_adjust_contrast_clamping() {
-        use image::Luma;
+    fn test_preprocess_image_with_dump_writes_named_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let img = DynamicImage::new_luma8(20, 20);
+        let config = PreprocessingConfig {
+            to_grayscale: true,
+            deskew: true,
+            ..PreprocessingConfig::default()
+        };
 
-        // Créer une image avec des valeurs extrêmes
-        let mut img = GrayImage::new(2, 2);
-        img.put_pixel(0, 0, Luma([10])); // Très sombre
-        img.put_pixel(0, 1, Luma([240])); // Très clair
+        let (_, files) = preprocess_image_with_dump(&img, &config, dir.path(), "page").unwrap();
 
-        // Augmenter fortement le contraste
-        let result = adjust_contrast(&img, 3.0);
+        let names: Vec<String> = files
+            .iter()
+            .map(|f| f.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["page_1_grayscale.png", "page_2_deskew.png"]);
+        for file in &files {
+            assert!(file.exists());
+        }
+    }
 
-        // Avec facteur 3.0:
-        // Pixel 0,0: ((10 - 128) * 3.0) + 128 = -354 + 128 = -226 -> clamped to 0
-        // Pixel 0,1: ((240 - 128) * 3.0) + 128 = 336 + 128 = 464 -> clamped to 255
-        assert_eq!(
-            result.get_pixel(0, 0)[0],
-            0,
-            "Very dark pixel with high contrast should clamp to 0"
-        );
-        assert_eq!(
-            result.get_pixel(0, 1)[0],
-            255,
-            "Very bright pixel with high contrast should clamp to 255"
-        );
+    /// Crée une image carrée de lignes de texte, centrées avec une marge
+    /// suffisante pour qu'une rotation ne les coupe pas.
+    fn text_lines_image(size: u32) -> GrayImage {
+        use crate::testutil::{RenderOptions, render_text_image};
+
+        let text = render_text_image(
+            "Lorem ipsum dolor\nsit amet, conse\nctetur adipiscin\ng elit, sed do e\niusmod tempor in",
+            &RenderOptions {
+                scale: 1,
+                margin: 0,
+                ..RenderOptions::default()
+            },
+        )
+        .to_luma8();
+
+        let mut img = GrayImage::from_pixel(size, size, image::Luma([255]));
+        let x = (size.saturating_sub(text.width()) / 2) as i64;
+        let y = (size.saturating_sub(text.height()) / 2) as i64;
+        imageops::replace(&mut img, &text, x, y);
+        img
     }
 
-    #[test]
-    fn test_denoise_removes_salt_and_pepper() {
-        use image::Luma;
+    #[test]
+    fn test_orientation_info_from_osd_output() {
+        let osd = "Page number: 0\nOrientation in degrees: 270\nRotate: 90\n\
+                   Orientation confidence: 1.75\nScript: Cyrillic\nScript confidence: 2.5";
+        let info = OrientationInfo::from_osd_output(osd).unwrap();
 
-        // Créer une image 5x5 avec bruit salt-and-pepper
-        let mut img = GrayImage::new(5, 5);
+        assert_eq!(info.orientation, Orientation::CounterClockwise90);
+        assert_eq!(info.orientation_confidence, Some(1.75));
+        assert_eq!(info.script.as_deref(), Some("Cyrillic"));
+        assert_eq!(info.script_confidence, Some(2.5));
 
-        // Remplir avec une valeur uniforme
-        for y in 0..5 {
-            for x in 0..5 {
-                img.put_pixel(x, y, Luma([128]));
-            }
-        }
+        assert!(OrientationInfo::from_osd_output("Script: Latin").is_none());
+    }
 
-        // Ajouter du bruit (pixels isolés)
-        img.put_pixel(2, 2, Luma([0])); // Pepper (noir)
-        img.put_pixel(1, 1, Luma([255])); // Salt (blanc)
-        img.put_pixel(3, 3, Luma([255])); // Salt (blanc)
+    #[test]
+    fn test_full_orientation_correction_near_90_degrees() {
+        let original = text_lines_image(200);
+        let rotated = rotate_image(&original, 95.0);
 
-        let denoised = denoise(&img);
+        let (corrected, angle) =
+            full_orientation_correction(&DynamicImage::ImageLuma8(rotated), None);
 
-        // Les pixels bruités au centre devraient être corrigés
-        // Le filtre médian remplace les valeurs aberrantes par la médiane du voisinage
-        assert_ne!(
-            denoised.get_pixel(2, 2)[0],
-            0,
-            "Pepper noise should be removed"
-        );
-        assert_ne!(
-            denoised.get_pixel(1, 1)[0],
-            255,
-            "Salt noise should be removed"
+        assert!(
+            (angle - (-95.0)).abs() <= 1.5,
+            "Angle total {} trop éloigné de -95°",
+            angle
         );
 
-        // Les pixels corrigés devraient être proches de 128
+        let original_variance = projection_variance(&original, 0.0, 255.0);
+        let corrected_variance = projection_variance(&corrected.to_luma8(), 0.0, 255.0);
         assert!(
-            (denoised.get_pixel(2, 2)[0] as i16 - 128).abs() < 10,
-            "Denoised pixel should be close to 128"
+            corrected_variance > original_variance * 0.7,
+            "Variance corrigée {} trop faible (originale {})",
+            corrected_variance,
+            original_variance
         );
     }
 
     #[test]
-    fn test_denoise_preserves_edges() {
-        use image::Luma;
+    fn test_full_orientation_correction_with_osd() {
+        let original = text_lines_image(200);
+        let osd = OrientationInfo {
+            orientation: Orientation::UpsideDown,
+            orientation_confidence: None,
+            script: None,
+            script_confidence: None,
+        };
 
-        // Créer une image avec un contour net (moitié noire, moitié blanche)
-        let mut img = GrayImage::new(5, 5);
+        let (_, angle) =
+            full_orientation_correction(&DynamicImage::ImageLuma8(original), Some(&osd));
+        assert!((angle - 180.0).abs() <= 0.5);
+    }
 
-        for y in 0..5 {
-            for x in 0..5 {
-                let value = if x < 2 { 50 } else { 200 };
-                img.put_pixel(x, y, Luma([value]));
-            }
-        }
+    #[test]
+    fn test_deskew_restores_skewed_lines() {
+        let original = text_lines_image(200);
+        let skewed = rotate_image(&original, 5.0);
 
-        let denoised = denoise(&img);
+        let corrected = deskew(&skewed);
 
-        // Les zones uniformes devraient rester similaires
-        assert_eq!(
-            denoised.get_pixel(1, 2)[0],
-            50,
-            "Dark area should be preserved"
-        );
-        assert_eq!(
-            denoised.get_pixel(3, 2)[0],
-            200,
-            "Bright area should be preserved"
-        );
+        let angle = detect_skew_angle(&corrected);
+        assert!(angle.abs() <= 0.5, "Inclinaison résiduelle {}°", angle);
     }
 
     #[test]
-    fn test_denoise_median_calculation() {
-        use image::Luma;
-
-        // Créer une image de test 3x3 avec des valeurs connues
-        let mut img = GrayImage::new(3, 3);
-        let values = [
-            [10, 20, 30],
-            [40, 100, 60], // Centre = 100, médiane du voisinage devrait être calculée
-            [70, 80, 90],
-        ];
+    fn test_normalize_angle() {
+        assert_eq!(normalize_angle(-95.0), -95.0);
+        assert_eq!(normalize_angle(270.0), -90.0);
+        assert_eq!(normalize_angle(-180.0), 180.0);
+    }
 
-        for y in 0..3 {
-            for x in 0..3 {
-                img.put_pixel(x, y, Luma([values[y as usize][x as usize]]));
+    /// Retourne les données du chunk `pHYs` d'un flux PNG.
+    fn find_phys_chunk(png: &[u8]) -> Option<&[u8]> {
+        let mut offset = 8;
+        while offset + 8 <= png.len() {
+            let length = u32::from_be_bytes(png[offset..offset + 4].try_into().unwrap()) as usize;
+            let chunk_type = &png[offset + 4..offset + 8];
+            let data = &png[offset + 8..offset + 8 + length];
+            if chunk_type == b"pHYs" {
+                return Some(data);
             }
+            offset += 12 + length;
         }
+        None
+    }
 
-        let denoised = denoise(&img);
+    #[test]
+    fn test_save_with_dpi_writes_phys_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dpi.png");
+        let img = DynamicImage::ImageLuma8(GrayImage::from_pixel(30, 10, image::Luma([200])));
+
+        save_with_dpi(&img, &path, 300).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let data = find_phys_chunk(&bytes).expect("chunk pHYs absent");
+        assert_eq!(data.len(), 9);
+        let ppm_x = u32::from_be_bytes(data[0..4].try_into().unwrap());
+        let ppm_y = u32::from_be_bytes(data[4..8].try_into().unwrap());
+        assert_eq!(ppm_x, ppm_y);
+        assert_eq!(data[8], 1);
+        assert_eq!((ppm_x as f64 * METERS_PER_INCH).round() as u32, 300);
+
+        // Le fichier reste un PNG valide (CRC du chunk inséré compris)
+        let decoded = image::open(&path).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (30, 10));
+    }
 
-        // Le pixel central devrait être la médiane de [10,20,30,40,100,60,70,80,90]
-        // Trié: [10,20,30,40,60,70,80,90,100]
-        // Médiane (index 4): 60
-        assert_eq!(
-            denoised.get_pixel(1, 1)[0],
-            60,
-            "Center pixel should be the median of neighborhood"
+    #[test]
+    fn test_preprocess_report_deskew_and_otsu() {
+        use crate::testutil::{RenderOptions, render_text_image};
+
+        let skewed = render_text_image(
+            "Lorem ipsum dolor\nsit amet, conse\nctetur adipiscin\ng elit, sed do e",
+            &RenderOptions {
+                rotation_degrees: 5.0,
+                ..RenderOptions::default()
+            },
         );
+        let config = PreprocessingConfig {
+            deskew: true,
+            binarize: true,
+            binarization_method: BinarizationMethod::Otsu,
+            ..PreprocessingConfig::default()
+        };
+
+        let (output, report) = preprocess_image_with_report(&skewed, &config).unwrap();
+
+        assert_eq!(report.applied_steps, ["grayscale", "deskew", "binarize"]);
+        let angle = report.detected_skew_angle.unwrap();
+        assert!((angle + 5.0).abs() <= 1.0, "angle détecté : {}", angle);
+        let threshold = report.otsu_threshold.unwrap();
+        assert!((50..=200).contains(&threshold), "seuil : {}", threshold);
+        assert_eq!(report.contrast_factor_used, None);
+        assert!(!report.inverted);
+        assert_eq!(report.input_dimensions, (skewed.width(), skewed.height()));
+        assert_eq!(report.output_dimensions, (output.width(), output.height()));
+
+        // preprocess_image produit la même image, sans rapport
+        let plain = preprocess_image(&skewed, &config).unwrap();
+        assert_eq!(plain.to_luma8(), output.to_luma8());
     }
 
     #[test]
-    fn test_deskew_preserves_dimensions() {
-        use image::Luma;
+    fn test_preprocess_report_steps_follow_flags() {
+        let img = DynamicImage::ImageLuma8(GrayImage::from_pixel(200, 100, image::Luma([180])));
+        let config = PreprocessingConfig {
+            to_grayscale: true,
+            binarize: true,
+            binarization_method: BinarizationMethod::Fixed(128),
+            adjust_contrast: true,
+            contrast_factor: 1.5,
+            denoise: true,
+            deskew: true,
+            max_pixels: 5_000,
+            skip_blank_pages: false,
+            blank_detection: BlankDetectionOptions::default(),
+            split_double_pages: false,
+            despeckle: None,
+            grayscale_options: GrayscaleOptions::default(),
+            deskew_options: DeskewOptions::default(),
+        };
 
-        // Créer une image uniforme (angle nul attendu)
-        let mut img = GrayImage::new(20, 20);
-        for y in 0..20 {
-            for x in 0..20 {
-                img.put_pixel(x, y, Luma([200]));
-            }
+        let (_, report) = preprocess_image_with_report(&img, &config).unwrap();
+
+        assert_eq!(
+            report.applied_steps,
+            [
+                "downscale",
+                "grayscale",
+                "deskew",
+                "denoise",
+                "contrast",
+                "binarize"
+            ]
+        );
+        assert_eq!(report.input_dimensions, (200, 100));
+        assert_eq!(report.output_dimensions, (100, 50));
+        assert_eq!(report.scale_factor, 0.5);
+        assert!(report.to_string().contains("Facteur d'échelle : 0.500"));
+        assert_eq!(report.contrast_factor_used, Some(1.5));
+        // Seuil fixe : pas de seuil d'Otsu calculé
+        assert_eq!(report.otsu_threshold, None);
+        assert!(report.detected_skew_angle.is_some());
+
+        let (_, minimal) = preprocess_image_with_report(
+            &img,
+            &PreprocessingConfig {
+                to_grayscale: false,
+                ..PreprocessingConfig::default()
+            },
+        )
+        .unwrap();
+        assert!(minimal.applied_steps.is_empty());
+        assert_eq!(minimal.scale_factor, 1.0);
+        assert!(!minimal.to_string().contains("Facteur d'échelle"));
+        assert_eq!(minimal.detected_skew_angle, None);
+        assert_eq!(
+            minimal.to_string().lines().next(),
+            Some("Prétraitement : 200x100 → 200x100")
+        );
+    }
+
+    /// Page blanche de 200 × 200 avec `count` pixels noirs à des positions pseudo-aléatoires.
+    fn page_with_specks(count: usize) -> GrayImage {
+        let mut page = GrayImage::from_pixel(200, 200, image::Luma([255]));
+        let mut rng = SplitMix64::new(42);
+        for _ in 0..count {
+            let x = (rng.next_u64() % 200) as u32;
+            let y = (rng.next_u64() % 200) as u32;
+            page.put_pixel(x, y, image::Luma([0]));
         }
+        page
+    }
 
-        let deskewed = deskew(&img);
+    #[test]
+    fn test_is_blank_page_white() {
+        let result = is_blank_page(&page_with_specks(0), &BlankDetectionOptions::default());
+
+        assert_eq!(result.content, PageContent::Blank);
+        assert_eq!(result.foreground_ratio, 0.0);
+        assert_eq!(result.component_count, 0);
+
+        // Grain du papier : contraste trop faible pour contenir du texte
+        let mut grain = GrayImage::from_pixel(50, 50, image::Luma([240]));
+        grain.put_pixel(10, 10, image::Luma([228]));
+        let result = is_blank_page(&grain, &BlankDetectionOptions::default());
+        assert_eq!(result.content, PageContent::Blank);
+    }
 
-        // Les dimensions doivent être conservées
-        assert_eq!(deskewed.dimensions(), img.dimensions());
+    #[test]
+    fn test_is_blank_page_specks() {
+        let result = is_blank_page(&page_with_specks(5), &BlankDetectionOptions::default());
+
+        assert_eq!(result.content, PageContent::NearBlank);
+        assert!(result.component_count >= 1 && result.component_count <= 5);
+        assert!(result.largest_component <= 5);
+        assert!(result.foreground_ratio > 0.0);
     }
 
     #[test]
-    fn test_deskew_uniform_image_unchanged() {
-        use image::Luma;
+    fn test_is_blank_page_text() {
+        use crate::testutil::{RenderOptions, render_text_image};
 
-        // Une image uniforme n'a pas d'inclinaison détectable
-        // -> deskew doit retourner l'image quasi inchangée
-        let mut img = GrayImage::new(30, 30);
-        for y in 0..30 {
-            for x in 0..30 {
-                img.put_pixel(x, y, Luma([200]));
-            }
-        }
+        let page = to_grayscale(&render_text_image(
+            "Facture 2024",
+            &RenderOptions::default(),
+        ));
+        let result = is_blank_page(&page, &BlankDetectionOptions::default());
 
-        let deskewed = deskew(&img);
-        assert_eq!(deskewed.dimensions(), (30, 30));
+        assert_eq!(result.content, PageContent::HasContent);
+        assert!(result.largest_component > 16);
     }
 
     #[test]
-    fn test_detect_skew_angle_horizontal_lines() {
-        use image::Luma;
+    fn test_is_blank_page_thresholds() {
+        let options = BlankDetectionOptions {
+            max_speck_ratio: 0.0,
+            ..BlankDetectionOptions::default()
+        };
+        let result = is_blank_page(&page_with_specks(5), &options);
+        assert_eq!(result.content, PageContent::HasContent);
 
-        // Créer une image avec des lignes horizontales (texte simulé)
-        // -> l'angle détecté doit être proche de 0°
-        let width = 60u32;
-        let height = 40u32;
-        let mut img = GrayImage::new(width, height);
+        let component_areas = component_areas(
+            &[true, false, false, false, true, false, false, false, true],
+            3,
+        );
+        // Diagonale : une seule composante en 8-connexité
+        assert_eq!(component_areas, vec![3]);
+    }
 
-        // Fond blanc
-        for y in 0..height {
-            for x in 0..width {
-                img.put_pixel(x, y, Luma([255]));
-            }
-        }
+    /// Scan sombre bruité : bandes de texte (~30) sur fond gris (~80).
+    ///
+    /// Bruit triangulaire de ±30 : les deux classes se recouvrent, le seuil
+    /// d'Otsu tombe dans la vallée entre elles.
+    fn dark_scan(width: u32, height: u32) -> GrayImage {
+        let mut rng = SplitMix64::new(0x5EED);
+        GrayImage::from_fn(width, height, |_, y| {
+            let noise = (rng.next_u64() % 31) as i32 + (rng.next_u64() % 31) as i32 - 30;
+            let base = if (y / 12) % 2 == 0 { 80 } else { 30 };
+            image::Luma([(base + noise) as u8])
+        })
+    }
 
-        // Lignes sombres horizontales (simulation de texte)
-        for row in [8u32, 18, 28] {
-            for x in 5..55 {
-                img.put_pixel(x, row, Luma([30]));
-            }
-        }
+    #[test]
+    fn test_rotation_mask_matches_white_wedges() {
+        let black = GrayImage::new(200, 120);
 
-        let angle = detect_skew_angle(&img);
+        let (rotated, mask) = rotate_image_with_mask(&black, 15.0);
 
-        // L'angle détecté doit être proche de 0° (lignes déjà horizontales)
+        // Source noire : seuls les coins remplis sont blancs
+        let fill_area = rotated.pixels().filter(|p| p[0] == 255).count();
+        let masked_area = mask.pixels().filter(|p| p[0] == 0).count();
+        assert!(fill_area > 0);
+        assert_eq!(masked_area, fill_area);
         assert!(
-            angle.abs() <= 2.0,
-            "Angle détecté {} devrait être proche de 0°",
-            angle
+            rotated
+                .pixels()
+                .zip(mask.pixels())
+                .all(|(pixel, mask)| (pixel[0] == 255) == (mask[0] == 0))
         );
+        assert_eq!(rotate_image(&black, 15.0), rotated);
     }
 
     #[test]
-    fn test_rotate_image_zero_angle() {
-        use image::Luma;
-
-        // Une rotation de 0° doit retourner une image très proche de l'originale
-        let mut img = GrayImage::new(10, 10);
-        for y in 0..10 {
-            for x in 0..10 {
-                img.put_pixel(x, y, Luma([(x * 25) as u8]));
-            }
-        }
+    fn test_masked_otsu_ignores_rotation_fill() {
+        let original = dark_scan(240, 240);
+        let expected = otsu_threshold(&original);
 
-        let rotated = rotate_image(&img, 0.0);
-        assert_eq!(rotated.dimensions(), img.dimensions());
+        let (rotated, mask) = rotate_image_with_mask(&original, 15.0);
 
-        // Les pixels centraux (hors bords) doivent être quasi identiques
-        for y in 1..9 {
-            for x in 1..9 {
-                let orig = img.get_pixel(x, y)[0] as i16;
-                let rot = rotated.get_pixel(x, y)[0] as i16;
-                assert!(
-                    (orig - rot).abs() <= 2,
-                    "Pixel ({},{}) : orig={} rot={}",
-                    x,
-                    y,
-                    orig,
-                    rot
-                );
-            }
-        }
+        let masked = otsu_threshold_masked(&rotated, &mask);
+        assert!(
+            masked.abs_diff(expected) <= 2,
+            "seuil masqué {} (original {})",
+            masked,
+            expected
+        );
+        // Sans masque, le remplissage blanc attire le seuil vers le fond du scan
+        let unmasked = otsu_threshold(&rotated);
+        assert!(unmasked.abs_diff(expected) > 10, "seuil brut {}", unmasked);
     }
 
     #[test]
-    fn test_preprocess_pipeline_order() {
-        use image::{GenericImageView, Luma};
+    fn test_blank_page_masked_ignores_rotation_fill() {
+        // Page grise uniforme (papier sombre) pivotée : les coins blancs ressemblent à du contenu
+        let page = GrayImage::from_pixel(200, 200, image::Luma([110]));
+        let (rotated, mask) = rotate_image_with_mask(&page, 15.0);
+        let options = BlankDetectionOptions::default();
 
-        // Créer une image de test
-        let mut img = GrayImage::new(10, 10);
-        for y in 0..10 {
-            for x in 0..10 {
-                img.put_pixel(x, y, Luma([128]));
-            }
-        }
+        assert_eq!(
+            is_blank_page(&rotated, &options).content,
+            PageContent::HasContent
+        );
+        assert_eq!(
+            is_blank_page_masked(&rotated, &mask, &options).content,
+            PageContent::Blank
+        );
 
-        let dynamic_img = DynamicImage::ImageLuma8(img);
+        // Masque entièrement à 0 : aucun pixel à analyser
+        let empty = GrayImage::new(200, 200);
+        assert_eq!(
+            is_blank_page_masked(&rotated, &empty, &options).content,
+            PageContent::Blank
+        );
+    }
 
-        // Tester avec toutes les options activées
+    #[test]
+    fn test_preprocess_image_with_mask() {
         let config = PreprocessingConfig {
-            to_grayscale: true,
-            binarize: true,
-            binarization_method: BinarizationMethod::Fixed(128),
-            adjust_contrast: true,
-            contrast_factor: 1.5,
-            denoise: true,
             deskew: true,
+            ..PreprocessingConfig::default()
         };
 
-        let result = preprocess_image(&dynamic_img, &config);
+        // Sans rotation : masque plein
+        let straight = DynamicImage::ImageLuma8(text_lines_image(200));
+        let (_, mask) = preprocess_image_with_mask(&straight, &config).unwrap();
+        assert_eq!(mask.dimensions(), (200, 200));
+        assert!(mask.pixels().all(|p| p[0] == 255));
+
+        // Avec rotation : le masque suit le redressement
+        let skewed = DynamicImage::ImageLuma8(rotate_image(&text_lines_image(200), 5.0));
+        let (output, mask) = preprocess_image_with_mask(&skewed, &config).unwrap();
+        assert_eq!(mask.dimensions(), (output.width(), output.height()));
+        let (_, expected_mask) =
+            rotate_image_with_mask(&skewed.to_luma8(), report_angle(&skewed, &config));
+        assert_eq!(mask, expected_mask);
+        assert!(mask.pixels().any(|p| p[0] == 0));
+    }
 
-        // Le pipeline devrait réussir sans erreur
-        assert!(result.is_ok(), "Preprocessing pipeline should succeed");
+    /// Angle de correction retenu par le pipeline.
+    fn report_angle(image: &DynamicImage, config: &PreprocessingConfig) -> f64 {
+        let (_, report) = preprocess_image_with_report(image, config).unwrap();
+        report.detected_skew_angle.unwrap()
+    }
 
-        let processed = result.unwrap();
+    /// Page synthétique : blocs de « texte » (lignes de mots sombres de
+    /// largeurs aléatoires) sur fond blanc, un bloc par intervalle de colonnes.
+    fn text_blocks(width: u32, height: u32, blocks: &[(u32, u32)]) -> GrayImage {
+        let mut image = GrayImage::from_pixel(width, height, image::Luma([255]));
+        let mut rng = SplitMix64::new(0xB00C);
+        for &(left, right) in blocks {
+            for line_top in (30..height - 30).step_by(20) {
+                let mut x = left;
+                while x < right {
+                    let word = 15 + (rng.next_u64() % 45) as u32;
+                    for wx in x..(x + word).min(right) {
+                        for y in line_top..line_top + 10 {
+                            image.put_pixel(wx, y, image::Luma([20]));
+                        }
+                    }
+                    x += word + 5 + (rng.next_u64() % 10) as u32;
+                }
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn test_detect_page_split_in_gutter() {
+        // Gouttière blanche de 60 px entre 370 et 430
+        let image = text_blocks(800, 400, &[(40, 370), (430, 760)]);
+
+        let split = detect_page_split(&image).expect("double page non détectée");
+
+        assert!(split.abs_diff(400) <= 10, "coupe à x = {}", split);
+    }
+
+    #[test]
+    fn test_detect_page_split_single_block() {
+        let image = text_blocks(800, 400, &[(40, 760)]);
+        assert_eq!(detect_page_split(&image), None);
+
+        // Page blanche, image trop étroite
         assert_eq!(
-            processed.dimensions(),
-            (10, 10),
-            "Dimensions should be preserved"
+            detect_page_split(&GrayImage::from_pixel(800, 400, image::Luma([255]))),
+            None
         );
+        assert_eq!(detect_page_split(&GrayImage::new(20, 400)), None);
     }
 
     #[test]
-    fn test_to_grayscale_from_rgb() {
-        use image::{Rgb, RgbImage};
-
-        // Créer une image RGB de test
-        let mut rgb_img = RgbImage::new(3, 3);
-        rgb_img.put_pixel(0, 0, Rgb([255, 0, 0])); // Rouge
-        rgb_img.put_pixel(1, 1, Rgb([0, 255, 0])); // Vert
-        rgb_img.put_pixel(2, 2, Rgb([0, 0, 255])); // Bleu
+    fn test_detect_page_split_never_inside_text_block() {
+        // Gouttière décentrée (300..360) : la coupe reste entre les blocs
+        let image = text_blocks(800, 400, &[(40, 300), (360, 760)]);
 
-        let dynamic_img = DynamicImage::ImageRgb8(rgb_img);
+        let split = detect_page_split(&image).expect("double page non détectée");
 
-        // Convertir en niveaux de gris
-        let gray = to_grayscale(&dynamic_img);
+        assert!((300..=360).contains(&split), "coupe à x = {}", split);
 
-        // Vérifier que l'image est bien en niveaux de gris
-        assert_eq!(gray.dimensions(), (3, 3));
+        // Une seule marge latérale vide : pas de seconde page
+        let image = text_blocks(800, 400, &[(40, 330)]);
+        assert_eq!(detect_page_split(&image), None);
+    }
 
-        // Vérifier que la conversion a réussi et que les pixels ont des valeurs valides
-        // (les pixels u8 sont automatiquement dans [0, 255])
-        assert_eq!(gray.pixels().count(), 9, "Should have 9 pixels");
+    #[test]
+    fn test_detect_vertical_text() {
+        let horizontal = text_blocks(400, 400, &[(40, 360)]);
+        assert!(!detect_vertical_text(&horizontal));
+
+        // Mêmes lignes tournées d'un quart de tour : des barres verticales
+        let vertical = imageops::rotate90(&horizontal);
+        assert!(detect_vertical_text(&vertical));
+
+        // Une seule ligne de texte horizontale : rangées vides autour, pas de colonnes
+        let single_line = text_blocks(400, 80, &[(40, 360)]);
+        assert!(!detect_vertical_text(&single_line));
+
+        assert!(!detect_vertical_text(&GrayImage::from_pixel(
+            100,
+            100,
+            image::Luma([255])
+        )));
+        assert!(!detect_vertical_text(&GrayImage::new(0, 0)));
     }
 
     #[test]
-    fn test_preprocess_with_minimal_config() {
-        use image::{GenericImageView, Luma};
+    fn test_detect_text_lines_finds_each_strip() {
+        // Lignes de mots aux rangées 30, 50, 70, 90 et 110, hautes de 10 px
+        let mut image = text_blocks(400, 150, &[(40, 360)]);
+        assert_eq!(
+            detect_text_lines(&image),
+            [30..40, 50..60, 70..80, 90..100, 110..120]
+        );
 
-        // Créer une image de test
-        let mut img = GrayImage::new(5, 5);
-        for y in 0..5 {
-            for x in 0..5 {
-                img.put_pixel(x, y, Luma([150]));
+        // Une tache de deux rangées n'est pas une ligne
+        for y in 5..7 {
+            for x in 100..300 {
+                image.put_pixel(x, y, image::Luma([20]));
             }
         }
+        assert_eq!(detect_text_lines(&image).len(), 5);
+
+        assert!(detect_text_lines(&GrayImage::from_pixel(50, 50, image::Luma([255]))).is_empty());
+        assert!(detect_text_lines(&GrayImage::new(0, 0)).is_empty());
+    }
 
-        let dynamic_img = DynamicImage::ImageLuma8(img);
+    #[test]
+    fn test_split_pages_dimensions() {
+        let image = DynamicImage::ImageLuma8(text_blocks(800, 400, &[(40, 370), (430, 760)]));
 
-        // Configuration minimale : seulement grayscale
-        let config = PreprocessingConfig {
-            to_grayscale: true,
-            binarize: false,
-            binarization_method: BinarizationMethod::Otsu,
-            adjust_contrast: false,
-            contrast_factor: 1.0,
-            denoise: false,
-            deskew: false,
-        };
+        use image::GenericImageView;
 
-        let result = preprocess_image(&dynamic_img, &config);
+        let (left, right) = split_pages(&image, 400);
+        assert_eq!(left.dimensions(), (400, 400));
+        assert_eq!(right.dimensions(), (400, 400));
 
-        assert!(result.is_ok(), "Minimal preprocessing should succeed");
+        let (left, right) = split_pages(&image, 5000);
+        assert_eq!((left.width(), right.width()), (800, 0));
+    }
 
-        let processed = result.unwrap();
-        assert_eq!(processed.dimensions(), (5, 5));
+    /// Image binaire dessinée à la main : `#` pour l'encre, tout autre caractère pour le fond.
+    fn drawn(rows: &[&str]) -> GrayImage {
+        GrayImage::from_fn(rows[0].len() as u32, rows.len() as u32, |x, y| {
+            let ink = rows[y as usize].as_bytes()[x as usize] == b'#';
+            image::Luma([if ink { 0 } else { 255 }])
+        })
     }
 
     #[test]
-    fn test_preprocess_only_binarization() {
-        use image::Luma;
+    fn test_connected_components_diagonal_blobs() {
+        let image = drawn(&["##...", "##...", "..##.", "..##.", "....#"]);
+
+        let four = connected_components(&image, Connectivity::Four);
+        let areas: Vec<u32> = four.components.iter().map(|c| c.area).collect();
+        assert_eq!(areas, [4, 4, 1]);
+        assert_eq!(four.labels.get_pixel(3, 3)[0], 2);
+        assert_eq!(four.labels.get_pixel(4, 0)[0], 0);
+        assert_eq!(four.components[1].bbox, BBox::new(2, 2, 2, 2));
+        assert_eq!(four.components[1].centroid, (2.5, 2.5));
+
+        let eight = connected_components(&image, Connectivity::Eight);
+        assert_eq!(eight.components.len(), 1);
+        assert_eq!(eight.components[0].area, 9);
+        assert_eq!(eight.components[0].bbox, BBox::new(0, 0, 5, 5));
+        assert_eq!(eight.components[0].centroid, (16.0 / 9.0, 16.0 / 9.0));
+    }
 
-        // Créer une image de test avec des valeurs variées
-        let mut img = GrayImage::new(4, 4);
-        for y in 0..4 {
-            for x in 0..4 {
-                let value = if (x + y) % 2 == 0 { 50 } else { 200 };
-                img.put_pixel(x, y, Luma([value]));
-            }
+    #[test]
+    fn test_connected_components_merges_branches() {
+        // Les deux branches reçoivent des étiquettes provisoires différentes
+        let image = drawn(&["#.#.#", "#.#.#", "#####", ".....", "##..#"]);
+
+        for connectivity in [Connectivity::Four, Connectivity::Eight] {
+            let result = connected_components(&image, connectivity);
+            let areas: Vec<u32> = result.components.iter().map(|c| c.area).collect();
+            assert_eq!(areas, [11, 2, 1], "{:?}", connectivity);
+            assert_eq!(result.labels.get_pixel(4, 0)[0], 1);
+            assert_eq!(result.labels.get_pixel(4, 4)[0], 3);
         }
+        assert!(
+            connected_components(&drawn(&["...", "..."]), Connectivity::Eight)
+                .components
+                .is_empty()
+        );
+    }
 
-        let dynamic_img = DynamicImage::ImageLuma8(img);
+    #[test]
+    fn test_connected_components_giant_component() {
+        // Peigne : 2000 dents verticales reliées par la dernière ligne
+        let size = 4000;
+        let image = GrayImage::from_fn(size, size, |x, y| {
+            image::Luma([if x % 2 == 0 || y == size - 1 { 0 } else { 255 }])
+        });
+
+        let start = std::time::Instant::now();
+        let result = connected_components(&image, Connectivity::Four);
+        assert!(start.elapsed() < std::time::Duration::from_secs(30));
+
+        assert_eq!(result.components.len(), 1);
+        assert_eq!(result.components[0].area, 2000 * size + 2000);
+        assert_eq!(result.components[0].bbox, BBox::new(0, 0, size, size));
+        assert_eq!(result.labels.get_pixel(size - 2, 0)[0], 1);
+    }
+
+    #[test]
+    fn test_despeckle_removes_dot_keeps_letter() {
+        let mut page = GrayImage::from_pixel(60, 40, image::Luma([255]));
+        // Point de 3 pixels
+        for (x, y) in [(50, 5), (51, 5), (50, 6)] {
+            page.put_pixel(x, y, image::Luma([0]));
+        }
+        // « T » de 50 pixels : barre 10×2 et jambage 2×15
+        for (x, y) in (5..15).flat_map(|x| (10..12).map(move |y| (x, y))) {
+            page.put_pixel(x, y, image::Luma([0]));
+        }
+        for (x, y) in (9..11).flat_map(|x| (12..27).map(move |y| (x, y))) {
+            page.put_pixel(x, y, image::Luma([0]));
+        }
+        let ink = |image: &GrayImage| image.pixels().filter(|p| p[0] == 0).count();
+        assert_eq!(ink(&page), 53);
 
-        // Configuration : seulement binarisation
         let config = PreprocessingConfig {
-            to_grayscale: false,
             binarize: true,
-            binarization_method: BinarizationMethod::Fixed(100),
-            adjust_contrast: false,
-            contrast_factor: 1.0,
-            denoise: false,
-            deskew: false,
+            binarization_method: BinarizationMethod::Fixed(128),
+            despeckle: Some(10),
+            ..PreprocessingConfig::default()
         };
+        let (cleaned, report) =
+            preprocess_image_with_report(&DynamicImage::ImageLuma8(page.clone()), &config).unwrap();
+        let cleaned = cleaned.to_luma8();
+
+        assert_eq!(report.applied_steps, ["grayscale", "binarize", "despeckle"]);
+        assert_eq!(ink(&cleaned), 50);
+        assert_eq!(cleaned.get_pixel(50, 5)[0], 255);
+        assert_eq!(cleaned.get_pixel(10, 20)[0], 0);
+        assert_eq!(remove_small_components(&page, 10), cleaned);
+        assert_eq!(remove_small_components(&page, 3), page);
+    }
 
-        let result = preprocess_image(&dynamic_img, &config);
+    #[test]
+    fn test_despeckle_skipped_without_binarization() {
+        let config = PreprocessingConfig {
+            despeckle: Some(10),
+            ..PreprocessingConfig::default()
+        };
+        let page = DynamicImage::ImageLuma8(GrayImage::from_pixel(20, 20, image::Luma([0])));
+        let (_, report) = preprocess_image_with_report(&page, &config).unwrap();
 
-        assert!(
-            result.is_ok(),
-            "Binarization-only preprocessing should succeed"
+        assert_eq!(report.applied_steps, ["grayscale"]);
+        assert_eq!(
+            report.warnings,
+            ["despeckle ignoré : binarisation désactivée"]
         );
+    }
 
-        let processed = result.unwrap();
+    #[test]
+    fn test_profiles_enable_documented_steps() {
+        let page = DynamicImage::ImageLuma8(GrayImage::from_fn(60, 60, |x, y| {
+            image::Luma([if (y / 6) % 2 == 0 && x > 5 && x < 55 {
+                20
+            } else {
+                230
+            }])
+        }));
+        let steps = |profile| {
+            let config = PreprocessingConfig::from_profile(profile);
+            preprocess_image_with_report(&page, &config)
+                .unwrap()
+                .1
+                .applied_steps
+        };
 
-        // Vérifier que l'image est bien binarisée
-        let gray_result = processed.to_luma8();
-        for pixel in gray_result.pixels() {
-            assert!(
-                pixel[0] == 0 || pixel[0] == 255,
-                "Binarized pixel should be 0 or 255, got {}",
-                pixel[0]
+        assert_eq!(steps(PreprocessingProfile::Fast), ["grayscale"]);
+        assert_eq!(
+            steps(PreprocessingProfile::Balanced),
+            ["grayscale", "binarize", "despeckle"]
+        );
+        assert_eq!(
+            steps(PreprocessingProfile::Best),
+            ["grayscale", "deskew", "denoise", "binarize", "despeckle"]
+        );
+
+        let balanced = PreprocessingConfig::balanced();
+        assert_eq!(balanced.binarization_method, BinarizationMethod::Otsu);
+        assert_eq!(balanced.despeckle, Some(PROFILE_DESPECKLE_AREA));
+        let best = PreprocessingConfig::best();
+        assert_eq!(best.binarization_method, BinarizationMethod::Adaptive);
+        assert!(!best.adjust_contrast && !best.skip_blank_pages);
+    }
+
+    #[test]
+    fn test_profile_from_str() {
+        assert_eq!(
+            "Balanced".parse::<PreprocessingProfile>().unwrap(),
+            PreprocessingProfile::Balanced
+        );
+        for profile in PreprocessingProfile::ALL {
+            assert_eq!(
+                profile.to_string().parse::<PreprocessingProfile>().unwrap(),
+                profile
             );
         }
+        let error = "turbo".parse::<PreprocessingProfile>().unwrap_err();
+        assert!(
+            error.to_string().contains("fast, balanced, best"),
+            "{}",
+            error
+        );
+    }
+
+    /// Nombre de pixels sombres (< 128) d'une image en niveaux de gris.
+    fn dark_pixels(gray: &GrayImage) -> usize {
+        gray.pixels().filter(|pixel| pixel[0] < 128).count()
     }
 
     #[test]
-    fn test_preprocess_contrast_then_binarize() {
-        use image::Luma;
+    fn test_grayscale_weights_and_red_dropout() {
+        use crate::testutil::{RenderOptions, render_text_image};
+        use image::Rgb;
+
+        // Texte rouge pur sur fond blanc (tampon, annotation)
+        let red_text = render_text_image(
+            "TAMPON",
+            &RenderOptions {
+                foreground: Rgb([255, 0, 0]),
+                ..RenderOptions::default()
+            },
+        );
+        let ink = dark_pixels(&to_grayscale(&render_text_image(
+            "TAMPON",
+            &RenderOptions::default(),
+        )));
+        assert!(ink > 0);
+
+        // Poids sur le vert et le bleu, absorbés par l'encre rouge : texte noir
+        let emphasized = GrayscaleOptions {
+            weights: ChannelWeights::Custom {
+                r: 0.0,
+                g: 0.5,
+                b: 0.5,
+            },
+            ..GrayscaleOptions::default()
+        };
+        assert_eq!(dark_pixels(&to_grayscale_with(&red_text, &emphasized)), ink);
 
-        // Créer une image avec faible contraste
-        let mut img = GrayImage::new(4, 4);
-        for y in 0..4 {
-            for x in 0..4 {
-                let value = if (x + y) % 2 == 0 { 100 } else { 140 };
-                img.put_pixel(x, y, Luma([value]));
+        // Élimination du rouge : le texte disparaît dans le fond
+        let dropped = GrayscaleOptions {
+            drop_channel: Some(Channel::Red),
+            ..GrayscaleOptions::default()
+        };
+        let gray = to_grayscale_with(&red_text, &dropped);
+        assert!(gray.pixels().all(|pixel| pixel[0] == 255));
+
+        // Le texte noir, lui, survit à l'élimination du rouge
+        let black_text = render_text_image("TAMPON", &RenderOptions::default());
+        assert_eq!(dark_pixels(&to_grayscale_with(&black_text, &dropped)), ink);
+    }
+
+    #[test]
+    fn test_transparent_png_on_white_background() {
+        use image::{Rgba, RgbaImage};
+
+        // Capture PNG : bord transparent (pixels invisibles noirs), contenu
+        // opaque blanc avec un trait noir
+        let mut screenshot = RgbaImage::from_pixel(40, 30, Rgba([0, 0, 0, 0]));
+        for y in 5..25 {
+            for x in 5..35 {
+                let value = if y == 15 { 0 } else { 255 };
+                screenshot.put_pixel(x, y, Rgba([value, value, value, 255]));
             }
         }
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.png");
+        screenshot.save(&path).unwrap();
+        let image = image::open(&path).unwrap();
 
-        let dynamic_img = DynamicImage::ImageLuma8(img);
+        // Comportement historique : bord noir
+        assert_eq!(to_grayscale(&image).get_pixel(0, 0)[0], 0);
 
-        // Configuration : augmenter le contraste puis binariser
-        let config = PreprocessingConfig {
-            to_grayscale: false,
-            binarize: true,
-            binarization_method: BinarizationMethod::Otsu,
-            adjust_contrast: true,
-            contrast_factor: 2.0,
-            denoise: false,
-            deskew: false,
+        let options = GrayscaleOptions {
+            alpha_background: Some(255),
+            ..GrayscaleOptions::default()
         };
+        let gray = to_grayscale_with(&image, &options);
+        assert_eq!(gray.get_pixel(0, 0)[0], 255);
+        assert_eq!(gray.get_pixel(20, 15)[0], 0, "trait opaque conservé");
+        assert_eq!(dark_pixels(&gray), 30);
+
+        // Pixel à moitié transparent : mélange avec le fond
+        let half = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([0, 0, 0, 128])));
+        assert_eq!(to_grayscale_with(&half, &options).get_pixel(0, 0)[0], 127);
+    }
 
-        let result = preprocess_image(&dynamic_img, &config);
+    #[test]
+    fn test_default_grayscale_options_match_to_grayscale() {
+        use image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+        let rgb = RgbImage::from_fn(32, 16, |x, y| {
+            Rgb([(x * 8) as u8, (y * 16) as u8, (x * y) as u8])
+        });
+        let rgba = RgbaImage::from_fn(32, 16, |x, y| {
+            Rgba([(x * 8) as u8, (y * 16) as u8, 200, (x * y) as u8])
+        });
+        let images = [
+            DynamicImage::ImageRgb8(rgb.clone()),
+            DynamicImage::ImageRgba8(rgba),
+            DynamicImage::ImageRgb16(DynamicImage::ImageRgb8(rgb).to_rgb16()),
+            DynamicImage::ImageLuma8(GrayImage::from_fn(8, 8, |x, y| Luma([(x * y) as u8]))),
+        ];
+        for image in &images {
+            let expected = to_grayscale(image);
+            let gray = to_grayscale_with(image, &GrayscaleOptions::default());
+            assert_eq!(gray.as_raw(), expected.as_raw(), "{:?}", image.color());
+        }
 
-        assert!(
-            result.is_ok(),
-            "Contrast + binarization preprocessing should succeed"
+        // Même pondération par le chemin complet (fond alpha opaque sans effet)
+        let opaque = DynamicImage::ImageRgb8(RgbImage::from_fn(16, 16, |x, y| {
+            Rgb([(x * 16) as u8, (y * 16) as u8, 77])
+        }));
+        let options = GrayscaleOptions {
+            alpha_background: Some(0),
+            ..GrayscaleOptions::default()
+        };
+        assert_eq!(
+            to_grayscale_with(&opaque, &options).as_raw(),
+            to_grayscale(&opaque).as_raw()
         );
-
-        let processed = result.unwrap();
-
-        // Vérifier que le résultat est binarisé
-        let gray_result = processed.to_luma8();
-        for pixel in gray_result.pixels() {
-            assert!(
-                pixel[0] == 0 || pixel[0] == 255,
-                "Final image should be binarized"
-            );
-        }
     }
 
     #[test]
-    fn test_preprocess_denoise_then_binarize() {
-        use image::{GenericImageView, Luma};
+    fn test_channel_weights_from_str() {
+        use image::Rgb;
 
-        // Créer une image avec du bruit
-        let mut img = GrayImage::new(5, 5);
-        for y in 0..5 {
-            for x in 0..5 {
-                img.put_pixel(x, y, Luma([128]));
+        assert_eq!(
+            "601".parse::<ChannelWeights>().unwrap(),
+            ChannelWeights::Luma601
+        );
+        assert_eq!(
+            "Luma709".parse::<ChannelWeights>().unwrap(),
+            ChannelWeights::Luma709
+        );
+        assert_eq!(
+            "average".parse::<ChannelWeights>().unwrap(),
+            ChannelWeights::Average
+        );
+        assert_eq!(
+            "0, 0.5, 0.5".parse::<ChannelWeights>().unwrap(),
+            ChannelWeights::Custom {
+                r: 0.0,
+                g: 0.5,
+                b: 0.5
             }
+        );
+        for invalid in ["", "lab", "1,2", "0,0,0", "-1,1,1", "1,1,nan"] {
+            assert!(invalid.parse::<ChannelWeights>().is_err(), "{}", invalid);
         }
-        // Ajouter des pixels bruités
-        img.put_pixel(2, 2, Luma([0]));
-        img.put_pixel(1, 1, Luma([255]));
+        assert_eq!("Blue".parse::<Channel>().unwrap(), Channel::Blue);
+        assert!("cyan".parse::<Channel>().is_err());
+
+        // Pondérations sur un pixel orange
+        let pixel = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(1, 1, Rgb([255, 128, 0])));
+        let value = |weights| {
+            let options = GrayscaleOptions {
+                weights,
+                ..GrayscaleOptions::default()
+            };
+            to_grayscale_with(&pixel, &options).get_pixel(0, 0)[0]
+        };
+        assert_eq!(value(ChannelWeights::Luma601), 151);
+        assert_eq!(value(ChannelWeights::Average), 128);
+        assert_eq!(
+            value(ChannelWeights::Custom {
+                r: 2.0,
+                g: 2.0,
+                b: 0.0
+            }),
+            192
+        );
+    }
 
-        let dynamic_img = DynamicImage::ImageLuma8(img);
+    #[test]
+    fn test_pipeline_uses_grayscale_options() {
+        use image::{Rgb, RgbImage};
 
-        // Configuration : débruiter puis binariser
+        // Quadrillage rouge d'un registre et un trait d'encre noire
+        let ledger = DynamicImage::ImageRgb8(RgbImage::from_fn(40, 40, |x, y| {
+            if y == 20 {
+                Rgb([0, 0, 0])
+            } else if x % 10 == 0 {
+                Rgb([255, 0, 0])
+            } else {
+                Rgb([255, 255, 255])
+            }
+        }));
         let config = PreprocessingConfig {
-            to_grayscale: false,
             binarize: true,
             binarization_method: BinarizationMethod::Fixed(128),
-            adjust_contrast: false,
-            contrast_factor: 1.0,
-            denoise: true,
-            deskew: false,
+            grayscale_options: GrayscaleOptions {
+                drop_channel: Some(Channel::Red),
+                ..GrayscaleOptions::default()
+            },
+            ..PreprocessingConfig::default()
         };
+        let processed = preprocess_image(&ledger, &config).unwrap().to_luma8();
+        assert_eq!(dark_pixels(&processed), 40, "seul le trait noir reste");
 
-        let result = preprocess_image(&dynamic_img, &config);
-
-        assert!(
-            result.is_ok(),
-            "Denoise + binarization preprocessing should succeed"
-        );
-
-        let processed = result.unwrap();
-        assert_eq!(processed.dimensions(), (5, 5));
+        let without = PreprocessingConfig {
+            grayscale_options: GrayscaleOptions::default(),
+            ..config.clone()
+        };
+        let processed = preprocess_image(&ledger, &without).unwrap().to_luma8();
+        assert!(dark_pixels(&processed) > 40);
     }
 
     #[test]
-    fn test_binarization_method_clone() {
-        let method1 = BinarizationMethod::Otsu;
-        let method2 = method1;
+    fn test_grayscale_options_serde_defaults() {
+        let config: PreprocessingConfig = serde_json::from_str(
+            r#"{"to_grayscale": true, "binarize": false, "binarization_method": "Otsu",
+                "adjust_contrast": false, "contrast_factor": 1.0, "denoise": false,
+                "deskew": false}"#,
+        )
+        .unwrap();
+        assert_eq!(config.grayscale_options, GrayscaleOptions::default());
+
+        let options: GrayscaleOptions = serde_json::from_str(
+            r#"{"weights": {"custom": {"r": 0.0, "g": 1.0, "b": 1.0}}, "alpha_background": 255}"#,
+        )
+        .unwrap();
+        assert_eq!(options.alpha_background, Some(255));
+        assert_eq!(options.drop_channel, None);
+        assert!(matches!(options.weights, ChannelWeights::Custom { .. }));
+    }
 
-        assert_eq!(method1, method2);
+    /// Image pseudo-aléatoire de `width` × `height` pixels.
+    fn random_image(width: u32, height: u32, seed: u64) -> GrayImage {
+        let mut rng = SplitMix64::new(seed);
+        GrayImage::from_fn(width, height, |_, _| Luma([(rng.next_u64() >> 56) as u8]))
+    }
 
-        let method3 = BinarizationMethod::Fixed(150);
-        let method4 = method3;
+    /// Images de test : aléatoires, de tailles diverses, et cas dégénérés.
+    fn comparison_images() -> Vec<GrayImage> {
+        let mut images: Vec<GrayImage> = [(64, 48), (33, 17), (1, 9), (7, 1), (128, 128)]
+            .into_iter()
+            .enumerate()
+            .map(|(seed, (width, height))| random_image(width, height, seed as u64 + 1))
+            .collect();
+        images.push(GrayImage::new(0, 0));
+        images.push(text_lines_image(120));
+        images
+    }
 
-        assert_eq!(method3, method4);
+    /// Ancienne version pixel par pixel de [`adjust_contrast()`].
+    fn reference_adjust_contrast(image: &GrayImage, factor: f32) -> GrayImage {
+        let mut output = image.clone();
+        for pixel in output.pixels_mut() {
+            let value = pixel[0] as f32;
+            let new_value = ((value - 128.0) * factor) + 128.0;
+            pixel[0] = new_value.clamp(0.0, 255.0) as u8;
+        }
+        output
     }
 
-    #[test]
-    fn test_preprocessing_config_clone() {
-        let config1 = PreprocessingConfig::default();
-        let config2 = config1.clone();
+    /// Ancienne version pixel par pixel de [`binarize_fixed()`].
+    fn reference_binarize_fixed(image: &GrayImage, threshold: u8) -> GrayImage {
+        let mut output = image.clone();
+        for pixel in output.pixels_mut() {
+            pixel[0] = if pixel[0] >= threshold { 255 } else { 0 };
+        }
+        output
+    }
 
-        assert_eq!(config1.to_grayscale, config2.to_grayscale);
-        assert_eq!(config1.binarize, config2.binarize);
-        assert_eq!(config1.binarization_method, config2.binarization_method);
-        assert_eq!(config1.adjust_contrast, config2.adjust_contrast);
-        assert_eq!(config1.contrast_factor, config2.contrast_factor);
-        assert_eq!(config1.denoise, config2.denoise);
-        assert_eq!(config1.deskew, config2.deskew);
+    /// Ancienne version de [`rotate_image_with_mask()`] (`get_pixel`/`put_pixel`).
+    fn reference_rotate_image_with_mask(
+        image: &GrayImage,
+        angle_deg: f64,
+    ) -> (GrayImage, GrayImage) {
+        let (width, height) = image.dimensions();
+        let cx = width as f64 / 2.0;
+        let cy = height as f64 / 2.0;
+        let rad = angle_deg.to_radians();
+        let cos_a = rad.cos();
+        let sin_a = rad.sin();
+        let mut output = GrayImage::new(width, height);
+        let mut mask = GrayImage::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let dx = x as f64 - cx;
+                let dy = y as f64 - cy;
+                let src_x = dx * cos_a + dy * sin_a + cx;
+                let src_y = -dx * sin_a + dy * cos_a + cy;
+                if src_x >= 0.0
+                    && src_x < width as f64 - 1.0
+                    && src_y >= 0.0
+                    && src_y < height as f64 - 1.0
+                {
+                    let sx = src_x as u32;
+                    let sy = src_y as u32;
+                    let fx = src_x - sx as f64;
+                    let fy = src_y - sy as f64;
+                    let p00 = image.get_pixel(sx, sy)[0] as f64;
+                    let p10 = image.get_pixel(sx + 1, sy)[0] as f64;
+                    let p01 = image.get_pixel(sx, sy + 1)[0] as f64;
+                    let p11 = image.get_pixel(sx + 1, sy + 1)[0] as f64;
+                    let val = p00 * (1.0 - fx) * (1.0 - fy)
+                        + p10 * fx * (1.0 - fy)
+                        + p01 * (1.0 - fx) * fy
+                        + p11 * fx * fy;
+                    output.put_pixel(x, y, Luma([val.round() as u8]));
+                    mask.put_pixel(x, y, Luma([255u8]));
+                } else {
+                    output.put_pixel(x, y, Luma([255u8]));
+                }
+            }
+        }
+        (output, mask)
     }
 
     #[test]
-    fn test_binarize_all_methods() {
-        use image::Luma;
-
-        // Créer une image de test
-        let mut img = GrayImage::new(10, 10);
-        for y in 0..10 {
-            for x in 0..10 {
-                let value = if x < 5 { 60 } else { 180 };
-                img.put_pixel(x, y, Luma([value]));
+    fn test_adjust_contrast_matches_reference() {
+        for image in comparison_images() {
+            for factor in [0.0, 0.5, 1.0, 1.37, 2.0, 3.0, -1.0, f32::NAN] {
+                assert_eq!(
+                    adjust_contrast(&image, factor),
+                    reference_adjust_contrast(&image, factor),
+                    "facteur {factor}"
+                );
             }
         }
+    }
 
-        // Tester chaque méthode de binarisation
-        let methods = vec![
-            BinarizationMethod::Otsu,
-            BinarizationMethod::Fixed(120),
-            BinarizationMethod::Adaptive,
-        ];
-
-        for method in methods {
-            let binary = binarize(&img, method);
+    #[test]
+    fn test_binarize_fixed_matches_reference() {
+        for image in comparison_images() {
+            for threshold in [0, 1, 127, 128, 200, 254, 255] {
+                assert_eq!(
+                    binarize(&image, BinarizationMethod::Fixed(threshold)),
+                    reference_binarize_fixed(&image, threshold),
+                    "seuil {threshold}"
+                );
+            }
+            let otsu = otsu_threshold(&image);
+            assert_eq!(
+                binarize(&image, BinarizationMethod::Otsu),
+                reference_binarize_fixed(&image, otsu)
+            );
+        }
+    }
 
-            // Vérifier que tous les pixels sont 0 ou 255
-            for pixel in binary.pixels() {
-                assert!(
-                    pixel[0] == 0 || pixel[0] == 255,
-                    "Binarization method {:?} should produce only 0 or 255",
-                    method
+    #[test]
+    fn test_rotate_image_matches_reference() {
+        for image in comparison_images() {
+            for angle in [0.0, 0.3, -2.5, 10.0, 45.0, 90.0, 179.9, -135.0] {
+                assert_eq!(
+                    rotate_image_with_mask(&image, angle),
+                    reference_rotate_image_with_mask(&image, angle),
+                    "angle {angle}"
                 );
             }
         }
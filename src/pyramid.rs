@@ -0,0 +1,486 @@
+//! Lecture pyramidale : OCR sur des réductions de plus en plus grandes de l'image.
+//!
+//! Tesseract met plus de 30 secondes à lire une photo de 24 Mpx, alors que
+//! la même photo réduite à 30 % donne souvent exactement le même texte en
+//! 3 secondes. La lecture pyramidale analyse l'image à des échelles
+//! croissantes ([`PyramidConfig::levels`]) et s'arrête dès que deux niveaux
+//! consécutifs concordent ([`is_stable()`]) : même nombre de mots, textes à
+//! moins de [`PyramidConfig::max_cer`] l'un de l'autre et confiance moyenne
+//! du dernier niveau au moins égale à [`PyramidConfig::min_confidence`].
+//! Le DPI transmis à Tesseract suit l'échelle de chaque niveau
+//! ([`level_dpi()`]).
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::pyramid::{PyramidConfig, level_dpi};
+//!
+//! // Même écriture que l'option --pyramid
+//! let pyramid: PyramidConfig = "0.25,0.5,1".parse()?;
+//! assert_eq!(pyramid.levels, vec![0.25, 0.5, 1.0]);
+//! assert_eq!(level_dpi(300, 0.25), 75);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Avec un moteur OCR :
+//!
+//! ```no_run
+//! # #[cfg(feature = "tesseract")]
+//! # {
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::ocr::OcrEngine;
+//! use std::path::Path;
+//!
+//! let engine = OcrEngine::new(OcrConfig::default())?;
+//! let result = engine.extract_text_pyramid(Path::new("photo.jpg"), &[0.3, 0.6, 1.0])?;
+//! if let Some(level) = result.report.accepted() {
+//!     println!("Échelle retenue : {}", level.scale);
+//! }
+//! println!("Gain estimé : {:?}", result.report.estimated_time_saved());
+//! println!("{}", result.outcome.text);
+//! # }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::metrics::calculate_cer;
+use crate::preprocessing::effective_dpi;
+use anyhow::{Context, Result, bail};
+use image::{DynamicImage, imageops};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Échelles essayées par défaut : 30 %, 60 %, puis pleine résolution.
+pub const DEFAULT_PYRAMID_LEVELS: [f32; 3] = [0.3, 0.6, 1.0];
+
+/// CER maximal par défaut entre les textes de deux niveaux consécutifs.
+pub const DEFAULT_PYRAMID_MAX_CER: f64 = 0.02;
+
+/// Confiance moyenne minimale par défaut (0 à 100) du niveau accepté.
+pub const DEFAULT_PYRAMID_MIN_CONFIDENCE: f32 = 80.0;
+
+/// Paramètres de la lecture pyramidale.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::pyramid::PyramidConfig;
+///
+/// let pyramid = PyramidConfig {
+///     min_confidence: 90.0,
+///     ..PyramidConfig::with_levels(vec![0.5, 1.0])
+/// };
+/// assert!(pyramid.validate().is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PyramidConfig {
+    /// Échelles essayées, strictement croissantes (ex: `[0.3, 0.6, 1.0]`).
+    ///
+    /// La lecture s'arrête au plus tard au dernier niveau, dont le texte
+    /// est alors retenu quelle que soit sa stabilité.
+    pub levels: Vec<f32>,
+    /// CER maximal entre les textes de deux niveaux consécutifs (0 à 1).
+    pub max_cer: f64,
+    /// Confiance moyenne minimale des mots du niveau accepté (0 à 100).
+    pub min_confidence: f32,
+}
+
+impl Default for PyramidConfig {
+    fn default() -> Self {
+        Self::with_levels(DEFAULT_PYRAMID_LEVELS.to_vec())
+    }
+}
+
+impl PyramidConfig {
+    /// Crée une configuration avec les échelles `levels` et les seuils par défaut.
+    ///
+    /// # Arguments
+    ///
+    /// * `levels` - Échelles essayées, strictement croissantes
+    pub fn with_levels(levels: Vec<f32>) -> Self {
+        Self {
+            levels,
+            max_cer: DEFAULT_PYRAMID_MAX_CER,
+            min_confidence: DEFAULT_PYRAMID_MIN_CONFIDENCE,
+        }
+    }
+
+    /// Vérifie les échelles et les seuils.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si aucune échelle n'est donnée, si une échelle
+    /// n'est pas un nombre strictement positif, si les échelles ne sont pas
+    /// strictement croissantes ou si un seuil sort de son intervalle.
+    pub fn validate(&self) -> Result<()> {
+        if self.levels.is_empty() {
+            bail!("Lecture pyramidale : aucune échelle");
+        }
+        if let Some(scale) = self
+            .levels
+            .iter()
+            .find(|scale| !scale.is_finite() || **scale <= 0.0)
+        {
+            bail!(
+                "Lecture pyramidale : échelle invalide {} (attendu un nombre strictement positif)",
+                scale
+            );
+        }
+        if self.levels.windows(2).any(|pair| pair[0] >= pair[1]) {
+            bail!(
+                "Lecture pyramidale : les échelles doivent être strictement croissantes ({:?})",
+                self.levels
+            );
+        }
+        if !(0.0..=1.0).contains(&self.max_cer) {
+            bail!(
+                "Lecture pyramidale : CER maximal {} hors de [0, 1]",
+                self.max_cer
+            );
+        }
+        if !(0.0..=100.0).contains(&self.min_confidence) {
+            bail!(
+                "Lecture pyramidale : confiance minimale {} hors de [0, 100]",
+                self.min_confidence
+            );
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PyramidConfig {
+    type Err = anyhow::Error;
+
+    /// Lit des échelles séparées par des virgules (ex: `0.3,0.6,1`), avec les seuils par défaut.
+    fn from_str(s: &str) -> Result<Self> {
+        let levels = s
+            .split(',')
+            .map(|value| value.trim().parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| {
+                format!(
+                    "Échelles invalides : '{}' (attendu des nombres séparés par des virgules, ex: 0.3,0.6,1)",
+                    s
+                )
+            })?;
+        let config = Self::with_levels(levels);
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// DPI à transmettre à Tesseract pour l'image réduite à l'échelle `scale`.
+///
+/// Une image réduite de moitié a deux fois moins de pixels par pouce :
+/// sans cette correction, Tesseract estimerait mal la taille du texte.
+///
+/// # Arguments
+///
+/// * `dpi` - DPI de l'image à pleine résolution
+/// * `scale` - Échelle du niveau
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::pyramid::level_dpi;
+///
+/// assert_eq!(level_dpi(300, 0.3), 90);
+/// assert_eq!(level_dpi(300, 1.0), 300);
+/// ```
+pub fn level_dpi(dpi: u32, scale: f32) -> u32 {
+    effective_dpi(dpi, f64::from(scale))
+}
+
+/// Réduit (ou agrandit) `image` à l'échelle `scale`, sans copie à l'échelle 1.
+///
+/// Image d'un niveau de la pyramide ; chaque dimension garde au moins un pixel.
+///
+/// # Arguments
+///
+/// * `image` - Image à pleine résolution
+/// * `scale` - Échelle du niveau
+pub fn scale_image(image: &DynamicImage, scale: f32) -> Cow<'_, DynamicImage> {
+    if scale == 1.0 {
+        return Cow::Borrowed(image);
+    }
+    let resize = |size: u32| ((f64::from(size) * f64::from(scale)).round() as u32).max(1);
+    Cow::Owned(image.resize_exact(
+        resize(image.width()),
+        resize(image.height()),
+        imageops::FilterType::Triangle,
+    ))
+}
+
+/// Résultat de l'OCR d'un niveau de la pyramide.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyramidLevel {
+    /// Échelle de l'image analysée.
+    pub scale: f32,
+    /// DPI transmis à Tesseract (voir [`level_dpi()`]).
+    pub dpi: u32,
+    /// Texte extrait.
+    pub text: String,
+    /// Confiance moyenne des mots reconnus (0 à 100), si Tesseract en a fourni.
+    pub mean_confidence: Option<f32>,
+    /// Durée de l'OCR du niveau (réduction et prétraitement compris).
+    pub duration: Duration,
+}
+
+impl PyramidLevel {
+    /// Nombre de mots du texte extrait.
+    pub fn word_count(&self) -> usize {
+        self.text.split_whitespace().count()
+    }
+}
+
+/// Indique si `current` confirme `previous`, le niveau précédent : la lecture peut s'arrêter.
+///
+/// Les deux textes doivent compter autant de mots et différer d'au plus
+/// [`PyramidConfig::max_cer`] ; la confiance moyenne de `current` doit
+/// atteindre [`PyramidConfig::min_confidence`] (un niveau sans confiance
+/// connue n'est jamais stable).
+///
+/// # Arguments
+///
+/// * `previous` - Niveau précédent (échelle plus petite)
+/// * `current` - Niveau qui vient d'être lu
+/// * `config` - Seuils de stabilité
+pub fn is_stable(previous: &PyramidLevel, current: &PyramidLevel, config: &PyramidConfig) -> bool {
+    previous.word_count() == current.word_count()
+        && calculate_cer(&current.text, &previous.text) <= config.max_cer
+        && current
+            .mean_confidence
+            .is_some_and(|confidence| confidence >= config.min_confidence)
+}
+
+/// Déroulement d'une lecture pyramidale.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyramidReport {
+    /// Niveaux effectivement lus, dans l'ordre ; le dernier est celui retenu.
+    pub levels: Vec<PyramidLevel>,
+    /// Nombre de niveaux demandés.
+    pub requested_levels: usize,
+}
+
+impl PyramidReport {
+    /// Niveau dont le texte a été retenu, `None` si aucun n'a été lu (page blanche).
+    pub fn accepted(&self) -> Option<&PyramidLevel> {
+        self.levels.last()
+    }
+
+    /// Indique si la lecture s'est arrêtée avant le dernier niveau demandé.
+    pub fn stopped_early(&self) -> bool {
+        !self.levels.is_empty() && self.levels.len() < self.requested_levels
+    }
+
+    /// Durée cumulée de tous les niveaux lus.
+    pub fn total_duration(&self) -> Duration {
+        self.levels.iter().map(|level| level.duration).sum()
+    }
+
+    /// Durée estimée d'une lecture directe à pleine résolution.
+    ///
+    /// Le temps de Tesseract croît à peu près comme le nombre de pixels :
+    /// la durée du niveau retenu est divisée par le carré de son échelle.
+    pub fn estimated_full_duration(&self) -> Duration {
+        self.accepted().map_or(Duration::ZERO, |level| {
+            level.duration.div_f64(f64::from(level.scale).powi(2))
+        })
+    }
+
+    /// Temps gagné estimé par rapport à une lecture directe à pleine résolution.
+    ///
+    /// Nul lorsque la pyramide a coûté plus cher (tous les niveaux lus).
+    pub fn estimated_time_saved(&self) -> Duration {
+        self.estimated_full_duration()
+            .saturating_sub(self.total_duration())
+    }
+}
+
+/// Lit les niveaux de `config` par `run_level` jusqu'au premier niveau stable.
+///
+/// `run_level` reçoit l'échelle du niveau et retourne son résultat, avec
+/// une valeur associée (ex: le résultat OCR complet) : seule celle du
+/// niveau retenu est conservée. Un niveau en échec interrompt la lecture.
+///
+/// # Arguments
+///
+/// * `config` - Échelles et seuils de stabilité
+/// * `run_level` - OCR d'un niveau
+///
+/// # Erreurs
+///
+/// Retourne une erreur si `config` est invalide ou si un niveau échoue.
+pub fn run_pyramid<T, F>(config: &PyramidConfig, mut run_level: F) -> Result<(T, PyramidReport)>
+where
+    F: FnMut(f32) -> Result<(PyramidLevel, T)>,
+{
+    config.validate()?;
+    let mut levels: Vec<PyramidLevel> = Vec::with_capacity(config.levels.len());
+    let mut accepted = None;
+    for &scale in &config.levels {
+        let (level, value) = run_level(scale)?;
+        let stable = levels
+            .last()
+            .is_some_and(|previous| is_stable(previous, &level, config));
+        levels.push(level);
+        accepted = Some(value);
+        if stable {
+            break;
+        }
+    }
+    // `validate()` garantit au moins un niveau
+    let value = accepted.context("Lecture pyramidale : aucun niveau lu")?;
+    Ok((
+        value,
+        PyramidReport {
+            levels,
+            requested_levels: config.levels.len(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(scale: f32, text: &str, confidence: f32) -> PyramidLevel {
+        PyramidLevel {
+            scale,
+            dpi: level_dpi(300, scale),
+            text: text.to_string(),
+            mean_confidence: Some(confidence),
+            duration: Duration::from_millis((1000.0 * scale * scale) as u64),
+        }
+    }
+
+    /// Lecture sur des résultats synthétiques, en notant les échelles demandées.
+    fn run_synthetic(
+        config: &PyramidConfig,
+        results: &[PyramidLevel],
+    ) -> (Vec<f32>, PyramidReport) {
+        let mut requested = Vec::new();
+        let (index, report) = run_pyramid(config, |scale| {
+            let index = requested.len();
+            requested.push(scale);
+            Ok((results[index].clone(), index))
+        })
+        .unwrap();
+        assert_eq!(index + 1, report.levels.len());
+        (requested, report)
+    }
+
+    #[test]
+    fn test_stops_at_second_level_when_texts_agree() {
+        let config = PyramidConfig::default();
+        let results = [
+            level(0.3, "Facture n° 42 du 3 mars", 88.0),
+            level(0.6, "Facture n° 42 du 3 mars", 91.0),
+            level(1.0, "Facture n° 42 du 3 mars", 93.0),
+        ];
+        let (requested, report) = run_synthetic(&config, &results);
+        assert_eq!(requested, vec![0.3, 0.6]);
+        assert!(report.stopped_early());
+        assert_eq!(report.accepted(), Some(&results[1]));
+        // 360 ms à 60 % : ~1 s estimée à pleine résolution, 90 + 360 ms dépensées
+        assert_eq!(report.total_duration(), Duration::from_millis(450));
+        assert!(report.estimated_time_saved() > Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_goes_to_the_end_when_texts_keep_changing() {
+        let config = PyramidConfig::default();
+        let results = [
+            level(0.3, "Fac ure", 55.0),
+            level(0.6, "Facture n 42", 85.0),
+            level(1.0, "Facture n° 42 du 3 mars", 93.0),
+        ];
+        let (requested, report) = run_synthetic(&config, &results);
+        assert_eq!(requested, vec![0.3, 0.6, 1.0]);
+        assert!(!report.stopped_early());
+        assert_eq!(report.accepted().unwrap().text, results[2].text);
+        assert_eq!(report.estimated_time_saved(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_stability_criterion() {
+        let config = PyramidConfig::default();
+        let reference = level(0.3, "Total TTC 125,40 EUR", 90.0);
+
+        // Un caractère sur 20 : CER 5 %, au-delà du seuil de 2 %
+        assert!(!is_stable(
+            &reference,
+            &level(0.6, "Total TTC 125,48 EUR", 90.0),
+            &config
+        ));
+        // Même texte mais confiance insuffisante
+        assert!(!is_stable(
+            &reference,
+            &level(0.6, &reference.text, 70.0),
+            &config
+        ));
+        // Confiance inconnue
+        let unknown = PyramidLevel {
+            mean_confidence: None,
+            ..reference.clone()
+        };
+        assert!(!is_stable(&reference, &unknown, &config));
+        // Nombre de mots différent malgré un CER faible
+        let tolerant = PyramidConfig {
+            max_cer: 0.1,
+            ..PyramidConfig::default()
+        };
+        assert!(!is_stable(
+            &reference,
+            &level(0.6, "Total TTC 125,40EUR", 90.0),
+            &tolerant
+        ));
+        assert!(is_stable(
+            &reference,
+            &level(0.6, "Total TTC 125,48 EUR", 90.0),
+            &tolerant
+        ));
+    }
+
+    #[test]
+    fn test_level_dpi_and_scaling() {
+        assert_eq!(level_dpi(300, 0.3), 90);
+        assert_eq!(level_dpi(300, 0.6), 180);
+        assert_eq!(level_dpi(300, 1.0), 300);
+        assert_eq!(level_dpi(200, 0.25), 50);
+        assert_eq!(level_dpi(72, 0.001), 1);
+
+        let image = DynamicImage::new_luma8(1000, 10);
+        let scaled = scale_image(&image, 0.3);
+        assert_eq!((scaled.width(), scaled.height()), (300, 3));
+        assert!(matches!(scale_image(&image, 1.0), Cow::Borrowed(_)));
+        assert_eq!(scale_image(&image, 0.01).height(), 1);
+    }
+
+    #[test]
+    fn test_parse_and_validate_levels() {
+        let config: PyramidConfig = " 0.3, 0.6 ,1".parse().unwrap();
+        assert_eq!(config.levels, vec![0.3, 0.6, 1.0]);
+        assert_eq!(config.max_cer, DEFAULT_PYRAMID_MAX_CER);
+
+        for invalid in ["", "0.3,abc", "0.6,0.3", "0.5,0.5", "0,1", "-0.5,1"] {
+            assert!(invalid.parse::<PyramidConfig>().is_err(), "{}", invalid);
+        }
+        let config = PyramidConfig {
+            max_cer: 1.5,
+            ..PyramidConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_pyramid_config_serde_defaults() {
+        let config: PyramidConfig = serde_json::from_str(r#"{"levels":[0.5,1.0]}"#).unwrap();
+        assert_eq!(config.levels, vec![0.5, 1.0]);
+        assert_eq!(config.min_confidence, DEFAULT_PYRAMID_MIN_CONFIDENCE);
+        let default: PyramidConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(default, PyramidConfig::default());
+    }
+}
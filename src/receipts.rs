@@ -0,0 +1,1087 @@
+//! Analyse des tickets de caisse et contrôle de leur arithmétique.
+//!
+//! Un ticket reconnu avec quelques chiffres faux est difficile à croire sur
+//! parole, mais ses propres totaux permettent de le vérifier.
+//! [`parse_receipt()`] extrait les lignes d'articles (désignation, quantité,
+//! prix unitaire, montant), le sous-total, les lignes de taxe et le total,
+//! puis contrôle que :
+//!
+//! - la quantité multipliée par le prix unitaire redonne le montant de la ligne ;
+//! - la somme des articles redonne le sous-total (ou le total, sans sous-total) ;
+//! - le sous-total augmenté des taxes redonne le total.
+//!
+//! Les lignes qui expliquent un écart sont signalées ([`SuspectLine`]) :
+//! lettres lues à la place de chiffres (`1O,50`), virgule perdue (montant
+//! décalé d'un facteur 10 ou 100).
+//!
+//! Les montants sont reconnus aux formats français (`1 234,56`) et anglais
+//! (`1,234.56`), avec le symbole monétaire avant ou après (`€`, `$`, `£`,
+//! `EUR`). Les lignes qui suivent le total (paiement, rendu monnaie) ne
+//! sont pas des articles ; les taxes qui y figurent sont considérées comme
+//! incluses dans le total.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::receipts::parse_receipt;
+//!
+//! let receipt = parse_receipt("Baguette 1,20\nCroissant 2 x 1,10 2,20\nTOTAL 3,40 €");
+//! assert_eq!(receipt.items.len(), 2);
+//! assert_eq!(receipt.items[1].quantity, Some(2.0));
+//! assert_eq!(receipt.total, Some(3.4));
+//! assert!(receipt.valid);
+//! ```
+
+use serde::Serialize;
+
+/// Écart toléré par défaut entre un montant imprimé et le montant recalculé.
+///
+/// Deux centimes : les taxes arrondies ligne par ligne peuvent décaler le
+/// total d'un centime.
+pub const DEFAULT_RECEIPT_EPSILON: f64 = 0.02;
+
+/// Facteurs de décalage de la virgule recherchés dans les montants suspects.
+const DECIMAL_SHIFT_FACTORS: [f64; 2] = [10.0, 100.0];
+
+/// Mots qui désignent un sous-total (hors taxes).
+const SUBTOTAL_KEYWORDS: &[&str] = &[
+    "sous-total",
+    "sous total",
+    "soustotal",
+    "subtotal",
+    "sub-total",
+    "sub total",
+    "total ht",
+    "montant ht",
+    "total hors tax",
+];
+
+/// Mots qui désignent le montant à payer, même en présence d'un mot de taxe.
+const GRAND_TOTAL_KEYWORDS: &[&str] = &[
+    "ttc",
+    "à payer",
+    "a payer",
+    "amount due",
+    "balance due",
+    "grand total",
+];
+
+/// Mots (entiers) qui désignent une ligne de taxe.
+const TAX_WORDS: &[&str] = &["tva", "vat", "tax", "taxe", "taxes", "gst", "hst"];
+
+/// Mots (entiers) qui indiquent une taxe déjà incluse dans les montants.
+const INCLUDED_TAX_WORDS: &[&str] = &["dont", "incl", "included", "inclus", "incluse"];
+
+/// Options de [`parse_receipt_with()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReceiptOptions {
+    /// Écart maximal (en unités monétaires) pour qu'une relation soit vérifiée.
+    pub epsilon: f64,
+}
+
+impl Default for ReceiptOptions {
+    /// Tolérance de [`DEFAULT_RECEIPT_EPSILON`].
+    fn default() -> Self {
+        Self {
+            epsilon: DEFAULT_RECEIPT_EPSILON,
+        }
+    }
+}
+
+/// Ligne d'article d'un ticket.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReceiptItem {
+    /// Numéro de la ligne dans le texte (à partir de 1).
+    pub line: usize,
+    /// Désignation de l'article.
+    pub description: String,
+    /// Quantité (`2 x 1,10`), si elle figure sur la ligne.
+    pub quantity: Option<f64>,
+    /// Prix unitaire, si la quantité est suivie d'un prix.
+    pub unit_price: Option<f64>,
+    /// Montant de la ligne, s'il est imprimé.
+    pub total: Option<f64>,
+}
+
+impl ReceiptItem {
+    /// Montant retenu pour la somme des articles.
+    ///
+    /// Le montant imprimé, sinon la quantité multipliée par le prix unitaire,
+    /// sinon le prix unitaire seul.
+    pub fn amount(&self) -> Option<f64> {
+        self.total.or(match (self.quantity, self.unit_price) {
+            (Some(quantity), Some(price)) => Some(round_cents(quantity * price)),
+            (None, price) => price,
+            (Some(_), None) => None,
+        })
+    }
+}
+
+/// Ligne de taxe d'un ticket.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaxLine {
+    /// Numéro de la ligne dans le texte (à partir de 1).
+    pub line: usize,
+    /// Libellé de la ligne (ex: "TVA 5,5 %").
+    pub label: String,
+    /// Taux en pourcentage, s'il est imprimé.
+    pub rate: Option<f64>,
+    /// Montant de la taxe.
+    pub amount: f64,
+    /// Taxe déjà incluse dans les montants ("dont TVA", ligne après le total) :
+    /// elle n'est pas ajoutée au sous-total.
+    pub included: bool,
+}
+
+/// Relation arithmétique contrôlée sur un ticket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReceiptRelation {
+    /// Quantité × prix unitaire = montant de la ligne.
+    LineTotal {
+        /// Numéro de la ligne d'article.
+        line: usize,
+    },
+    /// Somme des articles = sous-total.
+    ItemsSubtotal,
+    /// Sous-total + taxes non incluses = total.
+    SubtotalTaxesTotal,
+    /// Somme des articles + taxes non incluses = total (ticket sans sous-total).
+    ItemsTotal,
+}
+
+/// Résultat du contrôle d'une relation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReceiptCheck {
+    /// Relation contrôlée.
+    pub relation: ReceiptRelation,
+    /// Montant recalculé à partir des autres lignes.
+    pub expected: f64,
+    /// Montant imprimé sur le ticket.
+    pub found: f64,
+    /// Indique si l'écart reste dans la tolérance.
+    pub holds: bool,
+}
+
+/// Raison pour laquelle une ligne est suspecte.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SuspectReason {
+    /// Un montant contient des lettres lues comme des chiffres (`O` pour `0`,
+    /// `l` ou `I` pour `1`).
+    Misread {
+        /// Montant tel qu'il figure dans le texte.
+        token: String,
+    },
+    /// Le montant imprimé vaut `factor` fois celui qui rétablirait les totaux
+    /// (virgule perdue si `factor` > 1, chiffre en trop à droite sinon).
+    DecimalShift {
+        /// Rapport entre le montant lu et le montant corrigé.
+        factor: f64,
+        /// Montant qui rétablirait la relation.
+        corrected: f64,
+    },
+    /// La quantité multipliée par le prix unitaire ne redonne pas le montant.
+    LineTotalMismatch,
+}
+
+/// Ligne du ticket dont un chiffre est probablement faux.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SuspectLine {
+    /// Numéro de la ligne dans le texte (à partir de 1).
+    pub line: usize,
+    /// Texte de la ligne, sans espaces de début et de fin.
+    pub text: String,
+    /// Raison du signalement.
+    pub reason: SuspectReason,
+}
+
+/// Ticket analysé par [`parse_receipt()`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReceiptParse {
+    /// Lignes d'articles, dans l'ordre du ticket.
+    pub items: Vec<ReceiptItem>,
+    /// Sous-total imprimé, s'il y en a un.
+    pub subtotal: Option<f64>,
+    /// Lignes de taxe.
+    pub taxes: Vec<TaxLine>,
+    /// Total imprimé, s'il y en a un.
+    pub total: Option<f64>,
+    /// Relations contrôlées, dans l'ordre : lignes, sous-total, total.
+    pub checks: Vec<ReceiptCheck>,
+    /// Lignes suspectes.
+    pub suspects: Vec<SuspectLine>,
+    /// Indique si au moins une relation a été contrôlée et si toutes sont vérifiées.
+    pub valid: bool,
+}
+
+impl ReceiptParse {
+    /// Somme des montants des articles ([`ReceiptItem::amount()`]).
+    pub fn items_sum(&self) -> f64 {
+        round_cents(self.items.iter().filter_map(ReceiptItem::amount).sum())
+    }
+}
+
+/// Analyse le texte d'un ticket avec la tolérance par défaut.
+///
+/// Voir [`parse_receipt_with()`].
+///
+/// # Arguments
+///
+/// * `text` - Texte reconnu du ticket, une ligne du ticket par ligne
+pub fn parse_receipt(text: &str) -> ReceiptParse {
+    parse_receipt_with(text, &ReceiptOptions::default())
+}
+
+/// Analyse le texte d'un ticket et contrôle son arithmétique.
+///
+/// Les articles sont les lignes qui précèdent le premier sous-total, taxe ou
+/// total et qui se terminent par un montant (avec décimales ou symbole
+/// monétaire). Une ligne réduite à `2 x 1,10` reprend la désignation de la
+/// ligne précédente. Le premier total termine le ticket.
+///
+/// # Arguments
+///
+/// * `text` - Texte reconnu du ticket, une ligne du ticket par ligne
+/// * `options` - Tolérance des contrôles
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::receipts::{ReceiptOptions, parse_receipt_with};
+///
+/// let text = "Coffee 7.00\nSubtotal 7.00\nTax 0.56\nTotal 7.57";
+/// assert!(!parse_receipt_with(text, &ReceiptOptions { epsilon: 0.0 }).valid);
+/// assert!(parse_receipt_with(text, &ReceiptOptions { epsilon: 0.01 }).valid);
+/// ```
+pub fn parse_receipt_with(text: &str, options: &ReceiptOptions) -> ReceiptParse {
+    let mut receipt = ReceiptParse {
+        items: Vec::new(),
+        subtotal: None,
+        taxes: Vec::new(),
+        total: None,
+        checks: Vec::new(),
+        suspects: Vec::new(),
+        valid: false,
+    };
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let mut subtotal_line = None;
+    let mut total_line = None;
+    let mut in_items = true;
+    let mut pending_description: Option<String> = None;
+
+    for (index, &trimmed) in lines.iter().enumerate() {
+        let line = index + 1;
+        if trimmed.is_empty() {
+            continue;
+        }
+        let tokens = tokenize(trimmed);
+
+        match classify_line(trimmed) {
+            LineKind::Subtotal if total_line.is_none() => {
+                if let Some(amount) = last_amount(&tokens) {
+                    if receipt.subtotal.is_none() {
+                        receipt.subtotal = Some(amount.value);
+                        subtotal_line = Some(line);
+                        flag_misread(&mut receipt.suspects, line, trimmed, amount);
+                    }
+                    in_items = false;
+                }
+            }
+            LineKind::Tax { included } => {
+                if let Some(amount) = last_amount(&tokens) {
+                    receipt.taxes.push(TaxLine {
+                        line,
+                        label: label_before(trimmed, &tokens),
+                        rate: tokens.iter().find_map(|token| match token {
+                            Token::Percent(rate) => Some(*rate),
+                            _ => None,
+                        }),
+                        amount: amount.value,
+                        included: included || total_line.is_some(),
+                    });
+                    flag_misread(&mut receipt.suspects, line, trimmed, amount);
+                    in_items = false;
+                }
+            }
+            LineKind::Total if total_line.is_none() => {
+                if let Some(amount) = last_amount(&tokens) {
+                    receipt.total = Some(amount.value);
+                    total_line = Some(line);
+                    flag_misread(&mut receipt.suspects, line, trimmed, amount);
+                    in_items = false;
+                }
+            }
+            LineKind::Other if in_items => {
+                match parse_item(line, trimmed, &tokens, pending_description.take()) {
+                    Some((item, used)) => {
+                        for amount in used {
+                            flag_misread(&mut receipt.suspects, line, trimmed, amount);
+                        }
+                        receipt.items.push(item);
+                    }
+                    None if trimmed.chars().any(char::is_alphabetic) => {
+                        pending_description = Some(trimmed.to_string());
+                    }
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    check_receipt(
+        &mut receipt,
+        options.epsilon,
+        &lines,
+        subtotal_line,
+        total_line,
+    );
+    receipt
+}
+
+/// Contrôle les relations du ticket et signale les lignes qui expliquent un écart.
+fn check_receipt(
+    receipt: &mut ReceiptParse,
+    epsilon: f64,
+    lines: &[&str],
+    subtotal_line: Option<usize>,
+    total_line: Option<usize>,
+) {
+    let text_of = |line: usize| lines.get(line - 1).copied().unwrap_or_default().to_string();
+    let holds = |expected: f64, found: f64| (expected - found).abs() <= epsilon + 1e-9;
+
+    // Quantité × prix unitaire
+    for item in &receipt.items {
+        let (Some(quantity), Some(price), Some(total)) =
+            (item.quantity, item.unit_price, item.total)
+        else {
+            continue;
+        };
+        let expected = round_cents(quantity * price);
+        let ok = holds(expected, total);
+        receipt.checks.push(ReceiptCheck {
+            relation: ReceiptRelation::LineTotal { line: item.line },
+            expected,
+            found: total,
+            holds: ok,
+        });
+        if !ok {
+            let reason =
+                decimal_shift(total, expected, epsilon).unwrap_or(SuspectReason::LineTotalMismatch);
+            receipt.suspects.push(SuspectLine {
+                line: item.line,
+                text: text_of(item.line),
+                reason,
+            });
+        }
+    }
+
+    let added_taxes = round_cents(
+        receipt
+            .taxes
+            .iter()
+            .filter(|tax| !tax.included)
+            .map(|tax| tax.amount)
+            .sum(),
+    );
+    let has_items = receipt.items.iter().any(|item| item.amount().is_some());
+    let items_sum = receipt.items_sum();
+
+    // Somme des articles, comparée au sous-total ou, à défaut, au total
+    let items_target = match (receipt.subtotal, subtotal_line, receipt.total, total_line) {
+        (Some(subtotal), Some(line), _, _) => {
+            Some((ReceiptRelation::ItemsSubtotal, 0.0, subtotal, line))
+        }
+        (None, _, Some(total), Some(line)) => {
+            Some((ReceiptRelation::ItemsTotal, added_taxes, total, line))
+        }
+        _ => None,
+    };
+    if let Some((relation, offset, found, summary_line)) = items_target.filter(|_| has_items) {
+        let expected = round_cents(items_sum + offset);
+        let ok = holds(expected, found);
+        receipt.checks.push(ReceiptCheck {
+            relation,
+            expected,
+            found,
+            holds: ok,
+        });
+        if !ok {
+            let suspects = items_sum_suspects(receipt, expected, found, epsilon);
+            if suspects.is_empty() {
+                // Le montant récapitulatif lui-même peut avoir perdu sa virgule
+                if let Some(reason) = decimal_shift(found, expected, epsilon) {
+                    receipt.suspects.push(SuspectLine {
+                        line: summary_line,
+                        text: text_of(summary_line),
+                        reason,
+                    });
+                }
+            }
+            for (line, reason) in suspects {
+                receipt.suspects.push(SuspectLine {
+                    line,
+                    text: text_of(line),
+                    reason,
+                });
+            }
+        }
+    }
+
+    // Sous-total + taxes
+    if let (Some(subtotal), Some(total), Some(line)) = (receipt.subtotal, receipt.total, total_line)
+    {
+        let expected = round_cents(subtotal + added_taxes);
+        let ok = holds(expected, total);
+        receipt.checks.push(ReceiptCheck {
+            relation: ReceiptRelation::SubtotalTaxesTotal,
+            expected,
+            found: total,
+            holds: ok,
+        });
+        if !ok && let Some(reason) = decimal_shift(total, expected, epsilon) {
+            receipt.suspects.push(SuspectLine {
+                line,
+                text: text_of(line),
+                reason,
+            });
+        }
+    }
+
+    receipt.suspects.sort_by_key(|suspect| suspect.line);
+    receipt.valid = !receipt.checks.is_empty() && receipt.checks.iter().all(|check| check.holds);
+}
+
+/// Articles dont la correction d'une virgule rétablirait la somme.
+fn items_sum_suspects(
+    receipt: &ReceiptParse,
+    expected: f64,
+    found: f64,
+    epsilon: f64,
+) -> Vec<(usize, SuspectReason)> {
+    receipt
+        .items
+        .iter()
+        .filter_map(|item| {
+            let amount = item.amount()?;
+            let others = expected - amount;
+            let reason = decimal_shift(amount, found - others, epsilon)?;
+            Some((item.line, reason))
+        })
+        .collect()
+}
+
+/// Facteur de décalage de la virgule qui ferait passer `found` à `target`.
+fn decimal_shift(found: f64, target: f64, epsilon: f64) -> Option<SuspectReason> {
+    if found == 0.0 || target == 0.0 {
+        return None;
+    }
+    DECIMAL_SHIFT_FACTORS
+        .iter()
+        .flat_map(|&factor| [factor, 1.0 / factor])
+        .find(|&factor| (found / factor - target).abs() <= epsilon + 1e-9)
+        .map(|factor| SuspectReason::DecimalShift {
+            factor,
+            corrected: round_cents(found / factor),
+        })
+}
+
+/// Signale un montant dont des lettres ont été lues comme des chiffres.
+fn flag_misread(suspects: &mut Vec<SuspectLine>, line: usize, text: &str, amount: &Amount) {
+    if amount.misread {
+        suspects.push(SuspectLine {
+            line,
+            text: text.to_string(),
+            reason: SuspectReason::Misread {
+                token: amount.raw.clone(),
+            },
+        });
+    }
+}
+
+/// Nature d'une ligne du ticket, d'après ses mots-clés.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Subtotal,
+    Tax { included: bool },
+    Total,
+    Other,
+}
+
+/// Classe une ligne d'après ses mots-clés (sans tenir compte de la casse).
+fn classify_line(line: &str) -> LineKind {
+    let lower = line.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let has_word = |set: &[&str]| words.iter().any(|word| set.contains(word));
+
+    if SUBTOTAL_KEYWORDS
+        .iter()
+        .any(|keyword| lower.contains(keyword))
+    {
+        LineKind::Subtotal
+    } else if GRAND_TOTAL_KEYWORDS
+        .iter()
+        .any(|keyword| lower.contains(keyword))
+    {
+        LineKind::Total
+    } else if has_word(TAX_WORDS) {
+        LineKind::Tax {
+            included: has_word(INCLUDED_TAX_WORDS),
+        }
+    } else if words.contains(&"total") {
+        LineKind::Total
+    } else {
+        LineKind::Other
+    }
+}
+
+/// Nombre lu dans une ligne.
+#[derive(Debug, Clone, PartialEq)]
+struct Amount {
+    /// Valeur.
+    value: f64,
+    /// Nombre de chiffres après la virgule.
+    decimals: usize,
+    /// Des lettres ont été lues comme des chiffres.
+    misread: bool,
+    /// Un symbole monétaire est accolé au nombre.
+    currency: bool,
+    /// Texte d'origine.
+    raw: String,
+}
+
+impl Amount {
+    /// Indique si le nombre ressemble à un prix (décimales ou symbole monétaire).
+    fn is_price(&self) -> bool {
+        self.decimals > 0 || self.currency
+    }
+}
+
+/// Élément d'une ligne du ticket.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Mot ordinaire, avec sa position (octet de début) dans la ligne.
+    Word(usize),
+    /// Nombre, avec sa position dans la ligne.
+    Number(Amount, usize),
+    /// Signe de multiplication (`x`, `×`, `*`, `@`).
+    Times(usize),
+    /// Taux en pourcentage (`20%`, `5,5 %`).
+    Percent(f64),
+    /// Symbole monétaire isolé (`€`, `EUR`).
+    Currency,
+}
+
+/// Découpe une ligne en mots, nombres, signes de multiplication et taux.
+fn tokenize(line: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut currency_pending = false;
+
+    for (offset, word) in words_with_offsets(line) {
+        if is_currency_word(word) {
+            match tokens.last_mut() {
+                Some(Token::Number(amount, _)) => amount.currency = true,
+                _ => currency_pending = true,
+            }
+            tokens.push(Token::Currency);
+            continue;
+        }
+        if word == "%" {
+            if let Some(Token::Number(amount, _)) = tokens.last() {
+                let rate = amount.value;
+                tokens.pop();
+                tokens.push(Token::Percent(rate));
+            }
+            continue;
+        }
+        if let Some(rate) = word.strip_suffix('%').and_then(parse_amount) {
+            tokens.push(Token::Percent(rate.value));
+            continue;
+        }
+        if let Some(mut amount) = parse_amount(word) {
+            amount.currency |= std::mem::take(&mut currency_pending);
+            // "1 234,56" : groupe de milliers séparé par une espace
+            if let Some(Token::Number(previous, start)) = tokens.last()
+                && is_thousands_group(previous, word)
+            {
+                let merged_raw = format!("{} {}", previous.raw, amount.raw);
+                let value = previous.value * 1000.0 + amount.value;
+                amount = Amount {
+                    value: round_cents(value),
+                    raw: merged_raw,
+                    misread: previous.misread || amount.misread,
+                    currency: previous.currency || amount.currency,
+                    ..amount
+                };
+                let start = *start;
+                tokens.pop();
+                tokens.push(Token::Number(amount, start));
+                continue;
+            }
+            tokens.push(Token::Number(amount, offset));
+            continue;
+        }
+        if let Some(parts) = split_times(word) {
+            // Positions approchées : seule celle du premier élément sert à la désignation
+            for (position, part) in (offset..).zip(parts) {
+                tokens.push(match part {
+                    Some(amount) => Token::Number(amount, position),
+                    None => Token::Times(position),
+                });
+            }
+            continue;
+        }
+        tokens.push(Token::Word(offset));
+    }
+    tokens
+}
+
+/// Mots d'une ligne, avec leur position (octet de début).
+fn words_with_offsets(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace()
+        .map(move |word| (word.as_ptr() as usize - line.as_ptr() as usize, word))
+}
+
+/// Indique si `word` est un symbole ou un code monétaire isolé.
+fn is_currency_word(word: &str) -> bool {
+    matches!(
+        word.to_uppercase().as_str(),
+        "€" | "$" | "£" | "EUR" | "EUR." | "USD" | "GBP"
+    )
+}
+
+/// Indique si `word` prolonge `previous` par un groupe de milliers (`1 234,56`).
+fn is_thousands_group(previous: &Amount, word: &str) -> bool {
+    let trimmed = word.trim_end_matches(['€', '$', '£']);
+    let digits: String = trimmed.chars().take_while(char::is_ascii_digit).collect();
+    let rest = &trimmed[digits.len()..];
+    previous.decimals == 0
+        && !previous.currency
+        && (1..=3).contains(&previous.raw.len())
+        && previous.raw.chars().all(|c| c.is_ascii_digit())
+        && digits.len() == 3
+        && (rest.is_empty()
+            || (rest.len() <= 3
+                && rest.starts_with([',', '.'])
+                && rest[1..].chars().all(|c| c.is_ascii_digit())))
+}
+
+/// Découpe `2x1,10`, `2x` ou `x2` en nombres et signe de multiplication.
+///
+/// `None` dans le résultat marque le signe. Retourne `None` si le mot
+/// n'a pas cette forme.
+fn split_times(word: &str) -> Option<Vec<Option<Amount>>> {
+    if matches!(word, "x" | "X" | "×" | "*" | "@") {
+        return Some(vec![None]);
+    }
+    let (position, sign) = word
+        .char_indices()
+        .find(|(_, c)| matches!(c, 'x' | 'X' | '×' | '*' | '@'))?;
+    let (left, right) = (&word[..position], &word[position + sign.len_utf8()..]);
+    let parse_side = |side: &str| -> Option<Option<Amount>> {
+        if side.is_empty() {
+            Some(None)
+        } else {
+            parse_amount(side).map(Some)
+        }
+    };
+    let (left, right) = (parse_side(left)?, parse_side(right)?);
+    if left.is_none() && right.is_none() {
+        return None;
+    }
+    let mut parts = Vec::new();
+    parts.extend(left.map(Some));
+    parts.push(None);
+    parts.extend(right.map(Some));
+    Some(parts)
+}
+
+/// Lit un nombre aux formats français ou anglais, symbole monétaire accolé compris.
+///
+/// `O`/`o` sont lus comme `0` et `I`/`l`/`|` comme `1`, sauf en fin de mot
+/// (`1,5l`) : le nombre est alors marqué [`Amount::misread`].
+fn parse_amount(word: &str) -> Option<Amount> {
+    let trimmed = word.trim_end_matches([':', ';']);
+    let stripped_start = trimmed.trim_start_matches(['€', '$', '£']);
+    let stripped = stripped_start.trim_end_matches(['€', '$', '£']);
+    let currency = stripped.len() != trimmed.len();
+    let (negative, body) = match stripped.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => match stripped.strip_suffix('-') {
+            Some(rest) => (true, rest),
+            None => (false, stripped),
+        },
+    };
+    let body = body.trim_start_matches(['€', '$', '£']);
+    let last = body.chars().last()?;
+    if !last.is_ascii_digit() || !body.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let mut misread = false;
+    let mut normalized = String::with_capacity(body.len());
+    for c in body.chars() {
+        match c {
+            '0'..='9' | '.' | ',' | '\'' => normalized.push(c),
+            'O' | 'o' => {
+                misread = true;
+                normalized.push('0');
+            }
+            'I' | 'l' | '|' => {
+                misread = true;
+                normalized.push('1');
+            }
+            _ => return None,
+        }
+    }
+    if !normalized.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let (integer, fraction) = match normalized.rfind(['.', ',', '\'']) {
+        None => (normalized.as_str(), ""),
+        Some(position) => {
+            let (integer, fraction) = (&normalized[..position], &normalized[position + 1..]);
+            let single_separator = !integer.contains(['.', ',', '\'']);
+            match fraction.len() {
+                1 | 2 if normalized[position..].starts_with(['.', ',']) => (integer, fraction),
+                // "0,534" : décimales ; "1,234" ou "1.234.567" : milliers
+                3 if single_separator && integer == "0" => (integer, fraction),
+                3 => (normalized.as_str(), ""),
+                _ => return None,
+            }
+        }
+    };
+    // Les séparateurs de milliers encadrent des groupes de trois chiffres
+    let mut groups = integer.split(['.', ',', '\'']);
+    let first = groups.next()?;
+    if first.is_empty() || (integer.len() > first.len() && first.len() > 3) {
+        return None;
+    }
+    let mut digits = first.to_string();
+    for group in groups {
+        if group.len() != 3 {
+            return None;
+        }
+        digits.push_str(group);
+    }
+
+    let value: f64 = format!(
+        "{}.{}",
+        digits,
+        if fraction.is_empty() { "0" } else { fraction }
+    )
+    .parse()
+    .ok()?;
+    Some(Amount {
+        value: if negative { -value } else { value },
+        decimals: fraction.len(),
+        misread,
+        currency,
+        raw: word.to_string(),
+    })
+}
+
+/// Dernier nombre de la ligne, hors taux.
+fn last_amount(tokens: &[Token]) -> Option<&Amount> {
+    tokens.iter().rev().find_map(|token| match token {
+        Token::Number(amount, _) => Some(amount),
+        _ => None,
+    })
+}
+
+/// Libellé d'une ligne récapitulative : le texte qui précède son montant.
+fn label_before(line: &str, tokens: &[Token]) -> String {
+    let end = tokens
+        .iter()
+        .rev()
+        .find_map(|token| match token {
+            Token::Number(_, start) => Some(*start),
+            _ => None,
+        })
+        .unwrap_or(line.len());
+    line[..end]
+        .trim()
+        .trim_end_matches(':')
+        .trim_end()
+        .to_string()
+}
+
+/// Lit une ligne d'article : désignation, puis quantité, prix unitaire et montant.
+///
+/// Formes reconnues en fin de ligne : `montant`, `qté x prix`,
+/// `qté x prix montant`, `qté prix montant` et `qté montant`. Retourne
+/// l'article et les nombres retenus, ou `None` si la ligne ne se termine
+/// pas par un prix.
+fn parse_item<'a>(
+    line: usize,
+    text: &str,
+    tokens: &'a [Token],
+    pending_description: Option<String>,
+) -> Option<(ReceiptItem, Vec<&'a Amount>)> {
+    // Les symboles monétaires isolés ne comptent pas dans la forme de la ligne
+    let significant: Vec<&Token> = tokens
+        .iter()
+        .filter(|token| !matches!(token, Token::Currency))
+        .collect();
+    let number = |index: usize| match significant.get(index) {
+        Some(Token::Number(amount, _)) => Some(amount),
+        _ => None,
+    };
+    let is_times = |index: usize| matches!(significant.get(index), Some(Token::Times(_)));
+
+    let last = significant.len().checked_sub(1)?;
+    let last_amount = number(last).filter(|amount| amount.is_price())?;
+
+    let (quantity, unit_price, total, first) = if last >= 2
+        && is_times(last - 1)
+        && let Some(quantity) = number(last - 2)
+    {
+        // "2 x 1,10"
+        (Some(quantity), Some(last_amount), None, last - 2)
+    } else if last >= 3
+        && is_times(last - 2)
+        && let (Some(quantity), Some(price)) = (number(last - 3), number(last - 1))
+    {
+        // "2 x 1,10 2,20"
+        (Some(quantity), Some(price), Some(last_amount), last - 3)
+    } else if let (Some(quantity), Some(price)) = (
+        last.checked_sub(2).and_then(number),
+        last.checked_sub(1).and_then(number),
+    ) && quantity.decimals == 0
+        && price.is_price()
+    {
+        // "2 1,10 2,20"
+        (Some(quantity), Some(price), Some(last_amount), last - 2)
+    } else if let Some(quantity) = last
+        .checked_sub(1)
+        .and_then(number)
+        .filter(|quantity| quantity.decimals == 0 && !quantity.currency)
+    {
+        // "2 2,20"
+        (Some(quantity), None, Some(last_amount), last - 1)
+    } else {
+        (None, None, Some(last_amount), last)
+    };
+
+    let description = Some(describe(text, significant[first]))
+        .filter(|description| !description.is_empty())
+        .or(pending_description)?;
+    if !description.chars().any(char::is_alphabetic) {
+        return None;
+    }
+
+    let used: Vec<&Amount> = [quantity, unit_price, total]
+        .into_iter()
+        .flatten()
+        .collect();
+    Some((
+        ReceiptItem {
+            line,
+            description,
+            quantity: quantity.map(|amount| amount.value),
+            unit_price: unit_price.map(|amount| amount.value),
+            total: total.map(|amount| amount.value),
+        },
+        used,
+    ))
+}
+
+/// Désignation d'un article : le texte de la ligne avant le premier nombre retenu.
+///
+/// Un signe de multiplication isolé en fin de désignation (`Café x2`) est retiré.
+fn describe(text: &str, first: &Token) -> String {
+    let start = match first {
+        Token::Number(_, start) | Token::Word(start) | Token::Times(start) => *start,
+        Token::Percent(_) | Token::Currency => 0,
+    };
+    let mut start = start.min(text.len());
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut description = text[..start].trim_end();
+    if let Some(rest) = description.strip_suffix(['x', 'X', '×', '*', '@'])
+        && (rest.is_empty() || rest.ends_with(char::is_whitespace))
+    {
+        description = rest.trim_end();
+    }
+    description.trim_end_matches(':').trim_end().to_string()
+}
+
+/// Arrondit un montant au centime.
+fn round_cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ticket français sans erreur : articles, sous-total HT, TVA, total TTC, paiement.
+    const CLEAN_RECEIPT: &str = "\
+SUPERMARCHÉ DU CENTRE
+12 rue de la Paix
+Tél. 01 23 45 67 89
+
+Pain de campagne        2,40 €
+Lait demi-écrémé  2 x 1,15   2,30 €
+Fromage râpé            3,25 €
+Sous-total              7,95 €
+TVA 5,5 %               0,44 €
+TOTAL TTC               8,39 €
+CB                      8,39 €
+";
+
+    #[test]
+    fn test_clean_french_receipt_validates() {
+        let receipt = parse_receipt(CLEAN_RECEIPT);
+
+        let descriptions: Vec<&str> = receipt
+            .items
+            .iter()
+            .map(|item| item.description.as_str())
+            .collect();
+        assert_eq!(
+            descriptions,
+            ["Pain de campagne", "Lait demi-écrémé", "Fromage râpé"]
+        );
+        assert_eq!(receipt.items[1].quantity, Some(2.0));
+        assert_eq!(receipt.items[1].unit_price, Some(1.15));
+        assert_eq!(receipt.items[1].total, Some(2.3));
+        assert_eq!(receipt.subtotal, Some(7.95));
+        assert_eq!(receipt.total, Some(8.39));
+        assert_eq!(receipt.taxes.len(), 1);
+        assert_eq!(receipt.taxes[0].label, "TVA 5,5 %");
+        assert_eq!(receipt.taxes[0].rate, Some(5.5));
+        assert_eq!(receipt.taxes[0].amount, 0.44);
+
+        let relations: Vec<ReceiptRelation> =
+            receipt.checks.iter().map(|check| check.relation).collect();
+        assert_eq!(
+            relations,
+            [
+                ReceiptRelation::LineTotal { line: 6 },
+                ReceiptRelation::ItemsSubtotal,
+                ReceiptRelation::SubtotalTaxesTotal,
+            ]
+        );
+        assert!(receipt.checks.iter().all(|check| check.holds));
+        assert!(receipt.suspects.is_empty());
+        assert!(receipt.valid);
+
+        let json = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(json["checks"][1]["relation"]["kind"], "items_subtotal");
+        assert_eq!(json["valid"], true);
+    }
+
+    #[test]
+    fn test_misread_digit_fails_validation() {
+        let text =
+            "Café 2 x 1,50 3,00\nCroissant 1O,50\nSous-total 4,50\nTVA 10 % 0,45\nTotal 4,95";
+        let receipt = parse_receipt(text);
+
+        assert_eq!(receipt.items[1].total, Some(10.5));
+        assert!(!receipt.valid);
+        let failed: Vec<ReceiptRelation> = receipt
+            .checks
+            .iter()
+            .filter(|check| !check.holds)
+            .map(|check| check.relation)
+            .collect();
+        assert_eq!(failed, [ReceiptRelation::ItemsSubtotal]);
+        assert_eq!(
+            receipt.suspects,
+            [SuspectLine {
+                line: 2,
+                text: "Croissant 1O,50".to_string(),
+                reason: SuspectReason::Misread {
+                    token: "1O,50".to_string()
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn test_dropped_decimal_is_located() {
+        let text = "Pain 2,40\nFromage 1250 €\nSous-total 14,90";
+        let receipt = parse_receipt(text);
+
+        assert!(!receipt.valid);
+        assert_eq!(receipt.suspects.len(), 1);
+        assert_eq!(receipt.suspects[0].line, 2);
+        assert_eq!(
+            receipt.suspects[0].reason,
+            SuspectReason::DecimalShift {
+                factor: 100.0,
+                corrected: 12.5
+            }
+        );
+    }
+
+    #[test]
+    fn test_comma_and_dot_decimals() {
+        let amount = |word: &str| parse_amount(word).map(|amount| amount.value);
+        assert_eq!(amount("12,50"), Some(12.5));
+        assert_eq!(amount("12.50"), Some(12.5));
+        assert_eq!(amount("1.234,56"), Some(1234.56));
+        assert_eq!(amount("1,234.56"), Some(1234.56));
+        assert_eq!(amount("1,234"), Some(1234.0));
+        assert_eq!(amount("0,534"), Some(0.534));
+        assert_eq!(amount("€3.20"), Some(3.2));
+        assert_eq!(amount("3,20€"), Some(3.2));
+        assert_eq!(amount("-1,00"), Some(-1.0));
+        assert_eq!(amount("12/03/2024"), None);
+        assert_eq!(amount("1,5l"), None);
+
+        let coffee = &parse_receipt("Café ×2 3,00").items[0];
+        assert_eq!(coffee.description, "Café");
+        assert_eq!(coffee.quantity, Some(2.0));
+
+        let english = parse_receipt(
+            "Laptop 1,234.56\nCoffee 2 @ 3.50 7.00\nBagel $2.25\n\
+             Subtotal $1,243.81\nSales tax 8% $99.50\nTotal $1,343.31",
+        );
+        let french = parse_receipt(
+            "Ordinateur 1 234,56 €\nCafé 2 x 3,50 7,00\nBagel 2,25€\n\
+             Sous-total 1 243,81 €\nTVA 8% 99,50 €\nTotal TTC 1 343,31 €",
+        );
+        for receipt in [&english, &french] {
+            assert_eq!(receipt.items[0].total, Some(1234.56));
+            assert_eq!(receipt.items[1].unit_price, Some(3.5));
+            assert_eq!(receipt.subtotal, Some(1243.81));
+            assert_eq!(receipt.taxes[0].rate, Some(8.0));
+            assert_eq!(receipt.total, Some(1343.31));
+            assert!(receipt.valid, "{:?}", receipt.checks);
+        }
+    }
+
+    #[test]
+    fn test_missing_tax_line_tolerated() {
+        let receipt =
+            parse_receipt("Baguette 1,20\nCroissant\n  2 x 1,10\nTOTAL 3,40 €\nRendu 1,60");
+
+        assert!(receipt.taxes.is_empty());
+        assert_eq!(receipt.subtotal, None);
+        assert_eq!(receipt.items.len(), 2);
+        assert_eq!(receipt.items[1].description, "Croissant");
+        assert_eq!(receipt.items[1].total, None);
+        assert_eq!(receipt.items[1].amount(), Some(2.2));
+        assert_eq!(receipt.checks.len(), 1);
+        assert_eq!(receipt.checks[0].relation, ReceiptRelation::ItemsTotal);
+        assert!(receipt.valid);
+
+        // Taxe incluse ("dont TVA") : elle ne s'ajoute pas au total
+        let included = parse_receipt("Baguette 1,20\nTotal 1,20\ndont TVA 5,5% 0,06");
+        assert!(included.taxes[0].included);
+        assert!(included.valid);
+
+        // Tolérance configurable
+        let rounded = "Baguette 1,21\nTotal 1,20";
+        assert!(parse_receipt(rounded).valid);
+        assert!(!parse_receipt_with(rounded, &ReceiptOptions { epsilon: 0.0 }).valid);
+    }
+}
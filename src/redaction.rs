@@ -0,0 +1,544 @@
+//! Masquage (caviardage) de zones sensibles dans une image.
+//!
+//! Avant l'archivage de documents numérisés, les données personnelles
+//! reconnues par l'OCR (IBAN, adresses e-mail...) doivent être rendues
+//! illisibles. Ce module masque des rectangles ([`redact_regions()`]) ou
+//! recherche directement les mots concernés dans un document HOCR
+//! ([`redact_matching()`]).
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! # #[cfg(feature = "tesseract")]
+//! # {
+//! use text_recognition::config::OcrEngineMode;
+//! use text_recognition::hocr::{HocrDocument, generate_hocr};
+//! use text_recognition::redaction::{FieldMatcher, RedactionStyle, redact_matching};
+//! use std::path::Path;
+//!
+//! let path = Path::new("releve.png");
+//! let hocr = generate_hocr(path, "fra", 3, OcrEngineMode::Default)?;
+//! let doc = HocrDocument::from_hocr_string(&hocr)?;
+//!
+//! let matcher = FieldMatcher::from_names("iban,email")?;
+//! let redacted = redact_matching(&image::open(path)?, &doc, &matcher, RedactionStyle::SolidBlack, 2);
+//! redacted.save("releve_masque.png")?;
+//! # }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::hocr::{BBox, HocrDocument};
+use anyhow::{Context, Result};
+use image::{DynamicImage, GenericImage, GenericImageView, Rgba};
+use regex::Regex;
+
+/// Champs sensibles reconnus par [`FieldMatcher::from_names()`].
+pub const BUILTIN_FIELDS: [&str; 2] = ["iban", "email"];
+
+/// IBAN : code pays, clé de contrôle puis groupes de 4 caractères, espaces facultatifs.
+const IBAN_PATTERN: &str = r"\b[A-Z]{2}[0-9]{2}(?: ?[A-Z0-9]{4}){2,7}(?: ?[A-Z0-9]{1,3})?\b";
+
+/// Adresse e-mail.
+const EMAIL_PATTERN: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}";
+
+/// Style de masquage d'une zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedactionStyle {
+    /// Remplit la zone en noir.
+    SolidBlack,
+
+    /// Remplit la zone en blanc.
+    SolidWhite,
+
+    /// Remplace la zone par des blocs carrés de la couleur moyenne.
+    ///
+    /// `block` est la taille d'un bloc en pixels (1 si 0). Les blocs sont
+    /// alignés sur le coin supérieur gauche de la zone ; ceux du bord droit
+    /// et du bord bas sont tronqués.
+    Pixelate {
+        /// Côté d'un bloc en pixels.
+        block: u32,
+    },
+}
+
+/// Occurrence d'un champ sensible dans un document HOCR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldMatch {
+    /// Nom du champ (ex: "iban").
+    pub field: String,
+    /// Texte reconnu, mots séparés par une espace.
+    pub text: String,
+    /// Bounding box de chacun des mots couverts par l'occurrence.
+    pub boxes: Vec<BBox>,
+}
+
+/// Motif recherché, associé au nom du champ.
+#[derive(Debug, Clone)]
+struct FieldPattern {
+    name: String,
+    regex: Regex,
+}
+
+/// Recherche de champs sensibles dans le texte d'un document HOCR.
+///
+/// Les mots du document sont mis bout à bout (séparés par une espace, y
+/// compris entre deux lignes) : une occurrence peut donc couvrir plusieurs
+/// mots, voire plusieurs lignes, comme un IBAN écrit par groupes de 4.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::redaction::FieldMatcher;
+///
+/// let matcher = FieldMatcher::from_names("iban")?
+///     .with_pattern("telephone", r"0[1-9](?: ?[0-9]{2}){4}")?;
+/// assert_eq!(matcher.field_names(), ["iban", "telephone"]);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FieldMatcher {
+    patterns: Vec<FieldPattern>,
+}
+
+impl FieldMatcher {
+    /// Crée une recherche sans aucun motif.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Crée une recherche à partir de noms de champs prédéfinis séparés par des virgules.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - Liste de champs, parmi [`BUILTIN_FIELDS`] (ex: "iban,email")
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si un nom n'est pas reconnu ou si la liste est vide.
+    pub fn from_names(names: &str) -> Result<Self> {
+        let mut matcher = Self::new();
+
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let pattern = match name.to_lowercase().as_str() {
+                "iban" => IBAN_PATTERN,
+                "email" => EMAIL_PATTERN,
+                _ => anyhow::bail!(
+                    "Champ à masquer inconnu : '{}'. Champs disponibles : {}",
+                    name,
+                    BUILTIN_FIELDS.join(", ")
+                ),
+            };
+            matcher = matcher.with_pattern(&name.to_lowercase(), pattern)?;
+        }
+
+        if matcher.patterns.is_empty() {
+            anyhow::bail!(
+                "Aucun champ à masquer. Champs disponibles : {}",
+                BUILTIN_FIELDS.join(", ")
+            );
+        }
+        Ok(matcher)
+    }
+
+    /// Ajoute un motif (expression régulière) associé à un nom de champ.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Nom du champ, reporté dans [`FieldMatch::field`]
+    /// * `pattern` - Expression régulière (syntaxe de la crate `regex`)
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si l'expression régulière est invalide.
+    pub fn with_pattern(mut self, name: &str, pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern)
+            .with_context(|| format!("Expression régulière invalide pour '{}'", name))?;
+        self.patterns.push(FieldPattern {
+            name: name.to_string(),
+            regex,
+        });
+        Ok(self)
+    }
+
+    /// Retourne les noms des champs recherchés, dans l'ordre d'ajout.
+    pub fn field_names(&self) -> Vec<&str> {
+        self.patterns.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    /// Recherche toutes les occurrences des champs dans le document.
+    ///
+    /// Un mot est rattaché à une occurrence dès qu'il la recoupe, même
+    /// partiellement : "IBAN:FR76" est masqué en entier.
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - Document HOCR dont les mots sont parcourus dans l'ordre
+    pub fn find_matches(&self, doc: &HocrDocument) -> Vec<FieldMatch> {
+        let mut text = String::new();
+        let mut spans = Vec::new();
+
+        for word in doc
+            .paragraphs()
+            .flat_map(|p| &p.lines)
+            .flat_map(|l| &l.words)
+        {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            let start = text.len();
+            text.push_str(&word.text);
+            spans.push((start, text.len(), &word.bbox));
+        }
+
+        let mut matches = Vec::new();
+        for pattern in &self.patterns {
+            for found in pattern.regex.find_iter(&text) {
+                let boxes: Vec<BBox> = spans
+                    .iter()
+                    .filter(|(start, end, _)| *start < found.end() && found.start() < *end)
+                    .map(|(_, _, bbox)| (*bbox).clone())
+                    .collect();
+                if !boxes.is_empty() {
+                    matches.push(FieldMatch {
+                        field: pattern.name.clone(),
+                        text: found.as_str().to_string(),
+                        boxes,
+                    });
+                }
+            }
+        }
+        matches
+    }
+}
+
+/// Masque des zones rectangulaires d'une image.
+///
+/// Les zones qui débordent de l'image sont tronquées à ses bords ; celles
+/// entièrement à l'extérieur sont ignorées. Le reste de l'image est inchangé.
+///
+/// # Arguments
+///
+/// * `image` - Image source
+/// * `boxes` - Zones à masquer, en pixels
+/// * `style` - Style de masquage
+///
+/// # Exemple
+///
+/// ```
+/// use image::{DynamicImage, GenericImageView, RgbImage};
+/// use text_recognition::hocr::BBox;
+/// use text_recognition::redaction::{RedactionStyle, redact_regions};
+///
+/// let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(20, 20, image::Rgb([255, 255, 255])));
+/// let redacted = redact_regions(&img, &[BBox::new(5, 5, 4, 4)], RedactionStyle::SolidBlack);
+/// assert_eq!(redacted.get_pixel(6, 6).0, [0, 0, 0, 255]);
+/// assert_eq!(redacted.get_pixel(0, 0).0, [255, 255, 255, 255]);
+/// ```
+pub fn redact_regions(image: &DynamicImage, boxes: &[BBox], style: RedactionStyle) -> DynamicImage {
+    let mut output = image.clone();
+
+    for bbox in boxes {
+        let Some((x0, y0, x1, y1)) = clamp_to_image(bbox, image.width(), image.height()) else {
+            continue;
+        };
+
+        match style {
+            RedactionStyle::SolidBlack => fill(&mut output, (x0, y0, x1, y1), Rgba([0, 0, 0, 255])),
+            RedactionStyle::SolidWhite => {
+                fill(&mut output, (x0, y0, x1, y1), Rgba([255, 255, 255, 255]))
+            }
+            RedactionStyle::Pixelate { block } => {
+                let block = block.max(1);
+                for by in (y0..y1).step_by(block as usize) {
+                    for bx in (x0..x1).step_by(block as usize) {
+                        let cell = (bx, by, (bx + block).min(x1), (by + block).min(y1));
+                        let color = average_color(&output, cell);
+                        fill(&mut output, cell, color);
+                    }
+                }
+            }
+        }
+    }
+
+    output
+}
+
+/// Masque les mots d'un document HOCR qui correspondent aux champs recherchés.
+///
+/// Chaque mot d'une occurrence est masqué individuellement (un IBAN sur deux
+/// lignes produit deux groupes de rectangles, sans masquer l'espace entre
+/// les lignes). Les coordonnées du document doivent correspondre à l'image.
+///
+/// # Arguments
+///
+/// * `image` - Image analysée pour produire `doc`
+/// * `doc` - Document HOCR de l'image
+/// * `matcher` - Champs à rechercher
+/// * `style` - Style de masquage
+/// * `padding` - Marge ajoutée autour de chaque mot, en pixels
+pub fn redact_matching(
+    image: &DynamicImage,
+    doc: &HocrDocument,
+    matcher: &FieldMatcher,
+    style: RedactionStyle,
+    padding: u32,
+) -> DynamicImage {
+    let boxes: Vec<BBox> = matcher
+        .find_matches(doc)
+        .iter()
+        .flat_map(|m| &m.boxes)
+        .map(|bbox| pad(bbox, padding))
+        .collect();
+
+    redact_regions(image, &boxes, style)
+}
+
+/// Agrandit un rectangle de `padding` pixels de chaque côté (sans dépasser 0 à gauche et en haut).
+fn pad(bbox: &BBox, padding: u32) -> BBox {
+    let x = bbox.x.saturating_sub(padding);
+    let y = bbox.y.saturating_sub(padding);
+    BBox::new(
+        x,
+        y,
+        (bbox.x - x)
+            .saturating_add(bbox.width)
+            .saturating_add(padding),
+        (bbox.y - y)
+            .saturating_add(bbox.height)
+            .saturating_add(padding),
+    )
+}
+
+/// Tronque un rectangle aux bords de l'image ; retourne `(x0, y0, x1, y1)` ou `None` s'il est vide.
+pub(crate) fn clamp_to_image(bbox: &BBox, width: u32, height: u32) -> Option<(u32, u32, u32, u32)> {
+    let x0 = bbox.x.min(width);
+    let y0 = bbox.y.min(height);
+    let x1 = bbox.x.saturating_add(bbox.width).min(width);
+    let y1 = bbox.y.saturating_add(bbox.height).min(height);
+
+    (x0 < x1 && y0 < y1).then_some((x0, y0, x1, y1))
+}
+
+/// Remplit le rectangle `(x0, y0, x1, y1)` (bornes hautes exclues) d'une couleur.
+fn fill(image: &mut DynamicImage, (x0, y0, x1, y1): (u32, u32, u32, u32), color: Rgba<u8>) {
+    for y in y0..y1 {
+        for x in x0..x1 {
+            image.put_pixel(x, y, color);
+        }
+    }
+}
+
+/// Couleur moyenne du rectangle `(x0, y0, x1, y1)`, non vide.
+fn average_color(image: &DynamicImage, (x0, y0, x1, y1): (u32, u32, u32, u32)) -> Rgba<u8> {
+    let mut sums = [0u64; 4];
+    for y in y0..y1 {
+        for x in x0..x1 {
+            for (sum, channel) in sums.iter_mut().zip(image.get_pixel(x, y).0) {
+                *sum += u64::from(channel);
+            }
+        }
+    }
+
+    let count = u64::from(x1 - x0) * u64::from(y1 - y0);
+    Rgba(sums.map(|sum| ((sum + count / 2) / count) as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    /// Relevé bancaire : IBAN coupé sur deux lignes, puis une adresse e-mail.
+    const STATEMENT_HOCR: &str = r#"<div class='ocr_page' id='page_1' title='bbox 0 0 200 100'>
+   <p class='ocr_par' id='par_1_1' title="bbox 10 10 190 90">
+    <span class='ocr_line' id='line_1_1' title="bbox 10 10 190 30">
+     <span class='ocrx_word' id='word_1_1' title='bbox 10 10 50 30; x_wconf 95'>IBAN</span>
+     <span class='ocrx_word' id='word_1_2' title='bbox 60 10 100 30; x_wconf 93'>FR76</span>
+     <span class='ocrx_word' id='word_1_3' title='bbox 110 10 150 30; x_wconf 93'>3000</span>
+     <span class='ocrx_word' id='word_1_4' title='bbox 160 10 190 30; x_wconf 93'>6000</span>
+    </span>
+    <span class='ocr_line' id='line_1_2' title="bbox 10 40 190 60">
+     <span class='ocrx_word' id='word_1_5' title='bbox 10 40 50 60; x_wconf 91'>0112</span>
+     <span class='ocrx_word' id='word_1_6' title='bbox 60 40 100 60; x_wconf 92'>3456</span>
+     <span class='ocrx_word' id='word_1_7' title='bbox 110 40 140 60; x_wconf 90'>789</span>
+     <span class='ocrx_word' id='word_1_8' title='bbox 150 40 190 60; x_wconf 96'>payé</span>
+    </span>
+    <span class='ocr_line' id='line_1_3' title="bbox 10 70 190 90">
+     <span class='ocrx_word' id='word_1_9' title='bbox 10 70 60 90; x_wconf 95'>Contact</span>
+     <span class='ocrx_word' id='word_1_10' title='bbox 70 70 190 90; x_wconf 94'>jean.dupont@exemple.fr</span>
+    </span>
+   </p>
+</div>"#;
+
+    fn white_image(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([255, 255, 255])))
+    }
+
+    /// Vérifie que les pixels dans `bbox` valent `inside` et tous les autres `outside`.
+    fn assert_region(image: &DynamicImage, bbox: BBox, inside: [u8; 4], outside: [u8; 4]) {
+        for (x, y, pixel) in image.pixels() {
+            let covered =
+                x >= bbox.x && x < bbox.x + bbox.width && y >= bbox.y && y < bbox.y + bbox.height;
+            let expected = if covered { inside } else { outside };
+            assert_eq!(pixel.0, expected, "pixel ({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn test_redact_solid_black() {
+        let redacted = redact_regions(
+            &white_image(20, 20),
+            &[BBox::new(5, 6, 4, 3)],
+            RedactionStyle::SolidBlack,
+        );
+        assert_region(
+            &redacted,
+            BBox::new(5, 6, 4, 3),
+            [0, 0, 0, 255],
+            [255, 255, 255, 255],
+        );
+    }
+
+    #[test]
+    fn test_redact_solid_white() {
+        let black = DynamicImage::ImageRgb8(RgbImage::new(10, 10));
+        let redacted = redact_regions(
+            &black,
+            &[BBox::new(0, 0, 3, 10)],
+            RedactionStyle::SolidWhite,
+        );
+        assert_region(
+            &redacted,
+            BBox::new(0, 0, 3, 10),
+            [255, 255, 255, 255],
+            [0, 0, 0, 255],
+        );
+    }
+
+    #[test]
+    fn test_redact_clamps_boxes_outside_image() {
+        let redacted = redact_regions(
+            &white_image(20, 20),
+            &[BBox::new(15, 12, 50, 50), BBox::new(30, 30, 5, 5)],
+            RedactionStyle::SolidBlack,
+        );
+        assert_region(
+            &redacted,
+            BBox::new(15, 12, 5, 8),
+            [0, 0, 0, 255],
+            [255, 255, 255, 255],
+        );
+    }
+
+    #[test]
+    fn test_redact_pixelate_uniform_blocks() {
+        let gradient = RgbImage::from_fn(16, 12, |x, y| Rgb([(x * 15) as u8, (y * 20) as u8, 100]));
+        let image = DynamicImage::ImageRgb8(gradient);
+        let area = BBox::new(2, 2, 10, 8);
+
+        let redacted = redact_regions(&image, &[area], RedactionStyle::Pixelate { block: 4 });
+
+        // Blocs 4x4 alignés sur (2, 2), tronqués à droite (2 px) et en bas (4 px)
+        for (x, y, pixel) in redacted.pixels() {
+            let inside = (2..12).contains(&x) && (2..10).contains(&y);
+            if !inside {
+                assert_eq!(pixel, image.get_pixel(x, y), "pixel ({}, {})", x, y);
+                continue;
+            }
+            let origin = (2 + (x - 2) / 4 * 4, 2 + (y - 2) / 4 * 4);
+            assert_eq!(
+                pixel,
+                redacted.get_pixel(origin.0, origin.1),
+                "pixel ({}, {})",
+                x,
+                y
+            );
+        }
+
+        // Les blocs voisins d'un dégradé ont des couleurs différentes
+        assert_ne!(redacted.get_pixel(2, 2), redacted.get_pixel(6, 2));
+        assert_ne!(redacted.get_pixel(2, 2), redacted.get_pixel(2, 6));
+        // Couleur moyenne du premier bloc (x de 2 à 5, y de 2 à 5)
+        assert_eq!(redacted.get_pixel(2, 2).0, [53, 70, 100, 255]);
+    }
+
+    #[test]
+    fn test_from_names_rejects_unknown_field() {
+        assert!(FieldMatcher::from_names("iban, Email").is_ok());
+        let err = FieldMatcher::from_names("iban,telephone").unwrap_err();
+        assert!(err.to_string().contains("telephone"));
+        assert!(err.to_string().contains("iban, email"));
+        assert!(FieldMatcher::from_names(" , ").is_err());
+    }
+
+    #[test]
+    fn test_with_pattern_rejects_invalid_regex() {
+        assert!(FieldMatcher::new().with_pattern("nom", "(").is_err());
+    }
+
+    #[test]
+    fn test_find_matches_spanning_lines() {
+        let doc = HocrDocument::from_hocr_string(STATEMENT_HOCR).unwrap();
+        let matcher = FieldMatcher::from_names("iban,email").unwrap();
+
+        let matches = matcher.find_matches(&doc);
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].field, "iban");
+        assert_eq!(matches[0].text, "FR76 3000 6000 0112 3456 789");
+        assert_eq!(
+            matches[0].boxes,
+            [
+                BBox::new(60, 10, 40, 20),
+                BBox::new(110, 10, 40, 20),
+                BBox::new(160, 10, 30, 20),
+                BBox::new(10, 40, 40, 20),
+                BBox::new(60, 40, 40, 20),
+                BBox::new(110, 40, 30, 20),
+            ]
+        );
+        assert_eq!(matches[1].field, "email");
+        assert_eq!(matches[1].boxes, [BBox::new(70, 70, 120, 20)]);
+    }
+
+    #[test]
+    fn test_find_matches_custom_pattern() {
+        let doc = HocrDocument::from_hocr_string(STATEMENT_HOCR).unwrap();
+        let matcher = FieldMatcher::new()
+            .with_pattern("nom", r"jean\.dupont")
+            .unwrap();
+
+        let matches = matcher.find_matches(&doc);
+
+        // Le mot qui contient l'occurrence est masqué en entier
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].boxes, [BBox::new(70, 70, 120, 20)]);
+    }
+
+    #[test]
+    fn test_redact_matching_with_padding() {
+        let doc = HocrDocument::from_hocr_string(STATEMENT_HOCR).unwrap();
+        let matcher = FieldMatcher::new().with_pattern("nom", "Contact").unwrap();
+
+        let redacted = redact_matching(
+            &white_image(200, 100),
+            &doc,
+            &matcher,
+            RedactionStyle::SolidBlack,
+            2,
+        );
+
+        assert_region(
+            &redacted,
+            BBox::new(8, 68, 54, 24),
+            [0, 0, 0, 255],
+            [255, 255, 255, 255],
+        );
+    }
+
+    #[test]
+    fn test_pad_saturates_at_origin() {
+        assert_eq!(pad(&BBox::new(1, 5, 10, 10), 3), BBox::new(0, 2, 14, 16));
+        assert_eq!(pad(&BBox::new(1, 5, 10, 10), 0), BBox::new(1, 5, 10, 10));
+    }
+}
@@ -0,0 +1,253 @@
+//! Régions d'image exprimées en fractions de ses dimensions.
+//!
+//! Une interface graphique connaît rarement la taille en pixels d'une
+//! capture d'écran avant de l'envoyer : elle désigne plutôt une zone comme
+//! « le coin supérieur droit, 20 % de la largeur et 10 % de la hauteur ».
+//! [`RelativeRegion`] décrit cette zone par des fractions entre 0 et 1 et la
+//! convertit en [`BBox`] une fois l'image chargée.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::hocr::BBox;
+//! use text_recognition::region::RelativeRegion;
+//!
+//! // Coin supérieur droit : 20 % × 10 %
+//! let region = RelativeRegion::new(0.8, 0.0, 0.2, 0.1)?;
+//! assert_eq!(region.to_bbox(1920, 1080), BBox::new(1536, 0, 384, 108));
+//!
+//! // Même écriture que l'option --region-pct
+//! let parsed: RelativeRegion = "0.8,0.0,0.2,0.1".parse()?;
+//! assert_eq!(parsed, region);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Avec un moteur OCR :
+//!
+//! ```no_run
+//! # #[cfg(feature = "tesseract")]
+//! # {
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::ocr::OcrEngine;
+//! use text_recognition::region::RelativeRegion;
+//! use std::path::Path;
+//!
+//! let engine = OcrEngine::new(OcrConfig::screenshot_preset())?;
+//! let clock = RelativeRegion::new(0.9, 0.0, 0.1, 0.05)?;
+//! let text = engine.extract_text_from_relative_region(Path::new("capture.png"), &clock)?;
+//! println!("{}", text);
+//! # }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::hocr::BBox;
+use anyhow::{Result, bail};
+use std::str::FromStr;
+
+/// Tolérance sur `x + width` et `y + height` (`0.8 + 0.2` n'est pas exactement 1 en flottant).
+const EDGE_TOLERANCE: f32 = 1e-6;
+
+/// Région rectangulaire exprimée en fractions des dimensions de l'image.
+///
+/// `x` et `width` sont relatifs à la largeur, `y` et `height` à la hauteur ;
+/// l'origine est le coin supérieur gauche. Les valeurs doivent être comprises
+/// entre 0 et 1, la largeur et la hauteur être non nulles, et la région tenir
+/// dans l'image (voir [`validate()`](Self::validate)).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelativeRegion {
+    /// Bord gauche, en fraction de la largeur.
+    pub x: f32,
+    /// Bord supérieur, en fraction de la hauteur.
+    pub y: f32,
+    /// Largeur, en fraction de la largeur de l'image.
+    pub width: f32,
+    /// Hauteur, en fraction de la hauteur de l'image.
+    pub height: f32,
+}
+
+impl RelativeRegion {
+    /// Crée une région relative après validation.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Bord gauche (0.0 à 1.0)
+    /// * `y` - Bord supérieur (0.0 à 1.0)
+    /// * `width` - Largeur (0.0 exclu à 1.0)
+    /// * `height` - Hauteur (0.0 exclu à 1.0)
+    ///
+    /// # Erreurs
+    ///
+    /// Voir [`validate()`](Self::validate).
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Result<Self> {
+        let region = Self {
+            x,
+            y,
+            width,
+            height,
+        };
+        region.validate()?;
+        Ok(region)
+    }
+
+    /// Vérifie que la région est bien formée.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si une valeur n'est pas un nombre fini entre 0 et 1,
+    /// si la largeur ou la hauteur est nulle, ou si la région dépasse le bord
+    /// droit ou le bord inférieur de l'image.
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in [
+            ("x", self.x),
+            ("y", self.y),
+            ("largeur", self.width),
+            ("hauteur", self.height),
+        ] {
+            if !value.is_finite() || !(0.0..=1.0).contains(&value) {
+                bail!(
+                    "Région relative invalide : {} = {} (attendu entre 0 et 1)",
+                    name,
+                    value
+                );
+            }
+        }
+        if self.width == 0.0 || self.height == 0.0 {
+            bail!(
+                "Région relative vide : largeur {} et hauteur {} doivent être non nulles",
+                self.width,
+                self.height
+            );
+        }
+        if self.x + self.width > 1.0 + EDGE_TOLERANCE || self.y + self.height > 1.0 + EDGE_TOLERANCE
+        {
+            bail!(
+                "Région relative hors de l'image : x + largeur = {}, y + hauteur = {} (maximum 1)",
+                self.x + self.width,
+                self.y + self.height
+            );
+        }
+        Ok(())
+    }
+
+    /// Convertit la région en rectangle de pixels pour une image donnée.
+    ///
+    /// Les bords sont arrondis au pixel le plus proche puis limités à
+    /// l'image : un bord droit à 1.0 correspond à la dernière colonne
+    /// (`x + width == image_width`). Une région trop petite pour couvrir un
+    /// pixel est agrandie à 1×1.
+    ///
+    /// # Arguments
+    ///
+    /// * `image_width` - Largeur de l'image en pixels
+    /// * `image_height` - Hauteur de l'image en pixels
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::hocr::BBox;
+    /// use text_recognition::region::RelativeRegion;
+    ///
+    /// let region = RelativeRegion::new(0.5, 0.5, 0.0001, 0.0001)?;
+    /// assert_eq!(region.to_bbox(100, 100), BBox::new(50, 50, 1, 1));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn to_bbox(&self, image_width: u32, image_height: u32) -> BBox {
+        let (x, width) = to_pixel_span(self.x, self.width, image_width);
+        let (y, height) = to_pixel_span(self.y, self.height, image_height);
+        BBox::new(x, y, width, height)
+    }
+}
+
+impl FromStr for RelativeRegion {
+    type Err = anyhow::Error;
+
+    /// Lit une région au format `x,y,largeur,hauteur` (ex: `0.8,0.0,0.2,0.1`).
+    fn from_str(s: &str) -> Result<Self> {
+        let values = s
+            .split(',')
+            .map(|value| value.trim().parse::<f32>())
+            .collect::<Result<Vec<_>, _>>();
+        match values.as_deref() {
+            Ok(&[x, y, width, height]) => Self::new(x, y, width, height),
+            _ => bail!(
+                "Région relative invalide : '{}' (attendu x,y,largeur,hauteur, ex: 0.8,0.0,0.2,0.1)",
+                s
+            ),
+        }
+    }
+}
+
+/// Convertit un segment relatif (début, longueur) en pixels sur une dimension.
+///
+/// Garantit au moins un pixel et reste dans `[0, size)` (pour `size > 0`).
+fn to_pixel_span(start: f32, length: f32, size: u32) -> (u32, u32) {
+    let scale = size as f64;
+    let last = size.saturating_sub(1);
+    let begin = ((start as f64 * scale).round() as u32).min(last);
+    let end = (((start as f64 + length as f64) * scale).round() as u32).min(size);
+    (begin, end.saturating_sub(begin).max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_bbox_at_image_edges() {
+        // Bord droit et bord inférieur à 1.0 : dernière colonne et dernière ligne incluses
+        let region = RelativeRegion::new(0.8, 0.9, 0.2, 0.1).unwrap();
+        let bbox = region.to_bbox(101, 57);
+        assert_eq!(bbox.x + bbox.width, 101);
+        assert_eq!(bbox.y + bbox.height, 57);
+
+        let whole = RelativeRegion::new(0.0, 0.0, 1.0, 1.0).unwrap();
+        assert_eq!(whole.to_bbox(640, 480), BBox::new(0, 0, 640, 480));
+
+        // Minuscule région collée au bord : 1×1 sur le dernier pixel
+        let corner = RelativeRegion::new(0.99999, 0.99999, 0.00001, 0.00001).unwrap();
+        assert_eq!(corner.to_bbox(100, 50), BBox::new(99, 49, 1, 1));
+    }
+
+    #[test]
+    fn test_pixel_and_relative_regions_match() {
+        // Image de 200×100 : le quart central (50, 25, 100, 50)
+        let region = RelativeRegion::new(0.25, 0.25, 0.5, 0.5).unwrap();
+        assert_eq!(region.to_bbox(200, 100), BBox::new(50, 25, 100, 50));
+
+        let region = RelativeRegion::new(0.3, 0.7, 0.1, 0.3).unwrap();
+        assert_eq!(region.to_bbox(1000, 500), BBox::new(300, 350, 100, 150));
+    }
+
+    #[test]
+    fn test_degenerate_regions_rejected() {
+        let error = RelativeRegion::new(0.5, 0.5, 0.0, 0.2).unwrap_err();
+        assert!(error.to_string().contains("vide"));
+        assert!(RelativeRegion::new(0.5, 0.5, 0.2, 0.0).is_err());
+        assert!(RelativeRegion::new(1.0, 0.0, 0.0, 0.1).is_err());
+
+        assert!(RelativeRegion::new(-0.1, 0.0, 0.2, 0.2).is_err());
+        assert!(RelativeRegion::new(0.0, f32::NAN, 0.2, 0.2).is_err());
+        assert!(RelativeRegion::new(0.0, 0.0, 1.5, 0.2).is_err());
+        let error = RelativeRegion::new(0.9, 0.0, 0.2, 0.2).unwrap_err();
+        assert!(error.to_string().contains("hors de l'image"));
+
+        // Champs publics : validate() détecte une région construite à la main
+        let manual = RelativeRegion {
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+            height: 1.0,
+        };
+        assert!(manual.validate().is_err());
+    }
+
+    #[test]
+    fn test_from_str() {
+        let region: RelativeRegion = " 0.8, 0 ,0.2,0.1".parse().unwrap();
+        assert_eq!(region, RelativeRegion::new(0.8, 0.0, 0.2, 0.1).unwrap());
+
+        assert!("0.8,0.0,0.2".parse::<RelativeRegion>().is_err());
+        assert!("0.8,0.0,0.2,abc".parse::<RelativeRegion>().is_err());
+        assert!("0.8,0.0,0.3,0.1".parse::<RelativeRegion>().is_err());
+    }
+}
@@ -0,0 +1,539 @@
+//! Lecture et écriture de fichiers de régions nommées (labelme, format simple).
+//!
+//! Les équipes d'annotation délimitent souvent les zones à lire avec
+//! [labelme](https://github.com/wkentaro/labelme) : chaque forme porte une
+//! étiquette (`total`, `date`, `iban`...) et des points en coordonnées
+//! flottantes. Ce module accepte deux formats JSON, reconnus à leur
+//! structure :
+//!
+//! - labelme : objet avec un tableau `shapes` de formes `rectangle` (deux
+//!   coins opposés) ou `polygon` (au moins trois sommets) ; les autres types
+//!   (`circle`, `line`, `point`...) sont refusés ;
+//! - format simple : tableau d'objets
+//!   `{"label": "total", "x": 410, "y": 880, "w": 160, "h": 40}`.
+//!
+//! Chaque forme est réduite à son rectangle englobant ([`NamedRegion`]) :
+//! les bords sont arrondis vers l'extérieur au pixel entier puis limités à
+//! l'image. [`RegionsFile::to_label_json()`] associe chaque étiquette à
+//! son texte reconnu ; [`RegionsFile::to_json_with_texts()`] réécrit le
+//! fichier lu en ajoutant un champ `text` à chaque forme, sans toucher au
+//! reste du document (`imagePath`, `flags`...).
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::hocr::BBox;
+//! use text_recognition::regions_io::RegionsFile;
+//!
+//! let file = RegionsFile::parse(
+//!     r#"{"shapes": [{"label": "total", "shape_type": "rectangle",
+//!                     "points": [[410.4, 880.0], [569.6, 919.5]]}]}"#,
+//! )?;
+//! let regions = file.regions(1240, 1754)?;
+//! assert_eq!(regions[0].label, "total");
+//! assert_eq!(regions[0].bbox, BBox::new(410, 880, 160, 40));
+//!
+//! let texts = ["12,50 EUR".to_string()];
+//! assert!(file.to_label_json(&texts)?.contains(r#""total": "12,50 EUR""#));
+//! let output = file.to_json_with_texts(&texts)?;
+//! assert!(output.contains(r#""text": "12,50 EUR""#));
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::hocr::BBox;
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Format d'un fichier de régions, déduit de sa structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionsFormat {
+    /// Objet labelme avec un tableau `shapes`.
+    Labelme,
+    /// Tableau d'objets `label`, `x`, `y`, `w`, `h`.
+    Simple,
+}
+
+/// Région étiquetée, en pixels de l'image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedRegion {
+    /// Étiquette de la forme (plusieurs régions peuvent partager la même).
+    pub label: String,
+    /// Rectangle englobant de la forme, limité à l'image.
+    pub bbox: BBox,
+}
+
+/// Forme lue dans un fichier de régions, avant arrondi.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionShape {
+    /// Étiquette de la forme.
+    pub label: String,
+    /// Bords gauche, haut, droit et bas du rectangle englobant, en pixels flottants.
+    pub bounds: [f64; 4],
+    /// Texte déjà associé à la forme (fichier écrit par [`RegionsFile::to_json_with_texts()`]).
+    pub text: Option<String>,
+}
+
+/// Fichier de régions lu par [`RegionsFile::parse()`] ou [`RegionsFile::load()`].
+#[derive(Debug, Clone)]
+pub struct RegionsFile {
+    /// Format reconnu.
+    pub format: RegionsFormat,
+    /// Formes, dans l'ordre du fichier.
+    pub shapes: Vec<RegionShape>,
+    /// Document d'origine, conservé pour la réécriture.
+    document: Value,
+}
+
+/// Forme labelme (les champs non utilisés sont conservés dans le document).
+#[derive(Deserialize)]
+struct LabelmeShape {
+    label: String,
+    points: Vec<[f64; 2]>,
+    /// labelme omet le type dans ses plus anciennes versions : polygone.
+    #[serde(default = "default_shape_type")]
+    shape_type: String,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+fn default_shape_type() -> String {
+    "polygon".to_string()
+}
+
+/// Entrée du format simple.
+#[derive(Deserialize)]
+struct SimpleRecord {
+    label: String,
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+impl RegionsFile {
+    /// Analyse un fichier de régions JSON (labelme ou format simple).
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - Contenu du fichier
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le JSON est invalide, s'il n'est ni un objet
+    /// avec `shapes` ni un tableau, si une forme a un type autre que
+    /// `rectangle` ou `polygon`, un nombre de points incohérent avec son
+    /// type, une coordonnée non finie ou une taille négative. L'erreur
+    /// indique le numéro (à partir de 1) et l'étiquette de la forme.
+    pub fn parse(json: &str) -> Result<Self> {
+        let document: Value = serde_json::from_str(json).context("JSON invalide")?;
+        let (format, entries) = match &document {
+            Value::Object(object) => match object.get("shapes") {
+                Some(Value::Array(shapes)) => (RegionsFormat::Labelme, shapes),
+                _ => bail!("Objet JSON sans tableau \"shapes\" : format labelme attendu"),
+            },
+            Value::Array(records) => (RegionsFormat::Simple, records),
+            _ => bail!("Fichier de régions invalide : objet labelme ou tableau de régions attendu"),
+        };
+
+        let shapes = entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let shape = match format {
+                    RegionsFormat::Labelme => labelme_shape(entry),
+                    RegionsFormat::Simple => simple_shape(entry),
+                };
+                shape.with_context(|| format!("Forme #{} {}", index + 1, describe_label(entry)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            format,
+            shapes,
+            document,
+        })
+    }
+
+    /// Lit et analyse un fichier de régions.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Chemin du fichier JSON
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le fichier ne peut pas être lu, ou pour les
+    /// raisons de [`parse()`](Self::parse).
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Impossible de lire le fichier '{}'", path.display()))?;
+        Self::parse(&content)
+            .with_context(|| format!("Fichier de régions invalide : '{}'", path.display()))
+    }
+
+    /// Convertit les formes en régions de pixels pour une image donnée.
+    ///
+    /// Le bord gauche et le bord haut sont arrondis à l'entier inférieur,
+    /// le bord droit et le bord bas à l'entier supérieur, puis chaque bord
+    /// est limité à l'image.
+    ///
+    /// # Arguments
+    ///
+    /// * `image_width` - Largeur de l'image en pixels
+    /// * `image_height` - Hauteur de l'image en pixels
+    ///
+    /// # Retour
+    ///
+    /// Une région par forme, dans l'ordre du fichier.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si une forme est entièrement hors de l'image ou
+    /// vide une fois arrondie.
+    pub fn regions(&self, image_width: u32, image_height: u32) -> Result<Vec<NamedRegion>> {
+        self.shapes
+            .iter()
+            .enumerate()
+            .map(|(index, shape)| {
+                let [left, top, right, bottom] = shape.bounds;
+                let (x, width) = pixel_span(left, right, image_width);
+                let (y, height) = pixel_span(top, bottom, image_height);
+                if width == 0 || height == 0 {
+                    bail!(
+                        "Forme #{} (\"{}\") vide ou hors de l'image ({}×{})",
+                        index + 1,
+                        shape.label,
+                        image_width,
+                        image_height
+                    );
+                }
+                Ok(NamedRegion {
+                    label: shape.label.clone(),
+                    bbox: BBox::new(x, y, width, height),
+                })
+            })
+            .collect()
+    }
+
+    /// Réécrit le fichier lu en ajoutant le texte reconnu de chaque forme.
+    ///
+    /// Le document garde sa structure et ses autres champs ; chaque forme
+    /// reçoit un champ `text` (remplacé s'il existait déjà).
+    ///
+    /// # Arguments
+    ///
+    /// * `texts` - Un texte par forme, dans l'ordre de [`shapes`](Self::shapes)
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si `texts` n'a pas autant d'éléments que le
+    /// fichier a de formes.
+    pub fn to_json_with_texts(&self, texts: &[String]) -> Result<String> {
+        self.check_text_count(texts)?;
+        let mut document = self.document.clone();
+        let entries = match (&mut document, self.format) {
+            (Value::Object(object), RegionsFormat::Labelme) => object.get_mut("shapes"),
+            (array @ Value::Array(_), RegionsFormat::Simple) => Some(array),
+            _ => None,
+        };
+        if let Some(Value::Array(entries)) = entries {
+            for (entry, text) in entries.iter_mut().zip(texts) {
+                if let Value::Object(entry) = entry {
+                    entry.insert("text".to_string(), Value::String(text.clone()));
+                }
+            }
+        }
+        serde_json::to_string_pretty(&document).context("Échec de la sérialisation JSON")
+    }
+
+    /// Associe chaque étiquette au texte reconnu de sa forme, en JSON.
+    ///
+    /// L'objet produit est trié par étiquette. Une étiquette portée par
+    /// plusieurs formes reçoit le tableau de leurs textes, dans l'ordre du
+    /// fichier.
+    ///
+    /// # Arguments
+    ///
+    /// * `texts` - Un texte par forme, dans l'ordre de [`shapes`](Self::shapes)
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si `texts` n'a pas autant d'éléments que le
+    /// fichier a de formes.
+    pub fn to_label_json(&self, texts: &[String]) -> Result<String> {
+        self.check_text_count(texts)?;
+        let mut labels = Map::new();
+        for (shape, text) in self.shapes.iter().zip(texts) {
+            let text = Value::String(text.clone());
+            match labels.get_mut(&shape.label) {
+                None => {
+                    labels.insert(shape.label.clone(), text);
+                }
+                Some(Value::Array(previous)) => previous.push(text),
+                Some(previous) => *previous = Value::Array(vec![previous.take(), text]),
+            }
+        }
+        serde_json::to_string_pretty(&labels).context("Échec de la sérialisation JSON")
+    }
+
+    fn check_text_count(&self, texts: &[String]) -> Result<()> {
+        if texts.len() != self.shapes.len() {
+            bail!(
+                "{} texte(s) pour {} forme(s)",
+                texts.len(),
+                self.shapes.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Lit une forme labelme et la réduit à son rectangle englobant.
+fn labelme_shape(entry: &Value) -> Result<RegionShape> {
+    let shape = LabelmeShape::deserialize(entry).context("forme labelme invalide")?;
+    let (valid, expected) = match shape.shape_type.as_str() {
+        "rectangle" => (shape.points.len() == 2, "2"),
+        "polygon" => (shape.points.len() >= 3, "au moins 3"),
+        other => bail!(
+            "type de forme non supporté : \"{}\" (seuls \"rectangle\" et \"polygon\" sont acceptés)",
+            other
+        ),
+    };
+    if !valid {
+        bail!(
+            "{} point(s) pour une forme \"{}\" (attendu : {})",
+            shape.points.len(),
+            shape.shape_type,
+            expected
+        );
+    }
+    let mut bounds = [
+        f64::INFINITY,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NEG_INFINITY,
+    ];
+    for [x, y] in &shape.points {
+        bounds = [
+            bounds[0].min(*x),
+            bounds[1].min(*y),
+            bounds[2].max(*x),
+            bounds[3].max(*y),
+        ];
+    }
+    check_finite(&bounds)?;
+    Ok(RegionShape {
+        label: shape.label,
+        bounds,
+        text: shape.text,
+    })
+}
+
+/// Lit une entrée du format simple.
+fn simple_shape(entry: &Value) -> Result<RegionShape> {
+    let record = SimpleRecord::deserialize(entry)
+        .context("région invalide (champs attendus : label, x, y, w, h)")?;
+    if record.w < 0.0 || record.h < 0.0 {
+        bail!("taille négative : {} × {}", record.w, record.h);
+    }
+    let bounds = [record.x, record.y, record.x + record.w, record.y + record.h];
+    check_finite(&bounds)?;
+    Ok(RegionShape {
+        label: record.label,
+        bounds,
+        text: record.text,
+    })
+}
+
+fn check_finite(bounds: &[f64; 4]) -> Result<()> {
+    if bounds.iter().any(|value| !value.is_finite()) {
+        bail!("coordonnée non finie");
+    }
+    Ok(())
+}
+
+/// Étiquette d'une entrée pour les messages d'erreur, si elle en a une.
+fn describe_label(entry: &Value) -> String {
+    entry
+        .get("label")
+        .and_then(Value::as_str)
+        .map(|label| format!("(\"{}\")", label))
+        .unwrap_or_default()
+}
+
+/// Arrondit un intervalle flottant vers l'extérieur et le limite à `[0, size]`.
+fn pixel_span(start: f64, end: f64, size: u32) -> (u32, u32) {
+    let clamp = |value: f64| value.clamp(0.0, f64::from(size)) as u32;
+    let start = clamp(start.floor());
+    let end = clamp(end.ceil());
+    (start, end.saturating_sub(start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fichier tel qu'enregistré par labelme 5 (imageData tronqué).
+    const LABELME: &str = r#"{
+  "version": "5.4.1",
+  "flags": {},
+  "shapes": [
+    {
+      "label": "total",
+      "points": [[569.6, 919.5], [410.4, 880.0]],
+      "group_id": null,
+      "description": "",
+      "shape_type": "rectangle",
+      "flags": {},
+      "mask": null
+    },
+    {
+      "label": "tampon",
+      "points": [[100.2, 300.0], [180.0, 260.7], [260.9, 310.0], [175.5, 390.1]],
+      "group_id": null,
+      "description": "incliné",
+      "shape_type": "polygon",
+      "flags": {},
+      "mask": null
+    }
+  ],
+  "imagePath": "facture.png",
+  "imageData": null,
+  "imageHeight": 1754,
+  "imageWidth": 1240
+}"#;
+
+    #[test]
+    fn test_parse_labelme_rectangle_and_polygon() {
+        let file = RegionsFile::parse(LABELME).unwrap();
+        assert_eq!(file.format, RegionsFormat::Labelme);
+        assert_eq!(
+            file.regions(1240, 1754).unwrap(),
+            [
+                NamedRegion {
+                    label: "total".to_string(),
+                    bbox: BBox::new(410, 880, 160, 40),
+                },
+                NamedRegion {
+                    label: "tampon".to_string(),
+                    bbox: BBox::new(100, 260, 161, 131),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_regions_are_clamped_to_image() {
+        let file = RegionsFile::parse(
+            r#"[{"label": "bord", "x": -12.5, "y": 90.2, "w": 60, "h": 40},
+                {"label": "dehors", "x": 300, "y": 10, "w": 20, "h": 20}]"#,
+        )
+        .unwrap();
+        assert_eq!(file.format, RegionsFormat::Simple);
+        assert_eq!(file.shapes[0].bounds, [-12.5, 90.2, 47.5, 130.2]);
+
+        let error = file.regions(200, 100).unwrap_err().to_string();
+        assert!(
+            error.contains("#2") && error.contains("dehors"),
+            "{}",
+            error
+        );
+
+        let first =
+            RegionsFile::parse(r#"[{"label": "bord", "x": -12.5, "y": 90.2, "w": 60, "h": 40}]"#)
+                .unwrap();
+        assert_eq!(
+            first.regions(200, 100).unwrap()[0].bbox,
+            BBox::new(0, 90, 48, 10)
+        );
+    }
+
+    #[test]
+    fn test_unsupported_shape_type_is_rejected() {
+        let json = LABELME.replacen("\"polygon\"", "\"circle\"", 1);
+        let error = format!("{:#}", RegionsFile::parse(&json).unwrap_err());
+        assert!(error.contains("#2"), "{}", error);
+        assert!(error.contains("tampon"), "{}", error);
+        assert!(error.contains("\"circle\""), "{}", error);
+
+        let error = format!(
+            "{:#}",
+            RegionsFile::parse(
+                r#"{"shapes": [{"label": "a", "shape_type": "rectangle", "points": [[0, 0]]}]}"#
+            )
+            .unwrap_err()
+        );
+        assert!(error.contains("1 point(s)"), "{}", error);
+        assert!(RegionsFile::parse(r#"{"version": "5.4.1"}"#).is_err());
+    }
+
+    #[test]
+    fn test_label_json_maps_labels_to_texts() {
+        let file = RegionsFile::parse(LABELME).unwrap();
+        let texts = ["12,50 EUR".to_string(), "PAYÉ".to_string()];
+        let labels: Value = serde_json::from_str(&file.to_label_json(&texts).unwrap()).unwrap();
+        assert_eq!(
+            labels,
+            serde_json::json!({"tampon": "PAYÉ", "total": "12,50 EUR"})
+        );
+
+        // Une étiquette répétée garde les textes de toutes ses formes
+        let file = RegionsFile::parse(
+            r#"[{"label": "ligne", "x": 0, "y": 0, "w": 10, "h": 10},
+                {"label": "total", "x": 0, "y": 20, "w": 10, "h": 10},
+                {"label": "ligne", "x": 0, "y": 40, "w": 10, "h": 10},
+                {"label": "ligne", "x": 0, "y": 60, "w": 10, "h": 10}]"#,
+        )
+        .unwrap();
+        let texts: Vec<String> = ["café", "9,90", "thé", "pain"].map(String::from).to_vec();
+        let labels: Value = serde_json::from_str(&file.to_label_json(&texts).unwrap()).unwrap();
+        assert_eq!(
+            labels,
+            serde_json::json!({"ligne": ["café", "thé", "pain"], "total": "9,90"})
+        );
+
+        assert!(file.to_label_json(&texts[..2]).is_err());
+    }
+
+    #[test]
+    fn test_texts_round_trip() {
+        let texts = ["12,50 EUR".to_string(), "PAYÉ \"le 3\"".to_string()];
+        for json in [
+            LABELME,
+            r#"[{"label": "total", "x": 410, "y": 880, "w": 160, "h": 40},
+                {"label": "tampon", "x": 100, "y": 260, "w": 161, "h": 131}]"#,
+        ] {
+            let file = RegionsFile::parse(json).unwrap();
+            let written = RegionsFile::parse(&file.to_json_with_texts(&texts).unwrap()).unwrap();
+
+            assert_eq!(written.format, file.format);
+            let pairs: Vec<(&str, Option<&str>)> = written
+                .shapes
+                .iter()
+                .map(|shape| (shape.label.as_str(), shape.text.as_deref()))
+                .collect();
+            assert_eq!(
+                pairs,
+                [
+                    ("total", Some("12,50 EUR")),
+                    ("tampon", Some("PAYÉ \"le 3\""))
+                ]
+            );
+            assert_eq!(
+                written.regions(1240, 1754).unwrap(),
+                file.regions(1240, 1754).unwrap()
+            );
+        }
+
+        let file = RegionsFile::parse(LABELME).unwrap();
+        let written: Value =
+            serde_json::from_str(&file.to_json_with_texts(&texts).unwrap()).unwrap();
+        assert_eq!(written["imagePath"], "facture.png");
+        assert_eq!(written["shapes"][1]["description"], "incliné");
+        assert!(file.to_json_with_texts(&texts[..1]).is_err());
+    }
+}
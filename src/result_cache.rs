@@ -0,0 +1,381 @@
+//! Cache des résultats OCR, indexé par le contenu de l'image.
+//!
+//! Un traitement nocturne relit souvent les mêmes factures inchangées. Avec
+//! [`OcrEngine::with_result_cache()`](crate::ocr::OcrEngine::with_result_cache),
+//! [`OcrEngine::extract_text_detailed()`](crate::ocr::OcrEngine::extract_text_detailed)
+//! consulte le cache avant toute lecture de l'image par Tesseract et y
+//! range le résultat ensuite ([`OcrOutcome::cache_hit`] signale un résultat
+//! réutilisé).
+//! [`OcrEngine::extract_hocr_document()`](crate::ocr::OcrEngine::extract_hocr_document)
+//! y range de même le document HOCR analysé, sérialisé en JSON versionné
+//! (voir [`HocrDocument::to_json()`]), sous une clé distincte.
+//!
+//! La clé ([`cache_key()`]) combine l'empreinte SHA-256 du fichier image et
+//! celle de la configuration OCR et de prétraitement : changer de langue, de
+//! PSM ou de prétraitement change la clé. La version de Tesseract n'en fait
+//! pas partie ; vider le cache après une mise à jour.
+//!
+//! Deux implémentations de [`ResultCache`] sont fournies :
+//!
+//! - [`MemoryCache`] : en mémoire, les entrées les moins récemment utilisées
+//!   sont évincées au-delà de sa capacité.
+//! - [`DirectoryCache`] : un fichier JSON par clé dans un répertoire, partagé
+//!   entre exécutions. Un fichier illisible ou corrompu est traité comme
+//!   absent : l'image est relue.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use std::sync::Arc;
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::ocr::OcrEngine;
+//! use text_recognition::result_cache::DirectoryCache;
+//!
+//! let engine = OcrEngine::new(OcrConfig::default())?
+//!     .with_result_cache(Arc::new(DirectoryCache::new("/var/cache/ocr")));
+//!
+//! let first = engine.extract_text_detailed(Path::new("facture.png"))?;
+//! let second = engine.extract_text_detailed(Path::new("facture.png"))?;
+//! assert!(!first.cache_hit && second.cache_hit);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::config::OcrConfig;
+use crate::diagnostics::Warning;
+use crate::hocr::HocrDocument;
+use crate::ocr::OcrOutcome;
+use crate::preprocessing::PreprocessingConfig;
+use crate::run_metadata::{sha256_file, sha256_hex};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Résultat OCR conservé dans un cache.
+///
+/// Seuls le texte et les avertissements sont conservés : le rapport de
+/// prétraitement n'est pas restitué lors d'un succès du cache.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedResult {
+    /// Texte extrait.
+    pub text: String,
+    /// Messages des avertissements de l'extraction.
+    pub warnings: Vec<String>,
+    /// Document HOCR analysé, pour les entrées de
+    /// [`extract_hocr_document()`](crate::ocr::OcrEngine::extract_hocr_document)
+    /// (JSON versionné, voir [`HocrDocument::to_json()`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document: Option<HocrDocument>,
+}
+
+impl From<&OcrOutcome> for CachedResult {
+    fn from(outcome: &OcrOutcome) -> Self {
+        Self {
+            text: outcome.text.clone(),
+            warnings: outcome
+                .warnings
+                .iter()
+                .map(|warning| warning.message.clone())
+                .collect(),
+            document: None,
+        }
+    }
+}
+
+impl From<CachedResult> for OcrOutcome {
+    fn from(cached: CachedResult) -> Self {
+        Self {
+            text: cached.text,
+            warnings: cached.warnings.into_iter().map(Warning::new).collect(),
+            preprocessing: None,
+            cache_hit: true,
+        }
+    }
+}
+
+/// Stockage de résultats OCR indexés par une clé (voir [`cache_key()`]).
+///
+/// Les implémentations sont partagées entre threads : `get` et `put`
+/// prennent `&self`.
+pub trait ResultCache: Send + Sync + fmt::Debug {
+    /// Retourne le résultat associé à la clé, s'il existe et est lisible.
+    fn get(&self, key: &str) -> Option<CachedResult>;
+
+    /// Associe un résultat à la clé, en remplaçant l'éventuel précédent.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le résultat ne peut pas être enregistré.
+    fn put(&self, key: &str, result: &CachedResult) -> Result<()>;
+}
+
+/// Calcule la clé de cache d'une image pour une configuration.
+///
+/// La clé est l'empreinte SHA-256 (hexadécimal) de l'empreinte du fichier
+/// image, de la configuration OCR et de prétraitement sérialisée (clés
+/// triées), et du contenu des fichiers `user_words` et `user_patterns`.
+///
+/// # Arguments
+///
+/// * `image` - Chemin du fichier image
+/// * `config` - Configuration OCR du moteur
+/// * `preprocessing` - Configuration du prétraitement, si activé
+///
+/// # Erreurs
+///
+/// Retourne une erreur si l'image ou un fichier de vocabulaire ne peut pas
+/// être lu.
+pub fn cache_key(
+    image: &Path,
+    config: &OcrConfig,
+    preprocessing: Option<&PreprocessingConfig>,
+) -> Result<String> {
+    // Passer par Value trie les clés (variables Tesseract dans une HashMap)
+    let config_json = serde_json::to_value(config)
+        .and_then(|value| serde_json::to_string(&value))
+        .context("Échec de la sérialisation de la configuration OCR")?;
+    let preprocessing_json =
+        serde_json::to_string(&preprocessing).context("Échec de la sérialisation JSON")?;
+    let vocabulary = [&config.user_words, &config.user_patterns]
+        .into_iter()
+        .map(|file| file.as_deref().map(sha256_file).transpose())
+        .collect::<Result<Vec<_>>>()?;
+
+    let material = format!(
+        "{}\n{}\n{}\n{:?}",
+        sha256_file(image)?,
+        config_json,
+        preprocessing_json,
+        vocabulary
+    );
+    Ok(sha256_hex(material.as_bytes()))
+}
+
+/// Cache en mémoire à capacité bornée (éviction LRU).
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::result_cache::{CachedResult, MemoryCache, ResultCache};
+///
+/// let cache = MemoryCache::new(1);
+/// let result = CachedResult {
+///     text: "Facture".into(),
+///     warnings: Vec::new(),
+///     document: None,
+/// };
+/// cache.put("a", &result)?;
+/// cache.put("b", &result)?;
+///
+/// assert!(cache.get("a").is_none());
+/// assert_eq!(cache.get("b"), Some(result));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct MemoryCache {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+/// Entrées du cache en mémoire et ordre d'utilisation (la plus ancienne en tête).
+#[derive(Debug, Default)]
+struct LruState {
+    entries: HashMap<String, CachedResult>,
+    order: VecDeque<String>,
+}
+
+impl LruState {
+    /// Marque la clé comme la plus récemment utilisée.
+    fn touch(&mut self, key: &str) {
+        if let Some(position) = self.order.iter().position(|entry| entry == key) {
+            self.order.remove(position);
+        }
+        self.order.push_back(key.to_string());
+    }
+}
+
+impl MemoryCache {
+    /// Crée un cache conservant au plus `capacity` résultats.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Nombre maximal de résultats (0 : rien n'est conservé)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    /// Nombre de résultats en cache.
+    pub fn len(&self) -> usize {
+        self.state.lock().map_or(0, |state| state.entries.len())
+    }
+
+    /// Indique si le cache est vide.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl ResultCache for MemoryCache {
+    fn get(&self, key: &str) -> Option<CachedResult> {
+        let mut state = self.state.lock().ok()?;
+        let result = state.entries.get(key).cloned()?;
+        state.touch(key);
+        Some(result)
+    }
+
+    fn put(&self, key: &str, result: &CachedResult) -> Result<()> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Cache de résultats inaccessible"))?;
+        state.entries.insert(key.to_string(), result.clone());
+        state.touch(key);
+        while state.entries.len() > self.capacity {
+            let Some(oldest) = state.order.pop_front() else {
+                break;
+            };
+            state.entries.remove(&oldest);
+        }
+        Ok(())
+    }
+}
+
+/// Cache sur disque : un fichier `<clé>.json` par résultat.
+///
+/// Le répertoire est créé au premier enregistrement. Chaque fichier est
+/// écrit à côté puis renommé, pour qu'un lecteur concurrent ne voie jamais
+/// un fichier à moitié écrit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryCache {
+    dir: PathBuf,
+}
+
+impl DirectoryCache {
+    /// Crée un cache dans le répertoire `dir`.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Répertoire des fichiers de cache
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Répertoire des fichiers de cache.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Fichier d'une clé.
+    pub fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+impl ResultCache for DirectoryCache {
+    fn get(&self, key: &str) -> Option<CachedResult> {
+        // Fichier absent ou corrompu : l'image sera relue
+        let content = std::fs::read_to_string(self.entry_path(key)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn put(&self, key: &str, result: &CachedResult) -> Result<()> {
+        std::fs::create_dir_all(&self.dir).with_context(|| {
+            format!(
+                "Impossible de créer le répertoire de cache '{}'",
+                self.dir.display()
+            )
+        })?;
+        let json = serde_json::to_string(result).context("Échec de la sérialisation JSON")?;
+        let path = self.entry_path(key);
+        let mut temp = tempfile::NamedTempFile::new_in(&self.dir).with_context(|| {
+            format!(
+                "Impossible d'écrire dans le répertoire de cache '{}'",
+                self.dir.display()
+            )
+        })?;
+        std::io::Write::write_all(&mut temp, json.as_bytes())
+            .with_context(|| format!("Impossible d'écrire '{}'", path.display()))?;
+        temp.persist(&path)
+            .with_context(|| format!("Impossible d'écrire '{}'", path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PageSegMode;
+
+    fn result(text: &str) -> CachedResult {
+        CachedResult {
+            text: text.to_string(),
+            warnings: vec!["Empty page!!".to_string()],
+            document: None,
+        }
+    }
+
+    #[test]
+    fn test_memory_cache_evicts_least_recently_used() {
+        let cache = MemoryCache::new(2);
+        cache.put("a", &result("A")).unwrap();
+        cache.put("b", &result("B")).unwrap();
+        // "a" redevient la plus récente : "b" est évincée
+        assert!(cache.get("a").is_some());
+        cache.put("c", &result("C")).unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a"), Some(result("A")));
+        assert_eq!(cache.get("c"), Some(result("C")));
+    }
+
+    #[test]
+    fn test_directory_cache_round_trip_and_corruption() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DirectoryCache::new(dir.path().join("cache"));
+
+        assert!(cache.get("abc").is_none());
+        cache.put("abc", &result("Facture")).unwrap();
+        assert_eq!(cache.get("abc"), Some(result("Facture")));
+
+        std::fs::write(cache.entry_path("abc"), "{\"text\": \"Fact").unwrap();
+        assert!(cache.get("abc").is_none());
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_content_and_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("page.png");
+        std::fs::write(&image, b"image A").unwrap();
+        let config = OcrConfig::default();
+        let key = cache_key(&image, &config, None).unwrap();
+
+        // Même contenu, même configuration : même clé, quel que soit le nom
+        let copy = dir.path().join("copie.png");
+        std::fs::write(&copy, b"image A").unwrap();
+        assert_eq!(cache_key(&copy, &config, None).unwrap(), key);
+
+        let single_line = OcrConfig {
+            page_seg_mode: PageSegMode::SingleLine,
+            ..OcrConfig::default()
+        };
+        assert_ne!(cache_key(&image, &single_line, None).unwrap(), key);
+        let preprocessing = PreprocessingConfig::default();
+        assert_ne!(
+            cache_key(&image, &config, Some(&preprocessing)).unwrap(),
+            key
+        );
+
+        std::fs::write(&image, b"image B").unwrap();
+        assert_ne!(cache_key(&image, &config, None).unwrap(), key);
+    }
+}
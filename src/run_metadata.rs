@@ -0,0 +1,487 @@
+//! Métadonnées de reproductibilité des métriques exportées.
+//!
+//! Pour qu'une ligne de métriques puisse être auditée, elle doit indiquer
+//! exactement quelle image a été traitée et dans quel environnement :
+//! empreinte SHA-256 et dimensions du fichier, versions de Tesseract et de
+//! la crate, paramètres OCR (dont les variables Tesseract effectives) et
+//! empreinte du prétraitement.
+//! [`RunMetadataBuilder`] construit cette table, directement utilisable avec
+//! [`OcrMetrics::to_csv()`](crate::metrics::OcrMetrics::to_csv).
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::metrics::compare_ocr_result;
+//! use text_recognition::run_metadata::build_run_metadata;
+//! use std::path::Path;
+//!
+//! let image = Path::new("facture.png");
+//! let metadata = build_run_metadata(image, &OcrConfig::default());
+//!
+//! let metrics = compare_ocr_result("Total 12,50", "Total 12,50");
+//! println!("{}", metrics.to_csv(true, Some(&metadata)));
+//! ```
+
+use crate::config::OcrConfig;
+use crate::effective_config::EffectiveConfig;
+use crate::preprocessing::PreprocessingConfig;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Clés toujours présentes dans les métadonnées construites.
+///
+/// Une valeur indisponible (fichier illisible, Tesseract introuvable,
+/// empreinte désactivée, aucun prétraitement) est une chaîne vide, pour que
+/// toutes les lignes d'un export CSV aient les mêmes colonnes.
+pub const RUN_METADATA_KEYS: [&str; 13] = [
+    "image",
+    "image_sha256",
+    "image_size_bytes",
+    "image_width",
+    "image_height",
+    "tesseract_version",
+    "crate_version",
+    "language",
+    "psm",
+    "oem",
+    "dpi",
+    "tesseract_variables",
+    "preprocessing_hash",
+];
+
+/// Construit les métadonnées d'exécution d'une image avec toutes les options.
+///
+/// Équivalent à `RunMetadataBuilder::new(image, config).build()`.
+///
+/// # Arguments
+///
+/// * `image` - Chemin de l'image traitée
+/// * `config` - Configuration OCR utilisée
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::config::OcrConfig;
+/// use text_recognition::run_metadata::build_run_metadata;
+/// use std::path::Path;
+///
+/// let metadata = build_run_metadata(Path::new("absente.png"), &OcrConfig::default());
+/// assert_eq!(metadata["psm"], "3");
+/// assert_eq!(metadata["image_sha256"], "");
+/// ```
+pub fn build_run_metadata(image: &Path, config: &OcrConfig) -> HashMap<String, String> {
+    RunMetadataBuilder::new(image, config).build()
+}
+
+/// Constructeur des métadonnées d'exécution (voir [`RUN_METADATA_KEYS`]).
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::config::OcrConfig;
+/// use text_recognition::preprocessing::PreprocessingConfig;
+/// use text_recognition::run_metadata::RunMetadataBuilder;
+/// use std::path::Path;
+///
+/// let config = OcrConfig::default();
+/// let preprocessing = PreprocessingConfig::default();
+///
+/// // Empreinte désactivée : évite de relire un fichier volumineux
+/// let metadata = RunMetadataBuilder::new(Path::new("scan.tiff"), &config)
+///     .preprocessing(Some(&preprocessing))
+///     .hash_image(false)
+///     .build();
+///
+/// assert_eq!(metadata["image_sha256"], "");
+/// assert_eq!(metadata["preprocessing_hash"].len(), 64);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RunMetadataBuilder<'a> {
+    image: &'a Path,
+    config: &'a OcrConfig,
+    preprocessing: Option<&'a PreprocessingConfig>,
+    hash_image: bool,
+}
+
+impl<'a> RunMetadataBuilder<'a> {
+    /// Crée un constructeur pour une image et une configuration OCR.
+    ///
+    /// Par défaut, l'empreinte SHA-256 de l'image est calculée et aucun
+    /// prétraitement n'est indiqué.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Chemin de l'image traitée
+    /// * `config` - Configuration OCR utilisée
+    pub fn new(image: &'a Path, config: &'a OcrConfig) -> Self {
+        Self {
+            image,
+            config,
+            preprocessing: None,
+            hash_image: true,
+        }
+    }
+
+    /// Indique la configuration de prétraitement appliquée, s'il y en a une.
+    pub fn preprocessing(mut self, preprocessing: Option<&'a PreprocessingConfig>) -> Self {
+        self.preprocessing = preprocessing;
+        self
+    }
+
+    /// Active ou désactive le calcul de l'empreinte SHA-256 de l'image.
+    ///
+    /// La lecture complète du fichier peut être longue pour de grandes
+    /// images ; désactivée, la valeur `image_sha256` est vide.
+    pub fn hash_image(mut self, hash_image: bool) -> Self {
+        self.hash_image = hash_image;
+        self
+    }
+
+    /// Construit la table des métadonnées.
+    ///
+    /// Ne retourne pas d'erreur : une information indisponible donne une
+    /// valeur vide.
+    pub fn build(&self) -> HashMap<String, String> {
+        let image_sha256 = if self.hash_image {
+            sha256_file(self.image).unwrap_or_default()
+        } else {
+            String::new()
+        };
+        let image_size = std::fs::metadata(self.image)
+            .map(|meta| meta.len().to_string())
+            .unwrap_or_default();
+        let (width, height) = image::image_dimensions(self.image)
+            .map(|(w, h)| (w.to_string(), h.to_string()))
+            .unwrap_or_default();
+        // La configuration se sérialise toujours ; une chaîne vide signale l'absence
+        let preprocessing_hash = self
+            .preprocessing
+            .and_then(|config| serde_json::to_string(config).ok())
+            .map(|json| sha256_hex(json.as_bytes()))
+            .unwrap_or_default();
+
+        let values = [
+            self.image.to_string_lossy().to_string(),
+            image_sha256,
+            image_size,
+            width,
+            height,
+            tesseract_version().unwrap_or_default(),
+            env!("CARGO_PKG_VERSION").to_string(),
+            self.config.language.clone(),
+            self.config.page_seg_mode.to_tesseract_psm().to_string(),
+            self.config.engine_mode.to_tesseract_oem().to_string(),
+            self.config.dpi.to_string(),
+            EffectiveConfig::from_config(self.config).variables_summary(),
+            preprocessing_hash,
+        ];
+
+        RUN_METADATA_KEYS
+            .iter()
+            .map(|key| key.to_string())
+            .zip(values)
+            .collect()
+    }
+}
+
+/// Retourne la version du binaire `tesseract` (ex: "5.3.4").
+///
+/// Le résultat de `tesseract --version` est mis en cache pour la durée du
+/// processus. Retourne `None` si le binaire est introuvable.
+pub fn tesseract_version() -> Option<String> {
+    static VERSION: OnceLock<Option<String>> = OnceLock::new();
+
+    VERSION
+        .get_or_init(|| {
+            let output = Command::new("tesseract").arg("--version").output().ok()?;
+            // Les anciennes versions écrivent la version sur la sortie d'erreur
+            parse_tesseract_version(&String::from_utf8_lossy(&output.stdout))
+                .or_else(|| parse_tesseract_version(&String::from_utf8_lossy(&output.stderr)))
+        })
+        .clone()
+}
+
+/// Extrait la version de la sortie de `tesseract --version`.
+///
+/// La première ligne est de la forme `tesseract 5.3.4` (ou `tesseract v5.0.0`).
+fn parse_tesseract_version(output: &str) -> Option<String> {
+    let first_line = output.lines().next()?.trim();
+    let version = first_line.strip_prefix("tesseract ")?.trim();
+    let version = version.strip_prefix('v').unwrap_or(version);
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// Calcule l'empreinte SHA-256 d'un fichier (hexadécimal, minuscules).
+///
+/// Le fichier est lu par blocs : la mémoire utilisée ne dépend pas de sa
+/// taille.
+///
+/// # Erreurs
+///
+/// Retourne une erreur si le fichier ne peut pas être lu.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file =
+        File::open(path).with_context(|| format!("Impossible d'ouvrir '{}'", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .with_context(|| format!("Impossible de lire '{}'", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher.finalize_hex())
+}
+
+/// Calcule l'empreinte SHA-256 de données en mémoire (hexadécimal, minuscules).
+pub(crate) fn sha256_hex(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hasher.finalize_hex()
+}
+
+/// Constantes de tour de SHA-256 (FIPS 180-4, section 4.2.2).
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Calcul incrémental de SHA-256 (FIPS 180-4).
+struct Sha256 {
+    /// État courant (valeurs de hachage intermédiaires).
+    state: [u32; 8],
+    /// Octets en attente d'un bloc complet de 64 octets.
+    pending: Vec<u8>,
+    /// Nombre total d'octets traités.
+    length: u64,
+}
+
+impl Sha256 {
+    fn new() -> Self {
+        Self {
+            state: [
+                0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+                0x5be0cd19,
+            ],
+            pending: Vec::with_capacity(64),
+            length: 0,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+
+        // Compléter le bloc en attente
+        if !self.pending.is_empty() {
+            let missing = (64 - self.pending.len()).min(data.len());
+            self.pending.extend_from_slice(&data[..missing]);
+            data = &data[missing..];
+            if self.pending.len() < 64 {
+                return;
+            }
+            let block = std::mem::take(&mut self.pending);
+            self.compress(&block);
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            self.compress(block);
+        }
+        self.pending.extend_from_slice(blocks.remainder());
+    }
+
+    fn finalize_hex(mut self) -> String {
+        let bit_length = self.length.wrapping_mul(8);
+
+        // Remplissage : 0x80, des zéros, puis la longueur en bits sur 64 bits
+        let mut padding = vec![0x80u8];
+        let used = (self.pending.len() + 1) % 64;
+        let zeros = if used <= 56 { 56 - used } else { 120 - used };
+        padding.extend(std::iter::repeat_n(0u8, zeros));
+        padding.extend_from_slice(&bit_length.to_be_bytes());
+        // `update` ajouterait le remplissage à la longueur : traiter les blocs directement
+        let mut tail = std::mem::take(&mut self.pending);
+        tail.extend_from_slice(&padding);
+        for block in tail.chunks_exact(64) {
+            self.compress(block);
+        }
+
+        self.state
+            .iter()
+            .map(|word| format!("{:08x}", word))
+            .collect()
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let mut w = [0u32; 64];
+        for (i, bytes) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let temp1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA256_K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    #[test]
+    fn test_sha256_known_vectors() {
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // Deux blocs après remplissage
+        assert_eq!(
+            sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn test_sha256_incremental_matches_one_shot() {
+        let data = vec![b'a'; 1000];
+        let mut hasher = Sha256::new();
+        for chunk in data.chunks(7) {
+            hasher.update(chunk);
+        }
+
+        let expected = "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3";
+        assert_eq!(hasher.finalize_hex(), expected);
+        assert_eq!(sha256_hex(&data), expected);
+    }
+
+    #[test]
+    fn test_sha256_file_spans_several_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("donnees.bin");
+        // 76 800 octets : plus d'un bloc de lecture de 64 Kio
+        let data: Vec<u8> = (0..300).flat_map(|_| 0..=255u8).collect();
+        std::fs::write(&path, &data).unwrap();
+
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "f8b0585eb91f58c007a5634362c9f90d8543822c113f702523bc7b73408a9392"
+        );
+        assert!(sha256_file(&dir.path().join("absent.bin")).is_err());
+    }
+
+    #[test]
+    fn test_metadata_contains_all_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("page.png");
+        GrayImage::from_pixel(12, 8, Luma([255]))
+            .save(&path)
+            .unwrap();
+        let config = OcrConfig::default();
+        let preprocessing = PreprocessingConfig::default();
+
+        let metadata = RunMetadataBuilder::new(&path, &config)
+            .preprocessing(Some(&preprocessing))
+            .build();
+
+        assert_eq!(metadata.len(), RUN_METADATA_KEYS.len());
+        for key in RUN_METADATA_KEYS {
+            assert!(metadata.contains_key(key), "clé manquante : {}", key);
+        }
+        assert_eq!(metadata["image_sha256"], sha256_file(&path).unwrap());
+        assert_eq!(
+            metadata["image_size_bytes"],
+            std::fs::metadata(&path).unwrap().len().to_string()
+        );
+        assert_eq!(metadata["image_width"], "12");
+        assert_eq!(metadata["image_height"], "8");
+        assert_eq!(metadata["crate_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(metadata["language"], "fra");
+        assert_eq!(metadata["psm"], "3");
+        assert_eq!(metadata["dpi"], "300");
+        assert_eq!(metadata["tesseract_variables"], "user_defined_dpi=300");
+        assert_eq!(metadata["preprocessing_hash"].len(), 64);
+    }
+
+    #[test]
+    fn test_metadata_hashing_can_be_disabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("page.png");
+        GrayImage::from_pixel(4, 4, Luma([0])).save(&path).unwrap();
+        let config = OcrConfig::default();
+
+        let metadata = RunMetadataBuilder::new(&path, &config)
+            .hash_image(false)
+            .build();
+
+        assert_eq!(metadata["image_sha256"], "");
+        assert_eq!(metadata["image_width"], "4");
+        assert_eq!(metadata["preprocessing_hash"], "");
+    }
+
+    #[test]
+    fn test_parse_tesseract_version() {
+        assert_eq!(
+            parse_tesseract_version("tesseract 5.3.4\n leptonica-1.84.1\n"),
+            Some("5.3.4".to_string())
+        );
+        assert_eq!(
+            parse_tesseract_version("tesseract v5.0.0-alpha\n"),
+            Some("5.0.0-alpha".to_string())
+        );
+        assert_eq!(parse_tesseract_version("inconnu"), None);
+        assert_eq!(parse_tesseract_version(""), None);
+    }
+}
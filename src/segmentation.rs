@@ -0,0 +1,956 @@
+//! Classification des régions d'une page : texte, image ou indéterminée.
+//!
+//! Une photo ou un logo lu par Tesseract produit des mots sans signification,
+//! souvent avec une confiance moyenne qui les laisse passer les filtres. Ce
+//! module distingue le texte des images à partir de mesures peu coûteuses
+//! sur chaque région ([`RegionFeatures`]) :
+//!
+//! - la proportion de pixels d'encre après binarisation d'Otsu ;
+//! - le nombre et la taille des composantes connexes : le texte forme de
+//!   nombreuses petites composantes (les lettres), une photo quelques grandes
+//!   plages ;
+//! - la régularité de l'épaisseur des traits, estimée pour chaque pixel
+//!   d'encre par la plus courte de ses deux traversées (horizontale et
+//!   verticale) jusqu'au fond : les traits d'une police ont une épaisseur
+//!   presque constante ;
+//! - la densité de contours, très élevée dans une zone bruitée ou texturée.
+//!
+//! [`classify_regions()`] classe des rectangles d'une page ;
+//! [`drop_words_in_regions()`] retire d'un document HOCR les mots situés
+//! dans les régions classées [`RegionClass::Image`].
+//!
+//! Le module repère aussi les blocs inclinés par rapport au reste de la
+//! page, comme un ticket agrafé de travers sur une feuille A4 :
+//! [`detect_skewed_components()`] estime l'angle de chaque bloc et
+//! [`split_skewed_regions()`] les sépare du fond de page, redressés, pour
+//! qu'ils soient lus à part.
+//!
+//! [`detect_text_regions()`] regroupe l'encre en blocs de texte (mots,
+//! lignes, paragraphes), hors images : leur nombre et leur forme servent
+//! à recommander un mode de segmentation ([`crate::layout`]).
+//!
+//! # Exemple
+//!
+//! ```
+//! use image::{GrayImage, Luma};
+//! use text_recognition::hocr::BBox;
+//! use text_recognition::segmentation::{RegionClass, classify_regions};
+//!
+//! // Dégradé doux, comme le ciel d'une photo
+//! let page = GrayImage::from_fn(200, 100, |x, y| Luma([(x + y) as u8]));
+//!
+//! let classes = classify_regions(&page, &[BBox::new(0, 0, 200, 100)]);
+//! assert_eq!(classes, [RegionClass::Image]);
+//! ```
+
+use crate::hocr::{BBox, HocrDocument, HocrPage};
+use crate::preprocessing::{
+    Connectivity, connected_components, otsu_threshold, rotate_image, skew_search,
+};
+use image::imageops;
+use image::{GrayImage, Luma};
+use serde::Serialize;
+
+/// Fraction minimale de la surface d'un mot dans une région image pour que
+/// [`drop_words_in_regions()`] le retire (par défaut).
+pub const DEFAULT_IMAGE_OVERLAP: f32 = 0.5;
+
+/// Plus petite largeur ou hauteur, en pixels, d'une région classable.
+const MIN_REGION_SIZE: u32 = 8;
+
+/// Écart minimal entre le pixel le plus clair et le plus sombre d'une région
+/// pour qu'elle contienne de l'encre.
+const MIN_CONTRAST: u8 = 40;
+
+/// Variation d'intensité entre deux pixels voisins comptée comme un contour.
+const EDGE_THRESHOLD: u32 = 48;
+
+/// Inclinaison minimale, en degrés, d'un bloc lu à part par [`split_skewed_regions()`].
+pub const MIN_REGION_SKEW: f64 = 2.0;
+
+/// Plus grande inclinaison recherchée dans un bloc, en degrés.
+const MAX_REGION_SKEW: f64 = 45.0;
+
+/// Pas de la recherche grossière de l'inclinaison d'un bloc, en degrés.
+const COARSE_SKEW_STEP: f64 = 2.0;
+
+/// Pas de la recherche fine autour de l'angle grossier, en degrés.
+const FINE_SKEW_STEP: f64 = 0.5;
+
+/// Surface minimale d'un bloc, en fraction de la surface de la page.
+const MIN_BLOCK_AREA_RATIO: f32 = 0.01;
+
+/// Surface maximale d'un bloc : au-delà, c'est la page entière.
+const MAX_BLOCK_AREA_RATIO: f32 = 0.9;
+
+/// Plus petite largeur ou hauteur d'un bloc, en pixels.
+const MIN_BLOCK_SIZE: u32 = 48;
+
+/// Nature d'une région de page, retournée par [`classify_regions()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionClass {
+    /// Texte imprimé : à lire.
+    Text,
+    /// Photo, logo, dessin ou bruit : à ne pas lire.
+    Image,
+    /// Région vide, trop petite ou ambiguë.
+    Unknown,
+}
+
+/// Mesures d'une région utilisées par [`classify_features()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionFeatures {
+    /// Proportion de pixels d'encre (la classe minoritaire après binarisation d'Otsu).
+    pub foreground_ratio: f32,
+    /// Nombre de composantes connexes de l'encre (8-connexité).
+    pub component_count: usize,
+    /// Surface de la plus grande composante, en fraction de la surface de la région.
+    pub largest_component_ratio: f32,
+    /// Surface médiane des composantes, en fraction de la surface de la région.
+    pub median_component_ratio: f32,
+    /// Épaisseur moyenne des traits, en pixels.
+    pub stroke_width_mean: f32,
+    /// Coefficient de variation (écart-type / moyenne) de l'épaisseur des traits.
+    pub stroke_width_variation: f32,
+    /// Proportion de pixels sur un contour (forte variation avec un voisin).
+    pub edge_density: f32,
+}
+
+/// Calcule les mesures d'une image entière (une région déjà découpée).
+///
+/// # Arguments
+///
+/// * `image` - Région en niveaux de gris
+///
+/// # Retour
+///
+/// `None` si la région est trop petite (moins de 8 pixels de côté) ou sans
+/// contraste (aucune encre à mesurer).
+pub fn region_features(image: &GrayImage) -> Option<RegionFeatures> {
+    let (width, height) = image.dimensions();
+    if width < MIN_REGION_SIZE || height < MIN_REGION_SIZE {
+        return None;
+    }
+    let (min, max) = image.pixels().fold((u8::MAX, u8::MIN), |(min, max), p| {
+        (min.min(p[0]), max.max(p[0]))
+    });
+    if max - min < MIN_CONTRAST {
+        return None;
+    }
+
+    // L'encre est la classe minoritaire : texte clair sur fond sombre compris
+    let threshold = otsu_threshold(image);
+    let dark = image.pixels().filter(|p| p[0] <= threshold).count();
+    let area = (width * height) as usize;
+    let ink_is_dark = dark * 2 <= area;
+    let ink: Vec<bool> = image
+        .pixels()
+        .map(|p| (p[0] <= threshold) == ink_is_dark)
+        .collect();
+    let ink_count = ink.iter().filter(|&&is_ink| is_ink).count();
+    if ink_count == 0 {
+        return None;
+    }
+
+    let binary = GrayImage::from_fn(width, height, |x, y| {
+        Luma([if ink[(y * width + x) as usize] {
+            0
+        } else {
+            255
+        }])
+    });
+    let mut areas: Vec<u32> = connected_components(&binary, Connectivity::Eight)
+        .components
+        .iter()
+        .map(|component| component.area)
+        .collect();
+    areas.sort_unstable();
+    let largest = areas.last().copied().unwrap_or(0);
+    let median = areas.get(areas.len() / 2).copied().unwrap_or(0);
+
+    let (stroke_width_mean, stroke_width_variation) = stroke_width_stats(&ink, width, height);
+
+    Some(RegionFeatures {
+        foreground_ratio: ink_count as f32 / area as f32,
+        component_count: areas.len(),
+        largest_component_ratio: largest as f32 / area as f32,
+        median_component_ratio: median as f32 / area as f32,
+        stroke_width_mean,
+        stroke_width_variation,
+        edge_density: edge_density(image),
+    })
+}
+
+/// Classe une région à partir de ses mesures.
+///
+/// Une région est une [`RegionClass::Image`] si une seule composante en
+/// couvre une large part (aplat, dégradé, logo plein), si près de la moitié
+/// de la région est de l'encre ou si les contours y sont omniprésents
+/// (bruit, texture). Elle est du [`RegionClass::Text`] si l'encre forme
+/// plusieurs petites composantes aux traits d'épaisseur régulière, et
+/// [`RegionClass::Unknown`] sinon.
+///
+/// # Arguments
+///
+/// * `features` - Mesures calculées par [`region_features()`]
+pub fn classify_features(features: &RegionFeatures) -> RegionClass {
+    if features.largest_component_ratio > 0.2
+        || features.foreground_ratio > 0.4
+        || features.edge_density > 0.5
+    {
+        return RegionClass::Image;
+    }
+    if features.component_count >= 2
+        && features.median_component_ratio < 0.05
+        && features.stroke_width_variation < 0.8
+    {
+        return RegionClass::Text;
+    }
+    RegionClass::Unknown
+}
+
+/// Classe une image entière (une région déjà découpée).
+///
+/// # Arguments
+///
+/// * `image` - Région en niveaux de gris
+pub fn classify_region(image: &GrayImage) -> RegionClass {
+    region_features(image).map_or(RegionClass::Unknown, |features| {
+        classify_features(&features)
+    })
+}
+
+/// Classe des régions d'une page.
+///
+/// Chaque rectangle est limité aux bords de l'image ; un rectangle
+/// entièrement hors de l'image est [`RegionClass::Unknown`].
+///
+/// # Arguments
+///
+/// * `image` - Page en niveaux de gris
+/// * `regions` - Rectangles à classer, en pixels de `image`
+///
+/// # Retour
+///
+/// Une classe par région, dans l'ordre de `regions`.
+pub fn classify_regions(image: &GrayImage, regions: &[BBox]) -> Vec<RegionClass> {
+    regions
+        .iter()
+        .map(|region| {
+            let x = region.x.min(image.width());
+            let y = region.y.min(image.height());
+            let width = region.width.min(image.width() - x);
+            let height = region.height.min(image.height() - y);
+            classify_region(&imageops::crop_imm(image, x, y, width, height).to_image())
+        })
+        .collect()
+}
+
+/// Retire d'un document HOCR les mots situés dans des régions image.
+///
+/// Un mot est retiré si au moins `min_overlap` de sa surface est couverte
+/// par l'une des régions. Les lignes et paragraphes vidés par le filtre sont
+/// supprimés.
+///
+/// # Arguments
+///
+/// * `doc` - Document HOCR à filtrer
+/// * `image_regions` - Régions classées [`RegionClass::Image`]
+/// * `min_overlap` - Fraction de la surface d'un mot (voir [`DEFAULT_IMAGE_OVERLAP`])
+///
+/// # Retour
+///
+/// Le nombre de mots retirés.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::hocr::{BBox, HocrDocument, HocrLine, HocrParagraph, HocrWord};
+/// use text_recognition::segmentation::{DEFAULT_IMAGE_OVERLAP, drop_words_in_regions};
+///
+/// let mut line = HocrLine::new(BBox::new(0, 0, 400, 30));
+/// line.add_word(HocrWord::new(BBox::new(0, 0, 100, 30), "Facture".into(), Some(95)));
+/// line.add_word(HocrWord::new(BBox::new(300, 0, 60, 30), "#@%".into(), Some(48)));
+/// let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 400, 30));
+/// paragraph.add_line(line);
+/// let mut doc = HocrDocument::new();
+/// doc.add_paragraph(paragraph);
+///
+/// // Logo à droite de la ligne
+/// let logo = BBox::new(280, 0, 120, 40);
+/// assert_eq!(drop_words_in_regions(&mut doc, &[logo], DEFAULT_IMAGE_OVERLAP), 1);
+/// assert_eq!(doc.full_text(), "Facture");
+/// ```
+pub fn drop_words_in_regions(
+    doc: &mut HocrDocument,
+    image_regions: &[BBox],
+    min_overlap: f32,
+) -> usize {
+    doc.pages
+        .iter_mut()
+        .map(|page| drop_page_words(page, image_regions, min_overlap))
+        .sum()
+}
+
+/// Retire d'un document HOCR les mots lus dans des illustrations de la page.
+///
+/// Chaque paragraphe du document est classé sur `image` par
+/// [`classify_regions()`] ; les mots couverts par un paragraphe classé
+/// [`RegionClass::Image`] sont retirés par [`drop_words_in_regions()`].
+/// Les pages dont les dimensions connues diffèrent de celles de `image`
+/// (document HOCR d'une autre résolution) sont laissées intactes.
+///
+/// # Arguments
+///
+/// * `doc` - Document HOCR produit à partir de `image`
+/// * `image` - Page en niveaux de gris
+/// * `min_overlap` - Fraction de la surface d'un mot (voir [`DEFAULT_IMAGE_OVERLAP`])
+///
+/// # Retour
+///
+/// Le nombre de mots retirés.
+pub fn drop_words_in_image_regions(
+    doc: &mut HocrDocument,
+    image: &GrayImage,
+    min_overlap: f32,
+) -> usize {
+    let mut removed = 0;
+    for page in &mut doc.pages {
+        let size = (page.bbox.width, page.bbox.height);
+        if size != (0, 0) && size != image.dimensions() {
+            continue;
+        }
+        let bboxes: Vec<BBox> = page
+            .paragraphs
+            .iter()
+            .map(|paragraph| paragraph.bbox.clone())
+            .collect();
+        let image_regions: Vec<BBox> = classify_regions(image, &bboxes)
+            .into_iter()
+            .zip(bboxes)
+            .filter(|(class, _)| *class == RegionClass::Image)
+            .map(|(_, bbox)| bbox)
+            .collect();
+        removed += drop_page_words(page, &image_regions, min_overlap);
+    }
+    removed
+}
+
+/// Retire les mots d'une page couverts par l'une des régions (voir [`drop_words_in_regions()`]).
+fn drop_page_words(page: &mut HocrPage, regions: &[BBox], min_overlap: f32) -> usize {
+    if regions.is_empty() {
+        return 0;
+    }
+    let inside = |word: &BBox| {
+        let area = u64::from(word.width) * u64::from(word.height);
+        area > 0
+            && regions.iter().any(|region| {
+                overlap_area(word, region) as f64 >= f64::from(min_overlap) * area as f64
+            })
+    };
+
+    let mut removed = 0;
+    page.paragraphs.retain_mut(|paragraph| {
+        let had_lines = !paragraph.lines.is_empty();
+        paragraph.lines.retain_mut(|line| {
+            let count = line.words.len();
+            line.words.retain(|word| !inside(&word.bbox));
+            removed += count - line.words.len();
+            count == 0 || !line.words.is_empty()
+        });
+        !had_lines || !paragraph.lines.is_empty()
+    });
+    removed
+}
+
+/// Bloc de la page et son inclinaison, détecté par [`detect_skewed_components()`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkewedRegion {
+    /// Rectangle englobant du bloc, en pixels de la page.
+    pub bbox: BBox,
+    /// Angle de correction en degrés, entre -45 et 45 : faire pivoter le
+    /// bloc de cet angle (positif = sens horaire) redresse ses lignes.
+    pub angle: f64,
+    /// Netteté de l'estimation entre 0 et 1 : écart entre la variance de
+    /// projection à l'angle retenu et la moyenne des angles testés.
+    pub confidence: f32,
+}
+
+/// Détecte les blocs de contenu d'une page et estime l'inclinaison de chacun.
+///
+/// L'encre (binarisation d'Otsu) est dilatée pour que les lettres et les
+/// lignes d'un même bloc se rejoignent ; les composantes connexes de
+/// l'image dilatée de taille plausible pour un document (au moins 1 % et au
+/// plus 90 % de la page, 48 pixels de côté au minimum) sont retenues.
+/// L'inclinaison de chaque bloc est estimée sur son découpage par la même
+/// méthode que le redressement (variance de projection horizontale), sur
+/// ±45° au lieu de ±20°.
+///
+/// # Arguments
+///
+/// * `image` - Page en niveaux de gris
+///
+/// # Retour
+///
+/// Les blocs, du haut de la page vers le bas puis de gauche à droite (y puis
+/// x du coin supérieur gauche), y compris ceux qui ne sont pas inclinés.
+pub fn detect_skewed_components(image: &GrayImage) -> Vec<SkewedRegion> {
+    let (width, height) = image.dimensions();
+    if width < MIN_BLOCK_SIZE || height < MIN_BLOCK_SIZE {
+        return Vec::new();
+    }
+    let threshold = otsu_threshold(image);
+    let ink: Vec<bool> = image.pixels().map(|p| p[0] <= threshold).collect();
+    // Les interlignes d'un document sont de l'ordre du centième de la page
+    let radius = (width.min(height) / 80).max(4) as usize;
+    let dilated = dilate(&ink, width as usize, height as usize, radius);
+    let binary = mask_image(&dilated, width, height);
+
+    let page_area = (width * height) as f32;
+    let mut regions: Vec<SkewedRegion> = connected_components(&binary, Connectivity::Eight)
+        .components
+        .into_iter()
+        .filter(|component| {
+            let bbox = &component.bbox;
+            let ratio = (bbox.width * bbox.height) as f32 / page_area;
+            bbox.width >= MIN_BLOCK_SIZE
+                && bbox.height >= MIN_BLOCK_SIZE
+                && (MIN_BLOCK_AREA_RATIO..=MAX_BLOCK_AREA_RATIO).contains(&ratio)
+        })
+        .map(|component| {
+            let crop = crop(image, &component.bbox);
+            let (angle, confidence) = estimate_skew(&crop);
+            SkewedRegion {
+                bbox: component.bbox,
+                angle,
+                confidence,
+            }
+        })
+        .collect();
+    regions.sort_by_key(|region| (region.bbox.y, region.bbox.x));
+    regions
+}
+
+/// Bloc incliné découpé et redressé par [`split_skewed_regions()`].
+#[derive(Debug, Clone)]
+pub struct StraightenedRegion {
+    /// Bloc détecté.
+    pub region: SkewedRegion,
+    /// Découpage du bloc pivoté de [`SkewedRegion::angle`].
+    pub image: GrayImage,
+}
+
+/// Sépare une page composite en fond de page et blocs inclinés redressés.
+///
+/// Les blocs de [`detect_skewed_components()`] inclinés d'au moins
+/// [`MIN_REGION_SKEW`] degrés sont découpés, pivotés pour redresser leurs
+/// lignes, puis effacés (en blanc) du fond de page, qui garde le reste du
+/// contenu. Le rectangle entier d'un bloc est effacé, y compris ce qu'il
+/// recouvre du fond.
+///
+/// # Arguments
+///
+/// * `image` - Page en niveaux de gris
+///
+/// # Retour
+///
+/// Le fond de page et les blocs redressés, dans l'ordre de
+/// [`detect_skewed_components()`]. Le fond de page est une copie de
+/// `image` si aucun bloc n'est incliné.
+pub fn split_skewed_regions(image: &GrayImage) -> (GrayImage, Vec<StraightenedRegion>) {
+    let mut background = image.clone();
+    let regions: Vec<StraightenedRegion> = detect_skewed_components(image)
+        .into_iter()
+        .filter(|region| region.angle.abs() >= MIN_REGION_SKEW)
+        .map(|region| {
+            let straightened = rotate_image(&crop(image, &region.bbox), region.angle);
+            imageops::replace(
+                &mut background,
+                &GrayImage::from_pixel(region.bbox.width, region.bbox.height, Luma([255])),
+                i64::from(region.bbox.x),
+                i64::from(region.bbox.y),
+            );
+            StraightenedRegion {
+                region,
+                image: straightened,
+            }
+        })
+        .collect();
+    (background, regions)
+}
+
+/// Détecte les blocs de texte d'une page.
+///
+/// L'encre (binarisation d'Otsu) est dilatée d'un rayon égal à la hauteur
+/// médiane des composantes connexes, soit à peu près celle d'une lettre :
+/// les lettres d'un mot, les mots d'une ligne et les lignes d'un
+/// paragraphe se rejoignent, alors que des étiquettes éloignées ou deux
+/// colonnes séparées par une large gouttière restent distinctes. Les blocs
+/// classés [`RegionClass::Image`] par [`classify_regions()`] (sur leur
+/// rectangle dilaté) sont écartés.
+///
+/// # Arguments
+///
+/// * `image` - Page en niveaux de gris
+///
+/// # Retour
+///
+/// Le rectangle englobant l'encre de chaque bloc, du haut de la page vers
+/// le bas puis de gauche à droite ; vide pour une page blanche ou sans
+/// contraste.
+///
+/// # Exemple
+///
+/// ```
+/// use image::{GrayImage, Luma};
+/// use text_recognition::segmentation::detect_text_regions;
+///
+/// // Deux « mots » de traits verticaux, très éloignés l'un de l'autre
+/// let page = GrayImage::from_fn(400, 100, |x, y| {
+///     let in_word = (20..80).contains(&x) || (300..360).contains(&x);
+///     if in_word && (40..60).contains(&y) && x % 6 < 2 { Luma([0]) } else { Luma([255]) }
+/// });
+///
+/// let regions = detect_text_regions(&page);
+/// assert_eq!(regions.len(), 2);
+/// assert_eq!((regions[0].x, regions[0].y, regions[0].height), (24, 40, 20));
+/// ```
+pub fn detect_text_regions(image: &GrayImage) -> Vec<BBox> {
+    let (width, height) = image.dimensions();
+    if width < MIN_REGION_SIZE || height < MIN_REGION_SIZE {
+        return Vec::new();
+    }
+    let (min, max) = image.pixels().fold((u8::MAX, u8::MIN), |(min, max), p| {
+        (min.min(p[0]), max.max(p[0]))
+    });
+    if max - min < MIN_CONTRAST {
+        return Vec::new();
+    }
+
+    let threshold = otsu_threshold(image);
+    let ink: Vec<bool> = image.pixels().map(|p| p[0] <= threshold).collect();
+    let binary = mask_image(&ink, width, height);
+    let mut heights: Vec<u32> = connected_components(&binary, Connectivity::Eight)
+        .components
+        .iter()
+        .filter(|component| component.area > 1)
+        .map(|component| component.bbox.height)
+        .collect();
+    if heights.is_empty() {
+        return Vec::new();
+    }
+    heights.sort_unstable();
+    let radius = heights[heights.len() / 2].max(2) as usize;
+
+    let dilated = dilate(&ink, width as usize, height as usize, radius);
+    let blocks = connected_components(&mask_image(&dilated, width, height), Connectivity::Eight);
+    // Rectangle de l'encre d'origine de chaque bloc (le bloc dilaté déborde du rayon)
+    let mut extents: Vec<Option<(u32, u32, u32, u32)>> = vec![None; blocks.components.len()];
+    for (index, _) in ink.iter().enumerate().filter(|(_, is_ink)| **is_ink) {
+        let (x, y) = (index as u32 % width, index as u32 / width);
+        let label = blocks.labels.get_pixel(x, y)[0] as usize;
+        let extent = &mut extents[label - 1];
+        *extent = Some(match *extent {
+            Some((left, top, right, bottom)) => {
+                (left.min(x), top.min(y), right.max(x), bottom.max(y))
+            }
+            None => (x, y, x, y),
+        });
+    }
+
+    let dilated_boxes: Vec<BBox> = blocks
+        .components
+        .iter()
+        .map(|component| component.bbox.clone())
+        .collect();
+    let mut regions: Vec<BBox> = classify_regions(image, &dilated_boxes)
+        .into_iter()
+        .zip(extents)
+        .filter(|(class, _)| *class != RegionClass::Image)
+        .filter_map(|(_, extent)| extent)
+        .map(|(left, top, right, bottom)| BBox::new(left, top, right - left + 1, bottom - top + 1))
+        .collect();
+    regions.sort_by_key(|region| (region.y, region.x));
+    regions
+}
+
+/// Image binaire (encre noire sur fond blanc) d'un masque d'encre.
+fn mask_image(mask: &[bool], width: u32, height: u32) -> GrayImage {
+    GrayImage::from_fn(width, height, |x, y| {
+        Luma([if mask[(y * width + x) as usize] {
+            0
+        } else {
+            255
+        }])
+    })
+}
+
+/// Estime l'angle de correction d'un bloc : recherche grossière sur ±45°, puis fine autour.
+fn estimate_skew(block: &GrayImage) -> (f64, f32) {
+    let coarse = skew_search(block, -MAX_REGION_SKEW, MAX_REGION_SKEW, COARSE_SKEW_STEP);
+    let fine = skew_search(
+        block,
+        (coarse.angle - COARSE_SKEW_STEP).max(-MAX_REGION_SKEW),
+        (coarse.angle + COARSE_SKEW_STEP).min(MAX_REGION_SKEW),
+        FINE_SKEW_STEP,
+    );
+    let confidence = if fine.best_variance > 0.0 {
+        (1.0 - coarse.mean_variance / fine.best_variance).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (fine.angle, confidence as f32)
+}
+
+/// Découpe un rectangle (supposé dans l'image).
+fn crop(image: &GrayImage, bbox: &BBox) -> GrayImage {
+    imageops::crop_imm(image, bbox.x, bbox.y, bbox.width, bbox.height).to_image()
+}
+
+/// Dilate un masque par un carré de côté `2 × radius + 1` (deux passes séparables).
+fn dilate(mask: &[bool], width: usize, height: usize, radius: usize) -> Vec<bool> {
+    let horizontal = dilate_lines(mask, width, height, radius, |row, column| {
+        row * width + column
+    });
+    dilate_lines(&horizontal, height, width, radius, |column, row| {
+        row * width + column
+    })
+}
+
+/// Dilate chaque ligne d'un masque ; `index(ligne, position)` donne l'indice d'un pixel.
+fn dilate_lines(
+    mask: &[bool],
+    length: usize,
+    lines: usize,
+    radius: usize,
+    index: impl Fn(usize, usize) -> usize,
+) -> Vec<bool> {
+    let mut output = vec![false; mask.len()];
+    for line in 0..lines {
+        // Position du dernier pixel d'encre vu, pour savoir s'il est à moins de `radius`
+        let mut last_ink: Option<usize> = None;
+        for position in 0..length + radius {
+            if position < length && mask[index(line, position)] {
+                last_ink = Some(position);
+            }
+            if position >= radius {
+                let target = position - radius;
+                if last_ink.is_some_and(|ink| ink + 2 * radius >= position) {
+                    output[index(line, target)] = true;
+                }
+            }
+        }
+    }
+    output
+}
+
+/// Surface de l'intersection de deux rectangles, en pixels.
+fn overlap_area(a: &BBox, b: &BBox) -> u64 {
+    let width = (a.x + a.width)
+        .min(b.x + b.width)
+        .saturating_sub(a.x.max(b.x));
+    let height = (a.y + a.height)
+        .min(b.y + b.height)
+        .saturating_sub(a.y.max(b.y));
+    u64::from(width) * u64::from(height)
+}
+
+/// Moyenne et coefficient de variation de l'épaisseur des traits.
+///
+/// L'épaisseur en un pixel d'encre est la plus courte de ses deux
+/// traversées, horizontale et verticale : la longueur de la suite de
+/// pixels d'encre qui le contient, jusqu'au fond de chaque côté.
+fn stroke_width_stats(ink: &[bool], width: u32, height: u32) -> (f32, f32) {
+    let (width, height) = (width as usize, height as usize);
+    let mut horizontal = vec![0u32; ink.len()];
+    let mut vertical = vec![0u32; ink.len()];
+    for y in 0..height {
+        fill_runs(ink, &mut horizontal, (0..width).map(|x| y * width + x));
+    }
+    for x in 0..width {
+        fill_runs(ink, &mut vertical, (0..height).map(|y| y * width + x));
+    }
+
+    let (mut sum, mut sum_squares, mut count) = (0f64, 0f64, 0f64);
+    for (index, _) in ink.iter().enumerate().filter(|(_, is_ink)| **is_ink) {
+        let stroke = f64::from(horizontal[index].min(vertical[index]));
+        sum += stroke;
+        sum_squares += stroke * stroke;
+        count += 1.0;
+    }
+    if count == 0.0 {
+        return (0.0, 0.0);
+    }
+    let mean = sum / count;
+    let variance = (sum_squares / count - mean * mean).max(0.0);
+    (mean as f32, (variance.sqrt() / mean) as f32)
+}
+
+/// Écrit dans `runs` la longueur de la suite d'encre contenant chaque pixel d'une ligne ou colonne.
+fn fill_runs(ink: &[bool], runs: &mut [u32], indices: impl Iterator<Item = usize>) {
+    let mut current: Vec<usize> = Vec::new();
+    for index in indices.chain(std::iter::once(usize::MAX)) {
+        if index != usize::MAX && ink[index] {
+            current.push(index);
+            continue;
+        }
+        for &pixel in &current {
+            runs[pixel] = current.len() as u32;
+        }
+        current.clear();
+    }
+}
+
+/// Proportion de pixels dont l'intensité diffère fortement de celle du voisin de droite ou du dessous.
+fn edge_density(image: &GrayImage) -> f32 {
+    let (width, height) = image.dimensions();
+    let mut edges = 0u64;
+    for y in 0..height - 1 {
+        for x in 0..width - 1 {
+            let value = i32::from(image.get_pixel(x, y)[0]);
+            let right = i32::from(image.get_pixel(x + 1, y)[0]);
+            let below = i32::from(image.get_pixel(x, y + 1)[0]);
+            if (value - right).unsigned_abs() + (value - below).unsigned_abs() > EDGE_THRESHOLD {
+                edges += 1;
+            }
+        }
+    }
+    edges as f32 / ((width - 1) * (height - 1)) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hocr::{HocrLine, HocrParagraph, HocrWord};
+    use crate::rng::SplitMix64;
+    use crate::testutil::{RenderOptions, render_text_image};
+
+    /// Bloc de texte rendu avec la police embarquée.
+    fn text_block() -> GrayImage {
+        render_text_image(
+            "Facture n. 2024-118\nTotal TTC : 12,50 EUR\nMerci de votre visite",
+            &RenderOptions {
+                scale: 2,
+                ..RenderOptions::default()
+            },
+        )
+        .to_luma8()
+    }
+
+    /// Bruit uniforme déterministe.
+    fn noise(width: u32, height: u32) -> GrayImage {
+        let mut rng = SplitMix64::new(0x2545_f491);
+        GrayImage::from_fn(width, height, |_, _| Luma([(rng.next_u64() >> 56) as u8]))
+    }
+
+    /// Dégradé radial avec une légère texture, comme une photo.
+    fn photo(width: u32, height: u32) -> GrayImage {
+        GrayImage::from_fn(width, height, |x, y| {
+            let dx = x as f32 - width as f32 / 2.0;
+            let dy = y as f32 - height as f32 / 3.0;
+            let shade = 230.0 - (dx * dx + dy * dy).sqrt() * 1.2 + ((x * 7 + y * 3) % 9) as f32;
+            Luma([shade.clamp(0.0, 255.0) as u8])
+        })
+    }
+
+    /// Page blanche avec un bloc de texte à gauche et une photo à droite.
+    fn page_with_text_and_photo() -> (GrayImage, BBox, BBox) {
+        let text = text_block();
+        let picture = photo(160, 120);
+        let mut page = GrayImage::from_pixel(text.width() + 220, 200, Luma([255]));
+        imageops::replace(&mut page, &text, 10, 20);
+        imageops::replace(&mut page, &picture, i64::from(text.width()) + 40, 40);
+        let text_region = BBox::new(10, 20, text.width(), text.height());
+        let photo_region = BBox::new(text.width() + 40, 40, 160, 120);
+        (page, text_region, photo_region)
+    }
+
+    fn word(x: u32, y: u32, text: &str) -> HocrWord {
+        HocrWord::new(BBox::new(x, y, 60, 20), text.to_string(), Some(90))
+    }
+
+    /// Page blanche avec un bloc de texte droit en haut à gauche et un ticket
+    /// incliné de 30° dans le coin inférieur droit.
+    fn composite_page() -> GrayImage {
+        let options = RenderOptions {
+            scale: 2,
+            ..RenderOptions::default()
+        };
+        let text = "RELEVE DE COMPTE 2024\nSolde au 31/01 : 1 254,18\nVirement recu loyer\nPrelevement EDF 0412";
+        let straight = render_text_image(text, &options).to_luma8();
+        let receipt = render_text_image(
+            "TICKET CAISSE 118\nPAIN 1,20\nCAFE 2,50\nTOTAL 3,70 EUR",
+            &RenderOptions {
+                rotation_degrees: 30.0,
+                ..options
+            },
+        )
+        .to_luma8();
+        let mut page = GrayImage::from_pixel(1000, 900, Luma([255]));
+        imageops::replace(&mut page, &straight, 20, 20);
+        imageops::replace(
+            &mut page,
+            &receipt,
+            i64::from(1000 - receipt.width() - 20),
+            i64::from(900 - receipt.height() - 20),
+        );
+        page
+    }
+
+    #[test]
+    fn test_detect_skewed_components() {
+        let regions = detect_skewed_components(&composite_page());
+
+        assert_eq!(regions.len(), 2, "{:?}", regions);
+        assert!(regions[0].angle.abs() <= 1.0, "{:?}", regions[0]);
+        // Ticket tourné de 30° dans le sens horaire : correction de -30°
+        assert!((regions[1].angle + 30.0).abs() <= 1.0, "{:?}", regions[1]);
+        assert!(regions.iter().all(|region| region.confidence > 0.3));
+        assert!(regions[1].bbox.x > 500 && regions[1].bbox.y > 500);
+    }
+
+    #[test]
+    fn test_split_skewed_regions_straightens_blocks() {
+        let page = composite_page();
+        let (background, blocks) = split_skewed_regions(&page);
+
+        assert_eq!(blocks.len(), 1);
+        let block = &blocks[0];
+        assert!(crate::preprocessing::detect_skew_angle(&block.image).abs() < 2.0);
+        // Le ticket est effacé du fond de page, le bloc droit y reste
+        let receipt = crop(&background, &block.region.bbox);
+        assert!(receipt.pixels().all(|p| p[0] == 255));
+        assert_eq!(
+            crop(&background, &BBox::new(0, 0, 500, 250)),
+            crop(&page, &BBox::new(0, 0, 500, 250))
+        );
+    }
+
+    #[test]
+    fn test_skewed_components_are_ordered_top_left_first() {
+        let page = composite_page();
+        // Même page retournée : le ticket passe en haut à gauche
+        let flipped = imageops::rotate180(&page);
+        let regions = detect_skewed_components(&flipped);
+
+        assert_eq!(regions.len(), 2);
+        assert!(regions[0].bbox.y < regions[1].bbox.y);
+        assert!(regions[0].angle.abs() > 25.0, "{:?}", regions);
+        assert_eq!(detect_skewed_components(&flipped), regions);
+    }
+
+    #[test]
+    fn test_detect_text_regions_skips_photo() {
+        let (page, text_region, _) = page_with_text_and_photo();
+        let regions = detect_text_regions(&page);
+
+        assert_eq!(regions.len(), 1, "{:?}", regions);
+        let region = &regions[0];
+        // Le bloc de texte (marges du rendu exclues) est dans son rectangle
+        assert!(region.x >= text_region.x && region.y >= text_region.y);
+        assert!(region.x + region.width <= text_region.x + text_region.width);
+        assert!(region.height > text_region.height / 2);
+        assert!(detect_text_regions(&GrayImage::from_pixel(100, 100, Luma([255]))).is_empty());
+    }
+
+    #[test]
+    fn test_rendered_text_is_text() {
+        let features = region_features(&text_block()).unwrap();
+        assert!(features.component_count > 20);
+        assert!(features.stroke_width_variation < 0.8);
+        assert_eq!(classify_features(&features), RegionClass::Text);
+    }
+
+    #[test]
+    fn test_noise_and_gradient_are_images() {
+        assert_eq!(classify_region(&noise(160, 120)), RegionClass::Image);
+        assert_eq!(classify_region(&photo(160, 120)), RegionClass::Image);
+        let gradient = GrayImage::from_fn(160, 120, |x, _| Luma([(x * 255 / 159) as u8]));
+        assert_eq!(classify_region(&gradient), RegionClass::Image);
+    }
+
+    #[test]
+    fn test_blank_or_tiny_region_is_unknown() {
+        let blank = GrayImage::from_pixel(100, 100, Luma([250]));
+        assert_eq!(region_features(&blank), None);
+        assert_eq!(classify_region(&blank), RegionClass::Unknown);
+        assert_eq!(classify_region(&noise(5, 40)), RegionClass::Unknown);
+    }
+
+    #[test]
+    fn test_classify_regions_of_a_page() {
+        let (page, text_region, photo_region) = page_with_text_and_photo();
+        let outside = BBox::new(page.width() + 10, 0, 50, 50);
+        assert_eq!(
+            classify_regions(&page, &[text_region, photo_region, outside]),
+            [RegionClass::Text, RegionClass::Image, RegionClass::Unknown]
+        );
+    }
+
+    #[test]
+    fn test_drop_words_in_image_regions() {
+        let photo_region = BBox::new(300, 0, 200, 200);
+        let mut text_line = HocrLine::new(BBox::new(0, 0, 400, 20));
+        text_line.add_word(word(0, 0, "Total"));
+        text_line.add_word(word(80, 0, "12,50"));
+        // Mot à cheval, majoritairement hors de la photo : conservé
+        text_line.add_word(word(260, 0, "EUR"));
+        let mut photo_line = HocrLine::new(BBox::new(320, 100, 150, 20));
+        photo_line.add_word(word(320, 100, "~#%"));
+        photo_line.add_word(word(400, 100, "lll"));
+        let mut paragraph = HocrParagraph::new(BBox::new(0, 0, 470, 120));
+        paragraph.add_line(text_line);
+        paragraph.add_line(photo_line);
+        let mut photo_paragraph = HocrParagraph::new(BBox::new(320, 150, 60, 20));
+        let mut caption = HocrLine::new(BBox::new(320, 150, 60, 20));
+        caption.add_word(word(320, 150, "|||"));
+        photo_paragraph.add_line(caption);
+        let mut doc = HocrDocument::new();
+        doc.add_paragraph(paragraph);
+        doc.add_paragraph(photo_paragraph);
+
+        assert_eq!(
+            drop_words_in_regions(&mut doc, &[photo_region], DEFAULT_IMAGE_OVERLAP),
+            3
+        );
+        assert_eq!(doc.full_text(), "Total 12,50 EUR");
+        assert_eq!(doc.pages[0].paragraphs.len(), 1);
+        assert_eq!(doc.pages[0].paragraphs[0].lines.len(), 1);
+        assert_eq!(
+            drop_words_in_regions(&mut doc, &[], DEFAULT_IMAGE_OVERLAP),
+            0
+        );
+    }
+
+    #[test]
+    fn test_drop_words_in_classified_paragraphs() {
+        let (page, text_region, photo_region) = page_with_text_and_photo();
+        let paragraph = |bbox: &BBox, text: &str| {
+            let mut line = HocrLine::new(bbox.clone());
+            line.add_word(HocrWord::new(bbox.clone(), text.to_string(), Some(60)));
+            let mut paragraph = HocrParagraph::new(bbox.clone());
+            paragraph.add_line(line);
+            paragraph
+        };
+        let mut hocr_page = HocrPage::new(BBox::new(0, 0, page.width(), page.height()), 0);
+        hocr_page.add_paragraph(paragraph(&text_region, "Facture"));
+        hocr_page.add_paragraph(paragraph(&photo_region, "~%#"));
+        let mut doc = HocrDocument::new();
+        doc.pages.push(hocr_page);
+
+        // Document d'une autre résolution : laissé intact
+        let mut other = doc.clone();
+        other.pages[0].bbox = BBox::new(0, 0, page.width() * 2, page.height() * 2);
+        assert_eq!(
+            drop_words_in_image_regions(&mut other, &page, DEFAULT_IMAGE_OVERLAP),
+            0
+        );
+
+        assert_eq!(
+            drop_words_in_image_regions(&mut doc, &page, DEFAULT_IMAGE_OVERLAP),
+            1
+        );
+        assert_eq!(doc.full_text(), "Facture");
+    }
+}
@@ -0,0 +1,975 @@
+//! Serveur HTTP d'OCR (feature `server`).
+//!
+//! Expose le moteur OCR sur le réseau sans service intermédiaire :
+//!
+//! - `POST /ocr` : image brute dans le corps (`Content-Type: image/*`),
+//!   réponse JSON `{"text", "duration_ms", "warnings"}` ;
+//! - `POST /hocr` : même requête, réponse hOCR brute ;
+//! - `GET /healthz` : vérifie que le binaire `tesseract` répond et s'initialise
+//!   avec la langue configurée.
+//!
+//! `/ocr` et `/hocr` acceptent les paramètres de requête `language`
+//! (ex: `eng+fra`), `psm` (code ou nom, voir [`PageSegMode`]) et
+//! `preprocess` (`true` ou `false`), qui surchargent la configuration du
+//! serveur pour cette seule requête.
+//!
+//! Le traitement des requêtes ([`OcrService`]) est indépendant de la couche
+//! HTTP ([`OcrServer`]) : il se teste avec de simples tampons d'octets, et
+//! l'OCR passe par un [`OcrBackend`] remplaçable. [`EngineBackend`] borne le
+//! nombre d'extractions simultanées par un [`OcrEnginePool`].
+//!
+//! # Erreurs
+//!
+//! Les erreurs sont des objets JSON `{"error", "kind"}` :
+//!
+//! | Statut | `kind` | Cause |
+//! |--------|--------|-------|
+//! | 400 | `bad_request` | Paramètre de requête invalide ou inconnu |
+//! | 404 | `not_found` | Route inconnue |
+//! | 405 | `method_not_allowed` | Méthode non supportée par la route |
+//! | 413 | `payload_too_large` | Corps plus grand que la limite configurée |
+//! | 415 | `unsupported_media_type` | `Content-Type` absent ou hors `image/*` |
+//! | 422 | `image_decode` | Corps vide ou image indécodable |
+//! | 500 | `ocr` | Échec de Tesseract ou du prétraitement |
+//! | 503 | `unavailable` | Tesseract introuvable (`/healthz`) |
+//! | 504 | `timeout` | Extraction plus longue que le délai du moteur |
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::ocr::OcrEngine;
+//! use text_recognition::server::{EngineBackend, OcrServer, OcrService};
+//!
+//! let engine = OcrEngine::new(OcrConfig::default())?;
+//! let service = OcrService::new(EngineBackend::new(engine, 4)?);
+//! let server = OcrServer::bind("127.0.0.1:8080", service)?;
+//! server.run();
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::config::PageSegMode;
+use crate::hocr::generate_hocr_with_options;
+use crate::ocr::{OcrEngine, OcrError, OcrOutcome, TesseractInvocation};
+use crate::pool::OcrEnginePool;
+use crate::preprocessing::{preprocess_image, save_with_dpi};
+use crate::run_metadata::tesseract_version;
+use anyhow::{Context, Result, anyhow, bail};
+use image::DynamicImage;
+use serde::Serialize;
+use std::io::Read;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Taille maximale par défaut du corps d'une requête : 20 Mio.
+pub const DEFAULT_MAX_BODY_BYTES: usize = 20 * 1024 * 1024;
+
+/// Type de contenu des réponses JSON.
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// Type de contenu des réponses hOCR (XHTML).
+const HOCR_CONTENT_TYPE: &str = "text/html; charset=utf-8";
+
+/// Surcharges de configuration d'une requête (paramètres de requête).
+///
+/// Un champ à `None` conserve la valeur configurée sur le serveur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RequestOptions<'a> {
+    /// Langue Tesseract (`language=eng+fra`).
+    pub language: Option<&'a str>,
+    /// Mode de segmentation (`psm=6` ou `psm=single-block`).
+    pub psm: Option<PageSegMode>,
+    /// Activer ou désactiver le prétraitement (`preprocess=true`).
+    pub preprocess: Option<bool>,
+}
+
+impl RequestOptions<'_> {
+    /// Indique si la requête ne surcharge aucun paramètre.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Paramètres de requête décodés, dont [`RequestOptions`] emprunte les valeurs.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct QueryParams {
+    language: Option<String>,
+    psm: Option<PageSegMode>,
+    preprocess: Option<bool>,
+}
+
+impl QueryParams {
+    /// Décode une chaîne de requête (`language=eng%2Bfra&psm=6`).
+    ///
+    /// Les valeurs sont décodées (`%XX`) ; `+` est conservé tel quel, pour
+    /// que `language=eng+fra` désigne deux langues.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Partie de l'URL après `?` (sans le `?`)
+    ///
+    /// # Exemple
+    ///
+    /// ```
+    /// use text_recognition::config::PageSegMode;
+    /// use text_recognition::server::QueryParams;
+    ///
+    /// let params = QueryParams::parse("language=eng+fra&psm=6&preprocess=true")?;
+    /// let options = params.options();
+    /// assert_eq!(options.language, Some("eng+fra"));
+    /// assert_eq!(options.psm, Some(PageSegMode::SingleBlock));
+    /// assert_eq!(options.preprocess, Some(true));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si un paramètre est inconnu, si une valeur est
+    /// mal encodée ou invalide (PSM inconnu, booléen illisible, langue vide).
+    pub fn parse(query: &str) -> Result<Self> {
+        let mut params = Self::default();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value)
+                .with_context(|| format!("Valeur du paramètre '{}' mal encodée", name))?;
+            match name {
+                "language" => {
+                    if value.trim().is_empty() {
+                        bail!("Le paramètre 'language' ne peut pas être vide");
+                    }
+                    params.language = Some(value);
+                }
+                "psm" => params.psm = Some(value.parse()?),
+                "preprocess" => params.preprocess = Some(parse_bool(&value)?),
+                _ => bail!(
+                    "Paramètre inconnu : '{}' (attendu : language, psm, preprocess)",
+                    name
+                ),
+            }
+        }
+        Ok(params)
+    }
+
+    /// Surcharges de configuration correspondantes.
+    pub fn options(&self) -> RequestOptions<'_> {
+        RequestOptions {
+            language: self.language.as_deref(),
+            psm: self.psm,
+            preprocess: self.preprocess,
+        }
+    }
+}
+
+/// Décode les séquences `%XX` d'une valeur de paramètre.
+fn percent_decode(value: &str) -> Result<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = bytes
+                .get(index + 1..index + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| anyhow!("séquence '%' invalide dans '{}'", value))?;
+            decoded.push(hex);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).with_context(|| format!("'{}' n'est pas de l'UTF-8", value))
+}
+
+/// Lit un booléen de paramètre de requête (`true`/`false`, `1`/`0`, `yes`/`no`).
+fn parse_bool(value: &str) -> Result<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" => Ok(true),
+        "false" | "0" | "no" => Ok(false),
+        _ => bail!(
+            "Valeur booléenne invalide : '{}' (attendu true ou false)",
+            value
+        ),
+    }
+}
+
+/// Moteur d'OCR utilisé par un [`OcrService`].
+///
+/// Implémenté par [`EngineBackend`]. Les tests peuvent fournir leur propre
+/// implémentation pour vérifier le service sans Tesseract.
+pub trait OcrBackend: Send + Sync {
+    /// Extrait le texte d'une image décodée.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image reçue
+    /// * `options` - Surcharges de configuration de la requête
+    fn extract_text(&self, image: &DynamicImage, options: &RequestOptions) -> Result<OcrOutcome>;
+
+    /// Génère le hOCR d'une image décodée.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image reçue
+    /// * `options` - Surcharges de configuration de la requête
+    fn extract_hocr(&self, image: &DynamicImage, options: &RequestOptions) -> Result<OcrOutcome>;
+
+    /// Vérifie que Tesseract est disponible et retourne sa version.
+    fn tesseract_version(&self) -> Result<String>;
+
+    /// Vérifie que Tesseract s'initialise avec la langue configurée.
+    ///
+    /// Ne vérifie rien par défaut : seul [`tesseract_version()`](Self::tesseract_version)
+    /// est alors consulté par `/healthz`.
+    fn health_check(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// [`OcrBackend`] s'appuyant sur un [`OcrEnginePool`].
+///
+/// Chaque requête emprunte un moteur du pool, ce qui borne le nombre
+/// d'instances Tesseract actives ; les requêtes suivantes attendent qu'un
+/// moteur se libère. Une requête avec surcharges utilise une copie du moteur
+/// modifiée en conséquence.
+pub struct EngineBackend {
+    pool: OcrEnginePool,
+    engine: OcrEngine,
+    timeout: Option<Duration>,
+}
+
+impl EngineBackend {
+    /// Crée le backend à partir d'un moteur configuré.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - Moteur servant de modèle (configuration, prétraitement)
+    /// * `max_concurrency` - Nombre maximal d'extractions simultanées
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si `max_concurrency` vaut 0.
+    pub fn new(engine: OcrEngine, max_concurrency: usize) -> Result<Self> {
+        Ok(Self {
+            pool: OcrEnginePool::from_engine(engine.clone(), max_concurrency)?,
+            engine,
+            timeout: None,
+        })
+    }
+
+    /// Crée et préchauffe chaque moteur du pool (voir [`OcrEnginePool::warm_up()`]).
+    ///
+    /// À appeler avant [`OcrServer::bind()`] : la première requête ne paie
+    /// plus l'initialisation de Tesseract et une installation défaillante
+    /// empêche le démarrage au lieu d'échouer sous le trafic.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le préchauffage d'un moteur échoue.
+    pub fn warm_up(&self) -> Result<()> {
+        self.pool.warm_up()
+    }
+
+    /// Fixe le délai maximal de chaque extraction (voir [`OcrEngine::with_timeout()`]).
+    ///
+    /// Le délai remplace celui du moteur modèle et s'applique aussi aux
+    /// moteurs construits pour une requête avec surcharges.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Nombre maximal d'extractions simultanées.
+    pub fn max_concurrency(&self) -> usize {
+        self.pool.max_workers()
+    }
+
+    /// Exécute `extract` avec un moteur du pool, adapté aux surcharges de la requête.
+    fn with_engine<T>(
+        &self,
+        options: &RequestOptions,
+        extract: impl FnOnce(&OcrEngine) -> Result<T>,
+    ) -> Result<T> {
+        let pooled = self.pool.get()?;
+        let base: &OcrEngine = &pooled;
+        if options.is_default() {
+            return match self.timeout {
+                Some(_) => extract(&base.clone().with_timeout(self.timeout)),
+                None => extract(base),
+            };
+        }
+
+        let mut config = base.config().clone();
+        if let Some(language) = options.language {
+            config.language = language.to_string();
+        }
+        if let Some(psm) = options.psm {
+            config.page_seg_mode = psm;
+        }
+        let preprocessing = match options.preprocess {
+            Some(true) => Some(base.preprocessing_config().cloned().unwrap_or_default()),
+            Some(false) => None,
+            None => base.preprocessing_config().cloned(),
+        };
+        let engine = match preprocessing {
+            Some(preprocessing) => OcrEngine::with_preprocessing(config, preprocessing)?,
+            None => OcrEngine::new(config)?,
+        };
+        extract(&engine.with_timeout(self.timeout))
+    }
+}
+
+impl OcrBackend for EngineBackend {
+    fn extract_text(&self, image: &DynamicImage, options: &RequestOptions) -> Result<OcrOutcome> {
+        self.with_engine(options, |engine| {
+            engine.extract_text_from_image_detailed(image)
+        })
+    }
+
+    fn extract_hocr(&self, image: &DynamicImage, options: &RequestOptions) -> Result<OcrOutcome> {
+        self.with_engine(options, |engine| {
+            let image = match engine.preprocessing_config() {
+                Some(preprocessing) => preprocess_image(image, preprocessing)
+                    .context("Échec du prétraitement de l'image")?,
+                None => image.clone(),
+            };
+            let config = engine.config();
+            let temp_dir =
+                tempfile::tempdir().context("Échec de création du répertoire temporaire")?;
+            let path = temp_dir.path().join("requete.png");
+            save_with_dpi(&image, &path, config.dpi)?;
+            let invocation = TesseractInvocation::new(config, &config.language, config.dpi);
+            generate_hocr_with_options(&path, &invocation, &[], self.timeout, config.thread_limit())
+        })
+    }
+
+    fn tesseract_version(&self) -> Result<String> {
+        tesseract_version().context("Binaire tesseract introuvable")
+    }
+
+    fn health_check(&self) -> Result<()> {
+        self.engine.health_check()
+    }
+}
+
+/// Réponse JSON de `POST /ocr`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OcrResponse {
+    /// Texte extrait.
+    pub text: String,
+    /// Durée de l'extraction, en millisecondes.
+    pub duration_ms: u64,
+    /// Avertissements émis pendant l'extraction.
+    pub warnings: Vec<String>,
+}
+
+/// Corps JSON d'une réponse d'erreur.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct ErrorBody<'a> {
+    /// Message d'erreur complet (avec ses causes).
+    error: String,
+    /// Catégorie de l'erreur (voir le tableau du module).
+    kind: &'a str,
+}
+
+/// Réponse d'un [`OcrService`], indépendante de la couche HTTP.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceResponse {
+    /// Code de statut HTTP.
+    pub status: u16,
+    /// Valeur de l'en-tête `Content-Type`.
+    pub content_type: &'static str,
+    /// Corps de la réponse.
+    pub body: Vec<u8>,
+}
+
+impl ServiceResponse {
+    /// Réponse JSON.
+    fn json(status: u16, value: &impl Serialize) -> Self {
+        match serde_json::to_vec(value) {
+            Ok(body) => Self {
+                status,
+                content_type: JSON_CONTENT_TYPE,
+                body,
+            },
+            Err(e) => Self::error(
+                500,
+                "internal",
+                &format!("Échec de la sérialisation JSON : {}", e),
+            ),
+        }
+    }
+
+    /// Réponse d'erreur `{"error", "kind"}`.
+    fn error(status: u16, kind: &str, message: &str) -> Self {
+        let body = ErrorBody {
+            error: message.to_string(),
+            kind,
+        };
+        Self {
+            status,
+            content_type: JSON_CONTENT_TYPE,
+            // La sérialisation d'une structure de chaînes ne peut pas échouer
+            body: serde_json::to_vec(&body).unwrap_or_default(),
+        }
+    }
+
+    /// Réponse d'erreur d'une extraction, classée selon sa cause.
+    fn extraction_error(error: &anyhow::Error) -> Self {
+        let message = format!("{:#}", error);
+        match error.downcast_ref::<OcrError>() {
+            Some(OcrError::Timeout(_)) => Self::error(504, "timeout", &message),
+            _ => Self::error(500, "ocr", &message),
+        }
+    }
+}
+
+/// Traitement des requêtes du serveur OCR.
+///
+/// Reçoit la méthode, l'URL, le type de contenu et le corps d'une requête
+/// et retourne une [`ServiceResponse`] ; la couche HTTP ([`OcrServer`]) ne
+/// fait que transporter ces valeurs.
+///
+/// # Exemple
+///
+/// ```no_run
+/// use text_recognition::config::OcrConfig;
+/// use text_recognition::ocr::OcrEngine;
+/// use text_recognition::server::{EngineBackend, OcrService};
+///
+/// let engine = OcrEngine::new(OcrConfig::default())?;
+/// let service = OcrService::new(EngineBackend::new(engine, 2)?);
+///
+/// let png = std::fs::read("page.png")?;
+/// let response = service.handle("POST", "/ocr?psm=6", Some("image/png"), &png);
+/// println!("{} {}", response.status, String::from_utf8_lossy(&response.body));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub struct OcrService<B: OcrBackend = EngineBackend> {
+    backend: B,
+    max_body_bytes: usize,
+}
+
+impl<B: OcrBackend> OcrService<B> {
+    /// Crée le service, avec une limite de corps de [`DEFAULT_MAX_BODY_BYTES`].
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - Moteur d'OCR des requêtes
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+        }
+    }
+
+    /// Fixe la taille maximale (en octets) du corps d'une requête.
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// Taille maximale (en octets) du corps d'une requête.
+    pub fn max_body_bytes(&self) -> usize {
+        self.max_body_bytes
+    }
+
+    /// Moteur d'OCR des requêtes.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Traite une requête.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - Méthode HTTP (`GET`, `POST`...)
+    /// * `url` - Chemin et chaîne de requête (ex: `/ocr?language=eng`)
+    /// * `content_type` - Valeur de l'en-tête `Content-Type`, si présent
+    /// * `body` - Corps de la requête
+    pub fn handle(
+        &self,
+        method: &str,
+        url: &str,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> ServiceResponse {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        match (path, method) {
+            ("/ocr", "POST") => self.handle_ocr(content_type, query, body),
+            ("/hocr", "POST") => self.handle_hocr(content_type, query, body),
+            ("/healthz", "GET") => self.handle_health(),
+            ("/ocr" | "/hocr" | "/healthz", _) => ServiceResponse::error(
+                405,
+                "method_not_allowed",
+                &format!("Méthode {} non supportée pour {}", method, path),
+            ),
+            _ => ServiceResponse::error(404, "not_found", &format!("Route inconnue : {}", path)),
+        }
+    }
+
+    /// `POST /ocr` : extrait le texte de l'image reçue.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - Valeur de l'en-tête `Content-Type` (doit être `image/*`)
+    /// * `query` - Chaîne de requête (voir [`QueryParams::parse()`])
+    /// * `body` - Contenu du fichier image
+    pub fn handle_ocr(
+        &self,
+        content_type: Option<&str>,
+        query: &str,
+        body: &[u8],
+    ) -> ServiceResponse {
+        self.with_image(content_type, query, body, |image, options| {
+            let start = Instant::now();
+            match self.backend.extract_text(image, options) {
+                Ok(outcome) => ServiceResponse::json(
+                    200,
+                    &OcrResponse {
+                        text: outcome.text,
+                        duration_ms: start.elapsed().as_millis() as u64,
+                        warnings: outcome.warnings.iter().map(ToString::to_string).collect(),
+                    },
+                ),
+                Err(e) => ServiceResponse::extraction_error(&e),
+            }
+        })
+    }
+
+    /// `POST /hocr` : génère le hOCR de l'image reçue.
+    ///
+    /// # Arguments
+    ///
+    /// * `content_type` - Valeur de l'en-tête `Content-Type` (doit être `image/*`)
+    /// * `query` - Chaîne de requête (voir [`QueryParams::parse()`])
+    /// * `body` - Contenu du fichier image
+    pub fn handle_hocr(
+        &self,
+        content_type: Option<&str>,
+        query: &str,
+        body: &[u8],
+    ) -> ServiceResponse {
+        self.with_image(content_type, query, body, |image, options| {
+            match self.backend.extract_hocr(image, options) {
+                Ok(outcome) => ServiceResponse {
+                    status: 200,
+                    content_type: HOCR_CONTENT_TYPE,
+                    body: outcome.text.into_bytes(),
+                },
+                Err(e) => ServiceResponse::extraction_error(&e),
+            }
+        })
+    }
+
+    /// `GET /healthz` : vérifie la disponibilité de Tesseract.
+    ///
+    /// Retourne `{"status": "ok", "tesseract": "<version>"}`, ou une erreur
+    /// 503 si le binaire ne répond pas ou si Tesseract ne s'initialise pas
+    /// avec la langue configurée (voir [`OcrBackend::health_check()`]).
+    pub fn handle_health(&self) -> ServiceResponse {
+        let health = self
+            .backend
+            .tesseract_version()
+            .and_then(|version| self.backend.health_check().map(|()| version));
+        match health {
+            Ok(version) => ServiceResponse::json(
+                200,
+                &serde_json::json!({ "status": "ok", "tesseract": version }),
+            ),
+            Err(e) => ServiceResponse::error(503, "unavailable", &format!("{:#}", e)),
+        }
+    }
+
+    /// Valide une requête portant une image, puis appelle `handle` avec l'image décodée.
+    fn with_image(
+        &self,
+        content_type: Option<&str>,
+        query: &str,
+        body: &[u8],
+        handle: impl FnOnce(&DynamicImage, &RequestOptions) -> ServiceResponse,
+    ) -> ServiceResponse {
+        if body.len() > self.max_body_bytes {
+            return ServiceResponse::error(
+                413,
+                "payload_too_large",
+                &format!(
+                    "Corps de la requête trop volumineux (limite : {} octets)",
+                    self.max_body_bytes
+                ),
+            );
+        }
+        let is_image = content_type.is_some_and(|value| {
+            value
+                .trim_start()
+                .get(..6)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case("image/"))
+        });
+        if !is_image {
+            return ServiceResponse::error(
+                415,
+                "unsupported_media_type",
+                &format!(
+                    "Content-Type attendu : image/* (reçu : {})",
+                    content_type.unwrap_or("aucun")
+                ),
+            );
+        }
+        let params = match QueryParams::parse(query) {
+            Ok(params) => params,
+            Err(e) => return ServiceResponse::error(400, "bad_request", &format!("{:#}", e)),
+        };
+        if body.is_empty() {
+            return ServiceResponse::error(422, "image_decode", "Aucune donnée image reçue");
+        }
+        match image::load_from_memory(body) {
+            Ok(image) => handle(&image, &params.options()),
+            Err(e) => ServiceResponse::error(
+                422,
+                "image_decode",
+                &format!("Échec du décodage de l'image : {}", e),
+            ),
+        }
+    }
+}
+
+/// Serveur HTTP transmettant les requêtes à un [`OcrService`].
+///
+/// Chaque requête est traitée sur son propre thread ; la concurrence de
+/// l'OCR est bornée par le backend (voir [`EngineBackend`]).
+pub struct OcrServer<B: OcrBackend = EngineBackend> {
+    server: tiny_http::Server,
+    service: Arc<OcrService<B>>,
+}
+
+impl<B: OcrBackend + 'static> OcrServer<B> {
+    /// Ouvre le port d'écoute.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Adresse d'écoute (ex: `127.0.0.1:8080`, port `0` = port libre)
+    /// * `service` - Traitement des requêtes
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si l'adresse est invalide ou déjà utilisée.
+    pub fn bind(addr: &str, service: OcrService<B>) -> Result<Self> {
+        let server = tiny_http::Server::http(addr)
+            .map_err(|e| anyhow!("Impossible d'écouter sur '{}' : {}", addr, e))?;
+        Ok(Self {
+            server,
+            service: Arc::new(service),
+        })
+    }
+
+    /// Adresse effectivement écoutée (utile avec le port `0`).
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    /// Traite les requêtes jusqu'à l'appel de [`shutdown()`](Self::shutdown).
+    pub fn run(&self) {
+        for request in self.server.incoming_requests() {
+            let service = Arc::clone(&self.service);
+            thread::spawn(move || respond(&service, request));
+        }
+    }
+
+    /// Arrête [`run()`](Self::run) ; les requêtes en cours se terminent.
+    pub fn shutdown(&self) {
+        self.server.unblock();
+    }
+}
+
+/// Lit le corps d'une requête HTTP, la traite et envoie la réponse.
+fn respond<B: OcrBackend>(service: &OcrService<B>, mut request: tiny_http::Request) {
+    let limit = service.max_body_bytes();
+    let content_type = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Content-Type"))
+        .map(|header| header.value.as_str().to_string());
+
+    // Content-Length trop grand : refuser sans lire le corps
+    let response = if request.body_length().is_some_and(|length| length > limit) {
+        ServiceResponse::error(
+            413,
+            "payload_too_large",
+            &format!(
+                "Corps de la requête trop volumineux (limite : {} octets)",
+                limit
+            ),
+        )
+    } else {
+        // Un octet de plus que la limite suffit à détecter un corps trop grand
+        let mut body = Vec::new();
+        match request
+            .as_reader()
+            .take(limit as u64 + 1)
+            .read_to_end(&mut body)
+        {
+            Ok(_) => service.handle(
+                request.method().as_str(),
+                request.url(),
+                content_type.as_deref(),
+                &body,
+            ),
+            Err(e) => ServiceResponse::error(
+                400,
+                "bad_request",
+                &format!("Lecture du corps de la requête impossible : {}", e),
+            ),
+        }
+    };
+
+    let mut http_response =
+        tiny_http::Response::from_data(response.body).with_status_code(response.status);
+    if let Ok(header) =
+        tiny_http::Header::from_bytes(&b"Content-Type"[..], response.content_type.as_bytes())
+    {
+        http_response = http_response.with_header(header);
+    }
+    // Client déconnecté : rien à faire de plus
+    let _ = request.respond(http_response);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Warning;
+    use image::{GrayImage, ImageFormat, Luma};
+    use std::io::Cursor;
+
+    /// Backend factice : le texte décrit l'image et les surcharges reçues.
+    struct EchoBackend {
+        tesseract: Option<&'static str>,
+        language_installed: bool,
+    }
+
+    impl OcrBackend for EchoBackend {
+        fn extract_text(
+            &self,
+            image: &DynamicImage,
+            options: &RequestOptions,
+        ) -> Result<OcrOutcome> {
+            if image.width() == 1 {
+                bail!("Tesseract a échoué");
+            }
+            Ok(OcrOutcome {
+                text: format!(
+                    "{}x{} {}",
+                    image.width(),
+                    image.height(),
+                    options.language.unwrap_or("défaut")
+                ),
+                warnings: vec![Warning::new("Image too small to scale!!")],
+                preprocessing: None,
+                cache_hit: false,
+            })
+        }
+
+        fn extract_hocr(
+            &self,
+            image: &DynamicImage,
+            _options: &RequestOptions,
+        ) -> Result<OcrOutcome> {
+            Ok(OcrOutcome {
+                text: format!(
+                    "<div class='ocr_page' title='bbox 0 0 {} {}'/>",
+                    image.width(),
+                    image.height()
+                ),
+                warnings: Vec::new(),
+                preprocessing: None,
+                cache_hit: false,
+            })
+        }
+
+        fn tesseract_version(&self) -> Result<String> {
+            self.tesseract
+                .map(str::to_string)
+                .context("Binaire tesseract introuvable")
+        }
+
+        fn health_check(&self) -> Result<()> {
+            if !self.language_installed {
+                bail!("Tesseract indisponible pour la langue 'xyz'");
+            }
+            Ok(())
+        }
+    }
+
+    fn service() -> OcrService<EchoBackend> {
+        OcrService::new(EchoBackend {
+            tesseract: Some("5.3.4"),
+            language_installed: true,
+        })
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        DynamicImage::ImageLuma8(GrayImage::from_pixel(width, height, Luma([255])))
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    fn json(response: &ServiceResponse) -> serde_json::Value {
+        assert_eq!(response.content_type, JSON_CONTENT_TYPE);
+        serde_json::from_slice(&response.body).unwrap()
+    }
+
+    #[test]
+    fn test_ocr_returns_text_duration_and_warnings() {
+        let response = service().handle(
+            "POST",
+            "/ocr?language=eng%2Bfra",
+            Some("image/png"),
+            &png(40, 20),
+        );
+
+        assert_eq!(response.status, 200);
+        let body = json(&response);
+        assert_eq!(body["text"], "40x20 eng+fra");
+        assert!(body["duration_ms"].is_u64());
+        assert_eq!(body["warnings"][0], "Image too small to scale!!");
+    }
+
+    #[test]
+    fn test_hocr_returns_raw_markup() {
+        let response = service().handle("POST", "/hocr", Some("image/png"), &png(40, 20));
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.content_type, HOCR_CONTENT_TYPE);
+        assert!(
+            String::from_utf8(response.body)
+                .unwrap()
+                .contains("bbox 0 0 40 20")
+        );
+    }
+
+    #[test]
+    fn test_undecodable_image_is_unprocessable() {
+        for body in [&b"pas une image"[..], &[]] {
+            let response = service().handle("POST", "/ocr", Some("image/png"), body);
+
+            assert_eq!(response.status, 422);
+            assert_eq!(json(&response)["kind"], "image_decode");
+        }
+    }
+
+    #[test]
+    fn test_request_validation_errors() {
+        let service = service().with_max_body_bytes(100);
+        let cases = [
+            (
+                "POST",
+                "/ocr",
+                Some("image/png"),
+                vec![0u8; 101],
+                413,
+                "payload_too_large",
+            ),
+            (
+                "POST",
+                "/ocr",
+                Some("text/plain"),
+                png(4, 4),
+                415,
+                "unsupported_media_type",
+            ),
+            (
+                "POST",
+                "/ocr",
+                None,
+                png(4, 4),
+                415,
+                "unsupported_media_type",
+            ),
+            (
+                "POST",
+                "/ocr?psm=42",
+                Some("image/png"),
+                png(4, 4),
+                400,
+                "bad_request",
+            ),
+            (
+                "POST",
+                "/ocr?dpi=300",
+                Some("image/png"),
+                png(4, 4),
+                400,
+                "bad_request",
+            ),
+            ("GET", "/ocr", None, Vec::new(), 405, "method_not_allowed"),
+            ("GET", "/inconnue", None, Vec::new(), 404, "not_found"),
+        ];
+
+        for (method, url, content_type, body, status, kind) in cases {
+            let response = service.handle(method, url, content_type, &body);
+            assert_eq!(response.status, status, "{} {}", method, url);
+            assert_eq!(json(&response)["kind"], kind, "{} {}", method, url);
+        }
+    }
+
+    #[test]
+    fn test_ocr_failure_is_server_error() {
+        let response = service().handle("POST", "/ocr", Some("IMAGE/PNG"), &png(1, 1));
+
+        assert_eq!(response.status, 500);
+        let body = json(&response);
+        assert_eq!(body["kind"], "ocr");
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .contains("Tesseract a échoué")
+        );
+    }
+
+    #[test]
+    fn test_healthz() {
+        let response = service().handle("GET", "/healthz", None, &[]);
+        assert_eq!(response.status, 200);
+        assert_eq!(json(&response)["tesseract"], "5.3.4");
+
+        let unavailable = OcrService::new(EchoBackend {
+            tesseract: None,
+            language_installed: true,
+        });
+        let response = unavailable.handle_health();
+        assert_eq!(response.status, 503);
+        assert_eq!(json(&response)["kind"], "unavailable");
+
+        let missing_language = OcrService::new(EchoBackend {
+            tesseract: Some("5.3.4"),
+            language_installed: false,
+        });
+        let response = missing_language.handle_health();
+        assert_eq!(response.status, 503);
+        assert!(
+            json(&response)["error"]
+                .as_str()
+                .unwrap()
+                .contains("langue 'xyz'")
+        );
+    }
+
+    #[test]
+    fn test_query_params_parse() {
+        let params = QueryParams::parse("psm=single-block&preprocess=0").unwrap();
+        let options = params.options();
+        assert_eq!(options.psm, Some(PageSegMode::SingleBlock));
+        assert_eq!(options.preprocess, Some(false));
+        assert_eq!(options.language, None);
+
+        assert!(QueryParams::parse("").unwrap().options().is_default());
+        assert!(QueryParams::parse("language=").is_err());
+        assert!(QueryParams::parse("language=%E").is_err());
+        assert!(QueryParams::parse("preprocess=peut-être").is_err());
+    }
+}
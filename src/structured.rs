@@ -0,0 +1,292 @@
+//! Texte extrait structuré en paragraphes, lignes et mots.
+//!
+//! [`OcrEngine::extract_text_from_file()`](crate::ocr::OcrEngine::extract_text_from_file)
+//! retourne une seule chaîne et perd les limites de paragraphes.
+//! [`StructuredText`] conserve la hiérarchie issue du HOCR, avec le bounding
+//! box et la confiance de chaque mot, et se sérialise en JSON.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! # #[cfg(feature = "tesseract")]
+//! # {
+//! use text_recognition::ocr::OcrEngine;
+//! use text_recognition::config::OcrConfig;
+//! use std::path::Path;
+//!
+//! let engine = OcrEngine::new(OcrConfig::default())?;
+//! let structured = engine.extract_structured(Path::new("article.png"))?;
+//!
+//! for paragraph in structured.paragraph_texts() {
+//!     println!("¶ {}", paragraph);
+//! }
+//! # }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::hocr::{BBox, HocrDocument, SortOrder};
+use serde::Serialize;
+use std::fmt;
+
+/// Mot reconnu.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Word {
+    /// Texte du mot.
+    pub text: String,
+    /// Confiance de Tesseract (0-100), si disponible.
+    pub confidence: Option<u8>,
+    /// Position du mot dans l'image.
+    pub bbox: BBox,
+}
+
+/// Ligne de texte.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Line {
+    /// Position de la ligne dans l'image.
+    pub bbox: BBox,
+    /// Mots de la ligne, de gauche à droite.
+    pub words: Vec<Word>,
+}
+
+/// Paragraphe de texte.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Paragraph {
+    /// Position du paragraphe dans l'image.
+    pub bbox: BBox,
+    /// Lignes du paragraphe, de haut en bas.
+    pub lines: Vec<Line>,
+}
+
+/// Texte extrait d'une image, structuré en paragraphes, lignes et mots.
+///
+/// L'affichage ([`fmt::Display`]) produit le texte brut : mots séparés par
+/// une espace, lignes par un retour à la ligne, paragraphes par une ligne
+/// vide. Les lignes et paragraphes sans mot sont ignorés.
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::hocr::HocrDocument;
+/// use text_recognition::structured::StructuredText;
+///
+/// let hocr = r#"<p class='ocr_par' title="bbox 0 0 100 20">
+///   <span class='ocr_line' title="bbox 0 0 100 20">
+///     <span class='ocrx_word' title='bbox 0 0 40 20; x_wconf 95'>Bonjour</span>
+///     <span class='ocrx_word' title='bbox 50 0 100 20; x_wconf 90'>monde</span>
+///   </span>
+/// </p>"#;
+/// let doc = HocrDocument::from_hocr_string(hocr)?;
+///
+/// let structured = StructuredText::from(&doc);
+/// assert_eq!(structured.to_string(), "Bonjour monde");
+/// assert_eq!(structured.paragraphs[0].lines[0].words[1].confidence, Some(90));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StructuredText {
+    /// Paragraphes, dans l'ordre du document.
+    pub paragraphs: Vec<Paragraph>,
+    /// Ordre appliqué au document HOCR source (voir [`HocrDocument::sort()`]).
+    pub order: SortOrder,
+}
+
+impl StructuredText {
+    /// Retourne le texte de chaque paragraphe non vide.
+    ///
+    /// Les lignes d'un paragraphe sont séparées par un retour à la ligne.
+    pub fn paragraph_texts(&self) -> Vec<String> {
+        self.paragraphs
+            .iter()
+            .map(|paragraph| {
+                paragraph
+                    .lines
+                    .iter()
+                    .map(Line::text)
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .filter(|paragraph| !paragraph.is_empty())
+            .collect()
+    }
+
+    /// Retourne le texte de chaque ligne non vide, tous paragraphes confondus.
+    pub fn line_texts(&self) -> Vec<String> {
+        self.paragraphs
+            .iter()
+            .flat_map(|paragraph| &paragraph.lines)
+            .map(Line::text)
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+}
+
+impl Line {
+    /// Retourne les mots de la ligne séparés par une espace.
+    pub fn text(&self) -> String {
+        self.words
+            .iter()
+            .map(|word| word.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl From<&HocrDocument> for StructuredText {
+    fn from(doc: &HocrDocument) -> Self {
+        let paragraphs = doc
+            .paragraphs()
+            .map(|paragraph| Paragraph {
+                bbox: paragraph.bbox.clone(),
+                lines: paragraph
+                    .lines
+                    .iter()
+                    .map(|line| Line {
+                        bbox: line.bbox.clone(),
+                        words: line
+                            .words
+                            .iter()
+                            .map(|word| Word {
+                                text: word.text.clone(),
+                                confidence: word.confidence,
+                                bbox: word.bbox.clone(),
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            paragraphs,
+            order: doc.order.clone(),
+        }
+    }
+}
+
+impl fmt::Display for StructuredText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.paragraph_texts().join("\n\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deux paragraphes : deux lignes puis une ligne, séparés par un paragraphe vide.
+    const ARTICLE_HOCR: &str = r#"<div class='ocr_page' id='page_1' title='bbox 0 0 300 200'>
+   <p class='ocr_par' id='par_1_1' title="bbox 10 10 290 70">
+    <span class='ocr_line' id='line_1_1' title="bbox 10 10 290 30">
+     <span class='ocrx_word' id='word_1_1' title='bbox 10 10 80 30; x_wconf 96'>Première</span>
+     <span class='ocrx_word' id='word_1_2' title='bbox 90 10 150 30; x_wconf 91'>ligne</span>
+    </span>
+    <span class='ocr_line' id='line_1_2' title="bbox 10 50 200 70">
+     <span class='ocrx_word' id='word_1_3' title='bbox 10 50 200 70'>suite</span>
+    </span>
+   </p>
+   <p class='ocr_par' id='par_1_2' title="bbox 10 80 290 90">
+   </p>
+   <p class='ocr_par' id='par_1_3' title="bbox 10 100 290 130">
+    <span class='ocr_line' id='line_1_3' title="bbox 10 100 290 130">
+     <span class='ocrx_word' id='word_1_4' title='bbox 10 100 120 130; x_wconf 88'>Second</span>
+     <span class='ocrx_word' id='word_1_5' title='bbox 130 100 290 130; x_wconf 85'>paragraphe.</span>
+    </span>
+   </p>
+</div>"#;
+
+    fn article() -> StructuredText {
+        StructuredText::from(&HocrDocument::from_hocr_string(ARTICLE_HOCR).unwrap())
+    }
+
+    #[test]
+    fn test_structure_nesting() {
+        let structured = article();
+
+        assert_eq!(structured.paragraphs.len(), 3);
+        let lines = &structured.paragraphs[0].lines;
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].words.len(), 2);
+        assert_eq!(lines[0].words[0].text, "Première");
+        assert_eq!(lines[0].words[0].confidence, Some(96));
+        assert_eq!(lines[0].words[0].bbox, BBox::new(10, 10, 70, 20));
+        assert_eq!(lines[1].words[0].confidence, None);
+        assert!(structured.paragraphs[1].lines.is_empty());
+        assert_eq!(structured.paragraphs[2].bbox, BBox::new(10, 100, 280, 30));
+    }
+
+    #[test]
+    fn test_display_joining_rules() {
+        assert_eq!(
+            article().to_string(),
+            "Première ligne\nsuite\n\nSecond paragraphe."
+        );
+        assert_eq!(StructuredText::default().to_string(), "");
+    }
+
+    #[test]
+    fn test_display_matches_hocr_full_text() {
+        let doc = HocrDocument::from_hocr_string(ARTICLE_HOCR).unwrap();
+        assert_eq!(StructuredText::from(&doc).to_string(), doc.full_text());
+    }
+
+    #[test]
+    fn test_paragraph_and_line_texts() {
+        let structured = article();
+
+        assert_eq!(
+            structured.paragraph_texts(),
+            ["Première ligne\nsuite", "Second paragraphe."]
+        );
+        assert_eq!(
+            structured.line_texts(),
+            ["Première ligne", "suite", "Second paragraphe."]
+        );
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let structured = StructuredText {
+            paragraphs: vec![Paragraph {
+                bbox: BBox::new(0, 0, 50, 10),
+                lines: vec![Line {
+                    bbox: BBox::new(0, 0, 50, 10),
+                    words: vec![Word {
+                        text: "Total".to_string(),
+                        confidence: Some(93),
+                        bbox: BBox::new(0, 0, 50, 10),
+                    }],
+                }],
+            }],
+            order: SortOrder::Natural,
+        };
+
+        let json = serde_json::to_string(&structured).unwrap();
+
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"paragraphs":[{"bbox":{"x":0,"y":0,"width":50,"height":10},"#,
+                r#""lines":[{"bbox":{"x":0,"y":0,"width":50,"height":10},"#,
+                r#""words":[{"text":"Total","confidence":93,"#,
+                r#""bbox":{"x":0,"y":0,"width":50,"height":10}}]}]}],"order":"natural"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_order_follows_document_sort() {
+        let mut doc = HocrDocument::from_hocr_string(ARTICLE_HOCR).unwrap();
+        doc.sort(SortOrder::Columns {
+            boundaries: vec![150],
+        });
+        let structured = StructuredText::from(&doc);
+
+        let json = serde_json::to_value(&structured).unwrap();
+        assert_eq!(
+            json["order"],
+            serde_json::json!({ "columns": { "boundaries": [150] } })
+        );
+        assert_eq!(article().order, SortOrder::Natural);
+    }
+}
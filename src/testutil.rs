@@ -0,0 +1,541 @@
+//! Générateur d'images de texte synthétiques pour les tests et exemples.
+//!
+//! Les tests qui dépendent des fichiers de `resources/` ne sont pas
+//! reproductibles partout, et versionner une image binaire par cas alourdit
+//! le dépôt. [`render_text_image()`] dessine une chaîne avec une police
+//! bitmap 8×16 embarquée (sans dépendance système), de façon déterministe :
+//! le même texte et les mêmes options produisent toujours la même image.
+//!
+//! Ce module n'est compilé que pour les tests unitaires de la bibliothèque
+//! ou avec la feature `testutil`.
+//!
+//! # Exemple
+//!
+//! ```
+//! use text_recognition::testutil::{RenderOptions, render_text_image};
+//!
+//! let options = RenderOptions {
+//!     rotation_degrees: 3.0,
+//!     noise_sigma: 10.0,
+//!     ..RenderOptions::default()
+//! };
+//! let img = render_text_image("Bonjour\nle monde", &options);
+//! assert!(img.width() > img.height());
+//! ```
+
+use crate::rng::SplitMix64;
+use image::{DynamicImage, Rgb, RgbImage};
+
+/// Largeur d'un caractère de la police embarquée, en pixels.
+pub const GLYPH_WIDTH: u32 = 8;
+
+/// Hauteur d'un caractère de la police embarquée, en pixels.
+pub const GLYPH_HEIGHT: u32 = 16;
+
+/// Espace vertical entre deux lignes de texte, en pixels (avant mise à l'échelle).
+const LINE_GAP: u32 = 4;
+
+/// Caractère dessiné à la place des caractères absents de la police.
+const FALLBACK_CHAR: char = '?';
+
+/// Options de rendu d'une image de texte synthétique.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    /// Facteur d'agrandissement de la police (1 = 8×16 pixels par caractère).
+    pub scale: u32,
+
+    /// Couleur du texte.
+    pub foreground: Rgb<u8>,
+
+    /// Couleur du fond (et des zones découvertes par la rotation).
+    pub background: Rgb<u8>,
+
+    /// Angle de rotation en degrés (positif = sens horaire).
+    ///
+    /// La toile est agrandie pour que le texte ne soit pas coupé.
+    pub rotation_degrees: f64,
+
+    /// Écart-type du bruit gaussien ajouté à chaque pixel (0 = pas de bruit).
+    pub noise_sigma: f64,
+
+    /// Graine du générateur pseudo-aléatoire du bruit.
+    pub noise_seed: u64,
+
+    /// Marge autour du texte, en pixels (après mise à l'échelle).
+    pub margin: u32,
+}
+
+impl Default for RenderOptions {
+    /// Texte noir sur fond blanc, police agrandie 2 fois, marge de 16 pixels,
+    /// sans rotation ni bruit.
+    fn default() -> Self {
+        Self {
+            scale: 2,
+            foreground: Rgb([0, 0, 0]),
+            background: Rgb([255, 255, 255]),
+            rotation_degrees: 0.0,
+            noise_sigma: 0.0,
+            noise_seed: 42,
+            margin: 16,
+        }
+    }
+}
+
+/// Dessine un texte dans une image.
+///
+/// Chaque `\n` commence une nouvelle ligne. Les caractères ASCII imprimables
+/// sont dessinés avec la police embarquée ; les autres (lettres accentuées
+/// comprises) sont remplacés par `?`. Les étapes sont appliquées dans l'ordre :
+/// rendu du texte, rotation, puis bruit.
+///
+/// # Arguments
+///
+/// * `text` - Texte à dessiner
+/// * `options` - Options de rendu
+///
+/// # Exemple
+///
+/// ```
+/// use text_recognition::testutil::{GLYPH_HEIGHT, GLYPH_WIDTH, RenderOptions, render_text_image};
+///
+/// let options = RenderOptions { scale: 1, margin: 0, ..RenderOptions::default() };
+/// let img = render_text_image("abc", &options);
+/// assert_eq!((img.width(), img.height()), (3 * GLYPH_WIDTH, GLYPH_HEIGHT));
+/// ```
+pub fn render_text_image(text: &str, options: &RenderOptions) -> DynamicImage {
+    let scale = options.scale.max(1);
+    let lines: Vec<&str> = text.split('\n').collect();
+    let columns = lines
+        .iter()
+        .map(|line| line.chars().count() as u32)
+        .max()
+        .unwrap_or(0);
+
+    let line_height = (GLYPH_HEIGHT + LINE_GAP) * scale;
+    let width = columns * GLYPH_WIDTH * scale + 2 * options.margin;
+    let height = lines.len() as u32 * line_height - LINE_GAP * scale + 2 * options.margin;
+
+    let mut img = RgbImage::from_pixel(width.max(1), height.max(1), options.background);
+    for (row, line) in lines.iter().enumerate() {
+        let y = options.margin + row as u32 * line_height;
+        for (column, c) in line.chars().enumerate() {
+            let x = options.margin + column as u32 * GLYPH_WIDTH * scale;
+            draw_glyph(&mut img, c, x, y, scale, options.foreground);
+        }
+    }
+
+    if options.rotation_degrees != 0.0 {
+        img = rotate_expanded(&img, options.rotation_degrees, options.background);
+    }
+    if options.noise_sigma > 0.0 {
+        add_gaussian_noise(&mut img, options.noise_sigma, options.noise_seed);
+    }
+
+    DynamicImage::ImageRgb8(img)
+}
+
+/// Dessine un caractère de la police embarquée à la position (x, y).
+fn draw_glyph(img: &mut RgbImage, c: char, x: u32, y: u32, scale: u32, color: Rgb<u8>) {
+    let glyph = glyph(c);
+    for (gy, row) in glyph.iter().enumerate() {
+        for gx in 0..GLYPH_WIDTH {
+            if row & (0x80 >> gx) == 0 {
+                continue;
+            }
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    img.put_pixel(x + gx * scale + dx, y + gy as u32 * scale + dy, color);
+                }
+            }
+        }
+    }
+}
+
+/// Retourne le dessin d'un caractère (ou de `?` s'il est absent de la police).
+fn glyph(c: char) -> &'static [u8; 16] {
+    let index = |c: char| (c as usize).checked_sub(' ' as usize);
+    match index(c) {
+        Some(i) if i < FONT_8X16.len() => &FONT_8X16[i],
+        _ => &FONT_8X16[index(FALLBACK_CHAR).unwrap_or_default()],
+    }
+}
+
+/// Fait pivoter une image autour de son centre en agrandissant la toile.
+///
+/// Même convention que le redressement de [`crate::preprocessing`] : un angle
+/// positif fait tourner le contenu dans le sens horaire. Interpolation
+/// bilinéaire, zones découvertes remplies avec `background`.
+fn rotate_expanded(img: &RgbImage, angle_deg: f64, background: Rgb<u8>) -> RgbImage {
+    let (width, height) = img.dimensions();
+    let rad = angle_deg.to_radians();
+    let (sin_a, cos_a) = rad.sin_cos();
+
+    let out_width = (width as f64 * cos_a.abs() + height as f64 * sin_a.abs()).ceil() as u32;
+    let out_height = (width as f64 * sin_a.abs() + height as f64 * cos_a.abs()).ceil() as u32;
+    let (cx, cy) = (width as f64 / 2.0, height as f64 / 2.0);
+    let (out_cx, out_cy) = (out_width as f64 / 2.0, out_height as f64 / 2.0);
+
+    let mut output = RgbImage::from_pixel(out_width.max(1), out_height.max(1), background);
+    for y in 0..out_height {
+        for x in 0..out_width {
+            let dx = x as f64 - out_cx;
+            let dy = y as f64 - out_cy;
+            let src_x = dx * cos_a + dy * sin_a + cx;
+            let src_y = -dx * sin_a + dy * cos_a + cy;
+
+            if src_x < 0.0
+                || src_y < 0.0
+                || src_x >= width as f64 - 1.0
+                || src_y >= height as f64 - 1.0
+            {
+                continue;
+            }
+
+            let (sx, sy) = (src_x as u32, src_y as u32);
+            let (fx, fy) = (src_x - sx as f64, src_y - sy as f64);
+            let mut pixel = [0u8; 3];
+            for (channel, value) in pixel.iter_mut().enumerate() {
+                let p00 = img.get_pixel(sx, sy)[channel] as f64;
+                let p10 = img.get_pixel(sx + 1, sy)[channel] as f64;
+                let p01 = img.get_pixel(sx, sy + 1)[channel] as f64;
+                let p11 = img.get_pixel(sx + 1, sy + 1)[channel] as f64;
+                let v = p00 * (1.0 - fx) * (1.0 - fy)
+                    + p10 * fx * (1.0 - fy)
+                    + p01 * (1.0 - fx) * fy
+                    + p11 * fx * fy;
+                *value = v.round() as u8;
+            }
+            output.put_pixel(x, y, Rgb(pixel));
+        }
+    }
+
+    output
+}
+
+/// Ajoute un bruit gaussien de même valeur aux trois canaux de chaque pixel.
+fn add_gaussian_noise(img: &mut RgbImage, sigma: f64, seed: u64) {
+    let mut rng = SplitMix64::new(seed);
+    for pixel in img.pixels_mut() {
+        let noise = rng.next_gaussian() * sigma;
+        for value in pixel.0.iter_mut() {
+            *value = (*value as f64 + noise).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// Police bitmap 8×16 des caractères ASCII imprimables (' ' à '~').
+///
+/// Une ligne par octet, bit de poids fort = pixel de gauche. Dessin 5×8
+/// classique des afficheurs LCD, doublé verticalement.
+#[rustfmt::skip]
+const FONT_8X16: [[u8; 16]; 95] = [
+    // ' '
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    // '!'
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x00, 0x00, 0x10, 0x10, 0x00, 0x00],
+    // '"'
+    [0x28, 0x28, 0x28, 0x28, 0x28, 0x28, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    // '#'
+    [0x28, 0x28, 0x28, 0x28, 0x7C, 0x7C, 0x28, 0x28, 0x7C, 0x7C, 0x28, 0x28, 0x28, 0x28, 0x00, 0x00],
+    // '$'
+    [0x10, 0x10, 0x3C, 0x3C, 0x50, 0x50, 0x38, 0x38, 0x14, 0x14, 0x78, 0x78, 0x10, 0x10, 0x00, 0x00],
+    // '%'
+    [0x60, 0x60, 0x64, 0x64, 0x08, 0x08, 0x10, 0x10, 0x20, 0x20, 0x4C, 0x4C, 0x0C, 0x0C, 0x00, 0x00],
+    // '&'
+    [0x20, 0x20, 0x50, 0x50, 0x50, 0x50, 0x20, 0x20, 0x54, 0x54, 0x48, 0x48, 0x34, 0x34, 0x00, 0x00],
+    // '\''
+    [0x30, 0x30, 0x10, 0x10, 0x20, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    // '('
+    [0x08, 0x08, 0x10, 0x10, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x10, 0x10, 0x08, 0x08, 0x00, 0x00],
+    // ')'
+    [0x20, 0x20, 0x10, 0x10, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x10, 0x10, 0x20, 0x20, 0x00, 0x00],
+    // '*'
+    [0x10, 0x10, 0x54, 0x54, 0x38, 0x38, 0x7C, 0x7C, 0x38, 0x38, 0x54, 0x54, 0x10, 0x10, 0x00, 0x00],
+    // '+'
+    [0x00, 0x00, 0x10, 0x10, 0x10, 0x10, 0x7C, 0x7C, 0x10, 0x10, 0x10, 0x10, 0x00, 0x00, 0x00, 0x00],
+    // ','
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x30, 0x30, 0x10, 0x10, 0x20, 0x20, 0x00, 0x00],
+    // '-'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7C, 0x7C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    // '.'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x30, 0x30, 0x30, 0x30, 0x00, 0x00],
+    // '/'
+    [0x00, 0x00, 0x04, 0x04, 0x08, 0x08, 0x10, 0x10, 0x20, 0x20, 0x40, 0x40, 0x00, 0x00, 0x00, 0x00],
+    // '0'
+    [0x38, 0x38, 0x44, 0x44, 0x4C, 0x4C, 0x54, 0x54, 0x64, 0x64, 0x44, 0x44, 0x38, 0x38, 0x00, 0x00],
+    // '1'
+    [0x10, 0x10, 0x30, 0x30, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x38, 0x38, 0x00, 0x00],
+    // '2'
+    [0x38, 0x38, 0x44, 0x44, 0x04, 0x04, 0x08, 0x08, 0x10, 0x10, 0x20, 0x20, 0x7C, 0x7C, 0x00, 0x00],
+    // '3'
+    [0x7C, 0x7C, 0x08, 0x08, 0x10, 0x10, 0x08, 0x08, 0x04, 0x04, 0x44, 0x44, 0x38, 0x38, 0x00, 0x00],
+    // '4'
+    [0x08, 0x08, 0x18, 0x18, 0x28, 0x28, 0x48, 0x48, 0x7C, 0x7C, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00],
+    // '5'
+    [0x7C, 0x7C, 0x40, 0x40, 0x78, 0x78, 0x04, 0x04, 0x04, 0x04, 0x44, 0x44, 0x38, 0x38, 0x00, 0x00],
+    // '6'
+    [0x18, 0x18, 0x20, 0x20, 0x40, 0x40, 0x78, 0x78, 0x44, 0x44, 0x44, 0x44, 0x38, 0x38, 0x00, 0x00],
+    // '7'
+    [0x7C, 0x7C, 0x04, 0x04, 0x08, 0x08, 0x10, 0x10, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x00, 0x00],
+    // '8'
+    [0x38, 0x38, 0x44, 0x44, 0x44, 0x44, 0x38, 0x38, 0x44, 0x44, 0x44, 0x44, 0x38, 0x38, 0x00, 0x00],
+    // '9'
+    [0x38, 0x38, 0x44, 0x44, 0x44, 0x44, 0x3C, 0x3C, 0x04, 0x04, 0x08, 0x08, 0x30, 0x30, 0x00, 0x00],
+    // ':'
+    [0x00, 0x00, 0x30, 0x30, 0x30, 0x30, 0x00, 0x00, 0x30, 0x30, 0x30, 0x30, 0x00, 0x00, 0x00, 0x00],
+    // ';'
+    [0x00, 0x00, 0x30, 0x30, 0x30, 0x30, 0x00, 0x00, 0x30, 0x30, 0x10, 0x10, 0x20, 0x20, 0x00, 0x00],
+    // '<'
+    [0x08, 0x08, 0x10, 0x10, 0x20, 0x20, 0x40, 0x40, 0x20, 0x20, 0x10, 0x10, 0x08, 0x08, 0x00, 0x00],
+    // '='
+    [0x00, 0x00, 0x00, 0x00, 0x7C, 0x7C, 0x00, 0x00, 0x7C, 0x7C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    // '>'
+    [0x20, 0x20, 0x10, 0x10, 0x08, 0x08, 0x04, 0x04, 0x08, 0x08, 0x10, 0x10, 0x20, 0x20, 0x00, 0x00],
+    // '?'
+    [0x38, 0x38, 0x44, 0x44, 0x04, 0x04, 0x08, 0x08, 0x10, 0x10, 0x00, 0x00, 0x10, 0x10, 0x00, 0x00],
+    // '@'
+    [0x38, 0x38, 0x44, 0x44, 0x54, 0x54, 0x5C, 0x5C, 0x58, 0x58, 0x40, 0x40, 0x3C, 0x3C, 0x00, 0x00],
+    // 'A'
+    [0x10, 0x10, 0x28, 0x28, 0x44, 0x44, 0x44, 0x44, 0x7C, 0x7C, 0x44, 0x44, 0x44, 0x44, 0x00, 0x00],
+    // 'B'
+    [0x78, 0x78, 0x44, 0x44, 0x44, 0x44, 0x78, 0x78, 0x44, 0x44, 0x44, 0x44, 0x78, 0x78, 0x00, 0x00],
+    // 'C'
+    [0x38, 0x38, 0x44, 0x44, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x44, 0x44, 0x38, 0x38, 0x00, 0x00],
+    // 'D'
+    [0x70, 0x70, 0x48, 0x48, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x48, 0x48, 0x70, 0x70, 0x00, 0x00],
+    // 'E'
+    [0x7C, 0x7C, 0x40, 0x40, 0x40, 0x40, 0x78, 0x78, 0x40, 0x40, 0x40, 0x40, 0x7C, 0x7C, 0x00, 0x00],
+    // 'F'
+    [0x7C, 0x7C, 0x40, 0x40, 0x40, 0x40, 0x78, 0x78, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x00, 0x00],
+    // 'G'
+    [0x38, 0x38, 0x44, 0x44, 0x40, 0x40, 0x5C, 0x5C, 0x44, 0x44, 0x44, 0x44, 0x3C, 0x3C, 0x00, 0x00],
+    // 'H'
+    [0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x7C, 0x7C, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x00, 0x00],
+    // 'I'
+    [0x38, 0x38, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x38, 0x38, 0x00, 0x00],
+    // 'J'
+    [0x1C, 0x1C, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x48, 0x48, 0x30, 0x30, 0x00, 0x00],
+    // 'K'
+    [0x44, 0x44, 0x48, 0x48, 0x50, 0x50, 0x60, 0x60, 0x50, 0x50, 0x48, 0x48, 0x44, 0x44, 0x00, 0x00],
+    // 'L'
+    [0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x7C, 0x7C, 0x00, 0x00],
+    // 'M'
+    [0x44, 0x44, 0x6C, 0x6C, 0x54, 0x54, 0x54, 0x54, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x00, 0x00],
+    // 'N'
+    [0x44, 0x44, 0x44, 0x44, 0x64, 0x64, 0x54, 0x54, 0x4C, 0x4C, 0x44, 0x44, 0x44, 0x44, 0x00, 0x00],
+    // 'O'
+    [0x38, 0x38, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x38, 0x38, 0x00, 0x00],
+    // 'P'
+    [0x78, 0x78, 0x44, 0x44, 0x44, 0x44, 0x78, 0x78, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x00, 0x00],
+    // 'Q'
+    [0x38, 0x38, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x54, 0x54, 0x48, 0x48, 0x34, 0x34, 0x00, 0x00],
+    // 'R'
+    [0x78, 0x78, 0x44, 0x44, 0x44, 0x44, 0x78, 0x78, 0x50, 0x50, 0x48, 0x48, 0x44, 0x44, 0x00, 0x00],
+    // 'S'
+    [0x3C, 0x3C, 0x40, 0x40, 0x40, 0x40, 0x38, 0x38, 0x04, 0x04, 0x04, 0x04, 0x78, 0x78, 0x00, 0x00],
+    // 'T'
+    [0x7C, 0x7C, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x00, 0x00],
+    // 'U'
+    [0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x38, 0x38, 0x00, 0x00],
+    // 'V'
+    [0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x28, 0x28, 0x10, 0x10, 0x00, 0x00],
+    // 'W'
+    [0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x54, 0x54, 0x54, 0x54, 0x54, 0x54, 0x28, 0x28, 0x00, 0x00],
+    // 'X'
+    [0x44, 0x44, 0x44, 0x44, 0x28, 0x28, 0x10, 0x10, 0x28, 0x28, 0x44, 0x44, 0x44, 0x44, 0x00, 0x00],
+    // 'Y'
+    [0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x28, 0x28, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x00, 0x00],
+    // 'Z'
+    [0x7C, 0x7C, 0x04, 0x04, 0x08, 0x08, 0x10, 0x10, 0x20, 0x20, 0x40, 0x40, 0x7C, 0x7C, 0x00, 0x00],
+    // '['
+    [0x38, 0x38, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x38, 0x38, 0x00, 0x00],
+    // '\\'
+    [0x00, 0x00, 0x40, 0x40, 0x20, 0x20, 0x10, 0x10, 0x08, 0x08, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00],
+    // ']'
+    [0x38, 0x38, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x38, 0x38, 0x00, 0x00],
+    // '^'
+    [0x10, 0x10, 0x28, 0x28, 0x44, 0x44, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    // '_'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x7C, 0x7C, 0x00, 0x00],
+    // '`'
+    [0x20, 0x20, 0x10, 0x10, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    // 'a'
+    [0x00, 0x00, 0x00, 0x00, 0x38, 0x38, 0x04, 0x04, 0x3C, 0x3C, 0x44, 0x44, 0x3C, 0x3C, 0x00, 0x00],
+    // 'b'
+    [0x40, 0x40, 0x40, 0x40, 0x58, 0x58, 0x64, 0x64, 0x44, 0x44, 0x44, 0x44, 0x78, 0x78, 0x00, 0x00],
+    // 'c'
+    [0x00, 0x00, 0x00, 0x00, 0x38, 0x38, 0x40, 0x40, 0x40, 0x40, 0x44, 0x44, 0x38, 0x38, 0x00, 0x00],
+    // 'd'
+    [0x04, 0x04, 0x04, 0x04, 0x34, 0x34, 0x4C, 0x4C, 0x44, 0x44, 0x44, 0x44, 0x3C, 0x3C, 0x00, 0x00],
+    // 'e'
+    [0x00, 0x00, 0x00, 0x00, 0x38, 0x38, 0x44, 0x44, 0x7C, 0x7C, 0x40, 0x40, 0x38, 0x38, 0x00, 0x00],
+    // 'f'
+    [0x18, 0x18, 0x24, 0x24, 0x20, 0x20, 0x70, 0x70, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x00, 0x00],
+    // 'g'
+    [0x00, 0x00, 0x00, 0x00, 0x3C, 0x3C, 0x44, 0x44, 0x44, 0x44, 0x3C, 0x3C, 0x04, 0x04, 0x38, 0x38],
+    // 'h'
+    [0x40, 0x40, 0x40, 0x40, 0x58, 0x58, 0x64, 0x64, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x00, 0x00],
+    // 'i'
+    [0x10, 0x10, 0x00, 0x00, 0x30, 0x30, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x38, 0x38, 0x00, 0x00],
+    // 'j'
+    [0x08, 0x08, 0x00, 0x00, 0x18, 0x18, 0x08, 0x08, 0x08, 0x08, 0x08, 0x08, 0x48, 0x48, 0x30, 0x30],
+    // 'k'
+    [0x40, 0x40, 0x40, 0x40, 0x48, 0x48, 0x50, 0x50, 0x60, 0x60, 0x50, 0x50, 0x48, 0x48, 0x00, 0x00],
+    // 'l'
+    [0x30, 0x30, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x38, 0x38, 0x00, 0x00],
+    // 'm'
+    [0x00, 0x00, 0x00, 0x00, 0x68, 0x68, 0x54, 0x54, 0x54, 0x54, 0x44, 0x44, 0x44, 0x44, 0x00, 0x00],
+    // 'n'
+    [0x00, 0x00, 0x00, 0x00, 0x58, 0x58, 0x64, 0x64, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x00, 0x00],
+    // 'o'
+    [0x00, 0x00, 0x00, 0x00, 0x38, 0x38, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x38, 0x38, 0x00, 0x00],
+    // 'p'
+    [0x00, 0x00, 0x00, 0x00, 0x78, 0x78, 0x44, 0x44, 0x44, 0x44, 0x78, 0x78, 0x40, 0x40, 0x40, 0x40],
+    // 'q'
+    [0x00, 0x00, 0x00, 0x00, 0x34, 0x34, 0x4C, 0x4C, 0x4C, 0x4C, 0x34, 0x34, 0x04, 0x04, 0x04, 0x04],
+    // 'r'
+    [0x00, 0x00, 0x00, 0x00, 0x58, 0x58, 0x64, 0x64, 0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0x00, 0x00],
+    // 's'
+    [0x00, 0x00, 0x00, 0x00, 0x38, 0x38, 0x40, 0x40, 0x38, 0x38, 0x04, 0x04, 0x78, 0x78, 0x00, 0x00],
+    // 't'
+    [0x20, 0x20, 0x20, 0x20, 0x70, 0x70, 0x20, 0x20, 0x20, 0x20, 0x24, 0x24, 0x18, 0x18, 0x00, 0x00],
+    // 'u'
+    [0x00, 0x00, 0x00, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x4C, 0x4C, 0x34, 0x34, 0x00, 0x00],
+    // 'v'
+    [0x00, 0x00, 0x00, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x28, 0x28, 0x10, 0x10, 0x00, 0x00],
+    // 'w'
+    [0x00, 0x00, 0x00, 0x00, 0x44, 0x44, 0x44, 0x44, 0x54, 0x54, 0x54, 0x54, 0x28, 0x28, 0x00, 0x00],
+    // 'x'
+    [0x00, 0x00, 0x00, 0x00, 0x44, 0x44, 0x28, 0x28, 0x10, 0x10, 0x28, 0x28, 0x44, 0x44, 0x00, 0x00],
+    // 'y'
+    [0x00, 0x00, 0x00, 0x00, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x3C, 0x3C, 0x04, 0x04, 0x38, 0x38],
+    // 'z'
+    [0x00, 0x00, 0x00, 0x00, 0x7C, 0x7C, 0x08, 0x08, 0x10, 0x10, 0x20, 0x20, 0x7C, 0x7C, 0x00, 0x00],
+    // '{'
+    [0x08, 0x08, 0x10, 0x10, 0x10, 0x10, 0x20, 0x20, 0x10, 0x10, 0x10, 0x10, 0x08, 0x08, 0x00, 0x00],
+    // '|'
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x00, 0x00],
+    // '}'
+    [0x20, 0x20, 0x10, 0x10, 0x10, 0x10, 0x08, 0x08, 0x10, 0x10, 0x10, 0x10, 0x20, 0x20, 0x00, 0x00],
+    // '~'
+    [0x00, 0x00, 0x00, 0x00, 0x20, 0x20, 0x54, 0x54, 0x08, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessing::detect_skew_angle;
+    use image::GenericImageView;
+
+    /// Paragraphe de plusieurs lignes, assez long pour une détection d'inclinaison fiable.
+    const PARAGRAPH: &str = "Lorem ipsum dolor sit amet\n\
+                             consectetur adipiscing elit\n\
+                             sed do eiusmod tempor incid\n\
+                             ut labore et dolore magna";
+
+    /// Variance des niveaux de gris d'une image.
+    fn luma_variance(img: &DynamicImage) -> f64 {
+        let gray = img.to_luma8();
+        let n = gray.pixels().len() as f64;
+        let mean = gray.pixels().map(|p| p[0] as f64).sum::<f64>() / n;
+        gray.pixels()
+            .map(|p| (p[0] as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n
+    }
+
+    #[test]
+    fn test_render_dimensions() {
+        let options = RenderOptions {
+            scale: 3,
+            margin: 5,
+            ..RenderOptions::default()
+        };
+        let img = render_text_image("ab\nabcd", &options);
+
+        assert_eq!(img.width(), 4 * GLYPH_WIDTH * 3 + 10);
+        assert_eq!(img.height(), (2 * GLYPH_HEIGHT + LINE_GAP) * 3 + 10);
+    }
+
+    #[test]
+    fn test_render_is_deterministic() {
+        let options = RenderOptions {
+            rotation_degrees: 2.0,
+            noise_sigma: 20.0,
+            ..RenderOptions::default()
+        };
+        assert_eq!(
+            render_text_image(PARAGRAPH, &options),
+            render_text_image(PARAGRAPH, &options)
+        );
+    }
+
+    #[test]
+    fn test_different_texts_produce_different_images() {
+        let options = RenderOptions::default();
+        let first = render_text_image("ABC", &options);
+        let second = render_text_image("ABD", &options);
+
+        assert_eq!(first.dimensions(), second.dimensions());
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_colors_are_applied() {
+        let options = RenderOptions {
+            foreground: Rgb([200, 0, 0]),
+            background: Rgb([0, 0, 80]),
+            ..RenderOptions::default()
+        };
+        let img = render_text_image("H", &options).to_rgb8();
+
+        assert_eq!(*img.get_pixel(0, 0), Rgb([0, 0, 80]));
+        assert!(img.pixels().any(|p| *p == Rgb([200, 0, 0])));
+    }
+
+    #[test]
+    fn test_unknown_characters_use_fallback() {
+        let options = RenderOptions::default();
+        assert_eq!(
+            render_text_image("é", &options),
+            render_text_image("?", &options)
+        );
+    }
+
+    #[test]
+    fn test_rotation_matches_detected_skew() {
+        let options = RenderOptions {
+            rotation_degrees: 5.0,
+            ..RenderOptions::default()
+        };
+        let img = render_text_image(PARAGRAPH, &options).to_luma8();
+
+        // L'angle détecté est la correction à appliquer : l'opposé de la rotation
+        let angle = detect_skew_angle(&img);
+        assert!(
+            (angle + 5.0).abs() <= 1.0,
+            "Angle détecté {}° au lieu de -5°",
+            angle
+        );
+    }
+
+    #[test]
+    fn test_noise_increases_variance() {
+        let options = RenderOptions::default();
+        let clean = render_text_image(PARAGRAPH, &options);
+        let noisy = render_text_image(
+            PARAGRAPH,
+            &RenderOptions {
+                noise_sigma: 25.0,
+                ..options.clone()
+            },
+        );
+        assert_eq!(clean.dimensions(), noisy.dimensions());
+
+        // Variance mesurée dans la marge : le texte noir sur blanc, déjà
+        // saturé, masquerait l'effet du bruit au-delà de 0 et 255
+        let margin = |img: &DynamicImage| img.crop_imm(0, 0, clean.width(), options.margin);
+        assert_eq!(luma_variance(&margin(&clean)), 0.0);
+        assert!(luma_variance(&margin(&noisy)) > 100.0);
+    }
+}
@@ -0,0 +1,619 @@
+//! Réglage du prétraitement : quelles étapes améliorent vraiment l'OCR ?
+//!
+//! Après avoir activé plusieurs options de prétraitement, il est difficile de
+//! savoir lesquelles aident. [`ablation_study()`] lance l'OCR avec la
+//! configuration complète, puis en désactivant chaque étape active l'une
+//! après l'autre (et, sur demande, en activant chaque étape inactive). La
+//! variation du CER est attribuée à l'étape concernée :
+//!
+//! - étape retirée : impact = CER sans l'étape − CER complet ;
+//! - étape ajoutée : impact = CER complet − CER avec l'étape.
+//!
+//! Un impact positif signifie que l'étape fait baisser le CER. Une étape
+//! active dont le retrait améliore le CER est signalée comme nuisible.
+//!
+//! Les appels à Tesseract passent par [`OcrRunner`], comme pour le
+//! benchmark, afin que la génération des variantes et le calcul des écarts
+//! soient testables sans Tesseract.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::preprocessing::PreprocessingConfig;
+//! use text_recognition::tuning::ablation_study;
+//! use std::path::Path;
+//!
+//! let base = PreprocessingConfig {
+//!     denoise: true,
+//!     deskew: true,
+//!     ..PreprocessingConfig::default()
+//! };
+//! let reference = std::fs::read_to_string("facture.txt")?;
+//! let report = ablation_study(Path::new("facture.png"), &reference, &base, &OcrConfig::default())?;
+//! println!("{}", report.render_markdown());
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::bench::{OcrRunner, TesseractRunner};
+use crate::config::OcrConfig;
+use crate::config_file::AppConfig;
+use crate::metrics::calculate_cer;
+use crate::preprocessing::PreprocessingConfig;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// En deçà de cet écart de CER, une étape est considérée sans effet.
+const NEUTRAL_DELTA: f64 = 1e-9;
+
+/// Étape du prétraitement pouvant être activée ou désactivée.
+///
+/// Les étapes sont listées dans l'ordre du pipeline (voir
+/// [`preprocess_image_with_callback()`](crate::preprocessing::preprocess_image_with_callback)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreprocessingStep {
+    /// Conversion en niveaux de gris (`to_grayscale`).
+    Grayscale,
+    /// Correction de l'inclinaison (`deskew`).
+    Deskew,
+    /// Débruitage (`denoise`).
+    Denoise,
+    /// Ajustement de contraste (`adjust_contrast`).
+    Contrast,
+    /// Binarisation (`binarize`).
+    Binarize,
+}
+
+impl PreprocessingStep {
+    /// Toutes les étapes, dans l'ordre du pipeline.
+    pub const ALL: [PreprocessingStep; 5] = [
+        PreprocessingStep::Grayscale,
+        PreprocessingStep::Deskew,
+        PreprocessingStep::Denoise,
+        PreprocessingStep::Contrast,
+        PreprocessingStep::Binarize,
+    ];
+
+    /// Nom de l'étape, tel qu'utilisé par le pipeline.
+    pub fn name(self) -> &'static str {
+        match self {
+            PreprocessingStep::Grayscale => "grayscale",
+            PreprocessingStep::Deskew => "deskew",
+            PreprocessingStep::Denoise => "denoise",
+            PreprocessingStep::Contrast => "contrast",
+            PreprocessingStep::Binarize => "binarize",
+        }
+    }
+
+    /// Indique si l'étape est active dans la configuration.
+    pub fn is_enabled(self, config: &PreprocessingConfig) -> bool {
+        match self {
+            PreprocessingStep::Grayscale => config.to_grayscale,
+            PreprocessingStep::Deskew => config.deskew,
+            PreprocessingStep::Denoise => config.denoise,
+            PreprocessingStep::Contrast => config.adjust_contrast,
+            PreprocessingStep::Binarize => config.binarize,
+        }
+    }
+
+    /// Active ou désactive l'étape dans la configuration.
+    pub fn set_enabled(self, config: &mut PreprocessingConfig, enabled: bool) {
+        let flag = match self {
+            PreprocessingStep::Grayscale => &mut config.to_grayscale,
+            PreprocessingStep::Deskew => &mut config.deskew,
+            PreprocessingStep::Denoise => &mut config.denoise,
+            PreprocessingStep::Contrast => &mut config.adjust_contrast,
+            PreprocessingStep::Binarize => &mut config.binarize,
+        };
+        *flag = enabled;
+    }
+}
+
+impl fmt::Display for PreprocessingStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Modification appliquée à la configuration de base pour une variante.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepChange {
+    /// Étape active désactivée.
+    Removed,
+    /// Étape inactive activée.
+    Added,
+}
+
+/// Variante de la configuration de base, différant d'une seule étape.
+#[derive(Debug, Clone)]
+pub struct AblationVariant {
+    /// Étape modifiée.
+    pub step: PreprocessingStep,
+    /// Sens de la modification.
+    pub change: StepChange,
+    /// Configuration de la variante.
+    pub config: PreprocessingConfig,
+}
+
+/// Options d'une étude d'ablation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AblationOptions {
+    /// Tester aussi chaque étape inactive, activée seule en plus de la base.
+    pub include_additions: bool,
+}
+
+/// Appréciation de l'effet d'une étape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepVerdict {
+    /// L'étape fait baisser le CER.
+    Helpful,
+    /// L'étape fait monter le CER.
+    Harmful,
+    /// L'étape ne change pas le CER.
+    Neutral,
+}
+
+/// Effet mesuré d'une étape.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepImpact {
+    /// Étape modifiée.
+    pub step: PreprocessingStep,
+    /// Sens de la modification testée.
+    pub change: StepChange,
+    /// CER de la variante.
+    pub cer: f64,
+    /// Baisse du CER attribuable à l'étape (négative si l'étape nuit).
+    pub delta: f64,
+    /// Appréciation déduite de `delta`.
+    pub verdict: StepVerdict,
+}
+
+/// Variante dont l'OCR a échoué.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VariantFailure {
+    /// Étape modifiée.
+    pub step: PreprocessingStep,
+    /// Sens de la modification testée.
+    pub change: StepChange,
+    /// Message d'erreur.
+    pub error: String,
+}
+
+/// Résultat d'une étude d'ablation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AblationReport {
+    /// Image analysée.
+    pub image: PathBuf,
+    /// Étapes actives dans la configuration de base.
+    pub enabled_steps: Vec<PreprocessingStep>,
+    /// CER avec la configuration de base.
+    pub baseline_cer: f64,
+    /// Effet de chaque étape, du plus fort au plus faible (en valeur absolue).
+    pub impacts: Vec<StepImpact>,
+    /// Variantes dont l'OCR a échoué.
+    pub failures: Vec<VariantFailure>,
+}
+
+impl AblationReport {
+    /// Étapes actives dont le retrait améliore le CER.
+    pub fn harmful_steps(&self) -> Vec<PreprocessingStep> {
+        self.impacts
+            .iter()
+            .filter(|impact| {
+                impact.change == StepChange::Removed && impact.verdict == StepVerdict::Harmful
+            })
+            .map(|impact| impact.step)
+            .collect()
+    }
+
+    /// Génère le rapport au format Markdown.
+    pub fn render_markdown(&self) -> String {
+        let mut output = String::from("# Étude d'ablation du prétraitement\n\n");
+        output.push_str(&format!("Image : `{}`\n\n", self.image.display()));
+        let enabled: Vec<&str> = self.enabled_steps.iter().map(|step| step.name()).collect();
+        output.push_str(&format!(
+            "Étapes actives : {}\n\n",
+            if enabled.is_empty() {
+                "aucune".to_string()
+            } else {
+                enabled.join(", ")
+            }
+        ));
+        output.push_str(&format!(
+            "CER de référence : {:.2}%\n\n",
+            self.baseline_cer * 100.0
+        ));
+
+        if self.impacts.is_empty() {
+            output.push_str("Aucune variante mesurée.\n");
+        } else {
+            output.push_str("| Étape | Variante | CER | Impact | Verdict |\n");
+            output.push_str("|-------|----------|-----|--------|---------|\n");
+            for impact in &self.impacts {
+                let change = match impact.change {
+                    StepChange::Removed => "retirée",
+                    StepChange::Added => "ajoutée",
+                };
+                let verdict = match impact.verdict {
+                    StepVerdict::Helpful => "utile",
+                    StepVerdict::Harmful => "nuisible",
+                    StepVerdict::Neutral => "sans effet",
+                };
+                output.push_str(&format!(
+                    "| {} | {} | {:.2}% | {:+.2} pts | {} |\n",
+                    impact.step,
+                    change,
+                    impact.cer * 100.0,
+                    impact.delta * 100.0,
+                    verdict
+                ));
+            }
+        }
+
+        let harmful = self.harmful_steps();
+        if !harmful.is_empty() {
+            let names: Vec<&str> = harmful.iter().map(|step| step.name()).collect();
+            output.push_str(&format!(
+                "\n⚠ Étapes à désactiver (leur retrait améliore le CER) : {}\n",
+                names.join(", ")
+            ));
+        }
+
+        if !self.failures.is_empty() {
+            output.push_str("\n## Variantes en échec\n\n");
+            for failure in &self.failures {
+                output.push_str(&format!(
+                    "- {} ({:?}) : {}\n",
+                    failure.step, failure.change, failure.error
+                ));
+            }
+        }
+
+        output
+    }
+}
+
+/// Génère les variantes d'une configuration, chacune différant d'une seule étape.
+///
+/// Une variante par étape active (désactivée), puis, avec
+/// `include_additions`, une par étape inactive (activée), dans l'ordre du
+/// pipeline.
+///
+/// # Arguments
+///
+/// * `base` - Configuration de référence
+/// * `include_additions` - Générer aussi les variantes qui activent une étape
+pub fn ablation_variants(
+    base: &PreprocessingConfig,
+    include_additions: bool,
+) -> Vec<AblationVariant> {
+    let variant = |step: PreprocessingStep, change: StepChange| {
+        let mut config = base.clone();
+        step.set_enabled(&mut config, change == StepChange::Added);
+        AblationVariant {
+            step,
+            change,
+            config,
+        }
+    };
+
+    let removals = PreprocessingStep::ALL
+        .into_iter()
+        .filter(|step| step.is_enabled(base))
+        .map(|step| variant(step, StepChange::Removed));
+    let additions = PreprocessingStep::ALL
+        .into_iter()
+        .filter(|step| include_additions && !step.is_enabled(base))
+        .map(|step| variant(step, StepChange::Added));
+    removals.chain(additions).collect()
+}
+
+/// Mesure l'effet de chaque étape du prétraitement sur le CER d'une image.
+///
+/// Teste le retrait de chaque étape active avec Tesseract (voir
+/// [`ablation_study_with()`] pour tester aussi les ajouts ou fournir un
+/// autre [`OcrRunner`]).
+///
+/// # Arguments
+///
+/// * `image` - Chemin vers l'image
+/// * `reference` - Texte attendu
+/// * `base` - Configuration de prétraitement à évaluer
+/// * `ocr` - Configuration OCR commune à toutes les variantes
+///
+/// # Erreurs
+///
+/// Retourne une erreur si l'OCR échoue avec la configuration de base.
+pub fn ablation_study(
+    image: &Path,
+    reference: &str,
+    base: &PreprocessingConfig,
+    ocr: &OcrConfig,
+) -> Result<AblationReport> {
+    ablation_study_with(
+        &mut TesseractRunner,
+        image,
+        reference,
+        base,
+        ocr,
+        &AblationOptions::default(),
+    )
+}
+
+/// Mesure l'effet de chaque étape du prétraitement avec un [`OcrRunner`] donné.
+///
+/// L'échec d'une variante est consigné dans [`AblationReport::failures`]
+/// sans interrompre l'étude.
+///
+/// # Arguments
+///
+/// * `runner` - Étape d'OCR
+/// * `image` - Chemin vers l'image
+/// * `reference` - Texte attendu
+/// * `base` - Configuration de prétraitement à évaluer
+/// * `ocr` - Configuration OCR commune à toutes les variantes
+/// * `options` - Variantes à tester
+///
+/// # Erreurs
+///
+/// Retourne une erreur si l'OCR échoue avec la configuration de base.
+pub fn ablation_study_with<R: OcrRunner>(
+    runner: &mut R,
+    image: &Path,
+    reference: &str,
+    base: &PreprocessingConfig,
+    ocr: &OcrConfig,
+    options: &AblationOptions,
+) -> Result<AblationReport> {
+    let mut measure = |preprocessing: &PreprocessingConfig| -> Result<f64> {
+        let config = AppConfig {
+            ocr: Some(ocr.clone()),
+            preprocessing: Some(preprocessing.clone()),
+            ..AppConfig::default()
+        };
+        let text = runner.run(&config, image)?;
+        Ok(calculate_cer(&text, reference))
+    };
+
+    let baseline_cer = measure(base).with_context(|| {
+        format!(
+            "Échec de l'OCR de '{}' avec la configuration complète",
+            image.display()
+        )
+    })?;
+
+    let mut impacts = Vec::new();
+    let mut failures = Vec::new();
+    for variant in ablation_variants(base, options.include_additions) {
+        match measure(&variant.config) {
+            Ok(cer) => impacts.push(step_impact(&variant, cer, baseline_cer)),
+            Err(error) => failures.push(VariantFailure {
+                step: variant.step,
+                change: variant.change,
+                error: format!("{:#}", error),
+            }),
+        }
+    }
+    // Tri stable : à impact égal, l'ordre du pipeline est conservé
+    impacts.sort_by(|a, b| b.delta.abs().total_cmp(&a.delta.abs()));
+
+    Ok(AblationReport {
+        image: image.to_path_buf(),
+        enabled_steps: PreprocessingStep::ALL
+            .into_iter()
+            .filter(|step| step.is_enabled(base))
+            .collect(),
+        baseline_cer,
+        impacts,
+        failures,
+    })
+}
+
+/// Calcule l'effet d'une étape à partir du CER de sa variante.
+fn step_impact(variant: &AblationVariant, cer: f64, baseline_cer: f64) -> StepImpact {
+    let delta = match variant.change {
+        StepChange::Removed => cer - baseline_cer,
+        StepChange::Added => baseline_cer - cer,
+    };
+    let verdict = if delta.abs() < NEUTRAL_DELTA {
+        StepVerdict::Neutral
+    } else if delta > 0.0 {
+        StepVerdict::Helpful
+    } else {
+        StepVerdict::Harmful
+    };
+    StepImpact {
+        step: variant.step,
+        change: variant.change,
+        cer,
+        delta,
+        verdict,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+
+    /// Runner factice : le texte dépend des étapes actives.
+    ///
+    /// La binarisation corrige deux caractères, le débruitage un caractère ;
+    /// le redressement en dégrade un. Le contraste fait échouer l'OCR.
+    #[derive(Default)]
+    struct StepAwareRunner {
+        configs: Vec<PreprocessingConfig>,
+    }
+
+    const REFERENCE: &str = "abcdefghij";
+
+    impl OcrRunner for StepAwareRunner {
+        fn run(&mut self, config: &AppConfig, _image: &Path) -> Result<String> {
+            let prep = config.preprocessing.clone().unwrap_or_default();
+            self.configs.push(prep.clone());
+            if prep.adjust_contrast {
+                bail!("contraste illisible");
+            }
+            let mut errors = 3;
+            if prep.binarize {
+                errors -= 2;
+            }
+            if prep.denoise {
+                errors -= 1;
+            }
+            if prep.deskew {
+                errors += 1;
+            }
+            Ok(format!("{}{}", "x".repeat(errors), &REFERENCE[errors..]))
+        }
+    }
+
+    fn base_with_three_steps() -> PreprocessingConfig {
+        PreprocessingConfig {
+            to_grayscale: false,
+            binarize: true,
+            adjust_contrast: false,
+            denoise: true,
+            deskew: true,
+            ..PreprocessingConfig::default()
+        }
+    }
+
+    fn enabled(config: &PreprocessingConfig) -> Vec<PreprocessingStep> {
+        PreprocessingStep::ALL
+            .into_iter()
+            .filter(|step| step.is_enabled(config))
+            .collect()
+    }
+
+    #[test]
+    fn test_variants_for_three_enabled_steps() {
+        let base = base_with_three_steps();
+
+        let variants = ablation_variants(&base, false);
+
+        let steps: Vec<PreprocessingStep> = variants.iter().map(|v| v.step).collect();
+        assert_eq!(
+            steps,
+            [
+                PreprocessingStep::Deskew,
+                PreprocessingStep::Denoise,
+                PreprocessingStep::Binarize
+            ]
+        );
+        for variant in &variants {
+            assert_eq!(variant.change, StepChange::Removed);
+            let mut expected = enabled(&base);
+            expected.retain(|step| *step != variant.step);
+            assert_eq!(enabled(&variant.config), expected);
+            // Les autres réglages sont conservés
+            assert_eq!(variant.config.max_pixels, base.max_pixels);
+        }
+
+        let with_additions = ablation_variants(&base, true);
+        assert_eq!(with_additions.len(), 5);
+        let added: Vec<PreprocessingStep> = with_additions[3..].iter().map(|v| v.step).collect();
+        assert_eq!(
+            added,
+            [PreprocessingStep::Grayscale, PreprocessingStep::Contrast]
+        );
+        assert!(with_additions[3].config.to_grayscale && with_additions[3].config.binarize);
+    }
+
+    #[test]
+    fn test_ablation_deltas_sorted_and_harmful_flagged() {
+        let mut runner = StepAwareRunner::default();
+
+        let report = ablation_study_with(
+            &mut runner,
+            Path::new("page.png"),
+            REFERENCE,
+            &base_with_three_steps(),
+            &OcrConfig::default(),
+            &AblationOptions::default(),
+        )
+        .unwrap();
+
+        // Base : 3 - 2 - 1 + 1 = 1 erreur sur 10
+        assert!((report.baseline_cer - 0.1).abs() < 1e-9);
+        assert_eq!(runner.configs.len(), 4);
+
+        let summary: Vec<(PreprocessingStep, i64)> = report
+            .impacts
+            .iter()
+            .map(|impact| (impact.step, (impact.delta * 100.0).round() as i64))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (PreprocessingStep::Binarize, 20),
+                (PreprocessingStep::Deskew, -10),
+                (PreprocessingStep::Denoise, 10),
+            ]
+        );
+        assert_eq!(report.impacts[0].verdict, StepVerdict::Helpful);
+        assert_eq!(report.harmful_steps(), [PreprocessingStep::Deskew]);
+        assert!(report.failures.is_empty());
+
+        let markdown = report.render_markdown();
+        assert!(markdown.contains("| binarize | retirée | 30.00% | +20.00 pts | utile |"));
+        assert!(markdown.contains("Étapes à désactiver (leur retrait améliore le CER) : deskew"));
+    }
+
+    #[test]
+    fn test_ablation_additions_and_variant_failures() {
+        let mut runner = StepAwareRunner::default();
+
+        let report = ablation_study_with(
+            &mut runner,
+            Path::new("page.png"),
+            REFERENCE,
+            &base_with_three_steps(),
+            &OcrConfig::default(),
+            &AblationOptions {
+                include_additions: true,
+            },
+        )
+        .unwrap();
+
+        let grayscale = report
+            .impacts
+            .iter()
+            .find(|impact| impact.step == PreprocessingStep::Grayscale)
+            .unwrap();
+        assert_eq!(grayscale.change, StepChange::Added);
+        assert_eq!(grayscale.verdict, StepVerdict::Neutral);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].step, PreprocessingStep::Contrast);
+        assert!(report.failures[0].error.contains("contraste"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["failures"][0]["change"], "added");
+        assert_eq!(json["impacts"][0]["step"], "binarize");
+    }
+
+    #[test]
+    fn test_ablation_fails_when_baseline_fails() {
+        let base = PreprocessingConfig {
+            adjust_contrast: true,
+            ..PreprocessingConfig::default()
+        };
+
+        let error = ablation_study_with(
+            &mut StepAwareRunner::default(),
+            Path::new("page.png"),
+            REFERENCE,
+            &base,
+            &OcrConfig::default(),
+            &AblationOptions::default(),
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().contains("configuration complète"));
+    }
+}
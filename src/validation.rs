@@ -0,0 +1,339 @@
+//! Vérifications préalables des fichiers images (mode batch).
+//!
+//! [`validate_image_file()`] détecte à moindre coût les fichiers vides,
+//! tronqués ou d'un format inconnu, sans décoder l'image : seuls les octets
+//! magiques et, pour PNG et JPEG, l'en-tête portant les dimensions sont lus.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::validation::{ImageFileError, validate_image_file};
+//! use std::path::Path;
+//!
+//! match validate_image_file(Path::new("scan.png")) {
+//!     Ok(info) => println!("{:?} ({} octets)", info.format, info.size_bytes),
+//!     Err(e) => match e.downcast_ref::<ImageFileError>() {
+//!         Some(reason) => println!("Ignorée: {}", reason),
+//!         None => return Err(e),
+//!     },
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::{Context, Result};
+use image::ImageFormat;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Largeur et hauteur minimales (en pixels) d'une image exploitable.
+///
+/// Tesseract refuse de traiter les images de moins de 3 pixels de large
+/// ("Image too small to scale").
+pub const MIN_IMAGE_DIMENSION: u32 = 3;
+
+/// Signature d'un fichier PNG.
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+/// Nombre d'octets lus pour reconnaître le format.
+const MAGIC_LEN: usize = 12;
+
+/// Raison pour laquelle un fichier image est écarté avant l'OCR.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ImageFileError {
+    /// Le fichier est vide (0 octet).
+    #[error("fichier vide")]
+    Empty,
+
+    /// Les octets magiques ne correspondent à aucun format connu.
+    #[error("format d'image inconnu")]
+    UnknownFormat,
+
+    /// L'en-tête est tronqué ou incohérent.
+    #[error("en-tête corrompu ou tronqué")]
+    CorruptHeader,
+
+    /// L'image est plus petite que [`MIN_IMAGE_DIMENSION`].
+    #[error("image trop petite ({width}×{height} pixels)")]
+    TooSmall {
+        /// Largeur lue dans l'en-tête.
+        width: u32,
+        /// Hauteur lue dans l'en-tête.
+        height: u32,
+    },
+}
+
+/// Informations lues par [`validate_image_file()`] sans décoder l'image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageFileInfo {
+    /// Format reconnu d'après les octets magiques.
+    pub format: ImageFormat,
+
+    /// Dimensions `(largeur, hauteur)` lues dans l'en-tête.
+    ///
+    /// Renseignées uniquement pour PNG et JPEG.
+    pub dimensions: Option<(u32, u32)>,
+
+    /// Taille du fichier en octets.
+    pub size_bytes: u64,
+}
+
+/// Vérifie qu'un fichier image est exploitable sans le décoder.
+///
+/// Le fichier doit être non vide, commencer par les octets magiques d'un
+/// format connu (PNG, JPEG, TIFF, BMP, GIF, WebP) et, pour PNG et JPEG,
+/// avoir un en-tête lisible dont les dimensions atteignent
+/// [`MIN_IMAGE_DIMENSION`].
+///
+/// # Arguments
+///
+/// * `path` - Chemin du fichier image
+///
+/// # Erreurs
+///
+/// Retourne une erreur contenant un [`ImageFileError`] (récupérable avec
+/// `downcast_ref`) si le fichier est écarté, ou une erreur d'entrée/sortie
+/// si le fichier ne peut pas être lu.
+pub fn validate_image_file(path: &Path) -> Result<ImageFileInfo> {
+    let size_bytes = fs::metadata(path)
+        .with_context(|| format!("Impossible de lire le fichier '{}'", path.display()))?
+        .len();
+    let invalid = |error: ImageFileError| {
+        anyhow::Error::new(error).context(format!("Fichier image invalide '{}'", path.display()))
+    };
+
+    if size_bytes == 0 {
+        return Err(invalid(ImageFileError::Empty));
+    }
+
+    let mut file = File::open(path)
+        .with_context(|| format!("Impossible d'ouvrir le fichier '{}'", path.display()))?;
+    let mut magic = Vec::with_capacity(MAGIC_LEN);
+    (&mut file)
+        .take(MAGIC_LEN as u64)
+        .read_to_end(&mut magic)
+        .with_context(|| format!("Impossible de lire le fichier '{}'", path.display()))?;
+
+    let format = detect_format(&magic).ok_or_else(|| invalid(ImageFileError::UnknownFormat))?;
+
+    let dimensions = match format {
+        ImageFormat::Png => Some(png_dimensions(&mut file)),
+        ImageFormat::Jpeg => Some(jpeg_dimensions(&mut file)),
+        _ => None,
+    }
+    .transpose()
+    .map_err(invalid)?;
+
+    if let Some((width, height)) = dimensions
+        && (width < MIN_IMAGE_DIMENSION || height < MIN_IMAGE_DIMENSION)
+    {
+        return Err(invalid(ImageFileError::TooSmall { width, height }));
+    }
+
+    Ok(ImageFileInfo {
+        format,
+        dimensions,
+        size_bytes,
+    })
+}
+
+/// Reconnaît le format d'après les premiers octets du fichier.
+fn detect_format(magic: &[u8]) -> Option<ImageFormat> {
+    if magic.starts_with(&PNG_SIGNATURE) {
+        Some(ImageFormat::Png)
+    } else if magic.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(ImageFormat::Jpeg)
+    } else if magic.starts_with(b"II*\0") || magic.starts_with(b"MM\0*") {
+        Some(ImageFormat::Tiff)
+    } else if magic.starts_with(b"BM") {
+        Some(ImageFormat::Bmp)
+    } else if magic.starts_with(b"GIF87a") || magic.starts_with(b"GIF89a") {
+        Some(ImageFormat::Gif)
+    } else if magic.len() >= 12 && magic.starts_with(b"RIFF") && &magic[8..12] == b"WEBP" {
+        Some(ImageFormat::WebP)
+    } else {
+        None
+    }
+}
+
+/// Lit les dimensions dans le chunk IHDR, qui suit immédiatement la signature PNG.
+fn png_dimensions(file: &mut File) -> std::result::Result<(u32, u32), ImageFileError> {
+    // Longueur (4) + type (4) + largeur (4) + hauteur (4)
+    let mut header = [0u8; 16];
+    file.seek(SeekFrom::Start(PNG_SIGNATURE.len() as u64))
+        .and_then(|_| file.read_exact(&mut header))
+        .map_err(|_| ImageFileError::CorruptHeader)?;
+
+    if header[0..4] != 13u32.to_be_bytes() || &header[4..8] != b"IHDR" {
+        return Err(ImageFileError::CorruptHeader);
+    }
+
+    let width = u32::from_be_bytes([header[8], header[9], header[10], header[11]]);
+    let height = u32::from_be_bytes([header[12], header[13], header[14], header[15]]);
+    if width == 0 || height == 0 {
+        return Err(ImageFileError::CorruptHeader);
+    }
+
+    Ok((width, height))
+}
+
+/// Parcourt les segments JPEG jusqu'au premier marqueur SOF (Start Of Frame).
+fn jpeg_dimensions(file: &mut File) -> std::result::Result<(u32, u32), ImageFileError> {
+    let corrupt = |_| ImageFileError::CorruptHeader;
+    // Après le marqueur SOI (FF D8)
+    file.seek(SeekFrom::Start(2)).map_err(corrupt)?;
+
+    loop {
+        let mut byte = [0u8; 1];
+        file.read_exact(&mut byte).map_err(corrupt)?;
+        if byte[0] != 0xFF {
+            return Err(ImageFileError::CorruptHeader);
+        }
+
+        // Les octets 0xFF répétés sont du remplissage
+        let mut marker = 0xFF;
+        while marker == 0xFF {
+            file.read_exact(&mut byte).map_err(corrupt)?;
+            marker = byte[0];
+        }
+
+        match marker {
+            // Marqueurs autonomes (sans longueur) : TEM, RSTn
+            0x01 | 0xD0..=0xD7 => continue,
+            // Fin d'image ou début des données avant toute trame
+            0xD9 | 0xDA => return Err(ImageFileError::CorruptHeader),
+            _ => {}
+        }
+
+        let mut length = [0u8; 2];
+        file.read_exact(&mut length).map_err(corrupt)?;
+        let length = u16::from_be_bytes(length);
+        if length < 2 {
+            return Err(ImageFileError::CorruptHeader);
+        }
+
+        // SOF0 à SOF15, hors DHT (C4), JPG (C8) et DAC (CC)
+        if matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            // Précision (1) + hauteur (2) + largeur (2)
+            let mut frame = [0u8; 5];
+            file.read_exact(&mut frame).map_err(corrupt)?;
+            let height = u32::from(u16::from_be_bytes([frame[1], frame[2]]));
+            let width = u32::from(u16::from_be_bytes([frame[3], frame[4]]));
+            if width == 0 || height == 0 {
+                return Err(ImageFileError::CorruptHeader);
+            }
+            return Ok((width, height));
+        }
+
+        file.seek(SeekFrom::Current(i64::from(length) - 2))
+            .map_err(corrupt)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, GrayImage, Luma};
+    use std::io::Cursor;
+
+    fn encode(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+        let img = DynamicImage::ImageLuma8(GrayImage::from_pixel(width, height, Luma([200])));
+        let mut bytes = Vec::new();
+        img.write_to(&mut Cursor::new(&mut bytes), format).unwrap();
+        bytes
+    }
+
+    fn validate_bytes(bytes: &[u8]) -> Result<ImageFileInfo> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("image");
+        fs::write(&path, bytes).unwrap();
+        validate_image_file(&path)
+    }
+
+    fn reason(result: Result<ImageFileInfo>) -> ImageFileError {
+        result
+            .unwrap_err()
+            .downcast_ref::<ImageFileError>()
+            .cloned()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_validate_valid_png() {
+        let bytes = encode(40, 20, ImageFormat::Png);
+        let info = validate_bytes(&bytes).unwrap();
+
+        assert_eq!(info.format, ImageFormat::Png);
+        assert_eq!(info.dimensions, Some((40, 20)));
+        assert_eq!(info.size_bytes, bytes.len() as u64);
+    }
+
+    #[test]
+    fn test_validate_valid_jpeg() {
+        let info = validate_bytes(&encode(32, 24, ImageFormat::Jpeg)).unwrap();
+
+        assert_eq!(info.format, ImageFormat::Jpeg);
+        assert_eq!(info.dimensions, Some((32, 24)));
+    }
+
+    #[test]
+    fn test_validate_empty_file() {
+        assert_eq!(reason(validate_bytes(&[])), ImageFileError::Empty);
+    }
+
+    #[test]
+    fn test_validate_truncated_png_header() {
+        let bytes = encode(40, 20, ImageFormat::Png);
+        // Signature complète, IHDR coupé au milieu de la largeur
+        let truncated = &bytes[..18];
+
+        assert_eq!(
+            reason(validate_bytes(truncated)),
+            ImageFileError::CorruptHeader
+        );
+    }
+
+    #[test]
+    fn test_validate_truncated_jpeg() {
+        let bytes = encode(32, 24, ImageFormat::Jpeg);
+
+        assert_eq!(
+            reason(validate_bytes(&bytes[..4])),
+            ImageFileError::CorruptHeader
+        );
+    }
+
+    #[test]
+    fn test_validate_one_pixel_png() {
+        assert_eq!(
+            reason(validate_bytes(&encode(1, 1, ImageFormat::Png))),
+            ImageFileError::TooSmall {
+                width: 1,
+                height: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_unknown_format() {
+        assert_eq!(
+            reason(validate_bytes(b"ceci n'est pas une image")),
+            ImageFileError::UnknownFormat
+        );
+    }
+
+    #[test]
+    fn test_validate_other_formats_without_dimensions() {
+        let info = validate_bytes(&encode(8, 8, ImageFormat::Bmp)).unwrap();
+
+        assert_eq!(info.format, ImageFormat::Bmp);
+        assert_eq!(info.dimensions, None);
+    }
+
+    #[test]
+    fn test_validate_missing_file_is_not_typed() {
+        let error = validate_image_file(Path::new("resources/inexistante.png")).unwrap_err();
+
+        assert!(error.downcast_ref::<ImageFileError>().is_none());
+    }
+}
@@ -0,0 +1,198 @@
+//! Préchauffage et vérification de santé du moteur OCR.
+//!
+//! Dans un service de longue durée, la première requête paie le chargement
+//! des modèles Tesseract et une installation défaillante n'apparaît qu'au
+//! premier trafic. [`OcrEngine::warm_up()`] effectue une extraction complète
+//! sur une petite image embarquée ([`WARM_UP_IMAGE`]) et vérifie que le mot
+//! attendu est reconnu ; [`OcrEngine::health_check()`], moins coûteux, se
+//! contente de réinitialiser Tesseract avec la langue configurée.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::ocr::OcrEngine;
+//!
+//! let engine = OcrEngine::new(OcrConfig::default())?;
+//! let report = engine.warm_up()?;
+//! println!(
+//!     "Initialisation : {:?}, extraction : {:?}",
+//!     report.init, report.extraction
+//! );
+//! engine.health_check()?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::ocr::OcrEngine;
+use anyhow::{Context, Result, bail};
+use std::time::{Duration, Instant};
+
+/// Mot dessiné dans [`WARM_UP_IMAGE`].
+pub const WARM_UP_WORD: &str = "Bonjour";
+
+/// Image PNG (144×64, niveaux de gris) contenant [`WARM_UP_WORD`].
+///
+/// Produite par le rendu synthétique de `testutil` (police embarquée,
+/// options par défaut) : `render_text_image(WARM_UP_WORD, &RenderOptions::default())`.
+/// Un test vérifie que les pixels correspondent toujours à ce rendu.
+pub const WARM_UP_IMAGE: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x90, 0x00, 0x00, 0x00, 0x40, 0x08, 0x00, 0x00, 0x00, 0x00, 0xd0, 0x13, 0xa1,
+    0x4b, 0x00, 0x00, 0x00, 0xad, 0x49, 0x44, 0x41, 0x54, 0x78, 0xda, 0xed, 0xd7, 0xeb, 0x0a, 0x80,
+    0x20, 0x0c, 0x86, 0x61, 0x07, 0xdd, 0xff, 0x2d, 0x7f, 0x11, 0x54, 0x34, 0xcd, 0xb2, 0x13, 0x68,
+    0xbe, 0xfb, 0x63, 0xe1, 0x81, 0x07, 0x37, 0xd2, 0x06, 0x85, 0xba, 0x62, 0xa8, 0xcc, 0x03, 0x08,
+    0x10, 0x20, 0x40, 0x80, 0x00, 0x01, 0xea, 0x11, 0x64, 0x73, 0x7b, 0xed, 0xa6, 0x64, 0x41, 0x5f,
+    0x81, 0x48, 0x59, 0x83, 0x20, 0xdd, 0xa8, 0x0a, 0x7d, 0x09, 0x22, 0x65, 0xcd, 0x83, 0xcc, 0x55,
+    0xc9, 0x54, 0x5b, 0x16, 0x7c, 0xd5, 0xf8, 0xf7, 0x74, 0x7c, 0xdc, 0xc6, 0xf3, 0x5b, 0xdf, 0x21,
+    0x40, 0xe7, 0x35, 0xb3, 0x6d, 0x97, 0xa7, 0xed, 0x77, 0x4a, 0xeb, 0x09, 0xb8, 0x37, 0x3e, 0x5d,
+    0x5f, 0xd9, 0x7e, 0x52, 0xf6, 0x0f, 0xd0, 0x59, 0x0d, 0x3c, 0x3d, 0xb7, 0x74, 0xb0, 0x0a, 0x29,
+    0x03, 0x94, 0x0b, 0x63, 0x87, 0xba, 0x02, 0xf9, 0xb3, 0x4c, 0xd1, 0x7b, 0x3a, 0xda, 0xf7, 0x68,
+    0x77, 0xbe, 0x0a, 0xab, 0x88, 0x94, 0x35, 0x07, 0xb2, 0x37, 0xfe, 0xa7, 0xf2, 0x37, 0x64, 0x52,
+    0x06, 0xa8, 0x2c, 0x54, 0x1b, 0x88, 0x94, 0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0xca,
+    0x63, 0x04, 0x69, 0x3b, 0x29, 0xf4, 0x76, 0x14, 0xbe, 0x26, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45,
+    0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+];
+
+/// Résultat de [`OcrEngine::warm_up()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarmUpReport {
+    /// Durée de l'initialisation de Tesseract (chargement des modèles).
+    pub init: Duration,
+
+    /// Durée de l'extraction complète de l'image embarquée.
+    pub extraction: Duration,
+
+    /// Texte extrait de l'image embarquée.
+    pub text: String,
+}
+
+impl WarmUpReport {
+    /// Durée totale du préchauffage.
+    pub fn total(&self) -> Duration {
+        self.init + self.extraction
+    }
+}
+
+impl OcrEngine {
+    /// Préchauffe le moteur par une extraction réelle sur [`WARM_UP_IMAGE`].
+    ///
+    /// Initialise d'abord Tesseract avec la langue configurée (durée
+    /// [`WarmUpReport::init`]), puis extrait le texte de l'image embarquée
+    /// comme pour toute autre image, prétraitement et nettoyage compris
+    /// (durée [`WarmUpReport::extraction`]). Le cache de résultats et les
+    /// artefacts de débogage du moteur ne sont pas utilisés.
+    ///
+    /// # Exemple
+    ///
+    /// ```no_run
+    /// use text_recognition::config::OcrConfig;
+    /// use text_recognition::ocr::OcrEngine;
+    ///
+    /// let engine = OcrEngine::new(OcrConfig::default())?;
+    /// let report = engine.warm_up()?;
+    /// println!("Moteur prêt en {:?}", report.total());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si Tesseract ne peut pas être initialisé, si
+    /// l'extraction échoue ou si [`WARM_UP_WORD`] n'est pas reconnu.
+    pub fn warm_up(&self) -> Result<WarmUpReport> {
+        let start = Instant::now();
+        self.health_check()?;
+        let init = start.elapsed();
+
+        let image = image::load_from_memory(WARM_UP_IMAGE)
+            .context("Image de préchauffage embarquée invalide")?;
+        let engine = match self.preprocessing_config() {
+            Some(preprocessing) => {
+                OcrEngine::with_preprocessing(self.config().clone(), preprocessing.clone())?
+            }
+            None => OcrEngine::new(self.config().clone())?,
+        };
+        let start = Instant::now();
+        let text = engine
+            .extract_text_from_image(&image)
+            .context("Échec de l'extraction de préchauffage")?;
+        let extraction = start.elapsed();
+
+        if !contains_word(&text, WARM_UP_WORD) {
+            bail!(
+                "Le mot de préchauffage '{}' n'a pas été reconnu (texte extrait : {:?})",
+                WARM_UP_WORD,
+                text.trim()
+            );
+        }
+
+        Ok(WarmUpReport {
+            init,
+            extraction,
+            text,
+        })
+    }
+
+    /// Vérifie que Tesseract s'initialise avec la langue configurée.
+    ///
+    /// Moins coûteux que [`warm_up()`](Self::warm_up) : aucune image n'est
+    /// analysée. Les variables Tesseract de la configuration sont appliquées,
+    /// une variable invalide est donc aussi signalée.
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si les données linguistiques sont absentes ou si
+    /// l'initialisation de Tesseract échoue.
+    pub fn health_check(&self) -> Result<()> {
+        let config = self.config();
+        self.init_tesseract(&config.language, config.dpi)
+            .with_context(|| {
+                format!(
+                    "Tesseract indisponible pour la langue '{}'",
+                    config.language
+                )
+            })?;
+        Ok(())
+    }
+}
+
+/// Indique si `text` contient `word`, sans tenir compte de la casse ni de la ponctuation.
+fn contains_word(text: &str, word: &str) -> bool {
+    let word = word.to_lowercase();
+    text.split(|c: char| !c.is_alphanumeric())
+        .any(|token| token.to_lowercase() == word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OcrConfig;
+    use crate::testutil::{RenderOptions, render_text_image};
+
+    #[test]
+    fn test_warm_up_image_matches_renderer() {
+        assert!(WARM_UP_IMAGE.len() < 5 * 1024);
+        let embedded = image::load_from_memory(WARM_UP_IMAGE).unwrap().to_luma8();
+        let rendered = render_text_image(WARM_UP_WORD, &RenderOptions::default()).to_luma8();
+        assert_eq!(embedded, rendered);
+    }
+
+    #[test]
+    fn test_contains_word() {
+        assert!(contains_word("Bonjour\n", "Bonjour"));
+        assert!(contains_word("« bonjour ! »", "Bonjour"));
+        assert!(!contains_word("Bonjours", "Bonjour"));
+        assert!(!contains_word("", "Bonjour"));
+    }
+
+    #[test]
+    fn test_health_check_fails_with_unknown_language() {
+        let config = OcrConfig {
+            language: "langue_inexistante".to_string(),
+            ..OcrConfig::default()
+        };
+        let engine = OcrEngine::new(config).unwrap();
+        let err = engine.health_check().unwrap_err();
+        assert!(format!("{:#}", err).contains("langue_inexistante"));
+        assert!(engine.warm_up().is_err());
+    }
+}
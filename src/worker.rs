@@ -0,0 +1,549 @@
+//! Extraction dans un processus isolé.
+//!
+//! Une image malformée peut faire planter la bibliothèque Tesseract (ou
+//! Leptonica) et emporter tout le processus : en mode batch, un seul fichier
+//! corrompu arrête alors le traitement de milliers d'autres. Avec
+//! [`OcrEngine::with_isolation()`], chaque extraction est confiée à un
+//! processus enfant : un plantage n'est plus qu'une erreur propre à l'image.
+//!
+//! Le protocole est volontairement minimal : le parent écrit une
+//! [`WorkerJob`] en JSON sur l'entrée standard de l'enfant, puis ferme
+//! celle-ci ; l'enfant répond par un [`WorkerResult`] en JSON sur sa sortie
+//! standard et se termine. Les deux messages portent la version du
+//! protocole ([`WORKER_PROTOCOL_VERSION`]) pour qu'un exécutable d'une autre
+//! version soit détecté au lieu d'être mal compris.
+//!
+//! # Exemple
+//!
+//! ```no_run
+//! use text_recognition::config::OcrConfig;
+//! use text_recognition::ocr::OcrEngine;
+//! use text_recognition::worker::WorkerCommand;
+//! use std::path::Path;
+//!
+//! // L'exécutable courant doit appeler run_worker() lorsqu'il reçoit WORKER_ARG
+//! let engine = OcrEngine::new(OcrConfig::default())?
+//!     .with_isolation(WorkerCommand::current_exe()?);
+//! let outcome = engine.extract_text_detailed(Path::new("scan.png"))?;
+//! println!("{}", outcome.text);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::cancellation::wait_with_timeout;
+use crate::config::OcrConfig;
+use crate::diagnostics::Warning;
+use crate::ocr::{OcrEngine, OcrError, OcrOutcome};
+use crate::preprocessing::PreprocessingConfig;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Version du protocole d'échange entre le parent et le processus enfant.
+pub const WORKER_PROTOCOL_VERSION: u32 = 1;
+
+/// Argument (caché) qui fait passer l'exécutable en mode processus enfant.
+pub const WORKER_ARG: &str = "__worker";
+
+/// Délai laissé à l'enfant, au-delà de celui de l'extraction, pour démarrer et répondre.
+///
+/// L'enfant borne lui-même son extraction et signale un dépassement par un
+/// [`WorkerOutcome::Failure`] ; le parent ne le tue qu'en dernier recours.
+const STARTUP_GRACE: Duration = Duration::from_secs(5);
+
+/// Nombre de lignes de la sortie d'erreur de l'enfant reprises dans un message d'échec.
+const STDERR_TAIL_LINES: usize = 3;
+
+/// Tâche confiée à un processus enfant : une image et la configuration du moteur.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerJob {
+    /// Version du protocole ([`WORKER_PROTOCOL_VERSION`]).
+    pub version: u32,
+    /// Image à analyser.
+    pub image: PathBuf,
+    /// Configuration du moteur OCR.
+    pub config: OcrConfig,
+    /// Configuration du prétraitement, le cas échéant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preprocessing: Option<PreprocessingConfig>,
+    /// Extraire avec correction d'orientation
+    /// ([`OcrEngine::extract_text_auto_rotated()`]).
+    #[serde(default)]
+    pub auto_rotate: bool,
+    /// Délai maximal de l'extraction, en millisecondes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl WorkerJob {
+    /// Crée une tâche pour la version courante du protocole.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - Image à analyser
+    /// * `config` - Configuration du moteur OCR
+    /// * `preprocessing` - Configuration du prétraitement, le cas échéant
+    pub fn new(
+        image: impl Into<PathBuf>,
+        config: OcrConfig,
+        preprocessing: Option<PreprocessingConfig>,
+    ) -> Self {
+        Self {
+            version: WORKER_PROTOCOL_VERSION,
+            image: image.into(),
+            config,
+            preprocessing,
+            auto_rotate: false,
+            timeout_ms: None,
+        }
+    }
+
+    /// Délai maximal de l'extraction, s'il est fixé.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(Duration::from_millis)
+    }
+}
+
+/// Réponse d'un processus enfant à une [`WorkerJob`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkerResult {
+    /// Version du protocole ([`WORKER_PROTOCOL_VERSION`]).
+    pub version: u32,
+    /// Résultat de l'extraction.
+    #[serde(flatten)]
+    pub outcome: WorkerOutcome,
+}
+
+impl WorkerResult {
+    /// Crée une réponse pour la version courante du protocole.
+    ///
+    /// # Arguments
+    ///
+    /// * `outcome` - Résultat de l'extraction
+    pub fn new(outcome: WorkerOutcome) -> Self {
+        Self {
+            version: WORKER_PROTOCOL_VERSION,
+            outcome,
+        }
+    }
+}
+
+/// Résultat d'une extraction dans un processus enfant.
+///
+/// Comme pour [`CachedResult`](crate::result_cache::CachedResult), seuls
+/// le texte et les messages des avertissements sont transmis : le rapport
+/// de prétraitement n'est pas restitué.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum WorkerOutcome {
+    /// L'extraction a abouti.
+    Success {
+        /// Texte extrait.
+        text: String,
+        /// Messages des avertissements de l'extraction.
+        #[serde(default)]
+        warnings: Vec<String>,
+    },
+    /// L'extraction a échoué sans faire planter l'enfant.
+    Failure {
+        /// Message d'erreur, avec ses causes.
+        error: String,
+        /// `true` si l'extraction a dépassé son délai.
+        #[serde(default)]
+        timed_out: bool,
+    },
+}
+
+/// Traite une tâche lue sur `input` et écrit la réponse sur `output`.
+///
+/// Point d'entrée du processus enfant : l'exécutable l'appelle lorsqu'il
+/// est lancé avec [`WORKER_ARG`]. Une tâche illisible ou une extraction en
+/// échec produisent une réponse [`WorkerOutcome::Failure`] ; seuls les
+/// échecs de lecture et d'écriture des tubes sont des erreurs.
+///
+/// # Arguments
+///
+/// * `input` - Entrée de la tâche (l'entrée standard)
+/// * `output` - Sortie de la réponse (la sortie standard)
+///
+/// # Erreurs
+///
+/// Retourne une erreur si la tâche ne peut pas être lue ou la réponse écrite.
+pub fn run_worker<R: Read, W: Write>(mut input: R, mut output: W) -> Result<()> {
+    let mut raw = String::new();
+    input
+        .read_to_string(&mut raw)
+        .context("Lecture de la tâche d'extraction impossible")?;
+
+    let outcome = match serde_json::from_str::<WorkerJob>(&raw) {
+        Ok(job) => execute_job(&job),
+        Err(e) => WorkerOutcome::Failure {
+            error: format!("Tâche d'extraction illisible : {}", e),
+            timed_out: false,
+        },
+    };
+
+    serde_json::to_writer(&mut output, &WorkerResult::new(outcome))
+        .context("Écriture du résultat de l'extraction impossible")?;
+    output
+        .flush()
+        .context("Écriture du résultat de l'extraction impossible")
+}
+
+/// Exécute une tâche dans le processus courant.
+fn execute_job(job: &WorkerJob) -> WorkerOutcome {
+    let result = if job.version == WORKER_PROTOCOL_VERSION {
+        extract_job(job)
+    } else {
+        Err(anyhow::anyhow!(
+            "Version du protocole non prise en charge : {} (attendu : {})",
+            job.version,
+            WORKER_PROTOCOL_VERSION
+        ))
+    };
+
+    match result {
+        Ok(outcome) => WorkerOutcome::Success {
+            text: outcome.text,
+            warnings: outcome
+                .warnings
+                .into_iter()
+                .map(|warning| warning.message)
+                .collect(),
+        },
+        Err(e) => WorkerOutcome::Failure {
+            timed_out: matches!(e.downcast_ref::<OcrError>(), Some(OcrError::Timeout(_))),
+            error: format!("{:#}", e),
+        },
+    }
+}
+
+/// Construit le moteur de la tâche et extrait le texte de son image.
+fn extract_job(job: &WorkerJob) -> Result<OcrOutcome> {
+    let engine = match job.preprocessing {
+        Some(ref preprocessing) => {
+            OcrEngine::with_preprocessing(job.config.clone(), preprocessing.clone())?
+        }
+        None => OcrEngine::new(job.config.clone())?,
+    };
+    let engine = engine.with_timeout(job.timeout());
+
+    let image = job.image.clone();
+    let auto_rotate = job.auto_rotate;
+    let extract = move |engine: &OcrEngine| {
+        if auto_rotate {
+            engine.extract_text_auto_rotated(&image)
+        } else {
+            engine.extract_text_detailed(&image)
+        }
+    };
+    // L'OCR par la bibliothèque ne s'interrompt pas : le thread est
+    // abandonné au-delà du délai et disparaît avec le processus
+    match job.timeout() {
+        Some(timeout) => engine.run_with_deadline(timeout, extract),
+        None => extract(&engine),
+    }
+}
+
+/// Commande qui lance un processus enfant d'extraction.
+///
+/// Le programme est lancé avec `args` : il doit lire une [`WorkerJob`] sur
+/// son entrée standard et répondre sur sa sortie standard (voir
+/// [`run_worker()`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerCommand {
+    /// Programme à lancer.
+    program: OsString,
+    /// Arguments du programme.
+    args: Vec<OsString>,
+}
+
+impl WorkerCommand {
+    /// Crée une commande à partir d'un programme et de ses arguments.
+    ///
+    /// # Arguments
+    ///
+    /// * `program` - Programme à lancer
+    /// * `args` - Arguments du programme
+    pub fn new<I, S>(program: impl Into<OsString>, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Relance l'exécutable courant avec [`WORKER_ARG`].
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne une erreur si le chemin de l'exécutable courant est inconnu.
+    pub fn current_exe() -> Result<Self> {
+        let program =
+            std::env::current_exe().context("Chemin de l'exécutable courant introuvable")?;
+        Ok(Self::new(program, [WORKER_ARG]))
+    }
+
+    /// Programme lancé par la commande.
+    pub fn program(&self) -> &Path {
+        Path::new(&self.program)
+    }
+
+    /// Confie `job` à un nouveau processus enfant et attend sa réponse.
+    ///
+    /// L'enfant est tué s'il n'a pas répondu dans le délai de la tâche
+    /// (augmenté d'une marge de démarrage). Un arrêt anormal (plantage,
+    /// code de sortie non nul) ou une réponse illisible sont des erreurs
+    /// propres à cette tâche : le processus courant n'est pas affecté.
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - Tâche à exécuter
+    ///
+    /// # Erreurs
+    ///
+    /// Retourne [`OcrError::Timeout`] si l'enfant a dépassé le délai, ou une
+    /// erreur si l'enfant ne peut pas être lancé, s'arrête anormalement,
+    /// répond de façon illisible ou signale l'échec de l'extraction.
+    pub fn run(&self, job: &WorkerJob) -> Result<OcrOutcome> {
+        let payload =
+            serde_json::to_vec(job).context("Sérialisation de la tâche d'extraction impossible")?;
+
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| {
+                format!(
+                    "Impossible de lancer le processus d'extraction '{}'",
+                    self.program().display()
+                )
+            })?;
+        // La tâche tient dans le tampon du tube ; fermer l'entrée signale sa fin
+        let sent = child
+            .stdin
+            .take()
+            .map(|mut stdin| stdin.write_all(&payload));
+        let output =
+            wait_with_timeout(child, job.timeout().map(|timeout| timeout + STARTUP_GRACE))?;
+
+        // Un enfant mort avant d'avoir lu sa tâche est signalé par son statut
+        if !output.status.success() {
+            bail!(
+                "Le processus d'extraction s'est arrêté anormalement ({}){}",
+                output.status,
+                stderr_tail(&output.stderr)
+            );
+        }
+        if let Some(Err(e)) = sent {
+            return Err(e).context("Envoi de la tâche au processus d'extraction impossible");
+        }
+
+        parse_result(&output.stdout)?.into_outcome(job)
+    }
+}
+
+impl WorkerResult {
+    /// Convertit la réponse en résultat d'extraction.
+    fn into_outcome(self, job: &WorkerJob) -> Result<OcrOutcome> {
+        match self.outcome {
+            WorkerOutcome::Success { text, warnings } => Ok(OcrOutcome {
+                text,
+                warnings: warnings.into_iter().map(Warning::new).collect(),
+                preprocessing: None,
+                cache_hit: false,
+            }),
+            WorkerOutcome::Failure {
+                error,
+                timed_out: true,
+            } => Err(
+                anyhow::Error::new(OcrError::Timeout(job.timeout().unwrap_or_default()))
+                    .context(error),
+            ),
+            WorkerOutcome::Failure { error, .. } => Err(anyhow::anyhow!(error)),
+        }
+    }
+}
+
+/// Analyse la réponse d'un processus enfant en vérifiant sa version.
+fn parse_result(stdout: &[u8]) -> Result<WorkerResult> {
+    /// Seul champ lu avant de connaître le format du reste de la réponse.
+    #[derive(Deserialize)]
+    struct VersionProbe {
+        version: u32,
+    }
+
+    let unreadable = || {
+        let text = String::from_utf8_lossy(stdout);
+        let excerpt: String = text.trim().chars().take(80).collect();
+        format!(
+            "Réponse illisible du processus d'extraction : '{}'",
+            excerpt
+        )
+    };
+    let probe: VersionProbe = serde_json::from_slice(stdout).with_context(unreadable)?;
+    if probe.version != WORKER_PROTOCOL_VERSION {
+        bail!(
+            "Version du protocole du processus d'extraction non prise en charge : {} (attendu : {})",
+            probe.version,
+            WORKER_PROTOCOL_VERSION
+        );
+    }
+    serde_json::from_slice(stdout).with_context(unreadable)
+}
+
+/// Dernières lignes non vides de la sortie d'erreur, précédées de « : ».
+fn stderr_tail(stderr: &[u8]) -> String {
+    let text = String::from_utf8_lossy(stderr);
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        return String::new();
+    }
+    let start = lines.len().saturating_sub(STDERR_TAIL_LINES);
+    format!(" : {}", lines[start..].join(" / "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Commande d'un enfant factice exécutant le script shell `script`.
+    fn shell_worker(script: &str) -> WorkerCommand {
+        WorkerCommand::new("/bin/sh", ["-c", script])
+    }
+
+    fn job(image: &str) -> WorkerJob {
+        WorkerJob::new(image, OcrConfig::default(), None)
+    }
+
+    #[test]
+    fn test_job_and_result_serde_round_trip() {
+        let job = WorkerJob {
+            auto_rotate: true,
+            timeout_ms: Some(1500),
+            preprocessing: Some(PreprocessingConfig::default()),
+            ..job("scans/page-1.png")
+        };
+        let json = serde_json::to_value(&job).unwrap();
+        let parsed: WorkerJob = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+        assert_eq!(parsed.image, PathBuf::from("scans/page-1.png"));
+        assert!(parsed.auto_rotate);
+        assert_eq!(job.timeout(), Some(Duration::from_millis(1500)));
+
+        for outcome in [
+            WorkerOutcome::Success {
+                text: "Bonjour".to_string(),
+                warnings: vec!["Empty page!!".to_string()],
+            },
+            WorkerOutcome::Failure {
+                error: "image corrompue".to_string(),
+                timed_out: true,
+            },
+        ] {
+            let result = WorkerResult::new(outcome);
+            let json = serde_json::to_string(&result).unwrap();
+            assert!(json.contains("\"version\":1"));
+            assert_eq!(serde_json::from_str::<WorkerResult>(&json).unwrap(), result);
+        }
+    }
+
+    #[test]
+    fn test_run_worker_reports_unreadable_job_as_failure() {
+        let mut output = Vec::new();
+        run_worker(&b"pas du json"[..], &mut output).unwrap();
+        let result: WorkerResult = serde_json::from_slice(&output).unwrap();
+        assert!(matches!(
+            result.outcome,
+            WorkerOutcome::Failure { ref error, timed_out: false } if error.contains("illisible")
+        ));
+    }
+
+    #[test]
+    fn test_parse_result_rejects_other_version() {
+        let error = parse_result(br#"{"version":2,"status":"success","text":""}"#).unwrap_err();
+        assert!(error.to_string().contains("Version du protocole"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_nonzero_exit_is_an_error() {
+        let worker = shell_worker("cat >/dev/null; echo 'Segmentation fault' >&2; exit 139");
+        let error = worker.run(&job("page.png")).unwrap_err().to_string();
+        assert!(error.contains("arrêté anormalement"), "{}", error);
+        assert!(error.contains("Segmentation fault"), "{}", error);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_garbage_output_is_an_error() {
+        let worker = shell_worker("cat >/dev/null; echo 'bavardage inattendu'");
+        let error = worker.run(&job("page.png")).unwrap_err().to_string();
+        assert!(error.contains("Réponse illisible"), "{}", error);
+        assert!(error.contains("bavardage inattendu"), "{}", error);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_child_failure_and_timeout_are_reported() {
+        let worker = shell_worker(
+            r#"cat >/dev/null; echo '{"version":1,"status":"failure","error":"délai dépassé","timed_out":true}'"#,
+        );
+        let job = WorkerJob {
+            timeout_ms: Some(2000),
+            ..job("page.png")
+        };
+        let error = worker.run(&job).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<OcrError>(),
+            Some(OcrError::Timeout(_))
+        ));
+        assert!(error.to_string().contains("délai dépassé"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_crash_on_second_image_spares_the_others() {
+        use crate::pool::OcrEnginePool;
+
+        // Plante (SIGSEGV) sur la deuxième image, répond normalement aux autres
+        let worker = shell_worker(
+            r#"job=$(cat)
+case "$job" in
+  *page-2.png*) echo 'Leptonica: image corrompue' >&2; kill -SEGV $$ ;;
+esac
+echo '{"version":1,"status":"success","text":"texte isolé","warnings":["Empty page!!"]}'"#,
+        );
+        let engine = OcrEngine::new(OcrConfig::default())
+            .unwrap()
+            .with_isolation(worker);
+        let pool = OcrEnginePool::from_engine(engine, 2).unwrap();
+        let paths: Vec<PathBuf> = (1..=3)
+            .map(|index| PathBuf::from(format!("page-{}.png", index)))
+            .collect();
+
+        let results = pool.batch_extract(&paths);
+        assert_eq!(results.len(), 3);
+        for index in [0, 2] {
+            let outcome = results[index].as_ref().unwrap();
+            assert_eq!(outcome.text, "texte isolé");
+            assert_eq!(outcome.warnings, vec![Warning::new("Empty page!!")]);
+            assert!(!outcome.cache_hit);
+        }
+        let error = results[1].as_ref().unwrap_err().to_string();
+        assert!(error.contains("arrêté anormalement"), "{}", error);
+        assert!(error.contains("image corrompue"), "{}", error);
+    }
+}
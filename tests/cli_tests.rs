@@ -0,0 +1,223 @@
+//! Tests d'intégration du binaire en ligne de commande.
+//!
+//! Ces tests lancent le binaire compilé et vérifient la lecture d'une image
+//! sur l'entrée standard (IMAGE = "-").
+
+#![cfg(feature = "tesseract")]
+
+use image::{DynamicImage, GrayImage, ImageFormat, Luma};
+use std::io::{Cursor, Write};
+use std::process::{Command, Output, Stdio};
+
+/// Lance le binaire avec les arguments donnés et envoie `stdin` sur son entrée standard.
+fn run_with_stdin(args: &[&str], stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_text-recognition"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Échec du lancement du binaire");
+
+    // Le binaire peut refuser les arguments avant d'avoir lu l'entrée : ignorer un tube fermé
+    let _ = child
+        .stdin
+        .take()
+        .expect("Entrée standard non disponible")
+        .write_all(stdin);
+
+    child
+        .wait_with_output()
+        .expect("Échec de l'attente du binaire")
+}
+
+/// Encode une image en niveaux de gris uniforme au format PNG.
+fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+    let img = DynamicImage::ImageLuma8(GrayImage::from_pixel(width, height, Luma([180])));
+    let mut bytes = Vec::new();
+    img.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .expect("Échec de l'encodage PNG");
+    bytes
+}
+
+/// Teste le prétraitement d'une image lue sur stdin (sans Tesseract).
+#[test]
+fn test_stdin_preprocess_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let out_path = dir.path().join("out.png");
+    let out_str = out_path.to_str().unwrap();
+
+    let output = run_with_stdin(
+        &[
+            "-",
+            "--preprocess",
+            "--grayscale",
+            "--preprocess-only",
+            out_str,
+        ],
+        &png_bytes(40, 20),
+    );
+
+    assert!(
+        output.status.success(),
+        "Échec : {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Image prétraitée sauvegardée"));
+
+    let saved = image::open(&out_path).unwrap();
+    assert_eq!((saved.width(), saved.height()), (40, 20));
+}
+
+/// Teste l'extraction de texte d'une image envoyée sur stdin.
+#[test]
+fn test_stdin_extract_text() {
+    let bytes = std::fs::read("resources/simple/img-4.png").unwrap();
+
+    let output = run_with_stdin(&["-"], &bytes);
+
+    assert!(
+        output.status.success(),
+        "Échec : {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("phrase") || stdout.contains("test"),
+        "Texte inattendu : {}",
+        stdout
+    );
+}
+
+/// Teste le refus des options qui nécessitent un fichier.
+#[test]
+fn test_stdin_incompatible_with_batch() {
+    let output = run_with_stdin(&["-", "--batch"], &png_bytes(4, 4));
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--batch"));
+}
+
+/// Teste l'erreur de décodage de données qui ne sont pas une image.
+#[test]
+fn test_stdin_invalid_image() {
+    let output = run_with_stdin(&["-"], b"pas une image");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("décodage"));
+}
+
+/// Teste le refus du masquage sur une image lue sur stdin.
+#[test]
+fn test_stdin_incompatible_with_redact() {
+    let output = run_with_stdin(
+        &["-", "--redact", "iban", "--redacted-out", "masque.png"],
+        &png_bytes(4, 4),
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--redact"));
+}
+
+/// Teste le refus du mode A/B sur une image lue sur stdin.
+#[test]
+fn test_stdin_incompatible_with_compare() {
+    let output = run_with_stdin(&["-", "--compare", "autre.png"], &png_bytes(4, 4));
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--compare"));
+}
+
+/// Teste que l'évaluation par annotations exige un fichier image.
+#[test]
+fn test_stdin_incompatible_with_annotations() {
+    let output = run_with_stdin(&["-", "--annotations", "mots.json"], &png_bytes(4, 4));
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--annotations"));
+}
+
+/// Teste l'affichage de la configuration effective sans image ni OCR.
+#[test]
+fn test_show_config_dry_run_json() {
+    let output = run_with_stdin(
+        &[
+            "--show-config",
+            "--dry-run",
+            "--format",
+            "json",
+            "--dpi",
+            "200",
+            "--var",
+            "tessedit_char_whitlist=0123456789",
+        ],
+        b"",
+    );
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let config: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(config["psm_value"], 3);
+    assert_eq!(config["tesseract_variables"]["user_defined_dpi"], "200");
+    assert_eq!(config["warnings"].as_array().unwrap().len(), 1);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("tessedit_char_whitelist"));
+}
+
+/// Teste les noms de sortie du mode batch : arborescence reproduite, ou
+/// suffixe numérique avec --flatten (sans Tesseract, via --preprocess-only).
+#[test]
+fn test_batch_outputs_do_not_collide() {
+    let dir = tempfile::tempdir().unwrap();
+    let scans = dir.path().join("scans");
+    for sub in ["a", "b"] {
+        std::fs::create_dir_all(scans.join(sub)).unwrap();
+        std::fs::write(scans.join(sub).join("page1.png"), png_bytes(40, 20)).unwrap();
+    }
+    let scans_str = scans.to_str().unwrap();
+
+    let mirrored = dir.path().join("mirrored");
+    let output = run_with_stdin(
+        &[
+            scans_str,
+            "--batch",
+            "--recursive",
+            "--preprocess",
+            "--preprocess-only",
+            "--output",
+            mirrored.to_str().unwrap(),
+        ],
+        b"",
+    );
+    assert!(
+        output.status.success(),
+        "Échec : {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(mirrored.join("a/page1.png").is_file());
+    assert!(mirrored.join("b/page1.png").is_file());
+
+    let flat = dir.path().join("flat");
+    let output = run_with_stdin(
+        &[
+            scans_str,
+            "--batch",
+            "--recursive",
+            "--preprocess",
+            "--preprocess-only",
+            "--output",
+            flat.to_str().unwrap(),
+            "--flatten",
+        ],
+        b"",
+    );
+    assert!(
+        output.status.success(),
+        "Échec : {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(flat.join("page1.png").is_file());
+    assert!(flat.join("page1-2.png").is_file());
+}
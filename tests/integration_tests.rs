@@ -3,8 +3,12 @@
 //! Ces tests vérifient le fonctionnement de base de l'extraction de texte
 //! avec différentes configurations et images de test.
 
+#![cfg(feature = "tesseract")]
+
 use std::path::Path;
-use text_recognition::{OcrConfig, OcrEngine};
+use text_recognition::metrics::calculate_cer;
+use text_recognition::pyramid::{PyramidConfig, level_dpi};
+use text_recognition::{OcrConfig, OcrEngine, WordDiff, compare_images};
 
 /// Teste l'extraction de texte sur une image simple avec configuration par défaut.
 #[test]
@@ -193,3 +197,134 @@ fn test_extract_with_preprocessing() {
         "Aucun texte extrait avec prétraitement"
     );
 }
+
+/// Teste que le texte structuré s'affiche comme le texte brut extrait.
+#[test]
+fn test_extract_structured_matches_plain_text() {
+    let engine = OcrEngine::new(OcrConfig::default()).expect("Échec de création du moteur OCR");
+    let path = Path::new("resources/simple/img-1.png");
+
+    let structured = engine
+        .extract_structured(path)
+        .expect("L'extraction structurée a échoué");
+    let text = engine
+        .extract_text_from_file(path)
+        .expect("L'extraction de texte a échoué");
+
+    assert!(!structured.paragraphs.is_empty());
+    assert_eq!(structured.to_string(), text);
+}
+
+/// Teste qu'une image comparée à elle-même donne un CER nul et un diff sans changement.
+#[test]
+fn test_compare_image_with_itself() {
+    let engine = OcrEngine::new(OcrConfig::default()).expect("Échec de création du moteur OCR");
+    let path = Path::new("resources/simple/img-1.png");
+
+    let comparison = compare_images(&engine, path, path).expect("La comparaison a échoué");
+
+    let metrics = comparison.metrics.expect("Métriques absentes");
+    assert_eq!(metrics.cer, 0.0);
+    assert!(!comparison.word_diff.is_empty());
+    assert!(
+        comparison
+            .word_diff
+            .iter()
+            .all(|op| matches!(op, WordDiff::Equal(_)))
+    );
+}
+
+/// Teste que l'échec de la seconde image est noté sans interrompre la comparaison.
+#[test]
+fn test_compare_images_reports_second_failure() {
+    let engine = OcrEngine::new(OcrConfig::default()).expect("Échec de création du moteur OCR");
+    let path = Path::new("resources/simple/img-1.png");
+    let missing = Path::new("resources/simple/inexistante.png");
+
+    let comparison = compare_images(&engine, path, missing).expect("La comparaison a échoué");
+
+    assert!(comparison.first.text.is_some());
+    assert!(comparison.first.error.is_none());
+    assert!(comparison.second.text.is_none());
+    assert!(comparison.second.error.is_some());
+    assert!(comparison.metrics.is_none());
+    assert!(comparison.word_diff.is_empty());
+}
+
+/// Teste l'extraction de texte d'une image WebP, décodée hors de Tesseract.
+#[test]
+fn test_extract_text_webp_image() {
+    let dir = tempfile::tempdir().expect("Échec de création du répertoire temporaire");
+    let webp_path = dir.path().join("img-4.webp");
+    image::open("resources/simple/img-4.png")
+        .expect("Échec du chargement de l'image de test")
+        .save(&webp_path)
+        .expect("Échec de l'encodage WebP");
+
+    let engine = OcrEngine::new(OcrConfig::default()).expect("Échec de création du moteur OCR");
+    let text = engine
+        .extract_text_from_file(&webp_path)
+        .expect("L'extraction de texte WebP a échoué");
+
+    assert!(
+        text.contains("phrase") || text.contains("test"),
+        "Le texte extrait ne contient pas les mots attendus. Texte: {}",
+        text
+    );
+}
+
+/// Teste le préchauffage : le mot embarqué est reconnu et les durées sont mesurées.
+#[test]
+fn test_warm_up_recognizes_embedded_word() {
+    let engine = OcrEngine::new(OcrConfig::default()).expect("Échec de création du moteur OCR");
+    let report = engine.warm_up().expect("Le préchauffage a échoué");
+
+    assert!(
+        report.text.contains(text_recognition::WARM_UP_WORD),
+        "Le mot de préchauffage n'a pas été lu. Texte: {}",
+        report.text
+    );
+    assert!(report.init > std::time::Duration::ZERO);
+    assert!(report.extraction > std::time::Duration::ZERO);
+    assert!(engine.health_check().is_ok());
+}
+
+/// Teste la lecture pyramidale : le texte retenu est celui de l'extraction directe.
+#[test]
+fn test_pyramid_matches_plain_extraction() {
+    let engine = OcrEngine::new(OcrConfig::default()).expect("Échec de création du moteur OCR");
+    let image_path = Path::new("resources/simple/img-4.png");
+    let pyramid = PyramidConfig::default();
+
+    let plain = engine
+        .extract_text_from_file(image_path)
+        .expect("L'extraction directe a échoué");
+    let result = engine
+        .extract_text_pyramid(image_path, &pyramid.levels)
+        .expect("La lecture pyramidale a échoué");
+
+    // Arrêt à 30 % ou 60 % comme lecture jusqu'à la pleine résolution : le
+    // texte retenu ne doit pas s'écarter de celui de l'extraction directe
+    let accepted = result.report.accepted().expect("Aucun niveau lu");
+    assert_eq!(
+        accepted.dpi,
+        level_dpi(OcrConfig::default().dpi, accepted.scale)
+    );
+    let cer = calculate_cer(result.outcome.text.trim(), plain.trim());
+    assert!(
+        cer <= pyramid.max_cer,
+        "Texte retenu à l'échelle {} (arrêt anticipé : {}) trop éloigné de l'extraction directe (CER {:.3}).\nPyramide : {}\nDirecte : {}",
+        accepted.scale,
+        result.report.stopped_early(),
+        cer,
+        result.outcome.text,
+        plain
+    );
+    assert!(
+        result
+            .outcome
+            .warnings
+            .iter()
+            .any(|warning| warning.kind() == text_recognition::WarningKind::PyramidLevel)
+    );
+}
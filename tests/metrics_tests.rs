@@ -3,6 +3,8 @@
 //! Ces tests vérifient que les fonctions de calcul de métriques fonctionnent correctement
 //! sur de vraies données OCR comparées aux textes de référence.
 
+#![cfg(feature = "tesseract")]
+
 use image::open;
 use std::fs;
 use text_recognition::config::{OcrConfig, PageSegMode};
@@ -154,6 +156,7 @@ fn test_compare_metrics_different_psm_modes() {
             page_seg_mode: psm,
             dpi: 300,
             tesseract_variables: std::collections::HashMap::new(),
+            ..OcrConfig::default()
         };
 
         let engine = OcrEngine::new(config).expect("Failed to create OCR engine");
@@ -440,7 +443,10 @@ fn test_report_quality_categories() {
 /// Test de comparaison avant/après prétraitement.
 #[test]
 fn test_metrics_with_and_without_preprocessing() {
-    use text_recognition::preprocessing::{BinarizationMethod, PreprocessingConfig};
+    use text_recognition::preprocessing::{
+        BinarizationMethod, BlankDetectionOptions, DEFAULT_MAX_PIXELS, DeskewOptions,
+        GrayscaleOptions, PreprocessingConfig,
+    };
 
     let img_path = "resources/simple/img-1.png";
     let img = open(img_path).expect("Failed to open test image");
@@ -463,6 +469,13 @@ fn test_metrics_with_and_without_preprocessing() {
         contrast_factor: 1.0,
         denoise: false,
         deskew: false,
+        max_pixels: DEFAULT_MAX_PIXELS,
+        skip_blank_pages: false,
+        blank_detection: BlankDetectionOptions::default(),
+        split_double_pages: false,
+        despeckle: None,
+        grayscale_options: GrayscaleOptions::default(),
+        deskew_options: DeskewOptions::default(),
     };
 
     let config_with_prep = OcrConfig::default();
@@ -5,8 +5,8 @@
 
 use image::{GenericImageView, open};
 use text_recognition::preprocessing::{
-    BinarizationMethod, PreprocessingConfig, adjust_contrast, binarize, denoise, deskew,
-    preprocess_image,
+    BinarizationMethod, BlankDetectionOptions, DEFAULT_MAX_PIXELS, DeskewOptions, GrayscaleOptions,
+    PreprocessingConfig, adjust_contrast, binarize, denoise, deskew, preprocess_image,
 };
 
 /// Vérifie que le prétraitement par défaut fonctionne sur une image simple.
@@ -81,6 +81,13 @@ fn test_preprocess_full_pipeline() {
         contrast_factor: 1.5,
         denoise: true,
         deskew: true,
+        max_pixels: DEFAULT_MAX_PIXELS,
+        skip_blank_pages: false,
+        blank_detection: BlankDetectionOptions::default(),
+        split_double_pages: false,
+        despeckle: None,
+        grayscale_options: GrayscaleOptions::default(),
+        deskew_options: DeskewOptions::default(),
     };
 
     let result = preprocess_image(&img, &config);
@@ -303,6 +310,13 @@ fn test_preprocess_no_operations() {
         contrast_factor: 1.0,
         denoise: false,
         deskew: false,
+        max_pixels: DEFAULT_MAX_PIXELS,
+        skip_blank_pages: false,
+        blank_detection: BlankDetectionOptions::default(),
+        split_double_pages: false,
+        despeckle: None,
+        grayscale_options: GrayscaleOptions::default(),
+        deskew_options: DeskewOptions::default(),
     };
 
     let result = preprocess_image(&img, &config);
@@ -332,6 +346,13 @@ fn test_preprocess_grayscale_only() {
         contrast_factor: 1.0,
         denoise: false,
         deskew: false,
+        max_pixels: DEFAULT_MAX_PIXELS,
+        skip_blank_pages: false,
+        blank_detection: BlankDetectionOptions::default(),
+        split_double_pages: false,
+        despeckle: None,
+        grayscale_options: GrayscaleOptions::default(),
+        deskew_options: DeskewOptions::default(),
     };
 
     let result = preprocess_image(&img, &config);
@@ -363,6 +384,13 @@ fn test_preprocess_binarize_only() {
         contrast_factor: 1.0,
         denoise: false,
         deskew: false,
+        max_pixels: DEFAULT_MAX_PIXELS,
+        skip_blank_pages: false,
+        blank_detection: BlankDetectionOptions::default(),
+        split_double_pages: false,
+        despeckle: None,
+        grayscale_options: GrayscaleOptions::default(),
+        deskew_options: DeskewOptions::default(),
     };
 
     let result = preprocess_image(&img, &config);
@@ -453,6 +481,13 @@ fn test_preprocess_document_pipeline() {
         contrast_factor: 1.0,
         denoise: false,
         deskew: true,
+        max_pixels: DEFAULT_MAX_PIXELS,
+        skip_blank_pages: false,
+        blank_detection: BlankDetectionOptions::default(),
+        split_double_pages: false,
+        despeckle: None,
+        grayscale_options: GrayscaleOptions::default(),
+        deskew_options: DeskewOptions::default(),
     };
 
     let result = preprocess_image(&img, &config);
@@ -486,6 +521,13 @@ fn test_preprocess_photo_pipeline() {
         contrast_factor: 1.5,
         denoise: true,
         deskew: false,
+        max_pixels: DEFAULT_MAX_PIXELS,
+        skip_blank_pages: false,
+        blank_detection: BlankDetectionOptions::default(),
+        split_double_pages: false,
+        despeckle: None,
+        grayscale_options: GrayscaleOptions::default(),
+        deskew_options: DeskewOptions::default(),
     };
 
     let result = preprocess_image(&img, &config);
@@ -3,6 +3,8 @@
 //! Ces tests vérifient que tous les modes PSM de Tesseract fonctionnent
 //! correctement et peuvent être utilisés sans erreur.
 
+#![cfg(feature = "tesseract")]
+
 use std::path::Path;
 use text_recognition::{OcrConfig, OcrEngine, PageSegMode};
 
@@ -14,6 +16,7 @@ fn test_psm_osd_only() {
         page_seg_mode: PageSegMode::OsdOnly,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config);
@@ -32,6 +35,7 @@ fn test_psm_auto_osd() {
         page_seg_mode: PageSegMode::AutoOsd,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config);
@@ -55,6 +59,7 @@ fn test_psm_auto_only() {
         page_seg_mode: PageSegMode::AutoOnly,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config);
@@ -81,6 +86,7 @@ fn test_psm_auto() {
         page_seg_mode: PageSegMode::Auto,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config).expect("Échec de création avec PSM Auto");
@@ -107,6 +113,7 @@ fn test_psm_single_column() {
         page_seg_mode: PageSegMode::SingleColumn,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config).expect("Échec de création avec PSM SingleColumn");
@@ -130,6 +137,7 @@ fn test_psm_single_block_vert_text() {
         page_seg_mode: PageSegMode::SingleBlockVertText,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config).expect("Échec de création avec PSM SingleBlockVertText");
@@ -150,6 +158,7 @@ fn test_psm_single_block() {
         page_seg_mode: PageSegMode::SingleBlock,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config).expect("Échec de création avec PSM SingleBlock");
@@ -179,6 +188,7 @@ fn test_psm_single_line() {
         page_seg_mode: PageSegMode::SingleLine,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config).expect("Échec de création avec PSM SingleLine");
@@ -202,6 +212,7 @@ fn test_psm_single_word() {
         page_seg_mode: PageSegMode::SingleWord,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config).expect("Échec de création avec PSM SingleWord");
@@ -222,6 +233,7 @@ fn test_psm_circle_word() {
         page_seg_mode: PageSegMode::CircleWord,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config).expect("Échec de création avec PSM CircleWord");
@@ -242,6 +254,7 @@ fn test_psm_single_char() {
         page_seg_mode: PageSegMode::SingleChar,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config).expect("Échec de création avec PSM SingleChar");
@@ -262,6 +275,7 @@ fn test_psm_sparse_text() {
         page_seg_mode: PageSegMode::SparseText,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config).expect("Échec de création avec PSM SparseText");
@@ -285,6 +299,7 @@ fn test_psm_sparse_text_osd() {
         page_seg_mode: PageSegMode::SparseTextOsd,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config).expect("Échec de création avec PSM SparseTextOsd");
@@ -305,6 +320,7 @@ fn test_psm_raw_line() {
         page_seg_mode: PageSegMode::RawLine,
         dpi: 300,
         tesseract_variables: Default::default(),
+        ..OcrConfig::default()
     };
 
     let engine = OcrEngine::new(config).expect("Échec de création avec PSM RawLine");
@@ -361,6 +377,7 @@ fn test_psm_modes_produce_results() {
             page_seg_mode: *mode,
             dpi: 300,
             tesseract_variables: Default::default(),
+            ..OcrConfig::default()
         };
 
         let engine = OcrEngine::new(config)
@@ -0,0 +1,120 @@
+//! Tests d'intégration du serveur HTTP d'OCR (feature `server`).
+//!
+//! Le serveur écoute sur un port libre et reçoit de vraies requêtes HTTP ;
+//! l'OCR est remplacé par un backend factice pour ne pas dépendre de
+//! Tesseract.
+
+#![cfg(feature = "server")]
+
+use image::{DynamicImage, GrayImage, ImageFormat, Luma};
+use std::io::{Cursor, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use text_recognition::ocr::OcrOutcome;
+use text_recognition::server::{OcrBackend, OcrServer, OcrService, RequestOptions};
+
+/// Backend factice : le texte décrit l'image reçue et la langue demandée.
+struct DimensionsBackend;
+
+impl OcrBackend for DimensionsBackend {
+    fn extract_text(
+        &self,
+        image: &DynamicImage,
+        options: &RequestOptions,
+    ) -> anyhow::Result<OcrOutcome> {
+        Ok(OcrOutcome {
+            text: format!(
+                "{}x{} {}",
+                image.width(),
+                image.height(),
+                options.language.unwrap_or("fra")
+            ),
+            warnings: Vec::new(),
+            preprocessing: None,
+            cache_hit: false,
+        })
+    }
+
+    fn extract_hocr(
+        &self,
+        _image: &DynamicImage,
+        _options: &RequestOptions,
+    ) -> anyhow::Result<OcrOutcome> {
+        anyhow::bail!("hOCR non supporté par ce backend")
+    }
+
+    fn tesseract_version(&self) -> anyhow::Result<String> {
+        Ok("5.3.4".to_string())
+    }
+}
+
+/// Envoie une requête HTTP/1.1 et retourne le statut et le corps de la réponse.
+fn send(
+    addr: SocketAddr,
+    method: &str,
+    url: &str,
+    content_type: &str,
+    body: &[u8],
+) -> (u16, String) {
+    let mut stream = TcpStream::connect(addr).expect("connexion au serveur");
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: {}\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n",
+        method,
+        url,
+        content_type,
+        body.len()
+    )
+    .unwrap();
+    stream.write_all(body).unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").expect("réponse HTTP");
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .expect("ligne de statut");
+    (status, body.to_string())
+}
+
+#[test]
+fn test_server_round_trips_generated_png() {
+    let service = OcrService::new(DimensionsBackend).with_max_body_bytes(64 * 1024);
+    let server = Arc::new(OcrServer::bind("127.0.0.1:0", service).unwrap());
+    let addr = server.local_addr().expect("adresse IP d'écoute");
+    let running = thread::spawn({
+        let server = Arc::clone(&server);
+        move || server.run()
+    });
+
+    let mut png = Vec::new();
+    DynamicImage::ImageLuma8(GrayImage::from_pixel(120, 45, Luma([255])))
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .unwrap();
+
+    let (status, body) = send(addr, "POST", "/ocr?language=eng", "image/png", &png);
+    assert_eq!(status, 200, "{}", body);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["text"], "120x45 eng");
+    assert!(json["duration_ms"].is_u64());
+
+    let (status, body) = send(addr, "POST", "/ocr", "image/png", b"pas une image");
+    assert_eq!(status, 422);
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["kind"], "image_decode");
+
+    // Content-Length au-delà de la limite : refusé sans lire le corps
+    let (status, _) = send(addr, "POST", "/ocr", "image/png", &vec![0; 65 * 1024]);
+    assert_eq!(status, 413);
+
+    let (status, body) = send(addr, "GET", "/healthz", "text/plain", b"");
+    assert_eq!(status, 200);
+    assert!(body.contains("5.3.4"));
+
+    server.shutdown();
+    running.join().unwrap();
+}
@@ -0,0 +1,102 @@
+//! Tests de la bibliothèque compilée sans la feature `tesseract`.
+//!
+//! Ce fichier n'est compilé qu'avec `cargo test --no-default-features` (voir
+//! `make check-no-tesseract`) : il vérifie que la configuration, le
+//! prétraitement, les métriques et l'analyse d'un document HOCR fourni
+//! restent utilisables sans Tesseract, par exemple pour une cible wasm32.
+
+#![cfg(not(feature = "tesseract"))]
+
+use image::{DynamicImage, GenericImageView, GrayImage, Luma};
+use text_recognition::config::{OcrConfig, PageSegMode};
+use text_recognition::hocr::{HocrDocument, LayoutTextOptions, to_layout_text};
+use text_recognition::metrics::{
+    calculate_cer, calculate_wer, compare_ocr_result, levenshtein_distance,
+    levenshtein_distance_fast,
+};
+use text_recognition::preprocessing::{
+    PageContent, PreprocessingConfig, PreprocessingConfigPatch, detect_text_lines, is_blank_page,
+    preprocess_image, preprocess_image_with_report,
+};
+use text_recognition::{OcrConfigPatch, StreamingComparer};
+
+/// Page blanche de 200 × 100 traversée de deux bandes sombres (deux « lignes »).
+fn two_line_page() -> GrayImage {
+    GrayImage::from_fn(200, 100, |x, y| {
+        let in_line = (20..32).contains(&y) || (60..72).contains(&y);
+        Luma([if in_line && (x / 4) % 2 == 0 { 20 } else { 235 }])
+    })
+}
+
+const HOCR: &str = r#"<div class='ocr_page' id='page_1' title='bbox 0 0 400 100'>
+   <p class='ocr_par' id='par_1_1' title="bbox 10 10 300 50">
+    <span class='ocr_line' id='line_1_1' title="bbox 10 10 300 50; baseline 0 -5">
+     <span class='ocrx_word' id='word_1_1' title='bbox 10 10 90 50; x_wconf 95'>Total</span>
+     <span class='ocrx_word' id='word_1_2' title='bbox 100 10 200 50; x_wconf 41'>12,50</span>
+    </span>
+   </p>
+</div>"#;
+
+#[test]
+fn test_preprocessing_pipeline_without_tesseract() {
+    let page = DynamicImage::ImageLuma8(two_line_page());
+
+    let processed = preprocess_image(&page, &PreprocessingConfig::balanced()).unwrap();
+    assert_eq!(processed.dimensions(), page.dimensions());
+
+    let (_, report) = preprocess_image_with_report(&page, &PreprocessingConfig::best()).unwrap();
+    assert!(!report.to_string().is_empty());
+
+    assert_eq!(detect_text_lines(&two_line_page()).len(), 2);
+    let blank = GrayImage::from_pixel(200, 100, Luma([250]));
+    assert_eq!(
+        is_blank_page(&blank, &Default::default()).content,
+        PageContent::Blank
+    );
+}
+
+#[test]
+fn test_metrics_without_tesseract() {
+    assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+    let reference = "Le café coûte 2,50 € ; ".repeat(30);
+    let ocr = reference.replace("café", "cafe");
+    assert_eq!(
+        levenshtein_distance_fast(&ocr, &reference),
+        levenshtein_distance(&ocr, &reference)
+    );
+    assert!(calculate_cer(&ocr, &reference) > 0.0);
+    assert_eq!(calculate_wer("bonjour monde", "bonjour monde"), 0.0);
+    assert_eq!(compare_ocr_result("abc", "abd").levenshtein_distance, 1);
+
+    let mut comparer = StreamingComparer::new("bonjour le monde", 8);
+    comparer.push_ocr_chunk("bonjour le ");
+    comparer.push_ocr_chunk("monde");
+    assert_eq!(comparer.finalize().levenshtein_distance, 0);
+}
+
+#[test]
+fn test_hocr_parsing_without_tesseract() {
+    let doc = HocrDocument::from_hocr_string(HOCR).unwrap();
+
+    let words = &doc.pages[0].paragraphs[0].lines[0].words;
+    assert_eq!(words.len(), 2);
+    assert_eq!(words[1].text, "12,50");
+    assert_eq!(doc.low_score_words(0.5).len(), 1);
+    assert!(to_layout_text(&doc, &LayoutTextOptions::default()).contains("Total"));
+}
+
+#[test]
+fn test_config_without_tesseract() {
+    let mut config = OcrConfig::document_preset();
+    config.apply_patch(&OcrConfigPatch {
+        page_seg_mode: Some(PageSegMode::SingleLine),
+        ..OcrConfigPatch::default()
+    });
+    let parsed: OcrConfig = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+    assert_eq!(parsed.page_seg_mode, PageSegMode::SingleLine);
+
+    let patch: PreprocessingConfigPatch = toml::from_str("binarize = true\n").unwrap();
+    let mut preprocessing = PreprocessingConfig::default();
+    preprocessing.apply_patch(&patch);
+    assert!(preprocessing.binarize);
+}